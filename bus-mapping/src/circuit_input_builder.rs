@@ -16,6 +16,7 @@ use crate::{
     error::Error,
     evm::opcodes::{gen_associated_ops, gen_associated_steps},
     operation::{CallContextField, Operation, RWCounter, StartOp, RW},
+    precompile::MODEXP_SIZE_LIMIT,
     rpc::GethClient,
     state_db::{self, CodeDB, StateDB},
 };
@@ -24,14 +25,23 @@ pub use block::{Block, BlockContext};
 pub use call::{Call, CallContext, CallKind};
 use core::fmt::Debug;
 use eth_types::{
-    self, geth_types,
+    self,
+    evm_types::{
+        system_contracts::{
+            beacon_root_slots, history_storage_slot, BEACON_ROOTS_ADDRESS, HISTORY_STORAGE_ADDRESS,
+        },
+        Hardfork,
+    },
+    geth_types,
     sign_types::{pk_bytes_le, pk_bytes_swap_endianness, SignData},
     Address, GethExecStep, GethExecTrace, ToWord, Word,
 };
 use ethers_providers::JsonRpcClient;
 pub use execution::{
-    CopyDataType, CopyEvent, CopyStep, ExecState, ExecStep, ExpEvent, ExpStep, NumberOrHash,
+    Blake2fEvent, CopyDataType, CopyEvent, CopyStep, EcAddEvent, EcMulEvent, ExecState, ExecStep,
+    ExpEvent, ExpStep, ModExpEvent, NumberOrHash, P256VerifyEvent,
 };
+use halo2_proofs::arithmetic::CurveAffine;
 pub use input_state_ref::CircuitInputStateRef;
 use itertools::Itertools;
 use log::warn;
@@ -52,10 +62,20 @@ pub struct FixedCParams {
     // TODO: evm_rows: Maximum number of rows in the EVM Circuit
     /// Maximum number of txs in the Tx Circuit
     pub max_txs: usize,
+    /// Number of leading tx slots whose signature is verified by the Tx
+    /// Circuit, at most `max_txs`.  The slots after them can only hold
+    /// deposits, and cost no signature verification.
+    pub max_signed_txs: usize,
     /// Maximum number of withdrawals in the Withdrawal Circuit
     pub max_withdrawals: usize,
     /// Maximum number of bytes from all txs calldata in the Tx Circuit
     pub max_calldata: usize,
+    /// Maximum number of addresses, and of storage keys, from all txs access
+    /// lists in the Tx Circuit
+    pub max_access_list: usize,
+    /// Maximum number of EIP-7702 authorizations, from all set-code txs
+    /// authorization lists, verified by the Tx Circuit
+    pub max_auths: usize,
     /// Max amount of rows that the CopyCircuit can have.
     pub max_copy_rows: usize,
     /// Max number of steps that the ExpCircuit can have. Each step is further
@@ -75,6 +95,75 @@ pub struct FixedCParams {
     /// calculated, so the same circuit will not be able to prove different
     /// witnesses.
     pub max_keccak_rows: usize,
+    /// Pad the Poseidon circuit to a static capacity of this number of
+    /// blocks of 32 bytes.  When 0, the Poseidon circuit number of blocks will
+    /// be dynamically calculated from its inputs.
+    pub max_poseidon_blocks: usize,
+    /// Pad the SHA-256 circuit to a static capacity of this number of blocks
+    /// of 64 bytes.  When 0, the SHA-256 circuit number of blocks will be
+    /// dynamically calculated from the inputs of the sha256 precompile calls.
+    pub max_sha256_blocks: usize,
+    /// Pad the RIPEMD-160 circuit to a static capacity of this number of
+    /// blocks of 64 bytes.  When 0, the RIPEMD-160 circuit number of blocks
+    /// will be dynamically calculated from the inputs of the ripemd160
+    /// precompile calls.
+    pub max_ripemd160_blocks: usize,
+    /// Pad the BLAKE2F circuit to a static capacity of this number of
+    /// blocks, each taken by a round or a compression without rounds.  When
+    /// 0, the BLAKE2F circuit number of blocks will be dynamically calculated
+    /// from the blake2f precompile calls.
+    pub max_blake2f_blocks: usize,
+    /// Maximum number of secp256k1 signatures verified by the Sig Circuit
+    /// for the ecrecover calls.  When 0, the Sig Circuit number of signatures
+    /// will be dynamically calculated from the ecrecover calls.
+    pub max_sig_verifs: usize,
+    /// Maximum number of modular exponentiations proven by the ModExp
+    /// Circuit for the modexp calls.  When 0, the ModExp Circuit number of
+    /// exponentiations will be dynamically calculated from the modexp calls.
+    pub max_modexps: usize,
+    /// Maximum number of bytes of the base, exponent and modulus of the
+    /// modexp calls proven by the ModExp Circuit, at most
+    /// [`MODEXP_SIZE_LIMIT`].  The calls with larger inputs are handled as
+    /// unimplemented precompiles.
+    pub max_modexp_size: usize,
+    /// Maximum number of BN254 additions proven by the ECC Circuit for the
+    /// ecAdd calls.  When 0, the ECC Circuit number of additions will be
+    /// dynamically calculated from the ecAdd calls.
+    pub max_ec_adds: usize,
+    /// Maximum number of BN254 scalar multiplications proven by the ECC
+    /// Circuit for the ecMul calls.  When 0, the ECC Circuit number of
+    /// multiplications will be dynamically calculated from the ecMul calls.
+    pub max_ec_muls: usize,
+    /// Maximum number of secp256r1 signatures verified by the P-256 Sig
+    /// Circuit for the P256VERIFY calls.  When 0, the P-256 Sig Circuit
+    /// number of signatures will be dynamically calculated from the
+    /// P256VERIFY calls.
+    pub max_p256_verifs: usize,
+    /// Hardfork whose rules the block is executed and proven with.
+    pub hardfork: Hardfork,
+    /// Hash function binding the bytes of the Bytecode Circuit to their
+    /// code hash.
+    pub code_hash_scheme: CodeHashScheme,
+    /// Support the OP-stack deposit txs, which are derived from L1 and have
+    /// no signature.
+    pub deposits: bool,
+    /// Support the P256VERIFY precompile (RIP-7212) of the L2s, at the
+    /// address 0x100.
+    pub p256_verify: bool,
+}
+
+/// Hash function of the code hashes of the Bytecode Circuit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CodeHashScheme {
+    /// Keccak256, the code hash of the accounts.  The bytes are looked up in
+    /// the KeccakTable.
+    #[default]
+    Keccak,
+    /// Poseidon digest of the code, without `hi`.  The bytes are looked up
+    /// in the PoseidonTable, which is much cheaper to prove than keccak but
+    /// doesn't match the code hash of the accounts, so the Super Circuit
+    /// rejects it.
+    Poseidon,
 }
 
 /// Unset Circuits Parameters
@@ -88,17 +177,42 @@ pub struct DynamicCParams {}
 pub trait CircuitsParams: Debug + Copy {
     /// Returns the max number of rws allowed
     fn max_rws(&self) -> Option<usize>;
+    /// Returns the hardfork whose rules the block is executed with
+    fn hardfork(&self) -> Hardfork;
+    /// Returns the maximum number of bytes of the inputs of the supported
+    /// modexp calls
+    fn max_modexp_size(&self) -> usize;
+    /// Returns whether the P256VERIFY precompile (RIP-7212) is enabled
+    fn p256_verify(&self) -> bool;
 }
 
 impl CircuitsParams for FixedCParams {
     fn max_rws(&self) -> Option<usize> {
         Some(self.max_rws)
     }
+    fn hardfork(&self) -> Hardfork {
+        self.hardfork
+    }
+    fn max_modexp_size(&self) -> usize {
+        self.max_modexp_size
+    }
+    fn p256_verify(&self) -> bool {
+        self.p256_verify
+    }
 }
 impl CircuitsParams for DynamicCParams {
     fn max_rws(&self) -> Option<usize> {
         None
     }
+    fn hardfork(&self) -> Hardfork {
+        Hardfork::default()
+    }
+    fn max_modexp_size(&self) -> usize {
+        MODEXP_SIZE_LIMIT
+    }
+    fn p256_verify(&self) -> bool {
+        false
+    }
 }
 
 impl Default for FixedCParams {
@@ -107,8 +221,11 @@ impl Default for FixedCParams {
         FixedCParams {
             max_rws: 1000,
             max_txs: 1,
+            max_signed_txs: 1,
             max_withdrawals: 1,
             max_calldata: 256,
+            max_access_list: 0,
+            max_auths: 0,
             // TODO: Check whether this value is correct or we should increase/decrease based on
             // this lib tests
             max_copy_rows: 1000,
@@ -116,6 +233,20 @@ impl Default for FixedCParams {
             max_bytecode: 512,
             max_evm_rows: 0,
             max_keccak_rows: 0,
+            max_poseidon_blocks: 0,
+            max_sha256_blocks: 0,
+            max_ripemd160_blocks: 0,
+            max_blake2f_blocks: 0,
+            max_sig_verifs: 0,
+            max_modexps: 0,
+            max_modexp_size: MODEXP_SIZE_LIMIT,
+            max_ec_adds: 0,
+            max_ec_muls: 0,
+            max_p256_verifs: 0,
+            hardfork: Hardfork::default(),
+            code_hash_scheme: CodeHashScheme::Keccak,
+            deposits: false,
+            p256_verify: false,
         }
     }
 }
@@ -181,6 +312,9 @@ impl<'a, C: CircuitsParams> CircuitInputBuilder<C> {
             tx,
             tx_ctx,
             max_rws: self.circuits_params.max_rws(),
+            hardfork: self.circuits_params.hardfork(),
+            max_modexp_size: self.circuits_params.max_modexp_size(),
+            p256_verify: self.circuits_params.p256_verify(),
         }
     }
 
@@ -248,6 +382,18 @@ impl<'a, C: CircuitsParams> CircuitInputBuilder<C> {
         tx_index: u64,
     ) -> Result<(), Error> {
         let mut tx = self.new_tx(tx_index, eth_tx, !geth_trace.failed)?;
+        let hardfork = self.circuits_params.hardfork();
+        if !tx.blob_versioned_hashes.is_empty() && !hardfork.is_blob_tx_active() {
+            return Err(Error::TxTypeNotActive("blob transaction", hardfork));
+        }
+        if tx.is_set_code() {
+            if !hardfork.is_set_code_tx_active() {
+                return Err(Error::TxTypeNotActive("set-code transaction", hardfork));
+            }
+            // The nonce bumps and the delegations of the authorities aren't
+            // applied to the state yet.
+            return Err(Error::TxTypeNotSupported("set-code transaction"));
+        }
         let mut tx_ctx = TransactionContext::new(eth_tx, geth_trace, is_last_tx)?;
 
         if !geth_trace.invalid {
@@ -273,6 +419,15 @@ impl<'a, C: CircuitsParams> CircuitInputBuilder<C> {
             let end_tx_step =
                 gen_associated_steps(&mut self.state_ref(&mut tx, &mut tx_ctx), ExecState::EndTx)?;
             tx.steps_mut().push(end_tx_step);
+
+            // Generate a DeleteAccount step for each account destructed in the tx
+            for _ in 0..self.sdb.destructed_accounts().len() {
+                let delete_account_step = gen_associated_steps(
+                    &mut self.state_ref(&mut tx, &mut tx_ctx),
+                    ExecState::DeleteAccount,
+                )?;
+                tx.steps_mut().push(delete_account_step);
+            }
         } else {
             // Generate InvalidTx step
             let invalid_tx_step = gen_associated_steps(
@@ -362,12 +517,56 @@ impl CircuitInputBuilder<FixedCParams> {
 }
 
 impl<C: CircuitsParams> CircuitInputBuilder<C> {
+    /// Generate the step of the system call which stores the parent beacon
+    /// block root before the first transaction (EIP-4788), if the block has
+    /// one and the hardfork enables it.
+    fn handle_beacon_root(&mut self) -> Result<(), Error> {
+        if !self.circuits_params.hardfork().is_beacon_root_active()
+            || self.block.parent_beacon_root.is_none()
+        {
+            return Ok(());
+        }
+
+        let mut dummy_tx = Transaction::default();
+        let mut dummy_tx_ctx = TransactionContext::default();
+        let beacon_root_step = gen_associated_steps(
+            &mut self.state_ref(&mut dummy_tx, &mut dummy_tx_ctx),
+            ExecState::BeaconRoot,
+        )?;
+        self.sdb.commit_tx();
+        self.block.block_steps.beacon_root = Some(beacon_root_step);
+        Ok(())
+    }
+
+    /// Generate the step of the system call which stores the parent block hash
+    /// before the first transaction (EIP-2935), if the block has a history of
+    /// hashes and the hardfork enables it.
+    fn handle_history_storage(&mut self) -> Result<(), Error> {
+        if !self.circuits_params.hardfork().is_history_storage_active()
+            || self.block.history_hashes.is_empty()
+        {
+            return Ok(());
+        }
+
+        let mut dummy_tx = Transaction::default();
+        let mut dummy_tx_ctx = TransactionContext::default();
+        let history_storage_step = gen_associated_steps(
+            &mut self.state_ref(&mut dummy_tx, &mut dummy_tx_ctx),
+            ExecState::HistoryStorage,
+        )?;
+        self.sdb.commit_tx();
+        self.block.block_steps.history_storage = Some(history_storage_step);
+        Ok(())
+    }
+
     /// First part of handle_block, common for dynamic and static circuit parameters.
     pub fn begin_handle_block(
         &mut self,
         eth_block: &EthBlock,
         geth_traces: &[eth_types::GethExecTrace],
     ) -> Result<(), Error> {
+        self.handle_beacon_root()?;
+        self.handle_history_storage()?;
         // accumulates gas across all txs in the block
         for (idx, tx) in eth_block.transactions.iter().enumerate() {
             let geth_trace = &geth_traces[idx];
@@ -407,6 +606,24 @@ impl CircuitInputBuilder<DynamicCParams> {
                 .transactions
                 .iter()
                 .fold(0, |acc, tx| acc + tx.input.len());
+            let access_list_items = || {
+                eth_block
+                    .transactions
+                    .iter()
+                    .flat_map(|tx| tx.access_list.iter().flat_map(|access_list| &access_list.0))
+            };
+            let max_access_list = std::cmp::max(
+                access_list_items().count(),
+                access_list_items()
+                    .map(|item| item.storage_keys.len())
+                    .sum(),
+            );
+            let max_auths = self
+                .block
+                .txs
+                .iter()
+                .map(|tx| tx.authorization_list.as_ref().map_or(0, |list| list.len()))
+                .sum();
             let max_exp_steps = self
                 .block
                 .exp_events
@@ -428,7 +645,7 @@ impl CircuitInputBuilder<DynamicCParams> {
                 <RWCounter as Into<usize>>::into(self.block_ctx.rwc) - 1; // -1 since rwc start from index `1`
             let max_rws_after_padding = total_rws_before_padding
                 + 1 // fill 1 to have exactly one StartOp padding in below `set_end_block`
-                + if total_rws_before_padding > 0 { 1 /*end_block -> CallContextFieldTag::TxId lookup*/ } else { 0 };
+                + if !self.block.txs.is_empty() { 1 /*end_block -> CallContextFieldTag::TxId lookup*/ } else { 0 };
             // Computing the number of rows for the EVM circuit requires the size of ExecStep,
             // which is determined in the code of zkevm-circuits and cannot be imported here.
             // When the evm circuit receives a 0 value it dynamically computes the minimum
@@ -439,16 +656,57 @@ impl CircuitInputBuilder<DynamicCParams> {
             // With a 0 value the keccak circuit computes dynamically the minimum number of rows
             // needed.
             let max_keccak_rows = 0;
+            // The Poseidon, SHA-256, RIPEMD-160 and BLAKE2F circuits also
+            // compute their number of blocks from their inputs with a 0 value,
+            // and the Sig, ModExp, ECC and P-256 Sig circuits their number of
+            // signatures, exponentiations and operations.
+            let max_poseidon_blocks = 0;
+            let max_sha256_blocks = 0;
+            let max_ripemd160_blocks = 0;
+            let max_blake2f_blocks = 0;
+            let max_sig_verifs = 0;
+            let max_modexps = 0;
+            let max_ec_adds = 0;
+            let max_ec_muls = 0;
+            let max_p256_verifs = 0;
+            // The slots of the deposits after the last signed tx are not verified.
+            let max_signed_txs = self
+                .block
+                .txs
+                .iter()
+                .enumerate()
+                .filter(|(_, tx)| !tx.is_deposit())
+                .last()
+                .map_or(0, |(tx_index, _)| tx_index + 1);
+            // Deposit txs are only supported by the circuits of the blocks which have some.
+            let deposits = self.block.txs.iter().any(|tx| tx.is_deposit());
             FixedCParams {
                 max_rws: max_rws_after_padding,
                 max_txs,
+                max_signed_txs,
                 max_withdrawals,
                 max_calldata,
+                max_access_list,
+                max_auths,
                 max_copy_rows,
                 max_exp_steps,
                 max_bytecode,
                 max_evm_rows,
                 max_keccak_rows,
+                max_poseidon_blocks,
+                max_sha256_blocks,
+                max_ripemd160_blocks,
+                max_blake2f_blocks,
+                max_sig_verifs,
+                max_modexps,
+                max_modexp_size: self.circuits_params.max_modexp_size(),
+                max_ec_adds,
+                max_ec_muls,
+                max_p256_verifs,
+                hardfork: self.circuits_params.hardfork(),
+                code_hash_scheme: CodeHashScheme::default(),
+                deposits,
+                p256_verify: self.circuits_params.p256_verify(),
             }
         };
         let mut cib = CircuitInputBuilder::<FixedCParams> {
@@ -481,6 +739,19 @@ pub fn keccak_inputs(block: &Block, code_db: &CodeDB) -> Result<Vec<Vec<u8>>, Er
     for input in &block.sha3_inputs {
         keccak_inputs.insert(input.clone());
     }
+    // Sig Circuit
+    for input in keccak_inputs_sign_verify(&block.ecrecover_events) {
+        keccak_inputs.insert(input);
+    }
+    // P-256 Sig Circuit
+    let p256_signatures = block
+        .p256_verify_events
+        .iter()
+        .filter_map(P256VerifyEvent::sign_data)
+        .collect_vec();
+    for input in keccak_inputs_sign_verify(&p256_signatures) {
+        keccak_inputs.insert(input);
+    }
     // MPT Circuit
     // TODO https://github.com/privacy-scaling-explorations/zkevm-circuits/issues/696
     Ok(keccak_inputs.into_iter().collect_vec())
@@ -488,7 +759,7 @@ pub fn keccak_inputs(block: &Block, code_db: &CodeDB) -> Result<Vec<Vec<u8>>, Er
 
 /// Generate the keccak inputs required by the SignVerify Chip from the
 /// signature datas.
-pub fn keccak_inputs_sign_verify(sigs: &[SignData]) -> Vec<Vec<u8>> {
+pub fn keccak_inputs_sign_verify<C: CurveAffine>(sigs: &[SignData<C>]) -> Vec<Vec<u8>> {
     let mut inputs = Vec::new();
     for sig in sigs {
         let pk_le = pk_bytes_le(&sig.pk);
@@ -496,7 +767,7 @@ pub fn keccak_inputs_sign_verify(sigs: &[SignData]) -> Vec<Vec<u8>> {
         inputs.push(pk_be.to_vec());
     }
     // Padding signature
-    let pk_le = pk_bytes_le(&SignData::default().pk);
+    let pk_le = pk_bytes_le(&SignData::<C>::padding().pk);
     let pk_be = pk_bytes_swap_endianness(&pk_le);
     inputs.push(pk_be.to_vec());
     inputs
@@ -524,6 +795,18 @@ pub fn keccak_inputs_tx_circuit(
     // Keccak inputs from SignVerify Chip
     let sign_verify_inputs = keccak_inputs_sign_verify(&sign_datas);
     inputs.extend_from_slice(&sign_verify_inputs);
+    // Keccak inputs of the EIP-7702 authorizations: the public keys of the authorities and the
+    // signed messages.  The authorizations whose signature can't be recovered are skipped by the
+    // Tx Circuit.
+    let authorizations = txs
+        .iter()
+        .flat_map(|tx| tx.authorization_list.iter().flatten());
+    let auth_sign_datas = authorizations
+        .clone()
+        .filter_map(|authorization| authorization.sign_data().ok())
+        .collect_vec();
+    inputs.extend(keccak_inputs_sign_verify(&auth_sign_datas));
+    inputs.extend(authorizations.map(|authorization| authorization.sign_message()));
     // NOTE: We don't verify the Tx Hash in the circuit yet, so we don't have more
     // hash inputs.
     Ok(inputs)
@@ -583,6 +866,50 @@ pub fn get_state_accesses(
                 .ok_or(Error::EthTypeError(eth_types::Error::IncompleteBlock))?,
         },
     )];
+    // The system call storing the parent beacon block root (EIP-4788) reads
+    // the code of the beacon roots contract and writes two of its slots.
+    if geth_types::parent_beacon_block_root(eth_block)?.is_some() {
+        let (timestamp_slot, root_slot) = beacon_root_slots(eth_block.timestamp.as_u64());
+        block_access_trace.push(Access::new(
+            None,
+            RW::READ,
+            AccessValue::Code {
+                address: BEACON_ROOTS_ADDRESS,
+            },
+        ));
+        for key in [timestamp_slot, root_slot] {
+            block_access_trace.push(Access::new(
+                None,
+                RW::WRITE,
+                AccessValue::Storage {
+                    address: BEACON_ROOTS_ADDRESS,
+                    key,
+                },
+            ));
+        }
+    }
+    // The system call storing the parent block hash (EIP-2935) reads the code
+    // of the history storage contract and writes one of its slots.
+    if let Some(parent_number) = eth_block
+        .number
+        .and_then(|number| number.as_u64().checked_sub(1))
+    {
+        block_access_trace.push(Access::new(
+            None,
+            RW::READ,
+            AccessValue::Code {
+                address: HISTORY_STORAGE_ADDRESS,
+            },
+        ));
+        block_access_trace.push(Access::new(
+            None,
+            RW::WRITE,
+            AccessValue::Storage {
+                address: HISTORY_STORAGE_ADDRESS,
+                key: history_storage_slot(parent_number),
+            },
+        ));
+    }
     for (tx_index, tx) in eth_block.transactions.iter().enumerate() {
         let geth_trace = &geth_traces[tx_index];
         let tx_access_trace = gen_state_access_trace(eth_block, tx, geth_trace)?;
@@ -609,7 +936,13 @@ pub fn build_state_code_db(
                 nonce: proof.nonce.as_u64(),
                 balance: proof.balance,
                 storage,
-                code_hash: proof.code_hash,
+                // The code hash of an account which isn't in the trie may be
+                // zero in the proof.
+                code_hash: if proof.code_hash.is_zero() {
+                    CodeDB::empty_code_hash()
+                } else {
+                    proof.code_hash
+                },
             },
         )
     }
@@ -1,5 +1,11 @@
 use crate::{operation::RW, Error};
-use eth_types::{evm_types::OpcodeId, Address, GethExecStep, GethExecTrace, ToAddress, Word};
+use eth_types::{
+    evm_types::{
+        system_contracts::{history_storage_slot, HISTORY_STORAGE_ADDRESS},
+        OpcodeId,
+    },
+    Address, GethExecStep, GethExecTrace, ToAddress, Word,
+};
 use ethers_core::utils::get_contract_address;
 use std::collections::{hash_map::Entry, HashMap, HashSet};
 
@@ -157,6 +163,13 @@ pub fn gen_state_access_trace<TX>(
                     let key = step.stack.nth_last(0)?;
                     accs.push(Access::new(i, READ, Storage { address, key }));
                 }
+                OpcodeId::BLOCKHASH => {
+                    // Since Prague, the hash is read from the history storage
+                    // contract (EIP-2935).
+                    let address = HISTORY_STORAGE_ADDRESS;
+                    let key = history_storage_slot(step.stack.nth_last(0)?.low_u64());
+                    accs.push(Access::new(i, READ, Storage { address, key }));
+                }
                 OpcodeId::SELFBALANCE => {
                     let address = contract_address;
                     accs.push(Access::new(i, READ, Account { address }));
@@ -1,13 +1,20 @@
 //! Block-related utility module
 
 use super::{
-    execution::ExecState, transaction::Transaction, CopyEvent, ExecStep, ExpEvent, Withdrawal,
+    execution::ExecState, transaction::Transaction, Blake2fEvent, CopyEvent, EcAddEvent,
+    EcMulEvent, ExecStep, ExpEvent, ModExpEvent, P256VerifyEvent, Withdrawal,
 };
 use crate::{
     operation::{OperationContainer, RWCounter},
     Error,
 };
-use eth_types::{evm_unimplemented, Address, Word, H256};
+use eth_types::{
+    evm_types::gas_utils::blob_base_fee,
+    evm_unimplemented,
+    geth_types::{excess_blob_gas, parent_beacon_block_root},
+    sign_types::SignData,
+    Address, Word, H256,
+};
 use itertools::Itertools;
 use std::collections::HashMap;
 
@@ -45,6 +52,12 @@ impl BlockContext {
 /// Block-wise execution steps that don't belong to any Transaction.
 #[derive(Debug)]
 pub struct BlockSteps {
+    /// BeaconRoot step of the system call before the first transaction, if
+    /// the block has a parent beacon block root (EIP-4788).
+    pub beacon_root: Option<ExecStep>,
+    /// HistoryStorage step of the system call before the first transaction,
+    /// if the block has a parent block hash (EIP-2935).
+    pub history_storage: Option<ExecStep>,
     /// EndBlock step that is repeated after the last transaction and before
     /// reaching the last EVM row.
     pub end_block_not_last: ExecStep,
@@ -73,6 +86,12 @@ pub struct Block {
     pub difficulty: Word,
     /// base fee
     pub base_fee: Word,
+    /// blob base fee (EIP-4844)
+    pub blob_base_fee: Word,
+    /// prev_randao (EIP-4399)
+    pub prev_randao: Word,
+    /// parent beacon block root (EIP-4788)
+    pub parent_beacon_root: Option<H256>,
     /// State root of the previous block
     pub prev_state_root: Word,
     /// Container of operations done in this block.
@@ -87,6 +106,22 @@ pub struct Block {
     pub sha3_inputs: Vec<Vec<u8>>,
     /// Exponentiation events in the block.
     pub exp_events: Vec<ExpEvent>,
+    /// Signatures verified by the ecrecover calls in the block.
+    pub ecrecover_events: Vec<SignData>,
+    /// Inputs hashed by the sha256 calls in the block.
+    pub sha256_inputs: Vec<Vec<u8>>,
+    /// Inputs hashed by the ripemd160 calls in the block.
+    pub ripemd160_inputs: Vec<Vec<u8>>,
+    /// Modular exponentiations of the modexp calls in the block.
+    pub modexp_events: Vec<ModExpEvent>,
+    /// Point additions of the ecAdd calls in the block.
+    pub ec_add_events: Vec<EcAddEvent>,
+    /// Scalar multiplications of the ecMul calls in the block.
+    pub ec_mul_events: Vec<EcMulEvent>,
+    /// Compressions of the successful blake2f calls in the block.
+    pub blake2f_events: Vec<Blake2fEvent>,
+    /// Signature verifications of the P256VERIFY calls in the block.
+    pub p256_verify_events: Vec<P256VerifyEvent>,
     /// Original block from geth
     pub eth_block: eth_types::Block<eth_types::Transaction>,
 }
@@ -119,20 +154,21 @@ impl Block {
                 .low_u64()
                 .into(),
             timestamp: eth_block.timestamp,
-            difficulty: if eth_block.difficulty.is_zero() {
-                eth_block
-                    .mix_hash
-                    .unwrap_or_default()
-                    .to_fixed_bytes()
-                    .into()
-            } else {
-                eth_block.difficulty
-            },
+            difficulty: eth_block.difficulty,
             base_fee: eth_block.base_fee_per_gas.unwrap_or_default(),
+            blob_base_fee: blob_base_fee(excess_blob_gas(eth_block)?),
+            prev_randao: eth_block
+                .mix_hash
+                .unwrap_or_default()
+                .to_fixed_bytes()
+                .into(),
+            parent_beacon_root: parent_beacon_block_root(eth_block)?,
             prev_state_root,
             container: OperationContainer::new(),
             txs: Vec::new(),
             block_steps: BlockSteps {
+                beacon_root: None,
+                history_storage: None,
                 end_block_not_last: ExecStep {
                     exec_state: ExecState::EndBlock,
                     ..ExecStep::default()
@@ -144,6 +180,14 @@ impl Block {
             },
             copy_events: Vec::new(),
             exp_events: Vec::new(),
+            ecrecover_events: Vec::new(),
+            sha256_inputs: Vec::new(),
+            ripemd160_inputs: Vec::new(),
+            modexp_events: Vec::new(),
+            ec_add_events: Vec::new(),
+            ec_mul_events: Vec::new(),
+            blake2f_events: Vec::new(),
+            p256_verify_events: Vec::new(),
             sha3_inputs: Vec::new(),
             eth_block: eth_block.clone(),
         })
@@ -191,4 +235,36 @@ impl Block {
     pub fn add_exp_event(&mut self, event: ExpEvent) {
         self.exp_events.push(event);
     }
+    /// Push an ecrecover event to the block.
+    pub fn add_ecrecover_event(&mut self, event: SignData) {
+        self.ecrecover_events.push(event);
+    }
+    /// Push a sha256 input to the block.
+    pub fn add_sha256_input(&mut self, input: Vec<u8>) {
+        self.sha256_inputs.push(input);
+    }
+    /// Push a ripemd160 input to the block.
+    pub fn add_ripemd160_input(&mut self, input: Vec<u8>) {
+        self.ripemd160_inputs.push(input);
+    }
+    /// Push a modexp event to the block.
+    pub fn add_modexp_event(&mut self, event: ModExpEvent) {
+        self.modexp_events.push(event);
+    }
+    /// Push an ecAdd event to the block.
+    pub fn add_ec_add_event(&mut self, event: EcAddEvent) {
+        self.ec_add_events.push(event);
+    }
+    /// Push an ecMul event to the block.
+    pub fn add_ec_mul_event(&mut self, event: EcMulEvent) {
+        self.ec_mul_events.push(event);
+    }
+    /// Push a blake2f event to the block.
+    pub fn add_blake2f_event(&mut self, event: Blake2fEvent) {
+        self.blake2f_events.push(event);
+    }
+    /// Push a P256VERIFY event to the block.
+    pub fn add_p256_verify_event(&mut self, event: P256VerifyEvent) {
+        self.p256_verify_events.push(event);
+    }
 }
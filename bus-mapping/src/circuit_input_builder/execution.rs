@@ -5,11 +5,14 @@ use crate::{
     error::{ExecError, OogError},
     exec_trace::OperationRef,
     operation::RWCounter,
-    precompile::PrecompileCalls,
+    precompile::{PrecompileAuxData, PrecompileCalls},
+};
+use eth_types::{
+    evm_types::OpcodeId, geth_types::secp256r1_sign_data, sign_types::SignData, Address,
+    GethExecStep, ToBigEndian, Word, H256,
 };
-use eth_types::{evm_types::OpcodeId, GethExecStep, Word, H256};
 use gadgets::impl_expr;
-use halo2_proofs::plonk::Expression;
+use halo2_proofs::{halo2curves::secp256r1::Secp256r1Affine, plonk::Expression};
 use strum_macros::EnumIter;
 
 /// An execution step of the EVM.
@@ -49,6 +52,8 @@ pub struct ExecStep {
     pub copy_rw_counter_delta: u64,
     /// Error generated by this step
     pub error: Option<ExecError>,
+    /// Auxiliary data of the precompile call handled by this step
+    pub aux_data: Option<PrecompileAuxData>,
 }
 
 impl ExecStep {
@@ -77,6 +82,7 @@ impl ExecStep {
             bus_mapping_instance: Vec::new(),
             copy_rw_counter_delta: 0,
             error: None,
+            aux_data: None,
         }
     }
 
@@ -144,10 +150,19 @@ pub enum ExecState {
     BeginTx,
     /// Virtual step End Tx
     EndTx,
+    /// Virtual step deleting an account destructed in the Tx (EIP-6780),
+    /// after EndTx
+    DeleteAccount,
     /// Virtual step End Block
     EndBlock,
     /// Invalid Tx
     InvalidTx,
+    /// Virtual step of the system call storing the parent beacon block root
+    /// (EIP-4788), before the first Tx
+    BeaconRoot,
+    /// Virtual step of the system call storing the parent block hash
+    /// (EIP-2935), after BeaconRoot
+    HistoryStorage,
 }
 
 impl Default for ExecState {
@@ -202,7 +217,9 @@ pub enum CopyDataType {
     Padding = 0,
     /// When the source for the copy event is the bytecode table.
     Bytecode,
-    /// When the source/destination for the copy event is memory.
+    /// When the source/destination for the copy event is memory.  Both are
+    /// memory for a copy within the memory of a call, as by MCOPY, whose
+    /// ranges can overlap.
     Memory,
     /// When the source for the copy event is tx's calldata.
     TxCalldata,
@@ -210,8 +227,18 @@ pub enum CopyDataType {
     TxLog,
     /// When the destination rows are not directly for copying but for a special
     /// scenario where we wish to accumulate the value (RLC) over all rows.
-    /// This is used for Copy Lookup from SHA3 opcode verification.
+    /// This is used for Copy Lookup from SHA3 opcode verification, and for the
+    /// input of a precompile call.  As a source, the bytes are the output of a
+    /// precompile call, given by their RLC.
     RlcAcc,
+    /// When the addresses of the access list of a tx are copied from the tx
+    /// table to the tx access list in the rw table, at the beginning of the
+    /// tx.  Used for both the source and the destination.
+    AccessListAddresses,
+    /// When the storage keys of the access list of a tx are copied from the
+    /// tx table to the tx access list in the rw table, at the beginning of the
+    /// tx.  Used for both the source and the destination.
+    AccessListStorageKeys,
 }
 
 impl From<CopyDataType> for usize {
@@ -272,19 +299,86 @@ pub struct CopyEvent {
     pub log_id: Option<u64>,
     /// Value of rw counter at start of this copy event
     pub rw_counter_start: RWCounter,
-    /// Represents the list of (bytes, is_code) copied during this copy event
+    /// Represents the list of (bytes, is_code) copied during this copy event.
+    /// For the access list copy events, the bytes are whether the entries
+    /// were warm before.
     pub bytes: Vec<(u8, bool)>,
+    /// The (address, storage key) entries copied by an access list copy event,
+    /// with a zero storage key for the addresses.  Empty for the other events.
+    pub access_list: Vec<(Address, Word)>,
 }
 
 impl CopyEvent {
+    /// Return the copy event of the input of the precompile call `call_id`:
+    /// the bytes read from the memory of the caller at `src_addr`, whose RLC
+    /// is looked up by the precompile gadget.
+    pub fn precompile_input(
+        caller_id: usize,
+        call_id: usize,
+        src_addr: u64,
+        bytes: &[u8],
+        rw_counter_start: RWCounter,
+    ) -> Self {
+        Self {
+            src_id: NumberOrHash::Number(caller_id),
+            src_type: CopyDataType::Memory,
+            src_addr,
+            src_addr_end: src_addr + bytes.len() as u64,
+            dst_id: NumberOrHash::Number(call_id),
+            dst_type: CopyDataType::RlcAcc,
+            dst_addr: 0,
+            log_id: None,
+            rw_counter_start,
+            bytes: bytes.iter().map(|byte| (*byte, false)).collect(),
+            access_list: vec![],
+        }
+    }
+
+    /// Return the copy event of the output of the precompile call `call_id`:
+    /// the bytes of its result, given by their RLC, written to its memory as
+    /// its return data.
+    pub fn precompile_output(call_id: usize, bytes: &[u8], rw_counter_start: RWCounter) -> Self {
+        Self {
+            src_id: NumberOrHash::Number(call_id),
+            src_type: CopyDataType::RlcAcc,
+            src_addr: 0,
+            src_addr_end: bytes.len() as u64,
+            dst_id: NumberOrHash::Number(call_id),
+            dst_type: CopyDataType::Memory,
+            dst_addr: 0,
+            log_id: None,
+            rw_counter_start,
+            bytes: bytes.iter().map(|byte| (*byte, false)).collect(),
+            access_list: vec![],
+        }
+    }
+
+    /// Whether the copy event copies bytes within the memory of a call, as
+    /// MCOPY does.  The source and destination ranges can overlap, so all the
+    /// bytes are read before the first one is written.
+    pub fn is_memory_copy(&self) -> bool {
+        self.src_type == CopyDataType::Memory
+            && self.dst_type == CopyDataType::Memory
+            && self.src_id == self.dst_id
+    }
+
     /// rw counter at step index
     pub fn rw_counter(&self, step_index: usize) -> u64 {
-        u64::try_from(self.rw_counter_start.0).unwrap() + self.rw_counter_increase(step_index)
+        let rw_counter_start = u64::try_from(self.rw_counter_start.0).unwrap();
+        if self.is_memory_copy() {
+            // The reads of all the bytes come before their writes
+            let byte_index = u64::try_from(step_index / 2).unwrap();
+            let is_write = step_index % 2 == 1;
+            return rw_counter_start
+                + byte_index
+                + if is_write { self.bytes.len() as u64 } else { 0 };
+        }
+        rw_counter_start + self.rw_counter_increase(step_index)
     }
 
     /// rw counter increase left at step index
     pub fn rw_counter_increase_left(&self, step_index: usize) -> u64 {
-        self.rw_counter(self.bytes.len() * 2) - self.rw_counter(step_index)
+        self.rw_counter_delta() - self.rw_counter_increase(step_index)
     }
 
     /// Number of rw operations performed by this copy event
@@ -292,10 +386,16 @@ impl CopyEvent {
         self.rw_counter_increase(self.bytes.len() * 2)
     }
 
-    // increase in rw counter from the start of the copy event to step index
+    // number of rw operations of the copy event before step index, which is
+    // the increase in rw counter from the start of the copy event to step index
+    // except for memory copies
     fn rw_counter_increase(&self, step_index: usize) -> u64 {
         let source_rw_increase = match self.src_type {
-            CopyDataType::Bytecode | CopyDataType::TxCalldata | CopyDataType::RlcAcc => 0,
+            CopyDataType::Bytecode
+            | CopyDataType::TxCalldata
+            | CopyDataType::RlcAcc
+            | CopyDataType::AccessListAddresses
+            | CopyDataType::AccessListStorageKeys => 0,
             CopyDataType::Memory => std::cmp::min(
                 u64::try_from(step_index + 1).unwrap() / 2,
                 self.src_addr_end
@@ -306,7 +406,10 @@ impl CopyEvent {
         };
         let destination_rw_increase = match self.dst_type {
             CopyDataType::RlcAcc | CopyDataType::Bytecode => 0,
-            CopyDataType::TxLog | CopyDataType::Memory => u64::try_from(step_index).unwrap() / 2,
+            CopyDataType::TxLog
+            | CopyDataType::Memory
+            | CopyDataType::AccessListAddresses
+            | CopyDataType::AccessListStorageKeys => u64::try_from(step_index).unwrap() / 2,
             CopyDataType::TxCalldata | CopyDataType::Padding => unreachable!(),
         };
         source_rw_increase + destination_rw_increase
@@ -349,6 +452,125 @@ pub struct ExpEvent {
     pub steps: Vec<ExpStep>,
 }
 
+/// Event representating a modular exponentiation `base ^ exponent == output
+/// (mod modulus)` of a modexp call, with `output == 0` when `modulus == 0`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ModExpEvent {
+    /// Base of the exponentiation.
+    pub base: Word,
+    /// Exponent of the exponentiation.
+    pub exponent: Word,
+    /// Modulus of the exponentiation.
+    pub modulus: Word,
+    /// Exponentiation result.
+    pub output: Word,
+}
+
+/// Event representating an addition `p + q == r` of points on the BN254 curve
+/// of an ecAdd call, with `r == 0` when the input is invalid.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EcAddEvent {
+    /// Coordinates of the first point.
+    pub p: [Word; 2],
+    /// Coordinates of the second point.
+    pub q: [Word; 2],
+    /// Coordinates of the sum.
+    pub r: [Word; 2],
+    /// Whether both points are on the curve, with coordinates in the base
+    /// field.
+    pub is_valid: bool,
+}
+
+/// Event representating a scalar multiplication `s * p == r` of a point on
+/// the BN254 curve of an ecMul call, with `r == 0` when the input is invalid.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EcMulEvent {
+    /// Coordinates of the point.
+    pub p: [Word; 2],
+    /// Scalar by which the point is multiplied.
+    pub s: Word,
+    /// Coordinates of the product.
+    pub r: [Word; 2],
+    /// Whether the point is on the curve, with coordinates in the base field.
+    pub is_valid: bool,
+}
+
+/// Event representating a compression by the function F of BLAKE2b of a
+/// successful blake2f call.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Blake2fEvent {
+    /// Number of rounds
+    pub rounds: u32,
+    /// State vector
+    pub h: [u64; 8],
+    /// Message block
+    pub m: [u64; 16],
+    /// Offset counter
+    pub t: [u64; 2],
+    /// Final block indicator flag
+    pub f: bool,
+    /// Compressed state vector
+    pub output: [u64; 8],
+}
+
+impl Blake2fEvent {
+    /// Return the bytes of `h`, `m` and `t`, the words being in little
+    /// endian as in the input of the call.
+    pub fn input_bytes(&self) -> Vec<u8> {
+        self.h
+            .iter()
+            .chain(self.m.iter())
+            .chain(self.t.iter())
+            .flat_map(|word| word.to_le_bytes())
+            .collect()
+    }
+
+    /// Return the bytes of the output, the words being in little endian.
+    pub fn output_bytes(&self) -> Vec<u8> {
+        self.output
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect()
+    }
+}
+
+/// Event representating the verification of the secp256r1 signature of a
+/// P256VERIFY call with an input of a valid length.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct P256VerifyEvent {
+    /// Hash of the signed message, not reduced modulo the order of the curve
+    pub msg_hash: Word,
+    /// r-component of the signature
+    pub sig_r: Word,
+    /// s-component of the signature
+    pub sig_s: Word,
+    /// x coordinate of the public key
+    pub pk_x: Word,
+    /// y coordinate of the public key
+    pub pk_y: Word,
+    /// Whether the public key is a point of the curve and the signature is
+    /// valid
+    pub is_valid: bool,
+}
+
+impl P256VerifyEvent {
+    /// Return the SignData of the signature to be verified by the P-256 Sig
+    /// Circuit, `None` if the signature is invalid.
+    pub fn sign_data(&self) -> Option<SignData<Secp256r1Affine>> {
+        if !self.is_valid {
+            return None;
+        }
+        secp256r1_sign_data(
+            &self.sig_r,
+            &self.sig_s,
+            &self.pk_x,
+            &self.pk_y,
+            self.msg_hash.to_be_bytes(),
+        )
+        .ok()
+    }
+}
+
 impl Default for ExpEvent {
     fn default() -> Self {
         Self {
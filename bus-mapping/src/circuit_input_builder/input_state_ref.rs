@@ -1,26 +1,31 @@
 //! CircuitInput builder tooling module.
 
 use super::{
-    get_call_memory_offset_length, get_create_init_code, Block, BlockContext, Call, CallContext,
-    CallKind, CodeSource, CopyEvent, ExecState, ExecStep, ExpEvent, Transaction,
-    TransactionContext,
+    get_call_memory_offset_length, get_create_init_code, Blake2fEvent, Block, BlockContext, Call,
+    CallContext, CallKind, CodeSource, CopyEvent, EcAddEvent, EcMulEvent, ExecState, ExecStep,
+    ExpEvent, ModExpEvent, P256VerifyEvent, Transaction, TransactionContext,
 };
 use crate::{
-    error::{DepthError, ExecError, InsufficientBalanceError, NonceUintOverflowError},
+    error::{
+        ContractAddressCollisionError, DepthError, ExecError, InsufficientBalanceError,
+        NonceUintOverflowError,
+    },
     exec_trace::OperationRef,
     operation::{
-        AccountField, AccountOp, CallContextField, CallContextOp, MemoryOp, Op, OpEnum, Operation,
-        StackOp, Target, TxAccessListAccountOp, TxLogField, TxLogOp, TxReceiptField, TxReceiptOp,
-        RW,
+        AccountField, AccountOp, CallContextField, CallContextOp, CreatedAccountOp,
+        CreatedAccountStatus, MemoryOp, Op, OpEnum, Operation, StackOp, StorageOp, Target,
+        TxAccessListAccountOp, TxLogField, TxLogOp, TxReceiptField, TxReceiptOp, RW,
     },
-    precompile::{is_precompiled, PrecompileCalls},
+    precompile::{is_modexp_supported, is_precompiled, PrecompileCalls},
     state_db::{CodeDB, StateDB},
     Error,
 };
 use eth_types::{
     evm_types::{
-        gas_utils::memory_expansion_gas_cost, GasCost, MemoryAddress, OpcodeId, StackAddress,
+        gas_utils::memory_expansion_gas_cost, GasCost, Hardfork, MemoryAddress, OpcodeId,
+        StackAddress,
     },
+    sign_types::SignData,
     Address, Bytecode, GethExecStep, ToAddress, ToBigEndian, ToWord, Word, H256, U256,
 };
 use ethers_core::utils::{get_contract_address, get_create2_address};
@@ -43,6 +48,12 @@ pub struct CircuitInputStateRef<'a> {
     pub tx_ctx: &'a mut TransactionContext,
     /// Max rw number limit
     pub max_rws: Option<usize>,
+    /// Hardfork whose rules the block is executed with
+    pub hardfork: Hardfork,
+    /// Maximum number of bytes of the inputs of the supported modexp calls
+    pub max_modexp_size: usize,
+    /// Whether the P256VERIFY precompile (RIP-7212) is enabled
+    pub p256_verify: bool,
 }
 
 impl<'a> CircuitInputStateRef<'a> {
@@ -117,6 +128,7 @@ impl<'a> CircuitInputStateRef<'a> {
                 0
             },
             log_id: self.tx_ctx.log_id,
+            gas_refund: self.sdb.refund(),
             ..Default::default()
         }
     }
@@ -442,6 +454,81 @@ impl<'a> CircuitInputStateRef<'a> {
         Ok(())
     }
 
+    /// Push a write type [`CreatedAccountOp`] of the account at `address` in
+    /// the current transaction, whose previous status is taken from the state
+    /// db, into the
+    /// [`OperationContainer`](crate::operation::OperationContainer) with the
+    /// next [`RWCounter`](crate::operation::RWCounter), and then
+    /// adds a reference to the stored operation ([`OperationRef`]) inside
+    /// the bus-mapping instance of the current [`ExecStep`].  Then increase
+    /// the `block_ctx` [`RWCounter`](crate::operation::RWCounter)  by one.
+    pub fn created_account_write(
+        &mut self,
+        step: &mut ExecStep,
+        address: Address,
+        value: CreatedAccountStatus,
+        reversible: bool,
+    ) -> Result<(), Error> {
+        let op = CreatedAccountOp::new(
+            self.tx_ctx.id(),
+            address,
+            value,
+            self.sdb.created_account_status(&address),
+        );
+        if reversible {
+            self.push_op_reversible(step, op)?;
+        } else {
+            self.sdb.set_created_account_status(address, value);
+            self.push_op(step, RW::WRITE, op)?;
+        }
+        Ok(())
+    }
+
+    /// Push a read type [`AccountOp`] of the code hash of a system contract,
+    /// which is 0 if the contract doesn't exist, and return whether the
+    /// contract has code.  A system contract which has code must have `code`.
+    pub(crate) fn system_contract_code_read(
+        &mut self,
+        step: &mut ExecStep,
+        address: Address,
+        code: &[u8],
+    ) -> Result<bool, Error> {
+        let account = self.sdb.get_account(&address).1;
+        let code_hash = if account.is_empty() {
+            Word::zero()
+        } else {
+            account.code_hash.to_word()
+        };
+        self.account_read(step, address, AccountField::CodeHash, code_hash)?;
+        if code_hash.is_zero() || code_hash == CodeDB::empty_code_hash().to_word() {
+            return Ok(false);
+        }
+        if code_hash != CodeDB::hash(code).to_word() {
+            return Err(Error::SystemContractCodeMismatch(address));
+        }
+        Ok(true)
+    }
+
+    /// Push a write type [`StorageOp`] of a system call, which is outside of
+    /// any transaction, and set the storage in the state.
+    pub(crate) fn system_storage_write(
+        &mut self,
+        step: &mut ExecStep,
+        address: Address,
+        key: Word,
+        value: Word,
+    ) -> Result<(), Error> {
+        let value_prev = *self.sdb.get_storage(&address, &key).1;
+        let committed_value = *self.sdb.get_committed_storage(&address, &key).1;
+        self.push_op(
+            step,
+            RW::WRITE,
+            StorageOp::new(address, key, value, value_prev, 0, committed_value),
+        )?;
+        self.sdb.set_storage(&address, &key, &value);
+        Ok(())
+    }
+
     /// Push a write type [`TxLogOp`] into the
     /// [`OperationContainer`](crate::operation::OperationContainer) with the
     /// next [`RWCounter`](crate::operation::RWCounter), and then
@@ -609,6 +696,7 @@ impl<'a> CircuitInputStateRef<'a> {
             sender_balance_prev,
             sender_balance
         );
+        self.sdb.touch_account(receiver);
         // If receiver doesn't exist, create it
         if !receiver_exists && (!value.is_zero() || must_create) {
             self.push_op_reversible(
@@ -673,7 +761,8 @@ impl<'a> CircuitInputStateRef<'a> {
         )
     }
 
-    /// Transfer to an address. Create an account if it is not existed before.
+    /// Transfer to an address. Create an account if it is not existed before,
+    /// unless the value is zero (EIP-161).
     pub fn transfer_to(
         &mut self,
         step: &mut ExecStep,
@@ -683,8 +772,9 @@ impl<'a> CircuitInputStateRef<'a> {
         value: Word,
         reversible: bool,
     ) -> Result<(), Error> {
+        self.sdb.touch_account(receiver);
         // If receiver doesn't exist, create it
-        if (!receiver_exists && !value.is_zero()) || must_create {
+        if !receiver_exists && (!value.is_zero() || must_create) {
             self.account_write(
                 step,
                 receiver,
@@ -860,7 +950,7 @@ impl<'a> CircuitInputStateRef<'a> {
 
     /// Check if address is a precompiled or not.
     pub fn is_precompiled(&self, address: &Address) -> bool {
-        address.0[0..19] == [0u8; 19] && (1..=9).contains(&address.0[19])
+        is_precompiled(address, self.hardfork, self.p256_verify)
     }
 
     /// Parse [`Call`] from a *CALL*/CREATE* step.
@@ -1003,6 +1093,22 @@ impl<'a> CircuitInputStateRef<'a> {
                     None
                 }
             }
+            OperationRef(Target::TransientStorage, idx) => {
+                let operation = &self.block.container.transient_storage[*idx];
+                if operation.rw().is_write() && operation.reversible() {
+                    Some(OpEnum::TransientStorage(operation.op().reverse()))
+                } else {
+                    None
+                }
+            }
+            OperationRef(Target::CreatedAccount, idx) => {
+                let operation = &self.block.container.created_account[*idx];
+                if operation.rw().is_write() && operation.reversible() {
+                    Some(OpEnum::CreatedAccount(operation.op().reverse()))
+                } else {
+                    None
+                }
+            }
             _ => None,
         }
     }
@@ -1035,6 +1141,13 @@ impl<'a> CircuitInputStateRef<'a> {
             OpEnum::TxRefund(op) => {
                 self.sdb.set_refund(op.value);
             }
+            OpEnum::TransientStorage(op) => {
+                self.sdb
+                    .set_transient_storage(&op.address, &op.key, &op.value);
+            }
+            OpEnum::CreatedAccount(op) => {
+                self.sdb.set_created_account_status(op.address, op.value);
+            }
             _ => unreachable!(),
         };
     }
@@ -1153,6 +1266,14 @@ impl<'a> CircuitInputStateRef<'a> {
                         }
                         (offset, length)
                     }
+                    // Returning from a precompile call, whose output has been
+                    // set as the return data by the call op and written at
+                    // the beginning of the callee memory.  The return data is
+                    // empty if the call failed.
+                    OpcodeId::CALL
+                    | OpcodeId::CALLCODE
+                    | OpcodeId::DELEGATECALL
+                    | OpcodeId::STATICCALL => (0, self.caller_ctx()?.return_data.len()),
                     _ => {
                         let caller_ctx = self.caller_ctx_mut()?;
                         caller_ctx.return_data.truncate(0);
@@ -1404,6 +1525,61 @@ impl<'a> CircuitInputStateRef<'a> {
         self.block.add_exp_event(event)
     }
 
+    /// Push an ecrecover event to the state.
+    pub fn push_ecrecover(&mut self, event: SignData) {
+        self.block.add_ecrecover_event(event)
+    }
+
+    /// Push a sha256 input to the state.
+    pub fn push_sha256(&mut self, input: Vec<u8>) {
+        self.block.add_sha256_input(input)
+    }
+
+    /// Push a ripemd160 input to the state.
+    pub fn push_ripemd160(&mut self, input: Vec<u8>) {
+        self.block.add_ripemd160_input(input)
+    }
+
+    /// Push a modexp event to the state.
+    pub fn push_modexp(&mut self, event: ModExpEvent) {
+        self.block.add_modexp_event(event)
+    }
+
+    /// Push an ecAdd event to the state.
+    pub fn push_ec_add(&mut self, event: EcAddEvent) {
+        self.block.add_ec_add_event(event)
+    }
+
+    /// Push an ecMul event to the state.
+    pub fn push_ec_mul(&mut self, event: EcMulEvent) {
+        self.block.add_ec_mul_event(event)
+    }
+
+    /// Push a blake2f event to the state.
+    pub fn push_blake2f(&mut self, event: Blake2fEvent) {
+        self.block.add_blake2f_event(event)
+    }
+
+    /// Push a P256VERIFY event to the state.
+    pub fn push_p256_verify(&mut self, event: P256VerifyEvent) {
+        self.block.add_p256_verify_event(event)
+    }
+
+    /// Return the call data of a *CALL* step, read from the memory of the
+    /// current call.
+    fn call_data_of_step(&self, step: &GethExecStep) -> Result<Vec<u8>, Error> {
+        let nth = if matches!(step.op, OpcodeId::CALL | OpcodeId::CALLCODE) {
+            3
+        } else {
+            2
+        };
+        let (offset, length) = get_call_memory_offset_length(step, nth)?;
+        Ok(self
+            .call_ctx()?
+            .memory
+            .read_chunk(offset.into(), length.into()))
+    }
+
     pub(crate) fn get_step_err(
         &self,
         step: &GethExecStep,
@@ -1413,7 +1589,9 @@ impl<'a> CircuitInputStateRef<'a> {
             return Ok(Some(error));
         }
 
-        if matches!(step.op, OpcodeId::INVALID(_)) {
+        if (matches!(step.op, OpcodeId::INVALID(_)) && !step.op.is_custom())
+            || !self.hardfork.is_opcode_active(step.op)
+        {
             return Ok(Some(ExecError::InvalidOpcode));
         }
 
@@ -1462,6 +1640,7 @@ impl<'a> CircuitInputStateRef<'a> {
                     OpcodeId::RETURNDATACOPY => Some(ExecError::ReturnDataOutOfBounds),
                     // Break write protection (CALL with value will be handled below)
                     OpcodeId::SSTORE
+                    | OpcodeId::TSTORE
                     | OpcodeId::CREATE
                     | OpcodeId::CREATE2
                     | OpcodeId::SELFDESTRUCT
@@ -1588,13 +1767,22 @@ impl<'a> CircuitInputStateRef<'a> {
                 })));
             }
 
-            // Address collision
-            if matches!(step.op, OpcodeId::CREATE2) {
-                let address = self.create2_address(step)?;
-
-                let (found, _) = self.sdb.get_account(&address);
-                if found {
-                    return Ok(Some(ExecError::ContractAddressCollision));
+            // Address collision, with an account which has a nonce or some code
+            if matches!(step.op, OpcodeId::CREATE | OpcodeId::CREATE2) {
+                let (address, collision_err) = if step.op == OpcodeId::CREATE {
+                    (
+                        self.create_address()?,
+                        ContractAddressCollisionError::Create,
+                    )
+                } else {
+                    (
+                        self.create2_address(step)?,
+                        ContractAddressCollisionError::Create2,
+                    )
+                };
+                let (found, account) = self.sdb.get_account(&address);
+                if found && (account.nonce != 0 || account.code_hash != CodeDB::empty_code_hash()) {
+                    return Ok(Some(ExecError::ContractAddressCollision(collision_err)));
                 }
             }
 
@@ -1608,23 +1796,19 @@ impl<'a> CircuitInputStateRef<'a> {
                 //   because the callGasTemp might probably be smaller than the gas
                 //   on top of the stack (step.stack.last())
                 // Therefore we postpone the oog handling to the implementor of callop.
-                if is_precompiled(&code_address) {
-                    let precompile_call: PrecompileCalls = code_address[19].into();
+                if self.is_precompiled(&code_address) {
+                    let precompile_call: PrecompileCalls = code_address.into();
                     match precompile_call {
-                        PrecompileCalls::Sha256
-                        | PrecompileCalls::Ripemd160
-                        | PrecompileCalls::Blake2F
-                        | PrecompileCalls::ECRecover
-                        | PrecompileCalls::Bn128Add
-                        | PrecompileCalls::Bn128Mul
-                        | PrecompileCalls::Bn128Pairing
-                        | PrecompileCalls::Modexp => {
-                            // Log the precompile address and gas left.
-                            // Failure due to precompile being unsupported.
-                            // Failure cases are routed to `PrecompileFailed` dummy gadget.
+                        PrecompileCalls::Modexp
+                            if !is_modexp_supported(
+                                &self.call_data_of_step(step)?,
+                                self.max_modexp_size,
+                            ) =>
+                        {
+                            // The base, exponent or modulus are too large for
+                            // the circuits.
                             log::trace!(
-                                "Precompile failed: code_address = {}, step.gas = {}",
-                                code_address,
+                                "Precompile failed: unsupported modexp input, step.gas = {}",
                                 step.gas,
                             );
                             return Ok(Some(ExecError::UnimplementedPrecompiles));
@@ -1793,6 +1977,27 @@ impl<'a> CircuitInputStateRef<'a> {
             self.memory_write(exec_step, (dst_addr + idx).into(), value)?;
         }
 
+        Ok(copy_steps)
+    }
+    /// Generate copy steps for a copy within the memory of the current call.
+    /// All the bytes are read before the first one is written, so that the
+    /// copy of overlapping ranges behaves as `memmove`.
+    pub(crate) fn gen_copy_steps_for_memory_copy(
+        &mut self,
+        exec_step: &mut ExecStep,
+        src_addr: u64,
+        dst_addr: u64,
+        bytes_left: u64,
+    ) -> Result<Vec<(u8, bool)>, Error> {
+        let mut copy_steps = Vec::with_capacity(bytes_left as usize);
+        for idx in 0..bytes_left {
+            let byte = self.memory_read(exec_step, (src_addr + idx).into())?;
+            copy_steps.push((byte, false));
+        }
+        for (idx, &(byte, _)) in (0..bytes_left).zip(&copy_steps) {
+            self.memory_write(exec_step, (dst_addr + idx).into(), byte)?;
+        }
+
         Ok(copy_steps)
     }
 }
@@ -1,7 +1,9 @@
 use super::*;
 use crate::{
     circuit_input_builder::access::gen_state_access_trace,
-    error::{DepthError, ExecError, InsufficientBalanceError, OogError},
+    error::{
+        ContractAddressCollisionError, DepthError, ExecError, InsufficientBalanceError, OogError,
+    },
     geth_errors::{
         GETH_ERR_GAS_UINT_OVERFLOW, GETH_ERR_OUT_OF_GAS, GETH_ERR_STACK_OVERFLOW,
         GETH_ERR_STACK_UNDERFLOW,
@@ -528,13 +530,19 @@ fn tracer_err_address_collision() {
             ..Account::zero()
         },
     );
-    builder
-        .builder
-        .sdb
-        .set_account(&create2_address, Account::zero());
+    // The contract created by the first CREATE2 has its nonce set to 1 (EIP-161)
+    builder.builder.sdb.set_account(
+        &create2_address,
+        Account {
+            nonce: 1,
+            ..Account::zero()
+        },
+    );
     assert_eq!(
         builder.state_ref().get_step_err(step, next_step).unwrap(),
-        Some(ExecError::ContractAddressCollision)
+        Some(ExecError::ContractAddressCollision(
+            ContractAddressCollisionError::Create2
+        ))
     );
 }
 
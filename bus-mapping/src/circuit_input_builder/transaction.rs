@@ -2,7 +2,10 @@
 
 use std::collections::BTreeMap;
 
-use eth_types::{evm_types::Memory, geth_types, GethExecTrace};
+use eth_types::{
+    evm_types::{Memory, MAX_REFUND_QUOTIENT_OF_GAS_USED},
+    geth_types, GethExecTrace,
+};
 use ethers_core::utils::get_contract_address;
 
 use crate::{
@@ -10,7 +13,7 @@ use crate::{
     Error,
 };
 
-use super::{call::ReversionGroup, Call, CallContext, CallKind, CodeSource, ExecStep};
+use super::{call::ReversionGroup, Call, CallContext, CallKind, CodeSource, ExecState, ExecStep};
 
 #[derive(Debug, Default)]
 /// Context of a [`Transaction`] which can mutate in an [`ExecStep`].
@@ -276,6 +279,11 @@ impl Transaction {
         &mut self.calls
     }
 
+    /// Return a mutable reference to the raw fields of this transaction.
+    pub fn tx_mut(&mut self) -> &mut geth_types::Transaction {
+        &mut self.tx
+    }
+
     pub(crate) fn push_call(&mut self, call: Call) {
         self.calls.push(call);
     }
@@ -289,6 +297,37 @@ impl Transaction {
         &self.steps[self.steps.len() - 1]
     }
 
+    /// Return the gas used by this transaction before the refund: its gas
+    /// limit minus the gas left at its EndTx step.  Invalid transactions and
+    /// padding transactions use no gas.
+    pub fn gas_used(&self) -> u64 {
+        match self.end_tx_step() {
+            Some(step) => self.tx.gas() - step.gas_left,
+            None => 0,
+        }
+    }
+
+    /// Return the gas refunded to the sender of this transaction: the refund
+    /// counter accumulated until its EndTx step, capped at
+    /// `gas_used / MAX_REFUND_QUOTIENT_OF_GAS_USED` (EIP-3529).
+    pub fn gas_refund(&self) -> u64 {
+        match self.end_tx_step() {
+            Some(step) => step
+                .gas_refund
+                .min(self.gas_used() / MAX_REFUND_QUOTIENT_OF_GAS_USED as u64),
+            None => 0,
+        }
+    }
+
+    /// Return the EndTx step of this transaction, which is followed by the
+    /// DeleteAccount steps of the destructed accounts.
+    fn end_tx_step(&self) -> Option<&ExecStep> {
+        self.steps
+            .iter()
+            .rev()
+            .find(|step| step.exec_state == ExecState::EndTx)
+    }
+
     /// Return whether the steps in this transaction is empty
     pub fn is_steps_empty(&self) -> bool {
         self.steps.is_empty()
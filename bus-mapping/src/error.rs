@@ -1,7 +1,10 @@
 //! Error module for the bus-mapping crate
 
 use core::fmt::{Display, Formatter, Result as FmtResult};
-use eth_types::{evm_types::OpcodeId, Address, GethExecStep, Word, H256};
+use eth_types::{
+    evm_types::{Hardfork, OpcodeId},
+    Address, GethExecStep, Word, H256,
+};
 use ethers_providers::ProviderError;
 use std::error::Error as StdError;
 
@@ -42,6 +45,13 @@ pub enum Error {
     InternalError(&'static str),
     /// Rw number overflow
     RwsNotEnough(usize, usize),
+    /// Transaction type which isn't enabled in the configured hardfork
+    TxTypeNotActive(&'static str, Hardfork),
+    /// Transaction type whose execution the circuits don't implement yet
+    TxTypeNotSupported(&'static str),
+    /// Code of a system contract which differs from the code the circuits
+    /// implement
+    SystemContractCodeMismatch(Address),
 }
 
 impl From<eth_types::Error> for Error {
@@ -76,7 +86,7 @@ pub enum OogError {
     /// expansion gas cost
     DynamicMemoryExpansion,
     /// Out of Gas for CALLDATACOPY, CODECOPY, EXTCODECOPY, RETURNDATACOPY,
-    /// which copy a specified chunk of memory
+    /// MCOPY, which copy a specified chunk of memory
     MemoryCopy,
     /// Out of Gas for BALANCE, EXTCODESIZE, EXTCODEHASH, which possibly touch
     /// an extra account
@@ -116,7 +126,8 @@ impl From<&OpcodeId> for OogError {
             OpcodeId::CALLDATACOPY
             | OpcodeId::CODECOPY
             | OpcodeId::EXTCODECOPY
-            | OpcodeId::RETURNDATACOPY => OogError::MemoryCopy,
+            | OpcodeId::RETURNDATACOPY
+            | OpcodeId::MCOPY => OogError::MemoryCopy,
             OpcodeId::BALANCE | OpcodeId::EXTCODESIZE | OpcodeId::EXTCODEHASH => {
                 OogError::AccountAccess
             }
@@ -156,6 +167,15 @@ pub enum NonceUintOverflowError {
     Create2,
 }
 
+/// Contract address collision errors by opcode/state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ContractAddressCollisionError {
+    /// Contract address collision during CREATE opcode.
+    Create,
+    /// Contract address collision during CREATE2 opcode.
+    Create2,
+}
+
 /// Call depth errors by opcode/state.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum DepthError {
@@ -185,8 +205,8 @@ pub enum ExecError {
     Depth(DepthError),
     /// For CALL, CALLCODE, CREATE, CREATE2
     InsufficientBalance(InsufficientBalanceError),
-    /// For CREATE2
-    ContractAddressCollision,
+    /// For CREATE, CREATE2
+    ContractAddressCollision(ContractAddressCollisionError),
     /// contract must not begin with 0xef due to EIP #3541 EVM Object Format
     /// (EOF)
     InvalidCreationCode,
@@ -3,6 +3,8 @@
 pub(crate) mod opcodes;
 
 pub use eth_types::evm_types::opcode_ids::OpcodeId;
+#[cfg(feature = "custom-opcodes")]
+pub use opcodes::register_custom_opcode;
 pub use opcodes::Opcode;
 #[cfg(any(feature = "test", test))]
 pub use opcodes::PrecompileCallArgs;
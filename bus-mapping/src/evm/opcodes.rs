@@ -1,17 +1,22 @@
 //! Definition of each opcode of the EVM.
 use crate::{
     circuit_input_builder::{CircuitInputStateRef, ExecState, ExecStep},
-    error::{DepthError, ExecError, InsufficientBalanceError, NonceUintOverflowError, OogError},
+    error::{
+        ContractAddressCollisionError, DepthError, ExecError, InsufficientBalanceError,
+        NonceUintOverflowError, OogError,
+    },
     evm::OpcodeId,
-    operation::{AccountField, AccountOp, TxAccessListAccountOp},
     Error,
 };
 use core::fmt::Debug;
-use eth_types::{evm_unimplemented, GethExecStep, ToAddress, ToWord, Word};
+use eth_types::{evm_unimplemented, GethExecStep};
 
 mod address;
 mod balance;
+mod beacon_root;
 mod begin_end_tx;
+mod blobhash;
+mod blockhash;
 mod calldatacopy;
 mod calldataload;
 mod calldatasize;
@@ -22,14 +27,19 @@ mod chainid;
 mod codecopy;
 mod codesize;
 mod create;
+#[cfg(feature = "custom-opcodes")]
+mod custom;
+mod delete_account;
 mod dup;
 mod exp;
 mod extcodecopy;
 mod extcodehash;
 mod extcodesize;
 mod gasprice;
+mod history_storage;
 mod invalid_tx;
 mod logs;
+mod mcopy;
 mod mload;
 mod mstore;
 mod number;
@@ -38,12 +48,15 @@ mod return_revert;
 mod returndatacopy;
 mod returndatasize;
 mod selfbalance;
+mod selfdestruct;
 mod sha3;
 mod sload;
 mod sstore;
 mod stackonlyop;
 mod stop;
 mod swap;
+mod tload;
+mod tstore;
 
 mod error_code_store;
 mod error_invalid_creation_code;
@@ -54,6 +67,7 @@ mod error_oog_exp;
 mod error_oog_log;
 mod error_oog_memory_copy;
 mod error_oog_precompile;
+mod error_oog_selfdestruct;
 mod error_oog_sload_sstore;
 mod error_precompile_failed;
 mod error_return_data_outofbound;
@@ -68,7 +82,10 @@ mod memory_expansion_test;
 use self::{invalid_tx::InvalidTx, sha3::Sha3};
 use address::Address;
 use balance::Balance;
+use beacon_root::BeaconRoot;
 use begin_end_tx::BeginEndTx;
+use blobhash::Blobhash;
+use blockhash::Blockhash;
 use calldatacopy::Calldatacopy;
 use calldataload::Calldataload;
 use calldatasize::Calldatasize;
@@ -78,6 +95,7 @@ use callvalue::Callvalue;
 use codecopy::Codecopy;
 use codesize::Codesize;
 use create::Create;
+use delete_account::DeleteAccount;
 use dup::Dup;
 use error_code_store::ErrorCodeStore;
 use error_invalid_creation_code::ErrorCreationCode;
@@ -87,6 +105,7 @@ use error_oog_call::OOGCall;
 use error_oog_exp::OOGExp;
 use error_oog_log::ErrorOOGLog;
 use error_oog_memory_copy::OOGMemoryCopy;
+use error_oog_selfdestruct::ErrorOOGSelfdestruct;
 use error_oog_sload_sstore::OOGSloadSstore;
 use error_return_data_outofbound::ErrorReturnDataOutOfBound;
 use error_simple::ErrorSimple;
@@ -96,7 +115,9 @@ use extcodecopy::Extcodecopy;
 use extcodehash::Extcodehash;
 use extcodesize::Extcodesize;
 use gasprice::GasPrice;
+use history_storage::HistoryStorage;
 use logs::Log;
+use mcopy::Mcopy;
 use mload::Mload;
 use mstore::Mstore;
 use origin::Origin;
@@ -104,14 +125,19 @@ use return_revert::ReturnRevert;
 use returndatacopy::Returndatacopy;
 use returndatasize::Returndatasize;
 use selfbalance::Selfbalance;
+use selfdestruct::Selfdestruct;
 use sload::Sload;
 use sstore::Sstore;
 use stackonlyop::StackOnlyOpcode;
 use stop::Stop;
 use swap::Swap;
+use tload::Tload;
+use tstore::Tstore;
 
 #[cfg(feature = "test")]
 pub use crate::precompile::PrecompileCallArgs;
+#[cfg(feature = "custom-opcodes")]
+pub use custom::register_custom_opcode;
 
 /// Generic opcode trait which defines the logic of the
 /// [`Operation`](crate::operation::Operation) that should be generated for one
@@ -204,7 +230,7 @@ fn fn_gen_associated_ops(opcode_id: &OpcodeId) -> FnGenAssociatedOps {
         OpcodeId::RETURNDATASIZE => Returndatasize::gen_associated_ops,
         OpcodeId::RETURNDATACOPY => Returndatacopy::gen_associated_ops,
         OpcodeId::EXTCODEHASH => Extcodehash::gen_associated_ops,
-        OpcodeId::BLOCKHASH => StackOnlyOpcode::<1, 1>::gen_associated_ops,
+        OpcodeId::BLOCKHASH => Blockhash::gen_associated_ops,
         OpcodeId::COINBASE => StackOnlyOpcode::<0, 1>::gen_associated_ops,
         OpcodeId::TIMESTAMP => StackOnlyOpcode::<0, 1>::gen_associated_ops,
         OpcodeId::NUMBER => StackOnlyOpcode::<0, 1>::gen_associated_ops,
@@ -213,18 +239,23 @@ fn fn_gen_associated_ops(opcode_id: &OpcodeId) -> FnGenAssociatedOps {
         OpcodeId::CHAINID => StackOnlyOpcode::<0, 1>::gen_associated_ops,
         OpcodeId::SELFBALANCE => Selfbalance::gen_associated_ops,
         OpcodeId::BASEFEE => StackOnlyOpcode::<0, 1>::gen_associated_ops,
+        OpcodeId::BLOBHASH => Blobhash::gen_associated_ops,
+        OpcodeId::BLOBBASEFEE => StackOnlyOpcode::<0, 1>::gen_associated_ops,
         OpcodeId::POP => StackOnlyOpcode::<1, 0>::gen_associated_ops,
         OpcodeId::MLOAD => Mload::gen_associated_ops,
         OpcodeId::MSTORE => Mstore::<false>::gen_associated_ops,
         OpcodeId::MSTORE8 => Mstore::<true>::gen_associated_ops,
         OpcodeId::SLOAD => Sload::gen_associated_ops,
         OpcodeId::SSTORE => Sstore::gen_associated_ops,
+        OpcodeId::TLOAD => Tload::gen_associated_ops,
+        OpcodeId::TSTORE => Tstore::gen_associated_ops,
         OpcodeId::JUMP => StackOnlyOpcode::<1, 0>::gen_associated_ops,
         OpcodeId::JUMPI => StackOnlyOpcode::<2, 0>::gen_associated_ops,
         OpcodeId::PC => StackOnlyOpcode::<0, 1>::gen_associated_ops,
         OpcodeId::MSIZE => StackOnlyOpcode::<0, 1>::gen_associated_ops,
         OpcodeId::GAS => StackOnlyOpcode::<0, 1>::gen_associated_ops,
         OpcodeId::JUMPDEST => Dummy::gen_associated_ops,
+        OpcodeId::MCOPY => Mcopy::gen_associated_ops,
         OpcodeId::DUP1 => Dup::<1>::gen_associated_ops,
         OpcodeId::DUP2 => Dup::<2>::gen_associated_ops,
         OpcodeId::DUP3 => Dup::<3>::gen_associated_ops,
@@ -267,9 +298,10 @@ fn fn_gen_associated_ops(opcode_id: &OpcodeId) -> FnGenAssociatedOps {
         OpcodeId::CREATE => Create::<false>::gen_associated_ops,
         OpcodeId::CREATE2 => Create::<true>::gen_associated_ops,
         OpcodeId::RETURN | OpcodeId::REVERT => ReturnRevert::gen_associated_ops,
-        OpcodeId::SELFDESTRUCT => {
-            evm_unimplemented!("Using dummy gen_selfdestruct_ops for opcode SELFDESTRUCT");
-            DummySelfDestruct::gen_associated_ops
+        OpcodeId::SELFDESTRUCT => Selfdestruct::gen_associated_ops,
+        #[cfg(feature = "custom-opcodes")]
+        OpcodeId::INVALID(opcode) if opcode_id.is_custom() => {
+            custom::fn_gen_associated_ops(*opcode)
         }
         _ => {
             evm_unimplemented!("Using dummy gen_associated_ops for opcode {:?}", opcode_id);
@@ -308,6 +340,9 @@ fn fn_gen_error_state_associated_ops(
         ExecError::OutOfGas(OogError::Sha3) => {
             Some(StackOnlyOpcode::<2, 0, true>::gen_associated_ops)
         }
+        ExecError::OutOfGas(OogError::SelfDestruct) => {
+            Some(ErrorOOGSelfdestruct::gen_associated_ops)
+        }
         ExecError::StackOverflow => Some(ErrorSimple::gen_associated_ops),
         ExecError::StackUnderflow => Some(ErrorSimple::gen_associated_ops),
         // call & callcode can encounter InsufficientBalance error, Use pop-7 generic CallOpcode
@@ -321,8 +356,13 @@ fn fn_gen_error_state_associated_ops(
         ExecError::InsufficientBalance(InsufficientBalanceError::Create2) => {
             Some(Create::<true>::gen_associated_ops)
         }
-        // only create2 may cause ContractAddressCollision error, so use Create::<true>.
-        ExecError::ContractAddressCollision => Some(Create::<true>::gen_associated_ops),
+        // create & create2 can encounter contract address collision.
+        ExecError::ContractAddressCollision(ContractAddressCollisionError::Create) => {
+            Some(Create::<false>::gen_associated_ops)
+        }
+        ExecError::ContractAddressCollision(ContractAddressCollisionError::Create2) => {
+            Some(Create::<true>::gen_associated_ops)
+        }
         // create & create2 can encounter nonce uint overflow.
         ExecError::NonceUintOverflow(NonceUintOverflowError::Create) => {
             Some(Create::<false>::gen_associated_ops)
@@ -416,7 +456,10 @@ pub fn gen_associated_steps(
 ) -> Result<ExecStep, Error> {
     let fn_gen_associated_steps = match execution_step {
         ExecState::BeginTx | ExecState::EndTx => BeginEndTx::gen_associated_steps,
+        ExecState::DeleteAccount => DeleteAccount::gen_associated_steps,
         ExecState::InvalidTx => InvalidTx::gen_associated_steps,
+        ExecState::BeaconRoot => BeaconRoot::gen_associated_steps,
+        ExecState::HistoryStorage => HistoryStorage::gen_associated_steps,
         _ => {
             unreachable!()
         }
@@ -424,92 +467,3 @@ pub fn gen_associated_steps(
 
     fn_gen_associated_steps(state, execution_step)
 }
-
-#[derive(Debug, Copy, Clone)]
-struct DummySelfDestruct;
-
-impl Opcode for DummySelfDestruct {
-    fn gen_associated_ops(
-        state: &mut CircuitInputStateRef,
-        geth_steps: &[GethExecStep],
-    ) -> Result<Vec<ExecStep>, Error> {
-        dummy_gen_selfdestruct_ops(state, geth_steps)
-    }
-}
-fn dummy_gen_selfdestruct_ops(
-    state: &mut CircuitInputStateRef,
-    geth_steps: &[GethExecStep],
-) -> Result<Vec<ExecStep>, Error> {
-    let geth_step = &geth_steps[0];
-    let mut exec_step = state.new_step(geth_step)?;
-    let sender = state.call()?.address;
-    let receiver = geth_step.stack.last()?.to_address();
-
-    let is_warm = state.sdb.check_account_in_access_list(&receiver);
-    state.push_op_reversible(
-        &mut exec_step,
-        TxAccessListAccountOp {
-            tx_id: state.tx_ctx.id(),
-            address: receiver,
-            is_warm: true,
-            is_warm_prev: is_warm,
-        },
-    )?;
-
-    let (found, receiver_account) = state.sdb.get_account(&receiver);
-    if !found {
-        return Err(Error::AccountNotFound(receiver));
-    }
-    let receiver_account = &receiver_account.clone();
-    let (found, sender_account) = state.sdb.get_account(&sender);
-    if !found {
-        return Err(Error::AccountNotFound(sender));
-    }
-    let sender_account = &sender_account.clone();
-    let value = sender_account.balance;
-
-    state.push_op_reversible(
-        &mut exec_step,
-        AccountOp {
-            address: sender,
-            field: AccountField::Balance,
-            value: Word::zero(),
-            value_prev: value,
-        },
-    )?;
-    state.push_op_reversible(
-        &mut exec_step,
-        AccountOp {
-            address: sender,
-            field: AccountField::Nonce,
-            value: Word::zero(),
-            value_prev: sender_account.nonce.into(),
-        },
-    )?;
-    state.push_op_reversible(
-        &mut exec_step,
-        AccountOp {
-            address: sender,
-            field: AccountField::CodeHash,
-            value: Word::zero(),
-            value_prev: sender_account.code_hash.to_word(),
-        },
-    )?;
-    if receiver != sender {
-        state.transfer_to(
-            &mut exec_step,
-            receiver,
-            !receiver_account.is_empty(),
-            false,
-            value,
-            true,
-        )?;
-    }
-
-    if state.call()?.is_persistent {
-        state.sdb.destruct_account(sender);
-    }
-
-    state.handle_return(&mut [&mut exec_step], geth_steps, !state.call()?.is_root)?;
-    Ok(vec![exec_step])
-}
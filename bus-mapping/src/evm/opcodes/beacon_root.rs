@@ -0,0 +1,57 @@
+use super::TxExecSteps;
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecState, ExecStep},
+    Error,
+};
+use eth_types::{
+    evm_types::system_contracts::{beacon_root_slots, beacon_roots_code, BEACON_ROOTS_ADDRESS},
+    ToWord,
+};
+
+/// Placeholder structure used to implement [`TxExecSteps`] trait over it
+/// corresponding to the system call which stores the parent beacon block root
+/// at the beginning of a block (EIP-4788).
+///
+/// The call is made from the system address with a gas limit which is neither
+/// bought nor charged to the block, so it doesn't touch any account but the
+/// beacon roots contract.  Instead of executing the code of the contract,
+/// the step writes the two storage slots which its code writes.  If the
+/// contract has no code, the call does nothing.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct BeaconRoot;
+
+impl TxExecSteps for BeaconRoot {
+    fn gen_associated_steps(
+        state: &mut CircuitInputStateRef,
+        _execution_step: ExecState,
+    ) -> Result<ExecStep, Error> {
+        let mut exec_step = ExecStep {
+            exec_state: ExecState::BeaconRoot,
+            rwc: state.block_ctx.rwc,
+            ..Default::default()
+        };
+        let parent_beacon_root = state
+            .block
+            .parent_beacon_root
+            .ok_or(Error::EthTypeError(eth_types::Error::IncompleteBlock))?;
+
+        if !state.system_contract_code_read(
+            &mut exec_step,
+            BEACON_ROOTS_ADDRESS,
+            &beacon_roots_code(),
+        )? {
+            return Ok(exec_step);
+        }
+
+        let timestamp = state.block.timestamp;
+        let (timestamp_slot, root_slot) = beacon_root_slots(timestamp.as_u64());
+        for (key, value) in [
+            (timestamp_slot, timestamp),
+            (root_slot, parent_beacon_root.to_word()),
+        ] {
+            state.system_storage_write(&mut exec_step, BEACON_ROOTS_ADDRESS, key, value)?;
+        }
+
+        Ok(exec_step)
+    }
+}
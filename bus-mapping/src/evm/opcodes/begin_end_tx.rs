@@ -1,15 +1,18 @@
 use super::TxExecSteps;
 use crate::{
-    circuit_input_builder::{Call, CircuitInputStateRef, ExecState, ExecStep},
-    operation::{AccountField, AccountOp, CallContextField, TxReceiptField, TxRefundOp, RW},
+    circuit_input_builder::{
+        Call, CircuitInputStateRef, CopyDataType, CopyEvent, ExecState, ExecStep, NumberOrHash,
+    },
+    operation::{
+        AccountField, AccountOp, CallContextField, CreatedAccountStatus,
+        TxAccessListAccountStorageOp, TxReceiptField, TxRefundOp, RW,
+    },
     state_db::CodeDB,
     Error,
 };
-use eth_types::{
-    evm_types::{GasCost, MAX_REFUND_QUOTIENT_OF_GAS_USED, PRECOMPILE_COUNT},
-    ToWord, Word,
-};
+use eth_types::{evm_types::MAX_REFUND_QUOTIENT_OF_GAS_USED, ToWord, Word};
 use ethers_core::utils::get_contract_address;
+use itertools::Itertools;
 
 #[derive(Clone, Copy, Debug)]
 pub(crate) struct BeginEndTx;
@@ -61,8 +64,22 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
         false,
     )?;
 
+    // Mint the ether of a deposit tx to the caller, which is kept even if the
+    // tx fails.
+    if state.tx.is_deposit() {
+        let balance_prev = state.sdb.get_account(&caller_address).1.balance;
+        state.account_write(
+            &mut exec_step,
+            caller_address,
+            AccountField::Balance,
+            balance_prev + state.tx.mint(),
+            balance_prev,
+            false,
+        )?;
+    }
+
     // Add precompile contract address to access list
-    for address in 1..=PRECOMPILE_COUNT {
+    for address in state.hardfork.precompile_addresses(state.p256_verify) {
         let address = eth_types::Address::from_low_u64_be(address);
         let is_warm_prev = !state.sdb.add_account_to_access_list(address);
         state.tx_accesslist_account_write(
@@ -86,20 +103,10 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
         )?;
     }
 
-    let init_code_gas_cost = if state.tx.is_create() {
-        // Calculate gas cost of init code for EIP-3860.
-        (state.tx.call_data.len() as u64 + 31) / 32 * eth_types::evm_types::INIT_CODE_WORD_GAS
-    } else {
-        0
-    };
+    // Add the addresses and storage keys of the tx access list (EIP-2930).
+    gen_access_list_copy_events(state, &mut exec_step)?;
 
-    let intrinsic_gas_cost = if state.tx.is_create() {
-        GasCost::CREATION_TX
-    } else {
-        GasCost::TX
-    } + state.tx.call_data_gas_cost()
-        + init_code_gas_cost;
-    exec_step.gas_cost = intrinsic_gas_cost;
+    exec_step.gas_cost = state.tx.intrinsic_gas_cost(state.hardfork);
 
     // Get code_hash of callee
     let (_, callee_account) = state.sdb.get_account(&call.address);
@@ -167,6 +174,12 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
                     value_prev: 0.into(),
                 },
             )?;
+            state.created_account_write(
+                &mut exec_step,
+                call.address,
+                CreatedAccountStatus::Created,
+                true,
+            )?;
             for (field, value) in [
                 (CallContextField::Depth, call.depth.into()),
                 (
@@ -243,6 +256,80 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
     Ok(exec_step)
 }
 
+/// Add the addresses and then the storage keys of the tx access list to the
+/// access list, with a copy event for each part of the list that is copied by
+/// the copy circuit from the tx table.
+fn gen_access_list_copy_events(
+    state: &mut CircuitInputStateRef,
+    exec_step: &mut ExecStep,
+) -> Result<(), Error> {
+    let tx_id = state.tx_ctx.id();
+    let addresses = state
+        .tx
+        .access_list_items()
+        .iter()
+        .map(|item| (item.address, Word::zero()))
+        .collect_vec();
+    let storage_keys = state
+        .tx
+        .access_list_storage_keys()
+        .map(|(address, key)| (address, key.to_word()))
+        .collect_vec();
+
+    for (copy_data_type, access_list) in [
+        (CopyDataType::AccessListAddresses, addresses),
+        (CopyDataType::AccessListStorageKeys, storage_keys),
+    ] {
+        if access_list.is_empty() {
+            continue;
+        }
+
+        let rw_counter_start = state.block_ctx.rwc;
+        let mut bytes = Vec::with_capacity(access_list.len());
+        for &(address, key) in access_list.iter() {
+            let is_warm_prev = if copy_data_type == CopyDataType::AccessListAddresses {
+                let is_warm_prev = !state.sdb.add_account_to_access_list(address);
+                state.tx_accesslist_account_write(exec_step, tx_id, address, true, is_warm_prev)?;
+                is_warm_prev
+            } else {
+                let is_warm_prev = !state.sdb.add_account_storage_to_access_list((address, key));
+                state.push_op(
+                    exec_step,
+                    RW::WRITE,
+                    TxAccessListAccountStorageOp {
+                        tx_id,
+                        address,
+                        key,
+                        is_warm: true,
+                        is_warm_prev,
+                    },
+                )?;
+                is_warm_prev
+            };
+            bytes.push((is_warm_prev as u8, false));
+        }
+
+        state.push_copy(
+            exec_step,
+            CopyEvent {
+                src_addr: 0,
+                src_addr_end: access_list.len() as u64,
+                src_type: copy_data_type,
+                src_id: NumberOrHash::Number(tx_id),
+                dst_addr: 0,
+                dst_type: copy_data_type,
+                dst_id: NumberOrHash::Number(tx_id),
+                log_id: None,
+                rw_counter_start,
+                bytes,
+                access_list,
+            },
+        );
+    }
+
+    Ok(())
+}
+
 fn gen_end_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Error> {
     let mut exec_step = state.new_end_tx_step();
     let call = state.tx.calls()[0].clone();
@@ -289,7 +376,12 @@ fn gen_end_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Error>
         false,
     )?;
 
-    let effective_tip = state.tx.gas_price - state.block.base_fee;
+    // A deposit tx doesn't pay the base fee, nor any tip, for its gas.
+    let effective_tip = if state.tx.is_deposit() {
+        Word::zero()
+    } else {
+        state.tx.gas_price - state.block.base_fee
+    };
     let (found, coinbase_account) = state.sdb.get_account(&state.block.coinbase);
     if !found {
         return Err(Error::AccountNotFound(state.block.coinbase));
@@ -0,0 +1,123 @@
+use super::Opcode;
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    operation::CallContextField,
+    Error,
+};
+use eth_types::GethExecStep;
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the [`OpcodeId::BLOBHASH`](crate::evm::OpcodeId::BLOBHASH)
+/// `OpcodeId`.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Blobhash;
+
+impl Opcode for Blobhash {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
+
+        // The blob versioned hashes are looked up in the tx table by tx_id
+        state.call_context_read(
+            &mut exec_step,
+            state.call()?.call_id,
+            CallContextField::TxId,
+            state.tx_ctx.id().into(),
+        )?;
+
+        let stack_position = geth_step.stack.last_filled();
+        state.stack_read(&mut exec_step, stack_position, geth_step.stack.last()?)?;
+        state.stack_write(&mut exec_step, stack_position, geth_steps[1].stack.last()?)?;
+
+        Ok(vec![exec_step])
+    }
+}
+
+#[cfg(test)]
+mod blobhash_tests {
+    use crate::{
+        circuit_input_builder::{ExecState, FixedCParams},
+        mock::BlockData,
+        operation::{StackOp, RW},
+    };
+    use eth_types::{
+        bytecode,
+        evm_types::{Hardfork, OpcodeId, StackAddress},
+        geth_types::GethData,
+        Word, H256,
+    };
+    use mock::{interpreter, test_ctx::helpers::account_0_code_account_1_no_code, TestContext};
+    use pretty_assertions::assert_eq;
+
+    fn test_ok(index: u64, hash: Word) {
+        let code = bytecode! {
+            .op_blobhash(index)
+            STOP
+        };
+        // The geth-utils tracer doesn't support EIP-4844 yet.
+        let block: GethData = TestContext::<2, 1>::new_with_tracer(
+            None,
+            account_0_code_account_1_no_code(code),
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .blob_versioned_hashes(vec![H256::repeat_byte(0x01)]);
+            },
+            |block, _tx| block,
+            interpreter::trace,
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data_with_params(
+            block.clone(),
+            FixedCParams {
+                hardfork: Hardfork::Cancun,
+                ..Default::default()
+            },
+        )
+        .new_circuit_input_builder();
+        let builder = builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let step = builder.block.txs()[0]
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(OpcodeId::BLOBHASH))
+            .unwrap();
+
+        let stack_ops = step.bus_mapping_instance[1..]
+            .iter()
+            .map(|op_ref| {
+                let operation = &builder.block.container.stack[op_ref.as_usize()];
+                (operation.rw(), operation.op().clone())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            stack_ops,
+            vec![
+                (
+                    RW::READ,
+                    StackOp::new(1, StackAddress(1023), Word::from(index))
+                ),
+                (RW::WRITE, StackOp::new(1, StackAddress(1023), hash)),
+            ]
+        );
+    }
+
+    #[test]
+    fn blobhash_opcode_impl() {
+        test_ok(0, Word::from_big_endian(&[0x01; 32]));
+    }
+
+    #[test]
+    fn blobhash_opcode_out_of_range() {
+        // An index beyond the blob versioned hashes of the tx returns 0.
+        test_ok(1, Word::zero());
+    }
+}
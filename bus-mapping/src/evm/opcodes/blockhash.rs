@@ -0,0 +1,182 @@
+use super::Opcode;
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    operation::{CallContextField, StorageOp, RW},
+    Error,
+};
+use eth_types::{
+    evm_types::system_contracts::{history_storage_slot, HISTORY_STORAGE_ADDRESS},
+    GethExecStep, Word,
+};
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the [`OpcodeId::BLOCKHASH`](crate::evm::OpcodeId::BLOCKHASH)
+/// `OpcodeId`.
+///
+/// Since Prague, the hashes of the blocks in the serve window are read from
+/// the storage of the history storage contract (EIP-2935), by tx_id;
+/// before, they are taken from the block history and the opcode is stack
+/// only.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Blockhash;
+
+impl Opcode for Blockhash {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
+        let is_history_storage = state.hardfork.is_history_storage_active();
+
+        if is_history_storage {
+            state.call_context_read(
+                &mut exec_step,
+                state.call()?.call_id,
+                CallContextField::TxId,
+                state.tx_ctx.id().into(),
+            )?;
+        }
+
+        let stack_position = geth_step.stack.last_filled();
+        let block_number = geth_step.stack.last()?;
+        state.stack_read(&mut exec_step, stack_position, block_number)?;
+        if !is_history_storage {
+            state.stack_write(&mut exec_step, stack_position, geth_steps[1].stack.last()?)?;
+            return Ok(vec![exec_step]);
+        }
+
+        let current_number = state.block.number;
+        let window = state.hardfork.block_hash_window();
+        let is_valid =
+            block_number < current_number && current_number - block_number <= Word::from(window);
+        if !is_valid {
+            state.stack_write(&mut exec_step, stack_position, Word::zero())?;
+            return Ok(vec![exec_step]);
+        }
+
+        let key = history_storage_slot(block_number.as_u64());
+        let block_hash = *state.sdb.get_storage(&HISTORY_STORAGE_ADDRESS, &key).1;
+        let committed_value = *state
+            .sdb
+            .get_committed_storage(&HISTORY_STORAGE_ADDRESS, &key)
+            .1;
+        state.stack_write(&mut exec_step, stack_position, block_hash)?;
+        state.push_op(
+            &mut exec_step,
+            RW::READ,
+            StorageOp::new(
+                HISTORY_STORAGE_ADDRESS,
+                key,
+                block_hash,
+                block_hash,
+                state.tx_ctx.id(),
+                committed_value,
+            ),
+        )?;
+
+        Ok(vec![exec_step])
+    }
+}
+
+#[cfg(test)]
+mod blockhash_tests {
+    use crate::{
+        circuit_input_builder::{ExecState, FixedCParams},
+        mock::BlockData,
+        operation::{StackOp, StorageOp, RW},
+    };
+    use eth_types::{
+        bytecode,
+        evm_types::{
+            system_contracts::{history_storage_code, HISTORY_STORAGE_ADDRESS},
+            Hardfork, OpcodeId, StackAddress,
+        },
+        geth_types::GethData,
+        Word,
+    };
+    use mock::{eth, TestContext, MOCK_ACCOUNTS};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn blockhash_opcode_history_storage() {
+        let current_number = 0xcafe_u64;
+        let number = current_number - 2;
+        let hash = Word::from(0xbeef);
+        let code = bytecode! {
+            PUSH32(Word::from(number))
+            BLOCKHASH
+            STOP
+        };
+        let history_hashes = vec![Word::from(0xdead), hash, Word::from(0xf00d)];
+        let block: GethData = TestContext::<3, 1>::new(
+            Some(history_hashes),
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).code(code);
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+                accs[2]
+                    .address(HISTORY_STORAGE_ADDRESS)
+                    .code(history_storage_code())
+                    .storage(vec![(Word::from(number % 8191), hash)].into_iter());
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[1].address).to(accs[0].address);
+            },
+            |block, _tx| block.number(current_number),
+        )
+        .unwrap()
+        .into();
+
+        let params = FixedCParams {
+            hardfork: Hardfork::Prague,
+            ..Default::default()
+        };
+        let mut builder = BlockData::new_from_geth_data_with_params(block.clone(), params)
+            .new_circuit_input_builder();
+        let builder = builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        // The parent hash is stored before the first tx.
+        let history_storage = builder.block.block_steps.history_storage.as_ref().unwrap();
+        assert_eq!(history_storage.bus_mapping_instance.len(), 2);
+
+        let step = builder.block.txs()[0]
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(OpcodeId::BLOCKHASH))
+            .unwrap();
+        let stack_ops = step.bus_mapping_instance[1..3]
+            .iter()
+            .map(|op_ref| {
+                let operation = &builder.block.container.stack[op_ref.as_usize()];
+                (operation.rw(), operation.op().clone())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            stack_ops,
+            vec![
+                (
+                    RW::READ,
+                    StackOp::new(1, StackAddress(1023), Word::from(number))
+                ),
+                (RW::WRITE, StackOp::new(1, StackAddress(1023), hash)),
+            ]
+        );
+        let storage_op = &builder.block.container.storage[step.bus_mapping_instance[3].as_usize()];
+        assert_eq!(
+            (storage_op.rw(), storage_op.op()),
+            (
+                RW::READ,
+                &StorageOp::new(
+                    HISTORY_STORAGE_ADDRESS,
+                    Word::from(number % 8191),
+                    hash,
+                    hash,
+                    1,
+                    hash,
+                )
+            )
+        );
+    }
+}
@@ -8,7 +8,7 @@ use crate::{
         precompiles::gen_associated_ops as precompile_associated_ops,
     },
     operation::{AccountField, CallContextField, TxAccessListAccountOp},
-    precompile::{execute_precompiled, is_precompiled, PrecompileCalls},
+    precompile::{execute_precompiled, modexp_input_len, PrecompileCalls},
     state_db::CodeDB,
     Error,
 };
@@ -180,7 +180,7 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
 
         let code_address = call.code_address();
         let is_precompile = code_address
-            .map(|ref addr| is_precompiled(addr))
+            .map(|ref addr| state.is_precompiled(addr))
             .unwrap_or(false);
         // CALLCODE does not need to do real transfer
         // Transfer value only when all these conditions met:
@@ -270,20 +270,18 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
             // 1. Call to precompiled.
             (true, true, _) => {
                 let code_address = code_address.unwrap();
-                let precompile_call: PrecompileCalls = code_address.0[19].into();
+                let precompile_call: PrecompileCalls = code_address.into();
 
                 // get the result of the precompile call.
                 // For failed call, it will cost all gas provided
-                let (result, precompile_call_gas_cost, has_oog_err) = execute_precompiled(
-                    &code_address,
-                    if args_length != 0 {
-                        let caller_memory = &state.caller_ctx()?.memory;
-                        &caller_memory.0[args_offset..args_offset + args_length]
-                    } else {
-                        &[]
-                    },
-                    callee_gas_left_with_stipend,
-                );
+                let input = if args_length != 0 {
+                    let caller_memory = &state.caller_ctx()?.memory;
+                    caller_memory.0[args_offset..args_offset + args_length].to_vec()
+                } else {
+                    vec![]
+                };
+                let (result, precompile_call_gas_cost, has_oog_err) =
+                    execute_precompiled(&code_address, &input, callee_gas_left_with_stipend);
 
                 // mutate the callee memory by at least the precompile call's result that will be
                 // written from memory addr 0 to memory addr result.len()
@@ -365,6 +363,10 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
                 if call.call_data_length > 0 {
                     let n_input_bytes = if let Some(input_len) = precompile_call.input_len() {
                         min(input_len, call.call_data_length as usize)
+                    } else if precompile_call == PrecompileCalls::Modexp {
+                        // Only the lengths and the bytes of the base, exponent
+                        // and modulus are read.
+                        modexp_input_len(&input, call.call_data_length as usize)
                     } else {
                         call.call_data_length as usize
                     };
@@ -377,18 +379,13 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
 
                     state.push_copy(
                         &mut exec_step,
-                        CopyEvent {
-                            src_id: NumberOrHash::Number(call.caller_id),
-                            src_type: CopyDataType::Memory,
-                            src_addr: call.call_data_offset,
-                            src_addr_end: call.call_data_offset + n_input_bytes as u64,
-                            dst_id: NumberOrHash::Number(call.call_id),
-                            dst_type: CopyDataType::RlcAcc,
-                            dst_addr: 0,
-                            log_id: None,
+                        CopyEvent::precompile_input(
+                            call.caller_id,
+                            call.call_id,
+                            call.call_data_offset,
+                            &input_bytes,
                             rw_counter_start,
-                            bytes: input_bytes.iter().map(|s| (*s, false)).collect(),
-                        },
+                        ),
                     );
                 }
 
@@ -400,18 +397,7 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
 
                     state.push_copy(
                         &mut exec_step,
-                        CopyEvent {
-                            src_id: NumberOrHash::Number(call.call_id),
-                            src_type: CopyDataType::RlcAcc,
-                            src_addr: 0,
-                            src_addr_end: output_bytes.len() as u64,
-                            dst_id: NumberOrHash::Number(call.call_id),
-                            dst_type: CopyDataType::Memory,
-                            dst_addr: 0,
-                            log_id: None,
-                            rw_counter_start,
-                            bytes: output_bytes.iter().map(|s| (*s, false)).collect(),
-                        },
+                        CopyEvent::precompile_output(call.call_id, &output_bytes, rw_counter_start),
                     );
                 }
 
@@ -437,11 +423,19 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
                             log_id: None,
                             rw_counter_start,
                             bytes: return_bytes.iter().map(|s| (*s, false)).collect(),
+                            access_list: vec![],
                         },
                     );
                 }
 
-                if has_oog_err {
+                // The insufficient gas of a modexp or blake2f call is handled
+                // in its own step, as its gas cost depends on the input.
+                if has_oog_err
+                    && !matches!(
+                        precompile_call,
+                        PrecompileCalls::Modexp | PrecompileCalls::Blake2F
+                    )
+                {
                     let mut oog_step = ErrorOOGPrecompile::gen_associated_ops(
                         state,
                         &geth_steps[1],
@@ -455,13 +449,15 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
 
                     Ok(vec![exec_step, oog_step])
                 } else {
-                    let precompile_call: PrecompileCalls = code_address.0[19].into();
+                    let precompile_call: PrecompileCalls = code_address.into();
 
                     let mut precompile_step = precompile_associated_ops(
                         state,
                         geth_steps[1].clone(),
                         call.clone(),
                         precompile_call,
+                        &input,
+                        &result,
                     )?;
 
                     // Set gas left and gas cost for precompile step.
@@ -2,9 +2,9 @@ use crate::{
     circuit_input_builder::{
         CircuitInputStateRef, CopyDataType, CopyEvent, ExecStep, NumberOrHash,
     },
-    error::ExecError,
+    error::{ContractAddressCollisionError, ExecError},
     evm::Opcode,
-    operation::{AccountField, AccountOp, CallContextField, MemoryOp, RW},
+    operation::{AccountField, AccountOp, CallContextField, CreatedAccountStatus, MemoryOp, RW},
     state_db::CodeDB,
     Error,
 };
@@ -127,7 +127,11 @@ impl<const IS_CREATE2: bool> Opcode for Create<IS_CREATE2> {
             // ErrContractAddressCollision
             let code_hash_previous = if callee_exists {
                 if is_precheck_ok && is_address_collision {
-                    exec_step.error = Some(ExecError::ContractAddressCollision);
+                    exec_step.error = Some(ExecError::ContractAddressCollision(if IS_CREATE2 {
+                        ContractAddressCollisionError::Create2
+                    } else {
+                        ContractAddressCollisionError::Create
+                    }));
                 }
                 callee_account.code_hash
             } else {
@@ -231,6 +235,12 @@ impl<const IS_CREATE2: bool> Opcode for Create<IS_CREATE2> {
                     value_prev: 0.into(),
                 },
             )?;
+            state.created_account_write(
+                &mut exec_step,
+                callee.address,
+                CreatedAccountStatus::Created,
+                true,
+            )?;
 
             if length > 0 {
                 for (field, value) in [
@@ -323,6 +333,7 @@ fn handle_copy(
             dst_addr: 0,
             log_id: None,
             bytes,
+            access_list: vec![],
         },
     );
 
@@ -392,7 +403,12 @@ mod tests {
             .last()
             .unwrap();
 
-        assert_eq!(step.error, Some(ExecError::ContractAddressCollision));
+        assert_eq!(
+            step.error,
+            Some(ExecError::ContractAddressCollision(
+                ContractAddressCollisionError::Create2
+            ))
+        );
 
         let container = builder.block.container.clone();
         println!("{:?}", container.stack);
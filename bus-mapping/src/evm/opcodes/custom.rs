@@ -0,0 +1,41 @@
+//! Witness generation of the custom opcodes of an L2.
+//!
+//! A fork implements [`Opcode`] for each of its [`CustomOpcode`]s and
+//! registers it with [`register_custom_opcode`].
+
+use super::{Dummy, FnGenAssociatedOps, Opcode};
+use eth_types::{
+    evm_types::custom_opcodes::{self, CustomOpcode},
+    evm_unimplemented,
+};
+use lazy_static::lazy_static;
+use std::{collections::HashMap, sync::RwLock};
+
+lazy_static! {
+    /// The witness generation of the registered custom opcodes, by byte
+    static ref CUSTOM_OPCODE_OPS: RwLock<HashMap<u8, FnGenAssociatedOps>> =
+        RwLock::new(HashMap::new());
+}
+
+/// Register a custom opcode of the L2 and the witness generation of its steps.
+/// See [`custom_opcodes::register_custom_opcode`].
+pub fn register_custom_opcode<T: CustomOpcode + Opcode>() {
+    custom_opcodes::register_custom_opcode::<T>();
+    CUSTOM_OPCODE_OPS
+        .write()
+        .expect("poisoned custom opcodes")
+        .insert(T::OPCODE, T::gen_associated_ops);
+}
+
+/// Return the witness generation of a custom opcode.
+pub(crate) fn fn_gen_associated_ops(opcode: u8) -> FnGenAssociatedOps {
+    CUSTOM_OPCODE_OPS
+        .read()
+        .expect("poisoned custom opcodes")
+        .get(&opcode)
+        .copied()
+        .unwrap_or_else(|| {
+            evm_unimplemented!("Using dummy gen_associated_ops for custom opcode {opcode:#x}");
+            Dummy::gen_associated_ops
+        })
+}
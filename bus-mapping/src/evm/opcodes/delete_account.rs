@@ -0,0 +1,79 @@
+use super::TxExecSteps;
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecState, ExecStep},
+    operation::{AccountField, CallContextField, CreatedAccountStatus},
+    state_db::Account,
+    Error,
+};
+use eth_types::{ToWord, Word};
+
+/// Placeholder structure used to implement [`TxExecSteps`] trait over it
+/// corresponding to the deletion of an account destructed by `SELFDESTRUCT`
+/// in the tx (EIP-6780).
+///
+/// The steps follow the EndTx step, one for each destructed account in the
+/// order of their addresses.  Each step deletes the lowest destructed account
+/// left: its nonce, balance and code hash are reset, and it's no longer
+/// marked as destructed.  As for the `SELFDESTRUCT` before EIP-6780, the
+/// storage of the account isn't cleared in the rw table.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct DeleteAccount;
+
+impl TxExecSteps for DeleteAccount {
+    fn gen_associated_steps(
+        state: &mut CircuitInputStateRef,
+        _execution_step: ExecState,
+    ) -> Result<ExecStep, Error> {
+        let prev_step = state
+            .tx
+            .steps()
+            .last()
+            .expect("steps should have at least one EndTx step");
+        let mut exec_step = ExecStep {
+            exec_state: ExecState::DeleteAccount,
+            rwc: state.block_ctx.rwc,
+            gas_left: prev_step.gas_left,
+            reversible_write_counter: prev_step.reversible_write_counter,
+            log_id: prev_step.log_id,
+            ..Default::default()
+        };
+        let call = state.tx.calls()[0].clone();
+        let address = *state
+            .sdb
+            .destructed_accounts()
+            .first()
+            .expect("DeleteAccount requires a destructed account");
+
+        state.call_context_read(
+            &mut exec_step,
+            call.call_id,
+            CallContextField::TxId,
+            state.tx_ctx.id().into(),
+        )?;
+        state.created_account_write(
+            &mut exec_step,
+            address,
+            CreatedAccountStatus::NotCreated,
+            false,
+        )?;
+
+        let account = state.sdb.get_account(&address).1.clone();
+        for (field, value_prev) in [
+            (AccountField::Nonce, account.nonce.into()),
+            (AccountField::Balance, account.balance),
+            (AccountField::CodeHash, account.code_hash.to_word()),
+        ] {
+            state.account_write(
+                &mut exec_step,
+                address,
+                field,
+                Word::zero(),
+                value_prev,
+                false,
+            )?;
+        }
+        state.sdb.set_account(&address, Account::zero());
+
+        Ok(exec_step)
+    }
+}
@@ -0,0 +1,54 @@
+use super::TxExecSteps;
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecState, ExecStep},
+    Error,
+};
+use eth_types::evm_types::system_contracts::{
+    history_storage_code, history_storage_slot, HISTORY_STORAGE_ADDRESS,
+};
+
+/// Placeholder structure used to implement [`TxExecSteps`] trait over it
+/// corresponding to the system call which stores the hash of the parent block
+/// at the beginning of a block (EIP-2935).
+///
+/// As for [`BeaconRoot`](super::beacon_root::BeaconRoot), the step writes the
+/// storage slot which the code of the history storage contract writes, after
+/// the beacon root system call, and does nothing if the contract has no code.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct HistoryStorage;
+
+impl TxExecSteps for HistoryStorage {
+    fn gen_associated_steps(
+        state: &mut CircuitInputStateRef,
+        _execution_step: ExecState,
+    ) -> Result<ExecStep, Error> {
+        let mut exec_step = ExecStep {
+            exec_state: ExecState::HistoryStorage,
+            rwc: state.block_ctx.rwc,
+            ..Default::default()
+        };
+        let parent_hash = *state
+            .block
+            .history_hashes
+            .last()
+            .ok_or(Error::EthTypeError(eth_types::Error::IncompleteBlock))?;
+
+        if !state.system_contract_code_read(
+            &mut exec_step,
+            HISTORY_STORAGE_ADDRESS,
+            &history_storage_code(),
+        )? {
+            return Ok(exec_step);
+        }
+
+        let parent_number = state.block.number.as_u64() - 1;
+        state.system_storage_write(
+            &mut exec_step,
+            HISTORY_STORAGE_ADDRESS,
+            history_storage_slot(parent_number),
+            parent_hash,
+        )?;
+
+        Ok(exec_step)
+    }
+}
@@ -0,0 +1,157 @@
+use super::Opcode;
+use crate::{
+    circuit_input_builder::{
+        CircuitInputStateRef, CopyDataType, CopyEvent, ExecStep, NumberOrHash,
+    },
+    Error,
+};
+use eth_types::GethExecStep;
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the [`OpcodeId::MCOPY`](crate::evm::OpcodeId::MCOPY)
+/// `OpcodeId`.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Mcopy;
+
+impl Opcode for Mcopy {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
+
+        let dst_offset = geth_step.stack.nth_last(0)?;
+        let src_offset = geth_step.stack.nth_last(1)?;
+        let length = geth_step.stack.nth_last(2)?;
+        for (i, value) in [dst_offset, src_offset, length].into_iter().enumerate() {
+            state.stack_read(&mut exec_step, geth_step.stack.nth_last_filled(i), value)?;
+        }
+
+        // The memory is expanded to cover both the source and destination ranges.
+        let memory = &mut state.call_ctx_mut()?.memory;
+        memory.extend_for_range(src_offset, length);
+        memory.extend_for_range(dst_offset, length);
+
+        // Get low Uint64 of the offsets to generate copy steps. Since they
+        // could be Uint64 overflow if length is zero.
+        let copy_event = gen_copy_event(
+            state,
+            &mut exec_step,
+            src_offset.low_u64(),
+            dst_offset.low_u64(),
+            length.as_u64(),
+        )?;
+        state.push_copy(&mut exec_step, copy_event);
+        Ok(vec![exec_step])
+    }
+}
+
+fn gen_copy_event(
+    state: &mut CircuitInputStateRef,
+    exec_step: &mut ExecStep,
+    src_addr: u64,
+    dst_addr: u64,
+    length: u64,
+) -> Result<CopyEvent, Error> {
+    let rw_counter_start = state.block_ctx.rwc;
+    let bytes = state.gen_copy_steps_for_memory_copy(exec_step, src_addr, dst_addr, length)?;
+
+    let call_id = NumberOrHash::Number(state.call()?.call_id);
+    Ok(CopyEvent {
+        src_type: CopyDataType::Memory,
+        src_id: call_id.clone(),
+        src_addr,
+        src_addr_end: src_addr + length,
+        dst_type: CopyDataType::Memory,
+        dst_id: call_id,
+        dst_addr,
+        log_id: None,
+        rw_counter_start,
+        bytes,
+        access_list: vec![],
+    })
+}
+
+#[cfg(test)]
+mod mcopy_tests {
+    use crate::{
+        circuit_input_builder::{ExecState, FixedCParams},
+        mock::BlockData,
+        operation::{MemoryOp, RW},
+    };
+    use eth_types::{
+        bytecode,
+        evm_types::{Hardfork, OpcodeId},
+        geth_types::GethData,
+        Word,
+    };
+    use mock::{
+        interpreter,
+        test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+        TestContext,
+    };
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn mcopy_opcode_overlapping_ranges() {
+        let word = Word::from_big_endian(&(1..=32).collect::<Vec<u8>>());
+        let code = bytecode! {
+            .op_mstore(0x00, word)
+            .op_mcopy(0x08, 0x00, 0x10)
+            STOP
+        };
+
+        // The geth-utils tracer doesn't support EIP-5656 yet.
+        let block: GethData = TestContext::<2, 1>::new_with_tracer(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block,
+            interpreter::trace,
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data_with_params(
+            block.clone(),
+            FixedCParams {
+                hardfork: Hardfork::Cancun,
+                ..Default::default()
+            },
+        )
+        .new_circuit_input_builder();
+        let builder = builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let step = builder.block.txs()[0]
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(OpcodeId::MCOPY))
+            .unwrap();
+        let call_id = builder.block.txs()[0].calls()[0].call_id;
+
+        // The 16 reads of the source come before the 16 writes of the destination.
+        let memory_ops = (3..step.bus_mapping_instance.len())
+            .map(|idx| {
+                let op = &builder.block.container.memory[step.bus_mapping_instance[idx].as_usize()];
+                (op.rw(), op.op().clone())
+            })
+            .collect::<Vec<_>>();
+        let expected = (0..0x10usize)
+            .map(|idx| (RW::READ, MemoryOp::new(call_id, idx.into(), idx as u8 + 1)))
+            .chain((0..0x10usize).map(|idx| {
+                (
+                    RW::WRITE,
+                    MemoryOp::new(call_id, (0x08 + idx).into(), idx as u8 + 1),
+                )
+            }))
+            .collect::<Vec<_>>();
+        assert_eq!(memory_ops, expected);
+
+        let copy_event = &builder.block.copy_events[0];
+        assert!(copy_event.is_memory_copy());
+        assert_eq!(copy_event.rw_counter_delta(), 0x20);
+    }
+}
@@ -3,7 +3,10 @@ use eth_types::{GethExecStep, ToWord, Word};
 use crate::{
     circuit_input_builder::{Call, CircuitInputStateRef, ExecState, ExecStep},
     operation::CallContextField,
-    precompile::PrecompileCalls,
+    precompile::{
+        Blake2fAuxData, EcAddAuxData, EcMulAuxData, EcrecoverAuxData, ModExpAuxData,
+        P256VerifyAuxData, PrecompileAuxData, PrecompileCalls, Ripemd160AuxData, Sha256AuxData,
+    },
     Error,
 };
 
@@ -12,6 +15,8 @@ pub fn gen_associated_ops(
     geth_step: GethExecStep,
     call: Call,
     precompile: PrecompileCalls,
+    input_bytes: &[u8],
+    output_bytes: &[u8],
 ) -> Result<ExecStep, Error> {
     assert_eq!(call.code_address(), Some(precompile.into()));
     let mut exec_step = state.new_step(&geth_step)?;
@@ -19,6 +24,73 @@ pub fn gen_associated_ops(
 
     common_call_ctx_reads(state, &mut exec_step, &call)?;
 
+    match precompile {
+        PrecompileCalls::ECRecover => {
+            let aux_data = EcrecoverAuxData::new(input_bytes, output_bytes);
+            // Only the signatures that could be recovered are verified in the
+            // SigTable.
+            if let Some(sign_data) = aux_data.sign_data() {
+                state.push_ecrecover(sign_data);
+            }
+            exec_step.aux_data = Some(PrecompileAuxData::Ecrecover(aux_data));
+        }
+        PrecompileCalls::Sha256 => {
+            // The whole call data is hashed, and its digest looked up in the
+            // Sha256Table.
+            let aux_data = Sha256AuxData::new(input_bytes, output_bytes);
+            state.push_sha256(aux_data.input_bytes.clone());
+            exec_step.aux_data = Some(PrecompileAuxData::Sha256(aux_data));
+        }
+        PrecompileCalls::Ripemd160 => {
+            // As for sha256, the digest of the whole call data is looked up in
+            // the Ripemd160Table.
+            let aux_data = Ripemd160AuxData::new(input_bytes, output_bytes);
+            state.push_ripemd160(aux_data.input_bytes.clone());
+            exec_step.aux_data = Some(PrecompileAuxData::Ripemd160(aux_data));
+        }
+        PrecompileCalls::Modexp => {
+            // Only the modular exponentiations of the successful calls are
+            // looked up in the ModExpTable.
+            let aux_data = ModExpAuxData::new(input_bytes, output_bytes);
+            if call.is_success {
+                state.push_modexp(aux_data.event());
+            }
+            exec_step.aux_data = Some(PrecompileAuxData::Modexp(aux_data));
+        }
+        PrecompileCalls::Bn128Add => {
+            // The call only fails for invalid points, whose invalidity is
+            // also looked up in the EccTable.
+            let aux_data = EcAddAuxData::new(input_bytes, output_bytes);
+            state.push_ec_add(aux_data.event(call.is_success));
+            exec_step.aux_data = Some(PrecompileAuxData::EcAdd(aux_data));
+        }
+        PrecompileCalls::Bn128Mul => {
+            let aux_data = EcMulAuxData::new(input_bytes, output_bytes);
+            state.push_ec_mul(aux_data.event(call.is_success));
+            exec_step.aux_data = Some(PrecompileAuxData::EcMul(aux_data));
+        }
+        PrecompileCalls::Blake2F => {
+            // As for modexp, only the compressions of the successful calls are
+            // looked up in the Blake2fTable.
+            let aux_data = Blake2fAuxData::new(input_bytes, output_bytes);
+            if call.is_success {
+                state.push_blake2f(aux_data.event());
+            }
+            exec_step.aux_data = Some(PrecompileAuxData::Blake2f(aux_data));
+        }
+        PrecompileCalls::P256Verify => {
+            // The call only fails when it runs out of gas.  The signature of
+            // an input with a valid length is verified in the P-256 SigTable,
+            // any other input being invalid.
+            let aux_data = P256VerifyAuxData::new(input_bytes, output_bytes);
+            if aux_data.has_valid_length() {
+                state.push_p256_verify(aux_data.event());
+            }
+            exec_step.aux_data = Some(PrecompileAuxData::P256Verify(aux_data));
+        }
+        _ => {}
+    }
+
     Ok(exec_step)
 }
 
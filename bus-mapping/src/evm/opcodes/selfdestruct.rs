@@ -0,0 +1,232 @@
+use super::Opcode;
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    operation::{AccountField, CallContextField, CreatedAccountStatus},
+    Error,
+};
+use eth_types::{GethExecStep, ToAddress, ToWord, Word};
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the
+/// [`OpcodeId::SELFDESTRUCT`](crate::evm::OpcodeId::SELFDESTRUCT) `OpcodeId`.
+///
+/// Since EIP-6780, the whole balance of the account is sent to the
+/// beneficiary, but the account itself is only destructed when it has been
+/// created in the same transaction.  The destructed account keeps its code
+/// until it's deleted by a `DeleteAccount` step at the end of the
+/// transaction.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Selfdestruct;
+
+impl Opcode for Selfdestruct {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
+
+        let call = state.call()?.clone();
+        let sender = call.address;
+        let beneficiary_word = geth_step.stack.last()?;
+        let beneficiary = beneficiary_word.to_address();
+
+        for (field, value) in [
+            (CallContextField::TxId, state.tx_ctx.id().into()),
+            (CallContextField::IsStatic, (call.is_static as u64).into()),
+            (CallContextField::IsSuccess, 1.into()),
+        ] {
+            state.call_context_read(&mut exec_step, call.call_id, field, value)?;
+        }
+        state.reversion_info_read(&mut exec_step, &call)?;
+        state.call_context_read(
+            &mut exec_step,
+            call.call_id,
+            CallContextField::CalleeAddress,
+            sender.to_word(),
+        )?;
+
+        state.stack_read(
+            &mut exec_step,
+            geth_step.stack.last_filled(),
+            beneficiary_word,
+        )?;
+
+        state.tx_access_list_write(&mut exec_step, beneficiary)?;
+
+        let beneficiary_account = state.sdb.get_account(&beneficiary).1.clone();
+        let beneficiary_exists = !beneficiary_account.is_empty();
+        state.account_read(
+            &mut exec_step,
+            beneficiary,
+            AccountField::CodeHash,
+            if beneficiary_exists {
+                beneficiary_account.code_hash.to_word()
+            } else {
+                Word::zero()
+            },
+        )?;
+
+        let (found, sender_account) = state.sdb.get_account(&sender);
+        if !found {
+            return Err(Error::AccountNotFound(sender));
+        }
+        let value = sender_account.balance;
+
+        // The account is marked as destructed, to be deleted at the end of the
+        // tx, only when it has been created in the tx.  The mark is reverted
+        // with the other writes of the call.
+        let is_destructed =
+            state.sdb.created_account_status(&sender) != CreatedAccountStatus::NotCreated;
+        state.created_account_write(
+            &mut exec_step,
+            sender,
+            if is_destructed {
+                CreatedAccountStatus::Destructed
+            } else {
+                CreatedAccountStatus::NotCreated
+            },
+            true,
+        )?;
+
+        // The balance is kept only when the beneficiary is the account itself
+        // and it isn't destructed, otherwise it's either sent or burnt.
+        state.account_write(
+            &mut exec_step,
+            sender,
+            AccountField::Balance,
+            if beneficiary == sender && !is_destructed {
+                value
+            } else {
+                Word::zero()
+            },
+            value,
+            true,
+        )?;
+        if beneficiary != sender {
+            state.transfer_to(
+                &mut exec_step,
+                beneficiary,
+                beneficiary_exists,
+                false,
+                value,
+                true,
+            )?;
+        }
+
+        state.handle_return(&mut [&mut exec_step], geth_steps, !call.is_root)?;
+        Ok(vec![exec_step])
+    }
+}
+
+#[cfg(test)]
+mod selfdestruct_tests {
+    use crate::{
+        circuit_input_builder::ExecState,
+        mock::BlockData,
+        operation::{AccountField, AccountOp, Target, RW},
+        state_db::CodeDB,
+    };
+    use eth_types::{bytecode, evm_types::OpcodeId, geth_types::GethData, Bytecode, ToWord, Word};
+    use mock::{
+        eth,
+        test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+        TestContext, MOCK_ACCOUNTS,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn account_ops(code: Bytecode) -> Vec<(RW, AccountOp)> {
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into();
+
+        let builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        let builder = builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let step = builder.block.txs()[0]
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(OpcodeId::SELFDESTRUCT))
+            .unwrap();
+
+        step.bus_mapping_instance
+            .iter()
+            .filter(|op_ref| op_ref.target() == Target::Account)
+            .map(|op_ref| {
+                let op = &builder.block.container.account[op_ref.as_usize()];
+                (op.rw(), op.op().clone())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn selfdestruct_opcode_keeps_existing_account() {
+        let beneficiary = MOCK_ACCOUNTS[2];
+        let code = bytecode! {
+            .op_selfdestruct(beneficiary.to_word())
+        };
+
+        // The balance is sent to the beneficiary, which is created, but the
+        // nonce and code hash of the account, which wasn't created in the tx,
+        // are untouched.
+        assert_eq!(
+            account_ops(code),
+            vec![
+                (
+                    RW::READ,
+                    AccountOp::new(
+                        beneficiary,
+                        AccountField::CodeHash,
+                        Word::zero(),
+                        Word::zero()
+                    )
+                ),
+                (
+                    RW::WRITE,
+                    AccountOp::new(
+                        MOCK_ACCOUNTS[0],
+                        AccountField::Balance,
+                        Word::zero(),
+                        eth(10)
+                    )
+                ),
+                (
+                    RW::WRITE,
+                    AccountOp::new(
+                        beneficiary,
+                        AccountField::CodeHash,
+                        CodeDB::empty_code_hash().to_word(),
+                        Word::zero()
+                    )
+                ),
+                (
+                    RW::WRITE,
+                    AccountOp::new(beneficiary, AccountField::Balance, eth(10), Word::zero())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn selfdestruct_opcode_to_itself_keeps_balance() {
+        let code = bytecode! {
+            ADDRESS
+            SELFDESTRUCT
+        };
+
+        assert_eq!(
+            account_ops(code)[1],
+            (
+                RW::WRITE,
+                AccountOp::new(MOCK_ACCOUNTS[0], AccountField::Balance, eth(10), eth(10))
+            )
+        );
+    }
+}
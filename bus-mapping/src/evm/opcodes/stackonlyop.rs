@@ -59,20 +59,21 @@ impl<const N_POP: usize, const N_PUSH: usize, const IS_ERR: bool> Opcode
 #[cfg(test)]
 mod stackonlyop_tests {
     use crate::{
-        circuit_input_builder::ExecState,
+        circuit_input_builder::{ExecState, FixedCParams},
         mock::BlockData,
         operation::{StackOp, RW},
     };
     use eth_types::{
         bytecode,
-        evm_types::{OpcodeId, StackAddress},
+        evm_types::{Hardfork, OpcodeId, StackAddress, MIN_BLOB_BASE_FEE},
         geth_types::GethData,
         word, Bytecode, Hash, ToWord, Word,
     };
     use itertools::Itertools;
     use mock::{
+        interpreter,
         test_ctx::{helpers::*, TestContext},
-        MOCK_BASEFEE, MOCK_DIFFICULTY, MOCK_GASLIMIT, MOCK_MIX_HASH,
+        MOCK_BASEFEE, MOCK_GASLIMIT, MOCK_MIX_HASH,
     };
     use pretty_assertions::assert_eq;
     use std::ops::{BitOr, BitXor};
@@ -397,6 +398,8 @@ mod stackonlyop_tests {
 
     #[test]
     fn difficulty_opcode_impl() {
+        // The tracer is post-merge, so DIFFICULTY returns the prev_randao of the
+        // block instead of its difficulty, and the mix hash of the mock block is 0.
         stack_only_opcode_impl::<0, 1>(
             OpcodeId::DIFFICULTY,
             bytecode! {
@@ -404,7 +407,7 @@ mod stackonlyop_tests {
                 STOP
             },
             vec![],
-            vec![StackOp::new(1, StackAddress(1023), *MOCK_DIFFICULTY)],
+            vec![StackOp::new(1, StackAddress(1023), Word::zero())],
         );
     }
 
@@ -448,6 +451,53 @@ mod stackonlyop_tests {
         );
     }
 
+    #[test]
+    fn blobbasefee_opcode_impl() {
+        let code = bytecode! {
+            BLOBBASEFEE
+            STOP
+        };
+
+        // The geth-utils tracer doesn't support EIP-7516 yet.
+        let block: GethData = TestContext::<2, 1>::new_with_tracer(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block,
+            interpreter::trace,
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data_with_params(
+            block.clone(),
+            FixedCParams {
+                hardfork: Hardfork::Cancun,
+                ..Default::default()
+            },
+        )
+        .new_circuit_input_builder();
+        let builder = builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let step = builder.block.txs()[0]
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(OpcodeId::BLOBBASEFEE))
+            .unwrap();
+
+        // The block has no excess blob gas, so the fee is the minimum one.
+        let operation = &builder.block.container.stack[step.bus_mapping_instance[0].as_usize()];
+        assert_eq!(
+            (operation.rw(), operation.op()),
+            (
+                RW::WRITE,
+                &StackOp::new(1, StackAddress(1023), Word::from(MIN_BLOB_BASE_FEE))
+            )
+        );
+    }
+
     #[test]
     fn push0_opcode_impl() {
         stack_only_opcode_impl::<0, 1>(
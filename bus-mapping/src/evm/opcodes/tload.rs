@@ -0,0 +1,152 @@
+use super::Opcode;
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    operation::{CallContextField, TransientStorageOp, RW},
+    Error,
+};
+use eth_types::{GethExecStep, ToWord, Word};
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the [`OpcodeId::TLOAD`](crate::evm::OpcodeId::TLOAD)
+/// `OpcodeId`.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Tload;
+
+impl Opcode for Tload {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
+
+        let call_id = state.call()?.call_id;
+        let contract_addr = state.call()?.address;
+
+        state.call_context_read(
+            &mut exec_step,
+            call_id,
+            CallContextField::TxId,
+            Word::from(state.tx_ctx.id()),
+        )?;
+
+        state.call_context_read(
+            &mut exec_step,
+            call_id,
+            CallContextField::CalleeAddress,
+            contract_addr.to_word(),
+        )?;
+
+        // First stack read
+        let key = geth_step.stack.last()?;
+        let stack_position = geth_step.stack.last_filled();
+
+        // Manage first stack read at latest stack position
+        state.stack_read(&mut exec_step, stack_position, key)?;
+
+        // Transient storage read.  The traces don't include the transient
+        // storage, so the value is the one written earlier in the transaction.
+        let (_, value) = state.sdb.get_transient_storage(&contract_addr, &key);
+        let value = *value;
+        state.push_op(
+            &mut exec_step,
+            RW::READ,
+            TransientStorageOp::new(state.tx_ctx.id(), contract_addr, key, value, value),
+        )?;
+
+        // First stack write
+        state.stack_write(&mut exec_step, stack_position, value)?;
+
+        Ok(vec![exec_step])
+    }
+}
+
+#[cfg(test)]
+mod tload_tests {
+    use super::*;
+    use crate::{
+        circuit_input_builder::{ExecState, FixedCParams},
+        mock::BlockData,
+        operation::StackOp,
+    };
+    use eth_types::{
+        bytecode,
+        evm_types::{Hardfork, OpcodeId, StackAddress},
+        geth_types::GethData,
+        Word,
+    };
+    use mock::{
+        interpreter,
+        test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+        TestContext, MOCK_ACCOUNTS,
+    };
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn tload_opcode_reads_value_of_tx() {
+        let code = bytecode! {
+            .op_tload(0x00)
+            .op_tstore(0x00, 0x6f)
+            .op_tload(0x00)
+            STOP
+        };
+
+        // The geth-utils tracer doesn't support EIP-1153 yet.
+        let block: GethData = TestContext::<2, 1>::new_with_tracer(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block,
+            interpreter::trace,
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data_with_params(
+            block.clone(),
+            FixedCParams {
+                hardfork: Hardfork::Cancun,
+                ..Default::default()
+            },
+        )
+        .new_circuit_input_builder();
+        let builder = builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let steps = builder.block.txs()[0]
+            .steps()
+            .iter()
+            .filter(|step| step.exec_state == ExecState::Op(OpcodeId::TLOAD))
+            .collect::<Vec<_>>();
+        assert_eq!(steps.len(), 2);
+
+        // The first TLOAD reads the empty transient storage, and the second one
+        // the value written by TSTORE.
+        for (step, value) in steps.into_iter().zip([0x00u64, 0x6f]) {
+            let transient_storage_op =
+                &builder.block.container.transient_storage[step.bus_mapping_instance[3].as_usize()];
+            assert_eq!(
+                (transient_storage_op.rw(), transient_storage_op.op()),
+                (
+                    RW::READ,
+                    &TransientStorageOp::new(
+                        1,
+                        MOCK_ACCOUNTS[0],
+                        Word::zero(),
+                        Word::from(value),
+                        Word::from(value),
+                    )
+                )
+            );
+            let stack_op = &builder.block.container.stack[step.bus_mapping_instance[4].as_usize()];
+            assert_eq!(
+                (stack_op.rw(), stack_op.op()),
+                (
+                    RW::WRITE,
+                    &StackOp::new(1, StackAddress::from(1023), Word::from(value))
+                )
+            );
+        }
+    }
+}
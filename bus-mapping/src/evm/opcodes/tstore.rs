@@ -0,0 +1,189 @@
+use super::Opcode;
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    operation::{CallContextField, TransientStorageOp},
+    Error,
+};
+use eth_types::{GethExecStep, ToWord, Word};
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the [`OpcodeId::TSTORE`](crate::evm::OpcodeId::TSTORE)
+/// `OpcodeId`.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Tstore;
+
+impl Opcode for Tstore {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
+
+        let contract_addr = state.call()?.address;
+
+        state.call_context_read(
+            &mut exec_step,
+            state.call()?.call_id,
+            CallContextField::TxId,
+            Word::from(state.tx_ctx.id()),
+        )?;
+        state.call_context_read(
+            &mut exec_step,
+            state.call()?.call_id,
+            CallContextField::IsStatic,
+            Word::from(state.call()?.is_static as u8),
+        )?;
+
+        state.call_context_read(
+            &mut exec_step,
+            state.call()?.call_id,
+            CallContextField::RwCounterEndOfReversion,
+            Word::from(state.call()?.rw_counter_end_of_reversion),
+        )?;
+
+        state.call_context_read(
+            &mut exec_step,
+            state.call()?.call_id,
+            CallContextField::IsPersistent,
+            Word::from(state.call()?.is_persistent as u8),
+        )?;
+
+        state.call_context_read(
+            &mut exec_step,
+            state.call()?.call_id,
+            CallContextField::CalleeAddress,
+            contract_addr.to_word(),
+        )?;
+
+        let key = geth_step.stack.nth_last(0)?;
+        let key_stack_position = geth_step.stack.nth_last_filled(0);
+        let value = geth_step.stack.nth_last(1)?;
+        let value_stack_position = geth_step.stack.nth_last_filled(1);
+
+        state.stack_read(&mut exec_step, key_stack_position, key)?;
+        state.stack_read(&mut exec_step, value_stack_position, value)?;
+
+        // The write is reverted together with the other state changes of the
+        // call when it fails.
+        let (_, value_prev) = state.sdb.get_transient_storage(&contract_addr, &key);
+        let value_prev = *value_prev;
+        state.push_op_reversible(
+            &mut exec_step,
+            TransientStorageOp::new(state.tx_ctx.id(), contract_addr, key, value, value_prev),
+        )?;
+
+        Ok(vec![exec_step])
+    }
+}
+
+#[cfg(test)]
+mod tstore_tests {
+    use super::*;
+    use crate::{
+        circuit_input_builder::{ExecState, FixedCParams},
+        mock::BlockData,
+        operation::RW,
+    };
+    use eth_types::{
+        bytecode,
+        evm_types::{Hardfork, OpcodeId},
+        geth_types::GethData,
+        Word,
+    };
+    use mock::{
+        interpreter,
+        test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+        TestContext, MOCK_ACCOUNTS,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn test_ok(is_persistent: bool) {
+        let mut code = bytecode! {
+            .op_tstore(0x00, 0x6f)
+            .op_tstore(0x00, 0x70)
+        };
+        if is_persistent {
+            code.op_stop();
+        } else {
+            code.op_revert(0x00, 0x00);
+        }
+
+        // The geth-utils tracer doesn't support EIP-1153 yet.
+        let block: GethData = TestContext::<2, 1>::new_with_tracer(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block,
+            interpreter::trace,
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data_with_params(
+            block.clone(),
+            FixedCParams {
+                hardfork: Hardfork::Cancun,
+                ..Default::default()
+            },
+        )
+        .new_circuit_input_builder();
+        let builder = builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let steps = builder.block.txs()[0]
+            .steps()
+            .iter()
+            .filter(|step| step.exec_state == ExecState::Op(OpcodeId::TSTORE))
+            .collect::<Vec<_>>();
+        assert_eq!(steps.len(), 2);
+
+        for (step, (value, value_prev)) in steps.into_iter().zip([(0x6fu64, 0x00u64), (0x70, 0x6f)])
+        {
+            let transient_storage_op =
+                &builder.block.container.transient_storage[step.bus_mapping_instance[7].as_usize()];
+            assert_eq!(
+                (transient_storage_op.rw(), transient_storage_op.op()),
+                (
+                    RW::WRITE,
+                    &TransientStorageOp::new(
+                        1,
+                        MOCK_ACCOUNTS[0],
+                        Word::zero(),
+                        Word::from(value),
+                        Word::from(value_prev),
+                    )
+                )
+            );
+        }
+
+        // The writes of a failed call are reverted in reverse order, at the end
+        // of the call.
+        let reverted = &builder.block.container.transient_storage[2..];
+        if is_persistent {
+            assert!(reverted.is_empty());
+        } else {
+            assert_eq!(
+                reverted
+                    .iter()
+                    .map(|operation| (operation.op().value, operation.op().value_prev))
+                    .collect::<Vec<_>>(),
+                vec![
+                    (Word::from(0x6f), Word::from(0x70)),
+                    (Word::from(0x00), Word::from(0x6f))
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn tstore_opcode_persistent() {
+        test_ok(true);
+    }
+
+    #[test]
+    fn tstore_opcode_reverted() {
+        test_ok(false);
+    }
+}
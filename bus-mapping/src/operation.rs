@@ -116,6 +116,10 @@ pub enum Target {
     TxReceipt,
     /// Means the target of the operation is the TxLog.
     TxLog,
+    /// Means the target of the operation is the TransientStorage.
+    TransientStorage,
+    /// Means the target of the operation is the CreatedAccount.
+    CreatedAccount,
 }
 
 impl_expr!(Target);
@@ -136,6 +140,8 @@ impl Target {
                 | Target::TxRefund
                 | Target::Account
                 | Target::Storage
+                | Target::TransientStorage
+                | Target::CreatedAccount
         )
     }
 }
@@ -405,6 +411,177 @@ impl Ord for StorageOp {
     }
 }
 
+/// Represents a change in the transient storage of an account (EIP-1153)
+/// implied by a `TLOAD` or `TSTORE` step of the
+/// [`ExecStep`](crate::circuit_input_builder::ExecStep).  The transient
+/// storage is empty at the beginning of every transaction.
+#[derive(Clone, PartialEq, Eq)]
+pub struct TransientStorageOp {
+    /// Transaction ID: Transaction index in the block starting at 1.
+    pub tx_id: usize,
+    /// Account Address
+    pub address: Address,
+    /// Transient Storage Key
+    pub key: Word,
+    /// Transient Storage Value after the operation
+    pub value: Word,
+    /// Transient Storage Value before the operation
+    pub value_prev: Word,
+}
+
+impl fmt::Debug for TransientStorageOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TransientStorageOp { ")?;
+        f.write_fmt(format_args!(
+            "tx_id: {:?}, addr: {:?}, key: {:?}, val_prev: 0x{:x}, val: 0x{:x}",
+            self.tx_id, self.address, self.key, self.value_prev, self.value
+        ))?;
+        f.write_str(" }")
+    }
+}
+
+impl TransientStorageOp {
+    /// Create a new instance of a `TransientStorageOp` from it's components.
+    pub const fn new(
+        tx_id: usize,
+        address: Address,
+        key: Word,
+        value: Word,
+        value_prev: Word,
+    ) -> TransientStorageOp {
+        TransientStorageOp {
+            tx_id,
+            address,
+            key,
+            value,
+            value_prev,
+        }
+    }
+
+    /// Returns the [`Target`] (operation type) of this operation.
+    pub const fn target(&self) -> Target {
+        Target::TransientStorage
+    }
+}
+
+impl Op for TransientStorageOp {
+    fn into_enum(self) -> OpEnum {
+        OpEnum::TransientStorage(self)
+    }
+
+    fn reverse(&self) -> Self {
+        let mut rev = self.clone();
+        swap(&mut rev.value, &mut rev.value_prev);
+        rev
+    }
+}
+
+impl PartialOrd for TransientStorageOp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TransientStorageOp {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.tx_id, &self.address, &self.key).cmp(&(&other.tx_id, &other.address, &other.key))
+    }
+}
+
+/// Represents the status of an account in a transaction, which is stored by
+/// a [`CreatedAccountOp`].  Since EIP-6780, `SELFDESTRUCT` only destructs the
+/// accounts created in the same transaction, and they are deleted at the end
+/// of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, EnumIter)]
+pub enum CreatedAccountStatus {
+    /// The account hasn't been created in the transaction
+    NotCreated = 0,
+    /// The account has been created in the transaction
+    Created,
+    /// The account has been created, and then destructed, in the transaction
+    Destructed,
+}
+
+impl_expr!(CreatedAccountStatus);
+
+impl From<CreatedAccountStatus> for u64 {
+    fn from(value: CreatedAccountStatus) -> u64 {
+        value as u64
+    }
+}
+
+/// Represents a change in the [`CreatedAccountStatus`] of an account implied
+/// by a `BeginTx`, `CREATE*`, `SELFDESTRUCT` or `DeleteAccount` step of the
+/// [`ExecStep`](crate::circuit_input_builder::ExecStep).
+#[derive(Clone, PartialEq, Eq)]
+pub struct CreatedAccountOp {
+    /// Transaction ID: Transaction index in the block starting at 1.
+    pub tx_id: usize,
+    /// Account Address
+    pub address: Address,
+    /// Status after the operation
+    pub value: CreatedAccountStatus,
+    /// Status before the operation
+    pub value_prev: CreatedAccountStatus,
+}
+
+impl fmt::Debug for CreatedAccountOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CreatedAccountOp { ")?;
+        f.write_fmt(format_args!(
+            "tx_id: {:?}, addr: {:?}, val_prev: {:?}, val: {:?}",
+            self.tx_id, self.address, self.value_prev, self.value
+        ))?;
+        f.write_str(" }")
+    }
+}
+
+impl CreatedAccountOp {
+    /// Create a new instance of a `CreatedAccountOp` from it's components.
+    pub const fn new(
+        tx_id: usize,
+        address: Address,
+        value: CreatedAccountStatus,
+        value_prev: CreatedAccountStatus,
+    ) -> CreatedAccountOp {
+        CreatedAccountOp {
+            tx_id,
+            address,
+            value,
+            value_prev,
+        }
+    }
+
+    /// Returns the [`Target`] (operation type) of this operation.
+    pub const fn target(&self) -> Target {
+        Target::CreatedAccount
+    }
+}
+
+impl Op for CreatedAccountOp {
+    fn into_enum(self) -> OpEnum {
+        OpEnum::CreatedAccount(self)
+    }
+
+    fn reverse(&self) -> Self {
+        let mut rev = self.clone();
+        swap(&mut rev.value, &mut rev.value_prev);
+        rev
+    }
+}
+
+impl PartialOrd for CreatedAccountOp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CreatedAccountOp {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.tx_id, &self.address).cmp(&(&other.tx_id, &other.address))
+    }
+}
+
 /// Represents a change in the Account AccessList implied by a `BeginTx`,
 /// `EXTCODECOPY`, `EXTCODESIZE`, `EXTCODEHASH` `BALANCE`, `SELFDESTRUCT`,
 /// `*CALL`* or `CREATE*` step.
@@ -954,6 +1131,10 @@ pub enum OpEnum {
     TxReceipt(TxReceiptOp),
     /// TxLog
     TxLog(TxLogOp),
+    /// TransientStorage
+    TransientStorage(TransientStorageOp),
+    /// CreatedAccount
+    CreatedAccount(CreatedAccountOp),
     /// Start
     Start(StartOp),
 }
@@ -1,7 +1,7 @@
 use super::{
-    AccountOp, CallContextOp, MemoryOp, Op, OpEnum, Operation, RWCounter, StackOp, StartOp,
-    StorageOp, Target, TxAccessListAccountOp, TxAccessListAccountStorageOp, TxLogOp, TxReceiptOp,
-    TxRefundOp, RW,
+    AccountOp, CallContextOp, CreatedAccountOp, MemoryOp, Op, OpEnum, Operation, RWCounter,
+    StackOp, StartOp, StorageOp, Target, TransientStorageOp, TxAccessListAccountOp,
+    TxAccessListAccountStorageOp, TxLogOp, TxReceiptOp, TxRefundOp, RW,
 };
 use crate::exec_trace::OperationRef;
 use itertools::Itertools;
@@ -42,6 +42,10 @@ pub struct OperationContainer {
     pub tx_receipt: Vec<Operation<TxReceiptOp>>,
     /// Operations of TxLogOp
     pub tx_log: Vec<Operation<TxLogOp>>,
+    /// Operations of TransientStorageOp
+    pub transient_storage: Vec<Operation<TransientStorageOp>>,
+    /// Operations of CreatedAccountOp
+    pub created_account: Vec<Operation<CreatedAccountOp>>,
     /// Operations of Start
     pub start: Vec<Operation<StartOp>>,
 }
@@ -67,6 +71,8 @@ impl OperationContainer {
             call_context: Vec::new(),
             tx_receipt: Vec::new(),
             tx_log: Vec::new(),
+            transient_storage: Vec::new(),
+            created_account: Vec::new(),
             start: Vec::new(),
         }
     }
@@ -160,6 +166,22 @@ impl OperationContainer {
                 self.tx_log.push(Operation::new(rwc, rw, op));
                 OperationRef::from((Target::TxLog, self.tx_log.len() - 1))
             }
+            OpEnum::TransientStorage(op) => {
+                self.transient_storage.push(if reversible {
+                    Operation::new_reversible(rwc, rw, op)
+                } else {
+                    Operation::new(rwc, rw, op)
+                });
+                OperationRef::from((Target::TransientStorage, self.transient_storage.len() - 1))
+            }
+            OpEnum::CreatedAccount(op) => {
+                self.created_account.push(if reversible {
+                    Operation::new_reversible(rwc, rw, op)
+                } else {
+                    Operation::new(rwc, rw, op)
+                });
+                OperationRef::from((Target::CreatedAccount, self.created_account.len() - 1))
+            }
             OpEnum::Start(op) => {
                 self.start.push(Operation::new(rwc, rw, op));
                 OperationRef::from((Target::Start, self.start.len() - 1))
@@ -1,27 +1,26 @@
 //! precompile helpers
 
+use crate::circuit_input_builder::{
+    Blake2fEvent, EcAddEvent, EcMulEvent, ModExpEvent, P256VerifyEvent,
+};
 use eth_types::{
-    evm_types::{GasCost, OpcodeId},
+    evm_types::{GasCost, Hardfork, OpcodeId},
+    geth_types::{secp256k1_sign_data, secp256r1_sign_data},
+    rsa_types::sha256,
+    sign_types::SignData,
     Address, Bytecode, Word,
 };
 #[cfg(not(target_arch = "wasm32"))]
-use revm_precompile::{Precompile, PrecompileError, Precompiles};
-
-#[allow(unused_variables)]
-/// Check if address is a precompiled or not.
-pub fn is_precompiled(address: &Address) -> bool {
-    #[cfg(target_arch = "wasm32")]
-    if address.0[0..19] == [0u8; 19] && (1..=9).contains(&address.0[19]) {
-        // TODO add support for precompiles in WASM
-        panic!("Precompile {address} is currently not supported in WASM");
-    } else {
-        return false;
-    }
+use revm_precompile::{primitives::Env, Precompile, PrecompileError, Precompiles};
+use std::{array, cmp::min};
 
-    #[cfg(not(target_arch = "wasm32"))]
-    Precompiles::berlin()
-        .get(address.as_fixed_bytes())
-        .is_some()
+/// Check if address is a precompiled or not in a hardfork, in which
+/// `p256_verify` enables the P256VERIFY precompile (RIP-7212) of the L2s.
+pub fn is_precompiled(address: &Address, hardfork: Hardfork, p256_verify: bool) -> bool {
+    hardfork
+        .precompile_addresses(p256_verify)
+        .into_iter()
+        .any(|precompile| *address == Address::from_low_u64_be(precompile))
 }
 
 #[allow(unused_variables)]
@@ -30,17 +29,24 @@ pub(crate) fn execute_precompiled(
     input: &[u8],
     gas: u64,
 ) -> (Vec<u8>, u64, bool) {
+    if *address == PrecompileCalls::P256Verify.into() {
+        return execute_p256_verify(input, gas);
+    }
+
     #[cfg(target_arch = "wasm32")]
     // TODO add support for precompiles in WASM
     panic!("Running precompile {address} is currently not supported in WASM");
 
     #[cfg(not(target_arch = "wasm32"))]
     {
-        let Some(Precompile::Standard(precompile_fn)) = Precompiles::berlin()
-        .get(address.as_fixed_bytes())  else {
-            panic!("calling non-exist precompiled contract address")
+        let result = match Precompiles::cancun().get(address.as_fixed_bytes()) {
+            Some(Precompile::Standard(precompile_fn)) => precompile_fn(input, gas),
+            // The point evaluation reads the trusted setup of the KZG
+            // commitments from the environment.
+            Some(Precompile::Env(precompile_fn)) => precompile_fn(input, gas, &Env::default()),
+            None => panic!("calling non-exist precompiled contract address"),
         };
-        let (return_data, gas_cost, is_oog, is_ok) = match precompile_fn(input, gas) {
+        let (return_data, gas_cost, is_oog, is_ok) = match result {
             Ok((gas_cost, return_value)) => {
                 // Some Revm behavior for invalid inputs might be overridden.
                 (return_value, gas_cost, false, true)
@@ -58,6 +64,22 @@ pub(crate) fn execute_precompiled(
     }
 }
 
+/// Run the P256VERIFY precompile of RIP-7212, which costs a fixed amount of
+/// gas and only fails when it runs out of gas.  It returns 1 as a word when
+/// the signature is valid, and nothing otherwise.
+fn execute_p256_verify(input: &[u8], gas: u64) -> (Vec<u8>, u64, bool) {
+    let gas_cost = GasCost::PRECOMPILE_P256VERIFY;
+    if gas < gas_cost {
+        return (vec![], gas, true);
+    }
+    let return_data = if p256_verify(input) {
+        p256_verify_output()
+    } else {
+        vec![]
+    };
+    (return_data, gas_cost, false)
+}
+
 /// Addresses of the precompiled contracts.
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum PrecompileCalls {
@@ -79,13 +101,15 @@ pub enum PrecompileCalls {
     Bn128Pairing = 0x08,
     /// Compression function
     Blake2F = 0x09,
+    /// KZG point evaluation
+    PointEvaluation = 0x0a,
+    /// secp256r1 signature verification (RIP-7212)
+    P256Verify = 0x100,
 }
 
 impl From<PrecompileCalls> for Address {
     fn from(value: PrecompileCalls) -> Self {
-        let mut addr = [0u8; 20];
-        addr[19] = value as u8;
-        Self(addr)
+        Self::from_low_u64_be(value.into())
     }
 }
 
@@ -113,7 +137,18 @@ impl From<u8> for PrecompileCalls {
             0x07 => Self::Bn128Mul,
             0x08 => Self::Bn128Pairing,
             0x09 => Self::Blake2F,
-            _ => unreachable!("precompile contracts only from 0x01 to 0x09"),
+            0x0a => Self::PointEvaluation,
+            _ => unreachable!("precompile contracts only from 0x01 to 0x0a"),
+        }
+    }
+}
+
+impl From<Address> for PrecompileCalls {
+    fn from(address: Address) -> Self {
+        if address == Self::P256Verify.into() {
+            Self::P256Verify
+        } else {
+            address.0[19].into()
         }
     }
 }
@@ -131,9 +166,41 @@ impl PrecompileCalls {
             Self::Bn128Mul => GasCost::PRECOMPILE_BN256MUL,
             Self::Bn128Pairing => GasCost::PRECOMPILE_BN256PAIRING,
             Self::Blake2F => GasCost::PRECOMPILE_BLAKE2F,
+            Self::PointEvaluation => GasCost::PRECOMPILE_POINT_EVALUATION,
+            Self::P256Verify => GasCost::PRECOMPILE_P256VERIFY,
         }
     }
 
+    /// Get the gas cost for the precompile call per word of input, per pair
+    /// of points for the pairing check, or per round for blake2f.
+    pub fn per_word_gas_cost(&self) -> u64 {
+        match self {
+            Self::Sha256 => GasCost::PRECOMPILE_SHA256_PER_WORD,
+            Self::Ripemd160 => GasCost::PRECOMPILE_RIPEMD160_PER_WORD,
+            Self::Identity => GasCost::PRECOMPILE_IDENTITY_PER_WORD,
+            Self::Bn128Pairing => GasCost::PRECOMPILE_BN256PAIRING_PER_PAIR,
+            Self::Blake2F => GasCost::PRECOMPILE_BLAKE2F_PER_ROUND,
+            _ => 0,
+        }
+    }
+
+    /// Return whether the precompile exists in a hardfork.  P256VERIFY isn't
+    /// an Ethereum precompile, and is enabled by the L2s independently of the
+    /// hardfork.
+    pub fn is_active(&self, hardfork: Hardfork) -> bool {
+        *self == Self::P256Verify || self.address() <= hardfork.precompile_count()
+    }
+
+    /// Get the gas schedule of the precompile call in a hardfork, or `None`
+    /// if the precompile doesn't exist in the hardfork.  A hardfork which
+    /// reprices a precompile changes it here.
+    pub fn gas_cost(&self, hardfork: Hardfork) -> Option<PrecompileGasCost> {
+        self.is_active(hardfork).then(|| PrecompileGasCost {
+            base: self.base_gas_cost(),
+            per_word: self.per_word_gas_cost(),
+        })
+    }
+
     /// Get the EVM address for this precompile call.
     pub fn address(&self) -> u64 {
         (*self).into()
@@ -149,6 +216,553 @@ impl PrecompileCalls {
     }
 }
 
+/// Gas schedule of a precompile call, which costs `base + n * per_word` for
+/// `n` words of input, pairs of points for the pairing check, or rounds for
+/// blake2f.  The gas of modexp is the maximum of `base` and its dynamic cost.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PrecompileGasCost {
+    /// Base gas cost
+    pub base: u64,
+    /// Gas cost per word
+    pub per_word: u64,
+}
+
+impl PrecompileGasCost {
+    /// Return the gas cost of a call with `n` words.
+    pub fn cost(&self, n: u64) -> u64 {
+        self.base + n * self.per_word
+    }
+}
+
+/// Auxiliary data attached to the execution step of a precompile call, that
+/// is witnessed by the gadget of the precompile.
+#[derive(Clone, Debug)]
+pub enum PrecompileAuxData {
+    /// Elliptic Curve Recovery
+    Ecrecover(EcrecoverAuxData),
+    /// SHA2-256 hash function
+    Sha256(Sha256AuxData),
+    /// RIPEMD-160 hash function
+    Ripemd160(Ripemd160AuxData),
+    /// Modular exponentiation
+    Modexp(ModExpAuxData),
+    /// Point addition on the BN254 curve
+    EcAdd(EcAddAuxData),
+    /// Scalar multiplication on the BN254 curve
+    EcMul(EcMulAuxData),
+    /// Compression function F of BLAKE2b
+    Blake2f(Blake2fAuxData),
+    /// secp256r1 signature verification
+    P256Verify(P256VerifyAuxData),
+}
+
+/// Auxiliary data of an ecrecover call.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EcrecoverAuxData {
+    /// Keccak hash of the message being signed.
+    pub msg_hash: Word,
+    /// v-component of the signature.
+    pub sig_v: Word,
+    /// r-component of the signature.
+    pub sig_r: Word,
+    /// s-component of the signature.
+    pub sig_s: Word,
+    /// Address that was recovered, zero when the recovery failed.
+    pub recovered_addr: Address,
+}
+
+impl EcrecoverAuxData {
+    /// Create the aux data of an ecrecover call from its input, right-padded
+    /// with zeros to 128 bytes, and its output.
+    pub fn new(input: &[u8], output: &[u8]) -> Self {
+        let mut padded_input = [0u8; 128];
+        let len = min(input.len(), padded_input.len());
+        padded_input[..len].copy_from_slice(&input[..len]);
+
+        let recovered_addr = if output.len() == 32 {
+            Address::from_slice(&output[12..])
+        } else {
+            Address::zero()
+        };
+
+        Self {
+            msg_hash: Word::from_big_endian(&padded_input[0x00..0x20]),
+            sig_v: Word::from_big_endian(&padded_input[0x20..0x40]),
+            sig_r: Word::from_big_endian(&padded_input[0x40..0x60]),
+            sig_s: Word::from_big_endian(&padded_input[0x60..0x80]),
+            recovered_addr,
+        }
+    }
+
+    /// Return the recovery id of the signature, `None` if `sig_v` is neither
+    /// 27 nor 28.
+    pub fn recovery_id(&self) -> Option<u8> {
+        if self.sig_v == Word::from(27) || self.sig_v == Word::from(28) {
+            Some(self.sig_v.low_u64() as u8 - 27)
+        } else {
+            None
+        }
+    }
+
+    /// Return the SignData of the call to be verified in the SigTable, `None`
+    /// if no address was recovered.
+    pub fn sign_data(&self) -> Option<SignData> {
+        if self.recovered_addr.is_zero() {
+            return None;
+        }
+        let mut msg_hash = [0u8; 32];
+        self.msg_hash.to_big_endian(&mut msg_hash);
+        secp256k1_sign_data(self.recovery_id()?, &self.sig_r, &self.sig_s, msg_hash).ok()
+    }
+}
+
+/// Auxiliary data of a sha256 call.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Sha256AuxData {
+    /// Bytes hashed by the call, i.e. the whole call data.
+    pub input_bytes: Vec<u8>,
+    /// Digest of the input.
+    pub output_bytes: Vec<u8>,
+}
+
+impl Sha256AuxData {
+    /// Create the aux data of a sha256 call from its input and output.
+    pub fn new(input: &[u8], output: &[u8]) -> Self {
+        Self {
+            input_bytes: input.to_vec(),
+            output_bytes: output.to_vec(),
+        }
+    }
+}
+
+/// Auxiliary data of a ripemd160 call.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Ripemd160AuxData {
+    /// Bytes hashed by the call, i.e. the whole call data.
+    pub input_bytes: Vec<u8>,
+    /// Digest of the input, left-padded with zeros to 32 bytes.
+    pub output_bytes: Vec<u8>,
+}
+
+impl Ripemd160AuxData {
+    /// Create the aux data of a ripemd160 call from its input and output.
+    pub fn new(input: &[u8], output: &[u8]) -> Self {
+        Self {
+            input_bytes: input.to_vec(),
+            output_bytes: output.to_vec(),
+        }
+    }
+}
+
+/// Number of bytes of the lengths of the base, exponent and modulus at the
+/// start of the input of a modexp call.
+pub const MODEXP_HEADER_LEN: usize = 96;
+
+/// Maximum number of bytes of the base, exponent and modulus of the modexp
+/// calls supported by the circuits: the 32 bytes of a word of the
+/// ModExpTable.  The `max_modexp_size` circuit parameter can lower it.
+pub const MODEXP_SIZE_LIMIT: usize = 32;
+
+/// Return the lengths of the base, exponent and modulus of a modexp call from
+/// the start of its input, right-padded with zeros.
+pub fn modexp_lengths(input: &[u8]) -> [Word; 3] {
+    let mut header = [0u8; MODEXP_HEADER_LEN];
+    let len = min(input.len(), MODEXP_HEADER_LEN);
+    header[..len].copy_from_slice(&input[..len]);
+    [0, 1, 2].map(|i| Word::from_big_endian(&header[i * 32..(i + 1) * 32]))
+}
+
+/// Return whether the circuits support a modexp call with the input: none of
+/// its lengths exceeds `max_size`, which is at most `MODEXP_SIZE_LIMIT`.  The
+/// calls with larger inputs are handled as unimplemented precompiles.
+pub fn is_modexp_supported(input: &[u8], max_size: usize) -> bool {
+    modexp_lengths(input)
+        .iter()
+        .all(|len| *len <= Word::from(max_size.min(MODEXP_SIZE_LIMIT)))
+}
+
+/// Return the number of bytes of the call data of a supported modexp call
+/// which are copied to the precompile: the lengths followed by the base,
+/// exponent and modulus, truncated to the `call_data_length` bytes of the call
+/// data.  Only the lengths at the start of `input` are read.
+pub fn modexp_input_len(input: &[u8], call_data_length: usize) -> usize {
+    let len = MODEXP_HEADER_LEN
+        + modexp_lengths(input)
+            .iter()
+            .map(|len| min(*len, call_data_length.into()).as_usize())
+            .sum::<usize>();
+    min(len, call_data_length)
+}
+
+/// Auxiliary data of a supported modexp call.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ModExpAuxData {
+    /// Lengths of the base, exponent and modulus.
+    pub input_lengths: [Word; 3],
+    /// Base, exponent and modulus.
+    pub inputs: [Word; 3],
+    /// Result of the call, zero when it fails.
+    pub output: Word,
+    /// Number of bytes of the call data copied to the precompile.
+    pub input_len: usize,
+}
+
+impl ModExpAuxData {
+    /// Create the aux data of a modexp call from its whole call data and its
+    /// output.
+    pub fn new(input: &[u8], output: &[u8]) -> Self {
+        let input_lengths = modexp_lengths(input);
+        let input_len = modexp_input_len(input, input.len());
+
+        // The values are read from the input right-padded with zeros.
+        let mut padded_input = input[..input_len].to_vec();
+        padded_input.resize(modexp_input_len(input, usize::MAX), 0);
+        let mut offset = MODEXP_HEADER_LEN;
+        let inputs = input_lengths.map(|len| {
+            let len = len.as_usize();
+            let value = Word::from_big_endian(&padded_input[offset..offset + len]);
+            offset += len;
+            value
+        });
+
+        Self {
+            input_lengths,
+            inputs,
+            output: Word::from_big_endian(output),
+            input_len,
+        }
+    }
+
+    /// Return the modular exponentiation of the call to be proven in the
+    /// ModExpTable.
+    pub fn event(&self) -> ModExpEvent {
+        let [base, exponent, modulus] = self.inputs;
+        ModExpEvent {
+            base,
+            exponent,
+            modulus,
+            output: self.output,
+        }
+    }
+}
+
+/// Number of bytes of the input of a blake2f call: the number of rounds, the
+/// state vector `h`, the message block `m`, the offset counter `t` and the
+/// final block flag `f`.
+pub const BLAKE2F_INPUT_LEN: usize = 213;
+
+/// Initialization vector of BLAKE2b
+pub const BLAKE2F_IV: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+/// Message word schedule of the rounds of BLAKE2b, round `i` using
+/// `BLAKE2F_SIGMA[i % 10]`
+pub const BLAKE2F_SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+/// Return the state vector compressed by the function F of BLAKE2b with
+/// `rounds` rounds, as specified by EIP-152.
+pub fn blake2f_compress(rounds: u32, h: [u64; 8], m: [u64; 16], t: [u64; 2], f: bool) -> [u64; 8] {
+    let mut v = [0; 16];
+    v[..8].copy_from_slice(&h);
+    v[8..].copy_from_slice(&BLAKE2F_IV);
+    v[12] ^= t[0];
+    v[13] ^= t[1];
+    if f {
+        v[14] = !v[14];
+    }
+
+    let mut g = |[a, b, c, d]: [usize; 4], x: u64, y: u64| {
+        v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+        v[d] = (v[d] ^ v[a]).rotate_right(32);
+        v[c] = v[c].wrapping_add(v[d]);
+        v[b] = (v[b] ^ v[c]).rotate_right(24);
+        v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+        v[d] = (v[d] ^ v[a]).rotate_right(16);
+        v[c] = v[c].wrapping_add(v[d]);
+        v[b] = (v[b] ^ v[c]).rotate_right(63);
+    };
+    for round in 0..rounds as usize {
+        let s = &BLAKE2F_SIGMA[round % 10];
+        g([0, 4, 8, 12], m[s[0]], m[s[1]]);
+        g([1, 5, 9, 13], m[s[2]], m[s[3]]);
+        g([2, 6, 10, 14], m[s[4]], m[s[5]]);
+        g([3, 7, 11, 15], m[s[6]], m[s[7]]);
+        g([0, 5, 10, 15], m[s[8]], m[s[9]]);
+        g([1, 6, 11, 12], m[s[10]], m[s[11]]);
+        g([2, 7, 8, 13], m[s[12]], m[s[13]]);
+        g([3, 4, 9, 14], m[s[14]], m[s[15]]);
+    }
+
+    array::from_fn(|i| h[i] ^ v[i] ^ v[i + 8])
+}
+
+/// Auxiliary data of a blake2f call.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Blake2fAuxData {
+    /// The whole call data.
+    pub input: Vec<u8>,
+    /// The compressed state vector, empty when the call fails.
+    pub output: Vec<u8>,
+}
+
+impl Blake2fAuxData {
+    /// Create the aux data of a blake2f call from its input and its output.
+    pub fn new(input: &[u8], output: &[u8]) -> Self {
+        Self {
+            input: input.to_vec(),
+            output: output.to_vec(),
+        }
+    }
+
+    /// Return whether the input has the length of `BLAKE2F_INPUT_LEN` bytes.
+    pub fn has_valid_length(&self) -> bool {
+        self.input.len() == BLAKE2F_INPUT_LEN
+    }
+
+    /// Return the number of rounds, a big-endian u32, or 0 if the input
+    /// doesn't have a valid length.
+    pub fn rounds(&self) -> u32 {
+        if self.has_valid_length() {
+            u32::from_be_bytes(self.input[..4].try_into().unwrap())
+        } else {
+            0
+        }
+    }
+
+    /// Return the compression of a call whose input has a valid length, to
+    /// be proven in the Blake2fTable.
+    pub fn event(&self) -> Blake2fEvent {
+        assert!(self.has_valid_length());
+        let words = self.input[4..BLAKE2F_INPUT_LEN - 1]
+            .chunks(8)
+            .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+            .collect::<Vec<_>>();
+        let h = array::from_fn(|i| words[i]);
+        let m = array::from_fn(|i| words[8 + i]);
+        let t = array::from_fn(|i| words[24 + i]);
+        let f = self.input[BLAKE2F_INPUT_LEN - 1] == 1;
+        let rounds = self.rounds();
+
+        Blake2fEvent {
+            rounds,
+            h,
+            m,
+            t,
+            f,
+            output: blake2f_compress(rounds, h, m, t, f),
+        }
+    }
+}
+
+/// Return the first `N` words of the input, right-padded with zeros.
+fn padded_input_words<const N: usize>(input: &[u8]) -> [Word; N] {
+    let mut padded_input = input[..min(input.len(), 32 * N)].to_vec();
+    padded_input.resize(32 * N, 0);
+    array::from_fn(|i| Word::from_big_endian(&padded_input[32 * i..32 * (i + 1)]))
+}
+
+/// Auxiliary data of an ecAdd call.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EcAddAuxData {
+    /// Coordinates of the first point.
+    pub p: [Word; 2],
+    /// Coordinates of the second point.
+    pub q: [Word; 2],
+    /// Coordinates of the sum, zero when the call fails.
+    pub r: [Word; 2],
+}
+
+impl EcAddAuxData {
+    /// Create the aux data of an ecAdd call from its input, right-padded with
+    /// zeros to 128 bytes, and its output.
+    pub fn new(input: &[u8], output: &[u8]) -> Self {
+        let [p_x, p_y, q_x, q_y] = padded_input_words(input);
+        Self {
+            p: [p_x, p_y],
+            q: [q_x, q_y],
+            r: padded_input_words(output),
+        }
+    }
+
+    /// Return the point addition of the call to be proven in the EccTable.
+    pub fn event(&self, is_valid: bool) -> EcAddEvent {
+        EcAddEvent {
+            p: self.p,
+            q: self.q,
+            r: self.r,
+            is_valid,
+        }
+    }
+}
+
+/// Auxiliary data of an ecMul call.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EcMulAuxData {
+    /// Coordinates of the point.
+    pub p: [Word; 2],
+    /// Scalar by which the point is multiplied.
+    pub s: Word,
+    /// Coordinates of the product, zero when the call fails.
+    pub r: [Word; 2],
+}
+
+impl EcMulAuxData {
+    /// Create the aux data of an ecMul call from its input, right-padded with
+    /// zeros to 96 bytes, and its output.
+    pub fn new(input: &[u8], output: &[u8]) -> Self {
+        let [p_x, p_y, s] = padded_input_words(input);
+        Self {
+            p: [p_x, p_y],
+            s,
+            r: padded_input_words(output),
+        }
+    }
+
+    /// Return the scalar multiplication of the call to be proven in the
+    /// EccTable.
+    pub fn event(&self, is_valid: bool) -> EcMulEvent {
+        EcMulEvent {
+            p: self.p,
+            s: self.s,
+            r: self.r,
+            is_valid,
+        }
+    }
+}
+
+/// Number of bytes of a pair of points of G1 and G2 in the input of an
+/// ecPairing call.
+pub const N_BYTES_EC_PAIR: usize = 192;
+
+/// Number of bytes of a compressed point of G1 of BLS12-381, the encoding of
+/// the KZG commitments and proofs.
+pub const N_BYTES_KZG_POINT: usize = 48;
+
+/// Version byte of the versioned hashes of the KZG commitments.
+pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+/// Number of field elements of a blob, the first word of the output of a
+/// successful point evaluation call.
+pub const FIELD_ELEMENTS_PER_BLOB: u64 = 4096;
+
+/// Modulus of the scalar field of BLS12-381, the second word of the output of
+/// a successful point evaluation call.
+pub const BLS_MODULUS: Word = Word([
+    0xffffffff00000001,
+    0x53bda402fffe5bfe,
+    0x3339d80809a1d805,
+    0x73eda753299d7d48,
+]);
+
+/// Return the versioned hash of a KZG commitment, its sha256 digest whose
+/// first byte is replaced by `VERSIONED_HASH_VERSION_KZG`.
+pub fn kzg_to_versioned_hash(commitment: &[u8]) -> [u8; 32] {
+    let mut hash = sha256(commitment);
+    hash[0] = VERSIONED_HASH_VERSION_KZG;
+    hash
+}
+
+/// Return the output of a successful point evaluation call,
+/// `FIELD_ELEMENTS_PER_BLOB` and `BLS_MODULUS` as 32-byte words.
+pub fn point_evaluation_output() -> Vec<u8> {
+    let mut output = vec![0u8; 64];
+    Word::from(FIELD_ELEMENTS_PER_BLOB).to_big_endian(&mut output[..32]);
+    BLS_MODULUS.to_big_endian(&mut output[32..]);
+    output
+}
+
+/// Length of the input of a P256VERIFY call: the message hash, the signature
+/// (r, s) and the public key (x, y).
+pub const P256VERIFY_INPUT_LEN: usize = 160;
+
+/// Return the output of a P256VERIFY call with a valid signature, 1 as a
+/// 32-byte word.
+pub fn p256_verify_output() -> Vec<u8> {
+    let mut output = vec![0u8; 32];
+    output[31] = 1;
+    output
+}
+
+/// Return whether the input of a P256VERIFY call has a valid length and holds
+/// a valid secp256r1 signature of the message hash by the public key.
+pub fn p256_verify(input: &[u8]) -> bool {
+    if input.len() != P256VERIFY_INPUT_LEN {
+        return false;
+    }
+    let [r, s, pk_x, pk_y] =
+        array::from_fn(|i| Word::from_big_endian(&input[(i + 1) * 32..(i + 2) * 32]));
+    let msg_hash = input[..32].try_into().unwrap();
+    secp256r1_sign_data(&r, &s, &pk_x, &pk_y, msg_hash)
+        .map(|sign_data| sign_data.verify())
+        .unwrap_or(false)
+}
+
+/// Auxiliary data of a P256VERIFY call.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct P256VerifyAuxData {
+    /// The whole call data.
+    pub input: Vec<u8>,
+    /// The output of the call, empty when the signature is invalid.
+    pub output: Vec<u8>,
+}
+
+impl P256VerifyAuxData {
+    /// Create the aux data of a P256VERIFY call from its input and its
+    /// output.
+    pub fn new(input: &[u8], output: &[u8]) -> Self {
+        Self {
+            input: input.to_vec(),
+            output: output.to_vec(),
+        }
+    }
+
+    /// Return whether the input has the length of `P256VERIFY_INPUT_LEN`
+    /// bytes, the only inputs whose signature is verified.
+    pub fn has_valid_length(&self) -> bool {
+        self.input.len() == P256VERIFY_INPUT_LEN
+    }
+
+    /// Return whether the signature is valid, i.e. the call returned 1.
+    pub fn is_valid(&self) -> bool {
+        !self.output.is_empty()
+    }
+
+    /// Return the signature verification of the call to be proven in the
+    /// P-256 SigTable.
+    pub fn event(&self) -> P256VerifyEvent {
+        assert!(self.has_valid_length());
+        let [msg_hash, sig_r, sig_s, pk_x, pk_y] =
+            array::from_fn(|i| Word::from_big_endian(&self.input[i * 32..(i + 1) * 32]));
+        P256VerifyEvent {
+            msg_hash,
+            sig_r,
+            sig_s,
+            pk_x,
+            pk_y,
+            is_valid: self.is_valid(),
+        }
+    }
+}
+
 /// Precompile call args
 pub struct PrecompileCallArgs {
     /// description for the instance of a precompile call.
@@ -220,3 +834,162 @@ impl PrecompileCallArgs {
         code
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use eth_types::{
+        keccak256,
+        sign_types::{pk_bytes_le, pk_bytes_swap_endianness},
+    };
+    use halo2_proofs::halo2curves::{group::ff::PrimeField, secp256r1::Secp256r1Affine};
+
+    // (msg_hash, v, r, s) of a signature of the address 0xceaccac6...
+    const ECRECOVER_INPUT: &str = "\
+        38d18acb67d25c8bb9942764b62f18e17054f66a817bd4295423adf9ed98873e\
+        000000000000000000000000000000000000000000000000000000000000001b\
+        38d18acb67d25c8bb9942764b62f18e17054f66a817bd4295423adf9ed98873e\
+        789d1dd423d25f0772d2748d60f7e4b81bb14d086eba8e8e8efb6dcff8a4ae02";
+
+    #[test]
+    fn ecrecover_aux_data() {
+        let input = hex::decode(ECRECOVER_INPUT).unwrap();
+        let recovered_addr =
+            Address::from_slice(&hex::decode("ceaccac640adf55b2028469bd36ba501f28b699d").unwrap());
+        let output = [&[0u8; 12][..], recovered_addr.as_bytes()].concat();
+
+        let aux_data = EcrecoverAuxData::new(&input, &output);
+        assert_eq!(aux_data.sig_v, Word::from(27));
+        assert_eq!(aux_data.sig_r, Word::from_big_endian(&input[0x40..0x60]));
+        assert_eq!(aux_data.recovered_addr, recovered_addr);
+        assert_eq!(aux_data.recovery_id(), Some(0));
+
+        let sign_data = aux_data.sign_data().unwrap();
+        let pk_hash = keccak256(pk_bytes_swap_endianness(&pk_bytes_le(&sign_data.pk)));
+        assert_eq!(&pk_hash[12..], recovered_addr.as_bytes());
+    }
+
+    #[test]
+    fn ecrecover_aux_data_invalid() {
+        // The input is right-padded with zeros, so that v is 0 and nothing is
+        // recovered.
+        let input = hex::decode(ECRECOVER_INPUT).unwrap();
+        let aux_data = EcrecoverAuxData::new(&input[..0x30], &[]);
+        assert_eq!(aux_data.sig_v, Word::zero());
+        assert_eq!(aux_data.sig_s, Word::zero());
+        assert_eq!(aux_data.recovery_id(), None);
+        assert_eq!(aux_data.recovered_addr, Address::zero());
+        assert!(aux_data.sign_data().is_none());
+    }
+
+    // The 5th test vector of EIP-152, with 12 rounds
+    const BLAKE2F_INPUT: &str = "\
+        0000000c\
+        48c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5\
+        d182e6ad7f520e511f6c3e2b8c68059b6bbd41fbabd9831f79217e1319cde05b\
+        6162630000000000000000000000000000000000000000000000000000000000\
+        0000000000000000000000000000000000000000000000000000000000000000\
+        0000000000000000000000000000000000000000000000000000000000000000\
+        0000000000000000000000000000000000000000000000000000000000000000\
+        03000000000000000000000000000000\
+        01";
+
+    #[test]
+    fn blake2f_aux_data() {
+        let input = hex::decode(BLAKE2F_INPUT).unwrap();
+        let (output, gas_cost, _) =
+            execute_precompiled(&PrecompileCalls::Blake2F.into(), &input, 100);
+        assert_eq!(gas_cost, 12);
+
+        let event = Blake2fAuxData::new(&input, &output).event();
+        assert_eq!(event.rounds, 12);
+        assert!(event.f);
+        assert_eq!(event.input_bytes(), input[4..BLAKE2F_INPUT_LEN - 1]);
+        assert_eq!(event.output_bytes(), output);
+
+        // The output of 0 rounds only depends on the initial state vector.
+        let event = Blake2fEvent { rounds: 0, ..event };
+        assert_eq!(
+            blake2f_compress(0, event.h, event.m, event.t, event.f),
+            array::from_fn(|i| {
+                let t = match i {
+                    4 => event.t[0],
+                    5 => event.t[1],
+                    _ => 0,
+                };
+                let f = if i == 6 { u64::MAX } else { 0 };
+                BLAKE2F_IV[i] ^ t ^ f
+            })
+        );
+    }
+
+    #[test]
+    fn point_evaluation() {
+        // The zero polynomial, whose commitment and proof at any point are the
+        // point at infinity.
+        let mut commitment = [0u8; N_BYTES_KZG_POINT];
+        commitment[0] = 0xc0;
+        let input = [
+            &kzg_to_versioned_hash(&commitment)[..],
+            &[0u8; 64],
+            &commitment,
+            &commitment,
+        ]
+        .concat();
+        let (output, gas_cost, _) =
+            execute_precompiled(&PrecompileCalls::PointEvaluation.into(), &input, 50000);
+        assert_eq!(gas_cost, GasCost::PRECOMPILE_POINT_EVALUATION);
+        assert_eq!(output, point_evaluation_output());
+
+        // The call fails for a wrong versioned hash.
+        let mut input = input;
+        input[31] ^= 1;
+        let (output, _, _) =
+            execute_precompiled(&PrecompileCalls::PointEvaluation.into(), &input, 50000);
+        assert!(output.is_empty());
+    }
+
+    /// Return the input of a P256VERIFY call with the padding signature of the
+    /// secp256r1 SignData, which signs the message hash 1 by the generator.
+    fn p256_verify_input() -> Vec<u8> {
+        let sign_data = SignData::<Secp256r1Affine>::padding();
+        let (sig_r, sig_s) = sign_data.signature;
+        [sign_data.msg_hash, sig_r, sig_s]
+            .iter()
+            .flat_map(|scalar| scalar.to_repr().into_iter().rev())
+            .chain(pk_bytes_swap_endianness(&pk_bytes_le(&sign_data.pk)))
+            .collect()
+    }
+
+    #[test]
+    fn p256_verify_aux_data() {
+        let input = p256_verify_input();
+        let (output, gas_cost, is_oog) =
+            execute_precompiled(&PrecompileCalls::P256Verify.into(), &input, 3450);
+        assert!(!is_oog);
+        assert_eq!(gas_cost, GasCost::PRECOMPILE_P256VERIFY);
+        assert_eq!(output, p256_verify_output());
+
+        let event = P256VerifyAuxData::new(&input, &output).event();
+        assert!(event.is_valid);
+        assert_eq!(event.msg_hash, Word::one());
+
+        // The signature of another message hash is invalid, but the call
+        // still succeeds.
+        let mut input = input;
+        input[31] = 2;
+        let (output, _, is_oog) =
+            execute_precompiled(&PrecompileCalls::P256Verify.into(), &input, 3450);
+        assert!(!is_oog);
+        assert!(output.is_empty());
+        assert!(!P256VerifyAuxData::new(&input, &output).event().is_valid);
+
+        // As well as a public key which isn't a point of the curve.
+        input[31] = 1;
+        input[159] ^= 1;
+        assert!(!p256_verify(&input));
+
+        let (_, _, is_oog) = execute_precompiled(&PrecompileCalls::P256Verify.into(), &input, 3449);
+        assert!(is_oog);
+    }
+}
@@ -1,6 +1,7 @@
 //! Implementation of an in-memory key-value database to represent the
 //! Ethereum State Trie.
 
+use crate::operation::CreatedAccountStatus;
 use eth_types::{geth_types, Address, BigEndianHash, Bytecode, Hash, Word, H256, U256};
 use ethers_core::utils::keccak256;
 use itertools::Itertools;
@@ -120,7 +121,8 @@ impl Account {
         }
     }
 
-    /// Return if account is empty or not.
+    /// Return if account is empty or not.  An empty account has no nonce, no
+    /// balance and no code, and it doesn't exist for the EVM since EIP-161.
     pub fn is_empty(&self) -> bool {
         self.nonce == 0 && self.balance.is_zero() && self.code_hash.eq(&EMPTY_CODE_HASH)
     }
@@ -140,9 +142,19 @@ pub struct StateDB {
     // state before current transaction, to calculate gas cost for some opcodes like sstore.
     // So both dirty storage and committed storage are needed.
     dirty_storage: HashMap<(Address, Word), Word>,
-    // Accounts that have been through `SELFDESTRUCT` under the situation that `is_persistent` is
-    // `true`. These accounts will be reset once `commit_tx` is called.
+    // `transient_storage` contains the values written by `TSTORE` during current transaction.
+    // It's discarded, not committed, when current transaction finishes (EIP-1153).
+    transient_storage: HashMap<(Address, Word), Word>,
+    // Accounts that have been created during current transaction. Since EIP-6780, only these
+    // accounts are destructed by `SELFDESTRUCT`.
+    created_account: HashSet<Address>,
+    // Accounts created during current transaction that have been through `SELFDESTRUCT`. These
+    // accounts are deleted at the end of the transaction.
     destructed_account: HashSet<Address>,
+    // Accounts whose balance has been changed, even by zero, during current transaction. The
+    // empty ones are deleted (EIP-161), that is reset, once `commit_tx` is called. They are kept
+    // in `state` since the accounts of the witness are expected to be found.
+    touched_account: HashSet<Address>,
     refund: u64,
 }
 
@@ -227,6 +239,22 @@ impl StateDB {
         self.dirty_storage.insert((*addr, *key), *value);
     }
 
+    /// Get a reference to the transient storage value of the account at `addr`,
+    /// at `key`.  Returns false and a zero [`Word`] when `key` wasn't written
+    /// in the current transaction.
+    pub fn get_transient_storage(&self, addr: &Address, key: &Word) -> (bool, &Word) {
+        match self.transient_storage.get(&(*addr, *key)) {
+            Some(value) => (true, value),
+            None => (false, &VALUE_ZERO),
+        }
+    }
+
+    /// Set transient storage value at `addr` and `key`.
+    /// The transient storage is cleared in `commit_tx` method.
+    pub fn set_transient_storage(&mut self, addr: &Address, key: &Word, value: &Word) {
+        self.transient_storage.insert((*addr, *key), *value);
+    }
+
     /// Get nonce of account with `addr`.
     pub fn get_nonce(&self, addr: &Address) -> u64 {
         let (_, account) = self.get_account(addr);
@@ -281,9 +309,46 @@ impl StateDB {
         debug_assert!(exist);
     }
 
-    /// Set account as self destructed.
-    pub fn destruct_account(&mut self, addr: Address) {
-        self.destructed_account.insert(addr);
+    /// Get the [`CreatedAccountStatus`] of the account at `addr` in the
+    /// current transaction.
+    pub fn created_account_status(&self, addr: &Address) -> CreatedAccountStatus {
+        if self.destructed_account.contains(addr) {
+            CreatedAccountStatus::Destructed
+        } else if self.created_account.contains(addr) {
+            CreatedAccountStatus::Created
+        } else {
+            CreatedAccountStatus::NotCreated
+        }
+    }
+
+    /// Set the [`CreatedAccountStatus`] of the account at `addr` in the
+    /// current transaction.
+    pub fn set_created_account_status(&mut self, addr: Address, status: CreatedAccountStatus) {
+        match status {
+            CreatedAccountStatus::NotCreated => {
+                self.created_account.remove(&addr);
+                self.destructed_account.remove(&addr);
+            }
+            CreatedAccountStatus::Created => {
+                self.created_account.insert(addr);
+                self.destructed_account.remove(&addr);
+            }
+            CreatedAccountStatus::Destructed => {
+                self.created_account.insert(addr);
+                self.destructed_account.insert(addr);
+            }
+        }
+    }
+
+    /// Returns the accounts destructed in the current transaction, sorted by
+    /// address.  They are deleted at the end of the transaction.
+    pub fn destructed_accounts(&self) -> Vec<Address> {
+        self.destructed_account.iter().copied().sorted().collect()
+    }
+
+    /// Set account as touched in the current transaction.
+    pub fn touch_account(&mut self, addr: Address) {
+        self.touched_account.insert(addr);
     }
 
     /// Retrieve refund.
@@ -296,7 +361,8 @@ impl StateDB {
         self.refund = value;
     }
 
-    /// Clear access list and refund, and commit dirty storage.
+    /// Clear access list, refund, transient storage and created accounts,
+    /// commit dirty storage, and delete the empty touched accounts.
     /// It should be invoked before processing
     /// with new transaction with the same [`StateDB`].
     pub fn commit_tx(&mut self) {
@@ -307,9 +373,17 @@ impl StateDB {
             *ptr = value;
         }
         self.dirty_storage = HashMap::new();
-        for addr in self.destructed_account.clone() {
+        self.transient_storage = HashMap::new();
+        self.created_account = HashSet::new();
+        debug_assert!(
+            self.destructed_account.is_empty(),
+            "destructed accounts are deleted at the end of the transaction"
+        );
+        for addr in std::mem::take(&mut self.touched_account) {
             let (_, account) = self.get_account_mut(&addr);
-            *account = ACCOUNT_ZERO.clone();
+            if account.is_empty() {
+                *account = ACCOUNT_ZERO.clone();
+            }
         }
         self.refund = 0;
     }
@@ -374,4 +448,92 @@ mod statedb_tests {
         assert!(found);
         assert_eq!(value, &Word::from(102));
     }
+
+    #[test]
+    fn statedb_transient_storage() {
+        let addr = address!("0x0000000000000000000000000000000000000001");
+        let mut statedb = StateDB::new();
+
+        // Transient storage is independent of the persistent storage
+        statedb.set_transient_storage(&addr, &Word::from(2), &Word::from(101));
+        let (found, value) = statedb.get_transient_storage(&addr, &Word::from(2));
+        assert!(found);
+        assert_eq!(value, &Word::from(101));
+        let (found, _) = statedb.get_storage(&addr, &Word::from(2));
+        assert!(!found);
+
+        // Transient storage is discarded at the end of the transaction
+        statedb.commit_tx();
+        let (found, value) = statedb.get_transient_storage(&addr, &Word::from(2));
+        assert!(!found);
+        assert_eq!(value, &Word::zero());
+        let (found, _) = statedb.get_storage(&addr, &Word::from(2));
+        assert!(!found);
+    }
+
+    #[test]
+    fn statedb_created_account() {
+        let addr = address!("0x0000000000000000000000000000000000000001");
+        let mut statedb = StateDB::new();
+
+        statedb.set_created_account_status(addr, CreatedAccountStatus::Created);
+        assert_eq!(
+            statedb.created_account_status(&addr),
+            CreatedAccountStatus::Created
+        );
+        assert!(statedb.destructed_accounts().is_empty());
+
+        // The destruction is reverted when the status is set back
+        statedb.set_created_account_status(addr, CreatedAccountStatus::Destructed);
+        assert_eq!(statedb.destructed_accounts(), vec![addr]);
+        statedb.set_created_account_status(addr, CreatedAccountStatus::Created);
+        assert!(statedb.destructed_accounts().is_empty());
+
+        // Accounts are only created in the transaction that creates them
+        statedb.commit_tx();
+        assert_eq!(
+            statedb.created_account_status(&addr),
+            CreatedAccountStatus::NotCreated
+        );
+    }
+
+    #[test]
+    fn statedb_touched_account() {
+        let addr_a = address!("0x0000000000000000000000000000000000000001");
+        let addr_b = address!("0x0000000000000000000000000000000000000002");
+        let addr_c = address!("0x0000000000000000000000000000000000000003");
+        let mut statedb = StateDB::new();
+
+        let storage = HashMap::from([(Word::from(1), Word::from(2))]);
+        statedb.set_account(
+            &addr_a,
+            Account {
+                storage: storage.clone(),
+                ..Account::zero()
+            },
+        );
+        statedb.set_account(
+            &addr_b,
+            Account {
+                balance: Word::from(1),
+                storage: storage.clone(),
+                ..Account::zero()
+            },
+        );
+        statedb.set_account(
+            &addr_c,
+            Account {
+                storage,
+                ..Account::zero()
+            },
+        );
+        statedb.touch_account(addr_a);
+        statedb.touch_account(addr_b);
+        statedb.commit_tx();
+
+        // Only the empty touched accounts are deleted
+        assert_eq!(statedb.get_account(&addr_a), (true, &Account::zero()));
+        assert!(statedb.get_storage(&addr_b, &Word::from(1)).0);
+        assert!(statedb.get_storage(&addr_c, &Word::from(1)).0);
+    }
 }
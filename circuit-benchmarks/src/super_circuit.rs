@@ -3,8 +3,11 @@
 #[cfg(test)]
 mod tests {
     use ark_std::{end_timer, start_timer};
-    use bus_mapping::circuit_input_builder::FixedCParams;
-    use eth_types::{address, bytecode, geth_types::GethData, Word};
+    use bus_mapping::{
+        circuit_input_builder::{CodeHashScheme, FixedCParams},
+        precompile::MODEXP_SIZE_LIMIT,
+    };
+    use eth_types::{address, bytecode, evm_types::Hardfork, geth_types::GethData, Word};
     use ethers_signers::{LocalWallet, Signer};
     use halo2_proofs::{
         halo2curves::bn256::{Bn256, Fr, G1Affine},
@@ -81,14 +84,31 @@ mod tests {
 
         let circuits_params = FixedCParams {
             max_txs: 1,
+            max_signed_txs: 1,
             max_withdrawals: 1,
             max_calldata: 32,
+            max_access_list: 0,
+            max_auths: 0,
             max_rws: 256,
             max_copy_rows: 256,
             max_exp_steps: 256,
             max_bytecode: 512,
             max_evm_rows: 0,
             max_keccak_rows: 0,
+            max_poseidon_blocks: 0,
+            max_sha256_blocks: 0,
+            max_ripemd160_blocks: 0,
+            max_blake2f_blocks: 0,
+            max_sig_verifs: 0,
+            max_modexps: 0,
+            max_modexp_size: MODEXP_SIZE_LIMIT,
+            max_ec_adds: 0,
+            max_ec_muls: 0,
+            max_p256_verifs: 0,
+            hardfork: Hardfork::default(),
+            code_hash_scheme: CodeHashScheme::Keccak,
+            deposits: false,
+            p256_verify: false,
         };
         let (_, circuit, instance, _) =
             SuperCircuit::build(block, circuits_params, Fr::from(0x100)).unwrap();
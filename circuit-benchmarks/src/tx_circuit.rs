@@ -82,7 +82,7 @@ mod tests {
             &general_params,
             &pk,
             &[circuit],
-            &[&[&[]]],
+            &[&[&[], &[]]],
             rng,
             &mut transcript,
         )
@@ -105,7 +105,7 @@ mod tests {
             &verifier_params,
             pk.get_vk(),
             strategy,
-            &[&[&[]]],
+            &[&[&[], &[]]],
             &mut verifier_transcript,
         )
         .expect("failed to verify bench circuit");
@@ -493,19 +493,24 @@ impl_other_opcodes! {
     (op_gaslimit, GASLIMIT),
     (op_chainid, CHAINID),
     (op_selfbalance, SELFBALANCE),
-    // (op_basefee, BASEFEE), ignored
+    (op_basefee, BASEFEE),
+    (op_blobhash, BLOBHASH, index: I),
+    (op_blobbasefee, BLOBBASEFEE),
     (op_pop, POP),
     (op_mload, MLOAD, offset: O),
     (op_mstore, MSTORE, offset: O, value: V),
     (op_mstore8, MSTORE8, offset: O, value: V),
     (op_sload, SLOAD, offset: O),
     (op_sstore, SSTORE, offset: O, value: V),
+    (op_tload, TLOAD, key: K),
+    (op_tstore, TSTORE, key: K, value: V),
     (op_jump, JUMP, counter: C),
     (op_jumpi, JUMPI, counter: C), // branch not included
     (op_pc, PC),
     (op_msize, MSIZE),
     (op_gas, GAS),
     // (op_jumpdest, JUMPDEST), manually implemented
+    (op_mcopy, MCOPY, dest_offset: D, offset: B, size: C),
     (op_dup1, DUP1),
     (op_dup2, DUP2),
     (op_dup3, DUP3),
@@ -552,7 +557,7 @@ impl_other_opcodes! {
     (op_staticcall, STATICCALL, gas: G, address: A, args_offset: AO, args_size: AS, ret_offset: RO, ret_size: RS),
     (op_revert, REVERT, offset: O, size: S),
     // (op_invalid, INVALID), ignored
-    // (op_selfdestruct, SELFDESTRUCT), ignored
+    (op_selfdestruct, SELFDESTRUCT, address: A),
 }
 
 #[cfg(test)]
@@ -0,0 +1,291 @@
+//! Ed25519 signature types and helper functions, as defined in RFC 8032 and
+//! used by some identity credentials and L2 sequencer attestations.
+
+use lazy_static::lazy_static;
+use num_bigint::BigUint;
+use sha2::{Digest, Sha512};
+
+lazy_static! {
+    /// Modulus of the base field of edwards25519: 2^255 - 19
+    pub static ref ED25519_P: BigUint = (BigUint::from(1u64) << 255) - 19u64;
+    /// Order of the prime subgroup of edwards25519: 2^252 +
+    /// 27742317777372353535851937790883648493
+    pub static ref ED25519_L: BigUint = (BigUint::from(1u64) << 252)
+        + BigUint::parse_bytes(b"27742317777372353535851937790883648493", 10).unwrap();
+    /// Constant d = -121665/121666 of the curve equation -x^2 + y^2 = 1 + d x^2 y^2
+    pub static ref ED25519_D: BigUint = BigUint::parse_bytes(
+        b"37095705934669439343138083508754565189542113879843219016388785533085940283555",
+        10
+    )
+    .unwrap();
+    /// Base point B of the prime subgroup, with y = 4/5
+    pub static ref ED25519_BASEPOINT: EdwardsPoint = EdwardsPoint {
+        x: BigUint::parse_bytes(
+            b"15112221349535400772501151409588531511454012693041857206046113283949847762202",
+            10
+        )
+        .unwrap(),
+        y: BigUint::parse_bytes(
+            b"46316835694926478169428394003475163141307993866256225615783033603165251855960",
+            10
+        )
+        .unwrap(),
+    };
+    static ref SIGN_DATA_PADDING: Ed25519SignData = {
+        let sk = Ed25519PrivateKey { seed: [1; 32] };
+        let msg = [0; 32];
+        Ed25519SignData {
+            signature: sk.sign(&msg),
+            pk: sk.public_key(),
+            msg,
+        }
+    };
+}
+
+/// Return the SHA-512 digest of the input.
+pub fn sha512(input: &[u8]) -> [u8; 64] {
+    Sha512::digest(input).into()
+}
+
+/// Return `a^-1 mod p`, or 0 for `a = 0`.
+fn invert(a: &BigUint) -> BigUint {
+    a.modpow(&(&*ED25519_P - 2u64), &ED25519_P)
+}
+
+/// Return `(a - b) mod p` for `b < p`.
+fn sub_mod(a: &BigUint, b: &BigUint) -> BigUint {
+    (a + &*ED25519_P - b) % &*ED25519_P
+}
+
+/// Convert a `BigUint` of at most 256 bits into 32 bytes in little endian.
+pub fn biguint_to_ed25519_bytes_le(v: &BigUint) -> [u8; 32] {
+    let v_le = v.to_bytes_le();
+    assert!(v_le.len() <= 32, "value is larger than 256 bits");
+    let mut res = [0u8; 32];
+    res[..v_le.len()].copy_from_slice(&v_le);
+    res
+}
+
+/// Point of edwards25519 in affine coordinates
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EdwardsPoint {
+    /// x coordinate
+    pub x: BigUint,
+    /// y coordinate
+    pub y: BigUint,
+}
+
+impl EdwardsPoint {
+    /// Return the neutral element (0, 1).
+    pub fn identity() -> Self {
+        Self {
+            x: BigUint::from(0u64),
+            y: BigUint::from(1u64),
+        }
+    }
+
+    /// Return the sum of two points, by the complete addition formulas of the twisted Edwards
+    /// curves with a = -1:
+    /// x3 = (x1 y2 + y1 x2) / (1 + d x1 x2 y1 y2) and
+    /// y3 = (y1 y2 + x1 x2) / (1 - d x1 x2 y1 y2).
+    pub fn add(&self, other: &Self) -> Self {
+        let p = &*ED25519_P;
+        let dxxyy = &*ED25519_D * &self.x * &other.x % p * &self.y * &other.y % p;
+        let x = (&self.x * &other.y + &self.y * &other.x) % p
+            * invert(&((BigUint::from(1u64) + &dxxyy) % p));
+        let y = (&self.y * &other.y + &self.x * &other.x) % p
+            * invert(&sub_mod(&BigUint::from(1u64), &dxxyy));
+        Self { x: x % p, y: y % p }
+    }
+
+    /// Return the opposite point (-x, y).
+    pub fn neg(&self) -> Self {
+        Self {
+            x: sub_mod(&BigUint::from(0u64), &self.x),
+            y: self.y.clone(),
+        }
+    }
+
+    /// Return `scalar * self`, by double-and-add from the most significant bit.
+    pub fn mul(&self, scalar: &BigUint) -> Self {
+        (0..scalar.bits()).rev().fold(Self::identity(), |acc, i| {
+            let acc = acc.add(&acc);
+            if scalar.bit(i) {
+                acc.add(self)
+            } else {
+                acc
+            }
+        })
+    }
+
+    /// Return the encoding of the point: y in 32 little endian bytes, with
+    /// the parity of x in the top bit.
+    pub fn compress(&self) -> [u8; 32] {
+        let mut bytes = biguint_to_ed25519_bytes_le(&self.y);
+        bytes[31] |= (self.x.bit(0) as u8) << 7;
+        bytes
+    }
+
+    /// Decode a point as defined in RFC 8032 section 5.1.3, or return None if
+    /// y is not canonical or the encoding is not a point of the curve.
+    pub fn decompress(bytes: &[u8; 32]) -> Option<Self> {
+        let p = &*ED25519_P;
+        let sign = bytes[31] >> 7 == 1;
+        let mut y_le = *bytes;
+        y_le[31] &= 0x7f;
+        let y = BigUint::from_bytes_le(&y_le);
+        if &y >= p {
+            return None;
+        }
+        // x^2 = (y^2 - 1) / (d y^2 + 1)
+        let yy = &y * &y % p;
+        let x2 = sub_mod(&yy, &BigUint::from(1u64)) * invert(&((&*ED25519_D * &yy + 1u64) % p)) % p;
+        let mut x = x2.modpow(&((p + 3u64) >> 3), p);
+        if &x * &x % p != x2 {
+            // Multiply by sqrt(-1) = 2^((p - 1) / 4)
+            x = x * BigUint::from(2u64).modpow(&((p - 1u64) >> 2), p) % p;
+        }
+        if &x * &x % p != x2 || (x == BigUint::from(0u64) && sign) {
+            return None;
+        }
+        if x.bit(0) != sign {
+            x = p - x;
+        }
+        Some(Self { x, y })
+    }
+}
+
+/// Ed25519 private key
+#[derive(Clone, Debug)]
+pub struct Ed25519PrivateKey {
+    /// Seed of 32 bytes which the signing scalar and the nonce prefix are
+    /// derived from
+    pub seed: [u8; 32],
+}
+
+impl Ed25519PrivateKey {
+    /// Return the clamped signing scalar and the nonce prefix, the two halves
+    /// of sha512(seed).
+    fn expand(&self) -> (BigUint, [u8; 32]) {
+        let hash = sha512(&self.seed);
+        let mut scalar: [u8; 32] = hash[..32].try_into().unwrap();
+        scalar[0] &= 248;
+        scalar[31] &= 127;
+        scalar[31] |= 64;
+        (
+            BigUint::from_bytes_le(&scalar),
+            hash[32..].try_into().unwrap(),
+        )
+    }
+
+    /// Return the encoded public key `[s]B`.
+    pub fn public_key(&self) -> [u8; 32] {
+        ED25519_BASEPOINT.mul(&self.expand().0).compress()
+    }
+
+    /// Sign a message: the signature is `R || S` with `R = [r]B`,
+    /// `r = sha512(prefix || msg) mod L` and
+    /// `S = r + sha512(R || A || msg) * s mod L`.
+    pub fn sign(&self, msg: &[u8]) -> [u8; 64] {
+        let (scalar, prefix) = self.expand();
+        let pk = ED25519_BASEPOINT.mul(&scalar).compress();
+        let r = BigUint::from_bytes_le(&sha512(&[&prefix[..], msg].concat())) % &*ED25519_L;
+        let r_point = ED25519_BASEPOINT.mul(&r).compress();
+        let k = BigUint::from_bytes_le(&sha512(&[&r_point[..], &pk, msg].concat())) % &*ED25519_L;
+        let s = (r + k * scalar) % &*ED25519_L;
+
+        let mut signature = [0; 64];
+        signature[..32].copy_from_slice(&r_point);
+        signature[32..].copy_from_slice(&biguint_to_ed25519_bytes_le(&s));
+        signature
+    }
+}
+
+/// Signature data required by the Ed25519SignVerifyChip as input to verify an
+/// Ed25519 signature of a 32 bytes message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ed25519SignData {
+    /// Signature `R || S`
+    pub signature: [u8; 64],
+    /// Encoded public key A
+    pub pk: [u8; 32],
+    /// Message that is being signed
+    pub msg: [u8; 32],
+}
+
+impl Ed25519SignData {
+    /// Return the padding signature data: a valid signature of the zero
+    /// message by a fixed key.
+    pub fn padding() -> Self {
+        SIGN_DATA_PADDING.clone()
+    }
+
+    /// Return the encoded point R of the signature.
+    pub fn r(&self) -> [u8; 32] {
+        self.signature[..32].try_into().unwrap()
+    }
+
+    /// Return the scalar S of the signature.
+    pub fn s(&self) -> BigUint {
+        BigUint::from_bytes_le(&self.signature[32..])
+    }
+
+    /// Return the input `R || A || M` hashed with SHA-512 to get the challenge.
+    pub fn sha512_input(&self) -> Vec<u8> {
+        [&self.signature[..32], &self.pk, &self.msg].concat()
+    }
+
+    /// Return the challenge `k = sha512(R || A || M) mod L`.
+    pub fn challenge(&self) -> BigUint {
+        BigUint::from_bytes_le(&sha512(&self.sha512_input())) % &*ED25519_L
+    }
+
+    /// Return true if `S < L`, R and A are valid encodings and
+    /// `[S]B = R + [k]A`.
+    pub fn verify(&self) -> bool {
+        if self.s() >= *ED25519_L {
+            return false;
+        }
+        let (Some(r), Some(pk)) = (
+            EdwardsPoint::decompress(&self.r()),
+            EdwardsPoint::decompress(&self.pk),
+        ) else {
+            return false;
+        };
+        ED25519_BASEPOINT.mul(&self.s()) == r.add(&pk.mul(&self.challenge()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ed25519_rfc8032_test_1() {
+        let sk = Ed25519PrivateKey {
+            seed: hex::decode("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60")
+                .unwrap()
+                .try_into()
+                .unwrap(),
+        };
+        assert_eq!(
+            hex::encode(sk.public_key()),
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a"
+        );
+        assert_eq!(
+            hex::encode(sk.sign(&[])),
+            "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b"
+        );
+
+        let msg = [0x42; 32];
+        let mut sign_data = Ed25519SignData {
+            signature: sk.sign(&msg),
+            pk: sk.public_key(),
+            msg,
+        };
+        assert!(sign_data.verify());
+        sign_data.msg[0] ^= 1;
+        assert!(!sign_data.verify());
+        assert!(Ed25519SignData::padding().verify());
+    }
+}
@@ -3,17 +3,25 @@
 // use serde::{Deserialize, Serialize};
 // use std::fmt;
 
+#[cfg(feature = "custom-opcodes")]
+pub mod custom_opcodes;
+pub mod eof;
 pub mod gas_utils;
+pub mod hardfork;
 pub mod memory;
 pub mod opcode_ids;
 pub mod stack;
 pub mod storage;
+pub mod system_contracts;
 
+pub use hardfork::Hardfork;
 pub use memory::{Memory, MemoryAddress};
 pub use opcode_ids::OpcodeId;
 pub use stack::{Stack, StackAddress};
 pub use storage::Storage;
 
+/// Maximum size of the code of a deployed contract (EIP-170).
+pub const MAX_CODE_SIZE: u64 = 0x6000;
 /// According to EIP-3541, disallow new code starting with 0xEF to be deployed.
 pub const INVALID_INIT_CODE_FIRST_BYTE: u8 = 0xef;
 /// Once per word of the init code when creating a contract.
@@ -28,38 +36,12 @@ pub const GAS_STIPEND_CALL_WITH_VALUE: u64 = 2300;
 /// <https://github.com/ethereum/go-ethereum/blob/e6b6a8b738069ad0579f6798ee59fde93ed13b43/core/vm/gas_table.go#L38>
 pub const MAX_EXPANDED_MEMORY_ADDRESS: u64 = 0x1FFFFFFFE0;
 
-#[cfg(feature = "shanghai")]
-mod gas_create {
-    // For EIP-3860, there are 2 special gas cost constraints in geth
-    // [gasCreate2Eip3860](https://github.com/ethereum/go-ethereum/blob/eb83e7c54021573eaceb14236af3a7a8c64f6027/core/vm/gas_table.go#L321)
-    // (similar for CREATE).
-    // 1. size <= 49152 (MaxInitCodeSize)
-    // 2. gasCost = memoryGasCost + (2 + 6) * ((size + 31) / 32) should not
-    //    overflow for Uint64.
-    // No need to constrain the second condition, since the maximum gas cost
-    // cannot overflow for Uint64 (36028809887100925 calculated by
-    // `memorySize = 0x1FFFFFFFE0` and `size = 49152`) if the first condition is
-    // satisfied.
-
-    /// Maximum init code size to permit in a creation transaction and create instructions.
-    pub const MAX_INIT_CODE_SIZE: u64 = 2 * super::MAX_CODE_SIZE;
-    /// Once per word of the init code when creating a contract.
-    pub const INIT_CODE_WORD_GAS: u64 = 2;
-    /// Gas per code word for CREATE.
-    pub const CREATE_GAS_PER_CODE_WORD: u64 = INIT_CODE_WORD_GAS;
-    /// Gas per code word for CREATE2.
-    pub const CREATE2_GAS_PER_CODE_WORD: u64 = INIT_CODE_WORD_GAS + super::GasCost::COPY_SHA3.0;
-}
-#[cfg(not(feature = "shanghai"))]
-mod gas_create {
-    /// Maximum init code size (0x1FFFFFFFE0) if not EIP-3860.
-    pub use super::MAX_EXPANDED_MEMORY_ADDRESS as MAX_INIT_CODE_SIZE;
-    /// Gas per code word for CREATE if not EIP-3860.
-    pub const CREATE_GAS_PER_CODE_WORD: u64 = 0;
-    /// Gas per code word for CREATE2 if not EIP-3860.
-    pub const CREATE2_GAS_PER_CODE_WORD: u64 = super::GasCost::COPY_SHA3;
-}
-pub use gas_create::*;
+/// Maximum number of blobs of a block, and so of a tx (EIP-4844).
+pub const MAX_BLOBS_PER_BLOCK: usize = 6;
+/// Minimum blob base fee (EIP-4844).
+pub const MIN_BLOB_BASE_FEE: u64 = 1;
+/// Controls the maximum rate of change of the blob base fee (EIP-4844).
+pub const BLOB_BASE_FEE_UPDATE_FRACTION: u64 = 3338477;
 
 /// Defines the gas consumption.
 pub struct GasCost;
@@ -111,6 +93,15 @@ impl GasCost {
     pub const TX: u64 = 21000;
     /// Constant cost for a creation transaction
     pub const CREATION_TX: u64 = 53000;
+    /// Constant cost for every address of the access list of a transaction
+    /// (EIP-2930)
+    pub const ACCESS_LIST_ADDRESS: u64 = 2400;
+    /// Constant cost for every storage key of the access list of a
+    /// transaction (EIP-2930)
+    pub const ACCESS_LIST_STORAGE_KEY: u64 = 1900;
+    /// Constant cost for every authorization of a set-code transaction,
+    /// charged as if its authority were an empty account (EIP-7702)
+    pub const AUTHORIZATION: u64 = 25000;
     /// Constant cost for calling with non-zero value
     pub const CALL_WITH_VALUE: u64 = 9000;
     /// Constant cost for turning empty account into non-empty account
@@ -154,7 +145,15 @@ impl GasCost {
     pub const PRECOMPILE_MODEXP_MIN: u64 = 200;
     /// Base gas cost for precompile call: BLAKE2F
     pub const PRECOMPILE_BLAKE2F: u64 = 0;
+    /// Per-round gas cost for BLAKE2F
+    pub const PRECOMPILE_BLAKE2F_PER_ROUND: u64 = 1;
+    /// Gas cost for precompile call: KZG point evaluation (EIP-4844)
+    pub const PRECOMPILE_POINT_EVALUATION: u64 = 50000;
+    /// Gas cost for precompile call: secp256r1 signature verification
+    /// (RIP-7212)
+    pub const PRECOMPILE_P256VERIFY: u64 = 3450;
 }
 
-/// This constant is used to iterate through precompile contract addresses 0x01 to 0x09
-pub const PRECOMPILE_COUNT: u64 = 9;
+/// Address of the P256VERIFY precompile (RIP-7212), which the L2s place after
+/// the range of the Ethereum precompiles
+pub const PRECOMPILE_P256VERIFY_ADDRESS: u64 = 0x100;
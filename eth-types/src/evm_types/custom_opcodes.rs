@@ -0,0 +1,181 @@
+//! Custom opcodes of an L2, which the circuits support with the
+//! `custom-opcodes` feature.
+//!
+//! A custom opcode takes a byte which no hardfork defines, so it's an
+//! [`OpcodeId::INVALID`] to the rest of the crate.  A fork implements
+//! [`CustomOpcode`] for each of its opcodes, with the witness generation of
+//! bus-mapping and the gadget of the EVM circuit, and registers them before
+//! tracing or configuring any circuit.  The stack and out of gas errors of a
+//! custom opcode aren't supported.
+
+use super::OpcodeId;
+use lazy_static::lazy_static;
+use std::{str::FromStr, sync::RwLock};
+
+/// Definition of a custom opcode.
+pub trait CustomOpcode {
+    /// Byte of the opcode, which must not be defined by any hardfork
+    const OPCODE: u8;
+    /// Name of the opcode in the traces of the L2 node
+    const NAME: &'static str;
+    /// Constant gas cost of the opcode
+    const CONSTANT_GAS: u64;
+}
+
+/// Registered custom opcode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CustomOpcodeInfo {
+    /// Byte of the opcode
+    pub opcode: u8,
+    /// Name of the opcode in the traces of the L2 node
+    pub name: &'static str,
+    /// Constant gas cost of the opcode
+    pub constant_gas: u64,
+}
+
+impl CustomOpcodeInfo {
+    /// Return the registration of a custom opcode.
+    pub const fn of<T: CustomOpcode>() -> Self {
+        Self {
+            opcode: T::OPCODE,
+            name: T::NAME,
+            constant_gas: T::CONSTANT_GAS,
+        }
+    }
+
+    /// Return the opcode id of the custom opcode.
+    pub fn opcode_id(&self) -> OpcodeId {
+        OpcodeId::INVALID(self.opcode)
+    }
+}
+
+lazy_static! {
+    /// The registered custom opcodes of the L2
+    static ref CUSTOM_OPCODES: RwLock<Vec<CustomOpcodeInfo>> = RwLock::new(Vec::new());
+}
+
+/// Register a custom opcode of the L2.  Registering it again is a no-op.
+///
+/// # Panics
+///
+/// If the byte of the opcode is defined by a hardfork or is the designated
+/// INVALID opcode, if its name is the one of an opcode of a hardfork, or if
+/// its byte or its name is registered by another custom opcode.
+pub fn register_custom_opcode<T: CustomOpcode>() {
+    let info = CustomOpcodeInfo::of::<T>();
+    assert!(
+        matches!(OpcodeId::from(info.opcode), OpcodeId::INVALID(_)) && info.opcode != 0xfe,
+        "custom opcode {} takes the defined byte {:#x}",
+        info.name,
+        info.opcode
+    );
+    assert!(
+        OpcodeId::from_str(info.name).map_or(true, |opcode| opcode == info.opcode_id()),
+        "custom opcode {} takes the name of another opcode",
+        info.name
+    );
+
+    let mut opcodes = CUSTOM_OPCODES.write().expect("poisoned custom opcodes");
+    if opcodes.contains(&info) {
+        return;
+    }
+    assert!(
+        opcodes
+            .iter()
+            .all(|registered| registered.opcode != info.opcode),
+        "custom opcode {} takes the byte {:#x} of another custom opcode",
+        info.name,
+        info.opcode
+    );
+    opcodes.push(info);
+}
+
+/// Return the registered custom opcodes.
+pub fn custom_opcodes() -> Vec<CustomOpcodeInfo> {
+    CUSTOM_OPCODES
+        .read()
+        .expect("poisoned custom opcodes")
+        .clone()
+}
+
+/// Return the registered custom opcode of a byte.
+pub fn custom_opcode(opcode: u8) -> Option<CustomOpcodeInfo> {
+    CUSTOM_OPCODES
+        .read()
+        .expect("poisoned custom opcodes")
+        .iter()
+        .find(|info| info.opcode == opcode)
+        .copied()
+}
+
+/// Return the registered custom opcode of a name in the traces.
+pub fn custom_opcode_by_name(name: &str) -> Option<CustomOpcodeInfo> {
+    CUSTOM_OPCODES
+        .read()
+        .expect("poisoned custom opcodes")
+        .iter()
+        .find(|info| info.name == name)
+        .copied()
+}
+
+#[cfg(test)]
+mod custom_opcodes_tests {
+    use super::*;
+
+    /// Custom opcode of the tests, on a byte which no test uses
+    struct TestOpcode;
+
+    impl CustomOpcode for TestOpcode {
+        const OPCODE: u8 = 0x21;
+        const NAME: &'static str = "TESTOPCODE";
+        const CONSTANT_GAS: u64 = 2;
+    }
+
+    #[test]
+    fn register_custom_opcode_ok() {
+        register_custom_opcode::<TestOpcode>();
+        register_custom_opcode::<TestOpcode>();
+
+        let info = CustomOpcodeInfo::of::<TestOpcode>();
+        assert_eq!(
+            custom_opcodes()
+                .iter()
+                .filter(|registered| **registered == info)
+                .count(),
+            1
+        );
+        assert_eq!(custom_opcode(0x21), Some(info));
+        assert_eq!(custom_opcode_by_name("TESTOPCODE"), Some(info));
+        assert!(OpcodeId::INVALID(0x21).is_custom());
+        assert!(!OpcodeId::invalid_opcodes().contains(&OpcodeId::INVALID(0x21)));
+        assert_eq!(
+            OpcodeId::from_str("TESTOPCODE").unwrap(),
+            OpcodeId::INVALID(0x21)
+        );
+        assert!(!OpcodeId::INVALID(0xfe).is_custom());
+    }
+
+    #[test]
+    #[should_panic(expected = "takes the defined byte")]
+    fn register_custom_opcode_defined_byte() {
+        struct Add;
+        impl CustomOpcode for Add {
+            const OPCODE: u8 = 0x01;
+            const NAME: &'static str = "CUSTOMADD";
+            const CONSTANT_GAS: u64 = 3;
+        }
+        register_custom_opcode::<Add>();
+    }
+
+    #[test]
+    #[should_panic(expected = "takes the name of another opcode")]
+    fn register_custom_opcode_defined_name() {
+        struct Add;
+        impl CustomOpcode for Add {
+            const OPCODE: u8 = 0x22;
+            const NAME: &'static str = "ADD";
+            const CONSTANT_GAS: u64 = 3;
+        }
+        register_custom_opcode::<Add>();
+    }
+}
@@ -0,0 +1,214 @@
+//! EVM Object Format (EOF) containers of EIP-3540, whose code section is
+//! validated at deployment by EIP-3670.
+//!
+//! A container is made of a header, a code section and an optional data
+//! section:
+//!
+//! ```text
+//! magic (0xef00) | version (0x01) | 0x01 | code_size (2 bytes)
+//!     [| 0x02 | data_size (2 bytes)] | 0x00 | code | [data]
+//! ```
+
+use super::OpcodeId;
+
+/// Magic of an EOF container, which legacy code can't start with since
+/// EIP-3541.
+pub const EOF_MAGIC: [u8; 2] = [0xef, 0x00];
+/// Version of the EOF containers
+pub const EOF_VERSION: u8 = 1;
+/// Kind of the section header terminating the header
+pub const EOF_KIND_TERMINATOR: u8 = 0;
+/// Kind of the code section header
+pub const EOF_KIND_CODE: u8 = 1;
+/// Kind of the data section header
+pub const EOF_KIND_DATA: u8 = 2;
+
+/// Byte of the header of an EOF container
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EofHeaderByte {
+    /// Byte of the magic, the version, or a section kind
+    Fixed(u8),
+    /// High byte of the size of the code or data section
+    SizeHi,
+    /// Low byte of the size of the code section
+    CodeSizeLo,
+    /// Low byte of the size of the data section
+    DataSizeLo,
+}
+
+impl EofHeaderByte {
+    /// Return the bytes of the header of a container with or without data
+    /// section.
+    pub fn header(has_data: bool) -> Vec<Self> {
+        let mut header = vec![
+            Self::Fixed(EOF_MAGIC[0]),
+            Self::Fixed(EOF_MAGIC[1]),
+            Self::Fixed(EOF_VERSION),
+            Self::Fixed(EOF_KIND_CODE),
+            Self::SizeHi,
+            Self::CodeSizeLo,
+        ];
+        if has_data {
+            header.extend([Self::Fixed(EOF_KIND_DATA), Self::SizeHi, Self::DataSizeLo]);
+        }
+        header.push(Self::Fixed(EOF_KIND_TERMINATOR));
+        header
+    }
+}
+
+/// Error of an invalid EOF container
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EofError {
+    /// The container is shorter than its header.
+    Truncated,
+    /// Byte `index` of the header is not the expected magic, version or
+    /// section kind.
+    Header(usize),
+    /// The code section or the data section is present but empty.
+    EmptySection,
+    /// The length of the container doesn't match the sizes of its sections.
+    ContainerSize,
+    /// The code section has an undefined opcode at `index`.
+    UndefinedOpcode(usize),
+    /// The code section ends within the data of a PUSH.
+    TruncatedPush,
+}
+
+/// Header of an EOF container
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EofHeader {
+    /// Whether the container has a data section
+    pub has_data: bool,
+    /// Size of the code section
+    pub code_size: usize,
+    /// Size of the data section, zero without data section
+    pub data_size: usize,
+}
+
+impl EofHeader {
+    /// Return the header read from `code`, tolerating the missing bytes and
+    /// the unexpected ones.  The result is meaningful only for a code
+    /// starting with the magic.
+    pub fn read(code: &[u8]) -> Self {
+        let size_at = |index: usize| {
+            (*code.get(index).unwrap_or(&0) as usize) << 8
+                | *code.get(index + 1).unwrap_or(&0) as usize
+        };
+        let has_data = code.get(6) == Some(&EOF_KIND_DATA);
+        Self {
+            has_data,
+            code_size: size_at(4),
+            data_size: if has_data { size_at(7) } else { 0 },
+        }
+    }
+
+    /// Return the size of the header.
+    pub fn header_size(&self) -> usize {
+        if self.has_data {
+            10
+        } else {
+            7
+        }
+    }
+
+    /// Return the size of the container.
+    pub fn container_size(&self) -> usize {
+        self.header_size() + self.code_size + self.data_size
+    }
+}
+
+/// Return whether `code` is an EOF container, possibly invalid.  Since
+/// EIP-3541 no legacy code starts with the first byte of the magic.
+pub fn is_eof(code: &[u8]) -> bool {
+    code.first() == Some(&EOF_MAGIC[0])
+}
+
+/// Return whether an opcode can be in the code section of a container
+/// (EIP-3670): every defined opcode and INVALID (0xfe).
+pub fn is_eof_valid_opcode(byte: u8) -> bool {
+    let opcode = OpcodeId::from(byte);
+    !matches!(opcode, OpcodeId::INVALID(_)) || opcode.is_custom() || byte == 0xfe
+}
+
+/// Validate the EOF container `code` as of EIP-3540 and EIP-3670, and return
+/// its header.
+pub fn validate_eof(code: &[u8]) -> Result<EofHeader, EofError> {
+    let header = EofHeader::read(code);
+    let header_bytes = EofHeaderByte::header(header.has_data);
+    if code.len() < header_bytes.len() {
+        return Err(EofError::Truncated);
+    }
+    for (index, header_byte) in header_bytes.into_iter().enumerate() {
+        if matches!(header_byte, EofHeaderByte::Fixed(byte) if byte != code[index]) {
+            return Err(EofError::Header(index));
+        }
+    }
+    if header.code_size == 0 || (header.has_data && header.data_size == 0) {
+        return Err(EofError::EmptySection);
+    }
+    if code.len() != header.container_size() {
+        return Err(EofError::ContainerSize);
+    }
+
+    let code_start = header.header_size();
+    let code_end = code_start + header.code_size;
+    let mut index = code_start;
+    while index < code_end {
+        if !is_eof_valid_opcode(code[index]) {
+            return Err(EofError::UndefinedOpcode(index));
+        }
+        index += 1 + OpcodeId::from(code[index]).data_len();
+    }
+    if index > code_end {
+        return Err(EofError::TruncatedPush);
+    }
+    Ok(header)
+}
+
+#[cfg(test)]
+mod eof_tests {
+    use super::*;
+
+    #[test]
+    fn validate_eof_containers() {
+        // PUSH1 1 STOP, and a byte of data
+        let container = [
+            0xef, 0x00, 0x01, 0x01, 0x00, 0x03, 0x02, 0x00, 0x01, 0x00, 0x60, 0x01, 0x00, 0xaa,
+        ];
+        assert_eq!(
+            validate_eof(&container),
+            Ok(EofHeader {
+                has_data: true,
+                code_size: 3,
+                data_size: 1,
+            })
+        );
+        assert_eq!(
+            validate_eof(&[0xef, 0x00, 0x01, 0x01, 0x00, 0x01, 0x00, 0xfe]),
+            Ok(EofHeader {
+                has_data: false,
+                code_size: 1,
+                data_size: 0,
+            })
+        );
+
+        let mut version_2 = container;
+        version_2[2] = 0x02;
+        assert_eq!(validate_eof(&version_2), Err(EofError::Header(2)));
+        assert_eq!(validate_eof(&container[..13]), Err(EofError::ContainerSize));
+        assert_eq!(validate_eof(&container[..8]), Err(EofError::Truncated));
+        assert_eq!(
+            validate_eof(&[0xef, 0x00, 0x01, 0x01, 0x00, 0x00, 0x00]),
+            Err(EofError::EmptySection)
+        );
+        // PUSH2 with a single byte of data
+        assert_eq!(
+            validate_eof(&[0xef, 0x00, 0x01, 0x01, 0x00, 0x02, 0x00, 0x61, 0x01]),
+            Err(EofError::TruncatedPush)
+        );
+        assert_eq!(
+            validate_eof(&[0xef, 0x00, 0x01, 0x01, 0x00, 0x01, 0x00, 0x0c]),
+            Err(EofError::UndefinedOpcode(7))
+        );
+    }
+}
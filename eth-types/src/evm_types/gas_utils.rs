@@ -1,12 +1,28 @@
 //! Utility functions to help calculate gas
 
-use super::GasCost;
+use super::{
+    GasCost, BLOB_BASE_FEE_UPDATE_FRACTION, MAX_EXPANDED_MEMORY_ADDRESS, MIN_BLOB_BASE_FEE,
+};
 use crate::Word;
 
+/// Calculate the memory word size of an address, which saturates like
+/// `toWordSize` of go-ethereum.
+pub fn memory_word_size(address: u64) -> u64 {
+    address
+        .checked_add(31)
+        .map_or(u64::MAX / 32 + 1, |address| address / 32)
+}
+
 /// Calculate memory expansion gas cost by current and next memory word size.
+///
+/// The cost saturates to `u64::MAX` if the next memory size is above
+/// `MAX_EXPANDED_MEMORY_ADDRESS`, where go-ethereum returns a gas overflow
+/// error, so that it's always out of gas.
 pub fn memory_expansion_gas_cost(curr_memory_word_size: u64, next_memory_word_size: u64) -> u64 {
     if next_memory_word_size == curr_memory_word_size {
         0
+    } else if next_memory_word_size > MAX_EXPANDED_MEMORY_ADDRESS / 32 {
+        u64::MAX
     } else {
         GasCost::MEMORY_EXPANSION_LINEAR_COEFF * (next_memory_word_size - curr_memory_word_size)
             + next_memory_word_size * next_memory_word_size
@@ -17,21 +33,22 @@ pub fn memory_expansion_gas_cost(curr_memory_word_size: u64, next_memory_word_si
 }
 
 /// Calculate memory copier gas cost by current and next memory word size, and
-/// number of bytes to copy.
+/// number of bytes to copy, which saturates to `u64::MAX` on overflow.
 pub fn memory_copier_gas_cost(
     curr_memory_word_size: u64,
     next_memory_word_size: u64,
     num_copy_bytes: u64,
 ) -> u64 {
-    let num_words = (num_copy_bytes + 31) / 32;
-    num_words * GasCost::COPY +
+    let num_words = memory_word_size(num_copy_bytes);
+    num_words.saturating_mul(GasCost::COPY).saturating_add(
         // Note that opcodes with a byte size parameter of 0 will not trigger
         // memory expansion, regardless of their offset parameters.
         if num_words > 0 {
             memory_expansion_gas_cost(curr_memory_word_size, next_memory_word_size)
         } else {
             0
-        }
+        },
+    )
 }
 
 /// Calculate EIP 150 gas passed to callee.
@@ -47,3 +64,67 @@ pub fn eip150_gas(gas_left: u64, gas_specified: Word) -> u64 {
 
     capped_gas
 }
+
+/// Calculate the EIP-4844 blob base fee from the excess blob gas of the block,
+/// approximating `MIN_BLOB_BASE_FEE * e^(excess_blob_gas /
+/// BLOB_BASE_FEE_UPDATE_FRACTION)` with a Taylor expansion
+/// (`fake_exponential` in the EIP).
+pub fn blob_base_fee(excess_blob_gas: u64) -> Word {
+    let numerator = Word::from(excess_blob_gas);
+    let denominator = Word::from(BLOB_BASE_FEE_UPDATE_FRACTION);
+    let mut output = Word::zero();
+    let mut numerator_accum = Word::from(MIN_BLOB_BASE_FEE) * denominator;
+    let mut i = Word::one();
+    while !numerator_accum.is_zero() {
+        output += numerator_accum;
+        numerator_accum = numerator_accum.saturating_mul(numerator) / (denominator * i);
+        i += Word::one();
+    }
+    output / denominator
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_gas_cost_at_max_expanded_address() {
+        let max_word_size = MAX_EXPANDED_MEMORY_ADDRESS / 32;
+        assert_eq!(memory_word_size(MAX_EXPANDED_MEMORY_ADDRESS), max_word_size);
+        assert_eq!(
+            memory_word_size(MAX_EXPANDED_MEMORY_ADDRESS + 1),
+            max_word_size + 1
+        );
+        assert_eq!(memory_word_size(u64::MAX), u64::MAX / 32 + 1);
+
+        // The quadratic cost of the largest memory still fits in an u64.
+        assert_eq!(
+            memory_expansion_gas_cost(0, max_word_size),
+            GasCost::MEMORY_EXPANSION_LINEAR_COEFF * max_word_size
+                + max_word_size * max_word_size / GasCost::MEMORY_EXPANSION_QUAD_DENOMINATOR
+        );
+        assert_eq!(memory_expansion_gas_cost(0, max_word_size + 1), u64::MAX);
+        assert_eq!(
+            memory_expansion_gas_cost(max_word_size + 1, max_word_size + 1),
+            0
+        );
+
+        assert_eq!(memory_copier_gas_cost(0, 0, 0), 0);
+        assert_eq!(
+            memory_copier_gas_cost(0, max_word_size, MAX_EXPANDED_MEMORY_ADDRESS),
+            GasCost::COPY * max_word_size + memory_expansion_gas_cost(0, max_word_size)
+        );
+        assert_eq!(memory_copier_gas_cost(0, 1, u64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn blob_base_fee_from_excess_blob_gas() {
+        assert_eq!(blob_base_fee(0), Word::one());
+        // e and e^10, rounded down
+        assert_eq!(blob_base_fee(BLOB_BASE_FEE_UPDATE_FRACTION), Word::from(2));
+        assert_eq!(
+            blob_base_fee(10 * BLOB_BASE_FEE_UPDATE_FRACTION),
+            Word::from(22026)
+        );
+    }
+}
@@ -0,0 +1,210 @@
+//! Hardforks of the Ethereum mainnet supported by the circuits
+
+use super::{
+    system_contracts::HISTORY_SERVE_WINDOW, GasCost, OpcodeId, INIT_CODE_WORD_GAS, MAX_CODE_SIZE,
+    MAX_EXPANDED_MEMORY_ADDRESS, PRECOMPILE_P256VERIFY_ADDRESS,
+};
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+/// Hardfork whose rules the circuits follow.  Hardforks are ordered by
+/// activation, so `hardfork >= Hardfork::Shanghai` holds for Shanghai and every
+/// later hardfork.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, EnumIter,
+)]
+pub enum Hardfork {
+    /// The Merge (Paris), the hardfork before Shanghai
+    Paris,
+    /// Shanghai, which enables PUSH0 (EIP-3855) and limits and meters the init
+    /// code (EIP-3860)
+    Shanghai,
+    /// Cancun, which enables MCOPY (EIP-5656), TLOAD and TSTORE (EIP-1153),
+    /// blob transactions with BLOBHASH and BLOBBASEFEE (EIP-4844, EIP-7516),
+    /// and the beacon block root in the EVM (EIP-4788)
+    Cancun,
+    /// Prague, which serves the historical block hashes from the state
+    /// (EIP-2935), and enables the set-code transactions (EIP-7702)
+    Prague,
+    /// Osaka, which introduces the EOF containers (EIP-3540) whose code is
+    /// validated at deployment (EIP-3670)
+    Osaka,
+}
+
+impl Default for Hardfork {
+    /// The hardfork of the go-ethereum version which geth-utils traces the
+    /// blocks with.
+    fn default() -> Self {
+        Self::Shanghai
+    }
+}
+
+impl Hardfork {
+    /// Return the hardfork in which an opcode was introduced, or `None` if the
+    /// opcode is older than every supported hardfork.
+    pub fn activation_of(opcode: OpcodeId) -> Option<Self> {
+        match opcode {
+            OpcodeId::PUSH0 => Some(Self::Shanghai),
+            OpcodeId::MCOPY
+            | OpcodeId::TLOAD
+            | OpcodeId::TSTORE
+            | OpcodeId::BLOBHASH
+            | OpcodeId::BLOBBASEFEE => Some(Self::Cancun),
+            _ => None,
+        }
+    }
+
+    /// Return whether a defined opcode is enabled in this hardfork.  An opcode
+    /// which isn't is executed as an invalid opcode.
+    pub fn is_opcode_active(&self, opcode: OpcodeId) -> bool {
+        Self::activation_of(opcode).map_or(true, |activation| activation <= *self)
+    }
+
+    /// Return the defined opcodes that aren't enabled yet in this hardfork.
+    pub fn inactive_opcodes(&self) -> Vec<OpcodeId> {
+        OpcodeId::valid_opcodes()
+            .into_iter()
+            .filter(|opcode| !self.is_opcode_active(*opcode))
+            .collect()
+    }
+
+    /// Return whether blob transactions (EIP-4844) are enabled.
+    pub fn is_blob_tx_active(&self) -> bool {
+        *self >= Self::Cancun
+    }
+
+    /// Return whether the parent beacon block root is stored in the beacon
+    /// roots contract by a system call at the beginning of a block (EIP-4788).
+    pub fn is_beacon_root_active(&self) -> bool {
+        *self >= Self::Cancun
+    }
+
+    /// Return whether set-code transactions (EIP-7702) are enabled.
+    pub fn is_set_code_tx_active(&self) -> bool {
+        *self >= Self::Prague
+    }
+
+    /// Return whether the hash of the parent block is stored in the history
+    /// storage contract by a system call at the beginning of a block, and
+    /// BLOCKHASH reads the hashes from its storage (EIP-2935).
+    pub fn is_history_storage_active(&self) -> bool {
+        *self >= Self::Prague
+    }
+
+    /// Return whether the code starting with 0xef is an EOF container, which
+    /// is validated at deployment (EIP-3540, EIP-3670).
+    pub fn is_eof_active(&self) -> bool {
+        *self >= Self::Osaka
+    }
+
+    /// Return the number of the Ethereum precompiles, which are at the
+    /// addresses 0x01 to the count.  Cancun adds the point evaluation at 0x0a
+    /// (EIP-4844).
+    pub fn precompile_count(&self) -> u64 {
+        if *self >= Self::Cancun {
+            10
+        } else {
+            9
+        }
+    }
+
+    /// Return the addresses of the precompiles, which are added to the access
+    /// list at the beginning of every tx.  `p256_verify` adds the P256VERIFY
+    /// precompile (RIP-7212) of the L2s which enable it.
+    pub fn precompile_addresses(&self, p256_verify: bool) -> Vec<u64> {
+        (1..=self.precompile_count())
+            .chain(p256_verify.then_some(PRECOMPILE_P256VERIFY_ADDRESS))
+            .collect()
+    }
+
+    /// Return the number of the most recent block hashes which BLOCKHASH
+    /// returns.
+    pub fn block_hash_window(&self) -> u64 {
+        if self.is_history_storage_active() {
+            HISTORY_SERVE_WINDOW
+        } else {
+            256
+        }
+    }
+
+    /// Return whether the init code is limited and metered (EIP-3860).
+    pub fn is_init_code_metered(&self) -> bool {
+        *self >= Self::Shanghai
+    }
+
+    /// Maximum init code size to permit in a creation transaction and create
+    /// instructions, which is 0x1FFFFFFFE0 if not EIP-3860.
+    pub fn max_init_code_size(&self) -> u64 {
+        if self.is_init_code_metered() {
+            2 * MAX_CODE_SIZE
+        } else {
+            MAX_EXPANDED_MEMORY_ADDRESS
+        }
+    }
+
+    /// Gas per code word for CREATE, and for the init code of a creation tx.
+    pub fn create_gas_per_code_word(&self) -> u64 {
+        if self.is_init_code_metered() {
+            INIT_CODE_WORD_GAS
+        } else {
+            0
+        }
+    }
+
+    /// Gas per code word for CREATE2.
+    pub fn create2_gas_per_code_word(&self) -> u64 {
+        self.create_gas_per_code_word() + GasCost::COPY_SHA3
+    }
+}
+
+#[cfg(test)]
+mod hardfork_tests {
+    use super::*;
+
+    #[test]
+    fn opcode_activation() {
+        assert!(!Hardfork::Paris.is_opcode_active(OpcodeId::PUSH0));
+        assert!(Hardfork::Shanghai.is_opcode_active(OpcodeId::PUSH0));
+        assert!(!Hardfork::Shanghai.is_opcode_active(OpcodeId::MCOPY));
+        assert!(!Hardfork::Shanghai.is_opcode_active(OpcodeId::TSTORE));
+        assert!(Hardfork::Cancun.is_opcode_active(OpcodeId::BLOBBASEFEE));
+        assert!(Hardfork::Paris.is_opcode_active(OpcodeId::SELFDESTRUCT));
+        assert!(Hardfork::Cancun.inactive_opcodes().is_empty());
+        assert!(!Hardfork::Shanghai.is_beacon_root_active());
+        assert!(Hardfork::Cancun.is_beacon_root_active());
+        assert!(Hardfork::Prague.inactive_opcodes().is_empty());
+    }
+
+    #[test]
+    fn block_hash_window() {
+        assert!(!Hardfork::Cancun.is_history_storage_active());
+        assert!(!Hardfork::Cancun.is_set_code_tx_active());
+        assert!(Hardfork::Prague.is_set_code_tx_active());
+        assert_eq!(Hardfork::Cancun.block_hash_window(), 256);
+        assert_eq!(Hardfork::Prague.block_hash_window(), 8191);
+        assert!(!Hardfork::Prague.is_eof_active());
+        assert!(Hardfork::Osaka.is_eof_active());
+    }
+
+    #[test]
+    fn precompile_addresses() {
+        assert_eq!(Hardfork::Shanghai.precompile_addresses(false).len(), 9);
+        assert_eq!(
+            Hardfork::Cancun.precompile_addresses(false).last(),
+            Some(&0x0a)
+        );
+        assert_eq!(
+            Hardfork::Shanghai.precompile_addresses(true).last(),
+            Some(&PRECOMPILE_P256VERIFY_ADDRESS)
+        );
+        assert_eq!(Hardfork::Prague.precompile_addresses(true).len(), 11);
+    }
+
+    #[test]
+    fn init_code_gas() {
+        assert_eq!(Hardfork::Paris.create_gas_per_code_word(), 0);
+        assert_eq!(Hardfork::Paris.create2_gas_per_code_word(), 6);
+        assert_eq!(Hardfork::Shanghai.max_init_code_size(), 49152);
+        assert_eq!(Hardfork::Cancun.create2_gas_per_code_word(), 8);
+    }
+}
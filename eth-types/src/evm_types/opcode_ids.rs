@@ -93,6 +93,8 @@ pub enum OpcodeId {
     MSIZE,
     /// `JUMPDEST`
     JUMPDEST,
+    /// `MCOPY`
+    MCOPY,
 
     // PUSHn
     /// `PUSH0`
@@ -281,10 +283,18 @@ pub enum OpcodeId {
     SELFBALANCE,
     /// `BASEFEE`
     BASEFEE,
+    /// `BLOBHASH`
+    BLOBHASH,
+    /// `BLOBBASEFEE`
+    BLOBBASEFEE,
     /// `SLOAD`
     SLOAD,
     /// `SSTORE`
     SSTORE,
+    /// `TLOAD`
+    TLOAD,
+    /// `TSTORE`
+    TSTORE,
     /// `GAS`
     GAS,
 
@@ -409,6 +419,7 @@ impl OpcodeId {
             OpcodeId::PC => 0x58u8,
             OpcodeId::MSIZE => 0x59u8,
             OpcodeId::JUMPDEST => 0x5bu8,
+            OpcodeId::MCOPY => 0x5eu8,
             OpcodeId::PUSH0 => 0x5fu8,
             OpcodeId::PUSH1 => 0x60u8,
             OpcodeId::PUSH2 => 0x61u8,
@@ -498,8 +509,12 @@ impl OpcodeId {
             OpcodeId::CHAINID => 0x46u8,
             OpcodeId::SELFBALANCE => 0x47u8,
             OpcodeId::BASEFEE => 0x48u8,
+            OpcodeId::BLOBHASH => 0x49u8,
+            OpcodeId::BLOBBASEFEE => 0x4au8,
             OpcodeId::SLOAD => 0x54u8,
             OpcodeId::SSTORE => 0x55u8,
+            OpcodeId::TLOAD => 0x5cu8,
+            OpcodeId::TSTORE => 0x5du8,
             OpcodeId::GAS => 0x5au8,
             OpcodeId::LOG0 => 0xa0u8,
             OpcodeId::LOG1 => 0xa1u8,
@@ -576,18 +591,23 @@ impl OpcodeId {
             OpcodeId::CHAINID => GasCost::QUICK,
             OpcodeId::SELFBALANCE => GasCost::FAST,
             OpcodeId::BASEFEE => GasCost::QUICK,
+            OpcodeId::BLOBHASH => GasCost::FASTEST,
+            OpcodeId::BLOBBASEFEE => GasCost::QUICK,
             OpcodeId::POP => GasCost::QUICK,
             OpcodeId::MLOAD => GasCost::FASTEST,
             OpcodeId::MSTORE => GasCost::FASTEST,
             OpcodeId::MSTORE8 => GasCost::FASTEST,
             OpcodeId::SLOAD => GasCost::ZERO,
             OpcodeId::SSTORE => GasCost::ZERO,
+            OpcodeId::TLOAD => GasCost::WARM_ACCESS,
+            OpcodeId::TSTORE => GasCost::WARM_ACCESS,
             OpcodeId::JUMP => GasCost::MID,
             OpcodeId::JUMPI => GasCost::SLOW,
             OpcodeId::PC => GasCost::QUICK,
             OpcodeId::MSIZE => GasCost::QUICK,
             OpcodeId::GAS => GasCost::QUICK,
             OpcodeId::JUMPDEST => GasCost::ONE,
+            OpcodeId::MCOPY => GasCost::FASTEST,
             OpcodeId::PUSH0 => GasCost::QUICK,
             OpcodeId::PUSH1 => GasCost::FASTEST,
             OpcodeId::PUSH2 => GasCost::FASTEST,
@@ -731,18 +751,23 @@ impl OpcodeId {
             OpcodeId::CHAINID => (1, 1024),
             OpcodeId::SELFBALANCE => (1, 1024),
             OpcodeId::BASEFEE => (1, 1024),
+            OpcodeId::BLOBHASH => (0, 1023),
+            OpcodeId::BLOBBASEFEE => (1, 1024),
             OpcodeId::POP => (0, 1023),
             OpcodeId::MLOAD => (0, 1023),
             OpcodeId::MSTORE => (0, 1022),
             OpcodeId::MSTORE8 => (0, 1022),
             OpcodeId::SLOAD => (0, 1023),
             OpcodeId::SSTORE => (0, 1022),
+            OpcodeId::TLOAD => (0, 1023),
+            OpcodeId::TSTORE => (0, 1022),
             OpcodeId::JUMP => (0, 1023),
             OpcodeId::JUMPI => (0, 1022),
             OpcodeId::PC => (1, 1024),
             OpcodeId::MSIZE => (1, 1024),
             OpcodeId::GAS => (1, 1024),
             OpcodeId::JUMPDEST => (0, 1024),
+            OpcodeId::MCOPY => (0, 1021),
             OpcodeId::PUSH0 => (1, 1024),
             OpcodeId::PUSH1 => (1, 1024),
             OpcodeId::PUSH2 => (1, 1024),
@@ -845,6 +870,7 @@ impl OpcodeId {
                 | OpcodeId::RETURNDATACOPY
                 | OpcodeId::CODECOPY
                 | OpcodeId::EXTCODECOPY
+                | OpcodeId::MCOPY
         )
     }
 
@@ -894,10 +920,20 @@ impl OpcodeId {
         })
     }
 
-    /// Returns the all invalid opcodes.
+    /// Returns whether the opcode is a registered custom opcode of an L2,
+    /// whose byte is undefined in the EVM.
+    pub fn is_custom(&self) -> bool {
+        #[cfg(feature = "custom-opcodes")]
+        if let Self::INVALID(b) = self {
+            return crate::evm_types::custom_opcodes::custom_opcode(*b).is_some();
+        }
+        false
+    }
+
+    /// Returns the all invalid opcodes, which exclude the custom opcodes.
     pub fn invalid_opcodes() -> Vec<Self> {
         (u8::MIN..=u8::MAX).fold(vec![], |mut acc, val| {
-            if matches!(val.into(), Self::INVALID(_)) {
+            if matches!(val.into(), Self::INVALID(_)) && !Self::INVALID(val).is_custom() {
                 acc.push(Self::INVALID(val));
             }
             acc
@@ -948,6 +984,7 @@ impl From<u8> for OpcodeId {
             0x58u8 => OpcodeId::PC,
             0x59u8 => OpcodeId::MSIZE,
             0x5bu8 => OpcodeId::JUMPDEST,
+            0x5eu8 => OpcodeId::MCOPY,
             0x5fu8 => OpcodeId::PUSH0,
             0x60u8 => OpcodeId::PUSH1,
             0x61u8 => OpcodeId::PUSH2,
@@ -1037,8 +1074,12 @@ impl From<u8> for OpcodeId {
             0x46u8 => OpcodeId::CHAINID,
             0x47u8 => OpcodeId::SELFBALANCE,
             0x48u8 => OpcodeId::BASEFEE,
+            0x49u8 => OpcodeId::BLOBHASH,
+            0x4au8 => OpcodeId::BLOBBASEFEE,
             0x54u8 => OpcodeId::SLOAD,
             0x55u8 => OpcodeId::SSTORE,
+            0x5cu8 => OpcodeId::TLOAD,
+            0x5du8 => OpcodeId::TSTORE,
             0x5au8 => OpcodeId::GAS,
             0xa0u8 => OpcodeId::LOG0,
             0xa1u8 => OpcodeId::LOG1,
@@ -1102,6 +1143,7 @@ impl FromStr for OpcodeId {
             "PC" => OpcodeId::PC,
             "MSIZE" => OpcodeId::MSIZE,
             "JUMPDEST" => OpcodeId::JUMPDEST,
+            "MCOPY" => OpcodeId::MCOPY,
             "PUSH0" => OpcodeId::PUSH0,
             "PUSH1" => OpcodeId::PUSH1,
             "PUSH2" => OpcodeId::PUSH2,
@@ -1191,6 +1233,8 @@ impl FromStr for OpcodeId {
             "GASLIMIT" => OpcodeId::GASLIMIT,
             "SLOAD" => OpcodeId::SLOAD,
             "SSTORE" => OpcodeId::SSTORE,
+            "TLOAD" => OpcodeId::TLOAD,
+            "TSTORE" => OpcodeId::TSTORE,
             "GAS" => OpcodeId::GAS,
             "LOG0" => OpcodeId::LOG0,
             "LOG1" => OpcodeId::LOG1,
@@ -1206,8 +1250,8 @@ impl FromStr for OpcodeId {
             "SELFDESTRUCT" => OpcodeId::SELFDESTRUCT,
             "CHAINID" => OpcodeId::CHAINID,
             "BASEFEE" => OpcodeId::BASEFEE,
-            "TLOAD" => OpcodeId::INVALID(0xb3),
-            "TSTORE" => OpcodeId::INVALID(0xb4),
+            "BLOBHASH" => OpcodeId::BLOBHASH,
+            "BLOBBASEFEE" => OpcodeId::BLOBBASEFEE,
             _ => {
                 // Parse an invalid opcode value as reported by geth
                 lazy_static! {
@@ -1221,6 +1265,10 @@ impl FromStr for OpcodeId {
                         ));
                     }
                 }
+                #[cfg(feature = "custom-opcodes")]
+                if let Some(info) = crate::evm_types::custom_opcodes::custom_opcode_by_name(s) {
+                    return Ok(info.opcode_id());
+                }
                 return Err(Error::OpcodeParsing(s.to_string()));
             }
         })
@@ -1246,6 +1294,7 @@ impl fmt::Display for OpcodeId {
 #[cfg(test)]
 mod opcode_ids_tests {
     use super::*;
+    use crate::evm_types::Hardfork;
 
     #[test]
     fn push_n() {
@@ -1258,6 +1307,16 @@ mod opcode_ids_tests {
         ));
     }
 
+    #[test]
+    fn push0_activation() {
+        // PUSH0 is always decoded, and executed as an invalid opcode before
+        // Shanghai
+        assert_eq!(OpcodeId::from(0x5fu8), OpcodeId::PUSH0);
+        assert!(OpcodeId::valid_opcodes().contains(&OpcodeId::PUSH0));
+        assert!(!Hardfork::Paris.is_opcode_active(OpcodeId::PUSH0));
+        assert!(Hardfork::Shanghai.is_opcode_active(OpcodeId::PUSH0));
+    }
+
     #[test]
     fn postfix() {
         assert_eq!(OpcodeId::PUSH0.postfix(), Some(0));
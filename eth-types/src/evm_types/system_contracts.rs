@@ -0,0 +1,101 @@
+//! System contracts which the client calls at the beginning of a block, from
+//! the system address and outside of any transaction.
+
+use crate::{Address, Word, H160};
+
+/// Caller of the system calls (EIP-4788).
+pub const SYSTEM_ADDRESS: Address = H160([
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xfe,
+]);
+
+/// Gas made available to a system call.  It's neither bought nor charged to
+/// the block gas limit.
+pub const SYSTEM_CALL_GAS: u64 = 30_000_000;
+
+/// Address of the beacon roots contract (EIP-4788).
+pub const BEACON_ROOTS_ADDRESS: Address = H160([
+    0x00, 0x0f, 0x3d, 0xf6, 0xd7, 0x32, 0x80, 0x7e, 0xf1, 0x31, 0x9f, 0xb7, 0xb8, 0xbb, 0x85, 0x22,
+    0xd0, 0xbe, 0xac, 0x02,
+]);
+
+/// Length of the ring buffers of the beacon roots contract, one for the
+/// timestamps and one for the roots.
+pub const HISTORY_BUFFER_LENGTH: u64 = 8191;
+
+/// Runtime code of the beacon roots contract.
+const BEACON_ROOTS_CODE: &str =
+    "3373fffffffffffffffffffffffffffffffffffffffe14604d5760203614602457\
+    5f5ffd5b5f35801560495762001fff810690815414603c575f5ffd5b62001fff01545f5260205ff35b5f5ffd5b\
+    62001fff42064281555f359062001fff015500";
+
+/// Return the runtime code of the beacon roots contract.
+pub fn beacon_roots_code() -> Vec<u8> {
+    hex::decode(BEACON_ROOTS_CODE).expect("beacon roots code is valid hex")
+}
+
+/// Address of the history storage contract (EIP-2935).
+pub const HISTORY_STORAGE_ADDRESS: Address = H160([
+    0x00, 0x00, 0xf9, 0x08, 0x27, 0xf1, 0xc5, 0x3a, 0x10, 0xcb, 0x7a, 0x02, 0x33, 0x5b, 0x17, 0x53,
+    0x20, 0x00, 0x29, 0x35,
+]);
+
+/// Number of the most recent block hashes which the history storage contract
+/// serves, in a ring buffer.
+pub const HISTORY_SERVE_WINDOW: u64 = 8191;
+
+/// Runtime code of the history storage contract.
+const HISTORY_STORAGE_CODE: &str =
+    "3373fffffffffffffffffffffffffffffffffffffffe14604657602036036042575f3560014303\
+    8111604257611fff81430311604257611fff9006545f5260205ff35b5f5ffd5b5f35611fff600143030655\
+    00";
+
+/// Return the runtime code of the history storage contract.
+pub fn history_storage_code() -> Vec<u8> {
+    hex::decode(HISTORY_STORAGE_CODE).expect("history storage code is valid hex")
+}
+
+/// Return the storage slot of the history storage contract which holds the
+/// hash of this block.
+pub fn history_storage_slot(number: u64) -> Word {
+    Word::from(number % HISTORY_SERVE_WINDOW)
+}
+
+/// Return the storage slots of the beacon roots contract which the system
+/// call of a block with this timestamp writes: the slot of the timestamp and
+/// the slot of the parent beacon block root.
+pub fn beacon_root_slots(timestamp: u64) -> (Word, Word) {
+    let timestamp_index = timestamp % HISTORY_BUFFER_LENGTH;
+    (
+        Word::from(timestamp_index),
+        Word::from(timestamp_index + HISTORY_BUFFER_LENGTH),
+    )
+}
+
+#[cfg(test)]
+mod system_contracts_tests {
+    use super::*;
+
+    #[test]
+    fn beacon_roots() {
+        assert_eq!(beacon_roots_code().len(), 97);
+        assert_eq!(&beacon_roots_code()[2..22], SYSTEM_ADDRESS.as_bytes());
+        assert_eq!(
+            beacon_root_slots(HISTORY_BUFFER_LENGTH + 5),
+            (Word::from(5), Word::from(HISTORY_BUFFER_LENGTH + 5))
+        );
+    }
+
+    #[test]
+    fn history_storage() {
+        let code = history_storage_code();
+        assert_eq!(code.len(), 83);
+        assert_eq!(&code[2..22], SYSTEM_ADDRESS.as_bytes());
+        // JUMPDEST of the revert and of the set branches
+        assert_eq!((code[0x42], code[0x46]), (0x5b, 0x5b));
+        assert_eq!(
+            history_storage_slot(HISTORY_SERVE_WINDOW + 7),
+            Word::from(7)
+        );
+    }
+}
@@ -1,18 +1,29 @@
 //! Types needed for generating Ethereum traces
 
 use crate::{
-    evm_types::{self, GasCost},
+    ed25519_types::Ed25519SignData,
+    evm_types::{gas_utils::blob_base_fee, GasCost, Hardfork},
     keccak256,
-    sign_types::{biguint_to_32bytes_le, ct_option_ok_or, recover_pk, SignData, SECP256K1_Q},
-    AccessList, Address, Block, Bytecode, Bytes, Error, GethExecTrace, Hash, ToBigEndian,
-    ToLittleEndian, ToWord, Word, U64,
+    rsa_types::{RsaPadding, RsaPublicKey, RsaSignData},
+    sign_types::{
+        biguint_to_32bytes_le, ct_option_ok_or, recover_pk, SignData, SECP256K1_Q, SECP256R1_Q,
+    },
+    AccessList, AccessListItem, Address, Block, Bytecode, Bytes, Error, GethExecTrace, Hash,
+    ToBigEndian, ToLittleEndian, ToWord, Word, H256, U64,
 };
 use ethers_core::{
-    types::{transaction::response, NameOrAddress, TransactionRequest},
-    utils::get_contract_address,
+    types::{transaction::response, NameOrAddress, OtherFields, Signature, TransactionRequest},
+    utils::{get_contract_address, rlp::RlpStream},
 };
 use ethers_signers::{LocalWallet, Signer};
-use halo2_proofs::halo2curves::{group::ff::PrimeField, secp256k1};
+use halo2_proofs::{
+    arithmetic::CurveAffine,
+    halo2curves::{
+        group::ff::PrimeField,
+        secp256k1,
+        secp256r1::{self, Secp256r1Affine},
+    },
+};
 use num::Integer;
 use num_bigint::BigUint;
 use serde::{Serialize, Serializer};
@@ -97,6 +108,10 @@ pub struct BlockConstants {
     pub gas_limit: Word,
     /// base fee
     pub base_fee: Word,
+    /// blob base fee (EIP-4844), derived from the excess blob gas of the block
+    pub blob_base_fee: Word,
+    /// prev_randao (EIP-4399), the mix hash of the block after the merge
+    pub prev_randao: Word,
 }
 
 impl<TX> TryFrom<&Block<TX>> for BlockConstants {
@@ -107,17 +122,62 @@ impl<TX> TryFrom<&Block<TX>> for BlockConstants {
             coinbase: block.author.ok_or(Error::IncompleteBlock)?,
             timestamp: block.timestamp,
             number: block.number.ok_or(Error::IncompleteBlock)?,
-            difficulty: if block.difficulty.is_zero() {
-                block.mix_hash.unwrap_or_default().to_fixed_bytes().into()
-            } else {
-                block.difficulty
-            },
+            difficulty: block.difficulty,
             gas_limit: block.gas_limit,
             base_fee: block.base_fee_per_gas.ok_or(Error::IncompleteBlock)?,
+            blob_base_fee: blob_base_fee(excess_blob_gas(block)?),
+            prev_randao: block.mix_hash.unwrap_or_default().to_fixed_bytes().into(),
         })
     }
 }
 
+/// Return the excess blob gas of a block (EIP-4844), which is 0 before
+/// Cancun.  The field is not part of the `Block` type, so it's read from its
+/// other fields.
+pub fn excess_blob_gas<TX>(block: &Block<TX>) -> Result<u64, Error> {
+    Ok(block
+        .other
+        .get_deserialized::<U64>("excessBlobGas")
+        .transpose()
+        .map_err(Error::SerdeError)?
+        .unwrap_or_default()
+        .as_u64())
+}
+
+/// Return the parent beacon block root of a block (EIP-4788), which is `None`
+/// before Cancun.  The field is not part of the `Block` type, so it's read from
+/// its other fields.
+pub fn parent_beacon_block_root<TX>(block: &Block<TX>) -> Result<Option<H256>, Error> {
+    block
+        .other
+        .get_deserialized::<H256>("parentBeaconBlockRoot")
+        .transpose()
+        .map_err(Error::SerdeError)
+}
+
+/// Return the other fields of a `Block` holding its Cancun header fields,
+/// the excess blob gas (EIP-4844) and the parent beacon block root
+/// (EIP-4788), which are not part of the type.
+pub fn cancun_fields(
+    excess_blob_gas: Option<u64>,
+    parent_beacon_block_root: Option<H256>,
+) -> OtherFields {
+    let mut other = OtherFields::default();
+    if let Some(excess_blob_gas) = excess_blob_gas {
+        other.insert(
+            "excessBlobGas".to_string(),
+            serde_json::to_value(U64::from(excess_blob_gas)).expect("u64 serializes to JSON"),
+        );
+    }
+    if let Some(parent_beacon_block_root) = parent_beacon_block_root {
+        other.insert(
+            "parentBeaconBlockRoot".to_string(),
+            serde_json::to_value(parent_beacon_block_root).expect("H256 serializes to JSON"),
+        );
+    }
+    other
+}
+
 impl BlockConstants {
     /// Generates a new `BlockConstants` instance from it's fields.
     pub fn new(
@@ -127,6 +187,8 @@ impl BlockConstants {
         difficulty: Word,
         gas_limit: Word,
         base_fee: Word,
+        blob_base_fee: Word,
+        prev_randao: Word,
     ) -> BlockConstants {
         BlockConstants {
             coinbase,
@@ -135,6 +197,8 @@ impl BlockConstants {
             difficulty,
             gas_limit,
             base_fee,
+            blob_base_fee,
+            prev_randao,
         }
     }
 }
@@ -153,6 +217,162 @@ pub struct Withdrawal {
     pub amount: u64,
 }
 
+/// EIP-2718 type of the EIP-7702 set-code transactions
+pub const SET_CODE_TX_TYPE: u8 = 0x04;
+/// Prefix of the messages signed by the authorizations of EIP-7702
+pub const SET_CODE_AUTHORIZATION_MAGIC: u8 = 0x05;
+
+/// EIP-7702 authorization of a set-code transaction: the account signing it
+/// (the authority) delegates its code to `address`.
+#[derive(PartialEq, Eq, Debug, Default, Clone, Serialize)]
+pub struct Authorization {
+    /// Chain ID the authorization is valid for, or 0 for all chains
+    pub chain_id: Word,
+    /// Address of the code the authority delegates to
+    pub address: Address,
+    /// Nonce of the authority
+    pub nonce: U64,
+    /// Parity of the y coordinate of the signature point R
+    pub y_parity: u8,
+    /// "r" value of the authorization signature
+    pub r: Word,
+    /// "s" value of the authorization signature
+    pub s: Word,
+}
+
+impl Authorization {
+    /// Return the message signed by the authority:
+    /// 0x05 || rlp([chain_id, address, nonce]).
+    pub fn sign_message(&self) -> Vec<u8> {
+        let mut stream = RlpStream::new_list(3);
+        stream.append(&self.chain_id);
+        stream.append(&self.address);
+        stream.append(&self.nonce);
+        [&[SET_CODE_AUTHORIZATION_MAGIC][..], &stream.out()].concat()
+    }
+
+    /// Return the hash of the message signed by the authority.
+    pub fn sign_hash(&self) -> [u8; 32] {
+        keccak256(&self.sign_message())
+    }
+
+    /// Return the SignData of the authorization, with the public key of the
+    /// authority.
+    pub fn sign_data(&self) -> Result<SignData, Error> {
+        secp256k1_sign_data(self.y_parity, &self.r, &self.s, self.sign_hash())
+    }
+
+    fn rlp_append(&self, stream: &mut RlpStream) {
+        stream.begin_list(6);
+        stream.append(&self.chain_id);
+        stream.append(&self.address);
+        stream.append(&self.nonce);
+        stream.append(&self.y_parity);
+        stream.append(&self.r);
+        stream.append(&self.s);
+    }
+}
+
+/// Return the SignData of the secp256k1 signature (r, s) with recovery id `v`
+/// of `msg_hash`, recovering the public key.
+pub fn secp256k1_sign_data(
+    v: u8,
+    r: &Word,
+    s: &Word,
+    msg_hash: [u8; 32],
+) -> Result<SignData, Error> {
+    let sig_r = ct_option_ok_or(
+        secp256k1::Fq::from_repr(r.to_le_bytes()),
+        Error::Signature(libsecp256k1::Error::InvalidSignature),
+    )?;
+    let sig_s = ct_option_ok_or(
+        secp256k1::Fq::from_repr(s.to_le_bytes()),
+        Error::Signature(libsecp256k1::Error::InvalidSignature),
+    )?;
+    let pk = recover_pk(v, r, s, &msg_hash)?;
+    // msg_hash = msg_hash % q
+    let msg_hash = BigUint::from_bytes_be(msg_hash.as_slice());
+    let msg_hash = msg_hash.mod_floor(&*SECP256K1_Q);
+    let msg_hash_le = biguint_to_32bytes_le(msg_hash);
+    let msg_hash = ct_option_ok_or(
+        secp256k1::Fq::from_repr(msg_hash_le),
+        libsecp256k1::Error::InvalidMessage,
+    )?;
+    Ok(SignData {
+        signature: (sig_r, sig_s),
+        pk,
+        msg_hash,
+    })
+}
+
+/// Return the SignData of the secp256r1 (P-256) signature (r, s) of
+/// `msg_hash` by the public key (x, y), as in the input of the P256VERIFY
+/// precompile.  The coordinates must be those of a point of the curve other
+/// than the identity.
+pub fn secp256r1_sign_data(
+    r: &Word,
+    s: &Word,
+    pk_x: &Word,
+    pk_y: &Word,
+    msg_hash: [u8; 32],
+) -> Result<SignData<Secp256r1Affine>, Error> {
+    let sig_r = ct_option_ok_or(
+        secp256r1::Fq::from_repr(r.to_le_bytes()),
+        Error::Signature(libsecp256k1::Error::InvalidSignature),
+    )?;
+    let sig_s = ct_option_ok_or(
+        secp256r1::Fq::from_repr(s.to_le_bytes()),
+        Error::Signature(libsecp256k1::Error::InvalidSignature),
+    )?;
+    // The identity, encoded as (0, 0), isn't a valid public key.
+    if pk_x.is_zero() && pk_y.is_zero() {
+        return Err(Error::Signature(libsecp256k1::Error::InvalidPublicKey));
+    }
+    let [x, y] = [pk_x, pk_y].map(|coordinate| {
+        ct_option_ok_or(
+            secp256r1::Fp::from_repr(coordinate.to_le_bytes()),
+            Error::Signature(libsecp256k1::Error::InvalidPublicKey),
+        )
+    });
+    let pk = ct_option_ok_or(
+        Secp256r1Affine::from_xy(x?, y?),
+        Error::Signature(libsecp256k1::Error::InvalidPublicKey),
+    )?;
+    // msg_hash = msg_hash % q
+    let msg_hash = BigUint::from_bytes_be(msg_hash.as_slice());
+    let msg_hash = msg_hash.mod_floor(&*SECP256R1_Q);
+    let msg_hash = ct_option_ok_or(
+        secp256r1::Fq::from_repr(biguint_to_32bytes_le(msg_hash)),
+        libsecp256k1::Error::InvalidMessage,
+    )?;
+    Ok(SignData {
+        signature: (sig_r, sig_s),
+        pk,
+        msg_hash,
+    })
+}
+
+/// Return the EIP-712 encoding of typed data, `0x19 || 0x01 ||
+/// domainSeparator || structHash`, whose keccak hash is the signed digest.
+pub fn eip712_encode(domain_separator: &Hash, struct_hash: &[u8; 32]) -> Vec<u8> {
+    [&[0x19, 0x01][..], domain_separator.as_bytes(), struct_hash].concat()
+}
+
+/// EIP-2718 type of the OP-stack deposit transactions
+pub const DEPOSIT_TX_TYPE: u8 = 0x7E;
+
+/// Fields of an OP-stack deposit transaction, which is derived from L1 and has
+/// no signature: the sender is authenticated by the rollup derivation.
+#[derive(PartialEq, Eq, Debug, Default, Clone, Serialize)]
+pub struct Deposit {
+    /// Hash which uniquely identifies the source of the deposit
+    pub source_hash: Hash,
+    /// Ether minted to the sender before the transaction is executed
+    pub mint: Word,
+    /// Whether the transaction is a system transaction
+    pub is_system_tx: bool,
+}
+
 /// Definition of all of the constants related to an Ethereum transaction.
 #[derive(Debug, Default, Clone, Serialize)]
 pub struct Transaction {
@@ -181,8 +401,18 @@ pub struct Transaction {
     pub call_data: Bytes,
     /// Access list
     pub access_list: Option<AccessList>,
+    /// Authorization list of an EIP-7702 set-code transaction, `None` for a
+    /// legacy transaction
+    pub authorization_list: Option<Vec<Authorization>>,
+    /// Versioned hashes of the blobs of an EIP-4844 blob transaction, empty
+    /// for other transactions
+    pub blob_versioned_hashes: Vec<Hash>,
+    /// Fields of an OP-stack deposit transaction, `None` for other
+    /// transactions
+    pub deposit: Option<Deposit>,
 
-    /// "v" value of the transaction signature
+    /// "v" value of the transaction signature, the y parity for a set-code
+    /// transaction
     pub v: u64,
     /// "r" value of the transaction signature
     pub r: Word,
@@ -206,6 +436,8 @@ impl From<&Transaction> for crate::Transaction {
             v: tx.v.into(),
             r: tx.r,
             s: tx.s,
+            transaction_type: tx.deposit.as_ref().map(|_| DEPOSIT_TX_TYPE.into()),
+            other: tx_other_fields(tx),
             ..Default::default()
         }
     }
@@ -224,6 +456,14 @@ impl From<&crate::Transaction> for Transaction {
             gas_fee_cap: tx.max_fee_per_gas.unwrap_or_default(),
             call_data: tx.input.clone(),
             access_list: tx.access_list.clone(),
+            authorization_list: None,
+            blob_versioned_hashes: tx
+                .other
+                .get_deserialized("blobVersionedHashes")
+                .and_then(Result::ok)
+                .unwrap_or_default(),
+            deposit: (tx.transaction_type == Some(DEPOSIT_TX_TYPE.into()))
+                .then(|| deposit_from_fields(&tx.other)),
             v: tx.v.as_u64(),
             r: tx.r,
             s: tx.s,
@@ -231,6 +471,62 @@ impl From<&crate::Transaction> for Transaction {
     }
 }
 
+/// Return the other fields of a `crate::Transaction` holding the versioned
+/// hashes of its blobs, which are not part of the type.
+pub fn blob_versioned_hashes_fields(blob_versioned_hashes: &[Hash]) -> OtherFields {
+    let mut other = OtherFields::default();
+    if !blob_versioned_hashes.is_empty() {
+        other.insert(
+            "blobVersionedHashes".to_string(),
+            serde_json::to_value(blob_versioned_hashes).expect("hashes serialize to JSON"),
+        );
+    }
+    other
+}
+
+/// Return the other fields of a `crate::Transaction` holding the fields of an
+/// OP-stack deposit transaction, which are not part of the type.
+pub fn deposit_fields(deposit: &Deposit) -> OtherFields {
+    let mut other = OtherFields::default();
+    for (key, value) in [
+        ("sourceHash", serde_json::to_value(deposit.source_hash)),
+        ("mint", serde_json::to_value(deposit.mint)),
+        ("isSystemTx", serde_json::to_value(deposit.is_system_tx)),
+    ] {
+        other.insert(
+            key.to_string(),
+            value.expect("deposit fields serialize to JSON"),
+        );
+    }
+    other
+}
+
+/// Return the other fields of the `crate::Transaction` of a transaction.
+fn tx_other_fields(tx: &Transaction) -> OtherFields {
+    let mut other = blob_versioned_hashes_fields(&tx.blob_versioned_hashes);
+    if let Some(deposit) = &tx.deposit {
+        other.extend(deposit_fields(deposit).into_iter());
+    }
+    other
+}
+
+fn deposit_from_fields(other: &OtherFields) -> Deposit {
+    Deposit {
+        source_hash: other
+            .get_deserialized("sourceHash")
+            .and_then(Result::ok)
+            .unwrap_or_default(),
+        mint: other
+            .get_deserialized("mint")
+            .and_then(Result::ok)
+            .unwrap_or_default(),
+        is_system_tx: other
+            .get_deserialized("isSystemTx")
+            .and_then(Result::ok)
+            .unwrap_or_default(),
+    }
+}
+
 impl From<&Transaction> for TransactionRequest {
     fn from(tx: &Transaction) -> TransactionRequest {
         TransactionRequest {
@@ -249,31 +545,67 @@ impl From<&Transaction> for TransactionRequest {
 impl Transaction {
     /// Return the SignData associated with this Transaction.
     pub fn sign_data(&self, chain_id: u64) -> Result<SignData, Error> {
-        let sig_r_le = self.r.to_le_bytes();
-        let sig_s_le = self.s.to_le_bytes();
+        secp256k1_sign_data(
+            self.recovery_id(chain_id)?,
+            &self.r,
+            &self.s,
+            self.sign_hash(chain_id),
+        )
+    }
+
+    /// Return the SignData of this Transaction signed as EIP-712 typed data,
+    /// as done by smart accounts: the signed message is the keccak hash of
+    /// [`eip712_encode`] of `domain_separator` and the struct hash, which is
+    /// the hash returned by `sign_hash`.
+    pub fn eip712_sign_data(
+        &self,
+        chain_id: u64,
+        domain_separator: Hash,
+    ) -> Result<SignData, Error> {
+        let struct_hash = self.sign_hash(chain_id);
+        secp256k1_sign_data(
+            self.recovery_id(chain_id)?,
+            &self.r,
+            &self.s,
+            keccak256(&eip712_encode(&domain_separator, &struct_hash)),
+        )
+    }
+
+    /// Return the recovery id of the secp256k1 signature: the v of a set-code
+    /// transaction is the y parity, and the one of a legacy transaction
+    /// follows EIP-155.
+    fn recovery_id(&self, chain_id: u64) -> Result<u8, Error> {
+        let v = if self.is_set_code() {
+            self.v
+        } else {
+            self.v
+                .checked_sub(35 + chain_id * 2)
+                .ok_or(Error::Signature(libsecp256k1::Error::InvalidSignature))?
+        };
+        Ok(v as u8)
+    }
+
+    /// Return the SignData associated with this Transaction, signed over secp256r1 (P-256) by
+    /// `pk`.  Unlike secp256k1, the public key is not recovered from the signature: P-256
+    /// signers such as smart cards and WebAuthn authenticators register their key.
+    pub fn sign_data_secp256r1(
+        &self,
+        chain_id: u64,
+        pk: Secp256r1Affine,
+    ) -> Result<SignData<Secp256r1Affine>, Error> {
         let sig_r = ct_option_ok_or(
-            secp256k1::Fq::from_repr(sig_r_le),
+            secp256r1::Fq::from_repr(self.r.to_le_bytes()),
             Error::Signature(libsecp256k1::Error::InvalidSignature),
         )?;
         let sig_s = ct_option_ok_or(
-            secp256k1::Fq::from_repr(sig_s_le),
+            secp256r1::Fq::from_repr(self.s.to_le_bytes()),
             Error::Signature(libsecp256k1::Error::InvalidSignature),
         )?;
-        // msg = rlp([nonce, gasPrice, gas, to, value, data, sig_v, r, s])
-        let req: TransactionRequest = self.into();
-        let msg = req.chain_id(chain_id).rlp();
-        let msg_hash: [u8; 32] = keccak256(&msg);
-        let v = self
-            .v
-            .checked_sub(35 + chain_id * 2)
-            .ok_or(Error::Signature(libsecp256k1::Error::InvalidSignature))? as u8;
-        let pk = recover_pk(v, &self.r, &self.s, &msg_hash)?;
         // msg_hash = msg_hash % q
-        let msg_hash = BigUint::from_bytes_be(msg_hash.as_slice());
-        let msg_hash = msg_hash.mod_floor(&*SECP256K1_Q);
-        let msg_hash_le = biguint_to_32bytes_le(msg_hash);
+        let msg_hash = BigUint::from_bytes_be(self.sign_hash(chain_id).as_slice());
+        let msg_hash = msg_hash.mod_floor(&*SECP256R1_Q);
         let msg_hash = ct_option_ok_or(
-            secp256k1::Fq::from_repr(msg_hash_le),
+            secp256r1::Fq::from_repr(biguint_to_32bytes_le(msg_hash)),
             libsecp256k1::Error::InvalidMessage,
         )?;
         Ok(SignData {
@@ -283,6 +615,184 @@ impl Transaction {
         })
     }
 
+    /// Return the RsaSignData of this Transaction signed with RSA-2048 PKCS#1
+    /// v1.5 or PSS: the signed message is the hash returned by `sign_hash`,
+    /// which the signer hashes again with SHA-256.
+    pub fn rsa_sign_data(
+        &self,
+        chain_id: u64,
+        pk: RsaPublicKey,
+        signature: BigUint,
+        padding: RsaPadding,
+    ) -> Result<RsaSignData, Error> {
+        let sign_data = RsaSignData {
+            signature,
+            padding,
+            pk,
+            msg: self.sign_hash(chain_id),
+        };
+        if !sign_data.verify() {
+            return Err(Error::Signature(libsecp256k1::Error::InvalidSignature));
+        }
+        Ok(sign_data)
+    }
+
+    /// Return the Ed25519SignData of this Transaction signed with Ed25519 by
+    /// the encoded public key `pk`: the signed message is the hash returned
+    /// by `sign_hash`.
+    pub fn ed25519_sign_data(
+        &self,
+        chain_id: u64,
+        pk: [u8; 32],
+        signature: [u8; 64],
+    ) -> Result<Ed25519SignData, Error> {
+        let sign_data = Ed25519SignData {
+            signature,
+            pk,
+            msg: self.sign_hash(chain_id),
+        };
+        if !sign_data.verify() {
+            return Err(Error::Signature(libsecp256k1::Error::InvalidSignature));
+        }
+        Ok(sign_data)
+    }
+
+    /// Return the hash of the message signed by the sender:
+    /// keccak(rlp([nonce, gasPrice, gas, to, value, data, chain_id, 0, 0])),
+    /// or for a set-code transaction keccak(0x04 || rlp([chain_id, nonce,
+    /// gasTipCap, gasFeeCap, gas, to, value, data, access_list,
+    /// authorization_list])).
+    pub fn sign_hash(&self, chain_id: u64) -> [u8; 32] {
+        if self.is_set_code() {
+            let stream = self.set_code_rlp_stream(chain_id, 10);
+            return keccak256(&[&[SET_CODE_TX_TYPE][..], &stream.out()].concat());
+        }
+        let req: TransactionRequest = self.into();
+        keccak256(&req.chain_id(chain_id).rlp())
+    }
+
+    /// Return the hash of the signed transaction, which identifies it:
+    /// keccak(rlp([nonce, gasPrice, gas, to, value, data, v, r, s])), or for a
+    /// set-code transaction keccak(0x04 || rlp([chain_id, nonce, gasTipCap,
+    /// gasFeeCap, gas, to, value, data, access_list, authorization_list,
+    /// y_parity, r, s])), or for a deposit transaction keccak(0x7E ||
+    /// rlp([sourceHash, from, to, mint, value, gas, isSystemTx, data])).
+    pub fn hash(&self, chain_id: u64) -> Hash {
+        if let Some(deposit) = &self.deposit {
+            let mut stream = RlpStream::new_list(8);
+            stream.append(&deposit.source_hash);
+            stream.append(&self.from);
+            match self.to {
+                Some(to) => stream.append(&to),
+                None => stream.append_empty_data(),
+            };
+            stream.append(&deposit.mint);
+            stream.append(&self.value);
+            stream.append(&self.gas_limit);
+            stream.append(&(deposit.is_system_tx as u8));
+            stream.append(&self.call_data);
+            return Hash::from(keccak256(&[&[DEPOSIT_TX_TYPE][..], &stream.out()].concat()));
+        }
+        if self.is_set_code() {
+            let mut stream = self.set_code_rlp_stream(chain_id, 13);
+            stream.append(&self.v);
+            stream.append(&self.r);
+            stream.append(&self.s);
+            return Hash::from(keccak256(
+                &[&[SET_CODE_TX_TYPE][..], &stream.out()].concat(),
+            ));
+        }
+        let req: TransactionRequest = self.into();
+        let signature = Signature {
+            r: self.r,
+            s: self.s,
+            v: self.v,
+        };
+        Hash::from(keccak256(&req.chain_id(chain_id).rlp_signed(&signature)))
+    }
+
+    /// Return an RLP list of `len` items, starting with the fields of a set-code
+    /// transaction: [chain_id, nonce, gasTipCap, gasFeeCap, gas, to, value,
+    /// data, access_list, authorization_list].
+    fn set_code_rlp_stream(&self, chain_id: u64, len: usize) -> RlpStream {
+        let authorization_list = self.authorization_list.as_deref().unwrap_or_default();
+        let mut stream = RlpStream::new_list(len);
+        stream.append(&chain_id);
+        stream.append(&self.nonce);
+        stream.append(&self.gas_tip_cap);
+        stream.append(&self.gas_fee_cap);
+        stream.append(&self.gas_limit);
+        stream.append(&self.to_or_zero());
+        stream.append(&self.value);
+        stream.append(&self.call_data);
+        stream.append(&self.access_list.clone().unwrap_or_default());
+        stream.begin_list(authorization_list.len());
+        for authorization in authorization_list {
+            authorization.rlp_append(&mut stream);
+        }
+        stream
+    }
+
+    /// Determine if this transaction is an EIP-7702 set-code transaction
+    pub fn is_set_code(&self) -> bool {
+        self.authorization_list.is_some()
+    }
+
+    /// Return the max fee per gas and the max priority fee per gas of this
+    /// transaction.  Both are the gas price for a legacy transaction.
+    pub fn fee_caps(&self) -> (Word, Word) {
+        if self.is_set_code() {
+            (self.gas_fee_cap, self.gas_tip_cap)
+        } else {
+            (self.gas_price, self.gas_price)
+        }
+    }
+
+    /// Determine if this transaction is an OP-stack deposit transaction
+    pub fn is_deposit(&self) -> bool {
+        self.deposit.is_some()
+    }
+
+    /// Ether minted to the sender before the transaction is executed, which
+    /// is zero but for a deposit transaction
+    pub fn mint(&self) -> Word {
+        self.deposit
+            .as_ref()
+            .map_or_else(Word::zero, |deposit| deposit.mint)
+    }
+
+    /// Return the items of the access list, which is empty for a transaction
+    /// without one
+    pub fn access_list_items(&self) -> &[AccessListItem] {
+        self.access_list
+            .as_ref()
+            .map_or(&[], |access_list| access_list.0.as_slice())
+    }
+
+    /// Return the storage keys of the access list with their address, in the
+    /// order of the access list
+    pub fn access_list_storage_keys(&self) -> impl Iterator<Item = (Address, Hash)> + '_ {
+        self.access_list_items().iter().flat_map(|item| {
+            item.storage_keys
+                .iter()
+                .map(move |storage_key| (item.address, *storage_key))
+        })
+    }
+
+    /// Compute the gas cost of the access list (EIP-2930)
+    pub fn access_list_gas_cost(&self) -> u64 {
+        self.access_list_items().len() as u64 * GasCost::ACCESS_LIST_ADDRESS
+            + self.access_list_storage_keys().count() as u64 * GasCost::ACCESS_LIST_STORAGE_KEY
+    }
+
+    /// Compute the gas cost of the authorization list (EIP-7702)
+    pub fn authorization_list_gas_cost(&self) -> u64 {
+        self.authorization_list
+            .as_ref()
+            .map_or(0, |authorization_list| authorization_list.len() as u64)
+            * GasCost::AUTHORIZATION
+    }
+
     /// Compute call data gas cost from call data
     pub fn call_data_gas_cost(&self) -> u64 {
         self.call_data
@@ -290,15 +800,17 @@ impl Transaction {
             .fold(0, |acc, byte| acc + if *byte == 0 { 4 } else { 16 })
     }
 
-    /// Compute the intrinsic gas cost
-    pub fn intrinsic_gas_cost(&self) -> u64 {
+    /// Compute the intrinsic gas cost in a hardfork
+    pub fn intrinsic_gas_cost(&self, hardfork: Hardfork) -> u64 {
         let is_create = self.is_create() as u64;
         // Calculate gas cost of init code for EIP-3860.
         let init_code_gas_cost =
-            ((self.call_data.len() as u64 + 31) / 32) * evm_types::INIT_CODE_WORD_GAS;
+            ((self.call_data.len() as u64 + 31) / 32) * hardfork.create_gas_per_code_word();
         is_create * (GasCost::CREATION_TX + init_code_gas_cost)
             + (1 - is_create) * GasCost::TX
             + self.call_data_gas_cost()
+            + self.access_list_gas_cost()
+            + self.authorization_list_gas_cost()
     }
 
     /// Get the "to" address. If `to` is None then zero address
@@ -337,6 +849,8 @@ impl Transaction {
             v: U64::from(self.v),
             block_number: Some(block_number),
             chain_id: Some(chain_id),
+            transaction_type: self.deposit.as_ref().map(|_| DEPOSIT_TX_TYPE.into()),
+            other: tx_other_fields(self),
             ..response::Transaction::default()
         }
     }
@@ -16,10 +16,14 @@ pub mod macros;
 pub mod error;
 #[macro_use]
 pub mod bytecode;
+pub mod ed25519_types;
 pub mod evm_types;
 pub mod geth_types;
 pub mod keccak;
+pub mod poseidon;
+pub mod rsa_types;
 pub mod sign_types;
+pub mod user_op_types;
 pub use keccak::{keccak256, Keccak};
 
 pub use bytecode::Bytecode;
@@ -34,7 +38,10 @@ use ethers_core::types;
 pub use ethers_core::{
     abi::ethereum_types::{BigEndianHash, U512},
     types::{
-        transaction::{eip2930::AccessList, response::Transaction},
+        transaction::{
+            eip2930::{AccessList, AccessListItem},
+            response::Transaction,
+        },
         Address, Block, Bytes, Signature, H160, H256, H64, U256, U64,
     },
 };
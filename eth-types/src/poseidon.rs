@@ -0,0 +1,248 @@
+//! Plain Poseidon hash over the scalar field, with a width of 3 elements and
+//! the x^5 S-box.
+//!
+//! The permutation uses the `x5_254_3` parameters of the reference
+//! implementation of the Poseidon paper, which are also the ones of circomlib
+//! for 2 inputs: the round constants and the Cauchy MDS matrix
+//! `1 / (x_i + y_j)` are sampled from the Grain LFSR.
+
+use crate::Field;
+
+/// Number of elements of the state
+pub const WIDTH: usize = 3;
+/// Number of elements absorbed per permutation, the first one of the state
+/// being the capacity.
+pub const RATE: usize = WIDTH - 1;
+/// Number of full rounds, half of them before the partial rounds
+pub const NUM_FULL_ROUNDS: usize = 8;
+/// Number of partial rounds, with the S-box on the first element only
+pub const NUM_PARTIAL_ROUNDS: usize = 57;
+/// Number of rounds of the permutation
+pub const NUM_ROUNDS: usize = NUM_FULL_ROUNDS + NUM_PARTIAL_ROUNDS;
+/// Number of bytes packed in an absorbed element, which keeps it below the
+/// modulus.
+pub const NUM_BYTES_PER_ELEMENT: usize = 16;
+/// Number of bytes absorbed per permutation
+pub const NUM_BYTES_PER_BLOCK: usize = RATE * NUM_BYTES_PER_ELEMENT;
+
+/// Number of bits of the modulus, used to seed the Grain LFSR
+const NUM_FIELD_BITS: usize = 254;
+
+/// The state of the permutation
+pub type State<F> = [F; WIDTH];
+
+/// Return whether round `round` is a full round.
+pub fn is_full_round(round: usize) -> bool {
+    round < NUM_FULL_ROUNDS / 2 || round >= NUM_FULL_ROUNDS / 2 + NUM_PARTIAL_ROUNDS
+}
+
+/// Grain LFSR in self-shrinking mode, as specified by the Poseidon paper.
+struct Grain {
+    state: Vec<bool>,
+}
+
+impl Grain {
+    fn new() -> Self {
+        let mut state = Vec::with_capacity(80);
+        for (value, num_bits) in [
+            // Prime field
+            (1, 2),
+            // x^alpha S-box
+            (0, 4),
+            (NUM_FIELD_BITS, 12),
+            (WIDTH, 12),
+            (NUM_FULL_ROUNDS, 10),
+            (NUM_PARTIAL_ROUNDS, 10),
+        ] {
+            state.extend((0..num_bits).rev().map(|i| (value >> i) & 1 == 1));
+        }
+        state.extend([true; 30]);
+        let mut grain = Self { state };
+        for _ in 0..160 {
+            grain.next_bit();
+        }
+        grain
+    }
+
+    fn next_bit(&mut self) -> bool {
+        let s = &self.state;
+        let bit = s[62] ^ s[51] ^ s[38] ^ s[23] ^ s[13] ^ s[0];
+        self.state.remove(0);
+        self.state.push(bit);
+        bit
+    }
+
+    /// Return the next output bit: pairs of bits are generated, and the
+    /// second bit is output only when the first one is set.
+    fn next_output_bit(&mut self) -> bool {
+        while !self.next_bit() {
+            self.next_bit();
+        }
+        self.next_bit()
+    }
+
+    /// Return the next field element, sampling again the values above the
+    /// modulus.
+    fn next_field_element<F: Field>(&mut self) -> F {
+        loop {
+            let mut repr = [0u8; 32];
+            for i in (0..NUM_FIELD_BITS).rev() {
+                if self.next_output_bit() {
+                    repr[i / 8] |= 1 << (i % 8);
+                }
+            }
+            if let Some(value) = Option::from(F::from_repr(repr)) {
+                return value;
+            }
+        }
+    }
+
+    /// Return the next field element, reducing the values above the modulus.
+    fn next_field_element_reduced<F: Field>(&mut self) -> F {
+        (0..NUM_FIELD_BITS).fold(F::ZERO, |acc, _| {
+            acc.double() + F::from(self.next_output_bit() as u64)
+        })
+    }
+}
+
+/// Parameters of the Poseidon permutation
+#[derive(Clone, Debug)]
+pub struct Poseidon<F> {
+    /// Round constants added to the state at the start of every round
+    pub round_constants: Vec<State<F>>,
+    /// MDS matrix multiplied with the state at the end of every round
+    pub mds: [State<F>; WIDTH],
+}
+
+impl<F: Field> Default for Poseidon<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Field> Poseidon<F> {
+    /// Return the parameters of the permutation.
+    pub fn new() -> Self {
+        let mut grain = Grain::new();
+        let round_constants = (0..NUM_ROUNDS)
+            .map(|_| std::array::from_fn(|_| grain.next_field_element()))
+            .collect();
+        let xs: State<F> = std::array::from_fn(|_| grain.next_field_element_reduced());
+        let ys: State<F> = std::array::from_fn(|_| grain.next_field_element_reduced());
+        let mds =
+            std::array::from_fn(|i| std::array::from_fn(|j| (xs[i] + ys[j]).invert().unwrap()));
+        Self {
+            round_constants,
+            mds,
+        }
+    }
+
+    /// Return the state after the round `round`.
+    pub fn round(&self, round: usize, state: &State<F>) -> State<F> {
+        let sbox = |x: F| x.square().square() * x;
+        let mut sboxed: State<F> =
+            std::array::from_fn(|i| state[i] + self.round_constants[round][i]);
+        for (i, x) in sboxed.iter_mut().enumerate() {
+            if i == 0 || is_full_round(round) {
+                *x = sbox(*x);
+            }
+        }
+        std::array::from_fn(|i| {
+            self.mds[i]
+                .iter()
+                .zip(sboxed.iter())
+                .fold(F::ZERO, |acc, (m, x)| acc + *m * x)
+        })
+    }
+
+    /// Return the state after the permutation.
+    pub fn permute(&self, state: &State<F>) -> State<F> {
+        (0..NUM_ROUNDS).fold(*state, |state, round| self.round(round, &state))
+    }
+}
+
+/// Return the elements absorbed for the bytes of a block padded with zeros:
+/// every `NUM_BYTES_PER_ELEMENT` bytes in big endian.
+pub fn absorbed_elements<F: Field>(block: &[u8]) -> [F; RATE] {
+    std::array::from_fn(|i| {
+        let start = (i * NUM_BYTES_PER_ELEMENT).min(block.len());
+        let end = ((i + 1) * NUM_BYTES_PER_ELEMENT).min(block.len());
+        let value = block[start..end]
+            .iter()
+            .chain(std::iter::repeat(&0))
+            .take(NUM_BYTES_PER_ELEMENT)
+            .fold(0u128, |acc, byte| (acc << 8) | *byte as u128);
+        F::from_u128(value)
+    })
+}
+
+/// Return the number of permutations required to hash `len` bytes: the
+/// empty input takes a block of zeros.
+pub fn num_blocks(len: usize) -> usize {
+    std::cmp::max(1, (len + NUM_BYTES_PER_BLOCK - 1) / NUM_BYTES_PER_BLOCK)
+}
+
+/// Return the initial state of the sponge for an input of `len` bytes.  The
+/// capacity holds the length so that the zero padding is unambiguous.
+pub fn initial_state<F: Field>(len: usize) -> State<F> {
+    let mut state = [F::ZERO; WIDTH];
+    state[0] = F::from(len as u64);
+    state
+}
+
+/// Return the Poseidon hash of the input bytes, the first rate element of
+/// the state after absorbing the last block.
+pub fn poseidon<F: Field>(input: &[u8]) -> F {
+    let poseidon = Poseidon::new();
+    let mut state = initial_state(input.len());
+    for idx in 0..num_blocks(input.len()) {
+        let start = (idx * NUM_BYTES_PER_BLOCK).min(input.len());
+        let end = ((idx + 1) * NUM_BYTES_PER_BLOCK).min(input.len());
+        for (x, element) in state[1..]
+            .iter_mut()
+            .zip(absorbed_elements::<F>(&input[start..end]))
+        {
+            *x += element;
+        }
+        state = poseidon.permute(&state);
+    }
+    state[1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ToScalar, Word};
+    use halo2_proofs::halo2curves::bn256::Fr;
+
+    fn fr(hex: &str) -> Fr {
+        Word::from_str_radix(hex, 16).unwrap().to_scalar().unwrap()
+    }
+
+    #[test]
+    fn poseidon_parameters() {
+        let poseidon = Poseidon::<Fr>::new();
+        assert_eq!(
+            poseidon.round_constants[0][0],
+            fr("0ee9a592ba9a9518d05986d656f40c2114c4993c11bb29938d21d47304cd8e6e")
+        );
+        assert_eq!(
+            poseidon.mds[0][0],
+            fr("109b7f411ba0e4c9b2b70caf5c36a7b194be7c11ad24378bfedb68592ba8118b")
+        );
+    }
+
+    #[test]
+    fn poseidon_permutation_test_vector() {
+        // Test vector of the reference implementation for `x5_254_3`
+        let state = [0, 1, 2].map(Fr::from);
+        assert_eq!(
+            Poseidon::new().permute(&state),
+            [
+                fr("115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a"),
+                fr("0fca49b798923ab0239de1c9e7a4a9a2210312b6a2f616d18b5a87f9b628ae29"),
+                fr("0e7ae82e40091e63cbd4f16a6d16310b3729d4b6e138fcf54110e2867045a30c"),
+            ]
+        );
+    }
+}
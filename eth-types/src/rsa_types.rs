@@ -0,0 +1,279 @@
+//! RSA-2048 PKCS#1 v1.5 and PSS signature types and helper functions, as
+//! produced by smart cards such as the My Number Card.
+
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+
+/// Number of bytes of an RSA-2048 modulus
+pub const RSA_2048_BYTES: usize = 256;
+
+/// Public exponent of the supported RSA keys
+pub const RSA_PUBLIC_EXPONENT: u64 = 65537;
+
+/// DER encoding of the DigestInfo prefix of a SHA-256 digest, as defined in
+/// RFC 8017 section 9.2.
+pub const SHA256_DIGEST_INFO_PREFIX: [u8; 19] = [
+    0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05,
+    0x00, 0x04, 0x20,
+];
+
+/// DER encoding of the DigestInfo prefix of a SHA-1 digest, as defined in
+/// RFC 8017 section 9.2.
+#[cfg(feature = "sha1")]
+pub const SHA1_DIGEST_INFO_PREFIX: [u8; 15] = [
+    0x30, 0x21, 0x30, 0x09, 0x06, 0x05, 0x2b, 0x0e, 0x03, 0x02, 0x1a, 0x05, 0x00, 0x04, 0x14,
+];
+
+/// Number of bytes of the salt of a PSS signature, the length of a SHA-256
+/// digest.
+pub const PSS_SALT_BYTES: usize = 32;
+
+/// Number of bytes of the masked data block of a PSS encoded message:
+/// everything but the hash and the trailer byte.
+pub const PSS_DB_BYTES: usize = RSA_2048_BYTES - 32 - 1;
+
+/// Number of zero bytes at the start of the data block of a PSS encoded
+/// message, followed by 0x01 and the salt.
+pub const PSS_PS_BYTES: usize = PSS_DB_BYTES - PSS_SALT_BYTES - 1;
+
+/// Trailer byte of a PSS encoded message
+pub const PSS_TRAILER: u8 = 0xbc;
+
+/// Return the SHA-256 digest of the input.
+pub fn sha256(input: &[u8]) -> [u8; 32] {
+    Sha256::digest(input).into()
+}
+
+/// Return the SHA-1 digest of the input.
+#[cfg(feature = "sha1")]
+pub fn sha1(input: &[u8]) -> [u8; 20] {
+    use sha1::Sha1;
+    Sha1::digest(input).into()
+}
+
+/// RSA-2048 public key with the public exponent `RSA_PUBLIC_EXPONENT`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RsaPublicKey {
+    /// Modulus
+    pub n: BigUint,
+}
+
+impl RsaPublicKey {
+    /// Return the modulus in big endian bytes, left padded to
+    /// `RSA_2048_BYTES`.
+    pub fn n_be(&self) -> [u8; RSA_2048_BYTES] {
+        biguint_to_rsa_bytes_be(&self.n)
+    }
+}
+
+/// RSA-2048 private key with the public exponent `RSA_PUBLIC_EXPONENT`
+#[derive(Clone, Debug)]
+pub struct RsaPrivateKey {
+    /// Public key
+    pub pk: RsaPublicKey,
+    /// Private exponent
+    pub d: BigUint,
+}
+
+impl RsaPrivateKey {
+    /// Parse a private key from its hexadecimal modulus and private exponent.
+    pub fn from_hex(n: &str, d: &str) -> Self {
+        let parse = |hex| BigUint::parse_bytes(hex, 16).expect("invalid hex");
+        Self {
+            pk: RsaPublicKey {
+                n: parse(n.as_bytes()),
+            },
+            d: parse(d.as_bytes()),
+        }
+    }
+
+    /// Sign a message with PKCS#1 v1.5 and SHA-256.
+    pub fn sign(&self, msg: &[u8]) -> BigUint {
+        emsa_pkcs1_v15_sha256(&sha256(msg)).modpow(&self.d, &self.pk.n)
+    }
+
+    /// Sign a message with PKCS#1 v1.5 and SHA-1.
+    #[cfg(feature = "sha1")]
+    pub fn sign_sha1(&self, msg: &[u8]) -> BigUint {
+        emsa_pkcs1_v15_sha1(&sha1(msg)).modpow(&self.d, &self.pk.n)
+    }
+
+    /// Sign a message with PSS, SHA-256 and MGF1-SHA-256, with the given
+    /// salt.
+    pub fn sign_pss(&self, msg: &[u8], salt: &[u8; PSS_SALT_BYTES]) -> BigUint {
+        emsa_pss_sha256(&sha256(msg), salt).modpow(&self.d, &self.pk.n)
+    }
+}
+
+/// Padding scheme of an RSA signature
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RsaPadding {
+    /// RSASSA-PKCS1-v1_5 with SHA-256
+    #[default]
+    Pkcs1v15,
+    /// RSASSA-PSS with SHA-256, MGF1-SHA-256 and a salt of `PSS_SALT_BYTES`
+    /// bytes
+    Pss,
+}
+
+/// Signature data required by the RsaSignVerifyChip as input to verify a
+/// PKCS#1 v1.5 or PSS signature with SHA-256.
+#[derive(Clone, Debug)]
+pub struct RsaSignData {
+    /// Signature
+    pub signature: BigUint,
+    /// Padding scheme of the signature
+    pub padding: RsaPadding,
+    /// Public key
+    pub pk: RsaPublicKey,
+    /// Message that is being signed, which is hashed with SHA-256 by the
+    /// signer
+    pub msg: [u8; 32],
+}
+
+impl RsaSignData {
+    /// Return the SHA-256 digest of the message.
+    pub fn digest(&self) -> [u8; 32] {
+        sha256(&self.msg)
+    }
+
+    /// Return the encoded message `signature^e mod n`.
+    pub fn encoded_message(&self) -> BigUint {
+        self.signature
+            .modpow(&BigUint::from(RSA_PUBLIC_EXPONENT), &self.pk.n)
+    }
+
+    /// Return true if the encoded message is the EMSA-PKCS1-v1_5 or the
+    /// EMSA-PSS encoding of the digest of the message, depending on the
+    /// padding.
+    pub fn verify(&self) -> bool {
+        if self.signature >= self.pk.n {
+            return false;
+        }
+        let em = self.encoded_message();
+        match self.padding {
+            RsaPadding::Pkcs1v15 => em == emsa_pkcs1_v15_sha256(&self.digest()),
+            RsaPadding::Pss => pss_salt(&em)
+                .map(|salt| em == emsa_pss_sha256(&self.digest(), &salt))
+                .unwrap_or(false),
+        }
+    }
+
+    /// Return the inputs hashed with SHA-256 to verify the signature: the
+    /// message, and for PSS the MGF1 seeds `H || counter` and
+    /// `M' = 0x00 * 8 || digest || salt`.
+    pub fn sha256_inputs(&self) -> Vec<Vec<u8>> {
+        let mut inputs = vec![self.msg.to_vec()];
+        if self.padding == RsaPadding::Pss {
+            let em = biguint_to_rsa_bytes_be(&self.encoded_message());
+            let hash = &em[PSS_DB_BYTES..RSA_2048_BYTES - 1];
+            inputs.extend(mgf1_sha256_inputs(hash, PSS_DB_BYTES));
+            let salt = pss_salt(&BigUint::from_bytes_be(&em)).unwrap_or_default();
+            inputs.push(pss_message(&self.digest(), &salt));
+        }
+        inputs
+    }
+}
+
+/// Return the seeds `seed || counter` hashed by MGF1-SHA-256 to generate a
+/// mask of `len` bytes, where the counter is 4 bytes in big endian.
+pub fn mgf1_sha256_inputs(seed: &[u8], len: usize) -> Vec<Vec<u8>> {
+    (0..((len + 31) / 32) as u32)
+        .map(|counter| [seed, &counter.to_be_bytes()].concat())
+        .collect()
+}
+
+/// Return the mask of `len` bytes generated by MGF1-SHA-256 from a seed.
+pub fn mgf1_sha256(seed: &[u8], len: usize) -> Vec<u8> {
+    let mut mask: Vec<u8> = mgf1_sha256_inputs(seed, len)
+        .iter()
+        .flat_map(|input| sha256(input))
+        .collect();
+    mask.truncate(len);
+    mask
+}
+
+/// Return the message `M' = 0x00 * 8 || digest || salt` whose SHA-256 hash is
+/// in a PSS encoded message.
+fn pss_message(digest: &[u8; 32], salt: &[u8; PSS_SALT_BYTES]) -> Vec<u8> {
+    [&[0u8; 8][..], digest, salt].concat()
+}
+
+/// Return the EMSA-PSS encoding of a SHA-256 digest for an RSA-2048 modulus,
+/// with MGF1-SHA-256: maskedDB || H || 0xbc where H = sha256(M') and maskedDB
+/// is the data block 0x00..0x00 || 0x01 || salt masked with MGF1(H).  The
+/// encoded message has 2047 bits, so the top bit of maskedDB is cleared.
+pub fn emsa_pss_sha256(digest: &[u8; 32], salt: &[u8; PSS_SALT_BYTES]) -> BigUint {
+    let hash = sha256(&pss_message(digest, salt));
+    let mut em = [0u8; RSA_2048_BYTES];
+    em[PSS_PS_BYTES] = 0x01;
+    em[PSS_PS_BYTES + 1..PSS_DB_BYTES].copy_from_slice(salt);
+    for (byte, mask) in em.iter_mut().zip(mgf1_sha256(&hash, PSS_DB_BYTES)) {
+        *byte ^= mask;
+    }
+    em[0] &= 0x7f;
+    em[PSS_DB_BYTES..RSA_2048_BYTES - 1].copy_from_slice(&hash);
+    em[RSA_2048_BYTES - 1] = PSS_TRAILER;
+    BigUint::from_bytes_be(&em)
+}
+
+/// Return the salt of a PSS encoded message, or None if the encoded message
+/// is malformed.  The hash of the encoded message is not checked.
+pub fn pss_salt(em: &BigUint) -> Option<[u8; PSS_SALT_BYTES]> {
+    if em.bits() > 8 * RSA_2048_BYTES as u64 - 1 {
+        return None;
+    }
+    let em = biguint_to_rsa_bytes_be(em);
+    if em[RSA_2048_BYTES - 1] != PSS_TRAILER {
+        return None;
+    }
+    let mut db = em[..PSS_DB_BYTES].to_vec();
+    for (byte, mask) in db.iter_mut().zip(mgf1_sha256(
+        &em[PSS_DB_BYTES..RSA_2048_BYTES - 1],
+        PSS_DB_BYTES,
+    )) {
+        *byte ^= mask;
+    }
+    db[0] &= 0x7f;
+    if db[..PSS_PS_BYTES].iter().any(|byte| *byte != 0) || db[PSS_PS_BYTES] != 0x01 {
+        return None;
+    }
+    db[PSS_PS_BYTES + 1..].try_into().ok()
+}
+
+/// Return the EMSA-PKCS1-v1_5 encoding of a SHA-256 digest for an RSA-2048
+/// modulus: 0x00 || 0x01 || 0xff..0xff || 0x00 || DigestInfo prefix || digest.
+pub fn emsa_pkcs1_v15_sha256(digest: &[u8; 32]) -> BigUint {
+    emsa_pkcs1_v15(&SHA256_DIGEST_INFO_PREFIX, digest)
+}
+
+/// Return the EMSA-PKCS1-v1_5 encoding of a SHA-1 digest for an RSA-2048
+/// modulus: 0x00 || 0x01 || 0xff..0xff || 0x00 || DigestInfo prefix || digest.
+#[cfg(feature = "sha1")]
+pub fn emsa_pkcs1_v15_sha1(digest: &[u8; 20]) -> BigUint {
+    emsa_pkcs1_v15(&SHA1_DIGEST_INFO_PREFIX, digest)
+}
+
+fn emsa_pkcs1_v15(digest_info_prefix: &[u8], digest: &[u8]) -> BigUint {
+    let mut em = [0xffu8; RSA_2048_BYTES];
+    em[0] = 0x00;
+    em[1] = 0x01;
+    let digest_info_start = RSA_2048_BYTES - digest.len() - digest_info_prefix.len();
+    em[digest_info_start - 1] = 0x00;
+    em[digest_info_start..RSA_2048_BYTES - digest.len()].copy_from_slice(digest_info_prefix);
+    em[RSA_2048_BYTES - digest.len()..].copy_from_slice(digest);
+    BigUint::from_bytes_be(&em)
+}
+
+/// Convert a `BigUint` of at most 2048 bits into `RSA_2048_BYTES` bytes in
+/// big endian.
+pub fn biguint_to_rsa_bytes_be(v: &BigUint) -> [u8; RSA_2048_BYTES] {
+    let v_be = v.to_bytes_be();
+    assert!(
+        v_be.len() <= RSA_2048_BYTES,
+        "value is larger than 2048 bits"
+    );
+    let mut res = [0u8; RSA_2048_BYTES];
+    res[RSA_2048_BYTES - v_be.len()..].copy_from_slice(&v_be);
+    res
+}
@@ -1,14 +1,12 @@
-//! secp256k1 signature types and helper functions.
+//! ECDSA signature types and helper functions for secp256k1 and secp256r1.
 
 use crate::{ToBigEndian, Word};
 use halo2_proofs::{
     arithmetic::{CurveAffine, Field},
     halo2curves::{
-        group::{
-            ff::{FromUniformBytes, PrimeField},
-            Curve,
-        },
+        group::{ff::PrimeField, Curve},
         secp256k1::{self, Secp256k1Affine},
+        secp256r1::{self, Secp256r1Affine},
         Coordinates,
     },
 };
@@ -22,62 +20,111 @@ pub fn sign(
     sk: secp256k1::Fq,
     msg_hash: secp256k1::Fq,
 ) -> (secp256k1::Fq, secp256k1::Fq) {
+    ecdsa_sign::<Secp256k1Affine>(randomness, sk, msg_hash)
+}
+
+/// Do a secp256r1 (P-256) signature with a given randomness value.
+pub fn sign_secp256r1(
+    randomness: secp256r1::Fq,
+    sk: secp256r1::Fq,
+    msg_hash: secp256r1::Fq,
+) -> (secp256r1::Fq, secp256r1::Fq) {
+    ecdsa_sign::<Secp256r1Affine>(randomness, sk, msg_hash)
+}
+
+fn ecdsa_sign<C: CurveAffine>(
+    randomness: C::ScalarExt,
+    sk: C::ScalarExt,
+    msg_hash: C::ScalarExt,
+) -> (C::ScalarExt, C::ScalarExt) {
     let randomness_inv =
-        Option::<secp256k1::Fq>::from(randomness.invert()).expect("cannot invert randomness");
-    let generator = Secp256k1Affine::generator();
+        Option::<C::ScalarExt>::from(randomness.invert()).expect("cannot invert randomness");
+    let generator = C::generator();
     let sig_point = generator * randomness;
     let x = *Option::<Coordinates<_>>::from(sig_point.to_affine().coordinates())
         .expect("point is the identity")
         .x();
 
-    let x_repr = &mut vec![0u8; 32];
-    x_repr.copy_from_slice(x.to_bytes().as_slice());
-
-    let mut x_bytes = [0u8; 64];
-    x_bytes[..32].copy_from_slice(&x_repr[..]);
-
-    let sig_r = secp256k1::Fq::from_uniform_bytes(&x_bytes); // get x coordinate (E::Base) on E::Scalar
+    // get x coordinate (E::Base) on E::Scalar
+    let x = BigUint::from_bytes_le(x.to_repr().as_ref()) % field_modulus::<C::ScalarExt>();
+    let mut sig_r = <C::ScalarExt as PrimeField>::Repr::default();
+    sig_r
+        .as_mut()
+        .copy_from_slice(&biguint_to_32bytes_le(x)[..]);
+    let sig_r = Option::<C::ScalarExt>::from(C::ScalarExt::from_repr(sig_r))
+        .expect("x mod n is a valid scalar");
     let sig_s = randomness_inv * (msg_hash + sig_r * sk);
     (sig_r, sig_s)
 }
 
+/// Return the modulus of a prime field.
+fn field_modulus<F: PrimeField>() -> BigUint {
+    BigUint::from_bytes_le((F::ZERO - F::ONE).to_repr().as_ref()) + 1u64
+}
+
 /// Signature data required by the SignVerify Chip as input to verify a
-/// signature.
+/// signature over the curve `C` (secp256k1 by default).
 #[derive(Clone, Debug)]
-pub struct SignData {
-    /// Secp256k1 signature point
-    pub signature: (secp256k1::Fq, secp256k1::Fq),
-    /// Secp256k1 public key
-    pub pk: Secp256k1Affine,
+pub struct SignData<C: CurveAffine = Secp256k1Affine> {
+    /// Signature (r, s)
+    pub signature: (C::ScalarExt, C::ScalarExt),
+    /// Public key
+    pub pk: C,
     /// Hash of the message that is being signed
-    pub msg_hash: secp256k1::Fq,
+    pub msg_hash: C::ScalarExt,
 }
 
-lazy_static! {
-    static ref SIGN_DATA_DEFAULT: SignData = {
-        let generator = Secp256k1Affine::generator();
-        let sk = secp256k1::Fq::ONE;
+impl<C: CurveAffine> SignData<C> {
+    /// Hardcoded valid signature corresponding to a hardcoded private key and
+    /// message hash generated from "nothing up my sleeve" values to make the
+    /// ECDSA chip pass the constraints, to be use for padding signature
+    /// verifications (where the constraints pass, but we don't care about the
+    /// message hash and public key).
+    pub fn padding() -> Self {
+        let generator = C::generator();
+        let sk = C::ScalarExt::ONE;
         let pk = generator * sk;
         let pk = pk.to_affine();
-        let msg_hash = secp256k1::Fq::ONE;
-        let randomness = secp256k1::Fq::ONE;
-        let (sig_r, sig_s) = sign(randomness, sk, msg_hash);
+        let msg_hash = C::ScalarExt::ONE;
+        let randomness = C::ScalarExt::ONE;
+        let (sig_r, sig_s) = ecdsa_sign::<C>(randomness, sk, msg_hash);
 
         SignData {
             signature: (sig_r, sig_s),
             pk,
             msg_hash,
         }
-    };
+    }
+
+    /// Return whether the signature of the message hash is valid for the
+    /// public key: `r` and `s` are non-zero and `r` is the x coordinate, modulo
+    /// the order of the curve, of `(msg_hash * G + r * pk) / s`.
+    pub fn verify(&self) -> bool {
+        let (sig_r, sig_s) = self.signature;
+        let Some(sig_s_inv) = Option::<C::ScalarExt>::from(sig_s.invert()) else {
+            return false;
+        };
+        if bool::from(sig_r.is_zero()) || bool::from(self.pk.is_identity()) {
+            return false;
+        }
+        let point = (C::generator() * (self.msg_hash * sig_s_inv) + self.pk * (sig_r * sig_s_inv))
+            .to_affine();
+        let Some(coordinates) = Option::<Coordinates<_>>::from(point.coordinates()) else {
+            return false;
+        };
+        let x = BigUint::from_bytes_le(coordinates.x().to_repr().as_ref())
+            % field_modulus::<C::ScalarExt>();
+        biguint_to_32bytes_le(x)[..] == *sig_r.to_repr().as_ref()
+    }
+}
+
+lazy_static! {
+    static ref SIGN_DATA_DEFAULT: SignData = SignData::padding();
 }
 
 impl Default for SignData {
     fn default() -> Self {
-        // Hardcoded valid signature corresponding to a hardcoded private key and
-        // message hash generated from "nothing up my sleeve" values to make the
-        // ECDSA chip pass the constraints, to be use for padding signature
-        // verifications (where the constraints pass, but we don't care about the
-        // message hash and public key).
+        // The secp256k1 padding signature, computed once.
         SIGN_DATA_DEFAULT.clone()
     }
 }
@@ -123,7 +170,10 @@ pub fn recover_pk(
 lazy_static! {
     /// Secp256k1 Curve Scalar.  Referece: Section 2.4.1 (parameter `n`) in "SEC 2: Recommended
     /// Elliptic Curve Domain Parameters" document at http://www.secg.org/sec2-v2.pdf
-    pub static ref SECP256K1_Q: BigUint = BigUint::from_bytes_le(&(secp256k1::Fq::ZERO - secp256k1::Fq::ONE).to_repr()) + 1u64;
+    pub static ref SECP256K1_Q: BigUint = field_modulus::<secp256k1::Fq>();
+    /// Secp256r1 Curve Scalar.  Referece: Section 2.4.2 (parameter `n`) in "SEC 2: Recommended
+    /// Elliptic Curve Domain Parameters" document at http://www.secg.org/sec2-v2.pdf
+    pub static ref SECP256R1_Q: BigUint = field_modulus::<secp256r1::Fq>();
 }
 
 /// Helper function to convert a `CtOption` into an `Result`.  Similar to
@@ -143,11 +193,11 @@ pub fn pk_bytes_swap_endianness<T: Clone>(pk: &[T]) -> [T; 64] {
     pk_swap
 }
 
-/// Return the secp256k1 public key (x, y) coordinates in little endian bytes.
-pub fn pk_bytes_le(pk: &Secp256k1Affine) -> [u8; 64] {
+/// Return the public key (x, y) coordinates in little endian bytes.
+pub fn pk_bytes_le<C: CurveAffine>(pk: &C) -> [u8; 64] {
     let pk_coord = Option::<Coordinates<_>>::from(pk.coordinates()).expect("point is the identity");
     let mut pk_le = [0u8; 64];
-    pk_le[..32].copy_from_slice(&pk_coord.x().to_bytes());
-    pk_le[32..].copy_from_slice(&pk_coord.y().to_bytes());
+    pk_le[..32].copy_from_slice(pk_coord.x().to_repr().as_ref());
+    pk_le[32..].copy_from_slice(pk_coord.y().to_repr().as_ref());
     pk_le
 }
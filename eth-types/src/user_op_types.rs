@@ -0,0 +1,145 @@
+//! ERC-4337 UserOperation types and helper functions, as used by the
+//! MynaWallet accounts.
+
+use crate::{
+    keccak256,
+    rsa_types::{RsaPadding, RsaPublicKey, RsaSignData},
+    Address, Bytes, Error, Word, U256,
+};
+use num_bigint::BigUint;
+
+/// Number of 32 bytes words of the packed encoding of a UserOperation
+pub const USER_OP_PACKED_WORDS: usize = 10;
+
+/// ERC-4337 UserOperation, as handled by the EntryPoint v0.6
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UserOperation {
+    /// Account making the operation
+    pub sender: Address,
+    /// Anti-replay nonce of the account
+    pub nonce: Word,
+    /// Factory address and calldata, when the account is not deployed yet
+    pub init_code: Bytes,
+    /// Calldata of the call to the account
+    pub call_data: Bytes,
+    /// Gas limit of the call to the account
+    pub call_gas_limit: Word,
+    /// Gas limit of the verification
+    pub verification_gas_limit: Word,
+    /// Gas paid to the bundler for the pre-verification execution and calldata
+    pub pre_verification_gas: Word,
+    /// Max fee per gas, as in EIP-1559
+    pub max_fee_per_gas: Word,
+    /// Max priority fee per gas, as in EIP-1559
+    pub max_priority_fee_per_gas: Word,
+    /// Paymaster address and data, when the operation is sponsored
+    pub paymaster_and_data: Bytes,
+    /// Signature of the owner of the account
+    pub signature: Bytes,
+}
+
+impl UserOperation {
+    /// Return the words of the packed encoding, in order: sender, nonce,
+    /// keccak(initCode), keccak(callData), callGasLimit,
+    /// verificationGasLimit, preVerificationGas, maxFeePerGas,
+    /// maxPriorityFeePerGas and keccak(paymasterAndData).
+    pub fn packed_words(&self) -> [Word; USER_OP_PACKED_WORDS] {
+        let hash = |bytes: &Bytes| U256::from_big_endian(&keccak256(bytes));
+        [
+            U256::from_big_endian(self.sender.as_bytes()),
+            self.nonce,
+            hash(&self.init_code),
+            hash(&self.call_data),
+            self.call_gas_limit,
+            self.verification_gas_limit,
+            self.pre_verification_gas,
+            self.max_fee_per_gas,
+            self.max_priority_fee_per_gas,
+            hash(&self.paymaster_and_data),
+        ]
+    }
+
+    /// Return the packed encoding `abi.encode(packed_words)`, which is
+    /// signed without the signature.
+    pub fn pack(&self) -> Vec<u8> {
+        encode_words(&self.packed_words())
+    }
+
+    /// Return the input of the userOpHash:
+    /// `abi.encode(keccak(pack), entry_point, chain_id)`.
+    pub fn hash_input(&self, entry_point: Address, chain_id: u64) -> Vec<u8> {
+        encode_words(&[
+            U256::from_big_endian(&keccak256(&self.pack())),
+            U256::from_big_endian(entry_point.as_bytes()),
+            U256::from(chain_id),
+        ])
+    }
+
+    /// Return the userOpHash, the hash of the operation signed by the owner
+    /// of the account.
+    pub fn hash(&self, entry_point: Address, chain_id: u64) -> [u8; 32] {
+        keccak256(&self.hash_input(entry_point, chain_id))
+    }
+
+    /// Return the RsaSignData of this UserOperation signed with RSA-2048 by
+    /// `pk`: the signed message is the userOpHash, which the signer hashes
+    /// again with SHA-256, and the signature is the big endian signature
+    /// field.
+    pub fn rsa_sign_data(
+        &self,
+        entry_point: Address,
+        chain_id: u64,
+        pk: RsaPublicKey,
+        padding: RsaPadding,
+    ) -> Result<RsaSignData, Error> {
+        let sign_data = RsaSignData {
+            signature: BigUint::from_bytes_be(&self.signature),
+            padding,
+            pk,
+            msg: self.hash(entry_point, chain_id),
+        };
+        if !sign_data.verify() {
+            return Err(Error::Signature(libsecp256k1::Error::InvalidSignature));
+        }
+        Ok(sign_data)
+    }
+}
+
+/// Return the ABI encoding of static 32 bytes words.
+fn encode_words(words: &[Word]) -> Vec<u8> {
+    words
+        .iter()
+        .flat_map(|word| {
+            let mut bytes = [0; 32];
+            word.to_big_endian(&mut bytes);
+            bytes
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address;
+
+    #[test]
+    fn user_op_pack() {
+        let user_op = UserOperation {
+            sender: address!("0x00000000000000000000000000000000deadbeef"),
+            nonce: Word::from(7),
+            ..Default::default()
+        };
+        let packed = user_op.pack();
+        assert_eq!(packed.len(), 32 * USER_OP_PACKED_WORDS);
+        assert_eq!(packed[28..32], [0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(packed[63], 7);
+        // keccak of the empty initCode
+        assert_eq!(packed[64..96], keccak256(&[]));
+
+        let hash_input = user_op.hash_input(Address::repeat_byte(0x11), 1);
+        assert_eq!(hash_input.len(), 96);
+        assert_eq!(hash_input[..32], keccak256(&packed));
+        assert_eq!(hash_input[44..64], [0x11; 20]);
+        assert_eq!(hash_input[95], 1);
+    }
+}
@@ -89,6 +89,7 @@ type Block struct {
 	Difficulty *hexutil.Big   `json:"difficulty"`
 	GasLimit   *hexutil.Big   `json:"gas_limit"`
 	BaseFee    *hexutil.Big   `json:"base_fee"`
+	PrevRandao *hexutil.Big   `json:"prev_randao"`
 }
 
 type Account struct {
@@ -192,8 +193,7 @@ func Trace(config TraceConfig) ([]*ExecutionResult, error) {
 	}
 
 	// For opcode PREVRANDAO
-	// Difficulty is one of MixHash or Difficulty.
-	randao := common.BigToHash(toBigInt(config.Block.Difficulty))
+	randao := common.BigToHash(toBigInt(config.Block.PrevRandao))
 
 	blockCtx := vm.BlockContext{
 		CanTransfer: core.CanTransfer,
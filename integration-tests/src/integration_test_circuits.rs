@@ -1,9 +1,10 @@
 use crate::{get_client, GenDataOutput};
 use bus_mapping::{
-    circuit_input_builder::{BuilderClient, CircuitInputBuilder, FixedCParams},
+    circuit_input_builder::{BuilderClient, CircuitInputBuilder, CodeHashScheme, FixedCParams},
     mock::BlockData,
+    precompile::MODEXP_SIZE_LIMIT,
 };
-use eth_types::geth_types::GethData;
+use eth_types::{evm_types::Hardfork, geth_types::GethData};
 use halo2_proofs::{
     self,
     circuit::Value,
@@ -70,13 +71,30 @@ const MAX_KECCAK_ROWS: usize = 38000;
 const CIRCUITS_PARAMS: FixedCParams = FixedCParams {
     max_rws: MAX_RWS,
     max_txs: MAX_TXS,
+    max_signed_txs: MAX_TXS,
     max_withdrawals: MAX_WITHDRAWALS,
     max_calldata: MAX_CALLDATA,
+    max_access_list: 0,
+    max_auths: 0,
     max_bytecode: MAX_BYTECODE,
     max_copy_rows: MAX_COPY_ROWS,
     max_evm_rows: MAX_EVM_ROWS,
     max_exp_steps: MAX_EXP_STEPS,
     max_keccak_rows: MAX_KECCAK_ROWS,
+    max_poseidon_blocks: 0,
+    max_sha256_blocks: 0,
+    max_ripemd160_blocks: 0,
+    max_blake2f_blocks: 0,
+    max_sig_verifs: 0,
+    max_modexps: 0,
+    max_modexp_size: MODEXP_SIZE_LIMIT,
+    max_ec_adds: 0,
+    max_ec_muls: 0,
+    max_p256_verifs: 0,
+    hardfork: Hardfork::Cancun,
+    code_hash_scheme: CodeHashScheme::Keccak,
+    deposits: false,
+    p256_verify: false,
 };
 
 const EVM_CIRCUIT_DEGREE: u32 = 18;
@@ -1,8 +1,12 @@
 #![cfg(feature = "circuit_input_builder")]
 
-use bus_mapping::circuit_input_builder::{
-    build_state_code_db, get_state_accesses, BuilderClient, FixedCParams,
+use bus_mapping::{
+    circuit_input_builder::{
+        build_state_code_db, get_state_accesses, BuilderClient, CodeHashScheme, FixedCParams,
+    },
+    precompile::MODEXP_SIZE_LIMIT,
 };
+use eth_types::evm_types::Hardfork;
 use integration_tests::{get_client, log_init, GenDataOutput};
 use lazy_static::lazy_static;
 use log::trace;
@@ -18,13 +22,30 @@ async fn test_circuit_input_builder_block(block_num: u64) {
         FixedCParams {
             max_rws: 16384,
             max_txs: 1,
+            max_signed_txs: 1,
             max_withdrawals: 1,
             max_calldata: 4000,
+            max_access_list: 0,
+            max_auths: 0,
             max_bytecode: 4000,
             max_copy_rows: 16384,
             max_evm_rows: 0,
             max_exp_steps: 1000,
             max_keccak_rows: 0,
+            max_poseidon_blocks: 0,
+            max_sha256_blocks: 0,
+            max_ripemd160_blocks: 0,
+            max_blake2f_blocks: 0,
+            max_sig_verifs: 0,
+            max_modexps: 0,
+            max_modexp_size: MODEXP_SIZE_LIMIT,
+            max_ec_adds: 0,
+            max_ec_muls: 0,
+            max_p256_verifs: 0,
+            hardfork: Hardfork::default(),
+            code_hash_scheme: CodeHashScheme::Keccak,
+            deposits: false,
+            p256_verify: false,
         },
     )
     .await
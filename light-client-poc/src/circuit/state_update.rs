@@ -19,9 +19,9 @@ use halo2_proofs::{
 };
 
 use zkevm_circuits::{
-    mpt_circuit::{MPTCircuit, MPTCircuitParams, MPTConfig},
+    mpt_circuit::{MPTCircuit, MPTCircuitConfigArgs, MPTCircuitParams, MPTConfig},
     table::{KeccakTable, MptTable},
-    util::{word, Challenges},
+    util::{word, Challenges, SubCircuitConfig},
 };
 
 use super::witness::{
@@ -30,8 +30,10 @@ use super::witness::{
 
 #[cfg(not(feature = "disable-keccak"))]
 use zkevm_circuits::{
-    keccak_circuit::{KeccakCircuit, KeccakCircuitConfig, KeccakCircuitConfigArgs},
-    util::{SubCircuit, SubCircuitConfig},
+    keccak_circuit::{
+        KeccakCircuit, KeccakCircuitConfig, KeccakCircuitConfigArgs, KeccakConfigParams,
+    },
+    util::SubCircuit,
 };
 
 pub const DEFAULT_MAX_PROOF_COUNT: usize = 20;
@@ -105,9 +107,18 @@ impl<F: Field> Circuit<F> for StateUpdateCircuit<F> {
             KeccakCircuitConfigArgs {
                 keccak_table: keccak_table.clone(),
                 challenges: challenges_expr.clone(),
+                parameters: KeccakConfigParams::default(),
+            },
+        );
+        let mpt_config = MPTConfig::new(
+            meta,
+            MPTCircuitConfigArgs {
+                mpt_table: MptTable::construct(meta),
+                hash_table: keccak_table,
+                challenges: challenges_expr,
+                params,
             },
         );
-        let mpt_config = MPTConfig::new(meta, challenges_expr, keccak_table, params);
 
         let is_first = meta.fixed_column();
         let count = meta.advice_column();
@@ -311,7 +322,7 @@ impl<F: Field> Circuit<F> for StateUpdateCircuit<F> {
             .load_mult_table(&mut layouter, &challenges, height)?;
 
         #[cfg(feature = "disable-keccak")]
-        config.mpt_config.keccak_table.dev_load(
+        config.mpt_config.hash_table.dev_load(
             &mut layouter,
             &self.mpt_circuit.keccak_data,
             &challenges,
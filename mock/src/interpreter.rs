@@ -0,0 +1,310 @@
+//! Interpreter of straight-line code, which traces the opcodes that the
+//! go-ethereum version pinned by geth-utils doesn't support yet
+
+use eth_types::{
+    evm_types::{
+        gas_utils::{
+            eip150_gas, memory_copier_gas_cost, memory_expansion_gas_cost, memory_word_size,
+        },
+        GasCost, Hardfork, Memory, OpcodeId, Stack, Storage, MAX_EXPANDED_MEMORY_ADDRESS,
+        PRECOMPILE_P256VERIFY_ADDRESS,
+    },
+    geth_types::{secp256r1_sign_data, Transaction},
+    Error, GethExecStep, GethExecTrace, ToBigEndian, ToWord, Word,
+};
+use external_tracer::TraceConfig;
+use std::{array, collections::HashMap, ops::Range};
+
+/// Error message of go-ethereum for out of gas
+const GETH_ERR_OUT_OF_GAS: &str = "out of gas";
+/// Error message of go-ethereum for a memory size whose gas overflows
+const GETH_ERR_GAS_UINT_OVERFLOW: &str = "gas uint64 overflow";
+
+/// Trace the txs of `config` like the external tracer, by interpreting the
+/// code of the accounts they call in the Cancun hardfork.  The code must only
+/// use the opcodes PUSH0..PUSH32, POP, MLOAD, MSTORE, MCOPY, TLOAD, TSTORE,
+/// BLOBHASH, BLOBBASEFEE, STOP and REVERT, and the calls without value to the
+/// P256VERIFY precompile (RIP-7212), and every tx must be a call.  An out of
+/// gas error ends the tx like in go-ethereum.
+pub fn trace(config: &TraceConfig) -> Result<Vec<GethExecTrace>, Error> {
+    trace_with_custom_opcodes(config, |_| None)
+}
+
+/// Execution of a custom opcode of an L2 by the interpreter: its constant gas
+/// and its update of the stack
+pub type CustomOpcodeExec = (u64, fn(&mut Vec<Word>) -> Result<(), Error>);
+
+/// Trace the txs of `config` like [`trace`], executing the custom opcodes
+/// with their [`CustomOpcodeExec`] returned by `custom_opcode`.
+pub fn trace_with_custom_opcodes(
+    config: &TraceConfig,
+    custom_opcode: impl Fn(OpcodeId) -> Option<CustomOpcodeExec>,
+) -> Result<Vec<GethExecTrace>, Error> {
+    config
+        .transactions
+        .iter()
+        .map(|tx| trace_tx(config, tx, &custom_opcode))
+        .collect()
+}
+
+fn tracing_error(reason: impl Into<String>) -> Error {
+    Error::TracingError(reason.into())
+}
+
+fn trace_tx(
+    config: &TraceConfig,
+    tx: &Transaction,
+    custom_opcode: &impl Fn(OpcodeId) -> Option<CustomOpcodeExec>,
+) -> Result<GethExecTrace, Error> {
+    let to = tx
+        .to
+        .ok_or_else(|| tracing_error("contract creations are not supported"))?;
+    let code = config
+        .accounts
+        .get(&to)
+        .map_or(&[][..], |account| account.code.as_ref());
+    let gas_limit = tx.gas_limit.as_u64();
+    let mut gas = gas_limit
+        .checked_sub(tx.intrinsic_gas_cost(Hardfork::Cancun))
+        .ok_or_else(|| tracing_error("intrinsic gas too low"))?;
+
+    let mut stack = Vec::new();
+    let mut memory = Vec::new();
+    // The transient storage is discarded at the end of every tx (EIP-1153).
+    let mut transient_storage = HashMap::new();
+    let mut struct_logs = Vec::new();
+    let mut failed = false;
+    let mut return_data = Vec::new();
+    let mut pc = 0;
+    loop {
+        // The code is followed by an implicit STOP.
+        let op = code
+            .get(pc)
+            .map_or(OpcodeId::STOP, |byte| OpcodeId::from(*byte));
+        let custom_exec = custom_opcode(op);
+        let step_stack = Stack(stack.clone());
+
+        // The memory expansion is charged before the op is executed.
+        let memory_ranges = match op {
+            OpcodeId::MLOAD | OpcodeId::MSTORE => vec![(peek(&stack, 0)?, Word::from(32))],
+            OpcodeId::MCOPY => {
+                let length = peek(&stack, 2)?;
+                vec![(peek(&stack, 0)?, length), (peek(&stack, 1)?, length)]
+            }
+            OpcodeId::REVERT => vec![(peek(&stack, 0)?, peek(&stack, 1)?)],
+            op if op.is_call() => {
+                let args = call_args_position(op);
+                vec![
+                    (peek(&stack, args)?, peek(&stack, args + 1)?),
+                    (peek(&stack, args + 2)?, peek(&stack, args + 3)?),
+                ]
+            }
+            _ => vec![],
+        };
+        let curr_memory_word_size = memory.len() as u64 / 32;
+        let next_memory_word_size = memory_ranges
+            .iter()
+            .filter(|(_, length)| !length.is_zero())
+            .map(|(offset, length)| {
+                let end = (offset.bits() <= 64 && length.bits() <= 64)
+                    .then(|| offset.as_u64().checked_add(length.as_u64()))
+                    .flatten();
+                memory_word_size(end.unwrap_or(u64::MAX))
+            })
+            .fold(curr_memory_word_size, u64::max);
+        let mut gas_cost = custom_exec.map_or(op.constant_gas_cost(), |(gas, _)| gas);
+        let mut callee_gas = 0;
+        let error = if next_memory_word_size > MAX_EXPANDED_MEMORY_ADDRESS / 32 {
+            Some(GETH_ERR_GAS_UINT_OVERFLOW)
+        } else {
+            gas_cost += if op == OpcodeId::MCOPY {
+                memory_copier_gas_cost(
+                    curr_memory_word_size,
+                    next_memory_word_size,
+                    memory_ranges[0].1.as_u64(),
+                )
+            } else {
+                memory_expansion_gas_cost(curr_memory_word_size, next_memory_word_size)
+            };
+            if op.is_call() && gas_cost <= gas {
+                // The gas given to the callee is part of the cost of the call
+                // (EIP-150).
+                callee_gas = eip150_gas(gas - gas_cost, peek(&stack, 0)?);
+                gas_cost += callee_gas;
+            }
+            (gas_cost > gas).then_some(GETH_ERR_OUT_OF_GAS)
+        };
+        struct_logs.push(GethExecStep {
+            pc: pc as u64,
+            op,
+            gas,
+            gas_cost,
+            refund: 0,
+            depth: 1,
+            error: error.map(str::to_string),
+            stack: step_stack,
+            memory: Memory::default(),
+            storage: Storage::default(),
+        });
+        if error.is_some() {
+            // The error consumes all the gas left.
+            gas = 0;
+            failed = true;
+            break;
+        }
+        gas -= gas_cost;
+        memory.resize(next_memory_word_size as usize * 32, 0);
+
+        let mut next_pc = pc + 1;
+        match op {
+            OpcodeId::STOP => break,
+            OpcodeId::REVERT => {
+                let offset = pop(&mut stack)?;
+                let length = pop(&mut stack)?;
+                return_data = memory[memory_range(offset, length)].to_vec();
+                failed = true;
+                break;
+            }
+            OpcodeId::POP => {
+                pop(&mut stack)?;
+            }
+            OpcodeId::MLOAD => {
+                let offset = pop(&mut stack)?;
+                let range = memory_range(offset, Word::from(32));
+                stack.push(Word::from_big_endian(&memory[range]));
+            }
+            OpcodeId::MSTORE => {
+                let offset = pop(&mut stack)?;
+                let value = pop(&mut stack)?;
+                memory[memory_range(offset, Word::from(32))].copy_from_slice(&value.to_be_bytes());
+            }
+            OpcodeId::MCOPY => {
+                let dst_offset = pop(&mut stack)?;
+                let src_offset = pop(&mut stack)?;
+                let length = pop(&mut stack)?;
+                let dst_range = memory_range(dst_offset, length);
+                memory.copy_within(memory_range(src_offset, length), dst_range.start);
+            }
+            OpcodeId::TLOAD => {
+                let key = pop(&mut stack)?;
+                stack.push(transient_storage.get(&key).copied().unwrap_or_default());
+            }
+            OpcodeId::TSTORE => {
+                let key = pop(&mut stack)?;
+                let value = pop(&mut stack)?;
+                transient_storage.insert(key, value);
+            }
+            OpcodeId::BLOBHASH => {
+                let index = pop(&mut stack)?;
+                let hash = (index.bits() <= 32)
+                    .then(|| index.as_usize())
+                    .and_then(|index| tx.blob_versioned_hashes.get(index))
+                    .map_or(Word::zero(), |hash| hash.to_word());
+                stack.push(hash);
+            }
+            OpcodeId::BLOBBASEFEE => stack.push(config.block_constants.blob_base_fee),
+            op if op.is_call() => {
+                let address = peek(&stack, 1)?;
+                let args = call_args_position(op);
+                let value = if args == 3 {
+                    peek(&stack, 2)?
+                } else {
+                    Word::zero()
+                };
+                if address != Word::from(PRECOMPILE_P256VERIFY_ADDRESS) || !value.is_zero() {
+                    return Err(tracing_error(
+                        "only the calls without value to P256VERIFY are supported",
+                    ));
+                }
+                let [args_offset, args_length, ret_offset, ret_length] =
+                    array::from_fn(|i| stack[stack.len() - 1 - args - i]);
+                stack.truncate(stack.len() - args - 4);
+
+                let input = &memory[memory_range(args_offset, args_length)];
+                // A call with insufficient gas consumes all the gas given to
+                // the precompile.
+                let success = callee_gas >= GasCost::PRECOMPILE_P256VERIFY;
+                let mut output = vec![];
+                if success {
+                    gas += callee_gas - GasCost::PRECOMPILE_P256VERIFY;
+                    if p256_verify(input) {
+                        output = Word::one().to_be_bytes().to_vec();
+                    }
+                }
+                let ret_start = memory_range(ret_offset, ret_length).start;
+                let length = output.len().min(ret_length.as_usize());
+                memory[ret_start..ret_start + length].copy_from_slice(&output[..length]);
+                stack.push(Word::from(success as u64));
+            }
+            op if op.is_push() => {
+                let data_len = op.data_len();
+                // The bytes after the end of the code are zeros.
+                let mut data = vec![0; data_len];
+                let code_data = code.get(pc + 1..).unwrap_or_default();
+                let copy_len = data_len.min(code_data.len());
+                data[..copy_len].copy_from_slice(&code_data[..copy_len]);
+                stack.push(Word::from_big_endian(&data));
+                next_pc += data_len;
+            }
+            op => match custom_exec {
+                Some((_, exec)) => exec(&mut stack)?,
+                None => return Err(tracing_error(format!("unsupported opcode {:?}", op))),
+            },
+        }
+        pc = next_pc;
+    }
+
+    Ok(GethExecTrace {
+        gas: gas_limit - gas,
+        failed,
+        invalid: false,
+        return_value: hex::encode(return_data),
+        struct_logs,
+    })
+}
+
+fn pop(stack: &mut Vec<Word>) -> Result<Word, Error> {
+    stack.pop().ok_or_else(|| tracing_error("stack underflow"))
+}
+
+/// Return the `n`-th word from the top of the stack.
+fn peek(stack: &[Word], n: usize) -> Result<Word, Error> {
+    stack
+        .iter()
+        .rev()
+        .nth(n)
+        .copied()
+        .ok_or_else(|| tracing_error("stack underflow"))
+}
+
+/// Return the position from the top of the stack of the offset of the call data
+/// of a call, after the gas, the address and the value of CALL and CALLCODE.
+fn call_args_position(op: OpcodeId) -> usize {
+    match op {
+        OpcodeId::CALL | OpcodeId::CALLCODE => 3,
+        _ => 2,
+    }
+}
+
+/// Return whether the input of a P256VERIFY call is a valid secp256r1
+/// signature of the message hash by the public key, as
+/// `bus_mapping::precompile::p256_verify` does.
+fn p256_verify(input: &[u8]) -> bool {
+    if input.len() != 160 {
+        return false;
+    }
+    let [r, s, pk_x, pk_y] =
+        array::from_fn(|i| Word::from_big_endian(&input[(i + 1) * 32..(i + 2) * 32]));
+    secp256r1_sign_data(&r, &s, &pk_x, &pk_y, input[..32].try_into().unwrap())
+        .is_ok_and(|sign_data| sign_data.verify())
+}
+
+/// Return the range of the memory which is accessed at `offset`, whose offset
+/// is ignored when `length` is zero.  The memory must already cover it.
+fn memory_range(offset: Word, length: Word) -> Range<usize> {
+    if length.is_zero() {
+        return 0..0;
+    }
+    let offset = offset.as_usize();
+    offset..offset + length.as_usize()
+}
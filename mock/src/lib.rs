@@ -2,13 +2,17 @@
 
 use std::str::FromStr;
 
-use eth_types::{address, bytecode, bytecode::Bytecode, word, Address, Bytes, Hash, Word};
+use eth_types::{
+    address, bytecode, bytecode::Bytecode, ed25519_types::Ed25519PrivateKey,
+    rsa_types::RsaPrivateKey, word, Address, Bytes, Hash, Word,
+};
 use ethers_signers::LocalWallet;
 use lazy_static::lazy_static;
 use rand::{random, SeedableRng};
 use rand_chacha::ChaCha20Rng;
 mod account;
 mod block;
+pub mod interpreter;
 mod sha3;
 pub mod test_ctx;
 pub mod test_ctx2;
@@ -70,6 +74,26 @@ lazy_static! {
             LocalWallet::new(&mut rng),
     ]
     };
+    /// Mock RSA-2048 key, as held by a My Number Card, used to generate Transactions signed
+    /// with RSA PKCS#1 v1.5.
+    pub static ref MOCK_RSA_KEY: RsaPrivateKey = RsaPrivateKey::from_hex(
+        "a38e99c6fda405c6700d061485df359a5cb18ab303b4ce2fb29f63916a6a274c101867ce1f2efabd59a9680ca9e699d08455531d64d007595f890e3d61b6cadf89d5bce6d16e5996f7a954dc75fb1a2f726f05057fc39880d5de5759e66ab8d8defe87e70d766be7c143d80726e6cb7c1b28732401e20536a7b943bebaa89693e3b4bc22112a6501453f1876e2fa0a33ee5c1811a9738ed1700547da43f52bd72886ea71eb7d8e36b2a716753f62cb2a5817cc3ae28f289ca5ae0d6ca44f6de0f57816cf731a35da57c3d83ceb0cbce82b54e814296a84ead009488480f7aff6acf55f1e2ee1b81989099129b65ecc24bb4109cf7cbeebb699246f4d5029ba1b",
+        "4b5b2148c885f0cf3bc314611249041669ccb71dc180655b1e8e473eb16bd54d3b41335a898d88a3031385480863a1ccab9bf05e639b61377147e152b0999aa1218d63aa6925d18b37f241920d9db2b3d7a2850ac291e8cabb3ee4ba1bea61de31e7cdcfcba91b103d4037e11bb04c6292d4b1300eda0712e675fa5b7efebbd90d09438c87b19a87e5d5d6fd81e983dbac7e87bc068fad272a7e32231c9e9124c97bf9dabf03b4cfa13642b7bcdf06e19f6af06898888f9eb98bf8734fcf2479019fa419fbaecdcd8c8ce83f87ce1aa28945405386b5c3d601b1d239e744914b33b1378da11d95dd3a1e2168826ddcbcb990feb678e35e6e1d47a755822121c9",
+    );
+    /// Mock Ed25519 key used to generate Transactions signed with Ed25519.
+    pub static ref MOCK_ED25519_KEY: Ed25519PrivateKey = Ed25519PrivateKey { seed: [0x25; 32] };
+    /// Mock RSA-2048 key of the certification authority that issues the certificate of
+    /// `MOCK_RSA_KEY` (`MOCK_X509_CERTIFICATE`).
+    pub static ref MOCK_RSA_CA_KEY: RsaPrivateKey = RsaPrivateKey::from_hex(
+        "995b2b0d025eec0e06b06b2593a665f3ff6e885b8b7b7b611f6ca6da9b3b0e212b89b31b5e795b43ba80b5f4cebf3bd561e02ec3df7dd3c23d1b70ff64692b217150a1bff72e07a4a83eecddeb739723db24220dc95f905e0408a63378f9c8e656463e04e89adc3aeef3106a72f17fb6a0e3c69090299b153be718d189dae2a5aebff90dc9b46d9cd9e868a58e6027910aa7f1c806da59842df91240d8e865a6a0e2c58ffc7c2f501b85e03ef503263f5e568439c2e2165ebdf42d4f198603fdd4830eb21af2da241ec5d29badf14ba2fd0dcc51280a21304fcac5d9dd0d52b12098bb1009d136115c2fe38522363c1e0ab88d5ebd0601430805980af32eee19",
+        "3b3d8d1d5ae7992f77e50996fbf9011466fd675e59bceb35e30241585c33cd6b01db111dc6fa81476211db7844af79e91d8490fc2866fe78dfcb2c74f3f06e715e49241ab85d0725d3ba6f1cf119f76e29f6c4d2e791eb7e6df77b6091e087fb76a36ecccda018e8092b4b119bf26f7ff899d4b83e805c00b161bde41fe936b8e96cd6af7f21b9591f841479fa4d0c6266a94e66665d135ce913075147db015f622163aecf07d505399e1f9e1220d1de315ea9dce5a74ee1af120a8337e99fad5ca1ee56ed077180ef373b593b31280b0c32c1c0ff5591ac0fd20706383ee16d4991d84cb6381eef93f0c866516716dacb63bd622eb521e9addc87afd6c46223",
+    );
+    /// Mock DER encoded X.509 certificate of `MOCK_RSA_KEY`, issued by `MOCK_RSA_CA_KEY` with
+    /// the validity from 2024-01-01 to 2029-01-01.
+    pub static ref MOCK_X509_CERTIFICATE: Vec<u8> = hex::decode(
+        "308202fa308201e2a00302010202021234300d06092a864886f70d01010b0500302e310b3009060355040613024a50310d300b060355040a0c044a504b493110300e06035504030c074d6f636b204341301e170d3234303130313030303030305a170d3239303130313030303030305a3030310b3009060355040613024a50310d300b060355040a0c044a504b493112301006035504030c094d6f636b205573657230820122300d06092a864886f70d01010105000382010f003082010a0282010100a38e99c6fda405c6700d061485df359a5cb18ab303b4ce2fb29f63916a6a274c101867ce1f2efabd59a9680ca9e699d08455531d64d007595f890e3d61b6cadf89d5bce6d16e5996f7a954dc75fb1a2f726f05057fc39880d5de5759e66ab8d8defe87e70d766be7c143d80726e6cb7c1b28732401e20536a7b943bebaa89693e3b4bc22112a6501453f1876e2fa0a33ee5c1811a9738ed1700547da43f52bd72886ea71eb7d8e36b2a716753f62cb2a5817cc3ae28f289ca5ae0d6ca44f6de0f57816cf731a35da57c3d83ceb0cbce82b54e814296a84ead009488480f7aff6acf55f1e2ee1b81989099129b65ecc24bb4109cf7cbeebb699246f4d5029ba1b0203010001a320301e300c0603551d130101ff04023000300e0603551d0f0101ff0404030206c0300d06092a864886f70d01010b0500038201010052344952081653dd8dc6df0e0553763e637d1110c40ff4445762e083c494531e962e01d164ab3b47cf55066c8f9aacac20ae693af91dada0772c2bc4779fd4aa88be8e1ef67fb8a0986a8292abb524624fc9ec9f667314acdf1bf66e947efc78ac6812535b6de4091fbe16d4c0ed4680d22d01a0a49d51d5aab85a776c7cd78ec9ef1336e097983dafdfad0c4e728da8231f961c134e64499055868c43ad0e57706f38df428aeea8f10452f7cc2219e4cd82c78c1951cb97fb5c2b9f931684a92d900a0addcb00ad5996da09fef571da3b092ae2344de3c2cd9221bef510fe3e56c7e8174648e037ca9adafa0043a679163088d376c1ffe2ab17e41f9d3eb28d",
+    )
+    .expect("invalid hex");
     /// Mock EVM bytecode for a deployed contract.
     /// PUSH1 0x20
     /// PUSH1 0
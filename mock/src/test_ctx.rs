@@ -3,8 +3,9 @@
 use crate::{eth, MockAccount, MockBlock, MockTransaction, TestContext2};
 use eth_types::{
     geth_types::{Account, GethData},
-    Bytecode, Error, Word,
+    Bytecode, Error, GethExecTrace, Word,
 };
+use external_tracer::TraceConfig;
 use helpers::*;
 
 pub use external_tracer::LoggerConfig;
@@ -131,6 +132,41 @@ impl<const NACC: usize, const NTX: usize> TestContext<NACC, NTX> {
         })
     }
 
+    /// Create a new TestContext like [`Self::new`], whose execution traces are
+    /// generated by `tracer` instead of the external tracer, such as
+    /// [`crate::interpreter::trace`] for the opcodes that the external tracer
+    /// doesn't support yet.
+    pub fn new_with_tracer<FAcc, FTx, Fb, FTr>(
+        history_hashes: Option<Vec<Word>>,
+        acc_fns: FAcc,
+        func_tx: FTx,
+        func_block: Fb,
+        tracer: FTr,
+    ) -> Result<Self, Error>
+    where
+        FTx: FnOnce(Vec<&mut MockTransaction>, [MockAccount; NACC]),
+        Fb: FnOnce(&mut MockBlock, Vec<MockTransaction>) -> &mut MockBlock,
+        FAcc: FnOnce([&mut MockAccount; NACC]),
+        FTr: FnOnce(&TraceConfig) -> Result<Vec<GethExecTrace>, Error>,
+    {
+        let test_ctx2 = TestContext2::<NACC, NTX, 0>::new_with_tracer(
+            history_hashes,
+            acc_fns,
+            func_tx,
+            |_| {},
+            func_block,
+            tracer,
+        )?;
+
+        Ok(Self {
+            chain_id: test_ctx2.chain_id,
+            accounts: test_ctx2.accounts,
+            history_hashes: test_ctx2.history_hashes.clone(),
+            eth_block: test_ctx2.eth_block,
+            geth_traces: test_ctx2.geth_traces,
+        })
+    }
+
     /// Create a new TestContext which starts with `NACC` default accounts and
     /// `NTX` default transactions.  Afterwards, we apply the `acc_fns`
     /// function to the accounts, the `func_tx` to the transactions and
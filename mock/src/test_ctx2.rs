@@ -58,6 +58,62 @@ impl<const NACC: usize, const NTX: usize, const NWD: usize> TestContext2<NACC, N
         FWd: FnOnce(Vec<&mut MockWithdrawal>),
         Fb: FnOnce(&mut MockBlock, Vec<MockTransaction>) -> &mut MockBlock,
         FAcc: FnOnce([&mut MockAccount; NACC]),
+    {
+        Self::new_with_tracer_and_logger_config(
+            history_hashes,
+            acc_fns,
+            func_tx,
+            func_wd,
+            func_block,
+            trace,
+            logger_config,
+        )
+    }
+
+    /// Create a new TestContext2 like [`Self::new`], whose execution traces
+    /// are generated by `tracer` instead of the external tracer, such as
+    /// [`crate::interpreter::trace`].
+    pub fn new_with_tracer<FAcc, FTx, FWd, Fb, FTr>(
+        history_hashes: Option<Vec<Word>>,
+        acc_fns: FAcc,
+        func_tx: FTx,
+        func_wd: FWd,
+        func_block: Fb,
+        tracer: FTr,
+    ) -> Result<Self, Error>
+    where
+        FTx: FnOnce(Vec<&mut MockTransaction>, [MockAccount; NACC]),
+        FWd: FnOnce(Vec<&mut MockWithdrawal>),
+        Fb: FnOnce(&mut MockBlock, Vec<MockTransaction>) -> &mut MockBlock,
+        FAcc: FnOnce([&mut MockAccount; NACC]),
+        FTr: FnOnce(&TraceConfig) -> Result<Vec<GethExecTrace>, Error>,
+    {
+        Self::new_with_tracer_and_logger_config(
+            history_hashes,
+            acc_fns,
+            func_tx,
+            func_wd,
+            func_block,
+            tracer,
+            LoggerConfig::default(),
+        )
+    }
+
+    fn new_with_tracer_and_logger_config<FAcc, FTx, FWd, Fb, FTr>(
+        history_hashes: Option<Vec<Word>>,
+        acc_fns: FAcc,
+        func_tx: FTx,
+        func_wd: FWd,
+        func_block: Fb,
+        tracer: FTr,
+        logger_config: LoggerConfig,
+    ) -> Result<Self, Error>
+    where
+        FTx: FnOnce(Vec<&mut MockTransaction>, [MockAccount; NACC]),
+        FWd: FnOnce(Vec<&mut MockWithdrawal>),
+        Fb: FnOnce(&mut MockBlock, Vec<MockTransaction>) -> &mut MockBlock,
+        FAcc: FnOnce([&mut MockAccount; NACC]),
+        FTr: FnOnce(&TraceConfig) -> Result<Vec<GethExecTrace>, Error>,
     {
         let mut accounts: Vec<MockAccount> = vec![MockAccount::default(); NACC];
         // Build Accounts modifiers
@@ -132,14 +188,14 @@ impl<const NACC: usize, const NTX: usize, const NWD: usize> TestContext2<NACC, N
             .try_into()
             .expect("Mismatched withdrawal len");
 
-        let geth_traces = gen_geth_traces(
+        let geth_traces = tracer(&trace_config(
             chain_id,
-            block.clone(),
+            &block,
             accounts.to_vec(),
             withdrawals.to_vec(),
             history_hashes.clone(),
             logger_config,
-        )?;
+        )?)?;
 
         // Don't allow invalid transactions unless explicitly allowed to avoid unrelated tests from
         // passing simply because the test transaction was incorrectly set up.
@@ -206,10 +262,30 @@ pub fn gen_geth_traces(
     history_hashes: Option<Vec<Word>>,
     logger_config: LoggerConfig,
 ) -> Result<Vec<GethExecTrace>, Error> {
-    let trace_config = TraceConfig {
+    trace(&trace_config(
+        chain_id,
+        &block,
+        accounts,
+        withdrawals,
+        history_hashes,
+        logger_config,
+    )?)
+}
+
+/// Returns the configuration of the tracer of the transactions included in the
+/// provided Block
+fn trace_config(
+    chain_id: Word,
+    block: &Block<Transaction>,
+    accounts: Vec<Account>,
+    withdrawals: Vec<Withdrawal>,
+    history_hashes: Option<Vec<Word>>,
+    logger_config: LoggerConfig,
+) -> Result<TraceConfig, Error> {
+    Ok(TraceConfig {
         chain_id,
         history_hashes: history_hashes.unwrap_or_default(),
-        block_constants: BlockConstants::try_from(&block)?,
+        block_constants: BlockConstants::try_from(block)?,
         accounts: accounts
             .iter()
             .map(|account| (account.address, account.clone()))
@@ -221,7 +297,5 @@ pub fn gen_geth_traces(
             .collect(),
         withdrawals,
         logger_config,
-    };
-    let traces = trace(&trace_config)?;
-    Ok(traces)
+    })
 }
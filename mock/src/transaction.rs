@@ -2,12 +2,15 @@
 
 use super::{MOCK_ACCOUNTS, MOCK_CHAIN_ID, MOCK_GASPRICE};
 use eth_types::{
-    geth_types::Transaction as GethTransaction, word, AccessList, Address, Bytes, Hash,
-    Transaction, Word, U64,
+    geth_types::{
+        blob_versioned_hashes_fields, deposit_fields, Deposit, Transaction as GethTransaction,
+        DEPOSIT_TX_TYPE,
+    },
+    word, AccessList, Address, Bytes, Hash, Transaction, Word, U64,
 };
 use ethers_core::{
     rand::{CryptoRng, RngCore},
-    types::{OtherFields, TransactionRequest},
+    types::TransactionRequest,
 };
 use ethers_signers::{LocalWallet, Signer};
 use lazy_static::lazy_static;
@@ -135,7 +138,9 @@ pub struct MockTransaction {
     pub access_list: AccessList,
     pub max_priority_fee_per_gas: Word,
     pub max_fee_per_gas: Word,
+    pub blob_versioned_hashes: Vec<Hash>,
     pub chain_id: Word,
+    pub deposit: Option<Deposit>,
     pub invalid: bool,
 }
 
@@ -160,7 +165,9 @@ impl Default for MockTransaction {
             access_list: AccessList::default(),
             max_priority_fee_per_gas: Word::zero(),
             max_fee_per_gas: Word::zero(),
+            blob_versioned_hashes: Vec::new(),
             chain_id: *MOCK_CHAIN_ID,
+            deposit: None,
             invalid: false,
         }
     }
@@ -168,6 +175,10 @@ impl Default for MockTransaction {
 
 impl From<MockTransaction> for Transaction {
     fn from(mock: MockTransaction) -> Self {
+        let mut other = blob_versioned_hashes_fields(&mock.blob_versioned_hashes);
+        if let Some(deposit) = &mock.deposit {
+            other.extend(deposit_fields(deposit).into_iter());
+        }
         Transaction {
             hash: mock.hash.unwrap_or_default(),
             nonce: mock.nonce.unwrap_or_default().into(),
@@ -188,7 +199,7 @@ impl From<MockTransaction> for Transaction {
             max_priority_fee_per_gas: Some(mock.max_priority_fee_per_gas),
             max_fee_per_gas: Some(mock.max_fee_per_gas),
             chain_id: Some(mock.chain_id),
-            other: OtherFields::default(),
+            other,
         }
     }
 }
@@ -299,6 +310,21 @@ impl MockTransaction {
         self
     }
 
+    /// Set blob_versioned_hashes field for the MockTransaction.
+    pub fn blob_versioned_hashes(&mut self, blob_versioned_hashes: Vec<Hash>) -> &mut Self {
+        self.blob_versioned_hashes = blob_versioned_hashes;
+        self
+    }
+
+    /// Make the MockTransaction an OP-stack deposit transaction, which has no
+    /// signature and pays no gas price.
+    pub fn deposit(&mut self, deposit: Deposit) -> &mut Self {
+        self.transaction_type = U64::from(DEPOSIT_TX_TYPE);
+        self.gas_price = Word::zero();
+        self.deposit = Some(deposit);
+        self
+    }
+
     /// Set chain_id field for the MockTransaction.
     pub(crate) fn chain_id(&mut self, chain_id: Word) -> &mut Self {
         self.chain_id = chain_id;
@@ -321,7 +347,7 @@ impl MockTransaction {
         match (self.v, self.r, self.s) {
             (None, None, None) => {
                 // Compute sig params and set them in case we have a wallet as `from` attr.
-                if self.from.is_wallet() && self.hash.is_none() {
+                if self.from.is_wallet() && self.hash.is_none() && self.deposit.is_none() {
                     let sig = self
                         .from
                         .as_wallet()
@@ -338,8 +364,12 @@ impl MockTransaction {
 
         // Compute tx hash in case is not already set
         if self.hash.is_none() {
-            let tmp_tx = Transaction::from(self.to_owned());
-            self.hash(tmp_tx.hash());
+            let hash = if self.deposit.is_some() {
+                GethTransaction::from(self.to_owned()).hash(self.chain_id.low_u64())
+            } else {
+                Transaction::from(self.to_owned()).hash()
+            };
+            self.hash(hash);
         }
 
         self.to_owned()
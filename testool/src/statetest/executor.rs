@@ -1,10 +1,14 @@
 use super::{AccountMatch, StateTest, StateTestResult};
 use crate::config::TestSuite;
 use bus_mapping::{
-    circuit_input_builder::{CircuitInputBuilder, FixedCParams},
+    circuit_input_builder::{CircuitInputBuilder, CodeHashScheme, FixedCParams},
     mock::BlockData,
+    precompile::MODEXP_SIZE_LIMIT,
+};
+use eth_types::{
+    evm_types::{gas_utils::blob_base_fee, Hardfork},
+    geth_types, Address, Bytes, Error, GethExecTrace, H256, U256, U64,
 };
-use eth_types::{geth_types, Address, Bytes, Error, GethExecTrace, U256, U64};
 use ethers_core::{
     k256::ecdsa::SigningKey,
     types::{transaction::eip2718::TypedTransaction, TransactionRequest, Withdrawal},
@@ -168,6 +172,11 @@ fn into_traceconfig(st: StateTest) -> (String, TraceConfig, StateTestResult) {
                 difficulty: st.env.current_difficulty,
                 gas_limit: U256::from(st.env.current_gas_limit),
                 base_fee: st.env.current_base_fee,
+                // The state tests have no excess blob gas
+                blob_base_fee: blob_base_fee(0),
+                // The state tests have no mix hash, so PREVRANDAO returns their
+                // difficulty
+                prev_randao: st.env.current_difficulty,
             },
 
             transactions: vec![geth_types::Transaction {
@@ -181,6 +190,9 @@ fn into_traceconfig(st: StateTest) -> (String, TraceConfig, StateTestResult) {
                 gas_tip_cap: U256::zero(),
                 call_data: st.data,
                 access_list: None,
+                authorization_list: None,
+                blob_versioned_hashes: vec![],
+                deposit: None,
                 v: sig.v,
                 r: sig.r,
                 s: sig.s,
@@ -301,6 +313,7 @@ pub fn run_test(
         timestamp: trace_config.block_constants.timestamp,
         number: Some(U64::from(trace_config.block_constants.number.as_u64())),
         difficulty: trace_config.block_constants.difficulty,
+        mix_hash: Some(H256::from_uint(&trace_config.block_constants.prev_randao)),
         gas_limit: trace_config.block_constants.gas_limit,
         base_fee_per_gas: Some(trace_config.block_constants.base_fee),
         withdrawals,
@@ -329,14 +342,31 @@ pub fn run_test(
     if !circuits_config.super_circuit {
         let circuits_params = FixedCParams {
             max_txs: 1,
+            max_signed_txs: 1,
             max_withdrawals: 1,
             max_rws: 55000,
             max_calldata: 5000,
+            max_access_list: 1000,
+            max_auths: 0,
             max_bytecode: 5000,
             max_copy_rows: 55000,
             max_evm_rows: 0,
             max_exp_steps: 5000,
             max_keccak_rows: 0,
+            max_poseidon_blocks: 0,
+            max_sha256_blocks: 0,
+            max_ripemd160_blocks: 0,
+            max_blake2f_blocks: 0,
+            max_sig_verifs: 0,
+            max_modexps: 0,
+            max_modexp_size: MODEXP_SIZE_LIMIT,
+            max_ec_adds: 0,
+            max_ec_muls: 0,
+            max_p256_verifs: 0,
+            hardfork: Hardfork::default(),
+            code_hash_scheme: CodeHashScheme::Keccak,
+            deposits: false,
+            p256_verify: false,
         };
         let block_data = BlockData::new_from_geth_data_with_params(geth_data, circuits_params);
 
@@ -367,14 +397,31 @@ pub fn run_test(
 
         let circuits_params = FixedCParams {
             max_txs: 1,
+            max_signed_txs: 1,
             max_withdrawals: 1,
             max_calldata: 32,
+            max_access_list: 16,
+            max_auths: 0,
             max_rws: 256,
             max_copy_rows: 256,
             max_exp_steps: 256,
             max_bytecode: 512,
             max_evm_rows: 0,
             max_keccak_rows: 0,
+            max_poseidon_blocks: 0,
+            max_sha256_blocks: 0,
+            max_ripemd160_blocks: 0,
+            max_blake2f_blocks: 0,
+            max_sig_verifs: 0,
+            max_modexps: 0,
+            max_modexp_size: MODEXP_SIZE_LIMIT,
+            max_ec_adds: 0,
+            max_ec_muls: 0,
+            max_p256_verifs: 0,
+            hardfork: Hardfork::default(),
+            code_hash_scheme: CodeHashScheme::Keccak,
+            deposits: false,
+            p256_verify: false,
         };
         let (k, circuit, instance, _builder) =
             SuperCircuit::<Fr>::build(geth_data, circuits_params, Fr::from(0x100)).unwrap();
@@ -0,0 +1,1048 @@
+//! The blake2f circuit implementation.
+//!
+//! The circuit proves the compressions by the function F of BLAKE2b of the
+//! [`Blake2fTable`], in which the blake2f precompile looks up the outputs of
+//! RLC'ed inputs.  Every round of a compression takes `ROWS_PER_BLOCK` rows,
+//! and a compression without rounds takes a block whose operations are
+//! ignored:
+//!
+//! - the state rows, with the initial local work vector `v` of the block: the one derived from `h`,
+//!   `t` and `f` for the first round, and the one at the end of the previous round otherwise.  The
+//!   rows of `h` and `t` are followed by the rows of `m`, and the bytes of these words are
+//!   accumulated into the input RLC on the first block,
+//! - a row per addition or xor-rotation of the 8 mixing functions G, whose operands are copied from
+//!   the rows of the latest values of the words of `v`, and whose added message words are selected
+//!   in `m` by the message schedule of the round,
+//! - a row per word of `v` at the end of the round, which is the initial value for a compression
+//!   without rounds,
+//! - two rows per word of the output `h[i] ^ v[i] ^ v[i + 8]`, whose bytes are accumulated into the
+//!   output RLC, the last one being the row of the table for the last block of a compression.
+//!
+//! The words of a row are decomposed into bits in `x_bits` and `y_bits`,
+//! and the inputs of the compression and the round of the block are copied
+//! over all the rows of their blocks.
+
+mod param;
+mod util;
+
+#[cfg(any(test, feature = "test-circuits"))]
+mod dev;
+#[cfg(test)]
+mod test;
+#[cfg(feature = "test-circuits")]
+pub use dev::Blake2fCircuit as TestBlake2fCircuit;
+
+use self::{param::*, util::*};
+use crate::{
+    evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    table::{Blake2fTable, LookupTable},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use bus_mapping::{circuit_input_builder::Blake2fEvent, precompile::blake2f_compress};
+use eth_types::Field;
+use gadgets::util::{pow_of_two, select, sum, Expr};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, VirtualCells},
+    poly::Rotation,
+};
+use itertools::Itertools;
+use log::error;
+use std::marker::PhantomData;
+
+/// Blake2fCircuitConfig
+#[derive(Clone, Debug)]
+pub struct Blake2fCircuitConfig<F> {
+    q_enable: Column<Fixed>,
+    q_first: Column<Fixed>,
+    q_block_start: Column<Fixed>,
+    q_last: Column<Fixed>,
+    /// Selectors of the state rows, by word of `v`
+    q_state: [Column<Fixed>; NUM_WORDS_V],
+    /// Selectors of the rows of the message block, by word of `m`
+    q_m: [Column<Fixed>; NUM_WORDS_M],
+    /// Selector of the rows whose words are accumulated into the input RLC
+    q_input: Column<Fixed>,
+    q_add: Column<Fixed>,
+    /// Selectors of the message words added by the operations, by position
+    /// in the message schedule
+    q_msg: [Column<Fixed>; NUM_WORDS_M],
+    /// Selectors of the amounts of the rotations of the xor operations
+    q_rots: [Column<Fixed>; ROTATIONS.len()],
+    q_end: Column<Fixed>,
+    q_output: Column<Fixed>,
+    /// Bits of the first operand, or of the result of an addition
+    x_bits: [Column<Advice>; NUM_BITS_PER_WORD],
+    /// Bits of the second operand of a xor
+    y_bits: [Column<Advice>; NUM_BITS_PER_WORD],
+    x: Column<Advice>,
+    y: Column<Advice>,
+    /// Message word of an addition
+    z: Column<Advice>,
+    /// Result of the row
+    r: Column<Advice>,
+    carry: Column<Advice>,
+    /// Inputs of the compression, copied over all its rows
+    h: [Column<Advice>; NUM_WORDS_H],
+    m: [Column<Advice>; NUM_WORDS_M],
+    rounds_inv: Column<Advice>,
+    /// Whether the compression has rounds
+    do_round: Column<Advice>,
+    /// Round of the block, copied over all its rows
+    is_first: Column<Advice>,
+    is_last: Column<Advice>,
+    round_idx: Column<Advice>,
+    /// Selectors of the message schedule of the round
+    schedules: [Column<Advice>; NUM_SCHEDULES],
+    /// The columns for other circuits to lookup blake2f compressions
+    pub blake2f_table: Blake2fTable,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+pub struct Blake2fCircuitConfigArgs<F: Field> {
+    /// Blake2fTable
+    pub blake2f_table: Blake2fTable,
+    /// Challenges randomness
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for Blake2fCircuitConfig<F> {
+    type ConfigArgs = Blake2fCircuitConfigArgs<F>;
+
+    /// Return a new Blake2fCircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            blake2f_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let q_enable = meta.fixed_column();
+        let q_first = meta.fixed_column();
+        let q_block_start = meta.fixed_column();
+        let q_last = meta.fixed_column();
+        let q_state = array_init::array_init(|_| meta.fixed_column());
+        let q_m = array_init::array_init(|_| meta.fixed_column());
+        let q_input = meta.fixed_column();
+        let q_add = meta.fixed_column();
+        let q_msg = array_init::array_init(|_| meta.fixed_column());
+        let q_rots = array_init::array_init(|_| meta.fixed_column());
+        let q_end = meta.fixed_column();
+        let q_output = meta.fixed_column();
+
+        let x_bits = array_init::array_init(|_| meta.advice_column());
+        let y_bits = array_init::array_init(|_| meta.advice_column());
+        let x = meta.advice_column();
+        let y = meta.advice_column();
+        let z = meta.advice_column();
+        let r = meta.advice_column();
+        let carry = meta.advice_column();
+        for column in [x, y, r] {
+            meta.enable_equality(column);
+        }
+        let h: [Column<Advice>; NUM_WORDS_H] = array_init::array_init(|_| meta.advice_column());
+        let m: [Column<Advice>; NUM_WORDS_M] = array_init::array_init(|_| meta.advice_column());
+        let rounds_inv = meta.advice_column();
+        let do_round = meta.advice_column();
+        let is_first = meta.advice_column();
+        let is_last = meta.advice_column();
+        let round_idx = meta.advice_column();
+        let schedules: [Column<Advice>; NUM_SCHEDULES] =
+            array_init::array_init(|_| meta.advice_column());
+
+        let is_enabled = blake2f_table.is_enabled;
+        let rounds = blake2f_table.rounds;
+        let input_rlc = blake2f_table.input_rlc;
+        let f = blake2f_table.f;
+        let output_rlc = blake2f_table.output_rlc;
+
+        let bits = |meta: &mut VirtualCells<F>,
+                    columns: &[Column<Advice>; NUM_BITS_PER_WORD]|
+         -> WordBits<F> {
+            array_init::array_init(|i| meta.query_advice(columns[i], Rotation::cur()))
+        };
+        let pow_of_two_64 = || Expression::Constant(pow_of_two::<F>(NUM_BITS_PER_WORD));
+        // The RLC of the words shifts the accumulator by 8 bytes.
+        let randomness = challenges.keccak_input();
+        let randomness_pow_8 = (0..NUM_BYTES_PER_WORD)
+            .fold(1.expr(), |acc: Expression<F>, _| acc * randomness.clone());
+
+        meta.create_gate("boolean checks", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in x_bits
+                .iter()
+                .chain(y_bits.iter())
+                .chain([f, is_last].iter())
+            {
+                cb.require_boolean("boolean", meta.query_advice(*column, Rotation::cur()));
+            }
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("call context", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in h
+                .iter()
+                .chain(m.iter())
+                .chain([rounds, f, rounds_inv, do_round].iter())
+            {
+                cb.require_equal(
+                    "the inputs are the same for all the rows of a compression",
+                    meta.query_advice(*column, Rotation::cur()),
+                    meta.query_advice(*column, Rotation::prev()),
+                );
+            }
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    - meta.query_fixed(q_block_start, Rotation::cur())
+                        * meta.query_advice(is_first, Rotation::cur()),
+            )
+        });
+
+        meta.create_gate("do_round", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let rounds = meta.query_advice(rounds, Rotation::cur());
+            let do_round = meta.query_advice(do_round, Rotation::cur());
+            cb.require_equal(
+                "do_round = rounds * rounds_inv",
+                do_round.clone(),
+                rounds.clone() * meta.query_advice(rounds_inv, Rotation::cur()),
+            );
+            cb.require_zero(
+                "do_round = 1 if rounds != 0",
+                rounds * (1.expr() - do_round),
+            );
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("round of a block", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_first = meta.query_fixed(q_first, Rotation::cur());
+            let is_first = meta.query_advice(is_first, Rotation::cur());
+            let is_last = meta.query_advice(is_last, Rotation::cur());
+            let round_idx = meta.query_advice(round_idx, Rotation::cur());
+            let rounds = meta.query_advice(rounds, Rotation::cur());
+            let do_round = meta.query_advice(do_round, Rotation::cur());
+            cb.require_equal(
+                "a compression starts on the first block, or after the last block of a compression",
+                is_first.clone(),
+                q_first.clone()
+                    + (1.expr() - q_first) * meta.query_advice(is_last, Rotation::prev()),
+            );
+            cb.condition(is_first.clone(), |cb| {
+                cb.require_zero("round_idx = 0 on the first round", round_idx.clone());
+                for (k, schedule) in schedules.iter().enumerate() {
+                    cb.require_equal(
+                        "the first round uses the first message schedule",
+                        meta.query_advice(*schedule, Rotation::cur()),
+                        (k == 0).expr(),
+                    );
+                }
+            });
+            cb.condition(1.expr() - is_first, |cb| {
+                cb.require_equal(
+                    "round_idx is incremented",
+                    round_idx.clone(),
+                    meta.query_advice(round_idx, Rotation::prev()) + 1.expr(),
+                );
+                for (k, schedule) in schedules.iter().enumerate() {
+                    cb.require_equal(
+                        "the message schedules are used cyclically",
+                        meta.query_advice(*schedule, Rotation::cur()),
+                        meta.query_advice(
+                            schedules[(k + NUM_SCHEDULES - 1) % NUM_SCHEDULES],
+                            Rotation::prev(),
+                        ),
+                    );
+                }
+            });
+            cb.require_zero(
+                "a compression without rounds takes a single block",
+                (1.expr() - do_round.clone()) * (1.expr() - is_last.clone()),
+            );
+            cb.require_zero(
+                "the last block of a compression is its last round",
+                is_last * do_round * (round_idx + 1.expr() - rounds),
+            );
+            cb.gate(meta.query_fixed(q_block_start, Rotation::cur()))
+        });
+
+        meta.create_gate("block context", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in [is_first, is_last, round_idx]
+                .iter()
+                .chain(schedules.iter())
+            {
+                cb.require_equal(
+                    "the round is the same for all the rows of a block",
+                    meta.query_advice(*column, Rotation::cur()),
+                    meta.query_advice(*column, Rotation::prev()),
+                );
+            }
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    - meta.query_fixed(q_block_start, Rotation::cur()),
+            )
+        });
+
+        meta.create_gate("initial state", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let x_bits = bits(meta, &x_bits);
+            let x = meta.query_advice(x, Rotation::cur());
+            let is_first = meta.query_advice(is_first, Rotation::cur());
+            for (s, q_state) in q_state.iter().enumerate() {
+                let q_state = meta.query_fixed(*q_state, Rotation::cur());
+                let init = match s {
+                    s if s < NUM_WORDS_H => {
+                        cb.condition(q_state.clone(), |cb| {
+                            cb.require_equal(
+                                "x = h",
+                                x.clone(),
+                                meta.query_advice(h[s], Rotation::cur()),
+                            );
+                            cb.require_equal("x = bits of x", x.clone(), compose(&x_bits));
+                        });
+                        x.clone()
+                    }
+                    s if T_IDX.contains(&s) => xor_constant(&x_bits, IV[s - NUM_WORDS_H]),
+                    F_IDX => {
+                        let iv = IV[F_IDX - NUM_WORDS_H];
+                        iv.expr()
+                            + meta.query_advice(f, Rotation::cur()) * ((!iv).expr() - iv.expr())
+                    }
+                    s => IV[s - NUM_WORDS_H].expr(),
+                };
+                cb.condition(q_state, |cb| {
+                    cb.require_equal(
+                        "v = init on the first block, else the final v of the previous block",
+                        meta.query_advice(r, Rotation::cur()),
+                        select::expr(
+                            is_first.clone(),
+                            init,
+                            meta.query_advice(y, Rotation::cur()),
+                        ),
+                    );
+                });
+            }
+            cb.gate(1.expr())
+        });
+
+        meta.create_gate("message block", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let x_bits = bits(meta, &x_bits);
+            let x = meta.query_advice(x, Rotation::cur());
+            for (j, q_m) in q_m.iter().enumerate() {
+                cb.condition(meta.query_fixed(*q_m, Rotation::cur()), |cb| {
+                    cb.require_equal("x = m", x.clone(), meta.query_advice(m[j], Rotation::cur()));
+                    cb.require_equal("x = bits of x", x.clone(), compose(&x_bits));
+                });
+            }
+            cb.gate(1.expr())
+        });
+
+        meta.create_gate("addition", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let z = meta.query_advice(z, Rotation::cur());
+            let r = meta.query_advice(r, Rotation::cur());
+            let carry = meta.query_advice(carry, Rotation::cur());
+            // The message word at the position j of the schedule of the round
+            let msg = |meta: &mut VirtualCells<F>, j: usize| {
+                sum::expr(schedules.iter().enumerate().map(|(k, schedule)| {
+                    meta.query_advice(*schedule, Rotation::cur())
+                        * meta.query_advice(m[SIGMA[k][j]], Rotation::cur())
+                }))
+            };
+            let expected_z = sum::expr(
+                q_msg
+                    .iter()
+                    .enumerate()
+                    .map(|(j, q_msg)| meta.query_fixed(*q_msg, Rotation::cur()) * msg(meta, j))
+                    .collect_vec(),
+            );
+            cb.require_equal(
+                "z = the message word of the operation",
+                z.clone(),
+                expected_z,
+            );
+            cb.require_equal("r = bits of r", r.clone(), compose(&bits(meta, &x_bits)));
+            cb.require_equal(
+                "x + y + z = r + carry * 2^64",
+                meta.query_advice(x, Rotation::cur()) + meta.query_advice(y, Rotation::cur()) + z,
+                r + carry.clone() * pow_of_two_64(),
+            );
+            cb.require_in_set("carry <= 2", carry, vec![0.expr(), 1.expr(), 2.expr()]);
+            cb.gate(meta.query_fixed(q_add, Rotation::cur()))
+        });
+
+        meta.create_gate("xor", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let x_bits = bits(meta, &x_bits);
+            let y_bits = bits(meta, &y_bits);
+            let xored = xor(&x_bits, &y_bits);
+            for (rot, q_rot) in ROTATIONS.iter().zip(q_rots.iter()) {
+                cb.condition(meta.query_fixed(*q_rot, Rotation::cur()), |cb| {
+                    cb.require_equal(
+                        "x = bits of x",
+                        meta.query_advice(x, Rotation::cur()),
+                        compose(&x_bits),
+                    );
+                    cb.require_equal(
+                        "y = bits of y",
+                        meta.query_advice(y, Rotation::cur()),
+                        compose(&y_bits),
+                    );
+                    cb.require_equal(
+                        "r = (x ^ y) >>> rot",
+                        meta.query_advice(r, Rotation::cur()),
+                        compose(&rotate_right(&xored, *rot)),
+                    );
+                });
+            }
+            cb.gate(1.expr())
+        });
+
+        meta.create_gate("final state", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            cb.require_equal(
+                "v = the mixed v if the compression has rounds, else the initial v",
+                meta.query_advice(r, Rotation::cur()),
+                select::expr(
+                    meta.query_advice(do_round, Rotation::cur()),
+                    meta.query_advice(x, Rotation::cur()),
+                    meta.query_advice(y, Rotation::cur()),
+                ),
+            );
+            cb.gate(meta.query_fixed(q_end, Rotation::cur()))
+        });
+
+        // The input RLC is started on the first row of a compression, and
+        // accumulates the words of its first block.  The output RLC is
+        // accumulated on every block, and used on the last row of the last one.
+        meta.create_gate("input and output RLCs", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let x_bits = bits(meta, &x_bits);
+            let y_bits = bits(meta, &y_bits);
+            let q_block_start = meta.query_fixed(q_block_start, Rotation::cur());
+            let is_first = meta.query_advice(is_first, Rotation::cur());
+            let [input_rlc_prev, output_rlc_prev] =
+                [input_rlc, output_rlc].map(|column| meta.query_advice(column, Rotation::prev()));
+            let [input_rlc, output_rlc] =
+                [input_rlc, output_rlc].map(|column| meta.query_advice(column, Rotation::cur()));
+            let input_bytes = bytes_rlc(&x_bits, randomness.clone());
+            let output_bytes = bytes_rlc(&xor(&x_bits, &y_bits), randomness.clone());
+            cb.condition(q_block_start.clone(), |cb| {
+                cb.require_equal(
+                    "input_rlc starts with the first word of a compression",
+                    input_rlc.clone(),
+                    select::expr(
+                        is_first.clone(),
+                        input_bytes.clone(),
+                        input_rlc_prev.clone(),
+                    ),
+                );
+                cb.require_zero("output_rlc starts at 0", output_rlc.clone());
+            });
+            cb.condition(
+                meta.query_fixed(q_enable, Rotation::cur()) - q_block_start,
+                |cb| {
+                    cb.require_equal(
+                        "input_rlc accumulates the words of the first block",
+                        input_rlc,
+                        input_rlc_prev.clone()
+                            + meta.query_fixed(q_input, Rotation::cur())
+                                * is_first
+                                * (input_rlc_prev * (randomness_pow_8.clone() - 1.expr())
+                                    + input_bytes),
+                    );
+                    cb.require_equal(
+                        "output_rlc accumulates the words of the output",
+                        output_rlc,
+                        output_rlc_prev.clone()
+                            + meta.query_fixed(q_output, Rotation::cur())
+                                * (output_rlc_prev * (randomness_pow_8.clone() - 1.expr())
+                                    + output_bytes),
+                    );
+                },
+            );
+            cb.gate(1.expr())
+        });
+
+        meta.create_gate("blake2f table", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+            let q_last = meta.query_fixed(q_last, Rotation::cur());
+            cb.condition(q_last.clone(), |cb| {
+                cb.require_equal(
+                    "the compression is enabled on its last block",
+                    meta.query_advice(is_enabled, Rotation::cur()),
+                    meta.query_advice(is_last, Rotation::cur()),
+                );
+            });
+            cb.condition(q_enable - q_last, |cb| {
+                cb.require_zero(
+                    "is_enabled only on the last row of a block",
+                    meta.query_advice(is_enabled, Rotation::cur()),
+                );
+            });
+            cb.gate(1.expr())
+        });
+
+        Self {
+            q_enable,
+            q_first,
+            q_block_start,
+            q_last,
+            q_state,
+            q_m,
+            q_input,
+            q_add,
+            q_msg,
+            q_rots,
+            q_end,
+            q_output,
+            x_bits,
+            y_bits,
+            x,
+            y,
+            z,
+            r,
+            carry,
+            h,
+            m,
+            rounds_inv,
+            do_round,
+            is_first,
+            is_last,
+            round_idx,
+            schedules,
+            blake2f_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Witness values of a row of the blake2f circuit
+#[derive(Clone, Debug)]
+pub(crate) struct Blake2fRow<F> {
+    x: u64,
+    y: u64,
+    z: u64,
+    r: u64,
+    carry: u64,
+    x_bits: u64,
+    y_bits: u64,
+    h: [u64; NUM_WORDS_H],
+    m: [u64; NUM_WORDS_M],
+    rounds: u32,
+    f: bool,
+    is_first: bool,
+    is_last: bool,
+    round_idx: usize,
+    is_enabled: bool,
+    input_rlc: Value<F>,
+    output_rlc: Value<F>,
+}
+
+impl<F: Field> Blake2fRow<F> {
+    /// Return a row of the round `round_idx` of the compression `event`, with
+    /// the RLCs `input_rlc` and `output_rlc`, and zero values.
+    fn new(
+        event: &Blake2fEvent,
+        round_idx: usize,
+        is_last: bool,
+        input_rlc: Value<F>,
+        output_rlc: Value<F>,
+    ) -> Self {
+        Self {
+            x: 0,
+            y: 0,
+            z: 0,
+            r: 0,
+            carry: 0,
+            x_bits: 0,
+            y_bits: 0,
+            h: event.h,
+            m: event.m,
+            rounds: event.rounds,
+            f: event.f,
+            is_first: round_idx == 0,
+            is_last,
+            round_idx,
+            is_enabled: false,
+            input_rlc,
+            output_rlc,
+        }
+    }
+}
+
+/// Append the rows of the compression `event` to `rows`.
+fn blake2f<F: Field>(
+    rows: &mut Vec<Blake2fRow<F>>,
+    event: &Blake2fEvent,
+    challenges: Challenges<Value<F>>,
+) {
+    let absorb = |rlc: Value<F>, word: u64| {
+        word.to_le_bytes().iter().fold(rlc, |rlc, byte| {
+            rlc * challenges.keccak_input() + Value::known(F::from(*byte as u64))
+        })
+    };
+    let num_blocks = event.rounds.max(1) as usize;
+    let do_round = event.rounds > 0;
+
+    let mut v: [u64; NUM_WORDS_V] = array_init::array_init(|s| {
+        if s < NUM_WORDS_H {
+            event.h[s]
+        } else {
+            IV[s - NUM_WORDS_H]
+        }
+    });
+    for (idx, t) in T_IDX.iter().zip(event.t) {
+        v[*idx] ^= t;
+    }
+    if event.f {
+        v[F_IDX] = !v[F_IDX];
+    }
+
+    let mut input_rlc = Value::known(F::ZERO);
+    for round_idx in 0..num_blocks {
+        let is_first = round_idx == 0;
+        let is_last = round_idx == num_blocks - 1;
+        let v_init = v;
+        let mut v_end = v;
+        let mut mixed = [0; NUM_WORDS_H];
+        let mut output = [0; NUM_WORDS_H];
+        let mut output_rlc = Value::known(F::ZERO);
+        for row_idx in 0..ROWS_PER_BLOCK {
+            let mut row = Blake2fRow::new(event, round_idx, is_last, input_rlc, output_rlc);
+            match row_kind(row_idx) {
+                RowKind::State(s) => {
+                    let word = if s < NUM_WORDS_H {
+                        row.x = event.h[s];
+                        Some(event.h[s])
+                    } else {
+                        T_IDX.iter().position(|idx| *idx == s).map(|k| event.t[k])
+                    };
+                    if let Some(word) = word {
+                        row.x_bits = word;
+                        if is_first {
+                            input_rlc = absorb(input_rlc, word);
+                        }
+                    }
+                    row.r = v_init[s];
+                }
+                RowKind::Message(j) => {
+                    row.x = event.m[j];
+                    row.x_bits = event.m[j];
+                    if is_first {
+                        input_rlc = absorb(input_rlc, event.m[j]);
+                    }
+                }
+                RowKind::Operation(k) => {
+                    let op = operation(k);
+                    row.x = v[op.src[0]];
+                    row.y = v[op.src[1]];
+                    if let Some(rot) = op.rot {
+                        row.r = (row.x ^ row.y).rotate_right(ROTATIONS[rot] as u32);
+                        row.x_bits = row.x;
+                        row.y_bits = row.y;
+                    } else {
+                        row.z = op
+                            .msg
+                            .map_or(0, |j| event.m[SIGMA[round_idx % NUM_SCHEDULES][j]]);
+                        let sum = row.x as u128 + row.y as u128 + row.z as u128;
+                        row.r = sum as u64;
+                        row.carry = (sum >> NUM_BITS_PER_WORD) as u64;
+                        row.x_bits = row.r;
+                    }
+                    v[op.dst] = row.r;
+                }
+                RowKind::End(s) => {
+                    row.x = v[s];
+                    row.y = v_init[s];
+                    row.r = if do_round { row.x } else { row.y };
+                    v_end[s] = row.r;
+                }
+                RowKind::Mix(i) => {
+                    row.x = v_end[i];
+                    row.y = v_end[i + NUM_WORDS_H];
+                    row.r = row.x ^ row.y;
+                    row.x_bits = row.x;
+                    row.y_bits = row.y;
+                    mixed[i] = row.r;
+                }
+                RowKind::Output(i) => {
+                    row.x = event.h[i];
+                    row.y = mixed[i];
+                    row.r = row.x ^ row.y;
+                    row.x_bits = row.x;
+                    row.y_bits = row.y;
+                    output[i] = row.r;
+                    output_rlc = absorb(output_rlc, row.r);
+                    row.is_enabled = is_last && row_idx == ROWS_PER_BLOCK - 1;
+                }
+            }
+            row.input_rlc = input_rlc;
+            row.output_rlc = output_rlc;
+            rows.push(row);
+        }
+        if is_last {
+            debug_assert_eq!(output, event.output);
+        }
+        v = v_end;
+    }
+}
+
+/// Return the rows of the compressions `events`, padded with compressions
+/// without rounds of zero inputs to `max_blocks` blocks.
+pub(crate) fn multi_blake2f<F: Field>(
+    events: &[Blake2fEvent],
+    challenges: Challenges<Value<F>>,
+    max_blocks: usize,
+) -> Result<Vec<Blake2fRow<F>>, Error> {
+    let num_blocks = Blake2fCircuit::<F>::num_blocks(events);
+    if num_blocks > max_blocks {
+        error!(
+            "number of blocks of the compressions = {} > max_blocks = {}",
+            num_blocks, max_blocks
+        );
+        return Err(Error::Synthesis);
+    }
+
+    let padding = Blake2fEvent {
+        output: blake2f_compress(0, [0; NUM_WORDS_H], [0; NUM_WORDS_M], [0; 2], false),
+        ..Default::default()
+    };
+    let mut rows = Vec::with_capacity(max_blocks * ROWS_PER_BLOCK);
+    for event in events {
+        blake2f(&mut rows, event, challenges);
+    }
+    for _ in num_blocks..max_blocks {
+        blake2f(&mut rows, &padding, challenges);
+    }
+
+    // The state rows of a block hold the final v of the previous block.
+    for block in 1..max_blocks {
+        for s in 0..NUM_WORDS_V {
+            rows[block * ROWS_PER_BLOCK + state_row(s)].y =
+                rows[(block - 1) * ROWS_PER_BLOCK + END_START + s].r;
+        }
+    }
+    Ok(rows)
+}
+
+impl<F: Field> Blake2fCircuitConfig<F> {
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        witness: &[Blake2fRow<F>],
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "assign blake2f rows",
+            |mut region| {
+                let mut cells = Vec::with_capacity(witness.len());
+                for (offset, row) in witness.iter().enumerate() {
+                    cells.push(self.set_row(&mut region, offset, row)?);
+                }
+
+                // Copy the operands of the rows from the latest values of the
+                // words of v, or of the output.
+                for (idx, block) in cells.chunks(ROWS_PER_BLOCK).enumerate() {
+                    if idx > 0 {
+                        let prev_block = &cells[(idx - 1) * ROWS_PER_BLOCK..];
+                        for s in 0..NUM_WORDS_V {
+                            region.constrain_equal(
+                                block[state_row(s)][1].cell(),
+                                prev_block[END_START + s][2].cell(),
+                            )?;
+                        }
+                    }
+                    let mut v = (0..NUM_WORDS_V)
+                        .map(|s| &block[state_row(s)][2])
+                        .collect_vec();
+                    for k in 0..NUM_OPS {
+                        let op = operation(k);
+                        let row = &block[OPS_START + k];
+                        region.constrain_equal(row[0].cell(), v[op.src[0]].cell())?;
+                        region.constrain_equal(row[1].cell(), v[op.src[1]].cell())?;
+                        v[op.dst] = &row[2];
+                    }
+                    for (s, v) in v.iter().enumerate() {
+                        let row = &block[END_START + s];
+                        region.constrain_equal(row[0].cell(), v.cell())?;
+                        region.constrain_equal(row[1].cell(), block[state_row(s)][2].cell())?;
+                    }
+                    for i in 0..NUM_WORDS_H {
+                        let mix = &block[OUT_START + 2 * i];
+                        let output = &block[OUT_START + 2 * i + 1];
+                        region.constrain_equal(mix[0].cell(), block[END_START + i][2].cell())?;
+                        region.constrain_equal(
+                            mix[1].cell(),
+                            block[END_START + NUM_WORDS_H + i][2].cell(),
+                        )?;
+                        region.constrain_equal(output[0].cell(), block[state_row(i)][0].cell())?;
+                        region.constrain_equal(output[1].cell(), mix[2].cell())?;
+                    }
+                }
+
+                self.blake2f_table.annotate_columns_in_region(&mut region);
+                self.annotate_circuit(&mut region);
+                Ok(())
+            },
+        )
+    }
+
+    fn set_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &Blake2fRow<F>,
+    ) -> Result<[AssignedCell<F, F>; 3], Error> {
+        // Fixed selectors, which only depend on the position of the row in its
+        // block.
+        let row_idx = offset % ROWS_PER_BLOCK;
+        let kind = row_kind(row_idx);
+        let op = match kind {
+            RowKind::Operation(k) => Some(operation(k)),
+            _ => None,
+        };
+        let rot = match kind {
+            RowKind::Mix(_) | RowKind::Output(_) => Some(OUTPUT_ROTATION),
+            _ => op.and_then(|op| op.rot),
+        };
+        let is_input = match kind {
+            RowKind::State(s) => s < NUM_WORDS_H || T_IDX.contains(&s),
+            RowKind::Message(_) => true,
+            _ => false,
+        };
+        let mut fixed = vec![
+            ("q_enable", self.q_enable, true),
+            ("q_first", self.q_first, offset == 0),
+            ("q_block_start", self.q_block_start, row_idx == 0),
+            ("q_last", self.q_last, row_idx == ROWS_PER_BLOCK - 1),
+            ("q_input", self.q_input, is_input),
+            (
+                "q_add",
+                self.q_add,
+                matches!(op, Some(op) if op.rot.is_none()),
+            ),
+            ("q_end", self.q_end, matches!(kind, RowKind::End(_))),
+            (
+                "q_output",
+                self.q_output,
+                matches!(kind, RowKind::Output(_)),
+            ),
+        ];
+        for (s, column) in self.q_state.iter().enumerate() {
+            fixed.push(("q_state", *column, kind == RowKind::State(s)));
+        }
+        for (j, column) in self.q_m.iter().enumerate() {
+            fixed.push(("q_m", *column, kind == RowKind::Message(j)));
+        }
+        for (j, column) in self.q_msg.iter().enumerate() {
+            let is_msg = matches!(op, Some(op) if op.msg == Some(j));
+            fixed.push(("q_msg", *column, is_msg));
+        }
+        for (idx, column) in self.q_rots.iter().enumerate() {
+            fixed.push(("q_rot", *column, rot == Some(idx)));
+        }
+        for (name, column, value) in fixed {
+            region.assign_fixed(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(F::from(value as u64)),
+            )?;
+        }
+
+        // Bits
+        for (name, columns, value) in [
+            ("x", &self.x_bits, row.x_bits),
+            ("y", &self.y_bits, row.y_bits),
+        ] {
+            for (i, column) in columns.iter().enumerate() {
+                region.assign_advice(
+                    || format!("assign {} bit {} {}", name, i, offset),
+                    *column,
+                    offset,
+                    || Value::known(F::from((value >> i) & 1)),
+                )?;
+            }
+        }
+
+        // Words of the row
+        let cells = [
+            ("x", self.x, row.x),
+            ("y", self.y, row.y),
+            ("r", self.r, row.r),
+        ]
+        .into_iter()
+        .map(|(name, column, value)| {
+            region.assign_advice(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(F::from(value)),
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+        for (name, column, value) in [("z", self.z, row.z), ("carry", self.carry, row.carry)] {
+            region.assign_advice(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(F::from(value)),
+            )?;
+        }
+
+        // Call and block context
+        let rounds = F::from(row.rounds as u64);
+        let mut context = vec![
+            (
+                "rounds_inv",
+                self.rounds_inv,
+                rounds.invert().unwrap_or(F::ZERO),
+            ),
+            ("do_round", self.do_round, F::from((row.rounds > 0) as u64)),
+            ("is_first", self.is_first, F::from(row.is_first as u64)),
+            ("is_last", self.is_last, F::from(row.is_last as u64)),
+            ("round_idx", self.round_idx, F::from(row.round_idx as u64)),
+        ];
+        for (column, h) in self.h.iter().zip(row.h) {
+            context.push(("h", *column, F::from(h)));
+        }
+        for (column, m) in self.m.iter().zip(row.m) {
+            context.push(("m", *column, F::from(m)));
+        }
+        for (k, column) in self.schedules.iter().enumerate() {
+            let is_schedule = row.round_idx % NUM_SCHEDULES == k;
+            context.push(("schedule", *column, F::from(is_schedule as u64)));
+        }
+        for (name, column, value) in context {
+            region.assign_advice(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(value),
+            )?;
+        }
+
+        for (column, value) in <Blake2fTable as LookupTable<F>>::advice_columns(&self.blake2f_table)
+            .into_iter()
+            .zip_eq([
+                Value::known(F::from(row.is_enabled as u64)),
+                Value::known(rounds),
+                row.input_rlc,
+                Value::known(F::from(row.f as u64)),
+                row.output_rlc,
+            ])
+        {
+            region.assign_advice(
+                || format!("assign blake2f table {}", offset),
+                column,
+                offset,
+                || value,
+            )?;
+        }
+
+        Ok(cells.try_into().unwrap())
+    }
+
+    fn annotate_circuit(&self, region: &mut Region<F>) {
+        region.name_column(|| "BLAKE2F_q_enable", self.q_enable);
+        region.name_column(|| "BLAKE2F_q_first", self.q_first);
+        region.name_column(|| "BLAKE2F_q_block_start", self.q_block_start);
+        region.name_column(|| "BLAKE2F_q_last", self.q_last);
+        region.name_column(|| "BLAKE2F_q_input", self.q_input);
+        region.name_column(|| "BLAKE2F_q_add", self.q_add);
+        region.name_column(|| "BLAKE2F_q_end", self.q_end);
+        region.name_column(|| "BLAKE2F_q_output", self.q_output);
+        region.name_column(|| "BLAKE2F_x", self.x);
+        region.name_column(|| "BLAKE2F_y", self.y);
+        region.name_column(|| "BLAKE2F_z", self.z);
+        region.name_column(|| "BLAKE2F_r", self.r);
+        region.name_column(|| "BLAKE2F_carry", self.carry);
+        region.name_column(|| "BLAKE2F_do_round", self.do_round);
+        region.name_column(|| "BLAKE2F_is_first", self.is_first);
+        region.name_column(|| "BLAKE2F_is_last", self.is_last);
+        region.name_column(|| "BLAKE2F_round_idx", self.round_idx);
+    }
+}
+
+/// Blake2f circuit, proving the compressions of the Blake2fTable
+#[derive(Clone, Default, Debug)]
+pub struct Blake2fCircuit<F: Field> {
+    /// Max number of blocks, each taken by a round or a compression without
+    /// rounds
+    pub max_blocks: usize,
+    /// Compressions to prove
+    pub events: Vec<Blake2fEvent>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> Blake2fCircuit<F> {
+    /// Return a new Blake2fCircuit with `max_blocks` blocks.
+    pub fn new(max_blocks: usize, events: Vec<Blake2fEvent>) -> Self {
+        Self {
+            max_blocks,
+            events,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the minimum number of rows required to prove `max_blocks`
+    /// blocks.
+    pub fn min_num_rows(max_blocks: usize) -> usize {
+        max_blocks * ROWS_PER_BLOCK
+    }
+
+    /// Return the number of blocks required to prove `events`.
+    pub fn num_blocks(events: &[Blake2fEvent]) -> usize {
+        events
+            .iter()
+            .map(|event| event.rounds.max(1) as usize)
+            .sum()
+    }
+}
+
+impl<F: Field> SubCircuit<F> for Blake2fCircuit<F> {
+    type Config = Blake2fCircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // The advice columns are only queried at the current and previous
+        // rows, so returns 6 unusable rows.
+        6
+    }
+
+    /// The `block.circuits_params.max_blake2f_blocks` parameter sets up the
+    /// circuit to support a fixed number of blocks; when 0, the circuit only
+    /// has the blocks required by the compressions of the blake2f precompile
+    /// calls.
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        let max_blocks = match block.circuits_params.max_blake2f_blocks {
+            0 => Self::num_blocks(&block.blake2f_events),
+            max_blocks => max_blocks,
+        };
+        Self::new(max_blocks, block.blake2f_events.clone())
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        (
+            Self::min_num_rows(Self::num_blocks(&block.blake2f_events)),
+            Self::min_num_rows(block.circuits_params.max_blake2f_blocks),
+        )
+    }
+
+    /// Make the assignments to the Blake2fCircuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let witness = multi_blake2f(&self.events, *challenges, self.max_blocks)?;
+        config.assign(layouter, &witness)
+    }
+}
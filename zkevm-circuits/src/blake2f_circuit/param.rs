@@ -0,0 +1,56 @@
+pub(crate) use bus_mapping::precompile::{BLAKE2F_IV as IV, BLAKE2F_SIGMA as SIGMA};
+
+pub(crate) const MAX_DEGREE: usize = 5;
+
+pub(crate) const NUM_BITS_PER_BYTE: usize = 8;
+pub(crate) const NUM_BYTES_PER_WORD: usize = 8;
+pub(crate) const NUM_BITS_PER_WORD: usize = NUM_BYTES_PER_WORD * NUM_BITS_PER_BYTE;
+/// Number of words of the state vector `h`
+pub(crate) const NUM_WORDS_H: usize = 8;
+/// Number of words of the message block `m`
+pub(crate) const NUM_WORDS_M: usize = 16;
+/// Number of words of the local work vector `v`
+pub(crate) const NUM_WORDS_V: usize = 16;
+/// Number of message schedules, used cyclically by the rounds
+pub(crate) const NUM_SCHEDULES: usize = SIGMA.len();
+/// Number of mixing functions G of a round, each made of 8 operations
+pub(crate) const NUM_G: usize = 8;
+pub(crate) const NUM_OPS_PER_G: usize = 8;
+pub(crate) const NUM_OPS: usize = NUM_G * NUM_OPS_PER_G;
+
+/// Every round takes a block of rows, a call without rounds taking a block
+/// whose operations are ignored:
+/// - a row per word of `h`, then of `m`, then of the rest of the initial local work vector, the
+///   words of `v` being held by the state rows,
+/// - a row per operation of the mixing functions,
+/// - a row per word of `v` at the end of the round,
+/// - two rows per word of the output, `h[i] ^ v[i] ^ v[i + 8]`.
+pub(crate) const M_START: usize = NUM_WORDS_H;
+pub(crate) const IV_START: usize = M_START + NUM_WORDS_M;
+pub(crate) const OPS_START: usize = IV_START + NUM_WORDS_V - NUM_WORDS_H;
+pub(crate) const END_START: usize = OPS_START + NUM_OPS;
+pub(crate) const OUT_START: usize = END_START + NUM_WORDS_V;
+pub(crate) const ROWS_PER_BLOCK: usize = OUT_START + 2 * NUM_WORDS_H;
+
+/// Indices of the words of `v` of the offset counter `t`
+pub(crate) const T_IDX: [usize; 2] = [12, 13];
+/// Index of the word of `v` inverted on the final block
+pub(crate) const F_IDX: usize = 14;
+
+/// Indices of the words `a, b, c, d` of `v` mixed by each function G
+pub(crate) const G_IDX: [[usize; 4]; NUM_G] = [
+    [0, 4, 8, 12],
+    [1, 5, 9, 13],
+    [2, 6, 10, 14],
+    [3, 7, 11, 15],
+    [0, 5, 10, 15],
+    [1, 6, 11, 12],
+    [2, 7, 8, 13],
+    [3, 4, 9, 14],
+];
+
+/// Amounts of the right rotations of the xor operations of G, and of the
+/// xor operations of the output
+pub(crate) const ROTATIONS: [usize; 5] = [32, 24, 16, 63, 0];
+/// Index in `ROTATIONS` of the xor operations of the output
+pub(crate) const OUTPUT_ROTATION: usize = 4;
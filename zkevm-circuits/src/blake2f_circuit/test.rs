@@ -0,0 +1,143 @@
+use super::*;
+use crate::{
+    evm_circuit::util::rlc,
+    util::{log2_ceil, unusable_rows},
+};
+use halo2_proofs::{
+    dev::{CellValue, MockProver},
+    halo2curves::bn256::Fr,
+    plonk::Circuit,
+};
+use itertools::izip;
+
+#[test]
+fn blake2f_circuit_unusable_rows() {
+    assert_eq!(
+        Blake2fCircuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, Blake2fCircuit::<Fr>>(()),
+    )
+}
+
+fn run(circuit: &Blake2fCircuit<Fr>) -> MockProver<Fr> {
+    let k = log2_ceil(
+        Blake2fCircuit::<Fr>::unusable_rows()
+            + Blake2fCircuit::<Fr>::min_num_rows(circuit.max_blocks),
+    );
+    MockProver::<Fr>::run(k, circuit, vec![]).unwrap()
+}
+
+fn assigned<F: Field>(cv: &CellValue<F>) -> F {
+    match *cv {
+        CellValue::Assigned(f) => f,
+        _ => F::ZERO,
+    }
+}
+
+/// Return the compression of a block of BLAKE2b-512 with `rounds` rounds.
+fn event(rounds: u32, f: bool) -> Blake2fEvent {
+    let mut h = IV;
+    h[0] ^= 0x01010040;
+    let m = array_init::array_init(|i| 0x0123456789abcdef_u64.rotate_left(5 * i as u32));
+    let t = [128 * rounds as u64, 0];
+    Blake2fEvent {
+        rounds,
+        h,
+        m,
+        t,
+        f,
+        output: blake2f_compress(rounds, h, m, t, f),
+    }
+}
+
+fn verify(max_blocks: usize, events: Vec<Blake2fEvent>) {
+    let circuit = Blake2fCircuit::<Fr>::new(max_blocks, events.clone());
+    let prover = run(&circuit);
+    assert_eq!(prover.verify(), Ok(()));
+
+    // Extract the rows of the table with the compressions.
+    let (config, challenges) = Blake2fCircuit::configure(&mut ConstraintSystem::<Fr>::default());
+    let input_challenge = prover.get_challenge(challenges.keccak_input());
+    let table = &config.blake2f_table;
+    let compressions = izip!(
+        prover.advice_values(table.is_enabled),
+        prover.advice_values(table.rounds),
+        prover.advice_values(table.input_rlc),
+        prover.advice_values(table.f),
+        prover.advice_values(table.output_rlc),
+    )
+    .filter(|(is_enabled, ..)| assigned(*is_enabled) == Fr::from(1))
+    .map(|(_, rounds, input_rlc, f, output_rlc)| {
+        (
+            assigned(rounds),
+            assigned(input_rlc),
+            assigned(f),
+            assigned(output_rlc),
+        )
+    })
+    .collect_vec();
+
+    // The compressions are followed by compressions without rounds of zero
+    // inputs up to `max_blocks` blocks.
+    let zero_inputs = Blake2fEvent {
+        output: blake2f_compress(0, [0; 8], [0; 16], [0; 2], false),
+        ..Default::default()
+    };
+    let padding = vec![zero_inputs; max_blocks - Blake2fCircuit::<Fr>::num_blocks(&events)];
+    assert_eq!(compressions.len(), events.len() + padding.len());
+    for (event, compression) in events.iter().chain(padding.iter()).zip(compressions) {
+        input_challenge
+            .map(|challenge| {
+                (
+                    rlc::value(event.input_bytes().iter().rev(), challenge),
+                    rlc::value(event.output_bytes().iter().rev(), challenge),
+                )
+            })
+            .assert_if_known(|(input_rlc, output_rlc)| {
+                (*input_rlc, *output_rlc) == (compression.1, compression.3)
+            });
+        assert_eq!(
+            (compression.0, compression.2),
+            (Fr::from(event.rounds as u64), Fr::from(event.f as u64))
+        );
+    }
+}
+
+#[test]
+fn blake2f_circuit_simple() {
+    // 12 rounds use the message schedules cyclically.
+    let events = vec![
+        event(12, true),
+        event(0, true),
+        event(1, false),
+        event(3, true),
+        event(0, false),
+    ];
+    verify(Blake2fCircuit::<Fr>::num_blocks(&events) + 2, events);
+}
+
+#[test]
+fn blake2f_circuit_no_inputs() {
+    verify(1, vec![]);
+}
+
+#[test]
+fn blake2f_circuit_exceeded_max_blocks() {
+    let circuit = Blake2fCircuit::<Fr>::new(2, vec![event(3, true)]);
+    let k = log2_ceil(
+        Blake2fCircuit::<Fr>::unusable_rows()
+            + Blake2fCircuit::<Fr>::min_num_rows(circuit.max_blocks),
+    );
+    assert!(MockProver::<Fr>::run(k, &circuit, vec![]).is_err());
+}
+
+#[test]
+fn blake2f_circuit_variadic_size_check() {
+    // The fixed columns only depend on `max_blocks`.
+    let prover_1 = run(&Blake2fCircuit::<Fr>::new(4, vec![event(1, true)]));
+    let prover_2 = run(&Blake2fCircuit::<Fr>::new(
+        4,
+        vec![event(0, false), event(2, true)],
+    ));
+    assert_eq!(prover_1.fixed(), prover_2.fixed());
+    assert_eq!(prover_1.permutation(), prover_2.permutation());
+}
@@ -0,0 +1,124 @@
+use super::param::*;
+use eth_types::Field;
+use gadgets::util::Expr;
+use halo2_proofs::plonk::Expression;
+
+/// Bits of a word in little-endian
+pub(crate) type WordBits<F> = [Expression<F>; NUM_BITS_PER_WORD];
+
+/// Return the value of little-endian bits.
+pub(crate) fn compose<F: Field>(bits: &[Expression<F>]) -> Expression<F> {
+    bits.iter()
+        .rev()
+        .fold(0.expr(), |acc, bit| acc * 2.expr() + bit.clone())
+}
+
+/// Return the bits of `bits` rotated right by `n`.
+pub(crate) fn rotate_right<F: Field>(bits: &WordBits<F>, n: usize) -> WordBits<F> {
+    array_init::array_init(|i| bits[(i + n) % NUM_BITS_PER_WORD].clone())
+}
+
+/// Return the bits of `a ^ b`, of degree 2.
+pub(crate) fn xor<F: Field>(a: &WordBits<F>, b: &WordBits<F>) -> WordBits<F> {
+    array_init::array_init(|i| a[i].clone() + b[i].clone() - 2.expr() * a[i].clone() * b[i].clone())
+}
+
+/// Return the value of the word of `bits` xored with the constant `c`.
+pub(crate) fn xor_constant<F: Field>(bits: &WordBits<F>, c: u64) -> Expression<F> {
+    let xored: WordBits<F> = array_init::array_init(|i| {
+        if (c >> i) & 1 == 1 {
+            1.expr() - bits[i].clone()
+        } else {
+            bits[i].clone()
+        }
+    });
+    compose(&xored)
+}
+
+/// Return the RLC of the bytes of the word serialized in little endian, the
+/// first byte having the highest power of `randomness`.
+pub(crate) fn bytes_rlc<F: Field>(bits: &WordBits<F>, randomness: Expression<F>) -> Expression<F> {
+    bits.chunks(NUM_BITS_PER_BYTE).fold(0.expr(), |acc, byte| {
+        acc * randomness.clone() + compose(byte)
+    })
+}
+
+/// Operation of the mixing functions G, `v[dst] = v[src[0]] + v[src[1]] + z`
+/// with the message word `z` for an addition, and `v[dst] = (v[src[0]] ^
+/// v[src[1]]) >>> r` for a xor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Operation {
+    pub(crate) dst: usize,
+    pub(crate) src: [usize; 2],
+    /// Position in the message schedule of the word added, if any
+    pub(crate) msg: Option<usize>,
+    /// Index in `ROTATIONS` of the rotation of a xor, `None` for an addition
+    pub(crate) rot: Option<usize>,
+}
+
+/// Return the operation `k` of a round.
+pub(crate) fn operation(k: usize) -> Operation {
+    let g = k / NUM_OPS_PER_G;
+    let [a, b, c, d] = G_IDX[g];
+    let add = |dst, src, msg| Operation {
+        dst,
+        src,
+        msg,
+        rot: None,
+    };
+    let xor = |dst, src, rot| Operation {
+        dst,
+        src,
+        msg: None,
+        rot: Some(rot),
+    };
+    match k % NUM_OPS_PER_G {
+        0 => add(a, [a, b], Some(2 * g)),
+        1 => xor(d, [d, a], 0),
+        2 => add(c, [c, d], None),
+        3 => xor(b, [b, c], 1),
+        4 => add(a, [a, b], Some(2 * g + 1)),
+        5 => xor(d, [d, a], 2),
+        6 => add(c, [c, d], None),
+        _ => xor(b, [b, c], 3),
+    }
+}
+
+/// Kind of a row, given by its position in its block
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum RowKind {
+    /// Initial value of the word `s` of `v`
+    State(usize),
+    /// Word `j` of `m`
+    Message(usize),
+    /// Operation `k` of the round
+    Operation(usize),
+    /// Value of the word `s` of `v` at the end of the round
+    End(usize),
+    /// `v[i] ^ v[i + 8]` of the word `i` of the output
+    Mix(usize),
+    /// Word `i` of the output
+    Output(usize),
+}
+
+/// Return the kind of the row `row_idx` of a block.
+pub(crate) fn row_kind(row_idx: usize) -> RowKind {
+    match row_idx {
+        idx if idx < M_START => RowKind::State(idx),
+        idx if idx < IV_START => RowKind::Message(idx - M_START),
+        idx if idx < OPS_START => RowKind::State(NUM_WORDS_H + idx - IV_START),
+        idx if idx < END_START => RowKind::Operation(idx - OPS_START),
+        idx if idx < OUT_START => RowKind::End(idx - END_START),
+        idx if (idx - OUT_START) % 2 == 0 => RowKind::Mix((idx - OUT_START) / 2),
+        idx => RowKind::Output((idx - OUT_START) / 2),
+    }
+}
+
+/// Return the position in its block of the state row of the word `s` of `v`.
+pub(crate) fn state_row(s: usize) -> usize {
+    if s < NUM_WORDS_H {
+        s
+    } else {
+        IV_START + s - NUM_WORDS_H
+    }
+}
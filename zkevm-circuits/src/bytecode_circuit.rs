@@ -2,6 +2,7 @@
 
 #[cfg(any(test, feature = "test-circuits"))]
 mod dev;
+mod eof;
 /// Bytecode circuit tester
 #[cfg(test)]
 mod test;
@@ -14,16 +15,19 @@ use crate::{
         constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
         not, or, select,
     },
-    table::{BytecodeFieldTag, BytecodeTable, KeccakTable, LookupTable},
-    util::{
-        self, get_push_size,
-        word::{empty_code_hash_word_value, Word, Word32, WordExpr},
-        Challenges, Expr, SubCircuit, SubCircuitConfig,
-    },
+    table::{BytecodeFieldTag, BytecodeTable, KeccakTable, LookupTable, SpongeChip},
+    util::{get_push_size, word::Word, Challenges, Expr, SubCircuit, SubCircuitConfig},
     witness::{self},
 };
-use bus_mapping::state_db::{CodeDB, EMPTY_CODE_HASH_LE};
-use eth_types::{Bytecode, Field};
+use bus_mapping::state_db::CodeDB;
+use eof::{EofConfig, EofRow};
+use eth_types::{
+    evm_types::{
+        eof::{is_eof, EofHeader},
+        Hardfork, MAX_CODE_SIZE,
+    },
+    Bytecode, Field,
+};
 use gadgets::is_zero::{IsZeroChip, IsZeroInstruction};
 use halo2_proofs::{
     circuit::{Layouter, Region, Value},
@@ -33,7 +37,7 @@ use halo2_proofs::{
     poly::Rotation,
 };
 use itertools::Itertools;
-use std::{iter, ops::Deref, vec};
+use std::{collections::HashSet, iter, marker::PhantomData, ops::Deref, vec};
 
 const PUSH_TABLE_WIDTH: usize = 2;
 
@@ -49,6 +53,9 @@ pub(crate) struct BytecodeCircuitRow<F: Field> {
     value_rlc: Value<F>,
     length: F,
     push_data_size: F,
+    /// Difference between the maximum length and the length of the bytecode
+    length_left: F,
+    eof: EofRow<F>,
 }
 impl<F: Field> BytecodeCircuitRow<F> {
     #[cfg(test)]
@@ -63,15 +70,19 @@ impl<F: Field> BytecodeCircuitRow<F> {
             value_rlc: Value::known(F::ZERO),
             length: F::ZERO,
             push_data_size: F::ZERO,
+            length_left: F::ZERO,
+            eof: EofRow::default(),
         }
     }
 
-    /// Padding must be a header, according to the q_last constraints
-    fn pad() -> Self {
+    /// Padding must be a header, according to the q_last constraints, with
+    /// the code hash of the empty code in the table `S`.
+    fn pad<S: SpongeChip<F>>() -> Self {
         Self {
-            code_hash: empty_code_hash_word_value(),
+            code_hash: S::digest(&[]).into_value(),
             tag: F::from(BytecodeFieldTag::Header as u64),
             value_rlc: Value::known(F::ZERO),
+            length_left: F::from(MAX_CODE_SIZE),
             ..Default::default()
         }
     }
@@ -85,12 +96,37 @@ impl<F: Field> BytecodeCircuitRow<F> {
 #[derive(Clone, Default, Debug)]
 pub(crate) struct BytecodeCircuitAssignment<F: Field>(pub(crate) Vec<BytecodeCircuitRow<F>>);
 
-impl<F: Field> From<Vec<Bytecode>> for BytecodeCircuitAssignment<F> {
-    fn from(codes: Vec<Bytecode>) -> Self {
+impl<F: Field> BytecodeCircuitAssignment<F> {
+    /// Unroll the bytecodes, with their code hashes in the table `S`.  A
+    /// bytecode is unrolled only at its first occurrence, as the rows are
+    /// looked up by code hash.
+    fn new<S: SpongeChip<F>>(
+        codes: impl IntoIterator<Item = Bytecode>,
+        hardfork: Hardfork,
+    ) -> Self {
         let mut rows = vec![];
-        for bytecode in codes.iter() {
-            let code_hash = util::word::Word::from(bytecode.hash()).into_value();
+        let mut code_hashes = HashSet::new();
+        for bytecode in codes {
+            if !code_hashes.insert(bytecode.hash()) {
+                continue;
+            }
+            let code = bytecode.code();
+            let code_hash = S::digest(&code).into_value();
             let code_size = bytecode.codesize();
+            // An oversized bytecode wraps around the field, so that the
+            // witness fails the range check.  The initcode is held to the same
+            // limit, as the circuit can't tell it from the deployed code.
+            let length_left = F::from(MAX_CODE_SIZE) - F::from(code_size as u64);
+            // The push data analysis of an EOF container restarts at its code
+            // section.
+            let (eof_rows, code_start) = if hardfork.is_eof_active() && is_eof(&code) {
+                (
+                    EofRow::rows(&code),
+                    Some(EofHeader::read(&code).header_size()),
+                )
+            } else {
+                (vec![EofRow::default(); code.len() + 1], None)
+            };
             let head = BytecodeCircuitRow {
                 code_hash,
                 tag: F::from(BytecodeFieldTag::Header as u64),
@@ -101,11 +137,17 @@ impl<F: Field> From<Vec<Bytecode>> for BytecodeCircuitAssignment<F> {
                 value_rlc: Value::known(F::ZERO),
                 length: F::from(code_size as u64),
                 push_data_size: F::ZERO,
+                length_left,
+                eof: eof_rows[0].clone(),
             };
             rows.push(head);
             let mut push_data_left = 0;
 
-            for (index, &(value, is_code)) in bytecode.code_vec().iter().enumerate() {
+            for (index, &value) in code.iter().enumerate() {
+                if code_start == Some(index) {
+                    push_data_left = 0;
+                }
+                let is_code = push_data_left == 0;
                 let push_data_size = get_push_size(value);
                 let value = F::from(value.into());
 
@@ -119,6 +161,8 @@ impl<F: Field> From<Vec<Bytecode>> for BytecodeCircuitAssignment<F> {
                     value_rlc: Value::unknown(),
                     length: F::from(code_size as u64),
                     push_data_size: F::from(push_data_size),
+                    length_left,
+                    eof: eof_rows[index + 1].clone(),
                 };
                 rows.push(body);
                 push_data_left = if is_code {
@@ -132,16 +176,30 @@ impl<F: Field> From<Vec<Bytecode>> for BytecodeCircuitAssignment<F> {
     }
 }
 
+impl<F: Field> BytecodeCircuitAssignment<F> {
+    /// Unroll the bytecodes of a CodeDB under the rules of `hardfork`, with
+    /// their code hashes in the table `S`.
+    fn from_code_db<S: SpongeChip<F>>(code_db: CodeDB, hardfork: Hardfork) -> Self {
+        // CodeDB use hash maps, so the bytecodes will be reordered.
+        Self::new::<S>(code_db, hardfork)
+    }
+}
+
+impl<F: Field> From<Vec<Bytecode>> for BytecodeCircuitAssignment<F> {
+    fn from(codes: Vec<Bytecode>) -> Self {
+        Self::new::<KeccakTable>(codes, Hardfork::default())
+    }
+}
+
 impl<F: Field> From<CodeDB> for BytecodeCircuitAssignment<F> {
     fn from(code_db: CodeDB) -> Self {
-        // CodeDB use hash maps, so the bytecodes will be reordered.
-        code_db.into_iter().collect_vec().into()
+        Self::from_code_db::<KeccakTable>(code_db, Hardfork::default())
     }
 }
 
 impl<F: Field> From<Vec<Vec<u8>>> for BytecodeCircuitAssignment<F> {
     fn from(codes: Vec<Vec<u8>>) -> Self {
-        // We don't go through BytecodeCollection struct to preserve bytecode order.
+        // We don't go through CodeDB to preserve bytecode order.
         codes
             .iter()
             .map(|bytes| Bytecode::from(bytes.clone()))
@@ -159,8 +217,9 @@ impl<F: Field> Deref for BytecodeCircuitAssignment<F> {
 }
 
 #[derive(Clone, Debug)]
-/// Bytecode circuit configuration
-pub struct BytecodeCircuitConfig<F> {
+/// Bytecode circuit configuration, binding the bytecodes to their code hashes
+/// in the table `S`
+pub struct BytecodeCircuitConfig<F, S = KeccakTable> {
     q_enable: Column<Fixed>,
     q_first: Column<Fixed>,
     q_last: Column<Fixed>,
@@ -169,33 +228,41 @@ pub struct BytecodeCircuitConfig<F> {
     value_rlc: Column<Advice>,
     length: Column<Advice>,
     push_data_size: Column<Advice>,
+    length_left: [Column<Advice>; 2],
     push_data_left_is_zero: IsZeroChip<F>,
     index_length_diff_is_zero: IsZeroChip<F>,
     push_table: [Column<Fixed>; PUSH_TABLE_WIDTH],
+    /// EOF validation, enabled since Osaka
+    eof: Option<EofConfig>,
     // External tables
-    pub(crate) keccak_table: KeccakTable,
+    pub(crate) code_hash_table: S,
 }
 
 /// Circuit configuration arguments
-pub struct BytecodeCircuitConfigArgs<F: Field> {
+pub struct BytecodeCircuitConfigArgs<F: Field, S = KeccakTable> {
     /// BytecodeTable
     pub bytecode_table: BytecodeTable,
-    /// KeccakTable
-    pub keccak_table: KeccakTable,
+    /// Table of the code hashes: the KeccakTable for the code hashes of the
+    /// accounts, or e.g. the PoseidonTable for the Poseidon code hashes of an
+    /// L2
+    pub code_hash_table: S,
     /// Challenges
     pub challenges: Challenges<Expression<F>>,
+    /// Hardfork whose rules are constrained
+    pub hardfork: Hardfork,
 }
 
-impl<F: Field> SubCircuitConfig<F> for BytecodeCircuitConfig<F> {
-    type ConfigArgs = BytecodeCircuitConfigArgs<F>;
+impl<F: Field, S: SpongeChip<F>> SubCircuitConfig<F> for BytecodeCircuitConfig<F, S> {
+    type ConfigArgs = BytecodeCircuitConfigArgs<F, S>;
 
     /// Return a new BytecodeCircuitConfig
     fn new(
         meta: &mut ConstraintSystem<F>,
         Self::ConfigArgs {
             bytecode_table,
-            keccak_table,
+            code_hash_table,
             challenges,
+            hardfork,
         }: Self::ConfigArgs,
     ) -> Self {
         let q_enable = meta.fixed_column();
@@ -206,13 +273,25 @@ impl<F: Field> SubCircuitConfig<F> for BytecodeCircuitConfig<F> {
         let value_rlc = meta.advice_column_in(SecondPhase);
         let length = meta.advice_column();
         let push_data_size = meta.advice_column();
+        let length_left = array_init::array_init(|_| meta.advice_column());
         let push_data_left_inv = meta.advice_column();
         let index_length_diff_inv = meta.advice_column();
         let push_table = array_init::array_init(|_| meta.fixed_column());
+        let eof = hardfork.is_eof_active().then(|| {
+            EofConfig::configure(
+                meta,
+                q_enable,
+                q_last,
+                &bytecode_table,
+                length,
+                push_data_left,
+                push_data_size,
+            )
+        });
 
         // annotate columns
         bytecode_table.annotate_columns(meta);
-        keccak_table.annotate_columns(meta);
+        code_hash_table.annotate_columns(meta);
         push_table.iter().enumerate().for_each(|(idx, &col)| {
             meta.annotate_lookup_any_column(col, || format!("push_table_{}", idx))
         });
@@ -293,6 +372,8 @@ impl<F: Field> SubCircuitConfig<F> for BytecodeCircuitConfig<F> {
         // When is_header ->
         // assert cur.index == 0
         // assert cur.value == cur.length
+        // assert cur.length + cur.length_left == MAX_CODE_SIZE
+        // assert u8_table_lookup(cur.length_left[0]) and u8_table_lookup(cur.length_left[1])
         meta.create_gate("Header row", |meta| {
             let mut cb = BaseConstraintBuilder::default();
 
@@ -307,12 +388,34 @@ impl<F: Field> SubCircuitConfig<F> for BytecodeCircuitConfig<F> {
                 meta.query_advice(length, Rotation::cur()),
             );
 
+            let [lo, hi] = length_left.map(|column| meta.query_advice(column, Rotation::cur()));
+            cb.require_equal(
+                "cur.length + cur.length_left == MAX_CODE_SIZE",
+                meta.query_advice(length, Rotation::cur()) + lo + hi * 256.expr(),
+                MAX_CODE_SIZE.expr(),
+            );
+
             cb.gate(and::expr(vec![
                 meta.query_fixed(q_enable, Rotation::cur()),
                 not::expr(meta.query_fixed(q_last, Rotation::cur())),
                 is_header(meta),
             ]))
         });
+        for column in length_left {
+            // The bytes of the push table are a range table of 8 bits.
+            meta.lookup_any("u8_table_lookup(cur.length_left)", |meta| {
+                let enable = and::expr(vec![
+                    meta.query_fixed(q_enable, Rotation::cur()),
+                    not::expr(meta.query_fixed(q_last, Rotation::cur())),
+                    is_header(meta),
+                ]);
+
+                vec![(
+                    enable * meta.query_advice(column, Rotation::cur()),
+                    meta.query_fixed(push_table[0], Rotation::cur()),
+                )]
+            });
+        }
 
         // When is_byte ->
         // assert push_data_size_table_lookup(cur.value, cur.push_data_size)
@@ -366,8 +469,7 @@ impl<F: Field> SubCircuitConfig<F> for BytecodeCircuitConfig<F> {
                 meta.query_advice(length, Rotation::cur()),
             );
 
-            let empty_hash_word: Word<Expression<F>> =
-                Word32::new(*EMPTY_CODE_HASH_LE).to_expr().to_word();
+            let empty_hash_word = S::digest(&[]).map(Expression::Constant);
 
             cb.require_equal_word(
                 "assert cur.hash == EMPTY_HASH",
@@ -436,7 +538,9 @@ impl<F: Field> SubCircuitConfig<F> for BytecodeCircuitConfig<F> {
         // assert next.index == cur.index + 1
         // assert next.hash == cur.hash
         // assert next.value_rlc == cur.value_rlc * randomness + next.value
-        // if cur.is_code:
+        // if next starts the code section of an EOF container:
+        //     assert next.push_data_left == 0 (in the EOF gates)
+        // elif cur.is_code:
         //     assert next.push_data_left == cur.push_data_size
         // else:
         //     assert next.push_data_left == cur.push_data_left - 1
@@ -470,15 +574,23 @@ impl<F: Field> SubCircuitConfig<F> for BytecodeCircuitConfig<F> {
                     + meta.query_advice(value, Rotation::next()),
             );
 
-            cb.require_equal(
-                "next.push_data_left == cur.is_code ? cur.push_data_size : cur.push_data_left - 1",
-                meta.query_advice(push_data_left, Rotation::next()),
-                select::expr(
-                    meta.query_advice(bytecode_table.is_code, Rotation::cur()),
-                    meta.query_advice(push_data_size, Rotation::cur()),
-                    meta.query_advice(push_data_left, Rotation::cur()) - 1.expr(),
-                ),
+            let push_data_left_next = meta.query_advice(push_data_left, Rotation::next());
+            let expected_push_data_left = select::expr(
+                meta.query_advice(bytecode_table.is_code, Rotation::cur()),
+                meta.query_advice(push_data_size, Rotation::cur()),
+                meta.query_advice(push_data_left, Rotation::cur()) - 1.expr(),
             );
+            let require_push_data_left = |cb: &mut BaseConstraintBuilder<F>| {
+                cb.require_equal(
+                    "next.push_data_left == cur.is_code ? cur.push_data_size : cur.push_data_left - 1",
+                    push_data_left_next,
+                    expected_push_data_left,
+                )
+            };
+            match eof.as_ref().map(|eof| eof.is_code_start(meta)) {
+                Some(is_code_start) => cb.condition(not::expr(is_code_start), require_push_data_left),
+                None => require_push_data_left(&mut cb),
+            }
 
             cb.gate(and::expr(vec![
                 meta.query_fixed(q_enable, Rotation::cur()),
@@ -506,7 +618,7 @@ impl<F: Field> SubCircuitConfig<F> for BytecodeCircuitConfig<F> {
 
         // When is_byte_to_header ->
         // assert cur.index + 1 == cur.length
-        // assert keccak256_table_lookup(cur.hash, cur.length, cur.value_rlc)
+        // assert code_hash_table_lookup(cur.hash, cur.length, cur.value_rlc)
         meta.create_gate("Byte to Header row", |meta| {
             let mut cb = BaseConstraintBuilder::default();
 
@@ -523,29 +635,17 @@ impl<F: Field> SubCircuitConfig<F> for BytecodeCircuitConfig<F> {
             ]))
         });
         meta.lookup_any(
-            "keccak256_table_lookup(cur.value_rlc, cur.length, cur.hash_word)",
+            "code_hash_table_lookup(cur.value_rlc, cur.length, cur.hash_word)",
             |meta| {
                 let enable = and::expr(vec![
                     meta.query_fixed(q_enable, Rotation::cur()),
                     not::expr(meta.query_fixed(q_last, Rotation::cur())),
                     is_byte_to_header(meta),
                 ]);
-
-                let mut constraints = vec![(
-                    enable.clone(),
-                    meta.query_advice(keccak_table.is_enabled, Rotation::cur()),
-                )];
-
-                for (circuit_column, table_column) in
-                    keccak_table.match_columns(value_rlc, length, bytecode_table.code_hash)
-                {
-                    constraints.push((
-                        enable.clone() * meta.query_advice(circuit_column, Rotation::cur()),
-                        meta.query_advice(table_column, Rotation::cur()),
-                    ))
-                }
-
-                constraints
+                let value_rlc = meta.query_advice(value_rlc, Rotation::cur());
+                let length = meta.query_advice(length, Rotation::cur());
+                let code_hash = bytecode_table.code_hash.query_advice(meta, Rotation::cur());
+                code_hash_table.lookup_exprs(meta, enable, value_rlc, length, code_hash)
             },
         );
 
@@ -561,15 +661,17 @@ impl<F: Field> SubCircuitConfig<F> for BytecodeCircuitConfig<F> {
             value_rlc,
             length,
             push_data_size,
+            length_left,
             push_data_left_is_zero,
             index_length_diff_is_zero,
             push_table,
-            keccak_table,
+            eof,
+            code_hash_table,
         }
     }
 }
 
-impl<F: Field> BytecodeCircuitConfig<F> {
+impl<F: Field, S: SpongeChip<F>> BytecodeCircuitConfig<F, S> {
     pub(crate) fn assign_internal(
         &self,
         layouter: &mut impl Layouter<F>,
@@ -578,6 +680,7 @@ impl<F: Field> BytecodeCircuitConfig<F> {
         challenges: &Challenges<Value<F>>,
     ) -> Result<(), Error> {
         let last_row_offset = max_rows - 1;
+        let pad = BytecodeCircuitRow::pad::<S>();
 
         if witness.len() > last_row_offset {
             // The last_row_offset-th row must be reserved for padding.
@@ -599,7 +702,7 @@ impl<F: Field> BytecodeCircuitConfig<F> {
                 // We take only the first "last_row_offset" rows.
                 for (offset, row) in witness
                     .iter()
-                    .chain(iter::repeat(&BytecodeCircuitRow::pad()))
+                    .chain(iter::repeat(&pad))
                     .take(last_row_offset)
                     .enumerate()
                 {
@@ -621,12 +724,7 @@ impl<F: Field> BytecodeCircuitConfig<F> {
                 }
 
                 // Last row must be a padding row
-                self.set_row(
-                    &mut region,
-                    last_row_offset,
-                    last_row_offset,
-                    &BytecodeCircuitRow::pad(),
-                )?;
+                self.set_row(&mut region, last_row_offset, last_row_offset, &pad)?;
 
                 Ok(())
             },
@@ -677,6 +775,16 @@ impl<F: Field> BytecodeCircuitConfig<F> {
             ),
             ("length", self.length, row.length),
             ("push_data_size", self.push_data_size, row.push_data_size),
+            (
+                "length_left_lo",
+                self.length_left[0],
+                F::from(row.length_left.to_repr()[0] as u64),
+            ),
+            (
+                "length_left_hi",
+                self.length_left[1],
+                F::from(row.length_left.to_repr()[1] as u64),
+            ),
         ] {
             region.assign_advice(
                 || format!("assign {} {}", name, offset),
@@ -709,12 +817,16 @@ impl<F: Field> BytecodeCircuitConfig<F> {
         self.index_length_diff_is_zero
             .assign(region, offset, Value::known(row.diff()))?;
 
+        if let Some(eof) = &self.eof {
+            eof.assign_row(region, offset, &row.eof)?;
+        }
+
         Ok(())
     }
 
     fn annotate_circuit(&self, region: &mut Region<F>) {
         self.bytecode_table.annotate_columns_in_region(region);
-        self.keccak_table.annotate_columns_in_region(region);
+        self.code_hash_table.annotate_columns_in_region(region);
 
         self.push_data_left_is_zero
             .annotate_columns_in_region(region, "BYTECODE");
@@ -727,6 +839,11 @@ impl<F: Field> BytecodeCircuitConfig<F> {
         region.name_column(|| "BYTECODE_push_data_left", self.push_data_left);
         region.name_column(|| "BYTECODE_push_data_size", self.push_data_size);
         region.name_column(|| "BYTECODE_value_rlc", self.value_rlc);
+        region.name_column(|| "BYTECODE_length_left_lo", self.length_left[0]);
+        region.name_column(|| "BYTECODE_length_left_hi", self.length_left[1]);
+        if let Some(eof) = &self.eof {
+            eof.annotate_columns_in_region(region);
+        }
     }
 
     /// load fixed tables
@@ -756,13 +873,17 @@ impl<F: Field> BytecodeCircuitConfig<F> {
             },
         )?;
 
+        if let Some(eof) = &self.eof {
+            eof.load_aux_tables(layouter)?;
+        }
+
         Ok(())
     }
 }
 
-/// BytecodeCircuit
-#[derive(Clone, Default, Debug)]
-pub struct BytecodeCircuit<F: Field> {
+/// BytecodeCircuit, whose code hashes are looked up in the table `S`
+#[derive(Clone, Debug)]
+pub struct BytecodeCircuit<F: Field, S = KeccakTable> {
     pub(crate) bytecodes: CodeDB,
     /// Unrolled bytecodes
     pub(crate) rows: BytecodeCircuitAssignment<F>,
@@ -771,22 +892,44 @@ pub struct BytecodeCircuit<F: Field> {
     /// In tests, we set the number as small as possible to reduce MockProver's overhead.
     /// In production, this number must be the power of degree minus unusable rows.
     pub max_rows: usize,
+    /// Hardfork whose rules are constrained
+    pub hardfork: Hardfork,
+    _marker: PhantomData<S>,
 }
 
-impl<F: Field> BytecodeCircuit<F> {
+impl<F: Field, S> Default for BytecodeCircuit<F, S> {
+    fn default() -> Self {
+        Self {
+            bytecodes: CodeDB::default(),
+            rows: BytecodeCircuitAssignment::default(),
+            max_rows: 0,
+            hardfork: Hardfork::default(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field, S: SpongeChip<F>> BytecodeCircuit<F, S> {
     /// new BytecodeCircuitTester
     pub fn new(bytecodes: CodeDB, max_rows: usize) -> Self {
-        let rows: BytecodeCircuitAssignment<F> = bytecodes.clone().into();
+        Self::new_with_params(bytecodes, max_rows, Hardfork::default())
+    }
+
+    /// Return a BytecodeCircuit constraining the rules of `hardfork`.
+    pub fn new_with_params(bytecodes: CodeDB, max_rows: usize, hardfork: Hardfork) -> Self {
+        let rows = BytecodeCircuitAssignment::from_code_db::<S>(bytecodes.clone(), hardfork);
         Self {
             bytecodes,
             rows,
             max_rows,
+            hardfork,
+            _marker: PhantomData,
         }
     }
 }
 
-impl<F: Field> SubCircuit<F> for BytecodeCircuit<F> {
-    type Config = BytecodeCircuitConfig<F>;
+impl<F: Field, S: SpongeChip<F>> SubCircuit<F> for BytecodeCircuit<F, S> {
+    type Config = BytecodeCircuitConfig<F, S>;
 
     fn unusable_rows() -> usize {
         // No column queried at more than 3 distinct rotations, so returns 6 as
@@ -794,8 +937,15 @@ impl<F: Field> SubCircuit<F> for BytecodeCircuit<F> {
         6
     }
 
+    /// The code hashes are the digests of `S`: the circuit including the
+    /// BytecodeCircuit checks that `S` is the table of the
+    /// `block.circuits_params.code_hash_scheme`.
     fn new_from_block(block: &witness::Block<F>) -> Self {
-        Self::new(block.bytecodes.clone(), block.circuits_params.max_bytecode)
+        Self::new_with_params(
+            block.bytecodes.clone(),
+            block.circuits_params.max_bytecode,
+            block.circuits_params.hardfork,
+        )
     }
 
     /// Return the minimum number of rows required to prove the block
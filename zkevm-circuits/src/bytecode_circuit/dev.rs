@@ -2,28 +2,42 @@ pub use super::BytecodeCircuit;
 
 use crate::{
     bytecode_circuit::{BytecodeCircuitConfig, BytecodeCircuitConfigArgs},
-    table::{BytecodeTable, KeccakTable},
+    table::{BytecodeTable, SpongeChip},
     util::{Challenges, SubCircuit, SubCircuitConfig},
 };
-use eth_types::Field;
+use eth_types::{evm_types::Hardfork, Field};
 use halo2_proofs::{
     circuit::{Layouter, SimpleFloorPlanner},
     plonk::{Circuit, ConstraintSystem, Error},
 };
 use itertools::Itertools;
 
-impl<F: Field> Circuit<F> for BytecodeCircuit<F> {
-    type Config = (BytecodeCircuitConfig<F>, Challenges);
+impl<F: Field, S: SpongeChip<F>> Circuit<F> for BytecodeCircuit<F, S> {
+    type Config = (BytecodeCircuitConfig<F, S>, Challenges);
     type FloorPlanner = SimpleFloorPlanner;
-    type Params = ();
+    type Params = Hardfork;
 
     fn without_witnesses(&self) -> Self {
-        Self::default()
+        Self {
+            hardfork: self.hardfork,
+            ..Self::default()
+        }
+    }
+
+    fn params(&self) -> Self::Params {
+        self.hardfork
     }
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        Self::configure_with_params(meta, Self::Params::default())
+    }
+
+    fn configure_with_params(
+        meta: &mut ConstraintSystem<F>,
+        hardfork: Self::Params,
+    ) -> Self::Config {
         let bytecode_table = BytecodeTable::construct(meta);
-        let keccak_table = KeccakTable::construct(meta);
+        let code_hash_table = S::construct(meta);
         let challenges = Challenges::construct(meta);
 
         let config = {
@@ -32,8 +46,9 @@ impl<F: Field> Circuit<F> for BytecodeCircuit<F> {
                 meta,
                 BytecodeCircuitConfigArgs {
                     bytecode_table,
-                    keccak_table,
+                    code_hash_table,
                     challenges,
+                    hardfork,
                 },
             )
         };
@@ -48,16 +63,15 @@ impl<F: Field> Circuit<F> for BytecodeCircuit<F> {
     ) -> Result<(), Error> {
         let challenges = challenges.values(&mut layouter);
 
-        config.keccak_table.dev_load(
-            &mut layouter,
-            &self
-                .bytecodes
-                .clone()
-                .into_iter()
-                .map(|b| b.code())
-                .collect_vec(),
-            &challenges,
-        )?;
+        let codes = self
+            .bytecodes
+            .clone()
+            .into_iter()
+            .map(|b| b.code())
+            .collect_vec();
+        config
+            .code_hash_table
+            .dev_load(&mut layouter, &codes, &challenges)?;
         self.synthesize_sub(&config, &challenges, &mut layouter)?;
         Ok(())
     }
@@ -0,0 +1,646 @@
+//! Validation of the EOF containers (EIP-3540) and of their code (EIP-3670),
+//! enabled in the bytecode circuit since Osaka.
+//!
+//! Every bytecode starting with 0xef is an EOF container, walked through its
+//! header, code and data sections.  The header bytes are looked up by position
+//! in a fixed table, the code section is counted down from its size, and the
+//! push data analysis restarts at the code section, whose opcodes must be
+//! defined and whose last PUSH must not be truncated.
+
+use crate::{
+    evm_circuit::util::{
+        and,
+        constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+        not, or, select,
+    },
+    table::BytecodeTable,
+    util::Expr,
+};
+use eth_types::{
+    evm_types::eof::{is_eof, is_eof_valid_opcode, EofHeader, EofHeaderByte, EOF_MAGIC},
+    Field,
+};
+use halo2_proofs::{
+    circuit::{Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, VirtualCells},
+    poly::Rotation,
+};
+
+/// Columns of the header table: `[has_data, position, is_size_byte, value,
+/// is_code_size_lo, is_data_size_lo, is_header_end]`, where the position is
+/// the index shifted by one so that the zero row matches no header byte.
+const HEADER_TABLE_WIDTH: usize = 7;
+
+/// EOF witness of a row of the bytecode circuit
+#[derive(Clone, Debug, Default)]
+pub(crate) struct EofRow<F> {
+    is_eof: F,
+    has_data: F,
+    code_size: F,
+    data_size: F,
+    in_header: F,
+    in_code: F,
+    in_data: F,
+    /// Number of bytes of the code section from the current one
+    code_left: F,
+    is_code_end: F,
+    is_size_byte: F,
+    is_code_size_lo: F,
+    is_data_size_lo: F,
+    is_header_end: F,
+    /// First byte of the bytecode minus the first byte of the magic, on the
+    /// header row
+    magic_diff: F,
+}
+
+impl<F: Field> EofRow<F> {
+    /// Return the EOF witness of the header row and of the byte rows of a
+    /// bytecode.  The witness of an invalid container fails the constraints.
+    pub(crate) fn rows(code: &[u8]) -> Vec<Self> {
+        let is_eof = is_eof(code);
+        let header = EofHeader::read(code);
+        let common = if is_eof {
+            Self {
+                is_eof: F::ONE,
+                has_data: F::from(header.has_data.into()),
+                code_size: F::from(header.code_size as u64),
+                data_size: F::from(header.data_size as u64),
+                ..Default::default()
+            }
+        } else {
+            Self::default()
+        };
+
+        let mut rows = vec![Self {
+            magic_diff: code.first().map_or(F::ZERO, |byte| {
+                F::from(*byte as u64) - F::from(EOF_MAGIC[0] as u64)
+            }),
+            ..common.clone()
+        }];
+        let header_bytes = EofHeaderByte::header(header.has_data);
+        let code_end = header.header_size() + header.code_size;
+        rows.extend((0..code.len()).map(|index| {
+            if !is_eof {
+                return common.clone();
+            }
+            match header_bytes.get(index) {
+                Some(header_byte) => Self {
+                    in_header: F::ONE,
+                    is_size_byte: F::from((!matches!(header_byte, EofHeaderByte::Fixed(_))).into()),
+                    is_code_size_lo: F::from((*header_byte == EofHeaderByte::CodeSizeLo).into()),
+                    is_data_size_lo: F::from((*header_byte == EofHeaderByte::DataSizeLo).into()),
+                    is_header_end: F::from((index + 1 == header_bytes.len()).into()),
+                    ..common.clone()
+                },
+                None if index < code_end => Self {
+                    in_code: F::ONE,
+                    code_left: F::from((code_end - index) as u64),
+                    is_code_end: F::from((index + 1 == code_end).into()),
+                    ..common.clone()
+                },
+                None => Self {
+                    in_data: F::ONE,
+                    ..common.clone()
+                },
+            }
+        }));
+        rows
+    }
+}
+
+#[derive(Clone, Debug)]
+/// Columns and tables of the EOF validation
+pub(crate) struct EofConfig {
+    is_eof: Column<Advice>,
+    has_data: Column<Advice>,
+    code_size: Column<Advice>,
+    data_size: Column<Advice>,
+    in_header: Column<Advice>,
+    in_code: Column<Advice>,
+    in_data: Column<Advice>,
+    code_left: Column<Advice>,
+    is_code_end: Column<Advice>,
+    is_size_byte: Column<Advice>,
+    is_code_size_lo: Column<Advice>,
+    is_data_size_lo: Column<Advice>,
+    is_header_end: Column<Advice>,
+    magic_inv: Column<Advice>,
+    size_inv: [Column<Advice>; 2],
+    header_table: [Column<Fixed>; HEADER_TABLE_WIDTH],
+    opcode_table: Column<Fixed>,
+}
+
+impl EofConfig {
+    /// Configure the EOF validation of the bytecodes of `bytecode_table`.
+    pub(crate) fn configure<F: Field>(
+        meta: &mut ConstraintSystem<F>,
+        q_enable: Column<Fixed>,
+        q_last: Column<Fixed>,
+        bytecode_table: &BytecodeTable,
+        length: Column<Advice>,
+        push_data_left: Column<Advice>,
+        push_data_size: Column<Advice>,
+    ) -> Self {
+        let is_eof = meta.advice_column();
+        let has_data = meta.advice_column();
+        let code_size = meta.advice_column();
+        let data_size = meta.advice_column();
+        let in_header = meta.advice_column();
+        let in_code = meta.advice_column();
+        let in_data = meta.advice_column();
+        let code_left = meta.advice_column();
+        let is_code_end = meta.advice_column();
+        let is_size_byte = meta.advice_column();
+        let is_code_size_lo = meta.advice_column();
+        let is_data_size_lo = meta.advice_column();
+        let is_header_end = meta.advice_column();
+        let magic_inv = meta.advice_column();
+        let size_inv = array_init::array_init(|_| meta.advice_column());
+        let header_table = array_init::array_init(|_| meta.fixed_column());
+        let opcode_table = meta.fixed_column();
+
+        header_table.iter().enumerate().for_each(|(idx, &col)| {
+            meta.annotate_lookup_any_column(col, || format!("eof_header_table_{}", idx))
+        });
+        meta.annotate_lookup_any_column(opcode_table, || "eof_opcode_table");
+
+        let tag = bytecode_table.tag;
+        let value = bytecode_table.value;
+        let enabled = |meta: &mut VirtualCells<F>| {
+            and::expr(vec![
+                meta.query_fixed(q_enable, Rotation::cur()),
+                not::expr(meta.query_fixed(q_last, Rotation::cur())),
+            ])
+        };
+        let propagate = |meta: &mut VirtualCells<F>, cb: &mut BaseConstraintBuilder<F>| {
+            for column in [is_eof, has_data, code_size, data_size] {
+                cb.require_equal(
+                    "next.eof_header == cur.eof_header",
+                    meta.query_advice(column, Rotation::next()),
+                    meta.query_advice(column, Rotation::cur()),
+                );
+            }
+        };
+
+        // When is_header ->
+        // assert cur.is_eof and cur.has_data are boolean, has_data => is_eof
+        // assert the header row is in no section
+        // if cur.is_eof:
+        //     assert cur.length == header_size + cur.code_size + cur.data_size
+        //     assert cur.code_size != 0
+        // assert cur.data_size != 0 if cur.has_data else cur.data_size == 0
+        meta.create_gate("EOF header row", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let is_eof = meta.query_advice(is_eof, Rotation::cur());
+            let has_data = meta.query_advice(has_data, Rotation::cur());
+            let code_size = meta.query_advice(code_size, Rotation::cur());
+            let data_size = meta.query_advice(data_size, Rotation::cur());
+            cb.require_boolean("cur.is_eof is boolean", is_eof.clone());
+            cb.require_boolean("cur.has_data is boolean", has_data.clone());
+            cb.require_zero(
+                "cur.has_data => cur.is_eof",
+                has_data.clone() * not::expr(is_eof.clone()),
+            );
+            for column in [in_header, in_code, in_data] {
+                cb.require_zero(
+                    "cur is in no section",
+                    meta.query_advice(column, Rotation::cur()),
+                );
+            }
+
+            cb.condition(is_eof, |cb| {
+                cb.require_equal(
+                    "cur.length == header_size + cur.code_size + cur.data_size",
+                    meta.query_advice(length, Rotation::cur()),
+                    7.expr() + has_data.clone() * 3.expr() + code_size.clone() + data_size.clone(),
+                );
+                cb.require_equal(
+                    "cur.code_size != 0",
+                    code_size * meta.query_advice(size_inv[0], Rotation::cur()),
+                    1.expr(),
+                );
+            });
+            cb.require_equal(
+                "cur.data_size != 0 if cur.has_data",
+                data_size.clone() * meta.query_advice(size_inv[1], Rotation::cur()),
+                has_data.clone(),
+            );
+            cb.require_zero(
+                "cur.data_size == 0 if not cur.has_data",
+                not::expr(has_data) * data_size,
+            );
+
+            cb.gate(and::expr(vec![
+                enabled(meta),
+                not::expr(meta.query_advice(tag, Rotation::cur())),
+            ]))
+        });
+
+        // When is_header_to_byte ->
+        // assert cur.is_eof == (next.value == 0xef)
+        // assert next.eof_header == cur.eof_header
+        // assert next.in_header == cur.is_eof
+        meta.create_gate("EOF header to byte row", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_equal(
+                "cur.is_eof == (next.value == 0xef)",
+                (meta.query_advice(value, Rotation::next()) - EOF_MAGIC[0].expr())
+                    * meta.query_advice(magic_inv, Rotation::cur()),
+                not::expr(meta.query_advice(is_eof, Rotation::cur())),
+            );
+            propagate(meta, &mut cb);
+            cb.require_equal(
+                "next.in_header == cur.is_eof",
+                meta.query_advice(in_header, Rotation::next()),
+                meta.query_advice(is_eof, Rotation::cur()),
+            );
+
+            cb.gate(and::expr(vec![
+                enabled(meta),
+                not::expr(meta.query_advice(tag, Rotation::cur())),
+                meta.query_advice(tag, Rotation::next()),
+            ]))
+        });
+
+        // When is_header_to_header or q_last ->
+        // assert cur.is_eof == 0
+        // assert the header row is in no section
+        meta.create_gate("EOF header to header row", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            for column in [is_eof, in_header, in_code, in_data] {
+                cb.require_zero(
+                    "cur is not an EOF container",
+                    meta.query_advice(column, Rotation::cur()),
+                );
+            }
+
+            cb.gate(and::expr(vec![
+                meta.query_fixed(q_enable, Rotation::cur()),
+                or::expr(vec![
+                    and::expr(vec![
+                        not::expr(meta.query_advice(tag, Rotation::cur())),
+                        not::expr(meta.query_advice(tag, Rotation::next())),
+                    ]),
+                    meta.query_fixed(q_last, Rotation::cur()),
+                ]),
+            ]))
+        });
+
+        // When is_byte ->
+        // assert cur is in exactly one section if cur.is_eof, else in none
+        // assert cur.is_header_end => cur.in_header
+        // assert cur.is_code_end => cur.in_code and cur.code_left == 1
+        // if cur.is_code_end:
+        //     assert no push data is left after the code section
+        // if cur.in_header and cur.is_code_size_lo:
+        //     assert cur.code_size == prev.value * 256 + cur.value
+        // if cur.in_header and cur.is_data_size_lo:
+        //     assert cur.data_size == prev.value * 256 + cur.value
+        meta.create_gate("EOF byte row", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let [in_header, in_code, in_data, is_code_end, is_header_end] =
+                [in_header, in_code, in_data, is_code_end, is_header_end]
+                    .map(|column| meta.query_advice(column, Rotation::cur()));
+            for (name, flag) in [
+                ("cur.in_header is boolean", in_header.clone()),
+                ("cur.in_code is boolean", in_code.clone()),
+                ("cur.in_data is boolean", in_data.clone()),
+                ("cur.is_code_end is boolean", is_code_end.clone()),
+            ] {
+                cb.require_boolean(name, flag);
+            }
+            cb.require_equal(
+                "cur.in_header + cur.in_code + cur.in_data == cur.is_eof",
+                in_header.clone() + in_code.clone() + in_data,
+                meta.query_advice(is_eof, Rotation::cur()),
+            );
+            cb.require_zero(
+                "cur.is_header_end => cur.in_header",
+                is_header_end * not::expr(in_header.clone()),
+            );
+            cb.require_zero(
+                "cur.is_code_end => cur.in_code",
+                is_code_end.clone() * not::expr(in_code),
+            );
+            cb.require_zero(
+                "cur.is_code_end => cur.code_left == 1",
+                is_code_end.clone() * (meta.query_advice(code_left, Rotation::cur()) - 1.expr()),
+            );
+
+            cb.condition(is_code_end, |cb| {
+                cb.require_zero(
+                    "no push data left after the code section",
+                    select::expr(
+                        meta.query_advice(bytecode_table.is_code, Rotation::cur()),
+                        meta.query_advice(push_data_size, Rotation::cur()),
+                        meta.query_advice(push_data_left, Rotation::cur()) - 1.expr(),
+                    ),
+                );
+            });
+
+            let size = meta.query_advice(value, Rotation::prev()) * 256.expr()
+                + meta.query_advice(value, Rotation::cur());
+            for (name, is_size_lo, section_size) in [
+                ("cur.code_size == size", is_code_size_lo, code_size),
+                ("cur.data_size == size", is_data_size_lo, data_size),
+            ] {
+                cb.require_zero(
+                    name,
+                    in_header.clone()
+                        * meta.query_advice(is_size_lo, Rotation::cur())
+                        * (meta.query_advice(section_size, Rotation::cur()) - size.clone()),
+                );
+            }
+
+            cb.gate(and::expr(vec![
+                enabled(meta),
+                meta.query_advice(tag, Rotation::cur()),
+            ]))
+        });
+
+        // When is_byte_to_byte ->
+        // assert next.eof_header == cur.eof_header
+        // if cur.in_header and not cur.is_header_end:
+        //     assert next.in_header
+        // if cur.is_header_end:
+        //     assert next.in_code and next.code_left == cur.code_size
+        //     assert next.push_data_left == 0
+        // if cur.in_code and not cur.is_code_end:
+        //     assert next.in_code and next.code_left == cur.code_left - 1
+        // if cur.is_code_end or cur.in_data:
+        //     assert next.in_data
+        meta.create_gate("EOF byte to byte row", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            propagate(meta, &mut cb);
+
+            let [in_header, in_code, in_data, is_code_end, is_header_end] =
+                [in_header, in_code, in_data, is_code_end, is_header_end]
+                    .map(|column| meta.query_advice(column, Rotation::cur()));
+            cb.condition(in_header - is_header_end.clone(), |cb| {
+                cb.require_equal(
+                    "next.in_header",
+                    meta.query_advice(in_header, Rotation::next()),
+                    1.expr(),
+                );
+            });
+            cb.condition(is_header_end, |cb| {
+                cb.require_equal(
+                    "next.in_code",
+                    meta.query_advice(in_code, Rotation::next()),
+                    1.expr(),
+                );
+                cb.require_equal(
+                    "next.code_left == cur.code_size",
+                    meta.query_advice(code_left, Rotation::next()),
+                    meta.query_advice(code_size, Rotation::cur()),
+                );
+                cb.require_zero(
+                    "next.push_data_left == 0",
+                    meta.query_advice(push_data_left, Rotation::next()),
+                );
+            });
+            cb.condition(in_code - is_code_end.clone(), |cb| {
+                cb.require_equal(
+                    "next.in_code",
+                    meta.query_advice(in_code, Rotation::next()),
+                    1.expr(),
+                );
+                cb.require_equal(
+                    "next.code_left == cur.code_left - 1",
+                    meta.query_advice(code_left, Rotation::next()),
+                    meta.query_advice(code_left, Rotation::cur()) - 1.expr(),
+                );
+            });
+            cb.condition(is_code_end + in_data, |cb| {
+                cb.require_equal(
+                    "next.in_data",
+                    meta.query_advice(in_data, Rotation::next()),
+                    1.expr(),
+                );
+            });
+
+            cb.gate(and::expr(vec![
+                enabled(meta),
+                meta.query_advice(tag, Rotation::cur()),
+                meta.query_advice(tag, Rotation::next()),
+            ]))
+        });
+
+        // When is_byte_to_header ->
+        // assert the container doesn't end in its header or its code section
+        meta.create_gate("EOF byte to header row", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_zero(
+                "not cur.in_header",
+                meta.query_advice(in_header, Rotation::cur()),
+            );
+            cb.require_zero(
+                "cur.in_code => cur.is_code_end",
+                meta.query_advice(in_code, Rotation::cur())
+                    - meta.query_advice(is_code_end, Rotation::cur()),
+            );
+
+            cb.gate(and::expr(vec![
+                enabled(meta),
+                meta.query_advice(tag, Rotation::cur()),
+                not::expr(meta.query_advice(tag, Rotation::next())),
+            ]))
+        });
+
+        meta.lookup_any("eof_header_table_lookup(cur.header_byte)", |meta| {
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(in_header, Rotation::cur());
+            let is_size_byte = meta.query_advice(is_size_byte, Rotation::cur());
+            [
+                meta.query_advice(has_data, Rotation::cur()),
+                meta.query_advice(bytecode_table.index, Rotation::cur()) + 1.expr(),
+                is_size_byte.clone(),
+                not::expr(is_size_byte) * meta.query_advice(value, Rotation::cur()),
+                meta.query_advice(is_code_size_lo, Rotation::cur()),
+                meta.query_advice(is_data_size_lo, Rotation::cur()),
+                meta.query_advice(is_header_end, Rotation::cur()),
+            ]
+            .into_iter()
+            .zip(header_table)
+            .map(|(input, column)| {
+                (
+                    enable.clone() * input,
+                    meta.query_fixed(column, Rotation::cur()),
+                )
+            })
+            .collect()
+        });
+
+        meta.lookup_any("eof_opcode_table_lookup(cur.value)", |meta| {
+            let enable = and::expr(vec![
+                meta.query_fixed(q_enable, Rotation::cur()),
+                meta.query_advice(in_code, Rotation::cur()),
+                meta.query_advice(bytecode_table.is_code, Rotation::cur()),
+            ]);
+            vec![(
+                enable * meta.query_advice(value, Rotation::cur()),
+                meta.query_fixed(opcode_table, Rotation::cur()),
+            )]
+        });
+
+        Self {
+            is_eof,
+            has_data,
+            code_size,
+            data_size,
+            in_header,
+            in_code,
+            in_data,
+            code_left,
+            is_code_end,
+            is_size_byte,
+            is_code_size_lo,
+            is_data_size_lo,
+            is_header_end,
+            magic_inv,
+            size_inv,
+            header_table,
+            opcode_table,
+        }
+    }
+
+    /// Return whether the next row starts the code section, where the push
+    /// data analysis restarts.
+    pub(crate) fn is_code_start<F: Field>(&self, meta: &mut VirtualCells<F>) -> Expression<F> {
+        meta.query_advice(self.is_header_end, Rotation::cur())
+    }
+
+    pub(crate) fn assign_row<F: Field>(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &EofRow<F>,
+    ) -> Result<(), Error> {
+        for (name, column, value) in [
+            ("is_eof", self.is_eof, row.is_eof),
+            ("has_data", self.has_data, row.has_data),
+            ("code_size", self.code_size, row.code_size),
+            ("data_size", self.data_size, row.data_size),
+            ("in_header", self.in_header, row.in_header),
+            ("in_code", self.in_code, row.in_code),
+            ("in_data", self.in_data, row.in_data),
+            ("code_left", self.code_left, row.code_left),
+            ("is_code_end", self.is_code_end, row.is_code_end),
+            ("is_size_byte", self.is_size_byte, row.is_size_byte),
+            ("is_code_size_lo", self.is_code_size_lo, row.is_code_size_lo),
+            ("is_data_size_lo", self.is_data_size_lo, row.is_data_size_lo),
+            ("is_header_end", self.is_header_end, row.is_header_end),
+            (
+                "magic_inv",
+                self.magic_inv,
+                row.magic_diff.invert().unwrap_or(F::ZERO),
+            ),
+            (
+                "code_size_inv",
+                self.size_inv[0],
+                row.code_size.invert().unwrap_or(F::ZERO),
+            ),
+            (
+                "data_size_inv",
+                self.size_inv[1],
+                row.data_size.invert().unwrap_or(F::ZERO),
+            ),
+        ] {
+            region.assign_advice(
+                || format!("assign eof {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(value),
+            )?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn annotate_columns_in_region<F: Field>(&self, region: &mut Region<F>) {
+        for (name, column) in [
+            ("is_eof", self.is_eof),
+            ("has_data", self.has_data),
+            ("code_size", self.code_size),
+            ("data_size", self.data_size),
+            ("in_header", self.in_header),
+            ("in_code", self.in_code),
+            ("in_data", self.in_data),
+            ("code_left", self.code_left),
+            ("is_code_end", self.is_code_end),
+            ("is_size_byte", self.is_size_byte),
+            ("is_code_size_lo", self.is_code_size_lo),
+            ("is_data_size_lo", self.is_data_size_lo),
+            ("is_header_end", self.is_header_end),
+            ("magic_inv", self.magic_inv),
+            ("code_size_inv", self.size_inv[0]),
+            ("data_size_inv", self.size_inv[1]),
+        ] {
+            region.name_column(|| format!("BYTECODE_eof_{}", name), column);
+        }
+    }
+
+    /// Load the header table and the table of the opcodes allowed in the code
+    /// section.
+    pub(crate) fn load_aux_tables<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "eof header table",
+            |mut region| {
+                let mut offset = 0;
+                for has_data in [false, true] {
+                    let header_bytes = EofHeaderByte::header(has_data);
+                    for (index, header_byte) in header_bytes.iter().enumerate() {
+                        let fixed_byte = match header_byte {
+                            EofHeaderByte::Fixed(byte) => Some(*byte as u64),
+                            _ => None,
+                        };
+                        for (column, value) in self.header_table.iter().zip([
+                            has_data.into(),
+                            index as u64 + 1,
+                            fixed_byte.is_none().into(),
+                            fixed_byte.unwrap_or_default(),
+                            (*header_byte == EofHeaderByte::CodeSizeLo).into(),
+                            (*header_byte == EofHeaderByte::DataSizeLo).into(),
+                            (index + 1 == header_bytes.len()).into(),
+                        ]) {
+                            region.assign_fixed(
+                                || format!("EOF header table {}", offset),
+                                *column,
+                                offset,
+                                || Value::known(F::from(value)),
+                            )?;
+                        }
+                        offset += 1;
+                    }
+                }
+                Ok(())
+            },
+        )?;
+
+        layouter.assign_region(
+            || "eof opcode table",
+            |mut region| {
+                for (offset, byte) in (0..=u8::MAX)
+                    .filter(|byte| is_eof_valid_opcode(*byte))
+                    .enumerate()
+                {
+                    region.assign_fixed(
+                        || format!("EOF opcode table {}", offset),
+                        self.opcode_table,
+                        offset,
+                        || Value::known(F::from(byte as u64)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
@@ -1,19 +1,31 @@
-use super::{BytecodeCircuit, BytecodeCircuitRow};
-use crate::util::{log2_ceil, unusable_rows, SubCircuit};
+use super::{BytecodeCircuit, BytecodeCircuitAssignment, BytecodeCircuitRow};
+use crate::{
+    table::{PoseidonTable, SpongeChip},
+    util::{log2_ceil, unusable_rows, SubCircuit},
+};
 use bus_mapping::{evm::OpcodeId, state_db::CodeDB};
-use eth_types::Field;
+use eth_types::{
+    evm_types::{eof::validate_eof, Hardfork, MAX_CODE_SIZE},
+    Field,
+};
 use halo2_proofs::{arithmetic::Field as Halo2Field, dev::MockProver, halo2curves::bn256::Fr};
 use log::error;
 
 #[test]
 fn bytecode_circuit_unusable_rows() {
+    for hardfork in [Hardfork::default(), Hardfork::Osaka] {
+        assert_eq!(
+            BytecodeCircuit::<Fr>::unusable_rows(),
+            unusable_rows::<Fr, BytecodeCircuit::<Fr>>(hardfork),
+        );
+    }
     assert_eq!(
-        BytecodeCircuit::<Fr>::unusable_rows(),
-        unusable_rows::<Fr, BytecodeCircuit::<Fr>>(()),
-    )
+        BytecodeCircuit::<Fr, PoseidonTable>::unusable_rows(),
+        unusable_rows::<Fr, BytecodeCircuit::<Fr, PoseidonTable>>(Hardfork::default()),
+    );
 }
 
-impl<F: Field> BytecodeCircuit<F> {
+impl<F: Field, S: SpongeChip<F>> BytecodeCircuit<F, S> {
     fn mut_rows(&mut self, mut mut_func: impl FnMut(&mut Vec<BytecodeCircuitRow<F>>)) -> Self {
         mut_func(&mut self.rows.0);
         self.clone()
@@ -23,6 +35,10 @@ impl<F: Field> BytecodeCircuit<F> {
         Self::new(bytecodes.into(), 2usize.pow(k) - Self::unusable_rows())
     }
 
+    fn with_hardfork(self, hardfork: Hardfork) -> Self {
+        Self::new_with_params(self.bytecodes, self.max_rows, hardfork)
+    }
+
     fn verify(&self, success: bool) {
         let prover = MockProver::<F>::run(log2_ceil(self.max_rows), self, Vec::new()).unwrap();
         let result = prover.verify_par();
@@ -73,6 +89,26 @@ fn bytecode_incomplete() {
     BytecodeCircuit::<Fr>::from_bytes(vec![vec![7u8; 2usize.pow(k) + 1]], k).verify(false);
 }
 
+/// Tests the code size limit of EIP-170
+#[test]
+fn bytecode_max_code_size() {
+    let k = 15;
+    BytecodeCircuit::<Fr>::from_bytes(vec![vec![7u8; MAX_CODE_SIZE as usize]], k).verify(true);
+    BytecodeCircuit::<Fr>::from_bytes(vec![vec![7u8; MAX_CODE_SIZE as usize + 1]], k).verify(false);
+}
+
+/// Tests that a bytecode shared by several accounts is unrolled once
+#[test]
+fn bytecode_deduplication() {
+    let rows = BytecodeCircuitAssignment::<Fr>::from(vec![vec![7u8, 8], vec![6u8], vec![7u8, 8]]);
+    assert_eq!(rows.len(), 5);
+    assert_eq!(rows[4].value, Fr::from(6));
+
+    let k = 9;
+    let bytecode = vec![7u8; 2usize.pow(k) - 8];
+    BytecodeCircuit::<Fr>::from_bytes(vec![bytecode.clone(), bytecode], k).verify(true);
+}
+
 /// Tests multiple bytecodes in a single circuit
 #[test]
 fn bytecode_push() {
@@ -116,6 +152,32 @@ fn bytecode_invalid_hash_data() {
     // witness.
 }
 
+/// Test the Poseidon code hashes
+#[test]
+fn bytecode_poseidon_code_hash() {
+    let k = 9;
+    let bytecodes = vec![
+        vec![8u8, 2, 3, 8, 9, 7, 128],
+        vec![OpcodeId::PUSH1.as_u8(), 1],
+    ];
+    BytecodeCircuit::<Fr, PoseidonTable>::from_bytes(bytecodes.clone(), k).verify(true);
+    BytecodeCircuit::<Fr, PoseidonTable>::from_bytes(vec![vec![]], k).verify(true);
+    // The keccak code hashes are not in the PoseidonTable
+    {
+        let keccak_rows = BytecodeCircuit::<Fr>::from_bytes(bytecodes.clone(), k).rows;
+        BytecodeCircuit::<Fr, PoseidonTable>::from_bytes(bytecodes, k)
+            .mut_rows(|rows| *rows = keccak_rows.0.clone())
+            .verify(false);
+    }
+    // The empty code hash is still constrained
+    BytecodeCircuit::<Fr, PoseidonTable>::from_bytes(vec![vec![]], k)
+        .mut_rows(|rows| {
+            let code_hash = rows[0].code_hash;
+            rows[0].code_hash = code_hash.map(|limb| limb.map(|limb| limb + Fr::one()));
+        })
+        .verify(false);
+}
+
 /// Test invalid index
 #[test]
 fn bytecode_invalid_index() {
@@ -238,3 +300,106 @@ fn bytecode_soundness_bug_1() {
         })
         .verify(false);
 }
+
+/// An EOF container with a code section of `code` and an optional data
+/// section of `data`
+fn eof_container(code: &[u8], data: Option<&[u8]>) -> Vec<u8> {
+    let mut container = vec![0xef, 0x00, 0x01, 0x01];
+    container.extend((code.len() as u16).to_be_bytes());
+    if let Some(data) = data {
+        container.push(0x02);
+        container.extend((data.len() as u16).to_be_bytes());
+    }
+    container.push(0x00);
+    container.extend(code);
+    container.extend(data.unwrap_or_default());
+    container
+}
+
+/// Tests the validation of valid EOF containers
+#[test]
+fn bytecode_eof_valid_containers() {
+    let k = 9;
+    let push1_add = [
+        OpcodeId::PUSH1.as_u8(),
+        OpcodeId::ADD.as_u8(),
+        OpcodeId::STOP.as_u8(),
+    ];
+    let bytecodes = vec![
+        eof_container(&push1_add, None),
+        eof_container(&push1_add, Some(&[0xaa, OpcodeId::PUSH32.as_u8()])),
+        // The low byte of the data size is PUSH3, whose data must not run
+        // into the code section.
+        eof_container(&push1_add, Some(&[0xaa; 0x62])),
+        vec![OpcodeId::PUSH1.as_u8(), 0xef],
+        vec![],
+    ];
+    for bytecode in bytecodes.iter() {
+        assert!(bytecode.first() != Some(&0xef) || validate_eof(bytecode).is_ok());
+    }
+    BytecodeCircuit::<Fr>::from_bytes(bytecodes, k)
+        .with_hardfork(Hardfork::Osaka)
+        .verify(true);
+}
+
+/// Tests the rejection of invalid EOF containers, which are legacy code before
+/// Osaka
+#[test]
+fn bytecode_eof_invalid_containers() {
+    let k = 9;
+    let code = [OpcodeId::PUSH1.as_u8(), 0x01, OpcodeId::STOP.as_u8()];
+    let container = eof_container(&code, Some(&[0xaa]));
+    let mut version_2 = container.clone();
+    version_2[2] = 0x02;
+    let mut unknown_section = container.clone();
+    unknown_section[6] = 0x03;
+    let mut trailing_byte = container.clone();
+    trailing_byte.push(0xaa);
+    let bytecodes = vec![
+        vec![0xef],
+        vec![0xef, 0x00, 0x01, 0x01, 0x00, 0x03, 0x00],
+        version_2,
+        unknown_section,
+        trailing_byte,
+        container[..container.len() - 1].to_vec(),
+        eof_container(&[], None),
+        eof_container(&code, Some(&[])),
+        // PUSH2 with a single byte of data
+        eof_container(&[OpcodeId::PUSH2.as_u8(), 0x01], None),
+        eof_container(&[OpcodeId::PUSH1.as_u8(), 0x01, 0x0c], None),
+    ];
+    for bytecode in bytecodes {
+        assert!(validate_eof(&bytecode).is_err());
+        BytecodeCircuit::<Fr>::from_bytes(vec![bytecode.clone()], k)
+            .with_hardfork(Hardfork::Prague)
+            .verify(true);
+        BytecodeCircuit::<Fr>::from_bytes(vec![bytecode], k)
+            .with_hardfork(Hardfork::Osaka)
+            .verify(false);
+    }
+}
+
+/// Test invalid EOF section witness
+#[test]
+fn bytecode_eof_invalid_sections() {
+    let k = 9;
+    let code = [OpcodeId::PUSH1.as_u8(), 0x0c, OpcodeId::STOP.as_u8()];
+    let bytecodes = vec![eof_container(&code, Some(&[0x0c]))];
+    BytecodeCircuit::<Fr>::from_bytes(bytecodes.clone(), k)
+        .with_hardfork(Hardfork::Osaka)
+        .verify(true);
+    // Start the code section at the terminator of the header
+    BytecodeCircuit::<Fr>::from_bytes(bytecodes.clone(), k)
+        .with_hardfork(Hardfork::Osaka)
+        .mut_rows(|rows| {
+            rows[10].eof = rows[11].eof.clone();
+        })
+        .verify(false);
+    // Mark the data byte as code
+    BytecodeCircuit::<Fr>::from_bytes(bytecodes, k)
+        .with_hardfork(Hardfork::Osaka)
+        .mut_rows(|rows| {
+            rows[14].eof = rows[13].eof.clone();
+        })
+        .verify(false);
+}
@@ -1,6 +1,6 @@
 //! The Copy circuit implements constraints and lookups for read-write steps for
 //! copied bytes while execution opcodes such as CALLDATACOPY, CODECOPY, LOGS,
-//! etc.
+//! etc., and for the pre-warming of the tx access lists.
 pub(crate) mod util;
 
 #[cfg(any(test, feature = "test-circuits"))]
@@ -11,12 +11,15 @@ mod test;
 pub use dev::CopyCircuit as TestCopyCircuit;
 
 use crate::{
-    evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    evm_circuit::util::{
+        address_word_to_expr,
+        constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    },
     table::{
         BytecodeFieldTag, BytecodeTable, CopyTable, LookupTable, RwTable, TxContextFieldTag,
         TxTable,
     },
-    util::{Challenges, SubCircuit, SubCircuitConfig},
+    util::{word, Challenges, SubCircuit, SubCircuitConfig},
     witness,
     witness::{RwMap, Transaction},
 };
@@ -28,6 +31,7 @@ use bus_mapping::{
 use eth_types::Field;
 use gadgets::{
     binary_number::{BinaryNumberChip, BinaryNumberConfig},
+    is_zero::{IsZeroChip, IsZeroConfig, IsZeroInstruction},
     less_than::{LtChip, LtConfig, LtInstruction},
     util::{and, not, or, Expr},
 };
@@ -41,6 +45,7 @@ use halo2_proofs::{
 };
 use itertools::Itertools;
 use std::marker::PhantomData;
+use util::number_or_hash_to_word;
 
 // Rows to enable but not use, that can be queried safely by the last event.
 const UNUSED_ROWS: usize = 2;
@@ -64,6 +69,15 @@ pub struct CopyCircuitConfig<F> {
     /// In case of a bytecode tag, this denotes whether or not the copied byte
     /// is an opcode or push data byte.
     pub is_code: Column<Advice>,
+    /// Whether the copy event copies bytes within the memory of a call, in
+    /// which case all the reads come before the writes.
+    pub is_memory_copy: Column<Advice>,
+    /// In case of an access list tag, the entry copied in this copy step: the
+    /// storage key on the read rows of the storage keys, and the address on
+    /// the other rows.  The value is then whether the entry was warm before.
+    pub value_word: word::Word<Column<Advice>>,
+    /// IsZero chip to check: dst_id == src_id, on the read rows.
+    pub id_diff_is_zero: IsZeroConfig<F>,
     /// Whether the row is enabled or not.
     pub q_enable: Column<Fixed>,
     /// The Copy Table contains the columns that are exposed via the lookup
@@ -120,6 +134,9 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
         let value_acc_rlc = meta.advice_column_in(SecondPhase);
         let is_code = meta.advice_column();
         let is_pad = meta.advice_column();
+        let is_memory_copy = meta.advice_column();
+        let value_word = word::Word::new([meta.advice_column(), meta.advice_column()]);
+        let id_diff_inv = meta.advice_column();
         let is_first = copy_table.is_first;
         let id = copy_table.id;
         let addr = copy_table.addr;
@@ -142,6 +159,15 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
             |meta| meta.query_advice(addr, Rotation::cur()),
             |meta| meta.query_advice(src_addr_end, Rotation::cur()),
         );
+        let id_diff_is_zero = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_selector(q_step),
+            |meta| {
+                meta.query_advice(id.lo(), Rotation::next())
+                    - meta.query_advice(id.lo(), Rotation::cur())
+            },
+            id_diff_inv,
+        );
 
         meta.create_gate("verify row", |meta| {
             let mut cb = BaseConstraintBuilder::default();
@@ -175,7 +201,7 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                 - meta.query_advice(is_last, Rotation::cur())
                 - meta.query_advice(is_last, Rotation::next());
             cb.condition(
-                not_last_two_rows
+                not_last_two_rows.clone()
                     * (not::expr(tag.value_equals(CopyDataType::Padding, Rotation::cur())(
                         meta,
                     ))),
@@ -203,21 +229,52 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                 },
             );
 
+            // The reads of a memory copy come first, then the writes, at consecutive rw counters.
+            cb.require_boolean(
+                "is_memory_copy is boolean",
+                meta.query_advice(is_memory_copy, Rotation::cur()),
+            );
+            cb.condition(
+                not_last_two_rows * meta.query_advice(is_memory_copy, Rotation::cur()),
+                |cb| {
+                    cb.require_equal(
+                        "rows[0].rw_counter + 1 == rows[2].rw_counter for memory copy",
+                        meta.query_advice(rw_counter, Rotation::cur()) + 1.expr(),
+                        meta.query_advice(rw_counter, Rotation(2)),
+                    );
+                },
+            );
+
+            // The access list copies only write to the rw table.
             let rw_diff = and::expr([
                 or::expr([
                     tag.value_equals(CopyDataType::Memory, Rotation::cur())(meta),
                     tag.value_equals(CopyDataType::TxLog, Rotation::cur())(meta),
                 ]),
                 not::expr(meta.query_advice(is_pad, Rotation::cur())),
+            ]) + and::expr([
+                or::expr([
+                    tag.value_equals(CopyDataType::AccessListAddresses, Rotation::cur())(meta),
+                    tag.value_equals(CopyDataType::AccessListStorageKeys, Rotation::cur())(meta),
+                ]),
+                not::expr(meta.query_selector(q_step)),
             ]);
             cb.condition(
-                not::expr(meta.query_advice(is_last, Rotation::cur())),
+                and::expr([
+                    not::expr(meta.query_advice(is_last, Rotation::cur())),
+                    not::expr(meta.query_advice(is_memory_copy, Rotation::cur())),
+                ]),
                 |cb| {
                     cb.require_equal(
                         "rows[0].rw_counter + rw_diff == rows[1].rw_counter",
                         meta.query_advice(rw_counter, Rotation::cur()) + rw_diff.clone(),
                         meta.query_advice(rw_counter, Rotation::next()),
                     );
+                },
+            );
+            cb.condition(
+                not::expr(meta.query_advice(is_last, Rotation::cur())),
+                |cb| {
                     cb.require_equal(
                         "rows[0].rwc_inc_left - rw_diff == rows[1].rwc_inc_left",
                         meta.query_advice(rwc_inc_left, Rotation::cur()) - rw_diff.clone(),
@@ -242,7 +299,7 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
         });
 
         meta.create_gate(
-            "Last Step (check value accumulator) Memory => Bytecode or RlcAcc",
+            "Last Step (check value accumulator) Memory => Bytecode or RlcAcc, RlcAcc => Memory",
             |meta: &mut halo2_proofs::plonk::VirtualCells<F>| {
                 let mut cb = BaseConstraintBuilder::default();
 
@@ -262,7 +319,9 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                     and::expr([
                         tag.value_equals(CopyDataType::Memory, Rotation::cur())(meta),
                         tag.value_equals(CopyDataType::Bytecode, Rotation::next())(meta),
-                    ]) + tag.value_equals(CopyDataType::RlcAcc, Rotation::next())(meta),
+                    ]) + tag.value_equals(CopyDataType::RlcAcc, Rotation::next())(meta)
+                        // The output of a precompile call is given by its RLC.
+                        + tag.value_equals(CopyDataType::RlcAcc, Rotation::cur())(meta),
                 ]))
             },
         );
@@ -297,6 +356,34 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                     meta.query_advice(value_acc_rlc, Rotation::cur()),
                 );
             });
+            cb.require_equal(
+                "is_memory_copy == (src_tag == dst_tag == Memory && src_id == dst_id)",
+                meta.query_advice(is_memory_copy, Rotation::cur()),
+                and::expr([
+                    tag.value_equals(CopyDataType::Memory, Rotation::cur())(meta),
+                    tag.value_equals(CopyDataType::Memory, Rotation::next())(meta),
+                    id_diff_is_zero.expr(),
+                ]),
+            );
+            cb.require_equal(
+                "is_memory_copy is same for read-write rows",
+                meta.query_advice(is_memory_copy, Rotation::cur()),
+                meta.query_advice(is_memory_copy, Rotation::next()),
+            );
+            cb.condition(
+                and::expr([
+                    meta.query_advice(is_first, Rotation::cur()),
+                    meta.query_advice(is_memory_copy, Rotation::cur()),
+                ]),
+                |cb| {
+                    cb.require_equal(
+                        "the first write of a memory copy is after all the reads",
+                        meta.query_advice(rw_counter, Rotation::cur())
+                            + meta.query_advice(bytes_left, Rotation::cur()),
+                        meta.query_advice(rw_counter, Rotation::next()),
+                    );
+                },
+            );
             cb.require_equal(
                 "write value == read value",
                 meta.query_advice(value, Rotation::cur()),
@@ -428,6 +515,120 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
             .collect()
         });
 
+        meta.create_gate("verify access list address step (q_step == 1)", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_equal_word(
+                "the address is the same for read-write rows",
+                value_word.query_advice(meta, Rotation::cur()),
+                value_word.query_advice(meta, Rotation::next()),
+            );
+
+            cb.gate(and::expr([
+                meta.query_selector(q_step),
+                tag.value_equals(CopyDataType::AccessListAddresses, Rotation::cur())(meta),
+            ]))
+        });
+
+        for (name, src_tag, tx_tag) in [
+            (
+                "Tx access list address lookup",
+                CopyDataType::AccessListAddresses,
+                TxContextFieldTag::AccessListAddress,
+            ),
+            (
+                "Tx access list storage key lookup",
+                CopyDataType::AccessListStorageKeys,
+                TxContextFieldTag::AccessListStorageKey,
+            ),
+        ] {
+            meta.lookup_any(name, |meta| {
+                let cond = meta.query_fixed(q_enable, Rotation::cur())
+                    * meta.query_selector(q_step)
+                    * tag.value_equals(src_tag, Rotation::cur())(meta);
+                vec![
+                    meta.query_advice(id.lo(), Rotation::cur()), // tx_id
+                    tx_tag.expr(),
+                    meta.query_advice(addr, Rotation::cur()), // index
+                    meta.query_advice(value_word.lo(), Rotation::cur()),
+                    meta.query_advice(value_word.hi(), Rotation::cur()),
+                ]
+                .into_iter()
+                .zip_eq(tx_table.table_exprs(meta).into_iter())
+                .map(|(arg, table)| (cond.clone() * arg, table))
+                .collect()
+            });
+        }
+
+        meta.lookup_any("Tx access list storage key address lookup", |meta| {
+            let cond = meta.query_fixed(q_enable, Rotation::cur())
+                * not::expr(meta.query_selector(q_step))
+                * tag.value_equals(CopyDataType::AccessListStorageKeys, Rotation::cur())(meta);
+            vec![
+                meta.query_advice(id.lo(), Rotation::cur()), // tx_id
+                TxContextFieldTag::AccessListStorageKeyAddress.expr(),
+                meta.query_advice(addr, Rotation::cur()), // index
+                meta.query_advice(value_word.lo(), Rotation::cur()),
+                meta.query_advice(value_word.hi(), Rotation::cur()),
+            ]
+            .into_iter()
+            .zip_eq(tx_table.table_exprs(meta).into_iter())
+            .map(|(arg, table)| (cond.clone() * arg, table))
+            .collect()
+        });
+
+        meta.lookup_any("TxAccessListAccount lookup", |meta| {
+            let cond = meta.query_fixed(q_enable, Rotation::cur())
+                * not::expr(meta.query_selector(q_step))
+                * tag.value_equals(CopyDataType::AccessListAddresses, Rotation::cur())(meta);
+            vec![
+                meta.query_advice(rw_counter, Rotation::cur()),
+                1.expr(),
+                Target::TxAccessListAccount.expr(),
+                meta.query_advice(id.lo(), Rotation::cur()), // tx_id
+                address_word_to_expr(value_word.query_advice(meta, Rotation::cur())),
+                0.expr(),                                  // field tag
+                0.expr(),                                  // storage_key_lo
+                0.expr(),                                  // storage_key_hi
+                1.expr(),                                  // value_lo
+                0.expr(),                                  // value_hi
+                meta.query_advice(value, Rotation::cur()), // value_prev_lo
+                0.expr(),                                  // value_prev_hi
+                0.expr(),                                  // init_val_lo
+                0.expr(),                                  // init_val_hi
+            ]
+            .into_iter()
+            .zip_eq(rw_table.table_exprs(meta).into_iter())
+            .map(|(arg, table)| (cond.clone() * arg, table))
+            .collect()
+        });
+
+        meta.lookup_any("TxAccessListAccountStorage lookup", |meta| {
+            let cond = meta.query_fixed(q_enable, Rotation::cur())
+                * not::expr(meta.query_selector(q_step))
+                * tag.value_equals(CopyDataType::AccessListStorageKeys, Rotation::cur())(meta);
+            vec![
+                meta.query_advice(rw_counter, Rotation::cur()),
+                1.expr(),
+                Target::TxAccessListAccountStorage.expr(),
+                meta.query_advice(id.lo(), Rotation::cur()), // tx_id
+                address_word_to_expr(value_word.query_advice(meta, Rotation::cur())),
+                0.expr(),                                             // field tag
+                meta.query_advice(value_word.lo(), Rotation::prev()), // storage_key_lo
+                meta.query_advice(value_word.hi(), Rotation::prev()), // storage_key_hi
+                1.expr(),                                             // value_lo
+                0.expr(),                                             // value_hi
+                meta.query_advice(value, Rotation::cur()),            // value_prev_lo
+                0.expr(),                                             // value_prev_hi
+                0.expr(),                                             // init_val_lo
+                0.expr(),                                             // init_val_hi
+            ]
+            .into_iter()
+            .zip_eq(rw_table.table_exprs(meta).into_iter())
+            .map(|(arg, table)| (cond.clone() * arg, table))
+            .collect()
+        });
+
         meta.create_gate("id_hi === 0 when Momory", |meta| {
             let mut cb = BaseConstraintBuilder::default();
 
@@ -461,6 +662,17 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
             cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
         });
 
+        meta.create_gate("id_hi === 0 when access list", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let cond = tag.value_equals(CopyDataType::AccessListAddresses, Rotation::cur())(meta)
+                + tag.value_equals(CopyDataType::AccessListStorageKeys, Rotation::cur())(meta);
+            cb.condition(cond, |cb| {
+                cb.require_zero("id_hi === 0", meta.query_advice(id.hi(), Rotation::cur()))
+            });
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
         Self {
             q_step,
             is_last,
@@ -468,6 +680,9 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
             value_acc_rlc,
             is_pad,
             is_code,
+            is_memory_copy,
+            value_word,
+            id_diff_is_zero,
             q_enable,
             addr_lt_addr_end,
             copy_table,
@@ -509,9 +724,13 @@ impl<F: Field> CopyCircuitConfig<F> {
         offset: &mut usize,
         tag_chip: &BinaryNumberChip<F, CopyDataType, 3>,
         lt_chip: &LtChip<F, 8>,
+        id_diff_is_zero_chip: &IsZeroChip<F>,
         challenges: Challenges<Value<F>>,
         copy_event: &CopyEvent,
     ) -> Result<(), Error> {
+        let is_memory_copy = Value::known(F::from(copy_event.is_memory_copy() as u64));
+        let id_diff = number_or_hash_to_word::<F>(&copy_event.dst_id).lo()
+            - number_or_hash_to_word::<F>(&copy_event.src_id).lo();
         for (step_idx, (tag, table_row, circuit_row)) in
             CopyTable::assignments(copy_event, challenges)
                 .iter()
@@ -549,13 +768,15 @@ impl<F: Field> CopyCircuitConfig<F> {
                 || Value::known(F::ONE),
             )?;
 
-            // is_last, value, is_pad, is_code
+            // is_last, value, is_pad, is_code, value_word
             for (column, &(value, label)) in [
                 self.is_last,
                 self.value,
                 self.value_acc_rlc,
                 self.is_pad,
                 self.is_code,
+                self.value_word.lo(),
+                self.value_word.hi(),
             ]
             .iter()
             .zip_eq(circuit_row)
@@ -568,11 +789,20 @@ impl<F: Field> CopyCircuitConfig<F> {
                 )?;
             }
 
+            // is_memory_copy
+            region.assign_advice(
+                || format!("is_memory_copy at row: {}", *offset),
+                self.is_memory_copy,
+                *offset,
+                || is_memory_copy,
+            )?;
+
             // tag
             tag_chip.assign(region, *offset, tag)?;
 
-            // lt chip
+            // lt chip and id diff chip
             if is_read {
+                id_diff_is_zero_chip.assign(region, *offset, id_diff)?;
                 lt_chip.assign(
                     region,
                     *offset,
@@ -607,6 +837,7 @@ impl<F: Field> CopyCircuitConfig<F> {
 
         let tag_chip = BinaryNumberChip::construct(self.copy_table.tag);
         let lt_chip = LtChip::construct(self.addr_lt_addr_end);
+        let id_diff_is_zero_chip = IsZeroChip::construct(self.id_diff_is_zero.clone());
 
         lt_chip.load(layouter)?;
 
@@ -617,6 +848,7 @@ impl<F: Field> CopyCircuitConfig<F> {
                 region.name_column(|| "value", self.value);
                 region.name_column(|| "is_code", self.is_code);
                 region.name_column(|| "is_pad", self.is_pad);
+                region.name_column(|| "is_memory_copy", self.is_memory_copy);
 
                 let mut offset = 0;
                 for copy_event in copy_events.iter() {
@@ -625,18 +857,33 @@ impl<F: Field> CopyCircuitConfig<F> {
                         &mut offset,
                         &tag_chip,
                         &lt_chip,
+                        &id_diff_is_zero_chip,
                         challenges,
                         copy_event,
                     )?;
                 }
 
                 for _ in 0..filler_rows {
-                    self.assign_padding_row(&mut region, &mut offset, true, &tag_chip, &lt_chip)?;
+                    self.assign_padding_row(
+                        &mut region,
+                        &mut offset,
+                        true,
+                        &tag_chip,
+                        &lt_chip,
+                        &id_diff_is_zero_chip,
+                    )?;
                 }
                 assert_eq!(offset % 2, 0, "enabled rows must come in pairs");
 
                 for _ in 0..DISABLED_ROWS {
-                    self.assign_padding_row(&mut region, &mut offset, false, &tag_chip, &lt_chip)?;
+                    self.assign_padding_row(
+                        &mut region,
+                        &mut offset,
+                        false,
+                        &tag_chip,
+                        &lt_chip,
+                        &id_diff_is_zero_chip,
+                    )?;
                 }
 
                 Ok(())
@@ -651,6 +898,7 @@ impl<F: Field> CopyCircuitConfig<F> {
         enabled: bool,
         tag_chip: &BinaryNumberChip<F, CopyDataType, 3>,
         lt_chip: &LtChip<F, 8>,
+        id_diff_is_zero_chip: &IsZeroChip<F>,
     ) -> Result<(), Error> {
         // q_enable
         region.assign_fixed(
@@ -761,10 +1009,31 @@ impl<F: Field> CopyCircuitConfig<F> {
             *offset,
             || Value::known(F::ZERO),
         )?;
+        // is_memory_copy
+        region.assign_advice(
+            || format!("assign is_memory_copy {}", *offset),
+            self.is_memory_copy,
+            *offset,
+            || Value::known(F::ZERO),
+        )?;
+        // value_word
+        for (label, column) in [
+            ("value_word_lo", self.value_word.lo()),
+            ("value_word_hi", self.value_word.hi()),
+        ] {
+            region.assign_advice(
+                || format!("assign {} {}", label, *offset),
+                column,
+                *offset,
+                || Value::known(F::ZERO),
+            )?;
+        }
         // tag
         tag_chip.assign(region, *offset, &CopyDataType::Padding)?;
         // Assign LT gadget
         lt_chip.assign(region, *offset, Value::known(F::ZERO), Value::known(F::ONE))?;
+        // Assign IsZero gadget
+        id_diff_is_zero_chip.assign(region, *offset, Value::known(F::ZERO))?;
 
         *offset += 1;
 
@@ -779,6 +1048,8 @@ pub struct ExternalData {
     pub max_txs: usize,
     /// TxCircuit -> max_calldata
     pub max_calldata: usize,
+    /// TxCircuit -> max_access_list
+    pub max_access_list: usize,
     /// TxCircuit -> txs
     pub txs: Vec<Transaction>,
     /// StateCircuit -> max_rws
@@ -854,6 +1125,7 @@ impl<F: Field> SubCircuit<F> for CopyCircuit<F> {
             ExternalData {
                 max_txs: block.circuits_params.max_txs,
                 max_calldata: block.circuits_params.max_calldata,
+                max_access_list: block.circuits_params.max_access_list,
                 txs: block.txs.clone(),
                 max_rws: block.circuits_params.max_rws,
                 rws: block.rws.clone(),
@@ -57,6 +57,7 @@ impl<F: Field> Circuit<F> for CopyCircuit<F> {
             &self.external_data.txs,
             self.external_data.max_txs,
             self.external_data.max_calldata,
+            self.external_data.max_access_list,
         )?;
 
         config.0.rw_table.load(
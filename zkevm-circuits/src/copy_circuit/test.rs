@@ -2,11 +2,12 @@ use crate::{
     copy_circuit::*,
     evm_circuit::{test::rand_bytes, witness::block_convert},
     util::unusable_rows,
-    witness::Block,
+    witness::{Block, Rw},
 };
 use bus_mapping::{
-    circuit_input_builder::{CircuitInputBuilder, FixedCParams},
+    circuit_input_builder::{CircuitInputBuilder, FixedCParams, NumberOrHash},
     mock::BlockData,
+    operation::RWCounter,
 };
 use eth_types::{bytecode, geth_types::GethData, ToWord, Word};
 use halo2_proofs::{
@@ -16,6 +17,7 @@ use halo2_proofs::{
 use mock::{
     test_ctx::helpers::account_0_code_account_1_no_code, Sha3CodeGen, TestContext, MOCK_ACCOUNTS,
 };
+use std::collections::HashMap;
 
 #[test]
 fn copy_circuit_unusable_rows() {
@@ -51,6 +53,7 @@ pub fn test_copy_circuit_from_block<F: Field>(
         ExternalData {
             max_txs: block.circuits_params.max_txs,
             max_calldata: block.circuits_params.max_calldata,
+            max_access_list: block.circuits_params.max_access_list,
             txs: block.txs,
             max_rws: block.circuits_params.max_rws,
             rws: block.rws,
@@ -287,6 +290,140 @@ fn copy_circuit_invalid_tx_log() {
     );
 }
 
+/// Return a copy event of `length` bytes within the memory of a call, as
+/// MCOPY generates, and the memory operations that it reads and writes.
+/// `interleaved` lays out each write right after its read instead of after
+/// all the reads.
+fn gen_memory_copy_data(
+    src_addr: u64,
+    dst_addr: u64,
+    length: u64,
+    interleaved: bool,
+) -> (CopyEvent, RwMap) {
+    let call_id = 1;
+    let memory = (1..=64).collect::<Vec<u8>>();
+    let bytes = (src_addr..src_addr + length)
+        .map(|addr| (memory[addr as usize], false))
+        .collect::<Vec<_>>();
+    let rws = bytes
+        .iter()
+        .enumerate()
+        .flat_map(|(idx, &(byte, _))| {
+            let (read_rwc, write_rwc) = if interleaved {
+                (2 * idx + 1, 2 * idx + 2)
+            } else {
+                (idx + 1, bytes.len() + idx + 1)
+            };
+            [
+                Rw::Memory {
+                    rw_counter: read_rwc,
+                    is_write: false,
+                    call_id,
+                    memory_address: src_addr + idx as u64,
+                    byte,
+                },
+                Rw::Memory {
+                    rw_counter: write_rwc,
+                    is_write: true,
+                    call_id,
+                    memory_address: dst_addr + idx as u64,
+                    byte,
+                },
+            ]
+        })
+        .collect();
+    let copy_event = CopyEvent {
+        src_type: CopyDataType::Memory,
+        src_id: NumberOrHash::Number(call_id),
+        src_addr,
+        src_addr_end: src_addr + length,
+        dst_type: CopyDataType::Memory,
+        dst_id: NumberOrHash::Number(call_id),
+        dst_addr,
+        log_id: None,
+        rw_counter_start: RWCounter(1),
+        bytes,
+        access_list: vec![],
+    };
+    (copy_event, RwMap(HashMap::from([(Target::Memory, rws)])))
+}
+
+fn test_copy_events(copy_events: Vec<CopyEvent>, rws: RwMap) -> Result<(), Vec<VerifyFailure>> {
+    test_copy_circuit::<Fr>(
+        10,
+        copy_events,
+        100,
+        ExternalData {
+            max_rws: 100,
+            rws,
+            ..Default::default()
+        },
+    )
+}
+
+#[test]
+fn copy_circuit_valid_memory_copy() {
+    // Forward and backward overlapping ranges, and disjoint ones
+    for (src_addr, dst_addr) in [(0, 8), (8, 0), (0, 32)] {
+        let (copy_event, rws) = gen_memory_copy_data(src_addr, dst_addr, 16, false);
+        assert!(copy_event.is_memory_copy());
+        assert_eq!(test_copy_events(vec![copy_event], rws), Ok(()));
+    }
+}
+
+#[test]
+fn copy_circuit_invalid_memory_copy() {
+    // All the reads of a memory copy come before its writes
+    let (copy_event, rws) = gen_memory_copy_data(0, 8, 16, true);
+    assert!(test_copy_events(vec![copy_event], rws).is_err());
+
+    // modify first byte of the copy event
+    let (mut copy_event, rws) = gen_memory_copy_data(0, 8, 16, false);
+    copy_event.bytes[0].0 = copy_event.bytes[0].0.wrapping_add(1);
+    assert_error_matches(
+        test_copy_events(vec![copy_event], rws),
+        vec!["Memory lookup", "Memory lookup"],
+    );
+}
+
+#[test]
+fn copy_circuit_valid_precompile_events() {
+    let (caller_id, call_id) = (1, 2);
+    let input = [0xaa, 0xbb, 0xcc];
+    let output = (1..=32).collect::<Vec<u8>>();
+    let copy_events = vec![
+        CopyEvent::precompile_input(caller_id, call_id, 0x10, &input, RWCounter(1)),
+        CopyEvent::precompile_output(call_id, &output, RWCounter(input.len() + 1)),
+    ];
+    // The reads of the input from the memory of the caller, then the writes
+    // of the output to the memory of the precompile call
+    let rws = input
+        .iter()
+        .enumerate()
+        .map(|(idx, &byte)| (false, caller_id, 0x10 + idx as u64, byte))
+        .chain(
+            output
+                .iter()
+                .enumerate()
+                .map(|(idx, &byte)| (true, call_id, idx as u64, byte)),
+        )
+        .enumerate()
+        .map(
+            |(idx, (is_write, call_id, memory_address, byte))| Rw::Memory {
+                rw_counter: idx + 1,
+                is_write,
+                call_id,
+                memory_address,
+                byte,
+            },
+        )
+        .collect();
+    assert_eq!(
+        test_copy_events(copy_events, RwMap(HashMap::from([(Target::Memory, rws)]))),
+        Ok(())
+    );
+}
+
 #[test]
 fn variadic_size_check() {
     let builder = gen_tx_log_data();
@@ -0,0 +1,827 @@
+//! ECC circuit.
+//!
+//! The circuit proves the point additions and scalar multiplications on the
+//! BN254 curve of the ecAdd and ecMul calls, and lays out every one of them in
+//! the [`EccTable`], so that the EVM circuit can look up the calls without
+//! computing them.
+//!
+//! A coordinate is assigned as the bytes of its word, and is in the base field
+//! when `coordinate + (2^256 - q)` doesn't overflow 256 bits, where `q` is the
+//! modulus of the base field.  The limbs of its base field integer are
+//! composed from its bytes, masked to 0 when it's not in the base field.  A
+//! point is valid when its coordinates are in the base field and it's on the
+//! curve or the point at infinity `(0, 0)`, where being on the curve is a
+//! witnessed bit constrained by the inverse of `y^2 - x^3 - 3` when it's 0.
+//!
+//! The point arithmetic is the one of the ECDSA verifications, whose additions
+//! are incomplete: the cases they don't cover, additions of the point at
+//! infinity, of opposite points and of a point to itself, are selected from
+//! flags of the inputs, and the additions of the other cases run on the
+//! generator instead.  Scalar multiplications go through the 256 bits of the
+//! scalar, which is replaced by 1 and the point by the generator when the
+//! product is the point at infinity, that is, when the point is invalid or the
+//! point at infinity, or when the scalar is a multiple of the order of the
+//! curve.  The result of an invalid input is `(0, 0)`.
+//!
+//! Every slot is a row of the table, after the all-zero first row, copied from
+//! the cells of its operation: the additions first and then the
+//! multiplications.  Padding slots prove the operations of the point at
+//! infinity and of the scalar 0.  The rows out of the slots have
+//! `is_enabled = 0`, as the table is made of advice columns.
+
+#[cfg(any(test, feature = "test-circuits"))]
+mod dev;
+#[cfg(test)]
+mod test;
+#[cfg(feature = "test-circuits")]
+pub use dev::EccCircuit as TestEccCircuit;
+
+use crate::{
+    table::{EccTable, LookupTable},
+    tx_circuit::sign_verify::{
+        assign_bytes_le, count_rows, range_chip_bit_lens, Base, Point, SignVerifyChip,
+        WeierstrassChip, WeierstrassConstants, BIT_LEN_LIMB, NUMBER_OF_LIMBS,
+    },
+    util::{word::Word, Challenges, Expr, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use bus_mapping::{
+    circuit_input_builder::{EcAddEvent, EcMulEvent},
+    precompile::PrecompileCalls,
+};
+use ecc::{AssignedPoint, EccConfig, GeneralEccChip};
+use eth_types::{Field, ToLittleEndian};
+use halo2_proofs::{
+    circuit::{Layouter, Value},
+    halo2curves::{
+        bn256::{Fq, Fr, G1Affine, G1},
+        ff::{Field as _, PrimeField},
+        group::{Curve, Group},
+    },
+    plonk::{Column, ConstraintSystem, Error, Fixed},
+    poly::Rotation,
+};
+use integer::{
+    rns::{Integer, Rns},
+    IntegerChip, IntegerInstructions, Range, UnassignedInteger,
+};
+use itertools::Itertools;
+use log::error;
+use maingate::{
+    AssignedCondition, AssignedValue, MainGate, MainGateConfig, MainGateInstructions, RangeChip,
+    RangeConfig, RangeInstructions, RegionCtx, Term,
+};
+use std::{iter, marker::PhantomData, rc::Rc};
+
+/// Window size of the scalar multiplications
+const WINDOW_SIZE: usize = 4;
+/// Number of multiples of the order of the curve that fit in 256 bits,
+/// including 0
+const NUM_ORDER_MULTIPLES: u64 = 6;
+
+/// Circuit configuration arguments
+pub struct EccCircuitConfigArgs {
+    /// EccTable
+    pub ecc_table: EccTable,
+}
+
+/// Config for EccCircuit
+#[derive(Clone, Debug)]
+pub struct EccCircuitConfig<F: Field> {
+    /// Enables the rows of the slots of the EccTable
+    q_enable: Column<Fixed>,
+    ecc_table: EccTable,
+    main_gate_config: MainGateConfig,
+    range_config: RangeConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for EccCircuitConfig<F> {
+    type ConfigArgs = EccCircuitConfigArgs;
+
+    /// Return a new EccCircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs { ecc_table }: Self::ConfigArgs,
+    ) -> Self {
+        let q_enable = meta.fixed_column();
+        for column in <EccTable as LookupTable<F>>::advice_columns(&ecc_table) {
+            meta.enable_equality(column);
+        }
+        meta.create_gate("ecc table is_enabled", |meta| {
+            let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+            let is_enabled = meta.query_advice(ecc_table.is_enabled, Rotation::cur());
+            vec![(1.expr() - q_enable) * is_enabled]
+        });
+
+        // The range table depends on the RNS of the curve fields, like in the SignVerifyChip.
+        let main_gate_config = MainGate::<F>::configure(meta);
+        let (composition_bit_lens, overflow_bit_lens) = range_chip_bit_lens::<F, G1Affine>();
+        let range_config = RangeChip::<F>::configure(
+            meta,
+            &main_gate_config,
+            composition_bit_lens,
+            overflow_bit_lens,
+        );
+
+        Self {
+            q_enable,
+            ecc_table,
+            main_gate_config,
+            range_config,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Chips of the ECC circuit
+struct EccChips<'a, F: Field> {
+    main_gate: &'a MainGate<F>,
+    range_chip: &'a RangeChip<F>,
+    base_chip: &'a IntegerChip<Fq, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+    weierstrass: WeierstrassChip<'a, G1Affine, F>,
+    rns_base: Rc<Rns<Fq, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>>,
+}
+
+/// Constants shared by the operations of the region
+struct EccConstants<F: Field> {
+    curve: WeierstrassConstants<G1Affine, F>,
+    /// Twice the generator, added to the generator instead of the inputs
+    double_generator: Point<G1Affine, F>,
+    /// Point at infinity, as (0, 0)
+    infinity: Point<G1Affine, F>,
+    one: Base<G1Affine, F>,
+}
+
+/// Coordinate of a point, with its base field integer, which is 0 when the
+/// coordinate isn't in the base field.
+struct AssignedCoordinate<F: Field> {
+    word: Word<AssignedValue<F>>,
+    in_field: AssignedCondition<F>,
+    integer: Base<G1Affine, F>,
+}
+
+/// Input point of an operation
+struct AssignedEcPoint<F: Field> {
+    x: AssignedCoordinate<F>,
+    y: AssignedCoordinate<F>,
+    point: Point<G1Affine, F>,
+    is_infinity: AssignedCondition<F>,
+    is_valid: AssignedCondition<F>,
+}
+
+/// Cells of a row of the EccTable
+struct AssignedEccRow<F: Field> {
+    is_enabled: AssignedValue<F>,
+    op_type: AssignedValue<F>,
+    args: [Word<AssignedValue<F>>; 4],
+    output: [Word<AssignedValue<F>>; 2],
+    is_valid: AssignedValue<F>,
+}
+
+impl<F: Field> AssignedEccRow<F> {
+    /// Return the cells in the order of the EccTable advice columns.
+    fn cells(&self) -> Vec<AssignedValue<F>> {
+        iter::once(self.is_enabled.clone())
+            .chain(iter::once(self.op_type.clone()))
+            .chain(
+                self.args
+                    .iter()
+                    .chain(&self.output)
+                    .flat_map(|word| [word.lo(), word.hi()]),
+            )
+            .chain(iter::once(self.is_valid.clone()))
+            .collect()
+    }
+}
+
+/// Return the modulus of a prime field as a word.
+fn modulus<FE: PrimeField>() -> eth_types::Word {
+    eth_types::Word::from_little_endian((-FE::ONE).to_repr().as_ref()) + 1
+}
+
+/// Return the 128-bit halves of a word.
+fn halves(word: eth_types::Word) -> [u128; 2] {
+    [word.low_u128(), (word >> 128).low_u128()]
+}
+
+/// Return the base field element of a coordinate, if it's in the base field.
+fn base_value(coordinate: eth_types::Word) -> Option<Fq> {
+    Option::from(Fq::from_repr(coordinate.to_le_bytes()))
+}
+
+/// Return whether a point is valid, as for the precompiles, and whether it's
+/// the point at infinity.
+fn point_flags(point: [eth_types::Word; 2]) -> (bool, bool) {
+    let is_infinity = point.iter().all(|coordinate| coordinate.is_zero());
+    let is_valid = match point.map(base_value) {
+        [Some(x), Some(y)] => is_infinity || y.square() == x.square() * x + Fq::from(3),
+        _ => false,
+    };
+    (is_valid, is_infinity)
+}
+
+impl<F: Field> EccCircuitConfig<F> {
+    /// Load the range table of the RangeChip
+    pub fn load_aux_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        let range_chip = RangeChip::<F>::new(self.range_config.clone());
+        range_chip.load_table(layouter)
+    }
+
+    fn ecc_chip_config(&self) -> EccConfig {
+        EccConfig::new(self.range_config.clone(), self.main_gate_config.clone())
+    }
+
+    /// Assign the EccTable, copying the rows from the assigned cells.
+    fn assign_ecc_table(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        rows: &[AssignedEccRow<F>],
+    ) -> Result<(), Error> {
+        let table = &self.ecc_table;
+        let advice_columns = <EccTable as LookupTable<F>>::advice_columns(table);
+        layouter.assign_region(
+            || "ecc table",
+            |mut region| {
+                // Empty entry
+                region.assign_fixed(|| "q_enable", self.q_enable, 0, || Value::known(F::ZERO))?;
+                for &column in &advice_columns {
+                    region.assign_advice(
+                        || "ecc table empty row",
+                        column,
+                        0,
+                        || Value::known(F::ZERO),
+                    )?;
+                }
+                for (i, row) in rows.iter().enumerate() {
+                    let offset = i + 1;
+                    region.assign_fixed(
+                        || "q_enable",
+                        self.q_enable,
+                        offset,
+                        || Value::known(F::ONE),
+                    )?;
+                    for (&column, cell) in advice_columns.iter().zip_eq(row.cells()) {
+                        cell.copy_advice(|| "ecc table row", &mut region, column, offset)?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// ECC Circuit for proving the BN254 operations of the EccTable
+#[derive(Clone, Debug)]
+pub struct EccCircuit<F: Field> {
+    /// Max number of supported point additions
+    pub max_ec_adds: usize,
+    /// Max number of supported scalar multiplications
+    pub max_ec_muls: usize,
+    /// Point additions to prove, one per slot
+    pub add_events: Vec<EcAddEvent>,
+    /// Scalar multiplications to prove, one per slot
+    pub mul_events: Vec<EcMulEvent>,
+    /// Offset of the entries of the window tables of the scalar
+    /// multiplications, as in the SignVerifyChip
+    aux_generator: G1Affine,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> Default for EccCircuit<F> {
+    fn default() -> Self {
+        Self::new(0, 0, vec![], vec![])
+    }
+}
+
+impl<F: Field> EccCircuit<F> {
+    /// Return a new EccCircuit with `max_ec_adds` addition slots and
+    /// `max_ec_muls` multiplication slots.
+    pub fn new(
+        max_ec_adds: usize,
+        max_ec_muls: usize,
+        add_events: Vec<EcAddEvent>,
+        mul_events: Vec<EcMulEvent>,
+    ) -> Self {
+        Self {
+            max_ec_adds,
+            max_ec_muls,
+            add_events,
+            mul_events,
+            aux_generator: SignVerifyChip::<F, G1Affine>::new(0).aux_generator(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the minimum number of rows required to prove `max_ec_adds`
+    /// additions and `max_ec_muls` multiplications.  Without slots, only the
+    /// empty row of the EccTable is assigned.
+    pub fn min_num_rows(max_ec_adds: usize, max_ec_muls: usize) -> usize {
+        if max_ec_adds + max_ec_muls == 0 {
+            return 1;
+        }
+        let (rows_constants, rows_per_add, rows_per_mul) = Self::count_rows_ecc();
+        itertools::max([
+            SignVerifyChip::<F, G1Affine>::rows_range_chip_table(),
+            1 + max_ec_adds + max_ec_muls,
+            rows_constants + rows_per_add * max_ec_adds + rows_per_mul * max_ec_muls,
+        ])
+        .unwrap()
+    }
+
+    /// Lay out the constants, one padding addition and one padding
+    /// multiplication, and return the number of rows used by each of them.
+    /// The layout doesn't depend on the witness, so every operation uses as
+    /// many rows as the padding one.
+    fn count_rows_ecc() -> (usize, usize, usize) {
+        let mut meta = ConstraintSystem::<F>::default();
+        let ecc_table = EccTable::construct(&mut meta);
+        let config = EccCircuitConfig::new(&mut meta, EccCircuitConfigArgs { ecc_table });
+
+        let circuit = Self::default();
+        let main_gate = MainGate::new(config.main_gate_config.clone());
+        let range_chip = RangeChip::new(config.range_config.clone());
+        let ecc_chip = GeneralEccChip::new(config.ecc_chip_config());
+        let chips = EccChips::new(&main_gate, &range_chip, &ecc_chip);
+        let (rows_constants, _) = count_rows(|ctx| circuit.assign_constants(ctx, &chips));
+        let (rows_add, _) = count_rows(|ctx| {
+            let constants = circuit.assign_constants(ctx, &chips)?;
+            circuit.assign_add(ctx, &chips, &constants, &EcAddEvent::default())
+        });
+        let (rows_mul, _) = count_rows(|ctx| {
+            let constants = circuit.assign_constants(ctx, &chips)?;
+            circuit.assign_mul(ctx, &chips, &constants, &EcMulEvent::default())
+        });
+        (
+            rows_constants,
+            rows_add - rows_constants,
+            rows_mul - rows_constants,
+        )
+    }
+
+    fn assign_constants(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        chips: &EccChips<F>,
+    ) -> Result<EccConstants<F>, Error> {
+        let base_chip = chips.base_chip;
+        let zero = base_chip.assign_constant(ctx, Fq::ZERO)?;
+        Ok(EccConstants {
+            curve: chips
+                .weierstrass
+                .assign_constants(ctx, self.aux_generator)?,
+            double_generator: chips
+                .weierstrass
+                .assign_constant(ctx, G1::generator().double().to_affine())?,
+            infinity: AssignedPoint::new(zero.clone(), zero),
+            one: base_chip.assign_constant(ctx, Fq::ONE)?,
+        })
+    }
+
+    /// Assign a coordinate, whose limbs are composed from its bytes, and are
+    /// masked to 0 when the coordinate isn't in the base field.
+    fn assign_coordinate(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        chips: &EccChips<F>,
+        coordinate: eth_types::Word,
+    ) -> Result<AssignedCoordinate<F>, Error> {
+        let main_gate = chips.main_gate;
+        let (word, bytes) = assign_bytes_le(ctx, chips.range_chip, &coordinate.to_le_bytes())?;
+
+        // coordinate + (2^256 - q) = sum + 2^256 * carry_hi, computed by halves
+        let complement = halves(eth_types::Word::zero().overflowing_sub(modulus::<Fq>()).0);
+        let [coordinate_lo, coordinate_hi] = halves(coordinate);
+        let (sum_lo, carry_lo) = coordinate_lo.overflowing_add(complement[0]);
+        let (sum_hi, carry_hi_0) = coordinate_hi.overflowing_add(complement[1]);
+        let (sum_hi, carry_hi_1) = sum_hi.overflowing_add(carry_lo as u128);
+        let carry_hi = carry_hi_0 || carry_hi_1;
+
+        let pow_2_128 = F::from_u128(1 << 64).square();
+        let [sum_lo, sum_hi] = [sum_lo, sum_hi].map(|sum| {
+            chips
+                .range_chip
+                .decompose(ctx, Value::known(F::from_u128(sum)), 8, 128)
+                .map(|(sum, _)| sum)
+        });
+        let (sum_lo, sum_hi) = (sum_lo?, sum_hi?);
+        let carry_lo = main_gate.assign_bit(ctx, Value::known(F::from(carry_lo as u64)))?;
+        let carry_hi = main_gate.assign_bit(ctx, Value::known(F::from(carry_hi as u64)))?;
+        // coordinate_lo + complement_lo = sum_lo + 2^128 * carry_lo
+        let lo = main_gate.compose(
+            ctx,
+            &[
+                Term::Assigned(&word.lo(), F::ONE),
+                Term::Assigned(&sum_lo, -F::ONE),
+                Term::Assigned(&carry_lo, -pow_2_128),
+            ],
+            F::from_u128(complement[0]),
+        )?;
+        main_gate.assert_zero(ctx, &lo)?;
+        // coordinate_hi + complement_hi + carry_lo = sum_hi + 2^128 * carry_hi
+        let hi = main_gate.compose(
+            ctx,
+            &[
+                Term::Assigned(&word.hi(), F::ONE),
+                Term::Assigned(&carry_lo, F::ONE),
+                Term::Assigned(&sum_hi, -F::ONE),
+                Term::Assigned(&carry_hi, -pow_2_128),
+            ],
+            F::from_u128(complement[1]),
+        )?;
+        main_gate.assert_zero(ctx, &hi)?;
+        let in_field = main_gate.not(ctx, &carry_hi)?;
+
+        let value = base_value(coordinate).unwrap_or(Fq::ZERO);
+        let integer = chips.base_chip.assign_integer(
+            ctx,
+            UnassignedInteger::from(Value::known(Integer::from_fe(
+                value,
+                chips.rns_base.clone(),
+            ))),
+            Range::Remainder,
+        )?;
+        for (limb, limb_bytes) in integer
+            .limbs()
+            .iter()
+            .zip_eq(bytes.chunks(BIT_LEN_LIMB / 8))
+        {
+            let terms = limb_bytes
+                .iter()
+                .enumerate()
+                .map(|(i, byte)| Term::Assigned(byte, F::from_u128(1 << (8 * i))))
+                .collect_vec();
+            let composed = main_gate.compose(ctx, &terms, F::ZERO)?;
+            let masked = main_gate.mul(ctx, &composed, &in_field)?;
+            main_gate.assert_equal(ctx, limb.as_ref(), &masked)?;
+        }
+
+        Ok(AssignedCoordinate {
+            word,
+            in_field,
+            integer,
+        })
+    }
+
+    /// Assign an input point, with its validity and whether it's the point at
+    /// infinity.
+    fn assign_point(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        chips: &EccChips<F>,
+        constants: &EccConstants<F>,
+        point: [eth_types::Word; 2],
+    ) -> Result<AssignedEcPoint<F>, Error> {
+        let (main_gate, base_chip) = (chips.main_gate, chips.base_chip);
+        let x = self.assign_coordinate(ctx, chips, point[0])?;
+        let y = self.assign_coordinate(ctx, chips, point[1])?;
+
+        // The halves are below 2^128, so that their sum is 0 only when they all are.
+        let coordinate_halves = [x.word.lo(), x.word.hi(), y.word.lo(), y.word.hi()];
+        let halves_sum = main_gate.compose(
+            ctx,
+            &coordinate_halves
+                .iter()
+                .map(|half| Term::Assigned(half, F::ONE))
+                .collect_vec(),
+            F::ZERO,
+        )?;
+        let is_infinity = main_gate.is_zero(ctx, &halves_sum)?;
+
+        // on_curve is 1 when diff = y^2 - x^3 - b is 0, and diff * inverse = 1 otherwise.
+        let [x_value, y_value] = point.map(|coordinate| base_value(coordinate).unwrap_or(Fq::ZERO));
+        let diff_value = y_value.square() - x_value.square() * x_value - Fq::from(3);
+        let on_curve = main_gate.assign_bit(
+            ctx,
+            Value::known(F::from(bool::from(diff_value.is_zero()) as u64)),
+        )?;
+        let inverse = base_chip.assign_integer(
+            ctx,
+            UnassignedInteger::from(Value::known(Integer::from_fe(
+                Option::from(diff_value.invert()).unwrap_or(Fq::ZERO),
+                chips.rns_base.clone(),
+            ))),
+            Range::Remainder,
+        )?;
+        let y_square = base_chip.square(ctx, &y.integer)?;
+        let x_square = base_chip.square(ctx, &x.integer)?;
+        let x_cube = base_chip.mul(ctx, &x_square, &x.integer)?;
+        let diff = base_chip.sub(ctx, &y_square, &x_cube)?;
+        let diff = base_chip.sub(ctx, &diff, &constants.curve.b)?;
+        let product = base_chip.mul(ctx, &diff, &inverse)?;
+        let product_minus_one = base_chip.sub(ctx, &product, &constants.one)?;
+        let must_be_zero = base_chip.select(ctx, &diff, &product_minus_one, &on_curve)?;
+        base_chip.assert_zero(ctx, &must_be_zero)?;
+
+        let in_field = main_gate.and(ctx, &x.in_field, &y.in_field)?;
+        let on_curve_or_infinity = main_gate.or(ctx, &on_curve, &is_infinity)?;
+        let is_valid = main_gate.and(ctx, &in_field, &on_curve_or_infinity)?;
+
+        Ok(AssignedEcPoint {
+            point: AssignedPoint::new(x.integer.clone(), y.integer.clone()),
+            x,
+            y,
+            is_infinity,
+            is_valid,
+        })
+    }
+
+    /// Assign the coordinates of the output of an operation, constraining
+    /// them to be the ones of `point`.
+    fn assign_output(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        chips: &EccChips<F>,
+        output: [eth_types::Word; 2],
+        point: &Point<G1Affine, F>,
+    ) -> Result<[Word<AssignedValue<F>>; 2], Error> {
+        let mut words = Vec::with_capacity(2);
+        for (coordinate, integer) in output.into_iter().zip_eq([point.x(), point.y()]) {
+            let coordinate = self.assign_coordinate(ctx, chips, coordinate)?;
+            chips.main_gate.assert_one(ctx, &coordinate.in_field)?;
+            chips
+                .base_chip
+                .assert_equal(ctx, &coordinate.integer, integer)?;
+            words.push(coordinate.word);
+        }
+        Ok(words.try_into().unwrap())
+    }
+
+    /// Return whether the halves of two words are equal.
+    fn words_equal(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        main_gate: &MainGate<F>,
+        a: &Word<AssignedValue<F>>,
+        b: &Word<AssignedValue<F>>,
+    ) -> Result<AssignedCondition<F>, Error> {
+        let diff_lo = main_gate.sub(ctx, &a.lo(), &b.lo())?;
+        let diff_hi = main_gate.sub(ctx, &a.hi(), &b.hi())?;
+        let lo_equal = main_gate.is_zero(ctx, &diff_lo)?;
+        let hi_equal = main_gate.is_zero(ctx, &diff_hi)?;
+        main_gate.and(ctx, &lo_equal, &hi_equal)
+    }
+
+    /// Return the row of a word of the constant 0.
+    fn assign_zero_word(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        main_gate: &MainGate<F>,
+    ) -> Result<Word<AssignedValue<F>>, Error> {
+        let lo = main_gate.assign_constant(ctx, F::ZERO)?;
+        let hi = main_gate.assign_constant(ctx, F::ZERO)?;
+        Ok(Word::new([lo, hi]))
+    }
+
+    /// Prove the point addition of an ecAdd event and return the cells of its
+    /// EccTable row.
+    fn assign_add(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        chips: &EccChips<F>,
+        constants: &EccConstants<F>,
+        event: &EcAddEvent,
+    ) -> Result<AssignedEccRow<F>, Error> {
+        let (main_gate, weierstrass) = (chips.main_gate, &chips.weierstrass);
+        let p = self.assign_point(ctx, chips, constants, event.p)?;
+        let q = self.assign_point(ctx, chips, constants, event.q)?;
+        let is_valid = main_gate.and(ctx, &p.is_valid, &q.is_valid)?;
+
+        // Both coordinates are in the base field when the input is valid, so that equal words
+        // are equal coordinates.
+        let x_equal = self.words_equal(ctx, main_gate, &p.x.word, &q.x.word)?;
+        let y_equal = self.words_equal(ctx, main_gate, &p.y.word, &q.y.word)?;
+        let p_is_finite = main_gate.not(ctx, &p.is_infinity)?;
+        let q_is_finite = main_gate.not(ctx, &q.is_infinity)?;
+        let are_finite = main_gate.and(ctx, &is_valid, &p_is_finite)?;
+        let are_finite = main_gate.and(ctx, &are_finite, &q_is_finite)?;
+        let x_differ = main_gate.not(ctx, &x_equal)?;
+        let is_add = main_gate.and(ctx, &are_finite, &x_differ)?;
+        let is_double = main_gate.and(ctx, &are_finite, &x_equal)?;
+        let is_double = main_gate.and(ctx, &is_double, &y_equal)?;
+        let only_p = main_gate.and(ctx, &is_valid, &q.is_infinity)?;
+        let only_q = main_gate.and(ctx, &is_valid, &p.is_infinity)?;
+
+        // The incomplete addition and the doubling run on the generator when their case isn't
+        // selected.
+        let generator = &constants.curve.generator;
+        let lhs = weierstrass.select(ctx, &is_add, &p.point, generator)?;
+        let rhs = weierstrass.select(ctx, &is_add, &q.point, &constants.double_generator)?;
+        let sum = weierstrass.add_incomplete(ctx, &lhs, &rhs)?;
+        let doubled = weierstrass.select(ctx, &is_double, &p.point, generator)?;
+        let double = weierstrass.double(ctx, &constants.curve.a, &doubled)?;
+
+        // The result of an invalid input and of opposite points is the point at infinity.
+        let result = weierstrass.select(ctx, &only_p, &p.point, &constants.infinity)?;
+        let result = weierstrass.select(ctx, &only_q, &q.point, &result)?;
+        let result = weierstrass.select(ctx, &is_double, &double, &result)?;
+        let result = weierstrass.select(ctx, &is_add, &sum, &result)?;
+
+        Ok(AssignedEccRow {
+            is_enabled: main_gate.assign_constant(ctx, F::ONE)?,
+            op_type: main_gate
+                .assign_constant(ctx, F::from(PrecompileCalls::Bn128Add.address()))?,
+            output: self.assign_output(ctx, chips, event.r, &result)?,
+            args: [p.x.word, p.y.word, q.x.word, q.y.word],
+            is_valid,
+        })
+    }
+
+    /// Prove the scalar multiplication of an ecMul event and return the cells
+    /// of its EccTable row.
+    fn assign_mul(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        chips: &EccChips<F>,
+        constants: &EccConstants<F>,
+        event: &EcMulEvent,
+    ) -> Result<AssignedEccRow<F>, Error> {
+        let (main_gate, weierstrass) = (chips.main_gate, &chips.weierstrass);
+        let p = self.assign_point(ctx, chips, constants, event.p)?;
+        let (s, _) = assign_bytes_le(ctx, chips.range_chip, &event.s.to_le_bytes())?;
+
+        // The scalar is a multiple of the order when it's equal to one of the multiples that
+        // fit in 256 bits.
+        let order = modulus::<Fr>();
+        let mut is_order_multiple = main_gate.assign_constant(ctx, F::ZERO)?;
+        for k in 0..NUM_ORDER_MULTIPLES {
+            let multiple = halves(order * eth_types::Word::from(k));
+            let diff_lo = main_gate.add_constant(ctx, &s.lo(), -F::from_u128(multiple[0]))?;
+            let diff_hi = main_gate.add_constant(ctx, &s.hi(), -F::from_u128(multiple[1]))?;
+            let lo_equal = main_gate.is_zero(ctx, &diff_lo)?;
+            let hi_equal = main_gate.is_zero(ctx, &diff_hi)?;
+            let is_multiple = main_gate.and(ctx, &lo_equal, &hi_equal)?;
+            is_order_multiple = main_gate.or(ctx, &is_order_multiple, &is_multiple)?;
+        }
+
+        // The product is the point at infinity unless the point is valid and finite and the
+        // scalar isn't a multiple of the order, in which case the multiplication runs on 1 and
+        // the generator.
+        let p_is_finite = main_gate.not(ctx, &p.is_infinity)?;
+        let is_not_order_multiple = main_gate.not(ctx, &is_order_multiple)?;
+        let is_mul = main_gate.and(ctx, &p.is_valid, &p_is_finite)?;
+        let is_mul = main_gate.and(ctx, &is_mul, &is_not_order_multiple)?;
+        let (is_valid_value, is_infinity_value) = point_flags(event.p);
+        let is_mul_value = is_valid_value && !is_infinity_value && !(event.s % order).is_zero();
+        let scalar_value = if is_mul_value {
+            event.s
+        } else {
+            eth_types::Word::one()
+        };
+        let (scalar, scalar_bytes) =
+            assign_bytes_le(ctx, chips.range_chip, &scalar_value.to_le_bytes())?;
+        let one = main_gate.assign_constant(ctx, F::ONE)?;
+        let zero = main_gate.assign_constant(ctx, F::ZERO)?;
+        let scalar_lo = main_gate.select(ctx, &s.lo(), &one, &is_mul)?;
+        let scalar_hi = main_gate.select(ctx, &s.hi(), &zero, &is_mul)?;
+        main_gate.assert_equal(ctx, &scalar.lo(), &scalar_lo)?;
+        main_gate.assert_equal(ctx, &scalar.hi(), &scalar_hi)?;
+        let scalar_bits = scalar_bytes
+            .iter()
+            .map(|byte| main_gate.to_bits(ctx, byte, 8))
+            .collect::<Result<Vec<_>, _>>()?
+            .concat();
+
+        let point = weierstrass.select(ctx, &is_mul, &p.point, &constants.curve.generator)?;
+        let product = weierstrass.mul_bits_batch(
+            ctx,
+            &constants.curve,
+            WINDOW_SIZE,
+            vec![(point, scalar_bits)],
+        )?;
+        let result = weierstrass.select(ctx, &is_mul, &product, &constants.infinity)?;
+
+        Ok(AssignedEccRow {
+            is_enabled: main_gate.assign_constant(ctx, F::ONE)?,
+            op_type: main_gate
+                .assign_constant(ctx, F::from(PrecompileCalls::Bn128Mul.address()))?,
+            output: self.assign_output(ctx, chips, event.r, &result)?,
+            args: [
+                p.x.word,
+                p.y.word,
+                s,
+                self.assign_zero_word(ctx, main_gate)?,
+            ],
+            is_valid: p.is_valid,
+        })
+    }
+}
+
+impl<'a, F: Field> EccChips<'a, F> {
+    fn new(
+        main_gate: &'a MainGate<F>,
+        range_chip: &'a RangeChip<F>,
+        ecc_chip: &'a GeneralEccChip<G1Affine, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+    ) -> Self {
+        let (rns_base, _) = GeneralEccChip::<G1Affine, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>::rns();
+        Self {
+            main_gate,
+            range_chip,
+            base_chip: ecc_chip.base_field_chip(),
+            weierstrass: WeierstrassChip::new(main_gate, ecc_chip),
+            rns_base: Rc::new(rns_base),
+        }
+    }
+}
+
+impl<F: Field> SubCircuit<F> for EccCircuit<F> {
+    type Config = EccCircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // No column queried at more than 3 distinct rotations, so returns 6 as
+        // minimum unusable rows.
+        6
+    }
+
+    /// The `block.circuits_params.max_ec_adds` and
+    /// `block.circuits_params.max_ec_muls` parameters set up the circuit to
+    /// support a fixed number of operations; when 0, the circuit only has the
+    /// slots of the ecAdd or ecMul calls.
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        let max_ec_adds = match block.circuits_params.max_ec_adds {
+            0 => block.ec_add_events.len(),
+            max_ec_adds => max_ec_adds,
+        };
+        let max_ec_muls = match block.circuits_params.max_ec_muls {
+            0 => block.ec_mul_events.len(),
+            max_ec_muls => max_ec_muls,
+        };
+        Self::new(
+            max_ec_adds,
+            max_ec_muls,
+            block.ec_add_events.clone(),
+            block.ec_mul_events.clone(),
+        )
+    }
+
+    /// The maingate of the circuit expects an instance column, which is
+    /// empty.
+    fn instance(&self) -> Vec<Vec<F>> {
+        vec![vec![]]
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        (
+            Self::min_num_rows(block.ec_add_events.len(), block.ec_mul_events.len()),
+            Self::min_num_rows(
+                block.circuits_params.max_ec_adds,
+                block.circuits_params.max_ec_muls,
+            ),
+        )
+    }
+
+    /// Make the assignments to the EccCircuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        _challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        if self.add_events.len() > self.max_ec_adds || self.mul_events.len() > self.max_ec_muls {
+            error!(
+                "add_events.len() = {} > max_ec_adds = {} or mul_events.len() = {} > max_ec_muls = {}",
+                self.add_events.len(),
+                self.max_ec_adds,
+                self.mul_events.len(),
+                self.max_ec_muls
+            );
+            return Err(Error::Synthesis);
+        }
+
+        if self.max_ec_adds + self.max_ec_muls == 0 {
+            // Circuits without slots, such as the ones of blocks without ecAdd or ecMul calls,
+            // don't load the range table.
+            return config.assign_ecc_table(layouter, &[]);
+        }
+
+        config.load_aux_tables(layouter)?;
+        let main_gate = MainGate::new(config.main_gate_config.clone());
+        let range_chip = RangeChip::new(config.range_config.clone());
+        let ecc_chip = GeneralEccChip::new(config.ecc_chip_config());
+        let chips = EccChips::new(&main_gate, &range_chip, &ecc_chip);
+        let rows = layouter.assign_region(
+            || "ecc",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+                let constants = self.assign_constants(&mut ctx, &chips)?;
+                let add_rows = self
+                    .add_events
+                    .iter()
+                    .chain(iter::repeat(&EcAddEvent::default()))
+                    .take(self.max_ec_adds)
+                    .map(|event| self.assign_add(&mut ctx, &chips, &constants, event))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let mul_rows = self
+                    .mul_events
+                    .iter()
+                    .chain(iter::repeat(&EcMulEvent::default()))
+                    .take(self.max_ec_muls)
+                    .map(|event| self.assign_mul(&mut ctx, &chips, &constants, event))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok([add_rows, mul_rows].concat())
+            },
+        )?;
+        config.assign_ecc_table(layouter, &rows)
+    }
+}
@@ -0,0 +1,138 @@
+use super::*;
+use crate::util::{log2_ceil, unusable_rows};
+use eth_types::Word;
+use halo2_proofs::{
+    arithmetic::CurveAffine,
+    dev::{MockProver, VerifyFailure},
+    halo2curves::{bn256::Fr, group::prime::PrimeCurveAffine},
+};
+
+#[test]
+fn ecc_circuit_unusable_rows() {
+    assert_eq!(
+        EccCircuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, EccCircuit::<Fr>>(()),
+    )
+}
+
+fn run<F: Field>(circuit: EccCircuit<F>) -> Result<(), Vec<VerifyFailure>> {
+    let k = log2_ceil(
+        EccCircuit::<Fr>::unusable_rows()
+            + EccCircuit::<Fr>::min_num_rows(circuit.max_ec_adds, circuit.max_ec_muls),
+    );
+    // MainGate instance column
+    let prover = match MockProver::run(k, &circuit, vec![vec![]]) {
+        Ok(prover) => prover,
+        Err(e) => panic!("{:#?}", e),
+    };
+    prover.verify()
+}
+
+/// Return the words of the coordinates of a point, which are 0 for the point
+/// at infinity.
+fn point_words(point: G1Affine) -> [Word; 2] {
+    if bool::from(point.is_identity()) {
+        return [Word::zero(); 2];
+    }
+    [point.x, point.y].map(|coordinate| Word::from_little_endian(&coordinate.to_repr()))
+}
+
+/// Return the point of valid coordinates.
+fn point(words: [Word; 2]) -> G1Affine {
+    match point_flags(words) {
+        (true, true) => G1Affine::identity(),
+        (true, false) => {
+            let [x, y] = words.map(|word| base_value(word).unwrap());
+            G1Affine::from_xy(x, y).unwrap()
+        }
+        _ => panic!("invalid point"),
+    }
+}
+
+/// Return the ecAdd event of `p + q`.
+fn add_event(p: [Word; 2], q: [Word; 2]) -> EcAddEvent {
+    let is_valid = point_flags(p).0 && point_flags(q).0;
+    EcAddEvent {
+        p,
+        q,
+        r: if is_valid {
+            point_words((point(p) + point(q)).to_affine())
+        } else {
+            [Word::zero(); 2]
+        },
+        is_valid,
+    }
+}
+
+/// Return the ecMul event of `s * p`.
+fn mul_event(p: [Word; 2], s: Word) -> EcMulEvent {
+    let is_valid = point_flags(p).0;
+    let scalar = Fr::from_repr((s % modulus::<Fr>()).to_le_bytes()).unwrap();
+    EcMulEvent {
+        p,
+        s,
+        r: if is_valid {
+            point_words((point(p) * scalar).to_affine())
+        } else {
+            [Word::zero(); 2]
+        },
+        is_valid,
+    }
+}
+
+fn generator_multiple(k: u64) -> [Word; 2] {
+    point_words((G1Affine::generator() * Fr::from(k)).to_affine())
+}
+
+#[test]
+fn ecc_circuit_add_and_mul() {
+    let g = generator_multiple(1);
+    let g2 = generator_multiple(2);
+    let neg_g = point_words(-G1Affine::generator());
+    let infinity = [Word::zero(); 2];
+    // Not on the curve
+    let invalid = [Word::one(), Word::one()];
+    // The x coordinate isn't in the base field
+    let out_of_field = [g[0] + modulus::<Fq>(), g[1]];
+    let add_events = vec![
+        add_event(g, g2),
+        add_event(g2, g2),
+        add_event(g, neg_g),
+        add_event(infinity, g),
+        add_event(g2, infinity),
+        add_event(g, invalid),
+        add_event(out_of_field, g),
+    ];
+    let mul_events = vec![
+        mul_event(g2, Word::from(5)),
+        mul_event(g, Word::MAX),
+        // Multiples of the order of the curve
+        mul_event(g, Word::zero()),
+        mul_event(g, modulus::<Fr>() * Word::from(3)),
+        mul_event(infinity, Word::from(5)),
+        mul_event(invalid, Word::from(5)),
+    ];
+    let circuit = EccCircuit::<Fr>::new(8, 7, add_events, mul_events);
+    assert_eq!(run(circuit), Ok(()));
+}
+
+#[test]
+fn ecc_circuit_wrong_output() {
+    let mut event = add_event(generator_multiple(1), generator_multiple(2));
+    event.r = generator_multiple(4);
+    let circuit = EccCircuit::<Fr>::new(1, 0, vec![event], vec![]);
+    assert!(run(circuit).is_err());
+
+    // The output of an invalid input is (0, 0).
+    let mut event = mul_event([Word::one(), Word::one()], Word::from(2));
+    event.r = generator_multiple(2);
+    let circuit = EccCircuit::<Fr>::new(0, 1, vec![], vec![event]);
+    assert!(run(circuit).is_err());
+}
+
+#[test]
+fn ecc_circuit_0max_ops() {
+    // Only the empty row of the EccTable is assigned.
+    let circuit = EccCircuit::<Fr>::new(0, 0, vec![], vec![]);
+    assert_eq!(run(circuit), Ok(()));
+}
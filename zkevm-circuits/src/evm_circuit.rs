@@ -16,18 +16,21 @@ pub(crate) mod test;
 use self::step::HasExecutionState;
 #[cfg(feature = "test-circuits")]
 pub use self::EvmCircuit as TestEvmCircuit;
+#[cfg(feature = "custom-opcodes")]
+pub use execution::register_custom_opcode_gadget;
 
 pub use crate::witness;
 use crate::{
     evm_circuit::param::{MAX_STEP_HEIGHT, STEP_STATE_HEIGHT},
     table::{
-        BlockTable, BytecodeTable, CopyTable, ExpTable, KeccakTable, LookupTable, RwTable, TxTable,
-        UXTable,
+        Blake2fTable, BlockHashTable, BlockTable, BytecodeTable, CopyTable, EccTable, ExpTable,
+        KeccakTable, LookupTable, ModExpTable, P256SigTable, Ripemd160Table, RwTable, Sha256Table,
+        SigTable, TxTable, UXTable,
     },
     util::{Challenges, SubCircuit, SubCircuitConfig},
 };
 use bus_mapping::evm::OpcodeId;
-use eth_types::Field;
+use eth_types::{evm_types::Hardfork, Field};
 use execution::ExecutionConfig;
 use itertools::Itertools;
 use strum::IntoEnumIterator;
@@ -37,6 +40,8 @@ use witness::Block;
 /// EvmCircuitConfig implements verification of execution trace of a block.
 #[derive(Clone, Debug)]
 pub struct EvmCircuitConfig<F> {
+    hardfork: Hardfork,
+    p256_verify: bool,
     fixed_table: [Column<Fixed>; 4],
     u8_table: UXTable<8>,
     u16_table: UXTable<16>,
@@ -50,10 +55,22 @@ pub struct EvmCircuitConfig<F> {
     copy_table: CopyTable,
     keccak_table: KeccakTable,
     exp_table: ExpTable,
+    sig_table: SigTable,
+    sha256_table: Sha256Table,
+    ripemd160_table: Ripemd160Table,
+    modexp_table: ModExpTable,
+    ecc_table: EccTable,
+    blake2f_table: Blake2fTable,
+    p256_sig_table: P256SigTable,
+    block_hash_table: BlockHashTable,
 }
 
 /// Circuit configuration arguments
 pub struct EvmCircuitConfigArgs<F: Field> {
+    /// Hardfork whose rules are constrained
+    pub hardfork: Hardfork,
+    /// Support the P256VERIFY precompile (RIP-7212) of the L2s
+    pub p256_verify: bool,
     /// Challenge
     pub challenges: Challenges<Expression<F>>,
     /// TxTable
@@ -70,6 +87,22 @@ pub struct EvmCircuitConfigArgs<F: Field> {
     pub keccak_table: KeccakTable,
     /// ExpTable
     pub exp_table: ExpTable,
+    /// SigTable
+    pub sig_table: SigTable,
+    /// Sha256Table
+    pub sha256_table: Sha256Table,
+    /// Ripemd160Table
+    pub ripemd160_table: Ripemd160Table,
+    /// ModExpTable
+    pub modexp_table: ModExpTable,
+    /// EccTable
+    pub ecc_table: EccTable,
+    /// Blake2fTable
+    pub blake2f_table: Blake2fTable,
+    /// P256SigTable
+    pub p256_sig_table: P256SigTable,
+    /// BlockHashTable
+    pub block_hash_table: BlockHashTable,
     /// U8Table
     pub u8_table: UXTable<8>,
     /// U16Table
@@ -83,6 +116,8 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
     fn new(
         meta: &mut ConstraintSystem<F>,
         Self::ConfigArgs {
+            hardfork,
+            p256_verify,
             challenges,
             tx_table,
             rw_table,
@@ -91,6 +126,14 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             copy_table,
             keccak_table,
             exp_table,
+            sig_table,
+            sha256_table,
+            ripemd160_table,
+            modexp_table,
+            ecc_table,
+            blake2f_table,
+            p256_sig_table,
+            block_hash_table,
             u8_table,
             u16_table,
         }: Self::ConfigArgs,
@@ -98,6 +141,8 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
         let fixed_table = [(); 4].map(|_| meta.fixed_column());
         let execution = Box::new(ExecutionConfig::configure(
             meta,
+            hardfork,
+            p256_verify,
             challenges,
             &fixed_table,
             &u8_table,
@@ -109,6 +154,14 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             &copy_table,
             &keccak_table,
             &exp_table,
+            &sig_table,
+            &sha256_table,
+            &ripemd160_table,
+            &modexp_table,
+            &ecc_table,
+            &blake2f_table,
+            &p256_sig_table,
+            &block_hash_table,
         ));
 
         u8_table.annotate_columns(meta);
@@ -123,10 +176,20 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
         copy_table.annotate_columns(meta);
         keccak_table.annotate_columns(meta);
         exp_table.annotate_columns(meta);
+        sig_table.annotate_columns(meta);
+        sha256_table.annotate_columns(meta);
+        ripemd160_table.annotate_columns(meta);
+        modexp_table.annotate_columns(meta);
+        ecc_table.annotate_columns(meta);
+        blake2f_table.annotate_columns(meta);
+        p256_sig_table.annotate_columns(meta);
+        block_hash_table.annotate_columns(meta);
         u8_table.annotate_columns(meta);
         u16_table.annotate_columns(meta);
 
         Self {
+            hardfork,
+            p256_verify,
             fixed_table,
             u8_table,
             u16_table,
@@ -138,6 +201,14 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             copy_table,
             keccak_table,
             exp_table,
+            sig_table,
+            sha256_table,
+            ripemd160_table,
+            modexp_table,
+            ecc_table,
+            blake2f_table,
+            p256_sig_table,
+            block_hash_table,
         }
     }
 }
@@ -153,7 +224,11 @@ impl<F: Field> EvmCircuitConfig<F> {
             || "fixed table",
             |mut region| {
                 for (offset, row) in std::iter::once([F::ZERO; 4])
-                    .chain(fixed_table_tags.iter().flat_map(|tag| tag.build()))
+                    .chain(
+                        fixed_table_tags
+                            .iter()
+                            .flat_map(|tag| tag.build(self.hardfork)),
+                    )
                     .enumerate()
                 {
                     for (column, value) in self.fixed_table.iter().zip_eq(row) {
@@ -167,6 +242,15 @@ impl<F: Field> EvmCircuitConfig<F> {
     }
 }
 
+/// Parameters of the EVM Circuit configuration
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EvmCircuitParams {
+    /// Hardfork whose rules are constrained
+    pub hardfork: Hardfork,
+    /// Support the P256VERIFY precompile (RIP-7212) of the L2s
+    pub p256_verify: bool,
+}
+
 /// Tx Circuit for verifying transaction signatures
 #[derive(Clone, Default, Debug)]
 pub struct EvmCircuit<F: Field> {
@@ -216,7 +300,12 @@ impl<F: Field> EvmCircuit<F> {
     }
     /// Compute the minimum number of rows required to process the block
     fn get_min_num_rows_required(block: &Block<F>) -> usize {
-        let mut num_rows = 0;
+        let mut num_rows = block
+            .beacon_root
+            .iter()
+            .chain(block.history_storage.iter())
+            .map(|step| step.execution_state().get_step_height())
+            .sum();
         for transaction in &block.txs {
             for step in transaction.steps() {
                 num_rows += step.execution_state().get_step_height();
@@ -246,7 +335,7 @@ impl<F: Field> SubCircuit<F> for EvmCircuit<F> {
         let num_rows_required_for_execution_steps: usize = Self::get_num_rows_required(block);
         let num_rows_required_for_fixed_table: usize = detect_fixed_table_tags(block)
             .iter()
-            .map(|tag| tag.build::<F>().count())
+            .map(|tag| tag.build::<F>(block.circuits_params.hardfork).count())
             .sum();
         (
             std::cmp::max(
@@ -265,6 +354,22 @@ impl<F: Field> SubCircuit<F> for EvmCircuit<F> {
         layouter: &mut impl Layouter<F>,
     ) -> Result<(), Error> {
         let block = self.block.as_ref().unwrap();
+        if block.circuits_params.hardfork != config.hardfork {
+            log::error!(
+                "block of {:?} proven by the EVM circuit configured for {:?}",
+                block.circuits_params.hardfork,
+                config.hardfork
+            );
+            return Err(Error::Synthesis);
+        }
+        if block.circuits_params.p256_verify != config.p256_verify {
+            log::error!(
+                "block with p256_verify {} proven by the EVM circuit configured with {}",
+                block.circuits_params.p256_verify,
+                config.p256_verify
+            );
+            return Err(Error::Synthesis);
+        }
 
         config.load_fixed_table(layouter, self.fixed_table_tags.clone())?;
         config.execution.assign_block(layouter, block, challenges)
@@ -325,12 +430,27 @@ pub(crate) mod cached {
     impl Circuit<Fr> for EvmCircuitCached {
         type Config = (EvmCircuitConfig<Fr>, Challenges);
         type FloorPlanner = SimpleFloorPlanner;
-        type Params = ();
+        type Params = EvmCircuitParams;
 
         fn without_witnesses(&self) -> Self {
             Self(self.0.without_witnesses())
         }
 
+        fn params(&self) -> Self::Params {
+            self.0.params()
+        }
+
+        /// Only the configuration for the default parameters is cached.
+        fn configure_with_params(
+            meta: &mut ConstraintSystem<Fr>,
+            params: Self::Params,
+        ) -> Self::Config {
+            if params != EvmCircuitParams::default() {
+                return EvmCircuit::<Fr>::configure_with_params(meta, params);
+            }
+            Self::configure(meta)
+        }
+
         fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
             *meta = CACHE.cs.clone();
             CACHE.config.clone()
@@ -356,13 +476,27 @@ pub(crate) mod cached {
 impl<F: Field> Circuit<F> for EvmCircuit<F> {
     type Config = (EvmCircuitConfig<F>, Challenges);
     type FloorPlanner = SimpleFloorPlanner;
-    type Params = ();
+    type Params = EvmCircuitParams;
 
     fn without_witnesses(&self) -> Self {
         Self::default()
     }
 
+    fn params(&self) -> Self::Params {
+        self.block
+            .as_ref()
+            .map(|block| EvmCircuitParams {
+                hardfork: block.circuits_params.hardfork,
+                p256_verify: block.circuits_params.p256_verify,
+            })
+            .unwrap_or_default()
+    }
+
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        Self::configure_with_params(meta, EvmCircuitParams::default())
+    }
+
+    fn configure_with_params(meta: &mut ConstraintSystem<F>, params: Self::Params) -> Self::Config {
         let tx_table = TxTable::construct(meta);
         let rw_table = RwTable::construct(meta);
         let bytecode_table = BytecodeTable::construct(meta);
@@ -371,6 +505,14 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
         let copy_table = CopyTable::construct(meta, q_copy_table);
         let keccak_table = KeccakTable::construct(meta);
         let exp_table = ExpTable::construct(meta);
+        let sig_table = SigTable::construct(meta);
+        let sha256_table = Sha256Table::construct(meta);
+        let ripemd160_table = Ripemd160Table::construct(meta);
+        let modexp_table = ModExpTable::construct(meta);
+        let ecc_table = EccTable::construct(meta);
+        let blake2f_table = Blake2fTable::construct(meta);
+        let p256_sig_table = P256SigTable::construct(meta);
+        let block_hash_table = BlockHashTable::construct(meta);
         let u8_table = UXTable::construct(meta);
         let u16_table = UXTable::construct(meta);
         let challenges = Challenges::construct(meta);
@@ -380,6 +522,8 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
             EvmCircuitConfig::new(
                 meta,
                 EvmCircuitConfigArgs {
+                    hardfork: params.hardfork,
+                    p256_verify: params.p256_verify,
                     challenges: challenges_expr,
                     tx_table,
                     rw_table,
@@ -388,6 +532,14 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
                     copy_table,
                     keccak_table,
                     exp_table,
+                    sig_table,
+                    sha256_table,
+                    ripemd160_table,
+                    modexp_table,
+                    ecc_table,
+                    blake2f_table,
+                    p256_sig_table,
+                    block_hash_table,
                     u8_table,
                     u16_table,
                 },
@@ -411,6 +563,7 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
             &block.txs,
             block.circuits_params.max_txs,
             block.circuits_params.max_calldata,
+            block.circuits_params.max_access_list,
         )?;
         block.rws.check_rw_counter_sanity();
         config.rw_table.load(
@@ -427,6 +580,30 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
             .keccak_table
             .dev_load(&mut layouter, &block.sha3_inputs, &challenges)?;
         config.exp_table.load(&mut layouter, block)?;
+        config
+            .sig_table
+            .dev_load(&mut layouter, &block.ecrecover_events)?;
+        config
+            .sha256_table
+            .dev_load(&mut layouter, &block.sha256_inputs, &challenges)?;
+        config
+            .ripemd160_table
+            .dev_load(&mut layouter, &block.ripemd160_inputs, &challenges)?;
+        config
+            .modexp_table
+            .dev_load(&mut layouter, &block.modexp_events)?;
+        config
+            .ecc_table
+            .dev_load(&mut layouter, &block.ec_add_events, &block.ec_mul_events)?;
+        config
+            .blake2f_table
+            .dev_load(&mut layouter, &block.blake2f_events, &challenges)?;
+        config
+            .p256_sig_table
+            .dev_load(&mut layouter, &block.p256_verify_events)?;
+        config
+            .block_hash_table
+            .load(&mut layouter, &block.context)?;
 
         config.u8_table.load(&mut layouter)?;
         config.u16_table.load(&mut layouter)?;
@@ -438,29 +615,151 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
 #[cfg(test)]
 mod evm_circuit_stats {
     use crate::{
-        evm_circuit::EvmCircuit,
+        evm_circuit::{
+            step::{ExecutionState, ResponsibleOp},
+            table::FixedTableTag,
+            EvmCircuit, EvmCircuitParams,
+        },
         test_util::CircuitTestBuilder,
         util::{unusable_rows, SubCircuit},
         witness::block_convert,
     };
-    use bus_mapping::{circuit_input_builder::FixedCParams, mock::BlockData};
+    use bus_mapping::{
+        circuit_input_builder::FixedCParams, mock::BlockData, precompile::PrecompileCalls,
+    };
 
-    use eth_types::{bytecode, geth_types::GethData};
+    use eth_types::{
+        bytecode,
+        evm_types::{GasCost, Hardfork, OpcodeId},
+        geth_types::GethData,
+    };
     use halo2_proofs::{self, dev::MockProver, halo2curves::bn256::Fr};
 
     use mock::test_ctx::{
         helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
         TestContext,
     };
+    use strum::IntoEnumIterator;
 
     #[test]
     fn evm_circuit_unusable_rows() {
         assert_eq!(
             EvmCircuit::<Fr>::unusable_rows(),
-            unusable_rows::<Fr, EvmCircuit::<Fr>>(()),
+            unusable_rows::<Fr, EvmCircuit::<Fr>>(EvmCircuitParams::default()),
         )
     }
 
+    #[test]
+    fn responsible_opcodes_follow_hardfork() {
+        let has_row = |hardfork: Hardfork, execution_state: ExecutionState, opcode: OpcodeId| {
+            FixedTableTag::ResponsibleOpcode
+                .build::<Fr>(hardfork)
+                .any(|row| {
+                    row[1] == Fr::from(execution_state.as_u64())
+                        && row[2] == Fr::from(opcode.as_u64())
+                        && row[3] == Fr::from(0)
+                })
+        };
+        for (hardfork, opcode, is_active) in [
+            (Hardfork::Paris, OpcodeId::PUSH0, false),
+            (Hardfork::Shanghai, OpcodeId::PUSH0, true),
+            (Hardfork::Shanghai, OpcodeId::MCOPY, false),
+            (Hardfork::Cancun, OpcodeId::MCOPY, true),
+        ] {
+            let execution_state = ExecutionState::iter()
+                .find(|state| {
+                    state
+                        .responsible_opcodes()
+                        .iter()
+                        .any(|op| matches!(op, ResponsibleOp::Op(op) if *op == opcode))
+                })
+                .unwrap();
+            assert_eq!(has_row(hardfork, execution_state, opcode), is_active);
+            assert_eq!(
+                has_row(hardfork, ExecutionState::ErrorInvalidOpcode, opcode),
+                !is_active
+            );
+        }
+    }
+
+    #[test]
+    fn precompile_gas_costs_follow_hardfork() {
+        let gas_cost = |hardfork: Hardfork, precompile: PrecompileCalls| {
+            let rows = FixedTableTag::PrecompileGasCost
+                .build::<Fr>(hardfork)
+                .filter(|row| row[1] == Fr::from(precompile.address()))
+                .collect::<Vec<_>>();
+            assert!(rows.len() <= 1);
+            rows.first().map(|row| (row[2], row[3]))
+        };
+        for hardfork in Hardfork::iter() {
+            // The point evaluation exists from Cancun (EIP-4844).
+            assert_eq!(
+                gas_cost(hardfork, PrecompileCalls::PointEvaluation),
+                (hardfork >= Hardfork::Cancun)
+                    .then(|| (Fr::from(GasCost::PRECOMPILE_POINT_EVALUATION), Fr::from(0)))
+            );
+            for (precompile, base, per_word) in [
+                (
+                    PrecompileCalls::ECRecover,
+                    GasCost::PRECOMPILE_ECRECOVER_BASE,
+                    0,
+                ),
+                (
+                    PrecompileCalls::Sha256,
+                    GasCost::PRECOMPILE_SHA256_BASE,
+                    GasCost::PRECOMPILE_SHA256_PER_WORD,
+                ),
+                (
+                    PrecompileCalls::Identity,
+                    GasCost::PRECOMPILE_IDENTITY_BASE,
+                    GasCost::PRECOMPILE_IDENTITY_PER_WORD,
+                ),
+                (PrecompileCalls::Modexp, GasCost::PRECOMPILE_MODEXP_MIN, 0),
+                (
+                    PrecompileCalls::Bn128Pairing,
+                    GasCost::PRECOMPILE_BN256PAIRING,
+                    GasCost::PRECOMPILE_BN256PAIRING_PER_PAIR,
+                ),
+                (
+                    PrecompileCalls::Blake2F,
+                    0,
+                    GasCost::PRECOMPILE_BLAKE2F_PER_ROUND,
+                ),
+                (
+                    PrecompileCalls::P256Verify,
+                    GasCost::PRECOMPILE_P256VERIFY,
+                    0,
+                ),
+            ] {
+                assert_eq!(
+                    gas_cost(hardfork, precompile),
+                    Some((Fr::from(base), Fr::from(per_word)))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn evm_circuit_for_each_hardfork() {
+        for hardfork in Hardfork::iter() {
+            CircuitTestBuilder::new_from_test_ctx(
+                TestContext::<2, 1>::new(
+                    None,
+                    account_0_code_account_1_no_code(bytecode! { STOP }),
+                    tx_from_1_to_0,
+                    |b, _| b,
+                )
+                .unwrap(),
+            )
+            .params(FixedCParams {
+                hardfork,
+                ..Default::default()
+            })
+            .run();
+        }
+    }
+
     #[test]
     pub fn empty_evm_circuit_no_padding() {
         CircuitTestBuilder::new_from_test_ctx(
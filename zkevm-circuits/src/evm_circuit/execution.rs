@@ -1,8 +1,11 @@
 use super::{
     param::{
-        BLOCK_TABLE_LOOKUPS, BYTECODE_TABLE_LOOKUPS, COPY_TABLE_LOOKUPS, EXP_TABLE_LOOKUPS,
-        FIXED_TABLE_LOOKUPS, KECCAK_TABLE_LOOKUPS, N_COPY_COLUMNS, N_PHASE1_COLUMNS, N_U16_LOOKUPS,
-        N_U8_LOOKUPS, RW_TABLE_LOOKUPS, TX_TABLE_LOOKUPS,
+        BLAKE2F_TABLE_LOOKUPS, BLOCK_HASH_TABLE_LOOKUPS, BLOCK_TABLE_LOOKUPS,
+        BYTECODE_TABLE_LOOKUPS, COPY_TABLE_LOOKUPS, ECC_TABLE_LOOKUPS, EXP_TABLE_LOOKUPS,
+        FIXED_TABLE_LOOKUPS, KECCAK_TABLE_LOOKUPS, MODEXP_TABLE_LOOKUPS, N_COPY_COLUMNS,
+        N_PHASE1_COLUMNS, N_U16_LOOKUPS, N_U8_LOOKUPS, P256_SIG_TABLE_LOOKUPS,
+        RIPEMD160_TABLE_LOOKUPS, RW_TABLE_LOOKUPS, SHA256_TABLE_LOOKUPS, SIG_TABLE_LOOKUPS,
+        TX_TABLE_LOOKUPS,
     },
     step::HasExecutionState,
     util::{instrumentation::Instrument, CachedRegion, StoredExpression},
@@ -27,7 +30,7 @@ use crate::{
     },
 };
 use bus_mapping::operation::Target;
-use eth_types::{evm_unimplemented, Field};
+use eth_types::{evm_types::Hardfork, evm_unimplemented, Field};
 use gadgets::util::not;
 use halo2_proofs::{
     circuit::{Layouter, Region, Value},
@@ -47,8 +50,11 @@ mod add_sub;
 mod addmod;
 mod address;
 mod balance;
+mod beacon_root;
 mod begin_tx;
 mod bitwise;
+mod blobbasefee;
+mod blobhash;
 mod block_ctx;
 mod blockhash;
 mod byte;
@@ -63,6 +69,9 @@ mod codecopy;
 mod codesize;
 mod comparator;
 mod create;
+#[cfg(feature = "custom-opcodes")]
+mod custom;
+mod delete_account;
 mod dummy;
 mod dup;
 mod end_block;
@@ -80,11 +89,13 @@ mod error_oog_exp;
 mod error_oog_log;
 mod error_oog_memory_copy;
 mod error_oog_precompile;
+mod error_oog_selfdestruct;
 mod error_oog_sha3;
 mod error_oog_sload_sstore;
 mod error_oog_static_memory;
 mod error_precompile_failed;
 mod error_return_data_oo_bound;
+mod error_simple;
 mod error_stack;
 mod error_write_protection;
 mod exp;
@@ -93,12 +104,14 @@ mod extcodehash;
 mod extcodesize;
 mod gas;
 mod gasprice;
+mod history_storage;
 mod invalid_tx;
 mod is_zero;
 mod jump;
 mod jumpdest;
 mod jumpi;
 mod logs;
+mod mcopy;
 mod memory;
 mod msize;
 mod mul_div_mod;
@@ -108,7 +121,7 @@ mod opcode_not;
 mod origin;
 mod pc;
 mod pop;
-mod precompiles;
+pub(crate) mod precompiles;
 mod push;
 mod return_revert;
 mod returndatacopy;
@@ -116,6 +129,7 @@ mod returndatasize;
 mod sar;
 mod sdiv_smod;
 mod selfbalance;
+mod selfdestruct;
 mod sha3;
 mod shl_shr;
 mod signed_comparator;
@@ -124,14 +138,19 @@ mod sload;
 mod sstore;
 mod stop;
 mod swap;
+mod tload;
+mod tstore;
 
 use self::{block_ctx::BlockCtxGadget, sha3::Sha3Gadget};
 use add_sub::AddSubGadget;
 use addmod::AddModGadget;
 use address::AddressGadget;
 use balance::BalanceGadget;
+use beacon_root::BeaconRootGadget;
 use begin_tx::BeginTxGadget;
 use bitwise::BitwiseGadget;
+use blobbasefee::BlobBaseFeeGadget;
+use blobhash::BlobHashGadget;
 use blockhash::BlockHashGadget;
 use byte::ByteGadget;
 use calldatacopy::CallDataCopyGadget;
@@ -145,6 +164,11 @@ use codecopy::CodeCopyGadget;
 use codesize::CodesizeGadget;
 use comparator::ComparatorGadget;
 use create::CreateGadget;
+#[cfg(feature = "custom-opcodes")]
+pub use custom::register_custom_opcode_gadget;
+#[cfg(feature = "custom-opcodes")]
+use custom::CustomGadget;
+use delete_account::DeleteAccountGadget;
 use dummy::DummyGadget;
 use dup::DupGadget;
 use end_block::EndBlockGadget;
@@ -161,6 +185,7 @@ use error_oog_dynamic_memory::ErrorOOGDynamicMemoryGadget;
 use error_oog_exp::ErrorOOGExpGadget;
 use error_oog_log::ErrorOOGLogGadget;
 use error_oog_memory_copy::ErrorOOGMemoryCopyGadget;
+use error_oog_selfdestruct::ErrorOOGSelfdestructGadget;
 use error_oog_sha3::ErrorOOGSha3Gadget;
 use error_oog_sload_sstore::ErrorOOGSloadSstoreGadget;
 use error_oog_static_memory::ErrorOOGStaticMemoryGadget;
@@ -174,12 +199,14 @@ use extcodehash::ExtcodehashGadget;
 use extcodesize::ExtcodesizeGadget;
 use gas::GasGadget;
 use gasprice::GasPriceGadget;
+use history_storage::HistoryStorageGadget;
 use invalid_tx::InvalidTxGadget;
 use is_zero::IsZeroGadget;
 use jump::JumpGadget;
 use jumpdest::JumpdestGadget;
 use jumpi::JumpiGadget;
 use logs::LogGadget;
+use mcopy::McopyGadget;
 
 use crate::evm_circuit::execution::error_oog_precompile::ErrorOOGPrecompileGadget;
 use memory::MemoryGadget;
@@ -190,7 +217,10 @@ use opcode_not::NotGadget;
 use origin::OriginGadget;
 use pc::PcGadget;
 use pop::PopGadget;
-use precompiles::IdentityGadget;
+use precompiles::{
+    Blake2fGadget, EcAddGadget, EcMulGadget, EcrecoverGadget, IdentityGadget, ModExpGadget,
+    P256VerifyGadget, Ripemd160Gadget, Sha256Gadget,
+};
 use push::PushGadget;
 use return_revert::ReturnRevertGadget;
 use returndatacopy::ReturnDataCopyGadget;
@@ -198,6 +228,7 @@ use returndatasize::ReturnDataSizeGadget;
 use sar::SarGadget;
 use sdiv_smod::SignedDivModGadget;
 use selfbalance::SelfbalanceGadget;
+use selfdestruct::SelfdestructGadget;
 use shl_shr::ShlShrGadget;
 use signed_comparator::SignedComparatorGadget;
 use signextend::SignextendGadget;
@@ -205,6 +236,8 @@ use sload::SloadGadget;
 use sstore::SstoreGadget;
 use stop::StopGadget;
 use swap::SwapGadget;
+use tload::TloadGadget;
+use tstore::TstoreGadget;
 
 pub(crate) trait ExecutionGadget<F: Field> {
     const NAME: &'static str;
@@ -248,9 +281,12 @@ pub struct ExecutionConfig<F> {
     debug_expressions_map: HashMap<ExecutionState, Vec<(String, Expression<F>)>>,
     instrument: Instrument,
     // internal state gadgets
+    beacon_root_gadget: Box<BeaconRootGadget<F>>,
+    history_storage_gadget: Box<HistoryStorageGadget<F>>,
     begin_tx_gadget: Box<BeginTxGadget<F>>,
     end_block_gadget: Box<EndBlockGadget<F>>,
     end_tx_gadget: Box<EndTxGadget<F>>,
+    delete_account_gadget: Box<DeleteAccountGadget<F>>,
     // opcode gadgets
     add_sub_gadget: Box<AddSubGadget<F>>,
     addmod_gadget: Box<AddModGadget<F>>,
@@ -280,6 +316,7 @@ pub struct ExecutionConfig<F> {
     jumpdest_gadget: Box<JumpdestGadget<F>>,
     jumpi_gadget: Box<JumpiGadget<F>>,
     log_gadget: Box<LogGadget<F>>,
+    mcopy_gadget: Box<McopyGadget<F>>,
     memory_gadget: Box<MemoryGadget<F>>,
     msize_gadget: Box<MsizeGadget<F>>,
     mul_div_mod_gadget: Box<MulDivModGadget<F>>,
@@ -299,14 +336,20 @@ pub struct ExecutionConfig<F> {
     returndatacopy_gadget: Box<ReturnDataCopyGadget<F>>,
     create_gadget: Box<CreateGadget<F, false, { ExecutionState::CREATE }>>,
     create2_gadget: Box<CreateGadget<F, true, { ExecutionState::CREATE2 }>>,
-    selfdestruct_gadget: Box<DummyGadget<F, 1, 0, { ExecutionState::SELFDESTRUCT }>>,
+    selfdestruct_gadget: Box<SelfdestructGadget<F>>,
+    #[cfg(feature = "custom-opcodes")]
+    custom_gadget: Box<CustomGadget<F>>,
     signed_comparator_gadget: Box<SignedComparatorGadget<F>>,
     signextend_gadget: Box<SignextendGadget<F>>,
     sload_gadget: Box<SloadGadget<F>>,
     sstore_gadget: Box<SstoreGadget<F>>,
     stop_gadget: Box<StopGadget<F>>,
     swap_gadget: Box<SwapGadget<F>>,
+    tload_gadget: Box<TloadGadget<F>>,
+    tstore_gadget: Box<TstoreGadget<F>>,
     blockhash_gadget: Box<BlockHashGadget<F>>,
+    blobhash_gadget: Box<BlobHashGadget<F>>,
+    blob_base_fee_gadget: Box<BlobBaseFeeGadget<F>>,
     block_ctx_gadget: Box<BlockCtxGadget<F>>,
     // error gadgets
     error_oog_call: Box<ErrorOOGCallGadget<F>>,
@@ -324,20 +367,22 @@ pub struct ExecutionConfig<F> {
     error_oog_account_access: Box<ErrorOOGAccountAccessGadget<F>>,
     error_oog_ext_codecopy: Box<DummyGadget<F, 0, 0, { ExecutionState::ErrorOutOfGasEXTCODECOPY }>>,
     error_oog_create: Box<ErrorOOGCreateGadget<F>>,
-    error_oog_self_destruct:
-        Box<DummyGadget<F, 0, 0, { ExecutionState::ErrorOutOfGasSELFDESTRUCT }>>,
+    error_oog_self_destruct: Box<ErrorOOGSelfdestructGadget<F>>,
     error_oog_code_store: Box<ErrorCodeStoreGadget<F>>,
     error_invalid_jump: Box<ErrorInvalidJumpGadget<F>>,
     error_invalid_opcode: Box<ErrorInvalidOpcodeGadget<F>>,
-    #[allow(dead_code, reason = "under active development")]
-    error_depth: Box<DummyGadget<F, 0, 0, { ExecutionState::ErrorDepth }>>,
-    #[allow(dead_code, reason = "under active development")]
-    error_contract_address_collision:
-        Box<DummyGadget<F, 0, 0, { ExecutionState::ErrorContractAddressCollision }>>,
     error_invalid_creation_code: Box<ErrorInvalidCreationCodeGadget<F>>,
     error_precompile_failed: Box<ErrorPrecompileFailedGadget<F>>,
     error_return_data_out_of_bound: Box<ErrorReturnDataOutOfBoundGadget<F>>,
+    precompile_ecrecover_gadget: Box<EcrecoverGadget<F>>,
     precompile_identity_gadget: Box<IdentityGadget<F>>,
+    precompile_sha256_gadget: Box<Sha256Gadget<F>>,
+    precompile_ripemd160_gadget: Box<Ripemd160Gadget<F>>,
+    precompile_modexp_gadget: Box<ModExpGadget<F>>,
+    precompile_ec_add_gadget: Box<EcAddGadget<F>>,
+    precompile_ec_mul_gadget: Box<EcMulGadget<F>>,
+    precompile_blake2f_gadget: Box<Blake2fGadget<F>>,
+    precompile_p256_verify_gadget: Box<P256VerifyGadget<F>>,
     invalid_tx: Box<InvalidTxGadget<F>>,
 }
 
@@ -346,6 +391,8 @@ impl<F: Field> ExecutionConfig<F> {
     #[allow(clippy::redundant_closure_call)]
     pub(crate) fn configure(
         meta: &mut ConstraintSystem<F>,
+        hardfork: Hardfork,
+        p256_verify: bool,
         challenges: Challenges<Expression<F>>,
         fixed_table: &dyn LookupTable<F>,
         u8_table: &dyn LookupTable<F>,
@@ -357,6 +404,14 @@ impl<F: Field> ExecutionConfig<F> {
         copy_table: &dyn LookupTable<F>,
         keccak_table: &dyn LookupTable<F>,
         exp_table: &dyn LookupTable<F>,
+        sig_table: &dyn LookupTable<F>,
+        sha256_table: &dyn LookupTable<F>,
+        ripemd160_table: &dyn LookupTable<F>,
+        modexp_table: &dyn LookupTable<F>,
+        ecc_table: &dyn LookupTable<F>,
+        blake2f_table: &dyn LookupTable<F>,
+        p256_sig_table: &dyn LookupTable<F>,
+        block_hash_table: &dyn LookupTable<F>,
     ) -> Self {
         let mut instrument = Instrument::default();
         let q_usable = meta.complex_selector();
@@ -397,14 +452,16 @@ impl<F: Field> ExecutionConfig<F> {
 
             // NEW: Enabled, this will break hand crafted tests, maybe we can remove them?
             let first_step_check = {
-                let begin_tx_invalid_tx_end_block_selector = step_curr.execution_state_selector([
+                let first_step_selector = step_curr.execution_state_selector([
+                    ExecutionState::BeaconRoot,
+                    ExecutionState::HistoryStorage,
                     ExecutionState::BeginTx,
                     ExecutionState::InvalidTx,
                     ExecutionState::EndBlock,
                 ]);
                 iter::once((
-                    "First step should be BeginTx, InvalidTx or EndBlock",
-                    q_step_first * (1.expr() - begin_tx_invalid_tx_end_block_selector),
+                    "First step should be a system call, BeginTx, InvalidTx or EndBlock",
+                    q_step_first * (1.expr() - first_step_selector),
                 ))
             };
 
@@ -492,6 +549,8 @@ impl<F: Field> ExecutionConfig<F> {
                 (|| {
                     Box::new(Self::configure_gadget(
                         meta,
+                        hardfork,
+                        p256_verify,
                         advices,
                         q_usable,
                         q_step,
@@ -521,9 +580,12 @@ impl<F: Field> ExecutionConfig<F> {
             q_step_last,
             advices,
             // internal states
+            beacon_root_gadget: configure_gadget!(),
+            history_storage_gadget: configure_gadget!(),
             begin_tx_gadget: configure_gadget!(),
             end_block_gadget: configure_gadget!(),
             end_tx_gadget: configure_gadget!(),
+            delete_account_gadget: configure_gadget!(),
             invalid_tx: configure_gadget!(),
             // opcode gadgets
             add_sub_gadget: configure_gadget!(),
@@ -550,6 +612,7 @@ impl<F: Field> ExecutionConfig<F> {
             jumpdest_gadget: configure_gadget!(),
             jumpi_gadget: configure_gadget!(),
             log_gadget: configure_gadget!(),
+            mcopy_gadget: configure_gadget!(),
             memory_gadget: configure_gadget!(),
             msize_gadget: configure_gadget!(),
             mul_div_mod_gadget: configure_gadget!(),
@@ -566,6 +629,8 @@ impl<F: Field> ExecutionConfig<F> {
             address_gadget: configure_gadget!(),
             balance_gadget: configure_gadget!(),
             blockhash_gadget: configure_gadget!(),
+            blobhash_gadget: configure_gadget!(),
+            blob_base_fee_gadget: configure_gadget!(),
             exp_gadget: configure_gadget!(),
             sar_gadget: configure_gadget!(),
             extcodecopy_gadget: configure_gadget!(),
@@ -574,6 +639,8 @@ impl<F: Field> ExecutionConfig<F> {
             create_gadget: configure_gadget!(),
             create2_gadget: configure_gadget!(),
             selfdestruct_gadget: configure_gadget!(),
+            #[cfg(feature = "custom-opcodes")]
+            custom_gadget: configure_gadget!(),
             shl_shr_gadget: configure_gadget!(),
             signed_comparator_gadget: configure_gadget!(),
             signextend_gadget: configure_gadget!(),
@@ -581,6 +648,8 @@ impl<F: Field> ExecutionConfig<F> {
             sstore_gadget: configure_gadget!(),
             stop_gadget: configure_gadget!(),
             swap_gadget: configure_gadget!(),
+            tload_gadget: configure_gadget!(),
+            tstore_gadget: configure_gadget!(),
             block_ctx_gadget: configure_gadget!(),
             // error gadgets
             error_oog_constant: configure_gadget!(),
@@ -602,13 +671,19 @@ impl<F: Field> ExecutionConfig<F> {
             error_invalid_jump: configure_gadget!(),
             error_invalid_opcode: configure_gadget!(),
             error_write_protection: configure_gadget!(),
-            error_depth: configure_gadget!(),
-            error_contract_address_collision: configure_gadget!(),
             error_invalid_creation_code: configure_gadget!(),
             error_precompile_failed: configure_gadget!(),
             error_return_data_out_of_bound: configure_gadget!(),
             // precompile calls
+            precompile_ecrecover_gadget: configure_gadget!(),
             precompile_identity_gadget: configure_gadget!(),
+            precompile_sha256_gadget: configure_gadget!(),
+            precompile_ripemd160_gadget: configure_gadget!(),
+            precompile_modexp_gadget: configure_gadget!(),
+            precompile_ec_add_gadget: configure_gadget!(),
+            precompile_ec_mul_gadget: configure_gadget!(),
+            precompile_blake2f_gadget: configure_gadget!(),
+            precompile_p256_verify_gadget: configure_gadget!(),
             // step and presets
             step: step_curr,
             height_map,
@@ -629,6 +704,14 @@ impl<F: Field> ExecutionConfig<F> {
             copy_table,
             keccak_table,
             exp_table,
+            sig_table,
+            sha256_table,
+            ripemd160_table,
+            modexp_table,
+            ecc_table,
+            blake2f_table,
+            p256_sig_table,
+            block_hash_table,
             &challenges,
             &cell_manager,
         );
@@ -642,6 +725,8 @@ impl<F: Field> ExecutionConfig<F> {
     #[allow(clippy::too_many_arguments)]
     fn configure_gadget<G: ExecutionGadget<F>>(
         meta: &mut ConstraintSystem<F>,
+        hardfork: Hardfork,
+        p256_verify: bool,
         advices: [Column<Advice>; STEP_WIDTH],
         q_usable: Selector,
         q_step: Column<Advice>,
@@ -661,6 +746,8 @@ impl<F: Field> ExecutionConfig<F> {
             let dummy_step_next = Step::new(meta, advices, MAX_STEP_HEIGHT);
             let mut cb = EVMConstraintBuilder::new(
                 meta,
+                hardfork,
+                p256_verify,
                 step_curr.clone(),
                 dummy_step_next,
                 challenges,
@@ -675,6 +762,8 @@ impl<F: Field> ExecutionConfig<F> {
         let step_next = &Step::new(meta, advices, height);
         let mut cb = EVMConstraintBuilder::new(
             meta,
+            hardfork,
+            p256_verify,
             step_curr.clone(),
             step_next.clone(),
             challenges,
@@ -787,9 +876,40 @@ impl<F: Field> ExecutionConfig<F> {
                 .chain(
                     IntoIterator::into_iter([
                         (
-                            "EndTx can only transit to BeginTx, InvalidTx or EndBlock",
+                            "BeaconRoot can only transit to a system call, a tx or EndBlock",
+                            ExecutionState::BeaconRoot,
+                            vec![
+                                ExecutionState::HistoryStorage,
+                                ExecutionState::BeginTx,
+                                ExecutionState::InvalidTx,
+                                ExecutionState::EndBlock,
+                            ],
+                        ),
+                        (
+                            "HistoryStorage can only transit to BeginTx, InvalidTx or EndBlock",
+                            ExecutionState::HistoryStorage,
+                            vec![
+                                ExecutionState::BeginTx,
+                                ExecutionState::InvalidTx,
+                                ExecutionState::EndBlock,
+                            ],
+                        ),
+                        (
+                            "EndTx can only transit to DeleteAccount, BeginTx, InvalidTx or EndBlock",
                             ExecutionState::EndTx,
                             vec![
+                                ExecutionState::DeleteAccount,
+                                ExecutionState::BeginTx,
+                                ExecutionState::InvalidTx,
+                                ExecutionState::EndBlock,
+                            ],
+                        ),
+                        (
+                            "DeleteAccount can only transit to DeleteAccount, BeginTx, InvalidTx or \
+                             EndBlock",
+                            ExecutionState::DeleteAccount,
+                            vec![
+                                ExecutionState::DeleteAccount,
                                 ExecutionState::BeginTx,
                                 ExecutionState::InvalidTx,
                                 ExecutionState::EndBlock,
@@ -807,14 +927,41 @@ impl<F: Field> ExecutionConfig<F> {
                 .chain(
                     IntoIterator::into_iter([
                         (
-                            "Only EndTx and InvalidTx can transit to InvalidTx",
+                            "No state can transit to BeaconRoot",
+                            ExecutionState::BeaconRoot,
+                            vec![],
+                        ),
+                        (
+                            "Only BeaconRoot can transit to HistoryStorage",
+                            ExecutionState::HistoryStorage,
+                            vec![ExecutionState::BeaconRoot],
+                        ),
+                        (
+                            "Only system calls, EndTx, DeleteAccount and InvalidTx can transit to InvalidTx",
                             ExecutionState::InvalidTx,
-                            vec![ExecutionState::EndTx, ExecutionState::InvalidTx],
+                            vec![
+                                ExecutionState::BeaconRoot,
+                                ExecutionState::HistoryStorage,
+                                ExecutionState::EndTx,
+                                ExecutionState::DeleteAccount,
+                                ExecutionState::InvalidTx,
+                            ],
                         ),
                         (
-                            "Only EndTx and InvalidTx can transit to BeginTx",
+                            "Only system calls, EndTx, DeleteAccount and InvalidTx can transit to BeginTx",
                             ExecutionState::BeginTx,
-                            vec![ExecutionState::EndTx, ExecutionState::InvalidTx],
+                            vec![
+                                ExecutionState::BeaconRoot,
+                                ExecutionState::HistoryStorage,
+                                ExecutionState::EndTx,
+                                ExecutionState::DeleteAccount,
+                                ExecutionState::InvalidTx,
+                            ],
+                        ),
+                        (
+                            "Only EndTx and DeleteAccount can transit to DeleteAccount",
+                            ExecutionState::DeleteAccount,
+                            vec![ExecutionState::EndTx, ExecutionState::DeleteAccount],
                         ),
                         (
                             "Only ExecutionState which halts or BeginTx can transit to EndTx",
@@ -825,10 +972,14 @@ impl<F: Field> ExecutionConfig<F> {
                                 .collect(),
                         ),
                         (
-                            "Only EndTx, InvalidTx or EndBlock can transit to EndBlock",
+                            "Only system calls, EndTx, DeleteAccount, InvalidTx, EndBlock can transit \
+                             to EndBlock",
                             ExecutionState::EndBlock,
                             vec![
+                                ExecutionState::BeaconRoot,
+                                ExecutionState::HistoryStorage,
                                 ExecutionState::EndTx,
+                                ExecutionState::DeleteAccount,
                                 ExecutionState::InvalidTx,
                                 ExecutionState::EndBlock,
                             ],
@@ -863,6 +1014,14 @@ impl<F: Field> ExecutionConfig<F> {
         copy_table: &dyn LookupTable<F>,
         keccak_table: &dyn LookupTable<F>,
         exp_table: &dyn LookupTable<F>,
+        sig_table: &dyn LookupTable<F>,
+        sha256_table: &dyn LookupTable<F>,
+        ripemd160_table: &dyn LookupTable<F>,
+        modexp_table: &dyn LookupTable<F>,
+        ecc_table: &dyn LookupTable<F>,
+        blake2f_table: &dyn LookupTable<F>,
+        p256_sig_table: &dyn LookupTable<F>,
+        block_hash_table: &dyn LookupTable<F>,
         challenges: &Challenges<Expression<F>>,
         cell_manager: &CellManager<CMFixedWidthStrategy>,
     ) {
@@ -882,6 +1041,14 @@ impl<F: Field> ExecutionConfig<F> {
                         Table::Copy => copy_table,
                         Table::Keccak => keccak_table,
                         Table::Exp => exp_table,
+                        Table::Sig => sig_table,
+                        Table::Sha256 => sha256_table,
+                        Table::Ripemd160 => ripemd160_table,
+                        Table::ModExp => modexp_table,
+                        Table::Ecc => ecc_table,
+                        Table::Blake2f => blake2f_table,
+                        Table::P256Sig => p256_sig_table,
+                        Table::BlockHash => block_hash_table,
                     }
                     .table_exprs(meta);
                     vec![(
@@ -961,14 +1128,17 @@ impl<F: Field> ExecutionConfig<F> {
                 let end_block_not_last = &block.end_block_not_last;
                 let end_block_last = &block.end_block_last;
                 // Collect all steps
+                let system_call = Call::default();
                 let mut steps = block
-                    .txs
+                    .beacon_root
                     .iter()
-                    .flat_map(|tx| {
+                    .chain(block.history_storage.iter())
+                    .map(|step| (&dummy_tx, &system_call, step))
+                    .chain(block.txs.iter().flat_map(|tx| {
                         tx.steps()
                             .iter()
                             .map(move |step| (tx, &tx.calls()[step.call_index], step))
-                    })
+                    }))
                     .chain(std::iter::once((&dummy_tx, &last_call, end_block_not_last)))
                     .peekable();
 
@@ -1093,6 +1263,14 @@ impl<F: Field> ExecutionConfig<F> {
             ("EVM_lookup_copy", COPY_TABLE_LOOKUPS),
             ("EVM_lookup_keccak", KECCAK_TABLE_LOOKUPS),
             ("EVM_lookup_exp", EXP_TABLE_LOOKUPS),
+            ("EVM_lookup_sig", SIG_TABLE_LOOKUPS),
+            ("EVM_lookup_sha256", SHA256_TABLE_LOOKUPS),
+            ("EVM_lookup_ripemd160", RIPEMD160_TABLE_LOOKUPS),
+            ("EVM_lookup_modexp", MODEXP_TABLE_LOOKUPS),
+            ("EVM_lookup_ecc", ECC_TABLE_LOOKUPS),
+            ("EVM_lookup_blake2f", BLAKE2F_TABLE_LOOKUPS),
+            ("EVM_lookup_p256_sig", P256_SIG_TABLE_LOOKUPS),
+            ("EVM_lookup_block_hash", BLOCK_HASH_TABLE_LOOKUPS),
             ("EVM_adv_phase2", N_PHASE2_COLUMNS),
             ("EVM_copy", N_COPY_COLUMNS),
             ("EVM_lookup_u8", N_U8_LOOKUPS),
@@ -1256,8 +1434,11 @@ impl<F: Field> ExecutionConfig<F> {
 
         match step.execution_state() {
             // internal states
+            ExecutionState::BeaconRoot => assign_exec_step!(self.beacon_root_gadget),
+            ExecutionState::HistoryStorage => assign_exec_step!(self.history_storage_gadget),
             ExecutionState::BeginTx => assign_exec_step!(self.begin_tx_gadget),
             ExecutionState::EndTx => assign_exec_step!(self.end_tx_gadget),
+            ExecutionState::DeleteAccount => assign_exec_step!(self.delete_account_gadget),
             ExecutionState::EndBlock => assign_exec_step!(self.end_block_gadget),
             ExecutionState::InvalidTx => assign_exec_step!(self.invalid_tx),
             // opcode
@@ -1288,6 +1469,7 @@ impl<F: Field> ExecutionConfig<F> {
             ExecutionState::JUMPDEST => assign_exec_step!(self.jumpdest_gadget),
             ExecutionState::JUMPI => assign_exec_step!(self.jumpi_gadget),
             ExecutionState::LOG => assign_exec_step!(self.log_gadget),
+            ExecutionState::MCOPY => assign_exec_step!(self.mcopy_gadget),
             ExecutionState::MEMORY => assign_exec_step!(self.memory_gadget),
             ExecutionState::MSIZE => assign_exec_step!(self.msize_gadget),
             ExecutionState::MUL_DIV_MOD => assign_exec_step!(self.mul_div_mod_gadget),
@@ -1305,18 +1487,24 @@ impl<F: Field> ExecutionConfig<F> {
             ExecutionState::SDIV_SMOD => assign_exec_step!(self.sdiv_smod_gadget),
             ExecutionState::BLOCKCTX => assign_exec_step!(self.block_ctx_gadget),
             ExecutionState::BLOCKHASH => assign_exec_step!(self.blockhash_gadget),
+            ExecutionState::BLOBHASH => assign_exec_step!(self.blobhash_gadget),
+            ExecutionState::BLOBBASEFEE => assign_exec_step!(self.blob_base_fee_gadget),
             ExecutionState::SELFBALANCE => assign_exec_step!(self.selfbalance_gadget),
             // dummy gadgets
             ExecutionState::EXTCODECOPY => assign_exec_step!(self.extcodecopy_gadget),
             ExecutionState::CREATE => assign_exec_step!(self.create_gadget),
             ExecutionState::CREATE2 => assign_exec_step!(self.create2_gadget),
-            ExecutionState::SELFDESTRUCT => assign_exec_step!(self.selfdestruct_gadget),
             // end of dummy gadgets
+            ExecutionState::SELFDESTRUCT => assign_exec_step!(self.selfdestruct_gadget),
+            #[cfg(feature = "custom-opcodes")]
+            ExecutionState::CUSTOM => assign_exec_step!(self.custom_gadget),
             ExecutionState::SHA3 => assign_exec_step!(self.sha3_gadget),
             ExecutionState::SHL_SHR => assign_exec_step!(self.shl_shr_gadget),
             ExecutionState::SIGNEXTEND => assign_exec_step!(self.signextend_gadget),
             ExecutionState::SLOAD => assign_exec_step!(self.sload_gadget),
             ExecutionState::SSTORE => assign_exec_step!(self.sstore_gadget),
+            ExecutionState::TLOAD => assign_exec_step!(self.tload_gadget),
+            ExecutionState::TSTORE => assign_exec_step!(self.tstore_gadget),
             ExecutionState::STOP => assign_exec_step!(self.stop_gadget),
             ExecutionState::SWAP => assign_exec_step!(self.swap_gadget),
             // dummy errors
@@ -1389,9 +1577,33 @@ impl<F: Field> ExecutionConfig<F> {
                 assign_exec_step!(self.error_precompile_failed)
             }
             // precompile calls
+            ExecutionState::PrecompileEcRecover => {
+                assign_exec_step!(self.precompile_ecrecover_gadget)
+            }
             ExecutionState::PrecompileIdentity => {
                 assign_exec_step!(self.precompile_identity_gadget)
             }
+            ExecutionState::PrecompileSha256 => {
+                assign_exec_step!(self.precompile_sha256_gadget)
+            }
+            ExecutionState::PrecompileRipemd160 => {
+                assign_exec_step!(self.precompile_ripemd160_gadget)
+            }
+            ExecutionState::PrecompileBigModExp => {
+                assign_exec_step!(self.precompile_modexp_gadget)
+            }
+            ExecutionState::PrecompileBn256Add => {
+                assign_exec_step!(self.precompile_ec_add_gadget)
+            }
+            ExecutionState::PrecompileBn256ScalarMul => {
+                assign_exec_step!(self.precompile_ec_mul_gadget)
+            }
+            ExecutionState::PrecompileBlake2f => {
+                assign_exec_step!(self.precompile_blake2f_gadget)
+            }
+            ExecutionState::PrecompileP256Verify => {
+                assign_exec_step!(self.precompile_p256_verify_gadget)
+            }
 
             unimpl_state => evm_unimplemented!("unimplemented ExecutionState: {:?}", unimpl_state),
         }
@@ -1560,7 +1772,7 @@ impl<F: Field> ExecutionConfig<F> {
 
         let mut rev_count = 0;
         let mut offset = 0;
-        let mut copy_lookup_processed = false;
+        let mut copy_lookups_processed = 0;
         for (idx, assigned_rw_value) in assigned_rw_values.iter().enumerate() {
             let is_rev = if assigned_rw_value.0.contains(" with reversion") {
                 rev_count += 1;
@@ -1581,27 +1793,34 @@ impl<F: Field> ExecutionConfig<F> {
             let idx = if is_rev {
                 step.rw_indices_len() - rev_count
             } else {
-                idx - rev_count + offset - copy_lookup_processed as usize
+                idx - rev_count + offset - copy_lookups_processed
             };
 
             // If assigned_rw_value is a `copy lookup` event, the following
-            // `step.copy_rw_counter_delta` rw lookups must be memory operations.
+            // `step.copy_rw_counter_delta` rw lookups must be memory operations, or the tx access
+            // list writes of the copy lookups of BeginTx, which are consecutive.
             if assigned_rw_value.0.starts_with("copy lookup") {
-                for i in 0..step.copy_rw_counter_delta as usize {
-                    let index = idx + i;
-                    let rw = block.get_rws(step, index);
-                    if rw.tag() != Target::Memory {
-                        log::error!(
+                if copy_lookups_processed == 0 {
+                    for i in 0..step.copy_rw_counter_delta as usize {
+                        let index = idx + i;
+                        let rw = block.get_rws(step, index);
+                        if !matches!(
+                            rw.tag(),
+                            Target::Memory
+                                | Target::TxAccessListAccount
+                                | Target::TxAccessListAccountStorage
+                        ) {
+                            log::error!(
                                 "incorrect rw memory witness from copy lookup.\n lookup name: \"{}\"\n {}th rw of step {:?}, rw: {:?}",
                                 assigned_rw_value.0,
                                 index,
                                 step.execution_state(),
                                 rw);
+                        }
                     }
+                    offset = step.copy_rw_counter_delta as usize;
                 }
-
-                offset = step.copy_rw_counter_delta as usize;
-                copy_lookup_processed = true;
+                copy_lookups_processed += 1;
                 continue;
             }
 
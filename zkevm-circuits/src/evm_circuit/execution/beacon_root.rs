@@ -0,0 +1,246 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::N_BYTES_U64,
+        step::ExecutionState,
+        util::{
+            common_gadget::SystemCallGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::ConstantDivisionGadget,
+            CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::BlockContextFieldTag,
+    util::{
+        word::{Word, WordCell, WordExpr},
+        Expr,
+    },
+};
+use eth_types::{
+    evm_types::system_contracts::{beacon_roots_code, BEACON_ROOTS_ADDRESS, HISTORY_BUFFER_LENGTH},
+    Field,
+};
+use halo2_proofs::{
+    circuit::Value,
+    plonk::{Error, Expression},
+};
+
+/// Gadget for the system call which stores the parent beacon block root in
+/// the beacon roots contract at the beginning of a block (EIP-4788).
+///
+/// The step comes first in the block and is outside of any transaction
+/// (call_id 0).  If the contract has code, which must be the one of
+/// EIP-4788, the step writes the timestamp and the root into the slots of the
+/// ring buffers which the code writes; otherwise the call does nothing.
+#[derive(Clone, Debug)]
+pub(crate) struct BeaconRootGadget<F> {
+    timestamp: Cell<F>,
+    parent_beacon_root: WordCell<F>,
+    system_call: SystemCallGadget<F>,
+    timestamp_index: ConstantDivisionGadget<F, N_BYTES_U64>,
+    timestamp_slot_prev: WordCell<F>,
+    timestamp_slot_committed: WordCell<F>,
+    root_slot_prev: WordCell<F>,
+    root_slot_committed: WordCell<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for BeaconRootGadget<F> {
+    const NAME: &'static str = "BeaconRoot";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::BeaconRoot;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        if !cb.hardfork.is_beacon_root_active() {
+            cb.require_zero("BeaconRoot is not active before Cancun", 1.expr());
+        }
+
+        let timestamp = cb.query_cell();
+        cb.block_lookup(
+            BlockContextFieldTag::Timestamp.expr(),
+            None,
+            Word::from_lo_unchecked(timestamp.expr()),
+        );
+        let parent_beacon_root = cb.query_word_unchecked();
+        cb.block_lookup(
+            BlockContextFieldTag::ParentBeaconRoot.expr(),
+            None,
+            parent_beacon_root.to_word(),
+        );
+
+        let address = Word::from(BEACON_ROOTS_ADDRESS).map(Expression::Constant);
+        let system_call = SystemCallGadget::construct(cb, address.clone(), &beacon_roots_code());
+
+        let timestamp_index =
+            ConstantDivisionGadget::construct(cb, timestamp.expr(), HISTORY_BUFFER_LENGTH);
+        let timestamp_slot_prev = cb.query_word_unchecked();
+        let timestamp_slot_committed = cb.query_word_unchecked();
+        let root_slot_prev = cb.query_word_unchecked();
+        let root_slot_committed = cb.query_word_unchecked();
+        cb.condition(system_call.has_code(), |cb| {
+            cb.account_storage_write(
+                address.clone(),
+                Word::from_lo_unchecked(timestamp_index.remainder()),
+                Word::from_lo_unchecked(timestamp.expr()),
+                timestamp_slot_prev.to_word(),
+                0.expr(),
+                timestamp_slot_committed.to_word(),
+                None,
+            );
+            cb.account_storage_write(
+                address.clone(),
+                Word::from_lo_unchecked(timestamp_index.remainder() + HISTORY_BUFFER_LENGTH.expr()),
+                parent_beacon_root.to_word(),
+                root_slot_prev.to_word(),
+                0.expr(),
+                root_slot_committed.to_word(),
+                None,
+            );
+        });
+
+        SystemCallGadget::constrain_transition(cb, 1.expr() + 2.expr() * system_call.has_code());
+
+        Self {
+            timestamp,
+            parent_beacon_root,
+            system_call,
+            timestamp_index,
+            timestamp_slot_prev,
+            timestamp_slot_committed,
+            root_slot_prev,
+            root_slot_committed,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _: &Transaction,
+        _: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let timestamp = block.context.timestamp.as_u64();
+        self.timestamp
+            .assign(region, offset, Value::known(F::from(timestamp)))?;
+        self.parent_beacon_root
+            .assign_u256(region, offset, block.context.parent_beacon_root)?;
+
+        let code_hash = block.get_rws(step, 0).account_codehash_pair().0;
+        self.system_call.assign(region, offset, code_hash)?;
+
+        self.timestamp_index
+            .assign(region, offset, timestamp as u128)?;
+        if step.rw_indices_len() > 1 {
+            for (idx, (value_prev, committed)) in [
+                (&self.timestamp_slot_prev, &self.timestamp_slot_committed),
+                (&self.root_slot_prev, &self.root_slot_committed),
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                let (_, prev, _, committed_value) =
+                    block.get_rws(step, 1 + idx).storage_value_aux();
+                value_prev.assign_u256(region, offset, prev)?;
+                committed.assign_u256(region, offset, committed_value)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use bus_mapping::circuit_input_builder::FixedCParams;
+    use eth_types::{
+        bytecode,
+        evm_types::{
+            system_contracts::{beacon_roots_code, BEACON_ROOTS_ADDRESS},
+            Hardfork,
+        },
+        H256,
+    };
+    use mock::{eth, TestContext, MOCK_ACCOUNTS};
+
+    fn run<const NACC: usize, const NTX: usize>(ctx: TestContext<NACC, NTX>) {
+        // The beacon root is stored from Cancun on (EIP-4788).
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(FixedCParams {
+                hardfork: Hardfork::Cancun,
+                ..Default::default()
+            })
+            .run();
+    }
+
+    const PARENT_BEACON_ROOT: H256 = H256::repeat_byte(0xbe);
+
+    fn test_ok(contract_code: Option<Vec<u8>>) {
+        let bytecode = bytecode! {
+            PUSH1(0)
+            STOP
+        };
+
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).code(bytecode);
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+                match contract_code {
+                    Some(code) => accs[2]
+                        .address(BEACON_ROOTS_ADDRESS)
+                        .balance(eth(1))
+                        .code(code),
+                    None => accs[2].address(MOCK_ACCOUNTS[2]).balance(eth(1)),
+                };
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[1].address).to(accs[0].address);
+            },
+            |mut block, _tx| {
+                block.parent_beacon_block_root(Some(PARENT_BEACON_ROOT));
+                block
+            },
+        )
+        .unwrap();
+
+        run(ctx);
+    }
+
+    #[test]
+    fn beacon_root_contract() {
+        test_ok(Some(beacon_roots_code()));
+    }
+
+    #[test]
+    fn beacon_root_contract_without_code() {
+        test_ok(Some(Vec::new()));
+    }
+
+    #[test]
+    fn beacon_root_no_contract() {
+        test_ok(None);
+    }
+
+    #[test]
+    fn beacon_root_empty_block() {
+        let ctx = TestContext::<1, 0>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(BEACON_ROOTS_ADDRESS)
+                    .code(beacon_roots_code());
+            },
+            |_, _| {},
+            |mut block, _tx| {
+                block.parent_beacon_block_root(Some(PARENT_BEACON_ROOT));
+                block
+            },
+        )
+        .unwrap();
+
+        run(ctx);
+    }
+}
@@ -5,14 +5,14 @@ use crate::{
         step::ExecutionState,
         util::{
             and,
-            common_gadget::TransferWithGasFeeGadget,
+            common_gadget::{TransferWithGasFeeGadget, TxAccessListGadget},
             constraint_builder::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
                 Transition::{Delta, To},
             },
-            is_precompiled,
             math_gadget::{
-                ContractCreateGadget, IsEqualWordGadget, IsZeroWordGadget, RangeCheckGadget,
+                AddWordsGadget, ContractCreateGadget, IsEqualWordGadget, IsZeroWordGadget,
+                LtWordGadget, RangeCheckGadget,
             },
             not, or,
             tx::{BeginTxHelperGadget, TxDataGadget},
@@ -26,8 +26,8 @@ use crate::{
         Expr,
     },
 };
-use bus_mapping::state_db::CodeDB;
-use eth_types::{evm_types::PRECOMPILE_COUNT, keccak256, Field, ToWord, U256};
+use bus_mapping::{operation::CreatedAccountStatus, precompile::is_precompiled, state_db::CodeDB};
+use eth_types::{keccak256, Field, ToWord, U256};
 use halo2_proofs::{
     circuit::Value,
     plonk::{Error, Expression},
@@ -37,11 +37,17 @@ use halo2_proofs::{
 pub(crate) struct BeginTxGadget<F> {
     begin_tx: BeginTxHelperGadget<F>,
     tx: TxDataGadget<F>,
+    // EIP-1559 (Base fee)
+    base_fee: Word32Cell<F>,
+    gas_price_lt_base_fee: LtWordGadget<F>,
     tx_caller_address_is_zero: IsZeroWordGadget<F, WordCell<F>>,
     call_callee_address: AccountAddress<F>,
     reversion_info: ReversionInfo<F>,
+    // OP-stack deposit tx
+    caller_balance_add_mint: AddWordsGadget<F, 2, true>,
     sufficient_gas_left: RangeCheckGadget<F, N_BYTES_GAS>,
     transfer_with_gas_fee: TransferWithGasFeeGadget<F>,
+    caller_balance_lt_total_cost: LtWordGadget<F>,
     code_hash: WordCell<F>,
     is_empty_code_hash: IsEqualWordGadget<F, Word<Expression<F>>, Word<Expression<F>>>,
     caller_nonce_hash_bytes: Word32Cell<F>,
@@ -54,6 +60,8 @@ pub(crate) struct BeginTxGadget<F> {
     // coinbase, and may be duplicate.
     // <https://github.com/ethereum/go-ethereum/blob/604e215d1bb070dff98fb76aa965064c74e3633f/core/state/statedb.go#LL1119C9-L1119C9>
     is_coinbase_warm: Cell<F>,
+    // EIP-2930 (Access list)
+    tx_access_list: TxAccessListGadget<F>,
 }
 
 impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
@@ -68,7 +76,25 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         let begin_tx = BeginTxHelperGadget::configure(cb);
         let tx_id = begin_tx.tx_id.expr();
 
-        let tx = TxDataGadget::configure(cb, tx_id.expr(), false);
+        let tx = TxDataGadget::configure(cb, tx_id.expr(), true);
+
+        // The gas price in the tx table is the effective gas price, which pays
+        // at least the base fee of the block.
+        let base_fee = cb.query_word32();
+        cb.block_lookup(
+            BlockContextFieldTag::BaseFee.expr(),
+            None,
+            base_fee.to_word(),
+        );
+        let gas_price_lt_base_fee =
+            LtWordGadget::construct(cb, &tx.gas_price.to_word(), &base_fee.to_word());
+        cb.condition(not::expr(tx.is_deposit.expr()), |cb| {
+            cb.require_zero("gas_price >= base_fee", gas_price_lt_base_fee.expr());
+        });
+        // A deposit tx buys its gas on L1, and pays no gas price.
+        cb.condition(tx.is_deposit.expr(), |cb| {
+            cb.require_zero_word("deposit tx gas_price == 0", tx.gas_price.to_word());
+        });
 
         let mut reversion_info = cb.reversion_info_write_unchecked(None); // rwc_delta += 2
         cb.call_context_lookup_write(
@@ -78,7 +104,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         ); // rwc_delta += 1
 
         // Check gas_left is sufficient
-        let gas_left = tx.gas.expr() - tx.intrinsic_gas();
+        let gas_left = tx.gas.expr() - tx.intrinsic_gas(cb.hardfork);
         let sufficient_gas_left = RangeCheckGadget::construct(cb, gas_left.clone());
 
         let tx_caller_address_is_zero = IsZeroWordGadget::construct(cb, &tx.caller_address);
@@ -107,8 +133,26 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             None,
         ); // rwc_delta += 1
 
+        // Mint the ether of a deposit tx to the caller, which is kept even if
+        // the tx fails.
+        let caller_balance_prev = cb.query_word32();
+        let caller_balance = cb.query_word32();
+        let caller_balance_add_mint =
+            AddWordsGadget::construct(cb, [caller_balance_prev, tx.mint.clone()], caller_balance);
+        cb.condition(tx.is_deposit.expr(), |cb| {
+            cb.account_write(
+                tx.caller_address.to_word(),
+                AccountFieldTag::Balance,
+                caller_balance_add_mint.sum().to_word(),
+                caller_balance_add_mint.addends()[0].to_word(),
+                None,
+            );
+        }); // rwc_delta += is_deposit
+
         // Add precompile contract address to access list
-        for addr in 1..=PRECOMPILE_COUNT {
+        let precompile_addresses = cb.hardfork.precompile_addresses(cb.p256_verify);
+        let num_precompiles = precompile_addresses.len();
+        for addr in precompile_addresses {
             cb.account_access_list_write_unchecked(
                 tx_id.expr(),
                 Word::new([addr.expr(), 0.expr()]),
@@ -116,7 +160,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 0.expr(),
                 None,
             );
-        } // rwc_delta += PRECOMPILE_COUNT
+        } // rwc_delta += num_precompiles
 
         // Prepare access list of caller and callee
         cb.account_access_list_write_unchecked(
@@ -153,6 +197,14 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             None,
         ); // rwc_delta += 1
 
+        // Add the addresses and storage keys of the tx access list (EIP-2930)
+        let tx_access_list = TxAccessListGadget::construct(
+            cb,
+            tx_id.expr(),
+            tx.access_list_length.expr(),
+            tx.access_list_storage_keys_length.expr(),
+        ); // rwc_delta += tx_access_list.rw_delta()
+
         // Read code_hash of callee
         let code_hash = cb.query_word_unchecked();
         let is_empty_code_hash =
@@ -183,6 +235,17 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             tx.mul_gas_fee_by_gas.product().clone(),
             &mut reversion_info,
         );
+        // The caller must afford the gas limit at the max fee per gas and the
+        // value up front, although it only pays the effective gas price.
+        let caller_balance_lt_total_cost = LtWordGadget::construct(
+            cb,
+            &transfer_with_gas_fee.sender_balance_prev().to_word(),
+            &tx.total_cost().to_word(),
+        );
+        cb.require_zero(
+            "caller balance >= gas * max_fee_per_gas + value",
+            caller_balance_lt_total_cost.expr(),
+        );
 
         let caller_nonce_hash_bytes = cb.query_word32();
         let create = ContractCreateGadget::construct(cb);
@@ -225,6 +288,13 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 Word::zero(),
                 Some(&mut reversion_info),
             );
+            cb.created_account_write(
+                tx_id.expr(),
+                call_callee_address.to_word(),
+                CreatedAccountStatus::Created.expr(),
+                CreatedAccountStatus::NotCreated.expr(),
+                Some(&mut reversion_info),
+            );
             for (field_tag, value) in [
                 (CallContextFieldTag::Depth, Word::one()),
                 (
@@ -262,18 +332,22 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             }
 
             cb.require_step_state_transition(StepStateTransition {
-                // 21 + a reads and writes:
+                // 24 + a reads and writes:
                 //   - Write CallContext TxId
                 //   - Write CallContext RwCounterEndOfReversion
                 //   - Write CallContext IsPersistent
                 //   - Write CallContext IsSuccess
                 //   - Write Account (Caller) Nonce
-                //   - Write TxAccessListAccount (Precompile) x PRECOMPILE_COUNT
+                //   - Write Account (Caller) Balance if a deposit tx
+                //   - Write TxAccessListAccount (Precompile) x num_precompiles
                 //   - Write TxAccessListAccount (Caller)
                 //   - Write TxAccessListAccount (Callee)
                 //   - Write TxAccessListAccount (Coinbase) for EIP-3651
+                //   - Write TxAccessListAccount x tx access list length
+                //   - Write TxAccessListAccountStorage x tx access list storage keys length
                 //   - a TransferWithGasFeeGadget
                 //   - Write Account (Callee) Nonce (Reversible)
+                //   - Write CreatedAccount (Callee) (Reversible)
                 //   - Write CallContext Depth
                 //   - Write CallContext CallerAddress
                 //   - Write CallContext CalleeAddress
@@ -288,17 +362,22 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 //   - Write CallContext IsCreate
                 //   - Write CallContext CodeHash
                 rw_counter: Delta(
-                    23.expr() + transfer_with_gas_fee.rw_delta() + PRECOMPILE_COUNT.expr(),
+                    24.expr()
+                        + tx.is_deposit.expr()
+                        + transfer_with_gas_fee.rw_delta()
+                        + num_precompiles.expr()
+                        + tx_access_list.rw_delta(),
                 ),
                 call_id: To(call_id.expr()),
                 is_root: To(true.expr()),
                 is_create: To(tx.is_create.expr()),
                 code_hash: To(cb.curr.state.code_hash.to_word()),
                 gas_left: To(gas_left.clone()),
-                // There are a + 1 reversible writes:
+                // There are a + 2 reversible writes:
                 //  - a TransferWithGasFeeGadget
                 //  - Callee Account Nonce
-                reversible_write_counter: To(transfer_with_gas_fee.reversible_w_delta() + 1.expr()),
+                //  - Callee CreatedAccount
+                reversible_write_counter: To(transfer_with_gas_fee.reversible_w_delta() + 2.expr()),
                 log_id: To(0.expr()),
                 ..StepStateTransition::new_context()
             });
@@ -328,14 +407,21 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                     //   - Write CallContext IsPersistent
                     //   - Write CallContext IsSuccess
                     //   - Write Account Nonce
-                    //   - Write TxAccessListAccount (Precompile) x PRECOMPILE_COUNT
+                    //   - Write Account Balance if a deposit tx
+                    //   - Write TxAccessListAccount (Precompile) x num_precompiles
                     //   - Write TxAccessListAccount (Caller)
                     //   - Write TxAccessListAccount (Callee)
                     //   - Write TxAccessListAccount (Coinbase) for EIP-3651
+                    //   - Write TxAccessListAccount x tx access list length
+                    //   - Write TxAccessListAccountStorage x tx access list storage keys length
                     //   - Read Account CodeHash
                     //   - a TransferWithGasFeeGadget
                     rw_counter: Delta(
-                        9.expr() + transfer_with_gas_fee.rw_delta() + PRECOMPILE_COUNT.expr(),
+                        9.expr()
+                            + tx.is_deposit.expr()
+                            + transfer_with_gas_fee.rw_delta()
+                            + num_precompiles.expr()
+                            + tx_access_list.rw_delta(),
                     ),
                     call_id: To(call_id.expr()),
                     ..StepStateTransition::any()
@@ -391,10 +477,13 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                     //   - Write CallContext IsPersistent
                     //   - Write CallContext IsSuccess
                     //   - Write Account Nonce
-                    //   - Write TxAccessListAccount (Precompile) x PRECOMPILE_COUNT
+                    //   - Write Account Balance if a deposit tx
+                    //   - Write TxAccessListAccount (Precompile) x num_precompiles
                     //   - Write TxAccessListAccount (Caller)
                     //   - Write TxAccessListAccount (Callee)
                     //   - Write TxAccessListAccount (Coinbase) for EIP-3651
+                    //   - Write TxAccessListAccount x tx access list length
+                    //   - Write TxAccessListAccountStorage x tx access list storage keys length
                     //   - Read Account CodeHash
                     //   - a TransferWithGasFeeGadget
                     //   - Write CallContext Depth
@@ -411,7 +500,11 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                     //   - Write CallContext IsCreate
                     //   - Write CallContext CodeHash
                     rw_counter: Delta(
-                        22.expr() + transfer_with_gas_fee.rw_delta() + PRECOMPILE_COUNT.expr(),
+                        22.expr()
+                            + tx.is_deposit.expr()
+                            + transfer_with_gas_fee.rw_delta()
+                            + num_precompiles.expr()
+                            + tx_access_list.rw_delta(),
                     ),
                     call_id: To(call_id.expr()),
                     is_root: To(true.expr()),
@@ -428,11 +521,15 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         Self {
             begin_tx,
             tx,
+            base_fee,
+            gas_price_lt_base_fee,
             tx_caller_address_is_zero,
             call_callee_address,
             reversion_info,
+            caller_balance_add_mint,
             sufficient_gas_left,
             transfer_with_gas_fee,
+            caller_balance_lt_total_cost,
             code_hash,
             is_empty_code_hash,
             caller_nonce_hash_bytes,
@@ -441,6 +538,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             is_caller_callee_equal,
             coinbase,
             is_coinbase_warm,
+            tx_access_list,
         }
     }
 
@@ -457,17 +555,34 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         let zero = eth_types::Word::zero();
 
         let mut rws = StepRws::new(block, step);
-        rws.offset_add(7);
-
-        rws.offset_add(PRECOMPILE_COUNT as usize);
+        rws.offset_add(5);
+        let caller_balance_mint_pair = if tx.is_deposit() {
+            rws.next().account_balance_pair()
+        } else {
+            (zero, zero)
+        };
+        rws.offset_add(2);
+
+        let params = &block.circuits_params;
+        rws.offset_add(
+            params
+                .hardfork
+                .precompile_addresses(params.p256_verify)
+                .len(),
+        );
 
         let is_coinbase_warm = rws.next().tx_access_list_value_pair().1;
+        rws.offset_add(TxAccessListGadget::<F>::num_entries(tx));
+        let is_callee_precompiled = is_precompiled(
+            &tx.to_or_contract_addr(),
+            params.hardfork,
+            params.p256_verify,
+        );
         let mut callee_code_hash = zero;
-        if !is_precompiled(&tx.to_or_contract_addr()) {
+        if !is_callee_precompiled {
             callee_code_hash = rws.next().account_codehash_pair().1;
         }
-        let callee_exists =
-            is_precompiled(&tx.to_or_contract_addr()) || !callee_code_hash.is_zero();
+        let callee_exists = is_callee_precompiled || !callee_code_hash.is_zero();
         let caller_balance_sub_fee_pair = rws.next().account_balance_pair();
         let must_create = tx.is_create();
         if !callee_exists && (!tx.value.is_zero() || must_create) {
@@ -482,6 +597,10 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
 
         self.begin_tx.assign(region, offset, tx)?;
         self.tx.assign(region, offset, tx)?;
+        self.base_fee
+            .assign_u256(region, offset, block.context.base_fee)?;
+        self.gas_price_lt_base_fee
+            .assign(region, offset, tx.gas_price, block.context.base_fee)?;
 
         self.tx_caller_address_is_zero.assign_u256(
             region,
@@ -501,6 +620,12 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             call.rw_counter_end_of_reversion,
             call.is_persistent,
         )?;
+        self.caller_balance_add_mint.assign(
+            region,
+            offset,
+            [caller_balance_mint_pair.1, tx.mint()],
+            caller_balance_mint_pair.0,
+        )?;
         self.sufficient_gas_left
             .assign(region, offset, F::from(tx.gas() - step.gas_cost))?;
         self.transfer_with_gas_fee.assign(
@@ -512,6 +637,13 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             tx.value,
             gas_fee,
         )?;
+        let (max_fee_per_gas, _) = tx.fee_caps();
+        self.caller_balance_lt_total_cost.assign(
+            region,
+            offset,
+            caller_balance_sub_fee_pair.1,
+            max_fee_per_gas * tx.gas() + tx.value,
+        )?;
         self.code_hash
             .assign_u256(region, offset, callee_code_hash)?;
         self.is_empty_code_hash.assign_u256(
@@ -552,6 +684,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             offset,
             Value::known(F::from(is_coinbase_warm as u64)),
         )?;
+        self.tx_access_list.assign(region, offset, tx)?;
 
         Ok(())
     }
@@ -560,8 +693,11 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
 #[cfg(test)]
 mod test {
     use crate::{evm_circuit::test::rand_bytes, test_util::CircuitTestBuilder};
-    use bus_mapping::evm::OpcodeId;
-    use eth_types::{self, bytecode, evm_types::GasCost, word, Address, Bytecode, Word};
+    use bus_mapping::{circuit_input_builder::FixedCParams, evm::OpcodeId};
+    use eth_types::{
+        self, bytecode, evm_types::GasCost, geth_types::Deposit, word, AccessList, AccessListItem,
+        Address, Bytecode, Word, H256,
+    };
     use ethers_core::utils::get_contract_address;
     use mock::{eth, gwei, MockTransaction, TestContext, MOCK_ACCOUNTS};
     use std::vec;
@@ -654,6 +790,31 @@ mod test {
         );
     }
 
+    #[test]
+    fn begin_tx_to_empty_account() {
+        // The empty receiver doesn't exist (EIP-161), so it's created only by
+        // a transfer of a non-zero value.
+        for value in [eth(0), eth(1)] {
+            let ctx = TestContext::<2, 1>::new(
+                None,
+                |accs| {
+                    accs[0].address(MOCK_ACCOUNTS[0]);
+                    accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+                },
+                |mut txs, accs| {
+                    txs[0]
+                        .from(accs[1].address)
+                        .to(accs[0].address)
+                        .value(value);
+                },
+                |block, _tx| block,
+            )
+            .unwrap();
+
+            CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        }
+    }
+
     #[test]
     fn begin_tx_large_nonce() {
         // This test checks that the rw table assignment and evm circuit are consistent
@@ -709,6 +870,119 @@ mod test {
         }
     }
 
+    #[test]
+    fn begin_tx_gadget_base_fee() {
+        // The gas price pays the base fee and a tip of 1 gwei
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).balance(eth(10));
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+            },
+            |mut txs, _accs| {
+                txs[0]
+                    .from(MOCK_ACCOUNTS[1])
+                    .to(MOCK_ACCOUNTS[0])
+                    .gas_price(gwei(2))
+                    .value(eth(1));
+            },
+            |mut block, _tx| {
+                block.base_fee_per_gas(Some(gwei(1)));
+                block
+            },
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn begin_tx_balance_below_max_fee() {
+        // The caller affords the value and the gas limit at the gas price of 2 gwei, but not at a
+        // max fee per gas of 3 gwei.
+        let (gas, gas_price, value) = (Word::from(21_000), gwei(2), eth(1));
+        let ctx = || {
+            TestContext::<2, 1>::new(
+                None,
+                |accs| {
+                    accs[0].address(MOCK_ACCOUNTS[0]).balance(eth(10));
+                    accs[1]
+                        .address(MOCK_ACCOUNTS[1])
+                        .balance(gas * gas_price + value);
+                },
+                |mut txs, _accs| {
+                    txs[0]
+                        .from(MOCK_ACCOUNTS[1])
+                        .to(MOCK_ACCOUNTS[0])
+                        .gas(gas)
+                        .gas_price(gas_price)
+                        .value(value);
+                },
+                |block, _tx| block,
+            )
+            .unwrap()
+        };
+        CircuitTestBuilder::new_from_test_ctx(ctx()).run();
+
+        // The external tracer runs every tx as a legacy one, whose fee caps are its gas price, so
+        // the fee caps are raised on the witness, as those of a set-code tx.
+        CircuitTestBuilder::new_from_test_ctx(ctx())
+            .block_modifier(Box::new(|block| {
+                let tx = block.txs[0].tx_mut();
+                tx.authorization_list = Some(vec![]);
+                tx.gas_fee_cap = gwei(3);
+                tx.gas_tip_cap = gwei(2);
+            }))
+            .run_with_result()
+            .unwrap_err()
+            .assert_evm_failure();
+    }
+
+    #[test]
+    fn begin_tx_access_list() {
+        // The callee and one of its storage keys are in the access list, so the SLOAD of the key
+        // is warm, and the other address of the access list doesn't exist.
+        let code = bytecode! {
+            PUSH1(1)
+            SLOAD
+            STOP
+        };
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(eth(10))
+                    .code(code);
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+            },
+            |mut txs, _accs| {
+                txs[0]
+                    .from(MOCK_ACCOUNTS[1])
+                    .to(MOCK_ACCOUNTS[0])
+                    .access_list(AccessList(vec![
+                        AccessListItem {
+                            address: MOCK_ACCOUNTS[0],
+                            storage_keys: vec![H256::from_low_u64_be(1)],
+                        },
+                        AccessListItem {
+                            address: MOCK_ACCOUNTS[2],
+                            storage_keys: vec![],
+                        },
+                    ]));
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(FixedCParams {
+                max_access_list: 2,
+                ..Default::default()
+            })
+            .run();
+    }
+
     #[test]
     fn begin_tx_no_code() {
         let ctx = TestContext::<2, 1>::new(
@@ -754,6 +1028,37 @@ mod test {
         CircuitTestBuilder::new_from_test_ctx(ctx).run();
     }
 
+    #[test]
+    fn begin_tx_deposit() {
+        // The deposit mints 1 ether to the caller, which pays no fee. The callee has no code,
+        // since the external tracer doesn't mint.
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).balance(eth(10));
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+            },
+            |mut txs, _accs| {
+                txs[0]
+                    .from(MOCK_ACCOUNTS[1])
+                    .to(MOCK_ACCOUNTS[0])
+                    .value(eth(2))
+                    .deposit(Deposit {
+                        source_hash: H256::repeat_byte(0xde),
+                        mint: eth(1),
+                        is_system_tx: false,
+                    });
+            },
+            |mut block, _tx| {
+                block.base_fee_per_gas(Some(gwei(1)));
+                block
+            },
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
     fn begin_tx_deploy(nonce: u64) {
         let code = bytecode! {
             // [ADDRESS, STOP]
@@ -0,0 +1,138 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            common_gadget::SameContextGadget,
+            constraint_builder::{EVMConstraintBuilder, StepStateTransition, Transition::Delta},
+            CachedRegion,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::BlockContextFieldTag,
+    util::{
+        word::{WordCell, WordExpr},
+        Expr,
+    },
+};
+use bus_mapping::evm::OpcodeId;
+use eth_types::Field;
+use halo2_proofs::plonk::Error;
+
+#[derive(Clone, Debug)]
+pub(crate) struct BlobBaseFeeGadget<F> {
+    same_context: SameContextGadget<F>,
+    blob_base_fee: WordCell<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for BlobBaseFeeGadget<F> {
+    const NAME: &'static str = "BLOBBASEFEE";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::BLOBBASEFEE;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let blob_base_fee = cb.query_word_unchecked();
+
+        // Push the value to the stack
+        cb.stack_push(blob_base_fee.to_word());
+
+        // Lookup block table with blob_base_fee
+        cb.block_lookup(
+            BlockContextFieldTag::BlobBaseFee.expr(),
+            None,
+            blob_base_fee.to_word(),
+        );
+
+        // State transition
+        let opcode = cb.query_cell();
+        let step_state_transition = StepStateTransition {
+            rw_counter: Delta(1.expr()),
+            program_counter: Delta(1.expr()),
+            stack_pointer: Delta((-1).expr()),
+            gas_left: Delta(-OpcodeId::BLOBBASEFEE.constant_gas_cost().expr()),
+            ..Default::default()
+        };
+        let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
+
+        Self {
+            same_context,
+            blob_base_fee,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _: &Transaction,
+        _: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.same_context.assign_exec_step(region, offset, step)?;
+        let blob_base_fee = block.get_rws(step, 0).stack_value();
+
+        self.blob_base_fee
+            .assign_u256(region, offset, blob_base_fee)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use bus_mapping::circuit_input_builder::FixedCParams;
+    use eth_types::{
+        bytecode,
+        evm_types::{Hardfork, OpcodeId, BLOB_BASE_FEE_UPDATE_FRACTION},
+    };
+    use mock::{
+        interpreter,
+        test_ctx::{helpers::*, TestContext},
+    };
+
+    fn test_ok(excess_blob_gas: Option<u64>) {
+        let bytecode = bytecode! {
+            #[start]
+            BLOBBASEFEE
+            STOP
+        };
+
+        // The geth-utils tracer doesn't support EIP-7516 yet.
+        let ctx = TestContext::<2, 1>::new_with_tracer(
+            None,
+            account_0_code_account_1_no_code(bytecode),
+            tx_from_1_to_0,
+            |mut block, _tx| {
+                block.excess_blob_gas(excess_blob_gas);
+                block
+            },
+            interpreter::trace,
+        )
+        .unwrap();
+        // go-ethereum charges the quick step gas for BLOBBASEFEE.
+        let step = ctx.geth_traces[0]
+            .struct_logs
+            .iter()
+            .find(|step| step.op == OpcodeId::BLOBBASEFEE)
+            .unwrap();
+        assert_eq!(step.gas_cost, 2);
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(FixedCParams {
+                hardfork: Hardfork::Cancun,
+                ..Default::default()
+            })
+            .run();
+    }
+
+    #[test]
+    fn blobbasefee_gadget_min_fee() {
+        test_ok(None);
+    }
+
+    #[test]
+    fn blobbasefee_gadget_excess_blob_gas() {
+        test_ok(Some(BLOB_BASE_FEE_UPDATE_FRACTION));
+    }
+}
@@ -0,0 +1,170 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::N_BYTES_U64,
+        step::ExecutionState,
+        util::{
+            common_gadget::{SameContextGadget, WordByteCapGadget},
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
+                Transition::Delta,
+            },
+            CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::{CallContextFieldTag, TxContextFieldTag},
+    util::{
+        word::{WordCell, WordExpr},
+        Expr,
+    },
+};
+use bus_mapping::evm::OpcodeId;
+use eth_types::Field;
+use gadgets::util::not;
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+#[derive(Clone, Debug)]
+pub(crate) struct BlobHashGadget<F> {
+    same_context: SameContextGadget<F>,
+    tx_id: Cell<F>,
+    blob_hashes_length: Cell<F>,
+    index: WordByteCapGadget<F, N_BYTES_U64>,
+    blob_hash: WordCell<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for BlobHashGadget<F> {
+    const NAME: &'static str = "BLOBHASH";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::BLOBHASH;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        let blob_hashes_length = cb.tx_context(
+            tx_id.expr(),
+            TxContextFieldTag::BlobVersionedHashesLength,
+            None,
+        );
+
+        let index = WordByteCapGadget::construct(cb, blob_hashes_length.expr());
+        cb.stack_pop(index.original_word().to_word());
+
+        // The hash of an index beyond the blob versioned hashes of the tx is 0
+        let blob_hash = cb.query_word_unchecked();
+        cb.condition(index.lt_cap(), |cb| {
+            cb.tx_context_lookup(
+                tx_id.expr(),
+                TxContextFieldTag::BlobVersionedHash,
+                Some(index.valid_value()),
+                blob_hash.to_word(),
+            );
+        });
+        cb.condition(not::expr(index.lt_cap()), |cb| {
+            cb.require_zero_word("blob_hash == 0 for an invalid index", blob_hash.to_word());
+        });
+
+        cb.stack_push(blob_hash.to_word());
+
+        let step_state_transition = StepStateTransition {
+            rw_counter: Delta(3.expr()),
+            program_counter: Delta(1.expr()),
+            gas_left: Delta(-OpcodeId::BLOBHASH.constant_gas_cost().expr()),
+            ..Default::default()
+        };
+        let opcode = cb.query_cell();
+        let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
+
+        Self {
+            same_context,
+            tx_id,
+            blob_hashes_length,
+            index,
+            blob_hash,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        _: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.same_context.assign_exec_step(region, offset, step)?;
+
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id)))?;
+        let blob_hashes_length = F::from(tx.blob_versioned_hashes.len() as u64);
+        self.blob_hashes_length
+            .assign(region, offset, Value::known(blob_hashes_length))?;
+
+        let index = block.get_rws(step, 1).stack_value();
+        self.index
+            .assign(region, offset, index, blob_hashes_length)?;
+        self.blob_hash
+            .assign_u256(region, offset, block.get_rws(step, 2).stack_value())?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use bus_mapping::circuit_input_builder::FixedCParams;
+    use eth_types::{
+        bytecode,
+        evm_types::{Hardfork, OpcodeId},
+        Word, H256,
+    };
+    use mock::{interpreter, test_ctx::helpers::account_0_code_account_1_no_code, TestContext};
+
+    fn test_ok(index: Word) {
+        let code = bytecode! {
+            .op_blobhash(index)
+            STOP
+        };
+
+        // The geth-utils tracer doesn't support EIP-4844 yet.
+        let ctx = TestContext::<2, 1>::new_with_tracer(
+            None,
+            account_0_code_account_1_no_code(code),
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .blob_versioned_hashes(vec![H256::repeat_byte(0x01), H256::repeat_byte(0x02)]);
+            },
+            |block, _tx| block,
+            interpreter::trace,
+        )
+        .unwrap();
+        // go-ethereum charges the hash opcode gas for BLOBHASH.
+        let step = ctx.geth_traces[0]
+            .struct_logs
+            .iter()
+            .find(|step| step.op == OpcodeId::BLOBHASH)
+            .unwrap();
+        assert_eq!(step.gas_cost, 3);
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(FixedCParams {
+                hardfork: Hardfork::Cancun,
+                ..Default::default()
+            })
+            .run();
+    }
+
+    #[test]
+    fn blobhash_gadget_simple() {
+        test_ok(Word::from(1));
+    }
+
+    #[test]
+    fn blobhash_gadget_out_of_range() {
+        test_ok(Word::from(2));
+        test_ok(Word::MAX);
+    }
+}
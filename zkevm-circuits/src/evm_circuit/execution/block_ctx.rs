@@ -19,11 +19,16 @@ use eth_types::Field;
 use halo2_proofs::plonk::Error;
 
 use super::ExecutionGadget;
+#[cfg(feature = "paris")]
+use crate::evm_circuit::util::math_gadget::IsEqualGadget;
 
 #[derive(Clone, Debug)]
 pub(crate) struct BlockCtxGadget<F> {
     same_context: SameContextGadget<F>,
     value: WordCell<F>,
+    /// After the merge, DIFFICULTY is PREVRANDAO (EIP-4399)
+    #[cfg(feature = "paris")]
+    is_prevrandao: IsEqualGadget<F>,
 }
 
 impl<F: Field> ExecutionGadget<F> for BlockCtxGadget<F> {
@@ -40,9 +45,18 @@ impl<F: Field> ExecutionGadget<F> for BlockCtxGadget<F> {
         let opcode = cb.query_cell();
         let blockctx_tag = BlockContextFieldTag::Coinbase.expr()
             + (opcode.expr() - OpcodeId::COINBASE.as_u64().expr());
+        // The prev_randao has its own tag at the end of the block table
+        #[cfg(feature = "paris")]
+        let is_prevrandao =
+            IsEqualGadget::construct(cb, opcode.expr(), OpcodeId::DIFFICULTY.as_u64().expr());
+        #[cfg(feature = "paris")]
+        let blockctx_tag = blockctx_tag
+            + is_prevrandao.expr()
+                * (BlockContextFieldTag::PrevRandao.expr()
+                    - BlockContextFieldTag::Difficulty.expr());
 
         // Lookup block table with block context ops
-        // TIMESTAMP/NUMBER/GASLIMIT, COINBASE and DIFFICULTY/BASEFEE
+        // TIMESTAMP/NUMBER/GASLIMIT, COINBASE and DIFFICULTY (PREVRANDAO)/BASEFEE
         cb.block_lookup(blockctx_tag, None, value.to_word());
 
         // State transition
@@ -58,6 +72,8 @@ impl<F: Field> ExecutionGadget<F> for BlockCtxGadget<F> {
         Self {
             same_context,
             value,
+            #[cfg(feature = "paris")]
+            is_prevrandao,
         }
     }
 
@@ -75,6 +91,13 @@ impl<F: Field> ExecutionGadget<F> for BlockCtxGadget<F> {
         let value = block.get_rws(step, 0).stack_value();
 
         self.value.assign_u256(region, offset, value)?;
+        #[cfg(feature = "paris")]
+        self.is_prevrandao.assign(
+            region,
+            offset,
+            F::from(step.opcode().unwrap().as_u64()),
+            F::from(OpcodeId::DIFFICULTY.as_u64()),
+        )?;
 
         Ok(())
     }
@@ -84,7 +107,11 @@ impl<F: Field> ExecutionGadget<F> for BlockCtxGadget<F> {
 mod test {
     use crate::test_util::CircuitTestBuilder;
     use eth_types::bytecode;
-    use mock::TestContext;
+    use mock::{
+        gwei,
+        test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+        TestContext, MOCK_MIX_HASH,
+    };
 
     fn test_ok(bytecode: bytecode::Bytecode) {
         CircuitTestBuilder::new_from_test_ctx(
@@ -124,4 +151,48 @@ mod test {
         };
         test_ok(bytecode);
     }
+
+    #[test]
+    fn blockcxt_basefee_gadget_test() {
+        let bytecode = bytecode! {
+            BASEFEE
+            STOP
+        };
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode),
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .gas_price(gwei(2));
+            },
+            |mut block, _tx| {
+                block.base_fee_per_gas(Some(gwei(1)));
+                block
+            },
+        )
+        .unwrap();
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[cfg(feature = "paris")]
+    #[test]
+    fn blockcxt_prevrandao_gadget_test() {
+        let bytecode = bytecode! {
+            DIFFICULTY
+            STOP
+        };
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode),
+            tx_from_1_to_0,
+            |mut block, _tx| {
+                block.mix_hash(*MOCK_MIX_HASH);
+                block
+            },
+        )
+        .unwrap();
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
 }
@@ -10,26 +10,38 @@ use crate::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
                 Transition::Delta,
             },
-            math_gadget::LtGadget,
+            math_gadget::{ConstantDivisionGadget, LtGadget},
             CachedRegion, Cell, Word,
         },
         witness::{Block, Call, ExecStep, Transaction},
     },
-    table::BlockContextFieldTag,
+    table::{BlockContextFieldTag, CallContextFieldTag},
     util::word::WordExpr,
 };
 use bus_mapping::evm::OpcodeId;
-use eth_types::{Field, ToScalar};
+use eth_types::{
+    evm_types::system_contracts::{HISTORY_SERVE_WINDOW, HISTORY_STORAGE_ADDRESS},
+    Field, ToScalar,
+};
 use gadgets::util::{not, Expr};
-use halo2_proofs::{circuit::Value, plonk::Error};
+use halo2_proofs::{
+    circuit::Value,
+    plonk::{Error, Expression},
+};
 
+/// Gadget for BLOCKHASH.  Before Prague the hash is looked up in the block
+/// hash table by its depth from the current block; since Prague it is read from the storage of the
+/// history storage contract (EIP-2935), which the HistoryStorage step keeps up to date.
 #[derive(Clone, Debug)]
 pub(crate) struct BlockHashGadget<F> {
     same_context: SameContextGadget<F>,
+    tx_id: Option<Cell<F>>,
     block_number: WordByteCapGadget<F, N_BYTES_U64>,
     current_block_number: Cell<F>,
     block_hash: Word<Cell<F>>,
     diff_lt: LtGadget<F, N_BYTES_U64>,
+    history_index: ConstantDivisionGadget<F, N_BYTES_U64>,
+    committed_value: Word<Cell<F>>,
 }
 
 impl<F: Field> ExecutionGadget<F> for BlockHashGadget<F> {
@@ -38,6 +50,9 @@ impl<F: Field> ExecutionGadget<F> for BlockHashGadget<F> {
     const EXECUTION_STATE: ExecutionState = ExecutionState::BLOCKHASH;
 
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let is_history_storage = cb.hardfork.is_history_storage_active();
+        let tx_id = is_history_storage.then(|| cb.call_context(None, CallContextFieldTag::TxId));
+
         let current_block_number = cb.query_cell();
         cb.block_lookup(
             BlockContextFieldTag::Number.expr(),
@@ -50,23 +65,16 @@ impl<F: Field> ExecutionGadget<F> for BlockHashGadget<F> {
 
         let block_hash = cb.query_word_unchecked();
 
+        let window = cb.hardfork.block_hash_window();
         let diff_lt = LtGadget::construct(
             cb,
             current_block_number.expr(),
-            257.expr() + block_number.valid_value(),
+            (window + 1).expr() + block_number.valid_value(),
         );
 
         let is_valid = and::expr([block_number.lt_cap(), diff_lt.expr()]);
 
-        cb.condition(is_valid.expr(), |cb| {
-            cb.block_lookup(
-                BlockContextFieldTag::BlockHash.expr(),
-                Some(block_number.valid_value()),
-                block_hash.to_word(),
-            );
-        });
-
-        cb.condition(not::expr(is_valid), |cb| {
+        cb.condition(not::expr(is_valid.expr()), |cb| {
             cb.require_zero_word(
                 "Invalid block number for block hash lookup",
                 block_hash.to_word(),
@@ -75,8 +83,30 @@ impl<F: Field> ExecutionGadget<F> for BlockHashGadget<F> {
 
         cb.stack_push(block_hash.to_word());
 
+        let history_index =
+            ConstantDivisionGadget::construct(cb, block_number.valid_value(), HISTORY_SERVE_WINDOW);
+        let committed_value = cb.query_word_unchecked();
+        cb.condition(is_valid.expr(), |cb| match &tx_id {
+            Some(tx_id) => cb.account_storage_read(
+                Word::from(HISTORY_STORAGE_ADDRESS).map(Expression::Constant),
+                Word::from_lo_unchecked(history_index.remainder()),
+                block_hash.to_word(),
+                tx_id.expr(),
+                committed_value.to_word(),
+            ),
+            None => cb.block_hash_lookup(
+                current_block_number.expr() - block_number.valid_value(),
+                block_hash.to_word(),
+            ),
+        });
+
+        let rw_counter = if is_history_storage {
+            3.expr() + is_valid
+        } else {
+            2.expr()
+        };
         let step_state_transition = StepStateTransition {
-            rw_counter: Delta(2.expr()),
+            rw_counter: Delta(rw_counter),
             program_counter: Delta(1.expr()),
             gas_left: Delta(-OpcodeId::BLOCKHASH.constant_gas_cost().expr()),
             ..Default::default()
@@ -86,10 +116,13 @@ impl<F: Field> ExecutionGadget<F> for BlockHashGadget<F> {
         let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
         Self {
             same_context,
+            tx_id,
             block_number,
             current_block_number,
             block_hash,
             diff_lt,
+            history_index,
+            committed_value,
         }
     }
 
@@ -98,7 +131,7 @@ impl<F: Field> ExecutionGadget<F> for BlockHashGadget<F> {
         region: &mut CachedRegion<'_, '_, F>,
         offset: usize,
         block: &Block<F>,
-        _: &Transaction,
+        tx: &Transaction,
         _: &Call,
         step: &ExecStep,
     ) -> Result<(), Error> {
@@ -109,23 +142,42 @@ impl<F: Field> ExecutionGadget<F> for BlockHashGadget<F> {
             .to_scalar()
             .expect("unexpected U256 -> Scalar conversion failure");
 
-        let block_number = block.get_rws(step, 0).stack_value();
+        let rw_offset = if let Some(tx_id) = &self.tx_id {
+            tx_id.assign(region, offset, Value::known(F::from(tx.id)))?;
+            1
+        } else {
+            0
+        };
+
+        let block_number = block.get_rws(step, rw_offset).stack_value();
         self.block_number
             .assign(region, offset, block_number, current_block_number)?;
 
         self.current_block_number
             .assign(region, offset, Value::known(current_block_number))?;
 
-        self.block_hash
-            .assign_u256(region, offset, block.get_rws(step, 1).stack_value())?;
+        self.block_hash.assign_u256(
+            region,
+            offset,
+            block.get_rws(step, rw_offset + 1).stack_value(),
+        )?;
 
+        let window = block.circuits_params.hardfork.block_hash_window();
         self.diff_lt.assign(
             region,
             offset,
             current_block_number,
-            F::from(u64::try_from(block_number).unwrap_or(u64::MAX)) + F::from(257),
+            F::from(u64::try_from(block_number).unwrap_or(u64::MAX)) + F::from(window + 1),
         )?;
 
+        self.history_index
+            .assign(region, offset, block_number.low_u64() as u128)?;
+        if step.rw_indices_len() > rw_offset + 2 {
+            let (_, _, _, committed_value) = block.get_rws(step, rw_offset + 2).storage_value_aux();
+            self.committed_value
+                .assign_u256(region, offset, committed_value)?;
+        }
+
         Ok(())
     }
 }
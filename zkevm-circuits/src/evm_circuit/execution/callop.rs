@@ -1,6 +1,6 @@
 use crate::{
     evm_circuit::{
-        execution::ExecutionGadget,
+        execution::{precompiles::PrecompileCallExprs, ExecutionGadget},
         param::{N_BYTES_ACCOUNT_ADDRESS, N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS, N_BYTES_U64},
         step::ExecutionState,
         util::{
@@ -11,7 +11,8 @@ use crate::{
                 Transition::{Delta, To},
             },
             math_gadget::{
-                ConstantDivisionGadget, IsZeroGadget, LtGadget, LtWordGadget, MinMaxGadget,
+                ConstantDivisionGadget, IsEqualGadget, IsZeroGadget, LtGadget, LtWordGadget,
+                MinMaxGadget,
             },
             memory_gadget::{CommonMemoryAddressGadget, MemoryAddressGadget},
             not, or,
@@ -29,7 +30,7 @@ use crate::{
 use bus_mapping::{
     circuit_input_builder::CopyDataType,
     evm::OpcodeId,
-    precompile::{is_precompiled, PrecompileCalls},
+    precompile::{is_precompiled, modexp_input_len, PrecompileCalls, MODEXP_HEADER_LEN},
 };
 use eth_types::{evm_types::GAS_STIPEND_CALL_WITH_VALUE, Field, ToAddress, ToScalar, U256};
 use halo2_proofs::{circuit::Value, plonk::Error};
@@ -68,6 +69,7 @@ pub(crate) struct CallOpGadget<F> {
     // check if the call is a precompile call.
     is_code_address_zero: IsZeroGadget<F>,
     is_precompile_lt: LtGadget<F, N_BYTES_ACCOUNT_ADDRESS>,
+    is_p256_verify: IsEqualGadget<F>,
     precompile_gadget: PrecompileGadget<F>,
     precompile_return_length: Cell<F>,
     precompile_return_length_zero: IsZeroGadget<F>,
@@ -212,13 +214,25 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
         });
 
         // whether the call is to a precompiled contract.
-        // precompile contracts are stored from address 0x01 to 0x09.
+        // precompile contracts are stored from address 0x01 to the precompile
+        // count of the hardfork, and P256VERIFY at 0x100 if enabled.
         let is_code_address_zero = IsZeroGadget::construct(cb, call_gadget.callee_address.expr());
-        let is_precompile_lt =
-            LtGadget::construct(cb, call_gadget.callee_address.expr(), 0x0A.expr());
-        let is_precompile = and::expr([
-            not::expr(is_code_address_zero.expr()),
-            is_precompile_lt.expr(),
+        let is_precompile_lt = LtGadget::construct(
+            cb,
+            call_gadget.callee_address.expr(),
+            (cb.hardfork.precompile_count() + 1).expr(),
+        );
+        let is_p256_verify = IsEqualGadget::construct(
+            cb,
+            call_gadget.callee_address.expr(),
+            PrecompileCalls::P256Verify.address().expr(),
+        );
+        let is_precompile = or::expr([
+            and::expr([
+                not::expr(is_code_address_zero.expr()),
+                is_precompile_lt.expr(),
+            ]),
+            cb.p256_verify.expr() * is_p256_verify.expr(),
         ]);
         let precompile_return_length = cb.query_cell();
         let precompile_return_length_zero =
@@ -498,17 +512,14 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
 
                 let precompile_gadget = PrecompileGadget::construct(
                     cb,
-                    call_gadget.is_success.expr(),
                     call_gadget.callee_address.expr(),
-                    cb.curr.state.call_id.expr(),
-                    call_gadget.cd_address.offset(),
-                    call_gadget.cd_address.length(),
-                    call_gadget.rd_address.offset(),
-                    call_gadget.rd_address.length(),
-                    precompile_return_length.expr(),
-                    precompile_input_bytes_rlc.expr(),
-                    precompile_output_bytes_rlc.expr(),
-                    precompile_return_bytes_rlc.expr(),
+                    PrecompileCallExprs {
+                        cd_length: call_gadget.cd_address.length(),
+                        return_length: precompile_return_length.expr(),
+                        input_len: precompile_input_len.expr(),
+                        input_bytes_rlc: precompile_input_bytes_rlc.expr(),
+                        output_bytes_rlc: precompile_output_bytes_rlc.expr(),
+                    },
                 );
 
                 (
@@ -770,6 +781,7 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             // precompile related fields.
             is_code_address_zero,
             is_precompile_lt,
+            is_p256_verify,
             precompile_gadget,
             precompile_return_length,
             precompile_return_length_zero,
@@ -957,17 +969,28 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             F::from(gas_available - gas_available / 64),
         )?;
 
-        let (_is_precompile_call, precompile_addr) = {
-            let precompile_addr = callee_address.to_address();
-            let is_precompiled_call = is_precompiled(&precompile_addr);
-            (is_precompiled_call, precompile_addr)
-        };
+        let precompile_addr = callee_address.to_address();
+        let is_precompile_call = is_precompiled(
+            &precompile_addr,
+            block.circuits_params.hardfork,
+            block.circuits_params.p256_verify,
+        );
         let code_address: F = callee_address.to_address().to_scalar().unwrap();
         self.is_code_address_zero
             .assign(region, offset, code_address)?;
-        self.is_precompile_lt
-            .assign(region, offset, code_address, 0x0Au64.into())?;
-        let precompile_return_length = if is_precompiled(&callee_address.to_address()) {
+        self.is_precompile_lt.assign(
+            region,
+            offset,
+            code_address,
+            (block.circuits_params.hardfork.precompile_count() + 1).into(),
+        )?;
+        self.is_p256_verify.assign(
+            region,
+            offset,
+            code_address,
+            F::from(PrecompileCalls::P256Verify.address()),
+        )?;
+        let precompile_return_length = if is_precompile_call {
             rws.offset_add(14); // skip
             let value_rw = rws.next();
             assert_eq!(
@@ -1004,17 +1027,23 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             input_rws,
             output_rws,
             return_rws,
-        ) = if is_precheck_ok && is_precompiled(&callee_address.to_address()) {
-            let precompile_call: PrecompileCalls = precompile_addr.0[19].into();
-            let input_len = if let Some(input_len) = precompile_call.input_len() {
-                min(input_len, cd_length.as_usize())
-            } else {
-                cd_length.as_usize()
+        ) = if is_precheck_ok && is_precompile_call {
+            let precompile_call: PrecompileCalls = precompile_addr.into();
+            let mut input_bytes = Vec::new();
+            let input_len = match precompile_call.input_len() {
+                Some(input_len) => min(input_len, cd_length.as_usize()),
+                None if precompile_call == PrecompileCalls::Modexp => {
+                    // The lengths of the base, exponent and modulus give the
+                    // number of bytes read after them.
+                    input_bytes = (0..min(MODEXP_HEADER_LEN, cd_length.as_usize()))
+                        .map(|_| rws.next().memory_value())
+                        .collect();
+                    modexp_input_len(&input_bytes, cd_length.as_usize())
+                }
+                None => cd_length.as_usize(),
             };
-
-            let input_bytes = (0..input_len)
-                .map(|_| rws.next().memory_value())
-                .collect::<Vec<_>>();
+            let n_header_bytes = input_bytes.len();
+            input_bytes.extend((n_header_bytes..input_len).map(|_| rws.next().memory_value()));
             let output_bytes = (0..precompile_return_length.as_u64())
                 .map(|_| rws.next().memory_value())
                 .collect::<Vec<_>>();
@@ -1089,12 +1118,9 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             F::from_u128(remainder),
         )?;
 
-        if is_precompiled(&callee_address.to_address()) {
-            self.precompile_gadget.assign(
-                region,
-                offset,
-                callee_address.to_address().0[19].into(),
-            )?;
+        if is_precompile_call {
+            self.precompile_gadget
+                .assign(region, offset, callee_address.to_address().into())?;
         }
 
         Ok(())
@@ -1349,6 +1375,103 @@ mod test {
         }
     }
 
+    #[test]
+    fn callop_delegatecall_chain() {
+        for ((opcode, callee_reverts), caller_reverts) in
+            [OpcodeId::DELEGATECALL, OpcodeId::CALLCODE]
+                .iter()
+                .cartesian_product([false, true])
+                .cartesian_product([false, true])
+        {
+            test_delegatecall_chain(opcode, callee_reverts, caller_reverts);
+        }
+    }
+
+    fn call_bytecode(opcode: &OpcodeId, address: Address) -> bytecode::Bytecode {
+        let mut bytecode = bytecode! {
+            PUSH1(0) // retLength
+            PUSH1(0) // retOffset
+            PUSH1(0) // argsLength
+            PUSH1(0) // argsOffset
+        };
+        if opcode == &OpcodeId::CALL || opcode == &OpcodeId::CALLCODE {
+            bytecode.push(1, U256::zero());
+        }
+        bytecode.append(&bytecode! {
+            PUSH20(address.to_word())
+            GAS
+            .write_op(*opcode)
+            POP
+        });
+        bytecode
+    }
+
+    fn terminator(reverts: bool) -> bytecode::Bytecode {
+        if reverts {
+            bytecode! {
+                PUSH1(0)
+                PUSH1(0)
+                REVERT
+            }
+        } else {
+            bytecode! { STOP }
+        }
+    }
+
+    // The transaction calls A, which delegatecalls B, which delegatecalls or
+    // callcodes C. C writes its caller, value and address into the storage of
+    // its context, which is the storage of A for DELEGATECALL and of B for
+    // CALLCODE.
+    fn test_delegatecall_chain(opcode: &OpcodeId, callee_reverts: bool, caller_reverts: bool) {
+        let [addr_a, addr_b, addr_c] = [0xaa, 0xbb, 0xcc].map(Address::repeat_byte);
+
+        let mut code_a = call_bytecode(&OpcodeId::DELEGATECALL, addr_b);
+        code_a.append(&bytecode! {
+            PUSH1(0)
+            SLOAD
+            POP
+            STOP
+        });
+        let mut code_b = call_bytecode(opcode, addr_c);
+        code_b.append(&terminator(caller_reverts));
+        let mut code_c = bytecode! {
+            CALLER
+            PUSH1(0)
+            SSTORE
+            CALLVALUE
+            PUSH1(1)
+            SSTORE
+            ADDRESS
+            PUSH1(2)
+            SSTORE
+        };
+        code_c.append(&terminator(callee_reverts));
+
+        let ctx = TestContext::<4, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x000000000000000000000000000000000000cafe"))
+                    .balance(Word::from(10u64.pow(19)));
+                accs[1].address(addr_a).code(code_a);
+                accs[2].address(addr_b).code(code_b);
+                accs[3].address(addr_c).code(code_c);
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(500000.into())
+                    // The value of the transaction is inherited by DELEGATECALL.
+                    .value(1000.into());
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
     #[test]
     fn callop_base() {
         test_ok(
@@ -1357,6 +1480,22 @@ mod test {
         );
     }
 
+    #[test]
+    fn callop_empty_account() {
+        // The empty callee doesn't exist (EIP-161), so it's created only by a
+        // CALL with value, which is reverted with the caller.
+        for (value, caller_is_success) in [(0, true), (1, true), (1, false)] {
+            let stack = Stack {
+                value: Word::from(value),
+                ..Default::default()
+            };
+            test_ok(
+                caller(&OpcodeId::CALL, stack, caller_is_success),
+                callee(bytecode! {}),
+            );
+        }
+    }
+
     fn test_ok(caller: Account, callee: Account) {
         let ctx = TestContext::<3, 1>::new(
             None,
@@ -30,12 +30,12 @@ use crate::{
     },
 };
 use bus_mapping::{
-    circuit_input_builder::CopyDataType, evm::OpcodeId, operation::Target, state_db::CodeDB,
-};
-use eth_types::{
-    evm_types::{GasCost, INIT_CODE_WORD_GAS},
-    Field, ToBigEndian, ToScalar, ToWord, U256,
+    circuit_input_builder::CopyDataType,
+    evm::OpcodeId,
+    operation::{CreatedAccountStatus, Target},
+    state_db::CodeDB,
 };
+use eth_types::{evm_types::GasCost, Field, ToBigEndian, ToScalar, ToWord, U256};
 use ethers_core::utils::keccak256;
 use gadgets::util::{and, select};
 use halo2_proofs::{
@@ -69,6 +69,9 @@ pub(crate) struct CreateGadget<F, const IS_CREATE2: bool, const S: ExecutionStat
     init_code: MemoryAddressGadget<F>,
     init_code_word_size: ConstantDivisionGadget<F, N_BYTES_MEMORY_ADDRESS>,
     init_code_rlc: Cell<F>,
+    // The init code size is at most 49152 (EIP-3860) if Shanghai, otherwise
+    // 0x1FFFFFFFE0, or the step is an ErrorOutOfGasCREATE.
+    init_code_size_overflow: LtGadget<F, N_BYTES_MEMORY_ADDRESS>,
     keccak_output: Word32Cell<F>,
 
     is_depth_in_range: LtGadget<F, N_BYTES_U64>,
@@ -155,6 +158,15 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
 
         // verify gas cost
         let init_code = MemoryAddressGadget::construct(cb, offset, length);
+        let init_code_size_overflow = LtGadget::construct(
+            cb,
+            cb.hardfork.max_init_code_size().expr(),
+            init_code.length(),
+        );
+        cb.require_zero(
+            "init code size <= MAX_INIT_CODE_SIZE",
+            init_code_size_overflow.expr(),
+        );
         let memory_expansion = MemoryExpansionGadget::construct(cb, [init_code.address()]);
         let init_code_word_size = ConstantDivisionGadget::construct(
             cb,
@@ -164,8 +176,8 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
         let keccak_gas_cost = init_code_word_size.quotient()
             * select::expr(
                 is_create2.expr(),
-                (INIT_CODE_WORD_GAS + GasCost::COPY_SHA3).expr(),
-                INIT_CODE_WORD_GAS.expr(),
+                cb.hardfork.create2_gas_per_code_word().expr(),
+                cb.hardfork.create_gas_per_code_word().expr(),
             );
         let gas_cost = GasCost::CREATE.expr() + memory_expansion.gas_cost() + keccak_gas_cost;
         let gas_remaining = cb.curr.state.gas_left.expr() - gas_cost.clone();
@@ -346,6 +358,15 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
                     Word::zero(),
                     Some(&mut callee_reversion_info),
                 );
+                // Mark the contract as created in the tx, so that it can be
+                // destructed by SELFDESTRUCT (EIP-6780)
+                cb.created_account_write(
+                    tx_id.expr(),
+                    contract_addr.to_word(),
+                    CreatedAccountStatus::Created.expr(),
+                    CreatedAccountStatus::NotCreated.expr(),
+                    Some(&mut callee_reversion_info),
+                );
 
                 cb.condition(init_code.has_length(), |cb| {
                     for (field_tag, value) in [
@@ -403,7 +424,7 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
                         code_hash: To(create.code_hash()),
                         gas_left: To(callee_gas_left),
                         reversible_write_counter: To(
-                            1.expr() + transfer.reversible_w_delta().expr()
+                            2.expr() + transfer.reversible_w_delta().expr()
                         ),
                         ..StepStateTransition::new_context()
                     })
@@ -428,7 +449,7 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
                         stack_pointer: Delta(2.expr() + is_create2.expr()),
                         gas_left: Delta(-gas_cost.expr()),
                         reversible_write_counter: Delta(
-                            3.expr() + transfer.reversible_w_delta().expr(),
+                            4.expr() + transfer.reversible_w_delta().expr(),
                         ),
                         ..Default::default()
                     })
@@ -489,6 +510,7 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
             transfer,
             init_code,
             init_code_rlc,
+            init_code_size_overflow,
             memory_expansion,
             gas_left,
             init_code_word_size,
@@ -518,6 +540,7 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
     ) -> Result<(), Error> {
         let opcode = step.opcode().unwrap();
         let is_create2 = opcode == OpcodeId::CREATE2;
+        let hardfork = block.circuits_params.hardfork;
         self.opcode
             .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
         self.is_create2.assign(
@@ -605,6 +628,12 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
         let init_code_address =
             self.init_code
                 .assign(region, offset, init_code_start, init_code_length)?;
+        self.init_code_size_overflow.assign(
+            region,
+            offset,
+            F::from(hardfork.max_init_code_size()),
+            F::from(init_code_length.as_u64()),
+        )?;
         let (_, memory_expansion_gas_cost) = self.memory_expansion.assign(
             region,
             offset,
@@ -618,9 +647,9 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
         )?;
         let initcode_gas_cost = u64::try_from(init_code_word_size).unwrap()
             * if is_create2 {
-                INIT_CODE_WORD_GAS + GasCost::COPY_SHA3
+                hardfork.create2_gas_per_code_word()
             } else {
-                INIT_CODE_WORD_GAS
+                hardfork.create_gas_per_code_word()
             };
         let gas_left =
             step.gas_left - GasCost::CREATE - memory_expansion_gas_cost - initcode_gas_cost;
@@ -709,6 +738,7 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
                 F::ONE
             } else {
                 rws.next(); // callee nonce += 1
+                rws.next(); // callee created
                 rws.next(); // caller id
                 let rw = rws.next();
                 debug_assert_eq!(rw.tag(), Target::CallContext);
@@ -737,6 +767,7 @@ mod test {
     use eth_types::{
         address, bytecode, evm_types::OpcodeId, geth_types::Account, word, Address, Bytecode, Word,
     };
+    use ethers_core::utils::get_contract_address;
     use itertools::Itertools;
     use lazy_static::lazy_static;
     use mock::{eth, TestContext};
@@ -947,6 +978,39 @@ mod test {
         run_test_circuits(test_context(caller));
     }
 
+    #[test]
+    fn test_create_address_collision_error_with_create() {
+        // The CREATE address of the caller is taken by an account with a nonce.
+        let caller = Account {
+            address: *CALLER_ADDRESS,
+            code: creator_bytecode(initialization_bytecode(true), 23414.into(), false, true).into(),
+            nonce: 1.into(),
+            balance: eth(10),
+            ..Default::default()
+        };
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x000000000000000000000000000000000000cafe"))
+                    .balance(eth(10));
+                accs[1].account(&caller);
+                accs[2]
+                    .address(get_contract_address(*CALLER_ADDRESS, 1))
+                    .nonce(1);
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(word!("0x2386F26FC10000"));
+            },
+            |block, _| block,
+        )
+        .unwrap();
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
     // Ignore this test case. It could run successfully but slow for CI.
     #[ignore]
     #[test]
@@ -0,0 +1,335 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            common_gadget::SameContextGadget,
+            constraint_builder::{EVMConstraintBuilder, StepStateTransition},
+            math_gadget::IsEqualGadget,
+            CachedRegion,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    util::Expr,
+};
+use bus_mapping::evm::{register_custom_opcode, Opcode};
+use eth_types::{evm_types::custom_opcodes::CustomOpcode, Field};
+use halo2_proofs::plonk::Error;
+use lazy_static::lazy_static;
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt::Debug,
+    rc::Rc,
+    sync::RwLock,
+};
+
+/// Gadget of a custom opcode of an L2, which executes in the same context.
+///
+/// The constraints of the gadget are enabled only on the steps which execute
+/// its opcode, so its lookups must be done on the constraint builder it's
+/// configured with.  A fork implements the trait for each custom opcode, and
+/// registers it with [`register_custom_opcode_gadget`].
+pub trait CustomOpcodeGadget<F: Field>: Debug {
+    /// The custom opcode of the gadget, with its witness generation
+    type Opcode: CustomOpcode + Opcode;
+
+    /// Configure the gadget, and return it with the transition of its step.
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> (Self, StepStateTransition<F>)
+    where
+        Self: Sized;
+
+    /// Assign the step of the custom opcode.
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        transaction: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error>;
+}
+
+/// Configuration of the gadget of a custom opcode.
+type ConfigureCustomGadget<F> =
+    fn(&mut EVMConstraintBuilder<F>) -> (Rc<dyn CustomOpcodeAssignment<F>>, StepStateTransition<F>);
+
+/// Configurations of the gadgets of the custom opcodes, by field of the EVM
+/// circuit, as `(opcode, ConfigureCustomGadget<F>)`
+type CustomOpcodeGadgets = HashMap<TypeId, Vec<(u8, Box<dyn Any + Send + Sync>)>>;
+
+lazy_static! {
+    /// The gadgets of the registered custom opcodes
+    static ref CUSTOM_OPCODE_GADGETS: RwLock<CustomOpcodeGadgets> = RwLock::new(HashMap::new());
+}
+
+/// Register a custom opcode of the L2 with its witness generation (see
+/// [`register_custom_opcode`]), and its gadget in the EVM circuits over `F`.
+/// It must be registered before the EVM circuit is configured.
+pub fn register_custom_opcode_gadget<F: Field, G: CustomOpcodeGadget<F> + 'static>() {
+    register_custom_opcode::<G::Opcode>();
+
+    let mut gadgets = CUSTOM_OPCODE_GADGETS
+        .write()
+        .expect("poisoned custom opcode gadgets");
+    let gadgets = gadgets.entry(TypeId::of::<F>()).or_default();
+    if gadgets
+        .iter()
+        .all(|(opcode, _)| *opcode != G::Opcode::OPCODE)
+    {
+        let configure: ConfigureCustomGadget<F> = configure_custom_gadget::<F, G>;
+        gadgets.push((G::Opcode::OPCODE, Box::new(configure)));
+    }
+}
+
+/// Return the configurations of the gadgets of the registered custom opcodes.
+fn custom_opcode_gadgets<F: Field>() -> Vec<(u8, ConfigureCustomGadget<F>)> {
+    CUSTOM_OPCODE_GADGETS
+        .read()
+        .expect("poisoned custom opcode gadgets")
+        .get(&TypeId::of::<F>())
+        .into_iter()
+        .flatten()
+        .map(|(opcode, configure)| {
+            let configure = configure
+                .downcast_ref::<ConfigureCustomGadget<F>>()
+                .expect("gadgets are registered by field");
+            (*opcode, *configure)
+        })
+        .collect()
+}
+
+fn configure_custom_gadget<F: Field, G: CustomOpcodeGadget<F> + 'static>(
+    cb: &mut EVMConstraintBuilder<F>,
+) -> (Rc<dyn CustomOpcodeAssignment<F>>, StepStateTransition<F>) {
+    let (gadget, step_state_transition) = G::configure(cb);
+    (Rc::new(gadget), step_state_transition)
+}
+
+/// Assignment of the gadget of a custom opcode, which is object safe.
+trait CustomOpcodeAssignment<F: Field>: Debug {
+    fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        transaction: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error>;
+}
+
+impl<F: Field, G: CustomOpcodeGadget<F>> CustomOpcodeAssignment<F> for G {
+    fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        transaction: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.assign_exec_step(region, offset, block, transaction, call, step)
+    }
+}
+
+/// Gadget for the custom opcodes of an L2, which dispatches every step to the
+/// gadget of its opcode.
+///
+/// The responsible opcodes of the state are the registered custom opcodes, so
+/// exactly one of the gadgets is enabled on a step.
+#[derive(Clone, Debug)]
+pub(crate) struct CustomGadget<F> {
+    same_context: SameContextGadget<F>,
+    gadgets: Vec<(u8, IsEqualGadget<F>, Rc<dyn CustomOpcodeAssignment<F>>)>,
+}
+
+impl<F: Field> ExecutionGadget<F> for CustomGadget<F> {
+    const NAME: &'static str = "CUSTOM";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::CUSTOM;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+
+        let gadgets = custom_opcode_gadgets()
+            .into_iter()
+            .map(|(custom_opcode, configure)| {
+                let is_opcode = IsEqualGadget::construct(cb, opcode.expr(), custom_opcode.expr());
+                cb.condition(is_opcode.expr(), |cb| {
+                    let (gadget, step_state_transition) = configure(cb);
+                    cb.require_step_state_transition(step_state_transition);
+                    (custom_opcode, is_opcode, gadget)
+                })
+            })
+            .collect();
+
+        // Every gadget constrains the transition of the steps of its opcode.
+        let same_context = SameContextGadget::construct(cb, opcode, StepStateTransition::any());
+
+        Self {
+            same_context,
+            gadgets,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        transaction: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.same_context.assign_exec_step(region, offset, step)?;
+
+        let opcode = step.opcode().unwrap().as_u8();
+        for (custom_opcode, is_opcode, gadget) in self.gadgets.iter() {
+            is_opcode.assign(
+                region,
+                offset,
+                F::from(opcode as u64),
+                F::from(*custom_opcode as u64),
+            )?;
+            if opcode == *custom_opcode {
+                gadget.assign(region, offset, block, transaction, call, step)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        evm_circuit::util::constraint_builder::Transition::Delta, test_util::CircuitTestBuilder,
+        util::word::Word,
+    };
+    use bus_mapping::circuit_input_builder::{CircuitInputStateRef, FixedCParams};
+    use eth_types::{
+        evm_types::{GasCost, Hardfork, OpcodeId},
+        Bytecode, GethExecStep,
+    };
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use mock::{
+        interpreter::{self, CustomOpcodeExec},
+        test_ctx::{helpers::*, TestContext},
+    };
+    use std::{marker::PhantomData, sync::Once};
+
+    /// Value pushed by the custom opcode of the tests
+    const MAGIC: u64 = 0xc0ffee;
+
+    /// Custom opcode of the tests, which pushes [`MAGIC`]
+    #[derive(Debug)]
+    struct Magic;
+
+    impl CustomOpcode for Magic {
+        const OPCODE: u8 = 0x2a;
+        const NAME: &'static str = "MAGIC";
+        const CONSTANT_GAS: u64 = GasCost::QUICK;
+    }
+
+    impl Opcode for Magic {
+        fn gen_associated_ops(
+            state: &mut CircuitInputStateRef,
+            geth_steps: &[GethExecStep],
+        ) -> Result<Vec<ExecStep>, bus_mapping::Error> {
+            let mut exec_step = state.new_step(&geth_steps[0])?;
+            state.stack_write(
+                &mut exec_step,
+                geth_steps[1].stack.last_filled(),
+                geth_steps[1].stack.last()?,
+            )?;
+            Ok(vec![exec_step])
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    struct MagicGadget<F>(PhantomData<F>);
+
+    impl<F: Field> CustomOpcodeGadget<F> for MagicGadget<F> {
+        type Opcode = Magic;
+
+        fn configure(cb: &mut EVMConstraintBuilder<F>) -> (Self, StepStateTransition<F>) {
+            cb.stack_push(Word::from_lo_unchecked(MAGIC.expr()));
+
+            let step_state_transition = StepStateTransition {
+                rw_counter: Delta(1.expr()),
+                program_counter: Delta(1.expr()),
+                stack_pointer: Delta((-1).expr()),
+                gas_left: Delta(-Magic::CONSTANT_GAS.expr()),
+                ..Default::default()
+            };
+            (Self(PhantomData), step_state_transition)
+        }
+
+        fn assign_exec_step(
+            &self,
+            _: &mut CachedRegion<'_, '_, F>,
+            _: usize,
+            _: &Block<F>,
+            _: &Transaction,
+            _: &Call,
+            _: &ExecStep,
+        ) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    /// Return the test of a tx executing the custom opcode, traced with the
+    /// pushed `value`.
+    fn test_circuit(value: u64) -> CircuitTestBuilder<2, 1> {
+        static REGISTER: Once = Once::new();
+        REGISTER.call_once(register_custom_opcode_gadget::<Fr, MagicGadget<Fr>>);
+
+        let mut code = Bytecode::default();
+        code.write_op(OpcodeId::INVALID(Magic::OPCODE)).op_stop();
+        let exec: fn(&mut Vec<eth_types::Word>) -> Result<(), eth_types::Error> = match value {
+            MAGIC => |stack| {
+                stack.push(MAGIC.into());
+                Ok(())
+            },
+            _ => |stack| {
+                stack.push((MAGIC + 1).into());
+                Ok(())
+            },
+        };
+
+        // The geth-utils tracer doesn't know the custom opcodes of the L2.
+        let ctx = TestContext::<2, 1>::new_with_tracer(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block,
+            |config| {
+                interpreter::trace_with_custom_opcodes(config, |op| {
+                    (op == OpcodeId::INVALID(Magic::OPCODE))
+                        .then_some::<CustomOpcodeExec>((Magic::CONSTANT_GAS, exec))
+                })
+            },
+        )
+        .unwrap();
+        CircuitTestBuilder::new_from_test_ctx(ctx).params(FixedCParams {
+            hardfork: Hardfork::Cancun,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn custom_opcode_gadget_simple() {
+        test_circuit(MAGIC).run();
+    }
+
+    #[test]
+    fn custom_opcode_gadget_wrong_value() {
+        test_circuit(MAGIC + 1)
+            .run_with_result()
+            .unwrap_err()
+            .assert_evm_failure();
+    }
+}
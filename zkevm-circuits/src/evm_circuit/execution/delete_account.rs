@@ -0,0 +1,165 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            constraint_builder::EVMConstraintBuilder, tx::end_tx_transition, AccountAddress,
+            CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::{AccountFieldTag, CallContextFieldTag},
+    util::{
+        word::{Word, WordCell, WordExpr},
+        Expr,
+    },
+};
+use bus_mapping::operation::CreatedAccountStatus;
+use eth_types::Field;
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget for the deletion of an account destructed by SELFDESTRUCT in the tx
+/// (EIP-6780).
+///
+/// The steps follow EndTx, one for each destructed account.  Each step resets
+/// the status of the account in the CreatedAccount rw table, which the state
+/// circuit requires for every destructed account at the end of the tx, and
+/// then resets its nonce, balance and code hash.  The storage of the account
+/// isn't cleared.
+#[derive(Clone, Debug)]
+pub(crate) struct DeleteAccountGadget<F> {
+    tx_id: Cell<F>,
+    address: AccountAddress<F>,
+    nonce: Cell<F>,
+    balance: WordCell<F>,
+    code_hash: WordCell<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for DeleteAccountGadget<F> {
+    const NAME: &'static str = "DeleteAccount";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::DeleteAccount;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+
+        let address = cb.query_account_address();
+        cb.created_account_write(
+            tx_id.expr(),
+            address.to_word(),
+            CreatedAccountStatus::NotCreated.expr(),
+            CreatedAccountStatus::Destructed.expr(),
+            None,
+        );
+
+        let nonce = cb.query_cell();
+        let balance = cb.query_word_unchecked();
+        let code_hash = cb.query_word_unchecked();
+        for (field_tag, value_prev) in [
+            (
+                AccountFieldTag::Nonce,
+                Word::from_lo_unchecked(nonce.expr()),
+            ),
+            (AccountFieldTag::Balance, balance.to_word()),
+            (AccountFieldTag::CodeHash, code_hash.to_word()),
+        ] {
+            cb.account_write(address.to_word(), field_tag, Word::zero(), value_prev, None);
+        }
+
+        let rw_counter_offset = cb.rw_counter_offset();
+        end_tx_transition(cb, tx_id.expr(), rw_counter_offset);
+
+        Self {
+            tx_id,
+            address,
+            nonce,
+            balance,
+            code_hash,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        _: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id)))?;
+
+        let address = block
+            .get_rws(step, 1)
+            .address()
+            .expect("CreatedAccount has an address");
+        self.address.assign_h160(region, offset, address)?;
+
+        let (_, nonce) = block.get_rws(step, 2).account_nonce_pair();
+        self.nonce
+            .assign(region, offset, Value::known(F::from(nonce.as_u64())))?;
+        let (_, balance) = block.get_rws(step, 3).account_balance_pair();
+        self.balance.assign_u256(region, offset, balance)?;
+        let (_, code_hash) = block.get_rws(step, 4).account_codehash_pair();
+        self.code_hash.assign_u256(region, offset, code_hash)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{address, bytecode, Address, Bytecode, ToWord};
+    use mock::{eth, TestContext, MOCK_ACCOUNTS};
+
+    const BENEFICIARY: Address = Address::repeat_byte(0xbe);
+
+    /// The code of a contract which creates, with `value`, a contract whose
+    /// init code selfdestructs.
+    fn create_and_selfdestruct(value: u64) -> Bytecode {
+        let init_code = bytecode! {
+            .op_selfdestruct(BENEFICIARY.to_word())
+        }
+        .code();
+        let mut code = Bytecode::default();
+        for (index, byte) in init_code.iter().enumerate() {
+            code.op_mstore8(index, *byte as u64);
+        }
+        code.op_create(value, 0, init_code.len()).op_stop();
+        code
+    }
+
+    fn test_ok<const NTX: usize>(code: Bytecode) {
+        let ctx = TestContext::<2, NTX>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x0000000000000000000000000000000000000123"))
+                    .balance(eth(10));
+                accs[1].address(MOCK_ACCOUNTS[0]).balance(eth(1)).code(code);
+            },
+            |mut txs, accs| {
+                for tx in txs.iter_mut() {
+                    tx.from(accs[0].address).to(accs[1].address);
+                }
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn delete_account_gadget() {
+        test_ok::<1>(create_and_selfdestruct(0));
+        test_ok::<1>(create_and_selfdestruct(1));
+    }
+
+    #[test]
+    fn delete_account_gadget_before_next_tx() {
+        test_ok::<2>(create_and_selfdestruct(1));
+    }
+}
@@ -37,9 +37,12 @@ impl<F: Field> ExecutionGadget<F> for EndBlockGadget<F> {
         let max_rws = cb.query_copy_cell();
         let total_txs = cb.query_cell();
         let total_txs_is_max_txs = IsEqualGadget::construct(cb, total_txs.expr(), max_txs.expr());
-        // Note that rw_counter starts at 1
-        let is_empty_block =
-            IsZeroGadget::construct(cb, cb.curr.state.rw_counter.clone().expr() - 1.expr());
+        // The call_id is propagated from the last tx, and it's 0 if there are none,
+        // even when the block begins with the BeaconRoot step.
+        cb.step_first(|cb| {
+            cb.require_zero("call_id is 0 in empty block", cb.curr.state.call_id.expr());
+        });
+        let is_empty_block = IsZeroGadget::construct(cb, cb.curr.state.call_id.expr());
 
         let total_rws_before_padding = cb.curr.state.rw_counter.clone().expr() - 1.expr()
             + select::expr(
@@ -122,11 +125,11 @@ impl<F: Field> ExecutionGadget<F> for EndBlockGadget<F> {
         offset: usize,
         block: &Block<F>,
         _: &Transaction,
-        _: &Call,
+        call: &Call,
         step: &ExecStep,
     ) -> Result<(), Error> {
         self.is_empty_block
-            .assign(region, offset, F::from(u64::from(step.rwc) - 1))?;
+            .assign(region, offset, F::from(call.call_id as u64))?;
         let max_rws = F::from(block.circuits_params.max_rws as u64);
         let max_rws_assigned = self.max_rws.assign(region, offset, Value::known(max_rws))?;
 
@@ -5,11 +5,12 @@ use crate::{
         step::ExecutionState,
         util::{
             common_gadget::{TransferToGadget, UpdateBalanceGadget},
-            constraint_builder::EVMConstraintBuilder,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
             math_gadget::{
                 AddWordsGadget, ConstantDivisionGadget, IsZeroWordGadget, MinMaxGadget,
                 MulWordByU64Gadget,
             },
+            not,
             tx::EndTxHelperGadget,
             CachedRegion, Cell,
         },
@@ -17,13 +18,21 @@ use crate::{
     },
     table::{AccountFieldTag, BlockContextFieldTag, CallContextFieldTag, TxContextFieldTag},
     util::{
-        word::{Word, WordCell, WordExpr},
+        word::{Word, Word32Cell, WordCell, WordExpr},
         Expr,
     },
 };
 use eth_types::{evm_types::MAX_REFUND_QUOTIENT_OF_GAS_USED, Field};
 use halo2_proofs::{circuit::Value, plonk::Error};
 
+/// Gadget for the end of a tx, which settles its gas fee (EIP-1559).
+///
+/// BeginTx has checked that the caller affords `gas * max_fee_per_gas + value`,
+/// and taken `gas * gas_price` from the caller, at the effective gas price of
+/// the tx.  EndTx gives back the gas left and the effective refund at
+/// the same price, and pays `gas_used * (gas_price - base_fee)` to the
+/// coinbase.  The base fee of the gas used is burnt, as no balance receives
+/// it.
 #[derive(Clone, Debug)]
 pub(crate) struct EndTxGadget<F> {
     tx_id: Cell<F>,
@@ -34,6 +43,9 @@ pub(crate) struct EndTxGadget<F> {
     mul_gas_price_by_refund: MulWordByU64Gadget<F>,
     tx_caller_address: WordCell<F>,
     gas_fee_refund: UpdateBalanceGadget<F, 2, true>,
+    tx_is_deposit: Cell<F>,
+    base_fee: Word32Cell<F>,
+    base_fee_paid: Word32Cell<F>,
     sub_gas_price_by_base_fee: AddWordsGadget<F, 2, true>,
     mul_effective_tip_by_gas_used: MulWordByU64Gadget<F>,
     coinbase: WordCell<F>,
@@ -57,6 +69,7 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
         let tx_caller_address =
             cb.tx_context_as_word(tx_id.expr(), TxContextFieldTag::CallerAddress, None);
         let tx_gas_price = cb.tx_context_as_word32(tx_id.expr(), TxContextFieldTag::GasPrice, None);
+        let tx_is_deposit = cb.tx_context(tx_id.expr(), TxContextFieldTag::IsDeposit, None);
 
         // Calculate effective gas to refund
         let gas_used = tx_gas.expr() - cb.curr.state.gas_left.expr();
@@ -99,9 +112,21 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
         ] {
             cb.block_lookup(tag.expr(), None, value);
         }
+        // A deposit tx pays no base fee, and no tip as its gas price is 0.
+        let base_fee_paid = cb.query_word32();
+        cb.require_equal_word(
+            "base_fee_paid == base_fee if not a deposit tx",
+            base_fee_paid.to_word(),
+            base_fee
+                .to_word()
+                .mul_selector(not::expr(tx_is_deposit.expr())),
+        );
         let effective_tip = cb.query_word32();
-        let sub_gas_price_by_base_fee =
-            AddWordsGadget::construct(cb, [effective_tip.clone(), base_fee], tx_gas_price);
+        let sub_gas_price_by_base_fee = AddWordsGadget::construct(
+            cb,
+            [effective_tip.clone(), base_fee_paid.clone()],
+            tx_gas_price,
+        );
         let mul_effective_tip_by_gas_used =
             MulWordByU64Gadget::construct(cb, effective_tip, gas_used.clone());
         let coinbase_reward = TransferToGadget::construct(
@@ -131,6 +156,9 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
             mul_gas_price_by_refund,
             tx_caller_address,
             gas_fee_refund,
+            tx_is_deposit,
+            base_fee,
+            base_fee_paid,
             sub_gas_price_by_base_fee,
             mul_effective_tip_by_gas_used,
             coinbase,
@@ -187,12 +215,26 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
             vec![gas_fee_refund],
             caller_balance,
         )?;
-        let effective_tip = tx.gas_price - block.context.base_fee;
+        self.tx_is_deposit.assign(
+            region,
+            offset,
+            Value::known(F::from(tx.is_deposit() as u64)),
+        )?;
+        self.base_fee
+            .assign_u256(region, offset, block.context.base_fee)?;
+        let base_fee_paid = if tx.is_deposit() {
+            eth_types::Word::zero()
+        } else {
+            block.context.base_fee
+        };
+        self.base_fee_paid
+            .assign_u256(region, offset, base_fee_paid)?;
+        let effective_tip = tx.gas_price - base_fee_paid;
         let coinbase_reward = effective_tip * gas_used;
         self.sub_gas_price_by_base_fee.assign(
             region,
             offset,
-            [effective_tip, block.context.base_fee],
+            [effective_tip, base_fee_paid],
             tx.gas_price,
         )?;
         self.mul_effective_tip_by_gas_used.assign(
@@ -241,17 +283,23 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
 mod test {
 
     use crate::{table::CallContextFieldTag, test_util::CircuitTestBuilder};
-    use bus_mapping::{circuit_input_builder::FixedCParams, operation::Target};
-    use eth_types::{self, bytecode, Word};
+    use bus_mapping::{
+        circuit_input_builder::FixedCParams,
+        mock::BlockData,
+        operation::{AccountField, Target},
+    };
+    use eth_types::{self, bytecode, geth_types::GethData, Address, Word};
     use itertools::Itertools;
     use mock::{
         eth, gwei, test_ctx::helpers::account_0_code_account_1_no_code, TestContext, MOCK_ACCOUNTS,
+        MOCK_COINBASE,
     };
 
     fn test_ok<const NACC: usize, const NTX: usize>(ctx: TestContext<NACC, NTX>) {
         CircuitTestBuilder::new_from_test_ctx(ctx)
             .params(FixedCParams {
                 max_txs: 5,
+                max_signed_txs: 5,
                 ..Default::default()
             })
             .run();
@@ -422,6 +470,121 @@ mod test {
         );
     }
 
+    #[test]
+    fn end_tx_gas_refund_witness() {
+        // Clearing 2 slots accumulates a refund of 9_600, which is capped at 31_012 / 5 = 6_202
+        let zero_value: Word = 0x0.into();
+        let original_value: Word = 0x060504.into();
+        let (key_1, key_2): (Word, Word) = (0x030201.into(), 0x030202.into());
+        let code = bytecode! {
+            PUSH32(zero_value)
+            PUSH32(key_1)
+            SSTORE
+            PUSH32(zero_value)
+            PUSH32(key_2)
+            SSTORE
+            STOP
+        };
+        let storage = vec![(key_1, original_value), (key_2, original_value)].into_iter();
+
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(Word::from(10u64.pow(19)))
+                    .code(code)
+                    .storage(storage);
+                accs[1]
+                    .address(MOCK_ACCOUNTS[1])
+                    .balance(Word::from(10u64.pow(19)));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .to(accs[0].address)
+                    .from(accs[1].address)
+                    .gas(Word::from(50_000));
+            },
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let tx = &builder.block.txs()[0];
+        assert_eq!(tx.last_step().gas_refund, 9_600);
+        assert_eq!(tx.gas_used(), 31_012);
+        assert_eq!(tx.gas_refund(), 6_202);
+    }
+
+    #[test]
+    fn end_tx_fee_payout() {
+        // A transfer of 1 ether with a gas price of 3 gwei, which tips 2 gwei per gas to the
+        // coinbase over the base fee of 1 gwei.
+        let (gas, gas_used, gas_price, base_fee) = (30_000u64, 21_000u64, gwei(3), gwei(1));
+        let ctx = || {
+            TestContext::<3, 1>::new(
+                None,
+                |accs| {
+                    accs[0].address(MOCK_ACCOUNTS[0]).balance(eth(1));
+                    accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+                    accs[2].address(*MOCK_COINBASE).balance(eth(1));
+                },
+                |mut txs, accs| {
+                    txs[0]
+                        .to(accs[0].address)
+                        .from(accs[1].address)
+                        .gas(Word::from(gas))
+                        .gas_price(gas_price)
+                        .value(eth(1));
+                },
+                |mut block, _tx| {
+                    block
+                        .author(*MOCK_COINBASE)
+                        .base_fee_per_gas(Some(base_fee));
+                    block
+                },
+            )
+            .unwrap()
+        };
+
+        let block: GethData = ctx().into();
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let balances = |address: Address| {
+            builder
+                .block
+                .container
+                .account
+                .iter()
+                .map(|op| op.op())
+                .filter(|op| op.address == address && op.field == AccountField::Balance)
+                .map(|op| (op.value_prev, op.value))
+                .collect_vec()
+        };
+
+        // The caller pays the gas limit up front, and gets the gas left back.
+        let caller = balances(MOCK_ACCOUNTS[1]);
+        let (refund_prev, refund) = caller.last().copied().unwrap();
+        assert_eq!(refund - refund_prev, gas_price * (gas - gas_used));
+        let caller_paid = caller.first().unwrap().0 - refund;
+        assert_eq!(caller_paid, eth(1) + gas_price * gas_used);
+
+        // The coinbase gets the tip, and the base fee is burnt.
+        let coinbase = balances(*MOCK_COINBASE);
+        let coinbase_reward = coinbase.last().unwrap().1 - coinbase.first().unwrap().0;
+        assert_eq!(coinbase_reward, (gas_price - base_fee) * gas_used);
+        assert_eq!(caller_paid - eth(1) - coinbase_reward, base_fee * gas_used);
+
+        test_ok(ctx());
+    }
+
     #[test]
     fn end_tx_consistent_tx_id_write() {
         // check there is no consecutive txid write with same txid in rw_table
@@ -450,6 +613,7 @@ mod test {
         )
         .params(FixedCParams {
             max_txs: 5,
+            max_signed_txs: 5,
             ..Default::default()
         })
         .build_block()
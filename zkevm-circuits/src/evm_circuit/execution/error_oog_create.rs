@@ -18,9 +18,7 @@ use crate::{
     witness::{Block, Call, ExecStep, Transaction},
 };
 use eth_types::{
-    evm_types::{
-        GasCost, OpcodeId, CREATE2_GAS_PER_CODE_WORD, CREATE_GAS_PER_CODE_WORD, MAX_INIT_CODE_SIZE,
-    },
+    evm_types::{GasCost, OpcodeId},
     Field, U256,
 };
 use halo2_proofs::{circuit::Value, plonk::Error};
@@ -68,8 +66,11 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCreateGadget<F> {
         cb.stack_pop(memory_address.length_word());
         cb.condition(is_create2.expr().0, |cb| cb.stack_pop(salt.to_word()));
 
-        let init_code_size_overflow =
-            LtGadget::construct(cb, MAX_INIT_CODE_SIZE.expr(), memory_address.length());
+        let init_code_size_overflow = LtGadget::construct(
+            cb,
+            cb.hardfork.max_init_code_size().expr(),
+            memory_address.length(),
+        );
 
         let minimum_word_size = MemoryWordSizeGadget::construct(cb, memory_address.length());
         let memory_expansion = MemoryExpansionGadget::construct(cb, [memory_address.address()]);
@@ -77,8 +78,8 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCreateGadget<F> {
         let code_store_gas_cost = minimum_word_size.expr()
             * select::expr(
                 is_create2.expr().0,
-                CREATE2_GAS_PER_CODE_WORD.expr(),
-                CREATE_GAS_PER_CODE_WORD.expr(),
+                cb.hardfork.create2_gas_per_code_word().expr(),
+                cb.hardfork.create_gas_per_code_word().expr(),
             );
         let gas_cost = GasCost::CREATE.expr() + memory_expansion.gas_cost() + code_store_gas_cost;
         let insufficient_gas = LtGadget::construct(cb, cb.curr.state.gas_left.expr(), gas_cost);
@@ -130,6 +131,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCreateGadget<F> {
 
         let opcode = step.opcode().unwrap();
         let is_create2 = opcode == OpcodeId::CREATE2;
+        let hardfork = block.circuits_params.hardfork;
         self.opcode
             .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
         self.is_create2.assign(
@@ -168,15 +170,15 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCreateGadget<F> {
         self.init_code_size_overflow.assign(
             region,
             offset,
-            F::from(MAX_INIT_CODE_SIZE),
+            F::from(hardfork.max_init_code_size()),
             F::from(init_code_size),
         )?;
 
         let code_store_gas_cost = minimum_word_size
             * if is_create2 {
-                CREATE2_GAS_PER_CODE_WORD
+                hardfork.create2_gas_per_code_word()
             } else {
-                CREATE_GAS_PER_CODE_WORD
+                hardfork.create_gas_per_code_word()
             };
         self.insufficient_gas.assign(
             region,
@@ -202,7 +204,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCreateGadget<F> {
 mod tests {
     use super::*;
     use crate::test_util::CircuitTestBuilder;
-    use eth_types::{bytecode, word, Bytecode, ToWord};
+    use eth_types::{bytecode, evm_types::Hardfork, word, Bytecode, ToWord};
     use mock::{
         eth,
         test_ctx::{helpers::account_0_code_account_1_no_code, LoggerConfig},
@@ -300,15 +302,15 @@ mod tests {
     #[test]
     fn test_oog_create_max_init_code_size() {
         for is_create2 in [true, false] {
-            // For Shanghai, MAX_INIT_CODE_SIZE is 49152, it is constrained by
-            // `init_code_size_overflow`.
-            // For not Shanghai, MAX_INIT_CODE_SIZE is 0x1FFFFFFFE0, it is
+            // For Shanghai, the max init code size is 49152, it is constrained
+            // by `init_code_size_overflow`.
+            // For not Shanghai, the max init code size is 0x1FFFFFFFE0, it is
             // constrained by `memory_address.overflow()`
             // (and `init_code_size_overflow`).
             let case = TestCase::new(
                 is_create2,
                 U256::zero(),
-                (MAX_INIT_CODE_SIZE + 1).into(),
+                (Hardfork::default().max_init_code_size() + 1).into(),
                 MOCK_BLOCK_GAS_LIMIT,
             );
 
@@ -29,7 +29,8 @@ use halo2_proofs::{circuit::Value, plonk::Error};
 
 /// Gadget to implement the corresponding out of gas errors for
 /// [`OpcodeId::CALLDATACOPY`], [`OpcodeId::CODECOPY`],
-/// [`OpcodeId::EXTCODECOPY`] and [`OpcodeId::RETURNDATACOPY`].
+/// [`OpcodeId::EXTCODECOPY`], [`OpcodeId::RETURNDATACOPY`] and
+/// [`OpcodeId::MCOPY`].
 #[derive(Clone, Debug)]
 pub(crate) struct ErrorOOGMemoryCopyGadget<F> {
     opcode: Cell<F>,
@@ -42,10 +43,14 @@ pub(crate) struct ErrorOOGMemoryCopyGadget<F> {
     src_offset: WordCell<F>,
     /// Destination offset and size to copy
     dst_memory_addr: MemoryExpandedAddressGadget<F>,
-    memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
+    /// Source offset and size to copy for `MCOPY`, which also expands the
+    /// memory of the current call
+    src_memory_addr: MemoryExpandedAddressGadget<F>,
+    memory_expansion: MemoryExpansionGadget<F, 2, N_BYTES_MEMORY_WORD_SIZE>,
     memory_copier_gas: MemoryCopierGasGadget<F, { GasCost::COPY }>,
     insufficient_gas: LtGadget<F, N_BYTES_GAS>,
     is_extcodecopy: IsZeroGadget<F>,
+    is_mcopy: IsZeroGadget<F>,
     common_error_gadget: CommonErrorGadget<F>,
 }
 
@@ -57,13 +62,14 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
         let opcode = cb.query_cell();
         cb.require_in_set(
-            "ErrorOutOfGasMemoryCopy opcode must be CALLDATACOPY, CODECOPY, EXTCODECOPY or RETURNDATACOPY",
+            "ErrorOutOfGasMemoryCopy opcode must be CALLDATACOPY, CODECOPY, EXTCODECOPY, RETURNDATACOPY or MCOPY",
             opcode.expr(),
             vec![
                 OpcodeId::CALLDATACOPY.expr(),
                 OpcodeId::CODECOPY.expr(),
                 OpcodeId::EXTCODECOPY.expr(),
                 OpcodeId::RETURNDATACOPY.expr(),
+                OpcodeId::MCOPY.expr(),
             ],
         );
 
@@ -74,6 +80,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
 
         let is_extcodecopy =
             IsZeroGadget::construct(cb, opcode.expr() - OpcodeId::EXTCODECOPY.expr());
+        let is_mcopy = IsZeroGadget::construct(cb, opcode.expr() - OpcodeId::MCOPY.expr());
 
         cb.condition(is_extcodecopy.expr(), |cb| {
             cb.call_context_lookup_read(
@@ -94,7 +101,28 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
         cb.stack_pop(src_offset.to_word());
         cb.stack_pop(dst_memory_addr.length_word());
 
-        let memory_expansion = MemoryExpansionGadget::construct(cb, [dst_memory_addr.address()]);
+        // MCOPY reads the source range from memory with the same size.
+        let src_memory_addr = MemoryExpandedAddressGadget::construct_self(cb);
+        cb.condition(is_mcopy.expr(), |cb| {
+            cb.require_equal_word(
+                "MCOPY source offset",
+                src_memory_addr.offset_word(),
+                src_offset.to_word(),
+            );
+            cb.require_equal_word(
+                "MCOPY source and destination have the same size",
+                src_memory_addr.length_word(),
+                dst_memory_addr.length_word(),
+            );
+        });
+
+        let memory_expansion = MemoryExpansionGadget::construct(
+            cb,
+            [
+                dst_memory_addr.address(),
+                is_mcopy.expr() * src_memory_addr.address(),
+            ],
+        );
         let memory_copier_gas = MemoryCopierGasGadget::construct(
             cb,
             dst_memory_addr.length(),
@@ -110,7 +138,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
                 GasCost::WARM_ACCESS.expr(),
                 GasCost::COLD_ACCOUNT_ACCESS.expr(),
             ),
-            // Constant gas cost is same for CALLDATACOPY, CODECOPY and RETURNDATACOPY.
+            // Constant gas cost is same for CALLDATACOPY, CODECOPY, RETURNDATACOPY and MCOPY.
             OpcodeId::CALLDATACOPY.constant_gas_cost().expr(),
         );
 
@@ -122,7 +150,11 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
 
         cb.require_equal(
             "Memory address is overflow or gas left is less than cost",
-            or::expr([dst_memory_addr.overflow(), insufficient_gas.expr()]),
+            or::expr([
+                dst_memory_addr.overflow(),
+                is_mcopy.expr() * src_memory_addr.overflow(),
+                insufficient_gas.expr(),
+            ]),
             1.expr(),
         );
 
@@ -136,10 +168,12 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
             external_address,
             src_offset,
             dst_memory_addr,
+            src_memory_addr,
             memory_expansion,
             memory_copier_gas,
             insufficient_gas,
             is_extcodecopy,
+            is_mcopy,
             common_error_gadget,
         }
     }
@@ -155,6 +189,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
     ) -> Result<(), Error> {
         let opcode = step.opcode().unwrap();
         let is_extcodecopy = opcode == OpcodeId::EXTCODECOPY;
+        let is_mcopy = opcode == OpcodeId::MCOPY;
 
         log::debug!(
             "ErrorOutOfGasMemoryCopy: opcode = {}, gas_left = {}, gas_cost = {}",
@@ -188,9 +223,20 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
         let memory_addr = self
             .dst_memory_addr
             .assign(region, offset, dst_offset, copy_size)?;
-        let (_, memory_expansion_cost) =
-            self.memory_expansion
-                .assign(region, offset, step.memory_word_size(), [memory_addr])?;
+        let src_memory_addr = if is_mcopy {
+            self.src_memory_addr
+                .assign(region, offset, src_offset, copy_size)?
+        } else {
+            self.src_memory_addr
+                .assign(region, offset, U256::zero(), U256::zero())?;
+            0
+        };
+        let (_, memory_expansion_cost) = self.memory_expansion.assign(
+            region,
+            offset,
+            step.memory_word_size(),
+            [memory_addr, src_memory_addr],
+        )?;
         let memory_copier_gas = self.memory_copier_gas.assign(
             region,
             offset,
@@ -217,6 +263,11 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
             offset,
             F::from(opcode.as_u64()) - F::from(OpcodeId::EXTCODECOPY.as_u64()),
         )?;
+        self.is_mcopy.assign(
+            region,
+            offset,
+            F::from(opcode.as_u64()) - F::from(OpcodeId::MCOPY.as_u64()),
+        )?;
         self.common_error_gadget.assign(
             region,
             offset,
@@ -241,12 +292,14 @@ mod tests {
     };
     use bus_mapping::circuit_input_builder::FixedCParams;
     use eth_types::{
-        bytecode, evm_types::gas_utils::memory_copier_gas_cost, Bytecode, ToWord, U256,
+        bytecode,
+        evm_types::{gas_utils::memory_copier_gas_cost, Hardfork},
+        Bytecode, ToWord, U256,
     };
     use itertools::Itertools;
     use mock::{
-        eth, test_ctx::helpers::account_0_code_account_1_no_code, TestContext, MOCK_ACCOUNTS,
-        MOCK_BLOCK_GAS_LIMIT,
+        eth, interpreter, test_ctx::helpers::account_0_code_account_1_no_code, TestContext,
+        MOCK_ACCOUNTS, MOCK_BLOCK_GAS_LIMIT,
     };
 
     const TESTING_COMMON_OPCODES: &[OpcodeId] = &[
@@ -286,6 +339,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_oog_memory_copy_for_mcopy() {
+        for (src_offset, (dst_offset, copy_size)) in [0x20, 0x3000]
+            .iter()
+            .cartesian_product(TESTING_DST_OFFSET_COPY_SIZE_PAIRS.iter())
+        {
+            let testing_data =
+                TestingData::new_for_mcopy(*dst_offset, *src_offset, *copy_size, None);
+
+            test_root_mcopy(&testing_data);
+        }
+
+        // The source range exceeds the max expanded memory address.
+        let testing_data =
+            TestingData::new_for_mcopy(0x20, u64::MAX, 0x20, Some(MOCK_BLOCK_GAS_LIMIT));
+        test_root_mcopy(&testing_data);
+    }
+
     #[test]
     fn test_oog_memory_copy_max_expanded_address() {
         // 0xffffffff1 + 0xffffffff0 = 0x1fffffffe1
@@ -328,6 +399,34 @@ mod tests {
             Self { bytecode, gas_cost }
         }
 
+        pub fn new_for_mcopy(
+            dst_offset: u64,
+            src_offset: u64,
+            copy_size: u64,
+            gas_cost: Option<u64>,
+        ) -> Self {
+            let bytecode = bytecode! {
+                PUSH32(copy_size)
+                PUSH32(src_offset)
+                PUSH32(dst_offset)
+                MCOPY
+            };
+
+            let gas_cost = gas_cost.unwrap_or_else(|| {
+                let memory_word_size = if copy_size == 0 {
+                    0
+                } else {
+                    (dst_offset.max(src_offset) + copy_size + 31) / 32
+                };
+
+                OpcodeId::PUSH32.constant_gas_cost() * 3
+                    + OpcodeId::MCOPY.constant_gas_cost()
+                    + memory_copier_gas_cost(0, memory_word_size, copy_size)
+            });
+
+            Self { bytecode, gas_cost }
+        }
+
         pub fn new_for_extcodecopy(
             is_warm: bool,
             dst_offset: u64,
@@ -375,16 +474,20 @@ mod tests {
         }
     }
 
-    fn test_root(testing_data: &TestingData) {
+    fn root_tx_gas(testing_data: &TestingData) -> u64 {
         let gas_cost = GasCost::TX
             // Decrease expected gas cost (by 1) to trigger out of gas error.
             .checked_add(testing_data.gas_cost - 1)
             .unwrap_or(MOCK_BLOCK_GAS_LIMIT);
-        let gas_cost = if gas_cost > MOCK_BLOCK_GAS_LIMIT {
+        if gas_cost > MOCK_BLOCK_GAS_LIMIT {
             MOCK_BLOCK_GAS_LIMIT
         } else {
             gas_cost
-        };
+        }
+    }
+
+    fn test_root(testing_data: &TestingData) {
+        let gas_cost = root_tx_gas(testing_data);
 
         let ctx = TestContext::<2, 1>::new(
             None,
@@ -407,6 +510,34 @@ mod tests {
             .run();
     }
 
+    // The geth-utils tracer doesn't support EIP-5656 yet, and the mock
+    // interpreter only traces root calls.
+    fn test_root_mcopy(testing_data: &TestingData) {
+        let gas_cost = root_tx_gas(testing_data);
+
+        let ctx = TestContext::<2, 1>::new_with_tracer(
+            None,
+            account_0_code_account_1_no_code(testing_data.bytecode.clone()),
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .gas(gas_cost.into());
+            },
+            |block, _tx| block.number(0xcafe_u64),
+            interpreter::trace,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(FixedCParams {
+                hardfork: Hardfork::Cancun,
+                max_copy_rows: 1750,
+                ..Default::default()
+            })
+            .run();
+    }
+
     fn test_internal(testing_data: &TestingData) {
         let (addr_a, addr_b) = (MOCK_ACCOUNTS[0], MOCK_ACCOUNTS[1]);
 
@@ -1,29 +1,32 @@
 use crate::{
     evm_circuit::{
         execution::ExecutionGadget,
-        param::{N_BYTES_GAS, N_BYTES_MEMORY_WORD_SIZE, N_BYTES_WORD},
+        param::{N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS, N_BYTES_MEMORY_WORD_SIZE, N_BYTES_WORD},
         step::ExecutionState,
         util::{
             common_gadget::RestoreContextGadget,
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
-            math_gadget::{BinaryNumberGadget, ConstantDivisionGadget, LtGadget},
+            math_gadget::{BinaryNumberGadget, ConstantDivisionGadget, IsEqualGadget, LtGadget},
             CachedRegion, Cell,
         },
     },
     table::CallContextFieldTag,
     witness::{Block, Call, ExecStep, Transaction},
 };
-use bus_mapping::precompile::PrecompileCalls;
-use eth_types::{evm_types::GasCost, Field, ToScalar};
+use bus_mapping::precompile::{PrecompileCalls, N_BYTES_EC_PAIR};
+use eth_types::{Field, ToScalar};
 use gadgets::util::{sum, Expr};
 use halo2_proofs::{circuit::Value, plonk::Error};
 
 #[derive(Clone, Debug)]
 pub(crate) struct ErrorOOGPrecompileGadget<F> {
     precompile_addr: Cell<F>,
+    is_p256_verify: IsEqualGadget<F>,
     addr_bits: BinaryNumberGadget<F, 4>,
     call_data_length: Cell<F>,
-    // n_pairs: ConstantDivisionGadget<F, N_BYTES_MEMORY_WORD_SIZE>,
+    base_gas_cost: Cell<F>,
+    per_word_gas_cost: Cell<F>,
+    n_pairs: ConstantDivisionGadget<F, N_BYTES_MEMORY_ADDRESS>,
     n_words: ConstantDivisionGadget<F, N_BYTES_MEMORY_WORD_SIZE>,
     required_gas: Cell<F>,
     insufficient_gas: LtGadget<F, N_BYTES_GAS>,
@@ -40,77 +43,83 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGPrecompileGadget<F> {
 
         // read callee_address
         let precompile_addr = cb.call_context(None, CallContextFieldTag::CalleeAddress);
-        let addr_bits = BinaryNumberGadget::construct(cb, precompile_addr.expr());
+        // P256VERIFY is outside of the range of the Ethereum precompiles,
+        // whose address is decomposed in bits.
+        let is_p256_verify = IsEqualGadget::construct(
+            cb,
+            precompile_addr.expr(),
+            PrecompileCalls::P256Verify.address().expr(),
+        );
+        let addr_bits = BinaryNumberGadget::construct(
+            cb,
+            precompile_addr.expr()
+                - is_p256_verify.expr() * PrecompileCalls::P256Verify.address().expr(),
+        );
 
         // read call data length
         let call_data_length = cb.call_context(None, CallContextFieldTag::CallDataLength);
-        // let n_pairs = cb.condition(
-        //     addr_bits.value_equals(PrecompileCalls::Bn128Pairing),
-        //     |cb| {
-        //         ConstantDivisionGadget::construct(
-        //             cb,
-        //             call_data_length.expr(),
-        //             N_BYTES_EC_PAIR as u64,
-        //         )
-        //     },
-        // );
-        let n_words = cb.condition(addr_bits.value_equals(PrecompileCalls::Identity), |cb| {
-            ConstantDivisionGadget::construct(
-                cb,
-                call_data_length.expr() + (N_BYTES_WORD - 1).expr(),
-                N_BYTES_WORD as u64,
-            )
-        });
-
-        // calculate required gas for precompile
-        let precompiles_required_gas = vec![
-            // (
-            //     addr_bits.value_equals(PrecompileCalls::ECRecover),
-            //     GasCost::PRECOMPILE_ECRECOVER_BASE.expr(),
-            // ),
-            // addr_bits.value_equals(PrecompileCalls::Sha256),
-            // addr_bits.value_equals(PrecompileCalls::Ripemd160),
-            // addr_bits.value_equals(PrecompileCalls::Blake2F),
-            (
-                addr_bits.value_equals(PrecompileCalls::Identity),
-                GasCost::PRECOMPILE_IDENTITY_BASE.expr()
-                    + n_words.quotient() * GasCost::PRECOMPILE_IDENTITY_PER_WORD.expr(),
-            ),
-            // modexp is handled in ModExpGadget
-            // (
-            //     addr_bits.value_equals(PrecompileCalls::Bn128Add),
-            //     GasCost::PRECOMPILE_BN256ADD.expr(),
-            // ),
-            // (
-            //     addr_bits.value_equals(PrecompileCalls::Bn128Mul),
-            //     GasCost::PRECOMPILE_BN256MUL.expr(),
-            // ),
-            // (
-            //     addr_bits.value_equals(PrecompileCalls::Bn128Pairing),
-            //     GasCost::PRECOMPILE_BN256PAIRING.expr()
-            //         + n_pairs.quotient() * GasCost::PRECOMPILE_BN256PAIRING_PER_PAIR.expr(),
-            // ),
-        ];
+        let n_pairs = cb.condition(
+            addr_bits.value_equals(PrecompileCalls::Bn128Pairing),
+            |cb| {
+                ConstantDivisionGadget::construct(
+                    cb,
+                    call_data_length.expr(),
+                    N_BYTES_EC_PAIR as u64,
+                )
+            },
+        );
+        let n_words = cb.condition(
+            addr_bits.value_equals(PrecompileCalls::Identity)
+                + addr_bits.value_equals(PrecompileCalls::Sha256)
+                + addr_bits.value_equals(PrecompileCalls::Ripemd160),
+            |cb| {
+                ConstantDivisionGadget::construct(
+                    cb,
+                    call_data_length.expr() + (N_BYTES_WORD - 1).expr(),
+                    N_BYTES_WORD as u64,
+                )
+            },
+        );
 
+        // blake2f and modexp are handled in their gadgets
         cb.require_equal(
             "precompile_addr must belong to precompile calls' set",
             sum::expr(
-                precompiles_required_gas
-                    .iter()
-                    .map(|(cond, _)| cond)
-                    .cloned(),
+                [
+                    PrecompileCalls::ECRecover,
+                    PrecompileCalls::Sha256,
+                    PrecompileCalls::Ripemd160,
+                    PrecompileCalls::Identity,
+                    PrecompileCalls::Bn128Add,
+                    PrecompileCalls::Bn128Mul,
+                    PrecompileCalls::Bn128Pairing,
+                    PrecompileCalls::PointEvaluation,
+                ]
+                .map(|precompile| addr_bits.value_equals(precompile))
+                .into_iter()
+                .chain([is_p256_verify.expr()]),
             ),
             1.expr(),
         );
 
+        // calculate required gas for precompile
+        let base_gas_cost = cb.query_cell();
+        let per_word_gas_cost = cb.query_cell();
+        cb.precompile_gas_cost_lookup(
+            precompile_addr.expr(),
+            base_gas_cost.expr(),
+            per_word_gas_cost.expr(),
+        );
+        let n_words_or_pairs = addr_bits.value_equals(PrecompileCalls::Bn128Pairing)
+            * n_pairs.quotient()
+            + (addr_bits.value_equals(PrecompileCalls::Identity)
+                + addr_bits.value_equals(PrecompileCalls::Sha256)
+                + addr_bits.value_equals(PrecompileCalls::Ripemd160))
+                * n_words.quotient();
         cb.require_equal(
-            "require_gas == sum(is_precompile[addr] * required_gas[addr])",
+            "require_gas == base_gas_cost + n_words_or_pairs * per_word_gas_cost",
             required_gas.expr(),
-            precompiles_required_gas
-                .iter()
-                .fold(0.expr(), |acc, (condition, required_gas)| {
-                    acc + condition.expr() * required_gas.expr()
-                }),
+            base_gas_cost.expr() + n_words_or_pairs * per_word_gas_cost.expr(),
         );
 
         // gas_left < required_gas
@@ -131,12 +140,15 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGPrecompileGadget<F> {
 
         Self {
             precompile_addr,
+            is_p256_verify,
             required_gas,
             insufficient_gas,
-            // n_pairs,
+            n_pairs,
             n_words,
             addr_bits,
             call_data_length,
+            base_gas_cost,
+            per_word_gas_cost,
             restore_context,
         }
     }
@@ -157,8 +169,19 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGPrecompileGadget<F> {
             offset,
             Value::known(precompile_addr.to_scalar().unwrap()),
         )?;
-        self.addr_bits
-            .assign(region, offset, precompile_addr.to_fixed_bytes()[19])?;
+        let precompile_call: PrecompileCalls = precompile_addr.into();
+        self.is_p256_verify.assign(
+            region,
+            offset,
+            precompile_addr.to_scalar().unwrap(),
+            F::from(PrecompileCalls::P256Verify.address()),
+        )?;
+        let addr_bits = if precompile_call == PrecompileCalls::P256Verify {
+            0
+        } else {
+            precompile_addr.to_fixed_bytes()[19]
+        };
+        self.addr_bits.assign(region, offset, addr_bits)?;
 
         // call_data_length
         self.call_data_length.assign(
@@ -168,9 +191,9 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGPrecompileGadget<F> {
         )?;
 
         // n_pairs
-        // let n_pairs = call.call_data_length / 192;
-        // self.n_pairs
-        //     .assign(region, offset, call.call_data_length as u128)?;
+        let n_pairs = call.call_data_length / N_BYTES_EC_PAIR as u64;
+        self.n_pairs
+            .assign(region, offset, call.call_data_length as u128)?;
 
         // n_words
         self.n_words.assign(
@@ -180,19 +203,23 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGPrecompileGadget<F> {
         )?;
 
         // required_gas
-        let precompile_call: PrecompileCalls = precompile_addr.to_fixed_bytes()[19].into();
+        let gas_cost = precompile_call
+            .gas_cost(block.circuits_params.hardfork)
+            .ok_or(Error::Synthesis)?;
+        self.base_gas_cost
+            .assign(region, offset, Value::known(F::from(gas_cost.base)))?;
+        self.per_word_gas_cost
+            .assign(region, offset, Value::known(F::from(gas_cost.per_word)))?;
         let required_gas = match precompile_call {
-            // PrecompileCalls::Bn128Pairing => {
-            //     precompile_call.base_gas_cost()
-            //         + n_pairs * GasCost::PRECOMPILE_BN256PAIRING_PER_PAIR
-            // }
-            PrecompileCalls::Identity => {
-                let n_words = (call.call_data_length + 31) / 32;
-                precompile_call.base_gas_cost() + n_words * GasCost::PRECOMPILE_IDENTITY_PER_WORD
+            PrecompileCalls::Bn128Pairing => gas_cost.cost(n_pairs),
+            PrecompileCalls::Identity | PrecompileCalls::Sha256 | PrecompileCalls::Ripemd160 => {
+                gas_cost.cost((call.call_data_length + 31) / 32)
             }
-            // PrecompileCalls::Bn128Add | PrecompileCalls::Bn128Mul | PrecompileCalls::ECRecover =>
-            // {     precompile_call.base_gas_cost()
-            // }
+            PrecompileCalls::ECRecover
+            | PrecompileCalls::Bn128Add
+            | PrecompileCalls::Bn128Mul
+            | PrecompileCalls::PointEvaluation
+            | PrecompileCalls::P256Verify => gas_cost.base,
             _ => unreachable!(),
         };
 
@@ -253,6 +280,78 @@ mod test {
                         - 1).to_word(),
                     ..Default::default()
                 },
+                PrecompileCallArgs {
+                    name: "ecrecover",
+                    setup_code: bytecode! {},
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x80.into(),
+                    ret_offset: 0x80.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::ECRecover.address().to_word(),
+                    gas: (PrecompileCalls::ECRecover.base_gas_cost() - 1).to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "sha256",
+                    setup_code: bytecode! {},
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x21.into(),
+                    ret_offset: 0x40.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::Sha256.address().to_word(),
+                    gas: (PrecompileCalls::Sha256.base_gas_cost()
+                        + 2 * GasCost::PRECOMPILE_SHA256_PER_WORD
+                        - 1).to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ripemd160",
+                    setup_code: bytecode! {},
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x21.into(),
+                    ret_offset: 0x40.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::Ripemd160.address().to_word(),
+                    gas: (PrecompileCalls::Ripemd160.base_gas_cost()
+                        + 2 * GasCost::PRECOMPILE_RIPEMD160_PER_WORD
+                        - 1).to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecAdd",
+                    setup_code: bytecode! {},
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x80.into(),
+                    ret_offset: 0x80.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Bn128Add.address().to_word(),
+                    gas: (PrecompileCalls::Bn128Add.base_gas_cost() - 1).to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecMul",
+                    setup_code: bytecode! {},
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x60.into(),
+                    ret_offset: 0x60.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Bn128Mul.address().to_word(),
+                    gas: (PrecompileCalls::Bn128Mul.base_gas_cost() - 1).to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecPairing",
+                    setup_code: bytecode! {},
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x180.into(),
+                    ret_offset: 0x180.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::Bn128Pairing.address().to_word(),
+                    gas: (PrecompileCalls::Bn128Pairing.base_gas_cost()
+                        + 2 * GasCost::PRECOMPILE_BN256PAIRING_PER_PAIR
+                        - 1).to_word(),
+                    ..Default::default()
+                },
             ]
         };
     }
@@ -0,0 +1,301 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::N_BYTES_GAS,
+        step::ExecutionState,
+        util::{
+            common_gadget::CommonErrorGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::{IsZeroWordGadget, LtGadget},
+            not, AccountAddress, CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::{AccountFieldTag, CallContextFieldTag},
+    util::{
+        word::{Word, WordCell, WordExpr},
+        Expr,
+    },
+};
+use eth_types::{
+    evm_types::{GasCost, OpcodeId},
+    Field, ToAddress, ToWord,
+};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget to implement the corresponding out of gas error for
+/// [`OpcodeId::SELFDESTRUCT`].
+#[derive(Clone, Debug)]
+pub(crate) struct ErrorOOGSelfdestructGadget<F> {
+    opcode: Cell<F>,
+    beneficiary: AccountAddress<F>,
+    tx_id: Cell<F>,
+    is_warm: Cell<F>,
+    beneficiary_code_hash: WordCell<F>,
+    beneficiary_not_exists: IsZeroWordGadget<F, WordCell<F>>,
+    address: WordCell<F>,
+    balance: WordCell<F>,
+    balance_is_zero: IsZeroWordGadget<F, WordCell<F>>,
+    insufficient_gas_cost: LtGadget<F, N_BYTES_GAS>,
+    common_error_gadget: CommonErrorGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for ErrorOOGSelfdestructGadget<F> {
+    const NAME: &'static str = "ErrorOutOfGasSELFDESTRUCT";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::ErrorOutOfGasSELFDESTRUCT;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.require_equal(
+            "ErrorOutOfGasSELFDESTRUCT opcode must be SELFDESTRUCT",
+            opcode.expr(),
+            OpcodeId::SELFDESTRUCT.expr(),
+        );
+
+        let beneficiary = cb.query_account_address();
+        cb.stack_pop(beneficiary.to_word());
+
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        let is_warm = cb.query_bool();
+        // read is_warm
+        cb.account_access_list_read(tx_id.expr(), beneficiary.to_word(), is_warm.expr());
+
+        // For non-existing accounts the code_hash must be 0 in the rw_table.
+        let beneficiary_code_hash = cb.query_word_unchecked();
+        cb.account_read(
+            beneficiary.to_word(),
+            AccountFieldTag::CodeHash,
+            beneficiary_code_hash.to_word(),
+        );
+        let beneficiary_not_exists = IsZeroWordGadget::construct(cb, &beneficiary_code_hash);
+
+        let address = cb.call_context_read_as_word(None, CallContextFieldTag::CalleeAddress);
+        let balance = cb.query_word_unchecked();
+        cb.account_read(
+            address.to_word(),
+            AccountFieldTag::Balance,
+            balance.to_word(),
+        );
+        let balance_is_zero = IsZeroWordGadget::construct(cb, &balance);
+
+        let gas_cost = GasCost::SELFDESTRUCT.expr()
+            + not::expr(is_warm.expr()) * GasCost::COLD_ACCOUNT_ACCESS.expr()
+            + beneficiary_not_exists.expr()
+                * not::expr(balance_is_zero.expr())
+                * GasCost::NEW_ACCOUNT.expr();
+
+        let insufficient_gas_cost =
+            LtGadget::construct(cb, cb.curr.state.gas_left.expr(), gas_cost);
+
+        cb.require_equal(
+            "Gas left is less than gas cost",
+            insufficient_gas_cost.expr(),
+            1.expr(),
+        );
+
+        let common_error_gadget =
+            CommonErrorGadget::construct(cb, opcode.expr(), cb.rw_counter_offset());
+        Self {
+            opcode,
+            beneficiary,
+            tx_id,
+            is_warm,
+            beneficiary_code_hash,
+            beneficiary_not_exists,
+            address,
+            balance,
+            balance_is_zero,
+            insufficient_gas_cost,
+            common_error_gadget,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode().unwrap();
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+
+        let beneficiary = block.get_rws(step, 0).stack_value();
+        self.beneficiary
+            .assign_h160(region, offset, beneficiary.to_address())?;
+
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id)))?;
+
+        let (_, is_warm) = block.get_rws(step, 2).tx_access_list_value_pair();
+        self.is_warm
+            .assign(region, offset, Value::known(F::from(is_warm as u64)))?;
+
+        let beneficiary_code_hash = block.get_rws(step, 3).account_codehash_pair().0;
+        self.beneficiary_code_hash
+            .assign_u256(region, offset, beneficiary_code_hash)?;
+        self.beneficiary_not_exists.assign_value(
+            region,
+            offset,
+            Value::known(Word::from(beneficiary_code_hash)),
+        )?;
+
+        self.address
+            .assign_u256(region, offset, call.address.to_word())?;
+        let balance = block.get_rws(step, 5).account_balance_pair().0;
+        self.balance.assign_u256(region, offset, balance)?;
+        self.balance_is_zero
+            .assign_value(region, offset, Value::known(Word::from(balance)))?;
+
+        let gas_cost = GasCost::SELFDESTRUCT
+            + if is_warm {
+                0
+            } else {
+                GasCost::COLD_ACCOUNT_ACCESS
+            }
+            + if beneficiary_code_hash.is_zero() && !balance.is_zero() {
+                GasCost::NEW_ACCOUNT
+            } else {
+                0
+            };
+        self.insufficient_gas_cost.assign_value(
+            region,
+            offset,
+            Value::known(F::from(step.gas_left)),
+            Value::known(F::from(gas_cost)),
+        )?;
+
+        self.common_error_gadget
+            .assign(region, offset, block, call, step, 8)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{
+        address, bytecode,
+        evm_types::{GasCost, OpcodeId},
+        Address, Bytecode, ToWord, Word,
+    };
+    use mock::{eth, TestContext, MOCK_ACCOUNTS};
+
+    const BENEFICIARY: Address = Address::repeat_byte(0xbe);
+
+    fn selfdestruct_code(is_warm: bool) -> Bytecode {
+        let mut code = Bytecode::default();
+        if is_warm {
+            code.append(&bytecode! {
+                PUSH20(BENEFICIARY.to_word())
+                BALANCE
+                POP
+            });
+        }
+        code.append(&bytecode! {
+            PUSH20(BENEFICIARY.to_word())
+            SELFDESTRUCT
+        });
+        code
+    }
+
+    /// Gas of the transaction which leaves exactly one less than the cost of
+    /// SELFDESTRUCT.
+    fn insufficient_gas(is_warm: bool, charges_new_account: bool) -> u64 {
+        GasCost::TX
+            + if is_warm {
+                OpcodeId::PUSH20.constant_gas_cost()
+                    + GasCost::COLD_ACCOUNT_ACCESS
+                    + OpcodeId::POP.constant_gas_cost()
+            } else {
+                GasCost::COLD_ACCOUNT_ACCESS
+            }
+            + OpcodeId::PUSH20.constant_gas_cost()
+            + GasCost::SELFDESTRUCT
+            + if charges_new_account {
+                GasCost::NEW_ACCOUNT
+            } else {
+                0
+            }
+            - 1
+    }
+
+    fn test_root_ok(is_warm: bool, balance: Word, beneficiary_exists: bool) {
+        let charges_new_account = !beneficiary_exists && !balance.is_zero();
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x0000000000000000000000000000000000000123"))
+                    .balance(eth(10));
+                accs[1]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(balance)
+                    .code(selfdestruct_code(is_warm));
+                if beneficiary_exists {
+                    accs[2].address(BENEFICIARY).balance(eth(1));
+                } else {
+                    accs[2].address(MOCK_ACCOUNTS[1]).balance(eth(1));
+                }
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(insufficient_gas(is_warm, charges_new_account).into());
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn oog_selfdestruct_root() {
+        for is_warm in [false, true] {
+            test_root_ok(is_warm, eth(1), true);
+            test_root_ok(is_warm, eth(1), false);
+            test_root_ok(is_warm, Word::zero(), false);
+        }
+    }
+
+    #[test]
+    fn oog_selfdestruct_internal() {
+        let code_a = bytecode! {
+            PUSH1(0x00) // retLength
+            PUSH1(0x00) // retOffset
+            PUSH1(0x00) // argsLength
+            PUSH1(0x00) // argsOffset
+            PUSH1(0x00) // value
+            PUSH32(MOCK_ACCOUNTS[0].to_word()) // addr
+            PUSH32(Word::from(GasCost::SELFDESTRUCT)) // gas insufficient
+            CALL
+            STOP
+        };
+
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(eth(1))
+                    .code(selfdestruct_code(false));
+                accs[1].address(MOCK_ACCOUNTS[1]).code(code_a);
+                accs[2].address(MOCK_ACCOUNTS[2]).balance(eth(10));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[2].address).to(accs[1].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+}
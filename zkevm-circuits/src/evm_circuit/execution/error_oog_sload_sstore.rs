@@ -245,7 +245,7 @@ mod test {
     use eth_types::{
         bytecode,
         evm_types::{GasCost, OpcodeId},
-        Bytecode, U256,
+        Bytecode, ToWord, U256,
     };
     use mock::{
         eth, generate_mock_call_bytecode, MockCallBytecodeParams, TestContext, MOCK_ACCOUNTS,
@@ -361,6 +361,82 @@ mod test {
         });
     }
 
+    #[test]
+    fn test_oog_sstore_gas_sentry() {
+        // A cold SSTORE of the current value costs 2_200, but the gas left is
+        // at the gas sentry, so the SSTORE runs out of gas.
+        let sstore_gas_cost = cal_sstore_gas_cost_for_assignment(
+            0x060504.into(),
+            0x060504.into(),
+            0x060504.into(),
+            false,
+        );
+        assert!(sstore_gas_cost <= GasCost::SSTORE_SENTRY);
+        let testing_data = TestingData {
+            key: TESTING_STORAGE_KEY,
+            original_value: 0x060504.into(),
+            gas_cost: 2 * OpcodeId::PUSH32.constant_gas_cost() + GasCost::SSTORE_SENTRY + 1,
+            bytecode: bytecode! {
+                PUSH32(0x060504)
+                PUSH32(TESTING_STORAGE_KEY)
+                SSTORE
+            },
+        };
+        test_root(&testing_data);
+        test_internal(&testing_data);
+    }
+
+    #[test]
+    fn test_oog_sstore_call_stipend() {
+        // A CALL with value and no gas gives the callee the stipend of 2_300 gas, which is never
+        // enough for an SSTORE because of the gas sentry, while an SLOAD fits in it.
+        let (addr_a, addr_b) = (MOCK_ACCOUNTS[0], MOCK_ACCOUNTS[1]);
+        for code_b in [
+            bytecode! {
+                PUSH1(1)
+                PUSH32(TESTING_STORAGE_KEY)
+                SSTORE
+                STOP
+            },
+            bytecode! {
+                PUSH32(TESTING_STORAGE_KEY)
+                SLOAD
+                POP
+                STOP
+            },
+        ] {
+            let code_a = bytecode! {
+                PUSH1(0) // retLength
+                PUSH1(0) // retOffset
+                PUSH1(0) // argsLength
+                PUSH1(0) // argsOffset
+                PUSH1(1) // value
+                PUSH32(addr_b.to_word()) // addr
+                PUSH1(0) // gas
+                CALL
+                STOP
+            };
+            let ctx = TestContext::<3, 1>::new(
+                None,
+                |accs| {
+                    accs[0].address(addr_a).code(code_a).balance(eth(1));
+                    accs[1]
+                        .address(addr_b)
+                        .code(code_b)
+                        .storage([(TESTING_STORAGE_KEY, 0x060504.into())].into_iter());
+                    accs[2].address(MOCK_ACCOUNTS[2]).balance(eth(10));
+                },
+                |mut txs, accs| {
+                    txs[0].from(accs[2].address).to(accs[0].address);
+                },
+                |block, _tx| block,
+            )
+            .unwrap();
+
+            CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        }
+    }
+
     #[derive(Default)]
     struct TestingData {
         key: U256,
@@ -470,7 +546,7 @@ mod test {
     }
 
     fn test_internal(testing_data: &TestingData) {
-        let (addr_a, addr_b) = (mock::MOCK_ACCOUNTS[0], mock::MOCK_ACCOUNTS[1]);
+        let (addr_a, addr_b) = (MOCK_ACCOUNTS[0], MOCK_ACCOUNTS[1]);
 
         // code B gets called by code A, so the call is an internal call.
         let code_b = testing_data.bytecode.clone();
@@ -497,7 +573,7 @@ mod test {
                     .code(code_b)
                     .storage([(testing_data.key, testing_data.original_value)].into_iter());
                 accs[1].address(addr_a).code(code_a);
-                accs[2].address(mock::MOCK_ACCOUNTS[2]).balance(eth(10));
+                accs[2].address(MOCK_ACCOUNTS[2]).balance(eth(10));
             },
             |mut txs, accs| {
                 txs[0].from(accs[2].address).to(accs[1].address);
@@ -1,7 +1,7 @@
 use crate::{
     evm_circuit::{
         execution::ExecutionGadget,
-        param::{N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS},
+        param::{N_BYTES_GAS, N_BYTES_MEMORY_WORD_SIZE},
         step::ExecutionState,
         util::{
             common_gadget::CommonErrorGadget,
@@ -24,13 +24,9 @@ use halo2_proofs::{circuit::Value, plonk::Error};
 pub(crate) struct ErrorOOGStaticMemoryGadget<F> {
     opcode: Cell<F>,
     is_mstore8: IsEqualGadget<F>,
-    // Allow memory size to expand to 5 bytes, because memory address could be
-    // at most 2^40 - 1, after constant division by 32, the memory word size
-    // then could be at most 2^35 - 1.
-    // So generic N_BYTES_MEMORY_WORD_SIZE for MemoryExpansionGadget needs to
-    // be larger by 1 than normal usage (to be 5), to be able to contain
-    // number up to 2^35 - 1.
-    memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_ADDRESS>,
+    // The expanded address is 0 if it's above MAX_EXPANDED_MEMORY_ADDRESS, so
+    // the memory word size is at most 2^32 - 1.
+    memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
     memory_address: MemoryExpandedAddressGadget<F>,
     insufficient_gas: LtGadget<F, N_BYTES_GAS>,
     common_error_gadget: CommonErrorGadget<F>,
@@ -168,6 +164,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_oog_static_memory_boundary_expanded_address() {
+        // The access ends at MAX_EXPANDED_MEMORY_ADDRESS (0x1fffffffe0) for
+        // MSTORE and MLOAD, which is out of gas for the quadratic cost.
+        for code in testing_bytecodes(0x1fffffffc0_u64.into()).iter() {
+            test_root(code);
+            test_internal(code);
+        }
+    }
+
     #[test]
     fn test_oog_static_memory_max_expanded_address() {
         // > MAX_EXPANDED_MEMORY_ADDRESS (0x1fffffffe0)
@@ -183,6 +183,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorReturnDataOutOfBoundGadget<F> {
 #[cfg(test)]
 mod test {
     use crate::{evm_circuit::test::rand_bytes, test_util::CircuitTestBuilder};
+    use bus_mapping::precompile::PrecompileCalls;
     use eth_types::{bytecode, ToWord, Word};
     use mock::test_ctx::TestContext;
 
@@ -295,4 +296,33 @@ mod test {
         test_ok(0, 0x10, 0x20, 0x10.into(), 0x10, false);
         test_ok(0, 0x10, 0x20, 1.into(), 0xff, true);
     }
+
+    // data_offset + size > length of the output of a precompile
+    #[test]
+    fn test_return_data_oo_bound_after_precompile() {
+        for (offset, size) in [(0, 0x21), (0x20, 1)] {
+            let code = bytecode! {
+                PUSH32(Word::from_big_endian(&rand_bytes(32)))
+                PUSH1(0x00)
+                MSTORE
+                PUSH1(0x00) // retLength
+                PUSH1(0x00) // retOffset
+                PUSH1(0x20) // argsLength
+                PUSH1(0x00) // argsOffset
+                PUSH20(PrecompileCalls::Identity.address()) // addr
+                GAS
+                STATICCALL
+                PUSH32(size) // size
+                PUSH32(offset) // data offset
+                PUSH32(0x40) // memory offset
+                RETURNDATACOPY
+                STOP
+            };
+
+            CircuitTestBuilder::new_from_test_ctx(
+                TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap(),
+            )
+            .run();
+        }
+    }
 }
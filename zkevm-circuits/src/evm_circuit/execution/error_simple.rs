@@ -0,0 +1,82 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        table::{FixedTableTag, Lookup},
+        util::{
+            common_gadget::CommonErrorGadget, constraint_builder::EVMConstraintBuilder,
+            CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    util::Expr,
+};
+use eth_types::Field;
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget shared by the error states which are fully determined by the
+/// responsible opcode lookup, without any stack or state access.  The current
+/// call fails and the caller's context is restored by the
+/// [`CommonErrorGadget`].
+///
+/// The lookup is done against [`FixedTableTag::ResponsibleOpcode`]; for
+/// [`ExecutionState::ErrorStack`] the auxiliary value is the stack pointer
+/// that underflows or overflows for the opcode, otherwise it's 0.
+#[derive(Clone, Debug)]
+pub(crate) struct ErrorSimpleGadget<F, const S: ExecutionState> {
+    opcode: Cell<F>,
+    common_error_gadget: CommonErrorGadget<F>,
+}
+
+impl<F: Field, const S: ExecutionState> ExecutionGadget<F> for ErrorSimpleGadget<F, S> {
+    const NAME: &'static str = match S {
+        ExecutionState::ErrorInvalidOpcode => "ErrorInvalidOpcode",
+        ExecutionState::ErrorStack => "ErrorStack",
+        _ => "ErrorSimple",
+    };
+
+    const EXECUTION_STATE: ExecutionState = S;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+
+        let aux = if S == ExecutionState::ErrorStack {
+            cb.curr.state.stack_pointer.expr()
+        } else {
+            0.expr()
+        };
+        cb.add_lookup(
+            "Responsible opcode lookup",
+            Lookup::Fixed {
+                tag: FixedTableTag::ResponsibleOpcode.expr(),
+                values: [Self::EXECUTION_STATE.as_u64().expr(), opcode.expr(), aux],
+            },
+        );
+
+        let common_error_gadget = CommonErrorGadget::construct(cb, opcode.expr(), 0.expr());
+
+        Self {
+            opcode,
+            common_error_gadget,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode().unwrap();
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+
+        self.common_error_gadget
+            .assign(region, offset, block, call, step, 2)?;
+
+        Ok(())
+    }
+}
@@ -1,72 +1,9 @@
-use crate::{
-    evm_circuit::{
-        execution::ExecutionGadget,
-        step::ExecutionState,
-        table::{FixedTableTag, Lookup},
-        util::{
-            common_gadget::CommonErrorGadget, constraint_builder::EVMConstraintBuilder,
-            CachedRegion, Cell,
-        },
-        witness::{Block, Call, ExecStep, Transaction},
-    },
-    util::Expr,
-};
-use eth_types::Field;
-use halo2_proofs::{circuit::Value, plonk::Error};
-
-#[derive(Clone, Debug)]
-pub(crate) struct ErrorStackGadget<F> {
-    opcode: Cell<F>,
-    common_error_gadget: CommonErrorGadget<F>,
-}
-
-impl<F: Field> ExecutionGadget<F> for ErrorStackGadget<F> {
-    const NAME: &'static str = "ErrorStack";
-
-    const EXECUTION_STATE: ExecutionState = ExecutionState::ErrorStack;
-
-    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
-        let opcode = cb.query_cell();
-
-        cb.add_lookup(
-            "Responsible opcode lookup for invalid stack pointer",
-            Lookup::Fixed {
-                tag: FixedTableTag::ResponsibleOpcode.expr(),
-                values: [
-                    Self::EXECUTION_STATE.as_u64().expr(),
-                    opcode.expr(),
-                    cb.curr.state.stack_pointer.expr(),
-                ],
-            },
-        );
+use super::error_simple::ErrorSimpleGadget;
+use crate::evm_circuit::step::ExecutionState;
 
-        let common_error_gadget = CommonErrorGadget::construct(cb, opcode.expr(), 0.expr());
-
-        Self {
-            opcode,
-            common_error_gadget,
-        }
-    }
-
-    fn assign_exec_step(
-        &self,
-        region: &mut CachedRegion<'_, '_, F>,
-        offset: usize,
-        block: &Block<F>,
-        _tx: &Transaction,
-        call: &Call,
-        step: &ExecStep,
-    ) -> Result<(), Error> {
-        let opcode = step.opcode().unwrap();
-        self.opcode
-            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
-
-        self.common_error_gadget
-            .assign(region, offset, block, call, step, 2)?;
-
-        Ok(())
-    }
-}
+/// Gadget for stack underflow and overflow. It verifies by a fixed lookup for
+/// ResponsibleOpcode with the invalid stack pointer of the opcode.
+pub(crate) type ErrorStackGadget<F> = ErrorSimpleGadget<F, { ExecutionState::ErrorStack }>;
 
 #[cfg(test)]
 mod test {
@@ -47,11 +47,12 @@ impl<F: Field> ExecutionGadget<F> for ErrorWriteProtectionGadget<F> {
         // max_degree. otherwise need to do fixed lookup for these opcodes
         // checking.
         cb.require_in_set(
-            "ErrorWriteProtection only happens in [CALL, SSTORE, CREATE, CREATE2, SELFDESTRUCT, LOG0..4 ]",
+            "ErrorWriteProtection only happens in [CALL, SSTORE, TSTORE, CREATE, CREATE2, SELFDESTRUCT, LOG0..4 ]",
             opcode.expr(),
             vec![
                 OpcodeId::CALL.expr(),
                 OpcodeId::SSTORE.expr(),
+                OpcodeId::TSTORE.expr(),
                 OpcodeId::CREATE.expr(),
                 OpcodeId::CREATE2.expr(),
                 OpcodeId::SELFDESTRUCT.expr(),
@@ -160,23 +161,92 @@ mod test {
         test_internal_write_protection(true);
     }
 
-    // ErrorWriteProtection error happen in internal call
-    fn test_internal_write_protection(is_call: bool) {
-        let mut caller_bytecode = bytecode! {
+    #[test]
+    fn test_write_protection_opcodes() {
+        let codes = [
+            bytecode! {
+                PUSH1(0x20)
+                PUSH1(0)
+                LOG0
+                STOP
+            },
+            bytecode! {
+                PUSH1(0xaa)
+                PUSH1(0xbb)
+                PUSH1(0xcc)
+                PUSH1(0xdd)
+                PUSH1(0x20)
+                PUSH1(0)
+                LOG4
+                STOP
+            },
+            bytecode! {
+                PUSH1(0)
+                PUSH1(0)
+                PUSH1(0)
+                CREATE
+                STOP
+            },
+            bytecode! {
+                PUSH1(0x12)
+                PUSH1(0)
+                PUSH1(0)
+                PUSH1(0)
+                CREATE2
+                STOP
+            },
+            bytecode! {
+                PUSH20(Address::repeat_byte(0xbe).to_word())
+                SELFDESTRUCT
+            },
+        ];
+
+        for code in codes {
+            test_ok(
+                Account::mock_100_ether(static_caller_bytecode()),
+                callee(code),
+            );
+        }
+    }
+
+    #[test]
+    fn test_write_protection_reverts_access_list() {
+        // The slot and the account are warmed in the static call before the
+        // error, which has to revert both access list writes.
+        let callee_bytecode = bytecode! {
+            PUSH1(0x02)
+            SLOAD
+            POP
+            PUSH20(Address::repeat_byte(0xbe).to_word())
+            BALANCE
+            POP
+            PUSH1(42)
+            PUSH1(0x02)
+            SSTORE
+            STOP
+        };
+
+        test_ok(
+            Account::mock_100_ether(static_caller_bytecode()),
+            callee(callee_bytecode),
+        );
+    }
+
+    fn static_caller_bytecode() -> Bytecode {
+        bytecode! {
             PUSH1(0)
             PUSH1(0)
             PUSH1(0)
             PUSH1(0)
-            //PUSH1(100) //skip value for static call
-        };
-
-        caller_bytecode.append(&bytecode! {
             PUSH32(Address::repeat_byte(0xff).to_word())
             PUSH2(40000) // gas
-            STATICCALL //CALL
+            STATICCALL
             STOP
-        });
+        }
+    }
 
+    // ErrorWriteProtection error happen in internal call
+    fn test_internal_write_protection(is_call: bool) {
         let mut callee_bytecode = bytecode! {
             PUSH1(42)
             PUSH1(0x02)
@@ -204,13 +274,17 @@ mod test {
         }
 
         test_ok(
-            Account::mock_100_ether(caller_bytecode),
+            Account::mock_100_ether(static_caller_bytecode()),
             callee(callee_bytecode),
         );
     }
 
     fn test_ok(caller: Account, callee: Account) {
-        let ctx = TestContext::<3, 1>::new(
+        CircuitTestBuilder::new_from_test_ctx(test_ctx(caller, callee)).run();
+    }
+
+    fn test_ctx(caller: Account, callee: Account) -> TestContext<3, 1> {
+        TestContext::<3, 1>::new(
             None,
             |accs| {
                 accs[0]
@@ -227,8 +301,6 @@ mod test {
             },
             |block, _tx| block.number(0xcafeu64),
         )
-        .unwrap();
-
-        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        .unwrap()
     }
 }
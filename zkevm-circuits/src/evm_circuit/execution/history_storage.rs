@@ -0,0 +1,204 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::N_BYTES_U64,
+        step::ExecutionState,
+        util::{
+            common_gadget::SystemCallGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::ConstantDivisionGadget,
+            CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::BlockContextFieldTag,
+    util::{
+        word::{Word, WordCell, WordExpr},
+        Expr,
+    },
+};
+use eth_types::{
+    evm_types::system_contracts::{
+        history_storage_code, HISTORY_SERVE_WINDOW, HISTORY_STORAGE_ADDRESS,
+    },
+    Field,
+};
+use halo2_proofs::{
+    circuit::Value,
+    plonk::{Error, Expression},
+};
+
+/// Gadget for the system call which stores the hash of the parent block in
+/// the history storage contract at the beginning of a block (EIP-2935).
+///
+/// The step comes after the BeaconRoot step, if any, and before the first tx.
+/// If the contract has code, which must be the one of EIP-2935, the step
+/// writes the parent hash, which is looked up in the block hash table, into
+/// the slot of the parent block number in the ring buffer.
+#[derive(Clone, Debug)]
+pub(crate) struct HistoryStorageGadget<F> {
+    number: Cell<F>,
+    parent_hash: WordCell<F>,
+    system_call: SystemCallGadget<F>,
+    parent_index: ConstantDivisionGadget<F, N_BYTES_U64>,
+    value_prev: WordCell<F>,
+    committed_value: WordCell<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for HistoryStorageGadget<F> {
+    const NAME: &'static str = "HistoryStorage";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::HistoryStorage;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        if !cb.hardfork.is_history_storage_active() {
+            cb.require_zero("HistoryStorage is not active before Prague", 1.expr());
+        }
+
+        let number = cb.query_cell();
+        cb.block_lookup(
+            BlockContextFieldTag::Number.expr(),
+            None,
+            Word::from_lo_unchecked(number.expr()),
+        );
+        let parent_hash = cb.query_word_unchecked();
+        cb.block_hash_lookup(1.expr(), parent_hash.to_word());
+
+        let address = Word::from(HISTORY_STORAGE_ADDRESS).map(Expression::Constant);
+        let system_call = SystemCallGadget::construct(cb, address.clone(), &history_storage_code());
+
+        let parent_index =
+            ConstantDivisionGadget::construct(cb, number.expr() - 1.expr(), HISTORY_SERVE_WINDOW);
+        let value_prev = cb.query_word_unchecked();
+        let committed_value = cb.query_word_unchecked();
+        cb.condition(system_call.has_code(), |cb| {
+            cb.account_storage_write(
+                address,
+                Word::from_lo_unchecked(parent_index.remainder()),
+                parent_hash.to_word(),
+                value_prev.to_word(),
+                0.expr(),
+                committed_value.to_word(),
+                None,
+            );
+        });
+
+        SystemCallGadget::constrain_transition(cb, 1.expr() + system_call.has_code());
+
+        Self {
+            number,
+            parent_hash,
+            system_call,
+            parent_index,
+            value_prev,
+            committed_value,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _: &Transaction,
+        _: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let number = block.context.number.as_u64();
+        self.number
+            .assign(region, offset, Value::known(F::from(number)))?;
+        let parent_hash = block
+            .context
+            .history_hashes
+            .last()
+            .copied()
+            .unwrap_or_default();
+        self.parent_hash.assign_u256(region, offset, parent_hash)?;
+
+        let code_hash = block.get_rws(step, 0).account_codehash_pair().0;
+        self.system_call.assign(region, offset, code_hash)?;
+
+        self.parent_index
+            .assign(region, offset, (number - 1) as u128)?;
+        if step.rw_indices_len() > 1 {
+            let (_, value_prev, _, committed_value) = block.get_rws(step, 1).storage_value_aux();
+            self.value_prev.assign_u256(region, offset, value_prev)?;
+            self.committed_value
+                .assign_u256(region, offset, committed_value)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use bus_mapping::circuit_input_builder::FixedCParams;
+    use eth_types::{
+        bytecode,
+        evm_types::{
+            system_contracts::{history_storage_code, HISTORY_STORAGE_ADDRESS},
+            Hardfork,
+        },
+        Word,
+    };
+    use mock::{eth, TestContext, MOCK_ACCOUNTS};
+
+    const NUMBER: u64 = 0xcafe;
+
+    fn test_ok(contract_code: Option<Vec<u8>>, block_number: u64) {
+        let hash = Word::from(0xbeef);
+        let code = bytecode! {
+            PUSH32(Word::from(block_number))
+            BLOCKHASH
+            POP
+            STOP
+        };
+        // The hash of NUMBER - 2, which the contract already stores.
+        let history_hashes = vec![Word::from(0xdead), hash, Word::from(0xf00d)];
+        let ctx = TestContext::<3, 1>::new(
+            Some(history_hashes),
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).code(code);
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+                match contract_code {
+                    Some(code) => accs[2]
+                        .address(HISTORY_STORAGE_ADDRESS)
+                        .balance(eth(1))
+                        .code(code)
+                        .storage(vec![(Word::from((NUMBER - 2) % 8191), hash)].into_iter()),
+                    None => accs[2].address(MOCK_ACCOUNTS[2]).balance(eth(1)),
+                };
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[1].address).to(accs[0].address);
+            },
+            |block, _tx| block.number(NUMBER),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(FixedCParams {
+                hardfork: Hardfork::Prague,
+                ..Default::default()
+            })
+            .run();
+    }
+
+    #[test]
+    fn history_storage_contract() {
+        test_ok(Some(history_storage_code()), NUMBER - 2);
+    }
+
+    #[test]
+    fn history_storage_blockhash_out_of_window() {
+        test_ok(Some(history_storage_code()), NUMBER);
+        test_ok(Some(history_storage_code()), NUMBER - 8192);
+    }
+
+    #[test]
+    fn history_storage_no_contract() {
+        test_ok(None, NUMBER - 8192);
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
     evm_circuit::{
         execution::ExecutionGadget,
-        param::N_BYTES_GAS,
+        param::{N_BYTES_GAS, N_BYTES_U64},
         step::ExecutionState,
         util::{
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
@@ -11,7 +11,7 @@ use crate::{
         },
         witness::{Block, Call, ExecStep, Transaction},
     },
-    table::AccountFieldTag,
+    table::{AccountFieldTag, BlockContextFieldTag},
     util::word::{Word32Cell, WordExpr},
 };
 use eth_types::{Field, ToScalar};
@@ -28,6 +28,9 @@ pub(crate) struct InvalidTxGadget<F> {
     insufficient_gas_limit: LtGadget<F, N_BYTES_GAS>,
     balance: Word32Cell<F>,
     insufficient_balance: LtWordGadget<F>,
+    base_fee: Word32Cell<F>,
+    gas_price_lt_base_fee: LtWordGadget<F>,
+    init_code_size_overflow: LtGadget<F, N_BYTES_U64>,
     end_tx: EndTxHelperGadget<F>,
 }
 
@@ -41,7 +44,7 @@ impl<F: Field> ExecutionGadget<F> for InvalidTxGadget<F> {
         let tx = TxDataGadget::configure(cb, begin_tx.tx_id.expr(), true);
 
         // Check if the nonce is invalid
-        let account_nonce = cb.query_cell_phase2();
+        let account_nonce = cb.query_cell();
         cb.account_read(
             tx.caller_address.to_word(),
             AccountFieldTag::Nonce,
@@ -51,9 +54,9 @@ impl<F: Field> ExecutionGadget<F> for InvalidTxGadget<F> {
 
         // Check if the gas limit is larger or equal to the intrinsic gas cost
         let insufficient_gas_limit =
-            LtGadget::<F, N_BYTES_GAS>::construct(cb, tx.gas.expr(), tx.intrinsic_gas());
+            LtGadget::<F, N_BYTES_GAS>::construct(cb, tx.gas.expr(), tx.intrinsic_gas(cb.hardfork));
 
-        // Check if the balance is sufficient to pay for the total tx cost (intrinsic gas + value)
+        // Check if the balance is sufficient to pay for the total tx cost (gas * max fee + value)
         let balance = cb.query_word32();
         cb.account_read(
             tx.caller_address.to_word(),
@@ -63,11 +66,30 @@ impl<F: Field> ExecutionGadget<F> for InvalidTxGadget<F> {
         let insufficient_balance =
             LtWordGadget::construct(cb, &balance.to_word(), &tx.total_cost().to_word());
 
+        // Check if the gas price pays for the base fee of the block
+        let base_fee = cb.query_word32();
+        cb.block_lookup(
+            BlockContextFieldTag::BaseFee.expr(),
+            None,
+            base_fee.to_word(),
+        );
+        let gas_price_lt_base_fee =
+            LtWordGadget::construct(cb, &tx.gas_price.to_word(), &base_fee.to_word());
+
+        // Check if the init code of a creation tx exceeds the maximum size (EIP-3860)
+        let init_code_size_overflow = LtGadget::<F, N_BYTES_U64>::construct(
+            cb,
+            cb.hardfork.max_init_code_size().expr(),
+            tx.call_data_length.expr(),
+        );
+
         // At least one of the invalid conditions needs to be true
         let invalid_tx = or::expr([
             not::expr(is_nonce_match.expr()),
             insufficient_gas_limit.expr(),
             insufficient_balance.expr(),
+            gas_price_lt_base_fee.expr(),
+            tx.is_create.expr() * init_code_size_overflow.expr(),
         ]);
         cb.require_equal("Tx needs to be invalid", invalid_tx.expr(), 1.expr());
 
@@ -87,6 +109,9 @@ impl<F: Field> ExecutionGadget<F> for InvalidTxGadget<F> {
             insufficient_gas_limit,
             balance,
             insufficient_balance,
+            base_fee,
+            gas_price_lt_base_fee,
+            init_code_size_overflow,
             end_tx,
         }
     }
@@ -100,6 +125,7 @@ impl<F: Field> ExecutionGadget<F> for InvalidTxGadget<F> {
         _call: &Call,
         step: &ExecStep,
     ) -> Result<(), Error> {
+        let hardfork = block.circuits_params.hardfork;
         let mut rws = StepRws::new(block, step);
         rws.offset_add(1);
 
@@ -120,7 +146,7 @@ impl<F: Field> ExecutionGadget<F> for InvalidTxGadget<F> {
             region,
             offset,
             tx.gas().scalar(),
-            tx.intrinsic_gas_cost().scalar(),
+            tx.intrinsic_gas_cost(hardfork).scalar(),
         )?;
         self.balance.assign_u256(region, offset, balance)?;
         self.insufficient_balance.assign(
@@ -129,6 +155,16 @@ impl<F: Field> ExecutionGadget<F> for InvalidTxGadget<F> {
             balance,
             tx.gas_price * tx.gas() + tx.value,
         )?;
+        self.base_fee
+            .assign_u256(region, offset, block.context.base_fee)?;
+        self.gas_price_lt_base_fee
+            .assign(region, offset, tx.gas_price, block.context.base_fee)?;
+        self.init_code_size_overflow.assign(
+            region,
+            offset,
+            F::from(hardfork.max_init_code_size()),
+            F::from(tx.call_data.len() as u64),
+        )?;
         self.end_tx.assign(region, offset, block, tx)?;
 
         Ok(())
@@ -138,7 +174,12 @@ impl<F: Field> ExecutionGadget<F> for InvalidTxGadget<F> {
 #[cfg(test)]
 mod test {
     use crate::test_util::CircuitTestBuilder;
-    use eth_types::{self, bytecode, Word};
+    use bus_mapping::circuit_input_builder::FixedCParams;
+    use eth_types::{
+        self, bytecode,
+        evm_types::{GasCost, Hardfork},
+        AccessList, AccessListItem, Word, H256,
+    };
 
     use mock::{eth, gwei, TestContext, MOCK_ACCOUNTS};
 
@@ -197,6 +238,53 @@ mod test {
         CircuitTestBuilder::new_from_test_ctx(ctx).run();
     }
 
+    #[test]
+    fn invalid_tx_insufficient_intrinsic_gas() {
+        // A creation tx with a zero byte of init code, which is STOP, and an
+        // access list, so the intrinsic gas covers all the tx.
+        let from = MOCK_ACCOUNTS[1];
+        let intrinsic_gas = GasCost::CREATION_TX
+            + 4
+            + Hardfork::default().create_gas_per_code_word()
+            + GasCost::ACCESS_LIST_ADDRESS
+            + GasCost::ACCESS_LIST_STORAGE_KEY;
+        let access_list = AccessList(vec![AccessListItem {
+            address: MOCK_ACCOUNTS[0],
+            storage_keys: vec![H256::from_low_u64_be(1)],
+        }]);
+        let ctx = TestContext::<2, 2>::new(
+            None,
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).balance(eth(1));
+                accs[1].address(from).balance(eth(1));
+            },
+            |mut txs, _| {
+                // Invalid if gas_limit < intrinsic gas, by one
+                txs[0]
+                    .from(from)
+                    .gas(Word::from(intrinsic_gas - 1))
+                    .input(vec![0].into())
+                    .access_list(access_list.clone())
+                    .invalid();
+                txs[1]
+                    .from(from)
+                    .gas(Word::from(intrinsic_gas))
+                    .input(vec![0].into())
+                    .access_list(access_list);
+            },
+            |block, _| block,
+        )
+        .unwrap();
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(FixedCParams {
+                max_txs: 2,
+                max_signed_txs: 2,
+                max_access_list: 2,
+                ..Default::default()
+            })
+            .run();
+    }
+
     #[test]
     fn invalid_tx_insufficient_balance() {
         // Invalid if the balance < intrinsic gas cost + value
@@ -223,6 +311,29 @@ mod test {
         CircuitTestBuilder::new_from_test_ctx(ctx).run();
     }
 
+    #[test]
+    fn invalid_tx_init_code_size_exceeded() {
+        // Invalid if the init code of a creation tx is larger than 49152 bytes
+        let from = MOCK_ACCOUNTS[1];
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).balance(eth(1));
+                accs[1].address(from).balance(eth(1));
+            },
+            |mut txs, _| {
+                txs[0]
+                    .from(from)
+                    .gas(Word::from(1_000_000))
+                    .input(vec![0; Hardfork::Shanghai.max_init_code_size() as usize + 1].into())
+                    .invalid();
+            },
+            |block, _| block,
+        )
+        .unwrap();
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
     #[test]
     fn invalid_tx_state_transitions() {
         fn test_ok<const NTX: usize>(tx_states: [bool; NTX]) {
@@ -0,0 +1,255 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::N_BYTES_MEMORY_WORD_SIZE,
+        step::ExecutionState,
+        util::{
+            common_gadget::SameContextGadget,
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
+                Transition::{Delta, To},
+            },
+            memory_gadget::{
+                CommonMemoryAddressGadget, MemoryAddressGadget, MemoryCopierGasGadget,
+                MemoryExpansionGadget,
+            },
+            CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    util::{
+        word::{Word, WordExpr},
+        Expr,
+    },
+};
+use bus_mapping::{circuit_input_builder::CopyDataType, evm::OpcodeId};
+use eth_types::{evm_types::GasCost, Field, ToScalar};
+use gadgets::util::not;
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+#[derive(Clone, Debug)]
+pub(crate) struct McopyGadget<F> {
+    same_context: SameContextGadget<F>,
+    /// The memory address from where we read.
+    src_memory_addr: MemoryAddressGadget<F>,
+    /// The memory address to which we write, with the same length as
+    /// `src_memory_addr`.
+    dst_memory_addr: MemoryAddressGadget<F>,
+    /// Opcode MCOPY has a dynamic gas cost:
+    /// gas_code = static_gas + minimum_word_size * copy_gas + memory_expansion_cost
+    /// where the memory is expanded to cover both the source and the
+    /// destination.
+    memory_expansion: MemoryExpansionGadget<F, 2, N_BYTES_MEMORY_WORD_SIZE>,
+    /// We account for the copying costs using the memory copier gas gadget.
+    memory_copier_gas: MemoryCopierGasGadget<F, { GasCost::COPY }>,
+    /// RW inverse counter from the copy table at the start of related copy
+    /// steps.
+    copy_rwc_inc: Cell<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for McopyGadget<F> {
+    const NAME: &'static str = "MCOPY";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::MCOPY;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+
+        let dst_offset = cb.query_word_unchecked();
+        let src_offset = cb.query_word_unchecked();
+        let length = cb.query_memory_address();
+
+        // Pop dst_offset, src_offset and length from the stack
+        cb.stack_pop(dst_offset.to_word());
+        cb.stack_pop(src_offset.to_word());
+        cb.stack_pop(Word::from_lo_unchecked(length.expr()));
+
+        let src_memory_addr = MemoryAddressGadget::construct(cb, src_offset, length.clone());
+        let dst_memory_addr = MemoryAddressGadget::construct(cb, dst_offset, length);
+
+        // Calculate the next memory size and the gas cost for this memory
+        // access, which also accounts for the dynamic gas required to copy
+        // the bytes.
+        let memory_expansion = MemoryExpansionGadget::construct(
+            cb,
+            [src_memory_addr.address(), dst_memory_addr.address()],
+        );
+        let memory_copier_gas = MemoryCopierGasGadget::construct(
+            cb,
+            dst_memory_addr.length(),
+            memory_expansion.gas_cost(),
+        );
+
+        // The bytes are copied within the memory of the current call.  The
+        // copy circuit reads all of them before writing the first one, so that
+        // overlapping ranges are copied as `memmove`.
+        let copy_rwc_inc = cb.query_cell();
+        cb.condition(dst_memory_addr.has_length(), |cb| {
+            cb.copy_table_lookup(
+                Word::from_lo_unchecked(cb.curr.state.call_id.expr()),
+                CopyDataType::Memory.expr(),
+                Word::from_lo_unchecked(cb.curr.state.call_id.expr()),
+                CopyDataType::Memory.expr(),
+                src_memory_addr.offset(),
+                src_memory_addr.address(),
+                dst_memory_addr.offset(),
+                dst_memory_addr.length(),
+                0.expr(), // for MCOPY rlc_acc is 0
+                copy_rwc_inc.expr(),
+            );
+        });
+        cb.condition(not::expr(dst_memory_addr.has_length()), |cb| {
+            cb.require_zero(
+                "if no bytes to copy, copy table rwc inc == 0",
+                copy_rwc_inc.expr(),
+            );
+        });
+
+        // State transition
+        let step_state_transition = StepStateTransition {
+            rw_counter: Delta(cb.rw_counter_offset()),
+            program_counter: Delta(1.expr()),
+            stack_pointer: Delta(3.expr()),
+            gas_left: Delta(
+                -(OpcodeId::MCOPY.constant_gas_cost().expr() + memory_copier_gas.gas_cost()),
+            ),
+            memory_word_size: To(memory_expansion.next_memory_word_size()),
+            ..Default::default()
+        };
+        let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
+
+        Self {
+            same_context,
+            src_memory_addr,
+            dst_memory_addr,
+            memory_expansion,
+            memory_copier_gas,
+            copy_rwc_inc,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _tx: &Transaction,
+        _call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.same_context.assign_exec_step(region, offset, step)?;
+
+        let [dst_offset, src_offset, length] =
+            [0, 1, 2].map(|index| block.get_rws(step, index).stack_value());
+
+        let src_address = self
+            .src_memory_addr
+            .assign(region, offset, src_offset, length)?;
+        let dst_address = self
+            .dst_memory_addr
+            .assign(region, offset, dst_offset, length)?;
+
+        // Assign to the gadgets handling the memory expansion cost and the
+        // copying cost.
+        let (_, memory_expansion_cost) = self.memory_expansion.assign(
+            region,
+            offset,
+            step.memory_word_size(),
+            [src_address, dst_address],
+        )?;
+        self.memory_copier_gas
+            .assign(region, offset, length.as_u64(), memory_expansion_cost)?;
+
+        // rw_counter always increases by `length` reads and `length` writes
+        let copy_rwc_inc = length + length;
+        self.copy_rwc_inc.assign(
+            region,
+            offset,
+            Value::known(
+                copy_rwc_inc
+                    .to_scalar()
+                    .expect("unexpected U256 -> Scalar conversion failure"),
+            ),
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use bus_mapping::circuit_input_builder::FixedCParams;
+    use eth_types::{
+        bytecode,
+        evm_types::{Hardfork, OpcodeId},
+        Word,
+    };
+    use mock::{
+        interpreter,
+        test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+        TestContext,
+    };
+
+    /// `gas_cost` is the cost go-ethereum charges for the MCOPY, with the
+    /// memory holding 2 words before it.
+    fn test_ok(dst_offset: Word, src_offset: Word, length: usize, gas_cost: u64) {
+        let code = bytecode! {
+            .op_mstore(0x00, Word::from_big_endian(&(1..=32).collect::<Vec<u8>>()))
+            .op_mstore(0x20, Word::MAX)
+            .op_mcopy(dst_offset, src_offset, length)
+            STOP
+        };
+
+        // The geth-utils tracer doesn't support EIP-5656 yet.
+        let ctx = TestContext::<2, 1>::new_with_tracer(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _txs| block,
+            interpreter::trace,
+        )
+        .unwrap();
+        let step = ctx.geth_traces[0]
+            .struct_logs
+            .iter()
+            .find(|step| step.op == OpcodeId::MCOPY)
+            .unwrap();
+        assert_eq!(step.gas_cost, gas_cost);
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(FixedCParams {
+                hardfork: Hardfork::Cancun,
+                ..Default::default()
+            })
+            .run();
+    }
+
+    #[test]
+    fn mcopy_gadget_simple() {
+        // 3 + 3 * 1 word copied + 3 to expand the memory to 3 words.
+        test_ok(0x40.into(), 0x00.into(), 0x20, 9);
+    }
+
+    #[test]
+    fn mcopy_gadget_overlapping_ranges() {
+        // Forward and backward copies within the same word.
+        test_ok(0x08.into(), 0x00.into(), 0x18, 6);
+        test_ok(0x00.into(), 0x08.into(), 0x18, 6);
+    }
+
+    #[test]
+    fn mcopy_gadget_memory_expansion() {
+        // The source range expands the memory more than the destination one,
+        // and conversely: 3 + 3 * 2 words copied + 9 to expand the memory to
+        // 5 words.
+        test_ok(0x00.into(), 0x50.into(), 0x40, 18);
+        test_ok(0x50.into(), 0x00.into(), 0x40, 18);
+    }
+
+    #[test]
+    fn mcopy_gadget_zero_length() {
+        // The offsets are ignored when nothing is copied.
+        test_ok(Word::MAX, Word::MAX, 0x00, 3);
+    }
+}
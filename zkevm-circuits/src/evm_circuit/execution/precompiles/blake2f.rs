@@ -0,0 +1,371 @@
+use bus_mapping::{
+    circuit_input_builder::Call,
+    precompile::{PrecompileAuxData, PrecompileCalls, BLAKE2F_INPUT_LEN},
+};
+use eth_types::Field;
+use gadgets::util::{not, Expr};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+use super::{
+    randomness_pow, PrecompileCallContextGadget, PrecompileCallExprs, PrecompileExecutionGadget,
+    N_CALL_CONTEXT_RWS,
+};
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::N_BYTES_GAS,
+        step::ExecutionState,
+        util::{
+            common_gadget::RestoreContextGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::{IsEqualGadget, IsZeroGadget, LtGadget},
+            rlc, CachedRegion, Cell,
+        },
+    },
+    witness::{Block, ExecStep, Transaction},
+};
+
+/// Number of bytes of the state vector, the message block and the offset
+/// counter in the input
+const N_BYTES_HMT: usize = BLAKE2F_INPUT_LEN - 5;
+
+/// Gadget of the blake2f precompile.  The input is the number of rounds as a
+/// big-endian u32, the state vector `h`, the message block `m`, the offset
+/// counter `t` and the final block flag `f`, and the output is the state
+/// vector compressed by the function F of BLAKE2b.  The compression of a
+/// successful call is looked up in the Blake2fTable, with a gas cost of 1 per
+/// round.
+#[derive(Clone, Debug)]
+pub struct Blake2fGadget<F> {
+    input_bytes_rlc: Cell<F>,
+    output_bytes_rlc: Cell<F>,
+    return_len: Cell<F>,
+
+    /// Big-endian bytes of the number of rounds
+    rounds: [Cell<F>; 4],
+    /// RLC of `h || m || t`
+    hmt_rlc: Cell<F>,
+    f: Cell<F>,
+    has_valid_length: IsEqualGadget<F>,
+    has_valid_flag: IsZeroGadget<F>,
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
+
+    call_context: PrecompileCallContextGadget<F>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for Blake2fGadget<F> {
+    const EXECUTION_STATE: ExecutionState = ExecutionState::PrecompileBlake2f;
+
+    const NAME: &'static str = "BLAKE2F";
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // These cells are constrained by the PrecompileGadget of the call, so
+        // they must be the first ones of their types.
+        let input_bytes_rlc = cb.query_cell_phase2();
+        let output_bytes_rlc = cb.query_cell_phase2();
+        let return_len = cb.query_cell();
+
+        let rounds = cb.query_bytes::<4>();
+        let hmt_rlc = cb.query_cell_phase2();
+        let f = cb.query_byte();
+
+        let call_context = PrecompileCallContextGadget::construct(cb);
+
+        // The whole call data is the input, whose bytes are only decomposed
+        // when it has the right length.
+        let has_valid_length = IsEqualGadget::construct(
+            cb,
+            call_context.call_data_length.expr(),
+            BLAKE2F_INPUT_LEN.expr(),
+        );
+        let randomness = cb.challenges().keccak_input();
+        let rounds_le = rounds
+            .iter()
+            .rev()
+            .map(|byte| byte.expr())
+            .collect::<Vec<_>>();
+        cb.condition(has_valid_length.expr(), |cb| {
+            cb.require_equal(
+                "input_bytes_rlc = RLC(rounds || h || m || t || f)",
+                input_bytes_rlc.expr(),
+                (rlc::expr(&rounds_le, randomness.clone())
+                    * randomness_pow(randomness.clone(), N_BYTES_HMT)
+                    + hmt_rlc.expr())
+                    * randomness.clone()
+                    + f.expr(),
+            );
+        });
+        let rounds_value = rounds
+            .iter()
+            .fold(0.expr(), |acc, byte| acc * 256.expr() + byte.expr());
+
+        // The call fails for an invalid length, a flag which isn't a boolean,
+        // or an insufficient gas, and consumes all its gas.
+        let has_valid_flag = IsZeroGadget::construct(cb, f.expr() * (f.expr() - 1.expr()));
+        let gas_cost = call_context.gas_cost(rounds_value.clone());
+        let insufficient_gas =
+            LtGadget::construct(cb, cb.curr.state.gas_left.expr(), gas_cost.clone());
+        cb.require_equal(
+            "the call succeeds iff the input is valid and the gas is sufficient",
+            call_context.is_success.expr(),
+            has_valid_length.expr() * has_valid_flag.expr() * not::expr(insufficient_gas.expr()),
+        );
+        cb.condition(call_context.is_success.expr(), |cb| {
+            cb.blake2f_table_lookup(
+                rounds_value.clone(),
+                hmt_rlc.expr(),
+                f.expr(),
+                output_bytes_rlc.expr(),
+            );
+        });
+        cb.condition(not::expr(call_context.is_success.expr()), |cb| {
+            cb.require_zero(
+                "output_bytes_rlc is 0 when the call fails",
+                output_bytes_rlc.expr(),
+            );
+        });
+        cb.require_equal(
+            "return_len = is_success * 64",
+            return_len.expr(),
+            call_context.is_success.expr() * 0x40.expr(),
+        );
+
+        let restore_context = call_context.restore_context(cb, gas_cost, return_len.expr());
+
+        Self {
+            input_bytes_rlc,
+            output_bytes_rlc,
+            return_len,
+            rounds,
+            hmt_rlc,
+            f,
+            has_valid_length,
+            has_valid_flag,
+            insufficient_gas,
+            call_context,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let aux_data = match &step.aux_data {
+            Some(PrecompileAuxData::Blake2f(aux_data)) => aux_data,
+            _ => {
+                log::error!("missing the aux data of the blake2f step");
+                return Err(Error::Synthesis);
+            }
+        };
+        let randomness = region.challenges().keccak_input();
+
+        self.input_bytes_rlc.assign(
+            region,
+            offset,
+            randomness.map(|randomness| rlc::value(aux_data.input.iter().rev(), randomness)),
+        )?;
+        self.output_bytes_rlc.assign(
+            region,
+            offset,
+            randomness.map(|randomness| rlc::value(aux_data.output.iter().rev(), randomness)),
+        )?;
+        self.return_len.assign(
+            region,
+            offset,
+            Value::known(F::from(aux_data.output.len() as u64)),
+        )?;
+
+        let (rounds, hmt, f) = if aux_data.has_valid_length() {
+            (
+                &aux_data.input[..4],
+                &aux_data.input[4..BLAKE2F_INPUT_LEN - 1],
+                aux_data.input[BLAKE2F_INPUT_LEN - 1],
+            )
+        } else {
+            (&[0u8; 4][..], &[][..], 0)
+        };
+        for (cell, byte) in self.rounds.iter().zip(rounds) {
+            cell.assign(region, offset, Value::known(F::from(*byte as u64)))?;
+        }
+        self.hmt_rlc.assign(
+            region,
+            offset,
+            randomness.map(|randomness| rlc::value(hmt.iter().rev(), randomness)),
+        )?;
+        let f = F::from(f as u64);
+        self.f.assign(region, offset, Value::known(f))?;
+        self.has_valid_length.assign(
+            region,
+            offset,
+            F::from(call.call_data_length),
+            F::from(BLAKE2F_INPUT_LEN as u64),
+        )?;
+        self.has_valid_flag
+            .assign(region, offset, f * (f - F::ONE))?;
+        let gas_cost = PrecompileCalls::Blake2F
+            .gas_cost(block.circuits_params.hardfork)
+            .ok_or(Error::Synthesis)?
+            .cost(aux_data.rounds() as u64);
+        self.insufficient_gas
+            .assign(region, offset, F::from(step.gas_left), F::from(gas_cost))?;
+
+        self.call_context.assign(region, offset, block, call)?;
+        self.restore_context
+            .assign(region, offset, block, call, step, N_CALL_CONTEXT_RWS)?;
+
+        Ok(())
+    }
+}
+
+impl<F: Field> PrecompileExecutionGadget<F> for Blake2fGadget<F> {
+    const PRECOMPILE: PrecompileCalls = PrecompileCalls::Blake2F;
+
+    fn constrain_call(cb: &mut EVMConstraintBuilder<F>, call: &PrecompileCallExprs<F>) {
+        let input_bytes_rlc = cb.query_cell_phase2();
+        let output_bytes_rlc = cb.query_cell_phase2();
+        let return_len = cb.query_cell();
+        call.constrain_bytes(cb, input_bytes_rlc.expr(), output_bytes_rlc.expr());
+        cb.require_equal(
+            "blake2f returns 64 bytes if the call succeeds",
+            call.return_length.clone(),
+            return_len.expr(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bus_mapping::{
+        evm::{OpcodeId, PrecompileCallArgs},
+        precompile::PrecompileCalls,
+    };
+    use eth_types::{Bytecode, ToWord, Word};
+    use itertools::Itertools;
+    use mock::TestContext;
+
+    use crate::test_util::CircuitTestBuilder;
+
+    /// Input of the test vectors of EIP-152 with `rounds` rounds and the flag
+    /// byte `f`.
+    fn blake2f_input(rounds: u32, f: u8) -> Vec<u8> {
+        let h = hex::decode(
+            "48c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5\
+             d182e6ad7f520e511f6c3e2b8c68059b6bbd41fbabd9831f79217e1319cde05b",
+        )
+        .unwrap();
+        let mut m = vec![0; 128];
+        m[..3].copy_from_slice(b"abc");
+        let mut t = vec![0; 16];
+        t[0] = 3;
+        [rounds.to_be_bytes().as_slice(), &h, &m, &t, &[f]].concat()
+    }
+
+    /// Place the bytes in memory from address 0, right-padded with zeros to a
+    /// multiple of 32 bytes.
+    fn store_bytes(bytes: &[u8]) -> Bytecode {
+        let mut code = Bytecode::default();
+        for (i, chunk) in bytes.chunks(32).enumerate() {
+            let mut word = [0; 32];
+            word[..chunk.len()].copy_from_slice(chunk);
+            code.push(32, Word::from_big_endian(&word))
+                .push(32, Word::from(32 * i))
+                .write_op(OpcodeId::MSTORE);
+        }
+        code
+    }
+
+    lazy_static::lazy_static! {
+        static ref TEST_VECTOR: Vec<PrecompileCallArgs> = {
+            vec![
+                PrecompileCallArgs {
+                    name: "blake2f (12 rounds)",
+                    setup_code: store_bytes(&blake2f_input(12, 1)),
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0xd5.into(),
+                    ret_offset: 0xe0.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Blake2F.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "blake2f (no rounds)",
+                    setup_code: store_bytes(&blake2f_input(0, 1)),
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0xd5.into(),
+                    ret_offset: 0xe0.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Blake2F.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "blake2f (not the final block)",
+                    setup_code: store_bytes(&blake2f_input(12, 0)),
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0xd5.into(),
+                    // return only 32 bytes of the output
+                    ret_offset: 0xe0.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::Blake2F.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "blake2f (invalid length)",
+                    setup_code: store_bytes(&blake2f_input(12, 1)),
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0xd4.into(),
+                    ret_offset: 0xe0.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Blake2F.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "blake2f (invalid flag)",
+                    setup_code: store_bytes(&blake2f_input(12, 2)),
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0xd5.into(),
+                    ret_offset: 0xe0.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Blake2F.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "blake2f (insufficient gas)",
+                    setup_code: store_bytes(&blake2f_input(12, 1)),
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0xd5.into(),
+                    ret_offset: 0xe0.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Blake2F.address().to_word(),
+                    gas: 11.into(),
+                    ..Default::default()
+                },
+            ]
+        };
+    }
+
+    #[test]
+    fn precompile_blake2f_test() {
+        let call_kinds = vec![
+            OpcodeId::CALL,
+            OpcodeId::STATICCALL,
+            OpcodeId::DELEGATECALL,
+            OpcodeId::CALLCODE,
+        ];
+
+        for (test_vector, &call_kind) in TEST_VECTOR.iter().cartesian_product(&call_kinds) {
+            let bytecode = test_vector.with_call_op(call_kind);
+
+            CircuitTestBuilder::new_from_test_ctx(
+                TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+            )
+            .run();
+        }
+    }
+}
@@ -0,0 +1,423 @@
+use bus_mapping::{
+    circuit_input_builder::Call,
+    precompile::{PrecompileAuxData, PrecompileCalls},
+};
+use eth_types::{Field, ToBigEndian};
+use gadgets::util::Expr;
+use halo2_proofs::{circuit::Value, plonk::Error};
+use itertools::Itertools;
+
+use super::{
+    PaddedInputGadget, PrecompileCallContextGadget, PrecompileCallExprs, PrecompileExecutionGadget,
+    N_CALL_CONTEXT_RWS,
+};
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            common_gadget::RestoreContextGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            rlc, CachedRegion, Cell,
+        },
+    },
+    util::word::{Word32Cell, WordExpr},
+    witness::{Block, ExecStep, Transaction},
+};
+
+/// Number of bytes of the input of the ecAdd precompile.
+const N_BYTES_INPUT: usize = 128;
+
+/// Number of bits of the number of padding bytes of the input, which is at
+/// most `N_BYTES_INPUT`.
+const N_BITS_PADDING: usize = 8;
+
+/// Gadget of the ecAdd precompile.  The input is the coordinates of 2 points
+/// of the BN254 curve, right-padded with zeros to 128 bytes, and the output is
+/// the coordinates of their sum.  The points, their validity and their sum are
+/// looked up in the EccTable.
+#[derive(Clone, Debug)]
+pub struct EcAddGadget<F> {
+    input_bytes_rlc: Cell<F>,
+    output_bytes_rlc: Cell<F>,
+    is_valid: Cell<F>,
+    input_len: Cell<F>,
+
+    /// Coordinates of the first point
+    p: [Word32Cell<F>; 2],
+    /// Coordinates of the second point
+    q: [Word32Cell<F>; 2],
+    /// Coordinates of the sum
+    r: [Word32Cell<F>; 2],
+    padded_input: PaddedInputGadget<F, N_BITS_PADDING>,
+
+    call_context: PrecompileCallContextGadget<F>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for EcAddGadget<F> {
+    const EXECUTION_STATE: ExecutionState = ExecutionState::PrecompileBn256Add;
+
+    const NAME: &'static str = "BN256_ADD";
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // These cells are constrained by the PrecompileGadget of the call, so
+        // they must be the first ones of their types.
+        let is_valid = cb.query_bool();
+        let input_bytes_rlc = cb.query_cell_phase2();
+        let output_bytes_rlc = cb.query_cell_phase2();
+        let input_len = cb.query_cell();
+
+        let p = [(); 2].map(|_| cb.query_word32());
+        let q = [(); 2].map(|_| cb.query_word32());
+        let r = [(); 2].map(|_| cb.query_word32());
+
+        let call_context = PrecompileCallContextGadget::construct(cb);
+
+        // The input is p_x || p_y || q_x || q_y, so its little-endian bytes
+        // are those of the words in reverse order.
+        let padded_input = PaddedInputGadget::construct(
+            cb,
+            N_BYTES_INPUT,
+            call_context.call_data_length.expr(),
+            input_len.expr(),
+            input_bytes_rlc.expr(),
+            &p.iter()
+                .chain(q.iter())
+                .rev()
+                .flat_map(|word| word.limbs.iter().map(|byte| byte.expr()))
+                .collect_vec(),
+        );
+
+        // The points are valid when they're on the curve, which is the whole
+        // group of the BN254 curve, with coordinates in the base field.  The
+        // insufficient gas is handled in the ErrorOogPrecompile gadget, so the
+        // call fails iff the points are invalid.
+        cb.require_equal(
+            "the call succeeds iff the points are valid",
+            call_context.is_success.expr(),
+            is_valid.expr(),
+        );
+        cb.ecc_table_lookup(
+            PrecompileCalls::Bn128Add.address().expr(),
+            [&p[0], &p[1], &q[0], &q[1]].map(|word| word.to_word()),
+            r.clone().map(|word| word.to_word()),
+            is_valid.expr(),
+        );
+
+        cb.require_equal(
+            "output_bytes_rlc = is_valid * RLC of r_x || r_y",
+            output_bytes_rlc.expr(),
+            is_valid.expr()
+                * rlc::expr(
+                    &r.iter()
+                        .rev()
+                        .flat_map(|word| word.limbs.iter().map(|byte| byte.expr()))
+                        .collect_vec(),
+                    cb.challenges().keccak_input(),
+                ),
+        );
+
+        let gas_cost = call_context.base_gas_cost.expr();
+
+        let restore_context = call_context.restore_context(
+            cb,
+            gas_cost,
+            call_context.is_success.expr() * 0x40.expr(),
+        );
+
+        Self {
+            input_bytes_rlc,
+            output_bytes_rlc,
+            is_valid,
+            input_len,
+            p,
+            q,
+            r,
+            padded_input,
+            call_context,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let aux_data = match &step.aux_data {
+            Some(PrecompileAuxData::EcAdd(aux_data)) => aux_data,
+            _ => {
+                log::error!("missing the aux data of the ecAdd step");
+                return Err(Error::Synthesis);
+            }
+        };
+        let randomness = region.challenges().keccak_input();
+
+        let input_len = self
+            .padded_input
+            .assign(region, offset, call.call_data_length)?;
+        let input_bytes = aux_data
+            .p
+            .iter()
+            .chain(aux_data.q.iter())
+            .flat_map(|word| word.to_be_bytes())
+            .take(input_len as usize)
+            .collect_vec();
+        let output_bytes = if call.is_success {
+            aux_data
+                .r
+                .iter()
+                .flat_map(|word| word.to_be_bytes())
+                .collect_vec()
+        } else {
+            vec![]
+        };
+        self.input_bytes_rlc.assign(
+            region,
+            offset,
+            randomness.map(|randomness| rlc::value(input_bytes.iter().rev(), randomness)),
+        )?;
+        self.output_bytes_rlc.assign(
+            region,
+            offset,
+            randomness.map(|randomness| rlc::value(output_bytes.iter().rev(), randomness)),
+        )?;
+        self.is_valid.assign(
+            region,
+            offset,
+            Value::known(F::from(u64::from(call.is_success))),
+        )?;
+        self.input_len
+            .assign(region, offset, Value::known(F::from(input_len)))?;
+        for (cells, words) in [
+            (&self.p, &aux_data.p),
+            (&self.q, &aux_data.q),
+            (&self.r, &aux_data.r),
+        ] {
+            for (cell, &word) in cells.iter().zip(words.iter()) {
+                cell.assign_u256(region, offset, word)?;
+            }
+        }
+
+        self.call_context.assign(region, offset, block, call)?;
+        self.restore_context
+            .assign(region, offset, block, call, step, N_CALL_CONTEXT_RWS)?;
+
+        Ok(())
+    }
+}
+
+impl<F: Field> PrecompileExecutionGadget<F> for EcAddGadget<F> {
+    const PRECOMPILE: PrecompileCalls = PrecompileCalls::Bn128Add;
+
+    fn constrain_call(cb: &mut EVMConstraintBuilder<F>, call: &PrecompileCallExprs<F>) {
+        let is_valid = cb.query_bool();
+        let input_bytes_rlc = cb.query_cell_phase2();
+        let output_bytes_rlc = cb.query_cell_phase2();
+        let input_len = cb.query_cell();
+        call.constrain_bytes(cb, input_bytes_rlc.expr(), output_bytes_rlc.expr());
+        cb.require_equal(
+            "input length of the precompile call",
+            input_len.expr(),
+            call.input_len.clone(),
+        );
+        cb.require_equal(
+            "ecAdd returns 64 bytes if the points are valid",
+            call.return_length.clone(),
+            is_valid.expr() * 64.expr(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bus_mapping::{
+        evm::{OpcodeId, PrecompileCallArgs},
+        precompile::PrecompileCalls,
+    };
+    use eth_types::{bytecode, word, ToWord};
+    use itertools::Itertools;
+    use mock::TestContext;
+
+    use crate::test_util::CircuitTestBuilder;
+
+    lazy_static::lazy_static! {
+        static ref TEST_VECTOR: Vec<PrecompileCallArgs> = {
+            vec![
+                PrecompileCallArgs {
+                    name: "ecAdd (valid points)",
+                    setup_code: bytecode! {
+                        // place params in memory: the generator twice
+                        PUSH1(0x01)
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(0x02)
+                        PUSH1(0x20)
+                        MSTORE
+                        PUSH1(0x01)
+                        PUSH1(0x40)
+                        MSTORE
+                        PUSH1(0x02)
+                        PUSH1(0x60)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x80.into(),
+                    ret_offset: 0x80.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Bn128Add.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecAdd (different points)",
+                    setup_code: bytecode! {
+                        // place params in memory: the generator and its double
+                        PUSH1(0x01)
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(0x02)
+                        PUSH1(0x20)
+                        MSTORE
+                        PUSH32(word!("0x030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3"))
+                        PUSH1(0x40)
+                        MSTORE
+                        PUSH32(word!("0x15ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4"))
+                        PUSH1(0x60)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x80.into(),
+                    // return only the x coordinate
+                    ret_offset: 0x80.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::Bn128Add.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecAdd (short input)",
+                    setup_code: bytecode! {
+                        // place params in memory: only the generator, the
+                        // second point is the point at infinity
+                        PUSH1(0x01)
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(0x02)
+                        PUSH1(0x20)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x40.into(),
+                    ret_offset: 0x40.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Bn128Add.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecAdd (long input)",
+                    setup_code: bytecode! {
+                        // place params in memory: the generator twice,
+                        // followed by ignored bytes
+                        PUSH1(0x01)
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(0x02)
+                        PUSH1(0x20)
+                        MSTORE
+                        PUSH1(0x01)
+                        PUSH1(0x40)
+                        MSTORE
+                        PUSH1(0x02)
+                        PUSH1(0x60)
+                        MSTORE
+                        PUSH1(0xff)
+                        PUSH1(0x80)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0xa0.into(),
+                    ret_offset: 0xa0.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Bn128Add.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecAdd (no call data)",
+                    setup_code: bytecode! {},
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x00.into(),
+                    ret_offset: 0x00.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Bn128Add.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecAdd (point not on curve)",
+                    setup_code: bytecode! {
+                        // place params in memory: (1, 3) isn't on the curve
+                        PUSH1(0x01)
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(0x03)
+                        PUSH1(0x20)
+                        MSTORE
+                        PUSH1(0x01)
+                        PUSH1(0x40)
+                        MSTORE
+                        PUSH1(0x02)
+                        PUSH1(0x60)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x80.into(),
+                    ret_offset: 0x80.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Bn128Add.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecAdd (coordinate not in the base field)",
+                    setup_code: bytecode! {
+                        // place params in memory: the x coordinate of the
+                        // generator plus the base field modulus
+                        PUSH32(word!("0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd48"))
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(0x02)
+                        PUSH1(0x20)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x80.into(),
+                    ret_offset: 0x80.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Bn128Add.address().to_word(),
+                    ..Default::default()
+                },
+            ]
+        };
+    }
+
+    #[test]
+    fn precompile_ec_add_test() {
+        let call_kinds = vec![
+            OpcodeId::CALL,
+            OpcodeId::STATICCALL,
+            OpcodeId::DELEGATECALL,
+            OpcodeId::CALLCODE,
+        ];
+
+        for (test_vector, &call_kind) in TEST_VECTOR.iter().cartesian_product(&call_kinds) {
+            let bytecode = test_vector.with_call_op(call_kind);
+
+            CircuitTestBuilder::new_from_test_ctx(
+                TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+            )
+            .run();
+        }
+    }
+}
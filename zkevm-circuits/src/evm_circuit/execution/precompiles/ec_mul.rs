@@ -0,0 +1,390 @@
+use bus_mapping::{
+    circuit_input_builder::Call,
+    precompile::{PrecompileAuxData, PrecompileCalls},
+};
+use eth_types::{Field, ToBigEndian};
+use gadgets::util::Expr;
+use halo2_proofs::{circuit::Value, plonk::Error};
+use itertools::Itertools;
+
+use super::{
+    PaddedInputGadget, PrecompileCallContextGadget, PrecompileCallExprs, PrecompileExecutionGadget,
+    N_CALL_CONTEXT_RWS,
+};
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            common_gadget::RestoreContextGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            rlc, CachedRegion, Cell,
+        },
+    },
+    util::word::{Word, Word32Cell, WordExpr},
+    witness::{Block, ExecStep, Transaction},
+};
+
+/// Number of bytes of the input of the ecMul precompile.
+const N_BYTES_INPUT: usize = 96;
+
+/// Number of bits of the number of padding bytes of the input, which is at
+/// most `N_BYTES_INPUT`.
+const N_BITS_PADDING: usize = 7;
+
+/// Gadget of the ecMul precompile.  The input is the coordinates of a point
+/// of the BN254 curve and a scalar, right-padded with zeros to 96 bytes, and
+/// the output is the coordinates of their product.  The point, its validity
+/// and the product are looked up in the EccTable.
+#[derive(Clone, Debug)]
+pub struct EcMulGadget<F> {
+    input_bytes_rlc: Cell<F>,
+    output_bytes_rlc: Cell<F>,
+    is_valid: Cell<F>,
+    input_len: Cell<F>,
+
+    /// Coordinates of the point
+    p: [Word32Cell<F>; 2],
+    /// Scalar by which the point is multiplied
+    s: Word32Cell<F>,
+    /// Coordinates of the product
+    r: [Word32Cell<F>; 2],
+    padded_input: PaddedInputGadget<F, N_BITS_PADDING>,
+
+    call_context: PrecompileCallContextGadget<F>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for EcMulGadget<F> {
+    const EXECUTION_STATE: ExecutionState = ExecutionState::PrecompileBn256ScalarMul;
+
+    const NAME: &'static str = "BN256_SCALAR_MUL";
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // These cells are constrained by the PrecompileGadget of the call, so
+        // they must be the first ones of their types.
+        let is_valid = cb.query_bool();
+        let input_bytes_rlc = cb.query_cell_phase2();
+        let output_bytes_rlc = cb.query_cell_phase2();
+        let input_len = cb.query_cell();
+
+        let p = [(); 2].map(|_| cb.query_word32());
+        let s = cb.query_word32();
+        let r = [(); 2].map(|_| cb.query_word32());
+
+        let call_context = PrecompileCallContextGadget::construct(cb);
+
+        // The input is p_x || p_y || s, so its little-endian bytes are those
+        // of the words in reverse order.
+        let padded_input = PaddedInputGadget::construct(
+            cb,
+            N_BYTES_INPUT,
+            call_context.call_data_length.expr(),
+            input_len.expr(),
+            input_bytes_rlc.expr(),
+            &p.iter()
+                .chain([&s])
+                .rev()
+                .flat_map(|word| word.limbs.iter().map(|byte| byte.expr()))
+                .collect_vec(),
+        );
+
+        // As for ecAdd, the call fails iff the point is invalid, while any
+        // scalar is valid.
+        cb.require_equal(
+            "the call succeeds iff the point is valid",
+            call_context.is_success.expr(),
+            is_valid.expr(),
+        );
+        cb.ecc_table_lookup(
+            PrecompileCalls::Bn128Mul.address().expr(),
+            [p[0].to_word(), p[1].to_word(), s.to_word(), Word::zero()],
+            r.clone().map(|word| word.to_word()),
+            is_valid.expr(),
+        );
+
+        cb.require_equal(
+            "output_bytes_rlc = is_valid * RLC of r_x || r_y",
+            output_bytes_rlc.expr(),
+            is_valid.expr()
+                * rlc::expr(
+                    &r.iter()
+                        .rev()
+                        .flat_map(|word| word.limbs.iter().map(|byte| byte.expr()))
+                        .collect_vec(),
+                    cb.challenges().keccak_input(),
+                ),
+        );
+
+        let gas_cost = call_context.base_gas_cost.expr();
+
+        let restore_context = call_context.restore_context(
+            cb,
+            gas_cost,
+            call_context.is_success.expr() * 0x40.expr(),
+        );
+
+        Self {
+            input_bytes_rlc,
+            output_bytes_rlc,
+            is_valid,
+            input_len,
+            p,
+            s,
+            r,
+            padded_input,
+            call_context,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let aux_data = match &step.aux_data {
+            Some(PrecompileAuxData::EcMul(aux_data)) => aux_data,
+            _ => {
+                log::error!("missing the aux data of the ecMul step");
+                return Err(Error::Synthesis);
+            }
+        };
+        let randomness = region.challenges().keccak_input();
+
+        let input_len = self
+            .padded_input
+            .assign(region, offset, call.call_data_length)?;
+        let input_bytes = aux_data
+            .p
+            .iter()
+            .chain([&aux_data.s])
+            .flat_map(|word| word.to_be_bytes())
+            .take(input_len as usize)
+            .collect_vec();
+        let output_bytes = if call.is_success {
+            aux_data
+                .r
+                .iter()
+                .flat_map(|word| word.to_be_bytes())
+                .collect_vec()
+        } else {
+            vec![]
+        };
+        self.input_bytes_rlc.assign(
+            region,
+            offset,
+            randomness.map(|randomness| rlc::value(input_bytes.iter().rev(), randomness)),
+        )?;
+        self.output_bytes_rlc.assign(
+            region,
+            offset,
+            randomness.map(|randomness| rlc::value(output_bytes.iter().rev(), randomness)),
+        )?;
+        self.is_valid.assign(
+            region,
+            offset,
+            Value::known(F::from(u64::from(call.is_success))),
+        )?;
+        self.input_len
+            .assign(region, offset, Value::known(F::from(input_len)))?;
+        for (cell, &word) in self.p.iter().chain([&self.s]).chain(self.r.iter()).zip(
+            aux_data
+                .p
+                .iter()
+                .chain([&aux_data.s])
+                .chain(aux_data.r.iter()),
+        ) {
+            cell.assign_u256(region, offset, word)?;
+        }
+
+        self.call_context.assign(region, offset, block, call)?;
+        self.restore_context
+            .assign(region, offset, block, call, step, N_CALL_CONTEXT_RWS)?;
+
+        Ok(())
+    }
+}
+
+impl<F: Field> PrecompileExecutionGadget<F> for EcMulGadget<F> {
+    const PRECOMPILE: PrecompileCalls = PrecompileCalls::Bn128Mul;
+
+    fn constrain_call(cb: &mut EVMConstraintBuilder<F>, call: &PrecompileCallExprs<F>) {
+        let is_valid = cb.query_bool();
+        let input_bytes_rlc = cb.query_cell_phase2();
+        let output_bytes_rlc = cb.query_cell_phase2();
+        let input_len = cb.query_cell();
+        call.constrain_bytes(cb, input_bytes_rlc.expr(), output_bytes_rlc.expr());
+        cb.require_equal(
+            "input length of the precompile call",
+            input_len.expr(),
+            call.input_len.clone(),
+        );
+        cb.require_equal(
+            "ecMul returns 64 bytes if the point is valid",
+            call.return_length.clone(),
+            is_valid.expr() * 64.expr(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bus_mapping::{
+        evm::{OpcodeId, PrecompileCallArgs},
+        precompile::PrecompileCalls,
+    };
+    use eth_types::{bytecode, word, ToWord};
+    use itertools::Itertools;
+    use mock::TestContext;
+
+    use crate::test_util::CircuitTestBuilder;
+
+    lazy_static::lazy_static! {
+        static ref TEST_VECTOR: Vec<PrecompileCallArgs> = {
+            vec![
+                PrecompileCallArgs {
+                    name: "ecMul (valid point)",
+                    setup_code: bytecode! {
+                        // place params in memory: the generator and 2
+                        PUSH1(0x01)
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(0x02)
+                        PUSH1(0x20)
+                        MSTORE
+                        PUSH1(0x02)
+                        PUSH1(0x40)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x60.into(),
+                    ret_offset: 0x60.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Bn128Mul.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecMul (scalar larger than the group order)",
+                    setup_code: bytecode! {
+                        // place params in memory: the generator and 2^256 - 1
+                        PUSH1(0x01)
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(0x02)
+                        PUSH1(0x20)
+                        MSTORE
+                        PUSH32(word!("0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"))
+                        PUSH1(0x40)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x60.into(),
+                    // return only the y coordinate
+                    ret_offset: 0x60.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::Bn128Mul.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecMul (short input)",
+                    setup_code: bytecode! {
+                        // place params in memory: only the generator, so the
+                        // scalar is 0
+                        PUSH1(0x01)
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(0x02)
+                        PUSH1(0x20)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x40.into(),
+                    ret_offset: 0x40.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Bn128Mul.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecMul (long input)",
+                    setup_code: bytecode! {
+                        // place params in memory: the generator and 3,
+                        // followed by ignored bytes
+                        PUSH1(0x01)
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(0x02)
+                        PUSH1(0x20)
+                        MSTORE
+                        PUSH1(0x03)
+                        PUSH1(0x40)
+                        MSTORE
+                        PUSH1(0xff)
+                        PUSH1(0x60)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x80.into(),
+                    ret_offset: 0x80.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Bn128Mul.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecMul (no call data)",
+                    setup_code: bytecode! {},
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x00.into(),
+                    ret_offset: 0x00.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Bn128Mul.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecMul (point not on curve)",
+                    setup_code: bytecode! {
+                        // place params in memory: (1, 3) isn't on the curve
+                        PUSH1(0x01)
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(0x03)
+                        PUSH1(0x20)
+                        MSTORE
+                        PUSH1(0x02)
+                        PUSH1(0x40)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x60.into(),
+                    ret_offset: 0x60.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Bn128Mul.address().to_word(),
+                    ..Default::default()
+                },
+            ]
+        };
+    }
+
+    #[test]
+    fn precompile_ec_mul_test() {
+        let call_kinds = vec![
+            OpcodeId::CALL,
+            OpcodeId::STATICCALL,
+            OpcodeId::DELEGATECALL,
+            OpcodeId::CALLCODE,
+        ];
+
+        for (test_vector, &call_kind) in TEST_VECTOR.iter().cartesian_product(&call_kinds) {
+            let bytecode = test_vector.with_call_op(call_kind);
+
+            CircuitTestBuilder::new_from_test_ctx(
+                TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+            )
+            .run();
+        }
+    }
+}
@@ -0,0 +1,615 @@
+use bus_mapping::{
+    circuit_input_builder::Call,
+    precompile::{PrecompileAuxData, PrecompileCalls},
+};
+use eth_types::{sign_types::SECP256K1_Q, Field, ToBigEndian, Word, U256};
+use gadgets::util::{and, not, sum, Expr};
+use halo2_proofs::{
+    circuit::Value,
+    plonk::{Error, Expression},
+};
+use itertools::Itertools;
+
+use super::{
+    PrecompileCallContextGadget, PrecompileCallExprs, PrecompileExecutionGadget, N_CALL_CONTEXT_RWS,
+};
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::N_BYTES_MEMORY_ADDRESS,
+        step::ExecutionState,
+        util::{
+            common_gadget::RestoreContextGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::{IsEqualWordGadget, IsZeroWordGadget, LtGadget, LtWordGadget},
+            pow_of_two_expr, rlc, split_u256, AccountAddress, CachedRegion, Cell,
+        },
+    },
+    util::word::{self, Word32Cell, WordExpr},
+    witness::{Block, ExecStep, Transaction},
+};
+
+/// Number of bytes of the input of ecrecover: msg_hash, v, r and s.
+const N_BYTES_INPUT: usize = 128;
+
+/// Number of bits of the number of padding bytes, which is at most
+/// `N_BYTES_INPUT`.
+const N_BITS_PADDING: usize = 8;
+
+/// Return the order of secp256k1.
+fn secp256k1_n() -> Word {
+    Word::from_big_endian(&SECP256K1_Q.to_bytes_be())
+}
+
+/// Gadget of the ecrecover precompile.  An address can only be recovered from
+/// a signature with v in {27, 28} and r, s in [1, n), and the recovery is
+/// looked up in the SigTable.  The call returns nothing for any other input,
+/// but a signature in that range from which no public key can be recovered
+/// can't be proven yet.
+#[derive(Clone, Debug)]
+pub struct EcrecoverGadget<F> {
+    input_bytes_rlc: Cell<F>,
+    output_bytes_rlc: Cell<F>,
+    recovered: Cell<F>,
+    input_len: Cell<F>,
+
+    /// Input with which the call is made, right-padded with zeros
+    msg_hash_raw: Word32Cell<F>,
+    sig_v: Word32Cell<F>,
+    sig_r: Word32Cell<F>,
+    sig_s: Word32Cell<F>,
+    recovered_addr: AccountAddress<F>,
+
+    /// msg_hash_raw reduced modulo n, as it's stored in the SigTable
+    msg_hash: Word32Cell<F>,
+    msg_hash_overflow: Cell<F>,
+    msg_hash_carry: Cell<F>,
+    msg_hash_lt_n: LtWordGadget<F>,
+
+    sig_v_is_27: IsEqualWordGadget<F, Word32Cell<F>, word::Word<Expression<F>>>,
+    sig_v_is_28: IsEqualWordGadget<F, Word32Cell<F>, word::Word<Expression<F>>>,
+    sig_r_is_zero: IsZeroWordGadget<F, Word32Cell<F>>,
+    sig_s_is_zero: IsZeroWordGadget<F, Word32Cell<F>>,
+    sig_r_lt_n: LtWordGadget<F>,
+    sig_s_lt_n: LtWordGadget<F>,
+
+    /// Bits of the number of padding bytes, and the powers of the randomness
+    /// by the partial sums of the bits
+    input_is_short: LtGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    padding_bits: [Cell<F>; N_BITS_PADDING],
+    padding_pows: [Cell<F>; N_BITS_PADDING],
+
+    call_context: PrecompileCallContextGadget<F>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for EcrecoverGadget<F> {
+    const EXECUTION_STATE: ExecutionState = ExecutionState::PrecompileEcRecover;
+
+    const NAME: &'static str = "ECRECOVER";
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // These cells are constrained by the PrecompileGadget of the call, so
+        // they must be the first ones of their types.
+        let input_bytes_rlc = cb.query_cell_phase2();
+        let output_bytes_rlc = cb.query_cell_phase2();
+        let recovered = cb.query_bool();
+        let input_len = cb.query_cell();
+
+        let msg_hash_raw = cb.query_word32();
+        let sig_v = cb.query_word32();
+        let sig_r = cb.query_word32();
+        let sig_s = cb.query_word32();
+        let recovered_addr = cb.query_account_address();
+        let msg_hash = cb.query_word32();
+        let msg_hash_overflow = cb.query_bool();
+        let msg_hash_carry = cb.query_bool();
+        let padding_bits = [(); N_BITS_PADDING].map(|_| cb.query_bool());
+        let padding_pows = [(); N_BITS_PADDING].map(|_| cb.query_cell_phase2());
+
+        let call_context = PrecompileCallContextGadget::construct(cb);
+
+        let randomness = cb.challenges().keccak_input();
+
+        // The input is the first min(call_data_length, 128) bytes of the call
+        // data, right-padded with zeros to 128 bytes.
+        let input_is_short = LtGadget::construct(
+            cb,
+            call_context.call_data_length.expr(),
+            N_BYTES_INPUT.expr(),
+        );
+        let padding = sum::expr(
+            padding_bits
+                .iter()
+                .enumerate()
+                .map(|(i, bit)| bit.expr() * (1 << i).expr()),
+        );
+        cb.require_equal(
+            "padding = 128 - min(call_data_length, 128)",
+            padding.expr(),
+            input_is_short.expr() * (N_BYTES_INPUT.expr() - call_context.call_data_length.expr()),
+        );
+        cb.require_equal(
+            "input_len = min(call_data_length, 128)",
+            input_len.expr(),
+            N_BYTES_INPUT.expr() - padding,
+        );
+        // Without call data, the input isn't copied and its RLC must be 0.
+        cb.require_zero(
+            "input_bytes_rlc is 0 when there's no call data",
+            input_bytes_rlc.expr() * padding_bits[N_BITS_PADDING - 1].expr(),
+        );
+
+        // padding_pows[i] = randomness^(sum of the first i + 1 padding bits)
+        let mut randomness_pow = randomness.clone();
+        for (i, (bit, pow)) in padding_bits.iter().zip(padding_pows.iter()).enumerate() {
+            let pow_prev = if i == 0 {
+                1.expr()
+            } else {
+                padding_pows[i - 1].expr()
+            };
+            cb.require_equal(
+                "padding_pows[i] = padding_pows[i - 1] * randomness^(bit * 2^i)",
+                pow.expr(),
+                pow_prev * (1.expr() + bit.expr() * (randomness_pow.clone() - 1.expr())),
+            );
+            randomness_pow = randomness_pow.clone() * randomness_pow;
+        }
+        let padded_input_le = [&sig_s, &sig_r, &sig_v, &msg_hash_raw]
+            .iter()
+            .flat_map(|word| word.limbs.iter().map(|byte| byte.expr()))
+            .collect_vec();
+        cb.require_equal(
+            "input_bytes_rlc * randomness^padding = RLC of the padded input",
+            input_bytes_rlc.expr() * padding_pows[N_BITS_PADDING - 1].expr(),
+            rlc::expr(&padded_input_le, randomness.clone()),
+        );
+
+        // msg_hash = msg_hash_raw mod n, with msg_hash_raw < 2^256 < 2n.
+        let n = word::Word::from(secp256k1_n()).map(Expression::Constant);
+        let (n_lo, n_hi) = n.to_lo_hi();
+        let (msg_hash_raw_lo, msg_hash_raw_hi) = msg_hash_raw.to_word().to_lo_hi();
+        let (msg_hash_lo, msg_hash_hi) = msg_hash.to_word().to_lo_hi();
+        cb.require_equal(
+            "msg_hash_raw_lo + carry * 2^128 = msg_hash_lo + overflow * n_lo",
+            msg_hash_raw_lo + msg_hash_carry.expr() * pow_of_two_expr(128),
+            msg_hash_lo + msg_hash_overflow.expr() * n_lo,
+        );
+        cb.require_equal(
+            "msg_hash_raw_hi = msg_hash_hi + overflow * n_hi + carry",
+            msg_hash_raw_hi,
+            msg_hash_hi + msg_hash_overflow.expr() * n_hi + msg_hash_carry.expr(),
+        );
+        let msg_hash_lt_n = LtWordGadget::construct(cb, &msg_hash.to_word(), &n);
+        cb.require_equal("msg_hash < n", msg_hash_lt_n.expr(), 1.expr());
+
+        let sig_v_is_27 =
+            IsEqualWordGadget::construct(cb, &sig_v, &word::Word::from_lo_unchecked(27.expr()));
+        let sig_v_is_28 =
+            IsEqualWordGadget::construct(cb, &sig_v, &word::Word::from_lo_unchecked(28.expr()));
+        let sig_r_is_zero = IsZeroWordGadget::construct(cb, &sig_r);
+        let sig_s_is_zero = IsZeroWordGadget::construct(cb, &sig_s);
+        let sig_r_lt_n = LtWordGadget::construct(cb, &sig_r.to_word(), &n);
+        let sig_s_lt_n = LtWordGadget::construct(cb, &sig_s.to_word(), &n);
+        cb.require_equal(
+            "an address is recovered iff v in {27, 28} and r, s in [1, n)",
+            recovered.expr(),
+            and::expr([
+                sig_v_is_27.expr() + sig_v_is_28.expr(),
+                not::expr(sig_r_is_zero.expr()),
+                not::expr(sig_s_is_zero.expr()),
+                sig_r_lt_n.expr(),
+                sig_s_lt_n.expr(),
+            ]),
+        );
+
+        cb.condition(recovered.expr(), |cb| {
+            cb.sig_table_lookup(
+                msg_hash.to_word(),
+                sig_v.limbs[0].expr() - 27.expr(),
+                sig_r.to_word(),
+                sig_s.to_word(),
+                recovered_addr.to_word(),
+                1.expr(),
+            );
+        });
+
+        // The output is the address left-padded with zeros to 32 bytes, or
+        // nothing.
+        cb.require_equal(
+            "output_bytes_rlc = recovered * RLC of the left-padded address",
+            output_bytes_rlc.expr(),
+            recovered.expr()
+                * rlc::expr(
+                    &recovered_addr.limbs.clone().map(|byte| byte.expr()),
+                    randomness,
+                ),
+        );
+
+        let gas_cost = call_context.base_gas_cost.expr();
+
+        // As for Identity, the only failure is the insufficient gas, which is
+        // handled in the ErrorOogPrecompile gadget.
+        let restore_context =
+            call_context.restore_context(cb, gas_cost, recovered.expr() * 0x20.expr());
+
+        Self {
+            input_bytes_rlc,
+            output_bytes_rlc,
+            recovered,
+            input_len,
+            msg_hash_raw,
+            sig_v,
+            sig_r,
+            sig_s,
+            recovered_addr,
+            msg_hash,
+            msg_hash_overflow,
+            msg_hash_carry,
+            msg_hash_lt_n,
+            sig_v_is_27,
+            sig_v_is_28,
+            sig_r_is_zero,
+            sig_s_is_zero,
+            sig_r_lt_n,
+            sig_s_lt_n,
+            input_is_short,
+            padding_bits,
+            padding_pows,
+            call_context,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let aux_data = match &step.aux_data {
+            Some(PrecompileAuxData::Ecrecover(aux_data)) => aux_data,
+            _ => {
+                log::error!("missing the aux data of the ecrecover step");
+                return Err(Error::Synthesis);
+            }
+        };
+        let n = secp256k1_n();
+        let recovered = !aux_data.recovered_addr.is_zero();
+        let randomness = region.challenges().keccak_input();
+
+        let input_len = call.call_data_length.min(N_BYTES_INPUT as u64);
+        let padding = N_BYTES_INPUT as u64 - input_len;
+        let padded_input = [
+            aux_data.msg_hash,
+            aux_data.sig_v,
+            aux_data.sig_r,
+            aux_data.sig_s,
+        ]
+        .iter()
+        .flat_map(|word| word.to_be_bytes())
+        .collect_vec();
+        let input_bytes_rlc = randomness.map(|randomness| {
+            rlc::value(padded_input[..input_len as usize].iter().rev(), randomness)
+        });
+        let output_bytes_rlc = randomness.map(|randomness| {
+            if recovered {
+                rlc::value(aux_data.recovered_addr.as_bytes().iter().rev(), randomness)
+            } else {
+                F::ZERO
+            }
+        });
+        self.input_bytes_rlc
+            .assign(region, offset, input_bytes_rlc)?;
+        self.output_bytes_rlc
+            .assign(region, offset, output_bytes_rlc)?;
+        self.recovered
+            .assign(region, offset, Value::known(F::from(recovered as u64)))?;
+        self.input_len
+            .assign(region, offset, Value::known(F::from(input_len)))?;
+
+        self.msg_hash_raw
+            .assign_u256(region, offset, aux_data.msg_hash)?;
+        self.sig_v.assign_u256(region, offset, aux_data.sig_v)?;
+        self.sig_r.assign_u256(region, offset, aux_data.sig_r)?;
+        self.sig_s.assign_u256(region, offset, aux_data.sig_s)?;
+        self.recovered_addr
+            .assign_h160(region, offset, aux_data.recovered_addr)?;
+
+        let msg_hash_overflow = aux_data.msg_hash >= n;
+        let msg_hash = if msg_hash_overflow {
+            aux_data.msg_hash - n
+        } else {
+            aux_data.msg_hash
+        };
+        let (msg_hash_lo, _) = split_u256(&msg_hash);
+        let (n_lo, _) = split_u256(&n);
+        let (_, msg_hash_carry) = msg_hash_lo.as_u128().overflowing_add(if msg_hash_overflow {
+            n_lo.as_u128()
+        } else {
+            0
+        });
+        self.msg_hash.assign_u256(region, offset, msg_hash)?;
+        self.msg_hash_overflow.assign(
+            region,
+            offset,
+            Value::known(F::from(msg_hash_overflow as u64)),
+        )?;
+        self.msg_hash_carry
+            .assign(region, offset, Value::known(F::from(msg_hash_carry as u64)))?;
+        self.msg_hash_lt_n.assign(region, offset, msg_hash, n)?;
+
+        self.sig_v_is_27
+            .assign_u256(region, offset, aux_data.sig_v, U256::from(27))?;
+        self.sig_v_is_28
+            .assign_u256(region, offset, aux_data.sig_v, U256::from(28))?;
+        self.sig_r_is_zero
+            .assign_u256(region, offset, aux_data.sig_r)?;
+        self.sig_s_is_zero
+            .assign_u256(region, offset, aux_data.sig_s)?;
+        self.sig_r_lt_n.assign(region, offset, aux_data.sig_r, n)?;
+        self.sig_s_lt_n.assign(region, offset, aux_data.sig_s, n)?;
+
+        self.input_is_short.assign(
+            region,
+            offset,
+            F::from(call.call_data_length),
+            F::from(N_BYTES_INPUT as u64),
+        )?;
+        for (i, (bit, pow)) in self
+            .padding_bits
+            .iter()
+            .zip(self.padding_pows.iter())
+            .enumerate()
+        {
+            bit.assign(region, offset, Value::known(F::from((padding >> i) & 1)))?;
+            let exponent = padding & ((1 << (i + 1)) - 1);
+            pow.assign(
+                region,
+                offset,
+                randomness.map(|randomness| randomness.pow([exponent, 0, 0, 0])),
+            )?;
+        }
+
+        self.call_context.assign(region, offset, block, call)?;
+        self.restore_context
+            .assign(region, offset, block, call, step, N_CALL_CONTEXT_RWS)?;
+
+        Ok(())
+    }
+}
+
+impl<F: Field> PrecompileExecutionGadget<F> for EcrecoverGadget<F> {
+    const PRECOMPILE: PrecompileCalls = PrecompileCalls::ECRecover;
+
+    fn constrain_call(cb: &mut EVMConstraintBuilder<F>, call: &PrecompileCallExprs<F>) {
+        let input_bytes_rlc = cb.query_cell_phase2();
+        let output_bytes_rlc = cb.query_cell_phase2();
+        let recovered = cb.query_bool();
+        let input_len = cb.query_cell();
+        call.constrain_bytes(cb, input_bytes_rlc.expr(), output_bytes_rlc.expr());
+        cb.require_equal(
+            "input length of the precompile call",
+            input_len.expr(),
+            call.input_len.clone(),
+        );
+        cb.require_equal(
+            "ecrecover returns 32 bytes if an address is recovered",
+            call.return_length.clone(),
+            recovered.expr() * 32.expr(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bus_mapping::{
+        evm::{OpcodeId, PrecompileCallArgs},
+        precompile::PrecompileCalls,
+    };
+    use eth_types::{bytecode, word, ToWord};
+    use itertools::Itertools;
+    use mock::TestContext;
+
+    use crate::test_util::CircuitTestBuilder;
+
+    lazy_static::lazy_static! {
+        static ref TEST_VECTOR: Vec<PrecompileCallArgs> = {
+            vec![
+                PrecompileCallArgs {
+                    name: "ecrecover (valid sig, addr recovered)",
+                    setup_code: bytecode! {
+                        // msg_hash
+                        PUSH32(word!("0x456e9aea5e197a1f1af7a3e85a3212fa4049a3ba34c2289b4c860fc0b0c64ef3"))
+                        PUSH1(0x00)
+                        MSTORE
+                        // v
+                        PUSH1(28)
+                        PUSH1(0x20)
+                        MSTORE
+                        // r
+                        PUSH32(word!("0x9242685bf161793cc25603c231bc2f568eb630ea16aa137d2664ac8038825608"))
+                        PUSH1(0x40)
+                        MSTORE
+                        // s
+                        PUSH32(word!("0x4f8ae3bd7535248d0bd448298cc2e2071e56992d0774dc340c368ae950852ada"))
+                        PUSH1(0x60)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x80.into(),
+                    ret_offset: 0x80.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::ECRecover.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecrecover (overflowing msg_hash)",
+                    setup_code: bytecode! {
+                        // msg_hash
+                        PUSH32(word!("0xff00000000000000000000000000000000000000000000000000000000000000"))
+                        PUSH1(0x00)
+                        MSTORE
+                        // v
+                        PUSH1(28)
+                        PUSH1(0x20)
+                        MSTORE
+                        // r
+                        PUSH32(word!("0x9242685bf161793cc25603c231bc2f568eb630ea16aa137d2664ac8038825608"))
+                        PUSH1(0x40)
+                        MSTORE
+                        // s
+                        PUSH32(word!("0x4f8ae3bd7535248d0bd448298cc2e2071e56992d0774dc340c368ae950852ada"))
+                        PUSH1(0x60)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x80.into(),
+                    ret_offset: 0x80.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::ECRecover.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecrecover (more call data than the input)",
+                    setup_code: bytecode! {
+                        // msg_hash
+                        PUSH32(word!("0x456e9aea5e197a1f1af7a3e85a3212fa4049a3ba34c2289b4c860fc0b0c64ef3"))
+                        PUSH1(0x00)
+                        MSTORE
+                        // v
+                        PUSH1(28)
+                        PUSH1(0x20)
+                        MSTORE
+                        // r
+                        PUSH32(word!("0x9242685bf161793cc25603c231bc2f568eb630ea16aa137d2664ac8038825608"))
+                        PUSH1(0x40)
+                        MSTORE
+                        // s
+                        PUSH32(word!("0x4f8ae3bd7535248d0bd448298cc2e2071e56992d0774dc340c368ae950852ada"))
+                        PUSH1(0x60)
+                        MSTORE
+                        // trailing call data, which is ignored
+                        PUSH1(0xff)
+                        PUSH1(0x80)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0xa0.into(),
+                    ret_offset: 0xa0.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::ECRecover.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecrecover (invalid v)",
+                    setup_code: bytecode! {
+                        // msg_hash
+                        PUSH32(word!("0x456e9aea5e197a1f1af7a3e85a3212fa4049a3ba34c2289b4c860fc0b0c64ef3"))
+                        PUSH1(0x00)
+                        MSTORE
+                        // v
+                        PUSH1(29)
+                        PUSH1(0x20)
+                        MSTORE
+                        // r
+                        PUSH32(word!("0x9242685bf161793cc25603c231bc2f568eb630ea16aa137d2664ac8038825608"))
+                        PUSH1(0x40)
+                        MSTORE
+                        // s
+                        PUSH32(word!("0x4f8ae3bd7535248d0bd448298cc2e2071e56992d0774dc340c368ae950852ada"))
+                        PUSH1(0x60)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x80.into(),
+                    ret_offset: 0x80.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::ECRecover.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecrecover (r = 0)",
+                    setup_code: bytecode! {
+                        // msg_hash
+                        PUSH32(word!("0x456e9aea5e197a1f1af7a3e85a3212fa4049a3ba34c2289b4c860fc0b0c64ef3"))
+                        PUSH1(0x00)
+                        MSTORE
+                        // v
+                        PUSH1(28)
+                        PUSH1(0x20)
+                        MSTORE
+                        // s
+                        PUSH32(word!("0x4f8ae3bd7535248d0bd448298cc2e2071e56992d0774dc340c368ae950852ada"))
+                        PUSH1(0x60)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x80.into(),
+                    ret_offset: 0x80.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::ECRecover.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecrecover (short call data, v = 0)",
+                    setup_code: bytecode! {
+                        // msg_hash
+                        PUSH32(word!("0x456e9aea5e197a1f1af7a3e85a3212fa4049a3ba34c2289b4c860fc0b0c64ef3"))
+                        PUSH1(0x00)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x30.into(),
+                    ret_offset: 0x80.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::ECRecover.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecrecover (no call data)",
+                    setup_code: bytecode! {},
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x00.into(),
+                    ret_offset: 0x80.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::ECRecover.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecrecover (insufficient gas)",
+                    setup_code: bytecode! {},
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x80.into(),
+                    ret_offset: 0x80.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::ECRecover.address().to_word(),
+                    gas: 2999.into(),
+                    ..Default::default()
+                },
+            ]
+        };
+    }
+
+    #[test]
+    fn precompile_ecrecover_test() {
+        let call_kinds = vec![
+            OpcodeId::CALL,
+            OpcodeId::STATICCALL,
+            OpcodeId::DELEGATECALL,
+            OpcodeId::CALLCODE,
+        ];
+
+        for (test_vector, &call_kind) in TEST_VECTOR.iter().cartesian_product(&call_kinds) {
+            let bytecode = test_vector.with_call_op(call_kind);
+
+            CircuitTestBuilder::new_from_test_ctx(
+                TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+            )
+            .run();
+        }
+    }
+}
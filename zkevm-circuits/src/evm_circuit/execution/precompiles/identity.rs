@@ -1,32 +1,30 @@
-use bus_mapping::circuit_input_builder::Call;
-use eth_types::{evm_types::GasCost, Field, ToScalar};
-use gadgets::util::{select, Expr};
-use halo2_proofs::{circuit::Value, plonk::Error};
+use bus_mapping::{circuit_input_builder::Call, precompile::PrecompileCalls};
+use eth_types::Field;
+use gadgets::util::Expr;
+use halo2_proofs::plonk::Error;
 
+use super::{
+    PrecompileCallContextGadget, PrecompileCallExprs, PrecompileExecutionGadget, N_CALL_CONTEXT_RWS,
+};
 use crate::{
     evm_circuit::{
         execution::ExecutionGadget,
         param::{N_BYTES_MEMORY_WORD_SIZE, N_BYTES_WORD},
         step::ExecutionState,
         util::{
-            common_gadget::RestoreContextGadget, constraint_builder::EVMConstraintBuilder,
-            math_gadget::ConstantDivisionGadget, CachedRegion, Cell,
+            common_gadget::RestoreContextGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::ConstantDivisionGadget,
+            CachedRegion,
         },
     },
-    table::CallContextFieldTag,
     witness::{Block, ExecStep, Transaction},
 };
 
 #[derive(Clone, Debug)]
 pub struct IdentityGadget<F> {
     input_word_size: ConstantDivisionGadget<F, N_BYTES_MEMORY_WORD_SIZE>,
-    is_success: Cell<F>,
-    callee_address: Cell<F>,
-    caller_id: Cell<F>,
-    call_data_offset: Cell<F>,
-    call_data_length: Cell<F>,
-    return_data_offset: Cell<F>,
-    return_data_length: Cell<F>,
+    call_context: PrecompileCallContextGadget<F>,
     restore_context: RestoreContextGadget<F>,
 }
 
@@ -36,66 +34,25 @@ impl<F: Field> ExecutionGadget<F> for IdentityGadget<F> {
     const NAME: &'static str = "IDENTITY";
 
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
-        let [is_success, callee_address, caller_id, call_data_offset, call_data_length, return_data_offset, return_data_length] =
-            [
-                CallContextFieldTag::IsSuccess,
-                CallContextFieldTag::CalleeAddress,
-                CallContextFieldTag::CallerId,
-                CallContextFieldTag::CallDataOffset,
-                CallContextFieldTag::CallDataLength,
-                CallContextFieldTag::ReturnDataOffset,
-                CallContextFieldTag::ReturnDataLength,
-            ]
-            .map(|tag| cb.call_context(None, tag));
+        let call_context = PrecompileCallContextGadget::construct(cb);
 
         let input_word_size = ConstantDivisionGadget::construct(
             cb,
-            call_data_length.expr() + (N_BYTES_WORD - 1).expr(),
+            call_context.call_data_length.expr() + (N_BYTES_WORD - 1).expr(),
             N_BYTES_WORD as u64,
         );
 
-        let gas_cost = select::expr(
-            is_success.expr(),
-            GasCost::PRECOMPILE_IDENTITY_BASE.expr()
-                + input_word_size.quotient() * GasCost::PRECOMPILE_IDENTITY_PER_WORD.expr(),
-            cb.curr.state.gas_left.expr(),
-        );
-
-        cb.precompile_info_lookup(
-            cb.execution_state().as_u64().expr(),
-            callee_address.expr(),
-            cb.execution_state().precompile_base_gas_cost().expr(),
-        );
+        let gas_cost = call_context.gas_cost(input_word_size.quotient());
 
         // In the case of Identity precompile, the only failure is in the case of insufficient gas
         // for the call, which is diverted and handled in the ErrorOogPrecompile gadget.
-
-        // A separate select statement is not added here, as we expect execution that's verified
-        // under this specific gadget to always succeed.
-        let restore_context = RestoreContextGadget::construct2(
-            cb,
-            is_success.expr(),
-            gas_cost.expr(),
-            0.expr(),
-            0x00.expr(), // ReturnDataOffset
-            // note: In the case of `Identity` precompile, the only failure is in the case of
-            // insufficient gas for the call, which is diverted to `ErrorOogPrecompile`
-            // gadget. Therefore, `call_data_length` can be safely put here without
-            // conditionals.
-            call_data_length.expr(), // ReturnDataLength
-            0.expr(),
-            0.expr(),
-        );
+        // Therefore, `call_data_length` can be safely returned without conditionals.
+        let restore_context =
+            call_context.restore_context(cb, gas_cost, call_context.call_data_length.expr());
 
         Self {
             input_word_size,
-            is_success,
-            callee_address,
-            caller_id,
-            call_data_offset,
-            call_data_length,
-            return_data_offset,
-            return_data_length,
+            call_context,
             restore_context,
         }
     }
@@ -114,48 +71,26 @@ impl<F: Field> ExecutionGadget<F> for IdentityGadget<F> {
             offset,
             (call.call_data_length + (N_BYTES_WORD as u64) - 1).into(),
         )?;
-        self.is_success.assign(
-            region,
-            offset,
-            Value::known(F::from(u64::from(call.is_success))),
-        )?;
-        self.callee_address.assign(
-            region,
-            offset,
-            Value::known(call.code_address().unwrap().to_scalar().unwrap()),
-        )?;
-        self.caller_id.assign(
-            region,
-            offset,
-            Value::known(F::from(call.caller_id.try_into().unwrap())),
-        )?;
-        self.call_data_offset.assign(
-            region,
-            offset,
-            Value::known(F::from(call.call_data_offset)),
-        )?;
-        self.call_data_length.assign(
-            region,
-            offset,
-            Value::known(F::from(call.call_data_length)),
-        )?;
-        self.return_data_offset.assign(
-            region,
-            offset,
-            Value::known(F::from(call.return_data_offset)),
-        )?;
-        self.return_data_length.assign(
-            region,
-            offset,
-            Value::known(F::from(call.return_data_length)),
-        )?;
+        self.call_context.assign(region, offset, block, call)?;
         self.restore_context
-            .assign(region, offset, block, call, step, 7)?;
+            .assign(region, offset, block, call, step, N_CALL_CONTEXT_RWS)?;
 
         Ok(())
     }
 }
 
+impl<F: Field> PrecompileExecutionGadget<F> for IdentityGadget<F> {
+    const PRECOMPILE: PrecompileCalls = PrecompileCalls::Identity;
+
+    fn constrain_call(cb: &mut EVMConstraintBuilder<F>, call: &PrecompileCallExprs<F>) {
+        cb.require_equal(
+            "input length and precompile return length are the same",
+            call.cd_length.clone(),
+            call.return_length.clone(),
+        );
+    }
+}
+
 #[cfg(test)]
 mod test {
     use bus_mapping::{
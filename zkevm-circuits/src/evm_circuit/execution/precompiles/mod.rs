@@ -1,2 +1,430 @@
+use bus_mapping::{circuit_input_builder::Call, precompile::PrecompileCalls};
+use eth_types::{Field, ToScalar};
+use gadgets::util::{select, sum, Expr};
+use halo2_proofs::{
+    circuit::Value,
+    plonk::{Error, Expression},
+};
+
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::N_BYTES_MEMORY_ADDRESS,
+        step::ExecutionState,
+        util::{
+            common_gadget::RestoreContextGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::{IsZeroGadget, LtGadget},
+            rlc, CachedRegion, Cell,
+        },
+    },
+    table::CallContextFieldTag,
+    witness::Block,
+};
+
+mod blake2f;
+pub use blake2f::Blake2fGadget;
+
+mod ec_add;
+pub use ec_add::EcAddGadget;
+
+mod ec_mul;
+pub use ec_mul::EcMulGadget;
+
+mod ecrecover;
+pub use ecrecover::EcrecoverGadget;
+
 mod identity;
 pub use identity::IdentityGadget;
+
+mod modexp;
+pub use modexp::ModExpGadget;
+
+mod p256_verify;
+pub use p256_verify::P256VerifyGadget;
+
+mod ripemd160;
+pub use ripemd160::Ripemd160Gadget;
+
+mod sha256;
+pub use sha256::Sha256Gadget;
+
+/// Return the gadgets of all the supported precompiles.  A precompile,
+/// including one which isn't an Ethereum precompile, is supported by
+/// implementing `PrecompileExecutionGadget` for its gadget, configuring the
+/// gadget in the `ExecutionConfig` and adding it here.
+pub(crate) fn precompile_gadgets<F: Field>() -> Vec<PrecompileGadgetInfo<F>> {
+    vec![
+        PrecompileGadgetInfo::of::<EcrecoverGadget<F>>(),
+        PrecompileGadgetInfo::of::<Sha256Gadget<F>>(),
+        PrecompileGadgetInfo::of::<Ripemd160Gadget<F>>(),
+        PrecompileGadgetInfo::of::<IdentityGadget<F>>(),
+        PrecompileGadgetInfo::of::<ModExpGadget<F>>(),
+        PrecompileGadgetInfo::of::<EcAddGadget<F>>(),
+        PrecompileGadgetInfo::of::<EcMulGadget<F>>(),
+        PrecompileGadgetInfo::of::<Blake2fGadget<F>>(),
+        PrecompileGadgetInfo::of::<P256VerifyGadget<F>>(),
+    ]
+}
+
+/// Expressions of a call to a precompile, in the step of the call.
+pub(crate) struct PrecompileCallExprs<F> {
+    /// Length of the call data
+    pub(crate) cd_length: Expression<F>,
+    /// Length of the output of the precompile
+    pub(crate) return_length: Expression<F>,
+    /// Number of bytes of the call data copied to the precompile
+    pub(crate) input_len: Expression<F>,
+    /// RLC of the bytes copied to the precompile
+    pub(crate) input_bytes_rlc: Expression<F>,
+    /// RLC of the output of the precompile
+    pub(crate) output_bytes_rlc: Expression<F>,
+}
+
+impl<F: Field> PrecompileCallExprs<F> {
+    /// Constrain the RLCs of the input and the output of a precompile to the
+    /// ones of the bytes copied by the call.
+    pub(crate) fn constrain_bytes(
+        &self,
+        cb: &mut EVMConstraintBuilder<F>,
+        input_bytes_rlc: Expression<F>,
+        output_bytes_rlc: Expression<F>,
+    ) {
+        cb.require_equal(
+            "copy input bytes",
+            input_bytes_rlc,
+            self.input_bytes_rlc.clone(),
+        );
+        cb.require_equal(
+            "copy output bytes",
+            output_bytes_rlc,
+            self.output_bytes_rlc.clone(),
+        );
+    }
+}
+
+/// Gadget of the execution state to which a call to a precompile moves.
+pub(crate) trait PrecompileExecutionGadget<F: Field>: ExecutionGadget<F> {
+    /// Precompile executed by the gadget
+    const PRECOMPILE: PrecompileCalls;
+
+    /// Constrain the gadget in the next step to the call, by querying its
+    /// first cells in the order in which `configure` queries them.
+    fn constrain_call(cb: &mut EVMConstraintBuilder<F>, call: &PrecompileCallExprs<F>);
+}
+
+/// Precompile executed by a gadget, with its execution state and the
+/// constraint of its gadget on a call.
+pub(crate) struct PrecompileGadgetInfo<F> {
+    pub(crate) precompile: PrecompileCalls,
+    pub(crate) execution_state: ExecutionState,
+    pub(crate) constrain_call: fn(&mut EVMConstraintBuilder<F>, &PrecompileCallExprs<F>),
+}
+
+impl<F: Field> PrecompileGadgetInfo<F> {
+    fn of<G: PrecompileExecutionGadget<F>>() -> Self {
+        Self {
+            precompile: G::PRECOMPILE,
+            execution_state: G::EXECUTION_STATE,
+            constrain_call: G::constrain_call,
+        }
+    }
+}
+
+/// Number of rw lookups of `PrecompileCallContextGadget`, which precede the
+/// ones of the `RestoreContextGadget` of the precompile.
+pub(crate) const N_CALL_CONTEXT_RWS: usize = 7;
+
+/// Call context of a precompile, with its gas costs which are looked up in the
+/// precompile gas cost table, and the return to its caller.  A call which fails consumes all its
+/// gas, while a call with insufficient gas is handled by the ErrorOOGPrecompile
+/// gadget instead.
+#[derive(Clone, Debug)]
+pub(crate) struct PrecompileCallContextGadget<F> {
+    pub(crate) is_success: Cell<F>,
+    pub(crate) callee_address: Cell<F>,
+    pub(crate) caller_id: Cell<F>,
+    pub(crate) call_data_offset: Cell<F>,
+    pub(crate) call_data_length: Cell<F>,
+    pub(crate) return_data_offset: Cell<F>,
+    pub(crate) return_data_length: Cell<F>,
+    pub(crate) base_gas_cost: Cell<F>,
+    pub(crate) per_word_gas_cost: Cell<F>,
+}
+
+impl<F: Field> PrecompileCallContextGadget<F> {
+    pub(crate) fn construct(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let [is_success, callee_address, caller_id, call_data_offset, call_data_length, return_data_offset, return_data_length] =
+            [
+                CallContextFieldTag::IsSuccess,
+                CallContextFieldTag::CalleeAddress,
+                CallContextFieldTag::CallerId,
+                CallContextFieldTag::CallDataOffset,
+                CallContextFieldTag::CallDataLength,
+                CallContextFieldTag::ReturnDataOffset,
+                CallContextFieldTag::ReturnDataLength,
+            ]
+            .map(|tag| cb.call_context(None, tag));
+
+        cb.precompile_info_lookup(cb.execution_state().as_u64().expr(), callee_address.expr());
+
+        let base_gas_cost = cb.query_cell();
+        let per_word_gas_cost = cb.query_cell();
+        cb.precompile_gas_cost_lookup(
+            callee_address.expr(),
+            base_gas_cost.expr(),
+            per_word_gas_cost.expr(),
+        );
+
+        Self {
+            is_success,
+            callee_address,
+            caller_id,
+            call_data_offset,
+            call_data_length,
+            return_data_offset,
+            return_data_length,
+            base_gas_cost,
+            per_word_gas_cost,
+        }
+    }
+
+    /// Gas cost of a call with `n_words` words of input, pairs of points for
+    /// the pairing check, or rounds for blake2f.
+    pub(crate) fn gas_cost(&self, n_words: Expression<F>) -> Expression<F> {
+        self.base_gas_cost.expr() + n_words * self.per_word_gas_cost.expr()
+    }
+
+    /// Return to the caller with `return_data_length` bytes of output, at a
+    /// cost of `gas_cost` if the call succeeds and all its gas otherwise.
+    pub(crate) fn restore_context(
+        &self,
+        cb: &mut EVMConstraintBuilder<F>,
+        gas_cost: Expression<F>,
+        return_data_length: Expression<F>,
+    ) -> RestoreContextGadget<F> {
+        let gas_cost = select::expr(
+            self.is_success.expr(),
+            gas_cost,
+            cb.curr.state.gas_left.expr(),
+        );
+        RestoreContextGadget::construct2(
+            cb,
+            self.is_success.expr(),
+            gas_cost,
+            0.expr(),
+            0x00.expr(), // ReturnDataOffset
+            return_data_length,
+            0.expr(),
+            0.expr(),
+        )
+    }
+
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        call: &Call,
+    ) -> Result<(), Error> {
+        self.is_success.assign(
+            region,
+            offset,
+            Value::known(F::from(u64::from(call.is_success))),
+        )?;
+        self.callee_address.assign(
+            region,
+            offset,
+            Value::known(call.code_address().unwrap().to_scalar().unwrap()),
+        )?;
+        self.caller_id.assign(
+            region,
+            offset,
+            Value::known(F::from(call.caller_id.try_into().unwrap())),
+        )?;
+        self.call_data_offset.assign(
+            region,
+            offset,
+            Value::known(F::from(call.call_data_offset)),
+        )?;
+        self.call_data_length.assign(
+            region,
+            offset,
+            Value::known(F::from(call.call_data_length)),
+        )?;
+        self.return_data_offset.assign(
+            region,
+            offset,
+            Value::known(F::from(call.return_data_offset)),
+        )?;
+        self.return_data_length.assign(
+            region,
+            offset,
+            Value::known(F::from(call.return_data_length)),
+        )?;
+        let gas_cost = PrecompileCalls::from(call.code_address().unwrap())
+            .gas_cost(block.circuits_params.hardfork)
+            .ok_or(Error::Synthesis)?;
+        self.base_gas_cost
+            .assign(region, offset, Value::known(F::from(gas_cost.base)))?;
+        self.per_word_gas_cost
+            .assign(region, offset, Value::known(F::from(gas_cost.per_word)))?;
+        Ok(())
+    }
+}
+
+/// Return `randomness^n` by square-and-multiply, for a constant `n`.
+pub(crate) fn randomness_pow<F: Field>(randomness: Expression<F>, n: usize) -> Expression<F> {
+    let mut pow = 1.expr();
+    let mut square = randomness;
+    let mut n = n;
+    while n > 0 {
+        if n & 1 == 1 {
+            pow = pow * square.clone();
+        }
+        square = square.clone() * square;
+        n >>= 1;
+    }
+    pow
+}
+
+/// Bits of an exponent `n < 2^N`, and the powers of the randomness by the
+/// partial sums of the bits, the last of which is `randomness^n`.
+#[derive(Clone, Debug)]
+pub(crate) struct RandomnessPowGadget<F, const N: usize> {
+    bits: [Cell<F>; N],
+    pows: [Cell<F>; N],
+}
+
+impl<F: Field, const N: usize> RandomnessPowGadget<F, N> {
+    pub(crate) fn construct(cb: &mut EVMConstraintBuilder<F>, exponent: Expression<F>) -> Self {
+        let bits = [(); N].map(|_| cb.query_bool());
+        let pows = [(); N].map(|_| cb.query_cell_phase2());
+
+        cb.require_equal(
+            "exponent = sum of bits[i] * 2^i",
+            exponent,
+            sum::expr(
+                bits.iter()
+                    .enumerate()
+                    .map(|(i, bit)| bit.expr() * (1 << i).expr()),
+            ),
+        );
+
+        // pows[i] = randomness^(sum of the first i + 1 bits)
+        let mut randomness_pow = cb.challenges().keccak_input();
+        for (i, (bit, pow)) in bits.iter().zip(pows.iter()).enumerate() {
+            let pow_prev = if i == 0 { 1.expr() } else { pows[i - 1].expr() };
+            cb.require_equal(
+                "pows[i] = pows[i - 1] * randomness^(bit * 2^i)",
+                pow.expr(),
+                pow_prev * (1.expr() + bit.expr() * (randomness_pow.clone() - 1.expr())),
+            );
+            randomness_pow = randomness_pow.clone() * randomness_pow;
+        }
+
+        Self { bits, pows }
+    }
+
+    pub(crate) fn expr(&self) -> Expression<F> {
+        self.pows[N - 1].expr()
+    }
+
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        exponent: u64,
+    ) -> Result<(), Error> {
+        let randomness = region.challenges().keccak_input();
+        for (i, (bit, pow)) in self.bits.iter().zip(self.pows.iter()).enumerate() {
+            bit.assign(region, offset, Value::known(F::from((exponent >> i) & 1)))?;
+            let partial_exponent = exponent & ((1 << (i + 1)) - 1);
+            pow.assign(
+                region,
+                offset,
+                randomness.map(|randomness| randomness.pow([partial_exponent, 0, 0, 0])),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Input of a precompile which reads the first `min(call_data_length, n)`
+/// bytes of the call data, right-padded with zeros to `n` bytes, with a
+/// number of padding bytes `n - input_len < 2^N_BITS`.
+#[derive(Clone, Debug)]
+pub(crate) struct PaddedInputGadget<F, const N_BITS: usize> {
+    n_bytes: u64,
+    input_is_short: LtGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    no_call_data: IsZeroGadget<F>,
+    padding_pow: RandomnessPowGadget<F, N_BITS>,
+}
+
+impl<F: Field, const N_BITS: usize> PaddedInputGadget<F, N_BITS> {
+    /// Constrain `input_len` to `min(call_data_length, n_bytes)`, and the RLC
+    /// of the input to the RLC of `padded_input_le`, the little-endian bytes
+    /// of the padded input.
+    pub(crate) fn construct(
+        cb: &mut EVMConstraintBuilder<F>,
+        n_bytes: usize,
+        call_data_length: Expression<F>,
+        input_len: Expression<F>,
+        input_bytes_rlc: Expression<F>,
+        padded_input_le: &[Expression<F>],
+    ) -> Self {
+        debug_assert_eq!(padded_input_le.len(), n_bytes);
+
+        let input_is_short = LtGadget::construct(cb, call_data_length.clone(), n_bytes.expr());
+        cb.require_equal(
+            "input_len = min(call_data_length, n_bytes)",
+            input_len.clone(),
+            select::expr(
+                input_is_short.expr(),
+                call_data_length.clone(),
+                n_bytes.expr(),
+            ),
+        );
+        // Without call data, the input isn't copied and its RLC must be 0.
+        let no_call_data = IsZeroGadget::construct(cb, call_data_length);
+        cb.require_zero(
+            "input_bytes_rlc is 0 when there's no call data",
+            input_bytes_rlc.clone() * no_call_data.expr(),
+        );
+
+        let padding_pow = RandomnessPowGadget::construct(cb, n_bytes.expr() - input_len);
+        cb.require_equal(
+            "input_bytes_rlc * randomness^padding = RLC of the padded input",
+            input_bytes_rlc * padding_pow.expr(),
+            rlc::expr(padded_input_le, cb.challenges().keccak_input()),
+        );
+
+        Self {
+            n_bytes: n_bytes as u64,
+            input_is_short,
+            no_call_data,
+            padding_pow,
+        }
+    }
+
+    /// Assign the gadget, and return the length of the input.
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        call_data_length: u64,
+    ) -> Result<u64, Error> {
+        let input_len = call_data_length.min(self.n_bytes);
+        self.input_is_short.assign(
+            region,
+            offset,
+            F::from(call_data_length),
+            F::from(self.n_bytes),
+        )?;
+        self.no_call_data
+            .assign(region, offset, F::from(call_data_length))?;
+        self.padding_pow
+            .assign(region, offset, self.n_bytes - input_len)?;
+        Ok(input_len)
+    }
+}
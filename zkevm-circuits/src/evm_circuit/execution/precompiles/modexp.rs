@@ -0,0 +1,671 @@
+use bus_mapping::{
+    circuit_input_builder::Call,
+    precompile::{PrecompileAuxData, PrecompileCalls, MODEXP_HEADER_LEN, MODEXP_SIZE_LIMIT},
+};
+use eth_types::{Field, ToBigEndian, ToLittleEndian};
+use gadgets::util::{not, select, sum, Expr};
+use halo2_proofs::{circuit::Value, plonk::Error};
+use itertools::Itertools;
+use std::cmp::max;
+
+use super::{
+    PrecompileCallContextGadget, PrecompileCallExprs, PrecompileExecutionGadget,
+    RandomnessPowGadget, N_CALL_CONTEXT_RWS,
+};
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS, N_BYTES_WORD},
+        step::ExecutionState,
+        util::{
+            common_gadget::RestoreContextGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::{
+                ByteSizeGadget, ConstantDivisionGadget, IsZeroGadget, LtGadget, MinMaxGadget,
+            },
+            rlc, CachedRegion, Cell,
+        },
+    },
+    util::word::{Word32Cell, WordExpr},
+    witness::{Block, ExecStep, Transaction},
+};
+
+/// Number of bits of the lengths of the base, exponent and modulus, which are
+/// at most `MODEXP_SIZE_LIMIT`.
+const N_BITS_LENGTH: usize = 6;
+
+/// Number of bits of the number of padding bytes of the input, which is at
+/// most `MODEXP_HEADER_LEN + 3 * MODEXP_SIZE_LIMIT`.
+const N_BITS_PADDING: usize = 8;
+
+/// Gadget of the modexp precompile, for a base, exponent and modulus of at
+/// most `MODEXP_SIZE_LIMIT` bytes.  The input is their lengths followed by
+/// their values, read from the call data right-padded with zeros.  The gas
+/// cost follows EIP-2565, and the modular exponentiation of a successful call
+/// is looked up in the ModExpTable.
+#[derive(Clone, Debug)]
+pub struct ModExpGadget<F> {
+    input_bytes_rlc: Cell<F>,
+    output_bytes_rlc: Cell<F>,
+    input_len: Cell<F>,
+    return_len: Cell<F>,
+
+    /// Lengths and values of the base, exponent and modulus
+    lengths: [Word32Cell<F>; 3],
+    lengths_in_range: [LtGadget<F, 1>; 3],
+    values: [Word32Cell<F>; 3],
+    values_size: [ByteSizeGadget<F>; 3],
+    values_fit: [LtGadget<F, 1>; 3],
+    /// Result of the modular exponentiation
+    output: Word32Cell<F>,
+
+    /// Number of bytes read from the call data, and the powers of the
+    /// randomness by the lengths and the number of padding bytes
+    input_is_short: LtGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    no_call_data: IsZeroGadget<F>,
+    lengths_pow: [RandomnessPowGadget<F, N_BITS_LENGTH>; 3],
+    padding_pow: RandomnessPowGadget<F, N_BITS_PADDING>,
+
+    /// Gas cost
+    max_length: MinMaxGadget<F, 1>,
+    n_words: ConstantDivisionGadget<F, 1>,
+    exponent_msb_lt: [LtGadget<F, 1>; 7],
+    exponent_is_zero: IsZeroGadget<F>,
+    iteration_count: MinMaxGadget<F, 1>,
+    dynamic_gas: ConstantDivisionGadget<F, 2>,
+    required_gas: MinMaxGadget<F, 2>,
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
+
+    call_context: PrecompileCallContextGadget<F>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for ModExpGadget<F> {
+    const EXECUTION_STATE: ExecutionState = ExecutionState::PrecompileBigModExp;
+
+    const NAME: &'static str = "MODEXP";
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // These cells are constrained by the PrecompileGadget of the call, so
+        // they must be the first ones of their types.
+        let input_bytes_rlc = cb.query_cell_phase2();
+        let output_bytes_rlc = cb.query_cell_phase2();
+        let input_len = cb.query_cell();
+        let return_len = cb.query_cell();
+
+        let lengths = [(); 3].map(|_| cb.query_word32());
+        let values = [(); 3].map(|_| cb.query_word32());
+        let output = cb.query_word32();
+
+        let call_context = PrecompileCallContextGadget::construct(cb);
+
+        let randomness = cb.challenges().keccak_input();
+
+        // The larger lengths are handled in the ErrorPrecompileFailed gadget,
+        // so only the least significant byte of a length may be nonzero.
+        let lengths_in_range = lengths.clone().map(|length| {
+            cb.require_zero("length < 256", sum::expr(&length.limbs[1..N_BYTES_WORD]));
+            let lt =
+                LtGadget::construct(cb, length.limbs[0].expr(), (MODEXP_SIZE_LIMIT + 1).expr());
+            cb.require_equal("length <= MODEXP_SIZE_LIMIT", lt.expr(), 1.expr());
+            lt
+        });
+        let [base_len, exponent_len, modulus_len] =
+            lengths.clone().map(|length| length.limbs[0].expr());
+
+        let values_size = values
+            .clone()
+            .map(|value| ByteSizeGadget::construct(cb, value.to_word_n().limbs));
+        let values_fit = [
+            (&values_size[0], base_len.clone()),
+            (&values_size[1], exponent_len.clone()),
+            (&values_size[2], modulus_len.clone()),
+        ]
+        .map(|(value_size, length)| {
+            let lt = LtGadget::construct(cb, value_size.byte_size(), length + 1.expr());
+            cb.require_equal("the value fits in its length", lt.expr(), 1.expr());
+            lt
+        });
+
+        // The input is the first min(call_data_length, 96 + lengths) bytes of
+        // the call data, which are the lengths and the values right-padded
+        // with zeros.
+        let total_len = MODEXP_HEADER_LEN.expr()
+            + base_len.clone()
+            + exponent_len.clone()
+            + modulus_len.clone();
+        let input_is_short =
+            LtGadget::construct(cb, call_context.call_data_length.expr(), total_len.clone());
+        cb.require_equal(
+            "input_len = min(call_data_length, 96 + lengths)",
+            input_len.expr(),
+            select::expr(
+                input_is_short.expr(),
+                call_context.call_data_length.expr(),
+                total_len.clone(),
+            ),
+        );
+        // Without call data, the input isn't copied and its RLC must be 0.
+        let no_call_data = IsZeroGadget::construct(cb, call_context.call_data_length.expr());
+        cb.require_zero(
+            "input_bytes_rlc is 0 when there's no call data",
+            input_bytes_rlc.expr() * no_call_data.expr(),
+        );
+
+        let lengths_pow = [base_len.clone(), exponent_len.clone(), modulus_len.clone()]
+            .map(|length| RandomnessPowGadget::construct(cb, length));
+        let padding_pow = RandomnessPowGadget::construct(cb, total_len - input_len.expr());
+        let [base_pow, exponent_pow, modulus_pow] = lengths_pow.clone().map(|pow| pow.expr());
+
+        let lengths_rlc = rlc::expr(
+            &lengths
+                .iter()
+                .rev()
+                .flat_map(|length| length.limbs.iter().map(|byte| byte.expr()))
+                .collect_vec(),
+            randomness.clone(),
+        );
+        let [base_rlc, exponent_rlc, modulus_rlc] = values
+            .clone()
+            .map(|value| rlc::expr(&value.limbs.map(|byte| byte.expr()), randomness.clone()));
+        cb.require_equal(
+            "input_bytes_rlc * randomness^padding = RLC of the lengths and the values",
+            input_bytes_rlc.expr() * padding_pow.expr(),
+            ((lengths_rlc * base_pow + base_rlc) * exponent_pow + exponent_rlc) * modulus_pow
+                + modulus_rlc,
+        );
+
+        // EIP-2565: max(200, mult_complexity * iteration_count / 3), with
+        // - mult_complexity = ceil(max(base_len, modulus_len) / 8)^2,
+        // - iteration_count = max(bit_length(exponent) - 1, 1), as the exponent has at most 32
+        //   bytes.
+        let max_length = MinMaxGadget::construct(cb, base_len, modulus_len.clone());
+        let n_words = ConstantDivisionGadget::construct(cb, max_length.max() + 7.expr(), 8);
+
+        let exponent_size = values_size[1].byte_size();
+        let exponent_msb = values_size[1].most_significant_byte(&values[1].to_word_n().limbs);
+        let exponent_msb_lt = [1, 2, 3, 4, 5, 6, 7]
+            .map(|i| LtGadget::construct(cb, exponent_msb.clone(), (1u64 << i).expr()));
+        let exponent_is_zero = IsZeroGadget::construct(cb, exponent_size.clone());
+        // bit_length(exponent) - 1 = 8 * (size - 1) + bit_length(msb) - 1,
+        // with bit_length(msb) = 8 - sum of msb < 2^i for i in [1, 8), or 0
+        // for a zero exponent.
+        let exponent_bit_len_minus_one = 8.expr() * exponent_size
+            - 1.expr()
+            - sum::expr(exponent_msb_lt.iter().map(|lt| lt.expr()))
+            + 8.expr() * exponent_is_zero.expr();
+        let iteration_count = MinMaxGadget::construct(cb, exponent_bit_len_minus_one, 1.expr());
+
+        let dynamic_gas = ConstantDivisionGadget::construct(
+            cb,
+            n_words.quotient() * n_words.quotient() * iteration_count.max(),
+            3,
+        );
+        let required_gas = MinMaxGadget::construct(
+            cb,
+            dynamic_gas.quotient(),
+            call_context.base_gas_cost.expr(),
+        );
+
+        // Unlike the other precompiles, the insufficient gas is handled here
+        // as the gas cost depends on the input.
+        let insufficient_gas =
+            LtGadget::construct(cb, cb.curr.state.gas_left.expr(), required_gas.max());
+        cb.require_equal(
+            "the call succeeds iff the gas is sufficient",
+            call_context.is_success.expr(),
+            not::expr(insufficient_gas.expr()),
+        );
+
+        cb.condition(call_context.is_success.expr(), |cb| {
+            cb.modexp_table_lookup(
+                values[0].to_word(),
+                values[1].to_word(),
+                values[2].to_word(),
+                output.to_word(),
+            );
+        });
+
+        // The output is the result as modulus_len bytes, or nothing.
+        cb.require_equal(
+            "output_bytes_rlc = is_success * RLC of the output",
+            output_bytes_rlc.expr(),
+            call_context.is_success.expr()
+                * rlc::expr(&output.limbs.clone().map(|byte| byte.expr()), randomness),
+        );
+        cb.require_equal(
+            "return_len = is_success * modulus_len",
+            return_len.expr(),
+            call_context.is_success.expr() * modulus_len,
+        );
+
+        let restore_context =
+            call_context.restore_context(cb, required_gas.max(), return_len.expr());
+
+        Self {
+            input_bytes_rlc,
+            output_bytes_rlc,
+            input_len,
+            return_len,
+            lengths,
+            lengths_in_range,
+            values,
+            values_size,
+            values_fit,
+            output,
+            input_is_short,
+            no_call_data,
+            lengths_pow,
+            padding_pow,
+            max_length,
+            n_words,
+            exponent_msb_lt,
+            exponent_is_zero,
+            iteration_count,
+            dynamic_gas,
+            required_gas,
+            insufficient_gas,
+            call_context,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let aux_data = match &step.aux_data {
+            Some(PrecompileAuxData::Modexp(aux_data)) => aux_data,
+            _ => {
+                log::error!("missing the aux data of the modexp step");
+                return Err(Error::Synthesis);
+            }
+        };
+        let randomness = region.challenges().keccak_input();
+
+        let lengths = aux_data.input_lengths.map(|length| length.as_u64());
+        let [base_len, exponent_len, modulus_len] = lengths;
+        let total_len = MODEXP_HEADER_LEN as u64 + base_len + exponent_len + modulus_len;
+        let input_len = aux_data.input_len as u64;
+
+        // The lengths and the values, which are the input right-padded with
+        // zeros.
+        let padded_input = aux_data
+            .input_lengths
+            .iter()
+            .flat_map(|length| length.to_be_bytes())
+            .chain(
+                aux_data
+                    .inputs
+                    .iter()
+                    .zip(lengths)
+                    .flat_map(|(value, length)| {
+                        value.to_be_bytes()[N_BYTES_WORD - length as usize..].to_vec()
+                    }),
+            )
+            .collect_vec();
+        let output_bytes = if call.is_success {
+            aux_data.output.to_be_bytes()[N_BYTES_WORD - modulus_len as usize..].to_vec()
+        } else {
+            vec![]
+        };
+        self.input_bytes_rlc.assign(
+            region,
+            offset,
+            randomness.map(|randomness| {
+                rlc::value(padded_input[..input_len as usize].iter().rev(), randomness)
+            }),
+        )?;
+        self.output_bytes_rlc.assign(
+            region,
+            offset,
+            randomness.map(|randomness| rlc::value(output_bytes.iter().rev(), randomness)),
+        )?;
+        self.input_len
+            .assign(region, offset, Value::known(F::from(input_len)))?;
+        self.return_len.assign(
+            region,
+            offset,
+            Value::known(F::from(output_bytes.len() as u64)),
+        )?;
+
+        for ((cell, lt), &length) in self
+            .lengths
+            .iter()
+            .zip(self.lengths_in_range.iter())
+            .zip(aux_data.input_lengths.iter())
+        {
+            cell.assign_u256(region, offset, length)?;
+            lt.assign(
+                region,
+                offset,
+                F::from(length.as_u64()),
+                F::from(MODEXP_SIZE_LIMIT as u64 + 1),
+            )?;
+        }
+        for (((cell, value_size), lt), (&value, length)) in self
+            .values
+            .iter()
+            .zip(self.values_size.iter())
+            .zip(self.values_fit.iter())
+            .zip(aux_data.inputs.iter().zip(lengths))
+        {
+            cell.assign_u256(region, offset, value)?;
+            value_size.assign(region, offset, value)?;
+            let size = (value.bits() as u64 + 7) / 8;
+            lt.assign(region, offset, F::from(size), F::from(length + 1))?;
+        }
+        self.output.assign_u256(region, offset, aux_data.output)?;
+
+        self.input_is_short.assign(
+            region,
+            offset,
+            F::from(call.call_data_length),
+            F::from(total_len),
+        )?;
+        self.no_call_data
+            .assign(region, offset, F::from(call.call_data_length))?;
+        for (pow, length) in self.lengths_pow.iter().zip(lengths) {
+            pow.assign(region, offset, length)?;
+        }
+        self.padding_pow
+            .assign(region, offset, total_len - input_len)?;
+
+        let max_length = max(base_len, modulus_len);
+        self.max_length
+            .assign(region, offset, F::from(base_len), F::from(modulus_len))?;
+        let (n_words, _) = self
+            .n_words
+            .assign(region, offset, (max_length + 7).into())?;
+
+        let exponent = aux_data.inputs[1];
+        let exponent_size = (exponent.bits() + 7) / 8;
+        let exponent_msb = if exponent_size > 0 {
+            exponent.to_le_bytes()[exponent_size - 1]
+        } else {
+            0
+        };
+        for (i, lt) in self.exponent_msb_lt.iter().enumerate() {
+            lt.assign(
+                region,
+                offset,
+                F::from(exponent_msb as u64),
+                F::from(1 << (i + 1)),
+            )?;
+        }
+        self.exponent_is_zero
+            .assign(region, offset, F::from(exponent_size as u64))?;
+        let exponent_bit_len_minus_one = (exponent.bits() as u64).saturating_sub(1);
+        self.iteration_count
+            .assign(region, offset, F::from(exponent_bit_len_minus_one), F::ONE)?;
+        let iteration_count = max(exponent_bit_len_minus_one, 1);
+
+        let (dynamic_gas, _) = self.dynamic_gas.assign(
+            region,
+            offset,
+            n_words * n_words * u128::from(iteration_count),
+        )?;
+        let dynamic_gas = dynamic_gas as u64;
+        let min_gas = PrecompileCalls::Modexp
+            .gas_cost(block.circuits_params.hardfork)
+            .ok_or(Error::Synthesis)?
+            .base;
+        self.required_gas
+            .assign(region, offset, F::from(dynamic_gas), F::from(min_gas))?;
+        let required_gas = max(dynamic_gas, min_gas);
+        self.insufficient_gas.assign(
+            region,
+            offset,
+            F::from(step.gas_left),
+            F::from(required_gas),
+        )?;
+
+        self.call_context.assign(region, offset, block, call)?;
+        self.restore_context
+            .assign(region, offset, block, call, step, N_CALL_CONTEXT_RWS)?;
+
+        Ok(())
+    }
+}
+
+impl<F: Field> PrecompileExecutionGadget<F> for ModExpGadget<F> {
+    const PRECOMPILE: PrecompileCalls = PrecompileCalls::Modexp;
+
+    fn constrain_call(cb: &mut EVMConstraintBuilder<F>, call: &PrecompileCallExprs<F>) {
+        let input_bytes_rlc = cb.query_cell_phase2();
+        let output_bytes_rlc = cb.query_cell_phase2();
+        let input_len = cb.query_cell();
+        let return_len = cb.query_cell();
+        call.constrain_bytes(cb, input_bytes_rlc.expr(), output_bytes_rlc.expr());
+        cb.require_equal(
+            "input length of the precompile call",
+            input_len.expr(),
+            call.input_len.clone(),
+        );
+        cb.require_equal(
+            "modexp returns modulus_len bytes if the call succeeds",
+            call.return_length.clone(),
+            return_len.expr(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bus_mapping::{
+        evm::{OpcodeId, PrecompileCallArgs},
+        precompile::PrecompileCalls,
+    };
+    use eth_types::{bytecode, word, ToWord};
+    use itertools::Itertools;
+    use mock::TestContext;
+
+    use crate::test_util::CircuitTestBuilder;
+
+    lazy_static::lazy_static! {
+        static ref TEST_VECTOR: Vec<PrecompileCallArgs> = {
+            vec![
+                PrecompileCallArgs {
+                    name: "modexp (single-byte values)",
+                    setup_code: bytecode! {
+                        // base_len, exponent_len, modulus_len
+                        PUSH1(0x01)
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(0x01)
+                        PUSH1(0x20)
+                        MSTORE
+                        PUSH1(0x01)
+                        PUSH1(0x40)
+                        MSTORE
+                        // 8 ^ 9 mod 10
+                        PUSH1(0x08)
+                        PUSH1(0x60)
+                        MSTORE8
+                        PUSH1(0x09)
+                        PUSH1(0x61)
+                        MSTORE8
+                        PUSH1(0x0a)
+                        PUSH1(0x62)
+                        MSTORE8
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x63.into(),
+                    ret_offset: 0x80.into(),
+                    ret_size: 0x01.into(),
+                    address: PrecompileCalls::Modexp.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "modexp (32-byte values)",
+                    setup_code: bytecode! {
+                        // base_len, exponent_len, modulus_len
+                        PUSH1(0x20)
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(0x20)
+                        PUSH1(0x20)
+                        MSTORE
+                        PUSH1(0x20)
+                        PUSH1(0x40)
+                        MSTORE
+                        // base, exponent, modulus
+                        PUSH32(word!("0x0123456789abcdef0f1e2d3c4b5a6978aabbccdd001122331039abcdefefef84"))
+                        PUSH1(0x60)
+                        MSTORE
+                        PUSH32(word!("0xfffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2e"))
+                        PUSH1(0x80)
+                        MSTORE
+                        PUSH32(word!("0xfffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f"))
+                        PUSH1(0xa0)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0xc0.into(),
+                    ret_offset: 0xc0.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::Modexp.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "modexp (truncated call data)",
+                    setup_code: bytecode! {
+                        // base_len, exponent_len, modulus_len
+                        PUSH1(0x02)
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(0x01)
+                        PUSH1(0x20)
+                        MSTORE
+                        PUSH1(0x02)
+                        PUSH1(0x40)
+                        MSTORE
+                        // base, the exponent and the modulus are 0
+                        PUSH1(0x03)
+                        PUSH1(0x61)
+                        MSTORE8
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x62.into(),
+                    ret_offset: 0x80.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::Modexp.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "modexp (more call data than the input)",
+                    setup_code: bytecode! {
+                        // base_len, exponent_len, modulus_len
+                        PUSH1(0x01)
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(0x01)
+                        PUSH1(0x20)
+                        MSTORE
+                        PUSH1(0x01)
+                        PUSH1(0x40)
+                        MSTORE
+                        // 3 ^ 5 mod 7, and trailing call data which is ignored
+                        PUSH32(word!("0x030507ffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"))
+                        PUSH1(0x60)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x80.into(),
+                    ret_offset: 0x80.into(),
+                    ret_size: 0x01.into(),
+                    address: PrecompileCalls::Modexp.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "modexp (empty modulus)",
+                    setup_code: bytecode! {
+                        // base_len, exponent_len
+                        PUSH1(0x01)
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(0x01)
+                        PUSH1(0x20)
+                        MSTORE
+                        // base, exponent
+                        PUSH2(0x0203)
+                        PUSH1(0x42)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x62.into(),
+                    ret_offset: 0x80.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::Modexp.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "modexp (no call data)",
+                    setup_code: bytecode! {},
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x00.into(),
+                    ret_offset: 0x00.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::Modexp.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "modexp (insufficient gas)",
+                    setup_code: bytecode! {
+                        // base_len, exponent_len, modulus_len
+                        PUSH1(0x20)
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(0x20)
+                        PUSH1(0x20)
+                        MSTORE
+                        PUSH1(0x20)
+                        PUSH1(0x40)
+                        MSTORE
+                        // a 256-bit exponent costs 16 * 255 / 3 = 1360 gas
+                        PUSH1(0x02)
+                        PUSH1(0x60)
+                        MSTORE
+                        PUSH32(word!("0x8000000000000000000000000000000000000000000000000000000000000000"))
+                        PUSH1(0x80)
+                        MSTORE
+                        PUSH1(0x0d)
+                        PUSH1(0xa0)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0xc0.into(),
+                    ret_offset: 0xc0.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::Modexp.address().to_word(),
+                    gas: 1359.into(),
+                    ..Default::default()
+                },
+            ]
+        };
+    }
+
+    #[test]
+    fn precompile_modexp_test() {
+        let call_kinds = vec![
+            OpcodeId::CALL,
+            OpcodeId::STATICCALL,
+            OpcodeId::DELEGATECALL,
+            OpcodeId::CALLCODE,
+        ];
+
+        for (test_vector, &call_kind) in TEST_VECTOR.iter().cartesian_product(&call_kinds) {
+            let bytecode = test_vector.with_call_op(call_kind);
+
+            CircuitTestBuilder::new_from_test_ctx(
+                TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+            )
+            .run();
+        }
+    }
+}
@@ -0,0 +1,396 @@
+use bus_mapping::{
+    circuit_input_builder::Call,
+    precompile::{p256_verify_output, PrecompileAuxData, PrecompileCalls, P256VERIFY_INPUT_LEN},
+};
+use eth_types::{Field, U256};
+use gadgets::util::{not, Expr};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+use super::{
+    randomness_pow, PrecompileCallContextGadget, PrecompileCallExprs, PrecompileExecutionGadget,
+    N_CALL_CONTEXT_RWS,
+};
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::N_BYTES_WORD,
+        step::ExecutionState,
+        util::{
+            common_gadget::RestoreContextGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::IsEqualGadget,
+            rlc, CachedRegion, Cell,
+        },
+    },
+    util::word::{Word32Cell, WordExpr},
+    witness::{Block, ExecStep, Transaction},
+};
+
+/// Gadget of the secp256r1 signature verification precompile (RIP-7212) at
+/// 0x100.  The input is the message hash, the signature (r, s) and the public
+/// key (x, y), and the output is 1 as a 32-byte word when the signature is
+/// valid, and nothing otherwise.  The signature of an input with a valid
+/// length is looked up in the P256SigTable with its validity.  The call
+/// succeeds unless it runs out of gas.
+#[derive(Clone, Debug)]
+pub struct P256VerifyGadget<F> {
+    input_bytes_rlc: Cell<F>,
+    output_bytes_rlc: Cell<F>,
+    return_len: Cell<F>,
+
+    msg_hash: Word32Cell<F>,
+    sig_r: Word32Cell<F>,
+    sig_s: Word32Cell<F>,
+    pk_x: Word32Cell<F>,
+    pk_y: Word32Cell<F>,
+    has_valid_length: IsEqualGadget<F>,
+    /// Whether the public key is a point of the curve and the signature is
+    /// valid
+    is_valid: Cell<F>,
+
+    call_context: PrecompileCallContextGadget<F>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for P256VerifyGadget<F> {
+    const EXECUTION_STATE: ExecutionState = ExecutionState::PrecompileP256Verify;
+
+    const NAME: &'static str = "P256VERIFY";
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // These cells are constrained by the PrecompileGadget of the call, so
+        // they must be the first ones of their types.
+        let input_bytes_rlc = cb.query_cell_phase2();
+        let output_bytes_rlc = cb.query_cell_phase2();
+        let return_len = cb.query_cell();
+
+        let msg_hash = cb.query_word32();
+        let sig_r = cb.query_word32();
+        let sig_s = cb.query_word32();
+        let pk_x = cb.query_word32();
+        let pk_y = cb.query_word32();
+        let is_valid = cb.query_bool();
+
+        let call_context = PrecompileCallContextGadget::construct(cb);
+
+        // The whole call data is the input, whose fields are only decomposed
+        // when it has the right length.
+        let has_valid_length = IsEqualGadget::construct(
+            cb,
+            call_context.call_data_length.expr(),
+            P256VERIFY_INPUT_LEN.expr(),
+        );
+        let randomness = cb.challenges().keccak_input();
+        let pow_word = randomness_pow(randomness.clone(), N_BYTES_WORD);
+        cb.condition(has_valid_length.expr(), |cb| {
+            let input_rlc =
+                [&msg_hash, &sig_r, &sig_s, &pk_x, &pk_y]
+                    .iter()
+                    .fold(0.expr(), |acc, word| {
+                        acc * pow_word.clone()
+                            + rlc::expr(
+                                &word.limbs.clone().map(|byte| byte.expr()),
+                                randomness.clone(),
+                            )
+                    });
+            cb.require_equal(
+                "input_bytes_rlc = RLC(msg_hash || r || s || x || y)",
+                input_bytes_rlc.expr(),
+                input_rlc,
+            );
+            cb.p256_sig_table_lookup(
+                msg_hash.to_word(),
+                sig_r.to_word(),
+                sig_s.to_word(),
+                pk_x.to_word(),
+                pk_y.to_word(),
+                is_valid.expr(),
+            );
+        });
+        cb.condition(not::expr(has_valid_length.expr()), |cb| {
+            cb.require_zero(
+                "an input with an invalid length is invalid",
+                is_valid.expr(),
+            );
+        });
+
+        // The insufficient gas is handled in the ErrorOogPrecompile gadget,
+        // and an invalid signature only makes the output empty.
+        cb.require_equal(
+            "the call succeeds",
+            call_context.is_success.expr(),
+            1.expr(),
+        );
+
+        let output_bytes = p256_verify_output()
+            .into_iter()
+            .rev()
+            .map(|byte| byte.expr())
+            .collect::<Vec<_>>();
+        cb.require_equal(
+            "output_bytes_rlc = is_valid * RLC(1)",
+            output_bytes_rlc.expr(),
+            is_valid.expr() * rlc::expr(&output_bytes, randomness),
+        );
+        cb.require_equal(
+            "return_len = is_valid * 32",
+            return_len.expr(),
+            is_valid.expr() * N_BYTES_WORD.expr(),
+        );
+
+        let gas_cost = call_context.base_gas_cost.expr();
+        let restore_context = call_context.restore_context(cb, gas_cost, return_len.expr());
+
+        Self {
+            input_bytes_rlc,
+            output_bytes_rlc,
+            return_len,
+            msg_hash,
+            sig_r,
+            sig_s,
+            pk_x,
+            pk_y,
+            has_valid_length,
+            is_valid,
+            call_context,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let aux_data = match &step.aux_data {
+            Some(PrecompileAuxData::P256Verify(aux_data)) => aux_data,
+            _ => {
+                log::error!("missing the aux data of the p256verify step");
+                return Err(Error::Synthesis);
+            }
+        };
+        let randomness = region.challenges().keccak_input();
+
+        self.input_bytes_rlc.assign(
+            region,
+            offset,
+            randomness.map(|randomness| rlc::value(aux_data.input.iter().rev(), randomness)),
+        )?;
+        self.output_bytes_rlc.assign(
+            region,
+            offset,
+            randomness.map(|randomness| rlc::value(aux_data.output.iter().rev(), randomness)),
+        )?;
+        self.return_len.assign(
+            region,
+            offset,
+            Value::known(F::from(aux_data.output.len() as u64)),
+        )?;
+
+        // The fields of an input with an invalid length are left to 0.
+        let input = if aux_data.has_valid_length() {
+            aux_data.input.clone()
+        } else {
+            vec![0; P256VERIFY_INPUT_LEN]
+        };
+        for (word, bytes) in [
+            &self.msg_hash,
+            &self.sig_r,
+            &self.sig_s,
+            &self.pk_x,
+            &self.pk_y,
+        ]
+        .into_iter()
+        .zip(input.chunks(N_BYTES_WORD))
+        {
+            word.assign_u256(region, offset, U256::from_big_endian(bytes))?;
+        }
+        self.has_valid_length.assign(
+            region,
+            offset,
+            F::from(call.call_data_length),
+            F::from(P256VERIFY_INPUT_LEN as u64),
+        )?;
+        self.is_valid.assign(
+            region,
+            offset,
+            Value::known(F::from(u64::from(aux_data.is_valid()))),
+        )?;
+
+        self.call_context.assign(region, offset, block, call)?;
+        self.restore_context
+            .assign(region, offset, block, call, step, N_CALL_CONTEXT_RWS)?;
+
+        Ok(())
+    }
+}
+
+impl<F: Field> PrecompileExecutionGadget<F> for P256VerifyGadget<F> {
+    const PRECOMPILE: PrecompileCalls = PrecompileCalls::P256Verify;
+
+    fn constrain_call(cb: &mut EVMConstraintBuilder<F>, call: &PrecompileCallExprs<F>) {
+        let input_bytes_rlc = cb.query_cell_phase2();
+        let output_bytes_rlc = cb.query_cell_phase2();
+        let return_len = cb.query_cell();
+        call.constrain_bytes(cb, input_bytes_rlc.expr(), output_bytes_rlc.expr());
+        cb.require_equal(
+            "p256verify returns 32 bytes if the signature is valid",
+            call.return_length.clone(),
+            return_len.expr(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bus_mapping::{
+        circuit_input_builder::FixedCParams,
+        evm::{OpcodeId, PrecompileCallArgs},
+        precompile::PrecompileCalls,
+    };
+    use eth_types::{
+        evm_types::{GasCost, Hardfork},
+        sign_types::{pk_bytes_le, pk_bytes_swap_endianness, SignData},
+        Bytecode, ToWord, Word,
+    };
+    use halo2_proofs::halo2curves::{group::ff::PrimeField, secp256r1::Secp256r1Affine};
+    use itertools::Itertools;
+    use mock::{
+        interpreter,
+        test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+        TestContext,
+    };
+
+    use crate::test_util::CircuitTestBuilder;
+
+    /// Place the bytes, whose length is a multiple of 32, in memory from
+    /// address 0.
+    fn store_bytes(bytes: &[u8]) -> Bytecode {
+        let mut code = Bytecode::default();
+        for (i, word) in bytes.chunks(32).enumerate() {
+            code.push(32, Word::from_big_endian(word))
+                .push(32, Word::from(32 * i))
+                .write_op(OpcodeId::MSTORE);
+        }
+        code
+    }
+
+    /// Return the input of a call with the padding signature of the secp256r1
+    /// SignData, which signs the message hash 1 by the generator.
+    fn padding_signature_input() -> Vec<u8> {
+        let sign_data = SignData::<Secp256r1Affine>::padding();
+        let (sig_r, sig_s) = sign_data.signature;
+        [sign_data.msg_hash, sig_r, sig_s]
+            .iter()
+            .flat_map(|scalar| scalar.to_repr().into_iter().rev())
+            .chain(pk_bytes_swap_endianness(&pk_bytes_le(&sign_data.pk)))
+            .collect()
+    }
+
+    lazy_static::lazy_static! {
+        static ref TEST_VECTOR: Vec<PrecompileCallArgs> = {
+            let mut wrong_msg_hash = padding_signature_input();
+            wrong_msg_hash[31] = 2;
+            let mut invalid_pk = padding_signature_input();
+            invalid_pk[159] ^= 1;
+            vec![
+                PrecompileCallArgs {
+                    name: "p256verify (valid signature)",
+                    setup_code: store_bytes(&padding_signature_input()),
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0xa0.into(),
+                    ret_offset: 0xa0.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::P256Verify.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "p256verify (wrong message hash)",
+                    setup_code: store_bytes(&wrong_msg_hash),
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0xa0.into(),
+                    ret_offset: 0xa0.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::P256Verify.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "p256verify (public key not on the curve)",
+                    setup_code: store_bytes(&invalid_pk),
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0xa0.into(),
+                    ret_offset: 0xa0.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::P256Verify.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "p256verify (invalid length)",
+                    setup_code: store_bytes(&padding_signature_input()),
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x80.into(),
+                    ret_offset: 0xa0.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::P256Verify.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "p256verify (returned word partially copied)",
+                    setup_code: store_bytes(&padding_signature_input()),
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0xa0.into(),
+                    ret_offset: 0xb0.into(),
+                    ret_size: 0x10.into(),
+                    address: PrecompileCalls::P256Verify.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "p256verify (insufficient gas)",
+                    setup_code: store_bytes(&padding_signature_input()),
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0xa0.into(),
+                    ret_offset: 0xa0.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::P256Verify.address().to_word(),
+                    gas: (GasCost::PRECOMPILE_P256VERIFY - 1).to_word(),
+                    ..Default::default()
+                },
+            ]
+        };
+    }
+
+    #[test]
+    fn precompile_p256_verify_test() {
+        let call_kinds = vec![
+            OpcodeId::CALL,
+            OpcodeId::STATICCALL,
+            OpcodeId::DELEGATECALL,
+            OpcodeId::CALLCODE,
+        ];
+
+        for (test_vector, &call_kind) in TEST_VECTOR.iter().cartesian_product(&call_kinds) {
+            let bytecode = test_vector.with_call_op(call_kind);
+
+            // The geth-utils tracer doesn't support RIP-7212 yet.
+            let ctx = TestContext::<2, 1>::new_with_tracer(
+                None,
+                account_0_code_account_1_no_code(bytecode),
+                tx_from_1_to_0,
+                |block, _txs| block,
+                interpreter::trace,
+            )
+            .unwrap();
+            CircuitTestBuilder::new_from_test_ctx(ctx)
+                .params(FixedCParams {
+                    hardfork: Hardfork::Cancun,
+                    p256_verify: true,
+                    ..Default::default()
+                })
+                .run();
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use bus_mapping::{
+    circuit_input_builder::Call,
+    precompile::{PrecompileAuxData, PrecompileCalls},
+};
+use eth_types::{Field, U256};
+use gadgets::util::Expr;
+use halo2_proofs::plonk::Error;
+
+use super::{
+    PrecompileCallContextGadget, PrecompileCallExprs, PrecompileExecutionGadget, N_CALL_CONTEXT_RWS,
+};
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_MEMORY_WORD_SIZE, N_BYTES_WORD},
+        step::ExecutionState,
+        util::{
+            common_gadget::RestoreContextGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::ConstantDivisionGadget,
+            rlc, CachedRegion, Cell,
+        },
+    },
+    util::word::{Word32Cell, WordExpr},
+    witness::{Block, ExecStep, Transaction},
+};
+
+/// Gadget of the ripemd160 precompile.  The whole call data is hashed, and its
+/// digest is looked up in the Ripemd160Table.  The 20-byte digest is returned
+/// left-padded with zeros to a 32-byte word.
+#[derive(Clone, Debug)]
+pub struct Ripemd160Gadget<F> {
+    input_bytes_rlc: Cell<F>,
+    output_bytes_rlc: Cell<F>,
+
+    /// Digest of the input, left-padded to 32 bytes
+    output: Word32Cell<F>,
+    input_word_size: ConstantDivisionGadget<F, N_BYTES_MEMORY_WORD_SIZE>,
+
+    call_context: PrecompileCallContextGadget<F>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for Ripemd160Gadget<F> {
+    const EXECUTION_STATE: ExecutionState = ExecutionState::PrecompileRipemd160;
+
+    const NAME: &'static str = "RIPEMD160";
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // These cells are constrained by the PrecompileGadget of the call, so
+        // they must be the first ones of their types.
+        let input_bytes_rlc = cb.query_cell_phase2();
+        let output_bytes_rlc = cb.query_cell_phase2();
+
+        let output = cb.query_word32();
+
+        let call_context = PrecompileCallContextGadget::construct(cb);
+
+        cb.ripemd160_table_lookup(
+            input_bytes_rlc.expr(),
+            call_context.call_data_length.expr(),
+            output.to_word(),
+        );
+        cb.require_equal(
+            "output_bytes_rlc = RLC of the padded digest",
+            output_bytes_rlc.expr(),
+            rlc::expr(
+                &output.limbs.clone().map(|byte| byte.expr()),
+                cb.challenges().keccak_input(),
+            ),
+        );
+
+        let input_word_size = ConstantDivisionGadget::construct(
+            cb,
+            call_context.call_data_length.expr() + (N_BYTES_WORD - 1).expr(),
+            N_BYTES_WORD as u64,
+        );
+
+        let gas_cost = call_context.gas_cost(input_word_size.quotient());
+
+        // As for Sha256, the only failure is the insufficient gas, which is
+        // handled in the ErrorOogPrecompile gadget.
+        let restore_context = call_context.restore_context(cb, gas_cost, 0x20.expr());
+
+        Self {
+            input_bytes_rlc,
+            output_bytes_rlc,
+            output,
+            input_word_size,
+            call_context,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let aux_data = match &step.aux_data {
+            Some(PrecompileAuxData::Ripemd160(aux_data)) => aux_data,
+            _ => {
+                log::error!("missing the aux data of the ripemd160 step");
+                return Err(Error::Synthesis);
+            }
+        };
+        let randomness = region.challenges().keccak_input();
+
+        self.input_bytes_rlc.assign(
+            region,
+            offset,
+            randomness.map(|randomness| rlc::value(aux_data.input_bytes.iter().rev(), randomness)),
+        )?;
+        self.output_bytes_rlc.assign(
+            region,
+            offset,
+            randomness.map(|randomness| rlc::value(aux_data.output_bytes.iter().rev(), randomness)),
+        )?;
+        self.output.assign_u256(
+            region,
+            offset,
+            U256::from_big_endian(&aux_data.output_bytes),
+        )?;
+        self.input_word_size.assign(
+            region,
+            offset,
+            (call.call_data_length + (N_BYTES_WORD as u64) - 1).into(),
+        )?;
+
+        self.call_context.assign(region, offset, block, call)?;
+        self.restore_context
+            .assign(region, offset, block, call, step, N_CALL_CONTEXT_RWS)?;
+
+        Ok(())
+    }
+}
+
+impl<F: Field> PrecompileExecutionGadget<F> for Ripemd160Gadget<F> {
+    const PRECOMPILE: PrecompileCalls = PrecompileCalls::Ripemd160;
+
+    fn constrain_call(cb: &mut EVMConstraintBuilder<F>, call: &PrecompileCallExprs<F>) {
+        let (input_bytes_rlc, output_bytes_rlc) = (cb.query_cell_phase2(), cb.query_cell_phase2());
+        call.constrain_bytes(cb, input_bytes_rlc.expr(), output_bytes_rlc.expr());
+        cb.require_equal(
+            "ripemd160 returns 32 bytes",
+            call.return_length.clone(),
+            32.expr(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bus_mapping::{
+        evm::{OpcodeId, PrecompileCallArgs},
+        precompile::PrecompileCalls,
+    };
+    use eth_types::{bytecode, evm_types::GasCost, word, ToWord};
+    use itertools::Itertools;
+    use mock::TestContext;
+
+    use crate::test_util::CircuitTestBuilder;
+
+    lazy_static::lazy_static! {
+        static ref TEST_VECTOR: Vec<PrecompileCallArgs> = {
+            vec![
+                PrecompileCallArgs {
+                    name: "ripemd160 (single byte)",
+                    setup_code: bytecode! {
+                        // place params in memory
+                        PUSH1(0xff)
+                        PUSH1(0x00)
+                        MSTORE
+                    },
+                    call_data_offset: 0x1f.into(),
+                    call_data_length: 0x01.into(),
+                    ret_offset: 0x20.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::Ripemd160.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ripemd160 (more than a block)",
+                    setup_code: bytecode! {
+                        // place params in memory
+                        PUSH32(word!("0x0123456789abcdef0f1e2d3c4b5a6978aabbccdd001122331039abcdefefef84"))
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH32(word!("0xaabbccdd001122331039abcdefefef840123456789abcdef0f1e2d3c4b5a6978"))
+                        PUSH1(0x20)
+                        MSTORE
+                        PUSH32(word!("0x0123456789abcdef0f1e2d3c4b5a6978aabbccdd001122331039abcdefefef84"))
+                        PUSH1(0x40)
+                        MSTORE
+                    },
+                    // hash 70 bytes, which take 2 blocks
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x46.into(),
+                    // return only 16 bytes of the padded digest
+                    ret_offset: 0x60.into(),
+                    ret_size: 0x10.into(),
+                    address: PrecompileCalls::Ripemd160.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ripemd160 (no call data)",
+                    setup_code: bytecode! {},
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x00.into(),
+                    ret_offset: 0x00.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::Ripemd160.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ripemd160 (insufficient gas)",
+                    setup_code: bytecode! {},
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x20.into(),
+                    ret_offset: 0x20.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::Ripemd160.address().to_word(),
+                    gas: (PrecompileCalls::Ripemd160.base_gas_cost()
+                        + GasCost::PRECOMPILE_RIPEMD160_PER_WORD
+                        - 1).to_word(),
+                    ..Default::default()
+                },
+            ]
+        };
+    }
+
+    #[test]
+    fn precompile_ripemd160_test() {
+        let call_kinds = vec![
+            OpcodeId::CALL,
+            OpcodeId::STATICCALL,
+            OpcodeId::DELEGATECALL,
+            OpcodeId::CALLCODE,
+        ];
+
+        for (test_vector, &call_kind) in TEST_VECTOR.iter().cartesian_product(&call_kinds) {
+            let bytecode = test_vector.with_call_op(call_kind);
+
+            CircuitTestBuilder::new_from_test_ctx(
+                TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+            )
+            .run();
+        }
+    }
+}
@@ -237,8 +237,12 @@ mod test {
     }
 
     #[test]
-    fn push_gadget_simple() {
+    fn push_gadget_push0() {
         test_ok(OpcodeId::PUSH0, &[]);
+    }
+
+    #[test]
+    fn push_gadget_simple() {
         test_ok(OpcodeId::PUSH1, &[1]);
         test_ok(OpcodeId::PUSH2, &[1, 2]);
         test_ok(
@@ -261,7 +261,7 @@ impl<F: Field> ExecutionGadget<F> for ReturnDataCopyGadget<F> {
 #[cfg(test)]
 mod test {
     use crate::{evm_circuit::test::rand_bytes, test_util::CircuitTestBuilder};
-    use bus_mapping::circuit_input_builder::FixedCParams;
+    use bus_mapping::{circuit_input_builder::FixedCParams, precompile::PrecompileCalls};
     use eth_types::{bytecode, Word};
     use mock::{generate_mock_call_bytecode, test_ctx::TestContext, MockCallBytecodeParams};
 
@@ -321,6 +321,47 @@ mod test {
             .run();
     }
 
+    fn test_ok_after_precompile(precompile: PrecompileCalls, size: usize, offset: usize) {
+        // The output of the precompile isn't copied into the memory of the
+        // caller by the call, it's only available as return data.
+        let code = bytecode! {
+            PUSH32(Word::from_big_endian(&rand_bytes(32)))
+            PUSH1(0x00)
+            MSTORE
+            PUSH1(0x00) // retLength
+            PUSH1(0x00) // retOffset
+            PUSH1(0x20) // argsLength
+            PUSH1(0x00) // argsOffset
+            PUSH20(precompile.address()) // addr
+            GAS
+            STATICCALL
+            POP
+            RETURNDATASIZE
+            POP
+            PUSH32(size) // size
+            PUSH32(offset) // offset
+            PUSH32(0x40) // dest_offset
+            RETURNDATACOPY
+            STOP
+        };
+
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap(),
+        )
+        .params(FixedCParams {
+            max_rws: 2048,
+            ..Default::default()
+        })
+        .run();
+    }
+
+    #[test]
+    fn returndatacopy_gadget_after_precompile() {
+        test_ok_after_precompile(PrecompileCalls::Identity, 0x20, 0);
+        test_ok_after_precompile(PrecompileCalls::Identity, 0x10, 0x08);
+        test_ok_after_precompile(PrecompileCalls::Sha256, 0x20, 0);
+    }
+
     #[test]
     fn returndatacopy_gadget_do_nothing() {
         test_ok_internal(0, 2, 0, 0, 0x10.into());
@@ -0,0 +1,394 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            common_gadget::{RestoreContextGadget, TransferToGadget},
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
+                Transition::{Delta, Same},
+            },
+            math_gadget::{IsEqualWordGadget, IsZeroGadget, IsZeroWordGadget},
+            not, AccountAddress, CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::{AccountFieldTag, CallContextFieldTag},
+    util::{
+        word::{Word, Word32Cell, WordCell, WordExpr},
+        Expr,
+    },
+};
+use bus_mapping::{evm::OpcodeId, operation::CreatedAccountStatus};
+use eth_types::{evm_types::GasCost, Field, ToAddress, ToWord, U256};
+use halo2_proofs::{
+    circuit::Value,
+    plonk::{Error, Expression},
+};
+
+/// Gadget for SELFDESTRUCT with the EIP-6780 semantics: the whole balance of
+/// the account is sent to the beneficiary, and the account is only destructed
+/// (alongside its balance when it's its own beneficiary) when it has been
+/// created in the same transaction.  A destructed account is marked in the
+/// CreatedAccount rw table, and it's deleted by a DeleteAccount step at the
+/// end of the transaction.  There's no refund since EIP-3529.
+#[derive(Clone, Debug)]
+pub(crate) struct SelfdestructGadget<F> {
+    opcode: Cell<F>,
+    tx_id: Cell<F>,
+    is_static: Cell<F>,
+    reversion_info: ReversionInfo<F>,
+    address: WordCell<F>,
+    beneficiary: AccountAddress<F>,
+    is_warm: Cell<F>,
+    beneficiary_code_hash: WordCell<F>,
+    beneficiary_not_exists: IsZeroWordGadget<F, WordCell<F>>,
+    beneficiary_is_address: IsEqualWordGadget<F, Word<Expression<F>>, Word<Expression<F>>>,
+    is_self: Cell<F>,
+    created_account_status: Cell<F>,
+    is_not_created: IsZeroGadget<F>,
+    balance: Word32Cell<F>,
+    transfer: TransferToGadget<F>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for SelfdestructGadget<F> {
+    const NAME: &'static str = "SELFDESTRUCT";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::SELFDESTRUCT;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.opcode_lookup(opcode.expr(), 1.expr());
+        // We do the responsible opcode check explicitly here because we're not using
+        // the `SameContextGadget` for `SELFDESTRUCT`.
+        cb.require_equal(
+            "Opcode should be SELFDESTRUCT",
+            opcode.expr(),
+            OpcodeId::SELFDESTRUCT.expr(),
+        );
+
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        // constrain not in static call
+        let is_static = cb.call_context(None, CallContextFieldTag::IsStatic);
+        cb.require_zero("is_static is false", is_static.expr());
+        // Call ends with SELFDESTRUCT must be successful
+        cb.call_context_lookup_read(None, CallContextFieldTag::IsSuccess, Word::one());
+
+        let mut reversion_info = cb.reversion_info_read(None);
+        let address = cb.call_context_read_as_word(None, CallContextFieldTag::CalleeAddress);
+
+        let beneficiary = cb.query_account_address();
+        cb.stack_pop(beneficiary.to_word());
+
+        let is_warm = cb.query_bool();
+        cb.account_access_list_write_unchecked(
+            tx_id.expr(),
+            beneficiary.to_word(),
+            1.expr(),
+            is_warm.expr(),
+            Some(&mut reversion_info),
+        );
+
+        // For non-existing accounts the code_hash must be 0 in the rw_table.
+        let beneficiary_code_hash = cb.query_word_unchecked();
+        cb.account_read(
+            beneficiary.to_word(),
+            AccountFieldTag::CodeHash,
+            beneficiary_code_hash.to_word(),
+        );
+        let beneficiary_not_exists = IsZeroWordGadget::construct(cb, &beneficiary_code_hash);
+
+        let beneficiary_is_address =
+            IsEqualWordGadget::construct(cb, &beneficiary.to_word(), &address.to_word());
+        let is_self = cb.copy(beneficiary_is_address.expr());
+
+        // The account is destructed when it has been created in the tx, and
+        // maybe already destructed.
+        let created_account_status = cb.query_cell();
+        let is_not_created = IsZeroGadget::construct(cb, created_account_status.expr());
+        let is_destructed = not::expr(is_not_created.expr());
+        cb.created_account_write(
+            tx_id.expr(),
+            address.to_word(),
+            is_destructed.expr() * CreatedAccountStatus::Destructed.expr(),
+            created_account_status.expr(),
+            Some(&mut reversion_info),
+        );
+
+        // The balance is kept when the account is its own beneficiary, unless
+        // it's destructed, in which case it's burnt.
+        let balance = cb.query_word32();
+        cb.account_write(
+            address.to_word(),
+            AccountFieldTag::Balance,
+            balance
+                .to_word()
+                .mul_selector(is_self.expr() * not::expr(is_destructed.expr())),
+            balance.to_word(),
+            Some(&mut reversion_info),
+        );
+        let transfer = cb.condition(not::expr(is_self.expr()), |cb| {
+            TransferToGadget::construct(
+                cb,
+                beneficiary.to_word(),
+                not::expr(beneficiary_not_exists.expr()),
+                0.expr(),
+                balance.clone(),
+                Some(&mut reversion_info),
+                true,
+            )
+        });
+
+        // +1 Write TxAccessListAccount (beneficiary)
+        // +1 Write CreatedAccount (address)
+        // +1 Write Account (address) Balance
+        let reversible_write_counter_increase =
+            3.expr() + not::expr(is_self.expr()) * transfer.rw_delta();
+
+        let gas_cost = GasCost::SELFDESTRUCT.expr()
+            + not::expr(is_warm.expr()) * GasCost::COLD_ACCOUNT_ACCESS.expr()
+            + beneficiary_not_exists.expr()
+                * not::expr(transfer.value_is_zero.expr())
+                * GasCost::NEW_ACCOUNT.expr();
+
+        let is_to_end_tx = cb.next.execution_state_selector([ExecutionState::EndTx]);
+        cb.require_equal(
+            "Go to EndTx only when is_root",
+            cb.curr.state.is_root.expr(),
+            is_to_end_tx,
+        );
+
+        // When it's a root call
+        cb.condition(cb.curr.state.is_root.expr(), |cb| {
+            cb.require_step_state_transition(StepStateTransition {
+                call_id: Same,
+                rw_counter: Delta(cb.rw_counter_offset()),
+                gas_left: Delta(-gas_cost.clone()),
+                reversible_write_counter: Delta(reversible_write_counter_increase.clone()),
+                ..StepStateTransition::any()
+            });
+        });
+
+        // When it's an internal call, the gas cost is charged before the rest of
+        // the gas is returned to the caller.
+        let restore_context = cb.condition(1.expr() - cb.curr.state.is_root.expr(), |cb| {
+            RestoreContextGadget::construct(
+                cb,
+                true.expr(),
+                0.expr(),
+                0.expr(),
+                0.expr(),
+                gas_cost,
+                reversible_write_counter_increase,
+            )
+        });
+
+        Self {
+            opcode,
+            tx_id,
+            is_static,
+            reversion_info,
+            address,
+            beneficiary,
+            is_warm,
+            beneficiary_code_hash,
+            beneficiary_not_exists,
+            beneficiary_is_address,
+            is_self,
+            created_account_status,
+            is_not_created,
+            balance,
+            transfer,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode().unwrap();
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id)))?;
+        self.is_static
+            .assign(region, offset, Value::known(F::from(call.is_static as u64)))?;
+        self.reversion_info.assign(
+            region,
+            offset,
+            call.rw_counter_end_of_reversion,
+            call.is_persistent,
+        )?;
+        self.address.assign_h160(region, offset, call.address)?;
+
+        let beneficiary = block.get_rws(step, 6).stack_value().to_address();
+        self.beneficiary.assign_h160(region, offset, beneficiary)?;
+
+        let (_, is_warm) = block.get_rws(step, 7).tx_access_list_value_pair();
+        self.is_warm
+            .assign(region, offset, Value::known(F::from(is_warm as u64)))?;
+
+        let beneficiary_code_hash = block.get_rws(step, 8).account_codehash_pair().0;
+        self.beneficiary_code_hash
+            .assign_u256(region, offset, beneficiary_code_hash)?;
+        self.beneficiary_not_exists.assign_value(
+            region,
+            offset,
+            Value::known(Word::from(beneficiary_code_hash)),
+        )?;
+
+        let is_self = beneficiary == call.address;
+        self.beneficiary_is_address.assign_u256(
+            region,
+            offset,
+            beneficiary.to_word(),
+            call.address.to_word(),
+        )?;
+        self.is_self
+            .assign(region, offset, Value::known(F::from(is_self as u64)))?;
+
+        let (_, created_account_status) = block.get_rws(step, 9).created_account_value_pair();
+        self.created_account_status.assign(
+            region,
+            offset,
+            Value::known(F::from(created_account_status)),
+        )?;
+        self.is_not_created
+            .assign(region, offset, F::from(created_account_status))?;
+
+        let (_, balance) = block.get_rws(step, 10).account_balance_pair();
+        self.balance.assign_u256(region, offset, balance)?;
+
+        let mut rw_offset = 11;
+        let beneficiary_balance_pair = if is_self || balance.is_zero() {
+            (balance, U256::zero())
+        } else {
+            if beneficiary_code_hash.is_zero() {
+                rw_offset += 1;
+            }
+            rw_offset += 1;
+            block.get_rws(step, rw_offset - 1).account_balance_pair()
+        };
+        self.transfer
+            .assign(region, offset, beneficiary_balance_pair, balance)?;
+
+        if !call.is_root {
+            self.restore_context
+                .assign(region, offset, block, call, step, rw_offset)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{address, bytecode, Address, Bytecode, ToWord, Word};
+    use mock::{eth, TestContext, MOCK_ACCOUNTS};
+
+    const BENEFICIARY: Address = Address::repeat_byte(0xbe);
+
+    fn test_root_ok(code: Bytecode) {
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x0000000000000000000000000000000000000123"))
+                    .balance(eth(10));
+                accs[1].address(MOCK_ACCOUNTS[0]).balance(eth(1)).code(code);
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[0].address).to(accs[1].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    /// The contract selfdestructs in a call from a root contract that stops
+    /// afterwards.
+    fn test_internal_ok(callee_code: Bytecode, value: Word) {
+        let caller_code = bytecode! {
+            .op_call(0xffff, MOCK_ACCOUNTS[0], value, 0, 0, 0, 0)
+            STOP
+        };
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x0000000000000000000000000000000000000123"))
+                    .balance(eth(10));
+                accs[1]
+                    .address(MOCK_ACCOUNTS[1])
+                    .balance(eth(1))
+                    .code(caller_code);
+                accs[2]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(eth(1))
+                    .code(callee_code);
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[0].address).to(accs[1].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    /// The contract is created, and so destructed, in the tx: its init code
+    /// selfdestructs.
+    fn test_created_ok(beneficiary: Bytecode, value: Word) {
+        let init_code = {
+            let mut code = beneficiary;
+            code.write_op(eth_types::evm_types::OpcodeId::SELFDESTRUCT);
+            code.code()
+        };
+        let mut code = Bytecode::default();
+        for (index, byte) in init_code.iter().enumerate() {
+            code.op_mstore8(index, *byte as u64);
+        }
+        code.op_create(value, 0, init_code.len()).op_stop();
+        test_root_ok(code);
+    }
+
+    #[test]
+    fn selfdestruct_gadget_root() {
+        test_root_ok(bytecode! { .op_selfdestruct(BENEFICIARY.to_word()) });
+        test_root_ok(bytecode! { .op_selfdestruct(MOCK_ACCOUNTS[1].to_word()) });
+    }
+
+    #[test]
+    fn selfdestruct_gadget_root_to_itself() {
+        test_root_ok(bytecode! {
+            ADDRESS
+            SELFDESTRUCT
+        });
+    }
+
+    #[test]
+    fn selfdestruct_gadget_internal() {
+        let code = bytecode! { .op_selfdestruct(BENEFICIARY.to_word()) };
+        test_internal_ok(code.clone(), Word::zero());
+        test_internal_ok(code, eth(1));
+    }
+
+    #[test]
+    fn selfdestruct_gadget_created_in_tx() {
+        test_created_ok(bytecode! { PUSH20(BENEFICIARY.to_word()) }, Word::zero());
+        test_created_ok(bytecode! { PUSH20(BENEFICIARY.to_word()) }, eth(1));
+        test_created_ok(bytecode! { ADDRESS }, eth(1));
+    }
+}
@@ -410,7 +410,11 @@ fn calc_expected_tx_refund(
 mod test {
 
     use crate::test_util::CircuitTestBuilder;
-    use eth_types::{bytecode, Word};
+    use eth_types::{
+        bytecode,
+        evm_types::{GasCost, OpcodeId},
+        Word,
+    };
     use mock::{test_ctx::helpers::tx_from_1_to_0, TestContext, MOCK_ACCOUNTS};
 
     #[test]
@@ -474,6 +478,44 @@ mod test {
         );
     }
 
+    #[test]
+    fn sstore_gadget_above_gas_sentry() {
+        // A cold SSTORE of the current value costs 2_200, less than the gas sentry, but the gas
+        // left must still be above the sentry.
+        let (key, value) = (Word::from(0x030201), Word::from(0x060504));
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(Word::from(10u64.pow(19)))
+                    .code(bytecode! {
+                        PUSH32(value)
+                        PUSH32(key)
+                        SSTORE
+                        STOP
+                    })
+                    .storage(vec![(key, value)].into_iter());
+                accs[1]
+                    .address(MOCK_ACCOUNTS[1])
+                    .balance(Word::from(10u64.pow(19)));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[1].address).to(accs[0].address).gas(
+                    (GasCost::TX
+                        + 2 * OpcodeId::PUSH32.constant_gas_cost()
+                        + GasCost::SSTORE_SENTRY
+                        + 1)
+                    .into(),
+                );
+            },
+            |block, _txs| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
     fn test_ok(key: Word, value: Word, value_prev: Word, original_value: Word) {
         // Here we use two bytecodes to test both is_persistent(STOP) or not(REVERT)
         // Besides, in bytecode we use two SSTOREs,
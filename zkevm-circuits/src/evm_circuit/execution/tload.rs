@@ -0,0 +1,162 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            common_gadget::SameContextGadget,
+            constraint_builder::{EVMConstraintBuilder, StepStateTransition, Transition::Delta},
+            CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::CallContextFieldTag,
+    util::{
+        word::{WordCell, WordExpr},
+        Expr,
+    },
+};
+use bus_mapping::evm::OpcodeId;
+use eth_types::Field;
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+#[derive(Clone, Debug)]
+pub(crate) struct TloadGadget<F> {
+    same_context: SameContextGadget<F>,
+    tx_id: Cell<F>,
+    callee_address: WordCell<F>,
+    key: WordCell<F>,
+    value: WordCell<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for TloadGadget<F> {
+    const NAME: &'static str = "TLOAD";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::TLOAD;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        let callee_address = cb.call_context_read_as_word(None, CallContextFieldTag::CalleeAddress);
+
+        let key = cb.query_word_unchecked();
+        // Pop the key from the stack
+        cb.stack_pop(key.to_word());
+
+        // The transient storage is keyed by the tx, so that it's empty at the
+        // beginning of every tx.
+        let value = cb.query_word_unchecked();
+        cb.transient_storage_read(
+            tx_id.expr(),
+            callee_address.to_word(),
+            key.to_word(),
+            value.to_word(),
+        );
+
+        cb.stack_push(value.to_word());
+
+        let step_state_transition = StepStateTransition {
+            rw_counter: Delta(5.expr()),
+            program_counter: Delta(1.expr()),
+            gas_left: Delta(-OpcodeId::TLOAD.constant_gas_cost().expr()),
+            ..Default::default()
+        };
+        let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
+
+        Self {
+            same_context,
+            tx_id,
+            callee_address,
+            key,
+            value,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.same_context.assign_exec_step(region, offset, step)?;
+
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id)))?;
+        self.callee_address
+            .assign_h160(region, offset, call.address)?;
+
+        let key = block.get_rws(step, 2).stack_value();
+        let value = block.get_rws(step, 4).stack_value();
+        self.key.assign_u256(region, offset, key)?;
+        self.value.assign_u256(region, offset, value)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use bus_mapping::circuit_input_builder::FixedCParams;
+    use eth_types::{
+        bytecode,
+        evm_types::{Hardfork, OpcodeId},
+        Bytecode, Word,
+    };
+    use mock::{
+        interpreter,
+        test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+        TestContext,
+    };
+
+    fn run(code: Bytecode) {
+        // The geth-utils tracer doesn't support EIP-1153 yet.
+        let ctx = TestContext::<2, 1>::new_with_tracer(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _txs| block,
+            interpreter::trace,
+        )
+        .unwrap();
+        // go-ethereum charges the warm storage read cost for both opcodes.
+        for step in ctx.geth_traces[0].struct_logs.iter() {
+            if matches!(step.op, OpcodeId::TLOAD | OpcodeId::TSTORE) {
+                assert_eq!(step.gas_cost, 100);
+            }
+        }
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(FixedCParams {
+                hardfork: Hardfork::Cancun,
+                ..Default::default()
+            })
+            .run();
+    }
+
+    fn test_ok(key: Word, value: Word) {
+        // The first TLOAD reads the empty transient storage, the second one the
+        // value written by TSTORE.
+        let code = bytecode! {
+            .op_tload(key)
+            .op_tstore(key, value)
+            .op_tload(key)
+            STOP
+        };
+
+        run(code);
+    }
+
+    #[test]
+    fn tload_gadget_simple() {
+        test_ok(0x030201.into(), 0x060504.into());
+    }
+
+    #[test]
+    fn tload_gadget_max() {
+        test_ok(Word::MAX, Word::MAX);
+    }
+}
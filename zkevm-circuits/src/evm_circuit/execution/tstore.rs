@@ -0,0 +1,200 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            common_gadget::SameContextGadget,
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
+                Transition::Delta,
+            },
+            CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::CallContextFieldTag,
+    util::{
+        word::{WordCell, WordExpr},
+        Expr,
+    },
+};
+use bus_mapping::evm::OpcodeId;
+use eth_types::Field;
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+#[derive(Clone, Debug)]
+pub(crate) struct TstoreGadget<F> {
+    same_context: SameContextGadget<F>,
+    tx_id: Cell<F>,
+    is_static: Cell<F>,
+    reversion_info: ReversionInfo<F>,
+    callee_address: WordCell<F>,
+    key: WordCell<F>,
+    value: WordCell<F>,
+    value_prev: WordCell<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for TstoreGadget<F> {
+    const NAME: &'static str = "TSTORE";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::TSTORE;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+
+        // constrain not in static call
+        let is_static = cb.call_context(None, CallContextFieldTag::IsStatic);
+        cb.require_zero("is_static is false", is_static.expr());
+
+        let mut reversion_info = cb.reversion_info_read(None);
+        let callee_address = cb.call_context_read_as_word(None, CallContextFieldTag::CalleeAddress);
+
+        let key = cb.query_word_unchecked();
+        // Pop the key from the stack
+        cb.stack_pop(key.to_word());
+
+        let value = cb.query_word_unchecked();
+        // Pop the value from the stack
+        cb.stack_pop(value.to_word());
+
+        // The write is reverted when the call fails, like the writes to the
+        // storage.
+        let value_prev = cb.query_word_unchecked();
+        cb.transient_storage_write(
+            tx_id.expr(),
+            callee_address.to_word(),
+            key.to_word(),
+            value.to_word(),
+            value_prev.to_word(),
+            Some(&mut reversion_info),
+        );
+
+        let step_state_transition = StepStateTransition {
+            rw_counter: Delta(8.expr()),
+            program_counter: Delta(1.expr()),
+            stack_pointer: Delta(2.expr()),
+            reversible_write_counter: Delta(1.expr()),
+            gas_left: Delta(-OpcodeId::TSTORE.constant_gas_cost().expr()),
+            ..Default::default()
+        };
+        let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
+
+        Self {
+            same_context,
+            tx_id,
+            is_static,
+            reversion_info,
+            callee_address,
+            key,
+            value,
+            value_prev,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.same_context.assign_exec_step(region, offset, step)?;
+
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id)))?;
+        self.is_static
+            .assign(region, offset, Value::known(F::from(call.is_static as u64)))?;
+        self.reversion_info.assign(
+            region,
+            offset,
+            call.rw_counter_end_of_reversion,
+            call.is_persistent,
+        )?;
+        self.callee_address
+            .assign_h160(region, offset, call.address)?;
+
+        let key = block.get_rws(step, 5).stack_value();
+        let value = block.get_rws(step, 6).stack_value();
+        self.key.assign_u256(region, offset, key)?;
+        self.value.assign_u256(region, offset, value)?;
+
+        let (_, value_prev) = block.get_rws(step, 7).transient_storage_value_pair();
+        self.value_prev.assign_u256(region, offset, value_prev)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use bus_mapping::circuit_input_builder::FixedCParams;
+    use eth_types::{
+        bytecode,
+        evm_types::{Hardfork, OpcodeId},
+        Bytecode, Word,
+    };
+    use mock::{
+        interpreter,
+        test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+        TestContext,
+    };
+
+    fn run(code: Bytecode) {
+        // The geth-utils tracer doesn't support EIP-1153 yet.
+        let ctx = TestContext::<2, 1>::new_with_tracer(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _txs| block,
+            interpreter::trace,
+        )
+        .unwrap();
+        // go-ethereum charges the warm storage read cost for TSTORE.
+        for step in ctx.geth_traces[0].struct_logs.iter() {
+            if step.op == OpcodeId::TSTORE {
+                assert_eq!(step.gas_cost, 100);
+            }
+        }
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(FixedCParams {
+                hardfork: Hardfork::Cancun,
+                ..Default::default()
+            })
+            .run();
+    }
+
+    fn test_ok(key: Word, value: Word) {
+        // Here we use two bytecodes to test both is_persistent(STOP) or not(REVERT)
+        // Besides, in bytecode we use two TSTOREs, the second one overwrites the
+        // value of the first one
+        let bytecode_success = bytecode! {
+            .op_tstore(key, value)
+            .op_tstore(key, Word::MAX - value)
+            STOP
+        };
+        let bytecode_failure = bytecode! {
+            .op_tstore(key, value)
+            .op_tstore(key, Word::MAX - value)
+            .op_revert(0, 0)
+        };
+        for code in [bytecode_success, bytecode_failure] {
+            run(code);
+        }
+    }
+
+    #[test]
+    fn tstore_gadget_simple() {
+        test_ok(0x030201.into(), 0x060504.into());
+    }
+
+    #[test]
+    fn tstore_gadget_zero_value() {
+        test_ok(0x030201.into(), Word::zero());
+    }
+}
@@ -41,7 +41,15 @@ pub(crate) const EVM_LOOKUP_COLS: usize = FIXED_TABLE_LOOKUPS
     + BLOCK_TABLE_LOOKUPS
     + COPY_TABLE_LOOKUPS
     + KECCAK_TABLE_LOOKUPS
-    + EXP_TABLE_LOOKUPS;
+    + EXP_TABLE_LOOKUPS
+    + SIG_TABLE_LOOKUPS
+    + SHA256_TABLE_LOOKUPS
+    + RIPEMD160_TABLE_LOOKUPS
+    + MODEXP_TABLE_LOOKUPS
+    + ECC_TABLE_LOOKUPS
+    + BLAKE2F_TABLE_LOOKUPS
+    + P256_SIG_TABLE_LOOKUPS
+    + BLOCK_HASH_TABLE_LOOKUPS;
 
 /// Lookups done per row.
 pub const LOOKUP_CONFIG: &[(Table, usize)] = &[
@@ -53,6 +61,14 @@ pub const LOOKUP_CONFIG: &[(Table, usize)] = &[
     (Table::Copy, COPY_TABLE_LOOKUPS),
     (Table::Keccak, KECCAK_TABLE_LOOKUPS),
     (Table::Exp, EXP_TABLE_LOOKUPS),
+    (Table::Sig, SIG_TABLE_LOOKUPS),
+    (Table::Sha256, SHA256_TABLE_LOOKUPS),
+    (Table::Ripemd160, RIPEMD160_TABLE_LOOKUPS),
+    (Table::ModExp, MODEXP_TABLE_LOOKUPS),
+    (Table::Ecc, ECC_TABLE_LOOKUPS),
+    (Table::Blake2f, BLAKE2F_TABLE_LOOKUPS),
+    (Table::P256Sig, P256_SIG_TABLE_LOOKUPS),
+    (Table::BlockHash, BLOCK_HASH_TABLE_LOOKUPS),
 ];
 
 /// Fixed Table lookups done in EVMCircuit
@@ -79,6 +95,30 @@ pub const KECCAK_TABLE_LOOKUPS: usize = 1;
 /// Exp Table lookups done in EVMCircuit
 pub const EXP_TABLE_LOOKUPS: usize = 1;
 
+/// Sig Table lookups done in EVMCircuit
+pub const SIG_TABLE_LOOKUPS: usize = 1;
+
+/// Sha256 Table lookups done in EVMCircuit
+pub const SHA256_TABLE_LOOKUPS: usize = 1;
+
+/// Ripemd160 Table lookups done in EVMCircuit
+pub const RIPEMD160_TABLE_LOOKUPS: usize = 1;
+
+/// ModExp Table lookups done in EVMCircuit
+pub const MODEXP_TABLE_LOOKUPS: usize = 1;
+
+/// Ecc Table lookups done in EVMCircuit
+pub const ECC_TABLE_LOOKUPS: usize = 1;
+
+/// Blake2f Table lookups done in EVMCircuit
+pub const BLAKE2F_TABLE_LOOKUPS: usize = 1;
+
+/// P256Sig Table lookups done in EVMCircuit
+pub const P256_SIG_TABLE_LOOKUPS: usize = 1;
+
+/// BlockHash Table lookups done in EVMCircuit
+pub const BLOCK_HASH_TABLE_LOOKUPS: usize = 1;
+
 /// Maximum number of bytes that an integer can fit in field without wrapping
 /// around.
 pub(crate) const MAX_N_BYTES_INTEGER: usize = 31;
@@ -125,6 +165,9 @@ pub(crate) const N_BYTES_BASE_FEE: usize = N_BYTES_WORD;
 pub(crate) const N_BYTES_CHAIN_ID: usize = N_BYTES_U64;
 pub(crate) const N_BYTES_PREV_HASH: usize = 256 * N_BYTES_WORD;
 pub(crate) const N_BYTES_WITHDRAWAL_ROOT: usize = N_BYTES_WORD;
+pub(crate) const N_BYTES_BLOB_BASE_FEE: usize = N_BYTES_WORD;
+pub(crate) const N_BYTES_PREV_RANDAO: usize = N_BYTES_WORD;
+pub(crate) const N_BYTES_PARENT_BEACON_ROOT: usize = N_BYTES_WORD;
 
 pub(crate) const N_BYTES_BLOCK: usize = N_BYTES_COINBASE
     + N_BYTES_GAS_LIMIT
@@ -134,7 +177,10 @@ pub(crate) const N_BYTES_BLOCK: usize = N_BYTES_COINBASE
     + N_BYTES_BASE_FEE
     + N_BYTES_CHAIN_ID
     + N_BYTES_PREV_HASH
-    + N_BYTES_WITHDRAWAL_ROOT;
+    + N_BYTES_WITHDRAWAL_ROOT
+    + N_BYTES_BLOB_BASE_FEE
+    + N_BYTES_PREV_RANDAO
+    + N_BYTES_PARENT_BEACON_ROOT;
 
 pub(crate) const N_BYTES_EXTRA_VALUE: usize = N_BYTES_WORD // block hash
     + N_BYTES_WORD // state root
@@ -20,11 +20,16 @@ use crate::{
 };
 use bus_mapping::{
     circuit_input_builder::ExecState,
-    error::{DepthError, ExecError, InsufficientBalanceError, NonceUintOverflowError, OogError},
+    error::{
+        ContractAddressCollisionError, DepthError, ExecError, InsufficientBalanceError,
+        NonceUintOverflowError, OogError,
+    },
     evm::OpcodeId,
     precompile::PrecompileCalls,
 };
-use eth_types::{evm_unimplemented, Field, ToWord};
+#[cfg(feature = "custom-opcodes")]
+use eth_types::evm_types::custom_opcodes::custom_opcodes;
+use eth_types::{Field, ToWord};
 use halo2_proofs::{
     circuit::Value,
     plonk::{Advice, Column, ConstraintSystem, Error, Expression},
@@ -45,6 +50,8 @@ impl From<PrecompileCalls> for ExecutionState {
             PrecompileCalls::Bn128Mul => ExecutionState::PrecompileBn256ScalarMul,
             PrecompileCalls::Bn128Pairing => ExecutionState::PrecompileBn256Pairing,
             PrecompileCalls::Blake2F => ExecutionState::PrecompileBlake2f,
+            PrecompileCalls::PointEvaluation => ExecutionState::PrecompilePointEvaluation,
+            PrecompileCalls::P256Verify => ExecutionState::PrecompileP256Verify,
         }
     }
 }
@@ -57,8 +64,11 @@ pub enum ExecutionState {
     // Internal state
     BeginTx,
     EndTx,
+    DeleteAccount,
     EndBlock,
     InvalidTx,
+    BeaconRoot,
+    HistoryStorage,
     // Opcode successful cases
     STOP,
     /// ADD and SUB opcodes share this state
@@ -105,17 +115,22 @@ pub enum ExecutionState {
     BLOCKCTX,
     CHAINID,
     SELFBALANCE,
+    BLOBHASH,
+    BLOBBASEFEE,
     POP,
     /// MLOAD, MSTORE, MSTORE8
     MEMORY,
     SLOAD,
     SSTORE,
+    TLOAD,
+    TSTORE,
     JUMP,
     JUMPI,
     PC,
     MSIZE,
     GAS,
     JUMPDEST,
+    MCOPY,
     /// PUSH0, PUSH1, PUSH2, ..., PUSH32
     PUSH,
     /// DUP1, DUP2, ..., DUP16
@@ -130,13 +145,16 @@ pub enum ExecutionState {
     RETURN_REVERT,
     CREATE2,
     SELFDESTRUCT,
+    /// The custom opcodes of an L2
+    #[cfg(feature = "custom-opcodes")]
+    CUSTOM,
     // Error cases
     ErrorInvalidOpcode,
     ErrorStack,
     ErrorWriteProtection,
-    ErrorDepth,
-    ErrorInsufficientBalance,
-    ErrorContractAddressCollision,
+    // The depth, insufficient balance, nonce overflow and address collision
+    // errors don't halt the current call, they're handled by the `CALL_OP`,
+    // `CREATE` and `CREATE2` gadgets instead.
     ErrorInvalidCreationCode,
     ErrorCodeStore, // combine ErrorMaxCodeSizeExceeded and ErrorOutOfGasCodeStore
     ErrorInvalidJump,
@@ -167,6 +185,8 @@ pub enum ExecutionState {
     PrecompileBn256ScalarMul,
     PrecompileBn256Pairing,
     PrecompileBlake2f,
+    PrecompilePointEvaluation,
+    PrecompileP256Verify,
 }
 
 impl Default for ExecutionState {
@@ -202,7 +222,10 @@ impl From<&ExecError> for ExecutionState {
                 NonceUintOverflowError::Create => ExecutionState::CREATE,
                 NonceUintOverflowError::Create2 => ExecutionState::CREATE2,
             },
-            ExecError::ContractAddressCollision => ExecutionState::CREATE2,
+            ExecError::ContractAddressCollision(collision_err) => match collision_err {
+                ContractAddressCollisionError::Create => ExecutionState::CREATE,
+                ContractAddressCollisionError::Create2 => ExecutionState::CREATE2,
+            },
             ExecError::InvalidCreationCode => ExecutionState::ErrorInvalidCreationCode,
             ExecError::InvalidJump => ExecutionState::ErrorInvalidJump,
             ExecError::ReturnDataOutOfBounds => ExecutionState::ErrorReturnDataOutOfBound,
@@ -253,13 +276,6 @@ impl From<&ExecStep> for ExecutionState {
                     return ExecutionState::LOG;
                 }
 
-                macro_rules! dummy {
-                    ($name:expr) => {{
-                        evm_unimplemented!("{:?} is implemented with DummyGadget", $name);
-                        $name
-                    }};
-                }
-
                 match op {
                     OpcodeId::ADD | OpcodeId::SUB => ExecutionState::ADD_SUB,
                     OpcodeId::ADDMOD => ExecutionState::ADDMOD,
@@ -283,6 +299,7 @@ impl From<&ExecStep> for ExecutionState {
                     OpcodeId::MSTORE => ExecutionState::MEMORY,
                     OpcodeId::MSTORE8 => ExecutionState::MEMORY,
                     OpcodeId::JUMPDEST => ExecutionState::JUMPDEST,
+                    OpcodeId::MCOPY => ExecutionState::MCOPY,
                     OpcodeId::JUMP => ExecutionState::JUMP,
                     OpcodeId::JUMPI => ExecutionState::JUMPI,
                     OpcodeId::GASPRICE => ExecutionState::GASPRICE,
@@ -306,9 +323,13 @@ impl From<&ExecStep> for ExecutionState {
                     OpcodeId::SHL | OpcodeId::SHR => ExecutionState::SHL_SHR,
                     OpcodeId::SLOAD => ExecutionState::SLOAD,
                     OpcodeId::SSTORE => ExecutionState::SSTORE,
+                    OpcodeId::TLOAD => ExecutionState::TLOAD,
+                    OpcodeId::TSTORE => ExecutionState::TSTORE,
                     OpcodeId::CALLDATASIZE => ExecutionState::CALLDATASIZE,
                     OpcodeId::CALLDATACOPY => ExecutionState::CALLDATACOPY,
                     OpcodeId::CHAINID => ExecutionState::CHAINID,
+                    OpcodeId::BLOBHASH => ExecutionState::BLOBHASH,
+                    OpcodeId::BLOBBASEFEE => ExecutionState::BLOBBASEFEE,
                     OpcodeId::ISZERO => ExecutionState::ISZERO,
                     OpcodeId::CALL
                     | OpcodeId::CALLCODE
@@ -324,8 +345,9 @@ impl From<&ExecStep> for ExecutionState {
                     OpcodeId::RETURNDATACOPY => ExecutionState::RETURNDATACOPY,
                     OpcodeId::CREATE => ExecutionState::CREATE,
                     OpcodeId::CREATE2 => ExecutionState::CREATE2,
-                    // dummy ops
-                    OpcodeId::SELFDESTRUCT => dummy!(ExecutionState::SELFDESTRUCT),
+                    OpcodeId::SELFDESTRUCT => ExecutionState::SELFDESTRUCT,
+                    #[cfg(feature = "custom-opcodes")]
+                    op if op.is_custom() => ExecutionState::CUSTOM,
                     _ => unimplemented!("unimplemented opcode {:?}", op),
                 }
             }
@@ -339,11 +361,16 @@ impl From<&ExecStep> for ExecutionState {
                 PrecompileCalls::Bn128Mul => ExecutionState::PrecompileBn256ScalarMul,
                 PrecompileCalls::Bn128Pairing => ExecutionState::PrecompileBn256Pairing,
                 PrecompileCalls::Blake2F => ExecutionState::PrecompileBlake2f,
+                PrecompileCalls::PointEvaluation => ExecutionState::PrecompilePointEvaluation,
+                PrecompileCalls::P256Verify => ExecutionState::PrecompileP256Verify,
             },
             ExecState::BeginTx => ExecutionState::BeginTx,
             ExecState::EndTx => ExecutionState::EndTx,
+            ExecState::DeleteAccount => ExecutionState::DeleteAccount,
             ExecState::EndBlock => ExecutionState::EndBlock,
             ExecState::InvalidTx => ExecutionState::InvalidTx,
+            ExecState::BeaconRoot => ExecutionState::BeaconRoot,
+            ExecState::HistoryStorage => ExecutionState::HistoryStorage,
         }
     }
 }
@@ -379,25 +406,11 @@ impl ExecutionState {
                 | Self::PrecompileBn256ScalarMul
                 | Self::PrecompileBn256Pairing
                 | Self::PrecompileBlake2f
+                | Self::PrecompilePointEvaluation
+                | Self::PrecompileP256Verify
         )
     }
 
-    pub(crate) fn precompile_base_gas_cost(&self) -> u64 {
-        (match self {
-            Self::PrecompileEcRecover => PrecompileCalls::ECRecover,
-            Self::PrecompileSha256 => PrecompileCalls::Sha256,
-            Self::PrecompileRipemd160 => PrecompileCalls::Ripemd160,
-            Self::PrecompileIdentity => PrecompileCalls::Identity,
-            Self::PrecompileBigModExp => PrecompileCalls::Modexp,
-            Self::PrecompileBn256Add => PrecompileCalls::Bn128Add,
-            Self::PrecompileBn256ScalarMul => PrecompileCalls::Bn128Mul,
-            Self::PrecompileBn256Pairing => PrecompileCalls::Bn128Pairing,
-            Self::PrecompileBlake2f => PrecompileCalls::Blake2F,
-            _ => return 0,
-        })
-        .base_gas_cost()
-    }
-
     pub(crate) fn halts_in_exception(&self) -> bool {
         matches!(
             self,
@@ -488,18 +501,23 @@ impl ExecutionState {
             ],
             Self::CHAINID => vec![OpcodeId::CHAINID],
             Self::SELFBALANCE => vec![OpcodeId::SELFBALANCE],
+            Self::BLOBHASH => vec![OpcodeId::BLOBHASH],
+            Self::BLOBBASEFEE => vec![OpcodeId::BLOBBASEFEE],
             Self::POP => vec![OpcodeId::POP],
             Self::MEMORY => {
                 vec![OpcodeId::MLOAD, OpcodeId::MSTORE, OpcodeId::MSTORE8]
             }
             Self::SLOAD => vec![OpcodeId::SLOAD],
             Self::SSTORE => vec![OpcodeId::SSTORE],
+            Self::TLOAD => vec![OpcodeId::TLOAD],
+            Self::TSTORE => vec![OpcodeId::TSTORE],
             Self::JUMP => vec![OpcodeId::JUMP],
             Self::JUMPI => vec![OpcodeId::JUMPI],
             Self::PC => vec![OpcodeId::PC],
             Self::MSIZE => vec![OpcodeId::MSIZE],
             Self::GAS => vec![OpcodeId::GAS],
             Self::JUMPDEST => vec![OpcodeId::JUMPDEST],
+            Self::MCOPY => vec![OpcodeId::MCOPY],
             Self::PUSH => vec![
                 OpcodeId::PUSH0,
                 OpcodeId::PUSH1,
@@ -588,6 +606,11 @@ impl ExecutionState {
             Self::RETURN_REVERT => vec![OpcodeId::RETURN, OpcodeId::REVERT],
             Self::CREATE2 => vec![OpcodeId::CREATE2],
             Self::SELFDESTRUCT => vec![OpcodeId::SELFDESTRUCT],
+            #[cfg(feature = "custom-opcodes")]
+            Self::CUSTOM => custom_opcodes()
+                .iter()
+                .map(|info| info.opcode_id())
+                .collect(),
             Self::ErrorInvalidOpcode => OpcodeId::invalid_opcodes(),
             _ => vec![],
         }
@@ -6,7 +6,7 @@ use crate::{
     util::word::Word,
 };
 use bus_mapping::{evm::OpcodeId, precompile::PrecompileCalls};
-use eth_types::Field;
+use eth_types::{evm_types::Hardfork, Field};
 use gadgets::util::Expr;
 use halo2_proofs::plonk::Expression;
 use strum::IntoEnumIterator;
@@ -49,12 +49,14 @@ pub enum FixedTableTag {
     ConstantGasCost,
     /// Preocmpile information
     PrecompileInfo,
+    /// Lookup base and per-word gas costs for precompiles
+    PrecompileGasCost,
 }
 impl_expr!(FixedTableTag);
 
 impl FixedTableTag {
-    /// build up the fixed table row values
-    pub(crate) fn build<F: Field>(&self) -> Box<dyn Iterator<Item = [F; 4]>> {
+    /// build up the fixed table row values for the rules of a hardfork
+    pub(crate) fn build<F: Field>(&self, hardfork: Hardfork) -> Box<dyn Iterator<Item = [F; 4]>> {
         let tag = F::from(*self as u64);
         match self {
             Self::Zero => Box::new((0..1).map(move |_| [tag, F::ZERO, F::ZERO, F::ZERO])),
@@ -101,8 +103,22 @@ impl FixedTableTag {
             })),
             Self::ResponsibleOpcode => {
                 Box::new(ExecutionState::iter().flat_map(move |execution_state| {
-                    execution_state.responsible_opcodes().into_iter().map(
-                        move |responsible_opcode| {
+                    let mut responsible_opcodes = execution_state.responsible_opcodes();
+                    // The opcodes which aren't enabled yet in the hardfork are
+                    // executed as invalid opcodes.
+                    if execution_state == ExecutionState::ErrorInvalidOpcode {
+                        responsible_opcodes.extend(
+                            hardfork
+                                .inactive_opcodes()
+                                .into_iter()
+                                .map(ResponsibleOp::Op),
+                        );
+                    } else {
+                        responsible_opcodes.retain(|op| hardfork.is_opcode_active(op.opcode()));
+                    }
+                    responsible_opcodes
+                        .into_iter()
+                        .map(move |responsible_opcode| {
                             let (op, aux) = match responsible_opcode {
                                 ResponsibleOp::Op(op) => (op, F::ZERO),
                                 ResponsibleOp::InvalidStackPtr(op, stack_ptr) => {
@@ -115,8 +131,7 @@ impl FixedTableTag {
                                 F::from(op.as_u64()),
                                 aux,
                             ]
-                        },
-                    )
+                        })
                 }))
             }
             Self::Pow2 => Box::new((0..256).map(move |value| {
@@ -139,35 +154,48 @@ impl FixedTableTag {
                         ]
                     }),
             ),
-            Self::PrecompileInfo => Box::new(
-                vec![
-                    PrecompileCalls::ECRecover,
-                    PrecompileCalls::Sha256,
-                    PrecompileCalls::Ripemd160,
-                    PrecompileCalls::Identity,
-                    PrecompileCalls::Modexp,
-                    PrecompileCalls::Bn128Add,
-                    PrecompileCalls::Bn128Mul,
-                    PrecompileCalls::Bn128Pairing,
-                    PrecompileCalls::Blake2F,
+            Self::PrecompileInfo => Box::new(PRECOMPILES.into_iter().map(move |precompile| {
+                [
+                    tag,
+                    F::from({
+                        let state: ExecutionState = precompile.into();
+                        state.as_u64()
+                    }),
+                    F::from(u64::from(precompile)),
+                    F::ZERO,
                 ]
-                .into_iter()
-                .map(move |precompile| {
-                    [
+            })),
+            Self::PrecompileGasCost => {
+                Box::new(PRECOMPILES.into_iter().filter_map(move |precompile| {
+                    let gas_cost = precompile.gas_cost(hardfork)?;
+                    Some([
                         tag,
-                        F::from({
-                            let state: ExecutionState = precompile.into();
-                            state.as_u64()
-                        }),
                         F::from(u64::from(precompile)),
-                        F::from(precompile.base_gas_cost()),
-                    ]
-                }),
-            ),
+                        F::from(gas_cost.base),
+                        F::from(gas_cost.per_word),
+                    ])
+                }))
+            }
         }
     }
 }
 
+/// The precompiles which have a gadget, and ecPairing and the point
+/// evaluation, whose gas costs are still checked by `ErrorOOGPrecompile`.
+const PRECOMPILES: [PrecompileCalls; 11] = [
+    PrecompileCalls::ECRecover,
+    PrecompileCalls::Sha256,
+    PrecompileCalls::Ripemd160,
+    PrecompileCalls::Identity,
+    PrecompileCalls::Modexp,
+    PrecompileCalls::Bn128Add,
+    PrecompileCalls::Bn128Mul,
+    PrecompileCalls::Bn128Pairing,
+    PrecompileCalls::Blake2F,
+    PrecompileCalls::PointEvaluation,
+    PrecompileCalls::P256Verify,
+];
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, EnumIter)]
 /// Each item represents the lookup table to query
 pub enum Table {
@@ -191,6 +219,22 @@ pub enum Table {
     Keccak,
     /// Lookup for exp table
     Exp,
+    /// Lookup for signature table
+    Sig,
+    /// Lookup for sha256 table
+    Sha256,
+    /// Lookup for ripemd160 table
+    Ripemd160,
+    /// Lookup for modexp table
+    ModExp,
+    /// Lookup for ecc table
+    Ecc,
+    /// Lookup for blake2f table
+    Blake2f,
+    /// Lookup for P-256 signature table
+    P256Sig,
+    /// Lookup for block hash table
+    BlockHash,
 }
 
 #[derive(Clone, Debug)]
@@ -359,6 +403,95 @@ pub(crate) enum Lookup<F> {
         exponent_lo_hi: [Expression<F>; 2],
         exponentiation_lo_hi: [Expression<F>; 2],
     },
+    /// Lookup to signature table.
+    SigTable {
+        /// Hash of the signed message, reduced modulo the order of secp256k1.
+        msg_hash: Word<Expression<F>>,
+        /// Recovery id of the signature.
+        sig_v: Expression<F>,
+        /// r-component of the signature.
+        sig_r: Word<Expression<F>>,
+        /// s-component of the signature.
+        sig_s: Word<Expression<F>>,
+        /// Address recovered from the signature.
+        recovered_addr: Word<Expression<F>>,
+        /// Whether the signature is valid.
+        is_valid: Expression<F>,
+    },
+    /// Lookup to sha256 table.
+    Sha256Table {
+        /// Accumulator to the input.
+        input_rlc: Expression<F>,
+        /// Length of input that is being hashed.
+        input_len: Expression<F>,
+        /// Output (hash) of the input.
+        output: Word<Expression<F>>,
+    },
+    /// Lookup to ripemd160 table.
+    Ripemd160Table {
+        /// Accumulator to the input.
+        input_rlc: Expression<F>,
+        /// Length of input that is being hashed.
+        input_len: Expression<F>,
+        /// Output (hash) of the input, left-padded to 32 bytes.
+        output: Word<Expression<F>>,
+    },
+    /// Lookup to modexp table.
+    ModExpTable {
+        /// Base of the exponentiation.
+        base: Word<Expression<F>>,
+        /// Exponent of the exponentiation.
+        exponent: Word<Expression<F>>,
+        /// Modulus of the exponentiation.
+        modulus: Word<Expression<F>>,
+        /// Result of the exponentiation modulo the modulus.
+        output: Word<Expression<F>>,
+    },
+    /// Lookup to ecc table.
+    EccTable {
+        /// Precompile of the operation.
+        op_type: Expression<F>,
+        /// Arguments of the operation.
+        args: [Word<Expression<F>>; 4],
+        /// Coordinates of the resulting point.
+        output: [Word<Expression<F>>; 2],
+        /// Whether the input of the operation is valid.
+        is_valid: Expression<F>,
+    },
+    /// Lookup to blake2f table.
+    Blake2fTable {
+        /// Number of rounds of the compression.
+        rounds: Expression<F>,
+        /// Accumulator to the state vector, the message block and the offset
+        /// counter.
+        input_rlc: Expression<F>,
+        /// Final block indicator flag.
+        f: Expression<F>,
+        /// Accumulator to the compressed state vector.
+        output_rlc: Expression<F>,
+    },
+    /// Lookup to P-256 signature table.
+    P256SigTable {
+        /// Hash of the signed message.
+        msg_hash: Word<Expression<F>>,
+        /// r-component of the signature.
+        sig_r: Word<Expression<F>>,
+        /// s-component of the signature.
+        sig_s: Word<Expression<F>>,
+        /// x coordinate of the public key.
+        pk_x: Word<Expression<F>>,
+        /// y coordinate of the public key.
+        pk_y: Word<Expression<F>>,
+        /// Whether the public key is valid and the signature checks.
+        is_valid: Expression<F>,
+    },
+    /// Lookup to block hash table.
+    BlockHashTable {
+        /// Depth of the block from the current one.
+        depth: Expression<F>,
+        /// Hash of the block.
+        hash: Word<Expression<F>>,
+    },
     /// Conditional lookup enabled by the first element.
     Conditional(Expression<F>, Box<Lookup<F>>),
 }
@@ -378,6 +511,14 @@ impl<F: Field> Lookup<F> {
             Self::CopyTable { .. } => Table::Copy,
             Self::KeccakTable { .. } => Table::Keccak,
             Self::ExpTable { .. } => Table::Exp,
+            Self::SigTable { .. } => Table::Sig,
+            Self::Sha256Table { .. } => Table::Sha256,
+            Self::Ripemd160Table { .. } => Table::Ripemd160,
+            Self::ModExpTable { .. } => Table::ModExp,
+            Self::EccTable { .. } => Table::Ecc,
+            Self::Blake2fTable { .. } => Table::Blake2f,
+            Self::P256SigTable { .. } => Table::P256Sig,
+            Self::BlockHashTable { .. } => Table::BlockHash,
             Self::Conditional(_, lookup) => lookup.table(),
         }
     }
@@ -496,6 +637,112 @@ impl<F: Field> Lookup<F> {
                 exponentiation_lo_hi[0].clone(),
                 exponentiation_lo_hi[1].clone(),
             ],
+            Self::SigTable {
+                msg_hash,
+                sig_v,
+                sig_r,
+                sig_s,
+                recovered_addr,
+                is_valid,
+            } => vec![
+                1.expr(), // q_enable
+                msg_hash.lo(),
+                msg_hash.hi(),
+                sig_v.clone(),
+                sig_r.lo(),
+                sig_r.hi(),
+                sig_s.lo(),
+                sig_s.hi(),
+                recovered_addr.lo(),
+                recovered_addr.hi(),
+                is_valid.clone(),
+            ],
+            Self::Sha256Table {
+                input_rlc,
+                input_len,
+                output,
+            } => vec![
+                1.expr(), // is_enabled
+                input_rlc.clone(),
+                input_len.clone(),
+                output.lo(),
+                output.hi(),
+            ],
+            Self::Ripemd160Table {
+                input_rlc,
+                input_len,
+                output,
+            } => vec![
+                1.expr(), // is_enabled
+                input_rlc.clone(),
+                input_len.clone(),
+                output.lo(),
+                output.hi(),
+            ],
+            Self::ModExpTable {
+                base,
+                exponent,
+                modulus,
+                output,
+            } => vec![
+                1.expr(), // is_enabled
+                base.lo(),
+                base.hi(),
+                exponent.lo(),
+                exponent.hi(),
+                modulus.lo(),
+                modulus.hi(),
+                output.lo(),
+                output.hi(),
+            ],
+            Self::EccTable {
+                op_type,
+                args,
+                output,
+                is_valid,
+            } => [1.expr(), op_type.clone()] // is_enabled
+                .into_iter()
+                .chain(
+                    args.iter()
+                        .chain(output.iter())
+                        .flat_map(|word| [word.lo(), word.hi()]),
+                )
+                .chain([is_valid.clone()])
+                .collect(),
+            Self::Blake2fTable {
+                rounds,
+                input_rlc,
+                f,
+                output_rlc,
+            } => vec![
+                1.expr(), // is_enabled
+                rounds.clone(),
+                input_rlc.clone(),
+                f.clone(),
+                output_rlc.clone(),
+            ],
+            Self::P256SigTable {
+                msg_hash,
+                sig_r,
+                sig_s,
+                pk_x,
+                pk_y,
+                is_valid,
+            } => vec![
+                1.expr(), // is_enabled
+                msg_hash.lo(),
+                msg_hash.hi(),
+                sig_r.lo(),
+                sig_r.hi(),
+                sig_s.lo(),
+                sig_s.hi(),
+                pk_x.lo(),
+                pk_x.hi(),
+                pk_y.lo(),
+                pk_y.hi(),
+                is_valid.clone(),
+            ],
+            Self::BlockHashTable { depth, hash } => vec![depth.clone(), hash.lo(), hash.hi()],
             Self::Conditional(condition, lookup) => lookup
                 .input_exprs()
                 .into_iter()
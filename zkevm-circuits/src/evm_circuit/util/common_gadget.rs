@@ -25,9 +25,9 @@ use crate::{
         word::{Word, Word32, Word32Cell, WordCell, WordExpr},
         Expr,
     },
-    witness::{Block, Call, ExecStep},
+    witness::{Block, Call, ExecStep, Transaction},
 };
-use bus_mapping::state_db::CodeDB;
+use bus_mapping::{circuit_input_builder::CopyDataType, state_db::CodeDB};
 use eth_types::{evm_types::GasCost, Field, ToAddress, ToLittleEndian, ToScalar, ToWord, U256};
 use gadgets::util::{select, sum};
 use halo2_proofs::{
@@ -342,6 +342,15 @@ impl<F: Field, const N_ADDENDS: usize, const INCREASE: bool>
         Self { add_words }
     }
 
+    /// Balance before the update
+    pub(crate) fn balance_prev(&self) -> &Word32Cell<F> {
+        if INCREASE {
+            &self.add_words.addends()[0]
+        } else {
+            self.add_words.sum()
+        }
+    }
+
     pub(crate) fn assign(
         &self,
         region: &mut CachedRegion<'_, '_, F>,
@@ -532,6 +541,11 @@ impl<F: Field> TransferWithGasFeeGadget<F> {
         }
     }
 
+    /// Balance of the sender before the gas fee is subtracted
+    pub(crate) fn sender_balance_prev(&self) -> &Word32Cell<F> {
+        self.sender_sub_fee.balance_prev()
+    }
+
     pub(crate) fn rw_delta(&self) -> Expression<F> {
         // +1 Write Account (sender) Balance (Not Reversible tx fee)
         1.expr() +
@@ -695,6 +709,90 @@ impl<F: Field> TransferGadget<F> {
     }
 }
 
+/// The TxAccessListGadget adds the access list of a tx (EIP-2930) to the tx
+/// access list at the beginning of the tx.  The copy circuit copies the
+/// addresses and then the storage keys from the tx table, with a write of
+/// TxAccessListAccount or TxAccessListAccountStorage for every entry.
+#[derive(Clone, Debug)]
+pub(crate) struct TxAccessListGadget<F> {
+    addresses_length: Expression<F>,
+    storage_keys_length: Expression<F>,
+    no_addresses: IsZeroGadget<F>,
+    no_storage_keys: IsZeroGadget<F>,
+}
+
+impl<F: Field> TxAccessListGadget<F> {
+    pub(crate) fn construct(
+        cb: &mut EVMConstraintBuilder<F>,
+        tx_id: Expression<F>,
+        addresses_length: Expression<F>,
+        storage_keys_length: Expression<F>,
+    ) -> Self {
+        let no_addresses = IsZeroGadget::construct(cb, addresses_length.clone());
+        let no_storage_keys = IsZeroGadget::construct(cb, storage_keys_length.clone());
+        for (tag, length, is_empty) in [
+            (
+                CopyDataType::AccessListAddresses,
+                addresses_length.expr(),
+                no_addresses.expr(),
+            ),
+            (
+                CopyDataType::AccessListStorageKeys,
+                storage_keys_length.expr(),
+                no_storage_keys.expr(),
+            ),
+        ] {
+            cb.condition(not::expr(is_empty), |cb| {
+                cb.copy_table_lookup(
+                    Word::from_lo_unchecked(tx_id.expr()),
+                    tag.expr(),
+                    Word::from_lo_unchecked(tx_id.expr()),
+                    tag.expr(),
+                    0.expr(),
+                    length.expr(),
+                    0.expr(),
+                    length.expr(),
+                    0.expr(),
+                    length, // one write per entry
+                );
+            });
+        }
+
+        Self {
+            addresses_length,
+            storage_keys_length,
+            no_addresses,
+            no_storage_keys,
+        }
+    }
+
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        tx: &Transaction,
+    ) -> Result<(), Error> {
+        self.no_addresses
+            .assign(region, offset, F::from(tx.access_list_items().len() as u64))?;
+        self.no_storage_keys.assign(
+            region,
+            offset,
+            F::from(tx.access_list_storage_keys().count() as u64),
+        )?;
+        Ok(())
+    }
+
+    /// Number of entries of the access list of the tx
+    pub(crate) fn num_entries(tx: &Transaction) -> usize {
+        tx.access_list_items().len() + tx.access_list_storage_keys().count()
+    }
+
+    pub(crate) fn rw_delta(&self) -> Expression<F> {
+        // +1 Write TxAccessListAccount or TxAccessListAccountStorage per entry
+        self.addresses_length.expr() + self.storage_keys_length.expr()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct CommonCallGadget<F, MemAddrGadget, const IS_SUCCESS_CALL: bool> {
     pub is_success: Cell<F>,
@@ -1070,6 +1168,115 @@ pub(crate) fn cal_sstore_gas_cost_for_assignment(
     }
 }
 
+/// Gadget shared by the system calls at the beginning of a block, which are
+/// outside of any transaction (call_id 0).  It reads the code hash of the
+/// system contract, which is 0 if the contract doesn't exist, and requires
+/// the contract to have `code` if it has any code.  The step then writes the
+/// storage which the code writes, if the contract has code, and transits to
+/// the next system call, the first tx or EndBlock.
+#[derive(Clone, Debug)]
+pub(crate) struct SystemCallGadget<F> {
+    code_hash: WordCell<F>,
+    contract_not_exists: IsZeroWordGadget<F, WordCell<F>>,
+    is_empty_code_hash: IsEqualWordGadget<F, Word<Expression<F>>, Word<Expression<F>>>,
+}
+
+impl<F: Field> SystemCallGadget<F> {
+    pub(crate) fn construct(
+        cb: &mut EVMConstraintBuilder<F>,
+        address: Word<Expression<F>>,
+        code: &[u8],
+    ) -> Self {
+        cb.require_zero(
+            "System call is outside of any transaction",
+            cb.curr.state.call_id.expr(),
+        );
+
+        // For non-existing accounts the code_hash must be 0 in the rw_table.
+        let code_hash = cb.query_word_unchecked();
+        cb.account_read(address, AccountFieldTag::CodeHash, code_hash.to_word());
+        let contract_not_exists = IsZeroWordGadget::construct(cb, &code_hash);
+        let is_empty_code_hash =
+            IsEqualWordGadget::construct(cb, &code_hash.to_word(), &cb.empty_code_hash());
+
+        let gadget = Self {
+            code_hash,
+            contract_not_exists,
+            is_empty_code_hash,
+        };
+        cb.condition(gadget.has_code(), |cb| {
+            cb.require_equal_word(
+                "Code of the system contract",
+                gadget.code_hash.to_word(),
+                Word::from(CodeDB::hash(code)).map(Expression::Constant),
+            );
+        });
+        gadget
+    }
+
+    pub(crate) fn has_code(&self) -> Expression<F> {
+        1.expr() - self.contract_not_exists.expr() - self.is_empty_code_hash.expr()
+    }
+
+    /// Constrain the transition of a system call step which does
+    /// `rw_counter_offset` rw lookups.
+    pub(crate) fn constrain_transition(
+        cb: &mut EVMConstraintBuilder<F>,
+        rw_counter_offset: Expression<F>,
+    ) {
+        cb.condition(
+            cb.next
+                .execution_state_selector([ExecutionState::BeginTx, ExecutionState::InvalidTx]),
+            |cb| {
+                let next_step_rwc = cb.next.state.rw_counter.expr();
+                // The first tx isn't the first step, so its tx_id is constrained here.
+                cb.call_context_lookup_write_with_counter(
+                    next_step_rwc.clone(),
+                    Some(next_step_rwc),
+                    CallContextFieldTag::TxId,
+                    Word::one(),
+                );
+                cb.require_step_state_transition(StepStateTransition {
+                    rw_counter: Delta(rw_counter_offset.clone()),
+                    ..StepStateTransition::any()
+                });
+            },
+        );
+        cb.condition(
+            cb.next.execution_state_selector([
+                ExecutionState::HistoryStorage,
+                ExecutionState::EndBlock,
+            ]),
+            |cb| {
+                cb.require_step_state_transition(StepStateTransition {
+                    rw_counter: Delta(rw_counter_offset.clone()),
+                    // EndBlock tells an empty block by call_id 0.
+                    call_id: Same,
+                    ..StepStateTransition::any()
+                });
+            },
+        );
+    }
+
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        code_hash: U256,
+    ) -> Result<(), Error> {
+        self.code_hash.assign_u256(region, offset, code_hash)?;
+        self.contract_not_exists
+            .assign_u256(region, offset, code_hash)?;
+        self.is_empty_code_hash.assign_u256(
+            region,
+            offset,
+            code_hash,
+            CodeDB::empty_code_hash().to_word(),
+        )?;
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct CommonErrorGadget<F> {
     rw_counter_end_of_reversion: WordCell<F>,
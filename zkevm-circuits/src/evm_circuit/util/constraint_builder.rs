@@ -16,7 +16,7 @@ use crate::{
     },
 };
 use bus_mapping::{operation::Target, state_db::EMPTY_CODE_HASH_LE};
-use eth_types::Field;
+use eth_types::{evm_types::Hardfork, Field};
 use gadgets::util::{not, sum};
 use halo2_proofs::{
     circuit::Value,
@@ -53,7 +53,7 @@ impl<F> Default for Transition<F> {
 }
 
 #[derive(Default)]
-pub(crate) struct StepStateTransition<F: Field> {
+pub struct StepStateTransition<F: Field> {
     pub(crate) rw_counter: Transition<Expression<F>>,
     pub(crate) call_id: Transition<Expression<F>>,
     pub(crate) is_root: Transition<Expression<F>>,
@@ -298,7 +298,9 @@ pub(crate) struct Constraints<F> {
     pub(crate) not_step_last: Vec<(&'static str, Expression<F>)>,
 }
 
-pub(crate) struct EVMConstraintBuilder<'a, F: Field> {
+pub struct EVMConstraintBuilder<'a, F: Field> {
+    pub(crate) hardfork: Hardfork,
+    pub(crate) p256_verify: bool,
     pub(crate) curr: Step<F>,
     pub(crate) next: Step<F>,
     challenges: &'a Challenges<Expression<F>>,
@@ -333,12 +335,16 @@ pub(crate) type BoxedClosure<'a, F> = Box<dyn FnOnce(&mut EVMConstraintBuilder<F
 impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
     pub(crate) fn new(
         meta: &'a mut ConstraintSystem<F>,
+        hardfork: Hardfork,
+        p256_verify: bool,
         curr: Step<F>,
         next: Step<F>,
         challenges: &'a Challenges<Expression<F>>,
         execution_state: ExecutionState,
     ) -> Self {
         Self {
+            hardfork,
+            p256_verify,
             curr,
             next,
             challenges,
@@ -439,7 +445,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
     }
 
     // default query_word is 2 limbs. Each limb is not guaranteed to be 128 bits.
-    pub fn query_word_unchecked(&mut self) -> WordCell<F> {
+    pub(crate) fn query_word_unchecked(&mut self) -> WordCell<F> {
         Word::new(
             self.query_cells(CellType::StoragePhase1, 2)
                 .try_into()
@@ -605,13 +611,28 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         &mut self,
         execution_state: Expression<F>,
         address: Expression<F>,
-        base_gas_cost: Expression<F>,
     ) {
         self.add_lookup(
             "precompiles info",
             Lookup::Fixed {
                 tag: FixedTableTag::PrecompileInfo.expr(),
-                values: [execution_state, address, base_gas_cost],
+                values: [execution_state, address, 0.expr()],
+            },
+        )
+    }
+
+    // precompiled contract gas costs
+    pub(crate) fn precompile_gas_cost_lookup(
+        &mut self,
+        address: Expression<F>,
+        base_gas_cost: Expression<F>,
+        per_word_gas_cost: Expression<F>,
+    ) {
+        self.add_lookup(
+            "precompile gas cost",
+            Lookup::Fixed {
+                tag: FixedTableTag::PrecompileGasCost.expr(),
+                values: [address, base_gas_cost, per_word_gas_cost],
             },
         )
     }
@@ -760,6 +781,11 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    // block hash
+    pub(crate) fn block_hash_lookup(&mut self, depth: Expression<F>, hash: Word<Expression<F>>) {
+        self.add_lookup("Block hash lookup", Lookup::BlockHashTable { depth, hash });
+    }
+
     // Rw
 
     /// Add a Lookup::Rw without increasing the rw_counter_offset, which is
@@ -1080,17 +1106,89 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    // Transient storage
+
+    pub(crate) fn transient_storage_read(
+        &mut self,
+        tx_id: Expression<F>,
+        account_address: Word<Expression<F>>,
+        key: Word<Expression<F>>,
+        value: Word<Expression<F>>,
+    ) {
+        self.rw_lookup(
+            "TransientStorage read",
+            false.expr(),
+            Target::TransientStorage,
+            RwValues::new(
+                tx_id,
+                address_word_to_expr(account_address),
+                0.expr(),
+                key,
+                value.clone(),
+                value,
+                Word::zero(),
+            ),
+        );
+    }
+
+    pub(crate) fn transient_storage_write(
+        &mut self,
+        tx_id: Expression<F>,
+        account_address: Word<Expression<F>>,
+        key: Word<Expression<F>>,
+        value: Word<Expression<F>>,
+        value_prev: Word<Expression<F>>,
+        reversion_info: Option<&mut ReversionInfo<F>>,
+    ) {
+        self.reversible_write(
+            "TransientStorage write",
+            Target::TransientStorage,
+            RwValues::new(
+                tx_id,
+                address_word_to_expr(account_address),
+                0.expr(),
+                key,
+                value,
+                value_prev,
+                Word::zero(),
+            ),
+            reversion_info,
+        );
+    }
+
+    // Created account
+
+    pub(crate) fn created_account_write(
+        &mut self,
+        tx_id: Expression<F>,
+        account_address: Word<Expression<F>>,
+        value: Expression<F>,
+        value_prev: Expression<F>,
+        reversion_info: Option<&mut ReversionInfo<F>>,
+    ) {
+        self.reversible_write(
+            "CreatedAccount write",
+            Target::CreatedAccount,
+            RwValues::new(
+                tx_id,
+                address_word_to_expr(account_address),
+                0.expr(),
+                Word::zero(),
+                Word::from_lo_unchecked(value),
+                Word::from_lo_unchecked(value_prev),
+                Word::zero(),
+            ),
+            reversion_info,
+        );
+    }
+
     // Call context
     pub(crate) fn call_context(
         &mut self,
         call_id: Option<Expression<F>>,
         field_tag: CallContextFieldTag,
     ) -> Cell<F> {
-        let phase = match field_tag {
-            CallContextFieldTag::CodeHash => CellType::StoragePhase2,
-            _ => CellType::StoragePhase1,
-        };
-        let cell = self.query_cell_with_type(phase);
+        let cell = self.query_cell();
         self.call_context_lookup_read(
             call_id,
             field_tag,
@@ -1436,6 +1534,151 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    // Sig Table
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn sig_table_lookup(
+        &mut self,
+        msg_hash: Word<Expression<F>>,
+        sig_v: Expression<F>,
+        sig_r: Word<Expression<F>>,
+        sig_s: Word<Expression<F>>,
+        recovered_addr: Word<Expression<F>>,
+        is_valid: Expression<F>,
+    ) {
+        self.add_lookup(
+            "sig lookup",
+            Lookup::SigTable {
+                msg_hash,
+                sig_v,
+                sig_r,
+                sig_s,
+                recovered_addr,
+                is_valid,
+            },
+        );
+    }
+
+    // Sha256 Table
+
+    pub(crate) fn sha256_table_lookup(
+        &mut self,
+        input_rlc: Expression<F>,
+        input_len: Expression<F>,
+        output: Word<Expression<F>>,
+    ) {
+        self.add_lookup(
+            "sha256 lookup",
+            Lookup::Sha256Table {
+                input_rlc,
+                input_len,
+                output,
+            },
+        );
+    }
+
+    // Ripemd160 Table
+
+    pub(crate) fn ripemd160_table_lookup(
+        &mut self,
+        input_rlc: Expression<F>,
+        input_len: Expression<F>,
+        output: Word<Expression<F>>,
+    ) {
+        self.add_lookup(
+            "ripemd160 lookup",
+            Lookup::Ripemd160Table {
+                input_rlc,
+                input_len,
+                output,
+            },
+        );
+    }
+
+    // ModExp Table
+
+    pub(crate) fn modexp_table_lookup(
+        &mut self,
+        base: Word<Expression<F>>,
+        exponent: Word<Expression<F>>,
+        modulus: Word<Expression<F>>,
+        output: Word<Expression<F>>,
+    ) {
+        self.add_lookup(
+            "modexp lookup",
+            Lookup::ModExpTable {
+                base,
+                exponent,
+                modulus,
+                output,
+            },
+        );
+    }
+
+    // Ecc Table
+
+    pub(crate) fn ecc_table_lookup(
+        &mut self,
+        op_type: Expression<F>,
+        args: [Word<Expression<F>>; 4],
+        output: [Word<Expression<F>>; 2],
+        is_valid: Expression<F>,
+    ) {
+        self.add_lookup(
+            "ecc lookup",
+            Lookup::EccTable {
+                op_type,
+                args,
+                output,
+                is_valid,
+            },
+        );
+    }
+
+    // Blake2f Table
+
+    pub(crate) fn blake2f_table_lookup(
+        &mut self,
+        rounds: Expression<F>,
+        input_rlc: Expression<F>,
+        f: Expression<F>,
+        output_rlc: Expression<F>,
+    ) {
+        self.add_lookup(
+            "blake2f lookup",
+            Lookup::Blake2fTable {
+                rounds,
+                input_rlc,
+                f,
+                output_rlc,
+            },
+        );
+    }
+
+    // P256Sig Table
+
+    pub(crate) fn p256_sig_table_lookup(
+        &mut self,
+        msg_hash: Word<Expression<F>>,
+        sig_r: Word<Expression<F>>,
+        sig_s: Word<Expression<F>>,
+        pk_x: Word<Expression<F>>,
+        pk_y: Word<Expression<F>>,
+        is_valid: Expression<F>,
+    ) {
+        self.add_lookup(
+            "p256 sig lookup",
+            Lookup::P256SigTable {
+                msg_hash,
+                sig_r,
+                sig_s,
+                pk_x,
+                pk_y,
+                is_valid,
+            },
+        );
+    }
+
     // Validation
 
     pub(crate) fn validate_degree(&self, degree: usize, name: &'static str) {
@@ -1483,16 +1726,17 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
             sum::expr(&conditions),
         );
 
-        // TODO: constraining the same cells repeatedly requires a height-resetting mechanism
-        // on the cell manager. In this case, since only identity precompile is added
-        // this height management maneuver is temporarily left out.
         for ((&next_state, condition), constraint) in next_states
             .iter()
             .zip(conditions.into_iter())
             .zip(constraints.into_iter())
         {
-            // constrain the next step.
+            // constrain the next step, restoring the cell manager of the next step afterwards so
+            // that every constraint queries the cells from the start of the layout of its
+            // precompile gadget.
+            let next_cell_manager = self.next.cell_manager.clone();
             self.constrain_next_step(next_state, Some(condition), constraint);
+            self.next.cell_manager = next_cell_manager;
         }
     }
 
@@ -1685,7 +1929,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         }
     }
 
-    pub fn debug_expression<S: Into<String>>(&mut self, name: S, expr: Expression<F>) {
+    pub(crate) fn debug_expression<S: Into<String>>(&mut self, name: S, expr: Expression<F>) {
         self.debug_expressions.push((name.into(), expr));
     }
 }
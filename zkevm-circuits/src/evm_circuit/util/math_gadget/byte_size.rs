@@ -109,6 +109,21 @@ impl<F: Field> ByteSizeGadget<F> {
                 .map(|(i, cell)| i.expr() * cell.expr()),
         )
     }
+
+    /// Return the most significant nonzero byte of the `values` with which
+    /// the gadget is constructed, or 0 when they're all 0.
+    pub(crate) fn most_significant_byte(
+        &self,
+        values: &[Expression<F>; N_BYTES_WORD],
+    ) -> Expression<F> {
+        sum::expr(
+            self.most_significant_nonzero_byte_index
+                .iter()
+                .skip(1)
+                .zip(values.iter())
+                .map(|(cell, value)| cell.expr() * value.clone()),
+        )
+    }
 }
 
 #[cfg(test)]
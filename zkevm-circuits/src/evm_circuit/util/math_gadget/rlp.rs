@@ -208,10 +208,10 @@ pub struct ContractCreateGadget<F, const IS_CREATE2: bool> {
     /// Sender nonce of the contract creation tx.
     nonce: RlpU64Gadget<F>,
     /// Keccak256 hash of init code, used for CREATE2. We don't use a
-    /// RandomLinearCombination here since we require both keccak and word
-    /// RLC in the case of init code hash, for BeginTx and
-    /// CREATE2 respectively. Instead, we store just the bytes and calculate the
-    /// appropriate RLC wherever needed.
+    /// RandomLinearCombination here since we require both the keccak RLC and
+    /// the lo/hi word of the init code hash, for CREATE2 and BeginTx
+    /// respectively. Instead, we store just the bytes and calculate the
+    /// appropriate form wherever needed.
     code_hash: Word32Cell<F>,
     /// Random salt for CREATE2.
     salt: Word32Cell<F>,
@@ -269,7 +269,7 @@ impl<F: Field, const IS_CREATE2: bool> ContractCreateGadget<F, IS_CREATE2> {
         self.nonce.value()
     }
 
-    /// Code hash word RLC.
+    /// Code hash word.
     pub(crate) fn code_hash(&self) -> word::Word<Expression<F>> {
         self.code_hash.to_word()
     }
@@ -16,7 +16,7 @@ use crate::{
     table::LookupTable,
     util::{cell_manager::CellType, Challenges},
 };
-use eth_types::{Field, Word, U256};
+use eth_types::{evm_types::Hardfork, Field, Word, U256};
 pub(crate) use halo2_proofs::circuit::{Layouter, Value};
 use halo2_proofs::{
     circuit::SimpleFloorPlanner,
@@ -118,6 +118,8 @@ impl<F: Field, G: MathGadgetContainer<F>> Circuit<F> for UnitTestMathGadgetBaseC
         let step_next = Step::new(meta, advices, MAX_STEP_HEIGHT);
         let mut cb = EVMConstraintBuilder::new(
             meta,
+            Hardfork::default(),
+            false,
             step_curr.clone(),
             step_next,
             &challenges_exprs,
@@ -222,7 +224,7 @@ impl<F: Field, G: MathGadgetContainer<F>> Circuit<F> for UnitTestMathGadgetBaseC
                                         | FixedTableTag::Range1024
                                 )
                             })
-                            .flat_map(|tag| tag.build()),
+                            .flat_map(|tag| tag.build(Hardfork::default())),
                     )
                     .enumerate()
                 {
@@ -367,6 +367,13 @@ impl<F: Field> MemoryWordSizeGadget<F> {
 /// This gas cost is the difference between the next and current memory costs:
 /// `memory_cost = Gmem * memory_word_size + floor(memory_word_size *
 /// memory_word_size / 512)`
+///
+/// The memory word sizes are range checked to `N_BYTES_MEMORY_WORD_SIZE`
+/// (4 bytes), so the addresses can go up to `MAX_EXPANDED_MEMORY_ADDRESS`
+/// (`(2^32 - 1) * 32`), whose quadratic cost still fits in `N_BYTES_GAS`.
+/// Above it go-ethereum fails with a gas overflow, so the access is expected
+/// to come from a `MemoryExpandedAddressGadget`, which returns an address 0
+/// that the out of gas gadgets pair with its `overflow`.
 #[derive(Clone, Debug)]
 pub(crate) struct MemoryExpansionGadget<F, const N: usize, const N_BYTES_MEMORY_WORD_SIZE: usize> {
     memory_word_sizes: [MemoryWordSizeGadget<F>; N],
@@ -693,7 +700,7 @@ mod test_util;
 #[cfg(test)]
 mod test {
     use crate::evm_circuit::util::{constraint_builder::ConstrainBuilderCommon, Cell, U64Cell};
-    use eth_types::Word;
+    use eth_types::{evm_types::gas_utils::memory_expansion_gas_cost, Word};
     use halo2_proofs::{halo2curves::bn256::Fr, plonk::Error};
 
     use super::{test_util::*, *};
@@ -797,6 +804,170 @@ mod test {
         }
     }
 
+    #[derive(Clone)]
+    struct MemoryExpansionGadgetTestContainer<F> {
+        memory_address: MemoryExpandedAddressGadget<F>,
+        memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
+        curr_memory_word_size: Cell<F>,
+        overflow: Cell<F>,
+        next_memory_word_size: Cell<F>,
+        gas_cost: Cell<F>,
+    }
+
+    impl<F: Field> MemoryGadgetContainer<F> for MemoryExpansionGadgetTestContainer<F> {
+        fn configure_gadget_container(cb: &mut EVMConstraintBuilder<F>) -> Self {
+            let curr_memory_word_size = cb.curr.state.memory_word_size.clone();
+            let memory_address = MemoryExpandedAddressGadget::construct_self(cb);
+            let memory_expansion = MemoryExpansionGadget::construct(cb, [memory_address.address()]);
+
+            let overflow = cb.query_cell();
+            let next_memory_word_size = cb.query_cell();
+            let gas_cost = cb.query_cell();
+            cb.require_equal("overflow", memory_address.overflow(), overflow.expr());
+            cb.require_equal(
+                "next memory word size",
+                memory_expansion.next_memory_word_size(),
+                next_memory_word_size.expr(),
+            );
+            cb.require_equal(
+                "memory expansion gas cost",
+                memory_expansion.gas_cost(),
+                gas_cost.expr(),
+            );
+
+            MemoryExpansionGadgetTestContainer {
+                memory_address,
+                memory_expansion,
+                curr_memory_word_size,
+                overflow,
+                next_memory_word_size,
+                gas_cost,
+            }
+        }
+
+        fn assign_gadget_container(
+            &self,
+            witnesses: &[Word],
+            region: &mut CachedRegion<'_, '_, F>,
+        ) -> Result<(), Error> {
+            let offset = 0;
+            let curr_memory_word_size = witnesses[2].as_u64();
+            self.curr_memory_word_size.assign(
+                region,
+                offset,
+                Value::known(F::from(curr_memory_word_size)),
+            )?;
+            let address = self
+                .memory_address
+                .assign(region, offset, witnesses[0], witnesses[1])?;
+            self.memory_expansion
+                .assign(region, offset, curr_memory_word_size, [address])?;
+            for (cell, value) in [&self.overflow, &self.next_memory_word_size, &self.gas_cost]
+                .iter()
+                .zip(&witnesses[3..])
+            {
+                cell.assign(region, offset, Value::known(F::from(value.as_u64())))?;
+            }
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_memory_expansion_gadget_boundaries() {
+        let max_word_size = MAX_EXPANDED_MEMORY_ADDRESS / 32;
+        let max_gas_cost = memory_expansion_gas_cost(0, max_word_size);
+        // (offset, length, curr_memory_word_size, overflow, next_memory_word_size, gas_cost)
+        let cases = [
+            // The last word below MAX_EXPANDED_MEMORY_ADDRESS
+            (0x1fffffffc0, 0x20, 0, 0, max_word_size, max_gas_cost),
+            (0x1fffffffc1, 0x1f, 0, 0, max_word_size, max_gas_cost),
+            (
+                0,
+                MAX_EXPANDED_MEMORY_ADDRESS,
+                0,
+                0,
+                max_word_size,
+                max_gas_cost,
+            ),
+            (
+                0x1fffffffc0,
+                0x20,
+                max_word_size - 1,
+                0,
+                max_word_size,
+                max_gas_cost - memory_expansion_gas_cost(0, max_word_size - 1),
+            ),
+            (0x1fffffffc0, 0x20, max_word_size, 0, max_word_size, 0),
+            // One byte above MAX_EXPANDED_MEMORY_ADDRESS
+            (0x1fffffffc1, 0x20, 0, 1, 0, 0),
+            (0, MAX_EXPANDED_MEMORY_ADDRESS + 1, 2, 1, 2, 0),
+            (u64::MAX, 1, 0, 1, 0, 0),
+            // No expansion for a zero length
+            (u64::MAX, 0, 1, 0, 1, 0),
+        ];
+        for (offset, length, curr_memory_word_size, overflow, next_memory_word_size, gas_cost) in
+            cases
+        {
+            try_test!(
+                MemoryExpansionGadgetTestContainer<Fr>,
+                vec![
+                    Word::from(offset),
+                    Word::from(length),
+                    Word::from(curr_memory_word_size),
+                    Word::from(overflow),
+                    Word::from(next_memory_word_size),
+                    Word::from(gas_cost),
+                ],
+                true,
+            );
+        }
+
+        // Overflow of the sum of offset and length
+        try_test!(
+            MemoryExpansionGadgetTestContainer<Fr>,
+            vec![
+                Word::MAX,
+                Word::from(1),
+                Word::zero(),
+                Word::from(1),
+                Word::zero(),
+                Word::zero(),
+            ],
+            true,
+        );
+    }
+
+    #[test]
+    fn test_memory_expansion_gadget_soundness() {
+        let max_word_size = MAX_EXPANDED_MEMORY_ADDRESS / 32;
+        let max_gas_cost = memory_expansion_gas_cost(0, max_word_size);
+        // (offset, length, overflow, next_memory_word_size, gas_cost)
+        let cases = [
+            // Gas cost of the quadratic term off by one
+            (0x1fffffffc0, 0x20, 0, max_word_size, max_gas_cost - 1),
+            (0x1fffffffc0, 0x20, 0, max_word_size - 1, max_gas_cost),
+            // Expansion above MAX_EXPANDED_MEMORY_ADDRESS without overflow
+            (0x1fffffffc1, 0x20, 0, 0, 0),
+            // Overflow at MAX_EXPANDED_MEMORY_ADDRESS
+            (0x1fffffffc0, 0x20, 1, 0, 0),
+        ];
+        for (offset, length, overflow, next_memory_word_size, gas_cost) in cases {
+            try_test!(
+                MemoryExpansionGadgetTestContainer<Fr>,
+                vec![
+                    Word::from(offset),
+                    Word::from(length),
+                    Word::zero(),
+                    Word::from(overflow),
+                    Word::from(next_memory_word_size),
+                    Word::from(gas_cost),
+                ],
+                false,
+            );
+        }
+    }
+
     #[test]
     fn test_buffer_reader_gadget_completness() {
         // buffer len = data len
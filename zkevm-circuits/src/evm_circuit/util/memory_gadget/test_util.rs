@@ -11,7 +11,7 @@ use crate::{
     },
     util::Challenges,
 };
-use eth_types::{Field, Word};
+use eth_types::{evm_types::Hardfork, Field, Word};
 pub(crate) use halo2_proofs::circuit::Layouter;
 use halo2_proofs::{
     circuit::SimpleFloorPlanner,
@@ -97,6 +97,8 @@ impl<F: Field, G: MemoryGadgetContainer<F>> Circuit<F> for UnitTestMemoryGadgetB
         let step_next = Step::new(meta, advices, MAX_STEP_HEIGHT);
         let mut cb = EVMConstraintBuilder::new(
             meta,
+            Hardfork::default(),
+            false,
             step_curr.clone(),
             step_next,
             &challenges_exprs,
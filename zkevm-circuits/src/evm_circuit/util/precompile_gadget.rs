@@ -3,68 +3,79 @@ use eth_types::Field;
 use gadgets::util::{not, Expr};
 use halo2_proofs::plonk::Expression;
 
-use crate::evm_circuit::step::{ExecutionState, ExecutionState::ErrorOutOfGasPrecompile};
+use crate::evm_circuit::{
+    execution::precompiles::{precompile_gadgets, PrecompileCallExprs},
+    step::ExecutionState::ErrorOutOfGasPrecompile,
+};
 
 use super::{
-    constraint_builder::{BoxedClosure, ConstrainBuilderCommon, EVMConstraintBuilder},
-    math_gadget::BinaryNumberGadget,
+    constraint_builder::{BoxedClosure, EVMConstraintBuilder},
+    math_gadget::{BinaryNumberGadget, IsEqualGadget},
     CachedRegion,
 };
 
 #[derive(Clone, Debug)]
 pub struct PrecompileGadget<F> {
+    /// Whether the callee is P256VERIFY, outside of the range of the Ethereum
+    /// precompiles
+    is_p256_verify: IsEqualGadget<F>,
+    /// Address of an Ethereum precompile, 0 for P256VERIFY
     address: BinaryNumberGadget<F, 4>,
 }
 
 impl<F: Field> PrecompileGadget<F> {
-    #[allow(clippy::too_many_arguments)]
+    /// Constrain the next step, unless the call runs out of gas, to the gadget
+    /// of the precompile at `callee_address`, which constrains its first
+    /// cells to the call.
     pub(crate) fn construct(
         cb: &mut EVMConstraintBuilder<F>,
-        _is_success: Expression<F>,
         callee_address: Expression<F>,
-        _caller_id: Expression<F>,
-        _cd_offset: Expression<F>,
-        cd_length: Expression<F>,
-        _rd_offset: Expression<F>,
-        _rd_length: Expression<F>,
-        precompile_return_length: Expression<F>,
-        // input bytes to precompile call.
-        _input_bytes_rlc: Expression<F>,
-        // output result from precompile call.
-        _output_bytes_rlc: Expression<F>,
-        // returned bytes back to caller.
-        _return_bytes_rlc: Expression<F>,
+        call: PrecompileCallExprs<F>,
     ) -> Self {
-        let address = BinaryNumberGadget::construct(cb, callee_address.expr());
-
-        let conditions = vec![
-            address.value_equals(PrecompileCalls::Identity),
-            // match more precompiles
-        ]
-        .into_iter()
-        .map(|cond| {
-            cond.expr() * not::expr(cb.next.execution_state_selector([ErrorOutOfGasPrecompile]))
-        })
-        .collect::<Vec<_>>();
-
-        let next_states = vec![
-            ExecutionState::PrecompileIdentity, // add more precompile execution states
-        ];
+        let is_p256_verify = IsEqualGadget::construct(
+            cb,
+            callee_address.expr(),
+            PrecompileCalls::P256Verify.address().expr(),
+        );
+        let address = BinaryNumberGadget::construct(
+            cb,
+            callee_address.expr()
+                - is_p256_verify.expr() * PrecompileCalls::P256Verify.address().expr(),
+        );
 
-        let constraints: Vec<BoxedClosure<F>> = vec![
-            Box::new(|cb| {
-                // Identity
-                cb.require_equal(
-                    "input length and precompile return length are the same",
-                    cd_length,
-                    precompile_return_length,
-                );
-            }), // add more precompile constraint closures
-        ];
+        let precompiles = precompile_gadgets::<F>();
+        let is_not_oog = not::expr(cb.next.execution_state_selector([ErrorOutOfGasPrecompile]));
+        let conditions = precompiles
+            .iter()
+            .map(|precompile| {
+                let is_precompile = if precompile.precompile == PrecompileCalls::P256Verify {
+                    is_p256_verify.expr()
+                } else {
+                    address.value_equals(precompile.precompile)
+                };
+                is_precompile * is_not_oog.clone()
+            })
+            .collect::<Vec<_>>();
+        let next_states = precompiles
+            .iter()
+            .map(|precompile| precompile.execution_state)
+            .collect::<Vec<_>>();
+        let call = &call;
+        let constraints: Vec<BoxedClosure<F>> = precompiles
+            .iter()
+            .map(|precompile| {
+                let constrain_call = precompile.constrain_call;
+                Box::new(move |cb: &mut EVMConstraintBuilder<F>| constrain_call(cb, call))
+                    as BoxedClosure<F>
+            })
+            .collect();
 
         cb.constrain_mutually_exclusive_next_step(conditions, next_states, constraints);
 
-        Self { address }
+        Self {
+            is_p256_verify,
+            address,
+        }
     }
 
     pub(crate) fn assign(
@@ -73,6 +84,16 @@ impl<F: Field> PrecompileGadget<F> {
         offset: usize,
         address: PrecompileCalls,
     ) -> Result<(), halo2_proofs::plonk::Error> {
-        self.address.assign(region, offset, address)
+        self.is_p256_verify.assign(
+            region,
+            offset,
+            F::from(address.address()),
+            F::from(PrecompileCalls::P256Verify.address()),
+        )?;
+        if address == PrecompileCalls::P256Verify {
+            self.address.assign(region, offset, 0usize)
+        } else {
+            self.address.assign(region, offset, address)
+        }
     }
 }
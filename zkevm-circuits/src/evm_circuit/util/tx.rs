@@ -17,7 +17,10 @@ use crate::{
     util::word::{Word32Cell, WordCell},
 };
 use bus_mapping::operation::Target;
-use eth_types::{evm_types::GasCost, Field};
+use eth_types::{
+    evm_types::{GasCost, Hardfork},
+    Field,
+};
 use gadgets::util::{select, Expr, Scalar};
 use halo2_proofs::{
     circuit::Value,
@@ -66,6 +69,50 @@ impl<F: Field> BeginTxHelperGadget<F> {
     }
 }
 
+/// Constrain the transition out of the last step of a tx, EndTx, InvalidTx or
+/// DeleteAccount, whose rw counter increases by `rw_counter_offset`.  The next
+/// tx gets the id `tx_id + 1`, while the steps which still belong to the tx
+/// and EndBlock get the call of the tx.
+pub(crate) fn end_tx_transition<F: Field>(
+    cb: &mut EVMConstraintBuilder<F>,
+    tx_id: Expression<F>,
+    rw_counter_offset: Expression<F>,
+) {
+    cb.condition(
+        cb.next
+            .execution_state_selector([ExecutionState::BeginTx, ExecutionState::InvalidTx]),
+        |cb| {
+            let next_step_rwc = cb.next.state.rw_counter.expr();
+            // lookup use next step initial rwc, thus lead to same record on rw table
+            cb.call_context_lookup_write_with_counter(
+                next_step_rwc.clone(),
+                Some(next_step_rwc),
+                CallContextFieldTag::TxId,
+                // tx_id has been lookup and range_check above
+                Word::from_lo_unchecked(tx_id.expr() + 1.expr()),
+            );
+            cb.require_step_state_transition(StepStateTransition {
+                rw_counter: Delta(rw_counter_offset.clone()),
+                ..StepStateTransition::any()
+            });
+        },
+    );
+    cb.condition(
+        cb.next
+            .execution_state_selector([ExecutionState::DeleteAccount, ExecutionState::EndBlock]),
+        |cb| {
+            cb.require_step_state_transition(StepStateTransition {
+                rw_counter: Delta(rw_counter_offset),
+                // We propagate call_id so that DeleteAccount can get the tx_id,
+                // and EndBlock can get the last tx_id in order to count
+                // processed txs.
+                call_id: Same,
+                ..StepStateTransition::any()
+            });
+        },
+    );
+}
+
 /// Gadget for ending a tx
 #[derive(Clone, Debug)]
 pub(crate) struct EndTxHelperGadget<F> {
@@ -115,42 +162,18 @@ impl<F: Field> EndTxHelperGadget<F> {
             1.expr(),
             tx_id.expr(),
             TxReceiptFieldTag::CumulativeGasUsed,
-            gas_used + current_cumulative_gas_used.expr(),
+            gas_used.expr() + current_cumulative_gas_used.expr(),
+        );
+        // Expose the gas used by the tx in the tx table
+        cb.tx_context_lookup(
+            tx_id.expr(),
+            TxContextFieldTag::TxGasUsed,
+            None,
+            Word::from_lo_unchecked(gas_used),
         );
 
         // Transition
-        let rw_counter_offset = num_rw.expr() - is_first_tx.expr();
-        cb.condition(
-            cb.next
-                .execution_state_selector([ExecutionState::BeginTx, ExecutionState::InvalidTx]),
-            |cb| {
-                let next_step_rwc = cb.next.state.rw_counter.expr();
-                // lookup use next step initial rwc, thus lead to same record on rw table
-                cb.call_context_lookup_write_with_counter(
-                    next_step_rwc.clone(),
-                    Some(next_step_rwc),
-                    CallContextFieldTag::TxId,
-                    // tx_id has been lookup and range_check above
-                    Word::from_lo_unchecked(tx_id.expr() + 1.expr()),
-                );
-                cb.require_step_state_transition(StepStateTransition {
-                    rw_counter: Delta(rw_counter_offset.clone()),
-                    ..StepStateTransition::any()
-                });
-            },
-        );
-        cb.condition(
-            cb.next.execution_state_selector([ExecutionState::EndBlock]),
-            |cb| {
-                cb.require_step_state_transition(StepStateTransition {
-                    rw_counter: Delta(rw_counter_offset.expr()),
-                    // We propagate call_id so that EndBlock can get the last tx_id
-                    // in order to count processed txs.
-                    call_id: Same,
-                    ..StepStateTransition::any()
-                });
-            },
-        );
+        end_tx_transition(cb, tx_id, num_rw.expr() - is_first_tx.expr());
 
         Self {
             is_first_tx,
@@ -199,13 +222,19 @@ pub(crate) struct TxDataGadget<F> {
     pub(crate) gas: Cell<F>,
     pub(crate) call_data_length: Cell<F>,
     pub(crate) call_data_gas_cost: Cell<F>,
+    pub(crate) access_list_length: Cell<F>,
+    pub(crate) access_list_storage_keys_length: Cell<F>,
     pub(crate) gas_price: Word32Cell<F>,
     pub(crate) value: Word32Cell<F>,
+    pub(crate) is_deposit: Cell<F>,
+    pub(crate) mint: Word32Cell<F>,
+    pub(crate) max_fee_per_gas: Word32Cell<F>,
 
     pub(crate) mul_gas_fee_by_gas: MulWordByU64Gadget<F>,
     pub(crate) call_data_word_length: ConstantDivisionGadget<F, N_BYTES_U64>,
 
-    pub(crate) gas_mul_gas_price_plus_value: Option<AddWordsGadget<F, 2, false>>,
+    pub(crate) mul_max_fee_by_gas: Option<MulWordByU64Gadget<F>>,
+    pub(crate) gas_mul_max_fee_plus_value: Option<AddWordsGadget<F, 2, false>>,
     pub(crate) cost_sum: Option<Word32Cell<F>>,
 }
 
@@ -223,8 +252,19 @@ impl<F: Field> TxDataGadget<F> {
             TxContextFieldTag::CallDataGasCost,
         ]
         .map(|field_tag| cb.tx_context(tx_id.expr(), field_tag, None));
-        let [gas_price, value] = [TxContextFieldTag::GasPrice, TxContextFieldTag::Value]
-            .map(|field_tag| cb.tx_context_as_word32(tx_id.expr(), field_tag, None));
+        let [access_list_length, access_list_storage_keys_length] = [
+            TxContextFieldTag::AccessListLength,
+            TxContextFieldTag::AccessListStorageKeysLength,
+        ]
+        .map(|field_tag| cb.tx_context(tx_id.expr(), field_tag, None));
+        let [gas_price, value, mint, max_fee_per_gas] = [
+            TxContextFieldTag::GasPrice,
+            TxContextFieldTag::Value,
+            TxContextFieldTag::Mint,
+            TxContextFieldTag::MaxFeePerGas,
+        ]
+        .map(|field_tag| cb.tx_context_as_word32(tx_id.expr(), field_tag, None));
+        let is_deposit = cb.tx_context(tx_id.expr(), TxContextFieldTag::IsDeposit, None);
 
         let [caller_address, callee_address] = [
             TxContextFieldTag::CallerAddress,
@@ -240,16 +280,25 @@ impl<F: Field> TxDataGadget<F> {
         let call_data_word_length =
             ConstantDivisionGadget::construct(cb, call_data_length.expr() + 31.expr(), 32);
 
-        let (cost_sum, gas_mul_gas_price_plus_value) = if calculate_total_cost {
+        // The total cost is charged at the max fee per gas, as go-ethereum's
+        // buyGas checks the balance against it, while the caller only pays the
+        // effective gas price.
+        let (cost_sum, mul_max_fee_by_gas, gas_mul_max_fee_plus_value) = if calculate_total_cost {
+            let mul_max_fee_by_gas =
+                MulWordByU64Gadget::construct(cb, max_fee_per_gas.clone(), gas.expr());
             let cost_sum = cb.query_word32();
-            let gas_mul_gas_price_plus_value = AddWordsGadget::construct(
+            let gas_mul_max_fee_plus_value = AddWordsGadget::construct(
                 cb,
-                [mul_gas_fee_by_gas.product().clone(), value.clone()],
+                [mul_max_fee_by_gas.product().clone(), value.clone()],
                 cost_sum.clone(),
             );
-            (Some(cost_sum), Some(gas_mul_gas_price_plus_value))
+            (
+                Some(cost_sum),
+                Some(mul_max_fee_by_gas),
+                Some(gas_mul_max_fee_plus_value),
+            )
         } else {
-            (None, None)
+            (None, None, None)
         };
 
         Self {
@@ -258,23 +307,35 @@ impl<F: Field> TxDataGadget<F> {
             gas,
             call_data_length,
             call_data_gas_cost,
+            access_list_length,
+            access_list_storage_keys_length,
             gas_price,
             value,
+            is_deposit,
+            mint,
+            max_fee_per_gas,
             mul_gas_fee_by_gas,
             call_data_word_length,
             caller_address,
             callee_address,
-            gas_mul_gas_price_plus_value,
+            mul_max_fee_by_gas,
+            gas_mul_max_fee_plus_value,
             cost_sum,
         }
     }
 
-    pub(crate) fn intrinsic_gas(&self) -> Expression<F> {
+    /// Intrinsic gas of the tx: the base cost of a call or a creation, the
+    /// cost of the call data bytes, of the init code words of a creation
+    /// (EIP-3860), and of the access list (EIP-2930).  BeginTx requires the
+    /// tx gas to cover it, otherwise the tx is an InvalidTx.  The set-code
+    /// txs, whose authorizations cost gas too, are rejected by the
+    /// bus-mapping.
+    pub(crate) fn intrinsic_gas(&self, hardfork: Hardfork) -> Expression<F> {
         // Calculate gas cost of init code for EIP-3860.
         let init_code_gas_cost = select::expr(
             self.is_create.expr(),
             self.call_data_word_length.quotient().expr()
-                * eth_types::evm_types::INIT_CODE_WORD_GAS.expr(),
+                * hardfork.create_gas_per_code_word().expr(),
             0.expr(),
         );
 
@@ -284,10 +345,20 @@ impl<F: Field> TxDataGadget<F> {
             GasCost::TX.expr(),
         ) + self.call_data_gas_cost.expr()
             + init_code_gas_cost.expr()
+            + self.access_list_gas_cost()
+    }
+
+    /// Gas cost of the access list of the tx (EIP-2930)
+    pub(crate) fn access_list_gas_cost(&self) -> Expression<F> {
+        self.access_list_length.expr() * GasCost::ACCESS_LIST_ADDRESS.expr()
+            + self.access_list_storage_keys_length.expr() * GasCost::ACCESS_LIST_STORAGE_KEY.expr()
     }
 
+    /// Total cost of the tx, `gas * max_fee_per_gas + value`, which the
+    /// balance of the caller must cover.  Only available when the gadget is
+    /// configured with `calculate_total_cost`.
     pub(crate) fn total_cost(&self) -> Word32Cell<F> {
-        self.gas_mul_gas_price_plus_value
+        self.gas_mul_max_fee_plus_value
             .clone()
             .unwrap()
             .sum()
@@ -301,6 +372,8 @@ impl<F: Field> TxDataGadget<F> {
         tx: &Transaction,
     ) -> Result<(), Error> {
         let gas_fee = tx.gas_price * tx.gas();
+        let (max_fee_per_gas, _) = tx.fee_caps();
+        let max_gas_fee = max_fee_per_gas * tx.gas();
 
         self.nonce
             .assign(region, offset, Value::known(tx.nonce.as_u64().scalar()))?;
@@ -318,26 +391,48 @@ impl<F: Field> TxDataGadget<F> {
             offset,
             Value::known(tx.call_data_gas_cost().scalar()),
         )?;
+        self.access_list_length.assign(
+            region,
+            offset,
+            Value::known(F::from(tx.access_list_items().len() as u64)),
+        )?;
+        self.access_list_storage_keys_length.assign(
+            region,
+            offset,
+            Value::known(F::from(tx.access_list_storage_keys().count() as u64)),
+        )?;
         self.call_data_word_length
             .assign(region, offset, tx.call_data.len() as u128 + 31)?;
         self.gas_price.assign_u256(region, offset, tx.gas_price)?;
         self.value.assign_u256(region, offset, tx.value)?;
+        self.is_deposit
+            .assign(region, offset, Value::known(tx.is_deposit().scalar()))?;
+        self.mint.assign_u256(region, offset, tx.mint())?;
+        self.max_fee_per_gas
+            .assign_u256(region, offset, max_fee_per_gas)?;
         self.callee_address
             .assign_h160(region, offset, tx.to_or_contract_addr())?;
         self.caller_address.assign_h160(region, offset, tx.from)?;
         self.mul_gas_fee_by_gas
             .assign(region, offset, tx.gas_price, tx.gas(), gas_fee)?;
-        let sum = gas_fee + tx.value;
+        let sum = max_gas_fee + tx.value;
 
-        if self.cost_sum.is_some() && self.gas_mul_gas_price_plus_value.is_some() {
+        if self.cost_sum.is_some() && self.gas_mul_max_fee_plus_value.is_some() {
+            self.mul_max_fee_by_gas.as_ref().unwrap().assign(
+                region,
+                offset,
+                max_fee_per_gas,
+                tx.gas(),
+                max_gas_fee,
+            )?;
             self.cost_sum
                 .as_ref()
                 .unwrap()
                 .assign_u256(region, offset, sum)?;
-            self.gas_mul_gas_price_plus_value.as_ref().unwrap().assign(
+            self.gas_mul_max_fee_plus_value.as_ref().unwrap().assign(
                 region,
                 offset,
-                [gas_fee, tx.value],
+                [max_gas_fee, tx.value],
                 sum,
             )?;
         }
@@ -1,7 +1,7 @@
 //! The instance definition.
 
 use bus_mapping::circuit_input_builder::Withdrawal;
-use eth_types::{geth_types::BlockConstants, BigEndianHash, Field, Keccak};
+use eth_types::{geth_types::BlockConstants, sign_types::SignData, BigEndianHash, Field, Keccak};
 use std::{iter, ops::Deref};
 
 use eth_types::{geth_types::Transaction, Address, ToBigEndian, Word, H256};
@@ -31,6 +31,12 @@ pub struct BlockValues {
     pub chain_id: u64,
     /// withdrawals_root
     pub withdrawals_root: Word,
+    /// blob_base_fee
+    pub blob_base_fee: Word,
+    /// prev_randao
+    pub prev_randao: Word,
+    /// parent_beacon_root
+    pub parent_beacon_root: Word,
     /// history_hashes
     pub history_hashes: Vec<H256>,
 }
@@ -93,6 +99,8 @@ pub struct PublicData {
     pub block_hash: Option<H256>,
     /// withdrawals_root
     pub withdrawals_root: H256,
+    /// parent beacon block root (EIP-4788)
+    pub parent_beacon_root: H256,
 }
 
 impl Default for PublicData {
@@ -107,6 +115,7 @@ impl Default for PublicData {
             block_constants: BlockConstants::default(),
             block_hash: None,
             withdrawals_root: H256::zero(),
+            parent_beacon_root: H256::zero(),
         }
     }
 }
@@ -131,6 +140,9 @@ impl PublicData {
             base_fee: self.block_constants.base_fee,
             chain_id: self.chain_id.as_u64(),
             withdrawals_root: self.withdrawals_root.as_fixed_bytes().into(),
+            blob_base_fee: self.block_constants.blob_base_fee,
+            prev_randao: self.block_constants.prev_randao,
+            parent_beacon_root: self.parent_beacon_root.as_fixed_bytes().into(),
             history_hashes,
         }
     }
@@ -143,7 +155,13 @@ impl PublicData {
             .expect("Error converting chain_id to u64");
         let mut tx_vals = vec![];
         for tx in &self.transactions {
-            let sign_data_res = tx.sign_data(chain_id);
+            // A deposit tx has no signature, and its TxSignHash is the message hash of the
+            // padding signature, which tells it apart from the signed txs.
+            let sign_data_res = if tx.is_deposit() {
+                Ok(SignData::default())
+            } else {
+                tx.sign_data(chain_id)
+            };
             let msg_hash_le =
                 sign_data_res.map_or_else(|_| [0u8; 32], |sign_data| sign_data.msg_hash.to_bytes());
             tx_vals.push(TxValues {
@@ -196,6 +214,9 @@ impl PublicData {
             .chain(block_values.base_fee.to_be_bytes()) // base_fee
             .chain(block_values.chain_id.to_be_bytes()) // chain_id
             .chain(block_values.withdrawals_root.to_be_bytes()) // withdrawals root
+            .chain(block_values.blob_base_fee.to_be_bytes()) // blob_base_fee
+            .chain(block_values.prev_randao.to_be_bytes()) // prev_randao
+            .chain(block_values.parent_beacon_root.to_be_bytes()) // parent_beacon_root
             .chain(
                 block_values
                     .history_hashes
@@ -314,7 +335,10 @@ pub fn public_data_convert<F: Field>(block: &Block<F>) -> PublicData {
             difficulty: block.context.difficulty,
             gas_limit: block.context.gas_limit.into(),
             base_fee: block.context.base_fee,
+            blob_base_fee: block.context.blob_base_fee,
+            prev_randao: block.context.prev_randao,
         },
         withdrawals_root: block.withdrawals_root(),
+        parent_beacon_root: H256::from_uint(&block.context.parent_beacon_root),
     }
 }
@@ -25,9 +25,7 @@ use self::{
 use crate::{
     evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
     keccak_circuit::keccak_packed_multi::{
-        decode, get_num_bits_per_absorb_lookup, get_num_bits_per_base_chi_lookup,
-        get_num_bits_per_rho_pi_lookup, get_num_bits_per_theta_c_lookup, get_num_rows_per_round,
-        split, split_uniform, transform, transform_to, Part,
+        decode, get_num_rows_per_round, split, split_uniform, transform, transform_to, Part,
     },
     table::{KeccakTable, LookupTable},
     util::{
@@ -67,15 +65,85 @@ pub struct KeccakCircuitConfig<F> {
     normalize_6: [TableColumn; 2],
     chi_base_table: [TableColumn; 2],
     pack_table: [TableColumn; 2],
+    parameters: KeccakConfigParams,
     _marker: PhantomData<F>,
 }
 
+/// Layout parameters of the KeccakCircuit, which trade the degree of the
+/// circuit against its number of rows
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeccakConfigParams {
+    /// The circuit has 2^k rows, which bound the size of the lookup tables,
+    /// and so the number of bits of a word handled by a lookup
+    pub k: u32,
+    /// Number of rows per round of the keccak_f permutation, over which the
+    /// cells of a round are spread
+    pub rows_per_round: usize,
+}
+
+impl Default for KeccakConfigParams {
+    /// Return the parameters set by the KECCAK_DEGREE and KECCAK_ROWS env
+    /// variables.
+    fn default() -> Self {
+        Self {
+            k: get_degree() as u32,
+            rows_per_round: get_num_rows_per_round(),
+        }
+    }
+}
+
+impl KeccakConfigParams {
+    /// Return the number of unusable rows of the circuit with these
+    /// parameters
+    pub fn unusable_rows(&self) -> usize {
+        keccak_unusable_rows(self.rows_per_round)
+    }
+
+    /// Return the number of rows of a keccak_f permutation.
+    pub fn rows_per_permutation(&self) -> usize {
+        (NUM_ROUNDS + 1) * self.rows_per_round
+    }
+
+    /// Return the number of keccak_f's that fit in `num_rows` rows, or `None`
+    /// when `num_rows` is 0 and the circuit is sized to its inputs.
+    pub fn capacity(&self, num_rows: usize) -> Option<usize> {
+        if num_rows > 0 {
+            // Subtract two for unusable rows
+            Some(num_rows / self.rows_per_permutation() - 2)
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn num_bits_per_lookup(&self, range: usize) -> usize {
+        get_num_bits_per_lookup(range, self.k as usize, self.unusable_rows())
+    }
+
+    pub(crate) fn num_bits_per_absorb_lookup(&self) -> usize {
+        self.num_bits_per_lookup(ABSORB_LOOKUP_RANGE)
+    }
+
+    pub(crate) fn num_bits_per_theta_c_lookup(&self) -> usize {
+        self.num_bits_per_lookup(THETA_C_LOOKUP_RANGE)
+    }
+
+    pub(crate) fn num_bits_per_rho_pi_lookup(&self) -> usize {
+        self.num_bits_per_lookup(CHI_BASE_LOOKUP_RANGE.max(RHO_PI_LOOKUP_RANGE))
+    }
+
+    pub(crate) fn num_bits_per_base_chi_lookup(&self) -> usize {
+        self.num_bits_per_lookup(CHI_BASE_LOOKUP_RANGE.max(RHO_PI_LOOKUP_RANGE))
+    }
+}
+
 /// Circuit configuration arguments
 pub struct KeccakCircuitConfigArgs<F: Field> {
     /// KeccakTable
     pub keccak_table: KeccakTable,
     /// Challenges randomness
     pub challenges: Challenges<Expression<F>>,
+    /// Layout parameters
+    pub parameters: KeccakConfigParams,
 }
 
 impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
@@ -87,11 +155,12 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
         Self::ConfigArgs {
             keccak_table,
             challenges,
+            parameters,
         }: Self::ConfigArgs,
     ) -> Self {
         assert!(
-            get_num_rows_per_round() > NUM_BYTES_PER_WORD,
-            "KeccakCircuit requires KECCAK_ROWS>=9"
+            parameters.rows_per_round > NUM_BYTES_PER_WORD,
+            "KeccakCircuit requires rows_per_round >= 9"
         );
         let q_enable = meta.fixed_column();
         let q_first = meta.fixed_column();
@@ -114,7 +183,7 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
         let pack_table = array_init::array_init(|_| meta.lookup_table_column());
 
         let mut cell_manager = CellManager::new(CMFixedHeightStrategy::new(
-            get_num_rows_per_round(),
+            parameters.rows_per_round,
             DEFAULT_CELL_TYPE,
         ));
 
@@ -139,7 +208,9 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
             for j in 0..5 {
                 let cell = cell_manager.query_cell(meta, DEFAULT_CELL_TYPE);
                 s[i][j] = cell.expr();
-                s_next[i][j] = cell.at_offset(meta, get_num_rows_per_round() as i32).expr();
+                s_next[i][j] = cell
+                    .at_offset(meta, parameters.rows_per_round as i32)
+                    .expr();
             }
         }
         // Absorb data
@@ -150,7 +221,7 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
         let mut absorb_data_next = vec![0u64.expr(); NUM_WORDS_TO_ABSORB];
         let mut absorb_result_next = vec![0u64.expr(); NUM_WORDS_TO_ABSORB];
         for i in 0..NUM_WORDS_TO_ABSORB {
-            let rot = ((i + 1) * get_num_rows_per_round()) as i32;
+            let rot = ((i + 1) * parameters.rows_per_round) as i32;
             absorb_from_next[i] = absorb_from.at_offset(meta, rot).expr();
             absorb_data_next[i] = absorb_data.at_offset(meta, rot).expr();
             absorb_result_next[i] = absorb_result.at_offset(meta, rot).expr();
@@ -168,7 +239,7 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
         // rlc.
         cell_manager.get_strategy().start_region();
         let mut lookup_counter = 0;
-        let part_size = get_num_bits_per_absorb_lookup();
+        let part_size = parameters.num_bits_per_absorb_lookup();
         let input = absorb_from.expr() + absorb_data.expr();
         let absorb_fat = split::expr(meta, &mut cell_manager, &mut cb, input, 0, part_size);
         cell_manager.get_strategy().start_region();
@@ -242,7 +313,7 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
         // that allows us to also calculate the rotated value "for free".
         cell_manager.get_strategy().start_region();
         let mut lookup_counter = 0;
-        let part_size_c = get_num_bits_per_theta_c_lookup();
+        let part_size_c = parameters.num_bits_per_theta_c_lookup();
         let mut c_parts = Vec::new();
         for s in s.iter() {
             // Calculate c and split into parts
@@ -300,7 +371,7 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
         // `s[j][2 * i + 3 * j) % 5] = normalize(rot(s[i][j], RHOM[i][j]))`.
         cell_manager.get_strategy().start_region();
         let mut lookup_counter = 0;
-        let part_size = get_num_bits_per_base_chi_lookup();
+        let part_size = parameters.num_bits_per_base_chi_lookup();
         // To combine the rho/pi/chi steps we have to ensure a specific layout so
         // query those cells here first.
         // For chi we have to do `s[i][j] ^ ((~s[(i+1)%5][j]) & s[(i+2)%5][j])`. `j`
@@ -333,7 +404,7 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
                     if row_idx == 0 {
                         num_columns += 1;
                     }
-                    row_idx = (row_idx + 1) % get_num_rows_per_round();
+                    row_idx = (row_idx + 1) % parameters.rows_per_round;
                 }
             }
         }
@@ -387,7 +458,7 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
         // s[(i+2)%5][j])` five times, on each row (no selector needed).
         // This is calculated by making use of `CHI_BASE_LOOKUP_TABLE`.
         let mut lookup_counter = 0;
-        let part_size_base = get_num_bits_per_base_chi_lookup();
+        let part_size_base = parameters.num_bits_per_base_chi_lookup();
         for idx in 0..num_columns {
             // First fetch the cells we wan to use
             let mut input: [Expression<F>; 5] = array_init::array_init(|_| 0.expr());
@@ -436,7 +507,7 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
         // iota
         // Simply do the single xor on state [0][0].
         cell_manager.get_strategy().start_region();
-        let part_size = get_num_bits_per_absorb_lookup();
+        let part_size = parameters.num_bits_per_absorb_lookup();
         let input = s[0][0].clone() + round_cst_expr.clone();
         let iota_parts = split::expr(meta, &mut cell_manager, &mut cb, input, 0, part_size);
         cell_manager.get_strategy().start_region();
@@ -469,7 +540,7 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
         let squeeze_from = cell_manager.query_cell(meta, DEFAULT_CELL_TYPE);
         let mut squeeze_from_prev = vec![0u64.expr(); NUM_WORDS_TO_SQUEEZE];
         for (idx, squeeze_from_prev) in squeeze_from_prev.iter_mut().enumerate() {
-            let rot = (-(idx as i32) - 1) * get_num_rows_per_round() as i32;
+            let rot = (-(idx as i32) - 1) * parameters.rows_per_round as i32;
             *squeeze_from_prev = squeeze_from.at_offset(meta, rot).expr();
         }
         // Squeeze
@@ -549,7 +620,7 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
         let mut hash_bytes = Vec::new();
         for i in 0..NUM_WORDS_TO_SQUEEZE {
             for byte in squeeze_bytes.iter() {
-                let rot = (-(i as i32) - 1) * get_num_rows_per_round() as i32;
+                let rot = (-(i as i32) - 1) * parameters.rows_per_round as i32;
                 hash_bytes.push(byte.cell.at_offset(meta, rot).expr());
             }
         }
@@ -610,7 +681,7 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
         // Enforce logic for when this block is the last block for a hash
         let last_is_padding_in_block = is_paddings.last().unwrap().at_offset(
             meta,
-            -(((NUM_ROUNDS + 1 - NUM_WORDS_TO_ABSORB) * get_num_rows_per_round()) as i32),
+            -(((NUM_ROUNDS + 1 - NUM_WORDS_TO_ABSORB) * parameters.rows_per_round) as i32),
         );
         meta.create_gate("is final", |meta| {
             let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
@@ -628,7 +699,7 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
             );
             // For all the rows of a round, only the first row can have `is_final == 1`.
             cb.condition(
-                (1..get_num_rows_per_round() as i32)
+                (1..parameters.rows_per_round as i32)
                     .map(|i| meta.query_fixed(q_enable, Rotation(-i)))
                     .fold(0.expr(), |acc, elem| acc + elem),
                 |cb| {
@@ -647,7 +718,7 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
         let prev_is_padding = is_paddings
             .last()
             .unwrap()
-            .at_offset(meta, -(get_num_rows_per_round() as i32));
+            .at_offset(meta, -(parameters.rows_per_round as i32));
         meta.create_gate("padding", |meta| {
             let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
             let q_padding = meta.query_fixed(q_padding, Rotation::cur());
@@ -738,12 +809,12 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
 
             let q_padding = meta.query_fixed(q_padding, Rotation::cur());
             let start_new_hash_prev =
-                start_new_hash(meta, Rotation(-(get_num_rows_per_round() as i32)));
+                start_new_hash(meta, Rotation(-(parameters.rows_per_round as i32)));
             let length_prev =
-                meta.query_advice(length, Rotation(-(get_num_rows_per_round() as i32)));
+                meta.query_advice(length, Rotation(-(parameters.rows_per_round as i32)));
             let length = meta.query_advice(length, Rotation::cur());
             let data_rlc_prev =
-                meta.query_advice(data_rlc, Rotation(-(get_num_rows_per_round() as i32)));
+                meta.query_advice(data_rlc, Rotation(-(parameters.rows_per_round as i32)));
             let data_rlcs: Vec<_> = (0..NUM_BYTES_PER_WORD + 1)
                 .map(|i| meta.query_advice(data_rlc, Rotation(i as i32)))
                 .collect();
@@ -842,18 +913,30 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
             "num unused cells: {}",
             cell_manager.get_strategy().get_num_unused_cells()
         );
-        debug!("part_size absorb: {}", get_num_bits_per_absorb_lookup());
-        debug!("part_size theta: {}", get_num_bits_per_theta_c_lookup());
+        debug!(
+            "part_size absorb: {}",
+            parameters.num_bits_per_absorb_lookup()
+        );
+        debug!(
+            "part_size theta: {}",
+            parameters.num_bits_per_theta_c_lookup()
+        );
         debug!(
             "part_size theta c: {}",
-            get_num_bits_per_lookup(THETA_C_LOOKUP_RANGE)
+            parameters.num_bits_per_lookup(THETA_C_LOOKUP_RANGE)
+        );
+        debug!("part_size theta t: {}", parameters.num_bits_per_lookup(4));
+        debug!(
+            "part_size rho/pi: {}",
+            parameters.num_bits_per_rho_pi_lookup()
+        );
+        debug!(
+            "part_size chi base: {}",
+            parameters.num_bits_per_base_chi_lookup()
         );
-        debug!("part_size theta t: {}", get_num_bits_per_lookup(4));
-        debug!("part_size rho/pi: {}", get_num_bits_per_rho_pi_lookup());
-        debug!("part_size chi base: {}", get_num_bits_per_base_chi_lookup());
         debug!(
             "uniform part sizes: {:?}",
-            target_part_sizes(get_num_bits_per_theta_c_lookup())
+            target_part_sizes(parameters.num_bits_per_theta_c_lookup())
         );
 
         KeccakCircuitConfig {
@@ -872,12 +955,33 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
             normalize_6,
             chi_base_table,
             pack_table,
+            parameters,
             _marker: PhantomData,
         }
     }
 }
 
 impl<F: Field> KeccakCircuitConfig<F> {
+    /// Assign the keccak rows of the inputs yielded by `inputs`, which can
+    /// borrow the preimages so that they are not copied before the
+    /// assignment.  `num_rows` is the number of rows of the circuit, 0 meaning
+    /// that the circuit is sized to the inputs.
+    pub fn assign_inputs(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        num_rows: usize,
+        inputs: impl IntoIterator<Item = impl AsRef<[u8]> + Send>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        let witness = multi_keccak(
+            inputs,
+            *challenges,
+            self.parameters.capacity(num_rows),
+            self.parameters,
+        )?;
+        self.assign(layouter, witness.as_slice())
+    }
+
     pub(crate) fn assign(
         &self,
         layouter: &mut impl Layouter<F>,
@@ -967,14 +1071,24 @@ impl<F: Field> KeccakCircuitConfig<F> {
     }
 
     pub(crate) fn load_aux_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
-        load_normalize_table(layouter, "normalize_6", &self.normalize_6, 6u64)?;
-        load_normalize_table(layouter, "normalize_4", &self.normalize_4, 4u64)?;
-        load_normalize_table(layouter, "normalize_3", &self.normalize_3, 3u64)?;
+        for (name, tables, range) in [
+            ("normalize_6", &self.normalize_6, 6),
+            ("normalize_4", &self.normalize_4, 4),
+            ("normalize_3", &self.normalize_3, 3),
+        ] {
+            load_normalize_table(
+                layouter,
+                name,
+                tables,
+                range as u64,
+                self.parameters.num_bits_per_lookup(range),
+            )?;
+        }
         load_lookup_table(
             layouter,
             "chi base",
             &self.chi_base_table,
-            get_num_bits_per_base_chi_lookup(),
+            self.parameters.num_bits_per_base_chi_lookup(),
             &CHI_BASE_LOOKUP_TABLE,
         )?;
         load_pack_table(layouter, &self.pack_table)
@@ -995,6 +1109,7 @@ impl<F: Field> KeccakCircuitConfig<F> {
 pub struct KeccakCircuit<F: Field> {
     inputs: Vec<Vec<u8>>,
     num_rows: usize,
+    parameters: KeccakConfigParams,
     _marker: PhantomData<F>,
 }
 
@@ -1002,7 +1117,7 @@ impl<F: Field> SubCircuit<F> for KeccakCircuit<F> {
     type Config = KeccakCircuitConfig<F>;
 
     fn unusable_rows() -> usize {
-        keccak_unusable_rows()
+        KeccakConfigParams::default().unusable_rows()
     }
 
     /// The `block.circuits_params.keccak_padding` parmeter, when enabled, sets
@@ -1017,7 +1132,7 @@ impl<F: Field> SubCircuit<F> for KeccakCircuit<F> {
 
     /// Return the minimum number of rows required to prove the block
     fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
-        let rows_per_chunk = (NUM_ROUNDS + 1) * get_num_rows_per_round();
+        let rows_per_chunk = KeccakConfigParams::default().rows_per_permutation();
         (
             block
                 .keccak_inputs
@@ -1036,34 +1151,38 @@ impl<F: Field> SubCircuit<F> for KeccakCircuit<F> {
         layouter: &mut impl Layouter<F>,
     ) -> Result<(), Error> {
         config.load_aux_tables(layouter)?;
-        let witness = self.generate_witness(*challenges);
-        config.assign(layouter, witness.as_slice())
+        config.assign_inputs(layouter, self.num_rows, &self.inputs, challenges)
     }
 }
 
 impl<F: Field> KeccakCircuit<F> {
-    /// Creates a new circuit instance
+    /// Creates a new circuit instance with the layout parameters of the env
+    /// variables
     pub fn new(num_rows: usize, inputs: Vec<Vec<u8>>) -> Self {
+        Self::new_with_parameters(num_rows, inputs, KeccakConfigParams::default())
+    }
+
+    /// Creates a new circuit instance with the given layout parameters
+    pub fn new_with_parameters(
+        num_rows: usize,
+        inputs: Vec<Vec<u8>>,
+        parameters: KeccakConfigParams,
+    ) -> Self {
         KeccakCircuit {
             inputs,
             num_rows,
+            parameters,
             _marker: PhantomData,
         }
     }
 
-    /// The number of keccak_f's that can be done in this circuit
-    pub fn capacity(&self) -> Option<usize> {
-        if self.num_rows > 0 {
-            // Subtract two for unusable rows
-            Some(self.num_rows / ((NUM_ROUNDS + 1) * get_num_rows_per_round()) - 2)
-        } else {
-            None
-        }
+    /// Return the layout parameters of the circuit
+    pub fn parameters(&self) -> KeccakConfigParams {
+        self.parameters
     }
 
-    /// Sets the witness using the data to be hashed
-    pub(crate) fn generate_witness(&self, challenges: Challenges<Value<F>>) -> Vec<KeccakRow<F>> {
-        multi_keccak(self.inputs.as_slice(), challenges, self.capacity())
-            .expect("Too many inputs for given capacity")
+    /// The number of keccak_f's that can be done in this circuit
+    pub fn capacity(&self) -> Option<usize> {
+        self.parameters.capacity(self.num_rows)
     }
 }
@@ -1,7 +1,7 @@
 pub use super::KeccakCircuit;
 
 use crate::{
-    keccak_circuit::{KeccakCircuitConfig, KeccakCircuitConfigArgs},
+    keccak_circuit::{KeccakCircuitConfig, KeccakCircuitConfigArgs, KeccakConfigParams},
     table::KeccakTable,
     util::{Challenges, SubCircuit, SubCircuitConfig},
 };
@@ -14,13 +14,24 @@ use halo2_proofs::{
 impl<F: Field> Circuit<F> for KeccakCircuit<F> {
     type Config = (KeccakCircuitConfig<F>, Challenges);
     type FloorPlanner = SimpleFloorPlanner;
-    type Params = ();
+    type Params = KeccakConfigParams;
 
     fn without_witnesses(&self) -> Self {
-        Self::default()
+        Self::new_with_parameters(0, vec![], self.parameters())
+    }
+
+    fn params(&self) -> Self::Params {
+        self.parameters()
     }
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        Self::configure_with_params(meta, KeccakConfigParams::default())
+    }
+
+    fn configure_with_params(
+        meta: &mut ConstraintSystem<F>,
+        parameters: Self::Params,
+    ) -> Self::Config {
         let keccak_table = KeccakTable::construct(meta);
         let challenges = Challenges::construct(meta);
 
@@ -31,6 +42,7 @@ impl<F: Field> Circuit<F> for KeccakCircuit<F> {
                 KeccakCircuitConfigArgs {
                     keccak_table,
                     challenges,
+                    parameters,
                 },
             )
         };
@@ -1,4 +1,4 @@
-use super::{param::*, util::*, DEFAULT_CELL_TYPE};
+use super::{param::*, util::*, KeccakConfigParams, DEFAULT_CELL_TYPE};
 use crate::util::{
     cell_manager::{CMFixedHeightStrategy, Cell, CellManager},
     word::Word,
@@ -10,7 +10,8 @@ use halo2_proofs::{
     plonk::{Error, Expression},
 };
 use log::debug;
-use std::{env::var, vec};
+use rayon::prelude::*;
+use std::{collections::HashSet, env::var, vec};
 
 pub(crate) fn get_num_rows_per_round() -> usize {
     var("KECCAK_ROWS")
@@ -19,28 +20,12 @@ pub(crate) fn get_num_rows_per_round() -> usize {
         .expect("Cannot parse KECCAK_ROWS env var as usize")
 }
 
-pub(crate) fn keccak_unusable_rows() -> usize {
+pub(crate) fn keccak_unusable_rows(rows_per_round: usize) -> usize {
     const UNUSABLE_ROWS_BY_KECCAK_ROWS: [usize; 24] = [
         53, 67, 63, 59, 45, 79, 77, 75, 73, 71, 69, 67, 65, 63, 61, 59, 57, 71, 89, 107, 107, 107,
         107, 107,
     ];
-    UNUSABLE_ROWS_BY_KECCAK_ROWS[get_num_rows_per_round() - NUM_BYTES_PER_WORD - 1]
-}
-
-pub(crate) fn get_num_bits_per_absorb_lookup() -> usize {
-    get_num_bits_per_lookup(ABSORB_LOOKUP_RANGE)
-}
-
-pub(crate) fn get_num_bits_per_theta_c_lookup() -> usize {
-    get_num_bits_per_lookup(THETA_C_LOOKUP_RANGE)
-}
-
-pub(crate) fn get_num_bits_per_rho_pi_lookup() -> usize {
-    get_num_bits_per_lookup(CHI_BASE_LOOKUP_RANGE.max(RHO_PI_LOOKUP_RANGE))
-}
-
-pub(crate) fn get_num_bits_per_base_chi_lookup() -> usize {
-    get_num_bits_per_lookup(CHI_BASE_LOOKUP_RANGE.max(RHO_PI_LOOKUP_RANGE))
+    UNUSABLE_ROWS_BY_KECCAK_ROWS[rows_per_round - NUM_BYTES_PER_WORD - 1]
 }
 
 pub(crate) trait AssignKeccakRegion {
@@ -523,6 +508,7 @@ pub(crate) fn keccak<F: Field>(
     rows: &mut Vec<KeccakRow<F>>,
     bytes: &[u8],
     challenges: Challenges<Value<F>>,
+    parameters: KeccakConfigParams,
 ) {
     let mut bits = into_bits(bytes);
     let mut s = [[F::ZERO; 5]; 5];
@@ -567,7 +553,7 @@ pub(crate) fn keccak<F: Field>(
         let mut round_data_rlcs = Vec::new();
         for round in 0..NUM_ROUNDS + 1 {
             let mut cell_manager = CellManager::new(CMFixedHeightStrategy::new(
-                get_num_rows_per_round(),
+                parameters.rows_per_round,
                 DEFAULT_CELL_TYPE,
             ));
             let mut region = KeccakRegion::new();
@@ -595,7 +581,7 @@ pub(crate) fn keccak<F: Field>(
 
             // Absorb
             cell_manager.get_strategy().start_region();
-            let part_size = get_num_bits_per_absorb_lookup();
+            let part_size = parameters.num_bits_per_absorb_lookup();
             let input = absorb_row.from + absorb_row.absorb;
             let absorb_fat = split::value(&mut cell_manager, &mut region, input, 0, part_size);
             cell_manager.get_strategy().start_region();
@@ -618,7 +604,7 @@ pub(crate) fn keccak<F: Field>(
                 transform::value(&mut cell_manager, &mut region, packed, false, |v| *v, true);
             cell_manager.get_strategy().start_region();
             let mut is_paddings: Vec<Cell<F>> = Vec::new();
-            let mut data_rlcs = vec![Value::known(F::ZERO); get_num_rows_per_round()];
+            let mut data_rlcs = vec![Value::known(F::ZERO); parameters.rows_per_round];
             for _ in input_bytes.iter() {
                 is_paddings.push(cell_manager.query_cell_value(DEFAULT_CELL_TYPE));
             }
@@ -655,7 +641,7 @@ pub(crate) fn keccak<F: Field>(
 
             if round != NUM_ROUNDS {
                 // Theta
-                let part_size = get_num_bits_per_theta_c_lookup();
+                let part_size = parameters.num_bits_per_theta_c_lookup();
                 let mut bcf = Vec::new();
                 for s in &s {
                     let c = s[0] + s[1] + s[2] + s[3] + s[4];
@@ -688,7 +674,7 @@ pub(crate) fn keccak<F: Field>(
                 cell_manager.get_strategy().start_region();
 
                 // Rho/Pi
-                let part_size = get_num_bits_per_base_chi_lookup();
+                let part_size = parameters.num_bits_per_base_chi_lookup();
                 let target_word_sizes = target_part_sizes(part_size);
                 let num_word_parts = target_word_sizes.len();
                 let mut rho_pi_chi_cells: [[[Vec<Cell<F>>; 5]; 5]; 3] =
@@ -707,7 +693,7 @@ pub(crate) fn keccak<F: Field>(
                                         .query_cell_value_with_affinity(DEFAULT_CELL_TYPE, row_idx),
                                 );
                             }
-                            row_idx = (row_idx + 1) % get_num_rows_per_round();
+                            row_idx = (row_idx + 1) % parameters.rows_per_round;
                         }
                     }
                 }
@@ -738,7 +724,7 @@ pub(crate) fn keccak<F: Field>(
                 cell_manager.get_strategy().start_region();
 
                 // Chi
-                let part_size_base = get_num_bits_per_base_chi_lookup();
+                let part_size_base = parameters.num_bits_per_base_chi_lookup();
                 let three_packed = pack::<F>(&vec![3u8; part_size_base]);
                 let mut os = [[F::ZERO; 5]; 5];
                 for j in 0..5 {
@@ -770,7 +756,7 @@ pub(crate) fn keccak<F: Field>(
                 cell_manager.get_strategy().start_region();
 
                 // iota
-                let part_size = get_num_bits_per_absorb_lookup();
+                let part_size = parameters.num_bits_per_absorb_lookup();
                 let input = s[0][0] + pack_u64::<F>(ROUND_CST[round]);
                 let iota_parts =
                     split::value::<F>(&mut cell_manager, &mut region, input, 0, part_size);
@@ -831,7 +817,7 @@ pub(crate) fn keccak<F: Field>(
 
         for round in 0..NUM_ROUNDS + 1 {
             let round_cst = pack_u64(ROUND_CST[round]);
-            for row_idx in 0..get_num_rows_per_round() {
+            for row_idx in 0..parameters.rows_per_round {
                 rows.push(KeccakRow {
                     q_enable: row_idx == 0,
                     q_round: row_idx == 0 && round < NUM_ROUNDS,
@@ -868,15 +854,18 @@ pub(crate) fn keccak<F: Field>(
     }
 }
 
+/// Return the keccak rows of the inputs, which are consumed one by one from
+/// the iterator and only need to give access to their bytes.
 pub(crate) fn multi_keccak<F: Field>(
-    bytes: &[Vec<u8>],
+    bytes: impl IntoIterator<Item = impl AsRef<[u8]> + Send>,
     challenges: Challenges<Value<F>>,
     capacity: Option<usize>,
+    parameters: KeccakConfigParams,
 ) -> Result<Vec<KeccakRow<F>>, Error> {
     let mut rows: Vec<KeccakRow<F>> = Vec::new();
     // Dummy first row so that the initial data is absorbed
     // The initial data doesn't really matter, `is_final` just needs to be disabled.
-    for idx in 0..get_num_rows_per_round() {
+    for idx in 0..parameters.rows_per_round {
         rows.push(KeccakRow {
             q_enable: idx == 0,
             q_round: false,
@@ -892,26 +881,47 @@ pub(crate) fn multi_keccak<F: Field>(
             cell_values: Vec::new(),
         });
     }
-    // Actual keccaks
-    for bytes in bytes {
-        keccak(&mut rows, bytes, challenges);
+    // Actual keccaks, the rows of every input are generated in parallel and
+    // appended in the order of the inputs.  An input that already occurred is
+    // skipped, the lookups of all its occurrences hitting the same rows.
+    let bytes = bytes.into_iter().collect::<Vec<_>>();
+    let is_first_occurrence = {
+        let mut occurred = HashSet::new();
+        bytes
+            .iter()
+            .map(|bytes| occurred.insert(bytes.as_ref()))
+            .collect::<Vec<_>>()
+    };
+    let keccak_rows = bytes
+        .into_par_iter()
+        .zip(is_first_occurrence)
+        .filter_map(|(bytes, is_first_occurrence)| is_first_occurrence.then_some(bytes))
+        .map(|bytes| {
+            let mut rows = Vec::new();
+            keccak(&mut rows, bytes.as_ref(), challenges, parameters);
+            rows
+        })
+        .collect::<Vec<_>>();
+    rows.reserve(keccak_rows.iter().map(Vec::len).sum());
+    for keccak_rows in keccak_rows {
+        rows.extend(keccak_rows);
     }
     if let Some(capacity) = capacity {
         let padding_rows = {
             let mut rows = Vec::new();
-            keccak(&mut rows, &[], challenges);
+            keccak(&mut rows, &[], challenges, parameters);
             rows
         };
         // Pad with no data hashes to the expected capacity
-        while rows.len() < (1 + capacity * (NUM_ROUNDS + 1)) * get_num_rows_per_round() {
+        while rows.len() < (1 + capacity * (NUM_ROUNDS + 1)) * parameters.rows_per_round {
             rows.extend(padding_rows.clone());
         }
         // Check that we are not over capacity
-        if rows.len() > (1 + capacity * (NUM_ROUNDS + 1)) * get_num_rows_per_round() {
+        if rows.len() > (1 + capacity * (NUM_ROUNDS + 1)) * parameters.rows_per_round {
             log::error!(
                 "Keccack inputs exceed capacity.  needed_rows = {}, available_rows = {}",
                 rows.len(),
-                (1 + capacity * (NUM_ROUNDS + 1)) * get_num_rows_per_round()
+                (1 + capacity * (NUM_ROUNDS + 1)) * parameters.rows_per_round
             );
             return Err(Error::BoundsFailure);
         }
@@ -6,27 +6,16 @@ use halo2_proofs::{
 };
 use itertools::Itertools;
 
-/// Loads a normalization table with the given parameters and KECCAK_DEGREE.
+/// Loads a normalization table of the values in `range` of `part_size`
+/// parts.
 pub(crate) fn load_normalize_table<F: Field>(
     layouter: &mut impl Layouter<F>,
     name: &str,
     tables: &[TableColumn; 2],
     range: u64,
-) -> Result<(), Error> {
-    let log_height = get_degree();
-    load_normalize_table_impl(layouter, name, tables, range, log_height)
-}
-
-// Implementation of the above without environment dependency.
-fn load_normalize_table_impl<F: Field>(
-    layouter: &mut impl Layouter<F>,
-    name: &str,
-    tables: &[TableColumn; 2],
-    range: u64,
-    log_height: usize,
+    part_size: usize,
 ) -> Result<(), Error> {
     assert!(range <= BIT_SIZE as u64);
-    let part_size = get_num_bits_per_lookup_impl(range as usize, log_height);
     layouter.assign_table(
         || format!("{} table", name),
         |mut table| {
@@ -129,7 +118,7 @@ pub(crate) fn load_lookup_table<F: Field>(
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use super::{super::keccak_packed_multi::keccak_unusable_rows, *};
     use halo2_proofs::{
         circuit::SimpleFloorPlanner,
         dev::{CellValue, MockProver},
@@ -233,9 +222,16 @@ mod tests {
     }
 
     impl TableTestCircuit {
+        fn num_bits(&self) -> usize {
+            get_num_bits_per_lookup(
+                self.range,
+                self.log_height,
+                keccak_unusable_rows(DEFAULT_KECCAK_ROWS),
+            )
+        }
+
         fn expected_num_entries(&self) -> usize {
-            let num_bits = get_num_bits_per_lookup_impl(self.range, self.log_height);
-            self.range.pow(num_bits as u32)
+            self.range.pow(self.num_bits() as u32)
         }
     }
 
@@ -258,20 +254,19 @@ mod tests {
             mut layouter: impl Layouter<F>,
         ) -> Result<(), Error> {
             if self.normalize_else_chi {
-                load_normalize_table_impl(
+                load_normalize_table(
                     &mut layouter,
                     "normalize",
                     &config,
                     self.range as u64,
-                    self.log_height,
+                    self.num_bits(),
                 )?;
             } else {
-                let num_bits = get_num_bits_per_lookup_impl(self.range, self.log_height);
                 load_lookup_table(
                     &mut layouter,
                     "chi base",
                     &config,
-                    num_bits,
+                    self.num_bits(),
                     &CHI_BASE_LOOKUP_TABLE,
                 )?;
             }
@@ -16,27 +16,41 @@ use std::iter::zip;
 
 use super::util::{target_part_sizes, target_part_sizes_rot, WordParts};
 
-// This needs to be tested independent since it sets the environment variable
-// which might affect other tests.
-#[ignore]
 #[test]
-fn serial_keccak_circuit_unusable_rows() {
-    for keccak_rows in NUM_BYTES_PER_WORD + 1..=32 {
-        std::env::set_var("KECCAK_ROWS", format!("{keccak_rows}"));
+fn keccak_circuit_unusable_rows() {
+    for rows_per_round in NUM_BYTES_PER_WORD + 1..=32 {
+        let parameters = KeccakConfigParams {
+            rows_per_round,
+            ..KeccakConfigParams::default()
+        };
         assert_eq!(
-            KeccakCircuit::<Fr>::unusable_rows(),
-            unusable_rows::<Fr, KeccakCircuit::<Fr>>(()),
+            parameters.unusable_rows(),
+            unusable_rows::<Fr, KeccakCircuit::<Fr>>(parameters),
         )
     }
-    std::env::set_var("KECCAK_ROWS", format!("{DEFAULT_KECCAK_ROWS}"));
+    assert_eq!(
+        KeccakCircuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, KeccakCircuit::<Fr>>(KeccakConfigParams::default()),
+    )
 }
 
 const EMPTY_DIGEST: &str = "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470";
 
 fn verify<F: Field>(k: u32, inputs: Vec<Vec<u8>>, digests: Vec<String>, success: bool) {
-    let circuit = KeccakCircuit::new(2usize.pow(k), inputs.clone());
+    verify_with_parameters::<F>(k, KeccakConfigParams::default(), inputs, digests, success)
+}
+
+fn verify_with_parameters<F: Field>(
+    k: u32,
+    parameters: KeccakConfigParams,
+    inputs: Vec<Vec<u8>>,
+    digests: Vec<String>,
+    success: bool,
+) {
+    let circuit = KeccakCircuit::new_with_parameters(2usize.pow(k), inputs.clone(), parameters);
     let prover = MockProver::<F>::run(k, &circuit, vec![]).unwrap();
-    let (config, challenges) = KeccakCircuit::configure(&mut ConstraintSystem::<F>::default());
+    let (config, challenges) =
+        KeccakCircuit::configure_with_params(&mut ConstraintSystem::<F>::default(), parameters);
     let input_challenge = prover.get_challenge(challenges.keccak_input());
 
     // Check constraints.
@@ -126,6 +140,29 @@ fn packed_multi_keccak_simple() {
     verify::<Fr>(k, inputs, digests, true);
 }
 
+#[test]
+fn packed_multi_keccak_parameters() {
+    let inputs = vec![vec![], (0u8..137).collect::<Vec<_>>()];
+    let digests = vec![
+        EMPTY_DIGEST.to_string(),
+        "ac73d4fae68b8453f764007c1a20ce95994187861f0c3227a3a8e99a73a3b1db".to_string(),
+    ];
+    // Fewer rows per round take more columns, and larger lookup tables take
+    // fewer cells.
+    for parameters in [
+        KeccakConfigParams {
+            k: 10,
+            rows_per_round: 9,
+        },
+        KeccakConfigParams {
+            k: 12,
+            rows_per_round: 25,
+        },
+    ] {
+        verify_with_parameters::<Fr>(12, parameters, inputs.clone(), digests.clone(), true);
+    }
+}
+
 fn assigned_non_zero<F: Field>(cv: &CellValue<F>) -> bool {
     match *cv {
         CellValue::Assigned(v) => !v.is_zero_vartime(),
@@ -164,6 +201,33 @@ fn variadic_size_check() {
     assert_eq!(prover1.permutation(), prover2.permutation());
 }
 
+#[test]
+fn multi_keccak_borrowed_inputs() {
+    // Inputs borrowed from a single buffer give the same rows as owned ones.
+    let buffer = (0..400).map(|i| (1 + 3 * i) as u8).collect::<Vec<_>>();
+    let owned = buffer.chunks(136).map(<[u8]>::to_vec).collect::<Vec<_>>();
+    let challenges = Challenges::mock(Value::known(Fr::from(123456)), Value::unknown());
+    let parameters = KeccakConfigParams::default();
+    let rows = multi_keccak(&owned, challenges, None, parameters).unwrap();
+    let borrowed_rows = multi_keccak(buffer.chunks(136), challenges, None, parameters).unwrap();
+    assert_eq!(rows.len(), borrowed_rows.len());
+    for (row, borrowed_row) in rows.iter().zip(borrowed_rows.iter()) {
+        assert_eq!(row.cell_values, borrowed_row.cell_values);
+        assert_eq!(row.length, borrowed_row.length);
+    }
+}
+
+#[test]
+fn multi_keccak_duplicated_inputs() {
+    // Every distinct input is only hashed once.
+    let inputs = vec![vec![], (0u8..137).collect::<Vec<_>>(), vec![0], vec![0]];
+    let challenges = Challenges::mock(Value::known(Fr::from(123456)), Value::unknown());
+    let parameters = KeccakConfigParams::default();
+    let rows = multi_keccak(&inputs, challenges, None, parameters).unwrap();
+    let distinct_rows = multi_keccak(&inputs[..3], challenges, None, parameters).unwrap();
+    assert_eq!(rows.len(), distinct_rows.len());
+}
+
 #[test]
 fn test_target_part_sizes() {
     // Uniform 8 parts of 8 bits each.
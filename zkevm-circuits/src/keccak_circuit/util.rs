@@ -1,6 +1,6 @@
 //! Utility traits, functions used in the crate.
 
-use super::{keccak_packed_multi::keccak_unusable_rows, param::*};
+use super::param::*;
 use eth_types::{Field, ToScalar, Word};
 use std::env::var;
 
@@ -208,15 +208,13 @@ pub(crate) fn get_degree() -> usize {
 }
 
 /// Returns how many bits we can process in a single lookup given the range of
-/// values the bit can have and the height of the circuit (via KECCAK_DEGREE).
-pub(crate) fn get_num_bits_per_lookup(range: usize) -> usize {
-    let log_height = get_degree();
-    get_num_bits_per_lookup_impl(range, log_height)
-}
-
-// Implementation of the above without environment dependency.
-pub(crate) fn get_num_bits_per_lookup_impl(range: usize, log_height: usize) -> usize {
-    let num_unusable_rows = keccak_unusable_rows();
+/// values the bit can have and the height of the circuit, of which
+/// `num_unusable_rows` rows can't hold the lookup table.
+pub(crate) fn get_num_bits_per_lookup(
+    range: usize,
+    log_height: usize,
+    num_unusable_rows: usize,
+) -> usize {
     let height = 2usize.pow(log_height as u32);
     let mut num_bits = 1;
     while range.pow(num_bits + 1) + num_unusable_rows <= height {
@@ -255,7 +253,7 @@ pub(crate) mod to_bytes {
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use super::{super::keccak_packed_multi::keccak_unusable_rows, *};
     use halo2_proofs::halo2curves::bn256::Fr as F;
 
     #[test]
@@ -271,12 +269,15 @@ mod tests {
 
     #[test]
     fn num_bits_per_lookup() {
+        let num_unusable_rows = keccak_unusable_rows(DEFAULT_KECCAK_ROWS);
+        let num_bits =
+            |range, log_height| get_num_bits_per_lookup(range, log_height, num_unusable_rows);
         // Typical values.
-        assert_eq!(get_num_bits_per_lookup_impl(3, 19), 11);
-        assert_eq!(get_num_bits_per_lookup_impl(4, 19), 9);
-        assert_eq!(get_num_bits_per_lookup_impl(5, 19), 8);
-        assert_eq!(get_num_bits_per_lookup_impl(6, 19), 7);
+        assert_eq!(num_bits(3, 19), 11);
+        assert_eq!(num_bits(4, 19), 9);
+        assert_eq!(num_bits(5, 19), 8);
+        assert_eq!(num_bits(6, 19), 7);
         // The largest possible value does not overflow u64.
-        assert_eq!(get_num_bits_per_lookup_impl(3, 32) * BIT_COUNT, 60);
+        assert_eq!(num_bits(3, 32) * BIT_COUNT, 60);
     }
 }
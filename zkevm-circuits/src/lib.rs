@@ -22,18 +22,28 @@ compile_error!("bus-mapping: notwasm feature must be disabled when target arch i
 #[cfg(all(not(target_arch = "wasm32"), not(feature = "notwasm")))]
 compile_error!("bus-mapping: notwasm feature must be enabled when target arch is not wasm");
 
+pub mod blake2f_circuit;
 pub mod bytecode_circuit;
 #[allow(dead_code, reason = "under active development")]
 pub mod circuit_tools;
 pub mod copy_circuit;
+pub mod ecc_circuit;
 pub mod evm_circuit;
 pub mod exp_circuit;
 pub mod keccak_circuit;
+pub mod modexp_circuit;
 #[allow(dead_code, reason = "under active development")]
 pub mod mpt_circuit;
+pub mod p256_sig_circuit;
 pub mod pi_circuit;
+pub mod poseidon_circuit;
+pub mod ripemd160_circuit;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod root_circuit;
+#[cfg(feature = "sha1")]
+pub mod sha1_circuit;
+pub mod sha256_circuit;
+pub mod sig_circuit;
 pub mod state_circuit;
 pub mod super_circuit;
 pub mod table;
@@ -44,7 +54,9 @@ pub mod test_util;
 
 pub mod instance;
 pub mod tx_circuit;
+pub mod user_op_circuit;
 pub mod util;
 pub mod witness;
+pub mod x509_circuit;
 
 pub use gadgets::impl_expr;
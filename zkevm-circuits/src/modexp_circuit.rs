@@ -0,0 +1,670 @@
+//! ModExp circuit.
+//!
+//! The circuit proves the modular exponentiations `base ^ exponent mod modulus`
+//! of the modexp calls, and lays out every one of them in the [`ModExpTable`],
+//! so that the EVM circuit can look up the modexp calls without computing them.
+//!
+//! The base, exponent and modulus are split into 4 limbs of 64 bits, which
+//! only hold the `max_size` low bytes of their values: the limbs above are the
+//! constant 0, so that smaller bounds cost fewer rows.  A modular
+//! multiplication `a * b = q * m + r` is checked limb by limb, like in the
+//! RsaSignVerifyChip: the limb products of every position are summed and the
+//! carries between positions are witnessed and range checked, so that the
+//! equation holds over the integers.
+//!
+//! The exponentiation is computed by square and multiply over the
+//! `8 * max_size` bits of the exponent, from the most significant one, with
+//! the modulus replaced by 1 when it's 0, so that the result is 0 as for the
+//! precompile.  The intermediate results are only congruent to the reduced
+//! ones, and the final result is constrained to be smaller than the modulus.
+//!
+//! Every slot is a row of the table, after the all-zero first row, copied from
+//! the cells of its exponentiation.  Padding slots prove `0 ^ 0 mod 0 = 0`.
+//! The rows out of the slots have `is_enabled = 0`, as the table is made of
+//! advice columns.
+
+#[cfg(any(test, feature = "test-circuits"))]
+mod dev;
+#[cfg(test)]
+mod test;
+#[cfg(feature = "test-circuits")]
+pub use dev::ModExpCircuit as TestModExpCircuit;
+
+use crate::{
+    table::{LookupTable, ModExpTable},
+    tx_circuit::sign_verify::count_rows,
+    util::{word::Word, Challenges, Expr, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use bus_mapping::{circuit_input_builder::ModExpEvent, precompile::MODEXP_SIZE_LIMIT};
+use eth_types::{Field, ToLittleEndian};
+use halo2_proofs::{
+    circuit::{Layouter, Value},
+    plonk::{Column, ConstraintSystem, Error, Fixed},
+    poly::Rotation,
+};
+use itertools::Itertools;
+use log::error;
+use maingate::{
+    AssignedValue, MainGate, MainGateConfig, MainGateInstructions, RangeChip, RangeConfig,
+    RangeInstructions, RegionCtx, Term,
+};
+use num_bigint::{BigInt, BigUint};
+use std::{iter, marker::PhantomData};
+
+/// Number of limbs of an integer of at most 256 bits
+const NUMBER_OF_LIMBS: usize = 4;
+/// Number of bits of a limb
+const BIT_LEN_LIMB: usize = 64;
+/// Number of bits of a range checked carry between limb positions, offset by
+/// `2^71` to be non-negative.  The sum of the limb products of a position is
+/// below 2^130 in absolute value, so the carries are below 2^66.
+const BIT_LEN_CARRY: usize = 72;
+
+/// Circuit configuration arguments
+pub struct ModExpCircuitConfigArgs {
+    /// ModExpTable
+    pub modexp_table: ModExpTable,
+}
+
+/// Config for ModExpCircuit
+#[derive(Clone, Debug)]
+pub struct ModExpCircuitConfig<F: Field> {
+    /// Enables the rows of the slots of the ModExpTable
+    q_enable: Column<Fixed>,
+    modexp_table: ModExpTable,
+    main_gate_config: MainGateConfig,
+    range_config: RangeConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for ModExpCircuitConfig<F> {
+    type ConfigArgs = ModExpCircuitConfigArgs;
+
+    /// Return a new ModExpCircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs { modexp_table }: Self::ConfigArgs,
+    ) -> Self {
+        let q_enable = meta.fixed_column();
+        for column in <ModExpTable as LookupTable<F>>::advice_columns(&modexp_table) {
+            meta.enable_equality(column);
+        }
+        meta.create_gate("modexp table is_enabled", |meta| {
+            let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+            let is_enabled = meta.query_advice(modexp_table.is_enabled, Rotation::cur());
+            vec![(1.expr() - q_enable) * is_enabled]
+        });
+
+        let main_gate_config = MainGate::<F>::configure(meta);
+        let range_config = RangeChip::<F>::configure(meta, &main_gate_config, vec![8], vec![]);
+
+        Self {
+            q_enable,
+            modexp_table,
+            main_gate_config,
+            range_config,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Integer of at most 256 bits, assigned as range checked limbs whose bytes
+/// are assigned too, except for the constant limbs.
+struct AssignedInteger<F: Field> {
+    value: BigUint,
+    limbs: Vec<AssignedValue<F>>,
+    bytes_le: Vec<AssignedValue<F>>,
+}
+
+/// Cells of a row of the ModExpTable
+struct AssignedModExpRow<F: Field> {
+    is_enabled: AssignedValue<F>,
+    base: Word<AssignedValue<F>>,
+    exponent: Word<AssignedValue<F>>,
+    modulus: Word<AssignedValue<F>>,
+    output: Word<AssignedValue<F>>,
+}
+
+impl<F: Field> AssignedModExpRow<F> {
+    /// Return the cells in the order of the ModExpTable advice columns.
+    fn cells(&self) -> [AssignedValue<F>; 9] {
+        [
+            self.is_enabled.clone(),
+            self.base.lo(),
+            self.base.hi(),
+            self.exponent.lo(),
+            self.exponent.hi(),
+            self.modulus.lo(),
+            self.modulus.hi(),
+            self.output.lo(),
+            self.output.hi(),
+        ]
+    }
+}
+
+/// Return the limbs of an integer of at most 256 bits.
+fn limbs(value: &BigUint) -> [u64; NUMBER_OF_LIMBS] {
+    let mut limbs = [0; NUMBER_OF_LIMBS];
+    for (limb, digit) in limbs.iter_mut().zip_eq(
+        value
+            .to_u64_digits()
+            .into_iter()
+            .pad_using(NUMBER_OF_LIMBS, |_| 0),
+    ) {
+        *limb = digit;
+    }
+    limbs
+}
+
+/// Return the integer of a word.
+fn word_to_biguint(word: eth_types::Word) -> BigUint {
+    BigUint::from_bytes_le(&word.to_le_bytes())
+}
+
+impl<F: Field> ModExpCircuitConfig<F> {
+    /// Load the range table of the RangeChip
+    pub fn load_aux_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        let range_chip = RangeChip::<F>::new(self.range_config.clone());
+        range_chip.load_table(layouter)
+    }
+
+    /// Assign the ModExpTable, copying the rows from the assigned cells.
+    fn assign_modexp_table(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        rows: &[AssignedModExpRow<F>],
+    ) -> Result<(), Error> {
+        let table = &self.modexp_table;
+        let advice_columns = <ModExpTable as LookupTable<F>>::advice_columns(table);
+        layouter.assign_region(
+            || "modexp table",
+            |mut region| {
+                // Empty entry
+                region.assign_fixed(|| "q_enable", self.q_enable, 0, || Value::known(F::ZERO))?;
+                for &column in &advice_columns {
+                    region.assign_advice(
+                        || "modexp table empty row",
+                        column,
+                        0,
+                        || Value::known(F::ZERO),
+                    )?;
+                }
+                for (i, row) in rows.iter().enumerate() {
+                    let offset = i + 1;
+                    region.assign_fixed(
+                        || "q_enable",
+                        self.q_enable,
+                        offset,
+                        || Value::known(F::ONE),
+                    )?;
+                    for (&column, cell) in advice_columns.iter().zip_eq(row.cells()) {
+                        cell.copy_advice(|| "modexp table row", &mut region, column, offset)?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// ModExp Circuit for proving the modular exponentiations of the ModExpTable
+#[derive(Clone, Default, Debug)]
+pub struct ModExpCircuit<F: Field> {
+    /// Max number of supported modular exponentiations
+    pub max_modexps: usize,
+    /// Max number of bytes of the base, exponent and modulus, between 1 and
+    /// [`MODEXP_SIZE_LIMIT`]
+    pub max_size: usize,
+    /// Modular exponentiations to prove, one per slot
+    pub events: Vec<ModExpEvent>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> ModExpCircuit<F> {
+    /// Return a new ModExpCircuit with `max_modexps` slots for values of at
+    /// most `max_size` bytes, clamped to [1, MODEXP_SIZE_LIMIT].
+    pub fn new(max_modexps: usize, max_size: usize, events: Vec<ModExpEvent>) -> Self {
+        Self {
+            max_modexps,
+            max_size: max_size.clamp(1, MODEXP_SIZE_LIMIT),
+            events,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the minimum number of rows required to prove `max_modexps`
+    /// slots for values of at most `max_size` bytes.  Without slots, only the
+    /// empty row of the ModExpTable is assigned.
+    pub fn min_num_rows(max_modexps: usize, max_size: usize) -> usize {
+        if max_modexps == 0 {
+            return 1;
+        }
+        itertools::max([
+            Self::rows_range_chip_table(),
+            1 + max_modexps,
+            Self::rows_per_modexp(max_size) * max_modexps,
+        ])
+        .unwrap()
+    }
+
+    /// Return the number of rows of the RangeChip lookup table, which only
+    /// range checks bytes.
+    pub fn rows_range_chip_table() -> usize {
+        1 << 8
+    }
+
+    /// Return the number of rows used by one modular exponentiation of values
+    /// of at most `max_size` bytes, counted by laying out a padding slot.
+    /// Every slot goes through all the bits of the exponent, so that every
+    /// slot uses as many rows as the padding one.
+    pub fn rows_per_modexp(max_size: usize) -> usize {
+        let mut meta = ConstraintSystem::<F>::default();
+        let modexp_table = ModExpTable::construct(&mut meta);
+        let config = ModExpCircuitConfig::new(&mut meta, ModExpCircuitConfigArgs { modexp_table });
+
+        let main_gate = MainGate::new(config.main_gate_config.clone());
+        let range_chip = RangeChip::new(config.range_config.clone());
+        let (rows, _) = count_rows(|ctx| {
+            Self::new(1, max_size, vec![]).assign_modexp(
+                ctx,
+                &main_gate,
+                &range_chip,
+                &ModExpEvent::default(),
+            )
+        });
+        rows
+    }
+
+    /// Assign an integer of at most `max_size` bytes.
+    fn assign_integer(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        main_gate: &MainGate<F>,
+        range_chip: &RangeChip<F>,
+        value: &BigUint,
+    ) -> Result<AssignedInteger<F>, Error> {
+        let mut assigned_limbs = Vec::with_capacity(NUMBER_OF_LIMBS);
+        let mut bytes_le = Vec::with_capacity(self.max_size);
+        for (i, limb) in limbs(value).into_iter().enumerate() {
+            let num_bytes = self.max_size.saturating_sub(8 * i).min(8);
+            if num_bytes == 0 {
+                assigned_limbs.push(main_gate.assign_constant(ctx, F::ZERO)?);
+                continue;
+            }
+            let (limb, bytes) =
+                range_chip.decompose(ctx, Value::known(F::from(limb)), 8, 8 * num_bytes)?;
+            assigned_limbs.push(limb);
+            bytes_le.extend(bytes);
+        }
+        Ok(AssignedInteger {
+            value: value.clone(),
+            limbs: assigned_limbs,
+            bytes_le,
+        })
+    }
+
+    /// Assign the constant 1.
+    fn assign_one(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        main_gate: &MainGate<F>,
+    ) -> Result<AssignedInteger<F>, Error> {
+        Ok(AssignedInteger {
+            value: BigUint::from(1u64),
+            limbs: limbs(&BigUint::from(1u64))
+                .into_iter()
+                .map(|limb| main_gate.assign_constant(ctx, F::from(limb)))
+                .collect::<Result<_, _>>()?,
+            bytes_le: vec![],
+        })
+    }
+
+    /// Assign the sum of the limb products `a_i * b_j` with `i + j = k`, for
+    /// every position `k`.
+    fn assign_limb_products(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        main_gate: &MainGate<F>,
+        a: &AssignedInteger<F>,
+        b: &AssignedInteger<F>,
+    ) -> Result<Vec<AssignedValue<F>>, Error> {
+        (0..2 * NUMBER_OF_LIMBS - 1)
+            .map(|k| {
+                let mut pairs = (k.saturating_sub(NUMBER_OF_LIMBS - 1)
+                    ..=k.min(NUMBER_OF_LIMBS - 1))
+                    .map(|i| (&a.limbs[i], &b.limbs[k - i]));
+                let (a_i, b_j) = pairs.next().expect("every position has a product");
+                let first = main_gate.mul(ctx, a_i, b_j)?;
+                pairs.try_fold(first, |acc, (a_i, b_j)| {
+                    main_gate.mul_add(ctx, a_i, b_j, &acc)
+                })
+            })
+            .collect()
+    }
+
+    /// Return `a * b mod m`, constraining `a * b = q * m + r` over the
+    /// integers.  When `a < m`, the quotient is smaller than `b`, so that it
+    /// fits in `max_size` bytes like `r`.
+    fn mul_mod(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        main_gate: &MainGate<F>,
+        range_chip: &RangeChip<F>,
+        a: &AssignedInteger<F>,
+        b: &AssignedInteger<F>,
+        m: &AssignedInteger<F>,
+    ) -> Result<AssignedInteger<F>, Error> {
+        let product = &a.value * &b.value;
+        let q = self.assign_integer(ctx, main_gate, range_chip, &(&product / &m.value))?;
+        let r = self.assign_integer(ctx, main_gate, range_chip, &(&product % &m.value))?;
+
+        let ab = self.assign_limb_products(ctx, main_gate, a, b)?;
+        let qm = self.assign_limb_products(ctx, main_gate, &q, m)?;
+
+        // Witnesses of the sums of the products of every position, to compute the carries
+        let position_values = |x: &BigUint, y: &BigUint| {
+            let (x, y) = (limbs(x), limbs(y));
+            (0..2 * NUMBER_OF_LIMBS - 1)
+                .map(|k| {
+                    (k.saturating_sub(NUMBER_OF_LIMBS - 1)..=k.min(NUMBER_OF_LIMBS - 1))
+                        .map(|i| BigInt::from(x[i]) * BigInt::from(y[k - i]))
+                        .sum::<BigInt>()
+                })
+                .collect_vec()
+        };
+        let ab_values = position_values(&a.value, &b.value);
+        let qm_values = position_values(&q.value, &m.value);
+        let r_values = limbs(&r.value);
+
+        let pow_2_64 = F::from_u128(1 << BIT_LEN_LIMB);
+        let carry_offset = F::from_u128(1 << (BIT_LEN_CARRY - 1));
+        let mut carry_value = BigInt::from(0);
+        let mut carry: Option<AssignedValue<F>> = None;
+        for k in 0..2 * NUMBER_OF_LIMBS - 1 {
+            // ab_k - qm_k - r_k + carry_{k-1} = 2^64 * carry_k, where the carries are assigned
+            // shifted by the carry offset, and the last carry is 0.
+            let mut terms = vec![
+                Term::Assigned(&ab[k], F::ONE),
+                Term::Assigned(&qm[k], -F::ONE),
+            ];
+            let mut position_value = &ab_values[k] - &qm_values[k] + &carry_value;
+            if k < NUMBER_OF_LIMBS {
+                terms.push(Term::Assigned(&r.limbs[k], -F::ONE));
+                position_value -= BigInt::from(r_values[k]);
+            }
+            let mut constant = F::ZERO;
+            if let Some(carry) = carry.as_ref() {
+                terms.push(Term::Assigned(carry, F::ONE));
+                constant -= carry_offset;
+            }
+
+            let next_carry = if k < 2 * NUMBER_OF_LIMBS - 2 {
+                carry_value = position_value >> BIT_LEN_LIMB;
+                let shifted_carry = i128::try_from(&carry_value).expect("carry fits in 66 bits")
+                    + (1 << (BIT_LEN_CARRY - 1));
+                let (shifted_carry, _) = range_chip.decompose(
+                    ctx,
+                    Value::known(F::from_u128(shifted_carry as u128)),
+                    8,
+                    BIT_LEN_CARRY,
+                )?;
+                constant += pow_2_64 * carry_offset;
+                Some(shifted_carry)
+            } else {
+                None
+            };
+            if let Some(next_carry) = next_carry.as_ref() {
+                terms.push(Term::Assigned(next_carry, -pow_2_64));
+            }
+            let position = main_gate.compose(ctx, &terms, constant)?;
+            main_gate.assert_zero(ctx, &position)?;
+            carry = next_carry;
+        }
+
+        Ok(r)
+    }
+
+    /// Constrain `r < m` by witnessing `d = m - 1 - r` of at most `max_size`
+    /// bytes, and checking `d + r + 1 = m` over the 128-bit halves of the
+    /// integers.
+    fn assert_less_than(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        main_gate: &MainGate<F>,
+        range_chip: &RangeChip<F>,
+        r: &AssignedInteger<F>,
+        m: &AssignedInteger<F>,
+    ) -> Result<(), Error> {
+        let d_value = &m.value - 1u64 - &r.value;
+        let d = self.assign_integer(ctx, main_gate, range_chip, &d_value)?;
+
+        let pow_2_64 = F::from_u128(1 << BIT_LEN_LIMB);
+        let pow_2_128 = pow_2_64.square();
+        let half = |x: &BigUint, i: usize| (x >> (128 * i)) & BigUint::from(u128::MAX);
+        let carry_value = (half(&d.value, 0) + half(&r.value, 0) + 1u64) >> 128;
+        let carry = main_gate.assign_bit(
+            ctx,
+            Value::known(F::from(
+                u64::try_from(&carry_value).expect("carry is a bit"),
+            )),
+        )?;
+
+        // d_lo + r_lo + 1 = m_lo + 2^128 * carry and d_hi + r_hi + carry = m_hi
+        for i in 0..2 {
+            let mut terms = [&d, r]
+                .iter()
+                .flat_map(|x| {
+                    [
+                        Term::Assigned(&x.limbs[2 * i], F::ONE),
+                        Term::Assigned(&x.limbs[2 * i + 1], pow_2_64),
+                    ]
+                })
+                .collect_vec();
+            terms.extend([
+                Term::Assigned(&m.limbs[2 * i], -F::ONE),
+                Term::Assigned(&m.limbs[2 * i + 1], -pow_2_64),
+            ]);
+            let constant = if i == 0 {
+                terms.push(Term::Assigned(&carry, -pow_2_128));
+                F::ONE
+            } else {
+                terms.push(Term::Assigned(&carry, F::ONE));
+                F::ZERO
+            };
+            let sum = main_gate.compose(ctx, &terms, constant)?;
+            main_gate.assert_zero(ctx, &sum)?;
+        }
+        Ok(())
+    }
+
+    /// Return the word of the 4 limbs of an integer.
+    fn assign_word(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        main_gate: &MainGate<F>,
+        integer: &AssignedInteger<F>,
+    ) -> Result<Word<AssignedValue<F>>, Error> {
+        let pow_2_64 = F::from_u128(1 << BIT_LEN_LIMB);
+        let [lo, hi] = [0, 1].map(|i| {
+            main_gate.compose(
+                ctx,
+                &[
+                    Term::Assigned(&integer.limbs[2 * i], F::ONE),
+                    Term::Assigned(&integer.limbs[2 * i + 1], pow_2_64),
+                ],
+                F::ZERO,
+            )
+        });
+        Ok(Word::new([lo?, hi?]))
+    }
+
+    /// Prove `base ^ exponent mod modulus` of a modexp event, with the result
+    /// 0 when the modulus is 0, and return the cells of its ModExpTable row.
+    fn assign_modexp(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        main_gate: &MainGate<F>,
+        range_chip: &RangeChip<F>,
+        event: &ModExpEvent,
+    ) -> Result<AssignedModExpRow<F>, Error> {
+        let [base, exponent, modulus] = [event.base, event.exponent, event.modulus]
+            .map(|value| self.assign_integer(ctx, main_gate, range_chip, &word_to_biguint(value)));
+        let (base, exponent, modulus) = (base?, exponent?, modulus?);
+
+        // m = modulus + is_zero(modulus), where the sum of the limbs of the modulus doesn't
+        // overflow.
+        let modulus_limbs_sum = main_gate.compose(
+            ctx,
+            &modulus
+                .limbs
+                .iter()
+                .map(|limb| Term::Assigned(limb, F::ONE))
+                .collect_vec(),
+            F::ZERO,
+        )?;
+        let modulus_is_zero = main_gate.is_zero(ctx, &modulus_limbs_sum)?;
+        let mut m_limbs = modulus.limbs.clone();
+        m_limbs[0] = main_gate.add(ctx, &modulus.limbs[0], &modulus_is_zero)?;
+        let m = AssignedInteger {
+            value: if modulus.value == BigUint::from(0u64) {
+                BigUint::from(1u64)
+            } else {
+                modulus.value.clone()
+            },
+            limbs: m_limbs,
+            bytes_le: vec![],
+        };
+
+        // Square and multiply from the most significant bit of the exponent, starting from
+        // 1 mod m.
+        let one = self.assign_one(ctx, main_gate)?;
+        let mut acc = self.mul_mod(ctx, main_gate, range_chip, &one, &one, &m)?;
+        let exponent_bits = exponent
+            .bytes_le
+            .iter()
+            .map(|byte| main_gate.to_bits(ctx, byte, 8))
+            .collect::<Result<Vec<_>, _>>()?
+            .concat();
+        for (i, bit) in exponent_bits.iter().enumerate().rev() {
+            let square = self.mul_mod(ctx, main_gate, range_chip, &acc, &acc, &m)?;
+            let product = self.mul_mod(ctx, main_gate, range_chip, &square, &base, &m)?;
+            let bit_value = exponent.value.bit(i as u64);
+            acc = AssignedInteger {
+                value: if bit_value {
+                    product.value.clone()
+                } else {
+                    square.value.clone()
+                },
+                limbs: product
+                    .limbs
+                    .iter()
+                    .zip_eq(&square.limbs)
+                    .map(|(product, square)| main_gate.select(ctx, product, square, bit))
+                    .collect::<Result<_, _>>()?,
+                bytes_le: vec![],
+            };
+        }
+        self.assert_less_than(ctx, main_gate, range_chip, &acc, &m)?;
+
+        Ok(AssignedModExpRow {
+            is_enabled: main_gate.assign_constant(ctx, F::ONE)?,
+            base: self.assign_word(ctx, main_gate, &base)?,
+            exponent: self.assign_word(ctx, main_gate, &exponent)?,
+            modulus: self.assign_word(ctx, main_gate, &modulus)?,
+            output: self.assign_word(ctx, main_gate, &acc)?,
+        })
+    }
+}
+
+impl<F: Field> SubCircuit<F> for ModExpCircuit<F> {
+    type Config = ModExpCircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // No column queried at more than 3 distinct rotations, so returns 6 as
+        // minimum unusable rows.
+        6
+    }
+
+    /// The `block.circuits_params.max_modexps` parameter sets up the circuit
+    /// to support a fixed number of modular exponentiations; when 0, the
+    /// circuit only has the slots of the modexp calls.  The values are bound
+    /// by `block.circuits_params.max_modexp_size` bytes.
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        let max_modexps = match block.circuits_params.max_modexps {
+            0 => block.modexp_events.len(),
+            max_modexps => max_modexps,
+        };
+        Self::new(
+            max_modexps,
+            block.circuits_params.max_modexp_size,
+            block.modexp_events.clone(),
+        )
+    }
+
+    /// The maingate of the circuit expects an instance column, which is
+    /// empty.
+    fn instance(&self) -> Vec<Vec<F>> {
+        vec![vec![]]
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        let max_size = block.circuits_params.max_modexp_size;
+        (
+            Self::min_num_rows(block.modexp_events.len(), max_size),
+            Self::min_num_rows(block.circuits_params.max_modexps, max_size),
+        )
+    }
+
+    /// Make the assignments to the ModExpCircuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        _challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        if self.events.len() > self.max_modexps {
+            error!(
+                "events.len() = {} > max_modexps = {}",
+                self.events.len(),
+                self.max_modexps
+            );
+            return Err(Error::Synthesis);
+        }
+        if let Some(event) = self.events.iter().find(|event| {
+            [event.base, event.exponent, event.modulus]
+                .iter()
+                .any(|value| value.bits() > 8 * self.max_size)
+        }) {
+            error!(
+                "modexp event {:?} has values larger than max_size = {} bytes",
+                event, self.max_size
+            );
+            return Err(Error::Synthesis);
+        }
+
+        if self.max_modexps == 0 {
+            // Circuits without slots, such as the ones of blocks without modexp calls, don't
+            // load the range table.
+            return config.assign_modexp_table(layouter, &[]);
+        }
+
+        config.load_aux_tables(layouter)?;
+        let main_gate = MainGate::new(config.main_gate_config.clone());
+        let range_chip = RangeChip::new(config.range_config.clone());
+        let rows = layouter.assign_region(
+            || "modexp",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+                self.events
+                    .iter()
+                    .chain(iter::repeat(&ModExpEvent::default()))
+                    .take(self.max_modexps)
+                    .map(|event| self.assign_modexp(&mut ctx, &main_gate, &range_chip, event))
+                    .collect::<Result<Vec<_>, _>>()
+            },
+        )?;
+        config.assign_modexp_table(layouter, &rows)
+    }
+}
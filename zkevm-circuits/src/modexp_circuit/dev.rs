@@ -0,0 +1,40 @@
+pub use super::ModExpCircuit;
+
+use crate::{
+    modexp_circuit::{ModExpCircuitConfig, ModExpCircuitConfigArgs},
+    table::ModExpTable,
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+};
+use eth_types::Field;
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+impl<F: Field> Circuit<F> for ModExpCircuit<F> {
+    type Config = (ModExpCircuitConfig<F>, Challenges);
+    type FloorPlanner = SimpleFloorPlanner;
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.max_modexps, self.max_size, vec![])
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let modexp_table = ModExpTable::construct(meta);
+        let challenges = Challenges::construct(meta);
+        (
+            ModExpCircuitConfig::new(meta, ModExpCircuitConfigArgs { modexp_table }),
+            challenges,
+        )
+    }
+
+    fn synthesize(
+        &self,
+        (config, challenges): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let challenges = challenges.values(&mut layouter);
+        self.synthesize_sub(&config, &challenges, &mut layouter)
+    }
+}
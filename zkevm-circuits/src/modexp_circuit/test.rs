@@ -0,0 +1,83 @@
+use super::*;
+use crate::util::{log2_ceil, unusable_rows};
+use eth_types::Word;
+use halo2_proofs::{
+    dev::{MockProver, VerifyFailure},
+    halo2curves::bn256::Fr,
+};
+
+#[test]
+fn modexp_circuit_unusable_rows() {
+    assert_eq!(
+        ModExpCircuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, ModExpCircuit::<Fr>>(()),
+    )
+}
+
+fn run<F: Field>(circuit: ModExpCircuit<F>) -> Result<(), Vec<VerifyFailure>> {
+    let k = log2_ceil(
+        ModExpCircuit::<Fr>::unusable_rows()
+            + ModExpCircuit::<Fr>::min_num_rows(circuit.max_modexps, circuit.max_size),
+    );
+    // MainGate instance column
+    let prover = match MockProver::run(k, &circuit, vec![vec![]]) {
+        Ok(prover) => prover,
+        Err(e) => panic!("{:#?}", e),
+    };
+    prover.verify()
+}
+
+/// Return the modexp event of `base ^ exponent mod modulus`.
+fn modexp_event(base: u64, exponent: u64, modulus: u64) -> ModExpEvent {
+    let output = match modulus {
+        0 => 0,
+        _ => BigUint::from(base)
+            .modpow(&BigUint::from(exponent), &BigUint::from(modulus))
+            .to_u64_digits()
+            .first()
+            .copied()
+            .unwrap_or_default(),
+    };
+    ModExpEvent {
+        base: Word::from(base),
+        exponent: Word::from(exponent),
+        modulus: Word::from(modulus),
+        output: Word::from(output),
+    }
+}
+
+#[test]
+fn modexp_circuit_3events_4max_modexps() {
+    let events = vec![
+        modexp_event(3, 0x1234_5678, 0xffff_fffb),
+        // The modulus is larger than the base
+        modexp_event(u64::MAX, u64::MAX, 1 << 63),
+        // The result of a modulus 0 is 0
+        modexp_event(7, 2, 0),
+    ];
+    let circuit = ModExpCircuit::<Fr>::new(4, 8, events);
+    assert_eq!(run(circuit), Ok(()));
+}
+
+#[test]
+fn modexp_circuit_exponent_0() {
+    // 1 mod 1 = 0
+    let circuit =
+        ModExpCircuit::<Fr>::new(2, 1, vec![modexp_event(5, 0, 3), modexp_event(5, 0, 1)]);
+    assert_eq!(run(circuit), Ok(()));
+}
+
+#[test]
+fn modexp_circuit_0max_modexps() {
+    // Only the empty row of the ModExpTable is assigned.
+    let circuit = ModExpCircuit::<Fr>::new(0, MODEXP_SIZE_LIMIT, vec![]);
+    assert_eq!(run(circuit), Ok(()));
+}
+
+#[test]
+fn modexp_circuit_value_larger_than_max_size() {
+    let circuit = ModExpCircuit::<Fr>::new(1, 1, vec![modexp_event(0x100, 1, 3)]);
+    let k =
+        log2_ceil(ModExpCircuit::<Fr>::unusable_rows() + ModExpCircuit::<Fr>::min_num_rows(1, 1));
+    assert!(MockProver::run(k, &circuit, vec![vec![]]).is_err());
+}
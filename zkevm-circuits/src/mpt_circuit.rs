@@ -49,8 +49,9 @@ use crate::{
         start::StartConfig,
         storage_leaf::StorageLeafConfig,
     },
-    table::{KeccakTable, MPTProofType, MptTable},
-    util::Challenges,
+    table::{KeccakTable, MPTProofType, MptTable, SpongeChip},
+    util::{query_expression, Challenges, SubCircuit, SubCircuitConfig},
+    witness,
 };
 
 use extension_branch::ExtensionBranchConfig;
@@ -169,17 +170,18 @@ impl<F: Field> MPTContext<F> {
     }
 }
 
-/// Merkle Patricia Trie config.
+/// Merkle Patricia Trie config, looking up the hashes of the nodes in the
+/// table `S`.
 #[derive(Clone)]
-pub struct MPTConfig<F: Field> {
+pub struct MPTConfig<F: Field, S = KeccakTable> {
     pub(crate) q_enable: Column<Fixed>,
     pub(crate) q_first: Column<Fixed>,
     pub(crate) q_last: Column<Fixed>,
     pub(crate) memory: MptMemory<F>,
     /// MPT table
     pub mpt_table: MptTable,
-    /// Keccak table
-    pub keccak_table: KeccakTable,
+    /// Table of the hashes of the nodes
+    pub hash_table: S,
     fixed_table: [Column<Fixed>; 6],
     mult_table: [Column<Advice>; 2],
     rlp_item: MainRLPGadget<F>,
@@ -209,20 +211,36 @@ pub enum FixedTableTag {
 }
 impl_expr!(FixedTableTag);
 
-impl<F: Field> MPTConfig<F> {
+/// Circuit configuration arguments
+pub struct MPTCircuitConfigArgs<F: Field, S = KeccakTable> {
+    /// MptTable, whose rows are assigned by the circuit
+    pub mpt_table: MptTable,
+    /// Table of the hashes of the nodes: the KeccakTable for the Ethereum
+    /// tries
+    pub hash_table: S,
+    /// Challenges
+    pub challenges: Challenges<Expression<F>>,
+    /// Parameters of the circuit
+    pub params: MPTCircuitParams,
+}
+
+impl<F: Field, S: SpongeChip<F>> SubCircuitConfig<F> for MPTConfig<F, S> {
+    type ConfigArgs = MPTCircuitConfigArgs<F, S>;
+
     /// Configure MPT Circuit
-    pub fn new(
+    fn new(
         meta: &mut ConstraintSystem<F>,
-        challenges: Challenges<Expression<F>>,
-        keccak_table: KeccakTable,
-        params: MPTCircuitParams,
+        Self::ConfigArgs {
+            mpt_table,
+            hash_table,
+            challenges,
+            params,
+        }: Self::ConfigArgs,
     ) -> Self {
         let q_enable = meta.fixed_column();
         let q_first = meta.fixed_column();
         let q_last = meta.fixed_column();
 
-        let mpt_table = MptTable::construct(meta);
-
         let fixed_table: [Column<Fixed>; 6] = (0..6)
             .map(|_| meta.fixed_column())
             .collect::<Vec<_>>()
@@ -235,7 +253,13 @@ impl<F: Field> MPTConfig<F> {
         let mut cb = MPTConstraintBuilder::new(5, Some(challenges), None);
 
         // Load premade lookup tables
-        cb.load_table(meta, MptTableType::Keccak, &keccak_table);
+        query_expression(meta, |meta| {
+            cb.store_table(
+                S::NAME,
+                MptTableType::Keccak,
+                hash_table.sponge_exprs(meta).to_vec(),
+            )
+        });
         cb.load_table(meta, MptTableType::Byte, &[fixed_table[2]]);
         cb.load_table(meta, MptTableType::Fixed, &fixed_table);
         cb.load_table(meta, MptTableType::Mult, &mult_table);
@@ -372,7 +396,7 @@ impl<F: Field> MPTConfig<F> {
             q_first,
             q_last,
             memory,
-            keccak_table,
+            hash_table,
             fixed_table,
             mult_table,
             state_machine,
@@ -383,7 +407,9 @@ impl<F: Field> MPTConfig<F> {
             cb,
         }
     }
+}
 
+impl<F: Field, S: SpongeChip<F>> MPTConfig<F, S> {
     /// Make the assignments to the MPTCircuit
     pub fn assign(
         &self,
@@ -644,12 +670,13 @@ impl<F: Field> MPTConfig<F> {
     }
 }
 
-/// MPT Circuit for proving the storage modification is valid.
-#[derive(Default)]
-pub struct MPTCircuit<F: Field> {
+/// MPT Circuit for proving the storage modification is valid, with the hashes
+/// of the nodes in the table `S`.
+#[derive(Clone, Debug)]
+pub struct MPTCircuit<F: Field, S = KeccakTable> {
     /// MPT nodes
     pub nodes: Vec<Node>,
-    /// MPT keccak_data
+    /// MPT keccak_data, the inputs of the hashes of the nodes
     pub keccak_data: Vec<Vec<u8>>,
     /// log2(height)
     pub degree: usize,
@@ -657,7 +684,19 @@ pub struct MPTCircuit<F: Field> {
     /// preimage. ONLY ENABLE FOR TESTS!
     pub disable_preimage_check: bool,
     /// Marker
-    pub _marker: PhantomData<F>,
+    pub _marker: PhantomData<(F, S)>,
+}
+
+impl<F: Field, S> Default for MPTCircuit<F, S> {
+    fn default() -> Self {
+        Self {
+            nodes: vec![],
+            keccak_data: vec![],
+            degree: 0,
+            disable_preimage_check: false,
+            _marker: PhantomData,
+        }
+    }
 }
 
 /// MPT Circuit configuration parameters
@@ -681,8 +720,8 @@ impl MPTCircuitParams {
     }
 }
 
-impl<F: Field> Circuit<F> for MPTCircuit<F> {
-    type Config = (MPTConfig<F>, Challenges);
+impl<F: Field, S: SpongeChip<F>> Circuit<F> for MPTCircuit<F, S> {
+    type Config = (MPTConfig<F, S>, Challenges);
     type FloorPlanner = SimpleFloorPlanner;
     type Params = MPTCircuitParams;
 
@@ -700,9 +739,18 @@ impl<F: Field> Circuit<F> for MPTCircuit<F> {
     fn configure_with_params(meta: &mut ConstraintSystem<F>, params: Self::Params) -> Self::Config {
         let challenges = Challenges::construct(meta);
         let challenges_expr = challenges.exprs(meta);
-        let keccak_table = KeccakTable::construct(meta);
+        let mpt_table = MptTable::construct(meta);
+        let hash_table = S::construct(meta);
         (
-            MPTConfig::new(meta, challenges_expr, keccak_table, params),
+            MPTConfig::new(
+                meta,
+                MPTCircuitConfigArgs {
+                    mpt_table,
+                    hash_table,
+                    challenges: challenges_expr,
+                    params,
+                },
+            ),
             challenges,
         )
     }
@@ -717,13 +765,59 @@ impl<F: Field> Circuit<F> for MPTCircuit<F> {
         mut layouter: impl Layouter<F>,
     ) -> Result<(), Error> {
         let challenges = _challenges.values(&mut layouter);
-        let height = config.assign(&mut layouter, &self.nodes, &challenges)?;
-        config.load_fixed_table(&mut layouter)?;
-        config.load_mult_table(&mut layouter, &challenges, height)?;
         config
-            .keccak_table
+            .hash_table
             .dev_load(&mut layouter, &self.keccak_data, &challenges)?;
 
+        self.synthesize_sub(&config, &challenges, &mut layouter)
+    }
+}
+
+impl<F: Field, S: SpongeChip<F>> SubCircuit<F> for MPTCircuit<F, S> {
+    type Config = MPTConfig<F, S>;
+
+    fn unusable_rows() -> usize {
+        // The cells of the state machine are queried at up to 50 distinct
+        // rotations, so returns 53 unusable rows.
+        53
+    }
+
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        Self {
+            nodes: block.mpt_nodes.clone(),
+            keccak_data: vec![],
+            degree: 0,
+            disable_preimage_check: false,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        let rows = block
+            .mpt_nodes
+            .iter()
+            .map(|node| node.values.len())
+            .sum::<usize>()
+            .max(2 * HASH_WIDTH + 1);
+        (rows, rows)
+    }
+
+    /// Make the assignments to the MPTCircuit.  Without any proof, nothing is
+    /// assigned and the MptTable is left to the caller.
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        if self.nodes.is_empty() {
+            return Ok(());
+        }
+        let height = config.assign(layouter, &self.nodes, challenges)?;
+        config.load_fixed_table(layouter)?;
+        config.load_mult_table(layouter, challenges, height)?;
+
         Ok(())
     }
 }
@@ -755,9 +849,59 @@ pub fn load_proof(path: &str) -> Vec<Node> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        table::{AccountFieldTag, PoseidonTable},
+        witness::{MptUpdates, MptUpdatesError, Rw},
+    };
+    use eth_types::{Address, Word};
     use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
     use std::{fs, ops::Deref};
 
+    #[test]
+    fn mpt_updates_from_proofs() {
+        let nodes = load_proof("src/mpt_circuit/tests/BalanceModCShort.json");
+        let root =
+            |node: &Node, row: StartRowType| Word::from_big_endian(&node.values[row as usize][1..]);
+        let rows = [Rw::Account {
+            rw_counter: 1,
+            is_write: true,
+            account_address: Address::zero(),
+            field_tag: AccountFieldTag::Balance,
+            value: Word::from(2),
+            value_prev: Word::from(1),
+        }];
+
+        // The roots of the update are the ones of its proof, which is followed
+        // by a disabled one.
+        let updates = MptUpdates::new(&rows, &nodes).unwrap();
+        assert_eq!(updates.old_root(), root(&nodes[0], StartRowType::RootS));
+        assert_eq!(updates.new_root(), root(&nodes[0], StartRowType::RootC));
+        let update = updates.get(&rows[0]).unwrap();
+        assert_eq!(
+            update.root_assignments(),
+            (updates.new_root(), updates.old_root())
+        );
+
+        // The updates without a proof, or whose proof is of another type, are
+        // rejected.
+        assert_eq!(
+            MptUpdates::new(&rows, &[]).unwrap_err(),
+            MptUpdatesError::MissingProof { index: 0 }
+        );
+        let rows = [Rw::Account {
+            rw_counter: 1,
+            is_write: true,
+            account_address: Address::zero(),
+            field_tag: AccountFieldTag::Nonce,
+            value: Word::from(2),
+            value_prev: Word::from(1),
+        }];
+        assert!(matches!(
+            MptUpdates::new(&rows, &nodes),
+            Err(MptUpdatesError::ProofTypeMismatch { index: 0, .. })
+        ));
+    }
+
     #[test]
     fn test_mpt() {
         let path = "src/mpt_circuit/tests";
@@ -804,4 +948,27 @@ mod tests {
                 // prover.assert_satisfied();
             });
     }
+
+    #[test]
+    fn mpt_keccak_proof_in_poseidon_table() {
+        // The hashes of the nodes of the proof are keccak digests, which aren't
+        // in the PoseidonTable.
+        let nodes = load_proof("src/mpt_circuit/tests/BalanceModCShort.json");
+        let num_rows: usize = nodes.iter().map(|node| node.values.len()).sum();
+        let keccak_data = nodes
+            .iter()
+            .flat_map(|node| node.keccak_data.iter().map(|k| k.deref().clone()))
+            .collect();
+        let disable_preimage_check = nodes[0].start.clone().unwrap().disable_preimage_check;
+        let degree = 15;
+        let circuit = MPTCircuit::<Fr, PoseidonTable> {
+            nodes,
+            keccak_data,
+            degree,
+            disable_preimage_check,
+            _marker: PhantomData,
+        };
+        let prover = MockProver::<Fr>::run(degree as u32, &circuit, vec![]).unwrap();
+        assert!(prover.verify_at_rows(0..num_rows, 0..num_rows).is_err());
+    }
 }
@@ -6,7 +6,7 @@ use halo2_proofs::{
 };
 
 use super::{
-    helpers::{KeyDataWitness, ListKeyGadget, MainData, ParentDataWitness},
+    helpers::{AccountValueGadget, KeyDataWitness, ListKeyGadget, MainData, ParentDataWitness},
     mod_extension::ModExtensionGadget,
     rlp_gadgets::RLPItemWitness,
     witness_row::{AccountRowType, Node},
@@ -26,7 +26,7 @@ use crate::{
             IsPlaceholderLeafGadget, KeyData, MPTConstraintBuilder, ParentData, WrongGadget,
             KECCAK,
         },
-        param::{KEY_LEN_IN_NIBBLES, RLP_LIST_LONG, RLP_LONG},
+        param::KEY_LEN_IN_NIBBLES,
         MPTConfig, MPTContext, MptMemory, RlpItemType,
     },
     table::MPTProofType,
@@ -40,8 +40,7 @@ pub(crate) struct AccountLeafConfig<F> {
     key_data: [KeyData<F>; 2],
     parent_data: [ParentData<F>; 2],
     rlp_key: [ListKeyGadget<F>; 2],
-    value_rlp_bytes: [[Cell<F>; 2]; 2],
-    value_list_rlp_bytes: [[Cell<F>; 2]; 2],
+    value: [AccountValueGadget<F>; 2],
     is_placeholder_leaf: [IsPlaceholderLeafGadget<F>; 2],
     drifted: DriftedGadget<F>,
     wrong: WrongGadget<F>,
@@ -68,8 +67,6 @@ impl<F: Field> AccountLeafConfig<F> {
                 ctx.rlp_item(meta, cb, AccountRowType::KeyS as usize, RlpItemType::Key),
                 ctx.rlp_item(meta, cb, AccountRowType::KeyC as usize, RlpItemType::Key),
             ];
-            config.value_rlp_bytes = [cb.query_bytes(), cb.query_bytes()];
-            config.value_list_rlp_bytes = [cb.query_bytes(), cb.query_bytes()];
             let nonce_items = [
                 ctx.rlp_item(
                     meta,
@@ -174,46 +171,23 @@ impl<F: Field> AccountLeafConfig<F> {
                     let rlp_key = &mut config.rlp_key[is_s.idx()];
                     *rlp_key = ListKeyGadget::construct(cb, &key_items[is_s.idx()]);
 
-                    let nonce_rlp_rlc;
-                    let balance_rlp_rlc;
-                    let storage_rlp_rlc;
-                    let codehash_rlp_rlc;
-                    (nonce[is_s.idx()], nonce_rlp_rlc) = (
-                        nonce_items[is_s.idx()].word(),
-                        nonce_items[is_s.idx()].rlc_chain_data(),
-                    );
-                    (balance[is_s.idx()], balance_rlp_rlc) = (
-                        balance_items[is_s.idx()].word(),
-                        balance_items[is_s.idx()].rlc_chain_data(),
-                    );
-                    (storage[is_s.idx()], storage_rlp_rlc) = (
-                        storage_items[is_s.idx()].word(),
-                        storage_items[is_s.idx()].rlc_chain_data(),
-                    );
-                    (codehash[is_s.idx()], codehash_rlp_rlc) = (
-                        codehash_items[is_s.idx()].word(),
-                        codehash_items[is_s.idx()].rlc_chain_data(),
+                    // Decode the account fields from the RLP encoded value
+                    let value = &mut config.value[is_s.idx()];
+                    *value = AccountValueGadget::construct(
+                        cb,
+                        &nonce_items[is_s.idx()],
+                        &balance_items[is_s.idx()],
+                        &storage_items[is_s.idx()],
+                        &codehash_items[is_s.idx()],
                     );
+                    nonce[is_s.idx()] = value.nonce.word();
+                    balance[is_s.idx()] = value.balance.word();
+                    storage[is_s.idx()] = value.storage.word();
+                    codehash[is_s.idx()] = value.codehash.word();
 
                     // Calculate the leaf RLC
-                    let keccak_r = &cb.keccak_r;
-                    let value_rlp_bytes = config.value_rlp_bytes[is_s.idx()].to_expr_vec();
-                    let value_list_rlp_bytes = config.value_list_rlp_bytes[is_s.idx()].to_expr_vec();
-                    leaf_no_key_rlc[is_s.idx()] = value_rlp_bytes
-                        .rlc_rev(keccak_r)
-                        .rlc_chain_rev((
-                            value_list_rlp_bytes.rlc_rev(keccak_r),
-                            pow::expr(keccak_r.expr(), 2),
-                        ))
-                        .rlc_chain_rev(nonce_rlp_rlc.clone())
-                        .rlc_chain_rev(balance_rlp_rlc.clone())
-                        .rlc_chain_rev(storage_rlp_rlc.clone())
-                        .rlc_chain_rev(codehash_rlp_rlc.clone());
-                    leaf_no_key_rlc_mult[is_s.idx()] = pow::expr(keccak_r.expr(), 4)
-                        * nonce_rlp_rlc.1
-                        * balance_rlp_rlc.1
-                        * storage_rlp_rlc.1
-                        * codehash_rlp_rlc.1;
+                    (leaf_no_key_rlc[is_s.idx()], leaf_no_key_rlc_mult[is_s.idx()]) =
+                        value.rlc_chain_data(&cb.keccak_r);
                     let leaf_rlc = rlp_key.rlc2(&cb.keccak_r).rlc_chain_rev((
                         leaf_no_key_rlc[is_s.idx()].expr(),
                         leaf_no_key_rlc_mult[is_s.idx()].expr(),
@@ -240,19 +214,7 @@ impl<F: Field> AccountLeafConfig<F> {
                         require!((1.expr(), leaf_rlc, rlp_key.rlp_list.num_bytes(), hash.lo(), hash.hi()) =>> @KECCAK);
                     }}
 
-                    // Check the RLP encoding consistency.
-                    // RLP encoding: account = [key, "[nonce, balance, storage, codehash]"]
-                    // We always store between 55 and 256 bytes of data in the values list.
-                    require!(value_rlp_bytes[0] => RLP_LONG + 1);
-                    // The RLP encoded list always has 2 RLP bytes.
-                    require!(value_rlp_bytes[1] => value_list_rlp_bytes[1].expr() + 2.expr());
-                    // The first RLP byte of the list is always RLP_LIST_LONG + 1.
-                    require!(value_list_rlp_bytes[0] => RLP_LIST_LONG + 1);
-                    // The length of the list is `#(nonce) + #(balance) + 2 * (1 + #(hash))`.
-                    require!(value_list_rlp_bytes[1] => nonce_items[is_s.idx()].num_bytes() + balance_items[is_s.idx()].num_bytes() + (2 * (1 + 32)).expr());
-                    // Now check that the the key and value list length matches the account length.
-                    // The RLP encoded string always has 2 RLP bytes.
-                    value_list_num_bytes[is_s.idx()] = value_rlp_bytes[1].expr() + 2.expr();
+                    value_list_num_bytes[is_s.idx()] = value.num_bytes();
 
                     // Account length needs to equal all key bytes and all values list bytes.
                     require!(config.rlp_key[is_s.idx()].rlp_list.len() => config.rlp_key[is_s.idx()].key_value.num_bytes() + value_list_num_bytes[is_s.idx()].expr());
@@ -406,7 +368,7 @@ impl<F: Field> AccountLeafConfig<F> {
                 config.is_balance_mod => (MPTProofType::BalanceChanged.expr(), balance[true.idx()].lo(), balance[true.idx()].hi(), balance[false.idx()].lo(), balance[false.idx()].hi()),
                 config.is_storage_mod => (MPTProofType::StorageChanged.expr(), storage[true.idx()].lo(), storage[true.idx()].hi(), storage[false.idx()].lo(), storage[false.idx()].hi()),
                 config.is_codehash_mod => (MPTProofType::CodeHashChanged.expr(), codehash[true.idx()].lo(), codehash[true.idx()].hi(), codehash[false.idx()].lo(), codehash[false.idx()].hi()),
-                config.is_account_delete_mod => (MPTProofType::AccountDestructed.expr(), 0.expr(), 0.expr(), 0.expr(), 0.expr()),
+                config.is_account_delete_mod => (MPTProofType::AccountDestructed.expr(), codehash[true.idx()].lo(), codehash[true.idx()].hi(), 0.expr(), 0.expr()),
                 config.is_non_existing_account_proof => (MPTProofType::AccountDoesNotExist.expr(), 0.expr(), 0.expr(), 0.expr(), 0.expr()),
                 _ => (MPTProofType::Disabled.expr(), 0.expr(), 0.expr(), 0.expr(), 0.expr()),
             )};
@@ -515,20 +477,6 @@ impl<F: Field> AccountLeafConfig<F> {
                 account.is_mod_extension[is_s.idx()].scalar(),
             )?;
 
-            for (cell, byte) in self.value_rlp_bytes[is_s.idx()]
-                .iter()
-                .zip(account.value_rlp_bytes[is_s.idx()].iter())
-            {
-                cell.assign(region, offset, byte.scalar())?;
-            }
-
-            for (cell, byte) in self.value_list_rlp_bytes[is_s.idx()]
-                .iter()
-                .zip(account.value_list_rlp_bytes[is_s.idx()].iter())
-            {
-                cell.assign(region, offset, byte.scalar())?;
-            }
-
             key_data[is_s.idx()] = self.key_data[is_s.idx()].witness_load(
                 region,
                 offset,
@@ -556,10 +504,22 @@ impl<F: Field> AccountLeafConfig<F> {
                 &key_items[is_s.idx()],
             )?;
 
-            nonce[is_s.idx()] = nonce_items[is_s.idx()].word();
-            balance[is_s.idx()] = balance_items[is_s.idx()].word();
-            storage[is_s.idx()] = storage_items[is_s.idx()].word();
-            codehash[is_s.idx()] = codehash_items[is_s.idx()].word();
+            let value_witness = self.value[is_s.idx()].assign(
+                region,
+                offset,
+                &account.value_rlp_bytes[is_s.idx()],
+                &account.value_list_rlp_bytes[is_s.idx()],
+                [
+                    &nonce_items[is_s.idx()],
+                    &balance_items[is_s.idx()],
+                    &storage_items[is_s.idx()],
+                    &codehash_items[is_s.idx()],
+                ],
+            )?;
+            nonce[is_s.idx()] = value_witness.nonce;
+            balance[is_s.idx()] = value_witness.balance;
+            storage[is_s.idx()] = value_witness.storage;
+            codehash[is_s.idx()] = value_witness.codehash;
 
             // Key
             (key_rlc[is_s.idx()], _) = rlp_key_witness.key.key(
@@ -678,9 +638,14 @@ impl<F: Field> AccountLeafConfig<F> {
         } else if is_codehash_mod {
             (MPTProofType::CodeHashChanged, codehash)
         } else if is_account_delete_mod {
+            // The code hash of the removed account is the old value, so that
+            // the state circuit can look up the destruction of the account.
             (
                 MPTProofType::AccountDestructed,
-                vec![Word::<F>::new([0.scalar(), 0.scalar()]); 2],
+                vec![
+                    codehash[true.idx()],
+                    Word::<F>::new([0.scalar(), 0.scalar()]),
+                ],
             )
         } else if is_non_existing_proof {
             (
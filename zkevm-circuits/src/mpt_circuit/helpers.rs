@@ -10,14 +10,15 @@ use crate::{
         memory::MemoryBank,
     },
     evm_circuit::{
-        param::{N_BYTES_HALF_WORD, N_BYTES_WORD},
+        param::{N_BYTES_HALF_WORD, N_BYTES_U64, N_BYTES_WORD},
         util::from_bytes,
     },
     matchw,
     mpt_circuit::{
         param::{
             ADDRESS_WIDTH, EMPTY_TRIE_HASH, HASH_WIDTH, KEY_LEN_IN_NIBBLES, KEY_PREFIX_EVEN,
-            KEY_TERMINAL_PREFIX_EVEN, RLP_UNIT_NUM_BYTES, RLP_UNIT_NUM_VALUE_BYTES,
+            KEY_TERMINAL_PREFIX_EVEN, RLP_LIST_LONG, RLP_LONG, RLP_UNIT_NUM_BYTES,
+            RLP_UNIT_NUM_VALUE_BYTES,
         },
         rlp_gadgets::{get_ext_odd_nibble, get_terminal_odd_nibble},
     },
@@ -390,6 +391,131 @@ impl ListKeyWitness {
     }
 }
 
+/// Decodes the RLP encoded value of an account leaf,
+/// `"[nonce, balance, storage, codehash]"`, into the account fields.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct AccountValueGadget<F> {
+    pub(crate) value_rlp_bytes: [Cell<F>; 2],
+    pub(crate) value_list_rlp_bytes: [Cell<F>; 2],
+    pub(crate) nonce: RLPItemView<F>,
+    pub(crate) balance: RLPItemView<F>,
+    pub(crate) storage: RLPItemView<F>,
+    pub(crate) codehash: RLPItemView<F>,
+    nonce_in_range: LtGadget<F, 1>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct AccountValueWitness<F> {
+    pub(crate) nonce: Word<F>,
+    pub(crate) balance: Word<F>,
+    pub(crate) storage: Word<F>,
+    pub(crate) codehash: Word<F>,
+}
+
+impl<F: Field> AccountValueGadget<F> {
+    pub(crate) fn construct(
+        cb: &mut MPTConstraintBuilder<F>,
+        nonce: &RLPItemView<F>,
+        balance: &RLPItemView<F>,
+        storage: &RLPItemView<F>,
+        codehash: &RLPItemView<F>,
+    ) -> Self {
+        circuit!([meta, cb], {
+            let value_rlp_bytes = cb.query_bytes();
+            let value_list_rlp_bytes = cb.query_bytes();
+
+            // RLP encoding: account = [key, "[nonce, balance, storage, codehash]"]
+            // We always store between 55 and 256 bytes of data in the values list.
+            require!(value_rlp_bytes[0] => RLP_LONG + 1);
+            // The RLP encoded list always has 2 RLP bytes.
+            require!(value_rlp_bytes[1] => value_list_rlp_bytes[1].expr() + 2.expr());
+            // The first RLP byte of the list is always RLP_LIST_LONG + 1.
+            require!(value_list_rlp_bytes[0] => RLP_LIST_LONG + 1);
+            // The length of the list is `#(nonce) + #(balance) + 2 * (1 + #(hash))`.
+            require!(value_list_rlp_bytes[1] => nonce.num_bytes() + balance.num_bytes() + (2 * (1 + HASH_WIDTH)).expr());
+            // The nonce is a u64, the balance is bounded by the length of a value.
+            let nonce_in_range =
+                LtGadget::construct(&mut cb.base, nonce.len(), (N_BYTES_U64 + 1).expr());
+            require!(nonce_in_range.expr() => true);
+
+            AccountValueGadget {
+                value_rlp_bytes,
+                value_list_rlp_bytes,
+                nonce: nonce.clone(),
+                balance: balance.clone(),
+                storage: storage.clone(),
+                codehash: codehash.clone(),
+                nonce_in_range,
+            }
+        })
+    }
+
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        value_rlp_bytes: &[u8],
+        value_list_rlp_bytes: &[u8],
+        items: [&RLPItemWitness; 4],
+    ) -> Result<AccountValueWitness<F>, Error> {
+        for (cell, byte) in self.value_rlp_bytes.iter().zip(value_rlp_bytes.iter()) {
+            cell.assign(region, offset, byte.scalar())?;
+        }
+        for (cell, byte) in self
+            .value_list_rlp_bytes
+            .iter()
+            .zip(value_list_rlp_bytes.iter())
+        {
+            cell.assign(region, offset, byte.scalar())?;
+        }
+        let [nonce, balance, storage, codehash] = items;
+        self.nonce_in_range.assign(
+            region,
+            offset,
+            nonce.len().scalar(),
+            (N_BYTES_U64 + 1).scalar(),
+        )?;
+
+        Ok(AccountValueWitness {
+            nonce: nonce.word(),
+            balance: balance.word(),
+            storage: storage.word(),
+            codehash: codehash.word(),
+        })
+    }
+
+    /// Number of bytes of the RLP encoded value (including the RLP bytes)
+    pub(crate) fn num_bytes(&self) -> Expression<F> {
+        self.value_rlp_bytes[1].expr() + 2.expr()
+    }
+
+    /// RLC and multiplier of the RLP encoded value, to be chained after the
+    /// key of the leaf
+    pub(crate) fn rlc_chain_data(&self, r: &Expression<F>) -> (Expression<F>, Expression<F>) {
+        let value_rlp_bytes = self
+            .value_rlp_bytes
+            .iter()
+            .map(|c| c.expr())
+            .collect::<Vec<_>>();
+        let value_list_rlp_bytes = self
+            .value_list_rlp_bytes
+            .iter()
+            .map(|c| c.expr())
+            .collect::<Vec<_>>();
+        let items = [&self.nonce, &self.balance, &self.storage, &self.codehash];
+        let rlc = items.iter().fold(
+            value_rlp_bytes
+                .rlc_rev(r)
+                .rlc_chain_rev((value_list_rlp_bytes.rlc_rev(r), pow::expr(r.expr(), 2))),
+            |rlc, item| rlc.rlc_chain_rev(item.rlc_chain_data()),
+        );
+        let mult = items
+            .iter()
+            .fold(pow::expr(r.expr(), 4), |mult, item| mult * item.mult());
+        (rlc, mult)
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 pub(crate) struct KeyData<F> {
     pub(crate) rlc: Cell<F>,
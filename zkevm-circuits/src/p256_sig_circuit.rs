@@ -0,0 +1,410 @@
+//! P-256 signature circuit.
+//!
+//! The circuit verifies a list of secp256r1 signatures with the
+//! [`SignVerifyChip`] and lays out every verification in the
+//! [`P256SigTable`], so that the EVM circuit can look up the P256VERIFY calls
+//! (RIP-7212) without verifying their signatures.
+//!
+//! Every signature slot is a row of the table, after the all-zero first row.
+//! The row of a verified signature holds its message hash, (r, s) and the
+//! coordinates of the public key, copied from the cells of the
+//! SignVerifyChip, with `is_valid = 1`.  The chip verifies the message hash
+//! reduced modulo the order n of the curve, while the row holds the hash of the
+//! call, which is constrained to fit in 256 bits and to be the reduced hash
+//! plus 0 or n.  Padding slots verify the padding signature and their rows are
+//! zero but for `is_enabled`, which is a true claim: (0, 0) isn't a point of
+//! the curve.  Invalid signatures can't be proven yet, so the only rows with
+//! `is_valid = 0` are the padding ones, and the circuit rejects the
+//! verifications of invalid signatures.  The rows out of the slots have
+//! `is_enabled = 0`, as the table is made of advice columns.
+//!
+//! The SignVerifyChip constrains r and s to be in [1, n) and the coordinates of
+//! the public key to be in the base field, and doesn't constrain s <= n/2, as
+//! P256VERIFY accepts malleable signatures.  It also looks up the keccak hash
+//! of every public key, like for the secp256k1 signatures.
+
+#[cfg(any(test, feature = "test-circuits"))]
+mod dev;
+#[cfg(test)]
+mod test;
+#[cfg(feature = "test-circuits")]
+pub use dev::P256SigCircuit as TestP256SigCircuit;
+
+use crate::{
+    table::{KeccakTable, LookupTable, P256SigTable},
+    tx_circuit::sign_verify::{
+        AssignedSignature, AssignedSignatureVerify, SignVerifyChip, SignVerifyConfig,
+    },
+    util::{word::Word, Challenges, Expr, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use bus_mapping::circuit_input_builder::{keccak_inputs_sign_verify, P256VerifyEvent};
+use eth_types::{sign_types::SignData, Field};
+use halo2_proofs::{
+    circuit::{Layouter, Value},
+    halo2curves::{
+        ff::{Field as _, PrimeField},
+        secp256r1::{Fq, Secp256r1Affine},
+    },
+    plonk::{Column, ConstraintSystem, Error, Expression, Fixed},
+    poly::Rotation,
+};
+use itertools::Itertools;
+use log::error;
+use maingate::{
+    AssignedValue, MainGate, MainGateInstructions, RangeChip, RangeInstructions, RegionCtx, Term,
+};
+use std::marker::PhantomData;
+
+/// Circuit configuration arguments
+pub struct P256SigCircuitConfigArgs<F: Field> {
+    /// P256SigTable
+    pub p256_sig_table: P256SigTable,
+    /// KeccakTable
+    pub keccak_table: KeccakTable,
+    /// Challenges
+    pub challenges: Challenges<Expression<F>>,
+}
+
+/// Config for P256SigCircuit
+#[derive(Clone, Debug)]
+pub struct P256SigCircuitConfig<F: Field> {
+    /// Enables the rows of the slots of the P256SigTable
+    q_enable: Column<Fixed>,
+    p256_sig_table: P256SigTable,
+    /// SignVerifyChip config of the signatures of the table
+    sign_verify: SignVerifyConfig<Secp256r1Affine>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for P256SigCircuitConfig<F> {
+    type ConfigArgs = P256SigCircuitConfigArgs<F>;
+
+    /// Return a new P256SigCircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            p256_sig_table,
+            keccak_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let q_enable = meta.fixed_column();
+        for column in <P256SigTable as LookupTable<F>>::advice_columns(&p256_sig_table) {
+            meta.enable_equality(column);
+        }
+        meta.create_gate("p256 sig table is_enabled", |meta| {
+            let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+            let is_enabled = meta.query_advice(p256_sig_table.is_enabled, Rotation::cur());
+            vec![(1.expr() - q_enable) * is_enabled]
+        });
+        let sign_verify = SignVerifyConfig::new(meta, keccak_table, challenges, 1);
+
+        Self {
+            q_enable,
+            p256_sig_table,
+            sign_verify,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Cells of a row of the P256SigTable, masked to zero on padding slots but for
+/// `is_enabled`
+struct AssignedP256SigTableRow<F: Field> {
+    is_enabled: AssignedValue<F>,
+    msg_hash: Word<AssignedValue<F>>,
+    sig_r: Word<AssignedValue<F>>,
+    sig_s: Word<AssignedValue<F>>,
+    pk_x: Word<AssignedValue<F>>,
+    pk_y: Word<AssignedValue<F>>,
+    is_valid: AssignedValue<F>,
+}
+
+impl<F: Field> AssignedP256SigTableRow<F> {
+    /// Return the cells in the order of the P256SigTable advice columns.
+    fn cells(&self) -> [AssignedValue<F>; 12] {
+        [
+            self.is_enabled.clone(),
+            self.msg_hash.lo(),
+            self.msg_hash.hi(),
+            self.sig_r.lo(),
+            self.sig_r.hi(),
+            self.sig_s.lo(),
+            self.sig_s.hi(),
+            self.pk_x.lo(),
+            self.pk_x.hi(),
+            self.pk_y.lo(),
+            self.pk_y.hi(),
+            self.is_valid.clone(),
+        ]
+    }
+}
+
+/// Return the order n of secp256r1.
+fn order() -> eth_types::Word {
+    eth_types::Word::from_little_endian((-Fq::ONE).to_repr().as_ref()) + 1
+}
+
+impl<F: Field> P256SigCircuitConfig<F> {
+    /// Load the range table of the SignVerifyChip
+    pub fn load_aux_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.sign_verify.load_range(layouter)
+    }
+
+    /// Return the cells of the message hash of a call, `msg_hash + wraps * n`,
+    /// where `msg_hash` are the cells of the hash verified by the
+    /// SignVerifyChip, reduced modulo n, and `wraps` is a bit.  The halves of
+    /// the hash are range checked, so that it fits in 256 bits.  The hash of a
+    /// padding slot, `None`, is the reduced one.
+    fn assign_call_msg_hash(
+        ctx: &mut RegionCtx<F>,
+        main_gate: &MainGate<F>,
+        range_chip: &RangeChip<F>,
+        msg_hash: &Word<AssignedValue<F>>,
+        call_msg_hash: Option<eth_types::Word>,
+    ) -> Result<Word<AssignedValue<F>>, Error> {
+        let n = order();
+        let (n_lo, n_hi) = (n.low_u128(), (n >> 128).low_u128());
+        // The carry of the addition of the low halves
+        let (wraps, carry) = match call_msg_hash {
+            Some(hash) if hash >= n => (true, (hash - n).low_u128().checked_add(n_lo).is_none()),
+            _ => (false, false),
+        };
+        let wraps = main_gate.assign_bit(ctx, Value::known(F::from(wraps as u64)))?;
+        let carry = main_gate.assign_bit(ctx, Value::known(F::from(carry as u64)))?;
+        let pow_2_128 = F::from_u128(1 << 64).square();
+        // hash_lo = msg_hash_lo + wraps * n_lo - carry * 2^128
+        let hash_lo = main_gate.compose(
+            ctx,
+            &[
+                Term::Assigned(&msg_hash.lo(), F::ONE),
+                Term::Assigned(&wraps, F::from_u128(n_lo)),
+                Term::Assigned(&carry, -pow_2_128),
+            ],
+            F::ZERO,
+        )?;
+        // hash_hi = msg_hash_hi + wraps * n_hi + carry
+        let hash_hi = main_gate.compose(
+            ctx,
+            &[
+                Term::Assigned(&msg_hash.hi(), F::ONE),
+                Term::Assigned(&wraps, F::from_u128(n_hi)),
+                Term::Assigned(&carry, F::ONE),
+            ],
+            F::ZERO,
+        )?;
+        for half in [&hash_lo, &hash_hi] {
+            let (range_checked, _) = range_chip.decompose(ctx, half.value().copied(), 8, 128)?;
+            main_gate.assert_equal(ctx, half, &range_checked)?;
+        }
+        Ok(Word::new([hash_lo, hash_hi]))
+    }
+
+    /// Return the rows of the P256SigTable of the assigned verifications of
+    /// `events`: the values of the verifications multiplied by
+    /// `is_valid = 1 - is_padding`.
+    fn assign_masked_rows(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        events: &[P256VerifyEvent],
+        assigned_sig_verifs: &[(AssignedSignatureVerify<F>, AssignedSignature<F>)],
+    ) -> Result<Vec<AssignedP256SigTableRow<F>>, Error> {
+        let main_gate = MainGate::new(self.sign_verify.main_gate_config());
+        let range_chip = RangeChip::new(self.sign_verify.range_config());
+        layouter.assign_region(
+            || "p256 sig table masks",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+                assigned_sig_verifs
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (assigned_sig_verif, assigned_signature))| {
+                        let msg_hash = Self::assign_call_msg_hash(
+                            &mut ctx,
+                            &main_gate,
+                            &range_chip,
+                            &assigned_sig_verif.msg_hash,
+                            events.get(i).map(|event| event.msg_hash),
+                        )?;
+                        let is_valid = main_gate.not(&mut ctx, &assigned_sig_verif.is_padding)?;
+                        let mut mask_word = |word: &Word<AssignedValue<F>>| {
+                            Ok::<_, Error>(Word::new([
+                                main_gate.mul(&mut ctx, &word.lo(), &is_valid)?,
+                                main_gate.mul(&mut ctx, &word.hi(), &is_valid)?,
+                            ]))
+                        };
+                        Ok(AssignedP256SigTableRow {
+                            msg_hash: mask_word(&msg_hash)?,
+                            sig_r: mask_word(&assigned_signature.r)?,
+                            sig_s: mask_word(&assigned_signature.s)?,
+                            pk_x: mask_word(&assigned_signature.pk_x)?,
+                            pk_y: mask_word(&assigned_signature.pk_y)?,
+                            is_enabled: main_gate.assign_constant(&mut ctx, F::ONE)?,
+                            is_valid,
+                        })
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// Assign the P256SigTable, copying the rows from the assigned cells.
+    fn assign_p256_sig_table(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        rows: &[AssignedP256SigTableRow<F>],
+    ) -> Result<(), Error> {
+        let table = &self.p256_sig_table;
+        let advice_columns = <P256SigTable as LookupTable<F>>::advice_columns(table);
+        layouter.assign_region(
+            || "p256 sig table",
+            |mut region| {
+                // Empty entry
+                region.assign_fixed(|| "q_enable", self.q_enable, 0, || Value::known(F::ZERO))?;
+                for &column in &advice_columns {
+                    region.assign_advice(
+                        || "p256 sig table empty row",
+                        column,
+                        0,
+                        || Value::known(F::ZERO),
+                    )?;
+                }
+                for (i, row) in rows.iter().enumerate() {
+                    let offset = i + 1;
+                    region.assign_fixed(
+                        || "q_enable",
+                        self.q_enable,
+                        offset,
+                        || Value::known(F::ONE),
+                    )?;
+                    for (&column, cell) in advice_columns.iter().zip_eq(row.cells()) {
+                        cell.copy_advice(|| "p256 sig table row", &mut region, column, offset)?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// P-256 Signature Circuit for verifying the secp256r1 signatures of the
+/// P256SigTable
+#[derive(Clone, Default, Debug)]
+pub struct P256SigCircuit<F: Field> {
+    /// Max number of supported signatures
+    pub max_verif: usize,
+    /// Signature verifications of the P256VERIFY calls, one per slot
+    pub events: Vec<P256VerifyEvent>,
+    /// SignVerify chip
+    pub sign_verify: SignVerifyChip<F, Secp256r1Affine>,
+}
+
+impl<F: Field> P256SigCircuit<F> {
+    /// Return a new P256SigCircuit with `max_verif` signature slots.
+    pub fn new(max_verif: usize, events: Vec<P256VerifyEvent>) -> Self {
+        Self {
+            max_verif,
+            events,
+            sign_verify: SignVerifyChip::new(max_verif),
+        }
+    }
+
+    /// Return the minimum number of rows required to prove `max_verif`
+    /// signature slots.  Without slots, only the empty row of the
+    /// P256SigTable is assigned.
+    pub fn min_num_rows(max_verif: usize) -> usize {
+        if max_verif == 0 {
+            return 1;
+        }
+        std::cmp::max(
+            1 + max_verif,
+            SignVerifyChip::<F, Secp256r1Affine>::min_num_rows(max_verif, 1),
+        )
+    }
+
+    /// Return the signatures of the verifications, which are `None` for the
+    /// invalid ones.
+    fn signatures(&self) -> Vec<Option<SignData<Secp256r1Affine>>> {
+        self.events.iter().map(P256VerifyEvent::sign_data).collect()
+    }
+
+    /// Return the keccak inputs of the circuit: the public keys of the
+    /// signatures and of the padding signature.
+    pub fn keccak_inputs(&self) -> Vec<Vec<u8>> {
+        keccak_inputs_sign_verify(&self.signatures().into_iter().flatten().collect_vec())
+    }
+}
+
+impl<F: Field> SubCircuit<F> for P256SigCircuit<F> {
+    type Config = P256SigCircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // No column queried at more than 3 distinct rotations, so returns 6 as
+        // minimum unusable rows.
+        6
+    }
+
+    /// The `block.circuits_params.max_p256_verifs` parameter sets up the
+    /// circuit to support a fixed number of signatures; when 0, the circuit
+    /// only has the slots of the signatures of the P256VERIFY calls.
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        let max_verif = match block.circuits_params.max_p256_verifs {
+            0 => block.p256_verify_events.len(),
+            max_verif => max_verif,
+        };
+        Self::new(max_verif, block.p256_verify_events.clone())
+    }
+
+    /// The maingate of the SignVerifyChip expects an instance column, which
+    /// is empty.
+    fn instance(&self) -> Vec<Vec<F>> {
+        vec![vec![]]
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        (
+            Self::min_num_rows(block.p256_verify_events.len()),
+            Self::min_num_rows(block.circuits_params.max_p256_verifs),
+        )
+    }
+
+    /// Make the assignments to the P256SigCircuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        if self.events.len() > self.max_verif {
+            error!(
+                "events.len() = {} > max_verif = {}",
+                self.events.len(),
+                self.max_verif
+            );
+            return Err(Error::Synthesis);
+        }
+        let signatures = self.signatures();
+        if signatures.iter().any(Option::is_none) {
+            error!("the P256SigCircuit can't prove the verification of an invalid signature");
+            return Err(Error::Synthesis);
+        }
+
+        if self.max_verif == 0 {
+            // Circuits without signature slots, such as the ones of blocks without P256VERIFY
+            // calls, don't load the range table of the SignVerifyChip.
+            return config.assign_p256_sig_table(layouter, &[]);
+        }
+
+        config.load_aux_tables(layouter)?;
+        let assigned_sig_verifs = self.sign_verify.assign_with_signatures(
+            &config.sign_verify,
+            layouter,
+            &signatures,
+            challenges,
+        )?;
+        let rows = config.assign_masked_rows(layouter, &self.events, &assigned_sig_verifs)?;
+        config.assign_p256_sig_table(layouter, &rows)
+    }
+}
@@ -0,0 +1,53 @@
+pub use super::P256SigCircuit;
+
+use crate::{
+    p256_sig_circuit::{P256SigCircuitConfig, P256SigCircuitConfigArgs},
+    table::{KeccakTable, P256SigTable},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+};
+use eth_types::Field;
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+impl<F: Field> Circuit<F> for P256SigCircuit<F> {
+    type Config = (P256SigCircuitConfig<F>, Challenges, KeccakTable);
+    type FloorPlanner = SimpleFloorPlanner;
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.max_verif, vec![])
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let p256_sig_table = P256SigTable::construct(meta);
+        let keccak_table = KeccakTable::construct(meta);
+        let challenges = Challenges::construct(meta);
+
+        let config = {
+            let challenges = challenges.exprs(meta);
+            P256SigCircuitConfig::new(
+                meta,
+                P256SigCircuitConfigArgs {
+                    p256_sig_table,
+                    keccak_table: keccak_table.clone(),
+                    challenges,
+                },
+            )
+        };
+
+        (config, challenges, keccak_table)
+    }
+
+    fn synthesize(
+        &self,
+        (config, challenges, keccak_table): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let challenges = challenges.values(&mut layouter);
+
+        keccak_table.dev_load(&mut layouter, &self.keccak_inputs(), &challenges)?;
+        self.synthesize_sub(&config, &challenges, &mut layouter)
+    }
+}
@@ -0,0 +1,99 @@
+use super::*;
+use crate::util::{log2_ceil, unusable_rows};
+use eth_types::sign_types::sign_secp256r1;
+use halo2_proofs::{
+    dev::{MockProver, VerifyFailure},
+    halo2curves::{bn256::Fr, group::Curve, CurveAffine},
+};
+use rand::SeedableRng;
+use rand_xorshift::XorShiftRng;
+
+#[test]
+fn p256_sig_circuit_unusable_rows() {
+    assert_eq!(
+        P256SigCircuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, P256SigCircuit::<Fr>>(()),
+    )
+}
+
+fn run<F: Field>(circuit: P256SigCircuit<F>) -> Result<(), Vec<VerifyFailure>> {
+    let k = log2_ceil(
+        P256SigCircuit::<Fr>::unusable_rows()
+            + P256SigCircuit::<Fr>::min_num_rows(circuit.max_verif),
+    );
+    // SignVerifyChip -> ECDSAChip -> MainGate instance column
+    let prover = match MockProver::run(k, &circuit, vec![vec![]]) {
+        Ok(prover) => prover,
+        Err(e) => panic!("{:#?}", e),
+    };
+    prover.verify()
+}
+
+/// Return the P256VERIFY event of the signature of `msg_hash` by a random
+/// key.
+fn sign_event(rng: &mut XorShiftRng, msg_hash: Fq) -> P256VerifyEvent {
+    let sk = Fq::random(&mut *rng);
+    let pk = (Secp256r1Affine::generator() * sk).to_affine();
+    let randomness = Fq::random(&mut *rng);
+    let (sig_r, sig_s) = sign_secp256r1(randomness, sk, msg_hash);
+    let pk = pk.coordinates().unwrap();
+    let word = |repr: &[u8]| eth_types::Word::from_little_endian(repr);
+    P256VerifyEvent {
+        msg_hash: word(msg_hash.to_repr().as_ref()),
+        sig_r: word(sig_r.to_repr().as_ref()),
+        sig_s: word(sig_s.to_repr().as_ref()),
+        pk_x: word(pk.x().to_repr().as_ref()),
+        pk_y: word(pk.y().to_repr().as_ref()),
+        is_valid: true,
+    }
+}
+
+/// Return `n` events of signatures of random messages by random keys.
+fn gen_events(n: usize) -> Vec<P256VerifyEvent> {
+    let mut rng = XorShiftRng::seed_from_u64(2);
+    (0..n)
+        .map(|_| {
+            let msg_hash = Fq::random(&mut rng);
+            sign_event(&mut rng, msg_hash)
+        })
+        .collect()
+}
+
+#[test]
+fn p256_sig_circuit_2sigs_4max_verif() {
+    let mut events = gen_events(2);
+    // The hash of the call isn't reduced modulo the order of the curve.
+    let mut rng = XorShiftRng::seed_from_u64(3);
+    let mut event = sign_event(&mut rng, Fq::from(5));
+    event.msg_hash += order();
+    events.push(event);
+    let circuit = P256SigCircuit::<Fr>::new(4, events);
+    assert_eq!(run(circuit), Ok(()));
+}
+
+#[test]
+fn p256_sig_circuit_wrong_msg_hash() {
+    // The signature is not the one of the message hash
+    let mut events = gen_events(1);
+    events[0].msg_hash += eth_types::Word::one();
+    let circuit = P256SigCircuit::<Fr>::new(1, events);
+    assert!(run(circuit).is_err());
+}
+
+#[test]
+fn p256_sig_circuit_invalid_signature() {
+    // The verifications of invalid signatures can't be proven yet.
+    let mut events = gen_events(1);
+    events[0].is_valid = false;
+    let circuit = P256SigCircuit::<Fr>::new(1, events);
+    let k =
+        log2_ceil(P256SigCircuit::<Fr>::unusable_rows() + P256SigCircuit::<Fr>::min_num_rows(1));
+    assert!(MockProver::run(k, &circuit, vec![vec![]]).is_err());
+}
+
+#[test]
+fn p256_sig_circuit_0max_verif() {
+    // Only the empty row of the P256SigTable is assigned.
+    let circuit = P256SigCircuit::<Fr>::new(0, vec![]);
+    assert_eq!(run(circuit), Ok(()));
+}
@@ -31,7 +31,10 @@ use crate::{
         public_data_convert, BlockValues, ExtraValues, PublicData, TxValues, NONZERO_BYTE_GAS_COST,
         ZERO_BYTE_GAS_COST,
     },
-    table::{BlockTable, KeccakTable, LookupTable, TxFieldTag, TxTable, WdTable},
+    table::{
+        BlockHashTable, BlockTable, KeccakTable, LookupTable, SpongeChip, TxFieldTag, TxTable,
+        WdTable, BLOCK_HASH_WINDOW,
+    },
     tx_circuit::TX_LEN,
     util::{word::Word, Challenges, SubCircuit, SubCircuitConfig},
     witness,
@@ -46,9 +49,10 @@ use halo2_proofs::{
     poly::Rotation,
 };
 
-/// Config for PiCircuit
+/// Config for PiCircuit, committing to the public inputs with their digest in
+/// the table `S`
 #[derive(Clone, Debug)]
-pub struct PiCircuitConfig<F: Field> {
+pub struct PiCircuitConfig<F: Field, S = KeccakTable> {
     /// Max number of supported transactions
     max_txs: usize,
     /// Max number of supported withdrawals
@@ -106,13 +110,14 @@ pub struct PiCircuitConfig<F: Field> {
     _marker: PhantomData<F>,
     // External tables
     block_table: BlockTable,
+    block_hash_table: BlockHashTable,
     tx_table: TxTable,
     wd_table: WdTable,
-    keccak_table: KeccakTable,
+    hash_table: S,
 }
 
 /// Circuit configuration arguments
-pub struct PiCircuitConfigArgs<F: Field> {
+pub struct PiCircuitConfigArgs<F: Field, S = KeccakTable> {
     /// Max number of supported transactions
     pub max_txs: usize,
     /// Max number of supported withdrawals
@@ -125,14 +130,17 @@ pub struct PiCircuitConfigArgs<F: Field> {
     pub wd_table: WdTable,
     /// BlockTable
     pub block_table: BlockTable,
-    /// Keccak Table
-    pub keccak_table: KeccakTable,
+    /// BlockHashTable
+    pub block_hash_table: BlockHashTable,
+    /// Table of the digest of the public inputs: the KeccakTable for a
+    /// verifier on Ethereum
+    pub hash_table: S,
     /// Challenges
     pub challenges: Challenges<Expression<F>>,
 }
 
-impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
-    type ConfigArgs = PiCircuitConfigArgs<F>;
+impl<F: Field, S: SpongeChip<F>> SubCircuitConfig<F> for PiCircuitConfig<F, S> {
+    type ConfigArgs = PiCircuitConfigArgs<F, S>;
 
     /// Return a new PiCircuitConfig
     fn new(
@@ -142,9 +150,10 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
             max_withdrawals,
             max_calldata,
             block_table,
+            block_hash_table,
             tx_table,
             wd_table,
-            keccak_table,
+            hash_table,
             challenges,
         }: Self::ConfigArgs,
     ) -> Self {
@@ -186,9 +195,12 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
         tx_table.annotate_columns(meta);
         wd_table.annotate_columns(meta);
         block_table.annotate_columns(meta);
+        block_hash_table.annotate_columns(meta);
 
         meta.enable_equality(block_table.value.lo());
         meta.enable_equality(block_table.value.hi());
+        meta.enable_equality(block_hash_table.hash.lo());
+        meta.enable_equality(block_hash_table.hash.hi());
         meta.enable_equality(tx_table.tx_id);
         meta.enable_equality(tx_table.index);
         meta.enable_equality(tx_table.value.lo());
@@ -288,13 +300,7 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
             "lookup rpi_bytes_keccak_rlc against rpi_digest_bytes_limbs",
             |meta| {
                 let circuit_len =
-                    PiCircuitConfig::<F>::circuit_len_all(max_txs, max_withdrawals, max_calldata)
-                        .expr();
-                let is_enabled = meta.query_advice(keccak_table.is_enabled, Rotation::cur());
-                let input_rlc = meta.query_advice(keccak_table.input_rlc, Rotation::cur());
-                let input_len = meta.query_advice(keccak_table.input_len, Rotation::cur());
-                let output_lo = meta.query_advice(keccak_table.output.lo(), Rotation::cur());
-                let output_hi = meta.query_advice(keccak_table.output.hi(), Rotation::cur());
+                    Self::circuit_len_all(max_txs, max_withdrawals, max_calldata).expr();
 
                 // is_enabled
                 let q_rpi_keccak_lookup = meta.query_selector(q_rpi_keccak_lookup);
@@ -305,16 +311,13 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
                 let rpi_digest_lo = meta.query_advice(rpi_digest_bytes_limbs, Rotation::cur());
                 let rpi_digest_hi = meta.query_advice(rpi_digest_bytes_limbs, Rotation::next());
 
-                vec![
-                    (q_rpi_keccak_lookup.expr() * 1.expr(), is_enabled),
-                    (
-                        q_rpi_keccak_lookup.expr() * rpi_bytes_keccakrlc_cur,
-                        input_rlc,
-                    ),
-                    (q_rpi_keccak_lookup.expr() * circuit_len, input_len),
-                    (q_rpi_keccak_lookup.expr() * rpi_digest_lo, output_lo),
-                    (q_rpi_keccak_lookup * rpi_digest_hi, output_hi),
-                ]
+                hash_table.lookup_exprs(
+                    meta,
+                    q_rpi_keccak_lookup,
+                    rpi_bytes_keccakrlc_cur,
+                    circuit_len,
+                    Word::new([rpi_digest_lo, rpi_digest_hi]),
+                )
             },
         );
 
@@ -501,6 +504,7 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
             max_withdrawals,
             max_calldata,
             block_table,
+            block_hash_table,
             q_digest_last,
             q_bytes_last,
             q_tx_calldata,
@@ -511,7 +515,7 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
             q_digest_value_start,
             tx_table,
             wd_table,
-            keccak_table,
+            hash_table,
             tx_id_inv,
             tx_value_lo_inv,
             tx_id_diff_inv,
@@ -530,7 +534,7 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
     }
 }
 
-impl<F: Field> PiCircuitConfig<F> {
+impl<F: Field, S: SpongeChip<F>> PiCircuitConfig<F, S> {
     /// Return the number of rows in the circuit
     #[inline]
     fn circuit_len(&self) -> usize {
@@ -1294,13 +1298,78 @@ impl<F: Field> PiCircuitConfig<F> {
         block_copy_cells.push((block_value, word));
         *block_table_offset += 1;
 
-        for prev_hash in block_values.history_hashes {
-            let block_value = Word::from(prev_hash).into_value().assign_advice(
+        // blob_base_fee
+        let block_value = Word::from(block_values.blob_base_fee)
+            .into_value()
+            .assign_advice(
                 region,
-                || "prev_hash",
+                || "blob_base_fee",
+                self.block_table.value,
+                *block_table_offset,
+            )?;
+        let (_, word) = self.assign_raw_bytes(
+            region,
+            &block_values.blob_base_fee.to_le_bytes(),
+            rpi_bytes_keccak_rlc,
+            rpi_bytes,
+            current_rpi_offset,
+            challenges,
+            zero_cell.clone(),
+        )?;
+        block_copy_cells.push((block_value, word));
+        *block_table_offset += 1;
+
+        // prev_randao
+        let block_value = Word::from(block_values.prev_randao)
+            .into_value()
+            .assign_advice(
+                region,
+                || "prev_randao",
                 self.block_table.value,
                 *block_table_offset,
             )?;
+        let (_, word) = self.assign_raw_bytes(
+            region,
+            &block_values.prev_randao.to_le_bytes(),
+            rpi_bytes_keccak_rlc,
+            rpi_bytes,
+            current_rpi_offset,
+            challenges,
+            zero_cell.clone(),
+        )?;
+        block_copy_cells.push((block_value, word));
+        *block_table_offset += 1;
+
+        // parent_beacon_root
+        let block_value = Word::from(block_values.parent_beacon_root)
+            .into_value()
+            .assign_advice(
+                region,
+                || "parent_beacon_root",
+                self.block_table.value,
+                *block_table_offset,
+            )?;
+        let (_, word) = self.assign_raw_bytes(
+            region,
+            &block_values.parent_beacon_root.to_le_bytes(),
+            rpi_bytes_keccak_rlc,
+            rpi_bytes,
+            current_rpi_offset,
+            challenges,
+            zero_cell.clone(),
+        )?;
+        block_copy_cells.push((block_value, word));
+        *block_table_offset += 1;
+
+        // The history hashes go from the oldest to the parent hash, and are
+        // assigned in the block hash table by depth.
+        for (i, prev_hash) in block_values.history_hashes.into_iter().enumerate() {
+            let block_value = Word::from(prev_hash).into_value().assign_advice(
+                region,
+                || "prev_hash",
+                self.block_hash_table.hash,
+                BLOCK_HASH_WINDOW - i,
+            )?;
             let (_, word) = self.assign_raw_bytes(
                 region,
                 &prev_hash
@@ -1316,7 +1385,6 @@ impl<F: Field> PiCircuitConfig<F> {
                 zero_cell.clone(),
             )?;
             block_copy_cells.push((block_value, word));
-            *block_table_offset += 1;
         }
 
         block_copy_cells.iter().try_for_each(|(left, right)| {
@@ -1420,18 +1488,31 @@ impl<F: Field> PiCircuitConfig<F> {
     }
 }
 
-/// Public Inputs Circuit
-#[derive(Clone, Default, Debug)]
-pub struct PiCircuit<F: Field> {
+/// Public Inputs Circuit, whose public inputs are the digest in the table `S`
+/// of the public data
+#[derive(Clone, Debug)]
+pub struct PiCircuit<F: Field, S = KeccakTable> {
     max_txs: usize,
     max_withdrawals: usize,
     max_calldata: usize,
     /// PublicInputs data known by the verifier
     pub public_data: PublicData,
-    _marker: PhantomData<F>,
+    _marker: PhantomData<(F, S)>,
+}
+
+impl<F: Field, S> Default for PiCircuit<F, S> {
+    fn default() -> Self {
+        Self {
+            max_txs: 0,
+            max_withdrawals: 0,
+            max_calldata: 0,
+            public_data: PublicData::default(),
+            _marker: PhantomData,
+        }
+    }
 }
 
-impl<F: Field> PiCircuit<F> {
+impl<F: Field, S: SpongeChip<F>> PiCircuit<F, S> {
     /// Creates a new PiCircuit
     pub fn new(
         max_txs: usize,
@@ -1449,8 +1530,8 @@ impl<F: Field> PiCircuit<F> {
     }
 }
 
-impl<F: Field> SubCircuit<F> for PiCircuit<F> {
-    type Config = PiCircuitConfig<F>;
+impl<F: Field, S: SpongeChip<F>> SubCircuit<F> for PiCircuit<F, S> {
+    type Config = PiCircuitConfig<F, S>;
 
     fn unusable_rows() -> usize {
         // No column queried at more than 3 distinct rotations, so returns 6 as
@@ -1460,7 +1541,7 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
 
     fn new_from_block(block: &witness::Block<F>) -> Self {
         let public_data = public_data_convert(block);
-        PiCircuit::new(
+        Self::new(
             block.circuits_params.max_txs,
             block.circuits_params.max_withdrawals,
             block.circuits_params.max_calldata,
@@ -1483,11 +1564,11 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
 
     /// Compute the public inputs for this circuit.
     fn instance(&self) -> Vec<Vec<F>> {
-        let rpi_digest_byte_field = self.public_data.get_rpi_digest_word(
+        let rpi_digest_byte_field = S::digest(&self.public_data.get_pi_bytes(
             self.max_txs,
             self.max_withdrawals,
             self.max_calldata,
-        );
+        ));
 
         vec![vec![rpi_digest_byte_field.lo(), rpi_digest_byte_field.hi()]]
     }
@@ -1521,7 +1602,10 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                 config.tx_table.annotate_columns_in_region(&mut region);
                 config.wd_table.annotate_columns_in_region(&mut region);
                 config.block_table.annotate_columns_in_region(&mut region);
-                config.keccak_table.annotate_columns_in_region(&mut region);
+                config
+                    .block_hash_table
+                    .annotate_columns_in_region(&mut region);
+                config.hash_table.annotate_columns_in_region(&mut region);
 
                 region.name_column(|| "q_rpi_value_start", config.q_rpi_value_start);
                 region.name_column(|| "rpi_bytes", config.rpi_bytes);
@@ -1820,12 +1904,12 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                     })?;
                 assert_eq!(current_rpi_offset, 0);
 
-                // assign keccak digest
-                let digest_word = self.public_data.get_rpi_digest_word::<F>(
+                // assign the digest
+                let digest_word = S::digest(&self.public_data.get_pi_bytes(
                     config.max_txs,
                     config.max_withdrawals,
                     config.max_calldata,
-                );
+                ));
 
                 let digest_word_assigned =
                     config.assign_rpi_digest_word(&mut region, digest_word)?;
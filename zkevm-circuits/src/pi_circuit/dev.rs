@@ -3,7 +3,7 @@ use super::{PiCircuit, PiCircuitConfig, PiCircuitConfigArgs};
 use eth_types::{self, Field};
 
 use crate::{
-    table::{BlockTable, KeccakTable, TxTable, WdTable},
+    table::{BlockHashTable, BlockTable, SpongeChip, TxTable, WdTable},
     util::{Challenges, SubCircuit, SubCircuitConfig},
 };
 use halo2_proofs::{
@@ -22,8 +22,8 @@ pub struct PiCircuitParams {
     pub max_calldata: usize,
 }
 
-impl<F: Field> Circuit<F> for PiCircuit<F> {
-    type Config = (PiCircuitConfig<F>, Challenges);
+impl<F: Field, S: SpongeChip<F>> Circuit<F> for PiCircuit<F, S> {
+    type Config = (PiCircuitConfig<F, S>, Challenges);
     type FloorPlanner = SimpleFloorPlanner;
     type Params = PiCircuitParams;
 
@@ -41,9 +41,10 @@ impl<F: Field> Circuit<F> for PiCircuit<F> {
 
     fn configure_with_params(meta: &mut ConstraintSystem<F>, params: Self::Params) -> Self::Config {
         let block_table = BlockTable::construct(meta);
+        let block_hash_table = BlockHashTable::construct(meta);
         let tx_table = TxTable::construct(meta);
         let wd_table = WdTable::construct(meta);
-        let keccak_table = KeccakTable::construct(meta);
+        let hash_table = S::construct(meta);
         let challenges = Challenges::construct(meta);
         let challenge_exprs = challenges.exprs(meta);
         (
@@ -54,9 +55,10 @@ impl<F: Field> Circuit<F> for PiCircuit<F> {
                     max_withdrawals: params.max_withdrawals,
                     max_calldata: params.max_calldata,
                     block_table,
+                    block_hash_table,
                     tx_table,
                     wd_table,
-                    keccak_table,
+                    hash_table,
                     challenges: challenge_exprs,
                 },
             ),
@@ -74,14 +76,14 @@ impl<F: Field> Circuit<F> for PiCircuit<F> {
         mut layouter: impl Layouter<F>,
     ) -> Result<(), Error> {
         let challenges = challenges.values(&mut layouter);
-        // assign keccak table
+        // assign the hash table
         let rpi_bytes = self.public_data.get_pi_bytes(
             config.max_txs,
             config.max_withdrawals,
             config.max_calldata,
         );
         config
-            .keccak_table
+            .hash_table
             .dev_load(&mut layouter, vec![&rpi_bytes], &challenges)?;
 
         self.synthesize_sub(&config, &challenges, &mut layouter)
@@ -1,6 +1,9 @@
 use std::collections::HashMap;
 
-use crate::{pi_circuit::dev::PiCircuitParams, util::unusable_rows, witness::block_convert};
+use crate::{
+    pi_circuit::dev::PiCircuitParams, table::PoseidonTable, util::unusable_rows,
+    witness::block_convert,
+};
 
 use super::*;
 use bus_mapping::{
@@ -9,6 +12,7 @@ use bus_mapping::{
 use eth_types::{bytecode, geth_types::GethData, Address, Word, H160, H256};
 use ethers_signers::{LocalWallet, Signer};
 use halo2_proofs::{
+    arithmetic::Field as Halo2Field,
     dev::{MockProver, VerifyFailure},
     halo2curves::bn256::Fr,
 };
@@ -63,6 +67,20 @@ fn test_default_pi() {
     );
 }
 
+#[test]
+fn test_default_pi_poseidon() {
+    // The public inputs are the Poseidon digest of the public data, without
+    // hi.
+    let mut public_data = PublicData::default();
+    public_data.chain_id = *MOCK_CHAIN_ID;
+    let circuit = PiCircuit::<Fr, PoseidonTable>::new(2, 2, 8, public_data);
+    let public_inputs = circuit.instance();
+    assert_eq!(public_inputs[0][1], Fr::ZERO);
+
+    let prover = MockProver::run(17, &circuit, public_inputs).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
 #[test]
 fn test_simple_pi() {
     let max_txs = 8;
@@ -133,6 +151,7 @@ fn test_1tx_1maxtx() {
         block.clone(),
         FixedCParams {
             max_txs: MAX_TXS,
+            max_signed_txs: MAX_TXS,
             max_withdrawals: MAX_WITHDRAWALS,
             max_calldata: MAX_CALLDATA,
             max_rws: 1 << (degree - 1),
@@ -213,6 +232,7 @@ fn test_1wd_1wdmax() {
         block.clone(),
         FixedCParams {
             max_txs: MAX_TXS,
+            max_signed_txs: MAX_TXS,
             max_withdrawals: MAX_WITHDRAWALS,
             max_calldata: MAX_CALLDATA,
             max_rws: 1 << (degree - 1),
@@ -0,0 +1,656 @@
+//! The Poseidon circuit implementation.
+//!
+//! The circuit proves the Poseidon hashes of the [`PoseidonTable`], in which
+//! other circuits look up the digests of RLC'ed inputs, as computed by
+//! [`eth_types::poseidon::poseidon`].  Every block of 32 bytes of an input
+//! padded with zeros takes `ROWS_PER_BLOCK` rows:
+//!
+//! - the input row, with the bytes of the block accumulated into the input RLC and length until the
+//!   padding, and the state after absorbing the bytes as 2 elements of 16 bytes in big endian.  The
+//!   state is the digest of the previous block, or the initial state with the input length in the
+//!   capacity for the first block of a hash,
+//! - a row per round of the permutation with the state at the end of the round, the last one being
+//!   the row of the table for the last block of a hash.
+//!
+//! The bytes are not range checked: the input RLC binds them to the bytes
+//! of the looked up input.
+
+mod param;
+
+#[cfg(any(test, feature = "test-circuits"))]
+mod dev;
+#[cfg(test)]
+mod test;
+#[cfg(feature = "test-circuits")]
+pub use dev::PoseidonCircuit as TestPoseidonCircuit;
+
+use self::param::*;
+use crate::{
+    evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    table::{LookupTable, PoseidonTable},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use eth_types::{
+    poseidon::{self, absorbed_elements, initial_state, Poseidon},
+    Field,
+};
+use gadgets::util::{select, sum, Expr};
+use halo2_proofs::{
+    circuit::{Layouter, Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, VirtualCells,
+    },
+    poly::Rotation,
+};
+use itertools::{izip, Itertools};
+use log::error;
+use std::marker::PhantomData;
+
+/// PoseidonCircuitConfig
+#[derive(Clone, Debug)]
+pub struct PoseidonCircuitConfig<F> {
+    q_enable: Column<Fixed>,
+    q_first: Column<Fixed>,
+    q_block_start: Column<Fixed>,
+    q_full_round: Column<Fixed>,
+    q_partial_round: Column<Fixed>,
+    q_last: Column<Fixed>,
+    round_csts: [Column<Fixed>; WIDTH],
+    state: [Column<Advice>; WIDTH],
+    bytes: [Column<Advice>; NUM_BYTES_PER_BLOCK],
+    is_paddings: [Column<Advice>; NUM_BYTES_PER_BLOCK],
+    data_rlcs: [Column<Advice>; NUM_BYTES_PER_BLOCK - 1],
+    is_final: Column<Advice>,
+    /// Length of the input of the hash, copied over all the rows of its
+    /// blocks
+    hash_len: Column<Advice>,
+    /// The columns for other circuits to lookup Poseidon hash results
+    pub poseidon_table: PoseidonTable,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+pub struct PoseidonCircuitConfigArgs<F: Field> {
+    /// PoseidonTable
+    pub poseidon_table: PoseidonTable,
+    /// Challenges randomness
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for PoseidonCircuitConfig<F> {
+    type ConfigArgs = PoseidonCircuitConfigArgs<F>;
+
+    /// Return a new PoseidonCircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            poseidon_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let q_enable = meta.fixed_column();
+        let q_first = meta.fixed_column();
+        let q_block_start = meta.fixed_column();
+        let q_full_round = meta.fixed_column();
+        let q_partial_round = meta.fixed_column();
+        let q_last = meta.fixed_column();
+        let round_csts = array_init::array_init(|_| meta.fixed_column());
+
+        let state = array_init::array_init(|_| meta.advice_column());
+        let bytes = array_init::array_init(|_| meta.advice_column());
+        let is_paddings = array_init::array_init(|_| meta.advice_column());
+        let data_rlcs = array_init::array_init(|_| meta.advice_column_in(SecondPhase));
+        let is_final = meta.advice_column();
+        let hash_len = meta.advice_column();
+
+        let is_enabled = poseidon_table.is_enabled;
+        let data_rlc = poseidon_table.input_rlc;
+        let length = poseidon_table.input_len;
+        let hash = poseidon_table.output;
+
+        let mds = Poseidon::<F>::new().mds;
+
+        let state_at = |meta: &mut VirtualCells<F>, rot: Rotation| {
+            state.map(|column| meta.query_advice(column, rot))
+        };
+        // A new hash is started on the first block, or when the previous block
+        // is the last one of its hash.
+        let start_new_hash = |meta: &mut VirtualCells<F>| {
+            let q_first = meta.query_fixed(q_first, Rotation::cur());
+            q_first.clone() + (1.expr() - q_first) * meta.query_advice(is_final, Rotation::prev())
+        };
+
+        meta.create_gate("boolean checks", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in is_paddings.iter().chain([is_final].iter()) {
+                cb.require_boolean("boolean", meta.query_advice(*column, Rotation::cur()));
+            }
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("block state copy", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in [is_final, data_rlc, length] {
+                cb.require_equal(
+                    "the block state is the same for all the rows of a block",
+                    meta.query_advice(column, Rotation::cur()),
+                    meta.query_advice(column, Rotation::prev()),
+                );
+            }
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    - meta.query_fixed(q_block_start, Rotation::cur()),
+            )
+        });
+
+        meta.create_gate("hash_len copy", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            cb.require_equal(
+                "hash_len is the same for all the rows of a hash",
+                meta.query_advice(hash_len, Rotation::cur()),
+                meta.query_advice(hash_len, Rotation::prev()),
+            );
+            let start_new_hash = start_new_hash(meta);
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    - meta.query_fixed(q_block_start, Rotation::cur()) * start_new_hash,
+            )
+        });
+
+        meta.create_gate("absorb", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let start_new_hash = start_new_hash(meta);
+            let state = state_at(meta, Rotation::cur());
+            let state_prev = state_at(meta, Rotation::prev());
+            let bytes = bytes.map(|column| meta.query_advice(column, Rotation::cur()));
+            cb.require_equal(
+                "capacity = hash_len on a new hash, else the capacity of the previous digest",
+                state[0].clone(),
+                select::expr(
+                    start_new_hash.clone(),
+                    meta.query_advice(hash_len, Rotation::cur()),
+                    state_prev[0].clone(),
+                ),
+            );
+            for (i, bytes) in bytes.chunks(NUM_BYTES_PER_ELEMENT).enumerate() {
+                let element = bytes
+                    .iter()
+                    .fold(0.expr(), |acc, byte| acc * 256.expr() + byte.clone());
+                cb.require_equal(
+                    "rate element = absorbed element + the element of the previous digest",
+                    state[i + 1].clone(),
+                    (1.expr() - start_new_hash.clone()) * state_prev[i + 1].clone() + element,
+                );
+            }
+            cb.gate(meta.query_fixed(q_block_start, Rotation::cur()))
+        });
+
+        // The round constants are added to the state, which goes through the
+        // S-boxes and is multiplied with the MDS matrix.
+        for (name, q_round, is_full) in [
+            ("full round", q_full_round, true),
+            ("partial round", q_partial_round, false),
+        ] {
+            meta.create_gate(name, |meta| {
+                let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+                let state = state_at(meta, Rotation::cur());
+                let sboxed = izip!(state_at(meta, Rotation::prev()), round_csts)
+                    .enumerate()
+                    .map(|(i, (x, round_cst))| {
+                        let x = x + meta.query_fixed(round_cst, Rotation::cur());
+                        if i == 0 || is_full {
+                            x.clone() * x.clone() * x.clone() * x.clone() * x
+                        } else {
+                            x
+                        }
+                    })
+                    .collect_vec();
+                for (x, mds_row) in state.iter().zip(mds.iter()) {
+                    cb.require_equal(
+                        "state = MDS * S-box(state_prev + round_cst)",
+                        x.clone(),
+                        sum::expr(
+                            mds_row
+                                .iter()
+                                .zip(sboxed.iter())
+                                .map(|(m, x)| Expression::Constant(*m) * x.clone()),
+                        ),
+                    );
+                }
+                cb.gate(meta.query_fixed(q_round, Rotation::cur()))
+            });
+        }
+
+        meta.create_gate("poseidon table", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_last = meta.query_fixed(q_last, Rotation::cur());
+            let is_final = meta.query_advice(is_final, Rotation::cur());
+            cb.condition(q_last.clone(), |cb| {
+                cb.require_equal(
+                    "the hash is enabled on its last block",
+                    meta.query_advice(is_enabled, Rotation::cur()),
+                    is_final.clone(),
+                );
+                cb.require_equal(
+                    "hash = first rate element of the digest of the block",
+                    meta.query_advice(hash, Rotation::cur()),
+                    meta.query_advice(state[1], Rotation::cur()),
+                );
+            });
+            cb.condition(q_last.clone() * is_final, |cb| {
+                cb.require_equal(
+                    "hash_len is the length of the input",
+                    meta.query_advice(hash_len, Rotation::cur()),
+                    meta.query_advice(length, Rotation::cur()),
+                );
+            });
+            cb.condition(1.expr() - q_last, |cb| {
+                cb.require_zero(
+                    "is_enabled only on the last row of a block",
+                    meta.query_advice(is_enabled, Rotation::cur()),
+                );
+            });
+            cb.gate(1.expr())
+        });
+
+        meta.create_gate("input", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let start_new_hash = start_new_hash(meta);
+            let bytes = bytes.map(|column| meta.query_advice(column, Rotation::cur()));
+            let is_paddings = is_paddings.map(|column| meta.query_advice(column, Rotation::cur()));
+            let data_rlcs = [
+                vec![
+                    (1.expr() - start_new_hash.clone())
+                        * meta.query_advice(data_rlc, Rotation::prev()),
+                ],
+                data_rlcs
+                    .iter()
+                    .map(|column| meta.query_advice(*column, Rotation::cur()))
+                    .collect(),
+                vec![meta.query_advice(data_rlc, Rotation::cur())],
+            ]
+            .concat();
+            for (i, (byte, is_padding)) in bytes.iter().zip(is_paddings.iter()).enumerate() {
+                if i > 0 {
+                    cb.require_boolean(
+                        "is_padding can only go from 0 to 1",
+                        is_padding.clone() - is_paddings[i - 1].clone(),
+                    );
+                }
+                cb.require_zero("the padding bytes are 0", is_padding.clone() * byte.clone());
+                cb.require_equal(
+                    "data_rlc accumulates the bytes before the padding",
+                    data_rlcs[i + 1].clone(),
+                    select::expr(
+                        is_padding.clone(),
+                        data_rlcs[i].clone(),
+                        data_rlcs[i].clone() * challenges.keccak_input() + byte.clone(),
+                    ),
+                );
+            }
+            // The padding is only in the last block, which has at least one
+            // byte of input unless it's the block of the empty input.  So the
+            // number of blocks of a hash is determined by its length.
+            cb.require_zero(
+                "a block after the first one of a hash starts with input bytes",
+                (1.expr() - start_new_hash.clone()) * is_paddings[0].clone(),
+            );
+            cb.require_zero(
+                "only the last block of a hash has padding",
+                (1.expr() - meta.query_advice(is_final, Rotation::cur()))
+                    * is_paddings[NUM_BYTES_PER_BLOCK - 1].clone(),
+            );
+            cb.require_equal(
+                "length is the number of bytes before the padding",
+                meta.query_advice(length, Rotation::cur()),
+                (1.expr() - start_new_hash) * meta.query_advice(length, Rotation::prev())
+                    + sum::expr(
+                        is_paddings
+                            .iter()
+                            .map(|is_padding| 1.expr() - is_padding.clone()),
+                    ),
+            );
+            cb.gate(meta.query_fixed(q_block_start, Rotation::cur()))
+        });
+
+        Self {
+            q_enable,
+            q_first,
+            q_block_start,
+            q_full_round,
+            q_partial_round,
+            q_last,
+            round_csts,
+            state,
+            bytes,
+            is_paddings,
+            data_rlcs,
+            is_final,
+            hash_len,
+            poseidon_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Witness values of a row of the Poseidon circuit
+#[derive(Clone, Debug)]
+pub(crate) struct PoseidonRow<F> {
+    state: [F; WIDTH],
+    bytes: [u8; NUM_BYTES_PER_BLOCK],
+    is_paddings: [bool; NUM_BYTES_PER_BLOCK],
+    data_rlcs: [Value<F>; NUM_BYTES_PER_BLOCK - 1],
+    is_final: bool,
+    hash_len: usize,
+    is_enabled: bool,
+    data_rlc: Value<F>,
+    length: usize,
+    hash: F,
+}
+
+/// Append the rows of the Poseidon hash of `bytes` to `rows`.
+fn poseidon<F: Field>(
+    rows: &mut Vec<PoseidonRow<F>>,
+    params: &Poseidon<F>,
+    bytes: &[u8],
+    challenges: Challenges<Value<F>>,
+) {
+    let num_blocks = poseidon::num_blocks(bytes.len());
+
+    let mut state = initial_state(bytes.len());
+    let mut data_rlc = Value::known(F::ZERO);
+    let mut length = 0;
+    for idx in 0..num_blocks {
+        let is_final = idx == num_blocks - 1;
+        let mut row = PoseidonRow {
+            state,
+            bytes: [0; NUM_BYTES_PER_BLOCK],
+            is_paddings: [true; NUM_BYTES_PER_BLOCK],
+            data_rlcs: [data_rlc; NUM_BYTES_PER_BLOCK - 1],
+            is_final,
+            hash_len: bytes.len(),
+            is_enabled: false,
+            data_rlc,
+            length,
+            hash: F::ZERO,
+        };
+
+        // Input
+        let block =
+            &bytes[idx * NUM_BYTES_PER_BLOCK..bytes.len().min((idx + 1) * NUM_BYTES_PER_BLOCK)];
+        for (i, byte) in block.iter().enumerate() {
+            data_rlc = data_rlc * challenges.keccak_input() + Value::known(F::from(*byte as u64));
+            length += 1;
+            row.bytes[i] = *byte;
+            row.is_paddings[i] = false;
+            if i < NUM_BYTES_PER_BLOCK - 1 {
+                row.data_rlcs[i] = data_rlc;
+            }
+        }
+        for data_rlc_i in row.data_rlcs.iter_mut().skip(block.len()) {
+            *data_rlc_i = data_rlc;
+        }
+        for (x, element) in state[1..].iter_mut().zip(absorbed_elements::<F>(block)) {
+            *x += element;
+        }
+        row.state = state;
+        row.data_rlc = data_rlc;
+        row.length = length;
+        rows.push(row.clone());
+
+        // Rounds
+        for round in 0..NUM_ROUNDS {
+            state = params.round(round, &state);
+            let is_last = round == NUM_ROUNDS - 1;
+            rows.push(PoseidonRow {
+                state,
+                is_enabled: is_last && is_final,
+                hash: if is_last { state[1] } else { F::ZERO },
+                ..row.clone()
+            });
+        }
+    }
+}
+
+/// Return the rows of the Poseidon hashes of `inputs`, padded with hashes of
+/// the empty input to `max_blocks` blocks.
+pub(crate) fn multi_poseidon<F: Field>(
+    inputs: &[Vec<u8>],
+    challenges: Challenges<Value<F>>,
+    max_blocks: usize,
+) -> Result<Vec<PoseidonRow<F>>, Error> {
+    let num_blocks = PoseidonCircuit::<F>::num_blocks(inputs);
+    if num_blocks > max_blocks {
+        error!(
+            "number of blocks of the inputs = {} > max_blocks = {}",
+            num_blocks, max_blocks
+        );
+        return Err(Error::Synthesis);
+    }
+
+    let params = Poseidon::new();
+    let mut rows = Vec::with_capacity(max_blocks * ROWS_PER_BLOCK);
+    for input in inputs {
+        poseidon(&mut rows, &params, input, challenges);
+    }
+    for _ in num_blocks..max_blocks {
+        poseidon(&mut rows, &params, &[], challenges);
+    }
+    Ok(rows)
+}
+
+impl<F: Field> PoseidonCircuitConfig<F> {
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        witness: &[PoseidonRow<F>],
+    ) -> Result<(), Error> {
+        let round_constants = Poseidon::<F>::new().round_constants;
+        layouter.assign_region(
+            || "assign poseidon rows",
+            |mut region| {
+                for (offset, row) in witness.iter().enumerate() {
+                    self.set_row(&mut region, offset, row, &round_constants)?;
+                }
+                self.poseidon_table.annotate_columns_in_region(&mut region);
+                self.annotate_circuit(&mut region);
+                Ok(())
+            },
+        )
+    }
+
+    fn set_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &PoseidonRow<F>,
+        round_constants: &[[F; WIDTH]],
+    ) -> Result<(), Error> {
+        // Fixed selectors, which only depend on the position of the row in its
+        // block.
+        let row_idx = offset % ROWS_PER_BLOCK;
+        let round = row_idx.checked_sub(1);
+        for (name, column, value) in [
+            ("q_enable", self.q_enable, true),
+            ("q_first", self.q_first, offset == 0),
+            ("q_block_start", self.q_block_start, row_idx == 0),
+            (
+                "q_full_round",
+                self.q_full_round,
+                matches!(round, Some(r) if is_full_round(r)),
+            ),
+            (
+                "q_partial_round",
+                self.q_partial_round,
+                matches!(round, Some(r) if !is_full_round(r)),
+            ),
+            ("q_last", self.q_last, row_idx == ROWS_PER_BLOCK - 1),
+        ] {
+            region.assign_fixed(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(F::from(value as u64)),
+            )?;
+        }
+        for (i, column) in self.round_csts.iter().enumerate() {
+            region.assign_fixed(
+                || format!("assign round cst {}", offset),
+                *column,
+                offset,
+                || Value::known(round.map_or(F::ZERO, |r| round_constants[r][i])),
+            )?;
+        }
+
+        for (column, value) in self.state.iter().zip(row.state) {
+            region.assign_advice(
+                || format!("assign state {}", offset),
+                *column,
+                offset,
+                || Value::known(value),
+            )?;
+        }
+        for (column, byte) in self.bytes.iter().zip(row.bytes) {
+            region.assign_advice(
+                || format!("assign byte {}", offset),
+                *column,
+                offset,
+                || Value::known(F::from(byte as u64)),
+            )?;
+        }
+        for (column, is_padding) in self.is_paddings.iter().zip(row.is_paddings) {
+            region.assign_advice(
+                || format!("assign is_padding {}", offset),
+                *column,
+                offset,
+                || Value::known(F::from(is_padding as u64)),
+            )?;
+        }
+        for (column, data_rlc) in self.data_rlcs.iter().zip(row.data_rlcs) {
+            region.assign_advice(
+                || format!("assign data_rlc {}", offset),
+                *column,
+                offset,
+                || data_rlc,
+            )?;
+        }
+        for (name, column, value) in [
+            ("is_final", self.is_final, row.is_final as usize),
+            ("hash_len", self.hash_len, row.hash_len),
+        ] {
+            region.assign_advice(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(F::from(value as u64)),
+            )?;
+        }
+
+        for (column, value) in
+            <PoseidonTable as LookupTable<F>>::advice_columns(&self.poseidon_table)
+                .into_iter()
+                .zip_eq([
+                    Value::known(F::from(row.is_enabled as u64)),
+                    row.data_rlc,
+                    Value::known(F::from(row.length as u64)),
+                    Value::known(row.hash),
+                ])
+        {
+            region.assign_advice(
+                || format!("assign poseidon table {}", offset),
+                column,
+                offset,
+                || value,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn annotate_circuit(&self, region: &mut Region<F>) {
+        region.name_column(|| "POSEIDON_q_enable", self.q_enable);
+        region.name_column(|| "POSEIDON_q_first", self.q_first);
+        region.name_column(|| "POSEIDON_q_block_start", self.q_block_start);
+        region.name_column(|| "POSEIDON_q_full_round", self.q_full_round);
+        region.name_column(|| "POSEIDON_q_partial_round", self.q_partial_round);
+        region.name_column(|| "POSEIDON_q_last", self.q_last);
+        region.name_column(|| "POSEIDON_is_final", self.is_final);
+        region.name_column(|| "POSEIDON_hash_len", self.hash_len);
+    }
+}
+
+/// Poseidon circuit, proving the hashes of the PoseidonTable
+#[derive(Clone, Default, Debug)]
+pub struct PoseidonCircuit<F: Field> {
+    /// Max number of blocks of 32 bytes of the padded inputs
+    pub max_blocks: usize,
+    /// Inputs to hash
+    pub inputs: Vec<Vec<u8>>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> PoseidonCircuit<F> {
+    /// Return a new PoseidonCircuit with `max_blocks` blocks.
+    pub fn new(max_blocks: usize, inputs: Vec<Vec<u8>>) -> Self {
+        Self {
+            max_blocks,
+            inputs,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the minimum number of rows required to prove `max_blocks`
+    /// blocks.
+    pub fn min_num_rows(max_blocks: usize) -> usize {
+        max_blocks * ROWS_PER_BLOCK
+    }
+
+    /// Return the number of blocks required to hash `inputs`.
+    pub fn num_blocks(inputs: &[Vec<u8>]) -> usize {
+        inputs
+            .iter()
+            .map(|input| poseidon::num_blocks(input.len()))
+            .sum()
+    }
+}
+
+impl<F: Field> SubCircuit<F> for PoseidonCircuit<F> {
+    type Config = PoseidonCircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // The advice columns are only queried at the current and previous
+        // rows, so returns 6 unusable rows.
+        6
+    }
+
+    /// The `block.circuits_params.max_poseidon_blocks` parameter sets up the
+    /// circuit to support a fixed number of blocks; when 0, the circuit only
+    /// has the blocks required by the inputs.
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        let max_blocks = match block.circuits_params.max_poseidon_blocks {
+            0 => Self::num_blocks(&block.poseidon_inputs),
+            max_blocks => max_blocks,
+        };
+        Self::new(max_blocks, block.poseidon_inputs.clone())
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        (
+            Self::min_num_rows(Self::num_blocks(&block.poseidon_inputs)),
+            Self::min_num_rows(block.circuits_params.max_poseidon_blocks),
+        )
+    }
+
+    /// Make the assignments to the PoseidonCircuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let witness = multi_poseidon(&self.inputs, *challenges, self.max_blocks)?;
+        config.assign(layouter, &witness)
+    }
+}
@@ -0,0 +1,9 @@
+pub(crate) use eth_types::poseidon::{
+    is_full_round, NUM_BYTES_PER_BLOCK, NUM_BYTES_PER_ELEMENT, NUM_ROUNDS, WIDTH,
+};
+
+pub(crate) const MAX_DEGREE: usize = 6;
+
+/// A block starts with the row of the state after absorbing its input,
+/// followed by a row per round with the state at the end of the round.
+pub(crate) const ROWS_PER_BLOCK: usize = NUM_ROUNDS + 1;
@@ -0,0 +1,122 @@
+use super::*;
+use crate::{
+    evm_circuit::util::rlc,
+    util::{log2_ceil, unusable_rows},
+};
+use halo2_proofs::{
+    dev::{CellValue, MockProver},
+    halo2curves::bn256::Fr,
+    plonk::Circuit,
+};
+
+#[test]
+fn poseidon_circuit_unusable_rows() {
+    assert_eq!(
+        PoseidonCircuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, PoseidonCircuit::<Fr>>(()),
+    )
+}
+
+fn run(circuit: &PoseidonCircuit<Fr>) -> MockProver<Fr> {
+    let k = log2_ceil(
+        PoseidonCircuit::<Fr>::unusable_rows()
+            + PoseidonCircuit::<Fr>::min_num_rows(circuit.max_blocks),
+    );
+    MockProver::<Fr>::run(k, circuit, vec![]).unwrap()
+}
+
+fn assigned<F: Field>(cv: &CellValue<F>) -> F {
+    match *cv {
+        CellValue::Assigned(f) => f,
+        _ => F::ZERO,
+    }
+}
+
+fn verify(max_blocks: usize, inputs: Vec<Vec<u8>>) {
+    let circuit = PoseidonCircuit::<Fr>::new(max_blocks, inputs.clone());
+    let prover = run(&circuit);
+    assert_eq!(prover.verify(), Ok(()));
+
+    // Extract the rows of the table with hash results.
+    let (config, challenges) = PoseidonCircuit::configure(&mut ConstraintSystem::<Fr>::default());
+    let input_challenge = prover.get_challenge(challenges.keccak_input());
+    let table = &config.poseidon_table;
+    let hashes = izip!(
+        prover.advice_values(table.is_enabled),
+        prover.advice_values(table.input_rlc),
+        prover.advice_values(table.input_len),
+        prover.advice_values(table.output),
+    )
+    .filter(|(is_enabled, ..)| assigned(*is_enabled) == Fr::from(1))
+    .map(|(_, input_rlc, input_len, output)| {
+        (assigned(input_rlc), assigned(input_len), assigned(output))
+    })
+    .collect_vec();
+
+    // The inputs are followed by the hashes of the empty input up to
+    // `max_blocks` blocks.
+    let padding = vec![vec![]; max_blocks - PoseidonCircuit::<Fr>::num_blocks(&inputs)];
+    assert_eq!(hashes.len(), inputs.len() + padding.len());
+    for (input, hash) in inputs.iter().chain(padding.iter()).zip(hashes) {
+        input_challenge
+            .map(|challenge| rlc::value(input.iter().rev(), challenge))
+            .assert_if_known(|input_rlc| *input_rlc == hash.0);
+        assert_eq!(
+            (hash.1, hash.2),
+            (Fr::from(input.len() as u64), poseidon::poseidon(input))
+        );
+    }
+}
+
+#[test]
+fn poseidon_circuit_simple() {
+    let inputs = [0, 1, 15, 16, 31, 32, 33, 64, 100]
+        .into_iter()
+        .map(|len| (0..len).map(|i| (1 + 3 * i) as u8).collect_vec())
+        .collect_vec();
+    verify(PoseidonCircuit::<Fr>::num_blocks(&inputs) + 2, inputs);
+}
+
+#[test]
+fn poseidon_circuit_no_inputs() {
+    verify(1, vec![]);
+}
+
+#[test]
+fn poseidon_circuit_zero_padding_is_not_ambiguous() {
+    // The trailing zero bytes are absorbed like the padding, but the lengths
+    // differ.
+    let hashes = [
+        vec![1],
+        vec![1, 0],
+        vec![1; 32],
+        [vec![1; 32], vec![0]].concat(),
+    ]
+    .iter()
+    .map(|input| poseidon::poseidon::<Fr>(input))
+    .collect_vec();
+    assert!(hashes.iter().all_unique());
+}
+
+#[test]
+fn poseidon_circuit_exceeded_max_blocks() {
+    // 33 bytes don't fit in a block.
+    let circuit = PoseidonCircuit::<Fr>::new(1, vec![vec![0; 33]]);
+    let k = log2_ceil(
+        PoseidonCircuit::<Fr>::unusable_rows()
+            + PoseidonCircuit::<Fr>::min_num_rows(circuit.max_blocks),
+    );
+    assert!(MockProver::<Fr>::run(k, &circuit, vec![]).is_err());
+}
+
+#[test]
+fn poseidon_circuit_variadic_size_check() {
+    // The fixed columns only depend on `max_blocks`.
+    let prover_1 = run(&PoseidonCircuit::<Fr>::new(4, vec![vec![1, 2, 3]]));
+    let prover_2 = run(&PoseidonCircuit::<Fr>::new(
+        4,
+        vec![vec![4; 32], vec![5; 20]],
+    ));
+    assert_eq!(prover_1.fixed(), prover_2.fixed());
+    assert_eq!(prover_1.permutation(), prover_2.permutation());
+}
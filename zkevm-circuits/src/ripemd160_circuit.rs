@@ -0,0 +1,1011 @@
+//! The RIPEMD-160 circuit implementation.
+//!
+//! The circuit proves the RIPEMD-160 hashes of the [`Ripemd160Table`], in
+//! which other circuits look up the digests of RLC'ed inputs.  Every block of
+//! 64 bytes of a padded input takes `ROWS_PER_BLOCK` rows:
+//!
+//! - 5 rows with the initial state of the block, the initial hash value for the first block of an
+//!   input and the digest of the previous block otherwise,
+//! - a row per round of the compression, that holds the bits of the new value of `B` of both lines.
+//!   The 16 first rows also hold the words of the block, whose bytes are accumulated into the input
+//!   RLC and length until the padding, and which are copied to the rounds that use them,
+//! - 5 rows with the digest of the block, the last one being the row of the table for the last
+//!   block of an input.
+//!
+//! As `C` is the previous value of `B`, and `D, E, A` are the rotations by 10
+//! of the values of `B` before, the state of a line is only made of the bits
+//! of `B` at the 5 previous rows.  The state rows hold the words of the hash
+//! value in the same form: `h0, h4, h3` rotated right by 10, `h2` and `h1`.
+
+mod param;
+mod util;
+
+#[cfg(any(test, feature = "test-circuits"))]
+mod dev;
+#[cfg(test)]
+mod test;
+#[cfg(feature = "test-circuits")]
+pub use dev::Ripemd160Circuit as TestRipemd160Circuit;
+
+use self::{param::*, util::*};
+use crate::{
+    evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    table::{LookupTable, Ripemd160Table},
+    util::{word, Challenges, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use eth_types::{Field, U256};
+use gadgets::util::{select, sum, Expr};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, VirtualCells,
+    },
+    poly::Rotation,
+};
+use itertools::{izip, Itertools};
+use log::error;
+use std::marker::PhantomData;
+
+/// Index of the word of the hash value held by each state row
+const STATE_ROW_WORDS: [usize; NUM_STATE_ROWS] = [0, 4, 3, 2, 1];
+
+/// Return the bits of the word of the hash value held by the state row
+/// `state_row` from the bits of the row.
+fn state_word_bits<F: Field>(bits: &WordBits<F>, state_row: usize) -> WordBits<F> {
+    if state_row < 3 {
+        rotate_left(bits, ROTATION_C)
+    } else {
+        bits.clone()
+    }
+}
+
+/// Return the value of the state row `state_row` of the hash value `hs`.
+fn state_row_value(hs: &[u32; 5], state_row: usize) -> u32 {
+    let h = hs[STATE_ROW_WORDS[state_row]];
+    if state_row < 3 {
+        h.rotate_right(ROTATION_C as u32)
+    } else {
+        h
+    }
+}
+
+/// Ripemd160CircuitConfig
+#[derive(Clone, Debug)]
+pub struct Ripemd160CircuitConfig<F> {
+    q_enable: Column<Fixed>,
+    q_first: Column<Fixed>,
+    q_block_start: Column<Fixed>,
+    q_init: Column<Fixed>,
+    q_round: Column<Fixed>,
+    q_input: Column<Fixed>,
+    q_length_start: Column<Fixed>,
+    q_length_end: Column<Fixed>,
+    q_digest: [Column<Fixed>; NUM_STATE_ROWS],
+    q_last: Column<Fixed>,
+    /// Selectors of the groups of rounds
+    q_groups: [Column<Fixed>; NUM_GROUPS],
+    /// Selectors of the amounts of the rotations of the rounds, by line
+    q_shifts: [[Column<Fixed>; SHIFTS.len()]; NUM_LINES],
+    round_cst: [Column<Fixed>; NUM_LINES],
+    /// Initial hash value on the initial state rows
+    h_init: Column<Fixed>,
+    /// Bits of `B` by line, which hold the state on the state rows of the left
+    /// line
+    t: [[Column<Advice>; NUM_BITS_PER_WORD]; NUM_LINES],
+    /// Bits of the sum that is rotated in the rounds, by line
+    s: [[Column<Advice>; NUM_BITS_PER_WORD]; NUM_LINES],
+    carry_s: [[Column<Advice>; 2]; NUM_LINES],
+    carry_t: [Column<Advice>; NUM_LINES],
+    /// Word of the block used in the rounds, by line
+    x: [Column<Advice>; NUM_LINES],
+    /// Bits of the words of the block on the input rows
+    w: [Column<Advice>; NUM_BITS_PER_WORD],
+    is_paddings: [Column<Advice>; NUM_BYTES_PER_WORD],
+    data_rlcs: [Column<Advice>; NUM_BYTES_PER_WORD - 1],
+    is_final: Column<Advice>,
+    /// The columns for other circuits to lookup RIPEMD-160 hash results
+    pub ripemd160_table: Ripemd160Table,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+pub struct Ripemd160CircuitConfigArgs<F: Field> {
+    /// Ripemd160Table
+    pub ripemd160_table: Ripemd160Table,
+    /// Challenges randomness
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for Ripemd160CircuitConfig<F> {
+    type ConfigArgs = Ripemd160CircuitConfigArgs<F>;
+
+    /// Return a new Ripemd160CircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            ripemd160_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let q_enable = meta.fixed_column();
+        let q_first = meta.fixed_column();
+        let q_block_start = meta.fixed_column();
+        let q_init = meta.fixed_column();
+        let q_round = meta.fixed_column();
+        let q_input = meta.fixed_column();
+        let q_length_start = meta.fixed_column();
+        let q_length_end = meta.fixed_column();
+        let q_digest = array_init::array_init(|_| meta.fixed_column());
+        let q_last = meta.fixed_column();
+        let q_groups = array_init::array_init(|_| meta.fixed_column());
+        let q_shifts = array_init::array_init(|_| array_init::array_init(|_| meta.fixed_column()));
+        let round_cst = array_init::array_init(|_| meta.fixed_column());
+        let h_init = meta.fixed_column();
+
+        let t = array_init::array_init(|_| array_init::array_init(|_| meta.advice_column()));
+        let s = array_init::array_init(|_| array_init::array_init(|_| meta.advice_column()));
+        let carry_s = array_init::array_init(|_| array_init::array_init(|_| meta.advice_column()));
+        let carry_t = array_init::array_init(|_| meta.advice_column());
+        let x: [Column<Advice>; NUM_LINES] = array_init::array_init(|_| meta.advice_column());
+        let w = array_init::array_init(|_| meta.advice_column());
+        let is_paddings = array_init::array_init(|_| meta.advice_column());
+        let data_rlcs = array_init::array_init(|_| meta.advice_column_in(SecondPhase));
+        let is_final = meta.advice_column();
+        for column in x {
+            meta.enable_equality(column);
+        }
+
+        let is_enabled = ripemd160_table.is_enabled;
+        let data_rlc = ripemd160_table.input_rlc;
+        let length = ripemd160_table.input_len;
+        let hash_word = ripemd160_table.output;
+
+        let bits = |meta: &mut VirtualCells<F>,
+                    columns: &[Column<Advice>; NUM_BITS_PER_WORD],
+                    rot: i32|
+         -> WordBits<F> {
+            array_init::array_init(|i| meta.query_advice(columns[i], Rotation(rot)))
+        };
+        let value = |meta: &mut VirtualCells<F>, columns: &[Column<Advice>], rot: i32| {
+            compose(
+                &columns
+                    .iter()
+                    .map(|column| meta.query_advice(*column, Rotation(rot)))
+                    .collect_vec(),
+            )
+        };
+        let pow_of_two_32 = || (1u64 << NUM_BITS_PER_WORD).expr();
+        // A new hash is started on the first block, or when the previous block
+        // is the last one of its hash, as seen from the initial state rows.
+        let start_new_hash = |meta: &mut VirtualCells<F>| {
+            let q_first = meta.query_fixed(q_first, Rotation::cur());
+            q_first.clone()
+                + (1.expr() - q_first)
+                    * meta.query_advice(is_final, Rotation(-(NUM_STATE_ROWS as i32)))
+        };
+
+        meta.create_gate("boolean checks", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in t
+                .iter()
+                .chain(s.iter())
+                .flatten()
+                .chain(carry_s.iter().flatten())
+                .chain(carry_t.iter())
+                .chain(w.iter())
+                .chain(is_paddings.iter())
+                .chain([is_final].iter())
+            {
+                cb.require_boolean("boolean", meta.query_advice(*column, Rotation::cur()));
+            }
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("is_final", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            cb.require_equal(
+                "is_final is the same for all the rows of a block",
+                meta.query_advice(is_final, Rotation::cur()),
+                meta.query_advice(is_final, Rotation::prev()),
+            );
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    - meta.query_fixed(q_block_start, Rotation::cur()),
+            )
+        });
+
+        meta.create_gate("initial state", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let start_new_hash = start_new_hash(meta);
+            cb.require_equal(
+                "initial value = H on a new hash, else the digest of the previous block",
+                value(meta, &t[0], 0),
+                select::expr(
+                    start_new_hash,
+                    meta.query_fixed(h_init, Rotation::cur()),
+                    value(meta, &t[0], -(NUM_STATE_ROWS as i32)),
+                ),
+            );
+            cb.require_equal(
+                "both lines start from the same state",
+                value(meta, &t[1], 0),
+                value(meta, &t[0], 0),
+            );
+            cb.gate(meta.query_fixed(q_init, Rotation::cur()))
+        });
+
+        meta.create_gate("round", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for line in 0..NUM_LINES {
+                // A, D, E are the previous values of B rotated by 10.
+                let [a, b, c, d, e] = [-5, -1, -2, -3, -4].map(|rot| bits(meta, &t[line], rot));
+                let [a, d, e] = [a, d, e].map(|bits| rotate_left(&bits, ROTATION_C));
+                // The right line uses the boolean functions in the reverse order.
+                let f_bcd = sum::expr((0..NUM_GROUPS).map(|group| {
+                    let f_group = if line == 0 {
+                        group
+                    } else {
+                        NUM_GROUPS - 1 - group
+                    };
+                    meta.query_fixed(q_groups[group], Rotation::cur()) * f(f_group, &b, &c, &d)
+                }));
+                cb.require_equal(
+                    "s = A + f(B, C, D) + X + K",
+                    value(meta, &s[line], 0) + value(meta, &carry_s[line], 0) * pow_of_two_32(),
+                    compose(&a)
+                        + f_bcd
+                        + meta.query_advice(x[line], Rotation::cur())
+                        + meta.query_fixed(round_cst[line], Rotation::cur()),
+                );
+                let s = bits(meta, &s[line], 0);
+                let rotated_s = sum::expr(SHIFTS.iter().zip(q_shifts[line].iter()).map(
+                    |(shift, q_shift)| {
+                        meta.query_fixed(*q_shift, Rotation::cur())
+                            * compose(&rotate_left(&s, *shift))
+                    },
+                ));
+                cb.require_equal(
+                    "B = rol(s) + E",
+                    value(meta, &t[line], 0)
+                        + meta.query_advice(carry_t[line], Rotation::cur()) * pow_of_two_32(),
+                    rotated_s + compose(&e),
+                );
+            }
+            cb.gate(meta.query_fixed(q_round, Rotation::cur()))
+        });
+
+        // The state rows after the rounds hold the sum of the initial hash
+        // value and of the final states of both lines, in which `A, B, C, D, E`
+        // are at the rotations `-5, -1, -2, -3, -4` from the first of these
+        // rows, `A, D, E` being rotated by 10.
+        let final_rots = [-5, -1, -2, -3, -4];
+        meta.create_gate("digest", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for (state_row, q_digest) in q_digest.iter().enumerate() {
+                let digest_row = (NUM_STATE_ROWS + NUM_ROUNDS + state_row) as i32;
+                let final_value = |meta: &mut VirtualCells<F>, line: usize, idx: usize| {
+                    let bits = bits(meta, &t[line], final_rots[idx] - state_row as i32);
+                    if [0, 3, 4].contains(&idx) {
+                        compose(&rotate_left(&bits, ROTATION_C))
+                    } else {
+                        compose(&bits)
+                    }
+                };
+                // h'[i] = h[i + 1] + left[i + 2] + right[i + 3]
+                let i = STATE_ROW_WORDS[state_row];
+                let h_row = STATE_ROW_WORDS
+                    .iter()
+                    .position(|word| *word == (i + 1) % 5)
+                    .unwrap();
+                let h = compose(&state_word_bits(
+                    &bits(meta, &t[0], h_row as i32 - digest_row),
+                    h_row,
+                ));
+                let expected =
+                    h + final_value(meta, 0, (i + 2) % 5) + final_value(meta, 1, (i + 3) % 5);
+                cb.condition(meta.query_fixed(*q_digest, Rotation::cur()), |cb| {
+                    cb.require_equal(
+                        "digest = initial value + final states of the lines",
+                        compose(&state_word_bits(&bits(meta, &t[0], 0), state_row))
+                            + value(meta, &carry_s[0], 0) * pow_of_two_32(),
+                        expected,
+                    );
+                });
+            }
+            cb.gate(1.expr())
+        });
+
+        meta.create_gate("ripemd160 table", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+            let q_last = meta.query_fixed(q_last, Rotation::cur());
+            cb.condition(q_last.clone(), |cb| {
+                cb.require_equal(
+                    "the hash is enabled on its last block",
+                    meta.query_advice(is_enabled, Rotation::cur()),
+                    meta.query_advice(is_final, Rotation::cur()),
+                );
+                // The digest is serialized as the words h0..h4 in little endian,
+                // and is the last 20 bytes of the output word.
+                let words: [Expression<F>; 5] = array_init::array_init(|i| {
+                    let state_row = STATE_ROW_WORDS.iter().position(|word| *word == i).unwrap();
+                    let rot = state_row as i32 - (NUM_STATE_ROWS as i32 - 1);
+                    compose_swapped(&state_word_bits(&bits(meta, &t[0], rot), state_row))
+                });
+                cb.require_equal(
+                    "hash hi = h0",
+                    meta.query_advice(hash_word.hi(), Rotation::cur()),
+                    words[0].clone(),
+                );
+                cb.require_equal(
+                    "hash lo = h1..h4",
+                    meta.query_advice(hash_word.lo(), Rotation::cur()),
+                    words[1..]
+                        .iter()
+                        .fold(0.expr(), |acc, word| acc * pow_of_two_32() + word.clone()),
+                );
+            });
+            cb.condition(q_enable - q_last, |cb| {
+                cb.require_zero(
+                    "is_enabled only on the last row of a block",
+                    meta.query_advice(is_enabled, Rotation::cur()),
+                );
+            });
+            cb.gate(1.expr())
+        });
+
+        // The padding state, the input RLC and the length are reset at the
+        // start of a new hash, updated on the rows of the words of the block,
+        // and copied on the other rows.
+        let last_is_padding = |meta: &mut VirtualCells<F>| {
+            meta.query_advice(is_paddings[NUM_BYTES_PER_WORD - 1], Rotation::prev())
+        };
+        meta.create_gate("input state at the start of a block", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let start_new_hash = start_new_hash(meta);
+            for (name, column, column_prev) in [
+                (
+                    "last is_padding reset on a new hash",
+                    meta.query_advice(is_paddings[NUM_BYTES_PER_WORD - 1], Rotation::cur()),
+                    last_is_padding(meta),
+                ),
+                (
+                    "data_rlc reset on a new hash",
+                    meta.query_advice(data_rlc, Rotation::cur()),
+                    meta.query_advice(data_rlc, Rotation::prev()),
+                ),
+                (
+                    "length reset on a new hash",
+                    meta.query_advice(length, Rotation::cur()),
+                    meta.query_advice(length, Rotation::prev()),
+                ),
+            ] {
+                cb.require_equal(
+                    name,
+                    column,
+                    (1.expr() - start_new_hash.clone()) * column_prev,
+                );
+            }
+            cb.gate(meta.query_fixed(q_block_start, Rotation::cur()))
+        });
+        meta.create_gate("input state copy", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            cb.require_equal(
+                "last is_padding copied",
+                meta.query_advice(is_paddings[NUM_BYTES_PER_WORD - 1], Rotation::cur()),
+                last_is_padding(meta),
+            );
+            for column in [data_rlc, length] {
+                cb.require_equal(
+                    "input state copied",
+                    meta.query_advice(column, Rotation::cur()),
+                    meta.query_advice(column, Rotation::prev()),
+                );
+            }
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    - meta.query_fixed(q_block_start, Rotation::cur())
+                    - meta.query_fixed(q_input, Rotation::cur()),
+            )
+        });
+
+        // The bytes of a word in little endian and the padding flags of the
+        // bytes.
+        let input_bytes = |meta: &mut VirtualCells<F>| {
+            let w = bits(meta, &w, 0);
+            let bytes: [Expression<F>; NUM_BYTES_PER_WORD] = array_init::array_init(|i| {
+                compose(&w[i * NUM_BITS_PER_BYTE..(i + 1) * NUM_BITS_PER_BYTE])
+            });
+            let is_paddings = is_paddings.map(|column| meta.query_advice(column, Rotation::cur()));
+            let is_paddings_prev: [Expression<F>; NUM_BYTES_PER_WORD] =
+                array_init::array_init(|i| {
+                    if i == 0 {
+                        last_is_padding(meta)
+                    } else {
+                        is_paddings[i - 1].clone()
+                    }
+                });
+            (bytes, is_paddings, is_paddings_prev)
+        };
+        meta.create_gate("input", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            cb.require_equal(
+                "the word of the left line is the word of the block",
+                meta.query_advice(x[0], Rotation::cur()),
+                value(meta, &w, 0),
+            );
+            let (bytes, is_paddings, is_paddings_prev) = input_bytes(meta);
+            let data_rlcs = [
+                vec![meta.query_advice(data_rlc, Rotation::prev())],
+                data_rlcs
+                    .iter()
+                    .map(|column| meta.query_advice(*column, Rotation::cur()))
+                    .collect(),
+                vec![meta.query_advice(data_rlc, Rotation::cur())],
+            ]
+            .concat();
+            for (i, (byte, is_padding, is_padding_prev)) in
+                izip!(&bytes, &is_paddings, &is_paddings_prev).enumerate()
+            {
+                let is_first_padding = is_padding.clone() - is_padding_prev.clone();
+                cb.require_boolean(
+                    "is_padding can only go from 0 to 1",
+                    is_first_padding.clone(),
+                );
+                cb.require_zero(
+                    "the first padding byte is 0x80",
+                    is_first_padding * (byte.clone() - 0x80.expr()),
+                );
+                cb.require_equal(
+                    "data_rlc accumulates the bytes before the padding",
+                    data_rlcs[i + 1].clone(),
+                    select::expr(
+                        is_padding.clone(),
+                        data_rlcs[i].clone(),
+                        data_rlcs[i].clone() * challenges.keccak_input() + byte.clone(),
+                    ),
+                );
+            }
+            cb.require_equal(
+                "length is the number of bytes before the padding",
+                meta.query_advice(length, Rotation::cur()),
+                meta.query_advice(length, Rotation::prev())
+                    + sum::expr(
+                        is_paddings
+                            .iter()
+                            .map(|is_padding| 1.expr() - is_padding.clone()),
+                    ),
+            );
+            cb.gate(meta.query_fixed(q_input, Rotation::cur()))
+        });
+        meta.create_gate("padding bytes", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let (bytes, _, is_paddings_prev) = input_bytes(meta);
+            for (byte, is_padding_prev) in bytes.iter().zip(is_paddings_prev.iter()) {
+                cb.require_zero(
+                    "the padding bytes after 0x80 are 0",
+                    is_padding_prev.clone() * byte.clone(),
+                );
+            }
+            // The length bytes of the last block are checked below.
+            let is_final = meta.query_advice(is_final, Rotation::cur());
+            cb.gate(
+                meta.query_fixed(q_input, Rotation::cur())
+                    - (meta.query_fixed(q_length_start, Rotation::cur())
+                        + meta.query_fixed(q_length_end, Rotation::cur()))
+                        * is_final,
+            )
+        });
+        meta.create_gate("length bytes", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let is_final = meta.query_advice(is_final, Rotation::cur());
+            cb.condition(meta.query_fixed(q_length_start, Rotation::cur()), |cb| {
+                // The padding starts before the length bytes in the last block, so
+                // that these are all padding bytes, and after them otherwise, so
+                // that the next block is the last one.
+                cb.require_equal(
+                    "the padding starts before the length bytes iff the block is the last one",
+                    last_is_padding(meta),
+                    is_final.clone(),
+                );
+            });
+            cb.condition(
+                meta.query_fixed(q_length_end, Rotation::cur()) * is_final,
+                |cb| {
+                    cb.require_equal(
+                        "the last 8 bytes of the last block are the bit length",
+                        value(meta, &w, -1) + value(meta, &w, 0) * pow_of_two_32(),
+                        meta.query_advice(length, Rotation::cur()) * 8.expr(),
+                    );
+                },
+            );
+            cb.gate(1.expr())
+        });
+
+        Self {
+            q_enable,
+            q_first,
+            q_block_start,
+            q_init,
+            q_round,
+            q_input,
+            q_length_start,
+            q_length_end,
+            q_digest,
+            q_last,
+            q_groups,
+            q_shifts,
+            round_cst,
+            h_init,
+            t,
+            s,
+            carry_s,
+            carry_t,
+            x,
+            w,
+            is_paddings,
+            data_rlcs,
+            is_final,
+            ripemd160_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Witness values of a row of the RIPEMD-160 circuit
+#[derive(Clone, Debug)]
+pub(crate) struct Ripemd160Row<F> {
+    t: [u32; NUM_LINES],
+    s: [u32; NUM_LINES],
+    carry_s: [u64; NUM_LINES],
+    carry_t: [u64; NUM_LINES],
+    x: [u32; NUM_LINES],
+    w: u32,
+    is_paddings: [bool; NUM_BYTES_PER_WORD],
+    data_rlcs: [Value<F>; NUM_BYTES_PER_WORD - 1],
+    is_final: bool,
+    is_enabled: bool,
+    data_rlc: Value<F>,
+    length: usize,
+    hash: word::Word<Value<F>>,
+}
+
+impl<F: Field> Ripemd160Row<F> {
+    /// Return a row with the input state of `is_padding`, `data_rlc` and
+    /// `length`, and zero values.
+    fn new(is_final: bool, is_padding: bool, data_rlc: Value<F>, length: usize) -> Self {
+        Self {
+            t: [0; NUM_LINES],
+            s: [0; NUM_LINES],
+            carry_s: [0; NUM_LINES],
+            carry_t: [0; NUM_LINES],
+            x: [0; NUM_LINES],
+            w: 0,
+            is_paddings: [is_padding; NUM_BYTES_PER_WORD],
+            data_rlcs: [data_rlc; NUM_BYTES_PER_WORD - 1],
+            is_final,
+            is_enabled: false,
+            data_rlc,
+            length,
+            hash: word::Word::new([Value::known(F::ZERO); 2]),
+        }
+    }
+}
+
+/// Append the rows of the RIPEMD-160 hash of `bytes` to `rows`.
+fn ripemd160<F: Field>(
+    rows: &mut Vec<Ripemd160Row<F>>,
+    bytes: &[u8],
+    challenges: Challenges<Value<F>>,
+) {
+    let padded = pad(bytes);
+    let num_blocks = padded.len() / RATE;
+
+    let mut hs = H;
+    let mut is_padding = false;
+    let mut data_rlc = Value::known(F::ZERO);
+    let mut length = 0;
+    for (idx, block) in padded.chunks(RATE).enumerate() {
+        let is_final = idx == num_blocks - 1;
+
+        // Initial state
+        let mut ts = vec![[0; NUM_LINES]; NUM_STATE_ROWS];
+        for (state_row, t) in ts.iter_mut().enumerate() {
+            *t = [state_row_value(&hs, state_row); NUM_LINES];
+            rows.push(Ripemd160Row {
+                t: *t,
+                ..Ripemd160Row::new(is_final, is_padding, data_rlc, length)
+            });
+        }
+
+        // Rounds
+        let xs = block
+            .chunks(NUM_BYTES_PER_WORD)
+            .map(|word| u32::from_le_bytes(word.try_into().unwrap()))
+            .collect_vec();
+        for j in 0..NUM_ROUNDS {
+            let group = j / NUM_ROUNDS_PER_GROUP;
+            let mut row = Ripemd160Row::new(is_final, is_padding, data_rlc, length);
+            for line in 0..NUM_LINES {
+                let prev = |rot: usize| ts[ts.len() - rot][line];
+                let rotated = |rot: usize| prev(rot).rotate_left(ROTATION_C as u32);
+                let (a, b, c, d, e) = (rotated(5), prev(1), prev(2), rotated(3), rotated(4));
+                let f_group = if line == 0 {
+                    group
+                } else {
+                    NUM_GROUPS - 1 - group
+                };
+                let x = xs[WORD_IDX[line][j]];
+                let s = a as u64
+                    + f_value(f_group, b, c, d) as u64
+                    + x as u64
+                    + ROUND_CST[line][group] as u64;
+                let t = (s as u32).rotate_left(ROTATIONS[line][j] as u32) as u64 + e as u64;
+                row.t[line] = t as u32;
+                row.s[line] = s as u32;
+                row.carry_s[line] = s >> NUM_BITS_PER_WORD;
+                row.carry_t[line] = t >> NUM_BITS_PER_WORD;
+                row.x[line] = x;
+            }
+            ts.push(row.t);
+
+            if j < NUM_WORDS_TO_ABSORB {
+                let bytes_offset = idx * RATE + j * NUM_BYTES_PER_WORD;
+                for (i, byte) in block[j * NUM_BYTES_PER_WORD..(j + 1) * NUM_BYTES_PER_WORD]
+                    .iter()
+                    .enumerate()
+                {
+                    is_padding = bytes_offset + i >= bytes.len();
+                    if !is_padding {
+                        data_rlc = data_rlc * challenges.keccak_input()
+                            + Value::known(F::from(*byte as u64));
+                        length += 1;
+                    }
+                    row.is_paddings[i] = is_padding;
+                    if i < NUM_BYTES_PER_WORD - 1 {
+                        row.data_rlcs[i] = data_rlc;
+                    }
+                }
+                row.w = xs[j];
+                row.data_rlc = data_rlc;
+                row.length = length;
+            }
+            rows.push(row);
+        }
+
+        // Digest: h'[i] = h[i + 1] + left[i + 2] + right[i + 3], with the
+        // final states of the lines in the order A, B, C, D, E.
+        let finals: [[u32; 5]; NUM_LINES] = array_init::array_init(|line| {
+            let value = |rot: usize| ts[ts.len() - rot][line];
+            let rotated = |rot: usize| value(rot).rotate_left(ROTATION_C as u32);
+            [rotated(5), value(1), value(2), rotated(3), rotated(4)]
+        });
+        let sums: [u64; 5] = array_init::array_init(|i| {
+            hs[(i + 1) % 5] as u64 + finals[0][(i + 2) % 5] as u64 + finals[1][(i + 3) % 5] as u64
+        });
+        hs = sums.map(|sum| sum as u32);
+        let digest = hs.iter().flat_map(|h| h.to_le_bytes()).collect_vec();
+        let hash = word::Word::<F>::from(U256::from_big_endian(&digest)).map(Value::known);
+        for state_row in 0..NUM_STATE_ROWS {
+            let is_last = state_row == NUM_STATE_ROWS - 1;
+            let row = Ripemd160Row::new(is_final, is_padding, data_rlc, length);
+            let mut carry_s = row.carry_s;
+            carry_s[0] = sums[STATE_ROW_WORDS[state_row]] >> NUM_BITS_PER_WORD;
+            rows.push(Ripemd160Row {
+                t: [state_row_value(&hs, state_row), 0],
+                carry_s,
+                is_enabled: is_last && is_final,
+                hash: if is_last { hash } else { row.hash },
+                ..row
+            });
+        }
+    }
+}
+
+/// Return the rows of the RIPEMD-160 hashes of `inputs`, padded with hashes
+/// of the empty input to `max_blocks` blocks.
+pub(crate) fn multi_ripemd160<F: Field>(
+    inputs: &[Vec<u8>],
+    challenges: Challenges<Value<F>>,
+    max_blocks: usize,
+) -> Result<Vec<Ripemd160Row<F>>, Error> {
+    let num_blocks: usize = inputs.iter().map(|input| num_blocks(input.len())).sum();
+    if num_blocks > max_blocks {
+        error!(
+            "number of blocks of the inputs = {} > max_blocks = {}",
+            num_blocks, max_blocks
+        );
+        return Err(Error::Synthesis);
+    }
+
+    let mut rows = Vec::with_capacity(max_blocks * ROWS_PER_BLOCK);
+    for input in inputs {
+        ripemd160(&mut rows, input, challenges);
+    }
+    for _ in num_blocks..max_blocks {
+        ripemd160(&mut rows, &[], challenges);
+    }
+    Ok(rows)
+}
+
+impl<F: Field> Ripemd160CircuitConfig<F> {
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        witness: &[Ripemd160Row<F>],
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "assign ripemd160 rows",
+            |mut region| {
+                let mut x_cells = Vec::with_capacity(witness.len());
+                for (offset, row) in witness.iter().enumerate() {
+                    x_cells.push(self.set_row(&mut region, offset, row)?);
+                }
+
+                // Copy the words of the block to the rounds that use them.
+                for block in x_cells.chunks(ROWS_PER_BLOCK) {
+                    let rounds = &block[NUM_STATE_ROWS..NUM_STATE_ROWS + NUM_ROUNDS];
+                    for (line, j) in (0..NUM_LINES).cartesian_product(0..NUM_ROUNDS) {
+                        let word_idx = WORD_IDX[line][j];
+                        if line == 0 && j == word_idx {
+                            continue;
+                        }
+                        region
+                            .constrain_equal(rounds[j][line].cell(), rounds[word_idx][0].cell())?;
+                    }
+                }
+
+                self.ripemd160_table.annotate_columns_in_region(&mut region);
+                self.annotate_circuit(&mut region);
+                Ok(())
+            },
+        )
+    }
+
+    fn set_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &Ripemd160Row<F>,
+    ) -> Result<[AssignedCell<F, F>; NUM_LINES], Error> {
+        // Fixed selectors, which only depend on the position of the row in its
+        // block.
+        let row_idx = offset % ROWS_PER_BLOCK;
+        let round = row_idx
+            .checked_sub(NUM_STATE_ROWS)
+            .filter(|j| *j < NUM_ROUNDS);
+        let digest_row = row_idx.checked_sub(NUM_STATE_ROWS + NUM_ROUNDS);
+        let group = round.map(|j| j / NUM_ROUNDS_PER_GROUP);
+        let mut fixed = vec![
+            ("q_enable", self.q_enable, true),
+            ("q_first", self.q_first, offset < NUM_STATE_ROWS),
+            ("q_block_start", self.q_block_start, row_idx == 0),
+            ("q_init", self.q_init, row_idx < NUM_STATE_ROWS),
+            ("q_round", self.q_round, round.is_some()),
+            (
+                "q_input",
+                self.q_input,
+                matches!(round, Some(j) if j < NUM_WORDS_TO_ABSORB),
+            ),
+            (
+                "q_length_start",
+                self.q_length_start,
+                round == Some(NUM_WORDS_TO_ABSORB - 2),
+            ),
+            (
+                "q_length_end",
+                self.q_length_end,
+                round == Some(NUM_WORDS_TO_ABSORB - 1),
+            ),
+            ("q_last", self.q_last, row_idx == ROWS_PER_BLOCK - 1),
+        ];
+        for (state_row, column) in self.q_digest.iter().enumerate() {
+            fixed.push(("q_digest", *column, digest_row == Some(state_row)));
+        }
+        for (idx, column) in self.q_groups.iter().enumerate() {
+            fixed.push(("q_group", *column, group == Some(idx)));
+        }
+        for (line, columns) in self.q_shifts.iter().enumerate() {
+            for (shift, column) in SHIFTS.iter().zip(columns.iter()) {
+                let is_shift = matches!(round, Some(j) if ROTATIONS[line][j] == *shift);
+                fixed.push(("q_shift", *column, is_shift));
+            }
+        }
+        for (name, column, value) in fixed {
+            region.assign_fixed(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(F::from(value as u64)),
+            )?;
+        }
+        for (line, column) in self.round_cst.iter().enumerate() {
+            region.assign_fixed(
+                || format!("assign round cst {}", offset),
+                *column,
+                offset,
+                || {
+                    Value::known(F::from(
+                        group.map_or(0, |group| ROUND_CST[line][group]) as u64
+                    ))
+                },
+            )?;
+        }
+        let h_init = if row_idx < NUM_STATE_ROWS {
+            state_row_value(&H, row_idx)
+        } else {
+            0
+        };
+        region.assign_fixed(
+            || format!("assign h init {}", offset),
+            self.h_init,
+            offset,
+            || Value::known(F::from(h_init as u64)),
+        )?;
+
+        // Bits
+        let mut bits = vec![("w", &self.w[..], row.w as u64)];
+        for line in 0..NUM_LINES {
+            bits.extend([
+                ("t", &self.t[line][..], row.t[line] as u64),
+                ("s", &self.s[line][..], row.s[line] as u64),
+                ("carry_s", &self.carry_s[line][..], row.carry_s[line]),
+                ("carry_t", &self.carry_t[line..line + 1], row.carry_t[line]),
+            ]);
+        }
+        for (name, columns, value) in bits {
+            for (i, column) in columns.iter().enumerate() {
+                region.assign_advice(
+                    || format!("assign {} bit {} {}", name, i, offset),
+                    *column,
+                    offset,
+                    || Value::known(F::from((value >> i) & 1)),
+                )?;
+            }
+        }
+        let x_cells = self
+            .x
+            .iter()
+            .zip(row.x)
+            .map(|(column, x)| {
+                region.assign_advice(
+                    || format!("assign x {}", offset),
+                    *column,
+                    offset,
+                    || Value::known(F::from(x as u64)),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Input state
+        for (column, is_padding) in self.is_paddings.iter().zip(row.is_paddings) {
+            region.assign_advice(
+                || format!("assign is_padding {}", offset),
+                *column,
+                offset,
+                || Value::known(F::from(is_padding as u64)),
+            )?;
+        }
+        for (column, data_rlc) in self.data_rlcs.iter().zip(row.data_rlcs) {
+            region.assign_advice(
+                || format!("assign data_rlc {}", offset),
+                *column,
+                offset,
+                || data_rlc,
+            )?;
+        }
+        region.assign_advice(
+            || format!("assign is_final {}", offset),
+            self.is_final,
+            offset,
+            || Value::known(F::from(row.is_final as u64)),
+        )?;
+
+        for (column, value) in
+            <Ripemd160Table as LookupTable<F>>::advice_columns(&self.ripemd160_table)
+                .into_iter()
+                .zip_eq([
+                    Value::known(F::from(row.is_enabled as u64)),
+                    row.data_rlc,
+                    Value::known(F::from(row.length as u64)),
+                    row.hash.lo(),
+                    row.hash.hi(),
+                ])
+        {
+            region.assign_advice(
+                || format!("assign ripemd160 table {}", offset),
+                column,
+                offset,
+                || value,
+            )?;
+        }
+
+        Ok(x_cells.try_into().unwrap())
+    }
+
+    fn annotate_circuit(&self, region: &mut Region<F>) {
+        region.name_column(|| "RIPEMD160_q_enable", self.q_enable);
+        region.name_column(|| "RIPEMD160_q_first", self.q_first);
+        region.name_column(|| "RIPEMD160_q_block_start", self.q_block_start);
+        region.name_column(|| "RIPEMD160_q_init", self.q_init);
+        region.name_column(|| "RIPEMD160_q_round", self.q_round);
+        region.name_column(|| "RIPEMD160_q_input", self.q_input);
+        region.name_column(|| "RIPEMD160_q_last", self.q_last);
+        region.name_column(|| "RIPEMD160_x_left", self.x[0]);
+        region.name_column(|| "RIPEMD160_x_right", self.x[1]);
+        region.name_column(|| "RIPEMD160_is_final", self.is_final);
+    }
+}
+
+/// RIPEMD-160 circuit, proving the hashes of the Ripemd160Table
+#[derive(Clone, Default, Debug)]
+pub struct Ripemd160Circuit<F: Field> {
+    /// Max number of blocks of 64 bytes of the padded inputs
+    pub max_blocks: usize,
+    /// Inputs to hash
+    pub inputs: Vec<Vec<u8>>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> Ripemd160Circuit<F> {
+    /// Return a new Ripemd160Circuit with `max_blocks` blocks.
+    pub fn new(max_blocks: usize, inputs: Vec<Vec<u8>>) -> Self {
+        Self {
+            max_blocks,
+            inputs,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the minimum number of rows required to prove `max_blocks`
+    /// blocks.
+    pub fn min_num_rows(max_blocks: usize) -> usize {
+        max_blocks * ROWS_PER_BLOCK
+    }
+
+    /// Return the number of blocks required to hash `inputs`.
+    pub fn num_blocks(inputs: &[Vec<u8>]) -> usize {
+        inputs.iter().map(|input| num_blocks(input.len())).sum()
+    }
+}
+
+impl<F: Field> SubCircuit<F> for Ripemd160Circuit<F> {
+    type Config = Ripemd160CircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // The bits of B of the left line are queried at 9 distinct rotations,
+        // so returns 12 unusable rows.
+        12
+    }
+
+    /// The `block.circuits_params.max_ripemd160_blocks` parameter sets up the
+    /// circuit to support a fixed number of blocks; when 0, the circuit only
+    /// has the blocks required by the inputs of the ripemd160 precompile calls.
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        let max_blocks = match block.circuits_params.max_ripemd160_blocks {
+            0 => Self::num_blocks(&block.ripemd160_inputs),
+            max_blocks => max_blocks,
+        };
+        Self::new(max_blocks, block.ripemd160_inputs.clone())
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        (
+            Self::min_num_rows(Self::num_blocks(&block.ripemd160_inputs)),
+            Self::min_num_rows(block.circuits_params.max_ripemd160_blocks),
+        )
+    }
+
+    /// Make the assignments to the Ripemd160Circuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let witness = multi_ripemd160(&self.inputs, *challenges, self.max_blocks)?;
+        config.assign(layouter, &witness)
+    }
+}
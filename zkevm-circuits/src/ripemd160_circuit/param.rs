@@ -0,0 +1,67 @@
+pub(crate) const MAX_DEGREE: usize = 5;
+
+pub(crate) const NUM_BITS_PER_BYTE: usize = 8;
+pub(crate) const NUM_BYTES_PER_WORD: usize = 4;
+pub(crate) const NUM_BITS_PER_WORD: usize = NUM_BYTES_PER_WORD * NUM_BITS_PER_BYTE;
+pub(crate) const NUM_ROUNDS: usize = 80;
+/// Number of rounds using the same boolean function and constants
+pub(crate) const NUM_ROUNDS_PER_GROUP: usize = 16;
+pub(crate) const NUM_GROUPS: usize = NUM_ROUNDS / NUM_ROUNDS_PER_GROUP;
+pub(crate) const NUM_WORDS_TO_ABSORB: usize = 16;
+pub(crate) const RATE: usize = NUM_WORDS_TO_ABSORB * NUM_BYTES_PER_WORD;
+/// Number of bytes of the message length appended by the padding
+pub(crate) const NUM_BYTES_LENGTH: usize = 8;
+/// Number of bytes of a digest
+pub(crate) const NUM_BYTES_DIGEST: usize = 20;
+
+/// The state of a line is kept as the last 5 values of `B`, from which `A, C,
+/// D, E` are derived: the initial state of a block takes 5 rows before the
+/// rounds, and its digest 5 rows after them.
+pub(crate) const NUM_STATE_ROWS: usize = 5;
+pub(crate) const ROWS_PER_BLOCK: usize = NUM_STATE_ROWS + NUM_ROUNDS + NUM_STATE_ROWS;
+
+/// The left and the right lines of the compression
+pub(crate) const NUM_LINES: usize = 2;
+/// Rotation applied to `C` to get the next `D`
+pub(crate) const ROTATION_C: usize = 10;
+/// Amounts of the rotations of the rounds
+pub(crate) const SHIFTS: [usize; 11] = [5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+
+/// Selection of the message words by round
+pub(crate) const WORD_IDX: [[usize; NUM_ROUNDS]; NUM_LINES] = [
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 7, 4, 13, 1, 10, 6, 15, 3, 12, 0, 9,
+        5, 2, 14, 11, 8, 3, 10, 14, 4, 9, 15, 8, 1, 2, 7, 0, 6, 13, 11, 5, 12, 1, 9, 11, 10, 0, 8,
+        12, 4, 13, 3, 7, 15, 14, 5, 6, 2, 4, 0, 5, 9, 7, 12, 2, 10, 14, 1, 3, 8, 11, 6, 15, 13,
+    ],
+    [
+        5, 14, 7, 0, 9, 2, 11, 4, 13, 6, 15, 8, 1, 10, 3, 12, 6, 11, 3, 7, 0, 13, 5, 10, 14, 15, 8,
+        12, 4, 9, 1, 2, 15, 5, 1, 3, 7, 14, 6, 9, 11, 8, 12, 2, 10, 0, 4, 13, 8, 6, 4, 1, 3, 11,
+        15, 0, 5, 12, 2, 13, 9, 7, 10, 14, 12, 15, 10, 4, 1, 5, 8, 7, 6, 2, 13, 14, 0, 3, 9, 11,
+    ],
+];
+
+/// Amounts of the rotations by round
+pub(crate) const ROTATIONS: [[usize; NUM_ROUNDS]; NUM_LINES] = [
+    [
+        11, 14, 15, 12, 5, 8, 7, 9, 11, 13, 14, 15, 6, 7, 9, 8, 7, 6, 8, 13, 11, 9, 7, 15, 7, 12,
+        15, 9, 11, 7, 13, 12, 11, 13, 6, 7, 14, 9, 13, 15, 14, 8, 13, 6, 5, 12, 7, 5, 11, 12, 14,
+        15, 14, 15, 9, 8, 9, 14, 5, 6, 8, 6, 5, 12, 9, 15, 5, 11, 6, 8, 13, 12, 5, 12, 13, 14, 11,
+        8, 5, 6,
+    ],
+    [
+        8, 9, 9, 11, 13, 15, 15, 5, 7, 7, 8, 11, 14, 14, 12, 6, 9, 13, 15, 7, 12, 8, 9, 11, 7, 7,
+        12, 7, 6, 15, 13, 11, 9, 7, 15, 11, 8, 6, 6, 14, 12, 13, 5, 14, 13, 13, 7, 5, 15, 5, 8, 11,
+        14, 14, 6, 14, 6, 9, 12, 9, 12, 5, 15, 8, 8, 5, 12, 9, 12, 5, 14, 6, 8, 13, 6, 5, 15, 13,
+        11, 11,
+    ],
+];
+
+/// Constants of the rounds by group
+pub(crate) const ROUND_CST: [[u32; NUM_GROUPS]; NUM_LINES] = [
+    [0x00000000, 0x5a827999, 0x6ed9eba1, 0x8f1bbcdc, 0xa953fd4e],
+    [0x50a28be6, 0x5c4dd124, 0x6d703ef3, 0x7a6d76e9, 0x00000000],
+];
+
+/// Initial hash value
+pub(crate) const H: [u32; 5] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
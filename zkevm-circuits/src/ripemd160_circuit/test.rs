@@ -0,0 +1,115 @@
+use super::*;
+use crate::{
+    evm_circuit::util::rlc,
+    util::{log2_ceil, unusable_rows},
+};
+use halo2_proofs::{
+    dev::{CellValue, MockProver},
+    halo2curves::bn256::Fr,
+    plonk::Circuit,
+};
+use ripemd::{Digest, Ripemd160};
+
+#[test]
+fn ripemd160_circuit_unusable_rows() {
+    assert_eq!(
+        Ripemd160Circuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, Ripemd160Circuit::<Fr>>(()),
+    )
+}
+
+fn run(circuit: &Ripemd160Circuit<Fr>) -> MockProver<Fr> {
+    let k = log2_ceil(
+        Ripemd160Circuit::<Fr>::unusable_rows()
+            + Ripemd160Circuit::<Fr>::min_num_rows(circuit.max_blocks),
+    );
+    MockProver::<Fr>::run(k, circuit, vec![]).unwrap()
+}
+
+fn assigned<F: Field>(cv: &CellValue<F>) -> F {
+    match *cv {
+        CellValue::Assigned(f) => f,
+        _ => F::ZERO,
+    }
+}
+
+fn verify(max_blocks: usize, inputs: Vec<Vec<u8>>) {
+    let circuit = Ripemd160Circuit::<Fr>::new(max_blocks, inputs.clone());
+    let prover = run(&circuit);
+    assert_eq!(prover.verify(), Ok(()));
+
+    // Extract the rows of the table with hash results.
+    let (config, challenges) = Ripemd160Circuit::configure(&mut ConstraintSystem::<Fr>::default());
+    let input_challenge = prover.get_challenge(challenges.keccak_input());
+    let table = &config.ripemd160_table;
+    let hashes = izip!(
+        prover.advice_values(table.is_enabled),
+        prover.advice_values(table.input_rlc),
+        prover.advice_values(table.input_len),
+        prover.advice_values(table.output.lo()),
+        prover.advice_values(table.output.hi()),
+    )
+    .filter(|(is_enabled, ..)| assigned(*is_enabled) == Fr::from(1))
+    .map(|(_, input_rlc, input_len, lo, hi)| {
+        (
+            assigned(input_rlc),
+            assigned(input_len),
+            assigned(lo),
+            assigned(hi),
+        )
+    })
+    .collect_vec();
+
+    // The inputs are followed by the hashes of the empty input up to
+    // `max_blocks` blocks.
+    let padding = vec![vec![]; max_blocks - Ripemd160Circuit::<Fr>::num_blocks(&inputs)];
+    assert_eq!(hashes.len(), inputs.len() + padding.len());
+    for (input, hash) in inputs.iter().chain(padding.iter()).zip(hashes) {
+        let digest = U256::from_big_endian(&Ripemd160::digest(input));
+        let (lo, hi) = word::Word::<Fr>::from(digest).to_lo_hi();
+        input_challenge
+            .map(|challenge| rlc::value(input.iter().rev(), challenge))
+            .assert_if_known(|input_rlc| *input_rlc == hash.0);
+        assert_eq!(
+            (hash.1, hash.2, hash.3),
+            (Fr::from(input.len() as u64), lo, hi)
+        );
+    }
+}
+
+#[test]
+fn ripemd160_circuit_simple() {
+    let inputs = [0, 3, 55, 56, 63, 64, 119, 120, 200]
+        .into_iter()
+        .map(|len| (0..len).map(|i| (1 + 3 * i) as u8).collect_vec())
+        .collect_vec();
+    verify(Ripemd160Circuit::<Fr>::num_blocks(&inputs) + 2, inputs);
+}
+
+#[test]
+fn ripemd160_circuit_no_inputs() {
+    verify(1, vec![]);
+}
+
+#[test]
+fn ripemd160_circuit_exceeded_max_blocks() {
+    // 56 bytes don't fit in a block with the padding.
+    let circuit = Ripemd160Circuit::<Fr>::new(1, vec![vec![0; 56]]);
+    let k = log2_ceil(
+        Ripemd160Circuit::<Fr>::unusable_rows()
+            + Ripemd160Circuit::<Fr>::min_num_rows(circuit.max_blocks),
+    );
+    assert!(MockProver::<Fr>::run(k, &circuit, vec![]).is_err());
+}
+
+#[test]
+fn ripemd160_circuit_variadic_size_check() {
+    // The fixed columns only depend on `max_blocks`.
+    let prover_1 = run(&Ripemd160Circuit::<Fr>::new(4, vec![vec![1, 2, 3]]));
+    let prover_2 = run(&Ripemd160Circuit::<Fr>::new(
+        4,
+        vec![vec![4; 64], vec![5; 20]],
+    ));
+    assert_eq!(prover_1.fixed(), prover_2.fixed());
+    assert_eq!(prover_1.permutation(), prover_2.permutation());
+}
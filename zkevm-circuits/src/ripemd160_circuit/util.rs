@@ -0,0 +1,97 @@
+use super::param::*;
+use eth_types::Field;
+use gadgets::util::Expr;
+use halo2_proofs::plonk::Expression;
+
+/// Bits of a word in little-endian
+pub(crate) type WordBits<F> = [Expression<F>; NUM_BITS_PER_WORD];
+
+/// Return the value of little-endian bits.
+pub(crate) fn compose<F: Field>(bits: &[Expression<F>]) -> Expression<F> {
+    bits.iter()
+        .rev()
+        .fold(0.expr(), |acc, bit| acc * 2.expr() + bit.clone())
+}
+
+/// Return the value of the word with its bytes in the reverse order, which is
+/// the big-endian value of how the word is serialized in a digest.
+pub(crate) fn compose_swapped<F: Field>(bits: &WordBits<F>) -> Expression<F> {
+    bits.chunks(NUM_BITS_PER_BYTE)
+        .fold(0.expr(), |acc, byte| acc * 256.expr() + compose(byte))
+}
+
+/// Return the bits of `bits` rotated left by `n`.
+pub(crate) fn rotate_left<F: Field>(bits: &WordBits<F>, n: usize) -> WordBits<F> {
+    array_init::array_init(|i| bits[(i + NUM_BITS_PER_WORD - n) % NUM_BITS_PER_WORD].clone())
+}
+
+fn xor<F: Field>(a: Expression<F>, b: Expression<F>) -> Expression<F> {
+    a.clone() + b.clone() - 2.expr() * a * b
+}
+
+fn or<F: Field>(a: Expression<F>, b: Expression<F>) -> Expression<F> {
+    a.clone() + b.clone() - a * b
+}
+
+fn not<F: Field>(a: Expression<F>) -> Expression<F> {
+    1.expr() - a
+}
+
+/// Return the value of the boolean function of the group of rounds `group`
+/// applied on `x, y, z`.  The degree of the result is at most 3.
+pub(crate) fn f<F: Field>(
+    group: usize,
+    x: &WordBits<F>,
+    y: &WordBits<F>,
+    z: &WordBits<F>,
+) -> Expression<F> {
+    let bits = (0..NUM_BITS_PER_WORD)
+        .map(|i| {
+            let (x, y, z) = (x[i].clone(), y[i].clone(), z[i].clone());
+            match group {
+                // x ^ y ^ z
+                0 => xor(xor(x, y), z),
+                // (x & y) | (!x & z)
+                1 => x.clone() * y + not(x) * z,
+                // (x | !y) ^ z
+                2 => xor(or(x, not(y)), z),
+                // (x & z) | (y & !z)
+                3 => x * z.clone() + y * not(z),
+                // x ^ (y | !z)
+                4 => xor(x, or(y, not(z))),
+                _ => unreachable!("there are only 5 groups of rounds"),
+            }
+        })
+        .collect::<Vec<_>>();
+    compose(&bits)
+}
+
+/// Return the value of the boolean function of the group of rounds `group`.
+pub(crate) fn f_value(group: usize, x: u32, y: u32, z: u32) -> u32 {
+    match group {
+        0 => x ^ y ^ z,
+        1 => (x & y) | (!x & z),
+        2 => (x | !y) ^ z,
+        3 => (x & z) | (y & !z),
+        4 => x ^ (y | !z),
+        _ => unreachable!("there are only 5 groups of rounds"),
+    }
+}
+
+/// Return the bytes of the message padded to a multiple of `RATE` bytes:
+/// the message, `0x80`, zeros and the bit length of the message in little
+/// endian.
+pub(crate) fn pad(bytes: &[u8]) -> Vec<u8> {
+    let mut padded = bytes.to_vec();
+    padded.push(0x80);
+    while padded.len() % RATE != RATE - NUM_BYTES_LENGTH {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&(8 * bytes.len() as u64).to_le_bytes());
+    padded
+}
+
+/// Return the number of blocks required to hash a message of `len` bytes.
+pub(crate) fn num_blocks(len: usize) -> usize {
+    (len + NUM_BYTES_LENGTH) / RATE + 1
+}
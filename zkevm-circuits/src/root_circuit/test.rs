@@ -2,7 +2,11 @@ use crate::{
     root_circuit::{compile, Config, Gwc, PoseidonTranscript, RootCircuit},
     super_circuit::{test::block_1tx, SuperCircuit},
 };
-use bus_mapping::circuit_input_builder::FixedCParams;
+use bus_mapping::{
+    circuit_input_builder::{CodeHashScheme, FixedCParams},
+    precompile::MODEXP_SIZE_LIMIT,
+};
+use eth_types::evm_types::Hardfork;
 use halo2_proofs::{
     circuit::Value,
     dev::MockProver,
@@ -24,14 +28,31 @@ fn test_root_circuit() {
         const TEST_MOCK_RANDOMNESS: u64 = 0x100;
         let circuits_params = FixedCParams {
             max_txs: 1,
+            max_signed_txs: 1,
             max_withdrawals: 5,
             max_calldata: 32,
+            max_access_list: 0,
+            max_auths: 0,
             max_rws: 256,
             max_copy_rows: 256,
             max_exp_steps: 256,
             max_bytecode: 512,
             max_evm_rows: 0,
             max_keccak_rows: 0,
+            max_poseidon_blocks: 0,
+            max_sha256_blocks: 0,
+            max_ripemd160_blocks: 0,
+            max_blake2f_blocks: 0,
+            max_sig_verifs: 0,
+            max_modexps: 0,
+            max_modexp_size: MODEXP_SIZE_LIMIT,
+            max_ec_adds: 0,
+            max_ec_muls: 0,
+            max_p256_verifs: 0,
+            hardfork: Hardfork::default(),
+            code_hash_scheme: CodeHashScheme::Keccak,
+            deposits: false,
+            p256_verify: false,
         };
         let (k, circuit, instance, _) =
             SuperCircuit::<_>::build(block_1tx(), circuits_params, TEST_MOCK_RANDOMNESS.into())
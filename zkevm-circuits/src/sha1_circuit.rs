@@ -0,0 +1,867 @@
+//! The SHA-1 circuit implementation.
+//!
+//! The circuit proves the SHA-1 hashes of the [`Sha1Table`], in which other
+//! circuits look up the digests of RLC'ed inputs, such as the legacy X.509
+//! certificates signed with sha1WithRSAEncryption.  Every block of 64 bytes
+//! of a padded input takes `ROWS_PER_BLOCK` rows:
+//!
+//! - 5 rows with the initial state of the block, the initial hash value for the first block of an
+//!   input and the digest of the previous block otherwise,
+//! - a row per round of the compression, that holds the bits of the new value of `a` and of the
+//!   word `w` of the message schedule.  The 16 first words are the bytes of the block, which are
+//!   accumulated into the input RLC and length until the padding,
+//! - 5 rows with the digest of the block, the last one being the row of the table for the last
+//!   block of an input.
+//!
+//! As `b` is the previous value of `a`, and `c, d, e` are the rotations by 30
+//! of the values of `a` before, the state is only made of the bits of `a` at
+//! the 5 previous rows.  The state rows hold the words of the hash value in
+//! the same form: `h4, h3, h2` rotated right by 30, `h1` and `h0`.
+
+mod param;
+mod util;
+
+#[cfg(any(test, feature = "test-circuits"))]
+mod dev;
+#[cfg(test)]
+mod test;
+#[cfg(feature = "test-circuits")]
+pub use dev::Sha1Circuit as TestSha1Circuit;
+
+use self::{param::*, util::*};
+use crate::{
+    evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    table::{LookupTable, Sha1Table},
+    util::{word, Challenges, SubCircuitConfig},
+};
+use eth_types::{Field, U256};
+use gadgets::util::{select, sum, Expr};
+use halo2_proofs::{
+    circuit::{Layouter, Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, VirtualCells,
+    },
+    poly::Rotation,
+};
+use itertools::{izip, Itertools};
+use log::error;
+use std::marker::PhantomData;
+
+/// Index of the word of the hash value held by each state row
+const STATE_ROW_WORDS: [usize; NUM_STATE_ROWS] = [4, 3, 2, 1, 0];
+
+/// Return the bits of the word of the hash value held by the state row
+/// `state_row` from the bits of the row.
+fn state_word_bits<F: Field>(bits: &WordBits<F>, state_row: usize) -> WordBits<F> {
+    if state_row < 3 {
+        rotate_left(bits, ROTATION_B)
+    } else {
+        bits.clone()
+    }
+}
+
+/// Return the value of the state row `state_row` of the hash value `hs`.
+fn state_row_value(hs: &[u32; 5], state_row: usize) -> u32 {
+    let h = hs[STATE_ROW_WORDS[state_row]];
+    if state_row < 3 {
+        h.rotate_right(ROTATION_B as u32)
+    } else {
+        h
+    }
+}
+
+/// Sha1CircuitConfig
+#[derive(Clone, Debug)]
+pub struct Sha1CircuitConfig<F> {
+    q_enable: Column<Fixed>,
+    q_first: Column<Fixed>,
+    q_block_start: Column<Fixed>,
+    q_init: Column<Fixed>,
+    q_round: Column<Fixed>,
+    q_input: Column<Fixed>,
+    q_length_start: Column<Fixed>,
+    q_length_end: Column<Fixed>,
+    q_digest: [Column<Fixed>; NUM_STATE_ROWS],
+    q_last: Column<Fixed>,
+    /// Selectors of the groups of rounds
+    q_groups: [Column<Fixed>; NUM_GROUPS],
+    round_cst: Column<Fixed>,
+    /// Initial hash value on the initial state rows
+    h_init: Column<Fixed>,
+    /// Bits of `a`, which hold the state on the state rows
+    a: [Column<Advice>; NUM_BITS_PER_WORD],
+    /// Bits of the words of the message schedule on the rounds
+    w: [Column<Advice>; NUM_BITS_PER_WORD],
+    carry: [Column<Advice>; 3],
+    is_paddings: [Column<Advice>; NUM_BYTES_PER_WORD],
+    data_rlcs: [Column<Advice>; NUM_BYTES_PER_WORD - 1],
+    is_final: Column<Advice>,
+    /// The columns for other circuits to lookup SHA-1 hash results
+    pub sha1_table: Sha1Table,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+pub struct Sha1CircuitConfigArgs<F: Field> {
+    /// Sha1Table
+    pub sha1_table: Sha1Table,
+    /// Challenges randomness
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for Sha1CircuitConfig<F> {
+    type ConfigArgs = Sha1CircuitConfigArgs<F>;
+
+    /// Return a new Sha1CircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            sha1_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let q_enable = meta.fixed_column();
+        let q_first = meta.fixed_column();
+        let q_block_start = meta.fixed_column();
+        let q_init = meta.fixed_column();
+        let q_round = meta.fixed_column();
+        let q_input = meta.fixed_column();
+        let q_length_start = meta.fixed_column();
+        let q_length_end = meta.fixed_column();
+        let q_digest = array_init::array_init(|_| meta.fixed_column());
+        let q_last = meta.fixed_column();
+        let q_groups = array_init::array_init(|_| meta.fixed_column());
+        let round_cst = meta.fixed_column();
+        let h_init = meta.fixed_column();
+
+        let a = array_init::array_init(|_| meta.advice_column());
+        let w = array_init::array_init(|_| meta.advice_column());
+        let carry = array_init::array_init(|_| meta.advice_column());
+        let is_paddings = array_init::array_init(|_| meta.advice_column());
+        let data_rlcs = array_init::array_init(|_| meta.advice_column_in(SecondPhase));
+        let is_final = meta.advice_column();
+
+        let is_enabled = sha1_table.is_enabled;
+        let data_rlc = sha1_table.input_rlc;
+        let length = sha1_table.input_len;
+        let hash_word = sha1_table.output;
+
+        let bits = |meta: &mut VirtualCells<F>,
+                    columns: &[Column<Advice>; NUM_BITS_PER_WORD],
+                    rot: i32|
+         -> WordBits<F> {
+            array_init::array_init(|i| meta.query_advice(columns[i], Rotation(rot)))
+        };
+        let value = |meta: &mut VirtualCells<F>, columns: &[Column<Advice>], rot: i32| {
+            compose(
+                &columns
+                    .iter()
+                    .map(|column| meta.query_advice(*column, Rotation(rot)))
+                    .collect_vec(),
+            )
+        };
+        let pow_of_two_32 = || (1u64 << NUM_BITS_PER_WORD).expr();
+        // A new hash is started on the first block, or when the previous block
+        // is the last one of its hash, as seen from the initial state rows.
+        let start_new_hash = |meta: &mut VirtualCells<F>| {
+            let q_first = meta.query_fixed(q_first, Rotation::cur());
+            q_first.clone()
+                + (1.expr() - q_first)
+                    * meta.query_advice(is_final, Rotation(-(NUM_STATE_ROWS as i32)))
+        };
+
+        meta.create_gate("boolean checks", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in a
+                .iter()
+                .chain(w.iter())
+                .chain(carry.iter())
+                .chain(is_paddings.iter())
+                .chain([is_final].iter())
+            {
+                cb.require_boolean("boolean", meta.query_advice(*column, Rotation::cur()));
+            }
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("is_final", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            cb.require_equal(
+                "is_final is the same for all the rows of a block",
+                meta.query_advice(is_final, Rotation::cur()),
+                meta.query_advice(is_final, Rotation::prev()),
+            );
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    - meta.query_fixed(q_block_start, Rotation::cur()),
+            )
+        });
+
+        meta.create_gate("initial state", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let start_new_hash = start_new_hash(meta);
+            cb.require_equal(
+                "initial value = H on a new hash, else the digest of the previous block",
+                value(meta, &a, 0),
+                select::expr(
+                    start_new_hash,
+                    meta.query_fixed(h_init, Rotation::cur()),
+                    value(meta, &a, -(NUM_STATE_ROWS as i32)),
+                ),
+            );
+            cb.gate(meta.query_fixed(q_init, Rotation::cur()))
+        });
+
+        meta.create_gate("message schedule", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let words = [-3, -8, -14, -16].map(|rot| bits(meta, &w, rot));
+            cb.require_equal(
+                "w = ROTL^1(w[-3] ^ w[-8] ^ w[-14] ^ w[-16])",
+                value(meta, &w, 0),
+                compose(&rotate_left(&xor_bits(&words), 1)),
+            );
+            cb.gate(
+                meta.query_fixed(q_round, Rotation::cur())
+                    - meta.query_fixed(q_input, Rotation::cur()),
+            )
+        });
+
+        meta.create_gate("round", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            // C, D, E are the previous values of A rotated by 30.
+            let [a_prev, b, c, d, e] = [-1, -2, -3, -4, -5].map(|rot| bits(meta, &a, rot));
+            let [c, d, e] = [c, d, e].map(|bits| rotate_left(&bits, ROTATION_B));
+            let f_bcd = sum::expr((0..NUM_GROUPS).map(|group| {
+                meta.query_fixed(q_groups[group], Rotation::cur()) * f(group, &b, &c, &d)
+            }));
+            cb.require_equal(
+                "A = ROTL^5(A) + f(B, C, D) + E + K + W",
+                value(meta, &a, 0) + value(meta, &carry, 0) * pow_of_two_32(),
+                compose(&rotate_left(&a_prev, ROTATION_A))
+                    + f_bcd
+                    + compose(&e)
+                    + meta.query_fixed(round_cst, Rotation::cur())
+                    + value(meta, &w, 0),
+            );
+            cb.gate(meta.query_fixed(q_round, Rotation::cur()))
+        });
+
+        // The state rows after the rounds hold the sum of the initial hash
+        // value and of the final state.  The word of the final state held by a
+        // state row is in the same form at the rotation `-5`, and the word of
+        // the initial hash value is on the initial state rows of the block.
+        meta.create_gate("digest", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for (state_row, q_digest) in q_digest.iter().enumerate() {
+                let word = |meta: &mut VirtualCells<F>, rot: i32| {
+                    compose(&state_word_bits(&bits(meta, &a, rot), state_row))
+                };
+                let h = word(meta, -((NUM_STATE_ROWS + NUM_ROUNDS) as i32));
+                let expected = h + word(meta, -(NUM_STATE_ROWS as i32));
+                cb.condition(meta.query_fixed(*q_digest, Rotation::cur()), |cb| {
+                    cb.require_equal(
+                        "digest = initial value + final state",
+                        word(meta, 0) + value(meta, &carry, 0) * pow_of_two_32(),
+                        expected,
+                    );
+                });
+            }
+            cb.gate(1.expr())
+        });
+
+        meta.create_gate("sha1 table", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+            let q_last = meta.query_fixed(q_last, Rotation::cur());
+            cb.condition(q_last.clone(), |cb| {
+                cb.require_equal(
+                    "the hash is enabled on its last block",
+                    meta.query_advice(is_enabled, Rotation::cur()),
+                    meta.query_advice(is_final, Rotation::cur()),
+                );
+                // The digest is serialized as the words h0..h4 in big endian,
+                // and is the last 20 bytes of the output word.
+                let words: [Expression<F>; 5] = array_init::array_init(|i| {
+                    let state_row = STATE_ROW_WORDS.iter().position(|word| *word == i).unwrap();
+                    let rot = state_row as i32 - (NUM_STATE_ROWS as i32 - 1);
+                    compose(&state_word_bits(&bits(meta, &a, rot), state_row))
+                });
+                cb.require_equal(
+                    "hash hi = h0",
+                    meta.query_advice(hash_word.hi(), Rotation::cur()),
+                    words[0].clone(),
+                );
+                cb.require_equal(
+                    "hash lo = h1..h4",
+                    meta.query_advice(hash_word.lo(), Rotation::cur()),
+                    words[1..]
+                        .iter()
+                        .fold(0.expr(), |acc, word| acc * pow_of_two_32() + word.clone()),
+                );
+            });
+            cb.condition(q_enable - q_last, |cb| {
+                cb.require_zero(
+                    "is_enabled only on the last row of a block",
+                    meta.query_advice(is_enabled, Rotation::cur()),
+                );
+            });
+            cb.gate(1.expr())
+        });
+
+        // The padding state, the input RLC and the length are reset at the
+        // start of a new hash, updated on the rows of the words of the block,
+        // and copied on the other rows.
+        let last_is_padding = |meta: &mut VirtualCells<F>| {
+            meta.query_advice(is_paddings[NUM_BYTES_PER_WORD - 1], Rotation::prev())
+        };
+        meta.create_gate("input state at the start of a block", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let start_new_hash = start_new_hash(meta);
+            for (name, column, column_prev) in [
+                (
+                    "last is_padding reset on a new hash",
+                    meta.query_advice(is_paddings[NUM_BYTES_PER_WORD - 1], Rotation::cur()),
+                    last_is_padding(meta),
+                ),
+                (
+                    "data_rlc reset on a new hash",
+                    meta.query_advice(data_rlc, Rotation::cur()),
+                    meta.query_advice(data_rlc, Rotation::prev()),
+                ),
+                (
+                    "length reset on a new hash",
+                    meta.query_advice(length, Rotation::cur()),
+                    meta.query_advice(length, Rotation::prev()),
+                ),
+            ] {
+                cb.require_equal(
+                    name,
+                    column,
+                    (1.expr() - start_new_hash.clone()) * column_prev,
+                );
+            }
+            cb.gate(meta.query_fixed(q_block_start, Rotation::cur()))
+        });
+        meta.create_gate("input state copy", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            cb.require_equal(
+                "last is_padding copied",
+                meta.query_advice(is_paddings[NUM_BYTES_PER_WORD - 1], Rotation::cur()),
+                last_is_padding(meta),
+            );
+            for column in [data_rlc, length] {
+                cb.require_equal(
+                    "input state copied",
+                    meta.query_advice(column, Rotation::cur()),
+                    meta.query_advice(column, Rotation::prev()),
+                );
+            }
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    - meta.query_fixed(q_block_start, Rotation::cur())
+                    - meta.query_fixed(q_input, Rotation::cur()),
+            )
+        });
+
+        // The bytes of a word in big endian and the padding flags of the bytes.
+        let input_bytes = |meta: &mut VirtualCells<F>| {
+            let w = bits(meta, &w, 0);
+            let bytes: [Expression<F>; NUM_BYTES_PER_WORD] = array_init::array_init(|i| {
+                let offset = (NUM_BYTES_PER_WORD - 1 - i) * NUM_BITS_PER_BYTE;
+                compose(&w[offset..offset + NUM_BITS_PER_BYTE])
+            });
+            let is_paddings = is_paddings.map(|column| meta.query_advice(column, Rotation::cur()));
+            let is_paddings_prev: [Expression<F>; NUM_BYTES_PER_WORD] =
+                array_init::array_init(|i| {
+                    if i == 0 {
+                        last_is_padding(meta)
+                    } else {
+                        is_paddings[i - 1].clone()
+                    }
+                });
+            (bytes, is_paddings, is_paddings_prev)
+        };
+        meta.create_gate("input", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let (bytes, is_paddings, is_paddings_prev) = input_bytes(meta);
+            let data_rlcs = [
+                vec![meta.query_advice(data_rlc, Rotation::prev())],
+                data_rlcs
+                    .iter()
+                    .map(|column| meta.query_advice(*column, Rotation::cur()))
+                    .collect(),
+                vec![meta.query_advice(data_rlc, Rotation::cur())],
+            ]
+            .concat();
+            for (i, (byte, is_padding, is_padding_prev)) in
+                izip!(&bytes, &is_paddings, &is_paddings_prev).enumerate()
+            {
+                let is_first_padding = is_padding.clone() - is_padding_prev.clone();
+                cb.require_boolean(
+                    "is_padding can only go from 0 to 1",
+                    is_first_padding.clone(),
+                );
+                cb.require_zero(
+                    "the first padding byte is 0x80",
+                    is_first_padding * (byte.clone() - 0x80.expr()),
+                );
+                cb.require_equal(
+                    "data_rlc accumulates the bytes before the padding",
+                    data_rlcs[i + 1].clone(),
+                    select::expr(
+                        is_padding.clone(),
+                        data_rlcs[i].clone(),
+                        data_rlcs[i].clone() * challenges.keccak_input() + byte.clone(),
+                    ),
+                );
+            }
+            cb.require_equal(
+                "length is the number of bytes before the padding",
+                meta.query_advice(length, Rotation::cur()),
+                meta.query_advice(length, Rotation::prev())
+                    + sum::expr(
+                        is_paddings
+                            .iter()
+                            .map(|is_padding| 1.expr() - is_padding.clone()),
+                    ),
+            );
+            cb.gate(meta.query_fixed(q_input, Rotation::cur()))
+        });
+        meta.create_gate("padding bytes", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let (bytes, _, is_paddings_prev) = input_bytes(meta);
+            for (byte, is_padding_prev) in bytes.iter().zip(is_paddings_prev.iter()) {
+                cb.require_zero(
+                    "the padding bytes after 0x80 are 0",
+                    is_padding_prev.clone() * byte.clone(),
+                );
+            }
+            // The length bytes of the last block are checked below.
+            let is_final = meta.query_advice(is_final, Rotation::cur());
+            cb.gate(
+                meta.query_fixed(q_input, Rotation::cur())
+                    - (meta.query_fixed(q_length_start, Rotation::cur())
+                        + meta.query_fixed(q_length_end, Rotation::cur()))
+                        * is_final,
+            )
+        });
+        meta.create_gate("length bytes", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let is_final = meta.query_advice(is_final, Rotation::cur());
+            cb.condition(meta.query_fixed(q_length_start, Rotation::cur()), |cb| {
+                // The padding starts before the length bytes in the last block, so
+                // that these are all padding bytes, and after them otherwise, so
+                // that the next block is the last one.
+                cb.require_equal(
+                    "the padding starts before the length bytes iff the block is the last one",
+                    last_is_padding(meta),
+                    is_final.clone(),
+                );
+            });
+            cb.condition(
+                meta.query_fixed(q_length_end, Rotation::cur()) * is_final,
+                |cb| {
+                    cb.require_equal(
+                        "the last 8 bytes of the last block are the bit length",
+                        value(meta, &w, -1) * pow_of_two_32() + value(meta, &w, 0),
+                        meta.query_advice(length, Rotation::cur()) * 8.expr(),
+                    );
+                },
+            );
+            cb.gate(1.expr())
+        });
+
+        Self {
+            q_enable,
+            q_first,
+            q_block_start,
+            q_init,
+            q_round,
+            q_input,
+            q_length_start,
+            q_length_end,
+            q_digest,
+            q_last,
+            q_groups,
+            round_cst,
+            h_init,
+            a,
+            w,
+            carry,
+            is_paddings,
+            data_rlcs,
+            is_final,
+            sha1_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Witness values of a row of the SHA-1 circuit
+#[derive(Clone, Debug)]
+pub(crate) struct Sha1Row<F> {
+    a: u32,
+    w: u32,
+    carry: u64,
+    is_paddings: [bool; NUM_BYTES_PER_WORD],
+    data_rlcs: [Value<F>; NUM_BYTES_PER_WORD - 1],
+    is_final: bool,
+    is_enabled: bool,
+    data_rlc: Value<F>,
+    length: usize,
+    hash: word::Word<Value<F>>,
+}
+
+impl<F: Field> Sha1Row<F> {
+    /// Return a row with the input state of `is_padding`, `data_rlc` and
+    /// `length`, and zero values.
+    fn new(is_final: bool, is_padding: bool, data_rlc: Value<F>, length: usize) -> Self {
+        Self {
+            a: 0,
+            w: 0,
+            carry: 0,
+            is_paddings: [is_padding; NUM_BYTES_PER_WORD],
+            data_rlcs: [data_rlc; NUM_BYTES_PER_WORD - 1],
+            is_final,
+            is_enabled: false,
+            data_rlc,
+            length,
+            hash: word::Word::new([Value::known(F::ZERO); 2]),
+        }
+    }
+}
+
+/// Append the rows of the SHA-1 hash of `bytes` to `rows`.
+fn sha1<F: Field>(rows: &mut Vec<Sha1Row<F>>, bytes: &[u8], challenges: Challenges<Value<F>>) {
+    let padded = pad(bytes);
+    let num_blocks = padded.len() / RATE;
+
+    let mut hs = H;
+    let mut is_padding = false;
+    let mut data_rlc = Value::known(F::ZERO);
+    let mut length = 0;
+    for (idx, block) in padded.chunks(RATE).enumerate() {
+        let is_final = idx == num_blocks - 1;
+
+        // Initial state
+        let mut xs = Vec::with_capacity(NUM_STATE_ROWS + NUM_ROUNDS);
+        for state_row in 0..NUM_STATE_ROWS {
+            let a = state_row_value(&hs, state_row);
+            xs.push(a);
+            rows.push(Sha1Row {
+                a,
+                ..Sha1Row::new(is_final, is_padding, data_rlc, length)
+            });
+        }
+
+        // Message schedule
+        let mut ws = block
+            .chunks(NUM_BYTES_PER_WORD)
+            .map(|word| u32::from_be_bytes(word.try_into().unwrap()))
+            .collect_vec();
+        for t in NUM_WORDS_TO_ABSORB..NUM_ROUNDS {
+            ws.push((ws[t - 3] ^ ws[t - 8] ^ ws[t - 14] ^ ws[t - 16]).rotate_left(1));
+        }
+
+        // Rounds
+        for t in 0..NUM_ROUNDS {
+            let group = t / NUM_ROUNDS_PER_GROUP;
+            let prev = |rot: usize| xs[xs.len() - rot];
+            let rotated = |rot: usize| prev(rot).rotate_left(ROTATION_B as u32);
+            let (a, b, c, d, e) = (prev(1), prev(2), rotated(3), rotated(4), rotated(5));
+            let new_a = a.rotate_left(ROTATION_A as u32) as u64
+                + f_value(group, b, c, d) as u64
+                + e as u64
+                + ROUND_CST[group] as u64
+                + ws[t] as u64;
+            xs.push(new_a as u32);
+
+            let mut row = Sha1Row::new(is_final, is_padding, data_rlc, length);
+            if t < NUM_WORDS_TO_ABSORB {
+                let bytes_offset = idx * RATE + t * NUM_BYTES_PER_WORD;
+                for (i, byte) in block[t * NUM_BYTES_PER_WORD..(t + 1) * NUM_BYTES_PER_WORD]
+                    .iter()
+                    .enumerate()
+                {
+                    is_padding = bytes_offset + i >= bytes.len();
+                    if !is_padding {
+                        data_rlc = data_rlc * challenges.keccak_input()
+                            + Value::known(F::from(*byte as u64));
+                        length += 1;
+                    }
+                    row.is_paddings[i] = is_padding;
+                    if i < NUM_BYTES_PER_WORD - 1 {
+                        row.data_rlcs[i] = data_rlc;
+                    }
+                }
+                row.data_rlc = data_rlc;
+                row.length = length;
+            }
+            rows.push(Sha1Row {
+                a: new_a as u32,
+                w: ws[t],
+                carry: new_a >> NUM_BITS_PER_WORD,
+                ..row
+            });
+        }
+
+        // Digest, with the final state in the order A, B, C, D, E.
+        let finals = {
+            let prev = |rot: usize| xs[xs.len() - rot];
+            let rotated = |rot: usize| prev(rot).rotate_left(ROTATION_B as u32);
+            [prev(1), prev(2), rotated(3), rotated(4), rotated(5)]
+        };
+        let sums: [u64; 5] = array_init::array_init(|i| hs[i] as u64 + finals[i] as u64);
+        hs = sums.map(|sum| sum as u32);
+        let digest = hs.iter().flat_map(|h| h.to_be_bytes()).collect_vec();
+        let hash = word::Word::<F>::from(U256::from_big_endian(&digest)).map(Value::known);
+        for state_row in 0..NUM_STATE_ROWS {
+            let is_last = state_row == NUM_STATE_ROWS - 1;
+            let row = Sha1Row::new(is_final, is_padding, data_rlc, length);
+            rows.push(Sha1Row {
+                a: state_row_value(&hs, state_row),
+                carry: sums[STATE_ROW_WORDS[state_row]] >> NUM_BITS_PER_WORD,
+                is_enabled: is_last && is_final,
+                hash: if is_last { hash } else { row.hash },
+                ..row
+            });
+        }
+    }
+}
+
+/// Return the rows of the SHA-1 hashes of `inputs`, padded with hashes of the
+/// empty input to `max_blocks` blocks.
+pub(crate) fn multi_sha1<F: Field>(
+    inputs: &[Vec<u8>],
+    challenges: Challenges<Value<F>>,
+    max_blocks: usize,
+) -> Result<Vec<Sha1Row<F>>, Error> {
+    let num_blocks: usize = inputs.iter().map(|input| num_blocks(input.len())).sum();
+    if num_blocks > max_blocks {
+        error!(
+            "number of blocks of the inputs = {} > max_blocks = {}",
+            num_blocks, max_blocks
+        );
+        return Err(Error::Synthesis);
+    }
+
+    let mut rows = Vec::with_capacity(max_blocks * ROWS_PER_BLOCK);
+    for input in inputs {
+        sha1(&mut rows, input, challenges);
+    }
+    for _ in num_blocks..max_blocks {
+        sha1(&mut rows, &[], challenges);
+    }
+    Ok(rows)
+}
+
+impl<F: Field> Sha1CircuitConfig<F> {
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        witness: &[Sha1Row<F>],
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "assign sha1 rows",
+            |mut region| {
+                for (offset, row) in witness.iter().enumerate() {
+                    self.set_row(&mut region, offset, row)?;
+                }
+                self.sha1_table.annotate_columns_in_region(&mut region);
+                self.annotate_circuit(&mut region);
+                Ok(())
+            },
+        )
+    }
+
+    fn set_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &Sha1Row<F>,
+    ) -> Result<(), Error> {
+        // Fixed selectors, which only depend on the position of the row in its
+        // block.
+        let row_idx = offset % ROWS_PER_BLOCK;
+        let round = row_idx
+            .checked_sub(NUM_STATE_ROWS)
+            .filter(|t| *t < NUM_ROUNDS);
+        let digest_row = row_idx.checked_sub(NUM_STATE_ROWS + NUM_ROUNDS);
+        let group = round.map(|t| t / NUM_ROUNDS_PER_GROUP);
+        let mut fixed = vec![
+            ("q_enable", self.q_enable, true),
+            ("q_first", self.q_first, offset < NUM_STATE_ROWS),
+            ("q_block_start", self.q_block_start, row_idx == 0),
+            ("q_init", self.q_init, row_idx < NUM_STATE_ROWS),
+            ("q_round", self.q_round, round.is_some()),
+            (
+                "q_input",
+                self.q_input,
+                matches!(round, Some(t) if t < NUM_WORDS_TO_ABSORB),
+            ),
+            (
+                "q_length_start",
+                self.q_length_start,
+                round == Some(NUM_WORDS_TO_ABSORB - 2),
+            ),
+            (
+                "q_length_end",
+                self.q_length_end,
+                round == Some(NUM_WORDS_TO_ABSORB - 1),
+            ),
+            ("q_last", self.q_last, row_idx == ROWS_PER_BLOCK - 1),
+        ];
+        for (state_row, column) in self.q_digest.iter().enumerate() {
+            fixed.push(("q_digest", *column, digest_row == Some(state_row)));
+        }
+        for (idx, column) in self.q_groups.iter().enumerate() {
+            fixed.push(("q_group", *column, group == Some(idx)));
+        }
+        for (name, column, value) in fixed {
+            region.assign_fixed(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(F::from(value as u64)),
+            )?;
+        }
+        region.assign_fixed(
+            || format!("assign round cst {}", offset),
+            self.round_cst,
+            offset,
+            || Value::known(F::from(group.map_or(0, |group| ROUND_CST[group]) as u64)),
+        )?;
+        let h_init = if row_idx < NUM_STATE_ROWS {
+            state_row_value(&H, row_idx)
+        } else {
+            0
+        };
+        region.assign_fixed(
+            || format!("assign h init {}", offset),
+            self.h_init,
+            offset,
+            || Value::known(F::from(h_init as u64)),
+        )?;
+
+        // Bits
+        for (name, columns, value) in [
+            ("a", &self.a[..], row.a as u64),
+            ("w", &self.w[..], row.w as u64),
+            ("carry", &self.carry[..], row.carry),
+        ] {
+            for (i, column) in columns.iter().enumerate() {
+                region.assign_advice(
+                    || format!("assign {} bit {} {}", name, i, offset),
+                    *column,
+                    offset,
+                    || Value::known(F::from((value >> i) & 1)),
+                )?;
+            }
+        }
+
+        // Input state
+        for (column, is_padding) in self.is_paddings.iter().zip(row.is_paddings) {
+            region.assign_advice(
+                || format!("assign is_padding {}", offset),
+                *column,
+                offset,
+                || Value::known(F::from(is_padding as u64)),
+            )?;
+        }
+        for (column, data_rlc) in self.data_rlcs.iter().zip(row.data_rlcs) {
+            region.assign_advice(
+                || format!("assign data_rlc {}", offset),
+                *column,
+                offset,
+                || data_rlc,
+            )?;
+        }
+        region.assign_advice(
+            || format!("assign is_final {}", offset),
+            self.is_final,
+            offset,
+            || Value::known(F::from(row.is_final as u64)),
+        )?;
+
+        for (column, value) in <Sha1Table as LookupTable<F>>::advice_columns(&self.sha1_table)
+            .into_iter()
+            .zip_eq([
+                Value::known(F::from(row.is_enabled as u64)),
+                row.data_rlc,
+                Value::known(F::from(row.length as u64)),
+                row.hash.lo(),
+                row.hash.hi(),
+            ])
+        {
+            region.assign_advice(
+                || format!("assign sha1 table {}", offset),
+                column,
+                offset,
+                || value,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn annotate_circuit(&self, region: &mut Region<F>) {
+        region.name_column(|| "SHA1_q_enable", self.q_enable);
+        region.name_column(|| "SHA1_q_first", self.q_first);
+        region.name_column(|| "SHA1_q_block_start", self.q_block_start);
+        region.name_column(|| "SHA1_q_init", self.q_init);
+        region.name_column(|| "SHA1_q_round", self.q_round);
+        region.name_column(|| "SHA1_q_input", self.q_input);
+        region.name_column(|| "SHA1_q_last", self.q_last);
+        region.name_column(|| "SHA1_is_final", self.is_final);
+    }
+}
+
+/// SHA-1 circuit, proving the hashes of the Sha1Table
+#[derive(Clone, Default, Debug)]
+pub struct Sha1Circuit<F: Field> {
+    /// Max number of blocks of 64 bytes of the padded inputs
+    pub max_blocks: usize,
+    /// Inputs to hash
+    pub inputs: Vec<Vec<u8>>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> Sha1Circuit<F> {
+    /// Return a new Sha1Circuit with `max_blocks` blocks.
+    pub fn new(max_blocks: usize, inputs: Vec<Vec<u8>>) -> Self {
+        Self {
+            max_blocks,
+            inputs,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the number of unusable rows of the circuit
+    pub fn unusable_rows() -> usize {
+        // The bits of `a` are queried at 7 distinct rotations, so returns 10
+        // unusable rows.
+        10
+    }
+
+    /// Return the minimum number of rows required to prove `max_blocks`
+    /// blocks.
+    pub fn min_num_rows(max_blocks: usize) -> usize {
+        max_blocks * ROWS_PER_BLOCK
+    }
+
+    /// Return the number of blocks required to hash `inputs`.
+    pub fn num_blocks(inputs: &[Vec<u8>]) -> usize {
+        inputs.iter().map(|input| num_blocks(input.len())).sum()
+    }
+
+    /// Make the assignments to the Sha1Circuit
+    pub fn synthesize_sub(
+        &self,
+        config: &Sha1CircuitConfig<F>,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let witness = multi_sha1(&self.inputs, *challenges, self.max_blocks)?;
+        config.assign(layouter, &witness)
+    }
+}
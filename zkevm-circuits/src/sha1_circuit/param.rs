@@ -0,0 +1,30 @@
+pub(crate) const MAX_DEGREE: usize = 5;
+
+pub(crate) const NUM_BITS_PER_BYTE: usize = 8;
+pub(crate) const NUM_BYTES_PER_WORD: usize = 4;
+pub(crate) const NUM_BITS_PER_WORD: usize = NUM_BYTES_PER_WORD * NUM_BITS_PER_BYTE;
+pub(crate) const NUM_ROUNDS: usize = 80;
+/// Number of rounds using the same boolean function and constant
+pub(crate) const NUM_ROUNDS_PER_GROUP: usize = 20;
+pub(crate) const NUM_GROUPS: usize = NUM_ROUNDS / NUM_ROUNDS_PER_GROUP;
+pub(crate) const NUM_WORDS_TO_ABSORB: usize = 16;
+pub(crate) const RATE: usize = NUM_WORDS_TO_ABSORB * NUM_BYTES_PER_WORD;
+/// Number of bytes of the message length appended by the padding
+pub(crate) const NUM_BYTES_LENGTH: usize = 8;
+
+/// The state is kept as the last 5 values of `a`, from which `b, c, d, e`
+/// are derived: the initial state of a block takes 5 rows before the rounds,
+/// and its digest 5 rows after them.
+pub(crate) const NUM_STATE_ROWS: usize = 5;
+pub(crate) const ROWS_PER_BLOCK: usize = NUM_STATE_ROWS + NUM_ROUNDS + NUM_STATE_ROWS;
+
+/// Rotation applied to `b` to get the next `c`
+pub(crate) const ROTATION_B: usize = 30;
+/// Rotation applied to `a` in the rounds
+pub(crate) const ROTATION_A: usize = 5;
+
+/// Constants of the rounds by group
+pub(crate) const ROUND_CST: [u32; NUM_GROUPS] = [0x5a827999, 0x6ed9eba1, 0x8f1bbcdc, 0xca62c1d6];
+
+/// Initial hash value
+pub(crate) const H: [u32; 5] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
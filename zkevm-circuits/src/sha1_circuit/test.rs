@@ -0,0 +1,123 @@
+use super::*;
+use crate::{
+    evm_circuit::util::rlc,
+    util::{log2_ceil, unusable_rows},
+};
+use eth_types::rsa_types;
+use halo2_proofs::{
+    dev::{CellValue, MockProver},
+    halo2curves::bn256::Fr,
+    plonk::Circuit,
+};
+
+#[test]
+fn sha1_circuit_unusable_rows() {
+    assert_eq!(
+        Sha1Circuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, Sha1Circuit::<Fr>>(()),
+    )
+}
+
+fn run(circuit: &Sha1Circuit<Fr>) -> MockProver<Fr> {
+    let k = log2_ceil(
+        Sha1Circuit::<Fr>::unusable_rows() + Sha1Circuit::<Fr>::min_num_rows(circuit.max_blocks),
+    );
+    MockProver::<Fr>::run(k, circuit, vec![]).unwrap()
+}
+
+fn assigned<F: Field>(cv: &CellValue<F>) -> F {
+    match *cv {
+        CellValue::Assigned(f) => f,
+        _ => F::ZERO,
+    }
+}
+
+fn verify(max_blocks: usize, inputs: Vec<Vec<u8>>) {
+    let circuit = Sha1Circuit::<Fr>::new(max_blocks, inputs.clone());
+    let prover = run(&circuit);
+    assert_eq!(prover.verify(), Ok(()));
+
+    // Extract the rows of the table with hash results.
+    let (config, challenges) = Sha1Circuit::configure(&mut ConstraintSystem::<Fr>::default());
+    let input_challenge = prover.get_challenge(challenges.keccak_input());
+    let table = &config.sha1_table;
+    let hashes = izip!(
+        prover.advice_values(table.is_enabled),
+        prover.advice_values(table.input_rlc),
+        prover.advice_values(table.input_len),
+        prover.advice_values(table.output.lo()),
+        prover.advice_values(table.output.hi()),
+    )
+    .filter(|(is_enabled, ..)| assigned(*is_enabled) == Fr::from(1))
+    .map(|(_, input_rlc, input_len, lo, hi)| {
+        (
+            assigned(input_rlc),
+            assigned(input_len),
+            assigned(lo),
+            assigned(hi),
+        )
+    })
+    .collect_vec();
+
+    // The inputs are followed by the hashes of the empty input up to
+    // `max_blocks` blocks.
+    let padding = vec![vec![]; max_blocks - Sha1Circuit::<Fr>::num_blocks(&inputs)];
+    assert_eq!(hashes.len(), inputs.len() + padding.len());
+    for (input, hash) in inputs.iter().chain(padding.iter()).zip(hashes) {
+        let digest = U256::from_big_endian(&rsa_types::sha1(input));
+        let (lo, hi) = word::Word::<Fr>::from(digest).to_lo_hi();
+        input_challenge
+            .map(|challenge| rlc::value(input.iter().rev(), challenge))
+            .assert_if_known(|input_rlc| *input_rlc == hash.0);
+        assert_eq!(
+            (hash.1, hash.2, hash.3),
+            (Fr::from(input.len() as u64), lo, hi)
+        );
+    }
+}
+
+#[test]
+fn sha1_circuit_simple() {
+    let inputs = [0, 3, 55, 56, 63, 64, 119, 120, 200]
+        .into_iter()
+        .map(|len| (0..len).map(|i| (1 + 3 * i) as u8).collect_vec())
+        .collect_vec();
+    verify(Sha1Circuit::<Fr>::num_blocks(&inputs) + 2, inputs);
+}
+
+#[test]
+fn sha1_circuit_known_digest() {
+    // FIPS 180-2 appendix A.1
+    assert_eq!(
+        rsa_types::sha1(b"abc"),
+        [
+            0xa9, 0x99, 0x3e, 0x36, 0x47, 0x06, 0x81, 0x6a, 0xba, 0x3e, 0x25, 0x71, 0x78, 0x50,
+            0xc2, 0x6c, 0x9c, 0xd0, 0xd8, 0x9d,
+        ]
+    );
+    verify(1, vec![b"abc".to_vec()]);
+}
+
+#[test]
+fn sha1_circuit_no_inputs() {
+    verify(1, vec![]);
+}
+
+#[test]
+fn sha1_circuit_exceeded_max_blocks() {
+    // 56 bytes don't fit in a block with the padding.
+    let circuit = Sha1Circuit::<Fr>::new(1, vec![vec![0; 56]]);
+    let k = log2_ceil(
+        Sha1Circuit::<Fr>::unusable_rows() + Sha1Circuit::<Fr>::min_num_rows(circuit.max_blocks),
+    );
+    assert!(MockProver::<Fr>::run(k, &circuit, vec![]).is_err());
+}
+
+#[test]
+fn sha1_circuit_variadic_size_check() {
+    // The fixed columns only depend on `max_blocks`.
+    let prover_1 = run(&Sha1Circuit::<Fr>::new(4, vec![vec![1, 2, 3]]));
+    let prover_2 = run(&Sha1Circuit::<Fr>::new(4, vec![vec![4; 64], vec![5; 20]]));
+    assert_eq!(prover_1.fixed(), prover_2.fixed());
+    assert_eq!(prover_1.permutation(), prover_2.permutation());
+}
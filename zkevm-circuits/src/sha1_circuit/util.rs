@@ -0,0 +1,90 @@
+use super::param::*;
+use eth_types::Field;
+use gadgets::util::Expr;
+use halo2_proofs::plonk::Expression;
+
+/// Bits of a word in little-endian
+pub(crate) type WordBits<F> = [Expression<F>; NUM_BITS_PER_WORD];
+
+/// Return the value of little-endian bits.
+pub(crate) fn compose<F: Field>(bits: &[Expression<F>]) -> Expression<F> {
+    bits.iter()
+        .rev()
+        .fold(0.expr(), |acc, bit| acc * 2.expr() + bit.clone())
+}
+
+/// Return the bits of `bits` rotated left by `n`.
+pub(crate) fn rotate_left<F: Field>(bits: &WordBits<F>, n: usize) -> WordBits<F> {
+    array_init::array_init(|i| bits[(i + NUM_BITS_PER_WORD - n) % NUM_BITS_PER_WORD].clone())
+}
+
+fn xor<F: Field>(a: Expression<F>, b: Expression<F>) -> Expression<F> {
+    a.clone() + b.clone() - 2.expr() * a * b
+}
+
+/// Return the bits of the bitwise xor of the words.  Each xor of 2 bits is of
+/// degree 2, so the degree of the bits is the number of words.
+pub(crate) fn xor_bits<F: Field>(words: &[WordBits<F>]) -> WordBits<F> {
+    array_init::array_init(|i| {
+        words
+            .iter()
+            .skip(1)
+            .fold(words[0][i].clone(), |acc, word| xor(acc, word[i].clone()))
+    })
+}
+
+/// Return the value of the boolean function of the group of rounds `group`
+/// applied on `b, c, d`.  The degree of the result is at most 3.
+pub(crate) fn f<F: Field>(
+    group: usize,
+    b: &WordBits<F>,
+    c: &WordBits<F>,
+    d: &WordBits<F>,
+) -> Expression<F> {
+    let bits = (0..NUM_BITS_PER_WORD)
+        .map(|i| {
+            let (b, c, d) = (b[i].clone(), c[i].clone(), d[i].clone());
+            match group {
+                // Ch(b, c, d) = (b & c) ^ (!b & d)
+                0 => b.clone() * c + (1.expr() - b) * d,
+                // Parity(b, c, d) = b ^ c ^ d
+                1 | 3 => xor(xor(b, c), d),
+                // Maj(b, c, d) = (b & c) ^ (b & d) ^ (c & d)
+                2 => {
+                    b.clone() * c.clone() + b.clone() * d.clone() + c.clone() * d.clone()
+                        - 2.expr() * b * c * d
+                }
+                _ => unreachable!("there are only 4 groups of rounds"),
+            }
+        })
+        .collect::<Vec<_>>();
+    compose(&bits)
+}
+
+/// Return the value of the boolean function of the group of rounds `group`.
+pub(crate) fn f_value(group: usize, b: u32, c: u32, d: u32) -> u32 {
+    match group {
+        0 => (b & c) ^ (!b & d),
+        1 | 3 => b ^ c ^ d,
+        2 => (b & c) ^ (b & d) ^ (c & d),
+        _ => unreachable!("there are only 4 groups of rounds"),
+    }
+}
+
+/// Return the bytes of the message padded to a multiple of `RATE` bytes:
+/// the message, `0x80`, zeros and the bit length of the message in big
+/// endian.
+pub(crate) fn pad(bytes: &[u8]) -> Vec<u8> {
+    let mut padded = bytes.to_vec();
+    padded.push(0x80);
+    while padded.len() % RATE != RATE - NUM_BYTES_LENGTH {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&(8 * bytes.len() as u64).to_be_bytes());
+    padded
+}
+
+/// Return the number of blocks required to hash a message of `len` bytes.
+pub(crate) fn num_blocks(len: usize) -> usize {
+    (len + NUM_BYTES_LENGTH) / RATE + 1
+}
@@ -0,0 +1,882 @@
+//! The SHA-256 circuit implementation.
+//!
+//! The circuit proves the SHA-256 hashes of the [`Sha256Table`], in which
+//! other circuits look up the digests of RLC'ed inputs.  Every block of 64
+//! bytes of a padded input takes `ROWS_PER_BLOCK` rows:
+//!
+//! - 4 rows with the initial state of the block, the initial hash value for the first block of an
+//!   input and the digest of the previous block otherwise,
+//! - a row per round of the compression, that holds the bits of the new values of `a` and `e` and
+//!   of the word `w` of the message schedule.  The 16 first words are the bytes of the block, which
+//!   are accumulated into the input RLC and length until the padding,
+//! - 4 rows with the digest of the block, the last one being the row of the table for the last
+//!   block of an input.
+//!
+//! As `b, c, d` and `f, g, h` are the previous values of `a` and `e`, the
+//! state is only made of the bits of `a` and `e` at the 4 previous rows.
+
+mod param;
+mod util;
+
+#[cfg(any(test, feature = "test-circuits"))]
+mod dev;
+#[cfg(test)]
+mod test;
+#[cfg(feature = "test-circuits")]
+pub use dev::Sha256Circuit as TestSha256Circuit;
+
+use self::{param::*, util::*};
+use crate::{
+    evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    table::{LookupTable, Sha256Table},
+    util::{word, Challenges, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use eth_types::{Field, U256};
+use gadgets::util::{select, sum, Expr};
+use halo2_proofs::{
+    circuit::{Layouter, Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, VirtualCells,
+    },
+    poly::Rotation,
+};
+use itertools::{izip, Itertools};
+use log::error;
+use std::marker::PhantomData;
+
+/// Sha256CircuitConfig
+#[derive(Clone, Debug)]
+pub struct Sha256CircuitConfig<F> {
+    q_enable: Column<Fixed>,
+    q_first: Column<Fixed>,
+    q_block_start: Column<Fixed>,
+    q_init: Column<Fixed>,
+    q_round: Column<Fixed>,
+    q_input: Column<Fixed>,
+    q_length_start: Column<Fixed>,
+    q_length_end: Column<Fixed>,
+    q_digest: Column<Fixed>,
+    q_last: Column<Fixed>,
+    round_cst: Column<Fixed>,
+    /// Initial hash value of `a` and `e` on the initial state rows
+    h_init: [Column<Fixed>; 2],
+    a: [Column<Advice>; NUM_BITS_PER_WORD],
+    e: [Column<Advice>; NUM_BITS_PER_WORD],
+    w: [Column<Advice>; NUM_BITS_PER_WORD],
+    carry_a: [Column<Advice>; 3],
+    carry_e: [Column<Advice>; 3],
+    carry_w: [Column<Advice>; 2],
+    is_paddings: [Column<Advice>; NUM_BYTES_PER_WORD],
+    data_rlcs: [Column<Advice>; NUM_BYTES_PER_WORD - 1],
+    is_final: Column<Advice>,
+    /// The columns for other circuits to lookup SHA-256 hash results
+    pub sha256_table: Sha256Table,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+pub struct Sha256CircuitConfigArgs<F: Field> {
+    /// Sha256Table
+    pub sha256_table: Sha256Table,
+    /// Challenges randomness
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for Sha256CircuitConfig<F> {
+    type ConfigArgs = Sha256CircuitConfigArgs<F>;
+
+    /// Return a new Sha256CircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            sha256_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let q_enable = meta.fixed_column();
+        let q_first = meta.fixed_column();
+        let q_block_start = meta.fixed_column();
+        let q_init = meta.fixed_column();
+        let q_round = meta.fixed_column();
+        let q_input = meta.fixed_column();
+        let q_length_start = meta.fixed_column();
+        let q_length_end = meta.fixed_column();
+        let q_digest = meta.fixed_column();
+        let q_last = meta.fixed_column();
+        let round_cst = meta.fixed_column();
+        let h_init = array_init::array_init(|_| meta.fixed_column());
+
+        let a = array_init::array_init(|_| meta.advice_column());
+        let e = array_init::array_init(|_| meta.advice_column());
+        let w = array_init::array_init(|_| meta.advice_column());
+        let carry_a = array_init::array_init(|_| meta.advice_column());
+        let carry_e = array_init::array_init(|_| meta.advice_column());
+        let carry_w = array_init::array_init(|_| meta.advice_column());
+        let is_paddings = array_init::array_init(|_| meta.advice_column());
+        let data_rlcs = array_init::array_init(|_| meta.advice_column_in(SecondPhase));
+        let is_final = meta.advice_column();
+
+        let is_enabled = sha256_table.is_enabled;
+        let data_rlc = sha256_table.input_rlc;
+        let length = sha256_table.input_len;
+        let hash_word = sha256_table.output;
+
+        let bits = |meta: &mut VirtualCells<F>,
+                    columns: &[Column<Advice>; NUM_BITS_PER_WORD],
+                    rot: i32|
+         -> WordBits<F> {
+            array_init::array_init(|i| meta.query_advice(columns[i], Rotation(rot)))
+        };
+        let carry = |meta: &mut VirtualCells<F>, columns: &[Column<Advice>], rot: i32| {
+            compose(
+                &columns
+                    .iter()
+                    .map(|column| meta.query_advice(*column, Rotation(rot)))
+                    .collect_vec(),
+            )
+        };
+        let pow_of_two_32 = || (1u64 << NUM_BITS_PER_WORD).expr();
+        // A new hash is started on the first block, or when the previous block
+        // is the last one of its hash, as seen from the initial state rows.
+        let start_new_hash = |meta: &mut VirtualCells<F>| {
+            let q_first = meta.query_fixed(q_first, Rotation::cur());
+            q_first.clone()
+                + (1.expr() - q_first)
+                    * meta.query_advice(is_final, Rotation(-(NUM_STATE_ROWS as i32)))
+        };
+
+        meta.create_gate("boolean checks", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in a
+                .iter()
+                .chain(e.iter())
+                .chain(w.iter())
+                .chain(carry_a.iter())
+                .chain(carry_e.iter())
+                .chain(carry_w.iter())
+                .chain(is_paddings.iter())
+                .chain([is_final].iter())
+            {
+                cb.require_boolean("boolean", meta.query_advice(*column, Rotation::cur()));
+            }
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("is_final", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            cb.require_equal(
+                "is_final is the same for all the rows of a block",
+                meta.query_advice(is_final, Rotation::cur()),
+                meta.query_advice(is_final, Rotation::prev()),
+            );
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    - meta.query_fixed(q_block_start, Rotation::cur()),
+            )
+        });
+
+        meta.create_gate("initial state", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let start_new_hash = start_new_hash(meta);
+            for (columns, h_init) in [(&a, h_init[0]), (&e, h_init[1])] {
+                cb.require_equal(
+                    "initial value = H on a new hash, else the digest of the previous block",
+                    compose(&bits(meta, columns, 0)),
+                    select::expr(
+                        start_new_hash.clone(),
+                        meta.query_fixed(h_init, Rotation::cur()),
+                        compose(&bits(meta, columns, -(NUM_STATE_ROWS as i32))),
+                    ),
+                );
+            }
+            cb.gate(meta.query_fixed(q_init, Rotation::cur()))
+        });
+
+        meta.create_gate("round", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let [a_prev, b, c, d] = [-1, -2, -3, -4].map(|rot| bits(meta, &a, rot));
+            let [e_prev, f, g, h] = [-1, -2, -3, -4].map(|rot| bits(meta, &e, rot));
+            let t1 = compose(&h)
+                + sigma_upper_1(&e_prev)
+                + ch(&e_prev, &f, &g)
+                + meta.query_fixed(round_cst, Rotation::cur())
+                + compose(&bits(meta, &w, 0));
+            let t2 = sigma_upper_0(&a_prev) + maj(&a_prev, &b, &c);
+            cb.require_equal(
+                "e = d + t1",
+                compose(&bits(meta, &e, 0)) + carry(meta, &carry_e, 0) * pow_of_two_32(),
+                compose(&d) + t1.clone(),
+            );
+            cb.require_equal(
+                "a = t1 + t2",
+                compose(&bits(meta, &a, 0)) + carry(meta, &carry_a, 0) * pow_of_two_32(),
+                t1 + t2,
+            );
+            cb.gate(meta.query_fixed(q_round, Rotation::cur()))
+        });
+
+        meta.create_gate("message schedule", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            cb.require_equal(
+                "w = σ1(w[-2]) + w[-7] + σ0(w[-15]) + w[-16]",
+                compose(&bits(meta, &w, 0)) + carry(meta, &carry_w, 0) * pow_of_two_32(),
+                sigma_lower_1(&bits(meta, &w, -2))
+                    + compose(&bits(meta, &w, -7))
+                    + sigma_lower_0(&bits(meta, &w, -15))
+                    + compose(&bits(meta, &w, -16)),
+            );
+            cb.gate(
+                meta.query_fixed(q_round, Rotation::cur())
+                    - meta.query_fixed(q_input, Rotation::cur()),
+            )
+        });
+
+        meta.create_gate("digest", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for (columns, carry_columns) in [(&a, &carry_a[..]), (&e, &carry_e[..])] {
+                cb.require_equal(
+                    "digest = initial state + state after the last round",
+                    compose(&bits(meta, columns, 0))
+                        + carry(meta, carry_columns, 0) * pow_of_two_32(),
+                    compose(&bits(meta, columns, -(NUM_STATE_ROWS as i32)))
+                        + compose(&bits(
+                            meta,
+                            columns,
+                            -((ROWS_PER_BLOCK - NUM_STATE_ROWS) as i32),
+                        )),
+                );
+            }
+            cb.gate(meta.query_fixed(q_digest, Rotation::cur()))
+        });
+
+        meta.create_gate("sha256 table", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+            let q_last = meta.query_fixed(q_last, Rotation::cur());
+            cb.condition(q_last.clone(), |cb| {
+                cb.require_equal(
+                    "the hash is enabled on its last block",
+                    meta.query_advice(is_enabled, Rotation::cur()),
+                    meta.query_advice(is_final, Rotation::cur()),
+                );
+                // The last rows of the block hold the digest words H7..H4 in e and
+                // H3..H0 in a.
+                for (columns, hash_limb) in [(&a, hash_word.hi()), (&e, hash_word.lo())] {
+                    cb.require_equal(
+                        "hash = digest of the block",
+                        meta.query_advice(hash_limb, Rotation::cur()),
+                        (0..NUM_STATE_ROWS as i32).fold(0.expr(), |acc, rot| {
+                            acc * pow_of_two_32() + compose(&bits(meta, columns, -rot))
+                        }),
+                    );
+                }
+            });
+            cb.condition(q_enable - q_last, |cb| {
+                cb.require_zero(
+                    "is_enabled only on the last row of a block",
+                    meta.query_advice(is_enabled, Rotation::cur()),
+                );
+            });
+            cb.gate(1.expr())
+        });
+
+        // The padding state, the input RLC and the length are reset at the
+        // start of a new hash, updated on the rows of the words of the block,
+        // and copied on the other rows.
+        let last_is_padding = |meta: &mut VirtualCells<F>| {
+            meta.query_advice(is_paddings[NUM_BYTES_PER_WORD - 1], Rotation::prev())
+        };
+        meta.create_gate("input state at the start of a block", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let start_new_hash = start_new_hash(meta);
+            for (name, column, column_prev) in [
+                (
+                    "last is_padding reset on a new hash",
+                    meta.query_advice(is_paddings[NUM_BYTES_PER_WORD - 1], Rotation::cur()),
+                    last_is_padding(meta),
+                ),
+                (
+                    "data_rlc reset on a new hash",
+                    meta.query_advice(data_rlc, Rotation::cur()),
+                    meta.query_advice(data_rlc, Rotation::prev()),
+                ),
+                (
+                    "length reset on a new hash",
+                    meta.query_advice(length, Rotation::cur()),
+                    meta.query_advice(length, Rotation::prev()),
+                ),
+            ] {
+                cb.require_equal(
+                    name,
+                    column,
+                    (1.expr() - start_new_hash.clone()) * column_prev,
+                );
+            }
+            cb.gate(meta.query_fixed(q_block_start, Rotation::cur()))
+        });
+        meta.create_gate("input state copy", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            cb.require_equal(
+                "last is_padding copied",
+                meta.query_advice(is_paddings[NUM_BYTES_PER_WORD - 1], Rotation::cur()),
+                last_is_padding(meta),
+            );
+            for column in [data_rlc, length] {
+                cb.require_equal(
+                    "input state copied",
+                    meta.query_advice(column, Rotation::cur()),
+                    meta.query_advice(column, Rotation::prev()),
+                );
+            }
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    - meta.query_fixed(q_block_start, Rotation::cur())
+                    - meta.query_fixed(q_input, Rotation::cur()),
+            )
+        });
+
+        // The bytes of a word in big endian and the padding flags of the bytes.
+        let input_bytes = |meta: &mut VirtualCells<F>| {
+            let w = bits(meta, &w, 0);
+            let bytes: [Expression<F>; NUM_BYTES_PER_WORD] = array_init::array_init(|i| {
+                let offset = (NUM_BYTES_PER_WORD - 1 - i) * NUM_BITS_PER_BYTE;
+                compose(&w[offset..offset + NUM_BITS_PER_BYTE])
+            });
+            let is_paddings = is_paddings.map(|column| meta.query_advice(column, Rotation::cur()));
+            let is_paddings_prev: [Expression<F>; NUM_BYTES_PER_WORD] =
+                array_init::array_init(|i| {
+                    if i == 0 {
+                        last_is_padding(meta)
+                    } else {
+                        is_paddings[i - 1].clone()
+                    }
+                });
+            (bytes, is_paddings, is_paddings_prev)
+        };
+        meta.create_gate("input", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let (bytes, is_paddings, is_paddings_prev) = input_bytes(meta);
+            let data_rlcs = [
+                vec![meta.query_advice(data_rlc, Rotation::prev())],
+                data_rlcs
+                    .iter()
+                    .map(|column| meta.query_advice(*column, Rotation::cur()))
+                    .collect(),
+                vec![meta.query_advice(data_rlc, Rotation::cur())],
+            ]
+            .concat();
+            for (i, (byte, is_padding, is_padding_prev)) in
+                izip!(&bytes, &is_paddings, &is_paddings_prev).enumerate()
+            {
+                let is_first_padding = is_padding.clone() - is_padding_prev.clone();
+                cb.require_boolean(
+                    "is_padding can only go from 0 to 1",
+                    is_first_padding.clone(),
+                );
+                cb.require_zero(
+                    "the first padding byte is 0x80",
+                    is_first_padding * (byte.clone() - 0x80.expr()),
+                );
+                cb.require_equal(
+                    "data_rlc accumulates the bytes before the padding",
+                    data_rlcs[i + 1].clone(),
+                    select::expr(
+                        is_padding.clone(),
+                        data_rlcs[i].clone(),
+                        data_rlcs[i].clone() * challenges.keccak_input() + byte.clone(),
+                    ),
+                );
+            }
+            cb.require_equal(
+                "length is the number of bytes before the padding",
+                meta.query_advice(length, Rotation::cur()),
+                meta.query_advice(length, Rotation::prev())
+                    + sum::expr(
+                        is_paddings
+                            .iter()
+                            .map(|is_padding| 1.expr() - is_padding.clone()),
+                    ),
+            );
+            cb.gate(meta.query_fixed(q_input, Rotation::cur()))
+        });
+        meta.create_gate("padding bytes", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let (bytes, _, is_paddings_prev) = input_bytes(meta);
+            for (byte, is_padding_prev) in bytes.iter().zip(is_paddings_prev.iter()) {
+                cb.require_zero(
+                    "the padding bytes after 0x80 are 0",
+                    is_padding_prev.clone() * byte.clone(),
+                );
+            }
+            // The length bytes of the last block are checked below.
+            let is_final = meta.query_advice(is_final, Rotation::cur());
+            cb.gate(
+                meta.query_fixed(q_input, Rotation::cur())
+                    - (meta.query_fixed(q_length_start, Rotation::cur())
+                        + meta.query_fixed(q_length_end, Rotation::cur()))
+                        * is_final,
+            )
+        });
+        meta.create_gate("length bytes", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let is_final = meta.query_advice(is_final, Rotation::cur());
+            cb.condition(meta.query_fixed(q_length_start, Rotation::cur()), |cb| {
+                // The padding starts before the length bytes in the last block, so
+                // that these are all padding bytes, and after them otherwise, so
+                // that the next block is the last one.
+                cb.require_equal(
+                    "the padding starts before the length bytes iff the block is the last one",
+                    last_is_padding(meta),
+                    is_final.clone(),
+                );
+            });
+            cb.condition(
+                meta.query_fixed(q_length_end, Rotation::cur()) * is_final,
+                |cb| {
+                    cb.require_equal(
+                        "the last 8 bytes of the last block are the bit length",
+                        compose(&bits(meta, &w, -1)) * pow_of_two_32()
+                            + compose(&bits(meta, &w, 0)),
+                        meta.query_advice(length, Rotation::cur()) * 8.expr(),
+                    );
+                },
+            );
+            cb.gate(1.expr())
+        });
+
+        Self {
+            q_enable,
+            q_first,
+            q_block_start,
+            q_init,
+            q_round,
+            q_input,
+            q_length_start,
+            q_length_end,
+            q_digest,
+            q_last,
+            round_cst,
+            h_init,
+            a,
+            e,
+            w,
+            carry_a,
+            carry_e,
+            carry_w,
+            is_paddings,
+            data_rlcs,
+            is_final,
+            sha256_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Witness values of a row of the SHA-256 circuit
+#[derive(Clone, Debug)]
+pub(crate) struct Sha256Row<F> {
+    a: u32,
+    e: u32,
+    w: u32,
+    carry_a: u64,
+    carry_e: u64,
+    carry_w: u64,
+    is_paddings: [bool; NUM_BYTES_PER_WORD],
+    data_rlcs: [Value<F>; NUM_BYTES_PER_WORD - 1],
+    is_final: bool,
+    is_enabled: bool,
+    data_rlc: Value<F>,
+    length: usize,
+    hash: word::Word<Value<F>>,
+}
+
+impl<F: Field> Sha256Row<F> {
+    /// Return a row with the input state of `is_padding`, `data_rlc` and
+    /// `length`, and zero values.
+    fn new(is_final: bool, is_padding: bool, data_rlc: Value<F>, length: usize) -> Self {
+        Self {
+            a: 0,
+            e: 0,
+            w: 0,
+            carry_a: 0,
+            carry_e: 0,
+            carry_w: 0,
+            is_paddings: [is_padding; NUM_BYTES_PER_WORD],
+            data_rlcs: [data_rlc; NUM_BYTES_PER_WORD - 1],
+            is_final,
+            is_enabled: false,
+            data_rlc,
+            length,
+            hash: word::Word::new([Value::known(F::ZERO); 2]),
+        }
+    }
+}
+
+/// Append the rows of the SHA-256 hash of `bytes` to `rows`.
+fn sha256<F: Field>(rows: &mut Vec<Sha256Row<F>>, bytes: &[u8], challenges: Challenges<Value<F>>) {
+    let padded = pad(bytes);
+    let num_blocks = padded.len() / RATE;
+
+    let mut hs = H;
+    let mut is_padding = false;
+    let mut data_rlc = Value::known(F::ZERO);
+    let mut length = 0;
+    for (idx, block) in padded.chunks(RATE).enumerate() {
+        let is_final = idx == num_blocks - 1;
+
+        // Initial state
+        for i in 0..NUM_STATE_ROWS {
+            rows.push(Sha256Row {
+                a: hs[3 - i],
+                e: hs[7 - i],
+                ..Sha256Row::new(is_final, is_padding, data_rlc, length)
+            });
+        }
+
+        // Message schedule
+        let mut ws = block
+            .chunks(NUM_BYTES_PER_WORD)
+            .map(|word| u32::from_be_bytes(word.try_into().unwrap()))
+            .collect_vec();
+        let mut carries_w = vec![0; NUM_WORDS_TO_ABSORB];
+        for t in NUM_WORDS_TO_ABSORB..NUM_ROUNDS {
+            let s0 = ws[t - 15].rotate_right(7) ^ ws[t - 15].rotate_right(18) ^ (ws[t - 15] >> 3);
+            let s1 = ws[t - 2].rotate_right(17) ^ ws[t - 2].rotate_right(19) ^ (ws[t - 2] >> 10);
+            let w = s1 as u64 + ws[t - 7] as u64 + s0 as u64 + ws[t - 16] as u64;
+            ws.push(w as u32);
+            carries_w.push(w >> NUM_BITS_PER_WORD);
+        }
+
+        // Rounds
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = hs;
+        for t in 0..NUM_ROUNDS {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h as u64 + s1 as u64 + ch as u64 + ROUND_CST[t] as u64 + ws[t] as u64;
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let new_e = d as u64 + t1;
+            let new_a = t1 + s0 as u64 + maj as u64;
+            (h, g, f, e) = (g, f, e, new_e as u32);
+            (d, c, b, a) = (c, b, a, new_a as u32);
+
+            let mut row = Sha256Row::new(is_final, is_padding, data_rlc, length);
+            if t < NUM_WORDS_TO_ABSORB {
+                let bytes_offset = idx * RATE + t * NUM_BYTES_PER_WORD;
+                for (i, byte) in block[t * NUM_BYTES_PER_WORD..(t + 1) * NUM_BYTES_PER_WORD]
+                    .iter()
+                    .enumerate()
+                {
+                    is_padding = bytes_offset + i >= bytes.len();
+                    if !is_padding {
+                        data_rlc = data_rlc * challenges.keccak_input()
+                            + Value::known(F::from(*byte as u64));
+                        length += 1;
+                    }
+                    row.is_paddings[i] = is_padding;
+                    if i < NUM_BYTES_PER_WORD - 1 {
+                        row.data_rlcs[i] = data_rlc;
+                    }
+                }
+                row.data_rlc = data_rlc;
+                row.length = length;
+            }
+            rows.push(Sha256Row {
+                a,
+                e,
+                w: ws[t],
+                carry_a: new_a >> NUM_BITS_PER_WORD,
+                carry_e: new_e >> NUM_BITS_PER_WORD,
+                carry_w: carries_w[t],
+                ..row
+            });
+        }
+
+        // Digest
+        let sums = hs
+            .iter()
+            .zip([a, b, c, d, e, f, g, h])
+            .map(|(h, x)| *h as u64 + x as u64)
+            .collect_vec();
+        hs = array_init::array_init(|i| sums[i] as u32);
+        let hash = word::Word::<F>::from(U256::from_big_endian(
+            &hs.iter().flat_map(|h| h.to_be_bytes()).collect_vec(),
+        ))
+        .map(Value::known);
+        for i in 0..NUM_STATE_ROWS {
+            let is_last = i == NUM_STATE_ROWS - 1;
+            let row = Sha256Row::new(is_final, is_padding, data_rlc, length);
+            rows.push(Sha256Row {
+                a: hs[3 - i],
+                e: hs[7 - i],
+                carry_a: sums[3 - i] >> NUM_BITS_PER_WORD,
+                carry_e: sums[7 - i] >> NUM_BITS_PER_WORD,
+                is_enabled: is_last && is_final,
+                hash: if is_last { hash } else { row.hash },
+                ..row
+            });
+        }
+    }
+}
+
+/// Return the rows of the SHA-256 hashes of `inputs`, padded with hashes of
+/// the empty input to `max_blocks` blocks.
+pub(crate) fn multi_sha256<F: Field>(
+    inputs: &[Vec<u8>],
+    challenges: Challenges<Value<F>>,
+    max_blocks: usize,
+) -> Result<Vec<Sha256Row<F>>, Error> {
+    let num_blocks: usize = inputs.iter().map(|input| num_blocks(input.len())).sum();
+    if num_blocks > max_blocks {
+        error!(
+            "number of blocks of the inputs = {} > max_blocks = {}",
+            num_blocks, max_blocks
+        );
+        return Err(Error::Synthesis);
+    }
+
+    let mut rows = Vec::with_capacity(max_blocks * ROWS_PER_BLOCK);
+    for input in inputs {
+        sha256(&mut rows, input, challenges);
+    }
+    for _ in num_blocks..max_blocks {
+        sha256(&mut rows, &[], challenges);
+    }
+    Ok(rows)
+}
+
+impl<F: Field> Sha256CircuitConfig<F> {
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        witness: &[Sha256Row<F>],
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "assign sha256 rows",
+            |mut region| {
+                for (offset, row) in witness.iter().enumerate() {
+                    self.set_row(&mut region, offset, row)?;
+                }
+                self.sha256_table.annotate_columns_in_region(&mut region);
+                self.annotate_circuit(&mut region);
+                Ok(())
+            },
+        )
+    }
+
+    fn set_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &Sha256Row<F>,
+    ) -> Result<(), Error> {
+        // Fixed selectors, which only depend on the position of the row in its
+        // block.
+        let row_idx = offset % ROWS_PER_BLOCK;
+        let round = row_idx
+            .checked_sub(NUM_STATE_ROWS)
+            .filter(|t| *t < NUM_ROUNDS);
+        for (name, column, value) in [
+            ("q_enable", self.q_enable, true),
+            ("q_first", self.q_first, offset < NUM_STATE_ROWS),
+            ("q_block_start", self.q_block_start, row_idx == 0),
+            ("q_init", self.q_init, row_idx < NUM_STATE_ROWS),
+            ("q_round", self.q_round, round.is_some()),
+            (
+                "q_input",
+                self.q_input,
+                matches!(round, Some(t) if t < NUM_WORDS_TO_ABSORB),
+            ),
+            (
+                "q_length_start",
+                self.q_length_start,
+                round == Some(NUM_WORDS_TO_ABSORB - 2),
+            ),
+            (
+                "q_length_end",
+                self.q_length_end,
+                round == Some(NUM_WORDS_TO_ABSORB - 1),
+            ),
+            (
+                "q_digest",
+                self.q_digest,
+                row_idx >= NUM_STATE_ROWS + NUM_ROUNDS,
+            ),
+            ("q_last", self.q_last, row_idx == ROWS_PER_BLOCK - 1),
+        ] {
+            region.assign_fixed(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(F::from(value as u64)),
+            )?;
+        }
+        region.assign_fixed(
+            || format!("assign round cst {}", offset),
+            self.round_cst,
+            offset,
+            || Value::known(F::from(round.map_or(0, |t| ROUND_CST[t]) as u64)),
+        )?;
+        for (i, column) in self.h_init.iter().enumerate() {
+            let h_init = if row_idx < NUM_STATE_ROWS {
+                H[4 * i + 3 - row_idx]
+            } else {
+                0
+            };
+            region.assign_fixed(
+                || format!("assign h init {}", offset),
+                *column,
+                offset,
+                || Value::known(F::from(h_init as u64)),
+            )?;
+        }
+
+        // Bits
+        for (name, columns, value) in [
+            ("a", &self.a[..], row.a as u64),
+            ("e", &self.e[..], row.e as u64),
+            ("w", &self.w[..], row.w as u64),
+            ("carry_a", &self.carry_a[..], row.carry_a),
+            ("carry_e", &self.carry_e[..], row.carry_e),
+            ("carry_w", &self.carry_w[..], row.carry_w),
+        ] {
+            for (i, column) in columns.iter().enumerate() {
+                region.assign_advice(
+                    || format!("assign {} bit {} {}", name, i, offset),
+                    *column,
+                    offset,
+                    || Value::known(F::from((value >> i) & 1)),
+                )?;
+            }
+        }
+
+        // Input state
+        for (column, is_padding) in self.is_paddings.iter().zip(row.is_paddings) {
+            region.assign_advice(
+                || format!("assign is_padding {}", offset),
+                *column,
+                offset,
+                || Value::known(F::from(is_padding as u64)),
+            )?;
+        }
+        for (column, data_rlc) in self.data_rlcs.iter().zip(row.data_rlcs) {
+            region.assign_advice(
+                || format!("assign data_rlc {}", offset),
+                *column,
+                offset,
+                || data_rlc,
+            )?;
+        }
+        region.assign_advice(
+            || format!("assign is_final {}", offset),
+            self.is_final,
+            offset,
+            || Value::known(F::from(row.is_final as u64)),
+        )?;
+
+        for (column, value) in <Sha256Table as LookupTable<F>>::advice_columns(&self.sha256_table)
+            .into_iter()
+            .zip_eq([
+                Value::known(F::from(row.is_enabled as u64)),
+                row.data_rlc,
+                Value::known(F::from(row.length as u64)),
+                row.hash.lo(),
+                row.hash.hi(),
+            ])
+        {
+            region.assign_advice(
+                || format!("assign sha256 table {}", offset),
+                column,
+                offset,
+                || value,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn annotate_circuit(&self, region: &mut Region<F>) {
+        region.name_column(|| "SHA256_q_enable", self.q_enable);
+        region.name_column(|| "SHA256_q_first", self.q_first);
+        region.name_column(|| "SHA256_q_block_start", self.q_block_start);
+        region.name_column(|| "SHA256_q_init", self.q_init);
+        region.name_column(|| "SHA256_q_round", self.q_round);
+        region.name_column(|| "SHA256_q_input", self.q_input);
+        region.name_column(|| "SHA256_q_digest", self.q_digest);
+        region.name_column(|| "SHA256_q_last", self.q_last);
+        region.name_column(|| "SHA256_is_final", self.is_final);
+    }
+}
+
+/// SHA-256 circuit, proving the hashes of the Sha256Table
+#[derive(Clone, Default, Debug)]
+pub struct Sha256Circuit<F: Field> {
+    /// Max number of blocks of 64 bytes of the padded inputs
+    pub max_blocks: usize,
+    /// Inputs to hash
+    pub inputs: Vec<Vec<u8>>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> Sha256Circuit<F> {
+    /// Return a new Sha256Circuit with `max_blocks` blocks.
+    pub fn new(max_blocks: usize, inputs: Vec<Vec<u8>>) -> Self {
+        Self {
+            max_blocks,
+            inputs,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the minimum number of rows required to prove `max_blocks`
+    /// blocks.
+    pub fn min_num_rows(max_blocks: usize) -> usize {
+        max_blocks * ROWS_PER_BLOCK
+    }
+
+    /// Return the number of blocks required to hash `inputs`.
+    pub fn num_blocks(inputs: &[Vec<u8>]) -> usize {
+        inputs.iter().map(|input| num_blocks(input.len())).sum()
+    }
+}
+
+impl<F: Field> SubCircuit<F> for Sha256Circuit<F> {
+    type Config = Sha256CircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // The bit columns of a, e and w are queried at 6 distinct rotations,
+        // so returns 9 unusable rows.
+        9
+    }
+
+    /// The `block.circuits_params.max_sha256_blocks` parameter sets up the
+    /// circuit to support a fixed number of blocks; when 0, the circuit only
+    /// has the blocks required by the inputs of the sha256 precompile calls.
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        let max_blocks = match block.circuits_params.max_sha256_blocks {
+            0 => Self::num_blocks(&block.sha256_inputs),
+            max_blocks => max_blocks,
+        };
+        Self::new(max_blocks, block.sha256_inputs.clone())
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        (
+            Self::min_num_rows(Self::num_blocks(&block.sha256_inputs)),
+            Self::min_num_rows(block.circuits_params.max_sha256_blocks),
+        )
+    }
+
+    /// Make the assignments to the Sha256Circuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let witness = multi_sha256(&self.inputs, *challenges, self.max_blocks)?;
+        config.assign(layouter, &witness)
+    }
+}
@@ -0,0 +1,95 @@
+use super::param::*;
+use eth_types::Field;
+use gadgets::util::Expr;
+use halo2_proofs::plonk::Expression;
+
+/// Bits of a word in little-endian
+pub(crate) type WordBits<F> = [Expression<F>; NUM_BITS_PER_WORD];
+
+/// Return the value of little-endian bits.
+pub(crate) fn compose<F: Field>(bits: &[Expression<F>]) -> Expression<F> {
+    bits.iter()
+        .rev()
+        .fold(0.expr(), |acc, bit| acc * 2.expr() + bit.clone())
+}
+
+/// Return the bits of `bits` rotated right by `n`.
+pub(crate) fn rotate_right<F: Field>(bits: &WordBits<F>, n: usize) -> WordBits<F> {
+    array_init::array_init(|i| bits[(i + n) % NUM_BITS_PER_WORD].clone())
+}
+
+/// Return the bits of `bits` shifted right by `n`.
+pub(crate) fn shift_right<F: Field>(bits: &WordBits<F>, n: usize) -> WordBits<F> {
+    array_init::array_init(|i| bits.get(i + n).cloned().unwrap_or_else(|| 0.expr()))
+}
+
+/// Return the value of the bitwise xor of the words.  Each xor of 2 bits is
+/// of degree 2, so the degree of the result is the number of words.
+pub(crate) fn xor<F: Field>(words: &[WordBits<F>]) -> Expression<F> {
+    let bits = (0..NUM_BITS_PER_WORD)
+        .map(|i| {
+            words.iter().skip(1).fold(words[0][i].clone(), |acc, word| {
+                acc.clone() + word[i].clone() - 2.expr() * acc * word[i].clone()
+            })
+        })
+        .collect::<Vec<_>>();
+    compose(&bits)
+}
+
+/// Σ0(a) = ROTR^2(a) ^ ROTR^13(a) ^ ROTR^22(a)
+pub(crate) fn sigma_upper_0<F: Field>(a: &WordBits<F>) -> Expression<F> {
+    xor(&[rotate_right(a, 2), rotate_right(a, 13), rotate_right(a, 22)])
+}
+
+/// Σ1(e) = ROTR^6(e) ^ ROTR^11(e) ^ ROTR^25(e)
+pub(crate) fn sigma_upper_1<F: Field>(e: &WordBits<F>) -> Expression<F> {
+    xor(&[rotate_right(e, 6), rotate_right(e, 11), rotate_right(e, 25)])
+}
+
+/// σ0(w) = ROTR^7(w) ^ ROTR^18(w) ^ SHR^3(w)
+pub(crate) fn sigma_lower_0<F: Field>(w: &WordBits<F>) -> Expression<F> {
+    xor(&[rotate_right(w, 7), rotate_right(w, 18), shift_right(w, 3)])
+}
+
+/// σ1(w) = ROTR^17(w) ^ ROTR^19(w) ^ SHR^10(w)
+pub(crate) fn sigma_lower_1<F: Field>(w: &WordBits<F>) -> Expression<F> {
+    xor(&[rotate_right(w, 17), rotate_right(w, 19), shift_right(w, 10)])
+}
+
+/// Ch(e, f, g) = (e & f) ^ (!e & g)
+pub(crate) fn ch<F: Field>(e: &WordBits<F>, f: &WordBits<F>, g: &WordBits<F>) -> Expression<F> {
+    let bits = (0..NUM_BITS_PER_WORD)
+        .map(|i| e[i].clone() * f[i].clone() + (1.expr() - e[i].clone()) * g[i].clone())
+        .collect::<Vec<_>>();
+    compose(&bits)
+}
+
+/// Maj(a, b, c) = (a & b) ^ (a & c) ^ (b & c)
+pub(crate) fn maj<F: Field>(a: &WordBits<F>, b: &WordBits<F>, c: &WordBits<F>) -> Expression<F> {
+    let bits = (0..NUM_BITS_PER_WORD)
+        .map(|i| {
+            let (a, b, c) = (a[i].clone(), b[i].clone(), c[i].clone());
+            a.clone() * b.clone() + a.clone() * c.clone() + b.clone() * c.clone()
+                - 2.expr() * a * b * c
+        })
+        .collect::<Vec<_>>();
+    compose(&bits)
+}
+
+/// Return the bytes of the message padded to a multiple of `RATE` bytes:
+/// the message, `0x80`, zeros and the bit length of the message in big
+/// endian.
+pub(crate) fn pad(bytes: &[u8]) -> Vec<u8> {
+    let mut padded = bytes.to_vec();
+    padded.push(0x80);
+    while padded.len() % RATE != RATE - NUM_BYTES_LENGTH {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&(8 * bytes.len() as u64).to_be_bytes());
+    padded
+}
+
+/// Return the number of blocks required to hash a message of `len` bytes.
+pub(crate) fn num_blocks(len: usize) -> usize {
+    (len + NUM_BYTES_LENGTH) / RATE + 1
+}
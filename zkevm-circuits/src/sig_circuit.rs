@@ -0,0 +1,306 @@
+//! Signature circuit.
+//!
+//! The circuit verifies a list of secp256k1 signatures with the
+//! [`SignVerifyChip`] and lays out every verification in the [`SigTable`], so
+//! that other circuits can look up signature verifications without verifying
+//! them, as the EVM circuit needs for the ecrecover precompile.
+//!
+//! Every signature slot is a row of the table, after the all-zero first row.
+//! The row of a verified signature holds its message hash, recovery id,
+//! (r, s) and the recovered address, copied from the cells of the
+//! SignVerifyChip, with `is_valid = 1`.  Padding slots verify the padding
+//! signature and their rows are all zero, which is a true claim: (r, s) =
+//! (0, 0) is never a valid signature.  Invalid signatures can't be proven
+//! yet, so the only rows with `is_valid = 0` are the padding ones.
+//!
+//! The SignVerifyChip doesn't constrain s <= n/2, as ecrecover accepts any s
+//! in [1, n).  The TxCircuit keeps verifying the signatures of the txs with
+//! its own SignVerifyChip, which rejects malleable signatures.
+
+#[cfg(any(test, feature = "test-circuits"))]
+mod dev;
+#[cfg(test)]
+mod test;
+#[cfg(feature = "test-circuits")]
+pub use dev::SigCircuit as TestSigCircuit;
+
+use crate::{
+    table::{KeccakTable, LookupTable, SigTable},
+    tx_circuit::sign_verify::{
+        AssignedSignature, AssignedSignatureVerify, SignVerifyChip, SignVerifyConfig,
+    },
+    util::{word::Word, Challenges, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use bus_mapping::circuit_input_builder::keccak_inputs_sign_verify;
+use eth_types::{sign_types::SignData, Field};
+use halo2_proofs::{
+    circuit::{Layouter, Value},
+    plonk::{ConstraintSystem, Error, Expression},
+};
+use itertools::Itertools;
+use log::error;
+use maingate::{AssignedValue, MainGate, MainGateInstructions, RegionCtx};
+use std::marker::PhantomData;
+
+/// Circuit configuration arguments
+pub struct SigCircuitConfigArgs<F: Field> {
+    /// SigTable
+    pub sig_table: SigTable,
+    /// KeccakTable
+    pub keccak_table: KeccakTable,
+    /// Challenges
+    pub challenges: Challenges<Expression<F>>,
+}
+
+/// Config for SigCircuit
+#[derive(Clone, Debug)]
+pub struct SigCircuitConfig<F: Field> {
+    sig_table: SigTable,
+    /// SignVerifyChip config of the signatures of the table
+    sign_verify: SignVerifyConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for SigCircuitConfig<F> {
+    type ConfigArgs = SigCircuitConfigArgs<F>;
+
+    /// Return a new SigCircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            sig_table,
+            keccak_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        for column in <SigTable as LookupTable<F>>::advice_columns(&sig_table) {
+            meta.enable_equality(column);
+        }
+        let sign_verify = SignVerifyConfig::new(meta, keccak_table, challenges, 1);
+
+        Self {
+            sig_table,
+            sign_verify,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Cells of a row of the SigTable, masked to zero on padding slots
+struct AssignedSigTableRow<F: Field> {
+    msg_hash: Word<AssignedValue<F>>,
+    sig_v: AssignedValue<F>,
+    sig_r: Word<AssignedValue<F>>,
+    sig_s: Word<AssignedValue<F>>,
+    recovered_addr: Word<AssignedValue<F>>,
+    is_valid: AssignedValue<F>,
+}
+
+impl<F: Field> AssignedSigTableRow<F> {
+    /// Return the cells in the order of the SigTable advice columns.
+    fn cells(&self) -> [AssignedValue<F>; 10] {
+        [
+            self.msg_hash.lo(),
+            self.msg_hash.hi(),
+            self.sig_v.clone(),
+            self.sig_r.lo(),
+            self.sig_r.hi(),
+            self.sig_s.lo(),
+            self.sig_s.hi(),
+            self.recovered_addr.lo(),
+            self.recovered_addr.hi(),
+            self.is_valid.clone(),
+        ]
+    }
+}
+
+impl<F: Field> SigCircuitConfig<F> {
+    /// Load the range table of the SignVerifyChip
+    pub fn load_aux_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.sign_verify.load_range(layouter)
+    }
+
+    /// Return the rows of the SigTable of the assigned verifications: the
+    /// values of the verifications multiplied by `is_valid = 1 - is_padding`.
+    /// The address of a padding verification is already 0.
+    fn assign_masked_rows(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        assigned_sig_verifs: &[(AssignedSignatureVerify<F>, AssignedSignature<F>)],
+    ) -> Result<Vec<AssignedSigTableRow<F>>, Error> {
+        let main_gate = MainGate::new(self.sign_verify.main_gate_config());
+        layouter.assign_region(
+            || "sig table masks",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+                assigned_sig_verifs
+                    .iter()
+                    .map(|(assigned_sig_verif, assigned_signature)| {
+                        let is_valid = main_gate.not(&mut ctx, &assigned_sig_verif.is_padding)?;
+                        let mut mask =
+                            |value: &AssignedValue<F>| main_gate.mul(&mut ctx, value, &is_valid);
+                        let mut mask_word = |word: &Word<AssignedValue<F>>| {
+                            Ok::<_, Error>(Word::new([mask(&word.lo())?, mask(&word.hi())?]))
+                        };
+                        Ok(AssignedSigTableRow {
+                            msg_hash: mask_word(&assigned_sig_verif.msg_hash)?,
+                            sig_r: mask_word(&assigned_signature.r)?,
+                            sig_s: mask_word(&assigned_signature.s)?,
+                            sig_v: mask(&assigned_sig_verif.recovery_id)?,
+                            recovered_addr: assigned_sig_verif.address.clone(),
+                            is_valid,
+                        })
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// Assign the SigTable, copying the rows from the assigned cells.
+    fn assign_sig_table(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        rows: &[AssignedSigTableRow<F>],
+    ) -> Result<(), Error> {
+        let table = &self.sig_table;
+        let advice_columns = <SigTable as LookupTable<F>>::advice_columns(table);
+        layouter.assign_region(
+            || "sig table",
+            |mut region| {
+                // Empty entry
+                region.assign_fixed(|| "q_enable", table.q_enable, 0, || Value::known(F::ZERO))?;
+                for &column in &advice_columns {
+                    region.assign_advice(
+                        || "sig table empty row",
+                        column,
+                        0,
+                        || Value::known(F::ZERO),
+                    )?;
+                }
+                for (i, row) in rows.iter().enumerate() {
+                    let offset = i + 1;
+                    region.assign_fixed(
+                        || "q_enable",
+                        table.q_enable,
+                        offset,
+                        || Value::known(F::ONE),
+                    )?;
+                    for (&column, cell) in advice_columns.iter().zip_eq(row.cells()) {
+                        cell.copy_advice(|| "sig table row", &mut region, column, offset)?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// Signature Circuit for verifying the secp256k1 signatures of the SigTable
+#[derive(Clone, Default, Debug)]
+pub struct SigCircuit<F: Field> {
+    /// Max number of supported signatures
+    pub max_verif: usize,
+    /// Signatures to verify, one per slot
+    pub signatures: Vec<SignData>,
+    /// SignVerify chip
+    pub sign_verify: SignVerifyChip<F>,
+}
+
+impl<F: Field> SigCircuit<F> {
+    /// Return a new SigCircuit with `max_verif` signature slots.
+    pub fn new(max_verif: usize, signatures: Vec<SignData>) -> Self {
+        Self {
+            max_verif,
+            signatures,
+            sign_verify: SignVerifyChip::new(max_verif),
+        }
+    }
+
+    /// Return the minimum number of rows required to prove `max_verif`
+    /// signature slots.  Without slots, only the empty row of the SigTable is
+    /// assigned.
+    pub fn min_num_rows(max_verif: usize) -> usize {
+        if max_verif == 0 {
+            return 1;
+        }
+        std::cmp::max(
+            1 + max_verif,
+            SignVerifyChip::<F>::min_num_rows(max_verif, 1),
+        )
+    }
+
+    /// Return the keccak inputs of the circuit: the public keys of the
+    /// signatures and of the padding signature.
+    pub fn keccak_inputs(&self) -> Vec<Vec<u8>> {
+        keccak_inputs_sign_verify(&self.signatures)
+    }
+}
+
+impl<F: Field> SubCircuit<F> for SigCircuit<F> {
+    type Config = SigCircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // No column queried at more than 3 distinct rotations, so returns 6 as
+        // minimum unusable rows.
+        6
+    }
+
+    /// The `block.circuits_params.max_sig_verifs` parameter sets up the
+    /// circuit to support a fixed number of signatures; when 0, the circuit
+    /// only has the slots of the signatures of the ecrecover calls.
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        let max_verif = match block.circuits_params.max_sig_verifs {
+            0 => block.ecrecover_events.len(),
+            max_verif => max_verif,
+        };
+        Self::new(max_verif, block.ecrecover_events.clone())
+    }
+
+    /// The maingate of the SignVerifyChip expects an instance column, which
+    /// is empty.
+    fn instance(&self) -> Vec<Vec<F>> {
+        vec![vec![]]
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        (
+            Self::min_num_rows(block.ecrecover_events.len()),
+            Self::min_num_rows(block.circuits_params.max_sig_verifs),
+        )
+    }
+
+    /// Make the assignments to the SigCircuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        if self.signatures.len() > self.max_verif {
+            error!(
+                "signatures.len() = {} > max_verif = {}",
+                self.signatures.len(),
+                self.max_verif
+            );
+            return Err(Error::Synthesis);
+        }
+
+        if self.max_verif == 0 {
+            // Circuits without signature slots, such as the ones of blocks without ecrecover
+            // calls, don't load the range table of the SignVerifyChip.
+            return config.assign_sig_table(layouter, &[]);
+        }
+
+        config.load_aux_tables(layouter)?;
+        let assigned_sig_verifs = self.sign_verify.assign_with_signatures(
+            &config.sign_verify,
+            layouter,
+            &self.signatures.iter().cloned().map(Some).collect_vec(),
+            challenges,
+        )?;
+        let rows = config.assign_masked_rows(layouter, &assigned_sig_verifs)?;
+        config.assign_sig_table(layouter, &rows)
+    }
+}
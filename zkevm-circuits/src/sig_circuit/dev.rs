@@ -0,0 +1,53 @@
+pub use super::SigCircuit;
+
+use crate::{
+    sig_circuit::{SigCircuitConfig, SigCircuitConfigArgs},
+    table::{KeccakTable, SigTable},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+};
+use eth_types::Field;
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+impl<F: Field> Circuit<F> for SigCircuit<F> {
+    type Config = (SigCircuitConfig<F>, Challenges, KeccakTable);
+    type FloorPlanner = SimpleFloorPlanner;
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.max_verif, vec![])
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let sig_table = SigTable::construct(meta);
+        let keccak_table = KeccakTable::construct(meta);
+        let challenges = Challenges::construct(meta);
+
+        let config = {
+            let challenges = challenges.exprs(meta);
+            SigCircuitConfig::new(
+                meta,
+                SigCircuitConfigArgs {
+                    sig_table,
+                    keccak_table: keccak_table.clone(),
+                    challenges,
+                },
+            )
+        };
+
+        (config, challenges, keccak_table)
+    }
+
+    fn synthesize(
+        &self,
+        (config, challenges, keccak_table): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let challenges = challenges.values(&mut layouter);
+
+        keccak_table.dev_load(&mut layouter, &self.keccak_inputs(), &challenges)?;
+        self.synthesize_sub(&config, &challenges, &mut layouter)
+    }
+}
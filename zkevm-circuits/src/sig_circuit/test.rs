@@ -0,0 +1,75 @@
+use super::*;
+use crate::util::{log2_ceil, unusable_rows};
+use eth_types::sign_types::sign;
+use halo2_proofs::{
+    arithmetic::Field as _,
+    dev::{MockProver, VerifyFailure},
+    halo2curves::{
+        bn256::Fr,
+        group::Curve,
+        secp256k1::{self, Secp256k1Affine},
+        CurveAffine,
+    },
+};
+use rand::SeedableRng;
+use rand_xorshift::XorShiftRng;
+
+#[test]
+fn sig_circuit_unusable_rows() {
+    assert_eq!(
+        SigCircuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, SigCircuit::<Fr>>(()),
+    )
+}
+
+fn run<F: Field>(circuit: SigCircuit<F>) -> Result<(), Vec<VerifyFailure>> {
+    let k = log2_ceil(
+        SigCircuit::<Fr>::unusable_rows() + SigCircuit::<Fr>::min_num_rows(circuit.max_verif),
+    );
+    // SignVerifyChip -> ECDSAChip -> MainGate instance column
+    let prover = match MockProver::run(k, &circuit, vec![vec![]]) {
+        Ok(prover) => prover,
+        Err(e) => panic!("{:#?}", e),
+    };
+    prover.verify()
+}
+
+/// Return `n` signatures of random messages by random keys.
+fn gen_signatures(n: usize) -> Vec<SignData> {
+    let mut rng = XorShiftRng::seed_from_u64(2);
+    (0..n)
+        .map(|_| {
+            let sk = secp256k1::Fq::random(&mut rng);
+            let pk = (Secp256k1Affine::generator() * sk).to_affine();
+            let msg_hash = secp256k1::Fq::random(&mut rng);
+            let randomness = secp256k1::Fq::random(&mut rng);
+            SignData {
+                signature: sign(randomness, sk, msg_hash),
+                pk,
+                msg_hash,
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn sig_circuit_2sigs_3max_verif() {
+    let circuit = SigCircuit::<Fr>::new(3, gen_signatures(2));
+    assert_eq!(run(circuit), Ok(()));
+}
+
+#[test]
+fn sig_circuit_invalid_signature() {
+    // The signature is not the one of the message hash
+    let mut signatures = gen_signatures(1);
+    signatures[0].msg_hash += secp256k1::Fq::ONE;
+    let circuit = SigCircuit::<Fr>::new(1, signatures);
+    assert!(run(circuit).is_err());
+}
+
+#[test]
+fn sig_circuit_0max_verif() {
+    // Only the empty row of the SigTable is assigned.
+    let circuit = SigCircuit::<Fr>::new(0, vec![]);
+    assert_eq!(run(circuit), Ok(()));
+}
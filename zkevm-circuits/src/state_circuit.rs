@@ -31,20 +31,57 @@ use gadgets::{
 use halo2_proofs::{
     circuit::{Layouter, Region, Value},
     plonk::{
-        Advice, Column, ConstraintSystem, Error, Expression, FirstPhase, Fixed, SecondPhase,
-        VirtualCells,
+        Advice, Column, ConstraintSystem, Error, Expression, FirstPhase, Fixed, Instance,
+        SecondPhase, VirtualCells,
     },
     poly::Rotation,
 };
-use lexicographic_ordering::Config as LexicographicOrderingConfig;
+use lexicographic_ordering::{
+    Assignment as LexicographicOrderingAssignment, Config as LexicographicOrderingConfig,
+};
 use lookups::{Chip as LookupsChip, Config as LookupsConfig, Queries as LookupsQueries};
 use multiple_precision_integer::{Chip as MpiChip, Config as MpiConfig, Queries as MpiQueries};
 use param::*;
+use rayon::prelude::*;
 use std::marker::PhantomData;
 
 #[cfg(test)]
 use std::collections::HashMap;
 
+/// Errors for StateCircuit inputs that don't fit in the circuit parameters
+#[derive(Clone, Debug, thiserror::Error, PartialEq, Eq)]
+pub enum StateCircuitError {
+    /// There are more rws than rows in the circuit, which needs at least one
+    /// Rw::Start padding row before the rws
+    #[error("TooManyRws(num_rws: {num_rws}, max_rws: {max_rws})")]
+    TooManyRws {
+        /// Number of rws, without the Rw::Start padding
+        num_rws: usize,
+        /// Max number of rows of the circuit
+        max_rws: usize,
+    },
+    /// The rws of a group of rws with the same keys, which can't be split
+    /// across chunks, don't fit in the rows of a chunk
+    #[error("TooLargeRwGroup(num_rws: {num_rws}, max_rws: {max_rws})")]
+    TooLargeRwGroup {
+        /// Number of rws of the group
+        num_rws: usize,
+        /// Max number of rows of a chunk
+        max_rws: usize,
+    },
+}
+
+impl StateCircuitError {
+    /// Check that `num_rws` rws fit in `max_rws` rows, where `max_rws = 0`
+    /// means that the rows are dynamically calculated from the rws.
+    pub(crate) fn check_max_rws(num_rws: usize, max_rws: usize) -> Result<(), Self> {
+        if max_rws != 0 && num_rws >= max_rws {
+            return Err(Self::TooManyRws { num_rws, max_rws });
+        }
+        Ok(())
+    }
+}
+
 /// Config for StateCircuit
 #[derive(Clone)]
 pub struct StateCircuitConfig<F> {
@@ -61,6 +98,10 @@ pub struct StateCircuitConfig<F> {
     // new value are zero. Will do lookup for MPTProofType::StorageDoesNotExist if
     // non-existing, otherwise do lookup for MPTProofType::StorageChanged.
     is_non_exist: BatchedIsZeroConfig,
+    // For Rw::Account, identify the destruction of the account if the new code
+    // hash is zero but the committed one isn't. Will do lookup for
+    // MPTProofType::AccountDestructed if destructed.
+    is_zero_value: BatchedIsZeroConfig,
     // Intermediary witness used to reduce mpt lookup expression degree
     mpt_proof_type: Column<Advice>,
     state_root: word::Word<Column<Advice>>,
@@ -69,6 +110,24 @@ pub struct StateCircuitConfig<F> {
     lookups: LookupsConfig,
     // External tables
     mpt_table: MptTable,
+    // Sort keys of the first row after the Start padding, which is the
+    // boundary row of a chunk, in every row
+    chunk_boundary: [Column<Advice>; 7],
+    // 1 in every row if the first row after the Start padding is a boundary
+    // row, i.e. a copy of the last row of the previous chunk
+    has_chunk_boundary: Column<Advice>,
+    // 1 on the boundary row, which isn't an rw of the chunk
+    is_chunk_boundary: Column<Advice>,
+    // Challenges (alpha, gamma) of the fingerprint of the rws, in every row
+    fingerprint_challenges: [Column<Advice>; 2],
+    // RLC with gamma of the RwTable row, accumulated in three steps to keep
+    // the degree low
+    row_rlc: [Column<Advice>; 3],
+    // Running fingerprint of the rws, prod(alpha - row_rlc), up to the row
+    fingerprint: Column<Advice>,
+    // Public inputs of the sort keys of the boundary row and of the last row,
+    // of the state roots before and after the rws, and of the chunk context
+    instance: Column<Instance>,
     _marker: PhantomData<F>,
 }
 
@@ -82,8 +141,10 @@ pub struct StateCircuitConfigArgs<F: Field> {
     pub u8_table: UXTable<8>,
     /// U10Table
     pub u10_table: UXTable<10>,
-    /// U16Table
-    pub u16_table: UXTable<16>,
+    /// U16Tables, across which the u16 range checks of the limbs are spread
+    /// to reduce the number of lookups into each of them.  At least one is
+    /// needed.
+    pub u16_tables: Vec<UXTable<16>>,
     /// Challenges
     pub challenges: Challenges<Expression<F>>,
 }
@@ -99,24 +160,24 @@ impl<F: Field> SubCircuitConfig<F> for StateCircuitConfig<F> {
             mpt_table,
             u8_table,
             u10_table,
-            u16_table,
+            u16_tables,
             challenges,
         }: Self::ConfigArgs,
     ) -> Self {
         let selector = meta.fixed_column();
-        let lookups = LookupsChip::configure(meta, u8_table, u10_table, u16_table);
+        let lookups = LookupsChip::configure(meta, u8_table, u10_table, u16_tables.clone());
 
-        let rw_counter = MpiChip::configure(meta, selector, [rw_table.rw_counter], lookups);
+        let rw_counter = MpiChip::configure(meta, selector, [rw_table.rw_counter], &lookups);
         let tag = BinaryNumberChip::configure(meta, selector, Some(rw_table.tag));
-        let id = MpiChip::configure(meta, selector, [rw_table.id], lookups);
+        let id = MpiChip::configure(meta, selector, [rw_table.id], &lookups);
 
-        let address = MpiChip::configure(meta, selector, [rw_table.address], lookups);
+        let address = MpiChip::configure(meta, selector, [rw_table.address], &lookups);
 
         let storage_key = MpiChip::configure(
             meta,
             selector,
             [rw_table.storage_key.lo(), rw_table.storage_key.hi()],
-            lookups,
+            &lookups,
         );
         let initial_value = word::Word::new([meta.advice_column(), meta.advice_column()]);
 
@@ -133,8 +194,37 @@ impl<F: Field> SubCircuitConfig<F> for StateCircuitConfig<F> {
                 ]
             },
         );
+        let is_zero_value = BatchedIsZeroChip::configure(
+            meta,
+            (FirstPhase, FirstPhase),
+            |meta| meta.query_fixed(selector, Rotation::cur()),
+            |meta| {
+                [
+                    meta.query_advice(rw_table.value.lo(), Rotation::cur()),
+                    meta.query_advice(rw_table.value.hi(), Rotation::cur()),
+                ]
+            },
+        );
         let mpt_proof_type = meta.advice_column_in(SecondPhase);
+        let chunk_boundary = [(); 7].map(|_| meta.advice_column());
+        let has_chunk_boundary = meta.advice_column();
+        let is_chunk_boundary = meta.advice_column();
+        let fingerprint_challenges = [(); 2].map(|_| meta.advice_column());
+        let row_rlc = [(); 3].map(|_| meta.advice_column());
+        let fingerprint = meta.advice_column();
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        for column in chunk_boundary_columns(&rw_table)
+            .into_iter()
+            .chain(chunk_boundary)
+            .chain([has_chunk_boundary, fingerprint])
+            .chain(fingerprint_challenges)
+        {
+            meta.enable_equality(column);
+        }
         let state_root = word::Word::new([meta.advice_column(), meta.advice_column()]);
+        meta.enable_equality(state_root.lo());
+        meta.enable_equality(state_root.hi());
 
         let sort_keys = SortKeysConfig {
             tag,
@@ -147,20 +237,23 @@ impl<F: Field> SubCircuitConfig<F> for StateCircuitConfig<F> {
 
         let power_of_randomness: [Expression<F>; 31] = challenges.keccak_powers_of_randomness();
         let lexicographic_ordering =
-            LexicographicOrderingConfig::configure(meta, sort_keys, lookups, power_of_randomness);
+            LexicographicOrderingConfig::configure(meta, sort_keys, &lookups, power_of_randomness);
 
         // annotate columns
         rw_table.annotate_columns(meta);
         mpt_table.annotate_columns(meta);
         u8_table.annotate_columns(meta);
         u10_table.annotate_columns(meta);
-        u16_table.annotate_columns(meta);
+        for u16_table in &u16_tables {
+            u16_table.annotate_columns(meta);
+        }
 
         let config = Self {
             selector,
             sort_keys,
             initial_value,
             is_non_exist,
+            is_zero_value,
             mpt_proof_type,
             state_root,
             lexicographic_ordering,
@@ -168,9 +261,131 @@ impl<F: Field> SubCircuitConfig<F> for StateCircuitConfig<F> {
             lookups,
             rw_table,
             mpt_table,
+            chunk_boundary,
+            has_chunk_boundary,
+            is_chunk_boundary,
+            fingerprint_challenges,
+            row_rlc,
+            fingerprint,
+            instance,
             _marker: PhantomData::default(),
         };
 
+        // The boundary of the chunk is carried from its first row after the
+        // Start padding to the last row, which is copied to the public inputs.
+        meta.create_gate(
+            "chunk boundary is the first row after the Start padding",
+            |meta| {
+                let selector =
+                    meta.query_fixed(config.lexicographic_ordering.selector, Rotation::cur());
+                let tag = config.sort_keys.tag;
+                // degree = 4 + 4 = 8
+                let is_first_row = tag.value_equals(Target::Start, Rotation::prev())(meta)
+                    * (1.expr() - tag.value_equals(Target::Start, Rotation::cur())(meta));
+
+                let mut constraints = vec![];
+                for (boundary, key) in config
+                    .chunk_boundary
+                    .into_iter()
+                    .zip(chunk_boundary_columns(&config.rw_table))
+                {
+                    let boundary_prev = meta.query_advice(boundary, Rotation::prev());
+                    let boundary = meta.query_advice(boundary, Rotation::cur());
+                    let key = meta.query_advice(key, Rotation::cur());
+                    constraints
+                        .push(selector.clone() * is_first_row.clone() * (boundary.clone() - key));
+                    constraints.push(
+                        selector.clone()
+                            * (1.expr() - is_first_row.clone())
+                            * (boundary - boundary_prev),
+                    );
+                }
+
+                // The first row after the Start padding of a chunk after the
+                // first one is its boundary row.
+                let has_chunk_boundary =
+                    meta.query_advice(config.has_chunk_boundary, Rotation::cur());
+                let has_chunk_boundary_prev =
+                    meta.query_advice(config.has_chunk_boundary, Rotation::prev());
+                let is_chunk_boundary =
+                    meta.query_advice(config.is_chunk_boundary, Rotation::cur());
+                constraints.push(
+                    selector.clone() * (has_chunk_boundary.clone() - has_chunk_boundary_prev),
+                );
+                constraints.push(
+                    selector.clone()
+                        * has_chunk_boundary.clone()
+                        * (1.expr() - has_chunk_boundary.clone()),
+                );
+                // degree = 1 + 8 + 1 = 10
+                constraints
+                    .push(selector * (is_chunk_boundary - is_first_row * has_chunk_boundary));
+                constraints
+            },
+        );
+
+        // The fingerprint of the rws of a chunk is the product of (alpha -
+        // row_rlc) over its rows, but the Start padding and the boundary row,
+        // so that the fingerprints of the chunks, chained through the public
+        // inputs, add up to the fingerprint of the RwTable of the block.
+        meta.create_gate("fingerprint of the rws", |meta| {
+            let selector = meta.query_fixed(config.selector, Rotation::cur());
+            let lexicographic_ordering_selector =
+                meta.query_fixed(config.lexicographic_ordering.selector, Rotation::cur());
+            let [alpha, gamma] = config
+                .fingerprint_challenges
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+            let row_rlc = config
+                .row_rlc
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+            // The values of the row, from the last one, as accumulated by the
+            // RLC: row_rlc = sum(values[i] * gamma^i)
+            let values: Vec<_> = config
+                .rw_table
+                .table_exprs(meta)
+                .into_iter()
+                .rev()
+                .collect();
+            let rlc = |acc: Expression<F>, values: &[Expression<F>]| {
+                values
+                    .iter()
+                    .fold(acc, |acc, value| acc * gamma.clone() + value.clone())
+            };
+
+            let mut constraints = vec![
+                // degree = 1 + 5 = 6
+                selector.clone() * (row_rlc[0].clone() - rlc(0.expr(), &values[..5])),
+                // degree = 1 + 6 = 7
+                selector.clone() * (row_rlc[1].clone() - rlc(row_rlc[0].clone(), &values[5..10])),
+                // degree = 1 + 5 = 6
+                selector * (row_rlc[2].clone() - rlc(row_rlc[1].clone(), &values[10..])),
+            ];
+            for column in config.fingerprint_challenges {
+                constraints.push(
+                    lexicographic_ordering_selector.clone()
+                        * (meta.query_advice(column, Rotation::cur())
+                            - meta.query_advice(column, Rotation::prev())),
+                );
+            }
+            // degree = 4 + 1 = 5
+            let is_rw = (1.expr()
+                - config
+                    .sort_keys
+                    .tag
+                    .value_equals(Target::Start, Rotation::cur())(meta))
+                * (1.expr() - meta.query_advice(config.is_chunk_boundary, Rotation::cur()));
+            let fingerprint = meta.query_advice(config.fingerprint, Rotation::cur());
+            let fingerprint_prev = meta.query_advice(config.fingerprint, Rotation::prev());
+            // degree = 1 + 1 + 5 + 1 = 8
+            constraints.push(
+                lexicographic_ordering_selector
+                    * (fingerprint
+                        - fingerprint_prev
+                            * (1.expr() + is_rw * (alpha - row_rlc[2].clone() - 1.expr()))),
+            );
+            constraints
+        });
+
         let mut constraint_builder = ConstraintBuilder::new();
         meta.create_gate("state circuit constraints", |meta| {
             let queries = queries(meta, &config);
@@ -188,7 +403,7 @@ impl<F: Field> SubCircuitConfig<F> for StateCircuitConfig<F> {
 impl<F: Field> StateCircuitConfig<F> {
     /// load fixed tables
     pub(crate) fn load_aux_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
-        LookupsChip::construct(self.lookups).load(layouter)
+        LookupsChip::construct(self.lookups.clone()).load(layouter)
     }
 
     /// Make the assignments to the StateCircuit
@@ -199,17 +414,24 @@ impl<F: Field> StateCircuitConfig<F> {
         n_rows: usize, // 0 means dynamically calculated from `rows`.
     ) -> Result<(), Error> {
         let updates = MptUpdates::mock_from(rows);
+        let chunk = ChunkContext::first([F::ZERO; 2]);
         layouter.assign_region(
             || "state circuit",
-            |mut region| self.assign_with_region(&mut region, rows, &updates, n_rows),
+            |mut region| self.assign_with_region(&mut region, rows, &updates, &chunk, n_rows),
         )
     }
 
+    /// Assign the rows in a single region. Only the sort of the rws and their
+    /// ordering witness are computed in parallel: the layouter assigns the
+    /// regions one after the other, so splitting the rows in several regions
+    /// wouldn't assign them in parallel, and the test overrides have to be
+    /// assigned in the region of the rows.
     fn assign_with_region(
         &self,
         region: &mut Region<'_, F>,
         rows: &[Rw],
         updates: &MptUpdates,
+        chunk: &ChunkContext<F>,
         n_rows: usize, // 0 means dynamically calculated from `rows`.
     ) -> Result<(), Error> {
         let tag_chip = BinaryNumberChip::construct(self.sort_keys.tag);
@@ -218,6 +440,29 @@ impl<F: Field> StateCircuitConfig<F> {
         let rows_len = rows.len();
 
         let mut state_root = updates.old_root();
+        let chunk_boundary = chunk_boundary_values::<F>(&chunk_boundary(&rows));
+        let boundary_offset = rows.iter().position(|row| !matches!(row, Rw::Start { .. }));
+        let is_chunk_boundary =
+            |offset: usize| chunk.has_boundary && Some(offset) == boundary_offset;
+        // The boundary row has no MPT update of its own, and its initial value
+        // is its previous value.
+        let update = |offset: usize| {
+            if is_chunk_boundary(offset) {
+                None
+            } else {
+                updates.get(&rows[offset])
+            }
+        };
+        let [alpha, gamma] = chunk.fingerprint_challenges;
+        let mut fingerprint = chunk.prev_fingerprint;
+
+        // The ordering of each row only depends on the previous one, so it's
+        // computed in parallel, which saves the limb comparisons and the field
+        // inversions of the sequential assignment.
+        let orderings: Vec<LexicographicOrderingAssignment<F>> = rows
+            .par_windows(2)
+            .map(|rows| LexicographicOrderingAssignment::new(&rows[1], &rows[0]))
+            .collect();
 
         // annotate columns
         self.annotate_circuit_in_region(region);
@@ -255,10 +500,9 @@ impl<F: Field> StateCircuitConfig<F> {
             }
 
             if offset > 0 {
-                let prev_row = &rows[offset - 1];
-                let index = self
-                    .lexicographic_ordering
-                    .assign(region, offset, row, prev_row)?;
+                let index =
+                    self.lexicographic_ordering
+                        .assign(region, offset, &orderings[offset - 1])?;
                 let is_first_access =
                     !matches!(index, LimbIndex::RwCounter0 | LimbIndex::RwCounter1);
 
@@ -271,7 +515,7 @@ impl<F: Field> StateCircuitConfig<F> {
 
                 if is_first_access {
                     // If previous row was a last access, we need to update the state root.
-                    if let Some(update) = updates.get(prev_row) {
+                    if let Some(update) = update(offset - 1) {
                         let (new_root, old_root) = update.root_assignments();
                         assert_eq!(state_root, old_root);
                         state_root = new_root;
@@ -283,12 +527,13 @@ impl<F: Field> StateCircuitConfig<F> {
             }
 
             // The initial value can be determined from the mpt updates or is 0.
-            let initial_value = word::Word::<F>::from(
-                updates
-                    .get(row)
+            let initial_value = word::Word::<F>::from(if is_chunk_boundary(offset) {
+                row.value_prev_assignment().unwrap_or_default()
+            } else {
+                update(offset)
                     .map(|u| u.value_assignments().1)
-                    .unwrap_or_default(),
-            );
+                    .unwrap_or_default()
+            });
 
             initial_value.into_value().assign_advice(
                 region,
@@ -298,17 +543,8 @@ impl<F: Field> StateCircuitConfig<F> {
             )?;
 
             // Identify non-existing if both committed value and new value are zero.
-            let (committed_value, value) = {
-                let (_, committed_value) = updates
-                    .get(row)
-                    .map(|u| u.value_assignments())
-                    .unwrap_or_default();
-                let value = row.value_assignment();
-                (
-                    word::Word::<F>::from(committed_value),
-                    word::Word::<F>::from(value),
-                )
-            };
+            let (committed_value, value) =
+                (initial_value, word::Word::<F>::from(row.value_assignment()));
 
             BatchedIsZeroChip::construct(self.is_non_exist.clone()).assign(
                 region,
@@ -321,6 +557,12 @@ impl<F: Field> StateCircuitConfig<F> {
                 ]),
             )?;
 
+            BatchedIsZeroChip::construct(self.is_zero_value.clone()).assign(
+                region,
+                offset,
+                Value::known([value.lo(), value.hi()]),
+            )?;
+
             let mpt_proof_type = match row {
                 Rw::AccountStorage { .. } => {
                     if committed_value.is_zero_vartime() && value.is_zero_vartime() {
@@ -335,6 +577,11 @@ impl<F: Field> StateCircuitConfig<F> {
                         && matches!(field_tag, AccountFieldTag::CodeHash)
                     {
                         MPTProofType::AccountDoesNotExist as u64
+                    } else if !committed_value.is_zero_vartime()
+                        && value.is_zero_vartime()
+                        && matches!(field_tag, AccountFieldTag::CodeHash)
+                    {
+                        MPTProofType::AccountDestructed as u64
                     } else {
                         *field_tag as u64
                     }
@@ -349,6 +596,45 @@ impl<F: Field> StateCircuitConfig<F> {
                 || Value::known(F::from(mpt_proof_type)),
             )?;
 
+            for (column, value) in self.chunk_boundary.into_iter().zip(chunk_boundary) {
+                region.assign_advice(
+                    || "chunk_boundary",
+                    column,
+                    offset,
+                    || Value::known(value),
+                )?;
+            }
+            for (name, column, value) in [
+                (
+                    "has_chunk_boundary",
+                    self.has_chunk_boundary,
+                    F::from(chunk.has_boundary as u64),
+                ),
+                (
+                    "is_chunk_boundary",
+                    self.is_chunk_boundary,
+                    F::from(is_chunk_boundary(offset) as u64),
+                ),
+                ("fingerprint alpha", self.fingerprint_challenges[0], alpha),
+                ("fingerprint gamma", self.fingerprint_challenges[1], gamma),
+            ] {
+                region.assign_advice(|| name, column, offset, || Value::known(value))?;
+            }
+
+            let row_rlc = row_rlc(row, gamma);
+            for (column, value) in self.row_rlc.into_iter().zip(row_rlc) {
+                region.assign_advice(|| "row_rlc", column, offset, || Value::known(value))?;
+            }
+            if offset > 0 && !matches!(row, Rw::Start { .. }) && !is_chunk_boundary(offset) {
+                fingerprint *= alpha - row_rlc[2];
+            }
+            region.assign_advice(
+                || "fingerprint",
+                self.fingerprint,
+                offset,
+                || Value::known(fingerprint),
+            )?;
+
             // TODO: Switch from Rw::Start -> Rw::Padding to simplify this logic.
             // State root assignment is at previous row (offset - 1) because the state root
             // changes on the last access row.
@@ -361,7 +647,7 @@ impl<F: Field> StateCircuitConfig<F> {
             if offset == rows_len - 1 {
                 // The last row is always a last access, so we need to handle the case where the
                 // state root changes because of an mpt lookup on the last row.
-                if let Some(update) = updates.get(row) {
+                if let Some(update) = update(offset) {
                     state_root = {
                         let (new_root, old_root) = update.root_assignments();
                         assert_eq!(state_root, old_root);
@@ -373,6 +659,65 @@ impl<F: Field> StateCircuitConfig<F> {
                     .assign_advice(region, || "last row state_root", self.state_root, offset)?;
             }
         }
+        assert_eq!(state_root, updates.new_root());
+
+        // The sort keys of the boundary row and of the last row are the public
+        // inputs, so that the proofs of the chunks can be stitched together.
+        for (i, column) in self
+            .chunk_boundary
+            .into_iter()
+            .chain(chunk_boundary_columns(&self.rw_table))
+            .enumerate()
+        {
+            region.assign_advice_from_instance(
+                || "chunk boundary instance",
+                self.instance,
+                i,
+                column,
+                rows_len - 1,
+            )?;
+        }
+        // The state roots before and after the rws are the next public
+        // inputs, so that the proofs of the blocks can be chained.
+        for (i, (offset, column)) in [
+            (0, self.state_root.lo()),
+            (0, self.state_root.hi()),
+            (rows_len - 1, self.state_root.lo()),
+            (rows_len - 1, self.state_root.hi()),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            region.assign_advice_from_instance(
+                || "state root instance",
+                self.instance,
+                N_CHUNK_BOUNDARY_INSTANCES + i,
+                column,
+                offset,
+            )?;
+        }
+        // The chunk context is the last public inputs, so that the aggregator
+        // can check that the first chunk has no boundary row, that all the
+        // chunks have the same challenges, and that the fingerprints are
+        // chained.
+        for (i, (offset, column)) in [
+            (0, self.has_chunk_boundary),
+            (0, self.fingerprint_challenges[0]),
+            (0, self.fingerprint_challenges[1]),
+            (0, self.fingerprint),
+            (rows_len - 1, self.fingerprint),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            region.assign_advice_from_instance(
+                || "chunk context instance",
+                self.instance,
+                CHUNK_CONTEXT_INSTANCE_OFFSET + i,
+                column,
+                offset,
+            )?;
+        }
 
         Ok(())
     }
@@ -382,6 +727,8 @@ impl<F: Field> StateCircuitConfig<F> {
         self.mpt_table.annotate_columns_in_region(region);
         self.is_non_exist
             .annotate_columns_in_region(region, "STATE");
+        self.is_zero_value
+            .annotate_columns_in_region(region, "STATE_value");
         self.lexicographic_ordering
             .annotate_columns_in_region(region, "STATE");
         self.sort_keys.annotate_columns_in_region(region, "STATE");
@@ -392,6 +739,17 @@ impl<F: Field> StateCircuitConfig<F> {
         region.name_column(|| "STATE_mpt_proof_type", self.mpt_proof_type);
         region.name_column(|| "STATE_state_root lo", self.state_root.lo());
         region.name_column(|| "STATE_state_root hi", self.state_root.hi());
+        for (i, column) in self.chunk_boundary.into_iter().enumerate() {
+            region.name_column(|| format!("STATE_chunk_boundary {}", i), column);
+        }
+        region.name_column(|| "STATE_has_chunk_boundary", self.has_chunk_boundary);
+        region.name_column(|| "STATE_is_chunk_boundary", self.is_chunk_boundary);
+        region.name_column(|| "STATE_fingerprint alpha", self.fingerprint_challenges[0]);
+        region.name_column(|| "STATE_fingerprint gamma", self.fingerprint_challenges[1]);
+        for (i, column) in self.row_rlc.into_iter().enumerate() {
+            region.name_column(|| format!("STATE_row_rlc {}", i), column);
+        }
+        region.name_column(|| "STATE_fingerprint", self.fingerprint);
     }
 }
 
@@ -424,6 +782,7 @@ pub struct StateCircuit<F> {
     /// Rw rows
     pub rows: Vec<Rw>,
     updates: MptUpdates,
+    chunk: ChunkContext<F>,
     pub(crate) n_rows: usize,
     #[cfg(test)]
     overrides: HashMap<(dev::AdviceColumn, isize), F>,
@@ -431,26 +790,168 @@ pub struct StateCircuit<F> {
 }
 
 impl<F: Field> StateCircuit<F> {
-    /// make a new state circuit from an RwMap
+    /// make a new state circuit from an RwMap, with mock MPT updates
     pub fn new(rw_map: RwMap, n_rows: usize) -> Self {
         let rows = rw_map.table_assignments();
         let updates = MptUpdates::mock_from(&rows);
         Self {
             rows,
             updates,
+            chunk: ChunkContext::first([F::ZERO; 2]),
             n_rows,
             #[cfg(test)]
             overrides: HashMap::new(),
             _marker: PhantomData::default(),
         }
     }
+
+    /// Split the rws of an RwMap in chunks of `n_rows` rows, with their MPT
+    /// updates, to prove them with one StateCircuit per chunk.
+    ///
+    /// The rws are split between groups of rws with the same keys, so that
+    /// each chunk is valid on its own.  Every chunk but the first one starts
+    /// with a boundary row, which is a write of the initial value with the
+    /// sort keys of the last row of the previous chunk, to constrain the order
+    /// of the rws across the chunks.  The boundary row has no MPT update, and
+    /// isn't part of the fingerprint of the rws.  The public inputs of a chunk
+    /// are the sort keys of its first row after the Start padding, which is
+    /// its boundary row (ignored for the first chunk), and of its last row,
+    /// the state roots before and after its MPT updates, and its chunk
+    /// context.  The proofs of the chunks are stitched together by checking
+    /// that the boundary of each chunk is the last row of the previous one,
+    /// that its old state root is the new state root of the previous one, and
+    /// that its previous fingerprint is the fingerprint of the previous one,
+    /// so that the fingerprint of the last chunk is the one of all the rws.
+    ///
+    /// The `updates` are the MPT updates of all the rws, and the
+    /// `fingerprint_challenges` are the challenges (alpha, gamma) of the
+    /// fingerprints, which the aggregator derives from the commitments of all
+    /// the chunks.
+    pub fn new_chunks(
+        rw_map: RwMap,
+        updates: &MptUpdates,
+        n_rows: usize,
+        fingerprint_challenges: [F; 2],
+    ) -> Result<Vec<Self>, StateCircuitError> {
+        let rows: Vec<Rw> = rw_map
+            .table_assignments()
+            .into_iter()
+            .filter(|row| !matches!(row, Rw::Start { .. }))
+            .collect();
+        let mut chunks: Vec<Self> = vec![];
+        let mut start = 0;
+        while start < rows.len() || chunks.is_empty() {
+            // One row is left for the Start padding, and one for the boundary
+            // row of the chunks after the first one.
+            let capacity = n_rows.saturating_sub(1 + (start > 0) as usize);
+            let mut end = rows.len().min(start + capacity);
+            if end < rows.len() {
+                while end > start && is_same_group(&rows[end - 1], &rows[end]) {
+                    end -= 1;
+                }
+                if end == start {
+                    return Err(StateCircuitError::TooLargeRwGroup {
+                        num_rws: rows[start..]
+                            .iter()
+                            .take_while(|row| is_same_group(row, &rows[start]))
+                            .count(),
+                        max_rws: n_rows,
+                    });
+                }
+            }
+            let boundary = (start > 0).then(|| {
+                let last = &rows[start - 1];
+                let first = rows[..start]
+                    .iter()
+                    .rev()
+                    .take_while(|row| is_same_group(row, last))
+                    .last()
+                    .unwrap_or(last);
+                chunk_boundary_row(last, first.value_prev_assignment().unwrap_or_default())
+            });
+            let chunk_rows: Vec<Rw> = boundary
+                .into_iter()
+                .chain(rows[start..end].iter().copied())
+                .collect();
+            // The MPT updates of the chunk follow the ones of the previous
+            // chunk, and the fingerprint of its rws the one of the previous
+            // chunk.
+            let (old_root, prev_fingerprint) = match chunks.last() {
+                Some(prev) => (prev.updates.new_root(), prev.fingerprint()),
+                None => (updates.old_root(), F::ONE),
+            };
+            chunks.push(Self {
+                rows: chunk_rows,
+                updates: updates.slice(&rows[start..end], old_root),
+                chunk: ChunkContext {
+                    has_boundary: start > 0,
+                    fingerprint_challenges,
+                    prev_fingerprint,
+                },
+                n_rows,
+                #[cfg(test)]
+                overrides: HashMap::new(),
+                _marker: PhantomData::default(),
+            });
+            start = end;
+        }
+        Ok(chunks)
+    }
+
+    /// Set the challenges (alpha, gamma) of the fingerprint of the rws, which
+    /// are the ones of the chunks of the block if the rws are also proved in
+    /// chunks.
+    pub fn with_fingerprint_challenges(mut self, fingerprint_challenges: [F; 2]) -> Self {
+        self.chunk.fingerprint_challenges = fingerprint_challenges;
+        self
+    }
+
+    /// Return the fingerprint of the rws up to the end of the chunk, which is
+    /// the product of (alpha - RLC with gamma of the RwTable row) over the
+    /// rws, but the Start padding and the boundary row, of all the chunks up
+    /// to this one.
+    pub fn fingerprint(&self) -> F {
+        let [alpha, gamma] = self.chunk.fingerprint_challenges;
+        self.rows
+            .iter()
+            .filter(|row| !matches!(row, Rw::Start { .. }))
+            .skip(self.chunk.has_boundary as usize)
+            .fold(self.chunk.prev_fingerprint, |fingerprint, row| {
+                fingerprint * (alpha - row_rlc(row, gamma)[2])
+            })
+    }
+
+    /// Check that the rws fit in the rows of the circuit, before the padding
+    /// of the assignment panics on them.
+    pub fn validate(&self) -> Result<(), StateCircuitError> {
+        let num_rws = self
+            .rows
+            .iter()
+            .filter(|row| !matches!(row, Rw::Start { .. }))
+            .count();
+        StateCircuitError::check_max_rws(num_rws, self.n_rows)
+    }
 }
 
 impl<F: Field> SubCircuit<F> for StateCircuit<F> {
     type Config = StateCircuitConfig<F>;
 
     fn new_from_block(block: &witness::Block<F>) -> Self {
-        Self::new(block.rws.clone(), block.circuits_params.max_rws)
+        let rows = block.rws.table_assignments();
+        // Without MPT proofs, the roots of the updates are mocked.
+        let updates = block
+            .mpt_updates
+            .clone()
+            .unwrap_or_else(|| MptUpdates::mock_from(&rows));
+        Self {
+            rows,
+            updates,
+            chunk: ChunkContext::first([F::ZERO; 2]),
+            n_rows: block.circuits_params.max_rws,
+            #[cfg(test)]
+            overrides: HashMap::new(),
+            _marker: PhantomData::default(),
+        }
     }
 
     fn unusable_rows() -> usize {
@@ -486,7 +987,13 @@ impl<F: Field> SubCircuit<F> for StateCircuit<F> {
                     .rw_table
                     .load_with_region(&mut region, &self.rows, self.n_rows)?;
 
-                config.assign_with_region(&mut region, &self.rows, &self.updates, self.n_rows)?;
+                config.assign_with_region(
+                    &mut region,
+                    &self.rows,
+                    &self.updates,
+                    &self.chunk,
+                    self.n_rows,
+                )?;
                 #[cfg(test)]
                 {
                     let first_non_padding_index = if self.rows.len() < self.n_rows {
@@ -515,10 +1022,221 @@ impl<F: Field> SubCircuit<F> for StateCircuit<F> {
         )
     }
 
-    /// powers of randomness for instance columns
+    /// The sort keys of the boundary row and of the last row of the rws,
+    /// followed by the state roots before and after them, and by the chunk
+    /// context: whether the rws start with a boundary row, the challenges of
+    /// the fingerprint, and the fingerprints before and after the rws
     fn instance(&self) -> Vec<Vec<F>> {
-        vec![]
+        let (rows, _) = RwMap::table_assignments_prepad(&self.rows, self.n_rows);
+        let last = rows.last().copied().expect("at least one Start row");
+        let old_root = word::Word::<F>::from(self.updates.old_root());
+        let new_root = word::Word::<F>::from(self.updates.new_root());
+        let [alpha, gamma] = self.chunk.fingerprint_challenges;
+        vec![[chunk_boundary(&rows), last]
+            .iter()
+            .flat_map(chunk_boundary_values)
+            .chain([old_root.lo(), old_root.hi(), new_root.lo(), new_root.hi()])
+            .chain([
+                F::from(self.chunk.has_boundary as u64),
+                alpha,
+                gamma,
+                self.chunk.prev_fingerprint,
+                self.fingerprint(),
+            ])
+            .collect()]
+    }
+}
+
+/// Number of public inputs of the sort keys of the chunk boundaries, which are
+/// followed by the old and new state roots.
+const N_CHUNK_BOUNDARY_INSTANCES: usize = 14;
+
+/// Offset of the public inputs of the chunk context, after the old and new
+/// state roots
+const CHUNK_CONTEXT_INSTANCE_OFFSET: usize = N_CHUNK_BOUNDARY_INSTANCES + 4;
+
+/// The context of a chunk of the rws, which is proved by one StateCircuit
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ChunkContext<F> {
+    /// The chunk starts with a boundary row, which is a copy of the last row
+    /// of the previous chunk
+    pub has_boundary: bool,
+    /// Challenges (alpha, gamma) of the fingerprint of the rws, which are the
+    /// same for all the chunks
+    pub fingerprint_challenges: [F; 2],
+    /// Fingerprint of the rws of the previous chunks, which is 1 for the first
+    /// chunk
+    pub prev_fingerprint: F,
+}
+
+impl<F: Field> ChunkContext<F> {
+    /// Return the context of the first chunk, or of all the rws if they aren't
+    /// split in chunks
+    pub fn first(fingerprint_challenges: [F; 2]) -> Self {
+        Self {
+            has_boundary: false,
+            fingerprint_challenges,
+            prev_fingerprint: F::ONE,
+        }
+    }
+}
+
+/// Return the RLC with gamma of the RwTable row of the rw, accumulated from
+/// its last value in the three steps of the row_rlc columns
+fn row_rlc<F: Field>(row: &Rw, gamma: F) -> [F; 3] {
+    let values = row.table_assignment::<F>().unwrap().values();
+    let mut rlc = F::ZERO;
+    [&values[9..], &values[4..9], &values[..4]].map(|values| {
+        rlc = values
+            .iter()
+            .rev()
+            .fold(rlc, |rlc, value| rlc * gamma + value);
+        rlc
+    })
+}
+
+/// The columns of the sort keys of the rws, which are the public inputs of the
+/// chunk boundaries
+fn chunk_boundary_columns(rw_table: &RwTable) -> [Column<Advice>; 7] {
+    [
+        rw_table.rw_counter,
+        rw_table.tag,
+        rw_table.id,
+        rw_table.address,
+        rw_table.field_tag,
+        rw_table.storage_key.lo(),
+        rw_table.storage_key.hi(),
+    ]
+}
+
+/// Return the boundary row of the padded rows, which is the first row after the
+/// Start padding, or the last row if there are only Start rows.
+fn chunk_boundary(rows: &[Rw]) -> Rw {
+    rows.iter()
+        .copied()
+        .find(|row| !matches!(row, Rw::Start { .. }))
+        .or_else(|| rows.last().copied())
+        .expect("at least one Start row")
+}
+
+/// The values of the sort keys of `row` in the chunk boundary columns
+fn chunk_boundary_values<F: Field>(row: &Rw) -> [F; 7] {
+    let row = row.table_assignment::<F>().unwrap();
+    [
+        row.rw_counter,
+        row.tag,
+        row.id,
+        row.address,
+        row.field_tag,
+        row.storage_key.lo(),
+        row.storage_key.hi(),
+    ]
+}
+
+/// Return true if the rws have the same keys, i.e. if they're in the same
+/// access group.
+fn is_same_group(a: &Rw, b: &Rw) -> bool {
+    a.tag() == b.tag()
+        && a.id() == b.id()
+        && a.address() == b.address()
+        && a.field_tag() == b.field_tag()
+        && a.storage_key() == b.storage_key()
+}
+
+/// Return the boundary row of a chunk from the last row of the previous chunk,
+/// which has the same keys and is a write of the initial value of its group.
+/// It's the only access of its group in the chunk, so it's valid on its own,
+/// and it doesn't change the state root.
+fn chunk_boundary_row(row: &Rw, initial_value: Word) -> Rw {
+    let mut row = *row;
+    match &mut row {
+        Rw::Start { .. } => {}
+        Rw::TxAccessListAccount {
+            is_write,
+            is_warm,
+            is_warm_prev,
+            ..
+        }
+        | Rw::TxAccessListAccountStorage {
+            is_write,
+            is_warm,
+            is_warm_prev,
+            ..
+        } => {
+            *is_write = true;
+            *is_warm = false;
+            *is_warm_prev = false;
+        }
+        Rw::TxRefund {
+            is_write,
+            value,
+            value_prev,
+            ..
+        } => {
+            *is_write = true;
+            *value = 0;
+            *value_prev = 0;
+        }
+        Rw::Account {
+            is_write,
+            value,
+            value_prev,
+            ..
+        }
+        | Rw::AccountStorage {
+            is_write,
+            value,
+            value_prev,
+            ..
+        } => {
+            *is_write = true;
+            *value = initial_value;
+            *value_prev = initial_value;
+        }
+        Rw::CallContext {
+            is_write, value, ..
+        }
+        | Rw::Stack {
+            is_write, value, ..
+        }
+        | Rw::TxLog {
+            is_write, value, ..
+        } => {
+            *is_write = true;
+            *value = Word::zero();
+        }
+        Rw::Memory { is_write, byte, .. } => {
+            *is_write = true;
+            *byte = 0;
+        }
+        Rw::TxReceipt {
+            is_write, value, ..
+        } => {
+            *is_write = true;
+            *value = 0;
+        }
+        Rw::CreatedAccount {
+            is_write,
+            value,
+            value_prev,
+            ..
+        } => {
+            *is_write = true;
+            *value = 0;
+            *value_prev = 0;
+        }
+        Rw::TransientStorage {
+            is_write,
+            value,
+            value_prev,
+            ..
+        } => {
+            *is_write = true;
+            *value = Word::zero();
+            *value_prev = Word::zero();
+        }
     }
+    row
 }
 
 fn queries<F: Field>(meta: &mut VirtualCells<'_, F>, c: &StateCircuitConfig<F>) -> Queries<F> {
@@ -602,13 +1320,15 @@ fn queries<F: Field>(meta: &mut VirtualCells<'_, F>, c: &StateCircuitConfig<F>)
         initial_value: meta_query_word(meta, c.initial_value, Rotation::cur()),
         initial_value_prev: meta_query_word(meta, c.initial_value, Rotation::prev()),
         is_non_exist: meta.query_advice(c.is_non_exist.is_zero, Rotation::cur()),
+        is_zero_value: meta.query_advice(c.is_zero_value.is_zero, Rotation::cur()),
         mpt_proof_type: meta.query_advice(c.mpt_proof_type, Rotation::cur()),
-        lookups: LookupsQueries::new(meta, c.lookups),
+        lookups: LookupsQueries::new(meta, &c.lookups),
         first_different_limb: [0, 1, 2, 3]
             .map(|idx| meta.query_advice(first_different_limb.bits[idx], Rotation::cur())),
         not_first_access: meta.query_advice(c.not_first_access, Rotation::cur()),
         last_access: 1.expr() - meta.query_advice(c.not_first_access, Rotation::next()),
         state_root: meta_query_word(meta, c.state_root, Rotation::cur()),
         state_root_prev: meta_query_word(meta, c.state_root, Rotation::prev()),
+        is_chunk_boundary: meta.query_advice(c.is_chunk_boundary, Rotation::cur()),
     }
 }
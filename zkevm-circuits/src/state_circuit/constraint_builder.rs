@@ -6,7 +6,7 @@ use crate::{
     table::{AccountFieldTag, MPTProofType},
     util::{word, Expr},
 };
-use bus_mapping::operation::Target;
+use bus_mapping::operation::{CreatedAccountStatus, Target};
 use eth_types::Field;
 use gadgets::binary_number::BinaryNumberConfig;
 use halo2_proofs::plonk::Expression;
@@ -55,6 +55,7 @@ pub struct Queries<F: Field> {
     pub initial_value: word::Word<Expression<F>>,
     pub initial_value_prev: word::Word<Expression<F>>,
     pub is_non_exist: Expression<F>,
+    pub is_zero_value: Expression<F>,
     pub mpt_proof_type: Expression<F>,
     pub lookups: LookupsQueries<F>,
     pub first_different_limb: [Expression<F>; 4],
@@ -62,6 +63,7 @@ pub struct Queries<F: Field> {
     pub last_access: Expression<F>,
     pub state_root: word::Word<Expression<F>>,
     pub state_root_prev: word::Word<Expression<F>>,
+    pub is_chunk_boundary: Expression<F>,
 }
 
 type Constraint<F> = (&'static str, Expression<F>);
@@ -145,6 +147,12 @@ impl<F: Field> ConstraintBuilder<F> {
         self.condition(q.tag_matches(Target::TxLog), |cb| {
             cb.build_tx_log_constraints(q)
         });
+        self.condition(q.tag_matches(Target::TransientStorage), |cb| {
+            cb.build_transient_storage_constraints(q)
+        });
+        self.condition(q.tag_matches(Target::CreatedAccount), |cb| {
+            cb.build_created_account_constraints(q)
+        });
     }
 
     fn build_general_constraints(&mut self, q: &Queries<F>) {
@@ -180,6 +188,16 @@ impl<F: Field> ConstraintBuilder<F> {
             );
         });
 
+        // The boundary row of a chunk is a copy of the last row of the
+        // previous chunk, whose group is the last one of that chunk.
+        self.condition(q.is_chunk_boundary(), |cb| {
+            cb.require_equal(
+                "chunk boundary is the only access of its group",
+                q.last_access(),
+                1.expr(),
+            );
+        });
+
         // When all the keys in the current row and previous row are equal.
         self.condition(q.not_first_access.clone(), |cb| {
             cb.require_zero(
@@ -319,8 +337,10 @@ impl<F: Field> ConstraintBuilder<F> {
                 + (1.expr() - is_non_exist) * MPTProofType::StorageChanged.expr(),
         );
 
-        // ref. spec 4.1. MPT lookup for last access to (address, storage_key)
-        self.condition(q.last_access(), |cb| {
+        // ref. spec 4.1. MPT lookup for last access to (address, storage_key),
+        // but for the boundary row of a chunk, whose update is the one of the
+        // last row of the previous chunk.
+        self.condition(q.last_access() * not::expr(q.is_chunk_boundary()), |cb| {
             cb.add_lookup(
                 "mpt_update exists in mpt circuit for AccountStorage last access",
                 LookupBuilder::new()
@@ -334,6 +354,13 @@ impl<F: Field> ConstraintBuilder<F> {
                     .build(),
             );
         });
+        self.condition(q.is_chunk_boundary(), |cb| {
+            cb.require_word_equal(
+                "state_root is unchanged for a chunk boundary",
+                q.state_root(),
+                q.state_root_prev(),
+            );
+        });
 
         self.condition(q.not_first_access.clone(), |cb| {
             cb.require_word_equal(
@@ -437,32 +464,40 @@ impl<F: Field> ConstraintBuilder<F> {
         );
 
         // We use code_hash = 0 as non-existing account state.  code_hash: 0->0
-        // transition requires a non-existing proof.
+        // transition requires a non-existing proof, and code_hash: non-zero->0
+        // transition (the destruction of the account) requires a proof that
+        // removes the account leaf.
+        // is_code_hash degree = 3
+        let is_code_hash = generate_lagrange_base_polynomial(
+            q.field_tag(),
+            AccountFieldTag::CodeHash as usize,
+            [
+                AccountFieldTag::Nonce,
+                AccountFieldTag::Balance,
+                AccountFieldTag::CodeHash,
+            ]
+            .iter()
+            .map(|t| *t as usize),
+        );
         // is_non_exist degree = 4
-        //   q.is_non_exist() degree = 1
-        //   generate_lagrange_base_polynomial() degree = 3
-        let is_non_exist = q.is_non_exist()
-            * generate_lagrange_base_polynomial(
-                q.field_tag(),
-                AccountFieldTag::CodeHash as usize,
-                [
-                    AccountFieldTag::Nonce,
-                    AccountFieldTag::Balance,
-                    AccountFieldTag::CodeHash,
-                ]
-                .iter()
-                .map(|t| *t as usize),
-            );
+        let is_non_exist = q.is_non_exist() * is_code_hash.clone();
+        // is_destructed degree = 5
+        let is_destructed = q.is_zero_value() * not::expr(q.is_non_exist()) * is_code_hash;
+        // field_tag is CodeHash when is_non_exist or is_destructed are 1.
         self.require_equal(
-            "mpt_proof_type is field_tag or AccountDoesNotExist",
+            "mpt_proof_type is field_tag, AccountDoesNotExist or AccountDestructed",
             q.mpt_proof_type(),
-            // degree = max(4, 4 + 1) = 5
-            is_non_exist.expr() * MPTProofType::AccountDoesNotExist.expr()
-                + (1.expr() - is_non_exist) * q.field_tag(),
+            // degree = max(1, 4, 5) = 5
+            q.field_tag()
+                + is_non_exist
+                    * (MPTProofType::AccountDoesNotExist.expr() - AccountFieldTag::CodeHash.expr())
+                + is_destructed
+                    * (MPTProofType::AccountDestructed.expr() - AccountFieldTag::CodeHash.expr()),
         );
 
-        // last_access degree = 1
-        self.condition(q.last_access(), |cb| {
+        // last_access degree = 1.  The update of the boundary row of a chunk
+        // is the one of the last row of the previous chunk.
+        self.condition(q.last_access() * not::expr(q.is_chunk_boundary()), |cb| {
             cb.add_lookup(
                 "mpt_update exists in mpt circuit for Account last access",
                 LookupBuilder::new()
@@ -476,6 +511,13 @@ impl<F: Field> ConstraintBuilder<F> {
                     .build(),
             );
         });
+        self.condition(q.is_chunk_boundary(), |cb| {
+            cb.require_word_equal(
+                "state_root is unchanged for a chunk boundary",
+                q.state_root(),
+                q.state_root_prev(),
+            );
+        });
 
         self.condition(q.not_first_access.clone(), |cb| {
             cb.require_word_equal(
@@ -528,6 +570,73 @@ impl<F: Field> ConstraintBuilder<F> {
         );
     }
 
+    fn build_transient_storage_constraints(&mut self, q: &Queries<F>) {
+        self.require_zero("field_tag is 0 for TransientStorage", q.field_tag());
+
+        // The tx_id is the id of the rows, so an access group starts at every
+        // (tx_id, address, storage_key) transition.  The transient storage is
+        // reset at every tx, so the first access of each group reads 0.
+        self.require_word_zero("initial TransientStorage value is 0", q.initial_value());
+
+        // The transient storage is not part of the state trie
+        self.require_word_equal(
+            "state_root is unchanged for TransientStorage",
+            q.state_root(),
+            q.state_root_prev(),
+        );
+
+        self.condition(q.not_first_access.clone(), |cb| {
+            cb.require_word_equal(
+                "value column at Rotation::prev() equals value_prev at Rotation::cur()",
+                q.rw_table.value_prev.clone(),
+                q.value_prev_column(),
+            );
+        });
+    }
+
+    fn build_created_account_constraints(&mut self, q: &Queries<F>) {
+        self.require_zero("field_tag is 0 for CreatedAccount", q.field_tag());
+        self.require_word_zero(
+            "storage_key is 0 for CreatedAccount",
+            q.rw_table.storage_key.clone(),
+        );
+        self.require_zero("CreatedAccount value hi is 0", q.rw_table.value.hi());
+        self.require_in_set(
+            "CreatedAccount value in CreatedAccountStatus range",
+            q.rw_table.value.lo(),
+            set::<F, CreatedAccountStatus>(),
+        );
+
+        // As for the transient storage, an access group starts at every
+        // (tx_id, address) transition, and no account has been created at
+        // the beginning of a tx.
+        self.require_word_zero("initial CreatedAccount value is 0", q.initial_value());
+
+        self.require_word_equal(
+            "state_root is unchanged for CreatedAccount",
+            q.state_root(),
+            q.state_root_prev(),
+        );
+
+        self.condition(q.not_first_access.clone(), |cb| {
+            cb.require_word_equal(
+                "value column at Rotation::prev() equals value_prev at Rotation::cur()",
+                q.rw_table.value_prev.clone(),
+                q.value_prev_column(),
+            );
+        });
+
+        // Every destructed account is deleted by a DeleteAccount step, which
+        // resets its status, at the end of the tx.
+        self.condition(q.last_access(), |cb| {
+            cb.require_zero(
+                "destructed accounts are deleted at the end of the tx",
+                q.rw_table.value.lo()
+                    * (q.rw_table.value.lo() - CreatedAccountStatus::Created.expr()),
+            );
+        });
+    }
+
     fn require_zero(&mut self, name: &'static str, e: Expression<F>) {
         self.constraints.push((name, self.condition.clone() * e));
     }
@@ -622,6 +731,10 @@ impl<F: Field> Queries<F> {
         self.is_non_exist.clone()
     }
 
+    fn is_zero_value(&self) -> Expression<F> {
+        self.is_zero_value.clone()
+    }
+
     fn mpt_proof_type(&self) -> Expression<F> {
         self.mpt_proof_type.clone()
     }
@@ -654,6 +767,10 @@ impl<F: Field> Queries<F> {
         self.state_root_prev.clone()
     }
 
+    fn is_chunk_boundary(&self) -> Expression<F> {
+        self.is_chunk_boundary.clone()
+    }
+
     fn value_prev_column(&self) -> word::Word<Expression<F>> {
         self.rw_table.value_prev_column.clone()
     }
@@ -29,7 +29,9 @@ where
         let challenges = Challenges::construct(meta);
         let u8_table = UXTable::construct(meta);
         let u10_table = UXTable::construct(meta);
-        let u16_table = UXTable::construct(meta);
+        // The range checks are spread across two u16 tables, so that the tests
+        // cover the lookups into more than one of them.
+        let u16_tables = vec![UXTable::construct(meta), UXTable::construct(meta)];
 
         let config = {
             let challenges = challenges.exprs(meta);
@@ -40,7 +42,7 @@ where
                     mpt_table,
                     u8_table,
                     u10_table,
-                    u16_table,
+                    u16_tables,
                     challenges,
                 },
             )
@@ -97,6 +99,8 @@ pub(crate) enum AdviceColumn {
     // NonEmptyWitness is the BatchedIsZero chip witness that contains the
     // inverse of the non-zero value if any in [committed_value, value]
     NonEmptyWitness,
+    MptProofType,
+    StateRootLo,
 }
 
 #[cfg(test)]
@@ -132,6 +136,8 @@ impl AdviceColumn {
             Self::InitialValueHi => config.initial_value.hi(),
             Self::IsZero => config.is_non_exist.is_zero,
             Self::NonEmptyWitness => config.is_non_exist.nonempty_witness,
+            Self::MptProofType => config.mpt_proof_type,
+            Self::StateRootLo => config.state_root.lo(),
         }
     }
 }
@@ -106,7 +106,7 @@ impl Config {
     pub fn configure<F: Field>(
         meta: &mut ConstraintSystem<F>,
         keys: SortKeysConfig,
-        lookup: lookups::Config,
+        lookup: &lookups::Config,
         powers_of_randomness: [Expression<F>; N_BYTES_WORD - 1],
     ) -> Self {
         let selector = meta.fixed_column();
@@ -182,15 +182,13 @@ impl Config {
         config
     }
 
-    // Returns true if the `cur` row is a first access to a group (at least one of
-    // tag, id, address, field_tag, or storage_key is different from the one in
-    // `prev`), and false otherwise.
+    // Returns the index of the first different limb of the assignment, which is
+    // in the rw counter if the row isn't a first access to a group.
     pub fn assign<F: Field>(
         &self,
         region: &mut Region<'_, F>,
         offset: usize,
-        cur: &Rw,
-        prev: &Rw,
+        assignment: &Assignment<F>,
     ) -> Result<LimbIndex, Error> {
         region.assign_fixed(
             || "upper_limb_difference",
@@ -199,36 +197,26 @@ impl Config {
             || Value::known(F::ONE),
         )?;
 
-        let cur_be_limbs = rw_to_be_limbs(cur);
-        let prev_be_limbs = rw_to_be_limbs(prev);
-
-        let find_result = LimbIndex::iter()
-            .zip(&cur_be_limbs)
-            .zip(&prev_be_limbs)
-            .find(|((_, a), b)| a != b);
-        let ((index, cur_limb), prev_limb) = if cfg!(test) {
-            find_result.unwrap_or(((LimbIndex::RwCounter0, &0), &0))
-        } else {
-            find_result.expect("repeated rw counter")
-        };
-
-        BinaryNumberChip::construct(self.first_different_limb).assign(region, offset, &index)?;
+        BinaryNumberChip::construct(self.first_different_limb).assign(
+            region,
+            offset,
+            &assignment.index,
+        )?;
 
-        let limb_difference = F::from(*cur_limb as u64) - F::from(*prev_limb as u64);
         region.assign_advice(
             || "limb_difference",
             self.limb_difference,
             offset,
-            || Value::known(limb_difference),
+            || Value::known(assignment.limb_difference),
         )?;
         region.assign_advice(
             || "limb_difference_inverse",
             self.limb_difference_inverse,
             offset,
-            || Value::known(limb_difference.invert().unwrap()),
+            || Value::known(assignment.limb_difference_inverse),
         )?;
 
-        Ok(index)
+        Ok(assignment.index)
     }
 
     /// Annotates columns of this gadget embedded within a circuit region.
@@ -247,6 +235,40 @@ impl Config {
     }
 }
 
+/// The witness of the ordering of a row after the previous one.  It only
+/// depends on the two rows, so the witnesses of all the rows can be computed in
+/// parallel before being assigned.
+#[derive(Clone, Copy, Debug)]
+pub struct Assignment<F> {
+    index: LimbIndex,
+    limb_difference: F,
+    limb_difference_inverse: F,
+}
+
+impl<F: Field> Assignment<F> {
+    pub fn new(cur: &Rw, prev: &Rw) -> Self {
+        let cur_be_limbs = rw_to_be_limbs(cur);
+        let prev_be_limbs = rw_to_be_limbs(prev);
+
+        let find_result = LimbIndex::iter()
+            .zip(&cur_be_limbs)
+            .zip(&prev_be_limbs)
+            .find(|((_, a), b)| a != b);
+        let ((index, cur_limb), prev_limb) = if cfg!(test) {
+            find_result.unwrap_or(((LimbIndex::RwCounter0, &0), &0))
+        } else {
+            find_result.expect("repeated rw counter")
+        };
+
+        let limb_difference = F::from(*cur_limb as u64) - F::from(*prev_limb as u64);
+        Self {
+            index,
+            limb_difference,
+            limb_difference_inverse: limb_difference.invert().unwrap(),
+        }
+    }
+}
+
 struct Queries<F: Field> {
     tag: Expression<F>,       // 4 bits
     field_tag: Expression<F>, // 8 bits, so we can pack tag + field_tag into one limb.
@@ -9,13 +9,14 @@ use itertools::Itertools;
 use std::marker::PhantomData;
 use strum::IntoEnumIterator;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub struct Config {
     // Can these be TableColumn's?
     // https://github.com/zcash/halo2/blob/642efc1536d3ea2566b04814bd60a00c4745ae22/halo2_proofs/src/plonk/circuit.rs#L266
     u8_table: UXTable<8>,
     u10_table: UXTable<10>,
-    u16_table: UXTable<16>,
+    // The u16 range checks are spread across these tables in turn
+    u16_tables: Vec<UXTable<16>>,
     pub call_context_field_tag: Column<Fixed>,
 }
 
@@ -26,11 +27,12 @@ impl Config {
         msg: &'static str,
         exp_fn: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
     ) {
+        let u16_table = self.u16_tables[meta.lookups().len() % self.u16_tables.len()];
         meta.lookup_any(msg, |meta| {
             let exp = exp_fn(meta);
             vec![exp]
                 .into_iter()
-                .zip_eq(self.u16_table.table_exprs(meta))
+                .zip_eq(u16_table.table_exprs(meta))
                 .map(|(exp, table_expr)| (exp, table_expr))
                 .collect()
         });
@@ -46,11 +48,11 @@ pub struct Queries<F> {
 }
 
 impl<F: Field> Queries<F> {
-    pub fn new(meta: &mut VirtualCells<'_, F>, c: Config) -> Self {
+    pub fn new(meta: &mut VirtualCells<'_, F>, c: &Config) -> Self {
         Self {
             u8: c.u8_table.table_exprs(meta)[0].clone(),
             u10: c.u10_table.table_exprs(meta)[0].clone(),
-            u16: c.u16_table.table_exprs(meta)[0].clone(),
+            u16: c.u16_tables[0].table_exprs(meta)[0].clone(),
             call_context_field_tag: meta.query_fixed(c.call_context_field_tag, Rotation::cur()),
         }
     }
@@ -73,12 +75,13 @@ impl<F: Field> Chip<F> {
         meta: &mut ConstraintSystem<F>,
         u8_table: UXTable<8>,
         u10_table: UXTable<10>,
-        u16_table: UXTable<16>,
+        u16_tables: Vec<UXTable<16>>,
     ) -> Config {
+        assert!(!u16_tables.is_empty(), "at least one u16 table is needed");
         let config = Config {
             u8_table,
             u10_table,
-            u16_table,
+            u16_tables,
             call_context_field_tag: meta.fixed_column(),
         };
         meta.annotate_lookup_any_column(config.call_context_field_tag, || {
@@ -90,7 +93,9 @@ impl<F: Field> Chip<F> {
     pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
         self.config.u8_table.load(layouter)?;
         self.config.u10_table.load(layouter)?;
-        self.config.u16_table.load(layouter)?;
+        for u16_table in &self.config.u16_tables {
+            u16_table.load(layouter)?;
+        }
         layouter.assign_region(
             || "assign call_context_field_tags fixed column",
             |mut region| {
@@ -124,7 +124,7 @@ where
         meta: &mut ConstraintSystem<F>,
         selector: Column<Fixed>,
         values: [Column<Advice>; N_VALUES],
-        lookup: lookups::Config,
+        lookup: &lookups::Config,
     ) -> Config<T, N_LIMBS> {
         assert_eq!(N_LIMBS & N_VALUES, 0);
         let limbs_per_value = N_LIMBS / N_VALUES;
@@ -1,11 +1,12 @@
 pub use super::{dev::*, *};
 use crate::{
     table::{AccountFieldTag, CallContextFieldTag, TxLogFieldTag, TxReceiptFieldTag},
-    util::{unusable_rows, SubCircuit},
+    util::{unusable_rows, word, SubCircuit},
     witness::{MptUpdates, Rw, RwMap},
 };
 use bus_mapping::operation::{
-    MemoryOp, Operation, OperationContainer, RWCounter, StackOp, StorageOp, RW,
+    CreatedAccountStatus, MemoryOp, Operation, OperationContainer, RWCounter, StackOp, StorageOp,
+    RW,
 };
 use eth_types::{
     address,
@@ -90,6 +91,270 @@ fn verifying_key_independent_of_rw_length() {
     );
 }
 
+#[test]
+fn too_many_rws() {
+    let rw_map = RwMap::from(&OperationContainer {
+        memory: (0..3)
+            .map(|i| {
+                Operation::new(
+                    RWCounter::from(i + 1),
+                    RW::WRITE,
+                    MemoryOp::new(1, MemoryAddress::from(i), 32),
+                )
+            })
+            .collect(),
+        ..Default::default()
+    });
+
+    assert_eq!(
+        StateCircuit::<Fr>::new(rw_map.clone(), 4).validate(),
+        Ok(())
+    );
+    assert_eq!(
+        StateCircuit::<Fr>::new(rw_map.clone(), 0).validate(),
+        Ok(())
+    );
+    assert_eq!(
+        StateCircuit::<Fr>::new(rw_map, 3).validate(),
+        Err(StateCircuitError::TooManyRws {
+            num_rws: 3,
+            max_rws: 3
+        })
+    );
+}
+
+fn chunked_rw_map() -> RwMap {
+    RwMap::from(&OperationContainer {
+        memory: (0..5)
+            .map(|i| {
+                Operation::new(
+                    RWCounter::from(i + 1),
+                    RW::WRITE,
+                    MemoryOp::new(1, MemoryAddress::from(i), i as u8),
+                )
+            })
+            .collect(),
+        storage: (0..3)
+            .map(|i| {
+                Operation::new(
+                    RWCounter::from(i + 6),
+                    RW::WRITE,
+                    StorageOp::new(
+                        address!("0x000000000000000000000000000000000cafe002"),
+                        Word::from(0x40),
+                        Word::from(i + 1),
+                        Word::from(i),
+                        1,
+                        Word::zero(),
+                    ),
+                )
+            })
+            .collect(),
+        ..Default::default()
+    })
+}
+
+/// Return the chunks of the rws of `chunked_rw_map`, with mock MPT updates
+fn chunks(n_rows: usize) -> Result<Vec<StateCircuit<Fr>>, StateCircuitError> {
+    let rw_map = chunked_rw_map();
+    let updates = MptUpdates::mock_from(&rw_map.table_assignments());
+    StateCircuit::<Fr>::new_chunks(rw_map, &updates, n_rows, fingerprint_challenges())
+}
+
+fn fingerprint_challenges() -> [Fr; 2] {
+    [Fr::from(0xa1), Fr::from(0x6a)]
+}
+
+#[test]
+fn chunks_ok() {
+    let chunks = chunks(5).unwrap();
+    // The storage rws are in the same group, so they're moved to the last chunk.
+    assert_eq!(
+        chunks
+            .iter()
+            .map(|chunk| chunk.rows.len())
+            .collect::<Vec<_>>(),
+        vec![4, 2, 4]
+    );
+
+    let instances: Vec<Vec<Fr>> = chunks
+        .iter()
+        .map(|chunk| chunk.instance().remove(0))
+        .collect();
+    for (chunk, instance) in chunks.iter().zip(&instances) {
+        let prover = MockProver::<Fr>::run(17, chunk, vec![instance.clone()]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    // The chunks are stitched together by their boundaries, their state roots
+    // and their fingerprints, with the same challenges.
+    let [alpha, gamma] = fingerprint_challenges();
+    let roots = N_CHUNK_BOUNDARY_INSTANCES;
+    let context = CHUNK_CONTEXT_INSTANCE_OFFSET;
+    assert_eq!(
+        instances[0][context..context + 4],
+        [Fr::ZERO, alpha, gamma, Fr::ONE]
+    );
+    for instances in instances.windows(2) {
+        let [prev, next] = [&instances[0], &instances[1]];
+        assert_eq!(next[..7], prev[7..14]);
+        assert_eq!(next[roots..roots + 2], prev[roots + 2..roots + 4]);
+        assert_eq!(next[context..context + 3], [Fr::ONE, alpha, gamma]);
+        assert_eq!(next[context + 3], prev[context + 4]);
+    }
+
+    // The state roots and the fingerprint of the chunks are the ones of all
+    // the rws.
+    let circuit = StateCircuit::<Fr>::new(chunked_rw_map(), N_ROWS)
+        .with_fingerprint_challenges(fingerprint_challenges());
+    let instance = circuit.instance().remove(0);
+    let [first, last] = [&instances[0], &instances[instances.len() - 1]];
+    assert_eq!(instance[roots..roots + 2], first[roots..roots + 2]);
+    assert_eq!(instance[roots + 2..roots + 4], last[roots + 2..roots + 4]);
+    assert_eq!(instance[context + 4], last[context + 4]);
+    let prover = MockProver::<Fr>::run(17, &circuit, vec![instance]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn chunks_bad_boundary() {
+    let chunks = chunks(5).unwrap();
+    let mut instance = chunks[1].instance();
+    // The rw_counter of the boundary row
+    instance[0][0] += Fr::ONE;
+
+    let prover = MockProver::<Fr>::run(17, &chunks[1], instance).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+#[test]
+fn chunks_bad_fingerprint() {
+    let chunks = chunks(5).unwrap();
+    for i in [3, 4] {
+        let mut instance = chunks[1].instance();
+        // The fingerprints before and after the chunk
+        instance[0][CHUNK_CONTEXT_INSTANCE_OFFSET + i] += Fr::ONE;
+
+        let prover = MockProver::<Fr>::run(17, &chunks[1], instance).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[test]
+fn chunks_without_boundary() {
+    let chunks = chunks(5).unwrap();
+    let mut instance = chunks[2].instance();
+    // Without its boundary row, the rws of the chunk would include the copy of
+    // the last row of the previous chunk.
+    instance[0][CHUNK_CONTEXT_INSTANCE_OFFSET] = Fr::ZERO;
+
+    let prover = MockProver::<Fr>::run(17, &chunks[2], instance).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+#[test]
+fn chunks_storage_boundary() {
+    let storage_write = |rw_counter: usize, key: u64, value: u64| {
+        Operation::new(
+            RWCounter::from(rw_counter),
+            RW::WRITE,
+            StorageOp::new(
+                address!("0x000000000000000000000000000000000cafe002"),
+                Word::from(key),
+                Word::from(value),
+                Word::from(value - 1),
+                1,
+                Word::zero(),
+            ),
+        )
+    };
+    let rw_map = RwMap::from(&OperationContainer {
+        storage: vec![
+            storage_write(1, 1, 1),
+            storage_write(2, 1, 2),
+            storage_write(3, 2, 1),
+            storage_write(4, 2, 2),
+        ],
+        ..Default::default()
+    });
+    let updates = MptUpdates::mock_from(&rw_map.table_assignments());
+    let chunks =
+        StateCircuit::<Fr>::new_chunks(rw_map, &updates, 4, fingerprint_challenges()).unwrap();
+    assert_eq!(
+        chunks
+            .iter()
+            .map(|chunk| chunk.rows.len())
+            .collect::<Vec<_>>(),
+        vec![2, 3]
+    );
+
+    // The boundary row of the second chunk is a copy of the last write to the
+    // first key, whose MPT update is in the first chunk.
+    let instances: Vec<Vec<Fr>> = chunks
+        .iter()
+        .map(|chunk| chunk.instance().remove(0))
+        .collect();
+    for (chunk, instance) in chunks.iter().zip(&instances) {
+        let prover = MockProver::<Fr>::run(17, chunk, vec![instance.clone()]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+    let roots = N_CHUNK_BOUNDARY_INSTANCES;
+    assert_eq!(
+        instances[1][roots..roots + 2],
+        instances[0][roots + 2..roots + 4]
+    );
+    assert_eq!(
+        [chunks[0].updates.old_root(), chunks[1].updates.new_root()],
+        [updates.old_root(), updates.new_root()]
+    );
+}
+
+#[test]
+fn state_roots_instance() {
+    let rows = vec![storage_write_row()];
+    let updates = MptUpdates::mock_from(&rows);
+    let circuit = StateCircuit::<Fr> {
+        rows,
+        updates: updates.clone(),
+        chunk: ChunkContext::first([Fr::ZERO; 2]),
+        overrides: HashMap::new(),
+        n_rows: N_ROWS,
+        _marker: std::marker::PhantomData::default(),
+    };
+
+    let old_root = word::Word::<Fr>::from(updates.old_root());
+    let new_root = word::Word::<Fr>::from(updates.new_root());
+    assert_eq!(
+        circuit.instance()[0][N_CHUNK_BOUNDARY_INSTANCES..],
+        [old_root.lo(), old_root.hi(), new_root.lo(), new_root.hi()]
+    );
+}
+
+#[test]
+fn bad_old_state_root_instance() {
+    // The old state root is copied from the first Start row
+    let instance_overrides = HashMap::from([(N_CHUNK_BOUNDARY_INSTANCES, Fr::from(0xbeef))]);
+
+    let result = prover_with_instance_overrides(
+        vec![storage_write_row()],
+        HashMap::new(),
+        instance_overrides,
+    )
+    .verify();
+    assert!(result.is_err());
+}
+
+#[test]
+fn chunks_too_large_rw_group() {
+    assert_eq!(
+        chunks(3).err(),
+        Some(StateCircuitError::TooLargeRwGroup {
+            num_rws: 3,
+            max_rws: 3
+        })
+    );
+}
+
 #[test]
 fn state_circuit_simple_2() {
     let memory_op_0 = Operation::new(
@@ -279,6 +544,143 @@ fn diff_1_problem_repro() {
     assert_eq!(verify(rows), Ok(()));
 }
 
+fn destructed_account_rows() -> Vec<Rw> {
+    let account_address = address!("0x000000000000000000000000000000000cafe002");
+    let code_hash = U256::from(0xc0de);
+    vec![
+        Rw::Account {
+            rw_counter: 1,
+            is_write: false,
+            account_address,
+            field_tag: AccountFieldTag::CodeHash,
+            value: code_hash,
+            value_prev: code_hash,
+        },
+        Rw::Account {
+            rw_counter: 2,
+            is_write: true,
+            account_address,
+            field_tag: AccountFieldTag::Nonce,
+            value: U256::zero(),
+            value_prev: U256::one(),
+        },
+        Rw::Account {
+            rw_counter: 3,
+            is_write: true,
+            account_address,
+            field_tag: AccountFieldTag::CodeHash,
+            value: U256::zero(),
+            value_prev: code_hash,
+        },
+        // Reads after the destruction see the cleared account
+        Rw::Account {
+            rw_counter: 4,
+            is_write: false,
+            account_address,
+            field_tag: AccountFieldTag::CodeHash,
+            value: U256::zero(),
+            value_prev: U256::zero(),
+        },
+    ]
+}
+
+#[test]
+fn destructed_account_ok() {
+    assert_eq!(verify(destructed_account_rows()), Ok(()));
+}
+
+#[test]
+fn destructed_account_bad_mpt_proof_type() {
+    // The write of the code hash is the row at offset 2 once sorted, which isn't
+    // the last access, so the proof type isn't looked up in the MptTable.
+    let overrides = HashMap::from([(
+        (AdviceColumn::MptProofType, 2),
+        Fr::from(AccountFieldTag::CodeHash as u64),
+    )]);
+
+    assert_error_matches(
+        verify_with_overrides(destructed_account_rows(), overrides),
+        "mpt_proof_type is field_tag, AccountDoesNotExist or AccountDestructed",
+    );
+}
+
+fn storage_write_row() -> Rw {
+    Rw::AccountStorage {
+        rw_counter: 1,
+        is_write: true,
+        account_address: address!("0x000000000000000000000000000000000cafe002"),
+        storage_key: U256::from(6),
+        value: U256::from(35),
+        value_prev: U256::from(34),
+        tx_id: 4,
+        committed_value: U256::from(34),
+    }
+}
+
+#[test]
+fn storage_write_bad_mpt_old_value() {
+    // The old value of the write isn't the one of the MPT update
+    let overrides = HashMap::from([
+        ((AdviceColumn::InitialValueHi, 0), Fr::ZERO),
+        ((AdviceColumn::InitialValueLo, 0), Fr::from(33)),
+        ((AdviceColumn::ValuePrevHi, 0), Fr::ZERO),
+        ((AdviceColumn::ValuePrevLo, 0), Fr::from(33)),
+        (
+            (AdviceColumn::NonEmptyWitness, 0),
+            Fr::from(33).invert().unwrap(),
+        ),
+    ]);
+
+    assert_error_matches(
+        verify_with_overrides(vec![storage_write_row()], overrides),
+        "mpt_update exists in mpt circuit for AccountStorage last access",
+    );
+}
+
+#[test]
+fn storage_write_bad_mpt_new_root() {
+    // The state root after the write isn't the new root of the MPT update
+    let overrides = HashMap::from([((AdviceColumn::StateRootLo, 0), Fr::from(0xbeef))]);
+    // The new state root is also a public input, after the old one.
+    let instance_overrides = HashMap::from([(N_CHUNK_BOUNDARY_INSTANCES + 2, Fr::from(0xbeef))]);
+
+    let result =
+        prover_with_instance_overrides(vec![storage_write_row()], overrides, instance_overrides)
+            .verify_at_rows(N_ROWS - 1..N_ROWS, N_ROWS - 1..N_ROWS);
+    assert_error_matches(
+        result,
+        "mpt_update exists in mpt circuit for AccountStorage last access",
+    );
+}
+
+#[test]
+fn account_write_bad_mpt_old_value() {
+    let rows = vec![Rw::Account {
+        rw_counter: 1,
+        is_write: true,
+        account_address: address!("0x000000000000000000000000000000000cafe002"),
+        field_tag: AccountFieldTag::Balance,
+        value: U256::from(200),
+        value_prev: U256::from(100),
+    }];
+    // The old value of the write isn't the one of the MPT update
+    let overrides = HashMap::from([
+        ((AdviceColumn::InitialValueHi, 0), Fr::ZERO),
+        ((AdviceColumn::InitialValueLo, 0), Fr::from(150)),
+        ((AdviceColumn::ValuePrevHi, 0), Fr::ZERO),
+        ((AdviceColumn::ValuePrevLo, 0), Fr::from(150)),
+        (
+            (AdviceColumn::NonEmptyWitness, 0),
+            Fr::from(150).invert().unwrap(),
+        ),
+    ]);
+
+    assert_error_matches(
+        verify_with_overrides(rows, overrides),
+        "mpt_update exists in mpt circuit for Account last access",
+    );
+}
+
 #[test]
 fn tx_log_ok() {
     let rows = vec![
@@ -355,6 +757,139 @@ fn tx_log_bad() {
     assert_error_matches(verify(rows), "is_write is always true for TxLog");
 }
 
+#[test]
+fn transient_storage_ok() {
+    let rows = vec![
+        Rw::TransientStorage {
+            rw_counter: 1,
+            is_write: true,
+            tx_id: 1,
+            account_address: address!("0x000000000000000000000000000000000cafe002"),
+            storage_key: U256::from(2),
+            value: U256::from(394500u64),
+            value_prev: U256::zero(),
+        },
+        Rw::TransientStorage {
+            rw_counter: 2,
+            is_write: false,
+            tx_id: 1,
+            account_address: address!("0x000000000000000000000000000000000cafe002"),
+            storage_key: U256::from(2),
+            value: U256::from(394500u64),
+            value_prev: U256::from(394500u64),
+        },
+        // The transient storage of the next tx is empty
+        Rw::TransientStorage {
+            rw_counter: 3,
+            is_write: false,
+            tx_id: 2,
+            account_address: address!("0x000000000000000000000000000000000cafe002"),
+            storage_key: U256::from(2),
+            value: U256::zero(),
+            value_prev: U256::zero(),
+        },
+    ];
+
+    assert_eq!(verify(rows), Ok(()));
+}
+
+#[test]
+fn transient_storage_bad_value_prev() {
+    let rows = vec![
+        Rw::TransientStorage {
+            rw_counter: 1,
+            is_write: true,
+            tx_id: 1,
+            account_address: address!("0x000000000000000000000000000000000cafe002"),
+            storage_key: U256::from(2),
+            value: U256::from(394500u64),
+            value_prev: U256::zero(),
+        },
+        Rw::TransientStorage {
+            rw_counter: 2,
+            is_write: true,
+            tx_id: 1,
+            account_address: address!("0x000000000000000000000000000000000cafe002"),
+            storage_key: U256::from(2),
+            value: U256::from(1u64),
+            value_prev: U256::from(2u64),
+        },
+    ];
+
+    assert_error_matches(
+        verify(rows),
+        "value column at Rotation::prev() equals value_prev at Rotation::cur()",
+    );
+}
+
+#[test]
+fn created_account_ok() {
+    let rows = vec![
+        Rw::CreatedAccount {
+            rw_counter: 1,
+            is_write: true,
+            tx_id: 1,
+            account_address: address!("0x000000000000000000000000000000000cafe002"),
+            value: CreatedAccountStatus::Created as u64,
+            value_prev: CreatedAccountStatus::NotCreated as u64,
+        },
+        Rw::CreatedAccount {
+            rw_counter: 2,
+            is_write: true,
+            tx_id: 1,
+            account_address: address!("0x000000000000000000000000000000000cafe002"),
+            value: CreatedAccountStatus::Destructed as u64,
+            value_prev: CreatedAccountStatus::Created as u64,
+        },
+        Rw::CreatedAccount {
+            rw_counter: 3,
+            is_write: true,
+            tx_id: 1,
+            account_address: address!("0x000000000000000000000000000000000cafe002"),
+            value: CreatedAccountStatus::NotCreated as u64,
+            value_prev: CreatedAccountStatus::Destructed as u64,
+        },
+        // The account created in a previous tx isn't created in the next one
+        Rw::CreatedAccount {
+            rw_counter: 4,
+            is_write: true,
+            tx_id: 2,
+            account_address: address!("0x000000000000000000000000000000000cafe002"),
+            value: CreatedAccountStatus::NotCreated as u64,
+            value_prev: CreatedAccountStatus::NotCreated as u64,
+        },
+    ];
+
+    assert_eq!(verify(rows), Ok(()));
+}
+
+#[test]
+fn created_account_not_deleted() {
+    let rows = vec![
+        Rw::CreatedAccount {
+            rw_counter: 1,
+            is_write: true,
+            tx_id: 1,
+            account_address: address!("0x000000000000000000000000000000000cafe002"),
+            value: CreatedAccountStatus::Created as u64,
+            value_prev: CreatedAccountStatus::NotCreated as u64,
+        },
+        Rw::CreatedAccount {
+            rw_counter: 2,
+            is_write: true,
+            tx_id: 1,
+            account_address: address!("0x000000000000000000000000000000000cafe002"),
+            value: CreatedAccountStatus::Destructed as u64,
+            value_prev: CreatedAccountStatus::Created as u64,
+        },
+    ];
+
+    assert_error_matches(
+        verify(rows),
+        "destructed accounts are deleted at the end of the tx",
+    );
+}
+
 #[test]
 fn address_limb_mismatch() {
     let rows = vec![Rw::Account {
@@ -665,7 +1200,11 @@ fn skipped_start_rw_counter() {
         ((AdviceColumn::RwCounterLimb0, -1), Fr::ONE),
     ]);
 
-    let result = prover(vec![], overrides).verify_at_rows(N_ROWS - 1..N_ROWS, N_ROWS - 1..N_ROWS);
+    // The rw_counter of the last row is a public input.
+    let instance_overrides = HashMap::from([(7, Fr::from((1 << 16) + 1))]);
+
+    let result = prover_with_instance_overrides(vec![], overrides, instance_overrides)
+        .verify_at_rows(N_ROWS - 1..N_ROWS, N_ROWS - 1..N_ROWS);
     assert_error_matches(result, "rw_counter increases by 1 for every non-first row");
 }
 
@@ -904,6 +1443,32 @@ fn bad_initial_tx_log_value() {
     );
 }
 
+#[test]
+fn bad_initial_transient_storage_value() {
+    // The transient storage written in a previous tx is not carried over
+    let rows = vec![Rw::TransientStorage {
+        rw_counter: 1,
+        is_write: true,
+        tx_id: 2,
+        account_address: address!("0x000000000000000000000000000000000cafe002"),
+        storage_key: U256::from(2),
+        value: U256::from(394500u64),
+        value_prev: U256::zero(),
+    }];
+
+    let overrides = HashMap::from([
+        ((AdviceColumn::InitialValueHi, 0), Fr::ZERO),
+        ((AdviceColumn::InitialValueLo, 0), Fr::from(10)),
+        ((AdviceColumn::ValuePrevHi, 0), Fr::ZERO),
+        ((AdviceColumn::ValuePrevLo, 0), Fr::from(10)),
+    ]);
+
+    assert_error_matches(
+        verify_with_overrides(rows, overrides),
+        "initial TransientStorage value is 0",
+    );
+}
+
 #[test]
 fn variadic_size_check() {
     let mut rows = vec![
@@ -927,6 +1492,7 @@ fn variadic_size_check() {
     let circuit = StateCircuit::<Fr> {
         rows: rows.clone(),
         updates,
+        chunk: ChunkContext::first([Fr::ZERO; 2]),
         overrides: HashMap::default(),
         n_rows: N_ROWS,
         _marker: std::marker::PhantomData::default(),
@@ -955,6 +1521,7 @@ fn variadic_size_check() {
     let circuit = StateCircuit::<Fr> {
         rows,
         updates,
+        chunk: ChunkContext::first([Fr::ZERO; 2]),
         overrides: HashMap::default(),
         n_rows: N_ROWS,
         _marker: std::marker::PhantomData::default(),
@@ -991,15 +1558,27 @@ fn bad_initial_tx_receipt_value() {
 }
 
 fn prover(rows: Vec<Rw>, overrides: HashMap<(AdviceColumn, isize), Fr>) -> MockProver<Fr> {
+    prover_with_instance_overrides(rows, overrides, HashMap::new())
+}
+
+fn prover_with_instance_overrides(
+    rows: Vec<Rw>,
+    overrides: HashMap<(AdviceColumn, isize), Fr>,
+    instance_overrides: HashMap<usize, Fr>,
+) -> MockProver<Fr> {
     let updates = MptUpdates::mock_from(&rows);
     let circuit = StateCircuit::<Fr> {
         rows,
         updates,
+        chunk: ChunkContext::first([Fr::ZERO; 2]),
         overrides,
         n_rows: N_ROWS,
         _marker: std::marker::PhantomData::default(),
     };
-    let instance = circuit.instance();
+    let mut instance = circuit.instance();
+    for (index, value) in instance_overrides {
+        instance[0][index] = value;
+    }
 
     MockProver::<Fr>::run(17, &circuit, instance).unwrap()
 }
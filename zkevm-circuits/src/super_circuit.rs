@@ -14,8 +14,16 @@
 //! - [x] Copy Circuit
 //! - [x] Exponentiation Circuit
 //! - [ ] Keccak Circuit
-//! - [ ] MPT Circuit
+//! - [x] MPT Circuit
 //! - [x] PublicInputs Circuit
+//! - [x] SHA-256 Circuit
+//! - [x] RIPEMD-160 Circuit
+//! - [x] BLAKE2F Circuit
+//! - [x] Sig Circuit
+//! - [x] ModExp Circuit
+//! - [x] ECC Circuit
+//! - [x] P-256 Sig Circuit
+//! - [x] Poseidon Circuit
 //!
 //! And the following shared tables, with the circuits that use them:
 //!
@@ -40,40 +48,83 @@
 //! - [ ] Block Table
 //!   - [ ] EVM Circuit
 //!   - [x] PublicInputs Circuit
+//! - [ ] BlockHash Table
+//!   - [ ] EVM Circuit
+//!   - [x] PublicInputs Circuit
 //! - [ ] MPT Table
-//!   - [ ] MPT Circuit
+//!   - [x] MPT Circuit
 //!   - [ ] State Circuit
 //! - [x] Keccak Table
 //!   - [ ] Keccak Circuit
 //!   - [ ] EVM Circuit
 //!   - [x] Bytecode Circuit
 //!   - [x] Tx Circuit
-//!   - [ ] MPT Circuit
+//!   - [x] MPT Circuit
+//! - [x] Sig Table
+//!   - [x] Sig Circuit
+//!   - [x] EVM Circuit
+//! - [x] Sha256 Table
+//!   - [x] Sha256 Circuit
+//!   - [x] EVM Circuit
+//!   - [x] Tx Circuit
+//! - [x] Ripemd160 Table
+//!   - [x] Ripemd160 Circuit
+//!   - [x] EVM Circuit
+//! - [x] ModExp Table
+//!   - [x] ModExp Circuit
+//!   - [x] EVM Circuit
+//! - [x] Ecc Table
+//!   - [x] Ecc Circuit
+//!   - [x] EVM Circuit
+//! - [x] Blake2f Table
+//!   - [x] Blake2f Circuit
+//!   - [x] EVM Circuit
+//! - [x] P256Sig Table
+//!   - [x] P-256 Signature Circuit
+//!   - [x] EVM Circuit
+//! - [x] Poseidon Table
+//!   - [x] Poseidon Circuit
+//!
+//! A block calling ecPairing or the point evaluation, which have no gadget,
+//! is rejected, as well as Poseidon code hashes for the Bytecode Circuit.
 
 #[cfg(test)]
 pub(crate) mod test;
 
 use crate::{
+    blake2f_circuit::{Blake2fCircuit, Blake2fCircuitConfig, Blake2fCircuitConfigArgs},
     bytecode_circuit::{BytecodeCircuit, BytecodeCircuitConfig, BytecodeCircuitConfigArgs},
     copy_circuit::{CopyCircuit, CopyCircuitConfig, CopyCircuitConfigArgs},
+    ecc_circuit::{EccCircuit, EccCircuitConfig, EccCircuitConfigArgs},
     evm_circuit::{EvmCircuit, EvmCircuitConfig, EvmCircuitConfigArgs},
     exp_circuit::{ExpCircuit, ExpCircuitConfig},
-    keccak_circuit::{KeccakCircuit, KeccakCircuitConfig, KeccakCircuitConfigArgs},
+    keccak_circuit::{
+        KeccakCircuit, KeccakCircuitConfig, KeccakCircuitConfigArgs, KeccakConfigParams,
+    },
+    modexp_circuit::{ModExpCircuit, ModExpCircuitConfig, ModExpCircuitConfigArgs},
+    mpt_circuit::{MPTCircuit, MPTCircuitConfigArgs, MPTCircuitParams, MPTConfig},
+    p256_sig_circuit::{P256SigCircuit, P256SigCircuitConfig, P256SigCircuitConfigArgs},
     pi_circuit::{PiCircuit, PiCircuitConfig, PiCircuitConfigArgs},
+    poseidon_circuit::{PoseidonCircuit, PoseidonCircuitConfig, PoseidonCircuitConfigArgs},
+    ripemd160_circuit::{Ripemd160Circuit, Ripemd160CircuitConfig, Ripemd160CircuitConfigArgs},
+    sha256_circuit::{Sha256Circuit, Sha256CircuitConfig, Sha256CircuitConfigArgs},
+    sig_circuit::{SigCircuit, SigCircuitConfig, SigCircuitConfigArgs},
     state_circuit::{StateCircuit, StateCircuitConfig, StateCircuitConfigArgs},
     table::{
-        BlockTable, BytecodeTable, CopyTable, ExpTable, KeccakTable, MptTable, RwTable, TxTable,
-        UXTable, WdTable,
+        AuthTable, Blake2fTable, BlockHashTable, BlockTable, BytecodeTable, CopyTable, EccTable,
+        ExpTable, KeccakTable, ModExpTable, MptTable, P256SigTable, PoseidonTable, Ripemd160Table,
+        RwTable, Sha256Table, SigTable, TxTable, UXTable, WdTable,
     },
-    tx_circuit::{TxCircuit, TxCircuitConfig, TxCircuitConfigArgs},
+    tx_circuit::{sign_verify::RsaSignVerifyChip, TxCircuit, TxCircuitConfig, TxCircuitConfigArgs},
     util::{log2_ceil, Challenges, SubCircuit, SubCircuitConfig},
-    witness::{block_convert, Block, MptUpdates},
+    witness::{block_convert, Block},
 };
 use bus_mapping::{
-    circuit_input_builder::{CircuitInputBuilder, FixedCParams},
+    circuit_input_builder::{CircuitInputBuilder, CodeHashScheme, ExecState, FixedCParams},
     mock::BlockData,
+    precompile::PrecompileCalls,
 };
-use eth_types::{geth_types::GethData, Field};
+use eth_types::{evm_types::Hardfork, geth_types::GethData, Field};
 use halo2_proofs::{
     circuit::{Layouter, SimpleFloorPlanner, Value},
     plonk::{Circuit, ConstraintSystem, Error, Expression},
@@ -85,6 +136,7 @@ use std::array;
 #[derive(Clone)]
 pub struct SuperCircuitConfig<F: Field> {
     block_table: BlockTable,
+    block_hash_table: BlockHashTable,
     mpt_table: MptTable,
     u8_table: UXTable<8>,
     u10_table: UXTable<10>,
@@ -92,11 +144,20 @@ pub struct SuperCircuitConfig<F: Field> {
     evm_circuit: EvmCircuitConfig<F>,
     state_circuit: StateCircuitConfig<F>,
     tx_circuit: TxCircuitConfig<F>,
+    sig_circuit: SigCircuitConfig<F>,
+    modexp_circuit: ModExpCircuitConfig<F>,
+    ecc_circuit: EccCircuitConfig<F>,
+    p256_sig_circuit: P256SigCircuitConfig<F>,
     bytecode_circuit: BytecodeCircuitConfig<F>,
     copy_circuit: CopyCircuitConfig<F>,
     keccak_circuit: KeccakCircuitConfig<F>,
     pi_circuit: PiCircuitConfig<F>,
     exp_circuit: ExpCircuitConfig<F>,
+    mpt_circuit: MPTConfig<F>,
+    sha256_circuit: Sha256CircuitConfig<F>,
+    ripemd160_circuit: Ripemd160CircuitConfig<F>,
+    blake2f_circuit: Blake2fCircuitConfig<F>,
+    poseidon_circuit: PoseidonCircuitConfig<F>,
 }
 
 /// Circuit configuration arguments
@@ -107,6 +168,12 @@ pub struct SuperCircuitConfigArgs<F: Field> {
     pub max_withdrawals: usize,
     /// Max calldata
     pub max_calldata: usize,
+    /// Hardfork whose rules the EVM circuit constrains
+    pub hardfork: Hardfork,
+    /// Support the OP-stack deposit txs
+    pub deposits: bool,
+    /// Support the P256VERIFY precompile (RIP-7212) of the L2s
+    pub p256_verify: bool,
     /// Mock randomness
     pub mock_randomness: F,
 }
@@ -121,6 +188,9 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
             max_txs,
             max_withdrawals,
             max_calldata,
+            hardfork,
+            deposits,
+            p256_verify,
             mock_randomness,
         }: Self::ConfigArgs,
     ) -> Self {
@@ -130,10 +200,20 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
         let mpt_table = MptTable::construct(meta);
         let bytecode_table = BytecodeTable::construct(meta);
         let block_table = BlockTable::construct(meta);
+        let block_hash_table = BlockHashTable::construct(meta);
         let q_copy_table = meta.fixed_column();
         let copy_table = CopyTable::construct(meta, q_copy_table);
         let exp_table = ExpTable::construct(meta);
         let keccak_table = KeccakTable::construct(meta);
+        let sig_table = SigTable::construct(meta);
+        let sha256_table = Sha256Table::construct(meta);
+        let ripemd160_table = Ripemd160Table::construct(meta);
+        let modexp_table = ModExpTable::construct(meta);
+        let ecc_table = EccTable::construct(meta);
+        let blake2f_table = Blake2fTable::construct(meta);
+        let p256_sig_table = P256SigTable::construct(meta);
+        let poseidon_table = PoseidonTable::construct(meta);
+        let auth_table = AuthTable::construct(meta);
         let u8_table = UXTable::construct(meta);
         let u10_table = UXTable::construct(meta);
         let u16_table = UXTable::construct(meta);
@@ -153,6 +233,7 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
             KeccakCircuitConfigArgs {
                 keccak_table: keccak_table.clone(),
                 challenges: challenges.clone(),
+                parameters: KeccakConfigParams::default(),
             },
         );
 
@@ -163,9 +244,10 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
                 max_withdrawals,
                 max_calldata,
                 block_table: block_table.clone(),
+                block_hash_table: block_hash_table.clone(),
                 tx_table: tx_table.clone(),
                 wd_table,
-                keccak_table: keccak_table.clone(),
+                hash_table: keccak_table.clone(),
                 challenges: challenges.clone(),
             },
         );
@@ -174,6 +256,57 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
             TxCircuitConfigArgs {
                 tx_table: tx_table.clone(),
                 keccak_table: keccak_table.clone(),
+                block_table: block_table.clone(),
+                u8_table,
+                u16_table,
+                sign_verify_lanes: 1,
+                sign_verify_secp256r1: false,
+                // The RsaSignVerifyChip looks up the digests of the messages
+                // signed with RSA in the Sha256Table proven by the SHA-256
+                // circuit.
+                sha256_table: Some(sha256_table.clone()),
+                // There is no SHA-512 circuit to prove the challenges of Ed25519
+                // signatures, so txs signed with Ed25519 are rejected.
+                sha512_table: None,
+                auth_table: Some(auth_table),
+                eip712_digest: false,
+                deposits,
+                challenges: challenges.clone(),
+            },
+        );
+        // The instance column of the Sig circuit comes right after the ones of
+        // the Tx circuit.
+        let sig_circuit = SigCircuitConfig::new(
+            meta,
+            SigCircuitConfigArgs {
+                sig_table: sig_table.clone(),
+                keccak_table: keccak_table.clone(),
+                challenges: challenges.clone(),
+            },
+        );
+        // The instance column of the ModExp circuit comes right after the one
+        // of the Sig circuit.
+        let modexp_circuit = ModExpCircuitConfig::new(
+            meta,
+            ModExpCircuitConfigArgs {
+                modexp_table: modexp_table.clone(),
+            },
+        );
+        // The instance column of the ECC circuit comes right after the one of
+        // the ModExp circuit.
+        let ecc_circuit = EccCircuitConfig::new(
+            meta,
+            EccCircuitConfigArgs {
+                ecc_table: ecc_table.clone(),
+            },
+        );
+        // The instance column of the P-256 Sig circuit comes right after the
+        // one of the ECC circuit.
+        let p256_sig_circuit = P256SigCircuitConfig::new(
+            meta,
+            P256SigCircuitConfigArgs {
+                p256_sig_table: p256_sig_table.clone(),
+                keccak_table: keccak_table.clone(),
                 challenges: challenges.clone(),
             },
         );
@@ -181,8 +314,11 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
             meta,
             BytecodeCircuitConfigArgs {
                 bytecode_table: bytecode_table.clone(),
-                keccak_table: keccak_table.clone(),
+                // The EVM circuit looks up the bytecodes by the keccak code
+                // hashes of the accounts.
+                code_hash_table: keccak_table.clone(),
                 challenges: challenges.clone(),
+                hardfork,
             },
         );
         let copy_circuit = CopyCircuitConfig::new(
@@ -203,14 +339,53 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
                 mpt_table,
                 u8_table,
                 u10_table,
-                u16_table,
+                u16_tables: vec![u16_table],
                 challenges: challenges.clone(),
             },
         );
         let exp_circuit = ExpCircuitConfig::new(meta, exp_table);
+        let mpt_circuit = MPTConfig::new(
+            meta,
+            MPTCircuitConfigArgs {
+                mpt_table,
+                hash_table: keccak_table.clone(),
+                challenges: challenges.clone(),
+                params: MPTCircuitParams::default(),
+            },
+        );
+        let sha256_circuit = Sha256CircuitConfig::new(
+            meta,
+            Sha256CircuitConfigArgs {
+                sha256_table: sha256_table.clone(),
+                challenges: challenges.clone(),
+            },
+        );
+        let ripemd160_circuit = Ripemd160CircuitConfig::new(
+            meta,
+            Ripemd160CircuitConfigArgs {
+                ripemd160_table: ripemd160_table.clone(),
+                challenges: challenges.clone(),
+            },
+        );
+        let blake2f_circuit = Blake2fCircuitConfig::new(
+            meta,
+            Blake2fCircuitConfigArgs {
+                blake2f_table: blake2f_table.clone(),
+                challenges: challenges.clone(),
+            },
+        );
+        let poseidon_circuit = PoseidonCircuitConfig::new(
+            meta,
+            PoseidonCircuitConfigArgs {
+                poseidon_table,
+                challenges: challenges.clone(),
+            },
+        );
         let evm_circuit = EvmCircuitConfig::new(
             meta,
             EvmCircuitConfigArgs {
+                hardfork,
+                p256_verify,
                 challenges,
                 tx_table,
                 rw_table,
@@ -219,6 +394,14 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
                 copy_table,
                 keccak_table,
                 exp_table,
+                sig_table,
+                sha256_table,
+                ripemd160_table,
+                modexp_table,
+                ecc_table,
+                blake2f_table,
+                p256_sig_table,
+                block_hash_table: block_hash_table.clone(),
                 u8_table,
                 u16_table,
             },
@@ -226,6 +409,7 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
 
         Self {
             block_table,
+            block_hash_table,
             mpt_table,
             u8_table,
             u10_table,
@@ -234,10 +418,19 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
             state_circuit,
             copy_circuit,
             tx_circuit,
+            sig_circuit,
+            modexp_circuit,
+            ecc_circuit,
+            p256_sig_circuit,
             bytecode_circuit,
             keccak_circuit,
             pi_circuit,
             exp_circuit,
+            mpt_circuit,
+            sha256_circuit,
+            ripemd160_circuit,
+            blake2f_circuit,
+            poseidon_circuit,
         }
     }
 }
@@ -251,6 +444,14 @@ pub struct SuperCircuit<F: Field> {
     pub state_circuit: StateCircuit<F>,
     /// The transaction circuit that will be used in the `synthesize` step.
     pub tx_circuit: TxCircuit<F>,
+    /// Sig Circuit
+    pub sig_circuit: SigCircuit<F>,
+    /// ModExp Circuit
+    pub modexp_circuit: ModExpCircuit<F>,
+    /// ECC Circuit
+    pub ecc_circuit: EccCircuit<F>,
+    /// P-256 Sig Circuit
+    pub p256_sig_circuit: P256SigCircuit<F>,
     /// Public Input Circuit
     pub pi_circuit: PiCircuit<F>,
     /// Bytecode Circuit
@@ -261,6 +462,16 @@ pub struct SuperCircuit<F: Field> {
     pub exp_circuit: ExpCircuit<F>,
     /// Keccak Circuit
     pub keccak_circuit: KeccakCircuit<F>,
+    /// MPT Circuit
+    pub mpt_circuit: MPTCircuit<F>,
+    /// SHA-256 Circuit
+    pub sha256_circuit: Sha256Circuit<F>,
+    /// RIPEMD-160 Circuit
+    pub ripemd160_circuit: Ripemd160Circuit<F>,
+    /// BLAKE2F Circuit
+    pub blake2f_circuit: Blake2fCircuit<F>,
+    /// Poseidon Circuit
+    pub poseidon_circuit: PoseidonCircuit<F>,
     /// Circuits Parameters
     pub circuits_params: FixedCParams,
     /// Mock randomness
@@ -272,7 +483,7 @@ impl<F: Field> SuperCircuit<F> {
     pub fn get_num_rows_required(block: &Block<F>) -> usize {
         let num_rows_evm_circuit = EvmCircuit::<F>::get_num_rows_required(block);
         let num_rows_tx_circuit =
-            TxCircuitConfig::<F>::get_num_rows_required(block.circuits_params.max_txs);
+            TxCircuitConfig::<F>::get_num_rows_required(block.circuits_params.max_signed_txs);
         num_rows_evm_circuit.max(num_rows_tx_circuit)
     }
 }
@@ -288,11 +499,20 @@ impl<F: Field> SubCircuit<F> for SuperCircuit<F> {
             EvmCircuit::<F>::unusable_rows(),
             StateCircuit::<F>::unusable_rows(),
             TxCircuit::<F>::unusable_rows(),
+            SigCircuit::<F>::unusable_rows(),
+            ModExpCircuit::<F>::unusable_rows(),
+            EccCircuit::<F>::unusable_rows(),
+            P256SigCircuit::<F>::unusable_rows(),
             PiCircuit::<F>::unusable_rows(),
             BytecodeCircuit::<F>::unusable_rows(),
             CopyCircuit::<F>::unusable_rows(),
             ExpCircuit::<F>::unusable_rows(),
             KeccakCircuit::<F>::unusable_rows(),
+            MPTCircuit::<F>::unusable_rows(),
+            Sha256Circuit::<F>::unusable_rows(),
+            Ripemd160Circuit::<F>::unusable_rows(),
+            Blake2fCircuit::<F>::unusable_rows(),
+            PoseidonCircuit::<F>::unusable_rows(),
         ])
         .unwrap()
     }
@@ -300,22 +520,42 @@ impl<F: Field> SubCircuit<F> for SuperCircuit<F> {
     fn new_from_block(block: &Block<F>) -> Self {
         let evm_circuit = EvmCircuit::new_from_block(block);
         let state_circuit = StateCircuit::new_from_block(block);
-        let tx_circuit = TxCircuit::new_from_block(block);
+        // The configuration of the TxCircuit supports RSA, so every signed tx
+        // slot is verified by the RsaSignVerifyChip as well.
+        let tx_circuit = TxCircuit::new_from_block(block).with_rsa_signers(&[]);
+        let sig_circuit = SigCircuit::new_from_block(block);
+        let modexp_circuit = ModExpCircuit::new_from_block(block);
+        let ecc_circuit = EccCircuit::new_from_block(block);
+        let p256_sig_circuit = P256SigCircuit::new_from_block(block);
         let pi_circuit = PiCircuit::new_from_block(block);
         let bytecode_circuit = BytecodeCircuit::new_from_block(block);
         let copy_circuit = CopyCircuit::new_from_block_no_external(block);
         let exp_circuit = ExpCircuit::new_from_block(block);
         let keccak_circuit = KeccakCircuit::new_from_block(block);
+        let mpt_circuit = MPTCircuit::new_from_block(block);
+        let sha256_circuit = Sha256Circuit::new_from_block(block);
+        let ripemd160_circuit = Ripemd160Circuit::new_from_block(block);
+        let blake2f_circuit = Blake2fCircuit::new_from_block(block);
+        let poseidon_circuit = PoseidonCircuit::new_from_block(block);
 
         SuperCircuit::<_> {
             evm_circuit,
             state_circuit,
             tx_circuit,
+            sig_circuit,
+            modexp_circuit,
+            ecc_circuit,
+            p256_sig_circuit,
             pi_circuit,
             bytecode_circuit,
             copy_circuit,
             exp_circuit,
             keccak_circuit,
+            mpt_circuit,
+            sha256_circuit,
+            ripemd160_circuit,
+            blake2f_circuit,
+            poseidon_circuit,
             circuits_params: block.circuits_params,
             mock_randomness: block.randomness,
         }
@@ -327,6 +567,10 @@ impl<F: Field> SubCircuit<F> for SuperCircuit<F> {
         instance.extend_from_slice(&self.keccak_circuit.instance());
         instance.extend_from_slice(&self.pi_circuit.instance());
         instance.extend_from_slice(&self.tx_circuit.instance());
+        instance.extend_from_slice(&self.sig_circuit.instance());
+        instance.extend_from_slice(&self.modexp_circuit.instance());
+        instance.extend_from_slice(&self.ecc_circuit.instance());
+        instance.extend_from_slice(&self.p256_sig_circuit.instance());
         instance.extend_from_slice(&self.bytecode_circuit.instance());
         instance.extend_from_slice(&self.copy_circuit.instance());
         instance.extend_from_slice(&self.state_circuit.instance());
@@ -344,10 +588,51 @@ impl<F: Field> SubCircuit<F> for SuperCircuit<F> {
         let copy = CopyCircuit::min_num_rows_block(block);
         let keccak = KeccakCircuit::min_num_rows_block(block);
         let tx = TxCircuit::min_num_rows_block(block);
+        let rsa_sign_verify = |num_signed_txs: usize| {
+            // Without signed tx slots the RsaSignVerifyChip is skipped, see
+            // `TxCircuit::synthesize_sub`
+            if num_signed_txs == 0 {
+                0
+            } else {
+                RsaSignVerifyChip::<F>::min_num_rows(num_signed_txs)
+            }
+        };
+        let rsa_sign_verify = (
+            rsa_sign_verify(block.txs.iter().filter(|tx| !tx.is_deposit()).count()),
+            rsa_sign_verify(block.circuits_params.max_signed_txs),
+        );
+        let sig = SigCircuit::min_num_rows_block(block);
+        let modexp = ModExpCircuit::min_num_rows_block(block);
+        let ecc = EccCircuit::min_num_rows_block(block);
+        let p256_sig = P256SigCircuit::min_num_rows_block(block);
         let exp = ExpCircuit::min_num_rows_block(block);
         let pi = PiCircuit::min_num_rows_block(block);
-
-        let rows: Vec<(usize, usize)> = vec![evm, state, bytecode, copy, keccak, tx, exp, pi];
+        let mpt = MPTCircuit::min_num_rows_block(block);
+        let sha256 = Sha256Circuit::min_num_rows_block(block);
+        let ripemd160 = Ripemd160Circuit::min_num_rows_block(block);
+        let blake2f = Blake2fCircuit::min_num_rows_block(block);
+        let poseidon = PoseidonCircuit::min_num_rows_block(block);
+
+        let rows: Vec<(usize, usize)> = vec![
+            evm,
+            state,
+            bytecode,
+            copy,
+            keccak,
+            tx,
+            rsa_sign_verify,
+            sig,
+            modexp,
+            ecc,
+            p256_sig,
+            exp,
+            pi,
+            mpt,
+            sha256,
+            ripemd160,
+            blake2f,
+            poseidon,
+        ];
         let (rows_without_padding, rows_with_padding): (Vec<usize>, Vec<usize>) =
             rows.into_iter().unzip();
         (
@@ -367,8 +652,20 @@ impl<F: Field> SubCircuit<F> for SuperCircuit<F> {
             .synthesize_sub(&config.keccak_circuit, challenges, layouter)?;
         self.bytecode_circuit
             .synthesize_sub(&config.bytecode_circuit, challenges, layouter)?;
+        if self.tx_circuit.ed25519_sign_verify.is_some() {
+            log::error!("the SuperCircuit doesn't support txs signed with Ed25519");
+            return Err(Error::Synthesis);
+        }
         self.tx_circuit
             .synthesize_sub(&config.tx_circuit, challenges, layouter)?;
+        self.sig_circuit
+            .synthesize_sub(&config.sig_circuit, challenges, layouter)?;
+        self.modexp_circuit
+            .synthesize_sub(&config.modexp_circuit, challenges, layouter)?;
+        self.ecc_circuit
+            .synthesize_sub(&config.ecc_circuit, challenges, layouter)?;
+        self.p256_sig_circuit
+            .synthesize_sub(&config.p256_sig_circuit, challenges, layouter)?;
         self.state_circuit
             .synthesize_sub(&config.state_circuit, challenges, layouter)?;
         self.copy_circuit
@@ -379,6 +676,16 @@ impl<F: Field> SubCircuit<F> for SuperCircuit<F> {
             .synthesize_sub(&config.evm_circuit, challenges, layouter)?;
         self.pi_circuit
             .synthesize_sub(&config.pi_circuit, challenges, layouter)?;
+        self.mpt_circuit
+            .synthesize_sub(&config.mpt_circuit, challenges, layouter)?;
+        self.sha256_circuit
+            .synthesize_sub(&config.sha256_circuit, challenges, layouter)?;
+        self.ripemd160_circuit
+            .synthesize_sub(&config.ripemd160_circuit, challenges, layouter)?;
+        self.blake2f_circuit
+            .synthesize_sub(&config.blake2f_circuit, challenges, layouter)?;
+        self.poseidon_circuit
+            .synthesize_sub(&config.poseidon_circuit, challenges, layouter)?;
         Ok(())
     }
 }
@@ -389,6 +696,9 @@ pub struct SuperCircuitParams<F: Field> {
     max_txs: usize,
     max_withdrawals: usize,
     max_calldata: usize,
+    hardfork: Hardfork,
+    deposits: bool,
+    p256_verify: bool,
     mock_randomness: F,
 }
 
@@ -406,6 +716,9 @@ impl<F: Field> Circuit<F> for SuperCircuit<F> {
             max_txs: self.circuits_params.max_txs,
             max_withdrawals: self.circuits_params.max_withdrawals,
             max_calldata: self.circuits_params.max_calldata,
+            hardfork: self.circuits_params.hardfork,
+            deposits: self.circuits_params.deposits,
+            p256_verify: self.circuits_params.p256_verify,
             mock_randomness: self.mock_randomness,
         }
     }
@@ -417,6 +730,9 @@ impl<F: Field> Circuit<F> for SuperCircuit<F> {
                 max_txs: params.max_txs,
                 max_withdrawals: params.max_withdrawals,
                 max_calldata: params.max_calldata,
+                hardfork: params.hardfork,
+                deposits: params.deposits,
+                p256_verify: params.p256_verify,
                 mock_randomness: params.mock_randomness,
             },
         )
@@ -436,13 +752,52 @@ impl<F: Field> Circuit<F> for SuperCircuit<F> {
             Value::known(block.randomness),
             Value::known(block.randomness),
         );
-        let rws = &self.state_circuit.rows;
 
         config.block_table.load(&mut layouter, &block.context)?;
-
         config
-            .mpt_table
-            .load(&mut layouter, &MptUpdates::mock_from(rws))?;
+            .block_hash_table
+            .load(&mut layouter, &block.context)?;
+
+        // Without MPT proofs, the account and storage updates of the state
+        // circuit aren't proved, so the MptTable can only be mocked in tests.
+        if block.mpt_updates.is_none() {
+            #[cfg(any(test, feature = "test-circuits"))]
+            config.mpt_table.load(
+                &mut layouter,
+                &crate::witness::MptUpdates::mock_from(&self.state_circuit.rows),
+            )?;
+            #[cfg(not(any(test, feature = "test-circuits")))]
+            {
+                log::error!("the super circuit needs the MPT proofs of the block");
+                return Err(Error::Synthesis);
+            }
+        }
+        let calls = |precompile| {
+            block
+                .txs
+                .iter()
+                .flat_map(|tx| tx.steps())
+                .any(|step| step.exec_state == ExecState::Precompile(precompile))
+        };
+        for (precompile, called) in [
+            ("ecPairing", calls(PrecompileCalls::Bn128Pairing)),
+            ("point evaluation", calls(PrecompileCalls::PointEvaluation)),
+        ] {
+            if called {
+                log::error!(
+                    "no circuit of the super circuit proves the {} calls",
+                    precompile
+                );
+                return Err(Error::Synthesis);
+            }
+        }
+
+        if self.circuits_params.code_hash_scheme != CodeHashScheme::Keccak {
+            log::error!(
+                "the super circuit only proves keccak code hashes, by which the EVM circuit looks up the bytecodes"
+            );
+            return Err(Error::Synthesis);
+        }
 
         config.u8_table.load(&mut layouter)?;
         config.u10_table.load(&mut layouter)?;
@@ -1,13 +1,23 @@
 pub use super::*;
 use ethers_signers::{LocalWallet, Signer};
-use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
+use halo2_proofs::{
+    dev::MockProver,
+    halo2curves::{bn256::Fr, ff::PrimeField, secp256r1::Secp256r1Affine},
+};
 use log::error;
 use mock::{TestContext, MOCK_CHAIN_ID};
 use rand::SeedableRng;
 use rand_chacha::ChaCha20Rng;
 use std::collections::HashMap;
 
-use eth_types::{address, bytecode, geth_types::GethData, Word};
+use bus_mapping::{circuit_input_builder::CodeHashScheme, precompile::MODEXP_SIZE_LIMIT};
+use eth_types::{
+    address, bytecode,
+    evm_types::{Hardfork, OpcodeId},
+    geth_types::{Deposit, GethData},
+    sign_types::{pk_bytes_le, pk_bytes_swap_endianness, SignData},
+    word, Bytecode, Word, H256,
+};
 
 #[test]
 fn super_circuit_degree() {
@@ -17,6 +27,7 @@ fn super_circuit_degree() {
         max_withdrawals: 5,
         max_calldata: 32,
         mock_randomness: Fr::from(0x100),
+        ..Default::default()
     };
     SuperCircuit::configure_with_params(&mut cs, params);
     log::info!("super circuit degree: {}", cs.degree());
@@ -36,15 +47,18 @@ fn test_super_circuit(block: GethData, circuits_params: FixedCParams, mock_rando
 }
 
 pub(crate) fn block_1tx() -> GethData {
+    block_1tx_with_code(bytecode! {
+        GAS
+        STOP
+    })
+}
+
+/// Return a block with a tx calling an account with `bytecode`.
+fn block_1tx_with_code(bytecode: Bytecode) -> GethData {
     let mut rng = ChaCha20Rng::seed_from_u64(2);
 
     let chain_id = (*MOCK_CHAIN_ID).as_u64();
 
-    let bytecode = bytecode! {
-        GAS
-        STOP
-    };
-
     let wallet_a = LocalWallet::new(&mut rng).with_chain_id(chain_id);
 
     let addr_a = wallet_a.address();
@@ -76,6 +90,40 @@ pub(crate) fn block_1tx() -> GethData {
     block
 }
 
+/// Return a block with a deposit tx calling an account with `bytecode`.  The
+/// deposit isn't signed, so a circuit without signed tx slots proves it at a
+/// small degree.
+fn block_deposit_with_code(bytecode: Bytecode) -> GethData {
+    let addr_a = address!("0x000000000000000000000000000000000000AAAA");
+    let addr_b = address!("0x000000000000000000000000000000000000BBBB");
+
+    // The deposit mints nothing, as the external tracer doesn't mint.
+    TestContext::<2, 1>::new(
+        None,
+        |accs| {
+            accs[0]
+                .address(addr_b)
+                .balance(Word::from(1u64 << 20))
+                .code(bytecode);
+            accs[1].address(addr_a).balance(Word::from(1u64 << 20));
+        },
+        |mut txs, accs| {
+            txs[0]
+                .from(accs[1].address)
+                .to(accs[0].address)
+                .gas(Word::from(1_000_000u64))
+                .deposit(Deposit {
+                    source_hash: H256::repeat_byte(0xde),
+                    mint: Word::zero(),
+                    is_system_tx: false,
+                });
+        },
+        |block, _tx| block.number(0xcafeu64),
+    )
+    .unwrap()
+    .into()
+}
+
 fn block_2tx() -> GethData {
     let mut rng = ChaCha20Rng::seed_from_u64(2);
 
@@ -131,14 +179,31 @@ fn serial_test_super_circuit_1tx_1max_tx() {
     let block = block_1tx();
     let circuits_params = FixedCParams {
         max_txs: 1,
+        max_signed_txs: 1,
         max_withdrawals: 5,
         max_calldata: 32,
+        max_access_list: 0,
+        max_auths: 0,
         max_rws: 256,
         max_copy_rows: 256,
         max_exp_steps: 256,
         max_bytecode: 512,
         max_evm_rows: 0,
         max_keccak_rows: 0,
+        max_poseidon_blocks: 0,
+        max_sha256_blocks: 0,
+        max_ripemd160_blocks: 0,
+        max_blake2f_blocks: 0,
+        max_sig_verifs: 0,
+        max_modexps: 0,
+        max_modexp_size: MODEXP_SIZE_LIMIT,
+        max_ec_adds: 0,
+        max_ec_muls: 0,
+        max_p256_verifs: 0,
+        hardfork: Hardfork::default(),
+        code_hash_scheme: CodeHashScheme::Keccak,
+        deposits: false,
+        p256_verify: false,
     };
     test_super_circuit(block, circuits_params, Fr::from(TEST_MOCK_RANDOMNESS));
 }
@@ -148,14 +213,31 @@ fn serial_test_super_circuit_1tx_2max_tx() {
     let block = block_1tx();
     let circuits_params = FixedCParams {
         max_txs: 2,
+        max_signed_txs: 2,
         max_withdrawals: 5,
         max_calldata: 32,
+        max_access_list: 0,
+        max_auths: 0,
         max_rws: 256,
         max_copy_rows: 256,
         max_exp_steps: 256,
         max_bytecode: 512,
         max_evm_rows: 0,
         max_keccak_rows: 0,
+        max_poseidon_blocks: 0,
+        max_sha256_blocks: 0,
+        max_ripemd160_blocks: 0,
+        max_blake2f_blocks: 0,
+        max_sig_verifs: 0,
+        max_modexps: 0,
+        max_modexp_size: MODEXP_SIZE_LIMIT,
+        max_ec_adds: 0,
+        max_ec_muls: 0,
+        max_p256_verifs: 0,
+        hardfork: Hardfork::default(),
+        code_hash_scheme: CodeHashScheme::Keccak,
+        deposits: false,
+        p256_verify: false,
     };
     test_super_circuit(block, circuits_params, Fr::from(TEST_MOCK_RANDOMNESS));
 }
@@ -165,14 +247,385 @@ fn serial_test_super_circuit_2tx_2max_tx() {
     let block = block_2tx();
     let circuits_params = FixedCParams {
         max_txs: 2,
+        max_signed_txs: 2,
         max_withdrawals: 5,
         max_calldata: 32,
+        max_access_list: 0,
+        max_auths: 0,
         max_rws: 256,
         max_copy_rows: 256,
         max_exp_steps: 256,
         max_bytecode: 512,
         max_evm_rows: 0,
         max_keccak_rows: 0,
+        max_poseidon_blocks: 0,
+        max_sha256_blocks: 0,
+        max_ripemd160_blocks: 0,
+        max_blake2f_blocks: 0,
+        max_sig_verifs: 0,
+        max_modexps: 0,
+        max_modexp_size: MODEXP_SIZE_LIMIT,
+        max_ec_adds: 0,
+        max_ec_muls: 0,
+        max_p256_verifs: 0,
+        hardfork: Hardfork::default(),
+        code_hash_scheme: CodeHashScheme::Keccak,
+        deposits: false,
+        p256_verify: false,
+    };
+    test_super_circuit(block, circuits_params, Fr::from(TEST_MOCK_RANDOMNESS));
+}
+
+#[test]
+fn test_super_circuit_poseidon_code_hash() {
+    // The EVM circuit looks up the bytecodes by their keccak code hashes, so
+    // the Poseidon code hashes are rejected.
+    let block = block_deposit_with_code(bytecode! {
+        GAS
+        STOP
+    });
+    let circuits_params = FixedCParams {
+        max_txs: 1,
+        max_signed_txs: 0,
+        max_calldata: 32,
+        max_rws: 256,
+        max_copy_rows: 256,
+        max_exp_steps: 256,
+        max_bytecode: 512,
+        code_hash_scheme: CodeHashScheme::Poseidon,
+        deposits: true,
+        ..Default::default()
+    };
+    let (k, circuit, instance, _) =
+        SuperCircuit::<Fr>::build(block, circuits_params, Fr::from(TEST_MOCK_RANDOMNESS)).unwrap();
+    assert!(MockProver::run(k, &circuit, instance).is_err());
+}
+
+#[test]
+fn test_super_circuit_sha256() {
+    // sha256 of the 32 bytes at 0, returned at 0x20
+    let block = block_deposit_with_code(bytecode! {
+        PUSH32(Word::from(0xabcdef))
+        PUSH1(0)
+        MSTORE
+        PUSH1(0x20)
+        PUSH1(0x20)
+        PUSH1(0x20)
+        PUSH1(0)
+        PUSH1(2)
+        GAS
+        STATICCALL
+        STOP
+    });
+    let circuits_params = FixedCParams {
+        max_txs: 1,
+        max_signed_txs: 0,
+        max_calldata: 32,
+        max_rws: 256,
+        max_copy_rows: 256,
+        max_exp_steps: 256,
+        max_bytecode: 512,
+        max_sha256_blocks: 2,
+        deposits: true,
+        ..Default::default()
+    };
+    test_super_circuit(block, circuits_params, Fr::from(TEST_MOCK_RANDOMNESS));
+}
+
+#[test]
+fn test_super_circuit_ripemd160() {
+    // ripemd160 of the 32 bytes at 0, returned at 0x20
+    let block = block_deposit_with_code(bytecode! {
+        PUSH32(Word::from(0xabcdef))
+        PUSH1(0)
+        MSTORE
+        PUSH1(0x20)
+        PUSH1(0x20)
+        PUSH1(0x20)
+        PUSH1(0)
+        PUSH1(3)
+        GAS
+        STATICCALL
+        STOP
+    });
+    let circuits_params = FixedCParams {
+        max_txs: 1,
+        max_signed_txs: 0,
+        max_calldata: 32,
+        max_rws: 256,
+        max_copy_rows: 256,
+        max_exp_steps: 256,
+        max_bytecode: 512,
+        max_ripemd160_blocks: 2,
+        deposits: true,
+        ..Default::default()
+    };
+    test_super_circuit(block, circuits_params, Fr::from(TEST_MOCK_RANDOMNESS));
+}
+
+#[test]
+fn test_super_circuit_blake2f() {
+    // Compression without rounds of the 213 zero bytes at 0, returned at 0
+    let block = block_deposit_with_code(bytecode! {
+        PUSH1(0x40)
+        PUSH1(0)
+        PUSH1(213)
+        PUSH1(0)
+        PUSH1(9)
+        GAS
+        STATICCALL
+        STOP
+    });
+    let circuits_params = FixedCParams {
+        max_txs: 1,
+        max_signed_txs: 0,
+        max_calldata: 32,
+        max_rws: 512,
+        max_copy_rows: 512,
+        max_exp_steps: 256,
+        max_bytecode: 512,
+        max_blake2f_blocks: 1,
+        deposits: true,
+        ..Default::default()
+    };
+    test_super_circuit(block, circuits_params, Fr::from(TEST_MOCK_RANDOMNESS));
+}
+
+#[test]
+fn test_super_circuit_modexp() {
+    // 3 ^ 5 mod 7 of 1-byte values at 0x60, returned at 0xc0
+    let block = block_deposit_with_code(bytecode! {
+        PUSH1(1)
+        PUSH1(0)
+        MSTORE
+        PUSH1(1)
+        PUSH1(0x20)
+        MSTORE
+        PUSH1(1)
+        PUSH1(0x40)
+        MSTORE
+        PUSH32(word!("0x0305070000000000000000000000000000000000000000000000000000000000"))
+        PUSH1(0x60)
+        MSTORE
+        PUSH1(1)
+        PUSH1(0xc0)
+        PUSH1(0x63)
+        PUSH1(0)
+        PUSH1(5)
+        GAS
+        STATICCALL
+        STOP
+    });
+    let circuits_params = FixedCParams {
+        max_txs: 1,
+        max_signed_txs: 0,
+        max_calldata: 32,
+        max_rws: 512,
+        max_copy_rows: 512,
+        max_exp_steps: 256,
+        max_bytecode: 512,
+        max_modexps: 1,
+        max_modexp_size: 1,
+        deposits: true,
+        ..Default::default()
+    };
+    test_super_circuit(block, circuits_params, Fr::from(TEST_MOCK_RANDOMNESS));
+}
+
+// The RangeChip table of the ECC circuit takes 2^18 rows, so the circuit needs
+// k = 19.
+#[ignore = "high memory usage, run in serial"]
+#[test]
+fn serial_test_super_circuit_ec_add() {
+    // G + G of the BN254 generator G = (1, 2), returned at 0x80
+    let block = block_deposit_with_code(bytecode! {
+        PUSH1(1)
+        PUSH1(0)
+        MSTORE
+        PUSH1(2)
+        PUSH1(0x20)
+        MSTORE
+        PUSH1(1)
+        PUSH1(0x40)
+        MSTORE
+        PUSH1(2)
+        PUSH1(0x60)
+        MSTORE
+        PUSH1(0x40)
+        PUSH1(0x80)
+        PUSH1(0x80)
+        PUSH1(0)
+        PUSH1(6)
+        GAS
+        STATICCALL
+        STOP
+    });
+    let circuits_params = FixedCParams {
+        max_txs: 1,
+        max_signed_txs: 0,
+        max_calldata: 32,
+        max_rws: 512,
+        max_copy_rows: 512,
+        max_exp_steps: 256,
+        max_bytecode: 512,
+        max_ec_adds: 1,
+        deposits: true,
+        ..Default::default()
+    };
+    test_super_circuit(block, circuits_params, Fr::from(TEST_MOCK_RANDOMNESS));
+}
+
+/// Return the block of a deposit tx calling P256VERIFY with the padding
+/// signature of the secp256r1 SignData, returning its result at 0xa0.
+fn block_deposit_p256_verify() -> GethData {
+    let sign_data = SignData::<Secp256r1Affine>::padding();
+    let (sig_r, sig_s) = sign_data.signature;
+    let input: Vec<u8> = [sign_data.msg_hash, sig_r, sig_s]
+        .iter()
+        .flat_map(|scalar| scalar.to_repr().into_iter().rev())
+        .chain(pk_bytes_swap_endianness(&pk_bytes_le(&sign_data.pk)))
+        .collect();
+    let mut code = Bytecode::default();
+    for (i, word) in input.chunks(32).enumerate() {
+        code.push(32, Word::from_big_endian(word))
+            .push(32, Word::from(32 * i))
+            .write_op(OpcodeId::MSTORE);
+    }
+    code.append(&bytecode! {
+        PUSH1(0x20)
+        PUSH1(0xa0)
+        PUSH1(0xa0)
+        PUSH1(0)
+        PUSH2(0x100)
+        PUSH2(0x1000)
+        STATICCALL
+        STOP
+    });
+
+    // The geth-utils tracer doesn't support RIP-7212 yet.
+    TestContext::<2, 1>::new_with_tracer(
+        None,
+        |accs| {
+            accs[0]
+                .address(address!("0x000000000000000000000000000000000000BBBB"))
+                .balance(Word::from(1u64 << 20))
+                .code(code);
+            accs[1]
+                .address(address!("0x000000000000000000000000000000000000AAAA"))
+                .balance(Word::from(1u64 << 20));
+        },
+        |mut txs, accs| {
+            txs[0]
+                .from(accs[1].address)
+                .to(accs[0].address)
+                .gas(Word::from(1_000_000u64))
+                .deposit(Deposit {
+                    source_hash: H256::repeat_byte(0xde),
+                    mint: Word::zero(),
+                    is_system_tx: false,
+                });
+        },
+        |block, _tx| block.number(0xcafeu64),
+        mock::interpreter::trace,
+    )
+    .unwrap()
+    .into()
+}
+
+// The RangeChip table of the SignVerifyChip of the P-256 Sig circuit takes
+// 2^18 rows, so the circuit needs k = 19.
+#[ignore = "high memory usage, run in serial"]
+#[test]
+fn serial_test_super_circuit_p256_verify() {
+    let circuits_params = FixedCParams {
+        max_txs: 1,
+        max_signed_txs: 0,
+        max_calldata: 32,
+        max_rws: 1024,
+        max_copy_rows: 1024,
+        max_exp_steps: 256,
+        max_bytecode: 512,
+        max_p256_verifs: 1,
+        hardfork: Hardfork::Cancun,
+        deposits: true,
+        p256_verify: true,
+        ..Default::default()
+    };
+    test_super_circuit(
+        block_deposit_p256_verify(),
+        circuits_params,
+        Fr::from(TEST_MOCK_RANDOMNESS),
+    );
+}
+
+/// Return the code calling ecrecover with the signature `(v, r, s)` of a msg
+/// hash at 0, returning the recovered address at 0x80.
+fn ecrecover_code(v: u64) -> Bytecode {
+    bytecode! {
+        // msg_hash
+        PUSH32(word!("0x456e9aea5e197a1f1af7a3e85a3212fa4049a3ba34c2289b4c860fc0b0c64ef3"))
+        PUSH1(0x00)
+        MSTORE
+        // v
+        PUSH1(v)
+        PUSH1(0x20)
+        MSTORE
+        // r
+        PUSH32(word!("0x9242685bf161793cc25603c231bc2f568eb630ea16aa137d2664ac8038825608"))
+        PUSH1(0x40)
+        MSTORE
+        // s
+        PUSH32(word!("0x4f8ae3bd7535248d0bd448298cc2e2071e56992d0774dc340c368ae950852ada"))
+        PUSH1(0x60)
+        MSTORE
+        PUSH1(0x20)
+        PUSH1(0x80)
+        PUSH1(0x80)
+        PUSH1(0)
+        PUSH1(1)
+        GAS
+        STATICCALL
+        STOP
+    }
+}
+
+// The RangeChip tables of the SignVerifyChips of the Tx and Sig circuits take
+// 2^18 rows, so the circuit needs k = 19.
+#[ignore = "high memory usage, run in serial"]
+#[test]
+fn serial_test_super_circuit_ecrecover() {
+    // ecrecover of a valid signature
+    let block = block_1tx_with_code(ecrecover_code(28));
+    let circuits_params = FixedCParams {
+        max_txs: 1,
+        max_signed_txs: 1,
+        max_calldata: 32,
+        max_rws: 512,
+        max_copy_rows: 512,
+        max_exp_steps: 256,
+        max_bytecode: 512,
+        max_sig_verifs: 1,
+        ..Default::default()
+    };
+    test_super_circuit(block, circuits_params, Fr::from(TEST_MOCK_RANDOMNESS));
+}
+
+#[test]
+fn test_super_circuit_ecrecover_invalid_v() {
+    // ecrecover of a signature with v = 0 recovers nothing, so the Sig circuit
+    // has no signature slot and only assigns the empty row of the SigTable.
+    let block = block_deposit_with_code(ecrecover_code(0));
+    let circuits_params = FixedCParams {
+        max_txs: 1,
+        max_signed_txs: 0,
+        max_calldata: 32,
+        max_rws: 512,
+        max_copy_rows: 512,
+        max_exp_steps: 256,
+        max_bytecode: 512,
+        max_sig_verifs: 0,
+        deposits: true,
+        ..Default::default()
     };
     test_super_circuit(block, circuits_params, Fr::from(TEST_MOCK_RANDOMNESS));
 }
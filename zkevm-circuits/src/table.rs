@@ -27,27 +27,58 @@ use itertools::Itertools;
 use std::array;
 use strum_macros::{EnumCount, EnumIter};
 
+/// authorization table
+pub(crate) mod auth_table;
+/// blake2f table
+pub(crate) mod blake2f_table;
+/// block hash table
+pub(crate) mod block_hash_table;
 /// block table
 pub(crate) mod block_table;
 /// bytecode table
 pub(crate) mod bytecode_table;
 /// copy Table
 pub(crate) mod copy_table;
+/// ecc table
+pub(crate) mod ecc_table;
 /// exp(exponentiation) table
 pub(crate) mod exp_table;
 /// keccak table
 pub(crate) mod keccak_table;
+/// modexp table
+pub(crate) mod modexp_table;
 /// mpt table
 pub mod mpt_table;
+/// P-256 signature table
+pub(crate) mod p256_sig_table;
+/// poseidon table
+pub(crate) mod poseidon_table;
+/// ripemd160 table
+pub(crate) mod ripemd160_table;
 /// rw table
 pub(crate) mod rw_table;
+/// sha1 table
+#[cfg(feature = "sha1")]
+pub(crate) mod sha1_table;
+/// sha256 table
+pub(crate) mod sha256_table;
+/// sha512 table
+pub(crate) mod sha512_table;
+/// signature table
+pub(crate) mod sig_table;
+/// hash tables of RLC'ed inputs, shared by the hash backends
+pub(crate) mod sponge;
 /// tx table
 pub(crate) mod tx_table;
+/// user operation table
+pub(crate) mod user_op_table;
 /// ux table
 pub(crate) mod ux_table;
 /// withdrawal table
 pub(crate) mod wd_table;
 
+pub use auth_table::AuthTable;
+pub use block_hash_table::{BlockHashTable, BLOCK_HASH_WINDOW};
 pub(crate) use block_table::{BlockContextFieldTag, BlockTable};
 pub(crate) use bytecode_table::{BytecodeFieldTag, BytecodeTable};
 pub(crate) use copy_table::CopyTable;
@@ -55,11 +86,24 @@ pub(crate) use exp_table::ExpTable;
 pub use keccak_table::KeccakTable;
 pub(crate) use ux_table::UXTable;
 
+pub use blake2f_table::Blake2fTable;
+pub use ecc_table::EccTable;
+pub use modexp_table::ModExpTable;
 pub use mpt_table::{MPTProofType, MptTable};
+pub use p256_sig_table::P256SigTable;
+pub use poseidon_table::PoseidonTable;
+pub use ripemd160_table::Ripemd160Table;
 pub(crate) use rw_table::RwTable;
+#[cfg(feature = "sha1")]
+pub use sha1_table::Sha1Table;
+pub use sha256_table::Sha256Table;
+pub use sha512_table::Sha512Table;
+pub use sig_table::SigTable;
+pub use sponge::SpongeChip;
 pub(crate) use tx_table::{
     TxContextFieldTag, TxFieldTag, TxLogFieldTag, TxReceiptFieldTag, TxTable,
 };
+pub use user_op_table::{UserOpFieldTag, UserOpTable};
 pub(crate) use wd_table::WdTable;
 
 /// Trait used to define lookup tables
@@ -0,0 +1,103 @@
+use super::*;
+
+/// Authorization Table, with the EIP-7702 authorizations of the set-code txs
+/// verified by the TxCircuit.  A row of a tx slot claims that the authorization
+/// `index` of the tx `tx_id`, signed with `(sig_r, sig_s)` and recovery id
+/// `y_parity` over `sign_hash`, was signed by `authority`.  The first row is
+/// all zero, so that disabled lookups with all-zero inputs are satisfied.
+#[derive(Clone, Debug)]
+pub struct AuthTable {
+    /// 1 on the rows of the authorization slots
+    pub q_enable: Column<Fixed>,
+    /// Id of the tx of the authorization, 0 on padding rows
+    pub tx_id: Column<Advice>,
+    /// Index of the authorization in the authorization list of the tx
+    pub index: Column<Advice>,
+    /// Chain ID the authorization is valid for, or 0 for all chains
+    pub chain_id: word::Word<Column<Advice>>,
+    /// Address of the code the authority delegates to
+    pub address: word::Word<Column<Advice>>,
+    /// Nonce of the authority
+    pub nonce: Column<Advice>,
+    /// Parity of the y coordinate of the signature point R
+    pub y_parity: Column<Advice>,
+    /// Signature r
+    pub sig_r: word::Word<Column<Advice>>,
+    /// Signature s
+    pub sig_s: word::Word<Column<Advice>>,
+    /// Hash of the signed message, keccak(0x05 || rlp([chain_id, address,
+    /// nonce])), reduced modulo the order of secp256k1
+    pub sign_hash: word::Word<Column<Advice>>,
+    /// Address of the authority, recovered from the signature
+    pub authority: word::Word<Column<Advice>>,
+    /// 1 on the padding rows, which verify the padding signature
+    pub is_padding: Column<Advice>,
+}
+
+impl AuthTable {
+    /// Construct a new AuthTable
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            q_enable: meta.fixed_column(),
+            tx_id: meta.advice_column(),
+            index: meta.advice_column(),
+            chain_id: word::Word::new([meta.advice_column(), meta.advice_column()]),
+            address: word::Word::new([meta.advice_column(), meta.advice_column()]),
+            nonce: meta.advice_column(),
+            y_parity: meta.advice_column(),
+            sig_r: word::Word::new([meta.advice_column(), meta.advice_column()]),
+            sig_s: word::Word::new([meta.advice_column(), meta.advice_column()]),
+            sign_hash: word::Word::new([meta.advice_column(), meta.advice_column()]),
+            authority: word::Word::new([meta.advice_column(), meta.advice_column()]),
+            is_padding: meta.advice_column(),
+        }
+    }
+}
+
+impl<F: Field> LookupTable<F> for AuthTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.q_enable.into(),
+            self.tx_id.into(),
+            self.index.into(),
+            self.chain_id.lo().into(),
+            self.chain_id.hi().into(),
+            self.address.lo().into(),
+            self.address.hi().into(),
+            self.nonce.into(),
+            self.y_parity.into(),
+            self.sig_r.lo().into(),
+            self.sig_r.hi().into(),
+            self.sig_s.lo().into(),
+            self.sig_s.hi().into(),
+            self.sign_hash.lo().into(),
+            self.sign_hash.hi().into(),
+            self.authority.lo().into(),
+            self.authority.hi().into(),
+            self.is_padding.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("q_enable"),
+            String::from("tx_id"),
+            String::from("index"),
+            String::from("chain_id_lo"),
+            String::from("chain_id_hi"),
+            String::from("address_lo"),
+            String::from("address_hi"),
+            String::from("nonce"),
+            String::from("y_parity"),
+            String::from("sig_r_lo"),
+            String::from("sig_r_hi"),
+            String::from("sig_s_lo"),
+            String::from("sig_s_hi"),
+            String::from("sign_hash_lo"),
+            String::from("sign_hash_hi"),
+            String::from("authority_lo"),
+            String::from("authority_hi"),
+            String::from("is_padding"),
+        ]
+    }
+}
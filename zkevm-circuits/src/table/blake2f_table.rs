@@ -0,0 +1,117 @@
+use super::*;
+use bus_mapping::circuit_input_builder::Blake2fEvent;
+
+/// Blake2f Table, used to verify the compressions by the function F of
+/// BLAKE2b of the successful blake2f calls.
+#[derive(Clone, Debug)]
+pub struct Blake2fTable {
+    /// True when the row is enabled
+    pub is_enabled: Column<Advice>,
+    /// Number of rounds of the compression
+    pub rounds: Column<Advice>,
+    /// The state vector `h`, the message block `m` and the offset counter `t`
+    /// as `RLC(reversed(h || m || t))`, with the words in little endian
+    pub input_rlc: Column<Advice>,
+    /// Final block indicator flag
+    pub f: Column<Advice>,
+    /// The compressed state vector as `RLC(reversed(output))`
+    pub output_rlc: Column<Advice>,
+}
+
+impl<F: Field> LookupTable<F> for Blake2fTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.is_enabled.into(),
+            self.rounds.into(),
+            self.input_rlc.into(),
+            self.f.into(),
+            self.output_rlc.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("is_enabled"),
+            String::from("rounds"),
+            String::from("input_rlc"),
+            String::from("f"),
+            String::from("output_rlc"),
+        ]
+    }
+}
+
+impl Blake2fTable {
+    /// Construct a new Blake2fTable
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            is_enabled: meta.advice_column(),
+            rounds: meta.advice_column(),
+            input_rlc: meta.advice_column_in(SecondPhase),
+            f: meta.advice_column(),
+            output_rlc: meta.advice_column_in(SecondPhase),
+        }
+    }
+
+    /// Generate the blake2f table assignments from a compression.  The RLCs
+    /// use the keccak input challenge.
+    pub fn assignments<F: Field>(
+        event: &Blake2fEvent,
+        challenges: &Challenges<Value<F>>,
+    ) -> Vec<[Value<F>; 5]> {
+        let input_rlc = challenges
+            .keccak_input()
+            .map(|challenge| rlc::value(event.input_bytes().iter().rev(), challenge));
+        let output_rlc = challenges
+            .keccak_input()
+            .map(|challenge| rlc::value(event.output_bytes().iter().rev(), challenge));
+
+        vec![[
+            Value::known(F::ONE),
+            Value::known(F::from(event.rounds as u64)),
+            input_rlc,
+            Value::known(F::from(event.f as u64)),
+            output_rlc,
+        ]]
+    }
+
+    /// Provide this function for the case that we want to consume a blake2f
+    /// table but without running the full blake2f circuit
+    pub fn dev_load<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        events: &[Blake2fEvent],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "blake2f table",
+            |mut region| {
+                let mut offset = 0;
+                let blake2f_table_columns = <Blake2fTable as LookupTable<F>>::advice_columns(self);
+                for &column in blake2f_table_columns.iter() {
+                    region.assign_advice(
+                        || "blake2f table all-zero row",
+                        column,
+                        offset,
+                        || Value::known(F::ZERO),
+                    )?;
+                }
+                offset += 1;
+
+                for event in events {
+                    for row in Self::assignments(event, challenges) {
+                        for (&column, value) in blake2f_table_columns.iter().zip_eq(row) {
+                            region.assign_advice(
+                                || format!("blake2f table row {}", offset),
+                                column,
+                                offset,
+                                || value,
+                            )?;
+                        }
+                        offset += 1;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
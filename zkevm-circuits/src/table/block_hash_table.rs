@@ -0,0 +1,99 @@
+use std::iter;
+
+use super::*;
+use halo2_proofs::circuit::AssignedCell;
+
+/// Number of the most recent block hashes in the block hash table, which are
+/// the ones that BLOCKHASH returns before EIP-2935.
+pub const BLOCK_HASH_WINDOW: usize = 256;
+
+/// Table with the hashes of the previous blocks, by depth: the hash of the
+/// block `number - depth` is on row `depth`, for `1 <= depth <=
+/// BLOCK_HASH_WINDOW`.  The hashes missing from the witness are zero, and row
+/// 0 is all zero.
+///
+/// In the super circuit the table is assigned by the PI circuit, which copies
+/// the hashes from the public inputs.
+#[derive(Clone, Debug)]
+pub struct BlockHashTable {
+    /// Depth of the block from the current one
+    pub depth: Column<Fixed>,
+    /// Hash of the block
+    pub hash: word::Word<Column<Advice>>,
+}
+
+impl BlockHashTable {
+    /// Construct a new BlockHashTable
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            depth: meta.fixed_column(),
+            hash: word::Word::new([meta.advice_column(), meta.advice_column()]),
+        }
+    }
+
+    /// Return the hashes of the table, by depth from 0, from the history
+    /// hashes of a block, the latest one being the parent hash.
+    pub fn hashes(history_hashes: &[U256]) -> Vec<U256> {
+        iter::once(U256::zero())
+            .chain(history_hashes.iter().rev().copied())
+            .chain(iter::repeat(U256::zero()))
+            .take(BLOCK_HASH_WINDOW + 1)
+            .collect()
+    }
+
+    /// Assign the row of a depth.
+    pub(crate) fn assign_row<F: Field>(
+        &self,
+        region: &mut Region<'_, F>,
+        depth: usize,
+        hash: U256,
+    ) -> Result<word::Word<AssignedCell<F, F>>, Error> {
+        region.assign_fixed(
+            || format!("block hash table depth {}", depth),
+            self.depth,
+            depth,
+            || Value::known(F::from(depth as u64)),
+        )?;
+        word::Word::<F>::from(hash).into_value().assign_advice(
+            region,
+            || format!("block hash table hash {}", depth),
+            self.hash,
+            depth,
+        )
+    }
+
+    /// Assign the `BlockHashTable` from a `BlockContext`.
+    pub fn load<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        block: &BlockContext,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "block hash table",
+            |mut region| {
+                for (depth, hash) in Self::hashes(&block.history_hashes).into_iter().enumerate() {
+                    self.assign_row(&mut region, depth, hash)?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+impl<F: Field> LookupTable<F> for BlockHashTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.depth.into(),
+            self.hash.lo().into(),
+            self.hash.hi().into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("depth"),
+            String::from("hash_lo"),
+            String::from("hash_hi"),
+        ]
+    }
+}
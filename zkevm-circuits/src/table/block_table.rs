@@ -18,13 +18,19 @@ pub enum BlockContextFieldTag {
     GasLimit,
     /// Base Fee field
     BaseFee = 8,
-    /// Block Hash field
-    BlockHash,
     /// Chain ID field.  Although this is not a field in the block header, we
-    /// add it here for convenience.
-    ChainId,
+    /// add it here for convenience.  The block hashes are in the
+    /// [`BlockHashTable`](super::BlockHashTable).
+    ChainId = 10,
     /// Withdrawal Root field
     WithdrawalRoot,
+    /// Blob Base Fee field (EIP-4844)
+    BlobBaseFee,
+    /// PrevRandao field (EIP-4399), which the DIFFICULTY opcode returns after
+    /// the merge
+    PrevRandao,
+    /// Parent Beacon Root field (EIP-4788)
+    ParentBeaconRoot,
 }
 impl_expr!(BlockContextFieldTag);
 
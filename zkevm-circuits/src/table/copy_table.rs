@@ -1,10 +1,11 @@
 use super::*;
 
 type CopyTableRow<F> = [(Value<F>, &'static str); 9];
-type CopyCircuitRow<F> = [(Value<F>, &'static str); 5];
+type CopyCircuitRow<F> = [(Value<F>, &'static str); 7];
 
 /// Copy Table, used to verify copies of byte chunks between Memory, Bytecode,
-/// TxLogs and TxCallData.
+/// TxLogs and TxCallData, and the copies of the tx access lists to the rw
+/// table.
 #[derive(Clone, Copy, Debug)]
 pub struct CopyTable {
     /// Whether the row is the first read-write pair for a copy event.
@@ -13,7 +14,8 @@ pub struct CopyTable {
     /// combination. The ID may be one of the below:
     /// 1. Call ID/Caller ID for CopyDataType::Memory
     /// 2. The hi/lo limbs of bytecode hash for CopyDataType::Bytecode
-    /// 3. Transaction ID for CopyDataType::TxCalldata, CopyDataType::TxLog
+    /// 3. Transaction ID for CopyDataType::TxCalldata, CopyDataType::TxLog and
+    ///    the access list tags
     pub id: word::Word<Column<Advice>>,
     /// The source/destination address for this copy step.  Can be memory
     /// address, byte index in the bytecode, tx call data, and tx log data.
@@ -160,6 +162,20 @@ impl CopyTable {
             // is_code
             let is_code = Value::known(copy_step.is_code.map_or(F::ZERO, |v| F::from(v as u64)));
 
+            // value_word: the storage key on the read rows of the storage keys, and the address
+            // otherwise
+            let value_word = match (tag, copy_event.access_list.get(step_idx / 2)) {
+                (CopyDataType::AccessListStorageKeys, Some((_, key))) if is_read_step => {
+                    word::Word::<F>::from(*key)
+                }
+                (
+                    CopyDataType::AccessListAddresses | CopyDataType::AccessListStorageKeys,
+                    Some((address, _)),
+                ) => word::Word::from(*address),
+                _ => word::Word::default(),
+            }
+            .into_value();
+
             assignments.push((
                 tag,
                 [
@@ -196,6 +212,8 @@ impl CopyTable {
                     (value_acc, "value_acc"),
                     (is_pad, "is_pad"),
                     (is_code, "is_code"),
+                    (value_word.lo(), "value_word_lo"),
+                    (value_word.hi(), "value_word_hi"),
                 ],
             ));
         }
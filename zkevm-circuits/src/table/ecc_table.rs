@@ -0,0 +1,135 @@
+use super::*;
+use bus_mapping::{
+    circuit_input_builder::{EcAddEvent, EcMulEvent},
+    precompile::PrecompileCalls,
+};
+
+/// Ecc Table, used to verify the operations on the BN254 curve of the ecAdd
+/// and ecMul calls, including the validity of their inputs.
+#[derive(Clone, Debug)]
+pub struct EccTable {
+    /// True when the row is enabled
+    pub is_enabled: Column<Advice>,
+    /// Precompile of the operation
+    pub op_type: Column<Advice>,
+    /// Arguments of the operation: the coordinates of both points of an
+    /// addition, or the coordinates of the point and the scalar of a
+    /// multiplication
+    pub args: [word::Word<Column<Advice>>; 4],
+    /// Coordinates of the resulting point, which are 0 for an invalid input
+    pub output: [word::Word<Column<Advice>>; 2],
+    /// Whether the points are on the curve, with coordinates in the base
+    /// field
+    pub is_valid: Column<Advice>,
+}
+
+impl<F: Field> LookupTable<F> for EccTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        let mut columns: Vec<Column<Any>> = vec![self.is_enabled.into(), self.op_type.into()];
+        for word in self.args.iter().chain(self.output.iter()) {
+            columns.push(word.lo().into());
+            columns.push(word.hi().into());
+        }
+        columns.push(self.is_valid.into());
+        columns
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        let mut annotations = vec![String::from("is_enabled"), String::from("op_type")];
+        for i in 0..4 {
+            annotations.push(format!("arg{}_lo", i + 1));
+            annotations.push(format!("arg{}_hi", i + 1));
+        }
+        for i in 0..2 {
+            annotations.push(format!("output{}_lo", i + 1));
+            annotations.push(format!("output{}_hi", i + 1));
+        }
+        annotations.push(String::from("is_valid"));
+        annotations
+    }
+}
+
+impl EccTable {
+    /// Construct a new EccTable
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            is_enabled: meta.advice_column(),
+            op_type: meta.advice_column(),
+            args: [(); 4].map(|_| word::Word::new([meta.advice_column(), meta.advice_column()])),
+            output: [(); 2].map(|_| word::Word::new([meta.advice_column(), meta.advice_column()])),
+            is_valid: meta.advice_column(),
+        }
+    }
+
+    /// Generate the ecc table row of an operation.
+    fn assignments<F: Field>(
+        op_type: PrecompileCalls,
+        args: [U256; 4],
+        output: [U256; 2],
+        is_valid: bool,
+    ) -> Vec<Value<F>> {
+        let mut row = vec![
+            Value::known(F::ONE),
+            Value::known(F::from(op_type.address())),
+        ];
+        for value in args.into_iter().chain(output) {
+            let word = word::Word::<F>::from(value);
+            row.push(Value::known(word.lo()));
+            row.push(Value::known(word.hi()));
+        }
+        row.push(Value::known(F::from(is_valid as u64)));
+        row
+    }
+
+    /// Provide this function for the case that we want to consume an ecc
+    /// table but without running the full ecc circuit
+    pub fn dev_load<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        add_events: &[EcAddEvent],
+        mul_events: &[EcMulEvent],
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "ecc table",
+            |mut region| {
+                let ecc_table_columns = <EccTable as LookupTable<F>>::advice_columns(self);
+                for &column in ecc_table_columns.iter() {
+                    region.assign_advice(
+                        || "ecc table all-zero row",
+                        column,
+                        0,
+                        || Value::known(F::ZERO),
+                    )?;
+                }
+
+                let add_rows = add_events.iter().map(|event| {
+                    Self::assignments(
+                        PrecompileCalls::Bn128Add,
+                        [event.p[0], event.p[1], event.q[0], event.q[1]],
+                        event.r,
+                        event.is_valid,
+                    )
+                });
+                let mul_rows = mul_events.iter().map(|event| {
+                    Self::assignments(
+                        PrecompileCalls::Bn128Mul,
+                        [event.p[0], event.p[1], event.s, U256::zero()],
+                        event.r,
+                        event.is_valid,
+                    )
+                });
+                for (offset, row) in add_rows.chain(mul_rows).enumerate() {
+                    for (&column, value) in ecc_table_columns.iter().zip_eq(row) {
+                        region.assign_advice(
+                            || format!("ecc table row {}", offset + 1),
+                            column,
+                            offset + 1,
+                            || value,
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
@@ -0,0 +1,115 @@
+use super::*;
+use bus_mapping::circuit_input_builder::ModExpEvent;
+
+/// ModExp Table, used to verify the modular exponentiations `base ^ exponent
+/// mod modulus` of the modexp calls, with all the values in 32-byte words.
+#[derive(Clone, Debug)]
+pub struct ModExpTable {
+    /// True when the row is enabled
+    pub is_enabled: Column<Advice>,
+    /// Base of the exponentiation
+    pub base: word::Word<Column<Advice>>,
+    /// Exponent of the exponentiation
+    pub exponent: word::Word<Column<Advice>>,
+    /// Modulus of the exponentiation
+    pub modulus: word::Word<Column<Advice>>,
+    /// Result of the exponentiation, which is 0 when the modulus is 0
+    pub output: word::Word<Column<Advice>>,
+}
+
+impl<F: Field> LookupTable<F> for ModExpTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.is_enabled.into(),
+            self.base.lo().into(),
+            self.base.hi().into(),
+            self.exponent.lo().into(),
+            self.exponent.hi().into(),
+            self.modulus.lo().into(),
+            self.modulus.hi().into(),
+            self.output.lo().into(),
+            self.output.hi().into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("is_enabled"),
+            String::from("base_lo"),
+            String::from("base_hi"),
+            String::from("exponent_lo"),
+            String::from("exponent_hi"),
+            String::from("modulus_lo"),
+            String::from("modulus_hi"),
+            String::from("output_lo"),
+            String::from("output_hi"),
+        ]
+    }
+}
+
+impl ModExpTable {
+    /// Construct a new ModExpTable
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            is_enabled: meta.advice_column(),
+            base: word::Word::new([meta.advice_column(), meta.advice_column()]),
+            exponent: word::Word::new([meta.advice_column(), meta.advice_column()]),
+            modulus: word::Word::new([meta.advice_column(), meta.advice_column()]),
+            output: word::Word::new([meta.advice_column(), meta.advice_column()]),
+        }
+    }
+
+    /// Generate the modexp table row of a modular exponentiation.
+    pub fn assignments<F: Field>(event: &ModExpEvent) -> [Value<F>; 9] {
+        let [base, exponent, modulus, output] =
+            [event.base, event.exponent, event.modulus, event.output].map(word::Word::<F>::from);
+
+        [
+            Value::known(F::ONE),
+            Value::known(base.lo()),
+            Value::known(base.hi()),
+            Value::known(exponent.lo()),
+            Value::known(exponent.hi()),
+            Value::known(modulus.lo()),
+            Value::known(modulus.hi()),
+            Value::known(output.lo()),
+            Value::known(output.hi()),
+        ]
+    }
+
+    /// Provide this function for the case that we want to consume a modexp
+    /// table but without running the full modexp circuit
+    pub fn dev_load<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        events: &[ModExpEvent],
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "modexp table",
+            |mut region| {
+                let modexp_table_columns = <ModExpTable as LookupTable<F>>::advice_columns(self);
+                for &column in modexp_table_columns.iter() {
+                    region.assign_advice(
+                        || "modexp table all-zero row",
+                        column,
+                        0,
+                        || Value::known(F::ZERO),
+                    )?;
+                }
+
+                for (offset, event) in events.iter().enumerate() {
+                    let row = Self::assignments(event);
+                    for (&column, value) in modexp_table_columns.iter().zip_eq(row) {
+                        region.assign_advice(
+                            || format!("modexp table row {}", offset + 1),
+                            column,
+                            offset + 1,
+                            || value,
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
@@ -0,0 +1,143 @@
+use super::*;
+use bus_mapping::circuit_input_builder::P256VerifyEvent;
+
+/// P-256 SigTable, used to verify the secp256r1 signatures of the P256VERIFY
+/// calls (RIP-7212) whose input has a valid length.  Unlike the SigTable, the
+/// public key is given instead of being recovered from the signature.
+#[derive(Clone, Debug)]
+pub struct P256SigTable {
+    /// True when the row is enabled
+    pub is_enabled: Column<Advice>,
+    /// Hash of the signed message, not reduced modulo the order of the curve
+    pub msg_hash: word::Word<Column<Advice>>,
+    /// r-component of the signature
+    pub sig_r: word::Word<Column<Advice>>,
+    /// s-component of the signature
+    pub sig_s: word::Word<Column<Advice>>,
+    /// x coordinate of the public key
+    pub pk_x: word::Word<Column<Advice>>,
+    /// y coordinate of the public key
+    pub pk_y: word::Word<Column<Advice>>,
+    /// Whether the public key is a point of the curve and the signature is
+    /// valid
+    pub is_valid: Column<Advice>,
+}
+
+impl<F: Field> LookupTable<F> for P256SigTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.is_enabled.into(),
+            self.msg_hash.lo().into(),
+            self.msg_hash.hi().into(),
+            self.sig_r.lo().into(),
+            self.sig_r.hi().into(),
+            self.sig_s.lo().into(),
+            self.sig_s.hi().into(),
+            self.pk_x.lo().into(),
+            self.pk_x.hi().into(),
+            self.pk_y.lo().into(),
+            self.pk_y.hi().into(),
+            self.is_valid.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("is_enabled"),
+            String::from("msg_hash_lo"),
+            String::from("msg_hash_hi"),
+            String::from("sig_r_lo"),
+            String::from("sig_r_hi"),
+            String::from("sig_s_lo"),
+            String::from("sig_s_hi"),
+            String::from("pk_x_lo"),
+            String::from("pk_x_hi"),
+            String::from("pk_y_lo"),
+            String::from("pk_y_hi"),
+            String::from("is_valid"),
+        ]
+    }
+}
+
+impl P256SigTable {
+    /// Construct a new P256SigTable
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            is_enabled: meta.advice_column(),
+            msg_hash: word::Word::new([meta.advice_column(), meta.advice_column()]),
+            sig_r: word::Word::new([meta.advice_column(), meta.advice_column()]),
+            sig_s: word::Word::new([meta.advice_column(), meta.advice_column()]),
+            pk_x: word::Word::new([meta.advice_column(), meta.advice_column()]),
+            pk_y: word::Word::new([meta.advice_column(), meta.advice_column()]),
+            is_valid: meta.advice_column(),
+        }
+    }
+
+    /// Generate the P-256 sig table assignments from a signature
+    /// verification.
+    pub fn assignments<F: Field>(event: &P256VerifyEvent) -> Vec<[Value<F>; 12]> {
+        let [msg_hash, sig_r, sig_s, pk_x, pk_y] = [
+            event.msg_hash,
+            event.sig_r,
+            event.sig_s,
+            event.pk_x,
+            event.pk_y,
+        ]
+        .map(word::Word::<F>::from);
+
+        vec![[
+            Value::known(F::ONE),
+            Value::known(msg_hash.lo()),
+            Value::known(msg_hash.hi()),
+            Value::known(sig_r.lo()),
+            Value::known(sig_r.hi()),
+            Value::known(sig_s.lo()),
+            Value::known(sig_s.hi()),
+            Value::known(pk_x.lo()),
+            Value::known(pk_x.hi()),
+            Value::known(pk_y.lo()),
+            Value::known(pk_y.hi()),
+            Value::known(F::from(event.is_valid as u64)),
+        ]]
+    }
+
+    /// Provide this function for the case that we want to consume a P-256
+    /// sig table but without running the full P-256 signature circuit
+    pub fn dev_load<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        events: &[P256VerifyEvent],
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "p256 sig table",
+            |mut region| {
+                let mut offset = 0;
+                let p256_sig_table_columns = <P256SigTable as LookupTable<F>>::advice_columns(self);
+                for &column in p256_sig_table_columns.iter() {
+                    region.assign_advice(
+                        || "p256 sig table all-zero row",
+                        column,
+                        offset,
+                        || Value::known(F::ZERO),
+                    )?;
+                }
+                offset += 1;
+
+                for event in events {
+                    for row in Self::assignments(event) {
+                        for (&column, value) in p256_sig_table_columns.iter().zip_eq(row) {
+                            region.assign_advice(
+                                || format!("p256 sig table row {}", offset),
+                                column,
+                                offset,
+                                || value,
+                            )?;
+                        }
+                        offset += 1;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
@@ -0,0 +1,109 @@
+use super::*;
+use eth_types::poseidon::poseidon;
+
+/// Poseidon Table, used to verify Poseidon hashing from RLC'ed input.  It has
+/// the layout of the KeccakTable, with the digest as a single field element.
+#[derive(Clone, Debug)]
+pub struct PoseidonTable {
+    /// True when the row is enabled
+    pub is_enabled: Column<Advice>,
+    /// Byte array input as `RLC(reversed(input))`
+    pub input_rlc: Column<Advice>, // RLC of input bytes
+    /// Byte array input length
+    pub input_len: Column<Advice>,
+    /// Output hash, an element of the field
+    pub output: Column<Advice>,
+}
+
+impl<F: Field> LookupTable<F> for PoseidonTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.is_enabled.into(),
+            self.input_rlc.into(),
+            self.input_len.into(),
+            self.output.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("is_enabled"),
+            String::from("input_rlc"),
+            String::from("input_len"),
+            String::from("output"),
+        ]
+    }
+}
+
+impl PoseidonTable {
+    /// Construct a new PoseidonTable
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            is_enabled: meta.advice_column(),
+            input_rlc: meta.advice_column_in(SecondPhase),
+            input_len: meta.advice_column(),
+            output: meta.advice_column(),
+        }
+    }
+
+    /// Generate the poseidon table assignments from a byte array input.  The
+    /// input RLC uses the keccak input challenge.
+    pub fn assignments<F: Field>(
+        input: &[u8],
+        challenges: &Challenges<Value<F>>,
+    ) -> Vec<[Value<F>; 4]> {
+        let input_rlc = challenges
+            .keccak_input()
+            .map(|challenge| rlc::value(input.iter().rev(), challenge));
+        let input_len = F::from(input.len() as u64);
+
+        vec![[
+            Value::known(F::ONE),
+            input_rlc,
+            Value::known(input_len),
+            Value::known(poseidon(input)),
+        ]]
+    }
+
+    /// Provide this function for the case that we want to consume a poseidon
+    /// table but without running the full poseidon circuit
+    pub fn dev_load<'a, F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        inputs: impl IntoIterator<Item = &'a Vec<u8>> + Clone,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "poseidon table",
+            |mut region| {
+                let mut offset = 0;
+                for column in <PoseidonTable as LookupTable<F>>::advice_columns(self) {
+                    region.assign_advice(
+                        || "poseidon table all-zero row",
+                        column,
+                        offset,
+                        || Value::known(F::ZERO),
+                    )?;
+                }
+                offset += 1;
+
+                let poseidon_table_columns =
+                    <PoseidonTable as LookupTable<F>>::advice_columns(self);
+                for input in inputs.clone() {
+                    for row in Self::assignments(input, challenges) {
+                        for (&column, value) in poseidon_table_columns.iter().zip_eq(row) {
+                            region.assign_advice(
+                                || format!("poseidon table row {}", offset),
+                                column,
+                                offset,
+                                || value,
+                            )?;
+                        }
+                        offset += 1;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
@@ -0,0 +1,112 @@
+use super::*;
+use eth_types::rsa_types::sha256;
+
+/// Sha256 Table, used to verify SHA-256 hashing from RLC'ed input.  It has
+/// the same layout as the KeccakTable.
+#[derive(Clone, Debug)]
+pub struct Sha256Table {
+    /// True when the row is enabled
+    pub is_enabled: Column<Advice>,
+    /// Byte array input as `RLC(reversed(input))`
+    pub input_rlc: Column<Advice>, // RLC of input bytes
+    /// Byte array input length
+    pub input_len: Column<Advice>,
+    /// Output hash word
+    pub output: word::Word<Column<Advice>>,
+}
+
+impl<F: Field> LookupTable<F> for Sha256Table {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.is_enabled.into(),
+            self.input_rlc.into(),
+            self.input_len.into(),
+            self.output.lo().into(),
+            self.output.hi().into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("is_enabled"),
+            String::from("input_rlc"),
+            String::from("input_len"),
+            String::from("output_lo"),
+            String::from("output_hi"),
+        ]
+    }
+}
+
+impl Sha256Table {
+    /// Construct a new Sha256Table
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            is_enabled: meta.advice_column(),
+            input_rlc: meta.advice_column_in(SecondPhase),
+            input_len: meta.advice_column(),
+            output: word::Word::new([meta.advice_column(), meta.advice_column()]),
+        }
+    }
+
+    /// Generate the sha256 table assignments from a byte array input.  The
+    /// input RLC uses the keccak input challenge.
+    pub fn assignments<F: Field>(
+        input: &[u8],
+        challenges: &Challenges<Value<F>>,
+    ) -> Vec<[Value<F>; 5]> {
+        let input_rlc = challenges
+            .keccak_input()
+            .map(|challenge| rlc::value(input.iter().rev(), challenge));
+        let input_len = F::from(input.len() as u64);
+        let output = word::Word::from(U256::from_big_endian(&sha256(input)));
+
+        vec![[
+            Value::known(F::ONE),
+            input_rlc,
+            Value::known(input_len),
+            Value::known(output.lo()),
+            Value::known(output.hi()),
+        ]]
+    }
+
+    /// Provide this function for the case that we want to consume a sha256
+    /// table but without running the full sha256 circuit
+    pub fn dev_load<'a, F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        inputs: impl IntoIterator<Item = &'a Vec<u8>> + Clone,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "sha256 table",
+            |mut region| {
+                let mut offset = 0;
+                for column in <Sha256Table as LookupTable<F>>::advice_columns(self) {
+                    region.assign_advice(
+                        || "sha256 table all-zero row",
+                        column,
+                        offset,
+                        || Value::known(F::ZERO),
+                    )?;
+                }
+                offset += 1;
+
+                let sha256_table_columns = <Sha256Table as LookupTable<F>>::advice_columns(self);
+                for input in inputs.clone() {
+                    for row in Self::assignments(input, challenges) {
+                        for (&column, value) in sha256_table_columns.iter().zip_eq(row) {
+                            region.assign_advice(
+                                || format!("sha256 table row {}", offset),
+                                column,
+                                offset,
+                                || value,
+                            )?;
+                        }
+                        offset += 1;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
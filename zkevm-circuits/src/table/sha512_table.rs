@@ -0,0 +1,120 @@
+use super::*;
+use eth_types::ed25519_types::sha512;
+
+/// Number of 128 bits words of a SHA-512 digest
+pub const SHA512_OUTPUT_WORDS: usize = 4;
+
+/// Sha512 Table, used to verify SHA-512 hashing from RLC'ed input.  It has
+/// the layout of the KeccakTable, with the 64 bytes digest split in 4 words.
+#[derive(Clone, Debug)]
+pub struct Sha512Table {
+    /// True when the row is enabled
+    pub is_enabled: Column<Advice>,
+    /// Byte array input as `RLC(reversed(input))`
+    pub input_rlc: Column<Advice>, // RLC of input bytes
+    /// Byte array input length
+    pub input_len: Column<Advice>,
+    /// Output digest, where `output[i]` is the big endian value of the digest
+    /// bytes `16 * i..16 * (i + 1)`
+    pub output: [Column<Advice>; SHA512_OUTPUT_WORDS],
+}
+
+impl<F: Field> LookupTable<F> for Sha512Table {
+    fn columns(&self) -> Vec<Column<Any>> {
+        [self.is_enabled, self.input_rlc, self.input_len]
+            .into_iter()
+            .chain(self.output)
+            .map(|column| column.into())
+            .collect()
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        [
+            String::from("is_enabled"),
+            String::from("input_rlc"),
+            String::from("input_len"),
+        ]
+        .into_iter()
+        .chain((0..SHA512_OUTPUT_WORDS).map(|i| format!("output_{}", i)))
+        .collect()
+    }
+}
+
+impl Sha512Table {
+    /// Construct a new Sha512Table
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            is_enabled: meta.advice_column(),
+            input_rlc: meta.advice_column_in(SecondPhase),
+            input_len: meta.advice_column(),
+            output: array::from_fn(|_| meta.advice_column()),
+        }
+    }
+
+    /// Generate the sha512 table assignments from a byte array input.  The
+    /// input RLC uses the keccak input challenge.
+    pub fn assignments<F: Field>(
+        input: &[u8],
+        challenges: &Challenges<Value<F>>,
+    ) -> Vec<[Value<F>; 3 + SHA512_OUTPUT_WORDS]> {
+        let input_rlc = challenges
+            .keccak_input()
+            .map(|challenge| rlc::value(input.iter().rev(), challenge));
+        let input_len = F::from(input.len() as u64);
+        let digest = sha512(input);
+
+        let mut row = [Value::known(F::ZERO); 3 + SHA512_OUTPUT_WORDS];
+        row[0] = Value::known(F::ONE);
+        row[1] = input_rlc;
+        row[2] = Value::known(input_len);
+        for (cell, bytes) in row[3..].iter_mut().zip(digest.chunks(16)) {
+            let word = bytes
+                .iter()
+                .fold(0u128, |acc, byte| (acc << 8) + *byte as u128);
+            *cell = Value::known(F::from_u128(word));
+        }
+        vec![row]
+    }
+
+    /// Provide this function for the case that we want to consume a sha512
+    /// table without a circuit proving the hashes, as no SHA-512 circuit is
+    /// implemented yet.
+    pub fn dev_load<'a, F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        inputs: impl IntoIterator<Item = &'a Vec<u8>> + Clone,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "sha512 table",
+            |mut region| {
+                let mut offset = 0;
+                for column in <Sha512Table as LookupTable<F>>::advice_columns(self) {
+                    region.assign_advice(
+                        || "sha512 table all-zero row",
+                        column,
+                        offset,
+                        || Value::known(F::ZERO),
+                    )?;
+                }
+                offset += 1;
+
+                let sha512_table_columns = <Sha512Table as LookupTable<F>>::advice_columns(self);
+                for input in inputs.clone() {
+                    for row in Self::assignments(input, challenges) {
+                        for (&column, value) in sha512_table_columns.iter().zip_eq(row) {
+                            region.assign_advice(
+                                || format!("sha512 table row {}", offset),
+                                column,
+                                offset,
+                                || value,
+                            )?;
+                        }
+                        offset += 1;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
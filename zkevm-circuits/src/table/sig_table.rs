@@ -0,0 +1,144 @@
+use super::*;
+use crate::tx_circuit::sign_verify::recovery_id;
+use eth_types::{
+    keccak256,
+    sign_types::{pk_bytes_le, pk_bytes_swap_endianness, SignData},
+    Address,
+};
+use halo2_proofs::halo2curves::{ff::PrimeField, secp256k1::Fq};
+
+/// Signature Table, used to look up the secp256k1 signatures verified by the
+/// SigCircuit, as done by the ecrecover precompile.  A row with `is_valid`
+/// claims that `(sig_r, sig_s)` is a signature of `msg_hash` with recovery id
+/// `sig_v` by the public key of `recovered_addr`.  The first row is all zero,
+/// so that disabled lookups with all-zero inputs are satisfied.
+#[derive(Clone, Debug)]
+pub struct SigTable {
+    /// 1 on the rows of the signature slots
+    pub q_enable: Column<Fixed>,
+    /// Hash of the signed message, reduced modulo the order of secp256k1
+    pub msg_hash: word::Word<Column<Advice>>,
+    /// Recovery id (0 or 1), the parity of the y coordinate of the point R
+    pub sig_v: Column<Advice>,
+    /// Signature r
+    pub sig_r: word::Word<Column<Advice>>,
+    /// Signature s
+    pub sig_s: word::Word<Column<Advice>>,
+    /// Address of the public key recovered from the signature
+    pub recovered_addr: word::Word<Column<Advice>>,
+    /// 1 if the signature is valid, 0 on padding rows, where all the other
+    /// columns are 0
+    pub is_valid: Column<Advice>,
+}
+
+impl SigTable {
+    /// Construct a new SigTable
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            q_enable: meta.fixed_column(),
+            msg_hash: word::Word::new([meta.advice_column(), meta.advice_column()]),
+            sig_v: meta.advice_column(),
+            sig_r: word::Word::new([meta.advice_column(), meta.advice_column()]),
+            sig_s: word::Word::new([meta.advice_column(), meta.advice_column()]),
+            recovered_addr: word::Word::new([meta.advice_column(), meta.advice_column()]),
+            is_valid: meta.advice_column(),
+        }
+    }
+
+    /// Dev load the signature table with the rows of the valid signatures
+    /// `signatures`, without verifying them.
+    pub fn dev_load<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        signatures: &[SignData],
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "sig table (dev load)",
+            |mut region| {
+                let advice_columns = <SigTable as LookupTable<F>>::advice_columns(self);
+                region.assign_fixed(|| "q_enable", self.q_enable, 0, || Value::known(F::ZERO))?;
+                for &column in &advice_columns {
+                    region.assign_advice(
+                        || "sig table empty row",
+                        column,
+                        0,
+                        || Value::known(F::ZERO),
+                    )?;
+                }
+
+                for (i, sign_data) in signatures.iter().enumerate() {
+                    let offset = i + 1;
+                    let pk_be = pk_bytes_swap_endianness(&pk_bytes_le(&sign_data.pk));
+                    let recovered_addr = Address::from_slice(&keccak256(pk_be)[12..]);
+                    let to_word =
+                        |scalar: &Fq| Word::<F>::from(U256::from_little_endian(&scalar.to_repr()));
+                    let msg_hash = to_word(&sign_data.msg_hash);
+                    let sig_r = to_word(&sign_data.signature.0);
+                    let sig_s = to_word(&sign_data.signature.1);
+                    let recovered_addr = Word::<F>::from(recovered_addr);
+
+                    region.assign_fixed(
+                        || "q_enable",
+                        self.q_enable,
+                        offset,
+                        || Value::known(F::ONE),
+                    )?;
+                    for (&column, value) in advice_columns.iter().zip_eq([
+                        msg_hash.lo(),
+                        msg_hash.hi(),
+                        F::from(recovery_id(sign_data) as u64),
+                        sig_r.lo(),
+                        sig_r.hi(),
+                        sig_s.lo(),
+                        sig_s.hi(),
+                        recovered_addr.lo(),
+                        recovered_addr.hi(),
+                        F::ONE,
+                    ]) {
+                        region.assign_advice(
+                            || format!("sig table row {}", offset),
+                            column,
+                            offset,
+                            || Value::known(value),
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+impl<F: Field> LookupTable<F> for SigTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.q_enable.into(),
+            self.msg_hash.lo().into(),
+            self.msg_hash.hi().into(),
+            self.sig_v.into(),
+            self.sig_r.lo().into(),
+            self.sig_r.hi().into(),
+            self.sig_s.lo().into(),
+            self.sig_s.hi().into(),
+            self.recovered_addr.lo().into(),
+            self.recovered_addr.hi().into(),
+            self.is_valid.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("q_enable"),
+            String::from("msg_hash_lo"),
+            String::from("msg_hash_hi"),
+            String::from("sig_v"),
+            String::from("sig_r_lo"),
+            String::from("sig_r_hi"),
+            String::from("sig_s_lo"),
+            String::from("sig_s_hi"),
+            String::from("recovered_addr_lo"),
+            String::from("recovered_addr_hi"),
+            String::from("is_valid"),
+        ]
+    }
+}
@@ -0,0 +1,251 @@
+use super::*;
+use eth_types::{poseidon::poseidon, rsa_types::sha256};
+use gadgets::util::Expr;
+use std::fmt::Debug;
+
+/// Table of the hashes of RLC'ed byte inputs, implemented by the hash tables
+/// with the layout of the KeccakTable.  The circuits that only need a
+/// collision resistant hash of their inputs are generic over their SpongeChip,
+/// and can be instantiated with any of these backends: the MPT circuit for the
+/// nodes, the PI circuit for the commitment to the public inputs, and the
+/// Bytecode circuit for the code hashes.
+pub trait SpongeChip<F: Field>: LookupTable<F> + Clone + Debug {
+    /// Name of the hash function
+    const NAME: &'static str;
+
+    /// Construct the columns of the table.
+    fn construct(meta: &mut ConstraintSystem<F>) -> Self;
+
+    /// Return the digest of an input as a word.  A digest that is a single
+    /// field element is the `lo` of a word with a zero `hi`.
+    fn digest(input: &[u8]) -> word::Word<F>;
+
+    /// Return the expressions of the table in the order `[is_enabled,
+    /// input_rlc, input_len, output_lo, output_hi]`.
+    fn sponge_exprs(&self, meta: &mut VirtualCells<F>) -> [Expression<F>; 5];
+
+    /// Load the hashes of `inputs` without running the circuit of the hash
+    /// function.
+    fn dev_load<'a>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        inputs: impl IntoIterator<Item = &'a Vec<u8>> + Clone,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error>;
+
+    /// Return the lookup of `digest = hash(input)` enabled by `condition`,
+    /// where `input_rlc` is `RLC(reversed(input))` with the keccak input
+    /// challenge.
+    fn lookup_exprs(
+        &self,
+        meta: &mut VirtualCells<F>,
+        condition: Expression<F>,
+        input_rlc: Expression<F>,
+        input_len: Expression<F>,
+        digest: word::Word<Expression<F>>,
+    ) -> Vec<(Expression<F>, Expression<F>)> {
+        [1.expr(), input_rlc, input_len, digest.lo(), digest.hi()]
+            .into_iter()
+            .zip(self.sponge_exprs(meta))
+            .map(|(input, table)| (condition.clone() * input, table))
+            .collect()
+    }
+}
+
+impl<F: Field> SpongeChip<F> for KeccakTable {
+    const NAME: &'static str = "keccak";
+
+    fn construct(meta: &mut ConstraintSystem<F>) -> Self {
+        KeccakTable::construct(meta)
+    }
+
+    fn digest(input: &[u8]) -> word::Word<F> {
+        word::Word::from(keccak(input))
+    }
+
+    fn sponge_exprs(&self, meta: &mut VirtualCells<F>) -> [Expression<F>; 5] {
+        self.table_exprs(meta).try_into().unwrap()
+    }
+
+    fn dev_load<'a>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        inputs: impl IntoIterator<Item = &'a Vec<u8>> + Clone,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        KeccakTable::dev_load(self, layouter, inputs, challenges)
+    }
+}
+
+impl<F: Field> SpongeChip<F> for Sha256Table {
+    const NAME: &'static str = "sha256";
+
+    fn construct(meta: &mut ConstraintSystem<F>) -> Self {
+        Sha256Table::construct(meta)
+    }
+
+    fn digest(input: &[u8]) -> word::Word<F> {
+        word::Word::from(U256::from_big_endian(&sha256(input)))
+    }
+
+    fn sponge_exprs(&self, meta: &mut VirtualCells<F>) -> [Expression<F>; 5] {
+        self.table_exprs(meta).try_into().unwrap()
+    }
+
+    fn dev_load<'a>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        inputs: impl IntoIterator<Item = &'a Vec<u8>> + Clone,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        Sha256Table::dev_load(self, layouter, inputs, challenges)
+    }
+}
+
+impl<F: Field> SpongeChip<F> for PoseidonTable {
+    const NAME: &'static str = "poseidon";
+
+    fn construct(meta: &mut ConstraintSystem<F>) -> Self {
+        PoseidonTable::construct(meta)
+    }
+
+    fn digest(input: &[u8]) -> word::Word<F> {
+        word::Word::new([poseidon(input), F::ZERO])
+    }
+
+    fn sponge_exprs(&self, meta: &mut VirtualCells<F>) -> [Expression<F>; 5] {
+        // The `hi` of the output is the constant 0, so that a looked up digest
+        // must be a single field element.
+        let [is_enabled, input_rlc, input_len, output]: [Expression<F>; 4] =
+            self.table_exprs(meta).try_into().unwrap();
+        [is_enabled, input_rlc, input_len, output, 0.expr()]
+    }
+
+    fn dev_load<'a>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        inputs: impl IntoIterator<Item = &'a Vec<u8>> + Clone,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        PoseidonTable::dev_load(self, layouter, inputs, challenges)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        circuit::SimpleFloorPlanner, dev::MockProver, halo2curves::bn256::Fr, plonk::Circuit,
+    };
+    use std::marker::PhantomData;
+
+    /// Circuit looking up the digests of its inputs in a SpongeChip
+    #[derive(Clone, Debug)]
+    struct SpongeTestCircuit<S> {
+        inputs: Vec<Vec<u8>>,
+        // Whether the lo of the first digest is off by one
+        tampered: bool,
+        _marker: PhantomData<S>,
+    }
+
+    impl<S: SpongeChip<Fr>> Circuit<Fr> for SpongeTestCircuit<S> {
+        type Config = (S, Challenges, Selector, [Column<Advice>; 4]);
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                inputs: vec![],
+                tampered: false,
+                _marker: PhantomData,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            let table = S::construct(meta);
+            let challenges = Challenges::construct(meta);
+            let q_lookup = meta.complex_selector();
+            let columns = [
+                meta.advice_column_in(SecondPhase),
+                meta.advice_column(),
+                meta.advice_column(),
+                meta.advice_column(),
+            ];
+            meta.lookup_any(S::NAME, |meta| {
+                let q_lookup = meta.query_selector(q_lookup);
+                let [input_rlc, input_len, lo, hi] =
+                    columns.map(|column| meta.query_advice(column, Rotation::cur()));
+                table.lookup_exprs(
+                    meta,
+                    q_lookup,
+                    input_rlc,
+                    input_len,
+                    word::Word::new([lo, hi]),
+                )
+            });
+            (table, challenges, q_lookup, columns)
+        }
+
+        fn synthesize(
+            &self,
+            (table, challenges, q_lookup, columns): Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            let challenges = challenges.values(&mut layouter);
+            table.dev_load(&mut layouter, &self.inputs, &challenges)?;
+            layouter.assign_region(
+                || "sponge lookups",
+                |mut region| {
+                    for (offset, input) in self.inputs.iter().enumerate() {
+                        q_lookup.enable(&mut region, offset)?;
+                        let input_rlc = challenges
+                            .keccak_input()
+                            .map(|challenge| rlc::value(input.iter().rev(), challenge));
+                        let mut digest = S::digest(input);
+                        if self.tampered && offset == 0 {
+                            digest = word::Word::new([digest.lo() + Fr::from(1), digest.hi()]);
+                        }
+                        for (column, value) in columns.into_iter().zip([
+                            input_rlc,
+                            Value::known(Fr::from(input.len() as u64)),
+                            Value::known(digest.lo()),
+                            Value::known(digest.hi()),
+                        ]) {
+                            region.assign_advice(
+                                || format!("sponge lookup {}", offset),
+                                column,
+                                offset,
+                                || value,
+                            )?;
+                        }
+                    }
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    fn verify<S: SpongeChip<Fr>>(tampered: bool) -> bool {
+        let circuit = SpongeTestCircuit::<S> {
+            inputs: vec![vec![], vec![1, 2, 3], vec![0xff; 100]],
+            tampered,
+            _marker: PhantomData,
+        };
+        let prover = MockProver::<Fr>::run(8, &circuit, vec![]).unwrap();
+        prover.verify().is_ok()
+    }
+
+    #[test]
+    fn sponge_chip_backends() {
+        assert!(verify::<KeccakTable>(false));
+        assert!(verify::<Sha256Table>(false));
+        assert!(verify::<PoseidonTable>(false));
+    }
+
+    #[test]
+    fn sponge_chip_wrong_digest() {
+        assert!(!verify::<KeccakTable>(true));
+        assert!(!verify::<Sha256Table>(true));
+        assert!(!verify::<PoseidonTable>(true));
+    }
+}
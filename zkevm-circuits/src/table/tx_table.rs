@@ -1,4 +1,5 @@
 use super::*;
+use eth_types::evm_types::MAX_BLOBS_PER_BLOCK;
 
 /// Tag used to identify each field in the transaction in a row of the
 /// transaction table.
@@ -29,6 +30,37 @@ pub enum TxFieldTag {
     TxSignHash,
     /// CallData
     CallData,
+    /// TxGasUsed: Gas used by the transaction before the refund, as
+    /// constrained by the EVM circuit at the end of the transaction
+    TxGasUsed,
+    /// AccessListLength: Number of addresses in the access list
+    AccessListLength,
+    /// AccessListStorageKeysLength: Number of storage keys in the access list
+    AccessListStorageKeysLength,
+    /// AccessListAddress: Address of the access list entry at `index`
+    AccessListAddress,
+    /// AccessListStorageKey: Storage key at `index` in the storage keys of all
+    /// the access list entries
+    AccessListStorageKey,
+    /// AccessListStorageKeyAddress: Address of the access list entry of the
+    /// AccessListStorageKey at the same `index`
+    AccessListStorageKeyAddress,
+    /// BlobVersionedHashesLength: Number of blob versioned hashes (EIP-4844)
+    BlobVersionedHashesLength,
+    /// BlobVersionedHash: Blob versioned hash at `index` (EIP-4844)
+    BlobVersionedHash,
+    /// IsDeposit: 1 for an OP-stack deposit transaction, which has no
+    /// signature
+    IsDeposit,
+    /// Mint: Ether minted to the sender of a deposit transaction, 0 for other
+    /// transactions
+    Mint,
+    /// MaxFeePerGas: Max fee per gas of an EIP-1559 style transaction, or
+    /// the gas price of a legacy transaction
+    MaxFeePerGas,
+    /// MaxPriorityFeePerGas: Max priority fee per gas of an EIP-1559 style
+    /// transaction, or the gas price of a legacy transaction
+    MaxPriorityFeePerGas,
 }
 impl_expr!(TxFieldTag);
 
@@ -66,7 +98,8 @@ pub struct TxTable {
     pub tx_id: Column<Advice>,
     /// Tag (TxContextFieldTag)
     pub tag: Column<Fixed>,
-    /// Index for Tag = CallData
+    /// Index for Tag = CallData, AccessListAddress, AccessListStorageKey,
+    /// AccessListStorageKeyAddress and BlobVersionedHash
     pub index: Column<Advice>,
     /// Value
     pub value: word::Word<Column<Advice>>,
@@ -91,6 +124,7 @@ impl TxTable {
         txs: &[Transaction],
         max_txs: usize,
         max_calldata: usize,
+        max_access_list: usize,
     ) -> Result<(), Error> {
         assert!(
             txs.len() <= max_txs,
@@ -105,6 +139,27 @@ impl TxTable {
             sum_txs_calldata,
             max_calldata,
         );
+        let sum_txs_addresses = txs.iter().map(|tx| tx.access_list_items().len()).sum();
+        let sum_txs_storage_keys = txs
+            .iter()
+            .map(|tx| tx.access_list_storage_keys().count())
+            .sum();
+        assert!(
+            sum_txs_addresses <= max_access_list && sum_txs_storage_keys <= max_access_list,
+            "sum_txs_addresses <= max_access_list && sum_txs_storage_keys <= max_access_list: \
+            sum_txs_addresses={}, sum_txs_storage_keys={}, max_access_list={}",
+            sum_txs_addresses,
+            sum_txs_storage_keys,
+            max_access_list,
+        );
+        let sum_txs_blob_hashes = txs.iter().map(|tx| tx.blob_versioned_hashes.len()).sum();
+        assert!(
+            sum_txs_blob_hashes <= MAX_BLOBS_PER_BLOCK,
+            "sum_txs_blob_hashes <= MAX_BLOBS_PER_BLOCK: sum_txs_blob_hashes={}, \
+            MAX_BLOBS_PER_BLOCK={}",
+            sum_txs_blob_hashes,
+            MAX_BLOBS_PER_BLOCK,
+        );
 
         fn assign_row<F: Field>(
             region: &mut Region<'_, F>,
@@ -149,8 +204,16 @@ impl TxTable {
                 // Tx Table contains an initial region that has a size parametrized by max_txs
                 // with all the tx data except for calldata, and then a second
                 // region that has a size parametrized by max_calldata with all
-                // the tx calldata.  This is required to achieve a constant fixed column tag
-                // regardless of the number of input txs or the calldata size of each tx.
+                // the tx calldata, followed by the gas used and the access list lengths of every
+                // tx, and by three regions that have a size parametrized by max_access_list with
+                // the addresses, the storage keys and the addresses of the storage keys of the tx
+                // access lists, followed by the number
+                // of blob versioned hashes of every tx and by a region of MAX_BLOBS_PER_BLOCK rows
+                // with the blob versioned hashes of the txs, followed by the IsDeposit and the
+                // Mint of every tx, and by the MaxFeePerGas and the MaxPriorityFeePerGas of every
+                // tx.  This is required to achieve a
+                // constant fixed column tag regardless of the number of
+                // input txs or the calldata and access list sizes of each tx.
                 let mut calldata_assignments: Vec<[Value<F>; 5]> = Vec::new();
                 // Assign Tx data (all tx fields except for calldata)
                 let padding_txs: Vec<_> = (txs.len()..max_txs)
@@ -229,6 +292,175 @@ impl TxTable {
                     assign_row(&mut region, offset, &advice_columns, &self.tag, &row, "")?;
                     offset += 1;
                 }
+                // Assign the gas used by every tx, after the calldata
+                for tx in txs.iter().chain(padding_txs.iter()) {
+                    let row = [
+                        Value::known(F::from(tx.id)),
+                        Value::known(F::from(TxContextFieldTag::TxGasUsed as u64)),
+                        Value::known(F::ZERO),
+                        Value::known(F::from(tx.gas_used())),
+                        Value::known(F::ZERO),
+                    ];
+                    assign_row(&mut region, offset, &advice_columns, &self.tag, &row, "")?;
+                    offset += 1;
+                }
+                // Assign the access list length of every tx
+                for tx in txs.iter().chain(padding_txs.iter()) {
+                    let row = [
+                        Value::known(F::from(tx.id)),
+                        Value::known(F::from(TxContextFieldTag::AccessListLength as u64)),
+                        Value::known(F::ZERO),
+                        Value::known(F::from(tx.access_list_items().len() as u64)),
+                        Value::known(F::ZERO),
+                    ];
+                    assign_row(&mut region, offset, &advice_columns, &self.tag, &row, "")?;
+                    offset += 1;
+                }
+                // Assign the number of access list storage keys of every tx
+                for tx in txs.iter().chain(padding_txs.iter()) {
+                    let row = [
+                        Value::known(F::from(tx.id)),
+                        Value::known(F::from(
+                            TxContextFieldTag::AccessListStorageKeysLength as u64,
+                        )),
+                        Value::known(F::ZERO),
+                        Value::known(F::from(tx.access_list_storage_keys().count() as u64)),
+                        Value::known(F::ZERO),
+                    ];
+                    assign_row(&mut region, offset, &advice_columns, &self.tag, &row, "")?;
+                    offset += 1;
+                }
+                // Assign the access list addresses, indexed by the position of their entry in
+                // the access list of the tx, and the storage keys together with their addresses,
+                // indexed by the position of the key among the storage keys of the tx
+                let addresses = txs.iter().flat_map(|tx| {
+                    tx.access_list_items()
+                        .iter()
+                        .enumerate()
+                        .map(move |(index, item)| {
+                            (tx.id, index, word::Word::<F>::from(item.address))
+                        })
+                });
+                let tx_storage_keys = || {
+                    txs.iter().flat_map(|tx| {
+                        tx.access_list_storage_keys()
+                            .enumerate()
+                            .map(move |(index, (address, key))| (tx.id, index, address, key))
+                    })
+                };
+                let storage_keys = tx_storage_keys()
+                    .map(|(tx_id, index, _, key)| (tx_id, index, word::Word::<F>::from(key)));
+                let storage_key_addresses = tx_storage_keys().map(|(tx_id, index, address, _)| {
+                    (tx_id, index, word::Word::<F>::from(address))
+                });
+                for (tag, entries, len) in [
+                    (
+                        TxContextFieldTag::AccessListAddress,
+                        addresses.collect_vec(),
+                        sum_txs_addresses,
+                    ),
+                    (
+                        TxContextFieldTag::AccessListStorageKey,
+                        storage_keys.collect_vec(),
+                        sum_txs_storage_keys,
+                    ),
+                    (
+                        TxContextFieldTag::AccessListStorageKeyAddress,
+                        storage_key_addresses.collect_vec(),
+                        sum_txs_storage_keys,
+                    ),
+                ] {
+                    let padding_entries =
+                        (len..max_access_list).map(|_| (0, 0, word::Word::default()));
+                    for (tx_id, index, word) in entries.into_iter().chain(padding_entries) {
+                        let row = [
+                            Value::known(F::from(tx_id)),
+                            Value::known(F::from(tag as u64)),
+                            Value::known(F::from(index as u64)),
+                            Value::known(word.lo()),
+                            Value::known(word.hi()),
+                        ];
+                        assign_row(&mut region, offset, &advice_columns, &self.tag, &row, "")?;
+                        offset += 1;
+                    }
+                }
+                // Assign the number of blob versioned hashes of every tx
+                for tx in txs.iter().chain(padding_txs.iter()) {
+                    let row = [
+                        Value::known(F::from(tx.id)),
+                        Value::known(F::from(TxContextFieldTag::BlobVersionedHashesLength as u64)),
+                        Value::known(F::ZERO),
+                        Value::known(F::from(tx.blob_versioned_hashes.len() as u64)),
+                        Value::known(F::ZERO),
+                    ];
+                    assign_row(&mut region, offset, &advice_columns, &self.tag, &row, "")?;
+                    offset += 1;
+                }
+                // Assign the blob versioned hashes, indexed by their position in the tx
+                let blob_hashes = txs.iter().flat_map(|tx| {
+                    tx.blob_versioned_hashes
+                        .iter()
+                        .enumerate()
+                        .map(|(index, hash)| {
+                            (
+                                Value::known(F::from(tx.id)),
+                                index,
+                                word::Word::<F>::from(*hash),
+                            )
+                        })
+                });
+                let padding_blob_hashes = (sum_txs_blob_hashes..MAX_BLOBS_PER_BLOCK)
+                    .map(|_| (Value::known(F::ZERO), 0, word::Word::default()));
+                for (tx_id, index, word) in blob_hashes.chain(padding_blob_hashes) {
+                    let row = [
+                        tx_id,
+                        Value::known(F::from(TxContextFieldTag::BlobVersionedHash as u64)),
+                        Value::known(F::from(index as u64)),
+                        Value::known(word.lo()),
+                        Value::known(word.hi()),
+                    ];
+                    assign_row(&mut region, offset, &advice_columns, &self.tag, &row, "")?;
+                    offset += 1;
+                }
+                // Assign whether every tx is a deposit, and then the ether minted by every tx
+                for tag in [TxContextFieldTag::IsDeposit, TxContextFieldTag::Mint] {
+                    for tx in txs.iter().chain(padding_txs.iter()) {
+                        let word = if tag == TxContextFieldTag::IsDeposit {
+                            word::Word::<F>::from(tx.is_deposit())
+                        } else {
+                            word::Word::from(tx.mint())
+                        };
+                        let row = [
+                            Value::known(F::from(tx.id)),
+                            Value::known(F::from(tag as u64)),
+                            Value::known(F::ZERO),
+                            Value::known(word.lo()),
+                            Value::known(word.hi()),
+                        ];
+                        assign_row(&mut region, offset, &advice_columns, &self.tag, &row, "")?;
+                        offset += 1;
+                    }
+                }
+                // Assign the fee caps of every tx, the max fee per gas followed by the max
+                // priority fee per gas
+                for tx in txs.iter().chain(padding_txs.iter()) {
+                    let (fee_cap, tip_cap) = tx.fee_caps();
+                    for (tag, cap) in [
+                        (TxContextFieldTag::MaxFeePerGas, fee_cap),
+                        (TxContextFieldTag::MaxPriorityFeePerGas, tip_cap),
+                    ] {
+                        let word = word::Word::<F>::from(cap);
+                        let row = [
+                            Value::known(F::from(tx.id)),
+                            Value::known(F::from(tag as u64)),
+                            Value::known(F::ZERO),
+                            Value::known(word.lo()),
+                            Value::known(word.hi()),
+                        ];
+                        assign_row(&mut region, offset, &advice_columns, &self.tag, &row, "")?;
+                        offset += 1;
+                    }
+                }
                 Ok(())
             },
         )
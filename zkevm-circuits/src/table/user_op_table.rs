@@ -0,0 +1,87 @@
+use super::*;
+
+/// Tag used to identify each field of an ERC-4337 UserOperation in a row of
+/// the user operation table.  The fields from Sender to PaymasterAndDataHash
+/// are the words of the packed encoding, in order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+pub enum UserOpFieldTag {
+    /// Unused tag
+    Null = 0,
+    /// Sender
+    Sender,
+    /// Nonce
+    Nonce,
+    /// Keccak hash of the InitCode
+    InitCodeHash,
+    /// Keccak hash of the CallData
+    CallDataHash,
+    /// CallGasLimit
+    CallGasLimit,
+    /// VerificationGasLimit
+    VerificationGasLimit,
+    /// PreVerificationGas
+    PreVerificationGas,
+    /// MaxFeePerGas
+    MaxFeePerGas,
+    /// MaxPriorityFeePerGas
+    MaxPriorityFeePerGas,
+    /// Keccak hash of the PaymasterAndData
+    PaymasterAndDataHash,
+    /// Owner: address of the public key which signed the operation
+    Owner,
+    /// UserOpHash: hash of the operation with the EntryPoint and the chain
+    /// ID, signed by the owner.
+    UserOpHash,
+}
+impl_expr!(UserOpFieldTag);
+
+/// Table that contains the fields of the UserOperations of a bundle
+#[derive(Clone, Debug)]
+pub struct UserOpTable {
+    /// UserOperation ID
+    pub user_op_id: Column<Advice>,
+    /// Tag (UserOpFieldTag)
+    pub tag: Column<Fixed>,
+    /// Value
+    pub value: word::Word<Column<Advice>>,
+}
+
+impl UserOpTable {
+    /// Construct a new UserOpTable
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            user_op_id: meta.advice_column(),
+            tag: meta.fixed_column(),
+            value: word::Word::new([meta.advice_column(), meta.advice_column()]),
+        }
+    }
+}
+
+impl<F: Field> LookupTable<F> for UserOpTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.user_op_id.into(),
+            self.tag.into(),
+            self.value.lo().into(),
+            self.value.hi().into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("user_op_id"),
+            String::from("tag"),
+            String::from("value_lo"),
+            String::from("value_hi"),
+        ]
+    }
+
+    fn table_exprs(&self, meta: &mut VirtualCells<F>) -> Vec<Expression<F>> {
+        vec![
+            meta.query_advice(self.user_op_id, Rotation::cur()),
+            meta.query_fixed(self.tag, Rotation::cur()),
+            meta.query_advice(self.value.lo(), Rotation::cur()),
+            meta.query_advice(self.value.hi(), Rotation::cur()),
+        ]
+    }
+}
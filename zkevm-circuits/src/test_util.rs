@@ -156,10 +156,20 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
             .as_ref()
             .ok_or(CircuitTestError::NotEnoughAttributes)?;
         let block: GethData = block.clone().into();
-        let builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
-        let builder = builder
-            .handle_block(&block.eth_block, &block.geth_traces)
-            .map_err(|err| CircuitTestError::CannotHandleBlock(err.to_string()))?;
+        let builder = match self.circuits_params {
+            Some(params) => {
+                let mut builder = BlockData::new_from_geth_data_with_params(block.clone(), params)
+                    .new_circuit_input_builder();
+                builder
+                    .handle_block(&block.eth_block, &block.geth_traces)
+                    .map_err(|err| CircuitTestError::CannotHandleBlock(err.to_string()))?;
+                builder
+            }
+            None => BlockData::new_from_geth_data(block.clone())
+                .new_circuit_input_builder()
+                .handle_block(&block.eth_block, &block.geth_traces)
+                .map_err(|err| CircuitTestError::CannotHandleBlock(err.to_string()))?,
+        };
         // Build a witness block from trace result.
         let mut block = crate::witness::block_convert(&builder)
             .map_err(|err| CircuitTestError::CannotConvertBlock(err.to_string()))?;
@@ -6,33 +6,68 @@
 
 pub mod sign_verify;
 
+mod assignment;
+mod authorization;
 #[cfg(any(test, feature = "test-circuits"))]
 mod dev;
+mod error;
+mod sign_data;
 #[cfg(test)]
 mod test;
+mod validation;
 #[cfg(feature = "test-circuits")]
 pub use dev::TxCircuit as TestTxCircuit;
+pub use error::TxCircuitError;
+pub use sign_data::SignDataCache;
 
 use crate::{
-    table::{KeccakTable, TxFieldTag, TxTable},
-    util::{word::Word, Challenges, SubCircuit, SubCircuitConfig},
+    evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    table::{
+        AuthTable, BlockContextFieldTag, BlockTable, KeccakTable, LookupTable, Sha256Table,
+        Sha512Table, TxFieldTag, TxTable, UXTable,
+    },
+    util::{word::Word, Challenges, Expr, SubCircuit, SubCircuitConfig},
     witness,
 };
-use eth_types::{geth_types::Transaction, sign_types::SignData, Field};
+use authorization::{authorizations, is_valid_authorization};
+use bus_mapping::circuit_input_builder::keccak_inputs_sign_verify;
+use eth_types::{
+    evm_types::MAX_BLOBS_PER_BLOCK,
+    geth_types::{eip712_encode, Transaction},
+    rsa_types::{RsaPadding, RsaPublicKey},
+    sign_types::SignData,
+    Field, H256, U256,
+};
+use gadgets::{
+    is_zero::IsZeroChip,
+    less_than::LtChip,
+    util::{and, not},
+};
 use halo2_proofs::{
-    circuit::{AssignedCell, Layouter, Region, Value},
-    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed},
+    circuit::{Layouter, Value},
+    halo2curves::secp256r1::Secp256r1Affine,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Instance, VirtualCells},
+    poly::Rotation,
 };
 use itertools::Itertools;
 use log::error;
-use sign_verify::{AssignedSignatureVerify, SignVerifyChip, SignVerifyConfig};
-use std::{marker::PhantomData, ops::Deref};
+use num_bigint::BigUint;
+use rayon::prelude::*;
+use sign_data::{TxSignData, TxSignDatas};
+use sign_verify::{
+    AssignedSignatureVerify, AuthSignHashChip, AuthSignHashConfig, Ed25519SignVerifyChip,
+    Ed25519SignVerifyConfig, Eip712DigestChip, Eip712DigestConfig, RsaSignVerifyChip,
+    RsaSignVerifyConfig, SignVerifyChip, SignVerifyConfig,
+};
+use std::{iter, ops::Deref};
+use validation::{access_list_len, signed_txs_len};
 
 /// Number of static fields per tx: [nonce, gas, gas_price,
 /// caller_address, callee_address, is_create, value, call_data_length,
 /// call_data_gas_cost, tx_sign_hash].
 /// Note that call data bytes are laid out in the TxTable after all the static
-/// fields arranged by txs.
+/// fields arranged by txs, and are followed by the TxGasUsed, the access
+/// lists and the blob versioned hashes of every tx.
 pub(crate) const TX_LEN: usize = 10;
 
 /// Config for TxCircuit
@@ -42,8 +77,154 @@ pub struct TxCircuitConfig<F: Field> {
     tag: Column<Fixed>,
     index: Column<Advice>,
     value: Word<Column<Advice>>,
+    /// 1 on the all-zero first row of the tx table
+    q_first: Column<Fixed>,
+    /// 1 on the rows holding the static fields of a tx (all tags except Null
+    /// and CallData)
+    q_tx_fields: Column<Fixed>,
+    /// 1 on the first static field row (Nonce) of every tx
+    q_tx_first: Column<Fixed>,
+    /// 1 on the rows of the calldata section
+    q_calldata: Column<Fixed>,
+    /// 1 on the last row of the calldata section
+    q_calldata_last: Column<Fixed>,
+    /// 1 on the first row of every calldata chunk after the first one, which
+    /// is a copy of the last row of the previous chunk
+    q_calldata_carry: Column<Fixed>,
+    /// 1 on the CallDataLength row of every tx
+    q_calldata_length: Column<Fixed>,
+    /// 1 on the IsCreate row of every tx
+    q_is_create: Column<Fixed>,
+    /// 1 on the CallerAddress row of every tx
+    q_caller_address: Column<Fixed>,
+    /// 1 on the TxGasUsed rows, after the calldata section
+    q_gas_used: Column<Fixed>,
+    /// 1 on the AccessListLength rows, after the TxGasUsed rows
+    q_access_list_length: Column<Fixed>,
+    /// 1 on the AccessListStorageKeysLength rows, after the AccessListLength
+    /// rows
+    q_access_list_storage_keys_length: Column<Fixed>,
+    /// 1 on the rows of the access list address section
+    q_access_list_address: Column<Fixed>,
+    /// 1 on the last row of the access list address section
+    q_access_list_address_last: Column<Fixed>,
+    /// 1 on the rows of the access list storage key section
+    q_access_list_storage_key: Column<Fixed>,
+    /// 1 on the last row of the access list storage key section
+    q_access_list_storage_key_last: Column<Fixed>,
+    /// 1 on the rows of the section with the addresses of the access list
+    /// storage keys
+    q_access_list_storage_key_address: Column<Fixed>,
+    /// 1 on the BlobVersionedHashesLength rows, after the access list sections
+    q_blob_hashes_length: Column<Fixed>,
+    /// 1 on the rows of the blob versioned hash section
+    q_blob_hash: Column<Fixed>,
+    /// 1 on the last row of the blob versioned hash section
+    q_blob_hash_last: Column<Fixed>,
+    /// 1 on the IsDeposit rows, after the blob versioned hash section
+    q_is_deposit: Column<Fixed>,
+    /// 1 on the Mint rows, after the IsDeposit rows
+    q_mint: Column<Fixed>,
+    /// 1 on the MaxFeePerGas rows, after the Mint rows
+    q_fee_cap: Column<Fixed>,
+    /// 1 on the MaxPriorityFeePerGas rows, each after the MaxFeePerGas row of
+    /// its tx
+    q_tip_cap: Column<Fixed>,
+    /// 1 on the rows holding a u64: Nonce, Gas, CallDataLength,
+    /// CallDataGasCost, TxGasUsed, AccessListLength,
+    /// AccessListStorageKeysLength and BlobVersionedHashesLength
+    q_u64: Column<Fixed>,
+    /// 1 on the rows holding a u256: GasPrice, Value, AccessListStorageKey,
+    /// BlobVersionedHash, Mint, MaxFeePerGas and MaxPriorityFeePerGas
+    q_u256: Column<Fixed>,
+    /// 1 on the rows holding an address: CallerAddress, CalleeAddress,
+    /// AccessListAddress and AccessListStorageKeyAddress
+    q_address: Column<Fixed>,
+    /// On range checked rows: the little-endian u16 limbs of value.lo.  On the
+    /// block context row: the limbs of the remaining gas of the block.
+    value_lo_limbs: [Column<Advice>; 8],
+    /// On range checked rows: the little-endian u16 limbs of value.hi
+    value_hi_limbs: [Column<Advice>; 8],
+    /// On static field rows: the sum of the Gas fields of the txs up to the
+    /// row.  On the block context row: the sum of the Gas fields of all the
+    /// txs.
+    cumulative_gas: Column<Advice>,
+    /// The chain_id of the circuit, on the block context row
+    chain_id: Column<Advice>,
+    /// The gas limit of the block, on the block context row
+    gas_limit: Column<Advice>,
+    /// The base fee of the block, on the MaxFeePerGas rows
+    base_fee: Column<Advice>,
+    /// 1 on the block context row, whose chain_id and gas_limit are looked up
+    /// in the BlockTable
+    q_block_context: Column<Fixed>,
+    /// On CallData rows: 1 if the row holds the last calldata byte of a tx.  On
+    /// AccessListAddress and AccessListStorageKey rows: 1 if the row holds
+    /// the last address or storage key of the access list of a tx.  On
+    /// BlobVersionedHash rows: 1 if the row holds the last blob versioned hash
+    /// of a tx.
+    is_final: Column<Advice>,
+    /// On CallDataLength rows: 1 if the tx has non-empty calldata
+    has_calldata: Column<Advice>,
+    /// On AccessListLength rows: 1 if the tx has a non-empty access list.  On
+    /// AccessListStorageKeysLength rows: 1 if the access list of the tx has
+    /// storage keys
+    has_access_list: Column<Advice>,
+    /// On BlobVersionedHashesLength rows: 1 if the tx has blob versioned
+    /// hashes
+    has_blob_hashes: Column<Advice>,
+    /// On CallData rows: the gas cost of the calldata bytes of the tx up to
+    /// the row, 0 on unused rows
+    calldata_gas_cost: Column<Advice>,
+    /// 1 on the rows of padding txs (beyond the number of txs in the block) and
+    /// on the unused calldata, access list and blob versioned hash rows
+    is_padding: Column<Advice>,
+    /// On CallerAddress, Mint, MaxFeePerGas and MaxPriorityFeePerGas rows: 1 if
+    /// the tx is a deposit tx
+    is_deposit: Column<Advice>,
+    /// On CallerAddress rows: is_padding of the signature verification of the
+    /// tx slot, which is a padding one for deposit txs
+    signer_is_padding: Column<Advice>,
+    /// On CallerAddress rows: the address recovered by the signature
+    /// verification of the tx slot
+    signer_address: Word<Column<Advice>>,
+    tx_id_is_zero: IsZeroChip<F>,
+    tx_id_diff_is_zero: IsZeroChip<F>,
+    byte_is_zero: IsZeroChip<F>,
+    access_list_tx_id_is_zero: IsZeroChip<F>,
+    access_list_tx_id_diff_is_zero: IsZeroChip<F>,
+    storage_key_tx_id_is_zero: IsZeroChip<F>,
+    storage_key_tx_id_diff_is_zero: IsZeroChip<F>,
+    blob_hash_tx_id_is_zero: IsZeroChip<F>,
+    blob_hash_tx_id_diff_is_zero: IsZeroChip<F>,
+    /// On MaxFeePerGas rows: max_fee_per_gas < base_fee.  On
+    /// MaxPriorityFeePerGas rows: max_fee_per_gas < max_priority_fee_per_gas.
+    fee_caps_lt: LtChip<F, 16>,
     sign_verify: SignVerifyConfig,
-    _marker: PhantomData<F>,
+    /// SignVerifyChip config for the txs signed over secp256r1, if supported
+    sign_verify_secp256r1: Option<SignVerifyConfig<Secp256r1Affine>>,
+    /// RsaSignVerifyChip config for the txs signed with RSA-2048, if supported
+    rsa_sign_verify: Option<RsaSignVerifyConfig>,
+    /// Ed25519SignVerifyChip config for the txs signed with Ed25519, if
+    /// supported
+    ed25519_sign_verify: Option<Ed25519SignVerifyConfig>,
+    /// Eip712DigestChip config for the txs signed as EIP-712 typed data, if
+    /// supported
+    eip712_digest: Option<Eip712DigestConfig>,
+    /// AuthTable of the authorizations of the set-code txs, if supported
+    auth_table: Option<AuthTable>,
+    /// SignVerifyChip config for the authorizations of the set-code txs, if
+    /// supported
+    auth_sign_verify: Option<SignVerifyConfig>,
+    /// AuthSignHashChip config binding the authorizations to their signed
+    /// messages, if set-code txs are supported
+    auth_sign_hash: Option<AuthSignHashConfig>,
+    /// Instance column exposing the TxSignHash (lo, hi) and the signature
+    /// recovery id of every tx slot
+    tx_sign_hashes: Column<Instance>,
+    // External tables
+    u8_table: UXTable<8>,
+    u16_table: UXTable<16>,
 }
 
 /// Circuit configuration arguments
@@ -52,6 +233,32 @@ pub struct TxCircuitConfigArgs<F: Field> {
     pub tx_table: TxTable,
     /// KeccakTable
     pub keccak_table: KeccakTable,
+    /// BlockTable, holding the chain_id of the block context
+    pub block_table: BlockTable,
+    /// u8 lookup table
+    pub u8_table: UXTable<8>,
+    /// u16 lookup table
+    pub u16_table: UXTable<16>,
+    /// Number of lanes of the SignVerifyChip
+    pub sign_verify_lanes: usize,
+    /// Support txs signed over secp256r1 (P-256), with a one lane SignVerifyChip
+    pub sign_verify_secp256r1: bool,
+    /// Sha256Table of the digests of the txs signed with RSA-2048 PKCS#1 v1.5.
+    /// When given, the TxCircuit supports such txs with a RsaSignVerifyChip.
+    pub sha256_table: Option<Sha256Table>,
+    /// Sha512Table of the challenges of the txs signed with Ed25519.  When
+    /// given, the TxCircuit supports such txs with an Ed25519SignVerifyChip.
+    /// There is no SHA-512 circuit, so the table is only sound when the caller
+    /// proves it; the SuperCircuit gives none and rejects such txs.
+    pub sha512_table: Option<Sha512Table>,
+    /// AuthTable of the authorizations of the EIP-7702 set-code txs.  When
+    /// given, the TxCircuit supports such txs, and verifies their
+    /// authorizations with a SignVerifyChip.
+    pub auth_table: Option<AuthTable>,
+    /// Support txs signed as EIP-712 typed data, with an Eip712DigestChip
+    pub eip712_digest: bool,
+    /// Support the OP-stack deposit txs.  When false, no tx is a deposit.
+    pub deposits: bool,
     /// Challenges
     pub challenges: Challenges<Expression<F>>,
 }
@@ -65,6 +272,16 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
         Self::ConfigArgs {
             tx_table,
             keccak_table,
+            block_table,
+            u8_table,
+            u16_table,
+            sign_verify_lanes,
+            sign_verify_secp256r1,
+            sha256_table,
+            sha512_table,
+            auth_table,
+            eip712_digest,
+            deposits,
             challenges,
         }: Self::ConfigArgs,
     ) -> Self {
@@ -75,64 +292,1528 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
         meta.enable_equality(value.lo());
         meta.enable_equality(value.hi());
 
-        let sign_verify = SignVerifyConfig::new(meta, keccak_table, challenges);
+        let q_first = meta.fixed_column();
+        let q_tx_fields = meta.fixed_column();
+        let q_tx_first = meta.fixed_column();
+        let q_calldata = meta.fixed_column();
+        let q_calldata_last = meta.fixed_column();
+        let q_calldata_carry = meta.fixed_column();
+        let q_calldata_length = meta.fixed_column();
+        let q_is_create = meta.fixed_column();
+        let q_caller_address = meta.fixed_column();
+        let q_gas_used = meta.fixed_column();
+        let q_access_list_length = meta.fixed_column();
+        let q_access_list_storage_keys_length = meta.fixed_column();
+        let q_access_list_address = meta.fixed_column();
+        let q_access_list_address_last = meta.fixed_column();
+        let q_access_list_storage_key = meta.fixed_column();
+        let q_access_list_storage_key_last = meta.fixed_column();
+        let q_access_list_storage_key_address = meta.fixed_column();
+        let q_blob_hashes_length = meta.fixed_column();
+        let q_blob_hash = meta.fixed_column();
+        let q_blob_hash_last = meta.fixed_column();
+        let q_is_deposit = meta.fixed_column();
+        let q_mint = meta.fixed_column();
+        let q_fee_cap = meta.fixed_column();
+        let q_tip_cap = meta.fixed_column();
+        let q_u64 = meta.fixed_column();
+        let q_u256 = meta.fixed_column();
+        let q_address = meta.fixed_column();
+        let value_lo_limbs = [(); 8].map(|_| meta.advice_column());
+        let value_hi_limbs = [(); 8].map(|_| meta.advice_column());
+        let cumulative_gas = meta.advice_column();
+        meta.enable_equality(cumulative_gas);
+        let chain_id = meta.advice_column();
+        meta.enable_equality(chain_id);
+        let gas_limit = meta.advice_column();
+        let base_fee = meta.advice_column();
+        let q_block_context = meta.fixed_column();
+        let is_final = meta.advice_column();
+        let has_calldata = meta.advice_column();
+        let has_access_list = meta.advice_column();
+        let has_blob_hashes = meta.advice_column();
+        let is_padding = meta.advice_column();
+        meta.enable_equality(is_padding);
+        let is_deposit = meta.advice_column();
+        meta.enable_equality(is_deposit);
+        let signer_is_padding = meta.advice_column();
+        meta.enable_equality(signer_is_padding);
+        let signer_address = Word::new([meta.advice_column(), meta.advice_column()]);
+        meta.enable_equality(signer_address.lo());
+        meta.enable_equality(signer_address.hi());
+        // The last calldata row of a chunk is copied to the first row of the next chunk.
+        meta.enable_equality(tx_id);
+        meta.enable_equality(index);
+        meta.enable_equality(is_final);
+        let calldata_gas_cost = meta.advice_column();
+        meta.enable_equality(calldata_gas_cost);
+        let tx_id_inv = meta.advice_column();
+        let tx_id_diff_inv = meta.advice_column();
+        let byte_inv = meta.advice_column();
+        let access_list_tx_id_inv = meta.advice_column();
+        let access_list_tx_id_diff_inv = meta.advice_column();
+
+        tx_table.annotate_columns(meta);
+        u8_table.annotate_columns(meta);
+        u16_table.annotate_columns(meta);
+
+        // The tag column is fixed, so the TX_LEN layout of the static fields and the positions of
+        // the calldata and access list sections are decided by the verifier.  The gates below bind
+        // the advice columns (tx_id, index) to that layout so that rows can't be permuted
+        // by the prover.
+        //
+        // Layout:
+        // | q_first | q_tx_fields | q_tx_first | q_calldata | tx_id |         tag          | index
+        // | | ------- | ----------- | ---------- | ---------- | ----- |
+        // -------------------- | ----- | |    1    |      0      |      0     |      0
+        // |   0   |         Null         |   0   | |    0    |      1      |      1     |
+        // 0     |   1   |        Nonce         |   0   | |    0    |      1      |      0
+        // |      0     |   1   |         Gas          |   0   | |   ...   |     ...     |
+        // ...    |     ...    |  ...  |         ...          |  ...  | |    0    |      1
+        // |      0     |      0     |   1   |      TxSignHash      |   0   | |    0    |
+        // 1      |      1     |      0     |   2   |        Nonce         |   0   | |   ...
+        // |     ...     |     ...    |     ...    |  ...  |         ...          |  ...  |
+        // |    0    |      0      |      0     |      1     |   1   |       CallData       |   0
+        // | |    0    |      0      |      0     |      1     |   1   |       CallData
+        // |   1   | |    0    |      0      |      0     |      1     |   3   |
+        // CallData       |   0   | |   ...   |     ...     |     ...    |     ...    |  ...
+        // |         ...          |  ...  | |    0    |      0      |      0     |      1
+        // |   0   |       CallData       |   0   | |    0    |      0      |      0     |
+        // 0     |   1   |      TxGasUsed       |   0   | |   ...   |     ...     |     ...
+        // |     ...    |  ...  |         ...          |  ...  | |    0    |      0      |
+        // 0     |      0     |   1   |   AccessListLength   |   0   | |   ...   |     ...
+        // |     ...    |     ...    |  ...  |         ...          |  ...  | |    0    |
+        // 0      |      0     |      0     |   1   |  AccessListAddress   |   0   | |    0
+        // |      0      |      0     |      0     |   1   |  AccessListAddress   |   1   |
+        // |   ...   |     ...     |     ...    |     ...    |  ...  |         ...          |  ...
+        // | |    0    |      0      |      0     |      0     |   1   |
+        // AccessListStorageKey |   1   | |   ...   |     ...     |     ...    |     ...
+        // |  ...  |         ...          |  ...  |
+        //
+        // The calldata section can be split into chunks laid out in their own regions, so that
+        // large calldata doesn't need a single huge region.  The first row of every chunk after
+        // the first one is a carry row (q_calldata_carry = 1): a copy of the last row of the
+        // previous chunk, bound with copy constraints.  The gates between a calldata row and the
+        // previous one are disabled on carry rows, and are enabled between the carry row and
+        // the next one, so the constraints hold across chunks.  A carry row is a duplicate entry
+        // of the tx table, which doesn't change the lookups into it.
+
+        // Enables the gates between a calldata row and the previous calldata row of its chunk.
+        let q_calldata_transition = |meta: &mut VirtualCells<F>| {
+            and::expr([
+                meta.query_fixed(q_calldata, Rotation::cur()),
+                meta.query_fixed(q_calldata, Rotation::prev()),
+                not::expr(meta.query_fixed(q_calldata_carry, Rotation::cur())),
+            ])
+        };
+
+        let tx_id_is_zero = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_fixed(q_calldata, Rotation::cur()),
+            |meta| meta.query_advice(tx_id, Rotation::cur()),
+            tx_id_inv,
+        );
+        let tx_id_diff_is_zero = IsZeroChip::configure(
+            meta,
+            q_calldata_transition,
+            |meta| {
+                meta.query_advice(tx_id, Rotation::cur())
+                    - meta.query_advice(tx_id, Rotation::prev())
+            },
+            tx_id_diff_inv,
+        );
+        let byte_is_zero = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_fixed(q_calldata, Rotation::cur()),
+            |meta| meta.query_advice(value.lo(), Rotation::cur()),
+            byte_inv,
+        );
+        // Enables the gates between an access list address row and the previous one.
+        let q_access_list_transition = |meta: &mut VirtualCells<F>| {
+            and::expr([
+                meta.query_fixed(q_access_list_address, Rotation::cur()),
+                meta.query_fixed(q_access_list_address, Rotation::prev()),
+            ])
+        };
+        let access_list_tx_id_is_zero = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_fixed(q_access_list_address, Rotation::cur()),
+            |meta| meta.query_advice(tx_id, Rotation::cur()),
+            access_list_tx_id_inv,
+        );
+        let access_list_tx_id_diff_is_zero = IsZeroChip::configure(
+            meta,
+            q_access_list_transition,
+            |meta| {
+                meta.query_advice(tx_id, Rotation::cur())
+                    - meta.query_advice(tx_id, Rotation::prev())
+            },
+            access_list_tx_id_diff_inv,
+        );
+        // Enables the gates between an access list storage key row and the previous one.
+        let q_storage_key_transition = |meta: &mut VirtualCells<F>| {
+            and::expr([
+                meta.query_fixed(q_access_list_storage_key, Rotation::cur()),
+                meta.query_fixed(q_access_list_storage_key, Rotation::prev()),
+            ])
+        };
+        // The inverse columns are shared with the access list address section, which doesn't
+        // overlap with the storage key section.
+        let storage_key_tx_id_is_zero = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_fixed(q_access_list_storage_key, Rotation::cur()),
+            |meta| meta.query_advice(tx_id, Rotation::cur()),
+            access_list_tx_id_inv,
+        );
+        let storage_key_tx_id_diff_is_zero = IsZeroChip::configure(
+            meta,
+            q_storage_key_transition,
+            |meta| {
+                meta.query_advice(tx_id, Rotation::cur())
+                    - meta.query_advice(tx_id, Rotation::prev())
+            },
+            access_list_tx_id_diff_inv,
+        );
+        // Enables the gates between a blob versioned hash row and the previous one.
+        let q_blob_hash_transition = |meta: &mut VirtualCells<F>| {
+            and::expr([
+                meta.query_fixed(q_blob_hash, Rotation::cur()),
+                meta.query_fixed(q_blob_hash, Rotation::prev()),
+            ])
+        };
+        // The inverse columns are shared with the access list address section, which doesn't
+        // overlap with the blob versioned hash section.
+        let blob_hash_tx_id_is_zero = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_fixed(q_blob_hash, Rotation::cur()),
+            |meta| meta.query_advice(tx_id, Rotation::cur()),
+            access_list_tx_id_inv,
+        );
+        let blob_hash_tx_id_diff_is_zero = IsZeroChip::configure(
+            meta,
+            q_blob_hash_transition,
+            |meta| {
+                meta.query_advice(tx_id, Rotation::cur())
+                    - meta.query_advice(tx_id, Rotation::prev())
+            },
+            access_list_tx_id_diff_inv,
+        );
+        // EIP-2028: 4 gas per zero byte and 16 gas per non-zero byte
+        let byte_gas_cost = |byte_is_zero: Expression<F>| 16.expr() - 12.expr() * byte_is_zero;
+
+        meta.create_gate("tx table first row", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_zero("tx_id == 0", meta.query_advice(tx_id, Rotation::cur()));
+            cb.require_zero("index == 0", meta.query_advice(index, Rotation::cur()));
+            cb.require_zero_word("value == 0", value.query_advice(meta, Rotation::cur()));
+            cb.require_zero(
+                "is_padding == 0",
+                meta.query_advice(is_padding, Rotation::cur()),
+            );
+            cb.require_zero(
+                "cumulative_gas == 0",
+                meta.query_advice(cumulative_gas, Rotation::cur()),
+            );
+
+            cb.gate(meta.query_fixed(q_first, Rotation::cur()))
+        });
+
+        meta.create_gate("tx static fields", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_zero("index == 0", meta.query_advice(index, Rotation::cur()));
+            // tx_id increments by one on the Nonce row and stays the same on the following
+            // TX_LEN - 1 rows.
+            cb.require_equal(
+                "tx_id == tx_id_prev + q_tx_first",
+                meta.query_advice(tx_id, Rotation::cur()),
+                meta.query_advice(tx_id, Rotation::prev())
+                    + meta.query_fixed(q_tx_first, Rotation::cur()),
+            );
+            // The Gas row is the one following the Nonce row.
+            cb.require_equal(
+                "cumulative_gas == cumulative_gas_prev + gas on the Gas row",
+                meta.query_advice(cumulative_gas, Rotation::cur()),
+                meta.query_advice(cumulative_gas, Rotation::prev())
+                    + meta.query_fixed(q_tx_first, Rotation::prev())
+                        * meta.query_advice(value.lo(), Rotation::cur()),
+            );
+
+            cb.gate(meta.query_fixed(q_tx_fields, Rotation::cur()))
+        });
+
+        // Padding txs fill the slots after the txs of the block, and all their fields are zero.
+        // The only exception is TxSignHash, which holds the message hash of the dummy signature
+        // verified by the SignVerifyChip for the slot.
+        meta.create_gate("tx padding static fields", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let q_tx_first = meta.query_fixed(q_tx_first, Rotation::cur());
+            let is_padding_cur = meta.query_advice(is_padding, Rotation::cur());
+            let is_padding_prev = meta.query_advice(is_padding, Rotation::prev());
+
+            cb.require_boolean("is_padding is boolean", is_padding_cur.expr());
+            cb.require_zero(
+                "is_padding is the same for all the fields of a tx",
+                not::expr(q_tx_first.expr()) * (is_padding_cur.expr() - is_padding_prev.expr()),
+            );
+            cb.require_zero(
+                "a padding tx is only followed by padding txs",
+                q_tx_first * is_padding_prev * not::expr(is_padding_cur.expr()),
+            );
+            // (tag - TxSignHash) is a non-zero constant on all the other static field rows.
+            let (value_lo, value_hi) = value.query_advice(meta, Rotation::cur()).into_lo_hi();
+            let not_sign_hash =
+                meta.query_fixed(tag, Rotation::cur()) - TxFieldTag::TxSignHash.expr();
+            cb.require_zero(
+                "padding tx value_lo == 0",
+                is_padding_cur.expr() * not_sign_hash.expr() * value_lo,
+            );
+            cb.require_zero(
+                "padding tx value_hi == 0",
+                is_padding_cur * not_sign_hash * value_hi,
+            );
+
+            cb.gate(meta.query_fixed(q_tx_fields, Rotation::cur()))
+        });
+
+        // The static fields and the fields after the calldata are range checked by their u16
+        // limbs: u64 fields fit in the first four limbs of value.lo, and the limbs of u256 and
+        // address fields bound the lo and hi halves to 128 and 32 bits.  IsCreate and TxSignHash
+        // are constrained by other gates.  On the block context row, the limbs range check the
+        // remaining gas of the block.
+        let q_range = |meta: &mut VirtualCells<F>| {
+            meta.query_fixed(q_u64, Rotation::cur())
+                + meta.query_fixed(q_u256, Rotation::cur())
+                + meta.query_fixed(q_address, Rotation::cur())
+        };
+        for limb in value_lo_limbs.into_iter().chain(value_hi_limbs) {
+            meta.lookup_any("tx static field limb fits into u16", |meta| {
+                let enable = q_range(meta) + meta.query_fixed(q_block_context, Rotation::cur());
+                let limb = meta.query_advice(limb, Rotation::cur());
+
+                vec![(enable * limb, u16_table.table_exprs(meta)[0].clone())]
+            });
+        }
+
+        meta.create_gate("tx static field ranges", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let q_u64 = meta.query_fixed(q_u64, Rotation::cur());
+            let q_u256 = meta.query_fixed(q_u256, Rotation::cur());
+            let q_address = meta.query_fixed(q_address, Rotation::cur());
+            let (value_lo, value_hi) = value.query_advice(meta, Rotation::cur()).into_lo_hi();
+            let lo_limbs = value_lo_limbs.map(|limb| meta.query_advice(limb, Rotation::cur()));
+            let hi_limbs = value_hi_limbs.map(|limb| meta.query_advice(limb, Rotation::cur()));
+
+            cb.condition(q_u64.expr() + q_u256.expr() + q_address.expr(), |cb| {
+                cb.require_equal(
+                    "value_lo == value_lo_limbs",
+                    value_lo,
+                    value_from_limbs(&lo_limbs),
+                );
+            });
+            cb.condition(q_u64, |cb| {
+                cb.require_zero("u64 value_hi == 0", value_hi.expr());
+                for limb in &lo_limbs[4..] {
+                    cb.require_zero("u64 value_lo < 2^64", limb.expr());
+                }
+            });
+            cb.condition(q_u256, |cb| {
+                cb.require_equal(
+                    "value_hi == value_hi_limbs",
+                    value_hi.expr(),
+                    value_from_limbs(&hi_limbs),
+                );
+            });
+            cb.condition(q_address, |cb| {
+                cb.require_equal(
+                    "address value_hi == value_hi_limbs[0..2]",
+                    value_hi,
+                    value_from_limbs(&hi_limbs[..2]),
+                );
+            });
+
+            cb.gate(q_range(meta))
+        });
+
+        // The gas used by every tx is set by the EVM circuit at the end of the tx.  The tx_id and
+        // is_padding of a TxGasUsed row are copied from the Nonce row of its tx.
+        meta.create_gate("tx gas used", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_zero("index == 0", meta.query_advice(index, Rotation::cur()));
+            cb.condition(meta.query_advice(is_padding, Rotation::cur()), |cb| {
+                cb.require_zero_word(
+                    "padding tx gas used == 0",
+                    value.query_advice(meta, Rotation::cur()),
+                );
+            });
+
+            cb.gate(meta.query_fixed(q_gas_used, Rotation::cur()))
+        });
+
+        // The AccessListLength and AccessListStorageKeysLength rows follow the TxGasUsed rows, and
+        // their tx_id and is_padding are copied from the Nonce row of every tx as well.  They are
+        // followed by the address, the storage key and the storage key address sections, of
+        // max_access_list rows each, whose unused rows are at the end and have tx_id == 0.  The
+        // addresses of a tx are sorted by index from 0 to length - 1 like its calldata bytes, so
+        // that the copy circuit can read them in order to pre-warm the access list.
+        meta.create_gate("tx access list length", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let has_access_list = meta.query_advice(has_access_list, Rotation::cur());
+            cb.require_zero("index == 0", meta.query_advice(index, Rotation::cur()));
+            cb.require_boolean("has_access_list is boolean", has_access_list.expr());
+            // When has_access_list == 1, the lookup below guarantees that length != 0.
+            cb.require_zero(
+                "has_access_list == 0 => length == 0",
+                not::expr(has_access_list.expr()) * meta.query_advice(value.lo(), Rotation::cur()),
+            );
+            cb.require_zero(
+                "padding txs have no access list",
+                meta.query_advice(is_padding, Rotation::cur()) * has_access_list,
+            );
+
+            cb.gate(meta.query_fixed(q_access_list_length, Rotation::cur()))
+        });
+
+        // A tx with a non-empty access list has an address row with index == length - 1.  As for
+        // the calldata, with the index increment constraints and the `is_final` lookup below,
+        // this means that the address rows of the tx are exactly the indexes 0..length.
+        meta.lookup_any(
+            "tx access list length > 0 => the last address exists",
+            |meta| {
+                let enable = and::expr([
+                    meta.query_fixed(q_access_list_length, Rotation::cur()),
+                    meta.query_advice(has_access_list, Rotation::cur()),
+                ]);
+                let input = [
+                    meta.query_advice(tx_id, Rotation::cur()),
+                    TxFieldTag::AccessListAddress.expr(),
+                    meta.query_advice(value.lo(), Rotation::cur()) - 1.expr(),
+                ];
+                let table = [
+                    meta.query_advice(tx_id, Rotation::cur()),
+                    meta.query_fixed(tag, Rotation::cur()),
+                    meta.query_advice(index, Rotation::cur()),
+                ];
+
+                input
+                    .into_iter()
+                    .zip(table)
+                    .map(|(input, table)| (enable.expr() * input, table))
+                    .collect()
+            },
+        );
+
+        meta.create_gate("tx access list address rows", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let is_padding_cur = meta.query_advice(is_padding, Rotation::cur());
+            let is_final_cur = meta.query_advice(is_final, Rotation::cur());
+            cb.require_equal(
+                "is_padding == (tx_id == 0)",
+                is_padding_cur.expr(),
+                access_list_tx_id_is_zero.expr(),
+            );
+            cb.condition(is_padding_cur.expr(), |cb| {
+                cb.require_zero("index == 0", meta.query_advice(index, Rotation::cur()));
+                cb.require_zero_word("value == 0", value.query_advice(meta, Rotation::cur()));
+            });
+            cb.require_boolean("is_final is boolean", is_final_cur.expr());
+            cb.require_zero(
+                "is_final == 0 on unused rows",
+                is_padding_cur.expr() * is_final_cur.expr(),
+            );
+            cb.require_zero(
+                "is_final == 1 on the last row of the address section",
+                meta.query_fixed(q_access_list_address_last, Rotation::cur())
+                    * not::expr(is_padding_cur)
+                    * not::expr(is_final_cur),
+            );
+
+            cb.gate(meta.query_fixed(q_access_list_address, Rotation::cur()))
+        });
+
+        meta.create_gate("tx access list first address row", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_zero("index == 0", meta.query_advice(index, Rotation::cur()));
+
+            cb.gate(and::expr([
+                meta.query_fixed(q_access_list_address, Rotation::cur()),
+                not::expr(meta.query_fixed(q_access_list_address, Rotation::prev())),
+            ]))
+        });
+
+        meta.create_gate("tx access list address transition", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let index_cur = meta.query_advice(index, Rotation::cur());
+            let index_prev = meta.query_advice(index, Rotation::prev());
+            let is_same_tx = access_list_tx_id_diff_is_zero.expr();
+
+            cb.require_zero(
+                "is_padding_prev => is_padding",
+                meta.query_advice(is_padding, Rotation::prev())
+                    * not::expr(meta.query_advice(is_padding, Rotation::cur())),
+            );
+            // if tx_id != 0 and tx_id == tx_id_prev then index == index_prev + 1
+            cb.condition(not::expr(access_list_tx_id_is_zero.expr()), |cb| {
+                cb.require_zero(
+                    "index == index_prev + 1 within the same tx",
+                    is_same_tx.expr() * (index_cur.expr() - index_prev - 1.expr()),
+                );
+            });
+            // if tx_id != tx_id_prev then index == 0
+            cb.require_zero(
+                "index == 0 on the first address row of a tx",
+                not::expr(is_same_tx.expr()) * index_cur,
+            );
+            // The IsZero gate of tx_id is enabled on the previous row, so this is the inverse.
+            let is_tx_id_prev_nonzero = meta.query_advice(tx_id, Rotation::prev())
+                * meta.query_advice(access_list_tx_id_inv, Rotation::prev());
+            // if tx_id_prev != 0 then is_final_prev == (tx_id != tx_id_prev)
+            cb.condition(is_tx_id_prev_nonzero, |cb| {
+                cb.require_equal(
+                    "is_final_prev == (tx_id != tx_id_prev)",
+                    meta.query_advice(is_final, Rotation::prev()),
+                    not::expr(is_same_tx),
+                );
+            });
+
+            cb.gate(q_access_list_transition(meta))
+        });
+
+        // Address rows are sorted by tx_id: tx_id - tx_id_prev is in [0, 2^16) unless this is an
+        // unused row (tx_id == 0).
+        meta.lookup_any("tx access list tx_id is non-decreasing", |meta| {
+            let enable =
+                q_access_list_transition(meta) * not::expr(access_list_tx_id_is_zero.expr());
+            let tx_id_diff = meta.query_advice(tx_id, Rotation::cur())
+                - meta.query_advice(tx_id, Rotation::prev());
+
+            vec![(enable * tx_id_diff, u16_table.table_exprs(meta)[0].clone())]
+        });
+
+        // The last address of a tx has index == length - 1.
+        meta.lookup_any("tx access list is_final => index + 1 == length", |meta| {
+            let enable = and::expr([
+                meta.query_fixed(q_access_list_address, Rotation::cur()),
+                meta.query_advice(is_final, Rotation::cur()),
+            ]);
+            let input = [
+                meta.query_advice(tx_id, Rotation::cur()),
+                TxFieldTag::AccessListLength.expr(),
+                0.expr(),
+                meta.query_advice(index, Rotation::cur()) + 1.expr(),
+            ];
+            let table = [
+                meta.query_advice(tx_id, Rotation::cur()),
+                meta.query_fixed(tag, Rotation::cur()),
+                meta.query_advice(index, Rotation::cur()),
+                meta.query_advice(value.lo(), Rotation::cur()),
+            ];
+
+            input
+                .into_iter()
+                .zip(table)
+                .map(|(input, table)| (enable.expr() * input, table))
+                .collect()
+        });
+
+        // The storage keys of a tx are sorted by index from 0 to length - 1 like its addresses, in
+        // the order of the entries of its access list.  The address of every storage key is in
+        // the row at the same offset of the storage key address section, which is bound to the
+        // storage key section with copy constraints on tx_id, index and is_padding.
+        meta.create_gate("tx access list storage keys length", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let has_storage_keys = meta.query_advice(has_access_list, Rotation::cur());
+            cb.require_zero("index == 0", meta.query_advice(index, Rotation::cur()));
+            cb.require_boolean("has_storage_keys is boolean", has_storage_keys.expr());
+            // When has_storage_keys == 1, the lookup below guarantees that length != 0.
+            cb.require_zero(
+                "has_storage_keys == 0 => length == 0",
+                not::expr(has_storage_keys.expr()) * meta.query_advice(value.lo(), Rotation::cur()),
+            );
+            cb.require_zero(
+                "padding txs have no storage keys",
+                meta.query_advice(is_padding, Rotation::cur()) * has_storage_keys,
+            );
+
+            cb.gate(meta.query_fixed(q_access_list_storage_keys_length, Rotation::cur()))
+        });
+
+        meta.lookup_any(
+            "tx access list storage keys length > 0 => the last storage key exists",
+            |meta| {
+                let enable = and::expr([
+                    meta.query_fixed(q_access_list_storage_keys_length, Rotation::cur()),
+                    meta.query_advice(has_access_list, Rotation::cur()),
+                ]);
+                let input = [
+                    meta.query_advice(tx_id, Rotation::cur()),
+                    TxFieldTag::AccessListStorageKey.expr(),
+                    meta.query_advice(value.lo(), Rotation::cur()) - 1.expr(),
+                ];
+                let table = [
+                    meta.query_advice(tx_id, Rotation::cur()),
+                    meta.query_fixed(tag, Rotation::cur()),
+                    meta.query_advice(index, Rotation::cur()),
+                ];
+
+                input
+                    .into_iter()
+                    .zip(table)
+                    .map(|(input, table)| (enable.expr() * input, table))
+                    .collect()
+            },
+        );
+
+        meta.create_gate("tx access list storage key rows", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let is_padding_cur = meta.query_advice(is_padding, Rotation::cur());
+            let is_final_cur = meta.query_advice(is_final, Rotation::cur());
+            cb.require_equal(
+                "is_padding == (tx_id == 0)",
+                is_padding_cur.expr(),
+                storage_key_tx_id_is_zero.expr(),
+            );
+            cb.condition(is_padding_cur.expr(), |cb| {
+                cb.require_zero("index == 0", meta.query_advice(index, Rotation::cur()));
+                cb.require_zero_word("value == 0", value.query_advice(meta, Rotation::cur()));
+            });
+            cb.require_boolean("is_final is boolean", is_final_cur.expr());
+            cb.require_zero(
+                "is_final == 0 on unused rows",
+                is_padding_cur.expr() * is_final_cur.expr(),
+            );
+            cb.require_zero(
+                "is_final == 1 on the last row of the storage key section",
+                meta.query_fixed(q_access_list_storage_key_last, Rotation::cur())
+                    * not::expr(is_padding_cur)
+                    * not::expr(is_final_cur),
+            );
+
+            cb.gate(meta.query_fixed(q_access_list_storage_key, Rotation::cur()))
+        });
+
+        meta.create_gate("tx access list first storage key row", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_zero("index == 0", meta.query_advice(index, Rotation::cur()));
+
+            cb.gate(and::expr([
+                meta.query_fixed(q_access_list_storage_key, Rotation::cur()),
+                not::expr(meta.query_fixed(q_access_list_storage_key, Rotation::prev())),
+            ]))
+        });
+
+        meta.create_gate("tx access list storage key transition", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let index_cur = meta.query_advice(index, Rotation::cur());
+            let index_prev = meta.query_advice(index, Rotation::prev());
+            let is_same_tx = storage_key_tx_id_diff_is_zero.expr();
+
+            cb.require_zero(
+                "is_padding_prev => is_padding",
+                meta.query_advice(is_padding, Rotation::prev())
+                    * not::expr(meta.query_advice(is_padding, Rotation::cur())),
+            );
+            cb.condition(not::expr(storage_key_tx_id_is_zero.expr()), |cb| {
+                cb.require_zero(
+                    "index == index_prev + 1 within the same tx",
+                    is_same_tx.expr() * (index_cur.expr() - index_prev - 1.expr()),
+                );
+            });
+            cb.require_zero(
+                "index == 0 on the first storage key row of a tx",
+                not::expr(is_same_tx.expr()) * index_cur,
+            );
+            let is_tx_id_prev_nonzero = meta.query_advice(tx_id, Rotation::prev())
+                * meta.query_advice(access_list_tx_id_inv, Rotation::prev());
+            cb.condition(is_tx_id_prev_nonzero, |cb| {
+                cb.require_equal(
+                    "is_final_prev == (tx_id != tx_id_prev)",
+                    meta.query_advice(is_final, Rotation::prev()),
+                    not::expr(is_same_tx),
+                );
+            });
+
+            cb.gate(q_storage_key_transition(meta))
+        });
+
+        meta.lookup_any(
+            "tx access list storage key tx_id is non-decreasing",
+            |meta| {
+                let enable =
+                    q_storage_key_transition(meta) * not::expr(storage_key_tx_id_is_zero.expr());
+                let tx_id_diff = meta.query_advice(tx_id, Rotation::cur())
+                    - meta.query_advice(tx_id, Rotation::prev());
+
+                vec![(enable * tx_id_diff, u16_table.table_exprs(meta)[0].clone())]
+            },
+        );
+
+        meta.lookup_any(
+            "tx access list storage key is_final => index + 1 == length",
+            |meta| {
+                let enable = and::expr([
+                    meta.query_fixed(q_access_list_storage_key, Rotation::cur()),
+                    meta.query_advice(is_final, Rotation::cur()),
+                ]);
+                let input = [
+                    meta.query_advice(tx_id, Rotation::cur()),
+                    TxFieldTag::AccessListStorageKeysLength.expr(),
+                    0.expr(),
+                    meta.query_advice(index, Rotation::cur()) + 1.expr(),
+                ];
+                let table = [
+                    meta.query_advice(tx_id, Rotation::cur()),
+                    meta.query_fixed(tag, Rotation::cur()),
+                    meta.query_advice(index, Rotation::cur()),
+                    meta.query_advice(value.lo(), Rotation::cur()),
+                ];
+
+                input
+                    .into_iter()
+                    .zip(table)
+                    .map(|(input, table)| (enable.expr() * input, table))
+                    .collect()
+            },
+        );
+
+        meta.create_gate("tx access list storage key address rows", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.condition(meta.query_advice(is_padding, Rotation::cur()), |cb| {
+                cb.require_zero_word("value == 0", value.query_advice(meta, Rotation::cur()));
+            });
+
+            cb.gate(meta.query_fixed(q_access_list_storage_key_address, Rotation::cur()))
+        });
+
+        // The address of a storage key is one of the addresses of the access list of its tx.
+        meta.lookup_any(
+            "tx access list storage key address => the address exists",
+            |meta| {
+                let enable = and::expr([
+                    meta.query_fixed(q_access_list_storage_key_address, Rotation::cur()),
+                    not::expr(meta.query_advice(is_padding, Rotation::cur())),
+                ]);
+                let input = [
+                    meta.query_advice(tx_id, Rotation::cur()),
+                    TxFieldTag::AccessListAddress.expr(),
+                    meta.query_advice(value.lo(), Rotation::cur()),
+                    meta.query_advice(value.hi(), Rotation::cur()),
+                ];
+                let table = [
+                    meta.query_advice(tx_id, Rotation::cur()),
+                    meta.query_fixed(tag, Rotation::cur()),
+                    meta.query_advice(value.lo(), Rotation::cur()),
+                    meta.query_advice(value.hi(), Rotation::cur()),
+                ];
+
+                input
+                    .into_iter()
+                    .zip(table)
+                    .map(|(input, table)| (enable.expr() * input, table))
+                    .collect()
+            },
+        );
+
+        // The BlobVersionedHashesLength rows follow the access list sections, and their tx_id and
+        // is_padding are copied from the Nonce row of every tx.  They are followed by the blob
+        // versioned hash section of MAX_BLOBS_PER_BLOCK rows, whose unused rows are at the end
+        // and have tx_id == 0.  The hashes of a tx are sorted by index from 0 to length - 1 like
+        // the access list addresses, so that the BLOBHASH opcode can look them up (EIP-4844).
+        meta.create_gate("tx blob hashes length", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let has_blob_hashes = meta.query_advice(has_blob_hashes, Rotation::cur());
+            cb.require_zero("index == 0", meta.query_advice(index, Rotation::cur()));
+            cb.require_boolean("has_blob_hashes is boolean", has_blob_hashes.expr());
+            // When has_blob_hashes == 1, the lookup below guarantees that length != 0.
+            cb.require_zero(
+                "has_blob_hashes == 0 => length == 0",
+                not::expr(has_blob_hashes.expr()) * meta.query_advice(value.lo(), Rotation::cur()),
+            );
+            cb.require_zero(
+                "padding txs have no blob hashes",
+                meta.query_advice(is_padding, Rotation::cur()) * has_blob_hashes,
+            );
+
+            cb.gate(meta.query_fixed(q_blob_hashes_length, Rotation::cur()))
+        });
+
+        meta.lookup_any(
+            "tx blob hashes length > 0 => the last hash exists",
+            |meta| {
+                let enable = and::expr([
+                    meta.query_fixed(q_blob_hashes_length, Rotation::cur()),
+                    meta.query_advice(has_blob_hashes, Rotation::cur()),
+                ]);
+                let input = [
+                    meta.query_advice(tx_id, Rotation::cur()),
+                    TxFieldTag::BlobVersionedHash.expr(),
+                    meta.query_advice(value.lo(), Rotation::cur()) - 1.expr(),
+                ];
+                let table = [
+                    meta.query_advice(tx_id, Rotation::cur()),
+                    meta.query_fixed(tag, Rotation::cur()),
+                    meta.query_advice(index, Rotation::cur()),
+                ];
+
+                input
+                    .into_iter()
+                    .zip(table)
+                    .map(|(input, table)| (enable.expr() * input, table))
+                    .collect()
+            },
+        );
+
+        meta.create_gate("tx blob hash rows", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let is_padding_cur = meta.query_advice(is_padding, Rotation::cur());
+            let is_final_cur = meta.query_advice(is_final, Rotation::cur());
+            cb.require_equal(
+                "is_padding == (tx_id == 0)",
+                is_padding_cur.expr(),
+                blob_hash_tx_id_is_zero.expr(),
+            );
+            cb.condition(is_padding_cur.expr(), |cb| {
+                cb.require_zero("index == 0", meta.query_advice(index, Rotation::cur()));
+                cb.require_zero_word("value == 0", value.query_advice(meta, Rotation::cur()));
+            });
+            cb.require_boolean("is_final is boolean", is_final_cur.expr());
+            cb.require_zero(
+                "is_final == 0 on unused rows",
+                is_padding_cur.expr() * is_final_cur.expr(),
+            );
+            cb.require_zero(
+                "is_final == 1 on the last row of the blob hash section",
+                meta.query_fixed(q_blob_hash_last, Rotation::cur())
+                    * not::expr(is_padding_cur)
+                    * not::expr(is_final_cur),
+            );
+
+            cb.gate(meta.query_fixed(q_blob_hash, Rotation::cur()))
+        });
+
+        meta.create_gate("tx blob hash first row", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_zero("index == 0", meta.query_advice(index, Rotation::cur()));
+
+            cb.gate(and::expr([
+                meta.query_fixed(q_blob_hash, Rotation::cur()),
+                not::expr(meta.query_fixed(q_blob_hash, Rotation::prev())),
+            ]))
+        });
+
+        meta.create_gate("tx blob hash transition", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let index_cur = meta.query_advice(index, Rotation::cur());
+            let index_prev = meta.query_advice(index, Rotation::prev());
+            let is_same_tx = blob_hash_tx_id_diff_is_zero.expr();
+
+            cb.require_zero(
+                "is_padding_prev => is_padding",
+                meta.query_advice(is_padding, Rotation::prev())
+                    * not::expr(meta.query_advice(is_padding, Rotation::cur())),
+            );
+            cb.condition(not::expr(blob_hash_tx_id_is_zero.expr()), |cb| {
+                cb.require_zero(
+                    "index == index_prev + 1 within the same tx",
+                    is_same_tx.expr() * (index_cur.expr() - index_prev - 1.expr()),
+                );
+            });
+            cb.require_zero(
+                "index == 0 on the first hash row of a tx",
+                not::expr(is_same_tx.expr()) * index_cur,
+            );
+            let is_tx_id_prev_nonzero = meta.query_advice(tx_id, Rotation::prev())
+                * meta.query_advice(access_list_tx_id_inv, Rotation::prev());
+            cb.condition(is_tx_id_prev_nonzero, |cb| {
+                cb.require_equal(
+                    "is_final_prev == (tx_id != tx_id_prev)",
+                    meta.query_advice(is_final, Rotation::prev()),
+                    not::expr(is_same_tx),
+                );
+            });
+
+            cb.gate(q_blob_hash_transition(meta))
+        });
+
+        meta.lookup_any("tx blob hash tx_id is non-decreasing", |meta| {
+            let enable = q_blob_hash_transition(meta) * not::expr(blob_hash_tx_id_is_zero.expr());
+            let tx_id_diff = meta.query_advice(tx_id, Rotation::cur())
+                - meta.query_advice(tx_id, Rotation::prev());
+
+            vec![(enable * tx_id_diff, u16_table.table_exprs(meta)[0].clone())]
+        });
+
+        meta.lookup_any("tx blob hash is_final => index + 1 == length", |meta| {
+            let enable = and::expr([
+                meta.query_fixed(q_blob_hash, Rotation::cur()),
+                meta.query_advice(is_final, Rotation::cur()),
+            ]);
+            let input = [
+                meta.query_advice(tx_id, Rotation::cur()),
+                TxFieldTag::BlobVersionedHashesLength.expr(),
+                0.expr(),
+                meta.query_advice(index, Rotation::cur()) + 1.expr(),
+            ];
+            let table = [
+                meta.query_advice(tx_id, Rotation::cur()),
+                meta.query_fixed(tag, Rotation::cur()),
+                meta.query_advice(index, Rotation::cur()),
+                meta.query_advice(value.lo(), Rotation::cur()),
+            ];
+
+            input
+                .into_iter()
+                .zip(table)
+                .map(|(input, table)| (enable.expr() * input, table))
+                .collect()
+        });
+
+        // IsCreate is constrained to a boolean, and a creation to a zero CalleeAddress.  A
+        // creation (`to = None`) and a call to the zero address (`to = Some(0)`) still have the
+        // same CalleeAddress, and the circuit doesn't tell them apart: the `to` field is RLP
+        // encoded differently in both cases, but the TxCircuit has no RLP gadget and doesn't
+        // derive the TxSignHash from the tx fields.
+        // TODO: Bind IsCreate and the other tx fields to the RLP hashed into the TxSignHash.
+        meta.create_gate("tx is_create", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let (is_create, is_create_hi) = value.query_advice(meta, Rotation::cur()).into_lo_hi();
+            cb.require_boolean("is_create is boolean", is_create.expr());
+            cb.require_zero("is_create fits in the lo limb", is_create_hi);
+            // The CalleeAddress row precedes the IsCreate row.
+            cb.condition(is_create, |cb| {
+                cb.require_zero_word(
+                    "is_create => callee_address == 0",
+                    value.query_advice(meta, Rotation::prev()),
+                );
+            });
+
+            cb.gate(meta.query_fixed(q_is_create, Rotation::cur()))
+        });
+
+        // The signature verification of a tx slot recovers its caller address.  A deposit tx has
+        // no signature, and is verified with a padding signature like a padding tx: its caller
+        // address is authenticated by the derivation of the rollup blocks from L1.  Its
+        // TxSignHash is the message hash of the padding signature, 1, which no signed tx has, so
+        // the public TxSignHashes tell the deposits apart and a signed tx can't be turned into
+        // a deposit.
+        meta.create_gate("tx caller address", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let is_padding = meta.query_advice(is_padding, Rotation::cur());
+            let is_deposit = meta.query_advice(is_deposit, Rotation::cur());
+            cb.require_boolean("is_deposit is boolean", is_deposit.expr());
+            if !deposits {
+                cb.require_zero("deposits are not supported", is_deposit.expr());
+            }
+            cb.require_zero(
+                "padding txs are not deposits",
+                is_padding.expr() * is_deposit.expr(),
+            );
+            // The TxSignHash row is 6 rows after the CallerAddress row.
+            cb.condition(is_deposit.expr(), |cb| {
+                let (sign_hash_lo, sign_hash_hi) =
+                    value.query_advice(meta, Rotation(6)).into_lo_hi();
+                cb.require_equal(
+                    "is_deposit => TxSignHash == padding msg hash",
+                    sign_hash_lo,
+                    1.expr(),
+                );
+                cb.require_zero("is_deposit => TxSignHash hi == 0", sign_hash_hi);
+            });
+            cb.require_equal(
+                "signer_is_padding == is_padding + is_deposit",
+                meta.query_advice(signer_is_padding, Rotation::cur()),
+                is_padding + is_deposit.expr(),
+            );
+            cb.condition(not::expr(is_deposit), |cb| {
+                cb.require_equal_word(
+                    "caller_address == signer_address if not a deposit",
+                    value.query_advice(meta, Rotation::cur()),
+                    signer_address.query_advice(meta, Rotation::cur()),
+                );
+            });
+
+            cb.gate(meta.query_fixed(q_caller_address, Rotation::cur()))
+        });
+
+        // The IsDeposit rows and the Mint rows follow the blob versioned hash section, and their
+        // tx_id and is_padding are copied from the Nonce row of every tx.  The IsDeposit value
+        // and the is_deposit of a Mint row are copied from the is_deposit of the CallerAddress
+        // row.  Only deposit txs mint ether.
+        meta.create_gate("tx deposit fields", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let q_is_deposit = meta.query_fixed(q_is_deposit, Rotation::cur());
+            let q_mint = meta.query_fixed(q_mint, Rotation::cur());
+            cb.require_zero("index == 0", meta.query_advice(index, Rotation::cur()));
+            cb.condition(q_is_deposit, |cb| {
+                cb.require_zero(
+                    "is_deposit fits in the lo limb",
+                    meta.query_advice(value.hi(), Rotation::cur()),
+                );
+            });
+            cb.condition(
+                q_mint * not::expr(meta.query_advice(is_deposit, Rotation::cur())),
+                |cb| {
+                    cb.require_zero_word(
+                        "mint == 0 if not a deposit",
+                        value.query_advice(meta, Rotation::cur()),
+                    );
+                },
+            );
+
+            cb.gate(
+                meta.query_fixed(q_is_deposit, Rotation::cur())
+                    + meta.query_fixed(q_mint, Rotation::cur()),
+            )
+        });
+
+        // The MaxFeePerGas and the MaxPriorityFeePerGas rows of every tx follow the Mint rows, and
+        // their tx_id, is_padding and is_deposit are copied like the ones of the Mint rows.  The
+        // fee caps of the txs but the deposits, which have none, must pay the base fee of the
+        // block and the priority fee (EIP-1559).  The caps fit in 128 bits: a tx can't pay for its
+        // gas at a larger fee per gas with the ether supply.
+        let fee_caps_lt = LtChip::configure(
+            meta,
+            |meta| {
+                meta.query_fixed(q_fee_cap, Rotation::cur())
+                    + meta.query_fixed(q_tip_cap, Rotation::cur())
+            },
+            |meta| {
+                meta.query_fixed(q_fee_cap, Rotation::cur())
+                    * meta.query_advice(value.lo(), Rotation::cur())
+                    + meta.query_fixed(q_tip_cap, Rotation::cur())
+                        * meta.query_advice(value.lo(), Rotation::prev())
+            },
+            |meta| {
+                meta.query_fixed(q_fee_cap, Rotation::cur())
+                    * meta.query_advice(base_fee, Rotation::cur())
+                    + meta.query_fixed(q_tip_cap, Rotation::cur())
+                        * meta.query_advice(value.lo(), Rotation::cur())
+            },
+        );
+        meta.create_gate("tx fee caps", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_zero("index == 0", meta.query_advice(index, Rotation::cur()));
+            cb.require_zero(
+                "fee cap fits in the lo limb",
+                meta.query_advice(value.hi(), Rotation::cur()),
+            );
+            cb.condition(
+                and::expr([
+                    not::expr(meta.query_advice(is_padding, Rotation::cur())),
+                    not::expr(meta.query_advice(is_deposit, Rotation::cur())),
+                ]),
+                |cb| {
+                    cb.require_zero(
+                        "max_fee_per_gas >= base_fee, max_fee_per_gas >= max_priority_fee_per_gas",
+                        fee_caps_lt.is_lt(meta, None),
+                    );
+                },
+            );
+
+            cb.gate(
+                meta.query_fixed(q_fee_cap, Rotation::cur())
+                    + meta.query_fixed(q_tip_cap, Rotation::cur()),
+            )
+        });
+
+        meta.create_gate("tx padding calldata rows", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let is_padding_cur = meta.query_advice(is_padding, Rotation::cur());
+            cb.require_equal(
+                "is_padding == (tx_id == 0)",
+                is_padding_cur.expr(),
+                tx_id_is_zero.expr(),
+            );
+            cb.condition(is_padding_cur, |cb| {
+                cb.require_zero("index == 0", meta.query_advice(index, Rotation::cur()));
+                cb.require_zero_word("value == 0", value.query_advice(meta, Rotation::cur()));
+            });
+
+            cb.gate(meta.query_fixed(q_calldata, Rotation::cur()))
+        });
+
+        // Calldata bytes fit in value.lo, which is range checked by the lookup below.
+        meta.create_gate("tx calldata byte", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_zero(
+                "value_hi == 0",
+                meta.query_advice(value.hi(), Rotation::cur()),
+            );
+
+            cb.gate(meta.query_fixed(q_calldata, Rotation::cur()))
+        });
+
+        meta.lookup_any("tx calldata byte fits into u8", |meta| {
+            let enable = meta.query_fixed(q_calldata, Rotation::cur());
+            let byte = meta.query_advice(value.lo(), Rotation::cur());
+
+            vec![(enable * byte, u8_table.table_exprs(meta)[0].clone())]
+        });
+
+        meta.create_gate("tx padding calldata rows are at the end", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_zero(
+                "is_padding_prev => is_padding",
+                meta.query_advice(is_padding, Rotation::prev())
+                    * not::expr(meta.query_advice(is_padding, Rotation::cur())),
+            );
+
+            cb.gate(q_calldata_transition(meta))
+        });
+
+        meta.create_gate("tx calldata first row", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_zero("index == 0", meta.query_advice(index, Rotation::cur()));
+            cb.require_equal(
+                "calldata_gas_cost == byte gas cost (0 on unused rows)",
+                meta.query_advice(calldata_gas_cost, Rotation::cur()),
+                not::expr(meta.query_advice(is_padding, Rotation::cur()))
+                    * byte_gas_cost(byte_is_zero.expr()),
+            );
+
+            cb.gate(and::expr([
+                meta.query_fixed(q_calldata, Rotation::cur()),
+                not::expr(meta.query_fixed(q_calldata, Rotation::prev())),
+                not::expr(meta.query_fixed(q_calldata_carry, Rotation::cur())),
+            ]))
+        });
+
+        meta.create_gate("tx calldata rows", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let index_cur = meta.query_advice(index, Rotation::cur());
+            let index_prev = meta.query_advice(index, Rotation::prev());
+            let is_same_tx = tx_id_diff_is_zero.expr();
+
+            // if tx_id != 0 and tx_id == tx_id_prev then index == index_prev + 1
+            cb.condition(not::expr(tx_id_is_zero.expr()), |cb| {
+                cb.require_zero(
+                    "index == index_prev + 1 within the same tx",
+                    is_same_tx.expr() * (index_cur.expr() - index_prev - 1.expr()),
+                );
+            });
+            // if tx_id != tx_id_prev then index == 0
+            cb.require_zero(
+                "index == 0 on the first calldata row of a tx",
+                not::expr(is_same_tx.expr()) * index_cur,
+            );
+            // The gas cost accumulates the cost of the bytes of the tx, and restarts on the first
+            // calldata row of every tx.
+            cb.require_equal(
+                "calldata_gas_cost == is_same_tx * calldata_gas_cost_prev + byte gas cost",
+                meta.query_advice(calldata_gas_cost, Rotation::cur()),
+                not::expr(meta.query_advice(is_padding, Rotation::cur()))
+                    * (is_same_tx * meta.query_advice(calldata_gas_cost, Rotation::prev())
+                        + byte_gas_cost(byte_is_zero.expr())),
+            );
+
+            cb.gate(q_calldata_transition(meta))
+        });
+
+        // Calldata rows are sorted by tx_id: tx_id - tx_id_prev is in [0, 2^16) unless this is
+        // an unused row (tx_id == 0).
+        meta.lookup_any("tx calldata tx_id is non-decreasing", |meta| {
+            let enable = q_calldata_transition(meta) * not::expr(tx_id_is_zero.expr());
+            let tx_id_diff = meta.query_advice(tx_id, Rotation::cur())
+                - meta.query_advice(tx_id, Rotation::prev());
+
+            vec![(enable * tx_id_diff, u16_table.table_exprs(meta)[0].clone())]
+        });
+
+        meta.create_gate("tx calldata length", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let has_calldata = meta.query_advice(has_calldata, Rotation::cur());
+            let (length_lo, length_hi) = value.query_advice(meta, Rotation::cur()).into_lo_hi();
+            cb.require_boolean("has_calldata is boolean", has_calldata.expr());
+            cb.require_zero("length fits in the lo limb", length_hi);
+            // When has_calldata == 1, the lookup below guarantees that length != 0.
+            cb.require_zero(
+                "has_calldata == 0 => length == 0",
+                not::expr(has_calldata.expr()) * length_lo,
+            );
+            // The CallDataGasCost row follows the CallDataLength row.  When has_calldata == 1, the
+            // is_final lookup below binds it to the calldata bytes.
+            let (gas_cost_lo, gas_cost_hi) =
+                value.query_advice(meta, Rotation::next()).into_lo_hi();
+            cb.require_zero("gas cost fits in the lo limb", gas_cost_hi);
+            cb.require_zero(
+                "has_calldata == 0 => gas cost == 0",
+                not::expr(has_calldata) * gas_cost_lo,
+            );
+
+            cb.gate(meta.query_fixed(q_calldata_length, Rotation::cur()))
+        });
+
+        // A tx with non-empty calldata has a calldata row with index == length - 1.  Together with
+        // the index increment constraints and the `is_final` lookup below, this means that the
+        // calldata rows of the tx are exactly the indexes 0..length.
+        meta.lookup_any(
+            "tx calldata length > 0 => the last calldata byte exists",
+            |meta| {
+                let enable = and::expr([
+                    meta.query_fixed(q_calldata_length, Rotation::cur()),
+                    meta.query_advice(has_calldata, Rotation::cur()),
+                ]);
+                let input = [
+                    meta.query_advice(tx_id, Rotation::cur()),
+                    TxFieldTag::CallData.expr(),
+                    meta.query_advice(value.lo(), Rotation::cur()) - 1.expr(),
+                ];
+                let table = [
+                    meta.query_advice(tx_id, Rotation::cur()),
+                    meta.query_fixed(tag, Rotation::cur()),
+                    meta.query_advice(index, Rotation::cur()),
+                ];
+
+                input
+                    .into_iter()
+                    .zip(table)
+                    .map(|(input, table)| (enable.expr() * input, table))
+                    .collect()
+            },
+        );
+
+        meta.create_gate("tx calldata is_final", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let is_final_cur = meta.query_advice(is_final, Rotation::cur());
+            cb.require_boolean("is_final is boolean", is_final_cur.expr());
+            cb.require_zero(
+                "is_final == 0 on unused rows",
+                tx_id_is_zero.expr() * is_final_cur.expr(),
+            );
+            cb.require_zero(
+                "is_final == 1 on the last row of the calldata section",
+                meta.query_fixed(q_calldata_last, Rotation::cur())
+                    * not::expr(tx_id_is_zero.expr())
+                    * not::expr(is_final_cur),
+            );
+
+            cb.gate(meta.query_fixed(q_calldata, Rotation::cur()))
+        });
+
+        meta.create_gate("tx calldata is_final transition", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            // The IsZero gate of tx_id is enabled on the previous row, so this is the inverse.
+            let is_tx_id_prev_nonzero = meta.query_advice(tx_id, Rotation::prev())
+                * meta.query_advice(tx_id_inv, Rotation::prev());
+            // if tx_id_prev != 0 then is_final_prev == (tx_id != tx_id_prev)
+            cb.condition(is_tx_id_prev_nonzero, |cb| {
+                cb.require_equal(
+                    "is_final_prev == (tx_id != tx_id_prev)",
+                    meta.query_advice(is_final, Rotation::prev()),
+                    not::expr(tx_id_diff_is_zero.expr()),
+                );
+            });
+
+            cb.gate(q_calldata_transition(meta))
+        });
+
+        // The last calldata byte of a tx has index == length - 1.
+        meta.lookup_any("tx calldata is_final => index + 1 == length", |meta| {
+            let enable = and::expr([
+                meta.query_fixed(q_calldata, Rotation::cur()),
+                meta.query_advice(is_final, Rotation::cur()),
+            ]);
+            let input = [
+                meta.query_advice(tx_id, Rotation::cur()),
+                TxFieldTag::CallDataLength.expr(),
+                0.expr(),
+                meta.query_advice(index, Rotation::cur()) + 1.expr(),
+            ];
+            let table = [
+                meta.query_advice(tx_id, Rotation::cur()),
+                meta.query_fixed(tag, Rotation::cur()),
+                meta.query_advice(index, Rotation::cur()),
+                meta.query_advice(value.lo(), Rotation::cur()),
+            ];
+
+            input
+                .into_iter()
+                .zip(table)
+                .map(|(input, table)| (enable.expr() * input, table))
+                .collect()
+        });
+
+        // The calldata gas cost of a tx is the accumulated cost on its last calldata byte.
+        meta.lookup_any(
+            "tx calldata is_final => calldata_gas_cost == CallDataGasCost",
+            |meta| {
+                let enable = and::expr([
+                    meta.query_fixed(q_calldata, Rotation::cur()),
+                    meta.query_advice(is_final, Rotation::cur()),
+                ]);
+                let input = [
+                    meta.query_advice(tx_id, Rotation::cur()),
+                    TxFieldTag::CallDataGasCost.expr(),
+                    0.expr(),
+                    meta.query_advice(calldata_gas_cost, Rotation::cur()),
+                ];
+                let table = [
+                    meta.query_advice(tx_id, Rotation::cur()),
+                    meta.query_fixed(tag, Rotation::cur()),
+                    meta.query_advice(index, Rotation::cur()),
+                    meta.query_advice(value.lo(), Rotation::cur()),
+                ];
+
+                input
+                    .into_iter()
+                    .zip(table)
+                    .map(|(input, table)| (enable.expr() * input, table))
+                    .collect()
+            },
+        );
+
+        // The chain_id of the circuit, for which the txs are signed, and the gas limit are the
+        // ones of the block context.
+        for (name, tag, column) in [
+            (
+                "tx chain_id is the block chain_id",
+                BlockContextFieldTag::ChainId,
+                chain_id,
+            ),
+            (
+                "tx gas_limit is the block gas_limit",
+                BlockContextFieldTag::GasLimit,
+                gas_limit,
+            ),
+        ] {
+            meta.lookup_any(name, |meta| {
+                let enable = meta.query_fixed(q_block_context, Rotation::cur());
+                let input = [
+                    tag.expr(),
+                    0.expr(),
+                    meta.query_advice(column, Rotation::cur()),
+                    0.expr(),
+                ];
+
+                input
+                    .into_iter()
+                    .zip(block_table.table_exprs(meta))
+                    .map(|(input, table)| (enable.expr() * input, table))
+                    .collect()
+            });
+        }
+
+        // The base fee that the fee caps of a tx pay is the one of the block context.
+        meta.lookup_any("tx base_fee is the block base_fee", |meta| {
+            let enable = and::expr([
+                meta.query_fixed(q_fee_cap, Rotation::cur()),
+                not::expr(meta.query_advice(is_padding, Rotation::cur())),
+                not::expr(meta.query_advice(is_deposit, Rotation::cur())),
+            ]);
+            let input = [
+                BlockContextFieldTag::BaseFee.expr(),
+                0.expr(),
+                meta.query_advice(base_fee, Rotation::cur()),
+                0.expr(),
+            ];
+
+            input
+                .into_iter()
+                .zip(block_table.table_exprs(meta))
+                .map(|(input, table)| (enable.expr() * input, table))
+                .collect()
+        });
+
+        // The sum of the gas limits of the txs is an upper bound of the gas they use, so it
+        // must fit in the block: the remaining gas_limit - cumulative_gas is a u64.  The gas
+        // fields are u64s, so the subtraction can't wrap around.  This is stricter than the
+        // consensus rules, which only bound the gas limit of a tx by the remaining gas of the
+        // block after the previous txs.
+        meta.create_gate("tx cumulative gas <= block gas limit", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let lo_limbs = value_lo_limbs.map(|limb| meta.query_advice(limb, Rotation::cur()));
+            let hi_limbs = value_hi_limbs.map(|limb| meta.query_advice(limb, Rotation::cur()));
+            cb.require_equal(
+                "gas_limit - cumulative_gas == value_lo_limbs[0..4]",
+                meta.query_advice(gas_limit, Rotation::cur())
+                    - meta.query_advice(cumulative_gas, Rotation::cur()),
+                value_from_limbs(&lo_limbs[..4]),
+            );
+            for limb in lo_limbs[4..].iter().chain(&hi_limbs) {
+                cb.require_zero("remaining gas < 2^64", limb.expr());
+            }
+
+            cb.gate(meta.query_fixed(q_block_context, Rotation::cur()))
+        });
+
+        let sign_verify = SignVerifyConfig::new(
+            meta,
+            keccak_table.clone(),
+            challenges.clone(),
+            sign_verify_lanes,
+        );
+        let sign_verify_secp256r1 = sign_verify_secp256r1
+            .then(|| SignVerifyConfig::new(meta, keccak_table.clone(), challenges.clone(), 1));
+        let auth_sign_verify = auth_table.as_ref().map(|auth_table| {
+            auth_table.annotate_columns(meta);
+            // The signature verification cells are copied to the AuthTable.
+            for column in <AuthTable as LookupTable<F>>::advice_columns(auth_table) {
+                meta.enable_equality(column);
+            }
+            // Padding authorizations verify the padding signature, and their other fields are
+            // zero.
+            meta.create_gate("auth table padding rows", |meta| {
+                let mut cb = BaseConstraintBuilder::default();
+
+                cb.condition(
+                    meta.query_advice(auth_table.is_padding, Rotation::cur()),
+                    |cb| {
+                        for (name, column) in [
+                            ("tx_id == 0", auth_table.tx_id),
+                            ("index == 0", auth_table.index),
+                            ("nonce == 0", auth_table.nonce),
+                        ] {
+                            cb.require_zero(name, meta.query_advice(column, Rotation::cur()));
+                        }
+                        cb.require_zero_word(
+                            "chain_id == 0",
+                            auth_table.chain_id.query_advice(meta, Rotation::cur()),
+                        );
+                        cb.require_zero_word(
+                            "address == 0",
+                            auth_table.address.query_advice(meta, Rotation::cur()),
+                        );
+                    },
+                );
+
+                cb.gate(meta.query_fixed(auth_table.q_enable, Rotation::cur()))
+            });
+            SignVerifyConfig::new(meta, keccak_table.clone(), challenges.clone(), 1)
+        });
+        let auth_sign_hash = auth_sign_verify.as_ref().map(|auth_sign_verify| {
+            AuthSignHashConfig::new(
+                meta,
+                auth_sign_verify,
+                keccak_table.clone(),
+                challenges.clone(),
+            )
+        });
+        let rsa_sign_verify = sha256_table.map(|sha256_table| {
+            RsaSignVerifyConfig::new(meta, keccak_table.clone(), sha256_table, challenges.clone())
+        });
+        let ed25519_sign_verify = sha512_table.map(|sha512_table| {
+            Ed25519SignVerifyConfig::new(
+                meta,
+                keccak_table.clone(),
+                sha512_table,
+                challenges.clone(),
+            )
+        });
+        let eip712_digest =
+            eip712_digest.then(|| Eip712DigestConfig::new(meta, keccak_table, challenges));
+        // Created after the SignVerifyConfigs so that the maingate instance columns keep the first
+        // positions.
+        let tx_sign_hashes = meta.instance_column();
+        meta.enable_equality(tx_sign_hashes);
 
         Self {
             tx_id,
             tag,
             index,
             value,
+            q_first,
+            q_tx_fields,
+            q_tx_first,
+            q_calldata,
+            q_calldata_last,
+            q_calldata_carry,
+            q_calldata_length,
+            q_is_create,
+            q_caller_address,
+            q_gas_used,
+            q_access_list_length,
+            q_access_list_storage_keys_length,
+            q_access_list_address,
+            q_access_list_address_last,
+            q_access_list_storage_key,
+            q_access_list_storage_key_last,
+            q_access_list_storage_key_address,
+            q_blob_hashes_length,
+            q_blob_hash,
+            q_blob_hash_last,
+            q_is_deposit,
+            q_mint,
+            q_fee_cap,
+            q_tip_cap,
+            q_u64,
+            q_u256,
+            q_address,
+            value_lo_limbs,
+            value_hi_limbs,
+            cumulative_gas,
+            chain_id,
+            gas_limit,
+            base_fee,
+            q_block_context,
+            is_final,
+            has_calldata,
+            has_access_list,
+            has_blob_hashes,
+            calldata_gas_cost,
+            is_padding,
+            is_deposit,
+            signer_is_padding,
+            signer_address,
+            tx_id_is_zero: IsZeroChip::construct(tx_id_is_zero),
+            tx_id_diff_is_zero: IsZeroChip::construct(tx_id_diff_is_zero),
+            byte_is_zero: IsZeroChip::construct(byte_is_zero),
+            access_list_tx_id_is_zero: IsZeroChip::construct(access_list_tx_id_is_zero),
+            access_list_tx_id_diff_is_zero: IsZeroChip::construct(access_list_tx_id_diff_is_zero),
+            storage_key_tx_id_is_zero: IsZeroChip::construct(storage_key_tx_id_is_zero),
+            storage_key_tx_id_diff_is_zero: IsZeroChip::construct(storage_key_tx_id_diff_is_zero),
+            blob_hash_tx_id_is_zero: IsZeroChip::construct(blob_hash_tx_id_is_zero),
+            blob_hash_tx_id_diff_is_zero: IsZeroChip::construct(blob_hash_tx_id_diff_is_zero),
+            fee_caps_lt: LtChip::construct(fee_caps_lt),
             sign_verify,
-            _marker: PhantomData,
+            sign_verify_secp256r1,
+            rsa_sign_verify,
+            ed25519_sign_verify,
+            eip712_digest,
+            auth_table,
+            auth_sign_verify,
+            auth_sign_hash,
+            tx_sign_hashes,
+            u8_table,
+            u16_table,
         }
     }
 }
 
-impl<F: Field> TxCircuitConfig<F> {
-    /// Load ECDSA RangeChip table.
-    pub fn load_aux_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
-        self.sign_verify.load_range(layouter)
-    }
-
-    /// Assigns a tx circuit row and returns the assigned cell of the value in `word` in
-    /// the row.
-    fn assign_row(
-        &self,
-        region: &mut Region<'_, F>,
-        offset: usize,
-        tx_id: usize,
-        tag: TxFieldTag,
-        index: usize,
-        value: Word<Value<F>>,
-    ) -> Result<Word<AssignedCell<F, F>>, Error> {
-        region.assign_advice(
-            || "tx_id",
-            self.tx_id,
-            offset,
-            || Value::known(F::from(tx_id as u64)),
-        )?;
-        region.assign_fixed(
-            || "tag",
-            self.tag,
-            offset,
-            || Value::known(F::from(tag as u64)),
-        )?;
-        region.assign_advice(
-            || "index",
-            self.index,
-            offset,
-            || Value::known(F::from(index as u64)),
-        )?;
-        value.assign_advice(region, || "value", self.value, offset)
-    }
-
-    /// Get number of rows required.
-    pub fn get_num_rows_required(num_tx: usize) -> usize {
-        let num_rows_range_table = 1 << 18;
-        // Number of rows required to verify a transaction.
-        let num_rows_per_tx = 140436;
-        (num_tx * num_rows_per_tx).max(num_rows_range_table)
-    }
+/// Return the value of little-endian u16 limbs.
+fn value_from_limbs<F: Field>(limbs: &[Expression<F>]) -> Expression<F> {
+    limbs.iter().rev().fold(0.expr(), |result, limb| {
+        limb.clone() + result * (1u64 << 16).expr()
+    })
 }
 
 /// Tx Circuit for verifying transaction signatures
@@ -140,157 +1821,613 @@ impl<F: Field> TxCircuitConfig<F> {
 pub struct TxCircuit<F: Field> {
     /// Max number of supported transactions
     pub max_txs: usize,
+    /// Number of leading tx slots whose signature is verified, at most
+    /// `max_txs`.  The txs of the slots after them must be deposits, see
+    /// [`Self::with_max_signed_txs`].
+    pub max_signed_txs: usize,
     /// Max number of supported calldata bytes
     pub max_calldata: usize,
+    /// Max number of supported access list addresses, and of storage keys
+    pub max_access_list: usize,
+    /// Number of rows of the calldata chunks, each laid out in its own region.
+    /// `None` lays out the calldata section in a single chunk.
+    pub calldata_chunk_size: Option<usize>,
     /// SignVerify chip
     pub sign_verify: SignVerifyChip<F>,
-    /// List of Transactions
-    pub txs: Vec<Transaction>,
-    /// Chain ID
-    pub chain_id: u64,
+    /// SignVerify chip for the txs signed over secp256r1, if supported
+    pub sign_verify_secp256r1: Option<SignVerifyChip<F, Secp256r1Affine>>,
+    /// RsaSignVerify chip for the txs signed with RSA-2048, if supported
+    pub rsa_sign_verify: Option<RsaSignVerifyChip<F>>,
+    /// Ed25519SignVerify chip for the txs signed with Ed25519, if supported
+    pub ed25519_sign_verify: Option<Ed25519SignVerifyChip<F>>,
+    /// Eip712Digest chip for the txs signed as EIP-712 typed data, if
+    /// supported
+    pub eip712_digest: Option<Eip712DigestChip<F>>,
+    /// SignVerify chip for the authorizations of the set-code txs, if
+    /// supported
+    pub auth_sign_verify: Option<SignVerifyChip<F>>,
+    /// List of Transactions.  Private, as `sign_datas` and `auths` are derived
+    /// from it, see [`Self::txs`].
+    txs: Vec<Transaction>,
+    /// Chain ID.  Private, as `sign_datas` and `auths` are derived from it,
+    /// see [`Self::chain_id`].
+    chain_id: u64,
+    /// Gas limit of the block, which bounds the sum of the gas limits of the
+    /// txs
+    pub gas_limit: u64,
+    /// Base fee of the block, which bounds the max fee per gas of the txs
+    pub base_fee: U256,
+    /// Gas used by every tx, as computed by the EVM circuit.  0 for the txs
+    /// without an entry.
+    pub gas_used: Vec<u64>,
+    /// Expose the TxSignHash of every tx slot as public inputs
+    pub expose_tx_sign_hashes: bool,
+    /// Support the OP-stack deposit txs
+    pub deposits: bool,
+    /// SignData of every tx, computed once from `txs` and `chain_id` when the
+    /// circuit is created.  `None` for txs whose signature can't be recovered.
+    sign_datas: Vec<Option<TxSignData>>,
+    /// (tx index, authorization index, SignData) of every valid authorization
+    /// of the set-code txs, in the order of [`authorizations`].  The invalid
+    /// authorizations are skipped, see [`is_valid_authorization`].
+    auths: Vec<(usize, usize, SignData)>,
 }
 
 impl<F: Field> TxCircuit<F> {
     /// Return a new TxCircuit
     pub fn new(max_txs: usize, max_calldata: usize, chain_id: u64, txs: Vec<Transaction>) -> Self {
+        Self::new_with_sign_data(max_txs, max_calldata, chain_id, txs, |tx| {
+            tx.sign_data(chain_id)
+        })
+    }
+
+    /// Return a new TxCircuit whose secp256k1 SignData are taken from `cache`,
+    /// and recovered and inserted in it when they are missing.
+    pub fn new_with_cache(
+        max_txs: usize,
+        max_calldata: usize,
+        chain_id: u64,
+        txs: Vec<Transaction>,
+        cache: &SignDataCache,
+    ) -> Self {
+        Self::new_with_sign_data(max_txs, max_calldata, chain_id, txs, |tx| {
+            cache.sign_data(chain_id, tx)
+        })
+    }
+
+    /// Return a new TxCircuit with the secp256k1 SignData of every tx returned
+    /// by `sign_data`.
+    fn new_with_sign_data(
+        max_txs: usize,
+        max_calldata: usize,
+        chain_id: u64,
+        txs: Vec<Transaction>,
+        sign_data: impl Fn(&Transaction) -> Result<SignData, eth_types::Error> + Sync,
+    ) -> Self {
+        Self::new_unsigned(max_txs, max_calldata, chain_id, txs).with_sign_datas(sign_data)
+    }
+
+    /// Return a new TxCircuit whose SignData are not recovered yet, see
+    /// [`Self::with_sign_datas`].
+    fn new_unsigned(
+        max_txs: usize,
+        max_calldata: usize,
+        chain_id: u64,
+        txs: Vec<Transaction>,
+    ) -> Self {
         TxCircuit::<F> {
             max_txs,
+            max_signed_txs: max_txs,
             max_calldata,
+            max_access_list: 0,
+            calldata_chunk_size: None,
             sign_verify: SignVerifyChip::new(max_txs),
+            sign_verify_secp256r1: None,
+            rsa_sign_verify: None,
+            ed25519_sign_verify: None,
+            eip712_digest: None,
+            auth_sign_verify: None,
             txs,
             chain_id,
+            gas_limit: u64::MAX,
+            base_fee: U256::zero(),
+            gas_used: vec![],
+            expose_tx_sign_hashes: false,
+            deposits: false,
+            sign_datas: vec![],
+            auths: vec![],
+        }
+    }
+
+    /// Recover the secp256k1 SignData of every tx with `sign_data`, and the
+    /// SignData of the valid authorizations.
+    fn with_sign_datas(
+        mut self,
+        sign_data: impl Fn(&Transaction) -> Result<SignData, eth_types::Error> + Sync,
+    ) -> Self {
+        // Recovering the public key of every tx dominates the witness generation time, so it's
+        // done in parallel before entering the layouter.
+        self.sign_datas = self
+            .txs
+            .par_iter()
+            .enumerate()
+            .map(|(i, tx)| {
+                if tx.is_deposit() {
+                    return Some(TxSignData::Deposit);
+                }
+                sign_data(tx)
+                    .map(TxSignData::Secp256k1)
+                    .map_err(|e| error!("tx_to_sign_data error for tx {}: {:?}", i, e))
+                    .ok()
+            })
+            .collect();
+        self.auths = authorizations(&self.txs)
+            .filter(|(_, _, authorization)| is_valid_authorization(authorization, self.chain_id))
+            .collect_vec()
+            .par_iter()
+            .filter_map(|(tx_index, auth_index, authorization)| {
+                authorization
+                    .sign_data()
+                    .map(|sign_data| (*tx_index, *auth_index, sign_data))
+                    .map_err(|e| {
+                        log::debug!(
+                            "skipping authorization {} of tx {}: {:?}",
+                            auth_index,
+                            tx_index,
+                            e
+                        )
+                    })
+                    .ok()
+            })
+            .collect();
+        self
+    }
+
+    /// Return the txs of the circuit
+    pub fn txs(&self) -> &[Transaction] {
+        &self.txs
+    }
+
+    /// Return the chain_id of the circuit
+    pub fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    /// Return a new TxCircuit after checking that the txs fit in `max_txs` and
+    /// `max_calldata`, and that they are signed for `chain_id`.  The txs are
+    /// checked before their public keys are recovered, so that invalid inputs
+    /// are rejected cheaply.
+    pub fn try_new(
+        max_txs: usize,
+        max_calldata: usize,
+        chain_id: u64,
+        txs: Vec<Transaction>,
+    ) -> Result<Self, TxCircuitError> {
+        let circuit = Self::new_unsigned(max_txs, max_calldata, chain_id, txs);
+        circuit.validate()?;
+        Ok(circuit.with_sign_datas(|tx| tx.sign_data(chain_id)))
+    }
+
+    /// Split the signature verifications into `num_lanes` lanes laid out side by
+    /// side, trading columns for rows.  The circuit must be configured with
+    /// the same number of lanes.
+    pub fn with_sign_verify_lanes(mut self, num_lanes: usize) -> Self {
+        self.sign_verify = self.sign_verify.with_num_lanes(num_lanes);
+        self
+    }
+
+    /// Split the calldata section of the tx table into chunks of `chunk_size`
+    /// rows, each laid out in its own region, so that blocks with large
+    /// calldata don't need a single huge region.  Every chunk after the first
+    /// one starts with a copy of the last row of the previous chunk, see
+    /// [`Self::calldata_num_rows`].
+    pub fn with_calldata_chunk_size(mut self, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "calldata chunks can't be empty");
+        self.calldata_chunk_size = Some(chunk_size);
+        self
+    }
+
+    /// Verify the signatures of the first `max_signed_txs` tx slots only,
+    /// which is at most `max_txs`.  The slots after them can only hold
+    /// padding txs and deposits, and are given constant padding verifications
+    /// instead of full ones, so that sparse blocks cost much fewer rows.
+    pub fn with_max_signed_txs(mut self, max_signed_txs: usize) -> Self {
+        let max_verif = max_signed_txs.min(self.max_txs);
+        self.max_signed_txs = max_verif;
+        self.sign_verify.max_verif = max_verif;
+        if let Some(sign_verify_secp256r1) = &mut self.sign_verify_secp256r1 {
+            sign_verify_secp256r1.max_verif = max_verif;
+        }
+        if let Some(rsa_sign_verify) = &mut self.rsa_sign_verify {
+            rsa_sign_verify.max_verif = max_verif;
+        }
+        if let Some(ed25519_sign_verify) = &mut self.ed25519_sign_verify {
+            ed25519_sign_verify.max_verif = max_verif;
+        }
+        if let Some(eip712_digest) = &mut self.eip712_digest {
+            eip712_digest.max_verif = max_verif;
+        }
+        self
+    }
+
+    /// Reject txs with malleable signatures (s > n/2), as in EIP-2.
+    pub fn with_low_s(mut self) -> Self {
+        self.sign_verify = self.sign_verify.with_low_s();
+        self.sign_verify_secp256r1 = self
+            .sign_verify_secp256r1
+            .map(|sign_verify| sign_verify.with_low_s());
+        self
+    }
+
+    /// Support txs signed over secp256r1 (P-256), as done by smart cards and WebAuthn
+    /// authenticators, and verify the signature of the tx `i` over secp256r1 with the public
+    /// key `pks[i]` when it's `Some`.  The caller address of such a tx is derived from its
+    /// public key as for secp256k1.  Every signed tx slot is verified by both SignVerifyChips, one
+    /// of them with a padding signature, so the circuit must be configured with secp256r1
+    /// support.
+    pub fn with_secp256r1_signers(mut self, pks: &[Option<Secp256r1Affine>]) -> Self {
+        let mut sign_verify_secp256r1 = SignVerifyChip::new(self.max_signed_txs);
+        if self.sign_verify.low_s {
+            sign_verify_secp256r1 = sign_verify_secp256r1.with_low_s();
+        }
+        self.sign_verify_secp256r1 = Some(sign_verify_secp256r1);
+        for (i, (tx, pk)) in self.txs.iter().zip(pks).enumerate() {
+            if let Some(pk) = pk {
+                self.sign_datas[i] = tx
+                    .sign_data_secp256r1(self.chain_id, *pk)
+                    .map(TxSignData::Secp256r1)
+                    .map_err(|e| error!("tx_to_sign_data error for tx {}: {:?}", i, e))
+                    .ok();
+            }
+        }
+        self
+    }
+
+    /// Support txs signed with RSA-2048 PKCS#1 v1.5 or PSS and SHA-256, as done by the My
+    /// Number Card and newer smart cards, and verify the signature of the tx `i` with the
+    /// public key, signature and padding `signers[i]` when it's `Some`.  The signed message is
+    /// the TxSignHash, and the caller address is the end of the keccak hash of the modulus.
+    /// Every signed tx slot is verified by the RsaSignVerifyChip as well, so the circuit must be
+    /// configured with RSA support.
+    pub fn with_rsa_signers(
+        mut self,
+        signers: &[Option<(RsaPublicKey, BigUint, RsaPadding)>],
+    ) -> Self {
+        self.rsa_sign_verify = Some(RsaSignVerifyChip::new(self.max_signed_txs));
+        for (i, (tx, signer)) in self.txs.iter().zip(signers).enumerate() {
+            if let Some((pk, signature, padding)) = signer {
+                self.sign_datas[i] = tx
+                    .rsa_sign_data(self.chain_id, pk.clone(), signature.clone(), *padding)
+                    .map(TxSignData::Rsa)
+                    .map_err(|e| error!("tx_to_sign_data error for tx {}: {:?}", i, e))
+                    .ok();
+            }
+        }
+        self
+    }
+
+    /// Support txs signed with Ed25519, and verify the signature of the tx `i` with the encoded
+    /// public key and signature `signers[i]` when it's `Some`.  The signed message is the
+    /// TxSignHash, and the caller address is the end of the keccak hash of the encoded public
+    /// key.  Every signed tx slot is verified by the Ed25519SignVerifyChip as well, so the circuit
+    /// must be configured with Ed25519 support.
+    pub fn with_ed25519_signers(mut self, signers: &[Option<([u8; 32], [u8; 64])>]) -> Self {
+        self.ed25519_sign_verify = Some(Ed25519SignVerifyChip::new(self.max_signed_txs));
+        for (i, (tx, signer)) in self.txs.iter().zip(signers).enumerate() {
+            if let Some((pk, signature)) = signer {
+                self.sign_datas[i] = tx
+                    .ed25519_sign_data(self.chain_id, *pk, *signature)
+                    .map(TxSignData::Ed25519)
+                    .map_err(|e| error!("tx_to_sign_data error for tx {}: {:?}", i, e))
+                    .ok();
+            }
+        }
+        self
+    }
+
+    /// Support txs signed as EIP-712 typed data under `domain_separator`, as
+    /// done by smart accounts, and verify the signature of the tx `i` over
+    /// the EIP-712 digest of its TxSignHash when `signers[i]` is true.  The
+    /// TxSignHash of such a tx is the struct hash, and the signature is a
+    /// secp256k1 signature of `keccak(0x19 || 0x01 || domain_separator ||
+    /// TxSignHash)`.  The domain separator and the slots signed as typed data
+    /// are public inputs, so the circuit must be configured with EIP-712
+    /// support.
+    pub fn with_eip712_signers(mut self, domain_separator: H256, signers: &[bool]) -> Self {
+        self.eip712_digest = Some(Eip712DigestChip::new(domain_separator, self.max_signed_txs));
+        for (i, (tx, is_eip712)) in self.txs.iter().zip(signers).enumerate() {
+            if *is_eip712 {
+                self.sign_datas[i] = tx
+                    .eip712_sign_data(self.chain_id, domain_separator)
+                    .map(|sign_data| TxSignData::Eip712(sign_data, tx.sign_hash(self.chain_id)))
+                    .map_err(|e| error!("tx_to_sign_data error for tx {}: {:?}", i, e))
+                    .ok();
+            }
+        }
+        self
+    }
+
+    /// Support EIP-7702 set-code txs with up to `max_auths` valid
+    /// authorizations in total.  The signature of every authorization slot is
+    /// verified by a SignVerifyChip that rejects malleable signatures, its
+    /// signed message is bound to the chain_id, address and nonce of the
+    /// authorization, and it's laid out in the AuthTable with the authority
+    /// recovered from it, so the circuit must be configured with an AuthTable.
+    /// The circuit only proves the AuthTable: the delegation of the code of
+    /// the authorities is not executed by the EVM circuit.
+    pub fn with_max_authorizations(mut self, max_auths: usize) -> Self {
+        self.auth_sign_verify = Some(SignVerifyChip::new(max_auths).with_low_s());
+        self
+    }
+
+    /// Set the gas limit of the block, which is `u64::MAX` by default.
+    pub fn with_gas_limit(mut self, gas_limit: u64) -> Self {
+        self.gas_limit = gas_limit;
+        self
+    }
+
+    /// Set the base fee of the block, which is 0 by default.
+    pub fn with_base_fee(mut self, base_fee: U256) -> Self {
+        self.base_fee = base_fee;
+        self
+    }
+
+    /// Support access lists of up to `max_access_list` addresses and
+    /// `max_access_list` storage keys in total, laid out in the tx table after
+    /// the gas used by every tx.
+    pub fn with_max_access_list(mut self, max_access_list: usize) -> Self {
+        self.max_access_list = max_access_list;
+        self
+    }
+
+    /// Set the gas used by every tx.
+    pub fn with_gas_used(mut self, gas_used: Vec<u64>) -> Self {
+        self.gas_used = gas_used;
+        self
+    }
+
+    /// Support the OP-stack deposit txs, when `deposits` is true.
+    pub fn with_deposits(mut self, deposits: bool) -> Self {
+        self.deposits = deposits;
+        self
+    }
+
+    /// Expose the TxSignHash and the signature recovery id of every tx slot
+    /// (including the padding ones) as public inputs, as (lo, hi, recovery_id)
+    /// triples in the last instance column, followed by the chain_id.  This
+    /// binds a proof of the TxCircuit to a specific set of txs without the
+    /// PiCircuit.
+    pub fn with_exposed_tx_sign_hashes(mut self) -> Self {
+        self.expose_tx_sign_hashes = true;
+        self
+    }
+
+    /// Return the keccak inputs of the SignVerifyChips: the public keys of the
+    /// txs, of the authorities and of the padding signatures.
+    pub fn keccak_inputs(&self) -> Result<Vec<Vec<u8>>, Error> {
+        let sign_datas = self.sign_datas()?;
+        let mut inputs =
+            keccak_inputs_sign_verify(&sign_datas.secp256k1.into_iter().flatten().collect_vec());
+        if self.sign_verify_secp256r1.is_some() {
+            inputs.extend(keccak_inputs_sign_verify(
+                &sign_datas.secp256r1.into_iter().flatten().collect_vec(),
+            ));
+        }
+        inputs.extend(
+            sign_datas
+                .rsa
+                .iter()
+                .flatten()
+                .map(|sign_data| sign_data.pk.n_be().to_vec()),
+        );
+        inputs.extend(
+            sign_datas
+                .ed25519
+                .iter()
+                .flatten()
+                .map(|sign_data| sign_data.pk.to_vec()),
+        );
+        if let Some(eip712_digest) = &self.eip712_digest {
+            inputs.extend(
+                sign_datas
+                    .eip712
+                    .iter()
+                    .flatten()
+                    .map(|struct_hash| eip712_encode(&eip712_digest.domain_separator, struct_hash)),
+            );
+        }
+        if self.auth_sign_verify.is_some() {
+            inputs.extend(keccak_inputs_sign_verify(
+                &self
+                    .auths
+                    .iter()
+                    .map(|(_, _, sign_data)| sign_data.clone())
+                    .collect_vec(),
+            ));
+            inputs.extend(
+                self.auth_slots()
+                    .into_iter()
+                    .filter(|(tx_id, _, _)| *tx_id != 0)
+                    .map(|(_, _, authorization)| authorization.sign_message()),
+            );
+        }
+        Ok(inputs)
+    }
+
+    /// Return the sha256 inputs of the RsaSignVerifyChip: the messages signed
+    /// with RSA-2048, and the hashed parts of the PSS encoded messages.
+    pub fn sha256_inputs(&self) -> Result<Vec<Vec<u8>>, Error> {
+        Ok(self
+            .sign_datas()?
+            .rsa
+            .iter()
+            .flatten()
+            .flat_map(|sign_data| sign_data.sha256_inputs())
+            .collect())
+    }
+
+    /// Return the sha512 inputs of the Ed25519SignVerifyChip: `R || A || M`
+    /// of the signatures of the txs signed with Ed25519.
+    pub fn sha512_inputs(&self) -> Result<Vec<Vec<u8>>, Error> {
+        Ok(self
+            .sign_datas()?
+            .ed25519
+            .iter()
+            .flatten()
+            .map(|sign_data| sign_data.sha512_input())
+            .collect())
+    }
+
+    /// Return the number of rows of the calldata section of `max_calldata`
+    /// bytes split into chunks of `chunk_size` rows: one row per byte, plus a
+    /// carry row per chunk after the first one.
+    pub fn calldata_num_rows(max_calldata: usize, chunk_size: Option<usize>) -> usize {
+        match chunk_size {
+            Some(chunk_size) if max_calldata > 0 => max_calldata + (max_calldata - 1) / chunk_size,
+            _ => max_calldata,
         }
     }
 
     /// Return the minimum number of rows required to prove an input of a
-    /// particular size.
-    pub fn min_num_rows(txs_len: usize, call_data_len: usize) -> usize {
-        let tx_table_len = txs_len * TX_LEN + call_data_len;
-        std::cmp::max(tx_table_len, SignVerifyChip::<F>::min_num_rows(txs_len))
+    /// particular size with `sign_verify_lanes` SignVerifyChip lanes, where
+    /// the signatures of the first `signed_txs_len` tx slots are verified.
+    /// With calldata chunks, `call_data_len` is the number of rows of the
+    /// calldata section given by [`Self::calldata_num_rows`].
+    pub fn min_num_rows(
+        txs_len: usize,
+        signed_txs_len: usize,
+        call_data_len: usize,
+        max_access_list: usize,
+        sign_verify_lanes: usize,
+    ) -> usize {
+        // The first row of the tx table is the empty entry, and the TxGasUsed, AccessListLength and
+        // AccessListStorageKeysLength rows, the three access list sections, the
+        // BlobVersionedHashesLength rows, the blob versioned hash section, the IsDeposit and
+        // Mint rows and the MaxFeePerGas and MaxPriorityFeePerGas rows follow the calldata.
+        let tx_table_len = 1
+            + txs_len * TX_LEN
+            + call_data_len
+            + 8 * txs_len
+            + 3 * max_access_list
+            + MAX_BLOBS_PER_BLOCK;
+        if signed_txs_len == 0 {
+            // No signature verification, see `synthesize_sub`
+            return tx_table_len;
+        }
+        std::cmp::max(
+            tx_table_len,
+            SignVerifyChip::<F>::min_num_rows(signed_txs_len, sign_verify_lanes),
+        )
+    }
+
+    /// Return the minimum number of rows required to verify `num_auths`
+    /// authorizations: the SignVerifyChip of the authorizations and the
+    /// AuthSignHashChip share their columns, so their rows add up.
+    pub fn min_num_rows_authorizations(num_auths: usize) -> usize {
+        SignVerifyChip::<F>::min_num_rows(num_auths, 1)
+            + AuthSignHashChip::<F>::min_num_rows(num_auths)
     }
 
-    fn assign_tx_table(
+    /// Assign the signature verifications of every tx slot, merging the ones of the SignVerifyChips
+    /// of the other signature schemes into the ones of the secp256k1 SignVerifyChip.  The slots
+    /// after the first `max_signed_txs` ones get constant padding verifications.
+    fn assign_sig_verifs(
         &self,
         config: &TxCircuitConfig<F>,
         layouter: &mut impl Layouter<F>,
-        assigned_sig_verifs: Vec<AssignedSignatureVerify<F>>,
-    ) -> Result<(), Error> {
-        layouter.assign_region(
-            || "tx table",
-            |mut region| {
-                let mut offset = 0;
-                // Empty entry
-                config.assign_row(
-                    &mut region,
-                    offset,
-                    0,
-                    TxFieldTag::Null,
-                    0,
-                    Word::default().into_value(),
+        sign_datas: &TxSignDatas,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<Vec<AssignedSignatureVerify<F>>, Error> {
+        let mut assigned_sig_verifs = self.sign_verify.assign(
+            &config.sign_verify,
+            layouter,
+            &sign_datas.secp256k1,
+            challenges,
+        )?;
+        match (&self.sign_verify_secp256r1, &config.sign_verify_secp256r1) {
+            (Some(sign_verify_secp256r1), Some(sign_verify_secp256r1_config)) => {
+                let secp256r1_assigned_sig_verifs = sign_verify_secp256r1.assign(
+                    sign_verify_secp256r1_config,
+                    layouter,
+                    &sign_datas.secp256r1,
+                    challenges,
                 )?;
-                offset += 1;
-                // Assign all Tx fields except for call data
-                let tx_default = Transaction::default();
-                for (i, assigned_sig_verif) in assigned_sig_verifs.iter().enumerate() {
-                    let tx = if i < self.txs.len() {
-                        &self.txs[i]
-                    } else {
-                        &tx_default
-                    };
-
-                    for (tag, value) in [
-                        (
-                            TxFieldTag::Nonce,
-                            Word::from(tx.nonce.as_u64()).into_value(),
-                        ),
-                        (TxFieldTag::Gas, Word::from(tx.gas()).into_value()),
-                        (TxFieldTag::GasPrice, Word::from(tx.gas_price).into_value()),
-                        (TxFieldTag::CallerAddress, Word::from(tx.from).into_value()),
-                        (
-                            TxFieldTag::CalleeAddress,
-                            Word::from(tx.to_or_zero()).into_value(),
-                        ),
-                        (
-                            TxFieldTag::IsCreate,
-                            Word::from(tx.is_create() as u64).into_value(),
-                        ),
-                        (TxFieldTag::Value, Word::from(tx.value).into_value()),
-                        (
-                            TxFieldTag::CallDataLength,
-                            Word::from(tx.call_data.0.len() as u64).into_value(),
-                        ),
-                        (
-                            TxFieldTag::CallDataGasCost,
-                            Word::from(tx.call_data_gas_cost()).into_value(),
-                        ),
-                        (
-                            TxFieldTag::TxSignHash,
-                            assigned_sig_verif.msg_hash.map(|x| x.value().copied()),
-                        ),
-                    ] {
-                        let assigned_cell =
-                            config.assign_row(&mut region, offset, i + 1, tag, 0, value)?;
-                        offset += 1;
-
-                        // Ref. spec 0. Copy constraints using fixed offsets between the tx rows and
-                        // the SignVerifyChip
-                        match tag {
-                            TxFieldTag::CallerAddress => {
-                                region.constrain_equal(
-                                    assigned_cell.lo().cell(),
-                                    assigned_sig_verif.address.lo().cell(),
-                                )?;
-                                region.constrain_equal(
-                                    assigned_cell.hi().cell(),
-                                    assigned_sig_verif.address.hi().cell(),
-                                )?
-                            }
-                            TxFieldTag::TxSignHash => {
-                                region.constrain_equal(
-                                    assigned_cell.lo().cell(),
-                                    assigned_sig_verif.msg_hash.lo().cell(),
-                                )?;
-                                region.constrain_equal(
-                                    assigned_cell.hi().cell(),
-                                    assigned_sig_verif.msg_hash.hi().cell(),
-                                )?
-                            }
-                            _ => (),
-                        }
-                    }
-                }
-
-                // Assign call data
-                let mut calldata_count = 0;
-                for (i, tx) in self.txs.iter().enumerate() {
-                    for (index, byte) in tx.call_data.0.iter().enumerate() {
-                        assert!(calldata_count < self.max_calldata);
-                        config.assign_row(
-                            &mut region,
-                            offset,
-                            i + 1, // tx_id
-                            TxFieldTag::CallData,
-                            index,
-                            Word::from(*byte as u64).into_value(),
-                        )?;
-                        offset += 1;
-                        calldata_count += 1;
-                    }
-                }
-                for _ in calldata_count..self.max_calldata {
-                    config.assign_row(
-                        &mut region,
-                        offset,
-                        0, // tx_id
-                        TxFieldTag::CallData,
-                        0,
-                        Word::default().into_value(),
-                    )?;
-                    offset += 1;
-                }
-                Ok(())
-            },
-        )
+                assigned_sig_verifs = config.sign_verify.assign_merged_signature_verifies(
+                    layouter,
+                    assigned_sig_verifs,
+                    secp256r1_assigned_sig_verifs,
+                )?;
+            }
+            (None, None) => (),
+            _ => {
+                error!("secp256r1 support of the TxCircuit doesn't match its config");
+                return Err(Error::Synthesis);
+            }
+        }
+        match (&self.rsa_sign_verify, &config.rsa_sign_verify) {
+            (Some(rsa_sign_verify), Some(rsa_sign_verify_config)) => {
+                let rsa_assigned_sig_verifs = rsa_sign_verify.assign(
+                    rsa_sign_verify_config,
+                    layouter,
+                    &sign_datas.rsa,
+                    challenges,
+                )?;
+                assigned_sig_verifs = config.sign_verify.assign_merged_signature_verifies(
+                    layouter,
+                    assigned_sig_verifs,
+                    rsa_assigned_sig_verifs,
+                )?;
+            }
+            (None, None) => (),
+            _ => {
+                error!("RSA support of the TxCircuit doesn't match its config");
+                return Err(Error::Synthesis);
+            }
+        }
+        match (&self.ed25519_sign_verify, &config.ed25519_sign_verify) {
+            (Some(ed25519_sign_verify), Some(ed25519_sign_verify_config)) => {
+                let ed25519_assigned_sig_verifs = ed25519_sign_verify.assign(
+                    ed25519_sign_verify_config,
+                    layouter,
+                    &sign_datas.ed25519,
+                    challenges,
+                )?;
+                assigned_sig_verifs = config.sign_verify.assign_merged_signature_verifies(
+                    layouter,
+                    assigned_sig_verifs,
+                    ed25519_assigned_sig_verifs,
+                )?;
+            }
+            (None, None) => (),
+            _ => {
+                error!("Ed25519 support of the TxCircuit doesn't match its config");
+                return Err(Error::Synthesis);
+            }
+        }
+        // The struct hashes replace the signed digests after the merge, as they are only
+        // verified by the secp256k1 SignVerifyChip.
+        match (&self.eip712_digest, &config.eip712_digest) {
+            (Some(eip712_digest), Some(eip712_digest_config)) => {
+                assigned_sig_verifs = eip712_digest.assign(
+                    eip712_digest_config,
+                    layouter,
+                    assigned_sig_verifs,
+                    &sign_datas.eip712,
+                    challenges,
+                )?;
+            }
+            (None, None) => (),
+            _ => {
+                error!("EIP-712 support of the TxCircuit doesn't match its config");
+                return Err(Error::Synthesis);
+            }
+        }
+        assigned_sig_verifs.extend(self.sign_verify.assign_unverified(
+            &config.sign_verify,
+            layouter,
+            self.max_txs - self.max_signed_txs,
+        )?);
+        Ok(assigned_sig_verifs)
     }
 }
 
@@ -298,9 +2435,9 @@ impl<F: Field> SubCircuit<F> for TxCircuit<F> {
     type Config = TxCircuitConfig<F>;
 
     fn unusable_rows() -> usize {
-        // No column queried at more than 3 distinct rotations, so returns 6 as
-        // minimum unusable rows.
-        6
+        // The value column is queried at 4 distinct rotations, and no column at
+        // more, so returns 7 as minimum unusable rows.
+        7
     }
 
     fn new_from_block(block: &witness::Block<F>) -> Self {
@@ -310,18 +2447,54 @@ impl<F: Field> SubCircuit<F> for TxCircuit<F> {
             block.context.chain_id.as_u64(),
             block.txs.iter().map(|tx| tx.deref().clone()).collect_vec(),
         )
+        .with_max_signed_txs(block.circuits_params.max_signed_txs)
+        .with_gas_limit(block.context.gas_limit)
+        .with_base_fee(block.context.base_fee)
+        .with_gas_used(block.txs.iter().map(|tx| tx.gas_used()).collect())
+        .with_max_access_list(block.circuits_params.max_access_list)
+        .with_max_authorizations(block.circuits_params.max_auths)
+        .with_deposits(block.circuits_params.deposits)
     }
 
     /// Return the minimum number of rows required to prove the block
     fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        let num_auths = block
+            .txs
+            .iter()
+            .map(|tx| tx.authorization_list.as_ref().map_or(0, |list| list.len()))
+            .sum();
+        // Circuits without tx or authorization slots verify no authorization, see
+        // `synthesize_sub`
+        let min_num_rows_auths = |num_txs: usize, num_auths: usize| {
+            if num_txs == 0 || num_auths == 0 {
+                0
+            } else {
+                Self::min_num_rows_authorizations(num_auths)
+            }
+        };
         (
-            Self::min_num_rows(
-                block.txs.len(),
-                block.txs.iter().map(|tx| tx.call_data.len()).sum(),
+            std::cmp::max(
+                Self::min_num_rows(
+                    block.txs.len(),
+                    signed_txs_len(block.txs.iter().map(|tx| tx.deref())),
+                    block.txs.iter().map(|tx| tx.call_data.len()).sum(),
+                    access_list_len(block.txs.iter().map(|tx| tx.deref())),
+                    1,
+                ),
+                min_num_rows_auths(block.txs.len(), num_auths),
             ),
-            Self::min_num_rows(
-                block.circuits_params.max_txs,
-                block.circuits_params.max_calldata,
+            std::cmp::max(
+                Self::min_num_rows(
+                    block.circuits_params.max_txs,
+                    block.circuits_params.max_signed_txs,
+                    block.circuits_params.max_calldata,
+                    block.circuits_params.max_access_list,
+                    1,
+                ),
+                min_num_rows_auths(
+                    block.circuits_params.max_txs,
+                    block.circuits_params.max_auths,
+                ),
             ),
         )
     }
@@ -333,29 +2506,106 @@ impl<F: Field> SubCircuit<F> for TxCircuit<F> {
         challenges: &Challenges<Value<F>>,
         layouter: &mut impl Layouter<F>,
     ) -> Result<(), Error> {
-        assert!(self.txs.len() <= self.max_txs);
-        let sign_datas: Vec<SignData> = self
-            .txs
-            .iter()
-            .map(|tx| {
-                tx.sign_data(self.chain_id).map_err(|e| {
-                    error!("tx_to_sign_data error for tx {:?}", e);
-                    Error::Synthesis
-                })
-            })
-            .try_collect()?;
+        self.validate().map_err(|e| {
+            error!("invalid TxCircuit input: {}", e);
+            Error::Synthesis
+        })?;
+        let sign_datas = self.sign_datas()?;
 
-        config.load_aux_tables(layouter)?;
-        let assigned_sig_verifs =
+        let assigned_sig_verifs = if self.max_signed_txs == 0 {
+            // Circuits without signed tx slots, such as the ones sized for empty blocks, only
+            // give their slots constant padding verifications, and don't load the range tables
+            // of the SignVerifyChips of the txs.
             self.sign_verify
-                .assign(&config.sign_verify, layouter, &sign_datas, challenges)?;
-        self.assign_tx_table(config, layouter, assigned_sig_verifs)?;
+                .assign_unverified(&config.sign_verify, layouter, self.max_txs)?
+        } else {
+            config.load_sign_verify_tables(layouter)?;
+            self.assign_sig_verifs(config, layouter, &sign_datas, challenges)?
+        };
+        let max_auths = self
+            .auth_sign_verify
+            .as_ref()
+            .map_or(0, |auth_sign_verify| auth_sign_verify.max_verif);
+        let assigned_auth_verifs = if self.max_txs == 0 || max_auths == 0 {
+            // Circuits without tx or authorization slots verify no authorization, and don't
+            // load the range tables of the SignVerifyChip of the authorizations.
+            vec![]
+        } else {
+            config.load_auth_tables(layouter)?;
+            self.assign_auth_verifs(config, layouter, challenges)?
+        };
+        let recovery_id_cells = assigned_sig_verifs
+            .iter()
+            .map(|assigned_sig_verif| assigned_sig_verif.recovery_id.clone())
+            .collect_vec();
+        let (tx_sign_hash_cells, cumulative_gas_cell) =
+            self.assign_tx_table(config, layouter, assigned_sig_verifs)?;
+        let chain_id_cell = self.assign_block_context(config, layouter, &cumulative_gas_cell)?;
+        let assigned_auths = if self.max_txs == 0 || max_auths == 0 {
+            vec![]
+        } else {
+            self.assign_auth_sign_hashes(
+                config,
+                layouter,
+                &assigned_auth_verifs,
+                &chain_id_cell,
+                challenges,
+            )?
+        };
+        if let Some(auth_table) = &config.auth_table {
+            self.assign_auth_table(auth_table, layouter, &assigned_auth_verifs, &assigned_auths)?;
+        }
+        if self.expose_tx_sign_hashes {
+            layouter.constrain_instance(
+                chain_id_cell.cell(),
+                config.tx_sign_hashes,
+                3 * self.max_txs,
+            )?;
+            for (i, (sign_hash, recovery_id)) in tx_sign_hash_cells
+                .iter()
+                .zip(&recovery_id_cells)
+                .enumerate()
+            {
+                for (j, cell) in [sign_hash.lo(), sign_hash.hi(), recovery_id.clone()]
+                    .iter()
+                    .enumerate()
+                {
+                    layouter.constrain_instance(cell.cell(), config.tx_sign_hashes, 3 * i + j)?;
+                }
+            }
+        }
         Ok(())
     }
 
     fn instance(&self) -> Vec<Vec<F>> {
-        // The maingate expects an instance column, but we don't use it, so we return an
-        // "empty" instance column
-        vec![vec![]]
+        // The maingate of every SignVerifyChip lane expects an instance column, but we don't use
+        // them, so we return "empty" instance columns.  The Eip712DigestChip exposes the domain
+        // separator in its maingate instance column.  The last column holds the exposed
+        // TxSignHashes, recovery ids and chain_id, if any.
+        let eip712_digest = self.eip712_digest.as_ref().map(|eip712_digest| {
+            eip712_digest.instance(
+                &self
+                    .sign_datas()
+                    .expect("txs of the TxCircuit should be signed")
+                    .eip712,
+            )
+        });
+        let tx_sign_hashes = if self.expose_tx_sign_hashes {
+            self.tx_signatures_instance()
+                .expect("txs of the TxCircuit should be signed")
+        } else {
+            vec![]
+        };
+        iter::repeat(vec![])
+            .take(
+                self.sign_verify.num_lanes
+                    + self.sign_verify_secp256r1.is_some() as usize
+                    + self.rsa_sign_verify.is_some() as usize
+                    + self.ed25519_sign_verify.is_some() as usize
+                    + self.auth_sign_verify.is_some() as usize,
+            )
+            .chain(eip712_digest)
+            .chain(iter::once(tx_sign_hashes))
+            .collect()
     }
 }
@@ -0,0 +1,1212 @@
+//! Assignment of the tx table
+
+use super::{sign_verify::AssignedSignatureVerify, SignVerifyChip, TxCircuit, TxCircuitConfig};
+use crate::{table::TxFieldTag, util::word::Word};
+use eth_types::{evm_types::MAX_BLOBS_PER_BLOCK, geth_types::Transaction, Address, Field, U256};
+use gadgets::{is_zero::IsZeroInstruction, less_than::LtInstruction};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Region, Value},
+    halo2curves::ff::PrimeField,
+    plonk::Error,
+};
+use itertools::Itertools;
+use std::iter;
+
+impl<F: Field> TxCircuitConfig<F> {
+    /// Load the RangeChip tables of the SignVerifyChips.
+    pub fn load_aux_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.load_sign_verify_tables(layouter)?;
+        self.load_auth_tables(layouter)
+    }
+
+    /// Load the RangeChip tables of the SignVerifyChips of the tx slots.
+    pub(super) fn load_sign_verify_tables(
+        &self,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        self.sign_verify.load_range(layouter)?;
+        if let Some(sign_verify_secp256r1) = &self.sign_verify_secp256r1 {
+            sign_verify_secp256r1.load_range(layouter)?;
+        }
+        if let Some(rsa_sign_verify) = &self.rsa_sign_verify {
+            rsa_sign_verify.load_range(layouter)?;
+        }
+        if let Some(ed25519_sign_verify) = &self.ed25519_sign_verify {
+            ed25519_sign_verify.load_range(layouter)?;
+        }
+        if let Some(eip712_digest) = &self.eip712_digest {
+            eip712_digest.load_range(layouter)?;
+        }
+        Ok(())
+    }
+
+    /// Load the RangeChip table of the SignVerifyChip of the authorizations, if any.
+    pub(super) fn load_auth_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        if let Some(auth_sign_verify) = &self.auth_sign_verify {
+            auth_sign_verify.load_range(layouter)?;
+        }
+        Ok(())
+    }
+
+    /// Assigns a tx circuit row and returns its assigned cells.
+    #[allow(clippy::too_many_arguments)]
+    fn assign_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        tx_id: usize,
+        tag: TxFieldTag,
+        index: usize,
+        value: Word<Value<F>>,
+        is_padding: bool,
+    ) -> Result<AssignedTxRow<F>, Error> {
+        let is_padding = region.assign_advice(
+            || "is_padding",
+            self.is_padding,
+            offset,
+            || Value::known(F::from(is_padding as u64)),
+        )?;
+        let tx_id = region.assign_advice(
+            || "tx_id",
+            self.tx_id,
+            offset,
+            || Value::known(F::from(tx_id as u64)),
+        )?;
+        self.assign_tag(region, offset, tag)?;
+        let index = region.assign_advice(
+            || "index",
+            self.index,
+            offset,
+            || Value::known(F::from(index as u64)),
+        )?;
+        let value = value.assign_advice(region, || "value", self.value, offset)?;
+        Ok(AssignedTxRow {
+            tx_id,
+            index,
+            value,
+            is_padding,
+        })
+    }
+
+    /// Assigns the tag of a row and the selectors of the tag.
+    fn assign_tag(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        tag: TxFieldTag,
+    ) -> Result<(), Error> {
+        region.assign_fixed(
+            || "tag",
+            self.tag,
+            offset,
+            || Value::known(F::from(tag as u64)),
+        )?;
+        for (name, column, enabled) in [
+            ("q_first", self.q_first, tag == TxFieldTag::Null),
+            (
+                "q_tx_fields",
+                self.q_tx_fields,
+                !matches!(
+                    tag,
+                    TxFieldTag::Null
+                        | TxFieldTag::CallData
+                        | TxFieldTag::TxGasUsed
+                        | TxFieldTag::AccessListLength
+                        | TxFieldTag::AccessListStorageKeysLength
+                        | TxFieldTag::AccessListAddress
+                        | TxFieldTag::AccessListStorageKey
+                        | TxFieldTag::AccessListStorageKeyAddress
+                        | TxFieldTag::BlobVersionedHashesLength
+                        | TxFieldTag::BlobVersionedHash
+                        | TxFieldTag::IsDeposit
+                        | TxFieldTag::Mint
+                        | TxFieldTag::MaxFeePerGas
+                        | TxFieldTag::MaxPriorityFeePerGas
+                ),
+            ),
+            ("q_tx_first", self.q_tx_first, tag == TxFieldTag::Nonce),
+            ("q_calldata", self.q_calldata, tag == TxFieldTag::CallData),
+            (
+                "q_calldata_length",
+                self.q_calldata_length,
+                tag == TxFieldTag::CallDataLength,
+            ),
+            ("q_is_create", self.q_is_create, tag == TxFieldTag::IsCreate),
+            (
+                "q_caller_address",
+                self.q_caller_address,
+                tag == TxFieldTag::CallerAddress,
+            ),
+            ("q_gas_used", self.q_gas_used, tag == TxFieldTag::TxGasUsed),
+            (
+                "q_access_list_length",
+                self.q_access_list_length,
+                tag == TxFieldTag::AccessListLength,
+            ),
+            (
+                "q_access_list_storage_keys_length",
+                self.q_access_list_storage_keys_length,
+                tag == TxFieldTag::AccessListStorageKeysLength,
+            ),
+            (
+                "q_access_list_address",
+                self.q_access_list_address,
+                tag == TxFieldTag::AccessListAddress,
+            ),
+            (
+                "q_access_list_storage_key",
+                self.q_access_list_storage_key,
+                tag == TxFieldTag::AccessListStorageKey,
+            ),
+            (
+                "q_access_list_storage_key_address",
+                self.q_access_list_storage_key_address,
+                tag == TxFieldTag::AccessListStorageKeyAddress,
+            ),
+            (
+                "q_blob_hashes_length",
+                self.q_blob_hashes_length,
+                tag == TxFieldTag::BlobVersionedHashesLength,
+            ),
+            (
+                "q_blob_hash",
+                self.q_blob_hash,
+                tag == TxFieldTag::BlobVersionedHash,
+            ),
+            (
+                "q_is_deposit",
+                self.q_is_deposit,
+                tag == TxFieldTag::IsDeposit,
+            ),
+            ("q_mint", self.q_mint, tag == TxFieldTag::Mint),
+            ("q_fee_cap", self.q_fee_cap, tag == TxFieldTag::MaxFeePerGas),
+            (
+                "q_tip_cap",
+                self.q_tip_cap,
+                tag == TxFieldTag::MaxPriorityFeePerGas,
+            ),
+            (
+                "q_u64",
+                self.q_u64,
+                matches!(
+                    tag,
+                    TxFieldTag::Nonce
+                        | TxFieldTag::Gas
+                        | TxFieldTag::CallDataLength
+                        | TxFieldTag::CallDataGasCost
+                        | TxFieldTag::TxGasUsed
+                        | TxFieldTag::AccessListLength
+                        | TxFieldTag::AccessListStorageKeysLength
+                        | TxFieldTag::BlobVersionedHashesLength
+                ),
+            ),
+            (
+                "q_u256",
+                self.q_u256,
+                matches!(
+                    tag,
+                    TxFieldTag::GasPrice
+                        | TxFieldTag::Value
+                        | TxFieldTag::AccessListStorageKey
+                        | TxFieldTag::BlobVersionedHash
+                        | TxFieldTag::Mint
+                        | TxFieldTag::MaxFeePerGas
+                        | TxFieldTag::MaxPriorityFeePerGas
+                ),
+            ),
+            (
+                "q_address",
+                self.q_address,
+                matches!(
+                    tag,
+                    TxFieldTag::CallerAddress
+                        | TxFieldTag::CalleeAddress
+                        | TxFieldTag::AccessListAddress
+                        | TxFieldTag::AccessListStorageKeyAddress
+                ),
+            ),
+        ] {
+            region.assign_fixed(
+                || name,
+                column,
+                offset,
+                || Value::known(F::from(enabled as u64)),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Assigns the u16 limbs of the value of a static field row.
+    fn assign_value_limbs(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        value: Word<Value<F>>,
+    ) -> Result<(), Error> {
+        for (name, columns, value) in [
+            ("value_lo_limbs", self.value_lo_limbs, value.lo()),
+            ("value_hi_limbs", self.value_hi_limbs, value.hi()),
+        ] {
+            let le_bytes = value.map(|value| value.to_repr());
+            for (i, column) in columns.into_iter().enumerate() {
+                region.assign_advice(
+                    || format!("{}[{}]", name, i),
+                    column,
+                    offset,
+                    || {
+                        le_bytes.map(|le_bytes| {
+                            let le_bytes = le_bytes.as_ref();
+                            F::from(
+                                u16::from_le_bytes([le_bytes[2 * i], le_bytes[2 * i + 1]]) as u64
+                            )
+                        })
+                    },
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Assigns the witnesses of the calldata row gadgets, given the tx_id of
+    /// the previous row, and returns the assigned cells of `is_final` and
+    /// `calldata_gas_cost`.
+    fn assign_calldata_aux(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &CalldataRow,
+        tx_id_prev: usize,
+        is_last: bool,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        region.assign_fixed(
+            || "q_calldata_last",
+            self.q_calldata_last,
+            offset,
+            || Value::known(F::from(is_last as u64)),
+        )?;
+        region.assign_fixed(
+            || "q_calldata_carry",
+            self.q_calldata_carry,
+            offset,
+            || Value::known(F::ZERO),
+        )?;
+        let is_final = region.assign_advice(
+            || "is_final",
+            self.is_final,
+            offset,
+            || Value::known(F::from(row.is_final as u64)),
+        )?;
+        let calldata_gas_cost = region.assign_advice(
+            || "calldata_gas_cost",
+            self.calldata_gas_cost,
+            offset,
+            || Value::known(F::from(row.calldata_gas_cost)),
+        )?;
+        let tx_id = F::from(row.tx_id as u64);
+        self.tx_id_is_zero
+            .assign(region, offset, Value::known(tx_id))?;
+        self.tx_id_diff_is_zero.assign(
+            region,
+            offset,
+            Value::known(tx_id - F::from(tx_id_prev as u64)),
+        )?;
+        self.byte_is_zero
+            .assign(region, offset, Value::known(F::from(row.byte as u64)))?;
+        Ok((is_final, calldata_gas_cost))
+    }
+
+    /// Assigns the calldata `rows` of a chunk from `offset`, after a row with
+    /// `tx_id_prev`, and returns the assigned cells of the last row.
+    /// `is_last_chunk` tells if this is the last chunk of the calldata section.
+    fn assign_calldata_chunk(
+        &self,
+        region: &mut Region<'_, F>,
+        mut offset: usize,
+        rows: &[CalldataRow],
+        mut tx_id_prev: usize,
+        is_last_chunk: bool,
+    ) -> Result<Option<AssignedCalldataRow<F>>, Error> {
+        let mut last_row = None;
+        for (i, row) in rows.iter().enumerate() {
+            let assigned_row = self.assign_row(
+                region,
+                offset,
+                row.tx_id,
+                TxFieldTag::CallData,
+                row.index,
+                Word::from(row.byte as u64).into_value(),
+                row.tx_id == 0,
+            )?;
+            let (is_final, calldata_gas_cost) = self.assign_calldata_aux(
+                region,
+                offset,
+                row,
+                tx_id_prev,
+                is_last_chunk && i == rows.len() - 1,
+            )?;
+            tx_id_prev = row.tx_id;
+            offset += 1;
+            last_row = Some(AssignedCalldataRow {
+                row: assigned_row,
+                is_final,
+                calldata_gas_cost,
+            });
+        }
+        Ok(last_row)
+    }
+
+    /// Assigns the carry row at the start of a calldata chunk, a copy of the
+    /// last row `row_prev` of the previous chunk.
+    fn assign_calldata_carry_row(
+        &self,
+        region: &mut Region<'_, F>,
+        row_prev: &AssignedCalldataRow<F>,
+    ) -> Result<(), Error> {
+        let AssignedCalldataRow {
+            row:
+                AssignedTxRow {
+                    tx_id,
+                    index,
+                    value,
+                    is_padding,
+                },
+            is_final,
+            calldata_gas_cost,
+        } = row_prev;
+        for (name, cell, column) in [
+            ("tx_id", tx_id, self.tx_id),
+            ("index", index, self.index),
+            ("value_lo", &value.lo(), self.value.lo()),
+            ("value_hi", &value.hi(), self.value.hi()),
+            ("is_padding", is_padding, self.is_padding),
+            ("is_final", is_final, self.is_final),
+            (
+                "calldata_gas_cost",
+                calldata_gas_cost,
+                self.calldata_gas_cost,
+            ),
+        ] {
+            cell.copy_advice(|| name, region, column, 0)?;
+        }
+        self.assign_tag(region, 0, TxFieldTag::CallData)?;
+        for (name, column, enabled) in [
+            ("q_calldata_last", self.q_calldata_last, false),
+            ("q_calldata_carry", self.q_calldata_carry, true),
+        ] {
+            region.assign_fixed(|| name, column, 0, || Value::known(F::from(enabled as u64)))?;
+        }
+        self.tx_id_is_zero
+            .assign(region, 0, tx_id.value().copied())?;
+        self.byte_is_zero
+            .assign(region, 0, value.lo().value().copied())?;
+        // The tx_id transition gadget is disabled on carry rows.
+        self.tx_id_diff_is_zero
+            .assign(region, 0, Value::known(F::ZERO))
+    }
+
+    /// Get number of rows required to verify the signatures of `num_signed_tx` tx slots.
+    pub fn get_num_rows_required(num_signed_tx: usize) -> usize {
+        // The SuperCircuit uses a single SignVerifyChip lane, which verifies no signature
+        // without signed tx slots.
+        if num_signed_tx == 0 {
+            return 0;
+        }
+        SignVerifyChip::<F>::min_num_rows(num_signed_tx, 1)
+    }
+}
+
+/// Assigned cells of a tx table row
+struct AssignedTxRow<F: Field> {
+    tx_id: AssignedCell<F, F>,
+    index: AssignedCell<F, F>,
+    value: Word<AssignedCell<F, F>>,
+    is_padding: AssignedCell<F, F>,
+}
+
+/// A row of the calldata section of the tx table.  Unused rows have `tx_id =
+/// 0`.
+#[derive(Clone, Copy, Debug, Default)]
+struct CalldataRow {
+    tx_id: usize,
+    index: usize,
+    byte: u8,
+    is_final: bool,
+    /// Gas cost of the calldata bytes of the tx up to this one
+    calldata_gas_cost: u64,
+}
+
+/// Assigned cells of a calldata row
+struct AssignedCalldataRow<F: Field> {
+    row: AssignedTxRow<F>,
+    is_final: AssignedCell<F, F>,
+    calldata_gas_cost: AssignedCell<F, F>,
+}
+
+/// A row of the access list sections of the tx table: an address, or a
+/// storage key.  Also used for the rows of the blob versioned hash section.
+/// Unused rows have `tx_id = 0`.
+#[derive(Clone, Copy, Debug, Default)]
+struct AccessListRow<F: Field> {
+    tx_id: usize,
+    index: usize,
+    value: Word<F>,
+    /// Whether this is the last entry of the tx in the section
+    is_final: bool,
+}
+
+impl<F: Field> TxCircuit<F> {
+    /// Assign the block context row, given the cell of the sum of the gas
+    /// limits of the txs, and return the cell of the chain_id.
+    pub(super) fn assign_block_context(
+        &self,
+        config: &TxCircuitConfig<F>,
+        layouter: &mut impl Layouter<F>,
+        cumulative_gas_cell: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "tx block context",
+            |mut region| {
+                region.assign_fixed(
+                    || "q_block_context",
+                    config.q_block_context,
+                    0,
+                    || Value::known(F::ONE),
+                )?;
+                let cumulative_gas = cumulative_gas_cell.copy_advice(
+                    || "cumulative_gas",
+                    &mut region,
+                    config.cumulative_gas,
+                    0,
+                )?;
+                region.assign_advice(
+                    || "gas_limit",
+                    config.gas_limit,
+                    0,
+                    || Value::known(F::from(self.gas_limit)),
+                )?;
+                let remaining_gas =
+                    Value::known(F::from(self.gas_limit)) - cumulative_gas.value().copied();
+                config.assign_value_limbs(
+                    &mut region,
+                    0,
+                    Word::new([remaining_gas, Value::known(F::ZERO)]),
+                )?;
+                region.assign_advice(
+                    || "chain_id",
+                    config.chain_id,
+                    0,
+                    || Value::known(F::from(self.chain_id)),
+                )
+            },
+        )
+    }
+
+    pub(super) fn assign_tx_table(
+        &self,
+        config: &TxCircuitConfig<F>,
+        layouter: &mut impl Layouter<F>,
+        assigned_sig_verifs: Vec<AssignedSignatureVerify<F>>,
+    ) -> Result<(Vec<Word<AssignedCell<F, F>>>, AssignedCell<F, F>), Error> {
+        let calldata_rows = self
+            .txs
+            .iter()
+            .enumerate()
+            .flat_map(|(i, tx)| {
+                let len = tx.call_data.0.len();
+                tx.call_data.0.iter().enumerate().scan(
+                    0,
+                    move |calldata_gas_cost, (index, byte)| {
+                        *calldata_gas_cost += if *byte == 0 { 4 } else { 16 };
+                        Some(CalldataRow {
+                            tx_id: i + 1,
+                            index,
+                            byte: *byte,
+                            is_final: index == len - 1,
+                            calldata_gas_cost: *calldata_gas_cost,
+                        })
+                    },
+                )
+            })
+            .chain(iter::repeat(CalldataRow::default()))
+            .take(self.max_calldata)
+            .collect_vec();
+        let chunk_size = self.calldata_chunk_size.unwrap_or(self.max_calldata).max(1);
+        let chunks = calldata_rows.chunks(chunk_size).collect_vec();
+
+        let (tx_sign_hash_cells, total_gas_cell, tx_cells, is_deposit_cells, mut last_row) =
+            layouter.assign_region(
+                || "tx table",
+                |mut region| {
+                    let mut offset = 0;
+                    let mut tx_sign_hash_cells = Vec::with_capacity(assigned_sig_verifs.len());
+                    // (tx_id, is_padding) cells of the Nonce row of every tx
+                    let mut tx_cells = Vec::with_capacity(assigned_sig_verifs.len());
+                    // is_deposit cells of the CallerAddress row of every tx
+                    let mut is_deposit_cells = Vec::with_capacity(assigned_sig_verifs.len());
+                    // Empty entry
+                    config.assign_row(
+                        &mut region,
+                        offset,
+                        0,
+                        TxFieldTag::Null,
+                        0,
+                        Word::default().into_value(),
+                        false,
+                    )?;
+                    let mut cumulative_gas = 0;
+                    let mut cumulative_gas_cell = region.assign_advice(
+                        || "cumulative_gas",
+                        config.cumulative_gas,
+                        offset,
+                        || Value::known(F::ZERO),
+                    )?;
+                    offset += 1;
+                    // Assign all Tx fields except for call data
+                    // Padding txs have all their fields set to zero, so they are not creations.
+                    let tx_default = Transaction {
+                        to: Some(Address::zero()),
+                        ..Default::default()
+                    };
+                    for (i, assigned_sig_verif) in assigned_sig_verifs.iter().enumerate() {
+                        let tx = if i < self.txs.len() {
+                            &self.txs[i]
+                        } else {
+                            &tx_default
+                        };
+
+                        for (tag, value) in [
+                            (
+                                TxFieldTag::Nonce,
+                                Word::from(tx.nonce.as_u64()).into_value(),
+                            ),
+                            (TxFieldTag::Gas, Word::from(tx.gas()).into_value()),
+                            (TxFieldTag::GasPrice, Word::from(tx.gas_price).into_value()),
+                            (TxFieldTag::CallerAddress, Word::from(tx.from).into_value()),
+                            (
+                                TxFieldTag::CalleeAddress,
+                                Word::from(tx.to_or_zero()).into_value(),
+                            ),
+                            (
+                                TxFieldTag::IsCreate,
+                                Word::from(tx.is_create() as u64).into_value(),
+                            ),
+                            (TxFieldTag::Value, Word::from(tx.value).into_value()),
+                            (
+                                TxFieldTag::CallDataLength,
+                                Word::from(tx.call_data.0.len() as u64).into_value(),
+                            ),
+                            (
+                                TxFieldTag::CallDataGasCost,
+                                Word::from(tx.call_data_gas_cost()).into_value(),
+                            ),
+                            (
+                                TxFieldTag::TxSignHash,
+                                assigned_sig_verif.msg_hash.map(|x| x.value().copied()),
+                            ),
+                        ] {
+                            config.assign_value_limbs(&mut region, offset, value)?;
+                            let AssignedTxRow {
+                                tx_id: assigned_tx_id,
+                                value: assigned_cell,
+                                is_padding: assigned_is_padding,
+                                ..
+                            } = config.assign_row(
+                                &mut region,
+                                offset,
+                                i + 1,
+                                tag,
+                                0,
+                                value,
+                                i >= self.txs.len(),
+                            )?;
+                            if tag == TxFieldTag::CallDataLength {
+                                region.assign_advice(
+                                    || "has_calldata",
+                                    config.has_calldata,
+                                    offset,
+                                    || Value::known(F::from(!tx.call_data.0.is_empty() as u64)),
+                                )?;
+                            }
+                            if tag == TxFieldTag::Nonce {
+                                tx_cells.push((assigned_tx_id, assigned_is_padding.clone()));
+                            }
+                            if tag == TxFieldTag::Gas {
+                                cumulative_gas += tx.gas() as u128;
+                            }
+                            cumulative_gas_cell = region.assign_advice(
+                                || "cumulative_gas",
+                                config.cumulative_gas,
+                                offset,
+                                || Value::known(F::from_u128(cumulative_gas)),
+                            )?;
+
+                            // Ref. spec 0. Copy constraints using fixed offsets between the tx rows
+                            // and the SignVerifyChip
+                            match tag {
+                                TxFieldTag::CallerAddress => {
+                                    is_deposit_cells.push(region.assign_advice(
+                                        || "is_deposit",
+                                        config.is_deposit,
+                                        offset,
+                                        || Value::known(F::from(tx.is_deposit() as u64)),
+                                    )?);
+                                    let signer_is_padding = region.assign_advice(
+                                        || "signer_is_padding",
+                                        config.signer_is_padding,
+                                        offset,
+                                        || assigned_sig_verif.is_padding.value().copied(),
+                                    )?;
+                                    let signer_address = assigned_sig_verif
+                                        .address
+                                        .map(|cell| cell.value().copied())
+                                        .assign_advice(
+                                            &mut region,
+                                            || "signer_address",
+                                            config.signer_address,
+                                            offset,
+                                        )?;
+                                    region.constrain_equal(
+                                        signer_is_padding.cell(),
+                                        assigned_sig_verif.is_padding.cell(),
+                                    )?;
+                                    region.constrain_equal(
+                                        signer_address.lo().cell(),
+                                        assigned_sig_verif.address.lo().cell(),
+                                    )?;
+                                    region.constrain_equal(
+                                        signer_address.hi().cell(),
+                                        assigned_sig_verif.address.hi().cell(),
+                                    )?
+                                }
+                                TxFieldTag::TxSignHash => {
+                                    region.constrain_equal(
+                                        assigned_cell.lo().cell(),
+                                        assigned_sig_verif.msg_hash.lo().cell(),
+                                    )?;
+                                    region.constrain_equal(
+                                        assigned_cell.hi().cell(),
+                                        assigned_sig_verif.msg_hash.hi().cell(),
+                                    )?;
+                                    tx_sign_hash_cells.push(assigned_cell);
+                                }
+                                _ => (),
+                            }
+                            offset += 1;
+                        }
+                    }
+
+                    // Assign the first chunk of call data, after the last static field row
+                    let last_row = config.assign_calldata_chunk(
+                        &mut region,
+                        offset,
+                        chunks.first().copied().unwrap_or_default(),
+                        assigned_sig_verifs.len(),
+                        chunks.len() <= 1,
+                    )?;
+                    Ok((
+                        tx_sign_hash_cells,
+                        cumulative_gas_cell,
+                        tx_cells,
+                        is_deposit_cells,
+                        last_row,
+                    ))
+                },
+            )?;
+
+        // Assign the other chunks of call data, each after a copy of the last row of the previous
+        // chunk
+        for (i, chunk) in chunks.iter().enumerate().skip(1) {
+            last_row = layouter.assign_region(
+                || format!("tx table calldata chunk {}", i),
+                |mut region| {
+                    let row_prev = last_row.as_ref().ok_or(Error::Synthesis)?;
+                    config.assign_calldata_carry_row(&mut region, row_prev)?;
+                    config.assign_calldata_chunk(
+                        &mut region,
+                        1,
+                        chunk,
+                        chunks[i - 1].last().map_or(0, |row| row.tx_id),
+                        i == chunks.len() - 1,
+                    )
+                },
+            )?;
+        }
+
+        // Assign the gas used by every tx slot, after the call data
+        layouter.assign_region(
+            || "tx table gas used",
+            |mut region| {
+                for (offset, (tx_id, is_padding)) in tx_cells.iter().enumerate() {
+                    let gas_used =
+                        Word::from(self.gas_used.get(offset).copied().unwrap_or_default())
+                            .into_value();
+                    config.assign_value_limbs(&mut region, offset, gas_used)?;
+                    let row = config.assign_row(
+                        &mut region,
+                        offset,
+                        offset + 1,
+                        TxFieldTag::TxGasUsed,
+                        0,
+                        gas_used,
+                        offset >= self.txs.len(),
+                    )?;
+                    region.constrain_equal(row.tx_id.cell(), tx_id.cell())?;
+                    region.constrain_equal(row.is_padding.cell(), is_padding.cell())?;
+                }
+                Ok(())
+            },
+        )?;
+        self.assign_access_list(config, layouter, &tx_cells)?;
+        self.assign_blob_hashes(config, layouter, &tx_cells)?;
+        self.assign_deposits(config, layouter, &tx_cells, &is_deposit_cells)?;
+        self.assign_fee_caps(config, layouter, &tx_cells, &is_deposit_cells)?;
+        Ok((tx_sign_hash_cells, total_gas_cell))
+    }
+
+    /// Assign the access lists, after the gas used: the AccessListLength and
+    /// the AccessListStorageKeysLength of every tx slot, with the (tx_id,
+    /// is_padding) cells `tx_cells` of its Nonce row, followed by the address,
+    /// the storage key and the storage key address sections.
+    pub(super) fn assign_access_list(
+        &self,
+        config: &TxCircuitConfig<F>,
+        layouter: &mut impl Layouter<F>,
+        tx_cells: &[(AssignedCell<F, F>, AssignedCell<F, F>)],
+    ) -> Result<(), Error> {
+        let address_rows = self
+            .txs
+            .iter()
+            .enumerate()
+            .flat_map(|(i, tx)| {
+                let access_list_items = tx.access_list_items();
+                access_list_items
+                    .iter()
+                    .enumerate()
+                    .map(move |(index, item)| AccessListRow {
+                        tx_id: i + 1,
+                        index,
+                        value: Word::from(item.address),
+                        is_final: index == access_list_items.len() - 1,
+                    })
+            })
+            .chain(iter::repeat(AccessListRow::default()))
+            .take(self.max_access_list)
+            .collect_vec();
+        // The storage key rows, with the address of every storage key
+        let storage_key_rows = self
+            .txs
+            .iter()
+            .enumerate()
+            .flat_map(|(i, tx)| {
+                let len = tx.access_list_storage_keys().count();
+                tx.access_list_storage_keys()
+                    .enumerate()
+                    .map(move |(index, (address, key))| {
+                        let row = AccessListRow {
+                            tx_id: i + 1,
+                            index,
+                            value: Word::from(key),
+                            is_final: index == len - 1,
+                        };
+                        (row, Word::from(address))
+                    })
+            })
+            .chain(iter::repeat((AccessListRow::default(), Word::default())))
+            .take(self.max_access_list)
+            .collect_vec();
+
+        layouter.assign_region(
+            || "tx table access list",
+            |mut region| {
+                let mut offset = 0;
+                for (i, (tx_id, is_padding)) in tx_cells.iter().enumerate() {
+                    let len = self.txs.get(i).map_or(0, |tx| tx.access_list_items().len());
+                    let value = Word::from(len as u64).into_value();
+                    config.assign_value_limbs(&mut region, offset, value)?;
+                    let row = config.assign_row(
+                        &mut region,
+                        offset,
+                        i + 1,
+                        TxFieldTag::AccessListLength,
+                        0,
+                        value,
+                        i >= self.txs.len(),
+                    )?;
+                    region.assign_advice(
+                        || "has_access_list",
+                        config.has_access_list,
+                        offset,
+                        || Value::known(F::from((len > 0) as u64)),
+                    )?;
+                    region.constrain_equal(row.tx_id.cell(), tx_id.cell())?;
+                    region.constrain_equal(row.is_padding.cell(), is_padding.cell())?;
+                    offset += 1;
+                }
+                for (i, (tx_id, is_padding)) in tx_cells.iter().enumerate() {
+                    let len = self
+                        .txs
+                        .get(i)
+                        .map_or(0, |tx| tx.access_list_storage_keys().count());
+                    let value = Word::from(len as u64).into_value();
+                    config.assign_value_limbs(&mut region, offset, value)?;
+                    let row = config.assign_row(
+                        &mut region,
+                        offset,
+                        i + 1,
+                        TxFieldTag::AccessListStorageKeysLength,
+                        0,
+                        value,
+                        i >= self.txs.len(),
+                    )?;
+                    region.assign_advice(
+                        || "has_access_list",
+                        config.has_access_list,
+                        offset,
+                        || Value::known(F::from((len > 0) as u64)),
+                    )?;
+                    region.constrain_equal(row.tx_id.cell(), tx_id.cell())?;
+                    region.constrain_equal(row.is_padding.cell(), is_padding.cell())?;
+                    offset += 1;
+                }
+                let mut tx_id_prev = 0;
+                for (i, row) in address_rows.iter().enumerate() {
+                    let value = row.value.into_value();
+                    config.assign_value_limbs(&mut region, offset, value)?;
+                    config.assign_row(
+                        &mut region,
+                        offset,
+                        row.tx_id,
+                        TxFieldTag::AccessListAddress,
+                        row.index,
+                        value,
+                        row.tx_id == 0,
+                    )?;
+                    region.assign_fixed(
+                        || "q_access_list_address_last",
+                        config.q_access_list_address_last,
+                        offset,
+                        || Value::known(F::from((i == address_rows.len() - 1) as u64)),
+                    )?;
+                    region.assign_advice(
+                        || "is_final",
+                        config.is_final,
+                        offset,
+                        || Value::known(F::from(row.is_final as u64)),
+                    )?;
+                    let tx_id = F::from(row.tx_id as u64);
+                    config.access_list_tx_id_is_zero.assign(
+                        &mut region,
+                        offset,
+                        Value::known(tx_id),
+                    )?;
+                    config.access_list_tx_id_diff_is_zero.assign(
+                        &mut region,
+                        offset,
+                        Value::known(tx_id - F::from(tx_id_prev as u64)),
+                    )?;
+                    tx_id_prev = row.tx_id;
+                    offset += 1;
+                }
+                let mut tx_id_prev = 0;
+                let mut storage_key_cells = Vec::with_capacity(storage_key_rows.len());
+                for (i, (row, _)) in storage_key_rows.iter().enumerate() {
+                    let value = row.value.into_value();
+                    config.assign_value_limbs(&mut region, offset, value)?;
+                    storage_key_cells.push(config.assign_row(
+                        &mut region,
+                        offset,
+                        row.tx_id,
+                        TxFieldTag::AccessListStorageKey,
+                        row.index,
+                        value,
+                        row.tx_id == 0,
+                    )?);
+                    region.assign_fixed(
+                        || "q_access_list_storage_key_last",
+                        config.q_access_list_storage_key_last,
+                        offset,
+                        || Value::known(F::from((i == storage_key_rows.len() - 1) as u64)),
+                    )?;
+                    region.assign_advice(
+                        || "is_final",
+                        config.is_final,
+                        offset,
+                        || Value::known(F::from(row.is_final as u64)),
+                    )?;
+                    let tx_id = F::from(row.tx_id as u64);
+                    config.storage_key_tx_id_is_zero.assign(
+                        &mut region,
+                        offset,
+                        Value::known(tx_id),
+                    )?;
+                    config.storage_key_tx_id_diff_is_zero.assign(
+                        &mut region,
+                        offset,
+                        Value::known(tx_id - F::from(tx_id_prev as u64)),
+                    )?;
+                    tx_id_prev = row.tx_id;
+                    offset += 1;
+                }
+                for ((row, address), key_cells) in storage_key_rows.iter().zip(storage_key_cells) {
+                    let value = address.into_value();
+                    config.assign_value_limbs(&mut region, offset, value)?;
+                    let cells = config.assign_row(
+                        &mut region,
+                        offset,
+                        row.tx_id,
+                        TxFieldTag::AccessListStorageKeyAddress,
+                        row.index,
+                        value,
+                        row.tx_id == 0,
+                    )?;
+                    region.constrain_equal(cells.tx_id.cell(), key_cells.tx_id.cell())?;
+                    region.constrain_equal(cells.index.cell(), key_cells.index.cell())?;
+                    region.constrain_equal(cells.is_padding.cell(), key_cells.is_padding.cell())?;
+                    offset += 1;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Assign the blob versioned hashes, after the access lists: the
+    /// BlobVersionedHashesLength of every tx slot, with the (tx_id, is_padding)
+    /// cells `tx_cells` of its Nonce row, followed by the blob versioned hash
+    /// section.
+    pub(super) fn assign_blob_hashes(
+        &self,
+        config: &TxCircuitConfig<F>,
+        layouter: &mut impl Layouter<F>,
+        tx_cells: &[(AssignedCell<F, F>, AssignedCell<F, F>)],
+    ) -> Result<(), Error> {
+        let hash_rows = self
+            .txs
+            .iter()
+            .enumerate()
+            .flat_map(|(i, tx)| {
+                let hashes = &tx.blob_versioned_hashes;
+                hashes
+                    .iter()
+                    .enumerate()
+                    .map(move |(index, hash)| AccessListRow {
+                        tx_id: i + 1,
+                        index,
+                        value: Word::from(*hash),
+                        is_final: index == hashes.len() - 1,
+                    })
+            })
+            .chain(iter::repeat(AccessListRow::default()))
+            .take(MAX_BLOBS_PER_BLOCK)
+            .collect_vec();
+
+        layouter.assign_region(
+            || "tx table blob hashes",
+            |mut region| {
+                let mut offset = 0;
+                for (i, (tx_id, is_padding)) in tx_cells.iter().enumerate() {
+                    let len = self
+                        .txs
+                        .get(i)
+                        .map_or(0, |tx| tx.blob_versioned_hashes.len());
+                    let value = Word::from(len as u64).into_value();
+                    config.assign_value_limbs(&mut region, offset, value)?;
+                    let row = config.assign_row(
+                        &mut region,
+                        offset,
+                        i + 1,
+                        TxFieldTag::BlobVersionedHashesLength,
+                        0,
+                        value,
+                        i >= self.txs.len(),
+                    )?;
+                    region.assign_advice(
+                        || "has_blob_hashes",
+                        config.has_blob_hashes,
+                        offset,
+                        || Value::known(F::from((len > 0) as u64)),
+                    )?;
+                    region.constrain_equal(row.tx_id.cell(), tx_id.cell())?;
+                    region.constrain_equal(row.is_padding.cell(), is_padding.cell())?;
+                    offset += 1;
+                }
+                let mut tx_id_prev = 0;
+                for (i, row) in hash_rows.iter().enumerate() {
+                    let value = row.value.into_value();
+                    config.assign_value_limbs(&mut region, offset, value)?;
+                    config.assign_row(
+                        &mut region,
+                        offset,
+                        row.tx_id,
+                        TxFieldTag::BlobVersionedHash,
+                        row.index,
+                        value,
+                        row.tx_id == 0,
+                    )?;
+                    region.assign_fixed(
+                        || "q_blob_hash_last",
+                        config.q_blob_hash_last,
+                        offset,
+                        || Value::known(F::from((i == hash_rows.len() - 1) as u64)),
+                    )?;
+                    region.assign_advice(
+                        || "is_final",
+                        config.is_final,
+                        offset,
+                        || Value::known(F::from(row.is_final as u64)),
+                    )?;
+                    let tx_id = F::from(row.tx_id as u64);
+                    config.blob_hash_tx_id_is_zero.assign(
+                        &mut region,
+                        offset,
+                        Value::known(tx_id),
+                    )?;
+                    config.blob_hash_tx_id_diff_is_zero.assign(
+                        &mut region,
+                        offset,
+                        Value::known(tx_id - F::from(tx_id_prev as u64)),
+                    )?;
+                    tx_id_prev = row.tx_id;
+                    offset += 1;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Assign the deposit fields, after the blob versioned hashes: the IsDeposit
+    /// and then the Mint of every tx slot, with the (tx_id, is_padding) cells
+    /// `tx_cells` of its Nonce row and the is_deposit cells `is_deposit_cells`
+    /// of its CallerAddress row.
+    pub(super) fn assign_deposits(
+        &self,
+        config: &TxCircuitConfig<F>,
+        layouter: &mut impl Layouter<F>,
+        tx_cells: &[(AssignedCell<F, F>, AssignedCell<F, F>)],
+        is_deposit_cells: &[AssignedCell<F, F>],
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "tx table deposits",
+            |mut region| {
+                let mut offset = 0;
+                for tag in [TxFieldTag::IsDeposit, TxFieldTag::Mint] {
+                    for (i, ((tx_id, is_padding), is_deposit)) in
+                        tx_cells.iter().zip(is_deposit_cells).enumerate()
+                    {
+                        let tx = self.txs.get(i);
+                        let value = if tag == TxFieldTag::IsDeposit {
+                            Word::from(tx.map_or(false, |tx| tx.is_deposit()))
+                        } else {
+                            Word::from(tx.map_or(U256::zero(), |tx| tx.mint()))
+                        }
+                        .into_value();
+                        if tag == TxFieldTag::Mint {
+                            config.assign_value_limbs(&mut region, offset, value)?;
+                        }
+                        let row = config.assign_row(
+                            &mut region,
+                            offset,
+                            i + 1,
+                            tag,
+                            0,
+                            value,
+                            i >= self.txs.len(),
+                        )?;
+                        region.constrain_equal(row.tx_id.cell(), tx_id.cell())?;
+                        region.constrain_equal(row.is_padding.cell(), is_padding.cell())?;
+                        if tag == TxFieldTag::IsDeposit {
+                            region.constrain_equal(row.value.lo().cell(), is_deposit.cell())?;
+                        } else {
+                            let row_is_deposit = region.assign_advice(
+                                || "is_deposit",
+                                config.is_deposit,
+                                offset,
+                                || is_deposit.value().copied(),
+                            )?;
+                            region.constrain_equal(row_is_deposit.cell(), is_deposit.cell())?;
+                        }
+                        offset += 1;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Assign the fee caps, after the deposit fields: the MaxFeePerGas and
+    /// then the MaxPriorityFeePerGas of every tx slot, with the (tx_id,
+    /// is_padding) cells `tx_cells` of its Nonce row and the is_deposit cells
+    /// `is_deposit_cells` of its CallerAddress row.  The MaxFeePerGas rows
+    /// hold the base fee of the block, which the fee caps of the txs but the
+    /// deposits pay.
+    pub(super) fn assign_fee_caps(
+        &self,
+        config: &TxCircuitConfig<F>,
+        layouter: &mut impl Layouter<F>,
+        tx_cells: &[(AssignedCell<F, F>, AssignedCell<F, F>)],
+        is_deposit_cells: &[AssignedCell<F, F>],
+    ) -> Result<(), Error> {
+        config.fee_caps_lt.load(layouter)?;
+        let base_fee = Word::<F>::from(self.base_fee).lo();
+        layouter.assign_region(
+            || "tx table fee caps",
+            |mut region| {
+                let mut offset = 0;
+                for (i, ((tx_id, is_padding), is_deposit)) in
+                    tx_cells.iter().zip(is_deposit_cells).enumerate()
+                {
+                    let (fee_cap, tip_cap) = self
+                        .txs
+                        .get(i)
+                        .map_or((U256::zero(), U256::zero()), |tx| tx.fee_caps());
+                    let [fee_cap, tip_cap] = [fee_cap, tip_cap].map(Word::<F>::from);
+                    for (tag, cap, lhs, rhs) in [
+                        (TxFieldTag::MaxFeePerGas, fee_cap, fee_cap.lo(), base_fee),
+                        (
+                            TxFieldTag::MaxPriorityFeePerGas,
+                            tip_cap,
+                            fee_cap.lo(),
+                            tip_cap.lo(),
+                        ),
+                    ] {
+                        let value = cap.into_value();
+                        config.assign_value_limbs(&mut region, offset, value)?;
+                        let row = config.assign_row(
+                            &mut region,
+                            offset,
+                            i + 1,
+                            tag,
+                            0,
+                            value,
+                            i >= self.txs.len(),
+                        )?;
+                        region.constrain_equal(row.tx_id.cell(), tx_id.cell())?;
+                        region.constrain_equal(row.is_padding.cell(), is_padding.cell())?;
+                        let row_is_deposit = region.assign_advice(
+                            || "is_deposit",
+                            config.is_deposit,
+                            offset,
+                            || is_deposit.value().copied(),
+                        )?;
+                        region.constrain_equal(row_is_deposit.cell(), is_deposit.cell())?;
+                        if tag == TxFieldTag::MaxFeePerGas {
+                            region.assign_advice(
+                                || "base_fee",
+                                config.base_fee,
+                                offset,
+                                || Value::known(base_fee),
+                            )?;
+                        }
+                        config.fee_caps_lt.assign(
+                            &mut region,
+                            offset,
+                            Value::known(lhs),
+                            Value::known(rhs),
+                        )?;
+                        offset += 1;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
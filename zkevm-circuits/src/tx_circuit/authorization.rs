@@ -0,0 +1,261 @@
+//! EIP-7702 authorizations of the set-code txs
+
+use super::{
+    sign_verify::{
+        is_low_s, recovery_id, AssignedAuthorization, AssignedSignature, AssignedSignatureVerify,
+        AuthSignHashChip,
+    },
+    TxCircuit, TxCircuitConfig,
+};
+use crate::{
+    table::{AuthTable, LookupTable},
+    util::Challenges,
+};
+use eth_types::{
+    geth_types::{Authorization, Transaction},
+    Field, U256,
+};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::Error,
+};
+use itertools::Itertools;
+use log::error;
+use std::iter;
+
+/// Return the (tx index, authorization index, authorization) of every
+/// authorization of the set-code txs.
+pub(super) fn authorizations(
+    txs: &[Transaction],
+) -> impl Iterator<Item = (usize, usize, &Authorization)> {
+    txs.iter().enumerate().flat_map(|(tx_index, tx)| {
+        tx.authorization_list
+            .iter()
+            .flatten()
+            .enumerate()
+            .map(move |(auth_index, authorization)| (tx_index, auth_index, authorization))
+    })
+}
+
+/// EIP-7702: an authorization is valid for the chain_id of the circuit or for
+/// all chains, its nonce can be incremented and its signature has s <= n/2.
+/// The invalid authorizations are skipped by the EVM, so they are not laid
+/// out in the AuthTable.
+pub(super) fn is_valid_authorization(authorization: &Authorization, chain_id: u64) -> bool {
+    (authorization.chain_id.is_zero() || authorization.chain_id == U256::from(chain_id))
+        && authorization.y_parity <= 1
+        && authorization.nonce.as_u64() < u64::MAX
+        && is_low_s(&authorization.s)
+}
+
+impl<F: Field> TxCircuit<F> {
+    /// Return the (tx id, authorization index, authorization) of every
+    /// authorization slot: the valid authorizations of the set-code txs,
+    /// followed by the padding slots, whose tx id is 0.
+    pub(super) fn auth_slots(&self) -> Vec<(usize, usize, Authorization)> {
+        let max_auths = self
+            .auth_sign_verify
+            .as_ref()
+            .map_or(0, |auth_sign_verify| auth_sign_verify.max_verif);
+        self.auths
+            .iter()
+            .map(|(tx_index, auth_index, _)| {
+                let authorization = self.txs[*tx_index]
+                    .authorization_list
+                    .as_ref()
+                    .and_then(|authorization_list| authorization_list.get(*auth_index))
+                    .expect("valid authorizations are in the authorization list of their tx");
+                (tx_index + 1, *auth_index, authorization.clone())
+            })
+            .chain(iter::repeat((0, 0, Authorization::default())))
+            .take(max_auths)
+            .collect()
+    }
+
+    /// Assign the signature verifications of the authorizations of the set-code txs, if
+    /// supported.
+    #[allow(clippy::type_complexity)]
+    pub(super) fn assign_auth_verifs(
+        &self,
+        config: &TxCircuitConfig<F>,
+        layouter: &mut impl Layouter<F>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<Vec<(AssignedSignatureVerify<F>, AssignedSignature<F>)>, Error> {
+        match (&self.auth_sign_verify, &config.auth_sign_verify) {
+            (Some(auth_sign_verify), Some(auth_sign_verify_config)) => auth_sign_verify
+                .assign_with_signatures(
+                    auth_sign_verify_config,
+                    layouter,
+                    &self
+                        .auths
+                        .iter()
+                        .map(|(_, _, sign_data)| Some(sign_data.clone()))
+                        .collect_vec(),
+                    challenges,
+                ),
+            (None, None) => Ok(vec![]),
+            _ => {
+                error!("EIP-7702 support of the TxCircuit doesn't match its config");
+                Err(Error::Synthesis)
+            }
+        }
+    }
+
+    /// Bind the chain_id, address and nonce of every authorization slot to the
+    /// message signed by its authority, given the cell of the chain_id of the
+    /// circuit, if set-code txs are supported.
+    pub(super) fn assign_auth_sign_hashes(
+        &self,
+        config: &TxCircuitConfig<F>,
+        layouter: &mut impl Layouter<F>,
+        assigned_auth_verifs: &[(AssignedSignatureVerify<F>, AssignedSignature<F>)],
+        chain_id_cell: &AssignedCell<F, F>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<Vec<AssignedAuthorization<F>>, Error> {
+        match (&self.auth_sign_verify, &config.auth_sign_hash) {
+            (Some(auth_sign_verify), Some(auth_sign_hash_config)) => {
+                AuthSignHashChip::new(auth_sign_verify.max_verif).assign(
+                    auth_sign_hash_config,
+                    layouter,
+                    &assigned_auth_verifs
+                        .iter()
+                        .map(|(assigned_sig_verif, _)| assigned_sig_verif)
+                        .collect_vec(),
+                    &self
+                        .auth_slots()
+                        .into_iter()
+                        .map(|(_, _, authorization)| authorization)
+                        .collect_vec(),
+                    chain_id_cell,
+                    challenges,
+                )
+            }
+            (None, None) => Ok(vec![]),
+            _ => {
+                error!("EIP-7702 support of the TxCircuit doesn't match its config");
+                Err(Error::Synthesis)
+            }
+        }
+    }
+
+    /// Assign the AuthTable, copying the signature of every authorization slot and the
+    /// authority recovered from it from the cells of the SignVerifyChip, and its chain_id,
+    /// address and nonce from the cells of the AuthSignHashChip.
+    pub(super) fn assign_auth_table(
+        &self,
+        auth_table: &AuthTable,
+        layouter: &mut impl Layouter<F>,
+        assigned_auth_verifs: &[(AssignedSignatureVerify<F>, AssignedSignature<F>)],
+        assigned_auths: &[AssignedAuthorization<F>],
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "auth table",
+            |mut region| {
+                // Empty entry
+                region.assign_fixed(
+                    || "q_enable",
+                    auth_table.q_enable,
+                    0,
+                    || Value::known(F::ZERO),
+                )?;
+                for column in <AuthTable as LookupTable<F>>::advice_columns(auth_table) {
+                    region.assign_advice(
+                        || "auth table empty row",
+                        column,
+                        0,
+                        || Value::known(F::ZERO),
+                    )?;
+                }
+                // Padding authorizations have all their fields set to zero.
+                for (
+                    i,
+                    ((tx_id, index, _), ((assigned_sig_verif, assigned_signature), assigned_auth)),
+                ) in self
+                    .auth_slots()
+                    .into_iter()
+                    .zip(assigned_auth_verifs.iter().zip(assigned_auths))
+                    .enumerate()
+                {
+                    let offset = i + 1;
+                    region.assign_fixed(
+                        || "q_enable",
+                        auth_table.q_enable,
+                        offset,
+                        || Value::known(F::ONE),
+                    )?;
+                    for (name, column, value) in [
+                        ("tx_id", auth_table.tx_id, tx_id as u64),
+                        ("index", auth_table.index, index as u64),
+                    ] {
+                        region.assign_advice(
+                            || name,
+                            column,
+                            offset,
+                            || Value::known(F::from(value)),
+                        )?;
+                    }
+                    for (name, cell, column) in [
+                        (
+                            "chain_id_lo",
+                            assigned_auth.chain_id.lo(),
+                            auth_table.chain_id.lo(),
+                        ),
+                        (
+                            "chain_id_hi",
+                            assigned_auth.chain_id.hi(),
+                            auth_table.chain_id.hi(),
+                        ),
+                        (
+                            "address_lo",
+                            assigned_auth.address.lo(),
+                            auth_table.address.lo(),
+                        ),
+                        (
+                            "address_hi",
+                            assigned_auth.address.hi(),
+                            auth_table.address.hi(),
+                        ),
+                        ("nonce", assigned_auth.nonce.clone(), auth_table.nonce),
+                        (
+                            "y_parity",
+                            assigned_sig_verif.recovery_id.clone(),
+                            auth_table.y_parity,
+                        ),
+                        ("sig_r_lo", assigned_signature.r.lo(), auth_table.sig_r.lo()),
+                        ("sig_r_hi", assigned_signature.r.hi(), auth_table.sig_r.hi()),
+                        ("sig_s_lo", assigned_signature.s.lo(), auth_table.sig_s.lo()),
+                        ("sig_s_hi", assigned_signature.s.hi(), auth_table.sig_s.hi()),
+                        (
+                            "sign_hash_lo",
+                            assigned_sig_verif.msg_hash.lo(),
+                            auth_table.sign_hash.lo(),
+                        ),
+                        (
+                            "sign_hash_hi",
+                            assigned_sig_verif.msg_hash.hi(),
+                            auth_table.sign_hash.hi(),
+                        ),
+                        (
+                            "authority_lo",
+                            assigned_sig_verif.address.lo(),
+                            auth_table.authority.lo(),
+                        ),
+                        (
+                            "authority_hi",
+                            assigned_sig_verif.address.hi(),
+                            auth_table.authority.hi(),
+                        ),
+                        (
+                            "is_padding",
+                            assigned_sig_verif.is_padding.clone(),
+                            auth_table.is_padding,
+                        ),
+                    ] {
+                        cell.copy_advice(|| name, &mut region, column, offset)?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
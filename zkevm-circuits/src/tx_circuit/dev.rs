@@ -1,30 +1,116 @@
 pub use super::TxCircuit;
 
 use crate::{
-    table::{KeccakTable, TxTable},
-    tx_circuit::{TxCircuitConfig, TxCircuitConfigArgs},
+    table::{AuthTable, BlockTable, KeccakTable, Sha256Table, Sha512Table, TxTable, UXTable},
+    tx_circuit::{
+        sign_verify::{Ed25519SignVerifyChip, Eip712DigestChip, RsaSignVerifyChip, SignVerifyChip},
+        TxCircuitConfig, TxCircuitConfigArgs,
+    },
     util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness::BlockContext,
 };
-use bus_mapping::circuit_input_builder::keccak_inputs_tx_circuit;
 use eth_types::Field;
 use halo2_proofs::{
     circuit::{Layouter, SimpleFloorPlanner},
     plonk::{Circuit, ConstraintSystem, Error},
 };
-use log::error;
+
+/// Tx Circuit configuration parameters
+pub struct TxCircuitParams {
+    /// Number of SignVerifyChip lanes
+    pub sign_verify_lanes: usize,
+    /// Support txs signed over secp256r1
+    pub sign_verify_secp256r1: bool,
+    /// Support txs signed with RSA-2048
+    pub rsa_sign_verify: bool,
+    /// Support txs signed with Ed25519
+    pub ed25519_sign_verify: bool,
+    /// Support txs signed as EIP-712 typed data
+    pub eip712_digest: bool,
+    /// Support EIP-7702 set-code txs
+    pub auth_sign_verify: bool,
+    /// Support OP-stack deposit txs
+    pub deposits: bool,
+}
+
+impl Default for TxCircuitParams {
+    fn default() -> Self {
+        Self {
+            sign_verify_lanes: 1,
+            sign_verify_secp256r1: false,
+            rsa_sign_verify: false,
+            ed25519_sign_verify: false,
+            eip712_digest: false,
+            auth_sign_verify: false,
+            deposits: false,
+        }
+    }
+}
 
 impl<F: Field> Circuit<F> for TxCircuit<F> {
-    type Config = (TxCircuitConfig<F>, Challenges, KeccakTable);
+    type Config = (
+        TxCircuitConfig<F>,
+        Challenges,
+        KeccakTable,
+        BlockTable,
+        Option<Sha256Table>,
+        Option<Sha512Table>,
+        UXTable<8>,
+        UXTable<16>,
+    );
     type FloorPlanner = SimpleFloorPlanner;
-    type Params = ();
+    type Params = TxCircuitParams;
 
     fn without_witnesses(&self) -> Self {
-        Self::default()
+        Self {
+            sign_verify_secp256r1: self
+                .sign_verify_secp256r1
+                .as_ref()
+                .map(|_| SignVerifyChip::default()),
+            rsa_sign_verify: self
+                .rsa_sign_verify
+                .as_ref()
+                .map(|_| RsaSignVerifyChip::default()),
+            ed25519_sign_verify: self
+                .ed25519_sign_verify
+                .as_ref()
+                .map(|_| Ed25519SignVerifyChip::default()),
+            eip712_digest: self
+                .eip712_digest
+                .as_ref()
+                .map(|_| Eip712DigestChip::default()),
+            auth_sign_verify: self
+                .auth_sign_verify
+                .as_ref()
+                .map(|_| SignVerifyChip::default()),
+            deposits: self.deposits,
+            ..Self::default().with_sign_verify_lanes(self.sign_verify.num_lanes)
+        }
     }
 
-    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+    fn params(&self) -> Self::Params {
+        TxCircuitParams {
+            sign_verify_lanes: self.sign_verify.num_lanes,
+            sign_verify_secp256r1: self.sign_verify_secp256r1.is_some(),
+            rsa_sign_verify: self.rsa_sign_verify.is_some(),
+            ed25519_sign_verify: self.ed25519_sign_verify.is_some(),
+            eip712_digest: self.eip712_digest.is_some(),
+            auth_sign_verify: self.auth_sign_verify.is_some(),
+            deposits: self.deposits,
+        }
+    }
+
+    fn configure_with_params(meta: &mut ConstraintSystem<F>, params: Self::Params) -> Self::Config {
         let tx_table = TxTable::construct(meta);
         let keccak_table = KeccakTable::construct(meta);
+        let block_table = BlockTable::construct(meta);
+        let sha256_table = params.rsa_sign_verify.then(|| Sha256Table::construct(meta));
+        let sha512_table = params
+            .ed25519_sign_verify
+            .then(|| Sha512Table::construct(meta));
+        let auth_table = params.auth_sign_verify.then(|| AuthTable::construct(meta));
+        let u8_table = UXTable::construct(meta);
+        let u16_table = UXTable::construct(meta);
         let challenges = Challenges::construct(meta);
 
         let config = {
@@ -34,29 +120,71 @@ impl<F: Field> Circuit<F> for TxCircuit<F> {
                 TxCircuitConfigArgs {
                     tx_table,
                     keccak_table: keccak_table.clone(),
+                    block_table: block_table.clone(),
+                    u8_table,
+                    u16_table,
+                    sign_verify_lanes: params.sign_verify_lanes,
+                    sign_verify_secp256r1: params.sign_verify_secp256r1,
+                    sha256_table: sha256_table.clone(),
+                    sha512_table: sha512_table.clone(),
+                    auth_table,
+                    eip712_digest: params.eip712_digest,
+                    deposits: params.deposits,
                     challenges,
                 },
             )
         };
 
-        (config, challenges, keccak_table)
+        (
+            config,
+            challenges,
+            keccak_table,
+            block_table,
+            sha256_table,
+            sha512_table,
+            u8_table,
+            u16_table,
+        )
+    }
+
+    fn configure(_meta: &mut ConstraintSystem<F>) -> Self::Config {
+        unreachable!();
     }
 
     fn synthesize(
         &self,
-        (config, challenges, keccak_table): Self::Config,
+        (
+            config,
+            challenges,
+            keccak_table,
+            block_table,
+            sha256_table,
+            sha512_table,
+            u8_table,
+            u16_table,
+        ): Self::Config,
         mut layouter: impl Layouter<F>,
     ) -> Result<(), Error> {
         let challenges = challenges.values(&mut layouter);
 
-        keccak_table.dev_load(
+        keccak_table.dev_load(&mut layouter, &self.keccak_inputs()?, &challenges)?;
+        block_table.load(
             &mut layouter,
-            &keccak_inputs_tx_circuit(&self.txs[..], self.chain_id).map_err(|e| {
-                error!("keccak_inputs_tx_circuit error: {:?}", e);
-                Error::Synthesis
-            })?,
-            &challenges,
+            &BlockContext {
+                chain_id: self.chain_id.into(),
+                gas_limit: self.gas_limit,
+                base_fee: self.base_fee,
+                ..Default::default()
+            },
         )?;
+        if let Some(sha256_table) = sha256_table {
+            sha256_table.dev_load(&mut layouter, &self.sha256_inputs()?, &challenges)?;
+        }
+        if let Some(sha512_table) = sha512_table {
+            sha512_table.dev_load(&mut layouter, &self.sha512_inputs()?, &challenges)?;
+        }
+        u8_table.load(&mut layouter)?;
+        u16_table.load(&mut layouter)?;
         self.synthesize_sub(&config, &challenges, &mut layouter)
     }
 }
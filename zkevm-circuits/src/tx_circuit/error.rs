@@ -0,0 +1,106 @@
+//! Errors of the TxCircuit inputs
+
+/// Errors for TxCircuit inputs that don't fit in the circuit parameters
+#[derive(Clone, Debug, thiserror::Error, PartialEq, Eq)]
+pub enum TxCircuitError {
+    /// There are more txs than tx slots
+    #[error("TooManyTxs(num_txs: {num_txs}, max_txs: {max_txs})")]
+    TooManyTxs {
+        /// Number of txs
+        num_txs: usize,
+        /// Max number of supported txs
+        max_txs: usize,
+    },
+    /// A tx which isn't a deposit is in a tx slot whose signature isn't
+    /// verified
+    #[error("UnverifiedSignedTx(tx_index: {tx_index}, max_signed_txs: {max_signed_txs})")]
+    UnverifiedSignedTx {
+        /// Index of the tx
+        tx_index: usize,
+        /// Number of leading tx slots whose signature is verified
+        max_signed_txs: usize,
+    },
+    /// The calldata of all the txs doesn't fit in the calldata section
+    #[error("TooMuchCalldata(calldata_len: {calldata_len}, max_calldata: {max_calldata})")]
+    TooMuchCalldata {
+        /// Total calldata length of the txs
+        calldata_len: usize,
+        /// Max number of supported calldata bytes
+        max_calldata: usize,
+    },
+    /// The addresses or the storage keys of the access lists of all the txs
+    /// don't fit in the access list sections
+    #[error("TooLargeAccessList(num_entries: {num_entries}, max_access_list: {max_access_list})")]
+    TooLargeAccessList {
+        /// Total number of addresses or of storage keys of the txs, whichever
+        /// is larger
+        num_entries: usize,
+        /// Max number of supported addresses and storage keys
+        max_access_list: usize,
+    },
+    /// The blob versioned hashes of all the txs don't fit in the blob hash
+    /// section
+    #[error(
+        "TooManyBlobHashes(num_blob_hashes: {num_blob_hashes}, max_blob_hashes: {max_blob_hashes})"
+    )]
+    TooManyBlobHashes {
+        /// Total number of blob versioned hashes of the txs
+        num_blob_hashes: usize,
+        /// Max number of supported blob versioned hashes
+        max_blob_hashes: usize,
+    },
+    /// The EIP-155 `v` of a tx is not signed for the chain_id of the circuit
+    #[error("InvalidChainId(tx_index: {tx_index}, v: {v}, chain_id: {chain_id})")]
+    InvalidChainId {
+        /// Index of the tx
+        tx_index: usize,
+        /// `v` of the tx signature
+        v: u64,
+        /// Chain ID of the circuit
+        chain_id: u64,
+    },
+    /// The sum of the gas limits of the txs exceeds the gas limit of the block
+    #[error("GasLimitExceeded(gas: {gas}, gas_limit: {gas_limit})")]
+    GasLimitExceeded {
+        /// Sum of the gas limits of the txs
+        gas: u128,
+        /// Gas limit of the block
+        gas_limit: u64,
+    },
+    /// The signature of a tx has s > n/2 while the circuit enforces low-s
+    #[error("HighS(tx_index: {tx_index})")]
+    HighS {
+        /// Index of the tx
+        tx_index: usize,
+    },
+    /// The authorizations of the set-code txs don't fit in the authorization
+    /// slots
+    #[error("TooManyAuthorizations(num_auths: {num_auths}, max_auths: {max_auths})")]
+    TooManyAuthorizations {
+        /// Total number of authorizations of the txs
+        num_auths: usize,
+        /// Max number of supported authorizations
+        max_auths: usize,
+    },
+    /// A set-code tx creates a contract, has no authorization or a `v` which
+    /// is not a y parity
+    #[error("InvalidSetCodeTx(tx_index: {tx_index})")]
+    InvalidSetCodeTx {
+        /// Index of the tx
+        tx_index: usize,
+    },
+    /// The fee caps of a tx are invalid: max_priority_fee_per_gas >
+    /// max_fee_per_gas, max_fee_per_gas < the base fee of the block, or
+    /// max_fee_per_gas >= 2^128
+    #[error("InvalidFeeCaps(tx_index: {tx_index})")]
+    InvalidFeeCaps {
+        /// Index of the tx
+        tx_index: usize,
+    },
+    /// A tx is a deposit while the circuit doesn't support deposits
+    #[error("DepositNotSupported(tx_index: {tx_index})")]
+    DepositNotSupported {
+        /// Index of the tx
+        tx_index: usize,
+    },
+}
@@ -0,0 +1,203 @@
+//! SignData of the txs, and their cache
+
+use super::{
+    sign_verify::{recovery_id, SignVerifyCurve},
+    TxCircuit,
+};
+use crate::util::word::Word;
+use eth_types::{
+    ed25519_types::Ed25519SignData, geth_types::Transaction, rsa_types::RsaSignData,
+    sign_types::SignData, Field, H256, U256,
+};
+use halo2_proofs::{
+    halo2curves::{ff::PrimeField, secp256r1::Secp256r1Affine},
+    plonk::Error,
+};
+use itertools::Itertools;
+use rayon::prelude::*;
+use std::{
+    collections::HashMap,
+    iter,
+    sync::{Arc, RwLock},
+};
+
+/// SignData of a tx, for the scheme of its signature
+#[derive(Clone, Debug)]
+pub(super) enum TxSignData {
+    Secp256k1(SignData),
+    Secp256r1(SignData<Secp256r1Affine>),
+    Rsa(RsaSignData),
+    Ed25519(Ed25519SignData),
+    /// secp256k1 signature of the EIP-712 digest of the struct hash, which is the TxSignHash
+    Eip712(SignData, [u8; 32]),
+    /// No signature, for a deposit tx: the slot is verified with the padding
+    /// signatures
+    Deposit,
+}
+
+/// SignData of every tx, split by signature scheme: the SignData of a tx is in
+/// the vector of its scheme, and the other vectors hold `None`.  The txs signed
+/// as EIP-712 typed data are in the secp256k1 vector, and their struct hash in
+/// the eip712 one.
+#[derive(Default)]
+pub(super) struct TxSignDatas {
+    pub(super) secp256k1: Vec<Option<SignData>>,
+    pub(super) secp256r1: Vec<Option<SignData<Secp256r1Affine>>>,
+    pub(super) rsa: Vec<Option<RsaSignData>>,
+    pub(super) ed25519: Vec<Option<Ed25519SignData>>,
+    pub(super) eip712: Vec<Option<[u8; 32]>>,
+}
+
+impl TxSignData {
+    /// Return the (TxSignHash lo, TxSignHash hi, recovery_id) of the signature.
+    pub(super) fn instance<F: Field>(&self) -> [F; 3] {
+        fn instance<F: Field, C: SignVerifyCurve>(sign_data: &SignData<C>) -> [F; 3] {
+            let msg_hash: Word<F> = Word::from(U256::from_little_endian(
+                sign_data.msg_hash.to_repr().as_ref(),
+            ));
+            [
+                msg_hash.lo(),
+                msg_hash.hi(),
+                F::from(recovery_id(sign_data) as u64),
+            ]
+        }
+        match self {
+            Self::Secp256k1(sign_data) => instance(sign_data),
+            Self::Secp256r1(sign_data) => instance(sign_data),
+            // The signed message is the TxSignHash, and RSA and Ed25519 signatures have no
+            // recovery id.
+            Self::Rsa(RsaSignData { msg, .. }) | Self::Ed25519(Ed25519SignData { msg, .. }) => {
+                let msg_hash: Word<F> = Word::from(U256::from_big_endian(msg));
+                [msg_hash.lo(), msg_hash.hi(), F::ZERO]
+            }
+            // A deposit slot is verified with the padding signature.
+            Self::Deposit => Self::Secp256k1(SignData::default()).instance(),
+            // The TxSignHash is the struct hash, not the signed digest.
+            Self::Eip712(sign_data, struct_hash) => {
+                let struct_hash: Word<F> = Word::from(U256::from_big_endian(struct_hash));
+                [
+                    struct_hash.lo(),
+                    struct_hash.hi(),
+                    F::from(recovery_id(sign_data) as u64),
+                ]
+            }
+        }
+    }
+}
+
+/// Cache of the SignData of the txs signed over secp256k1, keyed by chain_id
+/// and tx hash, so that the TxCircuits built again for the same txs, as done
+/// when a block is proven again, don't recover their public keys again.  Clones
+/// share the same entries.  The hash of a tx doesn't always commit to the
+/// chain_id of its SignData, so the chain_id is part of the key.
+#[derive(Clone, Debug, Default)]
+pub struct SignDataCache(Arc<RwLock<HashMap<(u64, H256), SignData>>>);
+
+impl SignDataCache {
+    /// Recover in parallel the SignData of the txs missing from the cache, and
+    /// insert them.  The txs whose signature can't be recovered are skipped.
+    pub fn warm(&self, chain_id: u64, txs: &[Transaction]) {
+        let missing = {
+            let cache = self.0.read().expect("SignDataCache lock poisoned");
+            txs.iter()
+                .map(|tx| ((chain_id, tx.hash(chain_id)), tx))
+                .filter(|(key, _)| !cache.contains_key(key))
+                .collect_vec()
+        };
+        let sign_datas = missing
+            .into_par_iter()
+            .filter_map(|(key, tx)| Some((key, tx.sign_data(chain_id).ok()?)))
+            .collect::<Vec<_>>();
+        self.0
+            .write()
+            .expect("SignDataCache lock poisoned")
+            .extend(sign_datas);
+    }
+
+    /// Return the SignData of `tx`, recovering it and inserting it in the
+    /// cache when it's missing.
+    pub fn sign_data(&self, chain_id: u64, tx: &Transaction) -> Result<SignData, eth_types::Error> {
+        let key = (chain_id, tx.hash(chain_id));
+        if let Some(sign_data) = self
+            .0
+            .read()
+            .expect("SignDataCache lock poisoned")
+            .get(&key)
+        {
+            return Ok(sign_data.clone());
+        }
+        let sign_data = tx.sign_data(chain_id)?;
+        self.0
+            .write()
+            .expect("SignDataCache lock poisoned")
+            .insert(key, sign_data.clone());
+        Ok(sign_data)
+    }
+
+    /// Return the number of cached SignData
+    pub fn len(&self) -> usize {
+        self.0.read().expect("SignDataCache lock poisoned").len()
+    }
+
+    /// Return true if the cache holds no SignData
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<F: Field> TxCircuit<F> {
+    /// Return the SignData of the txs of the first `max_signed_txs` slots, split by signature
+    /// scheme.  The txs after them are deposits, which are not verified.
+    pub(super) fn sign_datas(&self) -> Result<TxSignDatas, Error> {
+        let mut sign_datas = TxSignDatas::default();
+        for sign_data in self.sign_datas.iter().take(self.max_signed_txs) {
+            let (secp256k1, secp256r1, rsa, ed25519, eip712) = match sign_data {
+                Some(TxSignData::Secp256k1(sign_data)) => {
+                    (Some(sign_data.clone()), None, None, None, None)
+                }
+                Some(TxSignData::Secp256r1(sign_data)) => {
+                    (None, Some(sign_data.clone()), None, None, None)
+                }
+                Some(TxSignData::Rsa(sign_data)) => {
+                    (None, None, Some(sign_data.clone()), None, None)
+                }
+                Some(TxSignData::Ed25519(sign_data)) => {
+                    (None, None, None, Some(sign_data.clone()), None)
+                }
+                Some(TxSignData::Eip712(sign_data, struct_hash)) => (
+                    Some(sign_data.clone()),
+                    None,
+                    None,
+                    None,
+                    Some(*struct_hash),
+                ),
+                Some(TxSignData::Deposit) => (None, None, None, None, None),
+                None => return Err(Error::Synthesis),
+            };
+            sign_datas.secp256k1.push(secp256k1);
+            sign_datas.secp256r1.push(secp256r1);
+            sign_datas.rsa.push(rsa);
+            sign_datas.ed25519.push(ed25519);
+            sign_datas.eip712.push(eip712);
+        }
+        Ok(sign_datas)
+    }
+
+    /// Return the (TxSignHash lo, TxSignHash hi, recovery_id) of every tx
+    /// slot, followed by the chain_id.  Padding slots hold the values of the
+    /// dummy signature verified by the secp256k1 SignVerifyChip.
+    pub(super) fn tx_signatures_instance(&self) -> Result<Vec<F>, Error> {
+        let padding = TxSignData::Secp256k1(SignData::default());
+        Ok(self
+            .sign_datas
+            .iter()
+            .map(|sign_data| sign_data.as_ref().ok_or(Error::Synthesis))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .chain(iter::repeat(&padding))
+            .take(self.max_txs)
+            .flat_map(|sign_data| sign_data.instance())
+            .chain(iter::once(F::from(self.chain_id)))
+            .collect())
+    }
+}
@@ -1,4 +1,5 @@
-//! Circuit to verify multiple ECDSA secp256k1 signatures.
+//! Circuit to verify multiple ECDSA signatures over secp256k1 or secp256r1, RSA-2048 signatures
+//! and Ed25519 signatures.
 
 // Naming notes:
 // - *_be: Big-Endian bytes
@@ -12,21 +13,26 @@ use crate::{
     table::KeccakTable,
     util::{word::Word, Challenges, Expr},
 };
-use ecc::{maingate, EccConfig, GeneralEccChip};
-use ecdsa::ecdsa::{AssignedEcdsaSig, AssignedPublicKey, EcdsaChip};
+use ecc::{maingate, AssignedPoint, EccConfig, GeneralEccChip};
+use ecdsa::ecdsa::{AssignedEcdsaSig, AssignedPublicKey};
 use eth_types::{
     self, keccak256,
     sign_types::{pk_bytes_le, pk_bytes_swap_endianness, SignData},
-    Field,
+    Field, U256,
 };
 use halo2_proofs::{
     arithmetic::CurveAffine,
-    circuit::{AssignedCell, Cell, Layouter, Value},
+    circuit::{
+        layouter::{RegionLayouter, RegionShape},
+        AssignedCell, Cell, Layouter, Value,
+    },
     halo2curves::{
+        bn256::G1Affine,
         ff::PrimeField,
         group::{Curve, Group},
-        secp256k1,
         secp256k1::Secp256k1Affine,
+        secp256r1::Secp256r1Affine,
+        Coordinates,
     },
     plonk::{Advice, Column, ConstraintSystem, Error, Expression, SecondPhase, Selector},
     poly::Rotation,
@@ -42,23 +48,45 @@ use maingate::{
     RangeInstructions, RegionCtx,
 };
 use num::Integer;
-use std::{iter, marker::PhantomData};
+use std::{iter, marker::PhantomData, ops};
+
+mod authorization;
+mod ed25519;
+mod eip712;
+mod rsa;
+mod weierstrass;
+
+pub use authorization::AuthSignHashChip;
+pub(crate) use authorization::{AssignedAuthorization, AuthSignHashConfig};
+pub use ed25519::Ed25519SignVerifyChip;
+pub(crate) use ed25519::Ed25519SignVerifyConfig;
+pub use eip712::Eip712DigestChip;
+pub(crate) use eip712::Eip712DigestConfig;
+pub use rsa::RsaSignVerifyChip;
+pub(crate) use rsa::{assign_bytes_le, assigned_term, AssignedRsaInteger, RsaSignVerifyConfig};
+pub use weierstrass::Glv;
+pub(crate) use weierstrass::{Base, Point, Scalar, WeierstrassChip, WeierstrassConstants};
 
 /// Auxiliary Gadget to verify a that a message hash is signed by the public
-/// key corresponding to an Ethereum Address.
+/// key corresponding to an Ethereum Address, over the curve `C`.
 #[derive(Clone, Debug)]
-pub struct SignVerifyChip<F: Field> {
-    /// Aux generator for EccChip
-    pub aux_generator: Secp256k1Affine,
-    /// Window size for EccChip
+pub struct SignVerifyChip<F: Field, C: SignVerifyCurve = Secp256k1Affine> {
+    /// Aux generator of the scalar multiplications
+    pub aux_generator: C,
+    /// Window size of the scalar multiplications
     pub window_size: usize,
     /// Max number of verifications
     pub max_verif: usize,
+    /// Number of lanes the verifications are split into.  Must match the
+    /// number of lanes of the SignVerifyConfig.
+    pub num_lanes: usize,
+    /// Constrain the signatures to have s <= n/2 (EIP-2)
+    pub low_s: bool,
     /// Marker
     pub _marker: PhantomData<F>,
 }
 
-impl<F: Field> SignVerifyChip<F> {
+impl<F: Field, C: SignVerifyCurve> SignVerifyChip<F, C> {
     /// Return a new SignVerifyChip
     pub fn new(max_verif: usize) -> Self {
         // TODO: Investigate if it is safe to use a random point as aux generator that
@@ -66,57 +94,285 @@ impl<F: Field> SignVerifyChip<F> {
         // EccChip to calculate an aux generator using the challenge API.
         // https://github.com/privacy-scaling-explorations/halo2wrong/issues/53
         let mut rng = ChaCha20Rng::seed_from_u64(0);
-        let aux_generator =
-            <Secp256k1Affine as CurveAffine>::CurveExt::random(&mut rng).to_affine();
+        let aux_generator = C::CurveExt::random(&mut rng).to_affine();
         Self {
             aux_generator,
             window_size: 4,
             max_verif,
+            num_lanes: 1,
+            low_s: false,
             _marker: PhantomData,
         }
     }
 
+    /// Return the aux generator of the scalar multiplications
+    pub(crate) fn aux_generator(&self) -> C {
+        self.aux_generator
+    }
+
+    /// Reject malleable signatures by constraining s <= n/2, as required by
+    /// EIP-2 for txs.
+    pub fn with_low_s(mut self) -> Self {
+        self.low_s = true;
+        self
+    }
+
+    /// Split the verifications into `num_lanes` lanes laid out side by side.
+    /// Each lane uses its own set of columns, so more lanes trade columns for
+    /// rows.
+    pub fn with_num_lanes(mut self, num_lanes: usize) -> Self {
+        assert!(num_lanes > 0, "SignVerifyChip needs at least one lane");
+        self.num_lanes = num_lanes;
+        self
+    }
+
     /// Return the minimum number of rows required to prove an input of a
-    /// particular size.
-    pub fn min_num_rows(num_verif: usize) -> usize {
-        // The values rows_ecc_chip_aux, rows_ecdsa_chip_verification and
-        // rows_ecdsa_chip_verification have been obtained from log debugs while running
-        // the tx circuit with max_txs=1. For example:
-        // `RUST_LOG=debug RUST_BACKTRACE=1 cargo test tx_circuit_1tx_1max_tx --release
-        // --all-features -- --nocapture`
-        // The value rows_range_chip_table has been obtained by patching the halo2
-        // library to report the number of rows used in the range chip table
-        // region. TODO: Figure out a way to get these numbers automatically.
-        let rows_range_chip_table = 295188;
-        let rows_ecc_chip_aux = 226;
-        let rows_ecdsa_chip_verification = 104471;
-        let rows_signature_address_verify = 76;
+    /// particular size with the verifications split into `num_lanes` lanes.
+    pub fn min_num_rows(num_verif: usize, num_lanes: usize) -> usize {
+        let (rows_ecdsa_constants, rows_per_verif) = Self::count_rows_ecdsa();
         std::cmp::max(
-            rows_range_chip_table,
-            (rows_ecc_chip_aux + rows_ecdsa_chip_verification + rows_signature_address_verify)
-                * num_verif,
+            Self::rows_range_chip_table(),
+            rows_ecdsa_constants + rows_per_verif * Integer::div_ceil(&num_verif, &num_lanes),
+        )
+    }
+
+    /// Return the number of rows of the RangeChip lookup table, which holds
+    /// one row per value of every distinct bit length it range checks.
+    pub fn rows_range_chip_table() -> usize {
+        let (composition_bit_lens, overflow_bit_lens) = range_chip_bit_lens::<F, C>();
+        iter::empty()
+            .chain(composition_bit_lens)
+            .chain(overflow_bit_lens)
+            .sorted()
+            .dedup()
+            .map(|bit_len| 1 << bit_len)
+            .sum()
+    }
+
+    /// Return the number of rows used by the constants shared by the verifications of a lane.
+    pub fn rows_ecdsa_constants() -> usize {
+        Self::count_rows_ecdsa().0
+    }
+
+    /// Return the number of rows used by one signature verification, including the low-s
+    /// check.
+    pub fn rows_per_verif() -> usize {
+        Self::count_rows_ecdsa().1
+    }
+
+    /// Lay out the constants and one padding verification of a lane with the default window
+    /// size, and return the number of rows used by the constants and by the verification.  The
+    /// layout doesn't depend on the witness, so every verification uses as many rows as the
+    /// padding one.
+    fn count_rows_ecdsa() -> (usize, usize) {
+        let mut meta = ConstraintSystem::<F>::default();
+        let keccak_table = KeccakTable::construct(&mut meta);
+        let challenges = Challenges::construct(&mut meta).exprs(&mut meta);
+        let config = SignVerifyLaneConfig::new::<F, C>(&mut meta, &keccak_table, &challenges);
+
+        let chip = Self::default().with_low_s();
+        let main_gate = MainGate::new(config.main_gate_config.clone());
+        let range_chip = RangeChip::new(config.range_config.clone());
+        let ecc_chip =
+            GeneralEccChip::<C, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>::new(config.ecc_chip_config());
+        let chips = ChipsRef {
+            main_gate: &main_gate,
+            range_chip: &range_chip,
+            ecc_chip: &ecc_chip,
+            scalar_chip: ecc_chip.scalar_field_chip(),
+        };
+        let sign_data = chip.padding_sign_data();
+
+        let (rows_constants, _) = count_rows(|ctx| chip.assign_ecdsa_constants(ctx, &chips));
+        let (rows_ecdsa, assigned_ecdsa) = count_rows(|ctx| {
+            let constants = chip.assign_ecdsa_constants(ctx, &chips)?;
+            chip.assign_ecdsa(ctx, &chips, &constants, &sign_data)
+        });
+        let (rows_signature_address_verify, _) = count_rows(|ctx| {
+            chip.assign_signature_verify(
+                &config,
+                ctx,
+                &chips,
+                None,
+                &assigned_ecdsa,
+                &Challenges::mock(Value::unknown(), Value::unknown()),
+            )
+        });
+        (
+            rows_constants,
+            rows_ecdsa - rows_constants + rows_signature_address_verify,
         )
     }
+
+    /// Return the signature verified by the padding slots: keccak lookup disabled, address ==
+    /// 0.  With the low-s check, s is flipped so that the padding signature passes it.
+    fn padding_sign_data(&self) -> SignData<C> {
+        let mut signature = SignData::padding();
+        if self.low_s {
+            // (r, n - s) is a valid signature as well
+            let (_, sig_s) = &mut signature.signature;
+            let s = eth_types::Word::from_little_endian(sig_s.to_repr().as_ref());
+            if !C::is_low_s(&s) {
+                *sig_s = -*sig_s;
+            }
+        }
+        signature
+    }
 }
 
-impl<F: Field> Default for SignVerifyChip<F> {
+impl<F: Field, C: SignVerifyCurve> Default for SignVerifyChip<F, C> {
     fn default() -> Self {
-        Self {
-            aux_generator: Secp256k1Affine::default(),
-            window_size: 4,
-            max_verif: 0,
-            _marker: PhantomData::default(),
-        }
+        // The WeierstrassChip assigns the aux generator as a constant, so the circuit without
+        // witnesses must use the same one.
+        Self::new(0)
     }
 }
 
-const NUMBER_OF_LIMBS: usize = 4;
-const BIT_LEN_LIMB: usize = 72;
+pub(crate) const NUMBER_OF_LIMBS: usize = 4;
+pub(crate) const BIT_LEN_LIMB: usize = 72;
 const BIT_LEN_LAST_LIMB: usize = 256 - (NUMBER_OF_LIMBS - 1) * BIT_LEN_LIMB;
 
-/// SignVerify Configuration
+/// Curve over which the SignVerifyChip verifies ECDSA signatures.
+pub trait SignVerifyCurve: CurveAffine {
+    /// Higher 128 bits of (n - 1) / 2, where n is the order of the curve
+    const HALF_N_HI: u128;
+    /// Lower 128 bits of (n - 1) / 2, where n is the order of the curve
+    const HALF_N_LO: u128;
+
+    /// Return true if `s` is at most n/2, where n is the order of the curve.
+    fn is_low_s(s: &eth_types::Word) -> bool {
+        let half_n = (eth_types::Word::from(Self::HALF_N_HI) << 128)
+            | eth_types::Word::from(Self::HALF_N_LO);
+        *s <= half_n
+    }
+
+    /// Return the GLV endomorphism of the curve, if it has one, to split the scalars of the
+    /// scalar multiplications in halves.
+    fn glv() -> Option<Glv<Self>> {
+        None
+    }
+}
+
+impl SignVerifyCurve for Secp256k1Affine {
+    const HALF_N_HI: u128 = 0x7fffffffffffffffffffffffffffffff;
+    const HALF_N_LO: u128 = 0x5d576e7357a4501ddfe92f46681b20a0;
+
+    fn glv() -> Option<Glv<Self>> {
+        Some(Glv::from_hex(
+            "7ae96a2b657c07106e64479eac3434e99cf0497512f58995c1396c28719501ee",
+            "5363ad4cc05c30e0a5261c028812645a122e22ea20816678df02967c1b23bd72",
+            [
+                (
+                    "3086d221a7d46bcde86c90e49284eb15",
+                    "-e4437ed6010e88286f547fa90abfe4c3",
+                ),
+                (
+                    "114ca50f7a8e2f3f657c1108d9d44cfd8",
+                    "3086d221a7d46bcde86c90e49284eb15",
+                ),
+            ],
+        ))
+    }
+}
+
+impl SignVerifyCurve for Secp256r1Affine {
+    const HALF_N_HI: u128 = 0x7fffffff800000007fffffffffffffff;
+    const HALF_N_LO: u128 = 0xde737d56d38bcf4279dce5617e3192a8;
+}
+
+/// BN254 G1, whose point arithmetic the ECC circuit shares with the ECDSA
+/// verifications.
+impl SignVerifyCurve for G1Affine {
+    const HALF_N_HI: u128 = 0x183227397098d014dc2822db40c0ac2e;
+    const HALF_N_LO: u128 = 0x9419f4243cdcb848a1f0fac9f8000000;
+}
+
+/// Return the least significant byte of the y coordinate of
+/// R = u1 * G + u2 * pk, where u1 = msg_hash / s and u2 = r / s.
+fn r_y_byte_0<C: CurveAffine>(sign_data: &SignData<C>) -> u8 {
+    let (r, s) = sign_data.signature;
+    let s_inv = Option::<C::ScalarExt>::from(s.invert()).expect("s should be non-zero");
+    let r_point =
+        (C::generator() * (sign_data.msg_hash * s_inv) + sign_data.pk * (r * s_inv)).to_affine();
+    let r_point = Option::<Coordinates<_>>::from(r_point.coordinates()).expect("R is the identity");
+    r_point.y().to_repr().as_ref()[0]
+}
+
+/// Return the recovery id of a signature: the parity of R.y.  For EIP-155
+/// txs, it's v - 35 - 2 * chain_id.
+pub(crate) fn recovery_id<C: CurveAffine>(sign_data: &SignData<C>) -> u8 {
+    r_y_byte_0(sign_data) & 1
+}
+
+/// Return true if `s` is at most n/2, where n is the order of secp256k1.
+pub fn is_low_s(s: &eth_types::Word) -> bool {
+    <Secp256k1Affine as SignVerifyCurve>::is_low_s(s)
+}
+
+/// Return the composition bit lengths and the overflow bit lengths range
+/// checked by the RangeChip of a lane verifying signatures over `C`.
+pub(crate) fn range_chip_bit_lens<F: Field, C: CurveAffine>() -> (Vec<usize>, Vec<usize>) {
+    let (rns_base, rns_scalar) = GeneralEccChip::<C, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>::rns();
+    (
+        vec![BIT_LEN_LIMB / NUMBER_OF_LIMBS, 8],
+        [rns_base.overflow_lengths(), rns_scalar.overflow_lengths()].concat(),
+    )
+}
+
+/// SignVerify Configuration, for signatures over `C`
+#[derive(Debug, Clone)]
+pub(crate) struct SignVerifyConfig<C: SignVerifyCurve = Secp256k1Affine> {
+    lanes: Vec<SignVerifyLaneConfig>,
+    _keccak_table: KeccakTable,
+    _marker: PhantomData<C>,
+}
+
+impl<C: SignVerifyCurve> SignVerifyConfig<C> {
+    /// Configure `num_lanes` lanes, each with its own columns, so that the
+    /// signature verifications can be laid out side by side.
+    pub(crate) fn new<F: Field>(
+        meta: &mut ConstraintSystem<F>,
+        keccak_table: KeccakTable,
+        challenges: Challenges<Expression<F>>,
+        num_lanes: usize,
+    ) -> Self {
+        assert!(num_lanes > 0, "SignVerifyConfig needs at least one lane");
+        let lanes = (0..num_lanes)
+            .map(|_| SignVerifyLaneConfig::new::<F, C>(meta, &keccak_table, &challenges))
+            .collect();
+
+        Self {
+            lanes,
+            _keccak_table: keccak_table,
+            _marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn num_lanes(&self) -> usize {
+        self.lanes.len()
+    }
+
+    /// Return the MainGate config of the first lane, to constrain values computed from the
+    /// assigned verifications.
+    pub(crate) fn main_gate_config(&self) -> MainGateConfig {
+        self.lanes[0].main_gate_config.clone()
+    }
+
+    /// Return the RangeChip config of the first lane.
+    pub(crate) fn range_config(&self) -> RangeConfig {
+        self.lanes[0].range_config.clone()
+    }
+
+    /// Return the RLC column of the first lane, which has equality enabled.
+    pub(crate) fn rlc_column(&self) -> Column<Advice> {
+        self.lanes[0].rlc
+    }
+}
+
+/// Configuration of a lane of the SignVerifyChip: the ECDSA chips, and the RLC and keccak lookup
+/// of the public key.  Every lane has its own MainGate, with its own instance column.
 #[derive(Debug, Clone)]
-pub(crate) struct SignVerifyConfig {
+pub(crate) struct SignVerifyLaneConfig {
     // ECDSA
     main_gate_config: MainGateConfig,
     range_config: RangeConfig,
@@ -125,24 +381,22 @@ pub(crate) struct SignVerifyConfig {
     rlc: Column<Advice>,
     // Keccak
     q_keccak: Selector,
-    _keccak_table: KeccakTable,
 }
 
-impl SignVerifyConfig {
-    pub(crate) fn new<F: Field>(
+impl SignVerifyLaneConfig {
+    fn new<F: Field, C: CurveAffine>(
         meta: &mut ConstraintSystem<F>,
-        keccak_table: KeccakTable,
-        challenges: Challenges<Expression<F>>,
+        keccak_table: &KeccakTable,
+        challenges: &Challenges<Expression<F>>,
     ) -> Self {
-        // ECDSA config
-        let (rns_base, rns_scalar) =
-            GeneralEccChip::<Secp256k1Affine, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>::rns();
+        // ECDSA config.  The range table depends on the RNS of the curve fields.
         let main_gate_config = MainGate::<F>::configure(meta);
+        let (composition_bit_lens, overflow_bit_lens) = range_chip_bit_lens::<F, C>();
         let range_config = RangeChip::<F>::configure(
             meta,
             &main_gate_config,
-            vec![BIT_LEN_LIMB / NUMBER_OF_LIMBS, 8],
-            [rns_base.overflow_lengths(), rns_scalar.overflow_lengths()].concat(),
+            composition_bit_lens,
+            overflow_bit_lens,
         );
 
         // RLC
@@ -163,36 +417,21 @@ impl SignVerifyConfig {
         // by keccak table lookup, where pub_key_bytes is built from the pub_key
         // in the ecdsa_chip.
         let q_keccak = meta.complex_selector();
-        meta.lookup_any("keccak", |meta| {
-            // When address is 0, we disable the signature verification by using a dummy pk,
-            // msg_hash and signature which is not constrained to match msg_hash nor the address.
-            // Layout:
-            // | q_keccak |        a        |    b     |     c     |   rlc   |
-            // | -------- | --------------- |--------- | --------- | ------- |
-            // |     1    |   is_addr_zero  | word_lo  |  word_hi  |  pk_rlc |
-            let q_keccak = meta.query_selector(q_keccak);
-            let is_address_zero = meta.query_advice(main_gate_config.advices()[0], Rotation::cur());
-            let is_enable = q_keccak * not::expr(is_address_zero);
-            let word_lo = meta.query_advice(main_gate_config.advices()[1], Rotation::cur());
-            let word_hi = meta.query_advice(main_gate_config.advices()[2], Rotation::cur());
-            let input = [
-                is_enable.clone(),
-                is_enable.clone() * meta.query_advice(rlc, Rotation::cur()),
-                is_enable.clone() * 64usize.expr(),
-                is_enable.clone() * word_lo,
-                is_enable * word_hi,
-            ];
-            let table = [
+        Self::configure_lookup(
+            meta,
+            "keccak",
+            &main_gate_config,
+            q_keccak,
+            rlc,
+            64,
+            [
                 keccak_table.is_enabled,
                 keccak_table.input_rlc,
                 keccak_table.input_len,
                 keccak_table.output.lo(),
                 keccak_table.output.hi(),
-            ]
-            .map(|column| meta.query_advice(column, Rotation::cur()));
-
-            input.into_iter().zip(table).collect()
-        });
+            ],
+        );
 
         Self {
             range_config,
@@ -200,12 +439,49 @@ impl SignVerifyConfig {
             q_rlc_keccak_input,
             rlc,
             q_keccak,
-            _keccak_table: keccak_table.clone(),
         }
     }
 
+    /// Configure the lookup of a hash of `input_len` bytes into a table with the layout of the
+    /// KeccakTable: `[is_enabled, input_rlc, input_len, output_lo, output_hi]`.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn configure_lookup<F: Field>(
+        meta: &mut ConstraintSystem<F>,
+        name: &'static str,
+        main_gate_config: &MainGateConfig,
+        q_lookup: Selector,
+        rlc: Column<Advice>,
+        input_len: usize,
+        table: [Column<Advice>; 5],
+    ) {
+        meta.lookup_any(name, |meta| {
+            // For padding slots, we disable the signature verification by using a dummy pk,
+            // msg_hash and signature which is not constrained to match msg_hash nor the address.
+            // The address of a padding slot is constrained to be 0.
+            // Layout:
+            // | q_lookup |        a        |    b     |     c     |    rlc    |
+            // | -------- | --------------- |--------- | --------- | --------- |
+            // |     1    |    is_padding   | word_lo  |  word_hi  | input_rlc |
+            let q_lookup = meta.query_selector(q_lookup);
+            let is_padding = meta.query_advice(main_gate_config.advices()[0], Rotation::cur());
+            let is_enable = q_lookup * not::expr(is_padding);
+            let word_lo = meta.query_advice(main_gate_config.advices()[1], Rotation::cur());
+            let word_hi = meta.query_advice(main_gate_config.advices()[2], Rotation::cur());
+            let input = [
+                is_enable.clone(),
+                is_enable.clone() * meta.query_advice(rlc, Rotation::cur()),
+                is_enable.clone() * input_len.expr(),
+                is_enable.clone() * word_lo,
+                is_enable * word_hi,
+            ];
+            let table = table.map(|column| meta.query_advice(column, Rotation::cur()));
+
+            input.into_iter().zip(table).collect()
+        });
+    }
+
     #[rustfmt::skip]
-    fn configure_rlc<F: Field>(
+    pub(crate) fn configure_rlc<F: Field>(
         meta: &mut ConstraintSystem<F>,
         name: &'static str,
         main_gate_config: MainGateConfig,
@@ -236,15 +512,67 @@ impl SignVerifyConfig {
     }
 }
 
-impl SignVerifyConfig {
+impl<C: SignVerifyCurve> SignVerifyConfig<C> {
     pub(crate) fn load_range<F: Field>(
         &self,
         layouter: &mut impl Layouter<F>,
     ) -> Result<(), Error> {
-        let range_chip = RangeChip::<F>::new(self.range_config.clone());
-        range_chip.load_table(layouter)
+        for lane in &self.lanes {
+            let range_chip = RangeChip::<F>::new(lane.range_config.clone());
+            range_chip.load_table(layouter)?;
+        }
+        Ok(())
+    }
+
+    /// Merge, slot by slot, the verifications assigned by this chip with the ones assigned by
+    /// a chip over another curve, so that every slot can be signed over either curve.  At most
+    /// one of the two verifications of a slot can be non padding, and the merged one is the non
+    /// padding one if any.  The merged slot is padding if both verifications are.
+    pub(crate) fn assign_merged_signature_verifies<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        assigned_sig_verifs: Vec<AssignedSignatureVerify<F>>,
+        other_assigned_sig_verifs: Vec<AssignedSignatureVerify<F>>,
+    ) -> Result<Vec<AssignedSignatureVerify<F>>, Error> {
+        let main_gate = MainGate::new(self.lanes[0].main_gate_config.clone());
+        layouter.assign_region(
+            || "merge signature verifications",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+                assigned_sig_verifs
+                    .iter()
+                    .zip_eq(&other_assigned_sig_verifs)
+                    .map(|(assigned, other)| {
+                        let is_real = main_gate.not(&mut ctx, &assigned.is_padding)?;
+                        let is_other_real = main_gate.not(&mut ctx, &other.is_padding)?;
+                        let are_both_real = main_gate.and(&mut ctx, &is_real, &is_other_real)?;
+                        main_gate.assert_zero(&mut ctx, &are_both_real)?;
+
+                        let is_padding =
+                            main_gate.and(&mut ctx, &assigned.is_padding, &other.is_padding)?;
+                        let mut select = |value: &AssignedValue<F>, other: &AssignedValue<F>| {
+                            main_gate.select(&mut ctx, other, value, &is_other_real)
+                        };
+                        Ok(AssignedSignatureVerify {
+                            is_padding,
+                            recovery_id: select(&assigned.recovery_id, &other.recovery_id)?,
+                            address: Word::new([
+                                select(&assigned.address.lo(), &other.address.lo())?,
+                                select(&assigned.address.hi(), &other.address.hi())?,
+                            ]),
+                            msg_hash: Word::new([
+                                select(&assigned.msg_hash.lo(), &other.msg_hash.lo())?,
+                                select(&assigned.msg_hash.hi(), &other.msg_hash.hi())?,
+                            ]),
+                        })
+                    })
+                    .collect()
+            },
+        )
     }
+}
 
+impl SignVerifyLaneConfig {
     pub(crate) fn ecc_chip_config(&self) -> EccConfig {
         EccConfig::new(self.range_config.clone(), self.main_gate_config.clone())
     }
@@ -265,7 +593,7 @@ pub(crate) enum Term<F> {
 }
 
 impl<F: Field> Term<F> {
-    fn assigned(cell: Cell, value: Value<F>) -> Self {
+    pub(crate) fn assigned(cell: Cell, value: Value<F>) -> Self {
         Self::Assigned(cell, value)
     }
 
@@ -285,17 +613,32 @@ impl<F: Field> Term<F> {
 }
 
 pub(crate) struct AssignedECDSA<F: Field> {
+    recovery_id: AssignedValue<F>,
     pk_x_le: [AssignedValue<F>; 32],
     pk_y_le: [AssignedValue<F>; 32],
     msg_hash_le: [AssignedValue<F>; 32],
+    sig_r_le: [AssignedValue<F>; 32],
+    sig_s_le: [AssignedValue<F>; 32],
 }
 
 #[derive(Debug)]
 pub(crate) struct AssignedSignatureVerify<F: Field> {
+    pub(crate) is_padding: AssignedValue<F>,
+    pub(crate) recovery_id: AssignedValue<F>,
     pub(crate) address: Word<AssignedValue<F>>,
     pub(crate) msg_hash: Word<AssignedValue<F>>,
 }
 
+/// Cells of the signature (r, s) of an ECDSA verification, and of the
+/// coordinates of its public key
+#[derive(Debug)]
+pub(crate) struct AssignedSignature<F: Field> {
+    pub(crate) r: Word<AssignedValue<F>>,
+    pub(crate) s: Word<AssignedValue<F>>,
+    pub(crate) pk_x: Word<AssignedValue<F>>,
+    pub(crate) pk_y: Word<AssignedValue<F>>,
+}
+
 // Return an array of bytes that corresponds to the little endian representation
 // of the integer, adding the constraints to verify the correctness of the
 // conversion (byte range check included).
@@ -320,32 +663,225 @@ fn integer_to_bytes_le<F: Field, FE: PrimeField>(
     Ok(bytes.try_into().unwrap())
 }
 
+/// Return the value composed from little endian byte cells, which are expected to be range
+/// checked already.  At most 16 bytes can be composed.
+fn compose_bytes_le<F: Field>(
+    ctx: &mut RegionCtx<F>,
+    main_gate: &MainGate<F>,
+    bytes: &[AssignedValue<F>],
+) -> Result<AssignedValue<F>, Error> {
+    assert!(bytes.len() <= 16, "can't compose more than 16 bytes");
+    let powers_of_256 = iter::successors(Some(F::ONE), |coeff| Some(F::from(256) * coeff));
+    let (cell, _) = main_gate.decompose(
+        ctx,
+        &bytes
+            .iter()
+            .zip(powers_of_256)
+            .map(|(cell, coeff)| maingate::Term::Assigned(cell, coeff))
+            .collect_vec(),
+        F::ZERO,
+        |_, _| Ok(()),
+    )?;
+    Ok(cell)
+}
+
+/// Assign the hash of a public key, given in little endian bytes, and the address built from
+/// its first 20 bytes.  Padding slots skip the hash lookup of the public key, so their address
+/// is forced to 0.  Return the is_padding bit, the address and the hash.
+#[allow(clippy::type_complexity)]
+fn assign_address<F: Field>(
+    ctx: &mut RegionCtx<F>,
+    main_gate: &MainGate<F>,
+    range_chip: &RangeChip<F>,
+    padding: bool,
+    pk_hash: &[u8; 32],
+) -> Result<
+    (
+        AssignedValue<F>,
+        Word<AssignedValue<F>>,
+        Word<AssignedValue<F>>,
+    ),
+    Error,
+> {
+    // Diagram of byte decomposition of little-endian pk_hash, and how address is built
+    // from it:
+    //
+    // byte 0             15 16           20 21   32
+    //      [ address_lo   ] [ address_hi  ] [     ]
+    //      [ pk_hash_lo   ] [ pk_hash_hi          ]
+
+    let pk_hash_lo_bytes = &pk_hash[..16];
+    let pk_hash_hi_bytes = &pk_hash[16..];
+    let pk_hash_lo = from_bytes::value::<F>(pk_hash_lo_bytes);
+    let pk_hash_hi = from_bytes::value::<F>(pk_hash_hi_bytes);
+    // Assign all bytes of pk_hash to cells which are range constrained to be 8 bits.  Then
+    // constrain the lower 16 cell bytes to build the lo cell, and the higher 16 bytes to
+    // build the hi cell.
+    let (pk_hash_cell_lo, pk_hash_lo_cell_bytes) =
+        range_chip.decompose(ctx, Value::known(pk_hash_lo), 8, 128)?;
+    let (pk_hash_cell_hi, pk_hash_hi_cell_bytes) =
+        range_chip.decompose(ctx, Value::known(pk_hash_hi), 8, 128)?;
+
+    // Take the 20 lowest assigned byte cells of pk_hash and constrain them to build
+    // address. From the lower 16 build the lo cell, and from the higher 4 build the hi
+    // cell.
+    let address_cell_lo = compose_bytes_le(ctx, main_gate, &pk_hash_lo_cell_bytes)?;
+    let address_cell_hi = compose_bytes_le(
+        ctx,
+        main_gate,
+        &pk_hash_hi_cell_bytes[..N_BYTES_ACCOUNT_ADDRESS - 16],
+    )?;
+    let address_cells = Word::new([address_cell_lo, address_cell_hi]);
+
+    let is_padding = main_gate.assign_bit(ctx, Value::known(F::from(padding as u64)))?;
+    let iz_zero_hi = main_gate.is_zero(ctx, &address_cells.hi())?;
+    let iz_zero_lo = main_gate.is_zero(ctx, &address_cells.lo())?;
+    let is_address_zero = main_gate.and(ctx, &iz_zero_lo, &iz_zero_hi)?;
+    let is_address_non_zero = main_gate.not(ctx, &is_address_zero)?;
+    let is_padding_with_address = main_gate.and(ctx, &is_padding, &is_address_non_zero)?;
+    main_gate.assert_zero(ctx, &is_padding_with_address)?;
+
+    Ok((
+        is_padding,
+        address_cells,
+        Word::new([pk_hash_cell_lo, pk_hash_cell_hi]),
+    ))
+}
+
+/// Enable a hash lookup laid out as the keccak lookup of `SignVerifyLaneConfig`: copy
+/// is_padding and the output word to the first three MainGate advice columns and the input RLC
+/// to the RLC column.
+pub(crate) fn enable_lookup<F: Field>(
+    ctx: &mut RegionCtx<F>,
+    q_lookup: Selector,
+    main_gate_config: &MainGateConfig,
+    rlc_column: Column<Advice>,
+    is_padding: &AssignedCell<F, F>,
+    input_rlc: &AssignedCell<F, F>,
+    output: &Word<AssignedCell<F, F>>,
+) -> Result<(), Error> {
+    let copy = |ctx: &mut RegionCtx<F>, name, column, assigned: &AssignedCell<F, F>| {
+        let copied = ctx.assign_advice(|| name, column, assigned.value().copied())?;
+        ctx.constrain_equal(assigned.cell(), copied.cell())?;
+        Ok::<_, Error>(())
+    };
+
+    ctx.enable(q_lookup)?;
+    copy(ctx, "is_padding", main_gate_config.advices()[0], is_padding)?;
+    copy(ctx, "input_rlc", rlc_column, input_rlc)?;
+    copy(
+        ctx,
+        "output_lo",
+        main_gate_config.advices()[1],
+        &output.lo(),
+    )?;
+    copy(
+        ctx,
+        "output_hi",
+        main_gate_config.advices()[2],
+        &output.hi(),
+    )?;
+    ctx.next();
+
+    Ok(())
+}
+
+/// Assign the RLC of `inputs_le` in big endian order, laying out the inputs in the MainGate
+/// advice columns five by five and enabling the `q_rlc` gate configured by
+/// `SignVerifyLaneConfig::configure_rlc`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn assign_rlc_le<F: Field>(
+    ctx: &mut RegionCtx<F>,
+    main_gate: &MainGate<F>,
+    main_gate_config: &MainGateConfig,
+    rlc_column: Column<Advice>,
+    name: &str,
+    q_rlc: Selector,
+    challenge: Value<F>,
+    inputs_le: impl IntoIterator<Item = Term<F>>,
+) -> Result<AssignedCell<F, F>, Error> {
+    let zero = main_gate.assign_constant(ctx, F::ZERO)?;
+    let columns = main_gate_config.advices();
+    let inputs_le = inputs_le.into_iter().collect_vec();
+    let inputs_be = iter::repeat_with(|| Term::assigned(zero.cell(), Value::known(F::ZERO)))
+        .take(Integer::next_multiple_of(&inputs_le.len(), &columns.len()) - inputs_le.len())
+        .chain(inputs_le.into_iter().rev())
+        .collect_vec();
+
+    let mut rlc = Value::known(F::ZERO);
+    for (chunk_idx, chunk) in inputs_be.chunks_exact(columns.len()).enumerate() {
+        ctx.enable(q_rlc)?;
+        let assigned_rlc = ctx.assign_advice(|| "{name}_rlc[{chunk_idx}]", rlc_column, rlc)?;
+        for ((idx, column), term) in (chunk_idx * chunk.len()..).zip(columns).zip(chunk) {
+            let copied =
+                ctx.assign_advice(|| format!("{name}_byte[{idx}]"), column, term.value())?;
+            if let Some(cell) = term.cell() {
+                ctx.constrain_equal(cell, copied.cell())?;
+            }
+        }
+        if chunk_idx == 0 {
+            ctx.constrain_equal(zero.cell(), assigned_rlc.cell())?;
+        }
+        rlc = iter::once(rlc)
+            .chain(chunk.iter().map(|term| term.value()))
+            .fold(Value::known(F::ZERO), |acc, input| acc * challenge + input);
+        ctx.next();
+    }
+
+    let assigned_rlc = ctx.assign_advice(|| "{name}_rlc", rlc_column, rlc)?;
+    ctx.next();
+
+    Ok(assigned_rlc)
+}
+
 /// Helper structure pass around references to all the chips required for an
 /// ECDSA verification.
-struct ChipsRef<'a, F: Field, const NUMBER_OF_LIMBS: usize, const BIT_LEN_LIMB: usize> {
+struct ChipsRef<
+    'a,
+    F: Field,
+    C: CurveAffine,
+    const NUMBER_OF_LIMBS: usize,
+    const BIT_LEN_LIMB: usize,
+> {
     main_gate: &'a MainGate<F>,
     range_chip: &'a RangeChip<F>,
-    ecc_chip: &'a GeneralEccChip<Secp256k1Affine, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
-    scalar_chip: &'a IntegerChip<secp256k1::Fq, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
-    ecdsa_chip: &'a EcdsaChip<Secp256k1Affine, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+    ecc_chip: &'a GeneralEccChip<C, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+    scalar_chip: &'a IntegerChip<C::ScalarExt, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
 }
 
-impl<F: Field> SignVerifyChip<F> {
-    fn assign_aux(
+/// Return the number of rows of a region assigned by `assign`, and the output of `assign`.  The
+/// region is laid out in a `RegionShape`, which records the rows used by the assignment without
+/// assigning any value, so that row counts follow the layout of the chips.
+pub(crate) fn count_rows<F: Field, T>(
+    assign: impl FnOnce(&mut RegionCtx<'_, F>) -> Result<T, Error>,
+) -> (usize, T) {
+    let mut shape = RegionShape::new(0.into());
+    let output = {
+        let region: &mut dyn RegionLayouter<F> = &mut shape;
+        let mut ctx = RegionCtx::new(region.into(), 0);
+        assign(&mut ctx).expect("regions are laid out without witnesses")
+    };
+    (shape.row_count(), output)
+}
+
+impl<F: Field, C: SignVerifyCurve> SignVerifyChip<F, C> {
+    /// Assign the constants shared by the ECDSA verifications of a region, once before the
+    /// first verification instead of once per verification.
+    fn assign_ecdsa_constants(
         &self,
-        ctx: &mut RegionCtx<'_, F>,
-        ecc_chip: &mut GeneralEccChip<Secp256k1Affine, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
-    ) -> Result<(), Error> {
-        ecc_chip.assign_aux_generator(ctx, Value::known(self.aux_generator))?;
-        ecc_chip.assign_aux(ctx, self.window_size, 2)?;
-        Ok(())
+        ctx: &mut RegionCtx<F>,
+        chips: &ChipsRef<F, C, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+    ) -> Result<WeierstrassConstants<C, F>, Error> {
+        WeierstrassChip::new(chips.main_gate, chips.ecc_chip)
+            .assign_constants(ctx, self.aux_generator)
     }
 
     fn assign_ecdsa(
         &self,
         ctx: &mut RegionCtx<F>,
-        chips: &ChipsRef<F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
-        sign_data: &SignData,
+        chips: &ChipsRef<F, C, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+        constants: &WeierstrassConstants<C, F>,
+        sign_data: &SignData<C>,
     ) -> Result<AssignedECDSA<F>, Error> {
         let SignData {
             signature,
@@ -355,11 +891,10 @@ impl<F: Field> SignVerifyChip<F> {
         let (sig_r, sig_s) = signature;
 
         let ChipsRef {
-            main_gate: _,
+            main_gate,
             range_chip,
             ecc_chip,
             scalar_chip,
-            ecdsa_chip,
         } = chips;
 
         let integer_r = ecc_chip.new_unassigned_scalar(Value::known(*sig_r));
@@ -373,133 +908,203 @@ impl<F: Field> SignVerifyChip<F> {
             s: s_assigned,
         };
 
-        let pk_in_circuit = ecc_chip.assign_point(ctx, Value::known(*pk))?;
+        let pk_in_circuit = WeierstrassChip::new(main_gate, ecc_chip).assign_point(
+            ctx,
+            constants,
+            Value::known(*pk),
+        )?;
         let pk_assigned = AssignedPublicKey {
             point: pk_in_circuit,
         };
         let msg_hash = scalar_chip.assign_integer(ctx, msg_hash, Range::Remainder)?;
 
-        // Convert (msg_hash, pk_x, pk_y) integers to little endian bytes
+        // Convert (msg_hash, pk_x, pk_y) integers to little endian bytes.  The coordinates of
+        // the public key are constrained to be canonical, so that the bytes are the ones of the
+        // point and not of an equivalent value modulo p.
         let msg_hash_le = integer_to_bytes_le(ctx, range_chip, &msg_hash)?;
+        let base_chip = ecc_chip.base_field_chip();
         let pk_x = pk_assigned.point.x();
+        base_chip.assert_in_field(ctx, pk_x)?;
         let pk_x_le = integer_to_bytes_le(ctx, range_chip, pk_x)?;
         let pk_y = pk_assigned.point.y();
+        base_chip.assert_in_field(ctx, pk_y)?;
         let pk_y_le = integer_to_bytes_le(ctx, range_chip, pk_y)?;
 
         // Ref. spec SignVerifyChip 4. Verify the ECDSA signature
-        ecdsa_chip.verify(ctx, &sig, &pk_assigned, &msg_hash)?;
+        let s_inv = Option::<C::ScalarExt>::from(sig_s.invert()).expect("s should be non-zero");
+        let u_values = (
+            Value::known(sign_data.msg_hash * s_inv),
+            Value::known(*sig_r * s_inv),
+        );
+        let r_point = self.verify_ecdsa(
+            ctx,
+            chips,
+            constants,
+            &sig,
+            &pk_assigned,
+            &msg_hash,
+            u_values,
+        )?;
+
+        // Ref. spec SignVerifyChip 5. Verify that the recovery id is the parity of R.y, so that
+        // pk is the key recovered from (r, s, v) and not the one recovered from (r, s, !v).
+        let recovery_id = {
+            let r_y = base_chip.reduce(ctx, r_point.y())?;
+            base_chip.assert_in_field(ctx, &r_y)?;
+            let r_y_le = integer_to_bytes_le(ctx, range_chip, &r_y)?;
+
+            let r_y_byte_0 = r_y_byte_0(sign_data);
+            let recovery_id =
+                main_gate.assign_bit(ctx, Value::known(F::from((r_y_byte_0 & 1) as u64)))?;
+            let (r_y_byte_0_half, _) =
+                range_chip.decompose(ctx, Value::known(F::from((r_y_byte_0 >> 1) as u64)), 8, 8)?;
+            // r_y_le[0] = 2 * r_y_byte_0_half + recovery_id
+            let r_y_byte_0 = main_gate.compose(
+                ctx,
+                &[
+                    maingate::Term::Assigned(&r_y_byte_0_half, F::from(2)),
+                    maingate::Term::Assigned(&recovery_id, F::ONE),
+                ],
+                F::ZERO,
+            )?;
+            main_gate.assert_equal(ctx, &r_y_byte_0, &r_y_le[0])?;
+            recovery_id
+        };
+
+        // Convert the signature (r, s) to little endian bytes.  Both are constrained to be
+        // canonical, so that the bytes are the ones of the signature and not of an equivalent
+        // value modulo n.
+        scalar_chip.assert_in_field(ctx, &sig.r)?;
+        scalar_chip.assert_in_field(ctx, &sig.s)?;
+        let sig_r_le = integer_to_bytes_le(ctx, range_chip, &sig.r)?;
+        let sig_s_le = integer_to_bytes_le(ctx, range_chip, &sig.s)?;
+
+        if self.low_s {
+            self.assert_low_s(ctx, chips, &sig_s_le, sig_s)?;
+        }
 
         // TODO: Update once halo2wrong suports the following methods:
         // - `IntegerChip::assign_integer_from_bytes_le`
         // - `GeneralEccChip::assing_point_from_bytes_le`
 
         Ok(AssignedECDSA {
+            recovery_id,
             pk_x_le,
             pk_y_le,
             msg_hash_le,
+            sig_r_le,
+            sig_s_le,
         })
     }
 
+    /// Verify the ECDSA signature as `EcdsaChip::verify` does, and return the
+    /// point R = u1 * G + u2 * pk whose x coordinate is r (mod n).  `u_values` are the native
+    /// values of (u1, u2), which the scalar multiplication splits by the GLV endomorphism.
     #[allow(clippy::too_many_arguments)]
-    fn assign_rlc_le(
+    fn verify_ecdsa(
         &self,
-        config: &SignVerifyConfig,
         ctx: &mut RegionCtx<F>,
-        chips: &ChipsRef<F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
-        name: &str,
-        q_rlc: Selector,
-        challenge: Value<F>,
-        inputs_le: impl IntoIterator<Item = Term<F>>,
-    ) -> Result<AssignedCell<F, F>, Error> {
-        let zero = chips.main_gate.assign_constant(ctx, F::ZERO)?;
-        let columns = config.main_gate_config.advices();
-        let inputs_le = inputs_le.into_iter().collect_vec();
-        let inputs_be = iter::repeat_with(|| Term::assigned(zero.cell(), Value::known(F::ZERO)))
-            .take(Integer::next_multiple_of(&inputs_le.len(), &columns.len()) - inputs_le.len())
-            .chain(inputs_le.into_iter().rev())
-            .collect_vec();
-
-        let mut rlc = Value::known(F::ZERO);
-        for (chunk_idx, chunk) in inputs_be.chunks_exact(columns.len()).enumerate() {
-            ctx.enable(q_rlc)?;
-            let assigned_rlc = ctx.assign_advice(|| "{name}_rlc[{chunk_idx}]", config.rlc, rlc)?;
-            for ((idx, column), term) in (chunk_idx * chunk.len()..).zip(columns).zip(chunk) {
-                let copied =
-                    ctx.assign_advice(|| format!("{name}_byte[{idx}]"), column, term.value())?;
-                if let Some(cell) = term.cell() {
-                    ctx.constrain_equal(cell, copied.cell())?;
-                }
-            }
-            if chunk_idx == 0 {
-                ctx.constrain_equal(zero.cell(), assigned_rlc.cell())?;
-            }
-            rlc = iter::once(rlc)
-                .chain(chunk.iter().map(|term| term.value()))
-                .fold(Value::known(F::ZERO), |acc, input| acc * challenge + input);
-            ctx.next();
-        }
-
-        let assigned_rlc = ctx.assign_advice(|| "{name}_rlc", config.rlc, rlc)?;
-        ctx.next();
+        chips: &ChipsRef<F, C, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+        constants: &WeierstrassConstants<C, F>,
+        sig: &AssignedEcdsaSig<C::ScalarExt, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+        pk: &AssignedPublicKey<C::Base, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+        msg_hash: &AssignedInteger<C::ScalarExt, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+        u_values: (Value<C::ScalarExt>, Value<C::ScalarExt>),
+    ) -> Result<AssignedPoint<C::Base, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>, Error> {
+        let ecc_chip = chips.ecc_chip;
+        let scalar_chip = chips.scalar_chip;
+        let base_chip = ecc_chip.base_field_chip();
+
+        // 0 < r, s < n
+        scalar_chip.assert_not_zero(ctx, &sig.r)?;
+        scalar_chip.assert_not_zero(ctx, &sig.s)?;
+        // u1 = msg_hash / s, u2 = r / s (mod n)
+        let (s_inv, _) = scalar_chip.invert(ctx, &sig.s)?;
+        let u1 = scalar_chip.mul(ctx, msg_hash, &s_inv)?;
+        let u2 = scalar_chip.mul(ctx, &sig.r, &s_inv)?;
+        // R = u1 * G + u2 * pk
+        let (u1_value, u2_value) = u_values;
+        let r_point = WeierstrassChip::new(chips.main_gate, ecc_chip).mul_batch(
+            ctx,
+            constants,
+            self.window_size,
+            vec![
+                (constants.generator.clone(), u1, u1_value),
+                (pk.point.clone(), u2, u2_value),
+            ],
+        )?;
+        // R.x == r (mod n)
+        let r_x = base_chip.reduce(ctx, r_point.x())?;
+        let r_x = scalar_chip.reduce_external(ctx, &r_x)?;
+        scalar_chip.assert_strict_equal(ctx, &r_x, &sig.r)?;
 
-        Ok(assigned_rlc)
+        Ok(r_point)
     }
 
-    fn enable_keccak_lookup(
+    /// Constrain the integer with little endian bytes `s_le` to be at most n/2
+    /// (EIP-2), by subtracting its 128-bit halves from the halves of n/2 and
+    /// range checking the differences.
+    fn assert_low_s(
         &self,
-        config: &SignVerifyConfig,
         ctx: &mut RegionCtx<F>,
-        is_address_zero: &AssignedCell<F, F>,
-        pk_rlc: &AssignedCell<F, F>,
-        pk_hash: &Word<AssignedCell<F, F>>,
+        chips: &ChipsRef<F, C, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+        s_le: &[AssignedValue<F>; 32],
+        s: &C::ScalarExt,
     ) -> Result<(), Error> {
-        let copy = |ctx: &mut RegionCtx<F>, name, column, assigned: &AssignedCell<F, F>| {
-            let copied = ctx.assign_advice(|| name, column, assigned.value().copied())?;
-            ctx.constrain_equal(assigned.cell(), copied.cell())?;
-            Ok::<_, Error>(())
-        };
+        let main_gate = chips.main_gate;
+        let range_chip = chips.range_chip;
 
-        ctx.enable(config.q_keccak)?;
-        copy(
+        let s_lo = compose_bytes_le(ctx, main_gate, &s_le[..16])?;
+        let s_hi = compose_bytes_le(ctx, main_gate, &s_le[16..])?;
+
+        let s_lo_value = u128::from_le_bytes(s.to_repr().as_ref()[..16].try_into().unwrap());
+        let borrow = main_gate.assign_bit(
             ctx,
-            "is_address_zero",
-            config.main_gate_config.advices()[0],
-            is_address_zero,
+            Value::known(F::from((s_lo_value > C::HALF_N_LO) as u64)),
         )?;
-        copy(ctx, "pk_rlc", config.rlc, pk_rlc)?;
-        copy(
+        let pow_2_128 = F::from_u128(u128::MAX) + F::ONE;
+        // diff_lo = half_n_lo - s_lo + borrow * 2^128
+        let diff_lo = main_gate.compose(
             ctx,
-            "pk_hash_lo",
-            config.main_gate_config.advices()[1],
-            &pk_hash.lo(),
+            &[
+                maingate::Term::Assigned(&s_lo, -F::ONE),
+                maingate::Term::Assigned(&borrow, pow_2_128),
+            ],
+            F::from_u128(C::HALF_N_LO),
         )?;
-        copy(
+        // diff_hi = half_n_hi - s_hi - borrow
+        let diff_hi = main_gate.compose(
             ctx,
-            "pk_hash_hi",
-            config.main_gate_config.advices()[2],
-            &pk_hash.hi(),
+            &[
+                maingate::Term::Assigned(&s_hi, -F::ONE),
+                maingate::Term::Assigned(&borrow, -F::ONE),
+            ],
+            F::from_u128(C::HALF_N_HI),
         )?;
-        ctx.next();
-
+        // Both differences are non-negative iff s <= n/2
+        for diff in [diff_lo, diff_hi] {
+            let (range_checked, _) = range_chip.decompose(ctx, diff.value().copied(), 8, 128)?;
+            main_gate.assert_equal(ctx, &diff, &range_checked)?;
+        }
         Ok(())
     }
 
     #[allow(clippy::too_many_arguments)]
     fn assign_signature_verify(
         &self,
-        config: &SignVerifyConfig,
+        config: &SignVerifyLaneConfig,
         ctx: &mut RegionCtx<F>,
-        chips: &ChipsRef<F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
-        sign_data: Option<&SignData>,
+        chips: &ChipsRef<F, C, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+        sign_data: Option<&SignData<C>>,
         assigned_ecdsa: &AssignedECDSA<F>,
         challenges: &Challenges<Value<F>>,
-    ) -> Result<AssignedSignatureVerify<F>, Error> {
+    ) -> Result<(AssignedSignatureVerify<F>, AssignedSignature<F>), Error> {
         let main_gate = chips.main_gate;
         let range_chip = chips.range_chip;
 
         let (padding, sign_data) = match sign_data {
             Some(sign_data) => (false, sign_data.clone()),
-            None => (true, SignData::default()),
+            None => (true, SignData::padding()),
         };
 
         let pk_le = pk_bytes_le(&sign_data.pk);
@@ -507,94 +1112,34 @@ impl<F: Field> SignVerifyChip<F> {
         let mut pk_hash = (!padding).then(|| keccak256(&pk_be)).unwrap_or_default();
         pk_hash.reverse();
 
-        let powers_of_256 = iter::successors(Some(F::ONE), |coeff| Some(F::from(256) * coeff))
-            .take(16)
-            .collect_vec();
-
         // Ref. spec SignVerifyChip 2. Verify that the first 20 bytes of the
         // pub_key_hash equal the address
-        let (address_cells, pk_hash_cells) = {
-            // Diagram of byte decomposition of little-endian pk_hash, and how address is built
-            // from it:
-            //
-            // byte 0             15 16           20 21   32
-            //      [ address_lo   ] [ address_hi  ] [     ]
-            //      [ pk_hash_lo   ] [ pk_hash_hi          ]
-
-            let pk_hash_lo_bytes = &pk_hash[..16];
-            let pk_hash_hi_bytes = &pk_hash[16..];
-            let pk_hash_lo = from_bytes::value::<F>(pk_hash_lo_bytes);
-            let pk_hash_hi = from_bytes::value::<F>(pk_hash_hi_bytes);
-            // Assign all bytes of pk_hash to cells which are range constrained to be 8 bits.  Then
-            // constrain the lower 16 cell bytes to build the lo cell, and the higher 16 bytes to
-            // build the hi cell.
-            let (pk_hash_cell_lo, pk_hash_lo_cell_bytes) =
-                range_chip.decompose(ctx, Value::known(pk_hash_lo), 8, 128)?;
-            let (pk_hash_cell_hi, pk_hash_hi_cell_bytes) =
-                range_chip.decompose(ctx, Value::known(pk_hash_hi), 8, 128)?;
-
-            // Take the 20 lowest assigned byte cells of pk_hash and constrain them to build
-            // address. From the lower 16 build the lo cell, and from the higher 4 build the hi
-            // cell.
-            let (address_cell_lo, _) = main_gate.decompose(
-                ctx,
-                &pk_hash_lo_cell_bytes
-                    .iter()
-                    .zip_eq(&powers_of_256)
-                    .map(|(cell, coeff)| maingate::Term::Assigned(cell, *coeff))
-                    .collect_vec(),
-                F::ZERO,
-                |_, _| Ok(()),
-            )?;
-            let (address_cell_hi, _) = main_gate.decompose(
-                ctx,
-                &pk_hash_hi_cell_bytes
-                    .iter()
-                    .take(N_BYTES_ACCOUNT_ADDRESS - 16)
-                    .zip(&powers_of_256)
-                    .map(|(cell, coeff)| maingate::Term::Assigned(cell, *coeff))
-                    .collect_vec(),
-                F::ZERO,
-                |_, _| Ok(()),
-            )?;
-
-            (
-                Word::new([address_cell_lo, address_cell_hi]),
-                Word::new([pk_hash_cell_lo, pk_hash_cell_hi]),
-            )
-        };
-
-        let iz_zero_hi = main_gate.is_zero(ctx, &address_cells.hi())?;
-        let iz_zero_lo = main_gate.is_zero(ctx, &address_cells.lo())?;
-        let is_address_zero = main_gate.and(ctx, &iz_zero_lo, &iz_zero_hi)?;
+        let (is_padding, address_cells, pk_hash_cells) =
+            assign_address(ctx, main_gate, range_chip, padding, &pk_hash)?;
 
         // Ref. spec SignVerifyChip 3. Verify that the signed message in the ecdsa_chip
         // corresponds to msg_hash
-        let msg_hash_cells = {
-            let msg_hash_lo_cell_bytes = &assigned_ecdsa.msg_hash_le[..16];
-            let msg_hash_hi_cell_bytes = &assigned_ecdsa.msg_hash_le[16..];
-            let (msg_hash_cell_lo, _) = main_gate.decompose(
-                ctx,
-                &msg_hash_lo_cell_bytes
-                    .iter()
-                    .zip_eq(&powers_of_256)
-                    .map(|(cell, coeff)| maingate::Term::Assigned(cell, *coeff))
-                    .collect_vec(),
-                F::ZERO,
-                |_, _| Ok(()),
-            )?;
-            let (msg_hash_cell_hi, _) = main_gate.decompose(
-                ctx,
-                &msg_hash_hi_cell_bytes
-                    .iter()
-                    .zip_eq(&powers_of_256)
-                    .map(|(cell, coeff)| maingate::Term::Assigned(cell, *coeff))
-                    .collect_vec(),
-                F::ZERO,
-                |_, _| Ok(()),
-            )?;
-
-            Word::new([msg_hash_cell_lo, msg_hash_cell_hi])
+        let msg_hash_cells = Word::new([
+            compose_bytes_le(ctx, main_gate, &assigned_ecdsa.msg_hash_le[..16])?,
+            compose_bytes_le(ctx, main_gate, &assigned_ecdsa.msg_hash_le[16..])?,
+        ]);
+        let signature_cells = AssignedSignature {
+            r: Word::new([
+                compose_bytes_le(ctx, main_gate, &assigned_ecdsa.sig_r_le[..16])?,
+                compose_bytes_le(ctx, main_gate, &assigned_ecdsa.sig_r_le[16..])?,
+            ]),
+            s: Word::new([
+                compose_bytes_le(ctx, main_gate, &assigned_ecdsa.sig_s_le[..16])?,
+                compose_bytes_le(ctx, main_gate, &assigned_ecdsa.sig_s_le[16..])?,
+            ]),
+            pk_x: Word::new([
+                compose_bytes_le(ctx, main_gate, &assigned_ecdsa.pk_x_le[..16])?,
+                compose_bytes_le(ctx, main_gate, &assigned_ecdsa.pk_x_le[16..])?,
+            ]),
+            pk_y: Word::new([
+                compose_bytes_le(ctx, main_gate, &assigned_ecdsa.pk_y_le[..16])?,
+                compose_bytes_le(ctx, main_gate, &assigned_ecdsa.pk_y_le[16..])?,
+            ]),
         };
 
         let pk_rlc = {
@@ -602,13 +1147,14 @@ impl<F: Field> SignVerifyChip<F> {
                 .chain(&assigned_ecdsa.pk_y_le)
                 .chain(&assigned_ecdsa.pk_x_le);
             let pk_le = iter::empty()
-                .chain(sign_data.pk.y.to_bytes())
-                .chain(sign_data.pk.x.to_bytes())
-                .map(|byte| Value::known(F::from(byte as u64)));
-            self.assign_rlc_le(
-                config,
+                .chain(&pk_le[32..])
+                .chain(&pk_le[..32])
+                .map(|byte| Value::known(F::from(*byte as u64)));
+            assign_rlc_le(
                 ctx,
-                chips,
+                main_gate,
+                &config.main_gate_config,
+                config.rlc,
                 "pk_hash",
                 config.q_rlc_keccak_input,
                 challenges.keccak_input(),
@@ -618,20 +1164,52 @@ impl<F: Field> SignVerifyChip<F> {
             )?
         };
 
-        self.enable_keccak_lookup(config, ctx, &is_address_zero, &pk_rlc, &pk_hash_cells)?;
-        Ok(AssignedSignatureVerify {
-            address: address_cells,
-            msg_hash: msg_hash_cells,
-        })
+        enable_lookup(
+            ctx,
+            config.q_keccak,
+            &config.main_gate_config,
+            config.rlc,
+            &is_padding,
+            &pk_rlc,
+            &pk_hash_cells,
+        )?;
+        Ok((
+            AssignedSignatureVerify {
+                is_padding,
+                recovery_id: assigned_ecdsa.recovery_id.clone(),
+                address: address_cells,
+                msg_hash: msg_hash_cells,
+            },
+            signature_cells,
+        ))
     }
 
+    /// Assign the verification of `signatures[i]` to the slot `i`.  Slots past the end of
+    /// `signatures`, or whose signature is `None`, are padding.
     pub(crate) fn assign(
         &self,
-        config: &SignVerifyConfig,
+        config: &SignVerifyConfig<C>,
         layouter: &mut impl Layouter<F>,
-        signatures: &[SignData],
+        signatures: &[Option<SignData<C>>],
         challenges: &Challenges<Value<F>>,
     ) -> Result<Vec<AssignedSignatureVerify<F>>, Error> {
+        Ok(self
+            .assign_with_signatures(config, layouter, signatures, challenges)?
+            .into_iter()
+            .map(|(assigned_sig_verif, _)| assigned_sig_verif)
+            .collect())
+    }
+
+    /// Assign the verifications as [`Self::assign`] does, and also return the cells of the
+    /// signature (r, s) of every slot.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn assign_with_signatures(
+        &self,
+        config: &SignVerifyConfig<C>,
+        layouter: &mut impl Layouter<F>,
+        signatures: &[Option<SignData<C>>],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<Vec<(AssignedSignatureVerify<F>, AssignedSignature<F>)>, Error> {
         if signatures.len() > self.max_verif {
             error!(
                 "signatures.len() = {} > max_verif = {}",
@@ -640,47 +1218,110 @@ impl<F: Field> SignVerifyChip<F> {
             );
             return Err(Error::Synthesis);
         }
-        let main_gate = MainGate::new(config.main_gate_config.clone());
-        let range_chip = RangeChip::new(config.range_config.clone());
-        let mut ecc_chip = GeneralEccChip::<Secp256k1Affine, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>::new(
-            config.ecc_chip_config(),
-        );
-        let cloned_ecc_chip = ecc_chip.clone();
-        let scalar_chip = cloned_ecc_chip.scalar_field_chip();
+        if config.num_lanes() != self.num_lanes {
+            error!(
+                "config.num_lanes() = {} != num_lanes = {}",
+                config.num_lanes(),
+                self.num_lanes
+            );
+            return Err(Error::Synthesis);
+        }
+
+        // Lane `l` verifies the signatures of the slots [l * verifs_per_lane, (l + 1) *
+        // verifs_per_lane).  The regions of different lanes don't share columns, so the floor
+        // planner lays them out side by side.
+        let verifs_per_lane = Integer::div_ceil(&self.max_verif, &self.num_lanes);
+        let mut assigned_sig_verifs = Vec::with_capacity(self.max_verif);
+        for (lane_idx, lane) in config.lanes.iter().enumerate() {
+            let start = (lane_idx * verifs_per_lane).min(self.max_verif);
+            let end = (start + verifs_per_lane).min(self.max_verif);
+            assigned_sig_verifs.extend(self.assign_lane(
+                lane,
+                layouter,
+                start..end,
+                signatures,
+                challenges,
+            )?);
+        }
+        Ok(assigned_sig_verifs)
+    }
 
+    /// Assign the verifications of `num_slots` slots which the chip doesn't verify, as their txs
+    /// can only be padding or deposits.  Their cells are constants equal to the ones of a padding
+    /// verification, shared by all the slots, so that they cost a few rows in total instead of a
+    /// full ECDSA verification each.
+    pub(crate) fn assign_unverified(
+        &self,
+        config: &SignVerifyConfig<C>,
+        layouter: &mut impl Layouter<F>,
+        num_slots: usize,
+    ) -> Result<Vec<AssignedSignatureVerify<F>>, Error> {
+        if num_slots == 0 {
+            return Ok(vec![]);
+        }
+        let main_gate = MainGate::new(config.main_gate_config());
+        let padding_sign_data = self.padding_sign_data();
+        let msg_hash: Word<F> = Word::from(U256::from_little_endian(
+            padding_sign_data.msg_hash.to_repr().as_ref(),
+        ));
         layouter.assign_region(
-            || "ecc chip aux",
+            || "unverified signature slots",
             |region| {
                 let mut ctx = RegionCtx::new(region, 0);
-                self.assign_aux(&mut ctx, &mut ecc_chip)?;
-                log::debug!("ecc chip aux: {} rows", ctx.offset());
-                Ok(())
+                let is_padding = main_gate.assign_constant(&mut ctx, F::ONE)?;
+                let recovery_id = main_gate
+                    .assign_constant(&mut ctx, F::from(recovery_id(&padding_sign_data) as u64))?;
+                // The address of a padding verification is 0.
+                let zero = main_gate.assign_constant(&mut ctx, F::ZERO)?;
+                let msg_hash = Word::new([
+                    main_gate.assign_constant(&mut ctx, msg_hash.lo())?,
+                    main_gate.assign_constant(&mut ctx, msg_hash.hi())?,
+                ]);
+                Ok(iter::repeat_with(|| AssignedSignatureVerify {
+                    is_padding: is_padding.clone(),
+                    recovery_id: recovery_id.clone(),
+                    address: Word::new([zero.clone(), zero.clone()]),
+                    msg_hash: msg_hash.clone(),
+                })
+                .take(num_slots)
+                .collect())
             },
-        )?;
-
-        let ecdsa_chip = EcdsaChip::new(ecc_chip.clone());
+        )
+    }
 
+    fn assign_lane(
+        &self,
+        config: &SignVerifyLaneConfig,
+        layouter: &mut impl Layouter<F>,
+        slots: ops::Range<usize>,
+        signatures: &[Option<SignData<C>>],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<Vec<(AssignedSignatureVerify<F>, AssignedSignature<F>)>, Error> {
+        let main_gate = MainGate::new(config.main_gate_config.clone());
+        let range_chip = RangeChip::new(config.range_config.clone());
+        let ecc_chip =
+            GeneralEccChip::<C, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>::new(config.ecc_chip_config());
         let chips = ChipsRef {
             main_gate: &main_gate,
             range_chip: &range_chip,
             ecc_chip: &ecc_chip,
-            scalar_chip,
-            ecdsa_chip: &ecdsa_chip,
+            scalar_chip: ecc_chip.scalar_field_chip(),
         };
+        let padding_sign_data = self.padding_sign_data();
 
         let assigned_ecdsas = layouter.assign_region(
             || "ecdsa chip verification",
             |region| {
                 let mut assigned_ecdsas = Vec::new();
                 let mut ctx = RegionCtx::new(region, 0);
-                for i in 0..self.max_verif {
-                    let signature = if i < signatures.len() {
-                        signatures[i].clone()
-                    } else {
-                        // padding (enabled when address == 0)
-                        SignData::default()
+                let constants = self.assign_ecdsa_constants(&mut ctx, &chips)?;
+                for i in slots.clone() {
+                    let signature = match signatures.get(i) {
+                        Some(Some(signature)) => signature,
+                        _ => &padding_sign_data,
                     };
-                    let assigned_ecdsa = self.assign_ecdsa(&mut ctx, &chips, &signature)?;
+                    let assigned_ecdsa =
+                        self.assign_ecdsa(&mut ctx, &chips, &constants, signature)?;
                     assigned_ecdsas.push(assigned_ecdsa);
                 }
                 log::debug!("ecdsa chip verification: {} rows", ctx.offset());
@@ -693,8 +1334,8 @@ impl<F: Field> SignVerifyChip<F> {
             |region| {
                 let mut assigned_sig_verifs = Vec::new();
                 let mut ctx = RegionCtx::new(region, 0);
-                for (i, assigned_ecdsa) in assigned_ecdsas.iter().enumerate() {
-                    let sign_data = signatures.get(i); // None when padding (enabled when address == 0)
+                for (i, assigned_ecdsa) in slots.clone().zip(assigned_ecdsas.iter()) {
+                    let sign_data = signatures.get(i).and_then(Option::as_ref); // None when padding
                     let assigned_sig_verif = self.assign_signature_verify(
                         config,
                         &mut ctx,
@@ -717,7 +1358,7 @@ mod sign_verify_tests {
     use super::*;
     use crate::util::Challenges;
     use bus_mapping::circuit_input_builder::keccak_inputs_sign_verify;
-    use eth_types::sign_types::sign;
+    use eth_types::sign_types::{sign, sign_secp256r1};
     use halo2_proofs::{
         arithmetic::Field as HaloField,
         circuit::SimpleFloorPlanner,
@@ -725,7 +1366,7 @@ mod sign_verify_tests {
         halo2curves::{
             bn256::Fr,
             group::{Curve, Group},
-            CurveAffine,
+            secp256k1, secp256r1, CurveAffine,
         },
         plonk::Circuit,
     };
@@ -733,19 +1374,19 @@ mod sign_verify_tests {
     use rand_xorshift::XorShiftRng;
 
     #[derive(Clone, Debug)]
-    struct TestCircuitSignVerifyConfig {
-        sign_verify: SignVerifyConfig,
+    struct TestCircuitSignVerifyConfig<C: SignVerifyCurve> {
+        sign_verify: SignVerifyConfig<C>,
         challenges: Challenges,
     }
 
-    impl TestCircuitSignVerifyConfig {
+    impl<C: SignVerifyCurve> TestCircuitSignVerifyConfig<C> {
         pub(crate) fn new<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
             let keccak_table = KeccakTable::construct(meta);
             let challenges = Challenges::construct(meta);
 
             let sign_verify = {
                 let challenges = challenges.exprs(meta);
-                SignVerifyConfig::new(meta, keccak_table, challenges)
+                SignVerifyConfig::new(meta, keccak_table, challenges, 1)
             };
 
             TestCircuitSignVerifyConfig {
@@ -755,19 +1396,21 @@ mod sign_verify_tests {
         }
     }
 
-    #[derive(Default)]
-    struct TestCircuitSignVerify<F: Field> {
-        sign_verify: SignVerifyChip<F>,
-        signatures: Vec<SignData>,
+    struct TestCircuitSignVerify<F: Field, C: SignVerifyCurve> {
+        sign_verify: SignVerifyChip<F, C>,
+        signatures: Vec<SignData<C>>,
     }
 
-    impl<F: Field> Circuit<F> for TestCircuitSignVerify<F> {
-        type Config = TestCircuitSignVerifyConfig;
+    impl<F: Field, C: SignVerifyCurve> Circuit<F> for TestCircuitSignVerify<F, C> {
+        type Config = TestCircuitSignVerifyConfig<C>;
         type FloorPlanner = SimpleFloorPlanner;
         type Params = ();
 
         fn without_witnesses(&self) -> Self {
-            Self::default()
+            Self {
+                sign_verify: SignVerifyChip::default(),
+                signatures: Vec::new(),
+            }
         }
 
         fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
@@ -784,7 +1427,7 @@ mod sign_verify_tests {
             self.sign_verify.assign(
                 &config.sign_verify,
                 &mut layouter,
-                &self.signatures,
+                &self.signatures.iter().cloned().map(Some).collect_vec(),
                 &challenges,
             )?;
             config.sign_verify._keccak_table.dev_load(
@@ -797,17 +1440,23 @@ mod sign_verify_tests {
         }
     }
 
-    fn run<F: Field>(k: u32, max_verif: usize, signatures: Vec<SignData>) {
+    fn run<F: Field, C: SignVerifyCurve>(
+        k: u32,
+        max_verif: usize,
+        low_s: bool,
+        signatures: Vec<SignData<C>>,
+    ) {
         let mut rng = XorShiftRng::seed_from_u64(2);
-        let aux_generator =
-            <Secp256k1Affine as CurveAffine>::CurveExt::random(&mut rng).to_affine();
+        let aux_generator = <C as CurveAffine>::CurveExt::random(&mut rng).to_affine();
 
         // SignVerifyChip -> ECDSAChip -> MainGate instance column
-        let circuit = TestCircuitSignVerify::<F> {
+        let circuit = TestCircuitSignVerify::<F, C> {
             sign_verify: SignVerifyChip {
                 aux_generator,
                 window_size: 4,
                 max_verif,
+                num_lanes: 1,
+                low_s,
                 _marker: PhantomData,
             },
             signatures,
@@ -821,10 +1470,10 @@ mod sign_verify_tests {
     }
 
     // Generate a test key pair
-    fn gen_key_pair(rng: impl RngCore) -> (secp256k1::Fq, Secp256k1Affine) {
+    fn gen_key_pair<C: CurveAffine>(rng: impl RngCore) -> (C::ScalarExt, C) {
         // generate a valid signature
-        let generator = Secp256k1Affine::generator();
-        let sk = secp256k1::Fq::random(rng);
+        let generator = C::generator();
+        let sk = C::ScalarExt::random(rng);
         let pk = generator * sk;
         let pk = pk.to_affine();
 
@@ -832,8 +1481,8 @@ mod sign_verify_tests {
     }
 
     // Generate a test message hash
-    fn gen_msg_hash(rng: impl RngCore) -> secp256k1::Fq {
-        secp256k1::Fq::random(rng)
+    fn gen_msg_hash<C: CurveAffine>(rng: impl RngCore) -> C::ScalarExt {
+        C::ScalarExt::random(rng)
     }
 
     // Returns (r, s)
@@ -846,6 +1495,41 @@ mod sign_verify_tests {
         sign(randomness, sk, msg_hash)
     }
 
+    #[test]
+    fn sign_verify_rows_range_chip_table() {
+        // Value obtained by patching the halo2 library to report the number of rows used in the
+        // range chip table region.
+        assert_eq!(SignVerifyChip::<Fr>::rows_range_chip_table(), 295188);
+    }
+
+    #[test]
+    fn sign_verify_glv_decompose() {
+        let glv = Secp256k1Affine::glv().unwrap();
+
+        // lambda * G = (beta * G.x, G.y)
+        let generator = Secp256k1Affine::generator().coordinates().unwrap();
+        let lambda_generator = (Secp256k1Affine::generator() * glv.lambda)
+            .to_affine()
+            .coordinates()
+            .unwrap();
+        assert_eq!(*lambda_generator.x(), *generator.x() * glv.beta);
+        assert_eq!(*lambda_generator.y(), *generator.y());
+
+        let mut rng = XorShiftRng::seed_from_u64(1);
+        for k in [secp256k1::Fq::ZERO, secp256k1::Fq::ONE, -secp256k1::Fq::ONE]
+            .into_iter()
+            .chain(iter::repeat_with(|| secp256k1::Fq::random(&mut rng)).take(100))
+        {
+            let [(k1_is_neg, k1), (k2_is_neg, k2)] = glv.decompose(&k);
+            // |k1|, |k2| < 2^128
+            assert!(k1.to_repr().as_ref()[16..].iter().all(|byte| *byte == 0));
+            assert!(k2.to_repr().as_ref()[16..].iter().all(|byte| *byte == 0));
+            let k1 = if k1_is_neg { -k1 } else { k1 };
+            let k2 = if k2_is_neg { -k2 } else { k2 };
+            assert_eq!(k1 + k2 * glv.lambda, k);
+        }
+    }
+
     #[test]
     fn sign_verify() {
         // Vectors using `XorShiftRng::seed_from_u64(1)`
@@ -861,8 +1545,8 @@ mod sign_verify_tests {
         const NUM_SIGS: usize = 2;
         let mut signatures = Vec::new();
         for _ in 0..NUM_SIGS {
-            let (sk, pk) = gen_key_pair(&mut rng);
-            let msg_hash = gen_msg_hash(&mut rng);
+            let (sk, pk) = gen_key_pair::<Secp256k1Affine>(&mut rng);
+            let msg_hash = gen_msg_hash::<Secp256k1Affine>(&mut rng);
             let sig = sign_with_rng(&mut rng, sk, msg_hash);
             signatures.push(SignData {
                 signature: sig,
@@ -872,6 +1556,51 @@ mod sign_verify_tests {
         }
 
         let k = 19;
-        run::<Fr>(k, MAX_VERIF, signatures);
+        run::<Fr, _>(k, MAX_VERIF, false, signatures);
+    }
+
+    #[test]
+    fn sign_verify_low_s() {
+        let mut rng = XorShiftRng::seed_from_u64(1);
+        const MAX_VERIF: usize = 2;
+        const NUM_SIGS: usize = 1;
+        let mut signatures = Vec::new();
+        for _ in 0..NUM_SIGS {
+            let (sk, pk) = gen_key_pair::<Secp256k1Affine>(&mut rng);
+            let msg_hash = gen_msg_hash::<Secp256k1Affine>(&mut rng);
+            let (sig_r, mut sig_s) = sign_with_rng(&mut rng, sk, msg_hash);
+            if !is_low_s(&eth_types::Word::from_little_endian(&sig_s.to_bytes())) {
+                sig_s = -sig_s;
+            }
+            signatures.push(SignData {
+                signature: (sig_r, sig_s),
+                pk,
+                msg_hash,
+            });
+        }
+
+        let k = 19;
+        run::<Fr, _>(k, MAX_VERIF, true, signatures);
+    }
+
+    #[test]
+    fn sign_verify_secp256r1() {
+        let mut rng = XorShiftRng::seed_from_u64(1);
+        const MAX_VERIF: usize = 2;
+        const NUM_SIGS: usize = 1;
+        let mut signatures = Vec::new();
+        for _ in 0..NUM_SIGS {
+            let (sk, pk) = gen_key_pair::<Secp256r1Affine>(&mut rng);
+            let msg_hash = gen_msg_hash::<Secp256r1Affine>(&mut rng);
+            let randomness = secp256r1::Fq::random(&mut rng);
+            signatures.push(SignData {
+                signature: sign_secp256r1(randomness, sk, msg_hash),
+                pk,
+                msg_hash,
+            });
+        }
+
+        let k = 19;
+        run::<Fr, _>(k, MAX_VERIF, false, signatures);
     }
 }
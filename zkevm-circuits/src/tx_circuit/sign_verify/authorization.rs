@@ -0,0 +1,457 @@
+//! Circuit to verify that the messages signed by the authorizations of the EIP-7702 set-code
+//! txs are `keccak(0x05 || rlp([chain_id, address, nonce]))`, so that the chain_id, the address
+//! and the nonce of the AuthTable are the ones signed by the authority.
+//!
+//! The chain_id and the nonce are RLP encoded in 1 to 9 bytes, so every byte of the encoding is
+//! laid out with a flag telling if it's part of the message, and the RLC of the message only
+//! accumulates the flagged bytes.  The chain_id of every authorization is constrained to be 0 or
+//! the chain_id of the circuit.
+//!
+//! The encodings are laid out in the columns of the first lane of the SignVerifyChip of the
+//! authorizations, which saves the instance column of another MainGate.  The message hash of a
+//! secp256k1 signature is the hash reduced modulo n, so the lookup fails for the hashes above
+//! n, which only happens with negligible probability.
+
+use super::{enable_lookup, AssignedSignatureVerify, SignVerifyConfig};
+use crate::{
+    evm_circuit::util::{from_bytes, not},
+    table::KeccakTable,
+    util::{word::Word, Challenges, Expr},
+};
+use eth_types::{
+    geth_types::{Authorization, SET_CODE_AUTHORIZATION_MAGIC},
+    Field,
+};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+use itertools::Itertools;
+use log::error;
+use maingate::{
+    AssignedValue, MainGate, MainGateConfig, MainGateInstructions, RangeChip, RangeConfig,
+    RangeInstructions, RegionCtx,
+};
+use std::{iter, marker::PhantomData};
+
+/// Length of the RLP encoding of an address: its 0x94 header and its 20 bytes
+const ADDRESS_ENCODING_LEN: usize = 1 + 20;
+
+/// Auxiliary Gadget to verify that the signed messages of the authorization
+/// slots are the EIP-7702 authorization messages of their chain_id, address
+/// and nonce.
+#[derive(Clone, Debug)]
+pub struct AuthSignHashChip<F: Field> {
+    /// Max number of verifications
+    pub max_verif: usize,
+    /// Marker
+    pub _marker: PhantomData<F>,
+}
+
+impl<F: Field> Default for AuthSignHashChip<F> {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl<F: Field> AuthSignHashChip<F> {
+    /// Return a new AuthSignHashChip
+    pub fn new(max_verif: usize) -> Self {
+        Self {
+            max_verif,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the number of rows required to prove an input of a particular
+    /// size.  The rows are laid out in the columns of the SignVerifyChip of
+    /// the authorizations, so they add to its rows.
+    pub fn min_num_rows(num_verif: usize) -> usize {
+        Self::rows_per_verif() * num_verif
+    }
+
+    /// Return the number of rows used by one verification.  It's an upper
+    /// bound of the byte decompositions (about 20 rows), the RLP encodings of
+    /// the chain_id and the nonce (about 45 rows each), the RLC of the message
+    /// (22 rows) and the keccak lookup.
+    pub fn rows_per_verif() -> usize {
+        192
+    }
+}
+
+/// Cells of the chain_id, the address and the nonce of an authorization slot,
+/// bound to its signed message.
+#[derive(Debug)]
+pub(crate) struct AssignedAuthorization<F: Field> {
+    pub(crate) chain_id: Word<AssignedValue<F>>,
+    pub(crate) address: Word<AssignedValue<F>>,
+    pub(crate) nonce: AssignedValue<F>,
+}
+
+/// AuthSignHash Configuration
+#[derive(Debug, Clone)]
+pub(crate) struct AuthSignHashConfig {
+    main_gate_config: MainGateConfig,
+    range_config: RangeConfig,
+    // RLC of the flagged bytes of the messages
+    q_rlc_message: Selector,
+    rlc: Column<Advice>,
+    // Keccak of the messages
+    q_keccak: Selector,
+    _keccak_table: KeccakTable,
+}
+
+impl AuthSignHashConfig {
+    pub(crate) fn new<F: Field>(
+        meta: &mut ConstraintSystem<F>,
+        auth_sign_verify: &SignVerifyConfig,
+        keccak_table: KeccakTable,
+        challenges: Challenges<Expression<F>>,
+    ) -> Self {
+        let main_gate_config = auth_sign_verify.main_gate_config();
+        let range_config = auth_sign_verify.range_config();
+        let rlc = auth_sign_verify.rlc_column();
+
+        // Layout, appending the bytes whose flag is 1 to the RLC:
+        // | q_rlc_message | rlc         |    a    |    b    |    c    |    d    |
+        // | ------------- | ----------- | ------- | ------- | ------- | ------- |
+        // |       1       |           0 | byte[0] | flag[0] | byte[1] | flag[1] |
+        // |       1       | rlc[0..2]   | byte[2] | flag[2] | byte[3] | flag[3] |
+        // |       0       | rlc[0..4]   |         |         |         |         |
+        //
+        // Note that the first row of zeros will be enforced by copy constraint.
+        let q_rlc_message = meta.selector();
+        meta.create_gate("auth message rlc", |meta| {
+            let q_rlc_message = meta.query_selector(q_rlc_message);
+            let [byte_0, flag_0, byte_1, flag_1, _] = main_gate_config
+                .advices()
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [rlc, rlc_next] = [Rotation::cur(), Rotation::next()]
+                .map(|rotation| meta.query_advice(rlc, rotation));
+            let challenge = challenges.keccak_input();
+            let append = |acc: Expression<F>, byte: Expression<F>, flag: Expression<F>| {
+                acc.clone() + flag * (acc * (challenge.clone() - 1.expr()) + byte)
+            };
+
+            vec![q_rlc_message * (rlc_next - append(append(rlc, byte_0, flag_0), byte_1, flag_1))]
+        });
+
+        // Verify that keccak(message) = msg_hash by keccak table lookup.
+        let q_keccak = meta.complex_selector();
+        meta.lookup_any("auth message keccak", |meta| {
+            // Layout:
+            // | q_keccak |     a      |    b    |    c    |      d      |     rlc     |
+            // | -------- | ---------- | ------- | ------- | ----------- | ----------- |
+            // |    1     | is_padding | hash_lo | hash_hi | message_len | message_rlc |
+            let q_keccak = meta.query_selector(q_keccak);
+            let [is_padding, hash_lo, hash_hi, message_len, _] = main_gate_config
+                .advices()
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+            let is_enable = q_keccak * not::expr(is_padding);
+            let input = [
+                is_enable.clone(),
+                is_enable.clone() * meta.query_advice(rlc, Rotation::cur()),
+                is_enable.clone() * message_len,
+                is_enable.clone() * hash_lo,
+                is_enable * hash_hi,
+            ];
+            let table = [
+                keccak_table.is_enabled,
+                keccak_table.input_rlc,
+                keccak_table.input_len,
+                keccak_table.output.lo(),
+                keccak_table.output.hi(),
+            ]
+            .map(|column| meta.query_advice(column, Rotation::cur()));
+
+            input.into_iter().zip(table).collect()
+        });
+
+        Self {
+            main_gate_config,
+            range_config,
+            q_rlc_message,
+            rlc,
+            q_keccak,
+            _keccak_table: keccak_table,
+        }
+    }
+}
+
+/// Cells of the RLP encoding of a scalar: its header and big endian bytes,
+/// each with the flag telling if it's part of the encoding, and the length of
+/// the encoding.
+struct AssignedScalarEncoding<F: Field> {
+    bytes: Vec<(AssignedValue<F>, AssignedValue<F>)>,
+    len: AssignedValue<F>,
+}
+
+/// Assign the RLP encoding of the scalar decomposed in `bytes_le`: the scalars
+/// from 0x01 to 0x7f are encoded in their single byte, the other ones in a
+/// 0x80 + len header followed by their big endian bytes without the leading
+/// zeros.
+fn assign_scalar_encoding<F: Field>(
+    ctx: &mut RegionCtx<F>,
+    main_gate: &MainGate<F>,
+    bytes_le: &[AssignedValue<F>],
+) -> Result<AssignedScalarEncoding<F>, Error> {
+    // The i-th flag is set when the bytes up to the i-th one in big endian order are zero
+    let mut is_leading_zero: Vec<AssignedValue<F>> = Vec::with_capacity(bytes_le.len());
+    for byte in bytes_le.iter().rev() {
+        let is_zero = main_gate.is_zero(ctx, byte)?;
+        let is_leading = match is_leading_zero.last() {
+            Some(prev) => main_gate.and(ctx, prev, &is_zero)?,
+            None => is_zero,
+        };
+        is_leading_zero.push(is_leading);
+    }
+    let len = main_gate.compose(
+        ctx,
+        &is_leading_zero
+            .iter()
+            .map(|is_leading| maingate::Term::Assigned(is_leading, -F::ONE))
+            .collect_vec(),
+        F::from(bytes_le.len() as u64),
+    )?;
+
+    // A single byte below 0x80 has no header
+    let len_minus_one =
+        main_gate.compose(ctx, &[maingate::Term::Assigned(&len, F::ONE)], -F::ONE)?;
+    let is_len_one = main_gate.is_zero(ctx, &len_minus_one)?;
+    let low_byte_bits = main_gate.to_bits(ctx, &bytes_le[0], 8)?;
+    let is_low_byte_small = main_gate.not(ctx, &low_byte_bits[7])?;
+    let is_single_byte = main_gate.and(ctx, &is_len_one, &is_low_byte_small)?;
+    let has_header = main_gate.not(ctx, &is_single_byte)?;
+    let header = main_gate.compose(
+        ctx,
+        &[maingate::Term::Assigned(&len, F::ONE)],
+        F::from(0x80),
+    )?;
+    let encoding_len = main_gate.compose(
+        ctx,
+        &[
+            maingate::Term::Assigned(&len, F::ONE),
+            maingate::Term::Assigned(&has_header, F::ONE),
+        ],
+        F::ZERO,
+    )?;
+
+    let mut bytes = vec![(header, has_header)];
+    for (byte, is_leading) in bytes_le.iter().rev().zip(&is_leading_zero) {
+        bytes.push((byte.clone(), main_gate.not(ctx, is_leading)?));
+    }
+    Ok(AssignedScalarEncoding {
+        bytes,
+        len: encoding_len,
+    })
+}
+
+/// Assign the RLC of the bytes of `message` whose flag is 1, laying out the
+/// bytes two by two in the gate configured by `AuthSignHashConfig::new`.
+fn assign_flagged_rlc<F: Field>(
+    ctx: &mut RegionCtx<F>,
+    config: &AuthSignHashConfig,
+    zero: &AssignedValue<F>,
+    message: &[(AssignedValue<F>, AssignedValue<F>)],
+    challenge: Value<F>,
+) -> Result<AssignedCell<F, F>, Error> {
+    let [a, b, c, d, _] = config.main_gate_config.advices();
+    let padding = (zero.clone(), zero.clone());
+
+    let mut rlc = Value::known(F::ZERO);
+    for (chunk_idx, chunk) in message.chunks(2).enumerate() {
+        ctx.enable(config.q_rlc_message)?;
+        let assigned_rlc =
+            ctx.assign_advice(|| format!("auth_message_rlc[{chunk_idx}]"), config.rlc, rlc)?;
+        if chunk_idx == 0 {
+            ctx.constrain_equal(zero.cell(), assigned_rlc.cell())?;
+        }
+        for ((byte, flag), [byte_column, flag_column]) in chunk
+            .iter()
+            .chain(iter::repeat(&padding))
+            .take(2)
+            .zip([[a, b], [c, d]])
+        {
+            for (name, column, cell) in [("byte", byte_column, byte), ("flag", flag_column, flag)] {
+                let copied = ctx.assign_advice(|| name, column, cell.value().copied())?;
+                ctx.constrain_equal(cell.cell(), copied.cell())?;
+            }
+            rlc = rlc
+                .zip(challenge)
+                .zip(byte.value().copied())
+                .zip(flag.value().copied())
+                .map(|(((rlc, challenge), byte), flag)| {
+                    if flag == F::ONE {
+                        rlc * challenge + byte
+                    } else {
+                        rlc
+                    }
+                });
+        }
+        ctx.next();
+    }
+
+    let assigned_rlc = ctx.assign_advice(|| "auth_message_rlc", config.rlc, rlc)?;
+    ctx.next();
+
+    Ok(assigned_rlc)
+}
+
+impl<F: Field> AuthSignHashChip<F> {
+    /// Verify that the message hash of the slot `i` is the hash of the message
+    /// signed for `authorizations[i]`, whose chain_id must be 0 or the one in
+    /// `chain_id_cell`, and return the cells of the chain_id, the address and
+    /// the nonce of every slot.  The padding slots skip the lookup, and are
+    /// given the default authorization.
+    pub(crate) fn assign(
+        &self,
+        config: &AuthSignHashConfig,
+        layouter: &mut impl Layouter<F>,
+        assigned_sig_verifs: &[&AssignedSignatureVerify<F>],
+        authorizations: &[Authorization],
+        chain_id_cell: &AssignedCell<F, F>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<Vec<AssignedAuthorization<F>>, Error> {
+        if assigned_sig_verifs.len() != self.max_verif || authorizations.len() > self.max_verif {
+            error!(
+                "assigned_sig_verifs.len() = {}, authorizations.len() = {}, max_verif = {}",
+                assigned_sig_verifs.len(),
+                authorizations.len(),
+                self.max_verif
+            );
+            return Err(Error::Synthesis);
+        }
+        let main_gate = MainGate::new(config.main_gate_config.clone());
+        let range_chip = RangeChip::new(config.range_config.clone());
+
+        layouter.assign_region(
+            || "auth sign hashes",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+                let zero = main_gate.assign_constant(&mut ctx, F::ZERO)?;
+                let one = main_gate.assign_constant(&mut ctx, F::ONE)?;
+                let magic = main_gate
+                    .assign_constant(&mut ctx, F::from(SET_CODE_AUTHORIZATION_MAGIC as u64))?;
+                let address_header = main_gate.assign_constant(&mut ctx, F::from(0x80 + 20))?;
+                let chain_id = main_gate.assign_value(&mut ctx, chain_id_cell.value().copied())?;
+                ctx.constrain_equal(chain_id_cell.cell(), chain_id.cell())?;
+
+                let padding = Authorization::default();
+                let mut assigned_auths = Vec::with_capacity(self.max_verif);
+                for (i, assigned_sig_verif) in assigned_sig_verifs.iter().enumerate() {
+                    let authorization = authorizations.get(i).unwrap_or(&padding);
+
+                    // The chain_id of the authorization is 0 or the one of the circuit
+                    let (auth_chain_id, chain_id_le) = range_chip.decompose(
+                        &mut ctx,
+                        Value::known(F::from(authorization.chain_id.low_u64())),
+                        8,
+                        64,
+                    )?;
+                    let chain_id_diff = main_gate.compose(
+                        &mut ctx,
+                        &[
+                            maingate::Term::Assigned(&auth_chain_id, F::ONE),
+                            maingate::Term::Assigned(&chain_id, -F::ONE),
+                        ],
+                        F::ZERO,
+                    )?;
+                    let chain_id_check = main_gate.mul(&mut ctx, &auth_chain_id, &chain_id_diff)?;
+                    main_gate.assert_zero(&mut ctx, &chain_id_check)?;
+
+                    let (nonce, nonce_le) = range_chip.decompose(
+                        &mut ctx,
+                        Value::known(F::from(authorization.nonce.as_u64())),
+                        8,
+                        64,
+                    )?;
+                    let mut address_le = authorization.address.to_fixed_bytes();
+                    address_le.reverse();
+                    let (address_lo, address_lo_le) = range_chip.decompose(
+                        &mut ctx,
+                        Value::known(from_bytes::value::<F>(&address_le[..16])),
+                        8,
+                        128,
+                    )?;
+                    let (address_hi, address_hi_le) = range_chip.decompose(
+                        &mut ctx,
+                        Value::known(from_bytes::value::<F>(&address_le[16..])),
+                        8,
+                        32,
+                    )?;
+
+                    // 0x05 || rlp([chain_id, address, nonce]), where the payload is at most
+                    // 9 + 21 + 9 bytes, so the list header is a single byte.
+                    let chain_id_encoding =
+                        assign_scalar_encoding(&mut ctx, &main_gate, &chain_id_le)?;
+                    let nonce_encoding = assign_scalar_encoding(&mut ctx, &main_gate, &nonce_le)?;
+                    let payload_len = main_gate.compose(
+                        &mut ctx,
+                        &[
+                            maingate::Term::Assigned(&chain_id_encoding.len, F::ONE),
+                            maingate::Term::Assigned(&nonce_encoding.len, F::ONE),
+                        ],
+                        F::from(ADDRESS_ENCODING_LEN as u64),
+                    )?;
+                    let list_header = main_gate.compose(
+                        &mut ctx,
+                        &[maingate::Term::Assigned(&payload_len, F::ONE)],
+                        F::from(0xc0),
+                    )?;
+                    let message_len = main_gate.compose(
+                        &mut ctx,
+                        &[maingate::Term::Assigned(&payload_len, F::ONE)],
+                        F::from(2),
+                    )?;
+                    let message = [(magic.clone(), one.clone()), (list_header, one.clone())]
+                        .into_iter()
+                        .chain(chain_id_encoding.bytes)
+                        .chain(iter::once((address_header.clone(), one.clone())))
+                        .chain(
+                            address_lo_le
+                                .iter()
+                                .chain(&address_hi_le)
+                                .rev()
+                                .map(|byte| (byte.clone(), one.clone())),
+                        )
+                        .chain(nonce_encoding.bytes)
+                        .collect_vec();
+                    let message_rlc = assign_flagged_rlc(
+                        &mut ctx,
+                        config,
+                        &zero,
+                        &message,
+                        challenges.keccak_input(),
+                    )?;
+
+                    // The lookup is skipped for the padding slots
+                    let [_, _, _, message_len_column, _] = config.main_gate_config.advices();
+                    let copied_len = ctx.assign_advice(
+                        || "message_len",
+                        message_len_column,
+                        message_len.value().copied(),
+                    )?;
+                    ctx.constrain_equal(message_len.cell(), copied_len.cell())?;
+                    enable_lookup(
+                        &mut ctx,
+                        config.q_keccak,
+                        &config.main_gate_config,
+                        config.rlc,
+                        &assigned_sig_verif.is_padding,
+                        &message_rlc,
+                        &assigned_sig_verif.msg_hash,
+                    )?;
+
+                    assigned_auths.push(AssignedAuthorization {
+                        chain_id: Word::new([auth_chain_id, zero.clone()]),
+                        address: Word::new([address_lo, address_hi]),
+                        nonce,
+                    });
+                }
+                log::debug!("auth sign hashes: {} rows", ctx.offset());
+                Ok(assigned_auths)
+            },
+        )
+    }
+}
@@ -0,0 +1,968 @@
+//! Circuit to verify multiple Ed25519 signatures (RFC 8032) over edwards25519, with the
+//! challenge hashed by SHA-512.
+//!
+//! The base field of edwards25519 is not supported by the IntegerChip, so the integers are split
+//! into 4 limbs of 64 bits and checked as in the RsaSignVerifyChip: a modular relation
+//! `a * b + c = q * p + r` is checked limb by limb, with range checked carries between
+//! positions, so that it holds over the integers.  The integers are only constrained to be
+//! smaller than 2^256 where congruence modulo p is enough, and to be canonical where the encoding
+//! matters (the decoded points and the scalars).
+//!
+//! A signature `R || S` of the message M by the public key A is valid when `[S]B = R + [k]A`,
+//! with `k = sha512(R || A || M) mod L`.  `[S]B + [k](-A)` is computed by an interleaved
+//! double-and-add over the bits of S and k with the complete addition formulas of the twisted
+//! Edwards curves, and constrained to be R.
+
+use super::{
+    assign_address, assign_bytes_le, assign_rlc_le, assigned_term, compose_bytes_le, count_rows,
+    enable_lookup, AssignedSignatureVerify, SignVerifyLaneConfig,
+};
+use crate::{
+    table::{KeccakTable, Sha512Table},
+    util::{word::Word, Challenges},
+};
+use eth_types::{
+    ed25519_types::{
+        sha512, Ed25519SignData, EdwardsPoint, ED25519_BASEPOINT, ED25519_D, ED25519_L, ED25519_P,
+    },
+    keccak256, Field,
+};
+use halo2_proofs::{
+    circuit::{Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, SecondPhase, Selector},
+};
+use itertools::Itertools;
+use log::error;
+use maingate::{
+    AssignedValue, MainGate, MainGateConfig, MainGateInstructions, RangeChip, RangeConfig,
+    RangeInstructions, RegionCtx,
+};
+use num_bigint::{BigInt, BigUint};
+use std::marker::PhantomData;
+
+/// Number of limbs of an edwards25519 integer
+const ED25519_NUMBER_OF_LIMBS: usize = 4;
+/// Number of bits of a limb of an edwards25519 integer
+const ED25519_BIT_LEN_LIMB: usize = 64;
+/// Number of bits of a range checked carry between limb positions, offset by `2^71` to be
+/// non-negative.  The sums of a position are below 2^131, so the carries are below 2^67.
+const ED25519_BIT_LEN_CARRY: usize = 72;
+/// Number of limbs of a quotient, at most `(2^512 + 2^258) / 2^252`
+const QUOTIENT_NUMBER_OF_LIMBS: usize = 5;
+/// Number of bits of the scalars S and k, which are smaller than L < 2^253
+const SCALAR_BITS: usize = 253;
+
+/// Auxiliary Gadget to verify that a message is signed with Ed25519 by the public key
+/// corresponding to an Ethereum Address: `keccak(A)[12..]` where A is the 32 bytes encoded
+/// public key.
+#[derive(Clone, Debug)]
+pub struct Ed25519SignVerifyChip<F: Field> {
+    /// Max number of verifications
+    pub max_verif: usize,
+    /// Marker
+    pub _marker: PhantomData<F>,
+}
+
+impl<F: Field> Default for Ed25519SignVerifyChip<F> {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl<F: Field> Ed25519SignVerifyChip<F> {
+    /// Return a new Ed25519SignVerifyChip
+    pub fn new(max_verif: usize) -> Self {
+        Self {
+            max_verif,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the minimum number of rows required to prove an input of a
+    /// particular size.
+    pub fn min_num_rows(num_verif: usize) -> usize {
+        let (rows_constants, rows_per_verif) = Self::count_rows();
+        std::cmp::max(
+            Self::rows_range_chip_table(),
+            rows_constants + rows_per_verif * num_verif,
+        )
+    }
+
+    /// Return the number of rows of the RangeChip lookup table, which only
+    /// range checks bytes.
+    pub fn rows_range_chip_table() -> usize {
+        1 << 8
+    }
+
+    /// Return the number of rows used by one signature verification.
+    pub fn rows_per_verif() -> usize {
+        Self::count_rows().1
+    }
+
+    /// Lay out the constants and one padding verification, and return the
+    /// number of rows used by the constants and by the verification.  The
+    /// layout doesn't depend on the witness, so every verification uses as
+    /// many rows as the padding one.
+    fn count_rows() -> (usize, usize) {
+        let mut meta = ConstraintSystem::<F>::default();
+        let keccak_table = KeccakTable::construct(&mut meta);
+        let sha512_table = Sha512Table::construct(&mut meta);
+        let challenges = Challenges::construct(&mut meta).exprs(&mut meta);
+        let config =
+            Ed25519SignVerifyConfig::new(&mut meta, keccak_table, sha512_table, challenges);
+
+        let chip = Self::default();
+        let main_gate = MainGate::new(config.main_gate_config.clone());
+        let range_chip = RangeChip::new(config.range_config.clone());
+        let (rows_constants, _) = count_rows(|ctx| chip.assign_constants(ctx, &main_gate));
+        let (rows, _) = count_rows(|ctx| {
+            let constants = chip.assign_constants(ctx, &main_gate)?;
+            let chips = Ed25519ChipsRef {
+                main_gate: &main_gate,
+                range_chip: &range_chip,
+                constants: &constants,
+            };
+            chip.assign_signature_verify(
+                &config,
+                ctx,
+                &chips,
+                None,
+                &Challenges::mock(Value::unknown(), Value::unknown()),
+            )
+        });
+        (rows_constants, rows - rows_constants)
+    }
+}
+
+/// Return the limbs of an integer of at most `64 * num_limbs` bits.
+fn limbs(value: &BigUint, num_limbs: usize) -> Vec<u64> {
+    let digits = value.to_u64_digits();
+    assert!(
+        digits.len() <= num_limbs,
+        "value doesn't fit in {} limbs",
+        num_limbs
+    );
+    digits.into_iter().pad_using(num_limbs, |_| 0).collect()
+}
+
+/// Ed25519SignVerify Configuration
+#[derive(Debug, Clone)]
+pub(crate) struct Ed25519SignVerifyConfig {
+    main_gate_config: MainGateConfig,
+    range_config: RangeConfig,
+    // RLC of the keccak and sha512 inputs
+    q_rlc_input: Selector,
+    rlc: Column<Advice>,
+    // Keccak of the public key
+    q_keccak: Selector,
+    // Sha512 of R || A || M, as two lookups of two digest words each
+    q_sha512: [Selector; 2],
+    _keccak_table: KeccakTable,
+    _sha512_table: Sha512Table,
+}
+
+impl Ed25519SignVerifyConfig {
+    pub(crate) fn new<F: Field>(
+        meta: &mut ConstraintSystem<F>,
+        keccak_table: KeccakTable,
+        sha512_table: Sha512Table,
+        challenges: Challenges<Expression<F>>,
+    ) -> Self {
+        let main_gate_config = MainGate::<F>::configure(meta);
+        let range_config = RangeChip::<F>::configure(meta, &main_gate_config, vec![8], vec![]);
+
+        // RLC
+        let q_rlc_input = meta.selector();
+        let rlc = meta.advice_column_in(SecondPhase);
+        meta.enable_equality(rlc);
+        SignVerifyLaneConfig::configure_rlc(
+            meta,
+            "ed25519_input_rlc",
+            main_gate_config.clone(),
+            q_rlc_input,
+            rlc,
+            challenges.keccak_input(),
+        );
+
+        // Verify that keccak(A) = pk_hash by keccak table lookup, where A are the 32 bytes of
+        // the encoded public key.
+        let q_keccak = meta.complex_selector();
+        SignVerifyLaneConfig::configure_lookup(
+            meta,
+            "ed25519 keccak",
+            &main_gate_config,
+            q_keccak,
+            rlc,
+            32,
+            [
+                keccak_table.is_enabled,
+                keccak_table.input_rlc,
+                keccak_table.input_len,
+                keccak_table.output.lo(),
+                keccak_table.output.hi(),
+            ],
+        );
+
+        // Verify that sha512(R || A || M) = digest by sha512 table lookups.  Each lookup checks
+        // two words of the digest, and both match the same row as the table holds one row per
+        // input.
+        let q_sha512 = [("ed25519 sha512 lo", [0, 1]), ("ed25519 sha512 hi", [2, 3])].map(
+            |(name, [lo, hi])| {
+                let q_lookup = meta.complex_selector();
+                SignVerifyLaneConfig::configure_lookup(
+                    meta,
+                    name,
+                    &main_gate_config,
+                    q_lookup,
+                    rlc,
+                    3 * 32,
+                    [
+                        sha512_table.is_enabled,
+                        sha512_table.input_rlc,
+                        sha512_table.input_len,
+                        sha512_table.output[lo],
+                        sha512_table.output[hi],
+                    ],
+                );
+                q_lookup
+            },
+        );
+
+        Self {
+            main_gate_config,
+            range_config,
+            q_rlc_input,
+            rlc,
+            q_keccak,
+            q_sha512,
+            _keccak_table: keccak_table,
+            _sha512_table: sha512_table,
+        }
+    }
+
+    pub(crate) fn load_range<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let range_chip = RangeChip::<F>::new(self.range_config.clone());
+        range_chip.load_table(layouter)
+    }
+}
+
+/// Integer assigned as limbs of 64 bits, whose bytes are assigned too when
+/// the limbs are range checked.
+#[derive(Clone, Debug)]
+struct AssignedEdInteger<F: Field> {
+    value: BigUint,
+    limbs: Vec<AssignedValue<F>>,
+    bytes_le: Vec<AssignedValue<F>>,
+}
+
+/// Point of edwards25519 assigned in affine coordinates
+#[derive(Clone, Debug)]
+struct AssignedEdPoint<F: Field> {
+    x: AssignedEdInteger<F>,
+    y: AssignedEdInteger<F>,
+}
+
+impl<F: Field> AssignedEdPoint<F> {
+    fn value(&self) -> EdwardsPoint {
+        EdwardsPoint {
+            x: &self.x.value % &*ED25519_P,
+            y: &self.y.value % &*ED25519_P,
+        }
+    }
+}
+
+/// Sums of the limb products of every position of a product of integers, or
+/// limbs of an integer, with their values to compute the carries.
+struct Positions<F: Field> {
+    cells: Vec<AssignedValue<F>>,
+    values: Vec<BigInt>,
+}
+
+impl<F: Field> From<&AssignedEdInteger<F>> for Positions<F> {
+    fn from(integer: &AssignedEdInteger<F>) -> Self {
+        Self {
+            cells: integer.limbs.clone(),
+            values: limbs(&integer.value, integer.limbs.len())
+                .into_iter()
+                .map(BigInt::from)
+                .collect(),
+        }
+    }
+}
+
+/// Constants of the verification, assigned once for all the slots
+struct Ed25519Constants<F: Field> {
+    zero: AssignedEdInteger<F>,
+    one: AssignedEdInteger<F>,
+    d: AssignedEdInteger<F>,
+    neg_d: AssignedEdInteger<F>,
+    p: AssignedEdInteger<F>,
+    // 3 * p, added to the left side of the modular relations so that the quotient is
+    // non-negative for any r < 2^256
+    p_3: AssignedEdInteger<F>,
+    l: AssignedEdInteger<F>,
+    base: AssignedEdPoint<F>,
+}
+
+/// Helper structure pass around references to all the chips required for an
+/// Ed25519 verification.
+struct Ed25519ChipsRef<'a, F: Field> {
+    main_gate: &'a MainGate<F>,
+    range_chip: &'a RangeChip<F>,
+    constants: &'a Ed25519Constants<F>,
+}
+
+impl<F: Field> Ed25519SignVerifyChip<F> {
+    /// Assign an integer of `num_limbs` limbs with range checked bytes.
+    fn assign_integer(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        range_chip: &RangeChip<F>,
+        value: &BigUint,
+        num_limbs: usize,
+    ) -> Result<AssignedEdInteger<F>, Error> {
+        let mut assigned_limbs = Vec::with_capacity(num_limbs);
+        let mut bytes_le = Vec::with_capacity(num_limbs * 8);
+        for limb in limbs(value, num_limbs) {
+            let (limb, bytes) =
+                range_chip.decompose(ctx, Value::known(F::from(limb)), 8, ED25519_BIT_LEN_LIMB)?;
+            assigned_limbs.push(limb);
+            bytes_le.extend(bytes);
+        }
+        Ok(AssignedEdInteger {
+            value: value.clone(),
+            limbs: assigned_limbs,
+            bytes_le,
+        })
+    }
+
+    /// Assign a constant integer of `num_limbs` limbs.
+    fn assign_constant_integer(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        main_gate: &MainGate<F>,
+        value: &BigUint,
+        num_limbs: usize,
+    ) -> Result<AssignedEdInteger<F>, Error> {
+        Ok(AssignedEdInteger {
+            value: value.clone(),
+            limbs: limbs(value, num_limbs)
+                .into_iter()
+                .map(|limb| main_gate.assign_constant(ctx, F::from(limb)))
+                .collect::<Result<_, _>>()?,
+            bytes_le: vec![],
+        })
+    }
+
+    /// Return the integer of the range checked little endian bytes `bytes_le`.
+    fn compose_integer(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        main_gate: &MainGate<F>,
+        bytes_le: &[AssignedValue<F>],
+        value: BigUint,
+    ) -> Result<AssignedEdInteger<F>, Error> {
+        Ok(AssignedEdInteger {
+            value,
+            limbs: bytes_le
+                .chunks(ED25519_BIT_LEN_LIMB / 8)
+                .map(|bytes| compose_bytes_le(ctx, main_gate, bytes))
+                .collect::<Result<_, _>>()?,
+            bytes_le: bytes_le.to_vec(),
+        })
+    }
+
+    fn assign_constants(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        main_gate: &MainGate<F>,
+    ) -> Result<Ed25519Constants<F>, Error> {
+        let p = &*ED25519_P;
+        let mut constant = |value: &BigUint, num_limbs| {
+            self.assign_constant_integer(ctx, main_gate, value, num_limbs)
+        };
+        Ok(Ed25519Constants {
+            zero: constant(&BigUint::from(0u64), ED25519_NUMBER_OF_LIMBS)?,
+            one: constant(&BigUint::from(1u64), ED25519_NUMBER_OF_LIMBS)?,
+            d: constant(&ED25519_D, ED25519_NUMBER_OF_LIMBS)?,
+            neg_d: constant(&(p - &*ED25519_D), ED25519_NUMBER_OF_LIMBS)?,
+            p: constant(p, ED25519_NUMBER_OF_LIMBS)?,
+            p_3: constant(&(p * 3u64), QUOTIENT_NUMBER_OF_LIMBS)?,
+            l: constant(&ED25519_L, ED25519_NUMBER_OF_LIMBS)?,
+            base: AssignedEdPoint {
+                x: constant(&ED25519_BASEPOINT.x, ED25519_NUMBER_OF_LIMBS)?,
+                y: constant(&ED25519_BASEPOINT.y, ED25519_NUMBER_OF_LIMBS)?,
+            },
+        })
+    }
+
+    /// Assign the sum of the limb products `a_i * b_j` with `i + j = k`, for
+    /// every position `k`.
+    fn assign_limb_products(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        main_gate: &MainGate<F>,
+        a: &AssignedEdInteger<F>,
+        b: &AssignedEdInteger<F>,
+    ) -> Result<Positions<F>, Error> {
+        let (a_limbs, b_limbs) = (
+            limbs(&a.value, a.limbs.len()),
+            limbs(&b.value, b.limbs.len()),
+        );
+        let mut positions = Positions {
+            cells: Vec::with_capacity(a.limbs.len() + b.limbs.len() - 1),
+            values: Vec::with_capacity(a.limbs.len() + b.limbs.len() - 1),
+        };
+        for k in 0..a.limbs.len() + b.limbs.len() - 1 {
+            let mut pairs = (k.saturating_sub(b.limbs.len() - 1)..=k.min(a.limbs.len() - 1))
+                .map(|i| (i, k - i));
+            let (i, j) = pairs.next().expect("every position has a product");
+            let first = main_gate.mul(ctx, &a.limbs[i], &b.limbs[j])?;
+            let mut value = BigInt::from(a_limbs[i]) * BigInt::from(b_limbs[j]);
+            let cell = pairs.try_fold(first, |acc, (i, j)| {
+                value += BigInt::from(a_limbs[i]) * BigInt::from(b_limbs[j]);
+                main_gate.mul_add(ctx, &a.limbs[i], &b.limbs[j], &acc)
+            })?;
+            positions.cells.push(cell);
+            positions.values.push(value);
+        }
+        Ok(positions)
+    }
+
+    /// Constrain `sum(lhs) = sum(rhs)` over the integers, position by
+    /// position with range checked carries.
+    fn assert_equal_positions(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        chips: &Ed25519ChipsRef<F>,
+        lhs: &[Positions<F>],
+        rhs: &[Positions<F>],
+    ) -> Result<(), Error> {
+        let main_gate = chips.main_gate;
+        let num_positions = lhs
+            .iter()
+            .chain(rhs)
+            .map(|positions| positions.cells.len())
+            .max()
+            .unwrap_or_default();
+
+        let pow_2_64 = F::from_u128(1 << ED25519_BIT_LEN_LIMB);
+        let carry_offset = F::from_u128(1 << (ED25519_BIT_LEN_CARRY - 1));
+        let mut carry_value = BigInt::from(0);
+        let mut carry: Option<AssignedValue<F>> = None;
+        for k in 0..num_positions {
+            // lhs_k - rhs_k + carry_{k-1} = 2^64 * carry_k, where the carries are assigned
+            // shifted by the carry offset, and the last carry is 0.
+            let mut terms = vec![];
+            let mut position_value = carry_value.clone();
+            for positions in lhs.iter().filter(|positions| k < positions.cells.len()) {
+                terms.push(maingate::Term::Assigned(&positions.cells[k], F::ONE));
+                position_value += &positions.values[k];
+            }
+            for positions in rhs.iter().filter(|positions| k < positions.cells.len()) {
+                terms.push(maingate::Term::Assigned(&positions.cells[k], -F::ONE));
+                position_value -= &positions.values[k];
+            }
+            let mut constant = F::ZERO;
+            if let Some(carry) = carry.as_ref() {
+                terms.push(maingate::Term::Assigned(carry, F::ONE));
+                constant -= carry_offset;
+            }
+
+            let next_carry = if k < num_positions - 1 {
+                carry_value = position_value >> ED25519_BIT_LEN_LIMB;
+                let shifted_carry = i128::try_from(&carry_value).expect("carry fits in 67 bits")
+                    + (1 << (ED25519_BIT_LEN_CARRY - 1));
+                let (shifted_carry, _) = chips.range_chip.decompose(
+                    ctx,
+                    Value::known(F::from_u128(shifted_carry as u128)),
+                    8,
+                    ED25519_BIT_LEN_CARRY,
+                )?;
+                constant += pow_2_64 * carry_offset;
+                Some(shifted_carry)
+            } else {
+                None
+            };
+            if let Some(next_carry) = next_carry.as_ref() {
+                terms.push(maingate::Term::Assigned(next_carry, -pow_2_64));
+            }
+            let position = main_gate.compose(ctx, &terms, constant)?;
+            main_gate.assert_zero(ctx, &position)?;
+            carry = next_carry;
+        }
+        Ok(())
+    }
+
+    /// Constrain `a * b + sum(adds) = r mod p`, as
+    /// `a * b + sum(adds) + 3p = q * p + r` over the integers.
+    fn constrain_mul_add_mod(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        chips: &Ed25519ChipsRef<F>,
+        a: &AssignedEdInteger<F>,
+        b: &AssignedEdInteger<F>,
+        adds: &[&AssignedEdInteger<F>],
+        r: &AssignedEdInteger<F>,
+    ) -> Result<(), Error> {
+        let constants = chips.constants;
+        let sum = &a.value * &b.value
+            + adds.iter().map(|add| &add.value).sum::<BigUint>()
+            + &constants.p_3.value;
+        // The quotient of a wrong r is any value: the relation fails anyway.
+        let q = (sum - &r.value) / &*ED25519_P;
+        let q = self.assign_integer(ctx, chips.range_chip, &q, QUOTIENT_NUMBER_OF_LIMBS)?;
+
+        let ab = self.assign_limb_products(ctx, chips.main_gate, a, b)?;
+        let qp = self.assign_limb_products(ctx, chips.main_gate, &q, &constants.p)?;
+        let lhs = [ab, Positions::from(&constants.p_3)]
+            .into_iter()
+            .chain(adds.iter().map(|add| Positions::from(*add)))
+            .collect_vec();
+        self.assert_equal_positions(ctx, chips, &lhs, &[qp, Positions::from(r)])
+    }
+
+    /// Return `a * b + sum(adds) mod p`.
+    fn mul_add_mod(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        chips: &Ed25519ChipsRef<F>,
+        a: &AssignedEdInteger<F>,
+        b: &AssignedEdInteger<F>,
+        adds: &[&AssignedEdInteger<F>],
+    ) -> Result<AssignedEdInteger<F>, Error> {
+        let value = (&a.value * &b.value + adds.iter().map(|add| &add.value).sum::<BigUint>())
+            % &*ED25519_P;
+        let r = self.assign_integer(ctx, chips.range_chip, &value, ED25519_NUMBER_OF_LIMBS)?;
+        self.constrain_mul_add_mod(ctx, chips, a, b, adds, &r)?;
+        Ok(r)
+    }
+
+    /// Constrain the integer x to be smaller than `bound`, by subtracting its
+    /// limbs from the limbs of `bound - 1` with borrows and range checking the
+    /// differences.
+    fn assert_less_than(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        chips: &Ed25519ChipsRef<F>,
+        x: &AssignedEdInteger<F>,
+        bound: &BigUint,
+    ) -> Result<(), Error> {
+        let main_gate = chips.main_gate;
+        let num_limbs = x.limbs.len();
+        let bound_limbs = limbs(&(bound - 1u64), num_limbs);
+        let x_limbs = limbs(&x.value, num_limbs);
+        let pow_2_64 = F::from_u128(1 << ED25519_BIT_LEN_LIMB);
+
+        let mut borrow: Option<AssignedValue<F>> = None;
+        let mut borrow_value = 0i128;
+        for i in 0..num_limbs {
+            // diff_i = bound_i - x_i - borrow_{i-1} + 2^64 * borrow_i, where the last borrow
+            // is 0.
+            let diff = bound_limbs[i] as i128 - x_limbs[i] as i128 - borrow_value;
+            borrow_value = (diff < 0) as i128;
+            let diff = diff + (borrow_value << ED25519_BIT_LEN_LIMB);
+            let (diff, _) = chips.range_chip.decompose(
+                ctx,
+                Value::known(F::from_u128(diff as u128)),
+                8,
+                ED25519_BIT_LEN_LIMB,
+            )?;
+            let mut terms = vec![
+                maingate::Term::Assigned(&x.limbs[i], -F::ONE),
+                maingate::Term::Assigned(&diff, -F::ONE),
+            ];
+            if let Some(borrow) = borrow.as_ref() {
+                terms.push(maingate::Term::Assigned(borrow, -F::ONE));
+            }
+            let next_borrow = if i < num_limbs - 1 {
+                Some(main_gate.assign_bit(ctx, Value::known(F::from(borrow_value as u64)))?)
+            } else {
+                None
+            };
+            if let Some(next_borrow) = next_borrow.as_ref() {
+                terms.push(maingate::Term::Assigned(next_borrow, pow_2_64));
+            }
+            let position = main_gate.compose(ctx, &terms, F::from(bound_limbs[i]))?;
+            main_gate.assert_zero(ctx, &position)?;
+            borrow = next_borrow;
+        }
+        Ok(())
+    }
+
+    /// Return the sum of two points, by the complete addition formulas of the
+    /// twisted Edwards curves with a = -1:
+    /// x3 = (x1 y2 + y1 x2) / (1 + d x1 x2 y1 y2) and
+    /// y3 = (y1 y2 + x1 x2) / (1 - d x1 x2 y1 y2).
+    /// The coordinates of the sum are witnessed and constrained by
+    /// multiplication with the denominators.
+    fn add(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        chips: &Ed25519ChipsRef<F>,
+        p1: &AssignedEdPoint<F>,
+        p2: &AssignedEdPoint<F>,
+    ) -> Result<AssignedEdPoint<F>, Error> {
+        let constants = chips.constants;
+        let x1x2 = self.mul_add_mod(ctx, chips, &p1.x, &p2.x, &[])?;
+        let y1y2 = self.mul_add_mod(ctx, chips, &p1.y, &p2.y, &[])?;
+        let y1x2 = self.mul_add_mod(ctx, chips, &p1.y, &p2.x, &[])?;
+        let x1x2y1y2 = self.mul_add_mod(ctx, chips, &x1x2, &y1y2, &[])?;
+        let num_x = self.mul_add_mod(ctx, chips, &p1.x, &p2.y, &[&y1x2])?;
+        let num_y = self.mul_add_mod(ctx, chips, &constants.one, &y1y2, &[&x1x2])?;
+        let den_x = self.mul_add_mod(ctx, chips, &constants.d, &x1x2y1y2, &[&constants.one])?;
+        let den_y = self.mul_add_mod(ctx, chips, &constants.neg_d, &x1x2y1y2, &[&constants.one])?;
+
+        let sum = p1.value().add(&p2.value());
+        let x3 = self.assign_integer(ctx, chips.range_chip, &sum.x, ED25519_NUMBER_OF_LIMBS)?;
+        let y3 = self.assign_integer(ctx, chips.range_chip, &sum.y, ED25519_NUMBER_OF_LIMBS)?;
+        self.constrain_mul_add_mod(ctx, chips, &x3, &den_x, &[], &num_x)?;
+        self.constrain_mul_add_mod(ctx, chips, &y3, &den_y, &[], &num_y)?;
+        Ok(AssignedEdPoint { x: x3, y: y3 })
+    }
+
+    /// Return `-point = (-x, y)`.
+    fn neg(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        chips: &Ed25519ChipsRef<F>,
+        point: &AssignedEdPoint<F>,
+    ) -> Result<AssignedEdPoint<F>, Error> {
+        let constants = chips.constants;
+        let neg_x = self.assign_integer(
+            ctx,
+            chips.range_chip,
+            &point.value().neg().x,
+            ED25519_NUMBER_OF_LIMBS,
+        )?;
+        // -x + x = 0 mod p
+        self.constrain_mul_add_mod(
+            ctx,
+            chips,
+            &constants.one,
+            &neg_x,
+            &[&point.x],
+            &constants.zero,
+        )?;
+        Ok(AssignedEdPoint {
+            x: neg_x,
+            y: point.y.clone(),
+        })
+    }
+
+    /// Return `a` if `cond` is 1, and `b` otherwise.
+    fn select(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        main_gate: &MainGate<F>,
+        (cond, cond_value): (&AssignedValue<F>, bool),
+        a: &AssignedEdPoint<F>,
+        b: &AssignedEdPoint<F>,
+    ) -> Result<AssignedEdPoint<F>, Error> {
+        let mut select_integer = |a: &AssignedEdInteger<F>, b: &AssignedEdInteger<F>| {
+            Ok::<_, Error>(AssignedEdInteger {
+                value: if cond_value { &a.value } else { &b.value }.clone(),
+                limbs: a
+                    .limbs
+                    .iter()
+                    .zip_eq(&b.limbs)
+                    .map(|(a, b)| main_gate.select(ctx, a, b, cond))
+                    .collect::<Result<_, _>>()?,
+                bytes_le: vec![],
+            })
+        };
+        Ok(AssignedEdPoint {
+            x: select_integer(&a.x, &b.x)?,
+            y: select_integer(&a.y, &b.y)?,
+        })
+    }
+
+    /// Decode a point from its 32 little endian encoding bytes, as defined in
+    /// RFC 8032 section 5.1.3: y is canonical, x is the canonical square
+    /// root of `(y^2 - 1) / (d y^2 + 1)` whose parity is the top bit of the
+    /// encoding.  Return the assigned encoding bytes and the point.
+    fn assign_decoded_point(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        chips: &Ed25519ChipsRef<F>,
+        encoding: &[u8; 32],
+    ) -> Result<(Vec<AssignedValue<F>>, AssignedEdPoint<F>), Error> {
+        let main_gate = chips.main_gate;
+        let constants = chips.constants;
+        // Encodings that are not points can't be verified, so any witness fails.
+        let point = EdwardsPoint::decompress(encoding).unwrap_or_else(EdwardsPoint::identity);
+        let (_, encoding_cells) = assign_bytes_le(ctx, chips.range_chip, encoding)?;
+
+        // y is the encoding without its top bit, which is the parity of x
+        let top_byte_bits = main_gate.to_bits(ctx, &encoding_cells[31], 8)?;
+        let mut y_limbs = encoding_cells[..24]
+            .chunks(8)
+            .map(|bytes| compose_bytes_le(ctx, main_gate, bytes))
+            .collect::<Result<Vec<_>, _>>()?;
+        let y_top_terms = encoding_cells[24..31]
+            .iter()
+            .enumerate()
+            .map(|(i, byte)| maingate::Term::Assigned(byte, F::from(1 << (8 * i))))
+            .chain(
+                top_byte_bits[..7]
+                    .iter()
+                    .enumerate()
+                    .map(|(i, bit)| maingate::Term::Assigned(bit, F::from(1 << (56 + i)))),
+            )
+            .collect_vec();
+        y_limbs.push(main_gate.compose(ctx, &y_top_terms, F::ZERO)?);
+        let y = AssignedEdInteger {
+            value: point.y.clone(),
+            limbs: y_limbs,
+            bytes_le: vec![],
+        };
+        self.assert_less_than(ctx, chips, &y, &ED25519_P)?;
+
+        let x = self.assign_integer(ctx, chips.range_chip, &point.x, ED25519_NUMBER_OF_LIMBS)?;
+        self.assert_less_than(ctx, chips, &x, &ED25519_P)?;
+        let x_low_bits = main_gate.to_bits(ctx, &x.bytes_le[0], 8)?;
+        main_gate.assert_equal(ctx, &x_low_bits[0], &top_byte_bits[7])?;
+
+        // The point is on the curve: x^2 (d y^2 + 1) + 1 = y^2
+        let yy = self.mul_add_mod(ctx, chips, &y, &y, &[])?;
+        let den = self.mul_add_mod(ctx, chips, &constants.d, &yy, &[&constants.one])?;
+        let xx = self.mul_add_mod(ctx, chips, &x, &x, &[])?;
+        self.constrain_mul_add_mod(ctx, chips, &xx, &den, &[&constants.one], &yy)?;
+
+        Ok((encoding_cells, AssignedEdPoint { x, y }))
+    }
+
+    /// Return the first `SCALAR_BITS` little endian bits of a scalar, with
+    /// their values.
+    fn assign_scalar_bits(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        main_gate: &MainGate<F>,
+        scalar: &AssignedEdInteger<F>,
+    ) -> Result<Vec<(AssignedValue<F>, bool)>, Error> {
+        let mut bits = Vec::with_capacity(8 * scalar.bytes_le.len());
+        for byte in &scalar.bytes_le {
+            bits.extend(main_gate.to_bits(ctx, byte, 8)?);
+        }
+        Ok(bits
+            .into_iter()
+            .take(SCALAR_BITS)
+            .enumerate()
+            .map(|(i, bit)| (bit, scalar.value.bit(i as u64)))
+            .collect())
+    }
+
+    /// Verify a signature and return its address and message, as the
+    /// SignVerifyChip does for ECDSA signatures.  The recovery id is 0.
+    fn assign_signature_verify(
+        &self,
+        config: &Ed25519SignVerifyConfig,
+        ctx: &mut RegionCtx<F>,
+        chips: &Ed25519ChipsRef<F>,
+        sign_data: Option<&Ed25519SignData>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<AssignedSignatureVerify<F>, Error> {
+        let main_gate = chips.main_gate;
+        let range_chip = chips.range_chip;
+        let constants = chips.constants;
+        let (padding, sign_data) = match sign_data {
+            Some(sign_data) => (false, sign_data.clone()),
+            None => (true, Ed25519SignData::padding()),
+        };
+
+        let (r_cells, r) = self.assign_decoded_point(ctx, chips, &sign_data.r())?;
+        let (pk_cells, pk) = self.assign_decoded_point(ctx, chips, &sign_data.pk)?;
+
+        // S < L
+        let (_, s_bytes) = assign_bytes_le(
+            ctx,
+            range_chip,
+            &sign_data.signature[32..].try_into().unwrap(),
+        )?;
+        let s = self.compose_integer(ctx, main_gate, &s_bytes, sign_data.s())?;
+        self.assert_less_than(ctx, chips, &s, &ED25519_L)?;
+
+        // The digest words are the big endian values of 16 bytes of the digest, as in the
+        // Sha512Table.
+        let digest = sha512(&sign_data.sha512_input());
+        let mut digest_words = Vec::with_capacity(4);
+        let mut digest_cells = Vec::with_capacity(64);
+        for bytes in digest.chunks(16) {
+            let word = bytes
+                .iter()
+                .fold(0u128, |acc, byte| (acc << 8) + *byte as u128);
+            let (word, bytes_le) =
+                range_chip.decompose(ctx, Value::known(F::from_u128(word)), 8, 128)?;
+            digest_words.push(word);
+            digest_cells.extend(bytes_le.into_iter().rev());
+        }
+        // k = h mod L, where h is the little endian integer of the digest
+        let h = self.compose_integer(
+            ctx,
+            main_gate,
+            &digest_cells,
+            BigUint::from_bytes_le(&digest),
+        )?;
+        let k = self.assign_integer(
+            ctx,
+            range_chip,
+            &sign_data.challenge(),
+            ED25519_NUMBER_OF_LIMBS,
+        )?;
+        let q = self.assign_integer(
+            ctx,
+            range_chip,
+            &(&h.value / &*ED25519_L),
+            QUOTIENT_NUMBER_OF_LIMBS,
+        )?;
+        let ql = self.assign_limb_products(ctx, main_gate, &q, &constants.l)?;
+        self.assert_equal_positions(
+            ctx,
+            chips,
+            &[Positions::from(&h)],
+            &[ql, Positions::from(&k)],
+        )?;
+        self.assert_less_than(ctx, chips, &k, &ED25519_L)?;
+
+        // [S]B + [k](-A) = R, by double-and-add over the bits of S and k from the most
+        // significant one, adding B, -A, B - A or the identity.
+        let neg_pk = self.neg(ctx, chips, &pk)?;
+        let base_neg_pk = self.add(ctx, chips, &constants.base, &neg_pk)?;
+        let identity = AssignedEdPoint {
+            x: constants.zero.clone(),
+            y: constants.one.clone(),
+        };
+        let s_bits = self.assign_scalar_bits(ctx, main_gate, &s)?;
+        let k_bits = self.assign_scalar_bits(ctx, main_gate, &k)?;
+        let mut acc = identity.clone();
+        for (s_bit, k_bit) in s_bits.iter().zip(&k_bits).rev() {
+            acc = self.add(ctx, chips, &acc, &acc)?;
+            let k_bit = (&k_bit.0, k_bit.1);
+            let with_base = self.select(ctx, main_gate, k_bit, &base_neg_pk, &constants.base)?;
+            let without_base = self.select(ctx, main_gate, k_bit, &neg_pk, &identity)?;
+            let point = self.select(
+                ctx,
+                main_gate,
+                (&s_bit.0, s_bit.1),
+                &with_base,
+                &without_base,
+            )?;
+            acc = self.add(ctx, chips, &acc, &point)?;
+        }
+        for (acc, r) in [(&acc.x, &r.x), (&acc.y, &r.y)] {
+            self.constrain_mul_add_mod(ctx, chips, &constants.one, acc, &[], r)?;
+        }
+
+        // Verify that the address is the end of keccak(A) as for an ECDSA public key
+        let mut pk_hash = (!padding)
+            .then(|| keccak256(&sign_data.pk))
+            .unwrap_or_default();
+        pk_hash.reverse();
+        let (is_padding, address_cells, pk_hash_cells) =
+            assign_address(ctx, main_gate, range_chip, padding, &pk_hash)?;
+
+        // The message is the tx sign hash
+        let mut msg_le = sign_data.msg;
+        msg_le.reverse();
+        let (msg_hash_cells, msg_le_cells) = assign_bytes_le(ctx, range_chip, &msg_le)?;
+
+        let pk_rlc = assign_rlc_le(
+            ctx,
+            main_gate,
+            &config.main_gate_config,
+            config.rlc,
+            "ed25519_pk",
+            config.q_rlc_input,
+            challenges.keccak_input(),
+            pk_cells.iter().rev().map(assigned_term),
+        )?;
+        let sha512_input_rlc = assign_rlc_le(
+            ctx,
+            main_gate,
+            &config.main_gate_config,
+            config.rlc,
+            "ed25519_sha512_input",
+            config.q_rlc_input,
+            challenges.keccak_input(),
+            msg_le_cells
+                .iter()
+                .chain(pk_cells.iter().rev())
+                .chain(r_cells.iter().rev())
+                .map(assigned_term),
+        )?;
+
+        enable_lookup(
+            ctx,
+            config.q_keccak,
+            &config.main_gate_config,
+            config.rlc,
+            &is_padding,
+            &pk_rlc,
+            &pk_hash_cells,
+        )?;
+        for (q_sha512, words) in config.q_sha512.iter().zip(digest_words.chunks(2)) {
+            enable_lookup(
+                ctx,
+                *q_sha512,
+                &config.main_gate_config,
+                config.rlc,
+                &is_padding,
+                &sha512_input_rlc,
+                &Word::new([words[0].clone(), words[1].clone()]),
+            )?;
+        }
+
+        Ok(AssignedSignatureVerify {
+            is_padding,
+            recovery_id: main_gate.assign_constant(ctx, F::ZERO)?,
+            address: address_cells,
+            msg_hash: msg_hash_cells,
+        })
+    }
+
+    /// Assign the verification of `signatures[i]` to the slot `i`.  Slots past the end of
+    /// `signatures`, or whose signature is `None`, are padding.
+    pub(crate) fn assign(
+        &self,
+        config: &Ed25519SignVerifyConfig,
+        layouter: &mut impl Layouter<F>,
+        signatures: &[Option<Ed25519SignData>],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<Vec<AssignedSignatureVerify<F>>, Error> {
+        if signatures.len() > self.max_verif {
+            error!(
+                "signatures.len() = {} > max_verif = {}",
+                signatures.len(),
+                self.max_verif
+            );
+            return Err(Error::Synthesis);
+        }
+        let main_gate = MainGate::new(config.main_gate_config.clone());
+        let range_chip = RangeChip::new(config.range_config.clone());
+
+        layouter.assign_region(
+            || "ed25519 signature verification",
+            |region| {
+                let mut assigned_sig_verifs = Vec::new();
+                let mut ctx = RegionCtx::new(region, 0);
+                let constants = self.assign_constants(&mut ctx, &main_gate)?;
+                let chips = Ed25519ChipsRef {
+                    main_gate: &main_gate,
+                    range_chip: &range_chip,
+                    constants: &constants,
+                };
+                for i in 0..self.max_verif {
+                    let sign_data = signatures.get(i).and_then(Option::as_ref); // None when padding
+                    let assigned_sig_verif = self
+                        .assign_signature_verify(config, &mut ctx, &chips, sign_data, challenges)?;
+                    assigned_sig_verifs.push(assigned_sig_verif);
+                }
+                log::debug!("ed25519 signature verification: {} rows", ctx.offset());
+                Ok(assigned_sig_verifs)
+            },
+        )
+    }
+}
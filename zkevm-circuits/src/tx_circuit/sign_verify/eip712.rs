@@ -0,0 +1,290 @@
+//! Circuit to verify that the messages signed by smart accounts are EIP-712 digests:
+//! `keccak(0x19 || 0x01 || domainSeparator || structHash)`, where the struct hash of a tx is its
+//! TxSignHash.
+//!
+//! Every slot holds an `is_eip712` bit.  The digest of the slots signed as typed data is checked
+//! by a keccak lookup against the message hash of their signature, and their message hash is
+//! replaced by the struct hash, so that the TxSignHash of the tx table is the struct hash like
+//! for the other txs.  The domain separator and the `is_eip712` bits are exposed as public
+//! inputs in the instance column of the MainGate, so that the verifier knows which txs were
+//! signed as typed data, and for which domain.
+//!
+//! The message hash of a secp256k1 signature is the digest reduced modulo n, so the lookup
+//! fails for the digests above n, which only happens with negligible probability.
+
+use super::{
+    assign_bytes_le, assign_rlc_le, assigned_term, enable_lookup, AssignedSignatureVerify,
+    SignVerifyLaneConfig,
+};
+use crate::{
+    table::KeccakTable,
+    util::{word::Word, Challenges},
+};
+use eth_types::{Field, H256};
+use halo2_proofs::{
+    circuit::{Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, SecondPhase, Selector},
+};
+use log::error;
+use maingate::{
+    MainGate, MainGateConfig, MainGateInstructions, RangeChip, RangeConfig, RangeInstructions,
+    RegionCtx,
+};
+use std::{iter, marker::PhantomData};
+
+/// Length of the EIP-712 encoding `0x19 || 0x01 || domainSeparator || structHash`
+const EIP712_ENCODING_LEN: usize = 2 + 32 + 32;
+
+/// Auxiliary Gadget to verify that the signed messages of the slots signed as
+/// typed data are the EIP-712 digests of their struct hashes under a domain
+/// separator.
+#[derive(Clone, Debug)]
+pub struct Eip712DigestChip<F: Field> {
+    /// Domain separator of the typed data
+    pub domain_separator: H256,
+    /// Max number of verifications
+    pub max_verif: usize,
+    /// Marker
+    pub _marker: PhantomData<F>,
+}
+
+impl<F: Field> Default for Eip712DigestChip<F> {
+    fn default() -> Self {
+        Self::new(H256::zero(), 0)
+    }
+}
+
+impl<F: Field> Eip712DigestChip<F> {
+    /// Return a new Eip712DigestChip
+    pub fn new(domain_separator: H256, max_verif: usize) -> Self {
+        Self {
+            domain_separator,
+            max_verif,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the minimum number of rows required to prove an input of a
+    /// particular size.  The bytes of the domain separator take less rows
+    /// than a verification.
+    pub fn min_num_rows(num_verif: usize) -> usize {
+        std::cmp::max(
+            Self::rows_range_chip_table(),
+            Self::rows_per_verif() * (num_verif + 1),
+        )
+    }
+
+    /// Return the number of rows of the RangeChip lookup table, which only
+    /// range checks bytes.
+    pub fn rows_range_chip_table() -> usize {
+        1 << 8
+    }
+
+    /// Return the number of rows used by one verification.  It's an upper
+    /// bound of the bytes of the struct hash (about 10 rows), the RLC of the
+    /// encoding (15 rows) and the selection of the message hash.
+    pub fn rows_per_verif() -> usize {
+        48
+    }
+
+    /// Return the public inputs of the chip: the domain separator (lo, hi),
+    /// followed by the `is_eip712` bit of every slot.
+    pub fn instance(&self, struct_hashes: &[Option<[u8; 32]>]) -> Vec<F> {
+        let domain_separator = Word::<F>::from(self.domain_separator);
+        [domain_separator.lo(), domain_separator.hi()]
+            .into_iter()
+            .chain(
+                struct_hashes
+                    .iter()
+                    .map(|struct_hash| F::from(struct_hash.is_some() as u64))
+                    .chain(iter::repeat(F::ZERO))
+                    .take(self.max_verif),
+            )
+            .collect()
+    }
+}
+
+/// Eip712Digest Configuration
+#[derive(Debug, Clone)]
+pub(crate) struct Eip712DigestConfig {
+    main_gate_config: MainGateConfig,
+    range_config: RangeConfig,
+    // RLC of the keccak inputs
+    q_rlc_input: Selector,
+    rlc: Column<Advice>,
+    // Keccak of the EIP-712 encodings
+    q_keccak: Selector,
+    _keccak_table: KeccakTable,
+}
+
+impl Eip712DigestConfig {
+    pub(crate) fn new<F: Field>(
+        meta: &mut ConstraintSystem<F>,
+        keccak_table: KeccakTable,
+        challenges: Challenges<Expression<F>>,
+    ) -> Self {
+        let main_gate_config = MainGate::<F>::configure(meta);
+        let range_config = RangeChip::<F>::configure(meta, &main_gate_config, vec![8], vec![]);
+
+        // RLC
+        let q_rlc_input = meta.selector();
+        let rlc = meta.advice_column_in(SecondPhase);
+        meta.enable_equality(rlc);
+        SignVerifyLaneConfig::configure_rlc(
+            meta,
+            "eip712_input_rlc",
+            main_gate_config.clone(),
+            q_rlc_input,
+            rlc,
+            challenges.keccak_input(),
+        );
+
+        // Verify that keccak(0x19 || 0x01 || domainSeparator || structHash) = msg_hash by
+        // keccak table lookup.
+        let q_keccak = meta.complex_selector();
+        SignVerifyLaneConfig::configure_lookup(
+            meta,
+            "eip712 keccak",
+            &main_gate_config,
+            q_keccak,
+            rlc,
+            EIP712_ENCODING_LEN,
+            [
+                keccak_table.is_enabled,
+                keccak_table.input_rlc,
+                keccak_table.input_len,
+                keccak_table.output.lo(),
+                keccak_table.output.hi(),
+            ],
+        );
+
+        Self {
+            main_gate_config,
+            range_config,
+            q_rlc_input,
+            rlc,
+            q_keccak,
+            _keccak_table: keccak_table,
+        }
+    }
+
+    pub(crate) fn load_range<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let range_chip = RangeChip::<F>::new(self.range_config.clone());
+        range_chip.load_table(layouter)
+    }
+}
+
+impl<F: Field> Eip712DigestChip<F> {
+    /// Verify that the message hash of the slot `i` is the EIP-712 digest of
+    /// `struct_hashes[i]` when it's `Some`, and return the verifications with
+    /// the struct hash as the message hash of these slots.  The other slots,
+    /// which must include the padding ones, are returned unchanged.
+    pub(crate) fn assign(
+        &self,
+        config: &Eip712DigestConfig,
+        layouter: &mut impl Layouter<F>,
+        assigned_sig_verifs: Vec<AssignedSignatureVerify<F>>,
+        struct_hashes: &[Option<[u8; 32]>],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<Vec<AssignedSignatureVerify<F>>, Error> {
+        if assigned_sig_verifs.len() != self.max_verif || struct_hashes.len() > self.max_verif {
+            error!(
+                "assigned_sig_verifs.len() = {}, struct_hashes.len() = {}, max_verif = {}",
+                assigned_sig_verifs.len(),
+                struct_hashes.len(),
+                self.max_verif
+            );
+            return Err(Error::Synthesis);
+        }
+        let main_gate = MainGate::new(config.main_gate_config.clone());
+        let range_chip = RangeChip::new(config.range_config.clone());
+
+        let (public_cells, assigned_sig_verifs) = layouter.assign_region(
+            || "eip712 digests",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+                let mut domain_separator_le = self.domain_separator.to_fixed_bytes();
+                domain_separator_le.reverse();
+                let (domain_separator_cells, domain_separator_le_cells) =
+                    assign_bytes_le(&mut ctx, &range_chip, &domain_separator_le)?;
+                let prefix_le_cells = [0x01, 0x19]
+                    .into_iter()
+                    .map(|byte| main_gate.assign_constant(&mut ctx, F::from(byte)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let mut public_cells =
+                    vec![domain_separator_cells.lo(), domain_separator_cells.hi()];
+
+                let mut eip712_sig_verifs = Vec::with_capacity(self.max_verif);
+                for (i, assigned_sig_verif) in assigned_sig_verifs.iter().enumerate() {
+                    let struct_hash = struct_hashes.get(i).copied().flatten();
+                    let is_eip712 = main_gate.assign_bit(
+                        &mut ctx,
+                        Value::known(F::from(struct_hash.is_some() as u64)),
+                    )?;
+                    // Padding slots have no signed message
+                    let is_padding_eip712 =
+                        main_gate.and(&mut ctx, &is_eip712, &assigned_sig_verif.is_padding)?;
+                    main_gate.assert_zero(&mut ctx, &is_padding_eip712)?;
+
+                    let mut struct_hash_le = struct_hash.unwrap_or_default();
+                    struct_hash_le.reverse();
+                    let (struct_hash_cells, struct_hash_le_cells) =
+                        assign_bytes_le(&mut ctx, &range_chip, &struct_hash_le)?;
+                    let encoding_rlc = assign_rlc_le(
+                        &mut ctx,
+                        &main_gate,
+                        &config.main_gate_config,
+                        config.rlc,
+                        "eip712_encoding",
+                        config.q_rlc_input,
+                        challenges.keccak_input(),
+                        struct_hash_le_cells
+                            .iter()
+                            .chain(&domain_separator_le_cells)
+                            .chain(&prefix_le_cells)
+                            .map(assigned_term),
+                    )?;
+                    // The lookup is skipped for the slots not signed as typed data
+                    let skip_lookup = main_gate.not(&mut ctx, &is_eip712)?;
+                    enable_lookup(
+                        &mut ctx,
+                        config.q_keccak,
+                        &config.main_gate_config,
+                        config.rlc,
+                        &skip_lookup,
+                        &encoding_rlc,
+                        &assigned_sig_verif.msg_hash,
+                    )?;
+
+                    let msg_hash = [
+                        (struct_hash_cells.lo(), assigned_sig_verif.msg_hash.lo()),
+                        (struct_hash_cells.hi(), assigned_sig_verif.msg_hash.hi()),
+                    ]
+                    .into_iter()
+                    .map(|(struct_hash, msg_hash)| {
+                        main_gate.select(&mut ctx, &struct_hash, &msg_hash, &is_eip712)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                    public_cells.push(is_eip712);
+                    eip712_sig_verifs.push(AssignedSignatureVerify {
+                        is_padding: assigned_sig_verif.is_padding.clone(),
+                        recovery_id: assigned_sig_verif.recovery_id.clone(),
+                        address: assigned_sig_verif.address.clone(),
+                        msg_hash: Word::new(msg_hash.try_into().unwrap()),
+                    });
+                }
+                log::debug!("eip712 digests: {} rows", ctx.offset());
+                Ok((public_cells, eip712_sig_verifs))
+            },
+        )?;
+
+        // Expose the domain separator and the is_eip712 bits, in the order of `Self::instance`
+        for (row, cell) in public_cells.into_iter().enumerate() {
+            main_gate.expose_public(layouter.namespace(|| "eip712 instance"), cell, row)?;
+        }
+        Ok(assigned_sig_verifs)
+    }
+}
@@ -0,0 +1,859 @@
+//! Circuit to verify multiple RSA-2048 PKCS#1 v1.5 or PSS signatures with SHA-256 and the
+//! public exponent 65537, as produced by the My Number Card and newer smart cards.
+//!
+//! The big integers are split into 32 limbs of 64 bits.  A modular multiplication
+//! `a * b = q * n + r` is checked limb by limb: the limb products of every position are summed
+//! and the carries between positions are witnessed and range checked, so that the equation holds
+//! over the integers.  The integers are only constrained to be smaller than 2^2048, which is
+//! enough as the signature only needs to be congruent to the encoded message modulo n.
+//!
+//! The padding of every signature is selected by a witnessed bit, so that both the
+//! EMSA-PKCS1-v1_5 and the EMSA-PSS checks of the encoded message are laid out for every slot
+//! and only the ones of its padding are enforced.
+
+use super::{
+    assign_address, assign_rlc_le, compose_bytes_le, count_rows, enable_lookup,
+    AssignedSignatureVerify, SignVerifyLaneConfig, Term,
+};
+use crate::{
+    evm_circuit::util::from_bytes,
+    table::{KeccakTable, Sha256Table},
+    util::{word::Word, Challenges},
+};
+#[cfg(feature = "sha1")]
+use eth_types::rsa_types::emsa_pkcs1_v15_sha1;
+use eth_types::{
+    keccak256,
+    rsa_types::{
+        biguint_to_rsa_bytes_be, emsa_pkcs1_v15_sha256, mgf1_sha256, RsaPadding, RsaPublicKey,
+        RsaSignData, PSS_DB_BYTES, PSS_PS_BYTES, PSS_SALT_BYTES, PSS_TRAILER, RSA_2048_BYTES,
+        RSA_PUBLIC_EXPONENT,
+    },
+    Field,
+};
+use halo2_proofs::{
+    circuit::{Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, SecondPhase, Selector},
+};
+use itertools::Itertools;
+use log::error;
+use maingate::{
+    AssignedValue, MainGate, MainGateConfig, MainGateInstructions, RangeChip, RangeConfig,
+    RangeInstructions, RegionCtx,
+};
+use num_bigint::{BigInt, BigUint};
+use std::{iter, marker::PhantomData};
+
+/// Number of limbs of an RSA-2048 integer
+const RSA_NUMBER_OF_LIMBS: usize = 32;
+/// Number of bits of a limb of an RSA-2048 integer
+const RSA_BIT_LEN_LIMB: usize = 64;
+/// Number of bits of a range checked carry between limb positions, offset by `2^71` to be
+/// non-negative.  The sum of the limb products of a position is below 2^134 in absolute value, so
+/// the carries are below 2^70.
+const RSA_BIT_LEN_CARRY: usize = 72;
+/// Number of limbs of the little endian digest at the end of the encoded message
+const DIGEST_LIMBS: usize = 32 * 8 / RSA_BIT_LEN_LIMB;
+/// Number of SHA-256 blocks of the MGF1 mask of a PSS data block
+const PSS_MGF1_BLOCKS: usize = (PSS_DB_BYTES + 31) / 32;
+
+/// Auxiliary Gadget to verify that a message is signed with RSA-2048 PKCS#1 v1.5 by the public
+/// key corresponding to an Ethereum Address: `keccak(n)[12..]`, like for a secp256k1 public key.
+#[derive(Clone, Debug)]
+pub struct RsaSignVerifyChip<F: Field> {
+    /// Max number of verifications
+    pub max_verif: usize,
+    /// Marker
+    pub _marker: PhantomData<F>,
+}
+
+impl<F: Field> Default for RsaSignVerifyChip<F> {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl<F: Field> RsaSignVerifyChip<F> {
+    /// Return a new RsaSignVerifyChip
+    pub fn new(max_verif: usize) -> Self {
+        Self {
+            max_verif,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the minimum number of rows required to prove an input of a
+    /// particular size.
+    pub fn min_num_rows(num_verif: usize) -> usize {
+        std::cmp::max(
+            Self::rows_range_chip_table(),
+            Self::rows_per_verif() * num_verif,
+        )
+    }
+
+    /// Return the number of rows of the RangeChip lookup table, which only
+    /// range checks bytes.
+    pub fn rows_range_chip_table() -> usize {
+        1 << 8
+    }
+
+    /// Return the number of rows used by one signature verification, counted by
+    /// laying out a padding verification.  The checks of the encoded message
+    /// for both paddings are laid out for every slot, so that every
+    /// verification uses as many rows as the padding one.
+    pub fn rows_per_verif() -> usize {
+        let mut meta = ConstraintSystem::<F>::default();
+        let keccak_table = KeccakTable::construct(&mut meta);
+        let sha256_table = Sha256Table::construct(&mut meta);
+        let challenges = Challenges::construct(&mut meta).exprs(&mut meta);
+        let config = RsaSignVerifyConfig::new(&mut meta, keccak_table, sha256_table, challenges);
+
+        let main_gate = MainGate::new(config.main_gate_config.clone());
+        let range_chip = RangeChip::new(config.range_config.clone());
+        let (rows, _) = count_rows(|ctx| {
+            Self::default().assign_signature_verify(
+                &config,
+                ctx,
+                &main_gate,
+                &range_chip,
+                None,
+                &Challenges::mock(Value::unknown(), Value::unknown()),
+            )
+        });
+        rows
+    }
+}
+
+/// Return the padding signature data: the signature 0 under the modulus 1,
+/// for which every modular multiplication is 0 * 0 = 0 * 1 + 0.  The
+/// encoded message and the hash lookups are not checked for padding slots.
+fn padding_sign_data() -> RsaSignData {
+    RsaSignData {
+        signature: BigUint::from(0u64),
+        padding: RsaPadding::Pkcs1v15,
+        pk: RsaPublicKey {
+            n: BigUint::from(1u64),
+        },
+        msg: [0; 32],
+    }
+}
+
+/// Return the limbs of an integer of at most 2048 bits.
+fn limbs(value: &BigUint) -> [u64; RSA_NUMBER_OF_LIMBS] {
+    let mut limbs = [0; RSA_NUMBER_OF_LIMBS];
+    for (limb, digit) in limbs.iter_mut().zip_eq(
+        value
+            .to_u64_digits()
+            .into_iter()
+            .pad_using(RSA_NUMBER_OF_LIMBS, |_| 0),
+    ) {
+        *limb = digit;
+    }
+    limbs
+}
+
+/// Return the RLC input term of an assigned byte.
+pub(crate) fn assigned_term<F: Field>(assigned: &AssignedValue<F>) -> Term<F> {
+    Term::assigned(assigned.cell(), assigned.value().copied())
+}
+
+/// Assign 32 little endian bytes, range checked, and return the word they
+/// compose and the byte cells.
+pub(crate) fn assign_bytes_le<F: Field>(
+    ctx: &mut RegionCtx<F>,
+    range_chip: &RangeChip<F>,
+    bytes_le: &[u8; 32],
+) -> Result<(Word<AssignedValue<F>>, Vec<AssignedValue<F>>), Error> {
+    let mut words = Vec::with_capacity(2);
+    let mut byte_cells = Vec::with_capacity(32);
+    for bytes in bytes_le.chunks(16) {
+        let (word, bytes) =
+            range_chip.decompose(ctx, Value::known(from_bytes::value::<F>(bytes)), 8, 128)?;
+        words.push(word);
+        byte_cells.extend(bytes);
+    }
+    Ok((Word::new(words.try_into().unwrap()), byte_cells))
+}
+
+/// Constrain `cond * (sum(terms) + constant) = 0`.
+fn assert_zero_if<F: Field>(
+    ctx: &mut RegionCtx<F>,
+    main_gate: &MainGate<F>,
+    cond: &AssignedValue<F>,
+    terms: &[maingate::Term<F>],
+    constant: F,
+) -> Result<(), Error> {
+    let value = main_gate.compose(ctx, terms, constant)?;
+    let value = main_gate.mul(ctx, &value, cond)?;
+    main_gate.assert_zero(ctx, &value)
+}
+
+/// RsaSignVerify Configuration
+#[derive(Debug, Clone)]
+pub(crate) struct RsaSignVerifyConfig {
+    main_gate_config: MainGateConfig,
+    range_config: RangeConfig,
+    // RLC of the keccak and sha256 inputs
+    q_rlc_input: Selector,
+    rlc: Column<Advice>,
+    // Keccak of the modulus
+    q_keccak: Selector,
+    // Sha256 of the message
+    q_sha256: Selector,
+    // Sha256 of the MGF1 seeds of a PSS encoded message
+    q_sha256_mgf1: Selector,
+    // Sha256 of the message M' of a PSS encoded message
+    q_sha256_pss: Selector,
+    _keccak_table: KeccakTable,
+    _sha256_table: Sha256Table,
+}
+
+impl RsaSignVerifyConfig {
+    pub(crate) fn new<F: Field>(
+        meta: &mut ConstraintSystem<F>,
+        keccak_table: KeccakTable,
+        sha256_table: Sha256Table,
+        challenges: Challenges<Expression<F>>,
+    ) -> Self {
+        let main_gate_config = MainGate::<F>::configure(meta);
+        let range_config = RangeChip::<F>::configure(meta, &main_gate_config, vec![8], vec![]);
+
+        // RLC
+        let q_rlc_input = meta.selector();
+        let rlc = meta.advice_column_in(SecondPhase);
+        meta.enable_equality(rlc);
+        SignVerifyLaneConfig::configure_rlc(
+            meta,
+            "rsa_input_rlc",
+            main_gate_config.clone(),
+            q_rlc_input,
+            rlc,
+            challenges.keccak_input(),
+        );
+
+        // Verify that keccak(n_bytes) = pk_hash by keccak table lookup, where n_bytes are the
+        // big endian bytes of the modulus.
+        let q_keccak = meta.complex_selector();
+        SignVerifyLaneConfig::configure_lookup(
+            meta,
+            "rsa keccak",
+            &main_gate_config,
+            q_keccak,
+            rlc,
+            RSA_2048_BYTES,
+            [
+                keccak_table.is_enabled,
+                keccak_table.input_rlc,
+                keccak_table.input_len,
+                keccak_table.output.lo(),
+                keccak_table.output.hi(),
+            ],
+        );
+
+        // Verify that sha256(msg) = digest, sha256(H || counter) = mask block and
+        // sha256(M') = H by sha256 table lookups, where the digest is encoded in the encoded
+        // message, directly for PKCS#1 v1.5 and inside M' for PSS.
+        let sha256_table_columns = [
+            sha256_table.is_enabled,
+            sha256_table.input_rlc,
+            sha256_table.input_len,
+            sha256_table.output.lo(),
+            sha256_table.output.hi(),
+        ];
+        let [q_sha256, q_sha256_mgf1, q_sha256_pss] = [
+            ("rsa sha256", 32),
+            ("rsa sha256 mgf1", 32 + 4),
+            ("rsa sha256 pss", 8 + 32 + PSS_SALT_BYTES),
+        ]
+        .map(|(name, input_len)| {
+            let q_lookup = meta.complex_selector();
+            SignVerifyLaneConfig::configure_lookup(
+                meta,
+                name,
+                &main_gate_config,
+                q_lookup,
+                rlc,
+                input_len,
+                sha256_table_columns,
+            );
+            q_lookup
+        });
+
+        Self {
+            main_gate_config,
+            range_config,
+            q_rlc_input,
+            rlc,
+            q_keccak,
+            q_sha256,
+            q_sha256_mgf1,
+            q_sha256_pss,
+            _keccak_table: keccak_table,
+            _sha256_table: sha256_table,
+        }
+    }
+
+    pub(crate) fn load_range<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let range_chip = RangeChip::<F>::new(self.range_config.clone());
+        range_chip.load_table(layouter)
+    }
+}
+
+/// Integer of at most 2048 bits, assigned as range checked limbs whose bytes
+/// are assigned too.
+pub(crate) struct AssignedRsaInteger<F: Field> {
+    pub(crate) value: BigUint,
+    pub(crate) limbs: Vec<AssignedValue<F>>,
+    pub(crate) bytes_le: Vec<AssignedValue<F>>,
+}
+
+impl<F: Field> RsaSignVerifyChip<F> {
+    pub(crate) fn assign_integer(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        range_chip: &RangeChip<F>,
+        value: &BigUint,
+    ) -> Result<AssignedRsaInteger<F>, Error> {
+        let mut assigned_limbs = Vec::with_capacity(RSA_NUMBER_OF_LIMBS);
+        let mut bytes_le = Vec::with_capacity(RSA_2048_BYTES);
+        for limb in limbs(value) {
+            let (limb, bytes) =
+                range_chip.decompose(ctx, Value::known(F::from(limb)), 8, RSA_BIT_LEN_LIMB)?;
+            assigned_limbs.push(limb);
+            bytes_le.extend(bytes);
+        }
+        Ok(AssignedRsaInteger {
+            value: value.clone(),
+            limbs: assigned_limbs,
+            bytes_le,
+        })
+    }
+
+    /// Assign the sum of the limb products `a_i * b_j` with `i + j = k`, for
+    /// every position `k`.
+    fn assign_limb_products(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        main_gate: &MainGate<F>,
+        a: &AssignedRsaInteger<F>,
+        b: &AssignedRsaInteger<F>,
+    ) -> Result<Vec<AssignedValue<F>>, Error> {
+        (0..2 * RSA_NUMBER_OF_LIMBS - 1)
+            .map(|k| {
+                let mut pairs = (k.saturating_sub(RSA_NUMBER_OF_LIMBS - 1)
+                    ..=k.min(RSA_NUMBER_OF_LIMBS - 1))
+                    .map(|i| (&a.limbs[i], &b.limbs[k - i]));
+                let (a_i, b_j) = pairs.next().expect("every position has a product");
+                let first = main_gate.mul(ctx, a_i, b_j)?;
+                pairs.try_fold(first, |acc, (a_i, b_j)| {
+                    main_gate.mul_add(ctx, a_i, b_j, &acc)
+                })
+            })
+            .collect()
+    }
+
+    /// Return `a * b mod n`, constraining `a * b = q * n + r` over the
+    /// integers.
+    fn mul_mod(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        main_gate: &MainGate<F>,
+        range_chip: &RangeChip<F>,
+        a: &AssignedRsaInteger<F>,
+        b: &AssignedRsaInteger<F>,
+        n: &AssignedRsaInteger<F>,
+    ) -> Result<AssignedRsaInteger<F>, Error> {
+        let product = &a.value * &b.value;
+        let q = self.assign_integer(ctx, range_chip, &(&product / &n.value))?;
+        let r = self.assign_integer(ctx, range_chip, &(&product % &n.value))?;
+
+        let ab = self.assign_limb_products(ctx, main_gate, a, b)?;
+        let qn = self.assign_limb_products(ctx, main_gate, &q, n)?;
+
+        // Witnesses of the sums of the products of every position, to compute the carries
+        let position_values = |x: &BigUint, y: &BigUint| {
+            let (x, y) = (limbs(x), limbs(y));
+            (0..2 * RSA_NUMBER_OF_LIMBS - 1)
+                .map(|k| {
+                    (k.saturating_sub(RSA_NUMBER_OF_LIMBS - 1)..=k.min(RSA_NUMBER_OF_LIMBS - 1))
+                        .map(|i| BigInt::from(x[i]) * BigInt::from(y[k - i]))
+                        .sum::<BigInt>()
+                })
+                .collect_vec()
+        };
+        let ab_values = position_values(&a.value, &b.value);
+        let qn_values = position_values(&q.value, &n.value);
+        let r_values = limbs(&r.value);
+
+        let pow_2_64 = F::from_u128(1 << RSA_BIT_LEN_LIMB);
+        let carry_offset = F::from_u128(1 << (RSA_BIT_LEN_CARRY - 1));
+        let mut carry_value = BigInt::from(0);
+        let mut carry: Option<AssignedValue<F>> = None;
+        for k in 0..2 * RSA_NUMBER_OF_LIMBS - 1 {
+            // ab_k - qn_k - r_k + carry_{k-1} = 2^64 * carry_k, where the carries are assigned
+            // shifted by the carry offset, and the last carry is 0.
+            let mut terms = vec![
+                maingate::Term::Assigned(&ab[k], F::ONE),
+                maingate::Term::Assigned(&qn[k], -F::ONE),
+            ];
+            let mut position_value = &ab_values[k] - &qn_values[k] + &carry_value;
+            if k < RSA_NUMBER_OF_LIMBS {
+                terms.push(maingate::Term::Assigned(&r.limbs[k], -F::ONE));
+                position_value -= BigInt::from(r_values[k]);
+            }
+            let mut constant = F::ZERO;
+            if let Some(carry) = carry.as_ref() {
+                terms.push(maingate::Term::Assigned(carry, F::ONE));
+                constant -= carry_offset;
+            }
+
+            let next_carry = if k < 2 * RSA_NUMBER_OF_LIMBS - 2 {
+                carry_value = position_value >> RSA_BIT_LEN_LIMB;
+                let shifted_carry = i128::try_from(&carry_value).expect("carry fits in 70 bits")
+                    + (1 << (RSA_BIT_LEN_CARRY - 1));
+                let (shifted_carry, _) = range_chip.decompose(
+                    ctx,
+                    Value::known(F::from_u128(shifted_carry as u128)),
+                    8,
+                    RSA_BIT_LEN_CARRY,
+                )?;
+                constant += pow_2_64 * carry_offset;
+                Some(shifted_carry)
+            } else {
+                None
+            };
+            if let Some(next_carry) = next_carry.as_ref() {
+                terms.push(maingate::Term::Assigned(next_carry, -pow_2_64));
+            }
+            let position = main_gate.compose(ctx, &terms, constant)?;
+            main_gate.assert_zero(ctx, &position)?;
+            carry = next_carry;
+        }
+
+        Ok(r)
+    }
+
+    /// Return `signature^65537 mod n`, by 16 squarings and one multiplication.
+    pub(crate) fn pow_mod_public_exponent(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        main_gate: &MainGate<F>,
+        range_chip: &RangeChip<F>,
+        signature: &AssignedRsaInteger<F>,
+        n: &AssignedRsaInteger<F>,
+    ) -> Result<AssignedRsaInteger<F>, Error> {
+        debug_assert_eq!(RSA_PUBLIC_EXPONENT, (1 << 16) + 1);
+        let mut acc = self.mul_mod(ctx, main_gate, range_chip, signature, signature, n)?;
+        for _ in 1..16 {
+            acc = self.mul_mod(ctx, main_gate, range_chip, &acc, &acc, n)?;
+        }
+        self.mul_mod(ctx, main_gate, range_chip, &acc, signature, n)
+    }
+
+    /// Verify that the encoded message is EMSA-PKCS1-v1_5(digest) when `check`
+    /// is 1: every limb above the digest is a constant, and the lower limbs
+    /// are the digest.
+    pub(crate) fn assert_pkcs1_v15_encoding(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        main_gate: &MainGate<F>,
+        em: &AssignedRsaInteger<F>,
+        digest: &Word<AssignedValue<F>>,
+        check: &AssignedValue<F>,
+    ) -> Result<(), Error> {
+        let em_limbs = limbs(&emsa_pkcs1_v15_sha256(&[0; 32]));
+        for (limb, em_limb) in em.limbs.iter().zip_eq(em_limbs).skip(DIGEST_LIMBS) {
+            assert_zero_if(
+                ctx,
+                main_gate,
+                check,
+                &[maingate::Term::Assigned(limb, F::ONE)],
+                -F::from(em_limb),
+            )?;
+        }
+        let pow_2_64 = F::from_u128(1 << RSA_BIT_LEN_LIMB);
+        for (limbs, digest) in [&em.limbs[0..2], &em.limbs[2..4]]
+            .into_iter()
+            .zip([digest.lo(), digest.hi()])
+        {
+            assert_zero_if(
+                ctx,
+                main_gate,
+                check,
+                &[
+                    maingate::Term::Assigned(&limbs[0], F::ONE),
+                    maingate::Term::Assigned(&limbs[1], pow_2_64),
+                    maingate::Term::Assigned(&digest, -F::ONE),
+                ],
+                F::ZERO,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Verify that the encoded message is EMSA-PKCS1-v1_5(digest) of a SHA-1
+    /// digest when `check` is 1.  The digest of 20 bytes is split in a `lo`
+    /// word of 128 bits and a `hi` word of 32 bits, which is at the bottom of
+    /// the third limb.
+    #[cfg(feature = "sha1")]
+    pub(crate) fn assert_pkcs1_v15_sha1_encoding(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        main_gate: &MainGate<F>,
+        em: &AssignedRsaInteger<F>,
+        digest: &Word<AssignedValue<F>>,
+        check: &AssignedValue<F>,
+    ) -> Result<(), Error> {
+        let em_limbs = limbs(&emsa_pkcs1_v15_sha1(&[0; 20]));
+        for (limb, em_limb) in em.limbs.iter().zip_eq(em_limbs).skip(3) {
+            assert_zero_if(
+                ctx,
+                main_gate,
+                check,
+                &[maingate::Term::Assigned(limb, F::ONE)],
+                -F::from(em_limb),
+            )?;
+        }
+        let pow_2_64 = F::from_u128(1 << RSA_BIT_LEN_LIMB);
+        assert_zero_if(
+            ctx,
+            main_gate,
+            check,
+            &[
+                maingate::Term::Assigned(&em.limbs[0], F::ONE),
+                maingate::Term::Assigned(&em.limbs[1], pow_2_64),
+                maingate::Term::Assigned(&digest.lo(), -F::ONE),
+            ],
+            F::ZERO,
+        )?;
+        // The upper bytes of the third limb are the end of the DigestInfo prefix.
+        assert_zero_if(
+            ctx,
+            main_gate,
+            check,
+            &[
+                maingate::Term::Assigned(&em.limbs[2], F::ONE),
+                maingate::Term::Assigned(&digest.hi(), -F::ONE),
+            ],
+            -F::from(em_limbs[2]),
+        )
+    }
+
+    /// Verify that the encoded message is EMSA-PSS(digest) when `check` is 1,
+    /// given the little endian bytes of the digest: the encoded message ends
+    /// with H || 0xbc, its top bit is 0, and unmasking the data block before
+    /// H with MGF1(H) gives 0x00..0x00 || 0x01 || salt, where
+    /// H = sha256(0x00 * 8 || digest || salt).
+    #[allow(clippy::too_many_arguments)]
+    fn assign_pss_verify(
+        &self,
+        config: &RsaSignVerifyConfig,
+        ctx: &mut RegionCtx<F>,
+        main_gate: &MainGate<F>,
+        range_chip: &RangeChip<F>,
+        em: &AssignedRsaInteger<F>,
+        digest_le: &[AssignedValue<F>],
+        check: &AssignedValue<F>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        let skip = main_gate.not(ctx, check)?;
+        let em_be = em.bytes_le.iter().rev().collect_vec();
+        let em_be_values = biguint_to_rsa_bytes_be(&em.value);
+
+        assert_zero_if(
+            ctx,
+            main_gate,
+            check,
+            &[maingate::Term::Assigned(em_be[RSA_2048_BYTES - 1], F::ONE)],
+            -F::from(PSS_TRAILER as u64),
+        )?;
+
+        // H is between the data block and the trailer byte
+        let hash_le = &em.bytes_le[1..33];
+        let hash_cells = Word::new([
+            compose_bytes_le(ctx, main_gate, &hash_le[..16])?,
+            compose_bytes_le(ctx, main_gate, &hash_le[16..])?,
+        ]);
+
+        // The blocks of the mask are sha256(H || counter), with the counter in 4 big endian
+        // bytes.
+        let mask_values = mgf1_sha256(
+            &em_be_values[PSS_DB_BYTES..RSA_2048_BYTES - 1],
+            PSS_MGF1_BLOCKS * 32,
+        );
+        let mut mask_be_cells = Vec::with_capacity(PSS_MGF1_BLOCKS * 32);
+        for (counter, block) in mask_values.chunks(32).enumerate() {
+            let mut block_le: [u8; 32] = block.try_into().unwrap();
+            block_le.reverse();
+            let (block_cells, block_le_cells) = assign_bytes_le(ctx, range_chip, &block_le)?;
+            let counter_le_cells = (counter as u32)
+                .to_le_bytes()
+                .iter()
+                .map(|byte| main_gate.assign_constant(ctx, F::from(*byte as u64)))
+                .collect::<Result<Vec<_>, _>>()?;
+            let seed_rlc = assign_rlc_le(
+                ctx,
+                main_gate,
+                &config.main_gate_config,
+                config.rlc,
+                "rsa_mgf1_seed",
+                config.q_rlc_input,
+                challenges.keccak_input(),
+                counter_le_cells.iter().chain(hash_le).map(assigned_term),
+            )?;
+            enable_lookup(
+                ctx,
+                config.q_sha256_mgf1,
+                &config.main_gate_config,
+                config.rlc,
+                &skip,
+                &seed_rlc,
+                &block_cells,
+            )?;
+            mask_be_cells.extend(block_le_cells.into_iter().rev());
+        }
+
+        // Unmask the data block bit by bit.  The top bit of the encoded message is 0 as it has
+        // 2047 bits, and is cleared from the data block.
+        let mut salt_be_cells = Vec::with_capacity(PSS_SALT_BYTES);
+        for (i, (masked_byte, mask_byte)) in em_be
+            .iter()
+            .zip(&mask_be_cells)
+            .take(PSS_DB_BYTES)
+            .enumerate()
+        {
+            let masked_bits = main_gate.to_bits(ctx, masked_byte, 8)?;
+            let mask_bits = main_gate.to_bits(ctx, mask_byte, 8)?;
+            if i <= PSS_PS_BYTES {
+                // 0x00..0x00 || 0x01: a xor b = bit, which is a - b = 0 for bit 0, and
+                // a + b - 1 = 0 for bit 1.
+                let db_byte = (i == PSS_PS_BYTES) as u64;
+                for (j, (a, b)) in masked_bits.iter().zip(&mask_bits).enumerate() {
+                    let (terms, constant) = if i == 0 && j == 7 {
+                        (vec![maingate::Term::Assigned(a, F::ONE)], F::ZERO)
+                    } else if (db_byte >> j) & 1 == 1 {
+                        (
+                            vec![
+                                maingate::Term::Assigned(a, F::ONE),
+                                maingate::Term::Assigned(b, F::ONE),
+                            ],
+                            -F::ONE,
+                        )
+                    } else {
+                        (
+                            vec![
+                                maingate::Term::Assigned(a, F::ONE),
+                                maingate::Term::Assigned(b, -F::ONE),
+                            ],
+                            F::ZERO,
+                        )
+                    };
+                    assert_zero_if(ctx, main_gate, check, &terms, constant)?;
+                }
+            } else {
+                // salt byte = sum(2^j * (a_j + b_j - 2 * a_j * b_j))
+                let products = masked_bits
+                    .iter()
+                    .zip(&mask_bits)
+                    .map(|(a, b)| main_gate.mul(ctx, a, b))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let terms = masked_bits
+                    .iter()
+                    .zip(&mask_bits)
+                    .zip(&products)
+                    .enumerate()
+                    .flat_map(|(j, ((a, b), ab))| {
+                        let coeff = F::from(1 << j);
+                        [
+                            maingate::Term::Assigned(a, coeff),
+                            maingate::Term::Assigned(b, coeff),
+                            maingate::Term::Assigned(ab, -coeff.double()),
+                        ]
+                    })
+                    .collect_vec();
+                salt_be_cells.push(main_gate.compose(ctx, &terms, F::ZERO)?);
+            }
+        }
+
+        // H = sha256(0x00 * 8 || digest || salt)
+        let zero = main_gate.assign_constant(ctx, F::ZERO)?;
+        let pss_message_rlc = assign_rlc_le(
+            ctx,
+            main_gate,
+            &config.main_gate_config,
+            config.rlc,
+            "rsa_pss_message",
+            config.q_rlc_input,
+            challenges.keccak_input(),
+            salt_be_cells
+                .iter()
+                .rev()
+                .chain(digest_le)
+                .chain(iter::repeat(&zero).take(8))
+                .map(assigned_term),
+        )?;
+        enable_lookup(
+            ctx,
+            config.q_sha256_pss,
+            &config.main_gate_config,
+            config.rlc,
+            &skip,
+            &pss_message_rlc,
+            &hash_cells,
+        )
+    }
+
+    /// Verify a signature and return its address and message, as the
+    /// SignVerifyChip does for ECDSA signatures.  The recovery id is 0.
+    fn assign_signature_verify(
+        &self,
+        config: &RsaSignVerifyConfig,
+        ctx: &mut RegionCtx<F>,
+        main_gate: &MainGate<F>,
+        range_chip: &RangeChip<F>,
+        sign_data: Option<&RsaSignData>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<AssignedSignatureVerify<F>, Error> {
+        let (padding, sign_data) = match sign_data {
+            Some(sign_data) => (false, sign_data.clone()),
+            None => (true, padding_sign_data()),
+        };
+
+        let signature = self.assign_integer(ctx, range_chip, &sign_data.signature)?;
+        let n = self.assign_integer(ctx, range_chip, &sign_data.pk.n)?;
+        let em = self.pow_mod_public_exponent(ctx, main_gate, range_chip, &signature, &n)?;
+
+        // Verify that the address is the end of keccak(n) as for an ECDSA public key
+        let n_be = sign_data.pk.n_be();
+        let mut pk_hash = (!padding).then(|| keccak256(&n_be)).unwrap_or_default();
+        pk_hash.reverse();
+        let (is_padding, address_cells, pk_hash_cells) =
+            assign_address(ctx, main_gate, range_chip, padding, &pk_hash)?;
+        let is_real = main_gate.not(ctx, &is_padding)?;
+        let is_pss = main_gate.assign_bit(
+            ctx,
+            Value::known(F::from((sign_data.padding == RsaPadding::Pss) as u64)),
+        )?;
+        let is_pkcs1_v15 = main_gate.not(ctx, &is_pss)?;
+        let check_pkcs1_v15 = main_gate.and(ctx, &is_real, &is_pkcs1_v15)?;
+        let check_pss = main_gate.and(ctx, &is_real, &is_pss)?;
+
+        // The digest of the message, verified by sha256 lookup
+        let mut digest_le = sign_data.digest();
+        digest_le.reverse();
+        let (digest_cells, digest_le_cells) = assign_bytes_le(ctx, range_chip, &digest_le)?;
+
+        // Verify that the encoded message is EMSA-PKCS1-v1_5(digest) for PKCS#1 v1.5
+        self.assert_pkcs1_v15_encoding(ctx, main_gate, &em, &digest_cells, &check_pkcs1_v15)?;
+
+        // Verify that the encoded message is EMSA-PSS(digest) for PSS
+        self.assign_pss_verify(
+            config,
+            ctx,
+            main_gate,
+            range_chip,
+            &em,
+            &digest_le_cells,
+            &check_pss,
+            challenges,
+        )?;
+
+        // The message is the tx sign hash
+        let mut msg_le = sign_data.msg;
+        msg_le.reverse();
+        let (msg_hash_cells, msg_le_cells) = assign_bytes_le(ctx, range_chip, &msg_le)?;
+
+        let n_rlc = assign_rlc_le(
+            ctx,
+            main_gate,
+            &config.main_gate_config,
+            config.rlc,
+            "rsa_n",
+            config.q_rlc_input,
+            challenges.keccak_input(),
+            n.bytes_le.iter().map(assigned_term),
+        )?;
+        let msg_rlc = assign_rlc_le(
+            ctx,
+            main_gate,
+            &config.main_gate_config,
+            config.rlc,
+            "rsa_msg",
+            config.q_rlc_input,
+            challenges.keccak_input(),
+            msg_le_cells.iter().map(assigned_term),
+        )?;
+
+        enable_lookup(
+            ctx,
+            config.q_keccak,
+            &config.main_gate_config,
+            config.rlc,
+            &is_padding,
+            &n_rlc,
+            &pk_hash_cells,
+        )?;
+        enable_lookup(
+            ctx,
+            config.q_sha256,
+            &config.main_gate_config,
+            config.rlc,
+            &is_padding,
+            &msg_rlc,
+            &digest_cells,
+        )?;
+
+        Ok(AssignedSignatureVerify {
+            is_padding,
+            recovery_id: main_gate.assign_constant(ctx, F::ZERO)?,
+            address: address_cells,
+            msg_hash: msg_hash_cells,
+        })
+    }
+
+    /// Assign the verification of `signatures[i]` to the slot `i`.  Slots past the end of
+    /// `signatures`, or whose signature is `None`, are padding.
+    pub(crate) fn assign(
+        &self,
+        config: &RsaSignVerifyConfig,
+        layouter: &mut impl Layouter<F>,
+        signatures: &[Option<RsaSignData>],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<Vec<AssignedSignatureVerify<F>>, Error> {
+        if signatures.len() > self.max_verif {
+            error!(
+                "signatures.len() = {} > max_verif = {}",
+                signatures.len(),
+                self.max_verif
+            );
+            return Err(Error::Synthesis);
+        }
+        let main_gate = MainGate::new(config.main_gate_config.clone());
+        let range_chip = RangeChip::new(config.range_config.clone());
+
+        layouter.assign_region(
+            || "rsa signature verification",
+            |region| {
+                let mut assigned_sig_verifs = Vec::new();
+                let mut ctx = RegionCtx::new(region, 0);
+                for i in 0..self.max_verif {
+                    let sign_data = signatures.get(i).and_then(Option::as_ref); // None when padding
+                    let assigned_sig_verif = self.assign_signature_verify(
+                        config,
+                        &mut ctx,
+                        &main_gate,
+                        &range_chip,
+                        sign_data,
+                        challenges,
+                    )?;
+                    assigned_sig_verifs.push(assigned_sig_verif);
+                }
+                log::debug!("rsa signature verification: {} rows", ctx.offset());
+                Ok(assigned_sig_verifs)
+            },
+        )
+    }
+}
@@ -0,0 +1,460 @@
+//! Point arithmetic over short Weierstrass curves y^2 = x^3 + a * x + b, such as secp256k1 and
+//! secp256r1, on top of the IntegerChips of the base and scalar fields.  Scalar multiplications
+//! are windowed, and split in halves by the GLV endomorphism on curves that have one.
+
+use super::{SignVerifyCurve, BIT_LEN_LIMB, NUMBER_OF_LIMBS};
+use ecc::{
+    maingate::{AssignedCondition, MainGate, MainGateInstructions, RegionCtx},
+    AssignedPoint, GeneralEccChip,
+};
+use eth_types::Field;
+use halo2_proofs::{
+    arithmetic::CurveAffine,
+    circuit::Value,
+    halo2curves::{
+        ff::PrimeField,
+        group::{Curve, Group},
+        Coordinates,
+    },
+    plonk::Error,
+};
+use integer::{
+    rns::{Integer, Rns},
+    AssignedInteger, IntegerChip, IntegerInstructions, Range, UnassignedInteger,
+};
+use itertools::Itertools;
+use num::{Integer as _, Signed};
+use num_bigint::{BigInt, Sign};
+use std::{ops, rc::Rc};
+
+pub(crate) type Point<C, F> =
+    AssignedPoint<<C as CurveAffine>::Base, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>;
+pub(crate) type Base<C, F> =
+    AssignedInteger<<C as CurveAffine>::Base, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>;
+pub(crate) type Scalar<C, F> =
+    AssignedInteger<<C as CurveAffine>::ScalarExt, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>;
+
+/// Number of bits of the halves of a scalar split by the GLV endomorphism
+const GLV_BIT_LEN: usize = 128;
+
+/// GLV endomorphism of a curve with a = 0: `lambda * (x, y) = (beta * x, y)`, where `beta` and
+/// `lambda` are cube roots of unity of the base and scalar fields.
+#[derive(Clone, Debug)]
+pub struct Glv<C: CurveAffine> {
+    /// Cube root of unity of the base field
+    pub beta: C::Base,
+    /// Cube root of unity of the scalar field
+    pub lambda: C::ScalarExt,
+    /// Short basis `[(a1, b1), (a2, b2)]` of the lattice of the `(k1, k2)` with
+    /// `k1 + k2 * lambda = 0 (mod n)`
+    pub basis: [(BigInt, BigInt); 2],
+}
+
+impl<C: CurveAffine> Glv<C> {
+    /// Return the GLV endomorphism with the given hexadecimal parameters.
+    pub(super) fn from_hex(beta: &str, lambda: &str, basis: [(&str, &str); 2]) -> Self {
+        let parse = |hex: &str| BigInt::parse_bytes(hex.as_bytes(), 16).expect("invalid hex");
+        Self {
+            beta: from_bigint(&parse(beta)),
+            lambda: from_bigint(&parse(lambda)),
+            basis: basis.map(|(a, b)| (parse(a), parse(b))),
+        }
+    }
+
+    /// Split `k` into `k1 + k2 * lambda (mod n)` with `|k1|, |k2| < 2^128`, by rounding `k`
+    /// to the closest vector of the lattice of the basis.  Return the sign and the absolute
+    /// value of `k1` and `k2`.
+    pub fn decompose(&self, k: &C::ScalarExt) -> [(bool, C::ScalarExt); 2] {
+        let n = to_bigint(&-C::ScalarExt::ONE) + 1;
+        let k = to_bigint(k);
+        let [(a1, b1), (a2, b2)] = &self.basis;
+        // c1 = round(b2 * k / n), c2 = round(-b1 * k / n)
+        let round = |x: BigInt| (2 * x + &n).div_floor(&(2 * &n));
+        let c1 = round(b2 * &k);
+        let c2 = round(-b1 * &k);
+        let k1 = &k - &c1 * a1 - &c2 * a2;
+        let k2 = -(&c1 * b1) - &c2 * b2;
+        [k1, k2].map(|k| (k.is_negative(), from_bigint(&k.abs())))
+    }
+}
+
+fn to_bigint<FE: PrimeField>(fe: &FE) -> BigInt {
+    BigInt::from_bytes_le(Sign::Plus, fe.to_repr().as_ref())
+}
+
+fn from_bigint<FE: PrimeField>(value: &BigInt) -> FE {
+    let (_, bytes) = value.to_bytes_le();
+    let mut repr = FE::Repr::default();
+    repr.as_mut()[..bytes.len()].copy_from_slice(&bytes);
+    Option::from(FE::from_repr(repr)).expect("value is not a canonical field element")
+}
+
+/// Constants of the curve and of the scalar multiplications, assigned once per region and
+/// shared by all the points and scalar multiplications of the region.
+pub(crate) struct WeierstrassConstants<C: CurveAffine, F: Field> {
+    pub(crate) a: Base<C, F>,
+    pub(crate) b: Base<C, F>,
+    /// Offset of the entries of the window tables of the scalar multiplications
+    aux: Point<C, F>,
+    aux_value: C,
+    /// Start of the accumulator of the scalar multiplications, `2 * aux`
+    acc_start: Point<C, F>,
+    /// Constants of the GLV endomorphism, if the curve has one
+    glv: Option<GlvConstants<C, F>>,
+    /// Generator of the curve
+    pub(crate) generator: Point<C, F>,
+}
+
+struct GlvConstants<C: CurveAffine, F: Field> {
+    beta: Base<C, F>,
+    lambda: Scalar<C, F>,
+    zero_base: Base<C, F>,
+    zero_scalar: Scalar<C, F>,
+}
+
+/// Chip for the points of a short Weierstrass curve.  Additions are incomplete: they require
+/// the points to have different x coordinates, so that scalar multiplications start from an
+/// auxiliary point to avoid the identity, as in the GeneralEccChip, and have the same caveat
+/// about the choice of the aux generator (see `SignVerifyChip::new`).  The aux generator is
+/// assigned as a constant.
+pub(crate) struct WeierstrassChip<'a, C: SignVerifyCurve, F: Field> {
+    main_gate: &'a MainGate<F>,
+    base_chip: &'a IntegerChip<C::Base, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+    scalar_chip: &'a IntegerChip<C::ScalarExt, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+    rns_base: Rc<Rns<C::Base, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>>,
+    rns_scalar: Rc<Rns<C::ScalarExt, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>>,
+}
+
+impl<'a, C: SignVerifyCurve, F: Field> WeierstrassChip<'a, C, F> {
+    pub(crate) fn new(
+        main_gate: &'a MainGate<F>,
+        ecc_chip: &'a GeneralEccChip<C, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+    ) -> Self {
+        let (rns_base, rns_scalar) = GeneralEccChip::<C, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>::rns();
+        Self {
+            main_gate,
+            base_chip: ecc_chip.base_field_chip(),
+            scalar_chip: ecc_chip.scalar_field_chip(),
+            rns_base: Rc::new(rns_base),
+            rns_scalar: Rc::new(rns_scalar),
+        }
+    }
+
+    /// Assign the constants of the curve and the constants of the scalar multiplications
+    /// starting from `aux`.
+    pub(crate) fn assign_constants(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        aux: C,
+    ) -> Result<WeierstrassConstants<C, F>, Error> {
+        let glv = C::glv()
+            .map(|glv| {
+                Ok::<_, Error>(GlvConstants {
+                    beta: self.base_chip.assign_constant(ctx, glv.beta)?,
+                    lambda: self.scalar_chip.assign_constant(ctx, glv.lambda)?,
+                    zero_base: self.base_chip.assign_constant(ctx, C::Base::ZERO)?,
+                    zero_scalar: self.scalar_chip.assign_constant(ctx, C::ScalarExt::ZERO)?,
+                })
+            })
+            .transpose()?;
+        Ok(WeierstrassConstants {
+            a: self.base_chip.assign_constant(ctx, C::a())?,
+            b: self.base_chip.assign_constant(ctx, C::b())?,
+            aux: self.assign_constant(ctx, aux)?,
+            aux_value: aux,
+            acc_start: self.assign_constant(ctx, aux.to_curve().double().to_affine())?,
+            glv,
+            generator: self.assign_constant(ctx, C::generator())?,
+        })
+    }
+
+    /// Assign a point, constraining it to be on the curve.
+    pub(super) fn assign_point(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        constants: &WeierstrassConstants<C, F>,
+        point: Value<C>,
+    ) -> Result<Point<C, F>, Error> {
+        let base_chip = self.base_chip;
+        let coordinates = point.map(|point| {
+            let coordinates =
+                Option::<Coordinates<_>>::from(point.coordinates()).expect("point is the identity");
+            (*coordinates.x(), *coordinates.y())
+        });
+        let [x, y] = [coordinates.map(|(x, _)| x), coordinates.map(|(_, y)| y)]
+            .map(|value| {
+                let integer = value.map(|value| Integer::from_fe(value, self.rns_base.clone()));
+                base_chip.assign_integer(ctx, UnassignedInteger::from(integer), Range::Remainder)
+            })
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?
+            .try_into()
+            .unwrap();
+
+        // y^2 = (x^2 + a) * x + b
+        let y_square = base_chip.square(ctx, &y)?;
+        let x_square = base_chip.square(ctx, &x)?;
+        let x_square_plus_a = base_chip.add(ctx, &x_square, &constants.a)?;
+        let rhs = base_chip.mul(ctx, &x_square_plus_a, &x)?;
+        let rhs = base_chip.add(ctx, &rhs, &constants.b)?;
+        base_chip.assert_equal(ctx, &y_square, &rhs)?;
+
+        Ok(AssignedPoint::new(x, y))
+    }
+
+    /// Assign a constant point.
+    pub(crate) fn assign_constant(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        point: C,
+    ) -> Result<Point<C, F>, Error> {
+        let coordinates =
+            Option::<Coordinates<_>>::from(point.coordinates()).expect("point is the identity");
+        let x = self.base_chip.assign_constant(ctx, *coordinates.x())?;
+        let y = self.base_chip.assign_constant(ctx, *coordinates.y())?;
+        Ok(AssignedPoint::new(x, y))
+    }
+
+    /// Return `p + q`, given that `p.x != q.x`.
+    pub(crate) fn add_incomplete(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        p: &Point<C, F>,
+        q: &Point<C, F>,
+    ) -> Result<Point<C, F>, Error> {
+        let base_chip = self.base_chip;
+        // lambda = (q.y - p.y) / (q.x - p.x)
+        let numerator = base_chip.sub(ctx, q.y(), p.y())?;
+        let denominator = base_chip.sub(ctx, q.x(), p.x())?;
+        let lambda = base_chip.div_incomplete(ctx, &numerator, &denominator)?;
+        self.point_from_lambda(ctx, &lambda, p, q.x())
+    }
+
+    /// Return `2 * p`, given that `p.y != 0`.  `a` is the assigned curve parameter.
+    pub(crate) fn double(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &Base<C, F>,
+        p: &Point<C, F>,
+    ) -> Result<Point<C, F>, Error> {
+        let base_chip = self.base_chip;
+        // lambda = (3 * p.x^2 + a) / (2 * p.y)
+        let x_square = base_chip.square(ctx, p.x())?;
+        let numerator = base_chip.add(ctx, &x_square, &x_square)?;
+        let numerator = base_chip.add(ctx, &numerator, &x_square)?;
+        let numerator = base_chip.add(ctx, &numerator, a)?;
+        let denominator = base_chip.add(ctx, p.y(), p.y())?;
+        let lambda = base_chip.div_incomplete(ctx, &numerator, &denominator)?;
+        self.point_from_lambda(ctx, &lambda, p, p.x())
+    }
+
+    /// Return the third point of the line of slope `lambda` through `p` and a point with x
+    /// coordinate `q_x`, reflected over the x axis:
+    /// (lambda^2 - p.x - q.x, lambda * (p.x - x) - p.y).
+    fn point_from_lambda(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        lambda: &Base<C, F>,
+        p: &Point<C, F>,
+        q_x: &Base<C, F>,
+    ) -> Result<Point<C, F>, Error> {
+        let base_chip = self.base_chip;
+        let lambda_square = base_chip.square(ctx, lambda)?;
+        let x = base_chip.sub(ctx, &lambda_square, p.x())?;
+        let x = base_chip.sub(ctx, &x, q_x)?;
+        let t = base_chip.sub(ctx, p.x(), &x)?;
+        let t = base_chip.mul(ctx, &t, lambda)?;
+        let y = base_chip.sub(ctx, &t, p.y())?;
+        Ok(AssignedPoint::new(x, y))
+    }
+
+    /// Return `p` if `cond` is 1, and `q` otherwise.
+    pub(crate) fn select(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        cond: &AssignedCondition<F>,
+        p: &Point<C, F>,
+        q: &Point<C, F>,
+    ) -> Result<Point<C, F>, Error> {
+        let x = self.base_chip.select(ctx, p.x(), q.x(), cond)?;
+        let y = self.base_chip.select(ctx, p.y(), q.y(), cond)?;
+        Ok(AssignedPoint::new(x, y))
+    }
+
+    /// Return `table[bits]`, where `bits` are the little endian bits of the index, by a binary
+    /// tree of selections.
+    fn select_window(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        table: &[Point<C, F>],
+        bits: &[AssignedCondition<F>],
+    ) -> Result<Point<C, F>, Error> {
+        debug_assert_eq!(table.len(), 1 << bits.len());
+        let mut entries = table.to_vec();
+        for bit in bits {
+            entries = entries
+                .chunks(2)
+                .map(|pair| self.select(ctx, bit, &pair[1], &pair[0]))
+                .collect::<Result<Vec<_>, _>>()?;
+        }
+        Ok(entries.pop().unwrap())
+    }
+
+    /// Return the little endian bits of the limbs of a scalar.
+    fn scalar_bits(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        scalar: &Scalar<C, F>,
+    ) -> Result<Vec<AssignedCondition<F>>, Error> {
+        Ok(scalar
+            .limbs()
+            .iter()
+            .map(|limb| self.main_gate.to_bits(ctx, limb.as_ref(), BIT_LEN_LIMB))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .flatten()
+            .collect_vec())
+    }
+
+    /// Split `scalar * point` into `k1 * p1 + k2 * p2` by the GLV endomorphism, where
+    /// `scalar = k1 + k2 * lambda (mod n)` with `|k1|, |k2| < 2^128`, `p1 = ±point` and
+    /// `p2 = ±(beta * point.x, point.y)` take the signs of `k1` and `k2`.  Return the points
+    /// `p1` and `p2` with the little endian bits of `|k1|` and `|k2|`.
+    #[allow(clippy::type_complexity)]
+    fn glv_split(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        constants: &GlvConstants<C, F>,
+        glv: &Glv<C>,
+        point: &Point<C, F>,
+        scalar: &Scalar<C, F>,
+        scalar_value: Value<C::ScalarExt>,
+    ) -> Result<Vec<(Point<C, F>, Vec<AssignedCondition<F>>)>, Error> {
+        let (main_gate, base_chip, scalar_chip) =
+            (self.main_gate, self.base_chip, self.scalar_chip);
+        let halves = scalar_value.map(|scalar| glv.decompose(&scalar));
+        let neg_y = base_chip.sub(ctx, &constants.zero_base, point.y())?;
+        let xs = [
+            point.x().clone(),
+            base_chip.mul(ctx, point.x(), &constants.beta)?,
+        ];
+
+        let mut signed_halves = Vec::with_capacity(2);
+        let mut pairs = Vec::with_capacity(2);
+        for (i, x) in xs.into_iter().enumerate() {
+            let is_neg =
+                main_gate.assign_bit(ctx, halves.map(|halves| F::from(halves[i].0 as u64)))?;
+            let abs = scalar_chip.assign_integer(
+                ctx,
+                UnassignedInteger::from(
+                    halves.map(|halves| Integer::from_fe(halves[i].1, self.rns_scalar.clone())),
+                ),
+                Range::Remainder,
+            )?;
+            // |k_i| < 2^128: the bits of the two lower limbs compose it, and the upper limbs
+            // are zero.
+            let limbs = abs.limbs();
+            let mut bits = main_gate.to_bits(ctx, limbs[0].as_ref(), BIT_LEN_LIMB)?;
+            bits.extend(main_gate.to_bits(ctx, limbs[1].as_ref(), GLV_BIT_LEN - BIT_LEN_LIMB)?);
+            for limb in &limbs[2..] {
+                main_gate.assert_zero(ctx, limb.as_ref())?;
+            }
+
+            let neg_abs = scalar_chip.sub(ctx, &constants.zero_scalar, &abs)?;
+            signed_halves.push(scalar_chip.select(ctx, &neg_abs, &abs, &is_neg)?);
+            let y = base_chip.select(ctx, &neg_y, point.y(), &is_neg)?;
+            let y = base_chip.reduce(ctx, &y)?;
+            pairs.push((AssignedPoint::new(x, y), bits));
+        }
+
+        // scalar = k1 + k2 * lambda (mod n)
+        let k2_lambda = scalar_chip.mul(ctx, &signed_halves[1], &constants.lambda)?;
+        let sum = scalar_chip.add(ctx, &signed_halves[0], &k2_lambda)?;
+        scalar_chip.assert_equal(ctx, &sum, scalar)?;
+
+        Ok(pairs)
+    }
+
+    /// Return `sum(scalar_i * point_i)`, given the native values of the scalars to split them
+    /// by the GLV endomorphism when the curve has one.  See `mul_bits_batch`.
+    pub(crate) fn mul_batch(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        constants: &WeierstrassConstants<C, F>,
+        window_size: usize,
+        pairs: Vec<(Point<C, F>, Scalar<C, F>, Value<C::ScalarExt>)>,
+    ) -> Result<Point<C, F>, Error> {
+        let mut pairs_bits = Vec::new();
+        for (point, scalar, scalar_value) in pairs {
+            match (&constants.glv, C::glv()) {
+                (Some(glv_constants), Some(glv)) => pairs_bits.extend(self.glv_split(
+                    ctx,
+                    glv_constants,
+                    &glv,
+                    &point,
+                    &scalar,
+                    scalar_value,
+                )?),
+                _ => pairs_bits.push((point, self.scalar_bits(ctx, &scalar)?)),
+            }
+        }
+        self.mul_bits_batch(ctx, constants, window_size, pairs_bits)
+    }
+
+    /// Return `sum(scalar_i * point_i)`, given the little endian bits of the scalars, which
+    /// all have the same number of bits.  The sum is computed by Straus' method with windows
+    /// of `window_size` bits: per window, the accumulator is doubled `window_size` times and
+    /// added the multiple of every point selected by the bits of the window from a table.  The
+    /// accumulator starts from `2 * aux` and the entries of the tables are offset by `aux`, so
+    /// that no intermediate point is the identity; the sum of the offsets is subtracted at the
+    /// end.
+    pub(crate) fn mul_bits_batch(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        constants: &WeierstrassConstants<C, F>,
+        window_size: usize,
+        pairs_bits: Vec<(Point<C, F>, Vec<AssignedCondition<F>>)>,
+    ) -> Result<Point<C, F>, Error> {
+        let num_bits = pairs_bits[0].1.len();
+        assert!(pairs_bits.iter().all(|(_, bits)| bits.len() == num_bits));
+
+        // table_i[j] = aux + j * point_i
+        let tables = pairs_bits
+            .iter()
+            .map(|(point, _)| {
+                let mut table = vec![constants.aux.clone()];
+                for _ in 1..(1 << window_size) {
+                    let entry = self.add_incomplete(ctx, table.last().unwrap(), point)?;
+                    table.push(entry);
+                }
+                Ok(table)
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let windows: Vec<ops::Range<usize>> = (0..num_bits)
+            .step_by(window_size)
+            .map(|start| start..(start + window_size).min(num_bits))
+            .collect();
+        let mut acc = constants.acc_start.clone();
+        // Sum of the offsets of the accumulator, starting from 2 * aux
+        let mut offset = constants.aux_value.to_curve().double();
+        for (i, window) in windows.into_iter().rev().enumerate() {
+            if i > 0 {
+                for _ in window.clone() {
+                    acc = self.double(ctx, &constants.a, &acc)?;
+                    offset = offset.double();
+                }
+            }
+            for ((_, bits), table) in pairs_bits.iter().zip(&tables) {
+                let entry =
+                    self.select_window(ctx, &table[..1 << window.len()], &bits[window.clone()])?;
+                acc = self.add_incomplete(ctx, &acc, &entry)?;
+                offset += constants.aux_value.to_curve();
+            }
+        }
+
+        // acc = offset + sum(scalar_i * point_i)
+        let correction = self.assign_constant(ctx, (-offset).to_affine())?;
+        self.add_incomplete(ctx, &acc, &correction)
+    }
+}
@@ -1,17 +1,29 @@
-use super::*;
+use super::{dev::TxCircuitParams, sign_verify::recovery_id, *};
 use crate::util::{log2_ceil, unusable_rows};
-use eth_types::address;
+use eth_types::{
+    address,
+    ed25519_types::Ed25519SignData,
+    geth_types::{Authorization, Deposit},
+    keccak256,
+    rsa_types::RsaSignData,
+    sign_types::{pk_bytes_le, pk_bytes_swap_endianness, sign_secp256r1},
+    AccessList, AccessListItem, Address, H256,
+};
+use ethers_signers::{LocalWallet, Signer};
 use halo2_proofs::{
+    arithmetic::{CurveAffine, Field as _},
     dev::{MockProver, VerifyFailure},
-    halo2curves::bn256::Fr,
+    halo2curves::{bn256::Fr, ff::PrimeField, group::Curve, secp256r1},
 };
 use mock::AddrOrWallet;
+use rand::SeedableRng;
+use rand_xorshift::XorShiftRng;
 
 #[test]
 fn tx_circuit_unusable_rows() {
     assert_eq!(
         TxCircuit::<Fr>::unusable_rows(),
-        unusable_rows::<Fr, TxCircuit::<Fr>>(()),
+        unusable_rows::<Fr, TxCircuit::<Fr>>(TxCircuitParams::default()),
     )
 }
 
@@ -21,13 +33,53 @@ fn run<F: Field>(
     max_txs: usize,
     max_calldata: usize,
 ) -> Result<(), Vec<VerifyFailure>> {
-    let k = log2_ceil(
-        TxCircuit::<Fr>::unusable_rows() + TxCircuit::<Fr>::min_num_rows(max_txs, max_calldata),
-    );
     // SignVerifyChip -> ECDSAChip -> MainGate instance column
     let circuit = TxCircuit::<F>::new(max_txs, max_calldata, chain_id, txs);
+    let instance = circuit.instance();
+
+    run_with_instance(circuit, instance)
+}
+
+fn run_with_instance<F: Field>(
+    circuit: TxCircuit<F>,
+    instance: Vec<Vec<F>>,
+) -> Result<(), Vec<VerifyFailure>> {
+    let mut min_num_rows = TxCircuit::<Fr>::min_num_rows(
+        circuit.max_txs,
+        circuit.max_signed_txs,
+        TxCircuit::<Fr>::calldata_num_rows(circuit.max_calldata, circuit.calldata_chunk_size),
+        circuit.max_access_list,
+        circuit.sign_verify.num_lanes,
+    );
+    if circuit.sign_verify_secp256r1.is_some() {
+        min_num_rows = min_num_rows.max(SignVerifyChip::<Fr, Secp256r1Affine>::min_num_rows(
+            circuit.max_signed_txs,
+            1,
+        ));
+    }
+    if circuit.rsa_sign_verify.is_some() {
+        min_num_rows = min_num_rows.max(RsaSignVerifyChip::<Fr>::min_num_rows(
+            circuit.max_signed_txs,
+        ));
+    }
+    if circuit.ed25519_sign_verify.is_some() {
+        min_num_rows = min_num_rows.max(Ed25519SignVerifyChip::<Fr>::min_num_rows(
+            circuit.max_signed_txs,
+        ));
+    }
+    if circuit.eip712_digest.is_some() {
+        min_num_rows =
+            min_num_rows.max(Eip712DigestChip::<Fr>::min_num_rows(circuit.max_signed_txs));
+    }
+    if let Some(auth_sign_verify) = &circuit.auth_sign_verify {
+        min_num_rows = min_num_rows.max(TxCircuit::<Fr>::min_num_rows_authorizations(
+            auth_sign_verify.max_verif,
+        ));
+    }
+    // The dev circuit loads the u16 range lookup table.
+    let k = log2_ceil(TxCircuit::<Fr>::unusable_rows() + min_num_rows.max(1 << 16));
 
-    let prover = match MockProver::run(k, &circuit, vec![vec![]]) {
+    let prover = match MockProver::run(k, &circuit, instance) {
         Ok(prover) => prover,
         Err(e) => panic!("{:#?}", e),
     };
@@ -78,6 +130,365 @@ fn tx_circuit_1tx_2max_tx() {
     assert_eq!(run::<Fr>(vec![tx], chain_id, MAX_TXS, MAX_CALLDATA), Ok(()));
 }
 
+#[test]
+fn tx_circuit_1tx_2max_tx_1signed_tx() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 32;
+
+    let chain_id: u64 = mock::MOCK_CHAIN_ID.as_u64();
+
+    let tx: Transaction = mock::CORRECT_MOCK_TXS[0].clone().into();
+
+    // The padding slot gets a constant verification instead of a full one.
+    let circuit = TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, vec![tx])
+        .with_max_signed_txs(1)
+        .with_exposed_tx_sign_hashes();
+    let instance = circuit.instance();
+    assert_eq!(run_with_instance(circuit, instance), Ok(()));
+}
+
+#[test]
+fn tx_circuit_0tx_0max_tx() {
+    const MAX_TXS: usize = 0;
+    const MAX_CALLDATA: usize = 32;
+
+    let chain_id: u64 = mock::MOCK_CHAIN_ID.as_u64();
+
+    // Only the tx table is assigned
+    assert_eq!(
+        TxCircuit::<Fr>::min_num_rows(MAX_TXS, MAX_TXS, MAX_CALLDATA, 0, 1),
+        1 + MAX_CALLDATA + MAX_BLOBS_PER_BLOCK
+    );
+    assert_eq!(run::<Fr>(vec![], chain_id, MAX_TXS, MAX_CALLDATA), Ok(()));
+}
+
+#[test]
+fn tx_circuit_0tx_2max_tx_0signed_tx() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 32;
+
+    let chain_id: u64 = mock::MOCK_CHAIN_ID.as_u64();
+
+    // The padding slots get constant verifications, so only the tx table is assigned.
+    let min_num_rows = TxCircuit::<Fr>::min_num_rows(MAX_TXS, 0, MAX_CALLDATA, 0, 1);
+    assert!(min_num_rows < SignVerifyChip::<Fr>::min_num_rows(1, 1));
+    let circuit = TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, vec![])
+        .with_max_signed_txs(0)
+        .with_exposed_tx_sign_hashes();
+    let instance = circuit.instance();
+    assert_eq!(run_with_instance(circuit, instance), Ok(()));
+}
+
+#[test]
+fn tx_circuit_calldata_full() {
+    const MAX_TXS: usize = 2;
+    // Every calldata row is used, so the last row of the calldata section holds
+    // the last byte of the last tx.
+    const MAX_CALLDATA: usize = 10;
+
+    assert_eq!(
+        run::<Fr>(
+            mock::CORRECT_MOCK_TXS[..MAX_TXS]
+                .iter()
+                .map(|tx| Transaction::from(tx.clone()))
+                .collect_vec(),
+            mock::MOCK_CHAIN_ID.as_u64(),
+            MAX_TXS,
+            MAX_CALLDATA
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn tx_circuit_calldata_chunks() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 16;
+    // The chunks split the calldata of the txs and the unused rows.
+    const CHUNK_SIZE: usize = 3;
+
+    // 6 chunks, 5 of them starting with a carry row
+    assert_eq!(
+        TxCircuit::<Fr>::calldata_num_rows(MAX_CALLDATA, Some(CHUNK_SIZE)),
+        MAX_CALLDATA + 5
+    );
+    assert_eq!(
+        TxCircuit::<Fr>::calldata_num_rows(MAX_CALLDATA, Some(MAX_CALLDATA)),
+        MAX_CALLDATA
+    );
+
+    let chain_id: u64 = mock::MOCK_CHAIN_ID.as_u64();
+    let txs = mock::CORRECT_MOCK_TXS[..MAX_TXS]
+        .iter()
+        .map(|tx| Transaction::from(tx.clone()))
+        .collect_vec();
+    let circuit = TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, txs)
+        .with_calldata_chunk_size(CHUNK_SIZE);
+    let instance = circuit.instance();
+
+    assert_eq!(run_with_instance(circuit, instance), Ok(()));
+}
+
+#[test]
+fn tx_circuit_2tx_2sign_verify_lanes() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 32;
+
+    let chain_id: u64 = mock::MOCK_CHAIN_ID.as_u64();
+    let txs = mock::CORRECT_MOCK_TXS[..MAX_TXS]
+        .iter()
+        .map(|tx| Transaction::from(tx.clone()))
+        .collect_vec();
+    let circuit =
+        TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, txs).with_sign_verify_lanes(2);
+    let instance = circuit.instance();
+
+    assert_eq!(run_with_instance(circuit, instance), Ok(()));
+}
+
+#[test]
+fn tx_circuit_sign_data_cache() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 32;
+
+    let chain_id: u64 = mock::MOCK_CHAIN_ID.as_u64();
+    let txs = mock::CORRECT_MOCK_TXS[..MAX_TXS]
+        .iter()
+        .map(|tx| Transaction::from(tx.clone()))
+        .collect_vec();
+    // The cache is keyed by the chain_id and the hash of the signed tx
+    assert_eq!(
+        txs[0].hash(chain_id),
+        mock::CORRECT_MOCK_TXS[0].hash.unwrap()
+    );
+
+    let cache = SignDataCache::default();
+    cache.warm(chain_id, &txs[..1]);
+    assert_eq!(cache.len(), 1);
+
+    // The circuit recovers the missing SignData and inserts it in the cache
+    let circuit =
+        TxCircuit::<Fr>::new_with_cache(MAX_TXS, MAX_CALLDATA, chain_id, txs.clone(), &cache);
+    assert_eq!(cache.len(), 2);
+    let instance = circuit.instance();
+    assert_eq!(run_with_instance(circuit, instance), Ok(()));
+
+    let circuit =
+        TxCircuit::<Fr>::new_with_cache(MAX_TXS, MAX_CALLDATA, chain_id, txs.clone(), &cache);
+    assert_eq!(cache.len(), 2);
+    let instance = circuit.instance();
+    assert_eq!(run_with_instance(circuit, instance), Ok(()));
+
+    // The entries are not shared with another chain_id, for which the txs are not signed
+    assert!(cache.sign_data(chain_id + 1, &txs[0]).is_err());
+    assert_eq!(cache.len(), 2);
+}
+
+#[test]
+fn tx_circuit_exposed_tx_sign_hashes() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 32;
+
+    let chain_id: u64 = mock::MOCK_CHAIN_ID.as_u64();
+    let tx: Transaction = mock::CORRECT_MOCK_TXS[0].clone().into();
+    let circuit = TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, vec![tx])
+        .with_exposed_tx_sign_hashes();
+
+    let instance = circuit.instance();
+    // (lo, hi, recovery_id) for the tx and the padding tx, then the chain_id
+    assert_eq!(instance.last().unwrap().len(), 3 * MAX_TXS + 1);
+    assert_eq!(instance.last().unwrap()[3 * MAX_TXS], Fr::from(chain_id));
+    assert_eq!(run_with_instance(circuit.clone(), instance.clone()), Ok(()));
+
+    let mut bad_instance = instance.clone();
+    bad_instance.last_mut().unwrap()[0] += Fr::ONE;
+    assert!(run_with_instance(circuit.clone(), bad_instance).is_err());
+
+    let mut bad_instance = instance.clone();
+    bad_instance.last_mut().unwrap()[3 * MAX_TXS] += Fr::ONE;
+    assert!(run_with_instance(circuit.clone(), bad_instance).is_err());
+
+    // The recovery id of the tx must match the one used to recover its caller
+    let mut bad_instance = instance;
+    let recovery_id = &mut bad_instance.last_mut().unwrap()[2];
+    *recovery_id = Fr::ONE - *recovery_id;
+    assert!(run_with_instance(circuit, bad_instance).is_err());
+}
+
+#[test]
+fn tx_circuit_secp256r1() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 32;
+
+    let mut rng = XorShiftRng::seed_from_u64(1);
+    let chain_id: u64 = mock::MOCK_CHAIN_ID.as_u64();
+    let tx_k1: Transaction = mock::CORRECT_MOCK_TXS[0].clone().into();
+
+    // Sign the second tx again over secp256r1, and send it from the address of the P-256 key.
+    let mut tx: Transaction = mock::CORRECT_MOCK_TXS[1].clone().into();
+    let sk = secp256r1::Fq::random(&mut rng);
+    let pk = (Secp256r1Affine::generator() * sk).to_affine();
+    let msg_hash = tx
+        .sign_data_secp256r1(chain_id, pk)
+        .expect("valid tx")
+        .msg_hash;
+    let (r, s) = sign_secp256r1(secp256r1::Fq::random(&mut rng), sk, msg_hash);
+    tx.r = U256::from_little_endian(&r.to_repr());
+    tx.s = U256::from_little_endian(&s.to_repr());
+    tx.from = Address::from_slice(&keccak256(pk_bytes_swap_endianness(&pk_bytes_le(&pk)))[12..]);
+    let sign_data = tx
+        .sign_data_secp256r1(chain_id, pk)
+        .expect("valid signature");
+    // v is 35 + 2 * chain_id + parity
+    tx.v = 35 + 2 * chain_id + recovery_id(&sign_data) as u64;
+
+    let txs = vec![tx_k1, tx];
+    let circuit = TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, txs.clone())
+        .with_secp256r1_signers(&[None, Some(pk)]);
+    assert_eq!(circuit.validate(), Ok(()));
+    let instance = circuit.instance();
+    assert_eq!(run_with_instance(circuit, instance), Ok(()));
+
+    // The signature doesn't verify with another public key
+    let other_pk = (Secp256r1Affine::generator() * (sk + secp256r1::Fq::ONE)).to_affine();
+    let circuit = TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, txs)
+        .with_secp256r1_signers(&[None, Some(other_pk)]);
+    let instance = circuit.instance();
+    assert!(run_with_instance(circuit, instance).is_err());
+}
+
+#[test]
+fn tx_circuit_rsa() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    let chain_id: u64 = mock::MOCK_CHAIN_ID.as_u64();
+    let key = &*mock::MOCK_RSA_KEY;
+    let mut tx: Transaction = mock::CORRECT_MOCK_TXS[0].clone().into();
+    tx.from = Address::from_slice(&keccak256(key.pk.n_be())[12..]);
+    // RSA signatures have no recovery id
+    tx.v = 35 + 2 * chain_id;
+    let msg = tx.sign_hash(chain_id);
+
+    let circuit = TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, vec![tx])
+        .with_rsa_signers(&[Some((key.pk.clone(), key.sign(&msg), RsaPadding::Pkcs1v15))]);
+    assert_eq!(circuit.validate(), Ok(()));
+    let instance = circuit.instance();
+    assert_eq!(run_with_instance(circuit.clone(), instance.clone()), Ok(()));
+
+    // The signature of another message doesn't verify
+    let mut circuit = circuit;
+    circuit.sign_datas[0] = Some(TxSignData::Rsa(RsaSignData {
+        signature: key.sign(&[0; 32]),
+        padding: RsaPadding::Pkcs1v15,
+        pk: key.pk.clone(),
+        msg,
+    }));
+    assert!(run_with_instance(circuit, instance).is_err());
+}
+
+#[test]
+fn tx_circuit_rsa_pss() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    let chain_id: u64 = mock::MOCK_CHAIN_ID.as_u64();
+    let key = &*mock::MOCK_RSA_KEY;
+    let mut tx: Transaction = mock::CORRECT_MOCK_TXS[0].clone().into();
+    tx.from = Address::from_slice(&keccak256(key.pk.n_be())[12..]);
+    tx.v = 35 + 2 * chain_id;
+    let msg = tx.sign_hash(chain_id);
+    let signature = key.sign_pss(&msg, &[0x5a; 32]);
+
+    let circuit = TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, vec![tx])
+        .with_rsa_signers(&[Some((key.pk.clone(), signature.clone(), RsaPadding::Pss))]);
+    assert_eq!(circuit.validate(), Ok(()));
+    let instance = circuit.instance();
+    assert_eq!(run_with_instance(circuit.clone(), instance.clone()), Ok(()));
+
+    // A PSS signature doesn't verify as a PKCS#1 v1.5 signature
+    let mut circuit = circuit;
+    circuit.sign_datas[0] = Some(TxSignData::Rsa(RsaSignData {
+        signature,
+        padding: RsaPadding::Pkcs1v15,
+        pk: key.pk.clone(),
+        msg,
+    }));
+    assert!(run_with_instance(circuit, instance).is_err());
+}
+
+#[test]
+fn tx_circuit_ed25519() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    let chain_id: u64 = mock::MOCK_CHAIN_ID.as_u64();
+    let key = &*mock::MOCK_ED25519_KEY;
+    let pk = key.public_key();
+    let mut tx: Transaction = mock::CORRECT_MOCK_TXS[0].clone().into();
+    tx.from = Address::from_slice(&keccak256(pk)[12..]);
+    // Ed25519 signatures have no recovery id
+    tx.v = 35 + 2 * chain_id;
+    let msg = tx.sign_hash(chain_id);
+
+    let circuit = TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, vec![tx])
+        .with_ed25519_signers(&[Some((pk, key.sign(&msg)))]);
+    assert_eq!(circuit.validate(), Ok(()));
+    let instance = circuit.instance();
+    assert_eq!(run_with_instance(circuit.clone(), instance.clone()), Ok(()));
+
+    // The signature of another message doesn't verify
+    let mut circuit = circuit;
+    circuit.sign_datas[0] = Some(TxSignData::Ed25519(Ed25519SignData {
+        signature: key.sign(&[0; 32]),
+        pk,
+        msg,
+    }));
+    assert!(run_with_instance(circuit, instance).is_err());
+}
+
+#[test]
+fn tx_circuit_eip712() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 32;
+
+    let chain_id: u64 = mock::MOCK_CHAIN_ID.as_u64();
+    let domain_separator = H256(keccak256(b"EIP712Domain"));
+    let tx_k1: Transaction = mock::CORRECT_MOCK_TXS[0].clone().into();
+
+    // Sign a tx as typed data, whose struct hash is the TxSignHash.
+    let wallet = &mock::MOCK_WALLETS[0];
+    let mut tx = sign_legacy_tx(wallet, chain_id, Some(Address::zero()));
+    let digest = keccak256(&eip712_encode(&domain_separator, &tx.sign_hash(chain_id)));
+    let sig = wallet.sign_hash(H256(digest)).expect("valid hash");
+    tx.v = sig.v - 27 + 35 + chain_id * 2;
+    tx.r = sig.r;
+    tx.s = sig.s;
+
+    let txs = vec![tx_k1, tx];
+    let circuit = TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, txs.clone())
+        .with_eip712_signers(domain_separator, &[false, true]);
+    assert_eq!(circuit.validate(), Ok(()));
+    let instance = circuit.instance();
+    assert_eq!(run_with_instance(circuit.clone(), instance.clone()), Ok(()));
+
+    // The domain separator (lo, hi) and the is_eip712 bits of the slots are public inputs
+    let eip712_column = instance.len() - 2;
+    let mut bad_instance = instance.clone();
+    bad_instance[eip712_column][0] += Fr::ONE;
+    assert!(run_with_instance(circuit.clone(), bad_instance).is_err());
+
+    let mut bad_instance = instance.clone();
+    bad_instance[eip712_column][2 + 1] = Fr::ZERO;
+    assert!(run_with_instance(circuit, bad_instance).is_err());
+
+    // The signature doesn't recover the caller under another domain
+    let circuit = TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, txs)
+        .with_eip712_signers(H256::zero(), &[false, true]);
+    let instance = circuit.instance();
+    assert!(run_with_instance(circuit, instance).is_err());
+}
+
 #[test]
 fn tx_circuit_bad_address() {
     const MAX_TXS: usize = 1;
@@ -96,6 +507,568 @@ fn tx_circuit_bad_address() {
     .is_err(),);
 }
 
+#[test]
+fn tx_circuit_try_new_limits() {
+    let chain_id: u64 = mock::MOCK_CHAIN_ID.as_u64();
+    let txs: Vec<Transaction> = mock::CORRECT_MOCK_TXS[..2]
+        .iter()
+        .map(|tx| Transaction::from(tx.clone()))
+        .collect_vec();
+    let calldata_len: usize = txs.iter().map(|tx| tx.call_data.len()).sum();
+
+    // The SignData are recovered once the txs are validated.
+    let circuit = TxCircuit::<Fr>::try_new(2, calldata_len, chain_id, txs.clone()).unwrap();
+    assert!(circuit.sign_datas.iter().all(Option::is_some));
+    assert_eq!(circuit.sign_datas.len(), 2);
+    assert_eq!(
+        TxCircuit::<Fr>::try_new(1, calldata_len, chain_id, txs.clone()).unwrap_err(),
+        TxCircuitError::TooManyTxs {
+            num_txs: 2,
+            max_txs: 1
+        }
+    );
+    assert_eq!(
+        TxCircuit::<Fr>::try_new(2, calldata_len - 1, chain_id, txs.clone()).unwrap_err(),
+        TxCircuitError::TooMuchCalldata {
+            calldata_len,
+            max_calldata: calldata_len - 1
+        }
+    );
+    assert_eq!(
+        TxCircuit::<Fr>::try_new(2, calldata_len, chain_id + 1, txs.clone()).unwrap_err(),
+        TxCircuitError::InvalidChainId {
+            tx_index: 0,
+            v: txs[0].v,
+            chain_id: chain_id + 1
+        }
+    );
+}
+
+#[test]
+fn tx_circuit_low_s() {
+    let chain_id: u64 = mock::MOCK_CHAIN_ID.as_u64();
+    let mut tx: Transaction = mock::CORRECT_MOCK_TXS[0].clone().into();
+    // (r, n - s) is the malleable version of the signature (r, s), with the parity of v flipped.
+    let n = eth_types::Word::from_str_radix(
+        "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
+        16,
+    )
+    .unwrap();
+    if sign_verify::is_low_s(&tx.s) {
+        tx.s = n - tx.s;
+        // v is 35 + 2 * chain_id + parity
+        tx.v = if tx.v % 2 == 1 { tx.v + 1 } else { tx.v - 1 };
+    }
+
+    let circuit = TxCircuit::<Fr>::new(1, 32, chain_id, vec![tx]).with_low_s();
+    assert_eq!(
+        circuit.validate(),
+        Err(TxCircuitError::HighS { tx_index: 0 })
+    );
+}
+
+#[test]
+fn tx_circuit_gas_limit() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 32;
+
+    let chain_id: u64 = mock::MOCK_CHAIN_ID.as_u64();
+    let txs: Vec<Transaction> = mock::CORRECT_MOCK_TXS[..2]
+        .iter()
+        .map(|tx| Transaction::from(tx.clone()))
+        .collect_vec();
+    let gas: u64 = txs.iter().map(|tx| tx.gas()).sum();
+
+    // The gas limits of the txs fill the block exactly
+    let circuit =
+        TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, txs.clone()).with_gas_limit(gas);
+    let instance = circuit.instance();
+    assert_eq!(run_with_instance(circuit, instance), Ok(()));
+
+    let circuit =
+        TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, txs).with_gas_limit(gas - 1);
+    assert_eq!(
+        circuit.validate(),
+        Err(TxCircuitError::GasLimitExceeded {
+            gas: gas as u128,
+            gas_limit: gas - 1
+        })
+    );
+}
+
+#[test]
+fn tx_circuit_gas_used() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 32;
+
+    let chain_id: u64 = mock::MOCK_CHAIN_ID.as_u64();
+    let tx: Transaction = mock::CORRECT_MOCK_TXS[0].clone().into();
+    // The padding tx slot has no gas used
+    let circuit = TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, vec![tx.clone()])
+        .with_gas_used(vec![tx.gas()]);
+    let instance = circuit.instance();
+    assert_eq!(run_with_instance(circuit, instance), Ok(()));
+}
+
+#[test]
+fn tx_circuit_access_list() {
+    const MAX_TXS: usize = 3;
+    const MAX_CALLDATA: usize = 32;
+
+    let chain_id: u64 = mock::MOCK_CHAIN_ID.as_u64();
+    let mut txs: Vec<Transaction> = mock::CORRECT_MOCK_TXS[..2]
+        .iter()
+        .map(|tx| Transaction::from(tx.clone()))
+        .collect_vec();
+    // The first tx has 2 addresses, the first one with 2 storage keys, and the second tx has an
+    // address with a storage key.  The access list of a legacy tx isn't signed.
+    txs[0].access_list = Some(AccessList(vec![
+        AccessListItem {
+            address: address!("0x00000000000000000000000000000000000000aa"),
+            storage_keys: vec![H256::from_low_u64_be(1), H256::repeat_byte(0xff)],
+        },
+        AccessListItem {
+            address: address!("0x00000000000000000000000000000000000000bb"),
+            storage_keys: vec![],
+        },
+    ]));
+    txs[1].access_list = Some(AccessList(vec![AccessListItem {
+        address: address!("0x00000000000000000000000000000000000000cc"),
+        storage_keys: vec![H256::from_low_u64_be(2)],
+    }]));
+
+    for max_access_list in [3, 4] {
+        let circuit = TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, txs.clone())
+            .with_max_access_list(max_access_list);
+        let instance = circuit.instance();
+        assert_eq!(run_with_instance(circuit, instance), Ok(()));
+    }
+
+    let circuit =
+        TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, txs).with_max_access_list(2);
+    assert_eq!(
+        circuit.validate(),
+        Err(TxCircuitError::TooLargeAccessList {
+            num_entries: 3,
+            max_access_list: 2
+        })
+    );
+}
+
+#[test]
+fn tx_circuit_blob_hashes() {
+    const MAX_TXS: usize = 3;
+    const MAX_CALLDATA: usize = 32;
+
+    let chain_id: u64 = mock::MOCK_CHAIN_ID.as_u64();
+    let mut txs: Vec<Transaction> = mock::CORRECT_MOCK_TXS[..2]
+        .iter()
+        .map(|tx| Transaction::from(tx.clone()))
+        .collect_vec();
+    // The first tx has 2 blob versioned hashes and the second one has 1.  The blob versioned
+    // hashes of a legacy tx aren't signed.
+    txs[0].blob_versioned_hashes = vec![H256::repeat_byte(0x01), H256::repeat_byte(0xff)];
+    txs[1].blob_versioned_hashes = vec![H256::from_low_u64_be(2)];
+
+    let circuit = TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, txs.clone());
+    let instance = circuit.instance();
+    assert_eq!(run_with_instance(circuit, instance), Ok(()));
+
+    txs[1].blob_versioned_hashes = vec![H256::from_low_u64_be(2); MAX_BLOBS_PER_BLOCK - 1];
+    let circuit = TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, txs);
+    assert_eq!(
+        circuit.validate(),
+        Err(TxCircuitError::TooManyBlobHashes {
+            num_blob_hashes: MAX_BLOBS_PER_BLOCK + 1,
+            max_blob_hashes: MAX_BLOBS_PER_BLOCK
+        })
+    );
+}
+
+/// Return a legacy tx to `to` signed by `wallet` for `chain_id`
+fn sign_legacy_tx(wallet: &LocalWallet, chain_id: u64, to: Option<Address>) -> Transaction {
+    let mut tx = Transaction {
+        from: wallet.address(),
+        to,
+        gas_limit: 100_000.into(),
+        gas_price: 1_000_000_000u64.into(),
+        call_data: vec![1, 2, 3].into(),
+        ..Default::default()
+    };
+    let sig = wallet
+        .sign_hash(H256(tx.sign_hash(chain_id)))
+        .expect("valid hash");
+    // EIP-155: v = chain_id * 2 + 35 + recovery_id
+    tx.v = sig.v - 27 + 35 + chain_id * 2;
+    tx.r = sig.r;
+    tx.s = sig.s;
+    tx
+}
+
+#[test]
+fn tx_circuit_create_and_call_to_zero_address() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 32;
+
+    let chain_id: u64 = mock::MOCK_CHAIN_ID.as_u64();
+    let wallet = &mock::MOCK_WALLETS[0];
+    let tx_create = sign_legacy_tx(wallet, chain_id, None);
+    let tx_call = sign_legacy_tx(wallet, chain_id, Some(Address::zero()));
+    // Both txs have a zero CalleeAddress, and are assigned with their own IsCreate.
+    assert!(tx_create.is_create() && !tx_call.is_create());
+    assert_ne!(tx_create.sign_hash(chain_id), tx_call.sign_hash(chain_id));
+
+    assert_eq!(
+        run::<Fr>(vec![tx_create, tx_call], chain_id, MAX_TXS, MAX_CALLDATA),
+        Ok(())
+    );
+}
+
+#[test]
+fn tx_circuit_deposit() {
+    const MAX_TXS: usize = 3;
+    const MAX_CALLDATA: usize = 32;
+
+    let chain_id: u64 = mock::MOCK_CHAIN_ID.as_u64();
+    // A deposit tx has no signature, and its sender needn't be a wallet.
+    let mut deposit = Transaction {
+        from: address!("0xdeaddeaddeaddeaddeaddeaddeaddeaddead0001"),
+        to: Some(mock::MOCK_ACCOUNTS[0]),
+        gas_limit: 1_000_000.into(),
+        value: 1_000u64.into(),
+        call_data: vec![1, 2, 3].into(),
+        deposit: Some(Deposit {
+            source_hash: H256::repeat_byte(0x5c),
+            mint: 2_000u64.into(),
+            is_system_tx: false,
+        }),
+        ..Default::default()
+    };
+    let txs = vec![
+        deposit.clone(),
+        sign_legacy_tx(
+            &mock::MOCK_WALLETS[0],
+            chain_id,
+            Some(mock::MOCK_ACCOUNTS[1]),
+        ),
+    ];
+
+    // Deposits are only supported when enabled.
+    let circuit = TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, txs.clone());
+    assert_eq!(
+        circuit.validate(),
+        Err(TxCircuitError::DepositNotSupported { tx_index: 0 })
+    );
+
+    let circuit =
+        TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, txs.clone()).with_deposits(true);
+    assert_eq!(circuit.validate(), Ok(()));
+    let instance = circuit.instance();
+    assert_eq!(run_with_instance(circuit, instance), Ok(()));
+
+    // The slots after the last signed tx can only hold deposits, which aren't verified.
+    let deposits_last = vec![txs[1].clone(), deposit.clone()];
+    let circuit = TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, deposits_last)
+        .with_deposits(true)
+        .with_max_signed_txs(1)
+        .with_exposed_tx_sign_hashes();
+    assert_eq!(circuit.validate(), Ok(()));
+    let instance = circuit.instance();
+    assert_eq!(run_with_instance(circuit, instance), Ok(()));
+
+    let circuit = TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, txs.clone())
+        .with_deposits(true)
+        .with_max_signed_txs(1);
+    assert_eq!(
+        circuit.validate(),
+        Err(TxCircuitError::UnverifiedSignedTx {
+            tx_index: 1,
+            max_signed_txs: 1
+        })
+    );
+
+    // Without the deposit fields, the tx isn't signed.
+    deposit.deposit = None;
+    let circuit = TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, vec![deposit]);
+    assert_eq!(
+        circuit.validate(),
+        Err(TxCircuitError::InvalidChainId {
+            tx_index: 0,
+            v: 0,
+            chain_id
+        })
+    );
+}
+
+#[test]
+fn tx_circuit_signed_tx_as_deposit() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    let chain_id: u64 = mock::MOCK_CHAIN_ID.as_u64();
+    let tx = sign_legacy_tx(
+        &mock::MOCK_WALLETS[0],
+        chain_id,
+        Some(mock::MOCK_ACCOUNTS[1]),
+    );
+    let circuit = TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, vec![tx.clone()])
+        .with_deposits(true)
+        .with_exposed_tx_sign_hashes();
+    let instance = circuit.instance();
+    assert_eq!(run_with_instance(circuit, instance.clone()), Ok(()));
+
+    // Flipping is_deposit on the signed tx skips the check of its caller address, but replaces
+    // its TxSignHash with the padding msg hash, so it doesn't match the exposed TxSignHash.
+    let mut deposit = tx;
+    deposit.from = address!("0x1230000000000000000000000000000000000456");
+    deposit.deposit = Some(Deposit::default());
+    let circuit = TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, vec![deposit])
+        .with_deposits(true)
+        .with_exposed_tx_sign_hashes();
+    assert_eq!(circuit.validate(), Ok(()));
+    assert!(run_with_instance(circuit, instance).is_err());
+}
+
+/// Return an EIP-7702 authorization to delegate the code of `wallet` to `address`
+fn sign_authorization(
+    wallet: &LocalWallet,
+    chain_id: u64,
+    address: Address,
+    nonce: u64,
+) -> Authorization {
+    let mut authorization = Authorization {
+        chain_id: chain_id.into(),
+        address,
+        nonce: nonce.into(),
+        ..Default::default()
+    };
+    let sig = wallet
+        .sign_hash(H256(authorization.sign_hash()))
+        .expect("valid hash");
+    authorization.y_parity = (sig.v - 27) as u8;
+    authorization.r = sig.r;
+    authorization.s = sig.s;
+    authorization
+}
+
+/// Return a set-code tx with `authorization_list` sent by `wallet`
+fn sign_set_code_tx(
+    wallet: &LocalWallet,
+    chain_id: u64,
+    authorization_list: Vec<Authorization>,
+) -> Transaction {
+    let mut tx = Transaction {
+        from: wallet.address(),
+        to: Some(mock::MOCK_ACCOUNTS[0]),
+        gas_limit: 100_000.into(),
+        gas_fee_cap: 2_000_000_000u64.into(),
+        gas_tip_cap: 1_000_000_000u64.into(),
+        call_data: vec![1, 2, 3].into(),
+        authorization_list: Some(authorization_list),
+        ..Default::default()
+    };
+    let sig = wallet
+        .sign_hash(H256(tx.sign_hash(chain_id)))
+        .expect("valid hash");
+    tx.v = sig.v - 27;
+    tx.r = sig.r;
+    tx.s = sig.s;
+    tx
+}
+
+#[test]
+fn tx_circuit_set_code() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 32;
+    const MAX_AUTHS: usize = 5;
+
+    let chain_id: u64 = mock::MOCK_CHAIN_ID.as_u64();
+    let tx_legacy: Transaction = mock::CORRECT_MOCK_TXS[0].clone().into();
+    // The second authorization is valid for all chains, and the nonces cover the RLP encodings
+    // of a scalar: 0x80 for 0, a single byte below 0x80, and a header followed by 1 or 2 bytes.
+    let authorization_list = vec![
+        sign_authorization(&mock::MOCK_WALLETS[1], chain_id, mock::MOCK_ACCOUNTS[1], 0),
+        sign_authorization(&mock::MOCK_WALLETS[2], 0, mock::MOCK_ACCOUNTS[2], 0x7f),
+        sign_authorization(
+            &mock::MOCK_WALLETS[1],
+            chain_id,
+            mock::MOCK_ACCOUNTS[3],
+            0x80,
+        ),
+        sign_authorization(
+            &mock::MOCK_WALLETS[2],
+            chain_id,
+            mock::MOCK_ACCOUNTS[4],
+            0x1234,
+        ),
+    ];
+    // The circuit checks that the caller is the signer of the set-code payload.
+    let tx = sign_set_code_tx(&mock::MOCK_WALLETS[0], chain_id, authorization_list);
+
+    let circuit = TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, vec![tx_legacy, tx])
+        .with_max_authorizations(MAX_AUTHS);
+    assert_eq!(circuit.validate(), Ok(()));
+    let instance = circuit.instance();
+
+    assert_eq!(run_with_instance(circuit, instance), Ok(()));
+}
+
+#[test]
+fn tx_circuit_fee_caps() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 32;
+    const MAX_AUTHS: usize = 1;
+
+    let chain_id: u64 = mock::MOCK_CHAIN_ID.as_u64();
+    let tx_legacy = sign_legacy_tx(
+        &mock::MOCK_WALLETS[2],
+        chain_id,
+        Some(mock::MOCK_ACCOUNTS[1]),
+    );
+    let authorization =
+        sign_authorization(&mock::MOCK_WALLETS[1], chain_id, mock::MOCK_ACCOUNTS[1], 0);
+    let tx = sign_set_code_tx(&mock::MOCK_WALLETS[0], chain_id, vec![authorization]);
+
+    // The gas price of the legacy tx and the max priority fee per gas of the set-code tx are the
+    // base fee, which the max fee per gas of the set-code tx exceeds.
+    let base_fee = tx_legacy.gas_price;
+    assert_eq!(tx.fee_caps(), (base_fee * 2, base_fee));
+    let circuit = TxCircuit::<Fr>::new(
+        MAX_TXS,
+        MAX_CALLDATA,
+        chain_id,
+        vec![tx_legacy.clone(), tx.clone()],
+    )
+    .with_max_authorizations(MAX_AUTHS)
+    .with_base_fee(base_fee);
+    let instance = circuit.instance();
+    assert_eq!(run_with_instance(circuit, instance), Ok(()));
+
+    // A legacy tx pays its gas price, which must cover the base fee.
+    assert_eq!(
+        TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, vec![tx_legacy, tx])
+            .with_max_authorizations(MAX_AUTHS)
+            .with_base_fee(base_fee + 1)
+            .validate(),
+        Err(TxCircuitError::InvalidFeeCaps { tx_index: 0 })
+    );
+
+    // The fee caps must fit in 128 bits.
+    let mut tx_huge_gas_price = sign_legacy_tx(&mock::MOCK_WALLETS[2], chain_id, None);
+    tx_huge_gas_price.gas_price = U256::one() << 128;
+    assert_eq!(
+        TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, vec![tx_huge_gas_price]).validate(),
+        Err(TxCircuitError::InvalidFeeCaps { tx_index: 0 })
+    );
+}
+
+#[test]
+fn tx_circuit_set_code_bad_authorization() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+    const MAX_AUTHS: usize = 1;
+
+    let chain_id: u64 = mock::MOCK_CHAIN_ID.as_u64();
+    let authorization =
+        sign_authorization(&mock::MOCK_WALLETS[1], chain_id, mock::MOCK_ACCOUNTS[1], 1);
+    // The AuthTable holds another nonce than the one signed by the authority.
+    let mut authorization_bad_nonce = authorization.clone();
+    authorization_bad_nonce.nonce = 2.into();
+    let tx = sign_set_code_tx(
+        &mock::MOCK_WALLETS[0],
+        chain_id,
+        vec![authorization_bad_nonce],
+    );
+
+    let mut circuit = TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, vec![tx])
+        .with_max_authorizations(MAX_AUTHS);
+    circuit.auths[0].2 = authorization.sign_data().expect("valid authorization");
+    assert_eq!(circuit.validate(), Ok(()));
+    let instance = circuit.instance();
+
+    assert!(run_with_instance(circuit, instance).is_err());
+}
+
+#[test]
+fn tx_circuit_set_code_validate() {
+    let chain_id: u64 = mock::MOCK_CHAIN_ID.as_u64();
+    let wallet = &mock::MOCK_WALLETS[0];
+    let authorization = sign_authorization(&mock::MOCK_WALLETS[1], chain_id, Address::zero(), 1);
+    let tx = sign_set_code_tx(wallet, chain_id, vec![authorization.clone(); 2]);
+
+    // Without EIP-7702 support
+    assert_eq!(
+        TxCircuit::<Fr>::new(1, 32, chain_id, vec![tx.clone()]).validate(),
+        Err(TxCircuitError::TooManyAuthorizations {
+            num_auths: 2,
+            max_auths: 0
+        })
+    );
+    assert_eq!(
+        TxCircuit::<Fr>::new(1, 32, chain_id, vec![tx])
+            .with_max_authorizations(1)
+            .validate(),
+        Err(TxCircuitError::TooManyAuthorizations {
+            num_auths: 2,
+            max_auths: 1
+        })
+    );
+
+    // The authorizations for other chains are skipped, so they don't take a slot.
+    let authorization_other_chain =
+        sign_authorization(&mock::MOCK_WALLETS[1], chain_id + 1, Address::zero(), 1);
+    let tx = sign_set_code_tx(
+        wallet,
+        chain_id,
+        vec![authorization_other_chain, authorization.clone()],
+    );
+    let circuit = TxCircuit::<Fr>::new(1, 32, chain_id, vec![tx]).with_max_authorizations(1);
+    assert_eq!(circuit.validate(), Ok(()));
+    assert_eq!(
+        circuit
+            .auths
+            .iter()
+            .map(|(tx_index, auth_index, _)| (*tx_index, *auth_index))
+            .collect_vec(),
+        vec![(0, 1)]
+    );
+
+    // The fee caps are checked against each other and the base fee.
+    let tx = sign_set_code_tx(wallet, chain_id, vec![authorization.clone()]);
+    assert_eq!(
+        TxCircuit::<Fr>::new(1, 32, chain_id, vec![tx.clone()])
+            .with_max_authorizations(1)
+            .with_base_fee(tx.gas_fee_cap)
+            .validate(),
+        Ok(())
+    );
+    assert_eq!(
+        TxCircuit::<Fr>::new(1, 32, chain_id, vec![tx.clone()])
+            .with_max_authorizations(1)
+            .with_base_fee(tx.gas_fee_cap + 1)
+            .validate(),
+        Err(TxCircuitError::InvalidFeeCaps { tx_index: 0 })
+    );
+    let mut tx_high_tip = tx;
+    tx_high_tip.gas_tip_cap = tx_high_tip.gas_fee_cap + 1;
+    assert_eq!(
+        TxCircuit::<Fr>::new(1, 32, chain_id, vec![tx_high_tip])
+            .with_max_authorizations(1)
+            .validate(),
+        Err(TxCircuitError::InvalidFeeCaps { tx_index: 0 })
+    );
+
+    // A set-code tx can't create a contract.
+    let mut tx = sign_set_code_tx(wallet, chain_id, vec![authorization]);
+    tx.to = None;
+    assert_eq!(
+        TxCircuit::<Fr>::new(1, 32, chain_id, vec![tx])
+            .with_max_authorizations(1)
+            .validate(),
+        Err(TxCircuitError::InvalidSetCodeTx { tx_index: 0 })
+    );
+}
+
 #[test]
 fn variadic_size_check() {
     const MAX_TXS: usize = 2;
@@ -105,10 +1078,10 @@ fn variadic_size_check() {
     let tx1: Transaction = mock::CORRECT_MOCK_TXS[0].clone().into();
     let tx2: Transaction = mock::CORRECT_MOCK_TXS[1].clone().into();
     let circuit = TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, vec![tx1.clone()]);
-    let prover1 = MockProver::<Fr>::run(20, &circuit, vec![vec![]]).unwrap();
+    let prover1 = MockProver::<Fr>::run(20, &circuit, circuit.instance()).unwrap();
 
     let circuit = TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, vec![tx1, tx2]);
-    let prover2 = MockProver::<Fr>::run(20, &circuit, vec![vec![]]).unwrap();
+    let prover2 = MockProver::<Fr>::run(20, &circuit, circuit.instance()).unwrap();
 
     assert_eq!(prover1.fixed(), prover2.fixed());
     assert_eq!(prover1.permutation(), prover2.permutation());
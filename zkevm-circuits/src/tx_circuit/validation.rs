@@ -0,0 +1,143 @@
+//! Validation of the TxCircuit inputs against the circuit parameters
+
+use super::{
+    authorization::{authorizations, is_valid_authorization},
+    sign_verify::is_low_s,
+    TxCircuit, TxCircuitError, TxSignData,
+};
+use eth_types::{evm_types::MAX_BLOBS_PER_BLOCK, geth_types::Transaction, Field};
+use halo2_proofs::halo2curves::secp256r1::Secp256r1Affine;
+
+impl<F: Field> TxCircuit<F> {
+    /// Check that the txs fit in the circuit parameters.
+    pub fn validate(&self) -> Result<(), TxCircuitError> {
+        if self.txs.len() > self.max_txs {
+            return Err(TxCircuitError::TooManyTxs {
+                num_txs: self.txs.len(),
+                max_txs: self.max_txs,
+            });
+        }
+        let calldata_len: usize = self.txs.iter().map(|tx| tx.call_data.len()).sum();
+        if calldata_len > self.max_calldata {
+            return Err(TxCircuitError::TooMuchCalldata {
+                calldata_len,
+                max_calldata: self.max_calldata,
+            });
+        }
+        let num_entries = access_list_len(&self.txs);
+        if num_entries > self.max_access_list {
+            return Err(TxCircuitError::TooLargeAccessList {
+                num_entries,
+                max_access_list: self.max_access_list,
+            });
+        }
+        let num_blob_hashes = self
+            .txs
+            .iter()
+            .map(|tx| tx.blob_versioned_hashes.len())
+            .sum();
+        if num_blob_hashes > MAX_BLOBS_PER_BLOCK {
+            return Err(TxCircuitError::TooManyBlobHashes {
+                num_blob_hashes,
+                max_blob_hashes: MAX_BLOBS_PER_BLOCK,
+            });
+        }
+        let gas: u128 = self.txs.iter().map(|tx| tx.gas() as u128).sum();
+        if gas > self.gas_limit as u128 {
+            return Err(TxCircuitError::GasLimitExceeded {
+                gas,
+                gas_limit: self.gas_limit,
+            });
+        }
+        // EIP-155: v = chain_id * 2 + 35 + {0, 1}
+        let v_min = self.chain_id.checked_mul(2).and_then(|v| v.checked_add(35));
+        for (tx_index, tx) in self.txs.iter().enumerate() {
+            // A deposit tx has no signature.
+            if tx.is_deposit() {
+                if !self.deposits {
+                    return Err(TxCircuitError::DepositNotSupported { tx_index });
+                }
+                continue;
+            }
+            // The fee caps are constrained by the "tx fee caps" gate.
+            let (fee_cap, tip_cap) = tx.fee_caps();
+            if tip_cap > fee_cap || fee_cap < self.base_fee || fee_cap.bits() > 128 {
+                return Err(TxCircuitError::InvalidFeeCaps { tx_index });
+            }
+            if tx_index >= self.max_signed_txs {
+                return Err(TxCircuitError::UnverifiedSignedTx {
+                    tx_index,
+                    max_signed_txs: self.max_signed_txs,
+                });
+            }
+            if tx.is_set_code() {
+                // EIP-7702: the chain_id is part of the signed payload, and v is the y parity.
+                let has_authorizations = tx
+                    .authorization_list
+                    .as_ref()
+                    .map_or(false, |authorization_list| !authorization_list.is_empty());
+                if tx.v > 1 || tx.is_create() || !has_authorizations {
+                    return Err(TxCircuitError::InvalidSetCodeTx { tx_index });
+                }
+            } else if !matches!(v_min.and_then(|v_min| tx.v.checked_sub(v_min)), Some(0 | 1)) {
+                return Err(TxCircuitError::InvalidChainId {
+                    tx_index,
+                    v: tx.v,
+                    chain_id: self.chain_id,
+                });
+            }
+            let is_low_s = match self.sign_datas.get(tx_index) {
+                Some(Some(TxSignData::Secp256r1(_))) => Secp256r1Affine::is_low_s(&tx.s),
+                // RSA signatures are not malleable, and Ed25519 signatures are verified with
+                // S < L.
+                Some(Some(TxSignData::Rsa(_) | TxSignData::Ed25519(_))) => true,
+                _ => is_low_s(&tx.s),
+            };
+            if self.sign_verify.low_s && !is_low_s {
+                return Err(TxCircuitError::HighS { tx_index });
+            }
+        }
+        let max_auths = self
+            .auth_sign_verify
+            .as_ref()
+            .map_or(0, |auth_sign_verify| auth_sign_verify.max_verif);
+        // The valid authorizations whose signature can't be recovered are counted as well, so
+        // that the check doesn't depend on the recovery of the SignData.
+        let num_auths = authorizations(&self.txs)
+            .filter(|(_, _, authorization)| is_valid_authorization(authorization, self.chain_id))
+            .count();
+        if num_auths > max_auths {
+            return Err(TxCircuitError::TooManyAuthorizations {
+                num_auths,
+                max_auths,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Return the number of rows of the access list sections used by the txs: the
+/// total number of addresses or of storage keys, whichever is larger.
+pub(super) fn access_list_len<'a>(txs: impl IntoIterator<Item = &'a Transaction> + Clone) -> usize {
+    let access_list_items = || {
+        txs.clone()
+            .into_iter()
+            .flat_map(|tx| tx.access_list_items())
+    };
+    std::cmp::max(
+        access_list_items().count(),
+        access_list_items()
+            .map(|item| item.storage_keys.len())
+            .sum(),
+    )
+}
+
+/// Return the number of leading tx slots whose signature must be verified:
+/// the txs after the last one which isn't a deposit need no verification.
+pub(super) fn signed_txs_len<'a>(txs: impl IntoIterator<Item = &'a Transaction>) -> usize {
+    txs.into_iter()
+        .enumerate()
+        .filter(|(_, tx)| !tx.is_deposit())
+        .last()
+        .map_or(0, |(tx_index, _)| tx_index + 1)
+}
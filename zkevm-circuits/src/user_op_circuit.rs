@@ -0,0 +1,783 @@
+//! ERC-4337 UserOperation circuit.
+//!
+//! The circuit lays out the fields of a bundle of UserOperations in the
+//! [`UserOpTable`], computes the userOpHash of every operation, and verifies
+//! that it's signed with RSA-2048 by the owner of the account, as done by the
+//! My Number Card for the MynaWallet accounts.  This lets a bundler prove the
+//! validity of the operations it submits to the EntryPoint.
+//!
+//! The userOpHash is `keccak(abi.encode(keccak(pack(userOp)), entry_point,
+//! chain_id))`, where `pack(userOp)` is the ABI encoding of the 10 words from
+//! Sender to PaymasterAndDataHash.  The dynamic fields (initCode, callData
+//! and paymasterAndData) are only hashed in the packed encoding, so the table
+//! holds their keccak hash instead of their bytes.  Both hashes are verified
+//! by KeccakTable lookups of the RLC of the encodings, computed from the
+//! range checked bytes of the field words.
+//!
+//! The public inputs, in the instance column of the MainGate, are the chain
+//! ID, the EntryPoint address (lo, hi) and the (userOpHash lo, userOpHash hi,
+//! owner lo, owner hi) of every operation slot.
+
+#[cfg(any(test, feature = "test-circuits"))]
+mod dev;
+#[cfg(test)]
+mod test;
+#[cfg(feature = "test-circuits")]
+pub use dev::UserOpCircuit as TestUserOpCircuit;
+
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+        from_bytes,
+    },
+    table::{KeccakTable, Sha256Table, UserOpFieldTag, UserOpTable},
+    tx_circuit::sign_verify::{
+        assign_bytes_le, assign_rlc_le, assigned_term, enable_lookup, AssignedSignatureVerify,
+        RsaSignVerifyChip, RsaSignVerifyConfig, SignVerifyLaneConfig,
+    },
+    util::{word::Word, Challenges, Expr, SubCircuitConfig},
+};
+use eth_types::{
+    keccak256,
+    rsa_types::{RsaPadding, RsaPublicKey, RsaSignData},
+    user_op_types::{UserOperation, USER_OP_PACKED_WORDS},
+    Address, Field, U256,
+};
+use gadgets::util::not;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, Selector},
+    poly::Rotation,
+};
+use itertools::Itertools;
+use log::error;
+use maingate::{
+    AssignedValue, MainGate, MainGateConfig, MainGateInstructions, RangeChip, RangeConfig,
+    RangeInstructions, RegionCtx,
+};
+use std::{iter, marker::PhantomData};
+
+/// Number of fields per UserOperation in the UserOpTable: the packed words,
+/// Owner and UserOpHash.
+pub(crate) const USER_OP_LEN: usize = USER_OP_PACKED_WORDS + 2;
+
+/// Errors for UserOpCircuit inputs that don't fit in the circuit parameters
+#[derive(Clone, Debug, thiserror::Error, PartialEq, Eq)]
+pub enum UserOpCircuitError {
+    /// There are more operations than operation slots
+    #[error("TooManyUserOps(num_user_ops: {num_user_ops}, max_user_ops: {max_user_ops})")]
+    TooManyUserOps {
+        /// Number of operations
+        num_user_ops: usize,
+        /// Max number of supported operations
+        max_user_ops: usize,
+    },
+    /// The signature of an operation is not the one of its userOpHash by its
+    /// owner
+    #[error("InvalidSignature(user_op_index: {user_op_index})")]
+    InvalidSignature {
+        /// Index of the operation
+        user_op_index: usize,
+    },
+}
+
+/// Circuit configuration arguments
+pub struct UserOpCircuitConfigArgs<F: Field> {
+    /// UserOpTable
+    pub user_op_table: UserOpTable,
+    /// KeccakTable
+    pub keccak_table: KeccakTable,
+    /// Sha256Table of the digests of the userOpHashes signed with RSA-2048
+    pub sha256_table: Sha256Table,
+    /// Challenges
+    pub challenges: Challenges<Expression<F>>,
+}
+
+/// Config for UserOpCircuit
+#[derive(Clone, Debug)]
+pub struct UserOpCircuitConfig<F: Field> {
+    user_op_id: Column<Advice>,
+    tag: Column<Fixed>,
+    value: Word<Column<Advice>>,
+    /// 1 on the all-zero first row of the user operation table
+    q_first: Column<Fixed>,
+    /// 1 on the rows holding the fields of an operation
+    q_fields: Column<Fixed>,
+    /// 1 on the first field row (Sender) of every operation
+    q_user_op_first: Column<Fixed>,
+    /// 1 on the rows of padding operations (beyond the number of operations
+    /// of the bundle)
+    is_padding: Column<Advice>,
+    // userOpHash computation
+    main_gate_config: MainGateConfig,
+    range_config: RangeConfig,
+    q_rlc_input: Selector,
+    rlc: Column<Advice>,
+    // Keccak of the packed encoding
+    q_keccak_pack: Selector,
+    // Keccak of the userOpHash input
+    q_keccak_hash: Selector,
+    /// RsaSignVerifyChip config for the signatures of the owners
+    rsa_sign_verify: RsaSignVerifyConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for UserOpCircuitConfig<F> {
+    type ConfigArgs = UserOpCircuitConfigArgs<F>;
+
+    /// Return a new UserOpCircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            user_op_table,
+            keccak_table,
+            sha256_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let user_op_id = user_op_table.user_op_id;
+        let tag = user_op_table.tag;
+        let value = user_op_table.value;
+        meta.enable_equality(value.lo());
+        meta.enable_equality(value.hi());
+
+        let q_first = meta.fixed_column();
+        let q_fields = meta.fixed_column();
+        let q_user_op_first = meta.fixed_column();
+        let is_padding = meta.advice_column();
+        meta.enable_equality(is_padding);
+
+        // Layout:
+        // | q_first | q_fields | q_user_op_first | user_op_id |     tag      |
+        // | ------- | -------- | --------------- | ---------- | ------------ |
+        // |    1    |    0     |        0        |     0      |     Null     |
+        // |    0    |    1     |        1        |     1      |    Sender    |
+        // |    0    |    1     |        0        |     1      |    Nonce     |
+        // |   ...   |   ...    |       ...       |    ...     |     ...      |
+        // |    0    |    1     |        0        |     1      |  UserOpHash  |
+        // |    0    |    1     |        1        |     2      |    Sender    |
+        // |   ...   |   ...    |       ...       |    ...     |     ...      |
+
+        meta.create_gate("user op table first row", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_zero(
+                "user_op_id == 0",
+                meta.query_advice(user_op_id, Rotation::cur()),
+            );
+            cb.require_zero_word("value == 0", value.query_advice(meta, Rotation::cur()));
+            cb.require_zero(
+                "is_padding == 0",
+                meta.query_advice(is_padding, Rotation::cur()),
+            );
+
+            cb.gate(meta.query_fixed(q_first, Rotation::cur()))
+        });
+
+        meta.create_gate("user op fields", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            // user_op_id increments by one on the Sender row and stays the same on the following
+            // USER_OP_LEN - 1 rows.
+            cb.require_equal(
+                "user_op_id == user_op_id_prev + q_user_op_first",
+                meta.query_advice(user_op_id, Rotation::cur()),
+                meta.query_advice(user_op_id, Rotation::prev())
+                    + meta.query_fixed(q_user_op_first, Rotation::cur()),
+            );
+
+            cb.gate(meta.query_fixed(q_fields, Rotation::cur()))
+        });
+
+        // Padding operations fill the slots after the operations of the bundle, and all their
+        // fields are zero, as the owner (address 0) and message (0) of the padding signature
+        // verified by the RsaSignVerifyChip for the slot.
+        meta.create_gate("user op padding fields", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let q_user_op_first = meta.query_fixed(q_user_op_first, Rotation::cur());
+            let is_padding_cur = meta.query_advice(is_padding, Rotation::cur());
+            let is_padding_prev = meta.query_advice(is_padding, Rotation::prev());
+
+            cb.require_boolean("is_padding is boolean", is_padding_cur.expr());
+            cb.require_zero(
+                "is_padding is the same for all the fields of an operation",
+                not::expr(q_user_op_first.expr())
+                    * (is_padding_cur.expr() - is_padding_prev.expr()),
+            );
+            cb.require_zero(
+                "a padding operation is only followed by padding operations",
+                q_user_op_first * is_padding_prev * not::expr(is_padding_cur.expr()),
+            );
+            cb.condition(is_padding_cur, |cb| {
+                cb.require_zero_word("value == 0", value.query_advice(meta, Rotation::cur()));
+            });
+
+            cb.gate(meta.query_fixed(q_fields, Rotation::cur()))
+        });
+
+        let main_gate_config = MainGate::<F>::configure(meta);
+        let range_config = RangeChip::<F>::configure(meta, &main_gate_config, vec![8], vec![]);
+
+        let q_rlc_input = meta.selector();
+        let rlc = meta.advice_column_in(SecondPhase);
+        meta.enable_equality(rlc);
+        SignVerifyLaneConfig::configure_rlc(
+            meta,
+            "user_op_input_rlc",
+            main_gate_config.clone(),
+            q_rlc_input,
+            rlc,
+            challenges.keccak_input(),
+        );
+
+        // Verify that keccak(pack(userOp)) = pack_hash and keccak(abi.encode(pack_hash,
+        // entry_point, chain_id)) = user_op_hash by keccak table lookups.
+        let keccak_table_columns = [
+            keccak_table.is_enabled,
+            keccak_table.input_rlc,
+            keccak_table.input_len,
+            keccak_table.output.lo(),
+            keccak_table.output.hi(),
+        ];
+        let [q_keccak_pack, q_keccak_hash] = [
+            ("user op pack keccak", 32 * USER_OP_PACKED_WORDS),
+            ("user op hash keccak", 32 * 3),
+        ]
+        .map(|(name, input_len)| {
+            let q_lookup = meta.complex_selector();
+            SignVerifyLaneConfig::configure_lookup(
+                meta,
+                name,
+                &main_gate_config,
+                q_lookup,
+                rlc,
+                input_len,
+                keccak_table_columns,
+            );
+            q_lookup
+        });
+
+        let rsa_sign_verify =
+            RsaSignVerifyConfig::new(meta, keccak_table, sha256_table, challenges);
+
+        Self {
+            user_op_id,
+            tag,
+            value,
+            q_first,
+            q_fields,
+            q_user_op_first,
+            is_padding,
+            main_gate_config,
+            range_config,
+            q_rlc_input,
+            rlc,
+            q_keccak_pack,
+            q_keccak_hash,
+            rsa_sign_verify,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> UserOpCircuitConfig<F> {
+    /// Load the RangeChip tables.
+    pub fn load_aux_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        let range_chip = RangeChip::<F>::new(self.range_config.clone());
+        range_chip.load_table(layouter)?;
+        self.rsa_sign_verify.load_range(layouter)
+    }
+
+    /// Assigns a user operation table row and returns the assigned cells of
+    /// `is_padding` and of the value in the row.
+    fn assign_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        user_op_id: usize,
+        tag: UserOpFieldTag,
+        value: Word<Value<F>>,
+        is_padding: bool,
+    ) -> Result<(AssignedCell<F, F>, Word<AssignedCell<F, F>>), Error> {
+        let is_padding = region.assign_advice(
+            || "is_padding",
+            self.is_padding,
+            offset,
+            || Value::known(F::from(is_padding as u64)),
+        )?;
+        region.assign_advice(
+            || "user_op_id",
+            self.user_op_id,
+            offset,
+            || Value::known(F::from(user_op_id as u64)),
+        )?;
+        region.assign_fixed(
+            || "tag",
+            self.tag,
+            offset,
+            || Value::known(F::from(tag as u64)),
+        )?;
+        for (name, column, enabled) in [
+            ("q_first", self.q_first, tag == UserOpFieldTag::Null),
+            ("q_fields", self.q_fields, tag != UserOpFieldTag::Null),
+            (
+                "q_user_op_first",
+                self.q_user_op_first,
+                tag == UserOpFieldTag::Sender,
+            ),
+        ] {
+            region.assign_fixed(
+                || name,
+                column,
+                offset,
+                || Value::known(F::from(enabled as u64)),
+            )?;
+        }
+        let value = value.assign_advice(region, || "value", self.value, offset)?;
+        Ok((is_padding, value))
+    }
+}
+
+/// Cells of the userOpHash computation of an operation slot
+struct AssignedUserOpHash<F: Field> {
+    is_padding: AssignedValue<F>,
+    packed_words: Vec<Word<AssignedValue<F>>>,
+    user_op_hash: Word<AssignedValue<F>>,
+}
+
+/// Assign a word of at most `bits` bits as range checked bytes, and return the
+/// word and its 32 little endian byte cells, padded with `zero`.
+fn assign_word<F: Field>(
+    ctx: &mut RegionCtx<F>,
+    range_chip: &RangeChip<F>,
+    zero: &AssignedValue<F>,
+    value: U256,
+    bits: usize,
+) -> Result<(Word<AssignedValue<F>>, Vec<AssignedValue<F>>), Error> {
+    let mut bytes_le = [0u8; 32];
+    value.to_little_endian(&mut bytes_le);
+    if bits == 256 {
+        return assign_bytes_le(ctx, range_chip, &bytes_le);
+    }
+
+    let mut words = Vec::with_capacity(2);
+    let mut byte_cells = Vec::with_capacity(32);
+    for (bytes, bits) in bytes_le
+        .chunks(16)
+        .zip([bits.min(128), bits.saturating_sub(128)])
+    {
+        if bits == 0 {
+            words.push(zero.clone());
+        } else {
+            let (word, bytes) = range_chip.decompose(
+                ctx,
+                Value::known(from_bytes::value::<F>(&bytes[..bits / 8])),
+                8,
+                bits,
+            )?;
+            words.push(word);
+            byte_cells.extend(bytes);
+        }
+        byte_cells.extend(iter::repeat(zero.clone()).take(16 - bits / 8));
+    }
+    Ok((Word::new(words.try_into().unwrap()), byte_cells))
+}
+
+/// UserOperation Circuit for verifying the hashes and the owner signatures of
+/// a bundle of operations
+#[derive(Clone, Default, Debug)]
+pub struct UserOpCircuit<F: Field> {
+    /// Max number of supported operations
+    pub max_user_ops: usize,
+    /// Chain ID
+    pub chain_id: u64,
+    /// Address of the EntryPoint
+    pub entry_point: Address,
+    /// UserOperations of the bundle
+    pub user_ops: Vec<UserOperation>,
+    /// RsaSignVerify chip for the signatures of the owners
+    pub rsa_sign_verify: RsaSignVerifyChip<F>,
+    /// RsaSignData of every operation, computed once when the circuit is
+    /// created.  `None` for operations whose signature doesn't verify.
+    sign_datas: Vec<Option<RsaSignData>>,
+}
+
+impl<F: Field> UserOpCircuit<F> {
+    /// Return a new UserOpCircuit, where the operation `user_ops[i]` is
+    /// signed with RSA-2048 PKCS#1 v1.5 by the owner public key `owners[i]`.
+    pub fn new(
+        max_user_ops: usize,
+        chain_id: u64,
+        entry_point: Address,
+        user_ops: Vec<UserOperation>,
+        owners: &[RsaPublicKey],
+    ) -> Self {
+        let sign_datas = user_ops
+            .iter()
+            .zip_eq(owners)
+            .enumerate()
+            .map(|(i, (user_op, pk))| {
+                user_op
+                    .rsa_sign_data(entry_point, chain_id, pk.clone(), RsaPadding::Pkcs1v15)
+                    .map_err(|e| error!("rsa_sign_data error for user op {}: {:?}", i, e))
+                    .ok()
+            })
+            .collect();
+        Self {
+            max_user_ops,
+            chain_id,
+            entry_point,
+            user_ops,
+            rsa_sign_verify: RsaSignVerifyChip::new(max_user_ops),
+            sign_datas,
+        }
+    }
+
+    /// Check that the operations fit in the circuit parameters and are signed
+    /// by their owners.
+    pub fn validate(&self) -> Result<(), UserOpCircuitError> {
+        if self.user_ops.len() > self.max_user_ops {
+            return Err(UserOpCircuitError::TooManyUserOps {
+                num_user_ops: self.user_ops.len(),
+                max_user_ops: self.max_user_ops,
+            });
+        }
+        if let Some(user_op_index) = self.sign_datas.iter().position(Option::is_none) {
+            return Err(UserOpCircuitError::InvalidSignature { user_op_index });
+        }
+        Ok(())
+    }
+
+    /// Return the number of unusable rows of the circuit
+    pub fn unusable_rows() -> usize {
+        // No column queried at more than 3 distinct rotations, so returns 6 as
+        // minimum unusable rows.
+        6
+    }
+
+    /// Return the minimum number of rows required to prove `max_user_ops`
+    /// operation slots.
+    pub fn min_num_rows(max_user_ops: usize) -> usize {
+        [
+            1 + max_user_ops * USER_OP_LEN,
+            max_user_ops * Self::rows_per_hash(),
+            RsaSignVerifyChip::<F>::min_num_rows(max_user_ops),
+        ]
+        .into_iter()
+        .max()
+        .unwrap()
+    }
+
+    /// Return the number of MainGate rows used by the userOpHash computation of
+    /// one operation: an upper bound of the byte decompositions of the words
+    /// and of the RLCs of the 416 hashed bytes.
+    pub fn rows_per_hash() -> usize {
+        500
+    }
+
+    /// Return the keccak inputs of the circuit: the encodings hashed to the
+    /// userOpHash of every operation, and the modulus of every owner.
+    pub fn keccak_inputs(&self) -> Vec<Vec<u8>> {
+        self.user_ops
+            .iter()
+            .flat_map(|user_op| {
+                [
+                    user_op.pack(),
+                    user_op.hash_input(self.entry_point, self.chain_id),
+                ]
+            })
+            .chain(
+                self.sign_datas
+                    .iter()
+                    .flatten()
+                    .map(|sign_data| sign_data.pk.n_be().to_vec()),
+            )
+            .collect()
+    }
+
+    /// Return the sha256 inputs of the RsaSignVerifyChip: the userOpHashes.
+    pub fn sha256_inputs(&self) -> Vec<Vec<u8>> {
+        self.sign_datas
+            .iter()
+            .flatten()
+            .flat_map(|sign_data| sign_data.sha256_inputs())
+            .collect()
+    }
+
+    /// Return the public inputs: the chain ID, the EntryPoint address and the
+    /// (userOpHash, owner) of every operation slot, as (lo, hi) words.
+    pub fn instance(&self) -> Vec<Vec<F>> {
+        let entry_point = Word::<F>::from(self.entry_point);
+        let user_op_instance = |i: usize| {
+            self.sign_datas
+                .get(i)
+                .and_then(Option::as_ref)
+                .map(|sign_data| {
+                    let user_op_hash = Word::<F>::from(U256::from_big_endian(&sign_data.msg));
+                    let owner = Word::<F>::from(Address::from_slice(
+                        &keccak256(&sign_data.pk.n_be())[12..],
+                    ));
+                    [user_op_hash.lo(), user_op_hash.hi(), owner.lo(), owner.hi()]
+                })
+                .unwrap_or([F::ZERO; 4])
+        };
+        let public_inputs = [F::from(self.chain_id), entry_point.lo(), entry_point.hi()]
+            .into_iter()
+            .chain((0..self.max_user_ops).flat_map(user_op_instance))
+            .collect();
+        // The maingate of the RsaSignVerifyChip expects an instance column, but we don't use it.
+        vec![public_inputs, vec![]]
+    }
+
+    /// Assign the userOpHash computation of every operation slot, and return
+    /// the cells of the chain ID, of the EntryPoint address and of every slot.
+    #[allow(clippy::type_complexity)]
+    fn assign_user_op_hashes(
+        &self,
+        config: &UserOpCircuitConfig<F>,
+        layouter: &mut impl Layouter<F>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<
+        (
+            AssignedValue<F>,
+            Word<AssignedValue<F>>,
+            Vec<AssignedUserOpHash<F>>,
+        ),
+        Error,
+    > {
+        let main_gate = MainGate::new(config.main_gate_config.clone());
+        let range_chip = RangeChip::new(config.range_config.clone());
+
+        layouter.assign_region(
+            || "user op hashes",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+                let zero = main_gate.assign_constant(&mut ctx, F::ZERO)?;
+                let (chain_id, chain_id_le) =
+                    assign_word(&mut ctx, &range_chip, &zero, U256::from(self.chain_id), 64)?;
+                let (entry_point, entry_point_le) = assign_word(
+                    &mut ctx,
+                    &range_chip,
+                    &zero,
+                    U256::from_big_endian(self.entry_point.as_bytes()),
+                    160,
+                )?;
+
+                let mut assigned_hashes = Vec::with_capacity(self.max_user_ops);
+                for i in 0..self.max_user_ops {
+                    // Padding slots hash the zero operation, and their hashes are not looked up.
+                    let user_op = self.user_ops.get(i);
+                    let is_padding = main_gate
+                        .assign_bit(&mut ctx, Value::known(F::from(user_op.is_none() as u64)))?;
+                    let words = user_op
+                        .map(|user_op| user_op.packed_words())
+                        .unwrap_or_default();
+                    let mut packed_words = Vec::with_capacity(USER_OP_PACKED_WORDS);
+                    let mut packed_le = Vec::with_capacity(USER_OP_PACKED_WORDS);
+                    for (j, word) in words.into_iter().enumerate() {
+                        // The Sender is an address
+                        let bits = if j == 0 { 160 } else { 256 };
+                        let (word, bytes_le) =
+                            assign_word(&mut ctx, &range_chip, &zero, word, bits)?;
+                        packed_words.push(word);
+                        packed_le.push(bytes_le);
+                    }
+                    let pack_rlc = assign_rlc_le(
+                        &mut ctx,
+                        &main_gate,
+                        &config.main_gate_config,
+                        config.rlc,
+                        "user_op_pack",
+                        config.q_rlc_input,
+                        challenges.keccak_input(),
+                        packed_le.iter().rev().flatten().map(assigned_term),
+                    )?;
+                    let mut pack_hash_le = user_op
+                        .map(|user_op| keccak256(&user_op.pack()))
+                        .unwrap_or_default();
+                    pack_hash_le.reverse();
+                    let (pack_hash, pack_hash_le_cells) =
+                        assign_bytes_le(&mut ctx, &range_chip, &pack_hash_le)?;
+                    enable_lookup(
+                        &mut ctx,
+                        config.q_keccak_pack,
+                        &config.main_gate_config,
+                        config.rlc,
+                        &is_padding,
+                        &pack_rlc,
+                        &pack_hash,
+                    )?;
+
+                    let hash_rlc = assign_rlc_le(
+                        &mut ctx,
+                        &main_gate,
+                        &config.main_gate_config,
+                        config.rlc,
+                        "user_op_hash_input",
+                        config.q_rlc_input,
+                        challenges.keccak_input(),
+                        chain_id_le
+                            .iter()
+                            .chain(&entry_point_le)
+                            .chain(&pack_hash_le_cells)
+                            .map(assigned_term),
+                    )?;
+                    let mut user_op_hash_le = user_op
+                        .map(|user_op| user_op.hash(self.entry_point, self.chain_id))
+                        .unwrap_or_default();
+                    user_op_hash_le.reverse();
+                    let (user_op_hash, _) =
+                        assign_bytes_le(&mut ctx, &range_chip, &user_op_hash_le)?;
+                    enable_lookup(
+                        &mut ctx,
+                        config.q_keccak_hash,
+                        &config.main_gate_config,
+                        config.rlc,
+                        &is_padding,
+                        &hash_rlc,
+                        &user_op_hash,
+                    )?;
+
+                    assigned_hashes.push(AssignedUserOpHash {
+                        is_padding,
+                        packed_words,
+                        user_op_hash,
+                    });
+                }
+                log::debug!("user op hashes: {} rows", ctx.offset());
+                Ok((chain_id.lo(), entry_point.clone(), assigned_hashes))
+            },
+        )
+    }
+
+    /// Assign the user operation table, copying the fields from the userOpHash
+    /// computation and the RsaSignVerifyChip.
+    fn assign_user_op_table(
+        &self,
+        config: &UserOpCircuitConfig<F>,
+        layouter: &mut impl Layouter<F>,
+        assigned_hashes: &[AssignedUserOpHash<F>],
+        assigned_sig_verifs: &[AssignedSignatureVerify<F>],
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "user op table",
+            |mut region| {
+                let mut offset = 0;
+                // Empty entry
+                config.assign_row(
+                    &mut region,
+                    offset,
+                    0,
+                    UserOpFieldTag::Null,
+                    Word::default().into_value(),
+                    false,
+                )?;
+                offset += 1;
+                for (i, (assigned_hash, assigned_sig_verif)) in assigned_hashes
+                    .iter()
+                    .zip_eq(assigned_sig_verifs)
+                    .enumerate()
+                {
+                    let fields = assigned_hash
+                        .packed_words
+                        .iter()
+                        .chain([&assigned_sig_verif.address, &assigned_hash.user_op_hash]);
+                    let tags = [
+                        UserOpFieldTag::Sender,
+                        UserOpFieldTag::Nonce,
+                        UserOpFieldTag::InitCodeHash,
+                        UserOpFieldTag::CallDataHash,
+                        UserOpFieldTag::CallGasLimit,
+                        UserOpFieldTag::VerificationGasLimit,
+                        UserOpFieldTag::PreVerificationGas,
+                        UserOpFieldTag::MaxFeePerGas,
+                        UserOpFieldTag::MaxPriorityFeePerGas,
+                        UserOpFieldTag::PaymasterAndDataHash,
+                        UserOpFieldTag::Owner,
+                        UserOpFieldTag::UserOpHash,
+                    ];
+                    for (tag, field) in tags.into_iter().zip_eq(fields) {
+                        let (assigned_is_padding, assigned_cell) = config.assign_row(
+                            &mut region,
+                            offset,
+                            i + 1,
+                            tag,
+                            field.map(|cell| cell.value().copied()),
+                            i >= self.user_ops.len(),
+                        )?;
+                        offset += 1;
+
+                        region.constrain_equal(assigned_cell.lo().cell(), field.lo().cell())?;
+                        region.constrain_equal(assigned_cell.hi().cell(), field.hi().cell())?;
+                        if tag == UserOpFieldTag::Sender {
+                            for is_padding in
+                                [&assigned_hash.is_padding, &assigned_sig_verif.is_padding]
+                            {
+                                region.constrain_equal(
+                                    assigned_is_padding.cell(),
+                                    is_padding.cell(),
+                                )?;
+                            }
+                        }
+                    }
+                    // The owner signed the userOpHash
+                    region.constrain_equal(
+                        assigned_hash.user_op_hash.lo().cell(),
+                        assigned_sig_verif.msg_hash.lo().cell(),
+                    )?;
+                    region.constrain_equal(
+                        assigned_hash.user_op_hash.hi().cell(),
+                        assigned_sig_verif.msg_hash.hi().cell(),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Make the assignments to the UserOpCircuit
+    pub fn synthesize_sub(
+        &self,
+        config: &UserOpCircuitConfig<F>,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        self.validate().map_err(|e| {
+            error!("invalid UserOpCircuit input: {}", e);
+            Error::Synthesis
+        })?;
+
+        config.load_aux_tables(layouter)?;
+        let assigned_sig_verifs = self.rsa_sign_verify.assign(
+            &config.rsa_sign_verify,
+            layouter,
+            &self.sign_datas,
+            challenges,
+        )?;
+        let (chain_id, entry_point, assigned_hashes) =
+            self.assign_user_op_hashes(config, layouter, challenges)?;
+        self.assign_user_op_table(config, layouter, &assigned_hashes, &assigned_sig_verifs)?;
+
+        let main_gate = MainGate::new(config.main_gate_config.clone());
+        let public_inputs = [chain_id, entry_point.lo(), entry_point.hi()]
+            .into_iter()
+            .chain(
+                assigned_hashes
+                    .iter()
+                    .zip_eq(&assigned_sig_verifs)
+                    .flat_map(|(assigned_hash, assigned_sig_verif)| {
+                        [
+                            assigned_hash.user_op_hash.lo(),
+                            assigned_hash.user_op_hash.hi(),
+                            assigned_sig_verif.address.lo(),
+                            assigned_sig_verif.address.hi(),
+                        ]
+                    }),
+            );
+        for (row, cell) in public_inputs.enumerate() {
+            main_gate.expose_public(layouter.namespace(|| "user op public input"), cell, row)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+pub use super::UserOpCircuit;
+
+use crate::{
+    table::{KeccakTable, Sha256Table, UserOpTable},
+    user_op_circuit::{UserOpCircuitConfig, UserOpCircuitConfigArgs},
+    util::{Challenges, SubCircuitConfig},
+};
+use eth_types::Field;
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+impl<F: Field> Circuit<F> for UserOpCircuit<F> {
+    type Config = (UserOpCircuitConfig<F>, Challenges, KeccakTable, Sha256Table);
+    type FloorPlanner = SimpleFloorPlanner;
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.max_user_ops, 0, Default::default(), vec![], &[])
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let user_op_table = UserOpTable::construct(meta);
+        let keccak_table = KeccakTable::construct(meta);
+        let sha256_table = Sha256Table::construct(meta);
+        let challenges = Challenges::construct(meta);
+
+        let config = {
+            let challenges = challenges.exprs(meta);
+            UserOpCircuitConfig::new(
+                meta,
+                UserOpCircuitConfigArgs {
+                    user_op_table,
+                    keccak_table: keccak_table.clone(),
+                    sha256_table: sha256_table.clone(),
+                    challenges,
+                },
+            )
+        };
+
+        (config, challenges, keccak_table, sha256_table)
+    }
+
+    fn synthesize(
+        &self,
+        (config, challenges, keccak_table, sha256_table): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let challenges = challenges.values(&mut layouter);
+
+        keccak_table.dev_load(&mut layouter, &self.keccak_inputs(), &challenges)?;
+        sha256_table.dev_load(&mut layouter, &self.sha256_inputs(), &challenges)?;
+        self.synthesize_sub(&config, &challenges, &mut layouter)
+    }
+}
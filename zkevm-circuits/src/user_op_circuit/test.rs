@@ -0,0 +1,91 @@
+use super::*;
+use crate::util::{log2_ceil, unusable_rows};
+use eth_types::{address, rsa_types::biguint_to_rsa_bytes_be, Bytes};
+use halo2_proofs::{
+    arithmetic::Field as _,
+    dev::{MockProver, VerifyFailure},
+    halo2curves::bn256::Fr,
+};
+
+#[test]
+fn user_op_circuit_unusable_rows() {
+    assert_eq!(
+        UserOpCircuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, UserOpCircuit::<Fr>>(()),
+    )
+}
+
+fn run_with_instance<F: Field>(
+    circuit: UserOpCircuit<F>,
+    instance: Vec<Vec<F>>,
+) -> Result<(), Vec<VerifyFailure>> {
+    let k = log2_ceil(
+        UserOpCircuit::<Fr>::unusable_rows()
+            + UserOpCircuit::<Fr>::min_num_rows(circuit.max_user_ops),
+    );
+    let prover = match MockProver::run(k, &circuit, instance) {
+        Ok(prover) => prover,
+        Err(e) => panic!("{:#?}", e),
+    };
+    prover.verify()
+}
+
+/// Return a UserOperation signed by `MOCK_RSA_KEY`.
+fn signed_user_op(chain_id: u64, entry_point: Address) -> UserOperation {
+    let mut user_op = UserOperation {
+        sender: address!("0x00000000000000000000000000000000cafebabe"),
+        nonce: U256::from(1),
+        call_data: Bytes::from(vec![0xb6, 0x1d, 0x27, 0xf6, 0x00, 0x01]),
+        call_gas_limit: U256::from(100_000),
+        verification_gas_limit: U256::from(1_500_000),
+        pre_verification_gas: U256::from(50_000),
+        max_fee_per_gas: U256::from(2_000_000_000u64),
+        max_priority_fee_per_gas: U256::from(1_000_000_000u64),
+        ..Default::default()
+    };
+    let signature = mock::MOCK_RSA_KEY.sign(&user_op.hash(entry_point, chain_id));
+    user_op.signature = Bytes::from(biguint_to_rsa_bytes_be(&signature).to_vec());
+    user_op
+}
+
+#[test]
+fn user_op_circuit_1op_2max_ops() {
+    let chain_id = mock::MOCK_CHAIN_ID.as_u64();
+    let entry_point = address!("0x5FF137D4b0FDCD49DcA30c7CF57E578a026d2789");
+    let owner = mock::MOCK_RSA_KEY.pk.clone();
+
+    let circuit = UserOpCircuit::<Fr>::new(
+        2,
+        chain_id,
+        entry_point,
+        vec![signed_user_op(chain_id, entry_point)],
+        &[owner],
+    );
+    assert_eq!(circuit.validate(), Ok(()));
+    let instance = circuit.instance();
+    assert_eq!(run_with_instance(circuit.clone(), instance.clone()), Ok(()));
+
+    // The public userOpHash must be the one of the operation
+    let mut wrong_instance = instance;
+    wrong_instance[0][3] += Fr::ONE;
+    assert!(run_with_instance(circuit, wrong_instance).is_err());
+}
+
+#[test]
+fn user_op_circuit_invalid_signature() {
+    let chain_id = mock::MOCK_CHAIN_ID.as_u64();
+    let entry_point = address!("0x5FF137D4b0FDCD49DcA30c7CF57E578a026d2789");
+
+    // The operation is signed for another chain
+    let circuit = UserOpCircuit::<Fr>::new(
+        1,
+        chain_id,
+        entry_point,
+        vec![signed_user_op(chain_id + 1, entry_point)],
+        &[mock::MOCK_RSA_KEY.pk.clone()],
+    );
+    assert_eq!(
+        circuit.validate(),
+        Err(UserOpCircuitError::InvalidSignature { user_op_index: 0 })
+    );
+}
@@ -5,7 +5,7 @@
 mod block;
 pub use block::{block_convert, Block, BlockContext};
 mod mpt;
-pub use mpt::{MptUpdate, MptUpdateRow, MptUpdates};
+pub use mpt::{MptUpdate, MptUpdateRow, MptUpdates, MptUpdatesError};
 mod rw;
 pub use bus_mapping::circuit_input_builder::{Call, ExecStep, Transaction, Withdrawal};
 pub use rw::{Rw, RwMap, RwRow};
@@ -1,17 +1,22 @@
-use super::{ExecStep, Rw, RwMap, Transaction};
+use super::{ExecStep, MptUpdates, MptUpdatesError, Rw, RwMap, Transaction};
 use crate::{
     evm_circuit::{detect_fixed_table_tags, EvmCircuit},
     exp_circuit::param::OFFSET_INCREMENT,
     instance::public_data_convert,
+    mpt_circuit::witness_row::Node,
+    state_circuit::StateCircuitError,
     table::BlockContextFieldTag,
     util::{log2_ceil, word, SubCircuit},
 };
 use bus_mapping::{
-    circuit_input_builder::{self, CopyEvent, ExpEvent, FixedCParams, Withdrawal},
+    circuit_input_builder::{
+        self, Blake2fEvent, CodeHashScheme, CopyEvent, EcAddEvent, EcMulEvent, ExpEvent,
+        FixedCParams, ModExpEvent, P256VerifyEvent, Withdrawal,
+    },
     state_db::CodeDB,
     Error,
 };
-use eth_types::{Address, Field, ToScalar, Word, H256};
+use eth_types::{sign_types::SignData, Address, Field, ToScalar, Word, H256};
 use halo2_proofs::circuit::Value;
 use itertools::Itertools;
 
@@ -24,6 +29,12 @@ pub struct Block<F> {
     pub randomness: F,
     /// Transactions in the block
     pub txs: Vec<Transaction>,
+    /// BeaconRoot step of the system call before the first transaction
+    /// (EIP-4788)
+    pub beacon_root: Option<ExecStep>,
+    /// HistoryStorage step of the system call storing the parent block hash
+    /// before the first transaction (EIP-2935)
+    pub history_storage: Option<ExecStep>,
     /// EndBlock step that is repeated after the last transaction and before
     /// reaching the last EVM row.
     pub end_block_not_last: ExecStep,
@@ -45,8 +56,33 @@ pub struct Block<F> {
     pub circuits_params: FixedCParams,
     /// Inputs to the SHA3 opcode
     pub sha3_inputs: Vec<Vec<u8>>,
+    /// Signatures verified by the ecrecover calls, for the SigTable
+    pub ecrecover_events: Vec<SignData>,
+    /// Inputs of the sha256 calls, for the Sha256Table
+    pub sha256_inputs: Vec<Vec<u8>>,
+    /// Inputs of the ripemd160 calls, for the Ripemd160Table
+    pub ripemd160_inputs: Vec<Vec<u8>>,
+    /// Inputs of the Poseidon hashes, for the PoseidonTable
+    pub poseidon_inputs: Vec<Vec<u8>>,
+    /// Modular exponentiations of the modexp calls, for the ModExpTable
+    pub modexp_events: Vec<ModExpEvent>,
+    /// Point additions of the ecAdd calls, for the EccTable
+    pub ec_add_events: Vec<EcAddEvent>,
+    /// Scalar multiplications of the ecMul calls, for the EccTable
+    pub ec_mul_events: Vec<EcMulEvent>,
+    /// Compressions of the successful blake2f calls, for the Blake2fTable
+    pub blake2f_events: Vec<Blake2fEvent>,
+    /// Signature verifications of the P256VERIFY calls, for the P256SigTable
+    pub p256_verify_events: Vec<P256VerifyEvent>,
     /// State root of the previous block
     pub prev_state_root: Word, // TODO: Make this H256
+    /// MPT proofs of the account and storage updates of the block, in the
+    /// order of the MptTable, which are empty if the proofs aren't generated,
+    /// in which case the block can't be proved by the SuperCircuit.
+    pub mpt_nodes: Vec<Node>,
+    /// MPT updates of the rws, with the roots of their MPT proofs, which are
+    /// set along with the proofs.
+    pub mpt_updates: Option<MptUpdates>,
     /// Keccak inputs
     pub keccak_inputs: Vec<Vec<u8>>,
     /// Original Block from geth
@@ -71,6 +107,34 @@ impl<F: Field> Block<F> {
         }
     }
 
+    /// Set the MPT proofs of the block, generated from its MPT updates, and
+    /// add their hashes to the keccak inputs.  Fails if the proofs don't
+    /// match the MPT updates of the rws.
+    pub fn set_mpt_nodes(&mut self, nodes: Vec<Node>) -> Result<(), MptUpdatesError> {
+        let updates = MptUpdates::new(&self.rws.table_assignments(), &nodes)?;
+        self.keccak_inputs.extend(
+            nodes
+                .iter()
+                .flat_map(|node| node.keccak_data.iter().map(|bytes| bytes.to_vec())),
+        );
+        self.mpt_nodes = nodes;
+        self.mpt_updates = Some(updates);
+        Ok(())
+    }
+
+    /// Check that the rws of the block fit in the `max_rws` of the circuit
+    /// parameters.
+    pub fn validate_rws(&self) -> Result<(), StateCircuitError> {
+        let num_rws = self
+            .rws
+            .0
+            .values()
+            .flatten()
+            .filter(|rw| !matches!(rw, Rw::Start { .. }))
+            .count();
+        StateCircuitError::check_max_rws(num_rws, self.circuits_params.max_rws)
+    }
+
     /// Get a read-write record
     pub(crate) fn get_rws(&self, step: &ExecStep, index: usize) -> Rw {
         self.rws[step.rw_index(index)]
@@ -109,7 +173,7 @@ impl<F: Field> Block<F> {
         let num_rows_required_for_rw_table: usize = self.circuits_params.max_rws;
         let num_rows_required_for_fixed_table: usize = detect_fixed_table_tags(self)
             .iter()
-            .map(|tag| tag.build::<F>().count())
+            .map(|tag| tag.build::<F>(self.circuits_params.hardfork).count())
             .sum();
         let num_rows_required_for_bytecode_table =
             self.bytecodes.num_rows_required_for_bytecode_table();
@@ -175,79 +239,85 @@ pub struct BlockContext {
     pub chain_id: Word,
     /// The withdrawal root
     pub withdrawals_root: Word,
+    /// The blob base fee, derived from the excess blob gas of the block
+    pub blob_base_fee: Word,
+    /// The prev_randao, which is the mix hash of the block
+    pub prev_randao: Word,
+    /// The root of the parent beacon block, which is 0 before Cancun
+    pub parent_beacon_root: Word,
 }
 
 impl BlockContext {
     /// Assignments for block table
     pub fn table_assignments<F: Field>(&self) -> Vec<[Value<F>; 4]> {
-        [
-            vec![
-                [
-                    Value::known(F::from(BlockContextFieldTag::Coinbase as u64)),
-                    Value::known(F::ZERO),
-                    Value::known(word::Word::from(self.coinbase).lo()),
-                    Value::known(word::Word::from(self.coinbase).hi()),
-                ],
-                [
-                    Value::known(F::from(BlockContextFieldTag::Timestamp as u64)),
-                    Value::known(F::ZERO),
-                    Value::known(self.timestamp.to_scalar().unwrap()),
-                    Value::known(F::ZERO),
-                ],
-                [
-                    Value::known(F::from(BlockContextFieldTag::Number as u64)),
-                    Value::known(F::ZERO),
-                    Value::known(self.number.to_scalar().unwrap()),
-                    Value::known(F::ZERO),
-                ],
-                [
-                    Value::known(F::from(BlockContextFieldTag::Difficulty as u64)),
-                    Value::known(F::ZERO),
-                    Value::known(word::Word::from(self.difficulty).lo()),
-                    Value::known(word::Word::from(self.difficulty).hi()),
-                ],
-                [
-                    Value::known(F::from(BlockContextFieldTag::GasLimit as u64)),
-                    Value::known(F::ZERO),
-                    Value::known(F::from(self.gas_limit)),
-                    Value::known(F::ZERO),
-                ],
-                [
-                    Value::known(F::from(BlockContextFieldTag::BaseFee as u64)),
-                    Value::known(F::ZERO),
-                    Value::known(word::Word::from(self.base_fee).lo()),
-                    Value::known(word::Word::from(self.base_fee).hi()),
-                ],
-                [
-                    Value::known(F::from(BlockContextFieldTag::ChainId as u64)),
-                    Value::known(F::ZERO),
-                    Value::known(word::Word::from(self.chain_id).lo()),
-                    Value::known(word::Word::from(self.chain_id).hi()),
-                ],
-                [
-                    Value::known(F::from(BlockContextFieldTag::WithdrawalRoot as u64)),
-                    Value::known(F::ZERO),
-                    Value::known(word::Word::from(self.withdrawals_root).lo()),
-                    Value::known(word::Word::from(self.withdrawals_root).hi()),
-                ],
+        vec![
+            [
+                Value::known(F::from(BlockContextFieldTag::Coinbase as u64)),
+                Value::known(F::ZERO),
+                Value::known(word::Word::from(self.coinbase).lo()),
+                Value::known(word::Word::from(self.coinbase).hi()),
+            ],
+            [
+                Value::known(F::from(BlockContextFieldTag::Timestamp as u64)),
+                Value::known(F::ZERO),
+                Value::known(self.timestamp.to_scalar().unwrap()),
+                Value::known(F::ZERO),
+            ],
+            [
+                Value::known(F::from(BlockContextFieldTag::Number as u64)),
+                Value::known(F::ZERO),
+                Value::known(self.number.to_scalar().unwrap()),
+                Value::known(F::ZERO),
+            ],
+            [
+                Value::known(F::from(BlockContextFieldTag::Difficulty as u64)),
+                Value::known(F::ZERO),
+                Value::known(word::Word::from(self.difficulty).lo()),
+                Value::known(word::Word::from(self.difficulty).hi()),
+            ],
+            [
+                Value::known(F::from(BlockContextFieldTag::GasLimit as u64)),
+                Value::known(F::ZERO),
+                Value::known(F::from(self.gas_limit)),
+                Value::known(F::ZERO),
+            ],
+            [
+                Value::known(F::from(BlockContextFieldTag::BaseFee as u64)),
+                Value::known(F::ZERO),
+                Value::known(word::Word::from(self.base_fee).lo()),
+                Value::known(word::Word::from(self.base_fee).hi()),
+            ],
+            [
+                Value::known(F::from(BlockContextFieldTag::ChainId as u64)),
+                Value::known(F::ZERO),
+                Value::known(word::Word::from(self.chain_id).lo()),
+                Value::known(word::Word::from(self.chain_id).hi()),
+            ],
+            [
+                Value::known(F::from(BlockContextFieldTag::WithdrawalRoot as u64)),
+                Value::known(F::ZERO),
+                Value::known(word::Word::from(self.withdrawals_root).lo()),
+                Value::known(word::Word::from(self.withdrawals_root).hi()),
+            ],
+            [
+                Value::known(F::from(BlockContextFieldTag::BlobBaseFee as u64)),
+                Value::known(F::ZERO),
+                Value::known(word::Word::from(self.blob_base_fee).lo()),
+                Value::known(word::Word::from(self.blob_base_fee).hi()),
+            ],
+            [
+                Value::known(F::from(BlockContextFieldTag::PrevRandao as u64)),
+                Value::known(F::ZERO),
+                Value::known(word::Word::from(self.prev_randao).lo()),
+                Value::known(word::Word::from(self.prev_randao).hi()),
+            ],
+            [
+                Value::known(F::from(BlockContextFieldTag::ParentBeaconRoot as u64)),
+                Value::known(F::ZERO),
+                Value::known(word::Word::from(self.parent_beacon_root).lo()),
+                Value::known(word::Word::from(self.parent_beacon_root).hi()),
             ],
-            {
-                let len_history = self.history_hashes.len();
-                self.history_hashes
-                    .iter()
-                    .enumerate()
-                    .map(|(idx, hash)| {
-                        [
-                            Value::known(F::from(BlockContextFieldTag::BlockHash as u64)),
-                            Value::known((self.number - len_history + idx).to_scalar().unwrap()),
-                            Value::known(word::Word::from(*hash).lo()),
-                            Value::known(word::Word::from(*hash).hi()),
-                        ]
-                    })
-                    .collect()
-            },
         ]
-        .concat()
     }
 }
 
@@ -263,6 +333,13 @@ impl From<&circuit_input_builder::Block> for BlockContext {
             history_hashes: block.history_hashes.clone(),
             chain_id: block.chain_id,
             withdrawals_root: block.withdrawals_root().as_fixed_bytes().into(),
+            blob_base_fee: block.blob_base_fee,
+            prev_randao: block.prev_randao,
+            parent_beacon_root: block
+                .parent_beacon_root
+                .unwrap_or_default()
+                .as_fixed_bytes()
+                .into(),
         }
     }
 }
@@ -281,15 +358,37 @@ pub fn block_convert<F: Field>(
         context: block.into(),
         rws,
         txs: block.txs().to_vec(),
+        beacon_root: block.block_steps.beacon_root.clone(),
+        history_storage: block.block_steps.history_storage.clone(),
         end_block_not_last: block.block_steps.end_block_not_last.clone(),
         end_block_last: block.block_steps.end_block_last.clone(),
         bytecodes: code_db.clone(),
         copy_events: block.copy_events.clone(),
         exp_events: block.exp_events.clone(),
         sha3_inputs: block.sha3_inputs.clone(),
+        ecrecover_events: block.ecrecover_events.clone(),
+        sha256_inputs: block.sha256_inputs.clone(),
+        ripemd160_inputs: block.ripemd160_inputs.clone(),
+        // The bytecodes are hashed by the Poseidon circuit when the code hashes
+        // of the Bytecode Circuit are Poseidon digests.
+        poseidon_inputs: match builder.circuits_params.code_hash_scheme {
+            CodeHashScheme::Keccak => vec![],
+            CodeHashScheme::Poseidon => code_db
+                .clone()
+                .into_iter()
+                .map(|bytecode| bytecode.code())
+                .collect(),
+        },
+        modexp_events: block.modexp_events.clone(),
+        ec_add_events: block.ec_add_events.clone(),
+        ec_mul_events: block.ec_mul_events.clone(),
+        blake2f_events: block.blake2f_events.clone(),
+        p256_verify_events: block.p256_verify_events.clone(),
         circuits_params: builder.circuits_params,
         exp_circuit_pad_to: <usize>::default(),
         prev_state_root: block.prev_state_root,
+        mpt_nodes: vec![],
+        mpt_updates: None,
         keccak_inputs: circuit_input_builder::keccak_inputs(block, code_db)?,
         eth_block: block.eth_block.clone(),
     };
@@ -1,12 +1,42 @@
 use crate::{
     evm_circuit::witness::Rw,
+    mpt_circuit::witness_row::{Node, StartRowType},
     table::{AccountFieldTag, MPTProofType},
     util::word,
 };
 use eth_types::{Address, Field, ToScalar, Word};
 use halo2_proofs::circuit::Value;
 use itertools::Itertools;
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, convert::Infallible};
+
+/// Errors for MPT proofs that don't match the MPT updates of the rws
+#[derive(Clone, Debug, thiserror::Error, PartialEq, Eq)]
+pub enum MptUpdatesError {
+    /// There is no proof for an update
+    #[error("MissingProof(index: {index})")]
+    MissingProof {
+        /// Index of the update
+        index: usize,
+    },
+    /// The proof of an update is of another type
+    #[error("ProofTypeMismatch(index: {index}, proof_type: {proof_type:?}, update_type: {update_type:?})")]
+    ProofTypeMismatch {
+        /// Index of the update
+        index: usize,
+        /// Type of the proof
+        proof_type: MPTProofType,
+        /// Type of the update
+        update_type: MPTProofType,
+    },
+    /// There are more proofs than updates
+    #[error("TooManyProofs(num_updates: {num_updates}, num_proofs: {num_proofs})")]
+    TooManyProofs {
+        /// Number of updates
+        num_updates: usize,
+        /// Number of proofs
+        num_proofs: usize,
+    },
+}
 
 /// An MPT update whose validity is proved by the MptCircuit
 #[derive(Debug, Clone, Copy)]
@@ -19,8 +49,8 @@ pub struct MptUpdate {
 }
 
 impl MptUpdate {
-    fn proof_type<F: Field>(&self) -> F {
-        let proof_type = match self.key {
+    fn proof_type(&self) -> MPTProofType {
+        match self.key {
             Key::AccountStorage { .. } => {
                 if self.old_value.is_zero() && self.new_value.is_zero() {
                     MPTProofType::StorageDoesNotExist
@@ -28,9 +58,18 @@ impl MptUpdate {
                     MPTProofType::StorageChanged
                 }
             }
-            Key::Account { field_tag, .. } => field_tag.into(),
-        };
-        F::from(proof_type as u64)
+            Key::Account { field_tag, .. } => {
+                // Clearing the code hash of an existing account destructs it
+                if matches!(field_tag, AccountFieldTag::CodeHash)
+                    && !self.old_value.is_zero()
+                    && self.new_value.is_zero()
+                {
+                    MPTProofType::AccountDestructed
+                } else {
+                    field_tag.into()
+                }
+            }
+        }
     }
 }
 
@@ -38,6 +77,7 @@ impl MptUpdate {
 #[derive(Default, Clone, Debug)]
 pub struct MptUpdates {
     old_root: Word,
+    new_root: Word,
     updates: BTreeMap<Key, MptUpdate>,
 }
 
@@ -58,13 +98,96 @@ impl MptUpdates {
         self.old_root
     }
 
+    /// Return the state root after all the updates.
+    pub(crate) fn new_root(&self) -> Word {
+        self.new_root
+    }
+
     pub(crate) fn get(&self, row: &Rw) -> Option<MptUpdate> {
         key(row).map(|key| *self.updates.get(&key).expect("missing key in mpt updates"))
     }
 
     pub(crate) fn mock_from(rows: &[Rw]) -> Self {
         let mock_old_root = Word::from(0xcafeu64);
-        let map: BTreeMap<_, _> = rows
+        let updates = Self::from_roots(rows, mock_old_root, |i, _| {
+            Ok::<_, Infallible>((
+                Word::from(i as u64) + mock_old_root,
+                Word::from(i as u64 + 1) + mock_old_root,
+            ))
+        });
+        match updates {
+            Ok(updates) => updates,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Return the MPT updates of the rows, whose roots are the ones of the MPT
+    /// proofs of the updates, in the same order, or an error if the proofs
+    /// don't match the updates.
+    pub(crate) fn new(rows: &[Rw], nodes: &[Node]) -> Result<Self, MptUpdatesError> {
+        // The proofs are terminated by a disabled start node.
+        let proofs: Vec<_> = nodes
+            .iter()
+            .filter_map(|node| node.start.as_ref().map(|start| (start, &node.values)))
+            .filter(|(start, _)| start.proof_type != MPTProofType::Disabled)
+            .map(|(start, values)| {
+                let [old_root, new_root] = [StartRowType::RootS, StartRowType::RootC]
+                    .map(|row| Word::from_big_endian(&values[row as usize][1..]));
+                (start.proof_type, old_root, new_root)
+            })
+            .collect();
+        let old_root = proofs.first().map(|proof| proof.1).unwrap_or_default();
+        let updates = Self::from_roots(rows, old_root, |index, update| {
+            let (proof_type, old_root, new_root) = *proofs
+                .get(index)
+                .ok_or(MptUpdatesError::MissingProof { index })?;
+            if proof_type != update.proof_type() {
+                return Err(MptUpdatesError::ProofTypeMismatch {
+                    index,
+                    proof_type,
+                    update_type: update.proof_type(),
+                });
+            }
+            Ok((old_root, new_root))
+        })?;
+        if updates.updates.len() != proofs.len() {
+            return Err(MptUpdatesError::TooManyProofs {
+                num_updates: updates.updates.len(),
+                num_proofs: proofs.len(),
+            });
+        }
+        Ok(updates)
+    }
+
+    /// Return the updates of the rows, which are a part of these updates, from
+    /// `old_root`, which is the new root of the updates before them.
+    pub(crate) fn slice(&self, rows: &[Rw], old_root: Word) -> Self {
+        let mut new_root = old_root;
+        let updates = rows
+            .iter()
+            .filter_map(key)
+            .map(|key| {
+                let update = *self.updates.get(&key).expect("missing key in mpt updates");
+                new_root = update.new_root;
+                (key, update)
+            })
+            .collect();
+        MptUpdates {
+            updates,
+            old_root,
+            new_root,
+        }
+    }
+
+    /// Return the MPT updates of the rows, with the roots of each update, by
+    /// index in the rows, given by `roots`.
+    fn from_roots<E>(
+        rows: &[Rw],
+        old_root: Word,
+        mut roots: impl FnMut(usize, &MptUpdate) -> Result<(Word, Word), E>,
+    ) -> Result<Self, E> {
+        let mut new_root = old_root;
+        let map = rows
             .iter()
             .group_by(|row| key(row))
             .into_iter()
@@ -75,22 +198,23 @@ impl MptUpdates {
                 let last = rows.last().unwrap_or(first);
                 let key_exists = key;
                 let key = key.set_non_exists(value_prev(first), value(last));
-                (
-                    key_exists,
-                    MptUpdate {
-                        key,
-                        old_root: Word::from(i as u64) + mock_old_root,
-                        new_root: Word::from(i as u64 + 1) + mock_old_root,
-                        old_value: value_prev(first),
-                        new_value: value(last),
-                    },
-                )
+                let mut update = MptUpdate {
+                    key,
+                    old_root: Word::zero(),
+                    new_root: Word::zero(),
+                    old_value: value_prev(first),
+                    new_value: value(last),
+                };
+                (update.old_root, update.new_root) = roots(i, &update)?;
+                new_root = update.new_root;
+                Ok((key_exists, update))
             })
-            .collect();
-        MptUpdates {
+            .collect::<Result<BTreeMap<_, _>, E>>()?;
+        Ok(MptUpdates {
             updates: map,
-            old_root: mock_old_root,
-        }
+            old_root,
+            new_root,
+        })
     }
 
     pub(crate) fn table_assignments<F: Field>(&self) -> Vec<MptUpdateRow<Value<F>>> {
@@ -102,7 +226,7 @@ impl MptUpdates {
                 MptUpdateRow {
                     address: Value::known(update.key.address().to_scalar().unwrap()),
                     storage_key: word::Word::<F>::from(update.key.storage_key()).into_value(),
-                    proof_type: Value::known(update.proof_type()),
+                    proof_type: Value::known(F::from(update.proof_type() as u64)),
                     new_root: word::Word::<F>::from(new_root).into_value(),
                     old_root: word::Word::<F>::from(old_root).into_value(),
                     new_value: word::Word::<F>::from(new_value).into_value(),
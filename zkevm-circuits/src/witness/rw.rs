@@ -8,6 +8,7 @@ use bus_mapping::{
 use eth_types::{Address, Field, ToAddress, ToScalar, Word, U256};
 use halo2_proofs::circuit::Value;
 use itertools::Itertools;
+use rayon::prelude::*;
 
 use crate::{
     table::{AccountFieldTag, CallContextFieldTag, TxLogFieldTag, TxReceiptFieldTag},
@@ -138,7 +139,7 @@ impl RwMap {
     /// Build Rws for assignment
     pub fn table_assignments(&self) -> Vec<Rw> {
         let mut rows: Vec<Rw> = self.0.values().flatten().cloned().collect();
-        rows.sort_by_key(|row| {
+        rows.par_sort_by_key(|row| {
             (
                 row.tag() as u64,
                 row.id().unwrap_or_default(),
@@ -256,6 +257,25 @@ pub enum Rw {
         field_tag: TxReceiptFieldTag,
         value: u64,
     },
+    /// TransientStorage
+    TransientStorage {
+        rw_counter: usize,
+        is_write: bool,
+        tx_id: usize,
+        account_address: Address,
+        storage_key: Word,
+        value: Word,
+        value_prev: Word,
+    },
+    /// CreatedAccount
+    CreatedAccount {
+        rw_counter: usize,
+        is_write: bool,
+        tx_id: usize,
+        account_address: Address,
+        value: u64,
+        value_prev: u64,
+    },
 }
 
 /// Rw table row assignment
@@ -426,6 +446,24 @@ impl Rw {
         }
     }
 
+    pub(crate) fn transient_storage_value_pair(&self) -> (Word, Word) {
+        match self {
+            Self::TransientStorage {
+                value, value_prev, ..
+            } => (*value, *value_prev),
+            _ => unreachable!(),
+        }
+    }
+
+    pub(crate) fn created_account_value_pair(&self) -> (u64, u64) {
+        match self {
+            Self::CreatedAccount {
+                value, value_prev, ..
+            } => (*value, *value_prev),
+            _ => unreachable!(),
+        }
+    }
+
     pub(crate) fn call_context_value(&self) -> Word {
         match self {
             Self::CallContext { value, .. } => *value,
@@ -483,7 +521,9 @@ impl Rw {
             | Self::Account { rw_counter, .. }
             | Self::CallContext { rw_counter, .. }
             | Self::TxLog { rw_counter, .. }
-            | Self::TxReceipt { rw_counter, .. } => *rw_counter,
+            | Self::TxReceipt { rw_counter, .. }
+            | Self::TransientStorage { rw_counter, .. }
+            | Self::CreatedAccount { rw_counter, .. } => *rw_counter,
         }
     }
 
@@ -499,7 +539,9 @@ impl Rw {
             | Self::Account { is_write, .. }
             | Self::CallContext { is_write, .. }
             | Self::TxLog { is_write, .. }
-            | Self::TxReceipt { is_write, .. } => *is_write,
+            | Self::TxReceipt { is_write, .. }
+            | Self::TransientStorage { is_write, .. }
+            | Self::CreatedAccount { is_write, .. } => *is_write,
         }
     }
 
@@ -516,6 +558,8 @@ impl Rw {
             Self::CallContext { .. } => Target::CallContext,
             Self::TxLog { .. } => Target::TxLog,
             Self::TxReceipt { .. } => Target::TxReceipt,
+            Self::TransientStorage { .. } => Target::TransientStorage,
+            Self::CreatedAccount { .. } => Target::CreatedAccount,
         }
     }
 
@@ -526,7 +570,9 @@ impl Rw {
             | Self::TxAccessListAccountStorage { tx_id, .. }
             | Self::TxRefund { tx_id, .. }
             | Self::TxLog { tx_id, .. }
-            | Self::TxReceipt { tx_id, .. } => Some(*tx_id),
+            | Self::TxReceipt { tx_id, .. }
+            | Self::TransientStorage { tx_id, .. }
+            | Self::CreatedAccount { tx_id, .. } => Some(*tx_id),
             Self::CallContext { call_id, .. }
             | Self::Stack { call_id, .. }
             | Self::Memory { call_id, .. } => Some(*call_id),
@@ -547,6 +593,12 @@ impl Rw {
             }
             | Self::AccountStorage {
                 account_address, ..
+            }
+            | Self::TransientStorage {
+                account_address, ..
+            }
+            | Self::CreatedAccount {
+                account_address, ..
             } => Some(*account_address),
             Self::Memory { memory_address, .. } => Some(U256::from(*memory_address).to_address()),
             Self::Stack { stack_pointer, .. } => {
@@ -580,14 +632,17 @@ impl Rw {
             | Self::TxAccessListAccount { .. }
             | Self::TxAccessListAccountStorage { .. }
             | Self::TxRefund { .. }
-            | Self::TxLog { .. } => None,
+            | Self::TxLog { .. }
+            | Self::TransientStorage { .. }
+            | Self::CreatedAccount { .. } => None,
         }
     }
 
     pub(crate) fn storage_key(&self) -> Option<Word> {
         match self {
             Self::AccountStorage { storage_key, .. }
-            | Self::TxAccessListAccountStorage { storage_key, .. } => Some(*storage_key),
+            | Self::TxAccessListAccountStorage { storage_key, .. }
+            | Self::TransientStorage { storage_key, .. } => Some(*storage_key),
             Self::Start { .. }
             | Self::CallContext { .. }
             | Self::Stack { .. }
@@ -596,7 +651,8 @@ impl Rw {
             | Self::Account { .. }
             | Self::TxAccessListAccount { .. }
             | Self::TxLog { .. }
-            | Self::TxReceipt { .. } => None,
+            | Self::TxReceipt { .. }
+            | Self::CreatedAccount { .. } => None,
         }
     }
 
@@ -607,24 +663,29 @@ impl Rw {
             | Self::Account { value, .. }
             | Self::AccountStorage { value, .. }
             | Self::Stack { value, .. }
-            | Self::TxLog { value, .. } => *value,
+            | Self::TxLog { value, .. }
+            | Self::TransientStorage { value, .. } => *value,
             Self::TxAccessListAccount { is_warm, .. }
             | Self::TxAccessListAccountStorage { is_warm, .. } => U256::from(*is_warm as u64),
             Self::Memory { byte, .. } => U256::from(u64::from(*byte)),
-            Self::TxRefund { value, .. } | Self::TxReceipt { value, .. } => U256::from(*value),
+            Self::TxRefund { value, .. }
+            | Self::TxReceipt { value, .. }
+            | Self::CreatedAccount { value, .. } => U256::from(*value),
         }
     }
 
     pub(crate) fn value_prev_assignment(&self) -> Option<Word> {
         match self {
-            Self::Account { value_prev, .. } | Self::AccountStorage { value_prev, .. } => {
-                Some(*value_prev)
-            }
+            Self::Account { value_prev, .. }
+            | Self::AccountStorage { value_prev, .. }
+            | Self::TransientStorage { value_prev, .. } => Some(*value_prev),
             Self::TxAccessListAccount { is_warm_prev, .. }
             | Self::TxAccessListAccountStorage { is_warm_prev, .. } => {
                 Some(U256::from(*is_warm_prev as u64))
             }
-            Self::TxRefund { value_prev, .. } => Some(U256::from(*value_prev)),
+            Self::TxRefund { value_prev, .. } | Self::CreatedAccount { value_prev, .. } => {
+                Some(U256::from(*value_prev))
+            }
             Self::Start { .. }
             | Self::Stack { .. }
             | Self::Memory { .. }
@@ -855,6 +916,37 @@ impl From<&operation::OperationContainer> for RwMap {
                 })
                 .collect(),
         );
+        rws.insert(
+            Target::TransientStorage,
+            container
+                .transient_storage
+                .iter()
+                .map(|op| Rw::TransientStorage {
+                    rw_counter: op.rwc().into(),
+                    is_write: op.rw().is_write(),
+                    tx_id: op.op().tx_id,
+                    account_address: op.op().address,
+                    storage_key: op.op().key,
+                    value: op.op().value,
+                    value_prev: op.op().value_prev,
+                })
+                .collect(),
+        );
+        rws.insert(
+            Target::CreatedAccount,
+            container
+                .created_account
+                .iter()
+                .map(|op| Rw::CreatedAccount {
+                    rw_counter: op.rwc().into(),
+                    is_write: op.rw().is_write(),
+                    tx_id: op.op().tx_id,
+                    account_address: op.op().address,
+                    value: op.op().value.into(),
+                    value_prev: op.op().value_prev.into(),
+                })
+                .collect(),
+        );
 
         Self(rws)
     }
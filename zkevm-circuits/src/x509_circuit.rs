@@ -0,0 +1,1285 @@
+//! X.509 certificate chain verification circuit.
+//!
+//! The circuit verifies a chain of DER encoded X.509 v3 certificates with RSA-2048 keys signed
+//! with sha256WithRSAEncryption, as issued by the JPKI for the My Number Card: the first
+//! certificate is signed by the public key of a certification authority, every next one by the
+//! subject public key of the previous one, and all of them are valid at a timestamp.  The CA
+//! public key, the timestamp and the subject public key of the last (leaf) certificate are
+//! public inputs, so that the leaf key can be bound to the keys verified by the
+//! RsaSignVerifyChip of the TxCircuit.
+//!
+//! Every byte of a certificate is assigned to a row tagged with its field (see [`X509Tag`]).  The
+//! tags follow the order of the fields, the bytes of every field are checked against a fixed
+//! table of the field contents, and the value and RLC of every field are accumulated so that the
+//! fields needed by the verification are looked up at their last row.  The fields of any content
+//! (the issuer, the subject and the extensions) are walked as sequences of DER TLVs, whose tags
+//! and lengths are assigned byte by byte, so that they can only end at the end of a TLV: as the
+//! fields before them end at the end of a TLV of the tbsCertificate, a well-formed certificate is
+//! split as by the parser.
+//!
+//! The signatures and the validity are verified with the MainGate, sharing the modular
+//! arithmetic of the RsaSignVerifyChip.  With the `sha1` feature, the certificates can also be
+//! signed with sha1WithRSAEncryption, as the legacy intermediate certificates of older chains:
+//! the tbsCertificate digest is then looked up in the Sha1Table instead of the Sha256Table.
+
+mod certificate;
+#[cfg(any(test, feature = "test-circuits"))]
+mod dev;
+#[cfg(test)]
+mod test;
+#[cfg(feature = "test-circuits")]
+pub use dev::X509Circuit as TestX509Circuit;
+
+pub use certificate::{
+    utc_time_value, X509Certificate, X509Error, X509FieldContent, X509SignatureAlgorithm, X509Tag,
+    UTC_TIME_BYTES,
+};
+
+#[cfg(feature = "sha1")]
+use crate::table::Sha1Table;
+use crate::{
+    evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    table::{LookupTable, Sha256Table, UXTable},
+    tx_circuit::sign_verify::{
+        assign_bytes_le, assign_rlc_le, assigned_term, AssignedRsaInteger, RsaSignVerifyChip,
+        SignVerifyLaneConfig,
+    },
+    util::{Challenges, SubCircuitConfig},
+};
+use eth_types::{
+    rsa_types::{RsaPublicKey, RSA_2048_BYTES},
+    Field,
+};
+use gadgets::util::{and, not, select, Expr};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, Selector,
+        VirtualCells,
+    },
+    poly::Rotation,
+};
+use itertools::Itertools;
+use log::error;
+use maingate::{
+    AssignedValue, MainGate, MainGateConfig, MainGateInstructions, RangeChip, RangeConfig,
+    RangeInstructions, RegionCtx, Term,
+};
+use num_bigint::BigUint;
+use std::{collections::HashMap, marker::PhantomData};
+use strum::IntoEnumIterator;
+
+/// Fields looked up at their last row to verify a certificate
+const EXPORTED_FIELDS: [X509Tag; 8] = [
+    X509Tag::CertLen,
+    X509Tag::TbsLen,
+    X509Tag::NotBefore,
+    X509Tag::NotAfter,
+    X509Tag::Modulus,
+    X509Tag::Extensions,
+    X509Tag::SignatureAlgorithm,
+    X509Tag::Signature,
+];
+
+/// Config arguments of the X509CircuitConfig
+#[derive(Clone, Debug)]
+pub struct X509CircuitConfigArgs<F: Field> {
+    /// Sha256Table, to verify the hashes of the tbsCertificates
+    pub sha256_table: Sha256Table,
+    /// Sha1Table, to verify the hashes of the tbsCertificates signed with
+    /// sha1WithRSAEncryption
+    #[cfg(feature = "sha1")]
+    pub sha1_table: Sha1Table,
+    /// u8 lookup table, to range check the certificate bytes
+    pub u8_table: UXTable<8>,
+    /// Challenges
+    pub challenges: Challenges<Expression<F>>,
+}
+
+/// X509Circuit configuration
+#[derive(Clone, Debug)]
+pub struct X509CircuitConfig<F: Field> {
+    // Certificate rows, one per byte
+    q_row: Selector,
+    // Every certificate row but the last one
+    q_step: Selector,
+    q_first: Selector,
+    q_last: Selector,
+    cert_id: Column<Fixed>,
+    // Offset of the byte in the certificate
+    offset: Column<Fixed>,
+    byte: Column<Advice>,
+    tag: Column<Advice>,
+    // Offset of the byte in its field, 0 for the fields of any content
+    field_offset: Column<Advice>,
+    is_any: Column<Advice>,
+    is_constant: Column<Advice>,
+    is_last: Column<Advice>,
+    is_tbs: Column<Advice>,
+    // DER TLVs of the fields of any content but the padding: is_tlv_tag, is_tlv_len and
+    // is_tlv_len_long tag the tag byte, the first length byte and the next ones of the long form,
+    // the other bytes being content.  len_left is the number of length bytes after the byte, and
+    // tlv_remaining the number of content bytes after the byte, or the length read so far.
+    is_der: Column<Advice>,
+    is_tlv_tag: Column<Advice>,
+    is_tlv_len: Column<Advice>,
+    is_tlv_len_long: Column<Advice>,
+    is_long_form: Column<Advice>,
+    len_left: Column<Advice>,
+    tlv_remaining: Column<Advice>,
+    // Big endian value and RLC of the field up to the byte
+    value: Column<Advice>,
+    rlc: Column<Advice>,
+    // RLC of the tbsCertificate up to the byte
+    tbs_rlc: Column<Advice>,
+    // Exported field rows, which share the value and RLC columns
+    q_field: Selector,
+    field_tag: Column<Fixed>,
+    end_offset: Column<Advice>,
+    // Fixed table of the field contents:
+    // (tag, field_offset, is_any, is_constant, byte, is_last, is_tbs, is_der)
+    content_table: [Column<Fixed>; 8],
+    // Verifications
+    main_gate_config: MainGateConfig,
+    range_config: RangeConfig,
+    q_rlc_input: Selector,
+    input_rlc: Column<Advice>,
+    q_tbs_digest: Selector,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for X509CircuitConfig<F> {
+    type ConfigArgs = X509CircuitConfigArgs<F>;
+
+    /// Return a new X509CircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            sha256_table,
+            #[cfg(feature = "sha1")]
+            sha1_table,
+            u8_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let q_row = meta.complex_selector();
+        let q_step = meta.complex_selector();
+        let q_first = meta.selector();
+        let q_last = meta.selector();
+        let cert_id = meta.fixed_column();
+        let offset = meta.fixed_column();
+        let [byte, tag, field_offset, is_any, is_constant, is_last, is_tbs, value] =
+            [(); 8].map(|_| meta.advice_column());
+        let [is_der, is_tlv_tag, is_tlv_len, is_tlv_len_long, is_long_form, len_left, tlv_remaining] =
+            [(); 7].map(|_| meta.advice_column());
+        let [rlc, tbs_rlc] = [(); 2].map(|_| meta.advice_column_in(SecondPhase));
+        let q_field = meta.complex_selector();
+        let field_tag = meta.fixed_column();
+        let end_offset = meta.advice_column();
+        let content_table = [(); 8].map(|_| meta.fixed_column());
+        for column in [value, rlc, tbs_rlc, end_offset] {
+            meta.enable_equality(column);
+        }
+        let challenge = challenges.keccak_input();
+
+        meta.lookup_any("x509 field content", |meta| {
+            let q_row = meta.query_selector(q_row);
+            let byte = meta.query_advice(byte, Rotation::cur());
+            let is_constant = meta.query_advice(is_constant, Rotation::cur());
+            let input = [
+                meta.query_advice(tag, Rotation::cur()),
+                meta.query_advice(field_offset, Rotation::cur()),
+                meta.query_advice(is_any, Rotation::cur()),
+                is_constant.clone(),
+                is_constant * byte,
+                meta.query_advice(is_last, Rotation::cur()),
+                meta.query_advice(is_tbs, Rotation::cur()),
+                meta.query_advice(is_der, Rotation::cur()),
+            ];
+            input
+                .into_iter()
+                .zip(content_table)
+                .map(|(input, column)| {
+                    (
+                        q_row.clone() * input,
+                        meta.query_fixed(column, Rotation::cur()),
+                    )
+                })
+                .collect()
+        });
+        meta.lookup_any("x509 byte range", |meta| {
+            let q_row = meta.query_selector(q_row);
+            let byte = meta.query_advice(byte, Rotation::cur());
+            vec![(q_row * byte, u8_table.table_exprs(meta)[0].clone())]
+        });
+        // The first length byte is < 0x80 in the short form, and >= 0x80 in the long form.
+        meta.lookup_any("x509 DER length form", |meta| {
+            let q_row = meta.query_selector(q_row);
+            let byte = meta.query_advice(byte, Rotation::cur());
+            let is_tlv_len = meta.query_advice(is_tlv_len, Rotation::cur());
+            let is_long_form = meta.query_advice(is_long_form, Rotation::cur());
+            vec![(
+                q_row * is_tlv_len * 2.expr() * (byte - is_long_form * 0x80.expr()),
+                u8_table.table_exprs(meta)[0].clone(),
+            )]
+        });
+
+        meta.create_gate("x509 first row", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let byte = meta.query_advice(byte, Rotation::cur());
+            cb.require_equal(
+                "the first field is the certificate header",
+                meta.query_advice(tag, Rotation::cur()),
+                (X509Tag::CertHeader as u64).expr(),
+            );
+            cb.require_zero(
+                "field_offset starts at 0",
+                meta.query_advice(field_offset, Rotation::cur()),
+            );
+            cb.require_equal(
+                "value starts from the byte",
+                meta.query_advice(value, Rotation::cur()),
+                byte.clone(),
+            );
+            cb.require_equal(
+                "rlc starts from the byte",
+                meta.query_advice(rlc, Rotation::cur()),
+                byte,
+            );
+            cb.require_zero(
+                "tbs_rlc starts at 0",
+                meta.query_advice(tbs_rlc, Rotation::cur()),
+            );
+
+            cb.gate(meta.query_selector(q_first))
+        });
+
+        meta.create_gate("x509 step", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let [tag, tag_next] = [Rotation::cur(), Rotation::next()]
+                .map(|rotation| meta.query_advice(tag, rotation));
+            let [field_offset, field_offset_next] = [Rotation::cur(), Rotation::next()]
+                .map(|rotation| meta.query_advice(field_offset, rotation));
+            let [value, value_next] = [Rotation::cur(), Rotation::next()]
+                .map(|rotation| meta.query_advice(value, rotation));
+            let [rlc, rlc_next] = [Rotation::cur(), Rotation::next()]
+                .map(|rotation| meta.query_advice(rlc, rotation));
+            let [tbs_rlc, tbs_rlc_next] = [Rotation::cur(), Rotation::next()]
+                .map(|rotation| meta.query_advice(tbs_rlc, rotation));
+            let byte_next = meta.query_advice(byte, Rotation::next());
+            let is_any = meta.query_advice(is_any, Rotation::cur());
+            let is_last = meta.query_advice(is_last, Rotation::cur());
+            let is_tbs_next = meta.query_advice(is_tbs, Rotation::next());
+
+            // The fields follow the order of the tags, without skipping any
+            let is_field_end = tag_next - tag;
+            cb.require_boolean("tag increases by 0 or 1", is_field_end.clone());
+            cb.condition(is_field_end.clone(), |cb| {
+                cb.require_equal("a field ends at its last byte", is_last, 1.expr());
+                cb.require_zero("field_offset starts at 0", field_offset_next.clone());
+                cb.require_equal(
+                    "value starts from the byte",
+                    value_next.clone(),
+                    byte_next.clone(),
+                );
+                cb.require_equal(
+                    "rlc starts from the byte",
+                    rlc_next.clone(),
+                    byte_next.clone(),
+                );
+            });
+            cb.condition(
+                and::expr([not::expr(is_field_end.clone()), not::expr(is_any)]),
+                |cb| {
+                    cb.require_equal(
+                        "field_offset increases",
+                        field_offset_next,
+                        field_offset + 1.expr(),
+                    );
+                },
+            );
+            cb.condition(not::expr(is_field_end), |cb| {
+                cb.require_equal(
+                    "value accumulates the byte",
+                    value_next,
+                    value * 256.expr() + byte_next.clone(),
+                );
+                cb.require_equal(
+                    "rlc accumulates the byte",
+                    rlc_next,
+                    rlc * challenge.clone() + byte_next.clone(),
+                );
+            });
+            cb.require_equal(
+                "tbs_rlc accumulates the bytes of the tbsCertificate",
+                tbs_rlc_next,
+                select::expr(
+                    is_tbs_next,
+                    tbs_rlc.clone() * challenge.clone() + byte_next,
+                    tbs_rlc,
+                ),
+            );
+
+            cb.gate(meta.query_selector(q_step))
+        });
+
+        meta.create_gate("x509 DER TLV", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let byte = meta.query_advice(byte, Rotation::cur());
+            let [is_tlv_tag, is_tlv_len, is_tlv_len_long, is_long_form, len_left, tlv_remaining] =
+                [
+                    is_tlv_tag,
+                    is_tlv_len,
+                    is_tlv_len_long,
+                    is_long_form,
+                    len_left,
+                    tlv_remaining,
+                ]
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+
+            for (name, value) in [
+                ("is_tlv_tag is boolean", is_tlv_tag.clone()),
+                ("is_tlv_len is boolean", is_tlv_len.clone()),
+                ("is_tlv_len_long is boolean", is_tlv_len_long.clone()),
+                ("is_long_form is boolean", is_long_form.clone()),
+                (
+                    "a byte is a tag, a length or a content byte",
+                    is_tlv_tag + is_tlv_len.clone() + is_tlv_len_long,
+                ),
+            ] {
+                cb.require_boolean(name, value);
+            }
+            cb.condition(is_tlv_len, |cb| {
+                cb.require_equal(
+                    "len_left is the number of length bytes of the long form",
+                    len_left.clone(),
+                    is_long_form.clone() * (byte.clone() - 0x80.expr()),
+                );
+                cb.condition(is_long_form.clone(), |cb| {
+                    cb.require_zero(
+                        "the long form has 1 or 2 length bytes",
+                        (len_left.clone() - 1.expr()) * (len_left - 2.expr()),
+                    );
+                });
+                cb.require_equal(
+                    "tlv_remaining is the length of the short form",
+                    tlv_remaining,
+                    not::expr(is_long_form) * byte,
+                );
+            });
+
+            cb.gate(meta.query_selector(q_row))
+        });
+
+        meta.create_gate("x509 DER TLV step", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let [is_der, is_der_next] = [Rotation::cur(), Rotation::next()]
+                .map(|rotation| meta.query_advice(is_der, rotation));
+            let [is_tlv_tag, is_tlv_tag_next] = [Rotation::cur(), Rotation::next()]
+                .map(|rotation| meta.query_advice(is_tlv_tag, rotation));
+            let [is_tlv_len, is_tlv_len_next] = [Rotation::cur(), Rotation::next()]
+                .map(|rotation| meta.query_advice(is_tlv_len, rotation));
+            let [is_tlv_len_long, is_tlv_len_long_next] = [Rotation::cur(), Rotation::next()]
+                .map(|rotation| meta.query_advice(is_tlv_len_long, rotation));
+            let [len_left, len_left_next] = [Rotation::cur(), Rotation::next()]
+                .map(|rotation| meta.query_advice(len_left, rotation));
+            let [tlv_remaining, tlv_remaining_next] = [Rotation::cur(), Rotation::next()]
+                .map(|rotation| meta.query_advice(tlv_remaining, rotation));
+            let byte_next = meta.query_advice(byte, Rotation::next());
+            let is_field_end =
+                meta.query_advice(tag, Rotation::next()) - meta.query_advice(tag, Rotation::cur());
+            let is_content_next = not::expr(
+                is_tlv_tag_next.clone() + is_tlv_len_next.clone() + is_tlv_len_long_next.clone(),
+            );
+
+            cb.condition(is_field_end.clone() * is_der_next, |cb| {
+                cb.require_equal(
+                    "a DER field starts with a tag",
+                    is_tlv_tag_next.clone(),
+                    1.expr(),
+                );
+            });
+            cb.condition(is_field_end.clone() * is_der.clone(), |cb| {
+                cb.require_zero("a DER field doesn't end at a tag", is_tlv_tag.clone());
+                cb.require_zero("a DER field doesn't end in a length", len_left.clone());
+                cb.require_zero(
+                    "a DER field ends at the end of a TLV",
+                    tlv_remaining.clone(),
+                );
+            });
+            cb.condition(and::expr([not::expr(is_field_end), is_der]), |cb| {
+                cb.require_equal("a length follows a tag", is_tlv_len_next, is_tlv_tag);
+                cb.condition(is_tlv_len_long_next.clone(), |cb| {
+                    cb.require_equal(
+                        "long form length bytes follow the length",
+                        is_tlv_len.clone() + is_tlv_len_long.clone(),
+                        1.expr(),
+                    );
+                    cb.require_equal(
+                        "len_left decreases",
+                        len_left_next,
+                        len_left.clone() - 1.expr(),
+                    );
+                    cb.require_equal(
+                        "the long form length accumulates the byte",
+                        tlv_remaining_next.clone(),
+                        tlv_remaining.clone() * 256.expr() + byte_next,
+                    );
+                });
+                cb.condition(
+                    (is_tlv_len + is_tlv_len_long) * not::expr(is_tlv_len_long_next),
+                    |cb| {
+                        cb.require_zero("all the length bytes are read", len_left);
+                    },
+                );
+                cb.condition(is_content_next, |cb| {
+                    cb.require_equal(
+                        "tlv_remaining decreases",
+                        tlv_remaining_next,
+                        tlv_remaining.clone() - 1.expr(),
+                    );
+                });
+                cb.condition(is_tlv_tag_next, |cb| {
+                    cb.require_zero("a TLV ends before the next tag", tlv_remaining);
+                });
+            });
+
+            cb.gate(meta.query_selector(q_step))
+        });
+
+        meta.create_gate("x509 last row", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_equal(
+                "all the fields end before the last row",
+                meta.query_advice(tag, Rotation::cur()),
+                (X509Tag::Padding as u64).expr(),
+            );
+
+            cb.gate(meta.query_selector(q_last))
+        });
+
+        // Look up the value, RLC, tbsCertificate RLC and offset of an exported field at its last
+        // row.
+        meta.lookup_any("x509 field end", |meta| {
+            let q_field = meta.query_selector(q_field);
+            let is_field_end = meta.query_selector(q_step)
+                * (meta.query_advice(tag, Rotation::next())
+                    - meta.query_advice(tag, Rotation::cur()));
+            let cert_id = meta.query_fixed(cert_id, Rotation::cur());
+            let [value, rlc, tbs_rlc] =
+                [value, rlc, tbs_rlc].map(|column| meta.query_advice(column, Rotation::cur()));
+            let input = [
+                cert_id.clone(),
+                meta.query_fixed(field_tag, Rotation::cur()),
+                value.clone(),
+                rlc.clone(),
+                tbs_rlc.clone(),
+                meta.query_advice(end_offset, Rotation::cur()),
+            ];
+            let table = [
+                cert_id,
+                meta.query_advice(tag, Rotation::cur()),
+                value,
+                rlc,
+                tbs_rlc,
+                meta.query_fixed(offset, Rotation::cur()),
+            ];
+            input
+                .into_iter()
+                .zip(table)
+                .map(|(input, table)| (q_field.clone() * input, is_field_end.clone() * table))
+                .collect()
+        });
+
+        let main_gate_config = MainGate::<F>::configure(meta);
+        let range_config = RangeChip::<F>::configure(meta, &main_gate_config, vec![8], vec![]);
+
+        let q_rlc_input = meta.selector();
+        let input_rlc = meta.advice_column_in(SecondPhase);
+        meta.enable_equality(input_rlc);
+        SignVerifyLaneConfig::configure_rlc(
+            meta,
+            "x509_input_rlc",
+            main_gate_config.clone(),
+            q_rlc_input,
+            input_rlc,
+            challenge,
+        );
+
+        // Verify that sha256(tbsCertificate) = digest by sha256 table lookup,
+        // or sha1(tbsCertificate) = digest by sha1 table lookup for a
+        // certificate signed with sha1WithRSAEncryption.
+        // Layout:
+        // | q_tbs_digest |   a   |     b     |     c     |     d     |    e    | input_rlc |
+        // | ------------ | ----- | --------- | --------- | --------- | ------- | --------- |
+        // |       1      |  len  | digest_lo | digest_hi | is_sha256 | is_sha1 |  tbs_rlc  |
+        let q_tbs_digest = meta.complex_selector();
+        let digest_lookup = |meta: &mut VirtualCells<F>,
+                             is_hash: usize,
+                             table: Vec<Expression<F>>|
+         -> Vec<(Expression<F>, Expression<F>)> {
+            let q_tbs_digest = meta.query_selector(q_tbs_digest);
+            let [len, digest_lo, digest_hi, is_hash] = [0, 1, 2, is_hash]
+                .map(|i| meta.query_advice(main_gate_config.advices()[i], Rotation::cur()));
+            let input = [
+                1.expr(),
+                meta.query_advice(input_rlc, Rotation::cur()),
+                len,
+                digest_lo,
+                digest_hi,
+            ];
+            input
+                .into_iter()
+                .zip(table)
+                .map(|(input, table)| (q_tbs_digest.clone() * is_hash.clone() * input, table))
+                .collect()
+        };
+        meta.lookup_any("x509 tbsCertificate sha256", |meta| {
+            let table = sha256_table.table_exprs(meta);
+            digest_lookup(meta, 3, table)
+        });
+        #[cfg(feature = "sha1")]
+        meta.lookup_any("x509 tbsCertificate sha1", |meta| {
+            let table = sha1_table.table_exprs(meta);
+            digest_lookup(meta, 4, table)
+        });
+
+        Self {
+            q_row,
+            q_step,
+            q_first,
+            q_last,
+            cert_id,
+            offset,
+            byte,
+            tag,
+            field_offset,
+            is_any,
+            is_constant,
+            is_last,
+            is_tbs,
+            is_der,
+            is_tlv_tag,
+            is_tlv_len,
+            is_tlv_len_long,
+            is_long_form,
+            len_left,
+            tlv_remaining,
+            value,
+            rlc,
+            tbs_rlc,
+            q_field,
+            field_tag,
+            end_offset,
+            content_table,
+            main_gate_config,
+            range_config,
+            q_rlc_input,
+            input_rlc,
+            q_tbs_digest,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> X509CircuitConfig<F> {
+    /// Load the fixed table of the field contents and the range table.
+    pub(crate) fn load_aux_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_region(
+            || "x509 field content table",
+            |mut region| {
+                let mut assign_row = |offset, row: [u64; 8]| {
+                    for (column, value) in self.content_table.iter().zip(row) {
+                        region.assign_fixed(
+                            || format!("x509 field content table row {}", offset),
+                            *column,
+                            offset,
+                            || Value::known(F::from(value)),
+                        )?;
+                    }
+                    Ok::<_, Error>(())
+                };
+
+                let mut offset = 0;
+                assign_row(offset, [0; 8])?;
+                offset += 1;
+                for tag in X509Tag::iter() {
+                    let (tag_value, is_tbs, is_der) =
+                        (tag as u64, tag.is_tbs() as u64, tag.is_der() as u64);
+                    let rows = match tag.content() {
+                        X509FieldContent::Constant(bytes) => bytes
+                            .iter()
+                            .enumerate()
+                            .map(|(i, byte)| {
+                                let is_last = (i + 1 == bytes.len()) as u64;
+                                let byte = *byte as u64;
+                                [tag_value, i as u64, 0, 1, byte, is_last, is_tbs, is_der]
+                            })
+                            .collect_vec(),
+                        X509FieldContent::Variable(len) => (0..len)
+                            .map(|i| {
+                                let is_last = (i + 1 == len) as u64;
+                                [tag_value, i as u64, 0, 0, 0, is_last, is_tbs, is_der]
+                            })
+                            .collect_vec(),
+                        X509FieldContent::Any => [0, 1]
+                            .map(|is_last| [tag_value, 0, 1, 0, 0, is_last, is_tbs, is_der])
+                            .to_vec(),
+                    };
+                    for row in rows {
+                        assign_row(offset, row)?;
+                        offset += 1;
+                    }
+                }
+                Ok(())
+            },
+        )?;
+
+        let range_chip = RangeChip::<F>::new(self.range_config.clone());
+        range_chip.load_table(layouter)
+    }
+}
+
+/// Cells of the exported fields of a certificate
+struct AssignedX509Fields<F: Field> {
+    cert_len: AssignedCell<F, F>,
+    tbs_len: AssignedCell<F, F>,
+    not_before: AssignedCell<F, F>,
+    not_after: AssignedCell<F, F>,
+    modulus_rlc: AssignedCell<F, F>,
+    tbs_rlc: AssignedCell<F, F>,
+    tbs_end_offset: AssignedCell<F, F>,
+    signature_algorithm: AssignedCell<F, F>,
+    signature_rlc: AssignedCell<F, F>,
+    signature_end_offset: AssignedCell<F, F>,
+}
+
+/// Witness of an exported field at its last row: (value, rlc, tbs_rlc, offset)
+type FieldEnd<F> = (F, Value<F>, Value<F>, usize);
+
+/// Witness of the DER TLVs at a byte of a certificate
+#[derive(Clone, Copy, Debug, Default)]
+struct TlvRow {
+    is_tag: bool,
+    is_len: bool,
+    is_len_long: bool,
+    is_long_form: bool,
+    len_left: u64,
+    remaining: u64,
+}
+
+/// Return the DER TLV witness of every byte of a certificate, walking the TLVs of the fields of
+/// any content.
+fn tlv_rows(cert: &X509Certificate) -> Vec<TlvRow> {
+    let mut rows = Vec::with_capacity(cert.der.len());
+    for tag in X509Tag::iter() {
+        let mut prev: Option<TlvRow> = None;
+        for byte in cert.field(tag).iter().map(|byte| *byte as u64) {
+            let row = match prev {
+                _ if !tag.is_der() => TlvRow::default(),
+                Some(TlvRow { is_tag: true, .. }) => {
+                    let is_long_form = byte >= 0x80;
+                    TlvRow {
+                        is_len: true,
+                        is_long_form,
+                        len_left: if is_long_form { byte - 0x80 } else { 0 },
+                        remaining: if is_long_form { 0 } else { byte },
+                        ..Default::default()
+                    }
+                }
+                Some(prev) if prev.len_left > 0 => TlvRow {
+                    is_len_long: true,
+                    len_left: prev.len_left - 1,
+                    remaining: prev.remaining * 256 + byte,
+                    ..Default::default()
+                },
+                Some(prev) if prev.remaining > 0 => TlvRow {
+                    remaining: prev.remaining - 1,
+                    ..Default::default()
+                },
+                _ => TlvRow {
+                    is_tag: true,
+                    ..Default::default()
+                },
+            };
+            rows.push(row);
+            prev = Some(row);
+        }
+    }
+    rows
+}
+
+/// Circuit to verify a chain of X.509 certificates issued by a certification authority
+#[derive(Clone, Debug, Default)]
+pub struct X509Circuit<F: Field> {
+    /// Certificates of the chain, from the one signed by the CA to the leaf one
+    pub certificates: Vec<X509Certificate>,
+    /// Public key of the certification authority
+    pub ca_pk: RsaPublicKey,
+    /// Unix timestamp at which the certificates must be valid
+    pub timestamp: u64,
+    /// Number of certificates of the chain
+    pub chain_len: usize,
+    /// Max number of bytes of a certificate.  A certificate must be strictly shorter, so that
+    /// its last row is padding.
+    pub max_cert_bytes: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> X509Circuit<F> {
+    /// Return a new X509Circuit
+    pub fn new(
+        certificates: Vec<X509Certificate>,
+        ca_pk: RsaPublicKey,
+        timestamp: u64,
+        max_cert_bytes: usize,
+    ) -> Self {
+        assert!(!certificates.is_empty(), "the chain has no certificate");
+        Self {
+            chain_len: certificates.len(),
+            certificates,
+            ca_pk,
+            timestamp,
+            max_cert_bytes,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the number of unusable rows of the circuit
+    pub fn unusable_rows() -> usize {
+        // No column queried at more than 3 distinct rotations, so returns 6 as
+        // minimum unusable rows.
+        6
+    }
+
+    /// Return the minimum number of rows required to prove a chain of `chain_len` certificates of
+    /// less than `max_cert_bytes` bytes.
+    pub fn min_num_rows(chain_len: usize, max_cert_bytes: usize) -> usize {
+        [
+            chain_len * (max_cert_bytes + EXPORTED_FIELDS.len()),
+            chain_len * Self::rows_per_verif(),
+            // Range table of the RangeChip and u8 table
+            1 << 8,
+        ]
+        .into_iter()
+        .max()
+        .unwrap()
+    }
+
+    /// Return the number of MainGate rows used by the verification of one
+    /// certificate.  It's an upper bound of the RSA signature verification
+    /// (see `RsaSignVerifyChip::rows_per_verif`) and of the RLCs of the
+    /// modulus and of the signature.
+    pub fn rows_per_verif() -> usize {
+        47000
+    }
+
+    /// Return the public inputs: the UTCTime value of the timestamp, the modulus of the CA public
+    /// key and the modulus of the leaf public key, as 16 words of 128 bits in little endian.
+    pub fn instance(&self) -> Vec<Vec<F>> {
+        let words = |n: &BigUint| {
+            let mut n_le = n.to_bytes_le();
+            n_le.resize(RSA_2048_BYTES, 0);
+            n_le.chunks(16)
+                .map(|word| F::from_u128(u128::from_le_bytes(word.try_into().unwrap())))
+                .collect_vec()
+        };
+        let leaf_pk = self
+            .certificates
+            .last()
+            .map(|cert| cert.public_key())
+            .unwrap_or_default();
+        vec![iter_instance(
+            F::from_u128(utc_time_value(self.timestamp)),
+            words(&self.ca_pk.n),
+            words(&leaf_pk.n),
+        )]
+    }
+
+    /// Return the sha256 inputs of the circuit: the tbsCertificates signed
+    /// with sha256WithRSAEncryption.
+    pub fn sha256_inputs(&self) -> Vec<Vec<u8>> {
+        self.certificates
+            .iter()
+            .filter(|cert| !cert.signature_algorithm().is_sha1())
+            .map(|cert| cert.tbs().to_vec())
+            .collect()
+    }
+
+    /// Return the sha1 inputs of the circuit: the tbsCertificates signed with
+    /// sha1WithRSAEncryption.
+    #[cfg(feature = "sha1")]
+    pub fn sha1_inputs(&self) -> Vec<Vec<u8>> {
+        self.certificates
+            .iter()
+            .filter(|cert| cert.signature_algorithm().is_sha1())
+            .map(|cert| cert.tbs().to_vec())
+            .collect()
+    }
+
+    /// Assign the rows of a certificate from the row `start`, and return the
+    /// witness of its exported fields.
+    fn assign_certificate(
+        &self,
+        config: &X509CircuitConfig<F>,
+        region: &mut Region<'_, F>,
+        start: usize,
+        cert_id: usize,
+        cert: Option<&X509Certificate>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<HashMap<X509Tag, FieldEnd<F>>, Error> {
+        let (der, tags, tlvs) = cert
+            .map(|cert| (cert.der.as_slice(), cert.tags(), tlv_rows(cert)))
+            .unwrap_or_default();
+        if der.len() >= self.max_cert_bytes {
+            error!(
+                "certificate of {} bytes >= max_cert_bytes = {}",
+                der.len(),
+                self.max_cert_bytes
+            );
+            return Err(Error::Synthesis);
+        }
+
+        let challenge = challenges.keccak_input();
+        let mut field_ends = HashMap::new();
+        let (mut value, mut rlc, mut tbs_rlc) =
+            (F::ZERO, Value::known(F::ZERO), Value::known(F::ZERO));
+        let mut field_start = 0;
+        for offset in 0..self.max_cert_bytes {
+            let byte = der.get(offset).copied().unwrap_or_default();
+            let tag = tags.get(offset).copied().unwrap_or(X509Tag::Padding);
+            let next_tag = tags.get(offset + 1).copied().unwrap_or(X509Tag::Padding);
+            let is_last = offset + 1 == self.max_cert_bytes || next_tag != tag;
+            if offset > 0 && tags.get(offset - 1).copied().unwrap_or(X509Tag::Padding) != tag {
+                field_start = offset;
+                value = F::ZERO;
+                rlc = Value::known(F::ZERO);
+            }
+            let byte_value = F::from(byte as u64);
+            value = value * F::from(256) + byte_value;
+            rlc = rlc * challenge + Value::known(byte_value);
+            if tag.is_tbs() {
+                tbs_rlc = tbs_rlc * challenge + Value::known(byte_value);
+            }
+            let (is_any, is_constant) = match tag.content() {
+                X509FieldContent::Constant(_) => (false, true),
+                X509FieldContent::Variable(_) => (false, false),
+                X509FieldContent::Any => (true, false),
+            };
+            let field_offset = if is_any { 0 } else { offset - field_start };
+            let tlv = tlvs.get(offset).copied().unwrap_or_default();
+            if is_last {
+                field_ends.insert(tag, (value, rlc, tbs_rlc, offset));
+            }
+
+            let row = start + offset;
+            config.q_row.enable(region, row)?;
+            if offset == 0 {
+                config.q_first.enable(region, row)?;
+            }
+            if offset + 1 == self.max_cert_bytes {
+                config.q_last.enable(region, row)?;
+            } else {
+                config.q_step.enable(region, row)?;
+            }
+            for (name, column, value) in [
+                ("cert_id", config.cert_id, F::from(cert_id as u64)),
+                ("offset", config.offset, F::from(offset as u64)),
+            ] {
+                region.assign_fixed(|| name, column, row, || Value::known(value))?;
+            }
+            for (name, column, value) in [
+                ("byte", config.byte, byte_value),
+                ("tag", config.tag, F::from(tag as u64)),
+                (
+                    "field_offset",
+                    config.field_offset,
+                    F::from(field_offset as u64),
+                ),
+                ("is_any", config.is_any, F::from(is_any as u64)),
+                (
+                    "is_constant",
+                    config.is_constant,
+                    F::from(is_constant as u64),
+                ),
+                ("is_last", config.is_last, F::from(is_last as u64)),
+                ("is_tbs", config.is_tbs, F::from(tag.is_tbs() as u64)),
+                ("is_der", config.is_der, F::from(tag.is_der() as u64)),
+                ("is_tlv_tag", config.is_tlv_tag, F::from(tlv.is_tag as u64)),
+                ("is_tlv_len", config.is_tlv_len, F::from(tlv.is_len as u64)),
+                (
+                    "is_tlv_len_long",
+                    config.is_tlv_len_long,
+                    F::from(tlv.is_len_long as u64),
+                ),
+                (
+                    "is_long_form",
+                    config.is_long_form,
+                    F::from(tlv.is_long_form as u64),
+                ),
+                ("len_left", config.len_left, F::from(tlv.len_left)),
+                (
+                    "tlv_remaining",
+                    config.tlv_remaining,
+                    F::from(tlv.remaining),
+                ),
+                ("value", config.value, value),
+            ] {
+                region.assign_advice(|| name, column, row, || Value::known(value))?;
+            }
+            region.assign_advice(|| "rlc", config.rlc, row, || rlc)?;
+            region.assign_advice(|| "tbs_rlc", config.tbs_rlc, row, || tbs_rlc)?;
+        }
+        Ok(field_ends)
+    }
+
+    /// Assign the rows of the exported fields of a certificate from the row
+    /// `start`.
+    fn assign_fields(
+        &self,
+        config: &X509CircuitConfig<F>,
+        region: &mut Region<'_, F>,
+        start: usize,
+        cert_id: usize,
+        field_ends: &HashMap<X509Tag, FieldEnd<F>>,
+    ) -> Result<AssignedX509Fields<F>, Error> {
+        let mut cells = Vec::with_capacity(EXPORTED_FIELDS.len());
+        for (i, field_tag) in EXPORTED_FIELDS.iter().enumerate() {
+            let row = start + i;
+            let (value, rlc, tbs_rlc, end_offset) = field_ends.get(field_tag).copied().unwrap_or((
+                F::ZERO,
+                Value::known(F::ZERO),
+                Value::known(F::ZERO),
+                0,
+            ));
+            config.q_field.enable(region, row)?;
+            region.assign_fixed(
+                || "cert_id",
+                config.cert_id,
+                row,
+                || Value::known(F::from(cert_id as u64)),
+            )?;
+            region.assign_fixed(
+                || "field_tag",
+                config.field_tag,
+                row,
+                || Value::known(F::from(*field_tag as u64)),
+            )?;
+            let value =
+                region.assign_advice(|| "value", config.value, row, || Value::known(value))?;
+            let rlc = region.assign_advice(|| "rlc", config.rlc, row, || rlc)?;
+            let tbs_rlc = region.assign_advice(|| "tbs_rlc", config.tbs_rlc, row, || tbs_rlc)?;
+            let end_offset = region.assign_advice(
+                || "end_offset",
+                config.end_offset,
+                row,
+                || Value::known(F::from(end_offset as u64)),
+            )?;
+            cells.push((value, rlc, tbs_rlc, end_offset));
+        }
+
+        let [cert_len, tbs_len, not_before, not_after, modulus, extensions, algorithm, signature] =
+            <[_; 8]>::try_from(cells).unwrap();
+        Ok(AssignedX509Fields {
+            cert_len: cert_len.0,
+            tbs_len: tbs_len.0,
+            not_before: not_before.0,
+            not_after: not_after.0,
+            modulus_rlc: modulus.1,
+            tbs_rlc: extensions.2,
+            tbs_end_offset: extensions.3,
+            signature_algorithm: algorithm.0,
+            signature_rlc: signature.1,
+            signature_end_offset: signature.3,
+        })
+    }
+
+    /// Verify a certificate signed by `issuer`, valid at `now`, and return its
+    /// subject public key.
+    #[allow(clippy::too_many_arguments)]
+    fn assign_certificate_verify(
+        &self,
+        config: &X509CircuitConfig<F>,
+        ctx: &mut RegionCtx<F>,
+        main_gate: &MainGate<F>,
+        range_chip: &RangeChip<F>,
+        cert: Option<&X509Certificate>,
+        fields: &AssignedX509Fields<F>,
+        issuer: &AssignedRsaInteger<F>,
+        now: &AssignedValue<F>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<AssignedRsaInteger<F>, Error> {
+        let rsa_chip = RsaSignVerifyChip::<F>::default();
+
+        // The tbsCertificate ends before the signature algorithm, and the certificate after the
+        // signature: tbs_end_offset = 4 + (tbs_len + 4) - 1 and
+        // signature_end_offset = (cert_len + 4) - 1.
+        for (end_offset, len, constant) in [
+            (&fields.tbs_end_offset, &fields.tbs_len, -F::from(7)),
+            (&fields.signature_end_offset, &fields.cert_len, -F::from(3)),
+        ] {
+            let diff = main_gate.compose(
+                ctx,
+                &[
+                    Term::Assigned(end_offset, F::ONE),
+                    Term::Assigned(len, -F::ONE),
+                ],
+                constant,
+            )?;
+            main_gate.assert_zero(ctx, &diff)?;
+        }
+
+        // The last byte of the OID of the signature algorithm is 0x0b for
+        // sha256WithRSAEncryption and 0x05 for sha1WithRSAEncryption:
+        // oid_byte = 0x0b - 6 * is_sha1.
+        #[cfg(feature = "sha1")]
+        let is_sha1 = main_gate.assign_bit(
+            ctx,
+            Value::known(F::from(
+                cert.map_or(false, |cert| cert.signature_algorithm().is_sha1()) as u64,
+            )),
+        )?;
+        #[cfg(not(feature = "sha1"))]
+        let is_sha1 = main_gate.assign_constant(ctx, F::ZERO)?;
+        let is_sha256 = main_gate.not(ctx, &is_sha1)?;
+        let diff = main_gate.compose(
+            ctx,
+            &[
+                Term::Assigned(&fields.signature_algorithm, F::ONE),
+                Term::Assigned(&is_sha1, F::from(0x0b - 0x05)),
+            ],
+            -F::from(0x0b),
+        )?;
+        main_gate.assert_zero(ctx, &diff)?;
+
+        // Verify that hash(tbsCertificate) = digest
+        let mut digest_le = cert.map(|cert| cert.tbs_digest()).unwrap_or_default();
+        digest_le.reverse();
+        let (digest, _) = assign_bytes_le(ctx, range_chip, &digest_le)?;
+        let tbs_input_len =
+            main_gate.compose(ctx, &[Term::Assigned(&fields.tbs_len, F::ONE)], F::from(4))?;
+        ctx.enable(config.q_tbs_digest)?;
+        for (name, column, assigned) in [
+            (
+                "tbs_input_len",
+                config.main_gate_config.advices()[0],
+                &tbs_input_len,
+            ),
+            (
+                "digest_lo",
+                config.main_gate_config.advices()[1],
+                &digest.lo(),
+            ),
+            (
+                "digest_hi",
+                config.main_gate_config.advices()[2],
+                &digest.hi(),
+            ),
+            (
+                "is_sha256",
+                config.main_gate_config.advices()[3],
+                &is_sha256,
+            ),
+            ("is_sha1", config.main_gate_config.advices()[4], &is_sha1),
+            ("tbs_rlc", config.input_rlc, &fields.tbs_rlc),
+        ] {
+            let copied = ctx.assign_advice(|| name, column, assigned.value().copied())?;
+            ctx.constrain_equal(assigned.cell(), copied.cell())?;
+        }
+        ctx.next();
+
+        // The signature and the subject public key are the ones of the certificate.  Without
+        // witness, the modulus is 1 so that the modular arithmetic is defined.
+        let signature = rsa_chip.assign_integer(
+            ctx,
+            range_chip,
+            &cert.map(|cert| cert.signature()).unwrap_or_default(),
+        )?;
+        let modulus = rsa_chip.assign_integer(
+            ctx,
+            range_chip,
+            &cert
+                .map(|cert| cert.public_key().n)
+                .unwrap_or_else(|| BigUint::from(1u64)),
+        )?;
+        for (name, integer, rlc) in [
+            ("x509_signature", &signature, &fields.signature_rlc),
+            ("x509_modulus", &modulus, &fields.modulus_rlc),
+        ] {
+            let integer_rlc = assign_rlc_le(
+                ctx,
+                main_gate,
+                &config.main_gate_config,
+                config.input_rlc,
+                name,
+                config.q_rlc_input,
+                challenges.keccak_input(),
+                integer.bytes_le.iter().map(assigned_term),
+            )?;
+            ctx.constrain_equal(integer_rlc.cell(), rlc.cell())?;
+        }
+
+        // Verify that the signature is the one of the digest by the issuer
+        let em =
+            rsa_chip.pow_mod_public_exponent(ctx, main_gate, range_chip, &signature, issuer)?;
+        rsa_chip.assert_pkcs1_v15_encoding(ctx, main_gate, &em, &digest, &is_sha256)?;
+        #[cfg(feature = "sha1")]
+        rsa_chip.assert_pkcs1_v15_sha1_encoding(ctx, main_gate, &em, &digest, &is_sha1)?;
+
+        // Verify that notBefore <= now <= notAfter
+        assert_le(ctx, main_gate, range_chip, &fields.not_before, now)?;
+        assert_le(ctx, main_gate, range_chip, now, &fields.not_after)?;
+
+        Ok(modulus)
+    }
+
+    /// Compose the limbs of an RSA integer into words of 128 bits.
+    fn assign_words(
+        ctx: &mut RegionCtx<F>,
+        main_gate: &MainGate<F>,
+        integer: &AssignedRsaInteger<F>,
+    ) -> Result<Vec<AssignedValue<F>>, Error> {
+        let pow_2_64 = F::from_u128(1 << 64);
+        integer
+            .limbs
+            .chunks(2)
+            .map(|limbs| {
+                main_gate.compose(
+                    ctx,
+                    &[
+                        Term::Assigned(&limbs[0], F::ONE),
+                        Term::Assigned(&limbs[1], pow_2_64),
+                    ],
+                    F::ZERO,
+                )
+            })
+            .collect()
+    }
+
+    /// Make the assignments to the X509Circuit
+    pub fn synthesize_sub(
+        &self,
+        config: &X509CircuitConfig<F>,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        if self.certificates.len() > self.chain_len {
+            error!(
+                "certificates.len() = {} > chain_len = {}",
+                self.certificates.len(),
+                self.chain_len
+            );
+            return Err(Error::Synthesis);
+        }
+        config.load_aux_tables(layouter)?;
+
+        let assigned_fields = layouter.assign_region(
+            || "x509 certificates",
+            |mut region| {
+                let field_ends = (0..self.chain_len)
+                    .map(|i| {
+                        self.assign_certificate(
+                            config,
+                            &mut region,
+                            i * self.max_cert_bytes,
+                            i,
+                            self.certificates.get(i),
+                            challenges,
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let fields_start = self.chain_len * self.max_cert_bytes;
+                field_ends
+                    .iter()
+                    .enumerate()
+                    .map(|(i, field_ends)| {
+                        self.assign_fields(
+                            config,
+                            &mut region,
+                            fields_start + i * EXPORTED_FIELDS.len(),
+                            i,
+                            field_ends,
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            },
+        )?;
+
+        let main_gate = MainGate::new(config.main_gate_config.clone());
+        let range_chip = RangeChip::new(config.range_config.clone());
+        let public_inputs = layouter.assign_region(
+            || "x509 chain verification",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+                let now = main_gate.assign_value(
+                    &mut ctx,
+                    Value::known(F::from_u128(utc_time_value(self.timestamp))),
+                )?;
+                let mut issuer = RsaSignVerifyChip::<F>::default().assign_integer(
+                    &mut ctx,
+                    &range_chip,
+                    &self.ca_pk.n.clone().max(BigUint::from(1u64)),
+                )?;
+                let ca_words = Self::assign_words(&mut ctx, &main_gate, &issuer)?;
+                for (i, fields) in assigned_fields.iter().enumerate() {
+                    issuer = self.assign_certificate_verify(
+                        config,
+                        &mut ctx,
+                        &main_gate,
+                        &range_chip,
+                        self.certificates.get(i),
+                        fields,
+                        &issuer,
+                        &now,
+                        challenges,
+                    )?;
+                }
+                let leaf_words = Self::assign_words(&mut ctx, &main_gate, &issuer)?;
+                log::debug!("x509 chain verification: {} rows", ctx.offset());
+                Ok(iter_instance(now, ca_words, leaf_words))
+            },
+        )?;
+
+        for (row, cell) in public_inputs.into_iter().enumerate() {
+            main_gate.expose_public(layouter.namespace(|| "x509 public input"), cell, row)?;
+        }
+        Ok(())
+    }
+}
+
+/// Return the public inputs in order: the time, the words of the CA modulus
+/// and the words of the leaf modulus.
+fn iter_instance<T>(now: T, ca_words: Vec<T>, leaf_words: Vec<T>) -> Vec<T> {
+    std::iter::once(now)
+        .chain(ca_words)
+        .chain(leaf_words)
+        .collect()
+}
+
+/// Constrain `lhs <= rhs` for values of at most 96 bits, by range checking
+/// `rhs - lhs`.
+fn assert_le<F: Field>(
+    ctx: &mut RegionCtx<F>,
+    main_gate: &MainGate<F>,
+    range_chip: &RangeChip<F>,
+    lhs: &AssignedValue<F>,
+    rhs: &AssignedValue<F>,
+) -> Result<(), Error> {
+    let diff_value = rhs.value().zip(lhs.value()).map(|(rhs, lhs)| *rhs - *lhs);
+    let (diff, _) = range_chip.decompose(ctx, diff_value, 8, 8 * UTC_TIME_BYTES)?;
+    let check = main_gate.compose(
+        ctx,
+        &[
+            Term::Assigned(rhs, F::ONE),
+            Term::Assigned(lhs, -F::ONE),
+            Term::Assigned(&diff, -F::ONE),
+        ],
+        F::ZERO,
+    )?;
+    main_gate.assert_zero(ctx, &check)
+}
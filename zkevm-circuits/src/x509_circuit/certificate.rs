@@ -0,0 +1,342 @@
+//! DER encoded X.509 certificates, split in the fields verified by the X509Circuit.
+
+#[cfg(feature = "sha1")]
+use eth_types::rsa_types::sha1;
+use eth_types::rsa_types::{sha256, RsaPublicKey, RSA_2048_BYTES};
+use num_bigint::BigUint;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+/// Field of a DER encoded X.509 v3 certificate with an RSA-2048 public key, signed with
+/// sha256WithRSAEncryption (or sha1WithRSAEncryption with the `sha1` feature), in the order of
+/// the encoding.  Every byte of a certificate is tagged
+/// with the field it belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter)]
+pub enum X509Tag {
+    /// SEQUENCE header of the certificate
+    CertHeader = 1,
+    /// Length of the certificate content
+    CertLen,
+    /// SEQUENCE header of the tbsCertificate
+    TbsHeader,
+    /// Length of the tbsCertificate content
+    TbsLen,
+    /// Version, serial number, signature algorithm and issuer
+    Issuer,
+    /// SEQUENCE header of the validity and UTCTime header of notBefore
+    ValidityHeader,
+    /// notBefore as YYMMDDHHMMSS
+    NotBefore,
+    /// End of notBefore and UTCTime header of notAfter
+    NotAfterHeader,
+    /// notAfter as YYMMDDHHMMSS
+    NotAfter,
+    /// End of notAfter
+    ValidityEnd,
+    /// Subject
+    Subject,
+    /// SubjectPublicKeyInfo of an RSA key up to the modulus
+    SpkiHeader,
+    /// Modulus of the subject public key
+    Modulus,
+    /// Public exponent 65537 of the subject public key
+    PublicExponent,
+    /// Extensions, which end the tbsCertificate
+    Extensions,
+    /// Signature algorithm identifier up to the last byte of its OID
+    SignatureHeader,
+    /// Last byte of the OID of the signature algorithm (see [`X509SignatureAlgorithm`])
+    SignatureAlgorithm,
+    /// NULL parameters of the signature algorithm and BIT STRING header of the signature
+    SignatureBitString,
+    /// Signature
+    Signature,
+    /// Rows after the end of the certificate
+    Padding,
+}
+
+/// Content of a certificate field
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum X509FieldContent {
+    /// Constant bytes
+    Constant(&'static [u8]),
+    /// Variable bytes of a fixed length
+    Variable(usize),
+    /// Any number of any bytes
+    Any,
+}
+
+impl X509Tag {
+    /// Return the content of the field.
+    pub fn content(&self) -> X509FieldContent {
+        use X509FieldContent::*;
+        match self {
+            Self::CertHeader | Self::TbsHeader => Constant(&[0x30, 0x82]),
+            Self::CertLen | Self::TbsLen => Variable(2),
+            Self::ValidityHeader => Constant(&[0x30, 0x1e, 0x17, 0x0d]),
+            Self::NotBefore | Self::NotAfter => Variable(UTC_TIME_BYTES),
+            Self::NotAfterHeader => Constant(&[b'Z', 0x17, 0x0d]),
+            Self::ValidityEnd => Constant(&[b'Z']),
+            Self::SpkiHeader => Constant(&[
+                0x30, 0x82, 0x01, 0x22, 0x30, 0x0d, 0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d,
+                0x01, 0x01, 0x01, 0x05, 0x00, 0x03, 0x82, 0x01, 0x0f, 0x00, 0x30, 0x82, 0x01, 0x0a,
+                0x02, 0x82, 0x01, 0x01, 0x00,
+            ]),
+            Self::Modulus | Self::Signature => Variable(RSA_2048_BYTES),
+            Self::PublicExponent => Constant(&[0x02, 0x03, 0x01, 0x00, 0x01]),
+            Self::SignatureHeader => Constant(&[
+                0x30, 0x0d, 0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01,
+            ]),
+            Self::SignatureAlgorithm => Variable(1),
+            Self::SignatureBitString => Constant(&[0x05, 0x00, 0x03, 0x82, 0x01, 0x01, 0x00]),
+            Self::Issuer | Self::Subject | Self::Extensions | Self::Padding => Any,
+        }
+    }
+
+    /// Return true if the field is a sequence of complete DER TLVs, whose end is bound by their
+    /// lengths.
+    pub fn is_der(&self) -> bool {
+        matches!(self, Self::Issuer | Self::Subject | Self::Extensions)
+    }
+
+    /// Return true if the field is part of the tbsCertificate.
+    pub fn is_tbs(&self) -> bool {
+        (Self::TbsHeader as u64..=Self::Extensions as u64).contains(&(*self as u64))
+    }
+}
+
+/// Number of bytes of a UTCTime without its final 'Z': YYMMDDHHMMSS
+pub const UTC_TIME_BYTES: usize = 12;
+
+/// Signature algorithm of a certificate, identified by the last byte of its PKCS#1 OID
+/// 1.2.840.113549.1.1.x
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum X509SignatureAlgorithm {
+    /// sha256WithRSAEncryption
+    #[default]
+    Sha256WithRsa,
+    /// sha1WithRSAEncryption, used by legacy intermediate certificates
+    #[cfg(feature = "sha1")]
+    Sha1WithRsa,
+}
+
+impl X509SignatureAlgorithm {
+    /// Return the algorithm of the last byte of its OID, if supported.
+    pub fn from_oid_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0x0b => Some(Self::Sha256WithRsa),
+            #[cfg(feature = "sha1")]
+            0x05 => Some(Self::Sha1WithRsa),
+            _ => None,
+        }
+    }
+
+    /// Return the last byte of the OID of the algorithm.
+    pub fn oid_byte(&self) -> u8 {
+        match self {
+            Self::Sha256WithRsa => 0x0b,
+            #[cfg(feature = "sha1")]
+            Self::Sha1WithRsa => 0x05,
+        }
+    }
+
+    /// Return true if the signed digest is a SHA-1 one.
+    pub fn is_sha1(&self) -> bool {
+        !matches!(self, Self::Sha256WithRsa)
+    }
+}
+
+/// Error of the parsing of a certificate
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum X509Error {
+    /// The certificate doesn't follow the supported layout
+    #[error("unsupported certificate layout at field {0:?}")]
+    Layout(X509Tag),
+    /// The certificate is signed with an unsupported algorithm
+    #[error("unsupported signature algorithm")]
+    SignatureAlgorithm,
+    /// The declared lengths don't match the certificate
+    #[error("invalid certificate length")]
+    Length,
+}
+
+/// DER encoded X.509 certificate, with the tag of every byte
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct X509Certificate {
+    /// DER encoding
+    pub der: Vec<u8>,
+    /// Start offset of every field, indexed by `X509Tag as usize - 1`
+    offsets: Vec<usize>,
+}
+
+impl X509Certificate {
+    /// Split a DER encoded certificate in its fields.  Fields of any content are sequences of DER
+    /// TLVs, which end at the end of the first TLV followed by the constant content of the next
+    /// field.
+    pub fn from_der(der: Vec<u8>) -> Result<Self, X509Error> {
+        let tags = X509Tag::iter().collect::<Vec<_>>();
+        let mut offsets = Vec::with_capacity(tags.len());
+        let mut offset = 0;
+        for (i, tag) in tags.iter().enumerate() {
+            offsets.push(offset);
+            match tag.content() {
+                X509FieldContent::Constant(bytes) => {
+                    if der.get(offset..offset + bytes.len()) != Some(bytes) {
+                        return Err(X509Error::Layout(*tag));
+                    }
+                    offset += bytes.len();
+                }
+                X509FieldContent::Variable(len) => {
+                    if offset + len > der.len() {
+                        return Err(X509Error::Layout(*tag));
+                    }
+                    offset += len;
+                }
+                X509FieldContent::Any if *tag == X509Tag::Padding => {}
+                X509FieldContent::Any => {
+                    let X509FieldContent::Constant(next) = tags[i + 1].content() else {
+                        unreachable!("fields of any content are followed by constant ones");
+                    };
+                    let mut end = offset;
+                    loop {
+                        end += tlv_len(&der[end..]).ok_or(X509Error::Layout(*tag))?;
+                        if der.get(end..end + next.len()) == Some(next) {
+                            break;
+                        }
+                    }
+                    offset = end;
+                }
+            }
+        }
+
+        let cert = Self { der, offsets };
+        if cert.der.len() != cert.field_value(X509Tag::CertLen) as usize + 4
+            || cert.offsets[X509Tag::SignatureHeader as usize - 1]
+                != cert.field_value(X509Tag::TbsLen) as usize + 8
+        {
+            return Err(X509Error::Length);
+        }
+        if X509SignatureAlgorithm::from_oid_byte(cert.field(X509Tag::SignatureAlgorithm)[0])
+            .is_none()
+        {
+            return Err(X509Error::SignatureAlgorithm);
+        }
+        Ok(cert)
+    }
+
+    /// Return the offset of the first byte of a field.
+    pub fn field_offset(&self, tag: X509Tag) -> usize {
+        self.offsets[tag as usize - 1]
+    }
+
+    /// Return the bytes of a field.
+    pub fn field(&self, tag: X509Tag) -> &[u8] {
+        let index = tag as usize - 1;
+        let end = self
+            .offsets
+            .get(index + 1)
+            .copied()
+            .unwrap_or(self.der.len());
+        &self.der[self.offsets[index]..end]
+    }
+
+    /// Return the big endian value of a field of at most 16 bytes.
+    pub fn field_value(&self, tag: X509Tag) -> u128 {
+        self.field(tag)
+            .iter()
+            .fold(0, |acc, byte| (acc << 8) + *byte as u128)
+    }
+
+    /// Return the tag of every byte of the certificate.
+    pub fn tags(&self) -> Vec<X509Tag> {
+        X509Tag::iter()
+            .flat_map(|tag| std::iter::repeat(tag).take(self.field(tag).len()))
+            .collect()
+    }
+
+    /// Return the tbsCertificate, which is signed by the issuer.
+    pub fn tbs(&self) -> &[u8] {
+        &self.der[self.offsets[X509Tag::TbsHeader as usize - 1]
+            ..self.offsets[X509Tag::SignatureHeader as usize - 1]]
+    }
+
+    /// Return the algorithm of the signature of the issuer.
+    pub fn signature_algorithm(&self) -> X509SignatureAlgorithm {
+        X509SignatureAlgorithm::from_oid_byte(self.field(X509Tag::SignatureAlgorithm)[0])
+            .expect("checked when parsed")
+    }
+
+    /// Return the digest of the tbsCertificate signed by the issuer, as a
+    /// big endian integer of 32 bytes.
+    pub fn tbs_digest(&self) -> [u8; 32] {
+        match self.signature_algorithm() {
+            X509SignatureAlgorithm::Sha256WithRsa => sha256(self.tbs()),
+            #[cfg(feature = "sha1")]
+            X509SignatureAlgorithm::Sha1WithRsa => {
+                let mut digest = [0; 32];
+                digest[12..].copy_from_slice(&sha1(self.tbs()));
+                digest
+            }
+        }
+    }
+
+    /// Return the public key of the subject.
+    pub fn public_key(&self) -> RsaPublicKey {
+        RsaPublicKey {
+            n: BigUint::from_bytes_be(self.field(X509Tag::Modulus)),
+        }
+    }
+
+    /// Return the signature of the issuer.
+    pub fn signature(&self) -> BigUint {
+        BigUint::from_bytes_be(self.field(X509Tag::Signature))
+    }
+}
+
+/// Return the number of bytes of the DER TLV at the start of `der`, with a single byte tag and a
+/// length of at most 2 bytes, or None if it doesn't fit in `der`.
+fn tlv_len(der: &[u8]) -> Option<usize> {
+    let (header_len, len) = match *der.get(1)? {
+        len @ 0..=0x7f => (2, len as usize),
+        0x81 => (3, *der.get(2)? as usize),
+        0x82 => (
+            4,
+            u16::from_be_bytes(der.get(2..4)?.try_into().unwrap()) as usize,
+        ),
+        _ => return None,
+    };
+    Some(header_len + len).filter(|tlv_len| *tlv_len <= der.len())
+}
+
+/// Return the UTCTime value YYMMDDHHMMSS of a unix timestamp as a big endian integer of its
+/// ASCII digits, which orders the times between 2000 and 2049 as the timestamps.
+pub fn utc_time_value(timestamp: u64) -> u128 {
+    let days = timestamp / 86400;
+    let seconds = timestamp % 86400;
+    // Civil date from the days since 1970-01-01, by Howard Hinnant's algorithm
+    let z = days + 719468;
+    let era = z / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as u64;
+    assert!(
+        (2000..2050).contains(&year),
+        "only the years 2000 to 2049 are supported"
+    );
+
+    format!(
+        "{:02}{:02}{:02}{:02}{:02}{:02}",
+        year % 100,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+    .bytes()
+    .fold(0, |acc, byte| (acc << 8) + byte as u128)
+}
@@ -0,0 +1,80 @@
+pub use super::X509Circuit;
+
+#[cfg(feature = "sha1")]
+use crate::table::Sha1Table;
+use crate::{
+    table::{Sha256Table, UXTable},
+    util::{Challenges, SubCircuitConfig},
+    x509_circuit::{X509CircuitConfig, X509CircuitConfigArgs},
+};
+use eth_types::Field;
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+/// Tables of the hashes of the tbsCertificates, loaded without their circuits
+#[derive(Clone, Debug)]
+pub struct X509HashTables {
+    sha256_table: Sha256Table,
+    #[cfg(feature = "sha1")]
+    sha1_table: Sha1Table,
+}
+
+impl<F: Field> Circuit<F> for X509Circuit<F> {
+    type Config = (X509CircuitConfig<F>, Challenges, X509HashTables, UXTable<8>);
+    type FloorPlanner = SimpleFloorPlanner;
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            chain_len: self.chain_len,
+            max_cert_bytes: self.max_cert_bytes,
+            ..Self::default()
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let hash_tables = X509HashTables {
+            sha256_table: Sha256Table::construct(meta),
+            #[cfg(feature = "sha1")]
+            sha1_table: Sha1Table::construct(meta),
+        };
+        let u8_table = UXTable::construct(meta);
+        let challenges = Challenges::construct(meta);
+
+        let config = {
+            let challenges = challenges.exprs(meta);
+            X509CircuitConfig::new(
+                meta,
+                X509CircuitConfigArgs {
+                    sha256_table: hash_tables.sha256_table.clone(),
+                    #[cfg(feature = "sha1")]
+                    sha1_table: hash_tables.sha1_table.clone(),
+                    u8_table,
+                    challenges,
+                },
+            )
+        };
+
+        (config, challenges, hash_tables, u8_table)
+    }
+
+    fn synthesize(
+        &self,
+        (config, challenges, hash_tables, u8_table): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let challenges = challenges.values(&mut layouter);
+
+        hash_tables
+            .sha256_table
+            .dev_load(&mut layouter, &self.sha256_inputs(), &challenges)?;
+        #[cfg(feature = "sha1")]
+        hash_tables
+            .sha1_table
+            .dev_load(&mut layouter, &self.sha1_inputs(), &challenges)?;
+        u8_table.load(&mut layouter)?;
+        self.synthesize_sub(&config, &challenges, &mut layouter)
+    }
+}
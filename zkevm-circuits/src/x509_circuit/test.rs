@@ -0,0 +1,238 @@
+use super::*;
+use crate::util::{log2_ceil, unusable_rows};
+use eth_types::rsa_types::{emsa_pkcs1_v15_sha256, sha256, RSA_PUBLIC_EXPONENT};
+use halo2_proofs::{
+    dev::{MockProver, VerifyFailure},
+    halo2curves::bn256::Fr,
+};
+
+const MAX_CERT_BYTES: usize = 1024;
+// 2025-01-01T00:00:00Z
+const TIMESTAMP: u64 = 1735689600;
+
+#[test]
+fn x509_circuit_unusable_rows() {
+    assert_eq!(
+        X509Circuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, X509Circuit::<Fr>>(()),
+    )
+}
+
+fn run_with_instance<F: Field>(
+    circuit: X509Circuit<F>,
+    instance: Vec<Vec<F>>,
+) -> Result<(), Vec<VerifyFailure>> {
+    let k = log2_ceil(
+        X509Circuit::<Fr>::unusable_rows()
+            + X509Circuit::<Fr>::min_num_rows(circuit.chain_len, circuit.max_cert_bytes),
+    );
+    let prover = match MockProver::run(k, &circuit, instance) {
+        Ok(prover) => prover,
+        Err(e) => panic!("{:#?}", e),
+    };
+    prover.verify()
+}
+
+fn mock_certificate() -> X509Certificate {
+    X509Certificate::from_der(mock::MOCK_X509_CERTIFICATE.clone()).unwrap()
+}
+
+#[test]
+fn x509_certificate_from_der() {
+    let cert = mock_certificate();
+    assert_eq!(cert.public_key(), mock::MOCK_RSA_KEY.pk);
+    assert_eq!(cert.field(X509Tag::NotBefore), b"240101000000");
+    assert_eq!(cert.field(X509Tag::NotAfter), b"290101000000");
+    assert_eq!(
+        cert.tbs().len(),
+        cert.field_value(X509Tag::TbsLen) as usize + 4
+    );
+    assert_eq!(cert.tags().len(), cert.der.len());
+    assert!(cert.field(X509Tag::Padding).is_empty());
+    assert_eq!(
+        cert.signature_algorithm(),
+        X509SignatureAlgorithm::Sha256WithRsa
+    );
+    assert_eq!(
+        cert.signature().modpow(
+            &BigUint::from(RSA_PUBLIC_EXPONENT),
+            &mock::MOCK_RSA_CA_KEY.pk.n
+        ),
+        emsa_pkcs1_v15_sha256(&sha256(cert.tbs()))
+    );
+
+    // A truncated certificate doesn't match its declared length
+    let mut der = cert.der.clone();
+    der.pop();
+    assert!(X509Certificate::from_der(der).is_err());
+    // A certificate with another public exponent is not supported
+    let offset = cert.field_offset(X509Tag::PublicExponent);
+    let mut der = cert.der;
+    der[offset + 2] = 0x03;
+    assert_eq!(
+        X509Certificate::from_der(der),
+        Err(X509Error::Layout(X509Tag::PublicExponent))
+    );
+    // A certificate signed with md5WithRSAEncryption is not supported
+    let mut der = mock_certificate().der;
+    der[cert_offset(X509Tag::SignatureAlgorithm)] = 0x04;
+    assert_eq!(
+        X509Certificate::from_der(der),
+        Err(X509Error::SignatureAlgorithm)
+    );
+}
+
+#[test]
+fn x509_certificate_der_split() {
+    // A subject starting with an OCTET STRING of the constant SpkiHeader still ends at the end of
+    // its TLVs, before the actual SpkiHeader.
+    let X509FieldContent::Constant(spki_header) = X509Tag::SpkiHeader.content() else {
+        unreachable!()
+    };
+    let mut octet_string = vec![0x04, spki_header.len() as u8];
+    octet_string.extend_from_slice(spki_header);
+    let cert = mock_certificate();
+    let offset = cert.field_offset(X509Tag::Subject);
+    let mut der = cert.der.clone();
+    der.splice(offset..offset, octet_string.iter().copied());
+    for len_offset in [2, 6] {
+        let len = u16::from_be_bytes([der[len_offset], der[len_offset + 1]]);
+        let len = len + octet_string.len() as u16;
+        der[len_offset..len_offset + 2].copy_from_slice(&len.to_be_bytes());
+    }
+    let split_cert = X509Certificate::from_der(der).unwrap();
+    assert_eq!(
+        split_cert.field(X509Tag::Subject),
+        [octet_string.as_slice(), cert.field(X509Tag::Subject)].concat()
+    );
+    assert_eq!(split_cert.public_key(), cert.public_key());
+
+    // A subject whose TLV length doesn't end before the SpkiHeader is not supported
+    let mut der = cert.der;
+    der[offset + 1] += 1;
+    assert_eq!(
+        X509Certificate::from_der(der),
+        Err(X509Error::Layout(X509Tag::Subject))
+    );
+}
+
+/// Return the offset of a field in the mock certificate.
+fn cert_offset(tag: X509Tag) -> usize {
+    mock_certificate().field_offset(tag)
+}
+
+/// Return the mock certificate signed with sha1WithRSAEncryption by the CA.
+#[cfg(feature = "sha1")]
+fn mock_sha1_certificate() -> X509Certificate {
+    let mut der = mock_certificate().der;
+    der[cert_offset(X509Tag::SignatureAlgorithm)] = 0x05;
+    let cert = X509Certificate::from_der(der).unwrap();
+    let signature = mock::MOCK_RSA_CA_KEY.sign_sha1(cert.tbs());
+    let mut der = cert.der;
+    der[cert_offset(X509Tag::Signature)..]
+        .copy_from_slice(&eth_types::rsa_types::biguint_to_rsa_bytes_be(&signature));
+    X509Certificate::from_der(der).unwrap()
+}
+
+#[test]
+fn x509_utc_time_value() {
+    assert_eq!(
+        utc_time_value(TIMESTAMP).to_be_bytes()[4..],
+        *b"250101000000"
+    );
+    assert_eq!(
+        utc_time_value(1709210096).to_be_bytes()[4..],
+        *b"240229123456"
+    );
+    assert!(utc_time_value(TIMESTAMP) < utc_time_value(TIMESTAMP + 1));
+}
+
+#[test]
+fn x509_circuit_valid_chain() {
+    let circuit = X509Circuit::<Fr>::new(
+        vec![mock_certificate()],
+        mock::MOCK_RSA_CA_KEY.pk.clone(),
+        TIMESTAMP,
+        MAX_CERT_BYTES,
+    );
+    let instance = circuit.instance();
+    assert_eq!(instance[0].len(), 1 + 2 * RSA_2048_BYTES / 16);
+    assert_eq!(run_with_instance(circuit, instance), Ok(()));
+}
+
+#[test]
+fn x509_circuit_wrong_ca() {
+    let circuit = X509Circuit::<Fr>::new(
+        vec![mock_certificate()],
+        mock::MOCK_RSA_KEY.pk.clone(),
+        TIMESTAMP,
+        MAX_CERT_BYTES,
+    );
+    let instance = circuit.instance();
+    assert!(run_with_instance(circuit, instance).is_err());
+}
+
+#[test]
+fn x509_circuit_expired() {
+    // 2030-01-01T00:00:00Z
+    let circuit = X509Circuit::<Fr>::new(
+        vec![mock_certificate()],
+        mock::MOCK_RSA_CA_KEY.pk.clone(),
+        1893456000,
+        MAX_CERT_BYTES,
+    );
+    let instance = circuit.instance();
+    assert!(run_with_instance(circuit, instance).is_err());
+}
+
+#[test]
+fn x509_circuit_tampered_certificate() {
+    let mut cert = mock_certificate();
+    // Change a byte of the subject, which is signed by the CA
+    let offset = cert.field_offset(X509Tag::Subject);
+    cert.der[offset + 10] ^= 1;
+    let circuit = X509Circuit::<Fr>::new(
+        vec![cert],
+        mock::MOCK_RSA_CA_KEY.pk.clone(),
+        TIMESTAMP,
+        MAX_CERT_BYTES,
+    );
+    let instance = circuit.instance();
+    assert!(run_with_instance(circuit, instance).is_err());
+}
+
+#[cfg(feature = "sha1")]
+#[test]
+fn x509_circuit_sha1_chain() {
+    let sha1_cert = mock_sha1_certificate();
+    assert_eq!(
+        sha1_cert.signature_algorithm(),
+        X509SignatureAlgorithm::Sha1WithRsa
+    );
+    let circuit = X509Circuit::<Fr>::new(
+        vec![sha1_cert],
+        mock::MOCK_RSA_CA_KEY.pk.clone(),
+        TIMESTAMP,
+        MAX_CERT_BYTES,
+    );
+    assert!(circuit.sha256_inputs().is_empty());
+    let instance = circuit.instance();
+    assert_eq!(run_with_instance(circuit, instance), Ok(()));
+}
+
+#[cfg(feature = "sha1")]
+#[test]
+fn x509_circuit_sha1_signature_as_sha256() {
+    // The SHA-1 signature doesn't verify a certificate claiming sha256WithRSAEncryption.
+    let sha1_cert = mock_sha1_certificate();
+    let mut der = sha1_cert.der;
+    der[cert_offset(X509Tag::SignatureAlgorithm)] = 0x0b;
+    let circuit = X509Circuit::<Fr>::new(
+        vec![X509Certificate::from_der(der).unwrap()],
+        mock::MOCK_RSA_CA_KEY.pk.clone(),
+        TIMESTAMP,
+        MAX_CERT_BYTES,
+    );
+    let instance = circuit.instance();
+    assert!(run_with_instance(circuit, instance).is_err());
+}